@@ -35,12 +35,17 @@ fn main() {
 
             match value.link {
                 Some(Ethernet2(value)) => println!("  Ethernet2 {:?} => {:?}", value.source(), value.destination()),
+                Some(LinuxSll(value)) => println!("  LinuxSll {:?}", value.sender_address()),
+                Some(LinuxSll2(value)) => println!("  LinuxSll2 {:?}", value.sender_address()),
+                Some(Null(value)) => println!("  Null {:?}", value.protocol_family()),
+                Some(Ieee80211(value)) => println!("  Ieee80211 {:?}", value.to_header()),
                 None => {}
             }
 
             match value.vlan {
                 Some(SingleVlan(value)) => println!("  SingleVlan {:?}", value.vlan_identifier()),
                 Some(DoubleVlan(value)) => println!("  DoubleVlan {:?}, {:?}", value.outer().vlan_identifier(), value.inner().vlan_identifier()),
+                Some(MultiVlan(value)) => println!("  MultiVlan {:?}", value.iter().map(|v| v.vlan_identifier()).collect::<Vec<_>>()),
                 None => {}
             }
 
@@ -67,6 +72,7 @@ fn main() {
                     let options: Vec<Result<TcpOptionElement, TcpOptionReadError>> = value.options_iterator().collect();
                     println!("    {:?}", options);
                 }
+                Some(Igmp(value)) => println!("  IGMP {:?}", value.to_header().igmp_type),
                 Some(Unknown(ip_protocol)) => println!("  Unknwon Protocol (ip protocol number {:?}", ip_protocol),
                 None => {}
             }
@@ -0,0 +1,450 @@
+use super::*;
+
+/// How the checksums of an anonymized packet are updated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ChecksumMode {
+    /// Recalculate the IPv4 header, UDP, TCP, ICMPv4, ICMPv6 & IGMP
+    /// checksums over the anonymized addresses & the truncated payload (the
+    /// transport checksums of fragmented packets are left unchanged as
+    /// they can not be calculated without the other fragments).
+    Recalculate,
+    /// Set the IPv4 header, UDP, TCP, ICMPv4, ICMPv6 & IGMP checksums to
+    /// zero.
+    Zero,
+}
+
+/// Settings controlling which parts of a packet are anonymized.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AnonymizeConfig {
+    /// If true the mac addresses in the Ethernet II header are replaced.
+    pub mac_addresses: bool,
+    /// If true the source & destination addresses in the IPv4 & IPv6
+    /// headers are replaced (prefix-preserving).
+    pub ip_addresses: bool,
+    /// If set payloads longer then the given length are truncated (the
+    /// length fields in the ip & udp headers are updated accordingly).
+    pub max_payload_len: Option<usize>,
+    /// How the checksums are updated.
+    pub checksums: ChecksumMode,
+}
+
+impl Default for AnonymizeConfig {
+    fn default() -> AnonymizeConfig {
+        AnonymizeConfig {
+            mac_addresses: true,
+            ip_addresses: true,
+            max_payload_len: None,
+            checksums: ChecksumMode::Recalculate,
+        }
+    }
+}
+
+/// Anonymizes the addresses in packets so captures can be shared while
+/// remaining parseable.
+///
+/// IP addresses are replaced using a prefix-preserving mapping following
+/// the Crypto-PAn construction (two addresses sharing a prefix of `n` bits
+/// are mapped to two addresses sharing a prefix of `n` bits). Instead of
+/// AES, SipHash-2-4 is used as the pseudo random function. The mapping
+/// only depends on the key, so the same key results in the same mapping
+/// across multiple captures.
+///
+/// Mac addresses are mapped the same way (unicast addresses stay unicast
+/// addresses). Multicast & broadcast mac addresses are left unchanged.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketBuilder, SlicedPacket};
+/// use etherparse::anonymize::{AnonymizeConfig, Anonymizer};
+///
+/// let builder = PacketBuilder::
+///     ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234);
+/// let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// builder.write(&mut packet, &[1,2,3,4]).unwrap();
+///
+/// let anonymizer = Anonymizer::new([7;32], AnonymizeConfig{
+///     max_payload_len: Some(2),
+///     ..Default::default()
+/// });
+/// anonymizer.anonymize_ethernet(&mut packet).unwrap();
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// assert_eq!(&[1,2], sliced.payload);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Anonymizer {
+    /// Key of the pseudo random function.
+    key: (u64, u64),
+    /// Bits used to fill up the not yet processed part of an address
+    /// before it is passed to the pseudo random function.
+    pad: [u8;16],
+    config: AnonymizeConfig,
+}
+
+impl Anonymizer {
+    /// Creates an anonymizer with the given key (the first 16 bytes are used
+    /// as the key of the pseudo random function, the last 16 bytes as padding).
+    pub fn new(key: [u8;32], config: AnonymizeConfig) -> Anonymizer {
+        let prf_key = (
+            u64::from_le_bytes([key[0], key[1], key[2], key[3], key[4], key[5], key[6], key[7]]),
+            u64::from_le_bytes([key[8], key[9], key[10], key[11], key[12], key[13], key[14], key[15]]),
+        );
+        // the padding is passed through the pseudo random function first so
+        // it is not directly taken from the key (same as in Crypto-PAn)
+        let mut pad = [0u8;16];
+        pad.copy_from_slice(&key[16..]);
+        let hash = siphash24(prf_key, &pad);
+        for (dst, src) in pad.iter_mut().zip(hash.to_le_bytes().iter().chain(hash.to_be_bytes().iter())) {
+            *dst ^= src;
+        }
+        Anonymizer {
+            key: prf_key,
+            pad,
+            config,
+        }
+    }
+
+    /// Returns the config of the anonymizer.
+    pub fn config(&self) -> &AnonymizeConfig {
+        &self.config
+    }
+
+    /// Returns the anonymized IPv4 address.
+    pub fn ipv4(&self, address: [u8;4]) -> [u8;4] {
+        let mut result = [0u8;4];
+        self.prefix_preserving(&address, &mut result);
+        result
+    }
+
+    /// Returns the anonymized IPv6 address.
+    pub fn ipv6(&self, address: [u8;16]) -> [u8;16] {
+        let mut result = [0u8;16];
+        self.prefix_preserving(&address, &mut result);
+        result
+    }
+
+    /// Returns the anonymized mac address (multicast & broadcast addresses
+    /// are returned unchanged).
+    pub fn mac(&self, address: [u8;6]) -> [u8;6] {
+        if 0 != address[0] & 1 {
+            address
+        } else {
+            let mut result = [0u8;6];
+            self.prefix_preserving(&address, &mut result);
+            // keep unicast addresses unicast addresses
+            result[0] &= !1;
+            result
+        }
+    }
+
+    /// Crypto-PAn style prefix-preserving mapping of the given address.
+    fn prefix_preserving(&self, address: &[u8], result: &mut [u8]) {
+        let bits = address.len()*8;
+        let mut input = self.pad;
+        for i in 0..bits {
+            // input consists of the first i bits of the address followed by the padding
+            let byte = i / 8;
+            let mask = 0x80u8 >> (i % 8);
+            if 0 < i {
+                let prev_byte = (i - 1) / 8;
+                let prev_mask = 0x80u8 >> ((i - 1) % 8);
+                input[prev_byte] = (input[prev_byte] & !prev_mask) | (address[prev_byte] & prev_mask);
+            }
+            let flip = 0 != (siphash24(self.key, &input) >> 63);
+            let bit = 0 != address[byte] & mask;
+            if bit != flip {
+                result[byte] |= mask;
+            } else {
+                result[byte] &= !mask;
+            }
+        }
+    }
+
+    /// Anonymizes a packet starting with an Ethernet II header in place.
+    ///
+    /// Returns an error if the packet could not be sliced (in this case
+    /// the packet is not modified).
+    pub fn anonymize_ethernet(&self, packet: &mut Vec<u8>) -> Result<(), ReadError> {
+        let layout = Layout::new(&SlicedPacket::from_ethernet(packet)?);
+        self.anonymize(packet, layout);
+        Ok(())
+    }
+
+    /// Anonymizes a packet starting with an IPv4 or IPv6 header in place.
+    ///
+    /// Returns an error if the packet could not be sliced (in this case
+    /// the packet is not modified).
+    pub fn anonymize_ip(&self, packet: &mut Vec<u8>) -> Result<(), ReadError> {
+        let layout = Layout::new(&SlicedPacket::from_ip(packet)?);
+        self.anonymize(packet, layout);
+        Ok(())
+    }
+
+    fn anonymize(&self, packet: &mut Vec<u8>, layout: Layout) {
+        // mac addresses
+        if self.config.mac_addresses && layout.link_len >= 12 {
+            for range in &[0..6, 6..12] {
+                let mut address = [0u8;6];
+                address.copy_from_slice(&packet[range.clone()]);
+                packet[range.clone()].copy_from_slice(&self.mac(address));
+            }
+        }
+
+        // ip addresses
+        let ip = layout.ip_start;
+        if self.config.ip_addresses {
+            match layout.ip {
+                Some(IpVersion::V4) => for range in &[ip + 12..ip + 16, ip + 16..ip + 20] {
+                    let mut address = [0u8;4];
+                    address.copy_from_slice(&packet[range.clone()]);
+                    packet[range.clone()].copy_from_slice(&self.ipv4(address));
+                },
+                Some(IpVersion::V6) => for range in &[ip + 8..ip + 24, ip + 24..ip + 40] {
+                    let mut address = [0u8;16];
+                    address.copy_from_slice(&packet[range.clone()]);
+                    packet[range.clone()].copy_from_slice(&self.ipv6(address));
+                },
+                None => {},
+            }
+        }
+
+        // truncate the payload & update the length fields
+        let mut payload_len = layout.payload_len;
+        if let Some(max_len) = self.config.max_payload_len {
+            if payload_len > max_len {
+                let removed = payload_len - max_len;
+                payload_len = max_len;
+                packet.truncate(layout.payload_start + payload_len);
+
+                let length_field = match layout.ip {
+                    Some(IpVersion::V4) => Some(ip + 2),
+                    Some(IpVersion::V6) => Some(ip + 4),
+                    None => None,
+                };
+                let fields = length_field.into_iter().chain(
+                    // udp length
+                    if let Some(Transport::Udp) = layout.transport {
+                        Some(layout.transport_start + 4)
+                    } else {
+                        None
+                    }
+                );
+                for offset in fields {
+                    let value = u16::from_be_bytes([packet[offset], packet[offset + 1]]);
+                    // a value of zero indicates a jumbogram (ipv6) or a length
+                    // determined by the lower layer (udp)
+                    if 0 != value {
+                        packet[offset..offset + 2].copy_from_slice(&(value.saturating_sub(removed as u16)).to_be_bytes());
+                    }
+                }
+            }
+        }
+
+        // checksums
+        let recalculate = ChecksumMode::Recalculate == self.config.checksums;
+        if let Some(IpVersion::V4) = layout.ip {
+            packet[ip + 10..ip + 12].copy_from_slice(&[0, 0]);
+            if recalculate {
+                let checksum = checksum::Sum16BitWords::new()
+                    .add_slice(&packet[ip..ip + layout.ip_header_len])
+                    .ones_complement()
+                    .to_be();
+                packet[ip + 10..ip + 12].copy_from_slice(&checksum.to_be_bytes());
+            }
+        }
+        let checksum_offset = match layout.transport {
+            Some(Transport::Udp) => layout.transport_start + 6,
+            Some(Transport::Tcp) => layout.transport_start + 16,
+            Some(Transport::Igmp) => layout.transport_start + 2,
+            // icmp messages are part of the payload (skip messages cut
+            // off before the end of the checksum field)
+            Some(Transport::Icmpv4) | Some(Transport::Icmpv6) if payload_len >= 4 => layout.payload_start + 2,
+            _ => return,
+        };
+        if !recalculate {
+            packet[checksum_offset..checksum_offset + 2].copy_from_slice(&[0, 0]);
+            return;
+        }
+        if layout.fragmented {
+            return;
+        }
+        match layout.transport {
+            Some(Transport::Igmp) | Some(Transport::Icmpv4) => {
+                packet[checksum_offset..checksum_offset + 2].copy_from_slice(&[0, 0]);
+                let message = if let Some(Transport::Igmp) = layout.transport {
+                    &packet[layout.transport_start..layout.payload_start]
+                } else {
+                    &packet[layout.payload_start..layout.payload_start + payload_len]
+                };
+                let checksum = checksum::Sum16BitWords::new()
+                    .add_slice(message)
+                    .ones_complement()
+                    .to_be();
+                packet[checksum_offset..checksum_offset + 2].copy_from_slice(&checksum.to_be_bytes());
+                return;
+            },
+            Some(Transport::Icmpv6) => {
+                packet[checksum_offset..checksum_offset + 2].copy_from_slice(&[0, 0]);
+                // the pseudo header contains the final destination in case
+                // a routing header is present
+                let (header, _) = Ipv6Header::from_slice(&packet[ip..]).unwrap();
+                let (extensions, _, _) = Ipv6Extensions::from_slice(
+                    header.next_header,
+                    &packet[ip + layout.ip_header_len..]
+                ).unwrap();
+                let message = &packet[layout.payload_start..layout.payload_start + payload_len];
+                let checksum = icmpv6::checksum_final_destination(&header, &extensions, message);
+                packet[checksum_offset..checksum_offset + 2].copy_from_slice(&checksum.to_be_bytes());
+                return;
+            },
+            _ => {},
+        }
+        let transport = &packet[layout.transport_start..layout.payload_start];
+        let payload = &packet[layout.payload_start..layout.payload_start + payload_len];
+        let checksum = match (layout.ip, layout.transport) {
+            (Some(IpVersion::V4), Some(Transport::Udp)) => {
+                let udp = UdpHeaderSlice::from_slice(transport).unwrap();
+                // zero indicates that no checksum is used
+                if 0 == udp.checksum() {
+                    return;
+                }
+                let mut source = [0u8;4];
+                source.copy_from_slice(&packet[ip + 12..ip + 16]);
+                let mut destination = [0u8;4];
+                destination.copy_from_slice(&packet[ip + 16..ip + 20]);
+                udp.calc_checksum_ipv4_raw(source, destination, payload)
+            },
+            (Some(IpVersion::V6), Some(Transport::Udp)) => {
+                let udp = UdpHeaderSlice::from_slice(transport).unwrap();
+                let mut source = [0u8;16];
+                source.copy_from_slice(&packet[ip + 8..ip + 24]);
+                let mut destination = [0u8;16];
+                destination.copy_from_slice(&packet[ip + 24..ip + 40]);
+                udp.calc_checksum_ipv6_raw(source, destination, payload)
+            },
+            (Some(IpVersion::V4), Some(Transport::Tcp)) => {
+                let tcp = TcpHeaderSlice::from_slice(transport).unwrap();
+                let mut source = [0u8;4];
+                source.copy_from_slice(&packet[ip + 12..ip + 16]);
+                let mut destination = [0u8;4];
+                destination.copy_from_slice(&packet[ip + 16..ip + 20]);
+                tcp.calc_checksum_ipv4_raw(source, destination, payload)
+            },
+            (Some(IpVersion::V6), Some(Transport::Tcp)) => {
+                let tcp = TcpHeaderSlice::from_slice(transport).unwrap();
+                let mut source = [0u8;16];
+                source.copy_from_slice(&packet[ip + 8..ip + 24]);
+                let mut destination = [0u8;16];
+                destination.copy_from_slice(&packet[ip + 24..ip + 40]);
+                tcp.calc_checksum_ipv6_raw(source, destination, payload)
+            },
+            _ => return,
+        };
+        // the payload length was already checked when the packet was sliced
+        if let Ok(checksum) = checksum {
+            packet[checksum_offset..checksum_offset + 2].copy_from_slice(&checksum.to_be_bytes());
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum IpVersion {
+    V4,
+    V6,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Transport {
+    Udp,
+    Tcp,
+    Igmp,
+    Icmpv4,
+    Icmpv6,
+}
+
+/// Offsets & lengths of the headers in a sliced packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Layout {
+    link_len: usize,
+    ip: Option<IpVersion>,
+    ip_start: usize,
+    ip_header_len: usize,
+    fragmented: bool,
+    transport: Option<Transport>,
+    transport_start: usize,
+    payload_start: usize,
+    payload_len: usize,
+}
+
+impl Layout {
+    fn new(sliced: &SlicedPacket) -> Layout {
+        let headers = sliced.header_bytes();
+        let link_len = headers[0].len();
+        let ip_start = link_len + headers[1].len() + headers[2].len();
+        // tunnel headers directly following the ip header precede the transport header
+        let transport_start = ip_start + headers[3].len() + headers[4].len() +
+            sliced.ip_tunnel_slice().map(|v| v.len()).unwrap_or(0);
+        Layout {
+            link_len,
+            ip: match &sliced.ip {
+                Some(InternetSlice::Ipv4(_, _)) => Some(IpVersion::V4),
+                Some(InternetSlice::Ipv6(_, _)) => Some(IpVersion::V6),
+                None => None,
+            },
+            ip_start,
+            ip_header_len: headers[3].len(),
+            fragmented: match &sliced.ip {
+                Some(InternetSlice::Ipv4(header, _)) => header.is_fragmenting_payload(),
+                Some(InternetSlice::Ipv6(_, extensions)) => extensions.is_fragmenting_payload(),
+                None => false,
+            },
+            transport: match &sliced.transport {
+                Some(TransportSlice::Udp(_)) => Some(Transport::Udp),
+                Some(TransportSlice::Tcp(_)) => Some(Transport::Tcp),
+                Some(TransportSlice::Igmp(_)) => Some(Transport::Igmp),
+                Some(TransportSlice::Unknown(ip_number::ICMP)) => Some(Transport::Icmpv4),
+                Some(TransportSlice::Unknown(ip_number::IPV6_ICMP)) => Some(Transport::Icmpv6),
+                _ => None,
+            },
+            transport_start,
+            payload_start: ip_start + headers[3..7].iter().map(|v| v.len()).sum::<usize>(),
+            payload_len: sliced.payload.len(),
+        }
+    }
+}
+
+/// SipHash-2-4 of a 16 byte message.
+fn siphash24(key: (u64, u64), message: &[u8;16]) -> u64 {
+    let mut v = [
+        key.0 ^ 0x736f_6d65_7073_6575,
+        key.1 ^ 0x646f_7261_6e64_6f6d,
+        key.0 ^ 0x6c79_6765_6e65_7261,
+        key.1 ^ 0x7465_6462_7974_6573,
+    ];
+    fn round(v: &mut [u64;4]) {
+        v[0] = v[0].wrapping_add(v[1]); v[1] = v[1].rotate_left(13); v[1] ^= v[0]; v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]); v[3] = v[3].rotate_left(16); v[3] ^= v[2];
+        v[0] = v[0].wrapping_add(v[3]); v[3] = v[3].rotate_left(21); v[3] ^= v[0];
+        v[2] = v[2].wrapping_add(v[1]); v[1] = v[1].rotate_left(17); v[1] ^= v[2]; v[2] = v[2].rotate_left(32);
+    }
+    let words = [
+        u64::from_le_bytes([message[0], message[1], message[2], message[3], message[4], message[5], message[6], message[7]]),
+        u64::from_le_bytes([message[8], message[9], message[10], message[11], message[12], message[13], message[14], message[15]]),
+        // final block only containing the message length
+        16u64 << 56,
+    ];
+    for m in words.iter() {
+        v[3] ^= m;
+        round(&mut v);
+        round(&mut v);
+        v[0] ^= m;
+    }
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
@@ -0,0 +1,87 @@
+use super::*;
+
+use rayon::prelude::*;
+
+/// Decodes a batch of frames starting with an Ethernet II header in
+/// parallel (see [`PacketHeaders::from_ethernet_slice`]).
+///
+/// The results are returned in the same order as the given frames, each
+/// frame gets its own result (errors in one frame do not abort the
+/// parsing of the other frames).
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::batch;
+///
+/// let frames = vec![packet.clone(), vec![1,2,3], packet];
+/// let results = batch::headers_from_ethernet_slices(&frames);
+///
+/// assert_eq!(3, results.len());
+/// assert!(results[0].is_ok());
+/// assert!(results[1].is_err());
+/// assert!(results[2].is_ok());
+/// ```
+pub fn headers_from_ethernet_slices<T>(frames: &[T]) -> Vec<Result<PacketHeaders<'_>, ReadError>>
+where
+    T: AsRef<[u8]> + Sync
+{
+    map_slices(frames, PacketHeaders::from_ethernet_slice)
+}
+
+/// Decodes a batch of packets starting with an IPv4 or IPv6 header
+/// in parallel (see [`PacketHeaders::from_ip_slice`]).
+///
+/// The results are returned in the same order as the given packets.
+pub fn headers_from_ip_slices<T>(packets: &[T]) -> Vec<Result<PacketHeaders<'_>, ReadError>>
+where
+    T: AsRef<[u8]> + Sync
+{
+    map_slices(packets, PacketHeaders::from_ip_slice)
+}
+
+/// Slices a batch of frames starting with an Ethernet II header in
+/// parallel (see [`SlicedPacket::from_ethernet`]).
+///
+/// The results are returned in the same order as the given frames.
+pub fn slice_ethernet_slices<T>(frames: &[T]) -> Vec<Result<SlicedPacket<'_>, ReadError>>
+where
+    T: AsRef<[u8]> + Sync
+{
+    map_slices(frames, SlicedPacket::from_ethernet)
+}
+
+/// Slices a batch of packets starting with an IPv4 or IPv6 header
+/// in parallel (see [`SlicedPacket::from_ip`]).
+///
+/// The results are returned in the same order as the given packets.
+pub fn slice_ip_slices<T>(packets: &[T]) -> Vec<Result<SlicedPacket<'_>, ReadError>>
+where
+    T: AsRef<[u8]> + Sync
+{
+    map_slices(packets, SlicedPacket::from_ip)
+}
+
+/// Applies the given function to all slices in parallel and returns
+/// the results in the order of the given slices.
+///
+/// This can be used to run custom decoding functions (e.g. decoding
+/// from a specific ether type) over a batch of packets.
+pub fn map_slices<'a, T, R, F>(slices: &'a [T], f: F) -> Vec<R>
+where
+    T: AsRef<[u8]> + Sync,
+    R: Send,
+    F: Fn(&'a [u8]) -> R + Sync + Send
+{
+    slices
+        .par_iter()
+        .map(|slice| f(slice.as_ref()))
+        .collect()
+}
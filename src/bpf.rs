@@ -0,0 +1,537 @@
+use super::*;
+
+/// Maximum number of instructions in a program (identical to `BPF_MAXINSNS` in libpcap & linux).
+pub const MAX_INSTRUCTIONS: usize = 4096;
+
+/// Number of scratch memory slots available to a program.
+pub const MEMORY_SLOTS: usize = 16;
+
+// instruction classes
+const LD: u16 = 0x00;
+const LDX: u16 = 0x01;
+const ST: u16 = 0x02;
+const STX: u16 = 0x03;
+const ALU: u16 = 0x04;
+const JMP: u16 = 0x05;
+const RET: u16 = 0x06;
+const MISC: u16 = 0x07;
+
+// load sizes
+const W: u16 = 0x00;
+const H: u16 = 0x08;
+const B: u16 = 0x10;
+
+// load modes
+const IMM: u16 = 0x00;
+const ABS: u16 = 0x20;
+const IND: u16 = 0x40;
+const MEM: u16 = 0x60;
+const LEN: u16 = 0x80;
+const MSH: u16 = 0xa0;
+
+// alu operations
+const ADD: u16 = 0x00;
+const SUB: u16 = 0x10;
+const MUL: u16 = 0x20;
+const DIV: u16 = 0x30;
+const OR: u16 = 0x40;
+const AND: u16 = 0x50;
+const LSH: u16 = 0x60;
+const RSH: u16 = 0x70;
+const NEG: u16 = 0x80;
+const MOD: u16 = 0x90;
+const XOR: u16 = 0xa0;
+
+// jump operations
+const JA: u16 = 0x00;
+const JEQ: u16 = 0x10;
+const JGT: u16 = 0x20;
+const JGE: u16 = 0x30;
+const JSET: u16 = 0x40;
+
+// sources
+const K: u16 = 0x00;
+const X: u16 = 0x08;
+const A: u16 = 0x10;
+
+// misc operations
+const TAX: u16 = 0x00;
+const TXA: u16 = 0x80;
+
+/// Errors that can occur when parsing or validating a BPF program.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BpfError {
+    /// Line of a textual program representation could not be parsed (line number starting at 1).
+    ParseError(usize),
+    /// Number of instructions in the textual representation does not match the count in the first line.
+    InstructionCountMismatch { expected: usize, actual: usize },
+    /// The program contains no instructions.
+    EmptyProgram,
+    /// The program contains more then [`MAX_INSTRUCTIONS`] instructions.
+    TooManyInstructions(usize),
+    /// Instruction at the given index has an unknown opcode.
+    InvalidOpcode { index: usize, code: u16 },
+    /// Jump at the given index targets a position outside of the program.
+    JumpOutOfBounds(usize),
+    /// Instruction at the given index accesses a scratch memory slot that does not exist.
+    InvalidMemoryIndex(usize),
+    /// Instruction at the given index divides by a constant 0.
+    DivisionByZero(usize),
+    /// The last instruction of the program is not a return instruction.
+    MissingReturn,
+}
+
+impl fmt::Display for BpfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use BpfError::*;
+        match self {
+            ParseError(line) => {
+                write!(f, "BpfError: Unable to parse line {} of the BPF program.", line)
+            },
+            InstructionCountMismatch{ expected, actual } => {
+                write!(f, "BpfError: The BPF program announced {} instructions but contained {} instructions.", expected, actual)
+            },
+            EmptyProgram => {
+                write!(f, "BpfError: The BPF program contains no instructions.")
+            },
+            TooManyInstructions(count) => {
+                write!(f, "BpfError: The BPF program contains {} instructions, which is more then the maximum of {} instructions.", count, MAX_INSTRUCTIONS)
+            },
+            InvalidOpcode{ index, code } => {
+                write!(f, "BpfError: Invalid opcode 0x{:x} in the instruction {} of the BPF program.", code, index)
+            },
+            JumpOutOfBounds(index) => {
+                write!(f, "BpfError: The jump instruction {} of the BPF program targets a position outside of the program.", index)
+            },
+            InvalidMemoryIndex(index) => {
+                write!(f, "BpfError: The instruction {} of the BPF program accesses a scratch memory slot outside of the {} available slots.", index, MEMORY_SLOTS)
+            },
+            DivisionByZero(index) => {
+                write!(f, "BpfError: The instruction {} of the BPF program divides by a constant 0.", index)
+            },
+            MissingReturn => {
+                write!(f, "BpfError: The last instruction of the BPF program is not a return instruction.")
+            },
+        }
+    }
+}
+
+impl Error for BpfError {}
+
+/// Single classic BPF instruction (identical in layout to `struct sock_filter`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct BpfInstruction {
+    pub code: u16,
+    /// Jump offset if the condition is true.
+    pub jt: u8,
+    /// Jump offset if the condition is false.
+    pub jf: u8,
+    pub k: u32,
+}
+
+impl BpfInstruction {
+    pub fn new(code: u16, jt: u8, jf: u8, k: u32) -> BpfInstruction {
+        BpfInstruction { code, jt, jf, k }
+    }
+}
+
+/// Validated classic BPF (cBPF) program that can be executed against packets.
+///
+/// Programs can be created from the output of `tcpdump -ddd` (see
+/// [`BpfProgram::from_tcpdump_ddd`]) & `tcpdump -dd` (see
+/// [`BpfProgram::from_tcpdump_dd`]) or directly from a list of instructions
+/// (e.g. as returned by `pcap_compile`).
+///
+/// The programs are executed with the same semantics as the libpcap
+/// `bpf_filter` function: Loads outside of the packet & divisions by 0
+/// abort the program & reject the packet.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::bpf::BpfProgram;
+///
+/// // output of "tcpdump -ddd ip"
+/// let program = BpfProgram::from_tcpdump_ddd("4\n40 0 0 12\n21 0 1 2048\n6 0 0 262144\n6 0 0 0\n").unwrap();
+///
+/// assert!(program.matches(&packet));
+/// assert_eq!(262144, program.run(&packet));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BpfProgram {
+    instructions: Vec<BpfInstruction>,
+}
+
+impl BpfProgram {
+
+    /// Validates the instructions & creates a program.
+    pub fn new(instructions: Vec<BpfInstruction>) -> Result<BpfProgram, BpfError> {
+        validate(&instructions)?;
+        Ok(BpfProgram { instructions })
+    }
+
+    /// Parses a program in the format produced by `tcpdump -ddd` (the number
+    /// of instructions in the first line, followed by one instruction
+    /// per line as `code jt jf k` in decimal).
+    pub fn from_tcpdump_ddd(text: &str) -> Result<BpfProgram, BpfError> {
+        let mut lines = text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let expected = match lines.next() {
+            Some((index, line)) => line.trim().parse::<usize>().map_err(|_| BpfError::ParseError(index + 1))?,
+            None => return Err(BpfError::EmptyProgram),
+        };
+
+        let mut instructions = Vec::with_capacity(expected.min(MAX_INSTRUCTIONS));
+        for (index, line) in lines {
+            let parsed = parse_instruction(line.split_whitespace(), |v| v.parse().ok());
+            instructions.push(parsed.ok_or(BpfError::ParseError(index + 1))?);
+        }
+
+        if expected != instructions.len() {
+            return Err(BpfError::InstructionCountMismatch{
+                expected,
+                actual: instructions.len(),
+            });
+        }
+        BpfProgram::new(instructions)
+    }
+
+    /// Parses a program in the format produced by `tcpdump -dd` (one C
+    /// struct initializer per line, e.g. `{ 0x28, 0, 0, 0x0000000c },`).
+    pub fn from_tcpdump_dd(text: &str) -> Result<BpfProgram, BpfError> {
+        let mut instructions = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let inner = line.trim_end_matches(',')
+                .trim()
+                .strip_prefix('{')
+                .and_then(|v| v.strip_suffix('}'))
+                .ok_or(BpfError::ParseError(index + 1))?;
+            let parsed = parse_instruction(inner.split(',').map(str::trim), parse_c_number);
+            instructions.push(parsed.ok_or(BpfError::ParseError(index + 1))?);
+        }
+        BpfProgram::new(instructions)
+    }
+
+    /// Instructions of the program.
+    #[inline]
+    pub fn instructions(&self) -> &[BpfInstruction] {
+        &self.instructions
+    }
+
+    /// Executes the program against a frame & returns the value returned
+    /// by the program (the number of bytes to capture, 0 if the frame is
+    /// rejected).
+    pub fn run(&self, frame: &[u8]) -> u32 {
+        self.execute(&frame)
+    }
+
+    /// Returns true if the program accepts the frame (returns a value
+    /// other then 0).
+    #[inline]
+    pub fn matches(&self, frame: &[u8]) -> bool {
+        0 != self.run(frame)
+    }
+
+    /// Executes the program against the bytes of a sliced packet (headers
+    /// followed by the payload & padding, without copying them into one buffer).
+    ///
+    /// Note that the program has to be compiled for the layer the packet
+    /// was sliced from (e.g. a program compiled for ethernet frames will
+    /// not match packets sliced via [`SlicedPacket::from_ip`]).
+    pub fn run_sliced(&self, packet: &SlicedPacket) -> u32 {
+        let headers = packet.header_bytes();
+        self.execute(&[
+            headers[0],
+            headers[1],
+            headers[2],
+            headers[3],
+            headers[4],
+            headers[5],
+            headers[6],
+            headers[7],
+            packet.payload,
+            packet.trailer,
+            packet.padding,
+        ])
+    }
+
+    /// Returns true if the program accepts the sliced packet (see [`BpfProgram::run_sliced`]).
+    #[inline]
+    pub fn matches_sliced(&self, packet: &SlicedPacket) -> bool {
+        0 != self.run_sliced(packet)
+    }
+
+    fn execute<T: PacketBytes>(&self, packet: &T) -> u32 {
+        let len = packet.len();
+        let mut a: u32 = 0;
+        let mut x: u32 = 0;
+        let mut mem = [0u32;MEMORY_SLOTS];
+        let mut pc = 0;
+
+        // validation guarantees that all jumps stay within the program
+        // and that the last instruction is a return
+        loop {
+            let ins = self.instructions[pc];
+            pc += 1;
+            let k = ins.k;
+            match ins.code {
+                // loads into the accumulator
+                c if c == LD|W|ABS => match packet.load(k as usize, 4) {
+                    Some(v) => a = v,
+                    None => return 0,
+                },
+                c if c == LD|H|ABS => match packet.load(k as usize, 2) {
+                    Some(v) => a = v,
+                    None => return 0,
+                },
+                c if c == LD|B|ABS => match packet.load(k as usize, 1) {
+                    Some(v) => a = v,
+                    None => return 0,
+                },
+                c if c == LD|W|IND => match packet.load(x.wrapping_add(k) as usize, 4) {
+                    Some(v) => a = v,
+                    None => return 0,
+                },
+                c if c == LD|H|IND => match packet.load(x.wrapping_add(k) as usize, 2) {
+                    Some(v) => a = v,
+                    None => return 0,
+                },
+                c if c == LD|B|IND => match packet.load(x.wrapping_add(k) as usize, 1) {
+                    Some(v) => a = v,
+                    None => return 0,
+                },
+                c if c == LD|W|LEN => a = len as u32,
+                c if c == LD|IMM => a = k,
+                c if c == LD|MEM => a = mem[k as usize],
+
+                // loads into the index register
+                c if c == LDX|W|LEN => x = len as u32,
+                c if c == LDX|IMM => x = k,
+                c if c == LDX|MEM => x = mem[k as usize],
+                c if c == LDX|B|MSH => match packet.load(k as usize, 1) {
+                    Some(v) => x = (v & 0xf) << 2,
+                    None => return 0,
+                },
+
+                // stores
+                ST => mem[k as usize] = a,
+                STX => mem[k as usize] = x,
+
+                // jumps
+                c if c == JMP|JA => pc += k as usize,
+                c if c & 0x07 == JMP => {
+                    let operand = if c & X == X { x } else { k };
+                    let condition = match c & 0xf0 {
+                        JEQ => a == operand,
+                        JGT => a > operand,
+                        JGE => a >= operand,
+                        JSET => 0 != a & operand,
+                        // can not be reached as the opcodes are checked during the validation
+                        _ => return 0,
+                    };
+                    pc += usize::from(if condition { ins.jt } else { ins.jf });
+                },
+
+                // arithmetic
+                c if c == ALU|NEG => a = a.wrapping_neg(),
+                c if c & 0x07 == ALU => {
+                    let operand = if c & X == X { x } else { k };
+                    a = match c & 0xf0 {
+                        ADD => a.wrapping_add(operand),
+                        SUB => a.wrapping_sub(operand),
+                        MUL => a.wrapping_mul(operand),
+                        DIV => match a.checked_div(operand) {
+                            Some(v) => v,
+                            None => return 0,
+                        },
+                        MOD => match a.checked_rem(operand) {
+                            Some(v) => v,
+                            None => return 0,
+                        },
+                        OR => a | operand,
+                        AND => a & operand,
+                        XOR => a ^ operand,
+                        LSH => a.checked_shl(operand).unwrap_or(0),
+                        RSH => a.checked_shr(operand).unwrap_or(0),
+                        // can not be reached as the opcodes are checked during the validation
+                        _ => return 0,
+                    };
+                },
+
+                // returns
+                c if c == RET|K => return k,
+                c if c == RET|A => return a,
+                c if c == RET|X => return x,
+
+                // misc
+                c if c == MISC|TAX => x = a,
+                c if c == MISC|TXA => a = x,
+
+                // can not be reached as the opcodes are checked during the validation
+                _ => return 0,
+            }
+        }
+    }
+}
+
+/// Bytes a program is executed against (a continous frame or the parts of a sliced packet).
+trait PacketBytes {
+    fn len(&self) -> usize;
+
+    /// Loads a big endian value with the given size (1, 2 or 4 bytes).
+    fn load(&self, offset: usize, size: usize) -> Option<u32>;
+}
+
+impl PacketBytes for &[u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn load(&self, offset: usize, size: usize) -> Option<u32> {
+        let end = offset.checked_add(size)?;
+        let bytes = self.get(offset..end)?;
+        Some(bytes.iter().fold(0u32, |acc, b| (acc << 8) | u32::from(*b)))
+    }
+}
+
+impl PacketBytes for [&[u8];11] {
+    fn len(&self) -> usize {
+        self.iter().map(|part| part.len()).sum()
+    }
+
+    fn load(&self, offset: usize, size: usize) -> Option<u32> {
+        let mut result = 0u32;
+        for i in 0..size {
+            let mut pos = offset.checked_add(i)?;
+            let mut byte = None;
+            for part in self.iter() {
+                if pos < part.len() {
+                    byte = Some(part[pos]);
+                    break;
+                }
+                pos -= part.len();
+            }
+            result = (result << 8) | u32::from(byte?);
+        }
+        Some(result)
+    }
+}
+
+/// Parses the four values `code, jt, jf & k` of an instruction.
+fn parse_instruction<'a, I, F>(mut values: I, parse: F) -> Option<BpfInstruction>
+where
+    I: Iterator<Item = &'a str>,
+    F: Fn(&str) -> Option<u32>,
+{
+    let code = parse(values.next()?)?;
+    let jt = parse(values.next()?)?;
+    let jf = parse(values.next()?)?;
+    let k = parse(values.next()?)?;
+    if values.next().is_some() || code > 0xffff || jt > 0xff || jf > 0xff {
+        return None;
+    }
+    Some(BpfInstruction::new(code as u16, jt as u8, jf as u8, k))
+}
+
+/// Parses a decimal or `0x` prefixed hex number.
+fn parse_c_number(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Checks the program with the same rules as `bpf_validate` in libpcap.
+fn validate(instructions: &[BpfInstruction]) -> Result<(), BpfError> {
+    if instructions.is_empty() {
+        return Err(BpfError::EmptyProgram);
+    }
+    if instructions.len() > MAX_INSTRUCTIONS {
+        return Err(BpfError::TooManyInstructions(instructions.len()));
+    }
+
+    for (index, ins) in instructions.iter().enumerate() {
+        let code = ins.code;
+        let invalid = Err(BpfError::InvalidOpcode{ index, code });
+        let mem_check = |k: u32| if (k as usize) < MEMORY_SLOTS {
+            Ok(())
+        } else {
+            Err(BpfError::InvalidMemoryIndex(index))
+        };
+        // number of instructions after the current one
+        let remaining = instructions.len() - index - 1;
+
+        match code & 0x07 {
+            LD | LDX => {
+                let valid = if LD == code & 0x07 {
+                    [LD|W|ABS, LD|H|ABS, LD|B|ABS, LD|W|IND, LD|H|IND, LD|B|IND, LD|W|LEN, LD|IMM, LD|MEM].contains(&code)
+                } else {
+                    [LDX|W|LEN, LDX|IMM, LDX|MEM, LDX|B|MSH].contains(&code)
+                };
+                if !valid {
+                    return invalid;
+                }
+                if MEM == code & 0xe0 {
+                    mem_check(ins.k)?;
+                }
+            },
+            ST | STX => {
+                if code & !0x07 != 0 {
+                    return invalid;
+                }
+                mem_check(ins.k)?;
+            },
+            ALU => {
+                let op = code & 0xf0;
+                if code & !0xff != 0 || op > XOR || (NEG == op && code & X == X) {
+                    return invalid;
+                }
+                if (DIV == op || MOD == op) && code & X == K && 0 == ins.k {
+                    return Err(BpfError::DivisionByZero(index));
+                }
+            },
+            JMP => {
+                let op = code & 0xf0;
+                if code & !0xff != 0 || op > JSET || (JA == op && code & X == X) {
+                    return invalid;
+                }
+                let in_bounds = if JA == op {
+                    (ins.k as usize) < remaining
+                } else {
+                    usize::from(ins.jt) < remaining && usize::from(ins.jf) < remaining
+                };
+                if !in_bounds {
+                    return Err(BpfError::JumpOutOfBounds(index));
+                }
+            },
+            RET => {
+                if ![RET|K, RET|A, RET|X].contains(&code) {
+                    return invalid;
+                }
+            },
+            _ => { // MISC
+                if code != MISC|TAX && code != MISC|TXA {
+                    return invalid;
+                }
+            },
+        }
+    }
+
+    if RET != instructions[instructions.len() - 1].code & 0x07 {
+        return Err(BpfError::MissingReturn);
+    }
+    Ok(())
+}
@@ -140,6 +140,18 @@ impl Sum16BitWords {
     }
 }
 
+/// Updates a checksum after a 16 bit word of the checksummed data was
+/// changed from `old` to `new` without recalculating the complete checksum
+/// (RFC 1624, equation 3). All values are in network byte order.
+pub fn incremental_update(checksum: [u8;2], old: [u8;2], new: [u8;2]) -> [u8;2] {
+    let sum = u32::from(!u16::from_be_bytes(checksum))
+        + u32::from(!u16::from_be_bytes(old))
+        + u32::from(u16::from_be_bytes(new));
+    let folded = (sum >> 16) + (sum & 0xffff);
+    let folded = (folded >> 16) + (folded & 0xffff);
+    (!(folded as u16)).to_be_bytes()
+}
+
 #[cfg(test)]
 mod sum16_bit_words_tests {
     use super::*;
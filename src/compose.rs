@@ -0,0 +1,857 @@
+use super::*;
+
+use std::borrow::Cow;
+use std::ops::Div;
+
+/// A single layer of a [`LayerStack`] (a header or the payload).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Layer {
+    Ethernet2(Ethernet2Header),
+    SingleVlan(SingleVlanHeader),
+    DoubleVlan(DoubleVlanHeader),
+    Ipv4(Ipv4Header),
+    Ipv6(Ipv6Header),
+    Udp(UdpHeader),
+    Tcp(TcpHeader),
+    Vxlan(VxlanHeader),
+    Geneve(GeneveHeader),
+    Gtpu(GtpuHeader),
+    Pppoe(PppoeHeader),
+    /// Header serialized at compile time (see [`StaticHeader`]).
+    Static(StaticHeader),
+    /// Raw bytes (e.g. the payload or an unsupported header).
+    Payload(Vec<u8>),
+}
+
+impl Layer {
+    /// Length of the serialized layer in bytes.
+    pub fn len(&self) -> usize {
+        use Layer::*;
+        match self {
+            Ethernet2(value) => value.header_len(),
+            SingleVlan(value) => value.header_len(),
+            DoubleVlan(value) => value.header_len(),
+            Ipv4(value) => value.header_len(),
+            Ipv6(value) => value.header_len(),
+            Udp(value) => value.header_len(),
+            Tcp(value) => usize::from(value.header_len()),
+            Vxlan(value) => value.header_len(),
+            Geneve(value) => value.header_len(),
+            Gtpu(value) => value.header_len(),
+            Pppoe(value) => value.header_len(),
+            Static(value) => value.bytes().len(),
+            Payload(value) => value.len(),
+        }
+    }
+
+    /// Returns true if the layer has no serialized bytes (only possible
+    /// for an empty payload).
+    pub fn is_empty(&self) -> bool {
+        0 == self.len()
+    }
+
+    /// Ether type identifying the layer (if it can follow an ethernet or vlan header).
+    fn ether_type(&self) -> Option<u16> {
+        use Layer::*;
+        match self {
+            SingleVlan(value) => Some(value.tpid),
+            DoubleVlan(value) => Some(value.outer.tpid),
+            Ipv4(_) => Some(ether_type::IPV4),
+            Ipv6(_) => Some(ether_type::IPV6),
+            Pppoe(value) => Some(
+                if value.ppp_protocol.is_some() {
+                    ether_type::PPPOE_SESSION
+                } else {
+                    ether_type::PPPOE_DISCOVERY
+                }
+            ),
+            Static(value) => match value.kind() {
+                StaticHeaderKind::Ipv4 => Some(ether_type::IPV4),
+                StaticHeaderKind::Ipv6 => Some(ether_type::IPV6),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// IP number identifying the layer (if it can follow an ip header).
+    fn ip_number(&self) -> Option<u8> {
+        use Layer::*;
+        match self {
+            Ipv4(_) => Some(ip_number::IPV4),
+            Ipv6(_) => Some(ip_number::IPV6),
+            Udp(_) => Some(ip_number::UDP),
+            Tcp(_) => Some(ip_number::TCP),
+            Static(value) => match value.kind() {
+                StaticHeaderKind::Ethernet2 => None,
+                StaticHeaderKind::Ipv4 => Some(ip_number::IPV4),
+                StaticHeaderKind::Ipv6 => Some(ip_number::IPV6),
+                StaticHeaderKind::Udp => Some(ip_number::UDP),
+                StaticHeaderKind::Tcp => Some(ip_number::TCP),
+            },
+            _ => None,
+        }
+    }
+
+    /// Source & destination address used in the pseudo header of the
+    /// transport checksums (if the layer is an ip header).
+    fn ip_addresses(&self) -> Option<IpAddresses> {
+        use Layer::*;
+        match self {
+            Ipv4(value) => Some(IpAddresses::V4(value.source, value.destination)),
+            Ipv6(value) => Some(IpAddresses::V6(value.source, value.destination)),
+            Static(value) => {
+                let bytes = value.bytes();
+                match value.kind() {
+                    StaticHeaderKind::Ipv4 => {
+                        let mut source = [0u8;4];
+                        source.copy_from_slice(&bytes[12..16]);
+                        let mut destination = [0u8;4];
+                        destination.copy_from_slice(&bytes[16..20]);
+                        Some(IpAddresses::V4(source, destination))
+                    },
+                    StaticHeaderKind::Ipv6 => {
+                        let mut source = [0u8;16];
+                        source.copy_from_slice(&bytes[8..24]);
+                        let mut destination = [0u8;16];
+                        destination.copy_from_slice(&bytes[24..40]);
+                        Some(IpAddresses::V6(source, destination))
+                    },
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Sets the checksum of an udp or tcp layer.
+    fn set_transport_checksum(&mut self, checksum: u16) {
+        use Layer::*;
+        match self {
+            Udp(value) => value.checksum = checksum,
+            Tcp(value) => value.checksum = checksum,
+            Static(value) => {
+                let offset = match value.kind() {
+                    StaticHeaderKind::Udp => 6,
+                    StaticHeaderKind::Tcp => 16,
+                    _ => return,
+                };
+                value.bytes.to_mut()[offset..offset + 2].copy_from_slice(&checksum.to_be_bytes());
+            },
+            _ => {},
+        }
+    }
+
+    fn write(&self, output: &mut Vec<u8>) -> Result<(), WriteError> {
+        use Layer::*;
+        match self {
+            Ethernet2(value) => value.write(output)?,
+            SingleVlan(value) => value.write(output)?,
+            DoubleVlan(value) => value.write(output)?,
+            Ipv4(value) => value.write(output)?,
+            Ipv6(value) => value.write(output)?,
+            Udp(value) => value.write(output)?,
+            Tcp(value) => value.write(output)?,
+            Vxlan(value) => value.write(output)?,
+            Geneve(value) => value.write(output)?,
+            Gtpu(value) => value.write(output)?,
+            Pppoe(value) => value.write(output)?,
+            Static(value) => output.extend_from_slice(value.bytes()),
+            Payload(value) => output.extend_from_slice(value),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum IpAddresses {
+    V4([u8;4], [u8;4]),
+    V6([u8;16], [u8;16]),
+}
+
+/// Header types that can be serialized at compile time by the
+/// [`crate::packet!`] macro.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum StaticHeaderKind {
+    Ethernet2,
+    Ipv4,
+    Ipv6,
+    Udp,
+    Tcp,
+}
+
+/// Header serialized at compile time, created by the [`crate::packet!`]
+/// macro for layers only containing literal field values.
+///
+/// The bytes are used as they are, except for the next header, length &
+/// checksum fields that get patched when the stack is serialized (see
+/// [`LayerStack`]). IPv4 & TCP headers never contain options.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StaticHeader {
+    kind: StaticHeaderKind,
+    bytes: Cow<'static, [u8]>,
+}
+
+impl StaticHeader {
+    /// Creates a static Ethernet II header.
+    pub const fn ethernet2(bytes: &'static [u8;14]) -> StaticHeader {
+        StaticHeader{ kind: StaticHeaderKind::Ethernet2, bytes: Cow::Borrowed(bytes) }
+    }
+
+    /// Creates a static IPv4 header (without options).
+    pub const fn ipv4(bytes: &'static [u8;20]) -> StaticHeader {
+        StaticHeader{ kind: StaticHeaderKind::Ipv4, bytes: Cow::Borrowed(bytes) }
+    }
+
+    /// Creates a static IPv6 header.
+    pub const fn ipv6(bytes: &'static [u8;40]) -> StaticHeader {
+        StaticHeader{ kind: StaticHeaderKind::Ipv6, bytes: Cow::Borrowed(bytes) }
+    }
+
+    /// Creates a static UDP header.
+    pub const fn udp(bytes: &'static [u8;8]) -> StaticHeader {
+        StaticHeader{ kind: StaticHeaderKind::Udp, bytes: Cow::Borrowed(bytes) }
+    }
+
+    /// Creates a static TCP header (without options).
+    pub const fn tcp(bytes: &'static [u8;20]) -> StaticHeader {
+        StaticHeader{ kind: StaticHeaderKind::Tcp, bytes: Cow::Borrowed(bytes) }
+    }
+
+    /// Type of the header.
+    pub fn kind(&self) -> StaticHeaderKind {
+        self.kind
+    }
+
+    /// Serialized header.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Patches the next header & length fields (the bytes only get copied
+    /// if a field is changed).
+    fn resolve(&mut self, next_ether_type: Option<u16>, next_ip_number: Option<u8>, following_len: usize) -> Result<(), ValueError> {
+        use StaticHeaderKind::*;
+        match self.kind {
+            Ethernet2 => if let Some(value) = next_ether_type {
+                self.patch(12, &value.to_be_bytes());
+            },
+            Ipv4 => {
+                if following_len > usize::from(u16::MAX) - Ipv4Header::SERIALIZED_SIZE {
+                    return Err(ValueError::Ipv4PayloadLengthTooLarge(following_len));
+                }
+                if let Some(value) = next_ip_number {
+                    self.patch(9, &[value]);
+                }
+                self.patch(2, &((Ipv4Header::SERIALIZED_SIZE + following_len) as u16).to_be_bytes());
+                self.patch(10, &[0, 0]);
+                let checksum = checksum::Sum16BitWords::new()
+                    .add_slice(&self.bytes)
+                    .ones_complement()
+                    .to_be();
+                self.patch(10, &checksum.to_be_bytes());
+            },
+            Ipv6 => {
+                if following_len > usize::from(u16::MAX) {
+                    return Err(ValueError::Ipv6PayloadLengthTooLarge(following_len));
+                }
+                if let Some(value) = next_ip_number {
+                    self.patch(6, &[value]);
+                }
+                self.patch(4, &(following_len as u16).to_be_bytes());
+            },
+            Udp => {
+                if following_len > usize::from(u16::MAX) - UdpHeader::SERIALIZED_SIZE {
+                    return Err(ValueError::UdpPayloadLengthTooLarge(following_len));
+                }
+                self.patch(4, &((UdpHeader::SERIALIZED_SIZE + following_len) as u16).to_be_bytes());
+            },
+            Tcp => {},
+        }
+        Ok(())
+    }
+
+    fn patch(&mut self, offset: usize, value: &[u8]) {
+        if &self.bytes[offset..offset + value.len()] != value {
+            self.bytes.to_mut()[offset..offset + value.len()].copy_from_slice(value);
+        }
+    }
+}
+
+/// Packet description consisting of a stack of layers, created by composing
+/// headers & payloads with the `/` operator.
+///
+/// In contrast to the [`crate::PacketBuilder`] the layers can be stacked in
+/// any order. When the stack gets serialized the following fields get
+/// resolved automatically:
+///
+/// * `ether_type` of ethernet & vlan headers followed by a vlan, PPPoE or ip header
+/// * `ppp_protocol` of PPPoE session headers followed by an ip header
+/// * `protocol`/`next_header` of ip headers followed by an ip, udp or tcp header
+/// * payload length fields of the ip, udp, GTP-U & PPPoE headers
+/// * checksums of the ipv4, udp & tcp headers (transport checksums are
+///   calculated based on the nearest preceding ip header)
+///
+/// All other fields are written as set.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// let stack = Ethernet2Header{
+///         source: [1,2,3,4,5,6],
+///         destination: [7,8,9,10,11,12],
+///         ether_type: 0, // resolved during serialization
+///     } /
+///     Ipv4Header::new(0, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2]) /
+///     UdpHeader{ source_port: 1234, destination_port: 53, length: 0, checksum: 0 } /
+///     [1,2,3,4];
+///
+/// let bytes = stack.to_bytes().unwrap();
+/// assert_eq!(stack.len(), bytes.len());
+///
+/// let sliced = SlicedPacket::from_ethernet(&bytes).unwrap();
+/// assert_eq!(&[1,2,3,4], sliced.payload);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LayerStack {
+    /// Layers in the order they are serialized.
+    pub layers: Vec<Layer>,
+}
+
+impl LayerStack {
+    /// Creates an empty stack.
+    pub fn new() -> LayerStack {
+        Default::default()
+    }
+
+    /// Adds a layer at the end of the stack.
+    pub fn push<L: Into<Layer>>(&mut self, layer: L) {
+        self.layers.push(layer.into());
+    }
+
+    /// Length of the serialized packet in bytes.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(|l| l.len()).sum()
+    }
+
+    /// Returns true if the serialized packet would contain no bytes.
+    pub fn is_empty(&self) -> bool {
+        0 == self.len()
+    }
+
+    /// Returns the layers with all next header, length & checksum fields resolved.
+    pub fn resolved(&self) -> Result<Vec<Layer>, WriteError> {
+        let mut layers = self.layers.clone();
+
+        // next header fields & lengths
+        let mut following_len = self.len();
+        for i in 0..layers.len() {
+            following_len -= layers[i].len();
+            let next_ether_type = layers.get(i + 1).and_then(|l| l.ether_type());
+            let next_ip_number = layers.get(i + 1).and_then(|l| l.ip_number());
+            match &mut layers[i] {
+                Layer::Ethernet2(header) => {
+                    if let Some(value) = next_ether_type {
+                        header.ether_type = value;
+                    }
+                },
+                Layer::SingleVlan(header) => {
+                    if let Some(value) = next_ether_type {
+                        header.ether_type = value;
+                    }
+                },
+                Layer::DoubleVlan(header) => {
+                    header.outer.ether_type = header.inner.tpid;
+                    if let Some(value) = next_ether_type {
+                        header.inner.ether_type = value;
+                    }
+                },
+                Layer::Ipv4(header) => {
+                    if let Some(value) = next_ip_number {
+                        header.protocol = value;
+                    }
+                    header.set_payload_len(following_len)?;
+                },
+                Layer::Ipv6(header) => {
+                    if let Some(value) = next_ip_number {
+                        header.next_header = value;
+                    }
+                    header.set_payload_length(following_len)?;
+                },
+                Layer::Udp(header) => {
+                    if following_len > usize::from(u16::MAX) - UdpHeader::SERIALIZED_SIZE {
+                        return Err(ValueError::UdpPayloadLengthTooLarge(following_len).into());
+                    }
+                    header.length = (UdpHeader::SERIALIZED_SIZE + following_len) as u16;
+                },
+                Layer::Pppoe(header) => {
+                    if header.ppp_protocol.is_some() {
+                        match next_ether_type {
+                            Some(ether_type::IPV4) => header.ppp_protocol = Some(ppp_protocol::IPV4),
+                            Some(ether_type::IPV6) => header.ppp_protocol = Some(ppp_protocol::IPV6),
+                            _ => {},
+                        }
+                    }
+                    header.set_payload_len(following_len)?;
+                },
+                Layer::Gtpu(header) => {
+                    header.set_payload_len(following_len)?;
+                },
+                Layer::Static(header) => {
+                    header.resolve(next_ether_type, next_ip_number, following_len)?;
+                },
+                Layer::Tcp(_) | Layer::Vxlan(_) | Layer::Geneve(_) | Layer::Payload(_) => {},
+            }
+        }
+
+        // transport checksums (calculated from the back as inner checksums
+        // are part of the payload of outer layers)
+        let mut bytes = Vec::with_capacity(self.len());
+        let mut offsets = Vec::with_capacity(layers.len());
+        for layer in &layers {
+            offsets.push(bytes.len());
+            layer.write(&mut bytes)?;
+        }
+        for i in (0..layers.len()).rev() {
+            let addresses = layers[..i].iter().rev().find_map(|l| l.ip_addresses());
+            // the headers were serialized above, so they can be sliced
+            let header = &bytes[offsets[i]..offsets[i] + layers[i].len()];
+            let payload = &bytes[offsets[i] + layers[i].len()..];
+            let checksum = match (layers[i].ip_number(), addresses) {
+                (Some(ip_number::UDP), Some(IpAddresses::V4(source, destination))) => {
+                    UdpHeaderSlice::from_slice(header).unwrap().calc_checksum_ipv4_raw(source, destination, payload)?
+                },
+                (Some(ip_number::UDP), Some(IpAddresses::V6(source, destination))) => {
+                    UdpHeaderSlice::from_slice(header).unwrap().calc_checksum_ipv6_raw(source, destination, payload)?
+                },
+                (Some(ip_number::TCP), Some(IpAddresses::V4(source, destination))) => {
+                    TcpHeaderSlice::from_slice(header).unwrap().calc_checksum_ipv4_raw(source, destination, payload)?
+                },
+                (Some(ip_number::TCP), Some(IpAddresses::V6(source, destination))) => {
+                    TcpHeaderSlice::from_slice(header).unwrap().calc_checksum_ipv6_raw(source, destination, payload)?
+                },
+                _ => continue,
+            };
+            layers[i].set_transport_checksum(checksum);
+            let mut header = Vec::with_capacity(layers[i].len());
+            layers[i].write(&mut header)?;
+            bytes[offsets[i]..offsets[i] + header.len()].copy_from_slice(&header);
+        }
+        Ok(layers)
+    }
+
+    /// Serializes the stack (see [`LayerStack`] for the fields that get resolved).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WriteError> {
+        let mut result = Vec::with_capacity(self.len());
+        for layer in self.resolved()? {
+            layer.write(&mut result)?;
+        }
+        Ok(result)
+    }
+
+    /// Serializes the stack to the given writer.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+impl<L: Into<Layer>> Div<L> for LayerStack {
+    type Output = LayerStack;
+
+    fn div(mut self, rhs: L) -> LayerStack {
+        self.push(rhs);
+        self
+    }
+}
+
+impl From<Vec<u8>> for Layer {
+    fn from(value: Vec<u8>) -> Layer {
+        Layer::Payload(value)
+    }
+}
+
+impl From<&[u8]> for Layer {
+    fn from(value: &[u8]) -> Layer {
+        Layer::Payload(value.to_vec())
+    }
+}
+
+impl<const N: usize> From<&[u8;N]> for Layer {
+    fn from(value: &[u8;N]) -> Layer {
+        Layer::Payload(value.to_vec())
+    }
+}
+
+macro_rules! impl_header_layer {
+    ($($header:ident => $variant:ident),*) => {
+        $(
+            impl From<$header> for Layer {
+                fn from(value: $header) -> Layer {
+                    Layer::$variant(value)
+                }
+            }
+
+            impl<L: Into<Layer>> Div<L> for $header {
+                type Output = LayerStack;
+
+                fn div(self, rhs: L) -> LayerStack {
+                    LayerStack{
+                        layers: vec![self.into(), rhs.into()],
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_header_layer!(
+    Ethernet2Header => Ethernet2,
+    SingleVlanHeader => SingleVlan,
+    DoubleVlanHeader => DoubleVlan,
+    Ipv4Header => Ipv4,
+    Ipv6Header => Ipv6,
+    UdpHeader => Udp,
+    TcpHeader => Tcp,
+    VxlanHeader => Vxlan,
+    GeneveHeader => Geneve,
+    GtpuHeader => Gtpu,
+    PppoeHeader => Pppoe
+);
+
+impl<const N: usize> From<[u8;N]> for Layer {
+    fn from(value: [u8;N]) -> Layer {
+        Layer::Payload(value.to_vec())
+    }
+}
+
+/// Const counterparts of the headers, used by the [`crate::packet!`] macro
+/// to serialize layers only containing literal values at compile time.
+///
+/// The fields & default values match the ones of the regular headers. The
+/// next header, length & checksum fields are patched when the stack gets
+/// serialized (see [`StaticHeader`]).
+#[doc(hidden)]
+pub mod __private {
+
+    pub struct Ethernet2Header {
+        pub source: [u8;6],
+        pub destination: [u8;6],
+        pub ether_type: u16,
+    }
+
+    impl Ethernet2Header {
+        pub const DEFAULT: Ethernet2Header = Ethernet2Header{
+            source: [0;6],
+            destination: [0;6],
+            ether_type: 0,
+        };
+
+        pub const fn to_bytes(&self) -> [u8;14] {
+            let mut result = [0u8;14];
+            let mut i = 0;
+            while i < 6 {
+                result[i] = self.destination[i];
+                result[6 + i] = self.source[i];
+                i += 1;
+            }
+            let ether_type = self.ether_type.to_be_bytes();
+            result[12] = ether_type[0];
+            result[13] = ether_type[1];
+            result
+        }
+    }
+
+    pub struct Ipv4Header {
+        pub payload_len: u16,
+        pub identification: u16,
+        pub dont_fragment: bool,
+        pub more_fragments: bool,
+        pub fragments_offset: u16,
+        pub time_to_live: u8,
+        pub protocol: u8,
+        pub header_checksum: u16,
+        pub source: [u8;4],
+        pub destination: [u8;4],
+    }
+
+    impl Ipv4Header {
+        pub const DEFAULT: Ipv4Header = Ipv4Header{
+            payload_len: 0,
+            identification: 0,
+            dont_fragment: true,
+            more_fragments: false,
+            fragments_offset: 0,
+            time_to_live: 0,
+            protocol: 0,
+            header_checksum: 0,
+            source: [0;4],
+            destination: [0;4],
+        };
+
+        pub const fn to_bytes(&self) -> [u8;20] {
+            assert!(self.fragments_offset <= 0x1fff, "ipv4 fragments_offset is bigger than 0x1fff");
+            let total_len = self.payload_len.wrapping_add(20).to_be_bytes();
+            let identification = self.identification.to_be_bytes();
+            let fragments_offset = self.fragments_offset.to_be_bytes();
+            let mut flags = 0;
+            if self.dont_fragment {
+                flags |= 64;
+            }
+            if self.more_fragments {
+                flags |= 32;
+            }
+            let header_checksum = self.header_checksum.to_be_bytes();
+            [
+                0x45, 0, total_len[0], total_len[1],
+                identification[0], identification[1], flags | fragments_offset[0], fragments_offset[1],
+                self.time_to_live, self.protocol, header_checksum[0], header_checksum[1],
+                self.source[0], self.source[1], self.source[2], self.source[3],
+                self.destination[0], self.destination[1], self.destination[2], self.destination[3],
+            ]
+        }
+    }
+
+    pub struct Ipv6Header {
+        pub traffic_class: u8,
+        pub flow_label: u32,
+        pub payload_length: u16,
+        pub next_header: u8,
+        pub hop_limit: u8,
+        pub source: [u8;16],
+        pub destination: [u8;16],
+    }
+
+    impl Ipv6Header {
+        pub const DEFAULT: Ipv6Header = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 0,
+            next_header: 0,
+            hop_limit: 0,
+            source: [0;16],
+            destination: [0;16],
+        };
+
+        pub const fn to_bytes(&self) -> [u8;40] {
+            assert!(self.flow_label <= 0xfffff, "ipv6 flow_label is bigger than 0xfffff");
+            let flow_label = self.flow_label.to_be_bytes();
+            let payload_length = self.payload_length.to_be_bytes();
+            let mut result = [0u8;40];
+            result[0] = (6 << 4) | (self.traffic_class >> 4);
+            result[1] = (self.traffic_class << 4) | flow_label[1];
+            result[2] = flow_label[2];
+            result[3] = flow_label[3];
+            result[4] = payload_length[0];
+            result[5] = payload_length[1];
+            result[6] = self.next_header;
+            result[7] = self.hop_limit;
+            let mut i = 0;
+            while i < 16 {
+                result[8 + i] = self.source[i];
+                result[24 + i] = self.destination[i];
+                i += 1;
+            }
+            result
+        }
+    }
+
+    pub struct UdpHeader {
+        pub source_port: u16,
+        pub destination_port: u16,
+        pub length: u16,
+        pub checksum: u16,
+    }
+
+    impl UdpHeader {
+        pub const DEFAULT: UdpHeader = UdpHeader{
+            source_port: 0,
+            destination_port: 0,
+            length: 0,
+            checksum: 0,
+        };
+
+        pub const fn to_bytes(&self) -> [u8;8] {
+            let source_port = self.source_port.to_be_bytes();
+            let destination_port = self.destination_port.to_be_bytes();
+            let length = self.length.to_be_bytes();
+            let checksum = self.checksum.to_be_bytes();
+            [
+                source_port[0], source_port[1], destination_port[0], destination_port[1],
+                length[0], length[1], checksum[0], checksum[1],
+            ]
+        }
+    }
+
+    pub struct TcpHeader {
+        pub source_port: u16,
+        pub destination_port: u16,
+        pub sequence_number: u32,
+        pub acknowledgment_number: u32,
+        pub ns: bool,
+        pub fin: bool,
+        pub syn: bool,
+        pub rst: bool,
+        pub psh: bool,
+        pub ack: bool,
+        pub urg: bool,
+        pub ece: bool,
+        pub cwr: bool,
+        pub window_size: u16,
+        pub checksum: u16,
+        pub urgent_pointer: u16,
+    }
+
+    impl TcpHeader {
+        pub const DEFAULT: TcpHeader = TcpHeader{
+            source_port: 0,
+            destination_port: 0,
+            sequence_number: 0,
+            acknowledgment_number: 0,
+            ns: false,
+            fin: false,
+            syn: false,
+            rst: false,
+            psh: false,
+            ack: false,
+            urg: false,
+            ece: false,
+            cwr: false,
+            window_size: 0,
+            checksum: 0,
+            urgent_pointer: 0,
+        };
+
+        pub const fn to_bytes(&self) -> [u8;20] {
+            let source_port = self.source_port.to_be_bytes();
+            let destination_port = self.destination_port.to_be_bytes();
+            let sequence_number = self.sequence_number.to_be_bytes();
+            let acknowledgment_number = self.acknowledgment_number.to_be_bytes();
+            let window_size = self.window_size.to_be_bytes();
+            let checksum = self.checksum.to_be_bytes();
+            let urgent_pointer = self.urgent_pointer.to_be_bytes();
+            let flags = (self.fin as u8)
+                | ((self.syn as u8) << 1)
+                | ((self.rst as u8) << 2)
+                | ((self.psh as u8) << 3)
+                | ((self.ack as u8) << 4)
+                | ((self.urg as u8) << 5)
+                | ((self.ece as u8) << 6)
+                | ((self.cwr as u8) << 7);
+            [
+                source_port[0], source_port[1], destination_port[0], destination_port[1],
+                sequence_number[0], sequence_number[1], sequence_number[2], sequence_number[3],
+                acknowledgment_number[0], acknowledgment_number[1], acknowledgment_number[2], acknowledgment_number[3],
+                (5 << 4) | (self.ns as u8), flags, window_size[0], window_size[1],
+                checksum[0], checksum[1], urgent_pointer[0], urgent_pointer[1],
+            ]
+        }
+    }
+}
+
+/// Creates a [`compose::LayerStack`] from a concise list of layers & fields.
+///
+/// Each layer is written as the name of the layer followed by the fields
+/// that differ from the default values of the header. The supported layers
+/// are `ethernet2`, `single_vlan`, `double_vlan`, `ipv4`, `ipv6`, `udp`,
+/// `tcp` & `payload(..)` (anything that converts into a [`compose::Layer`]).
+///
+/// `ethernet2`, `ipv4`, `ipv6`, `udp` & `tcp` layers whose field values are
+/// all literals (numbers, booleans & arrays of them) are serialized at
+/// compile time into a [`compose::StaticHeader`]. Only the next header,
+/// length & checksum fields of these layers are patched at runtime, when the
+/// stack gets serialized. Invalid literal values (e.g. an IPv4
+/// `fragments_offset` bigger than `0x1fff`) are therefore reported at
+/// compile time.
+///
+/// Layers containing any other expression (e.g. a variable) are created as
+/// regular headers at runtime & all their fields are resolved as for any
+/// other [`compose::LayerStack`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{packet, SlicedPacket, compose::Layer};
+///
+/// let dynamic_port = 1234;
+/// let stack = packet![
+///     ethernet2 { source: [1,2,3,4,5,6], destination: [7,8,9,10,11,12] },
+///     ipv4 { source: [192,168,1,1], destination: [192,168,1,2], time_to_live: 64 },
+///     udp { source_port: dynamic_port, destination_port: 53 },
+///     payload([1,2,3,4]),
+/// ];
+/// // the literal only layers got serialized at compile time
+/// assert!(matches!(stack.layers[1], Layer::Static(_)));
+/// assert!(matches!(stack.layers[2], Layer::Udp(_)));
+///
+/// let bytes = stack.to_bytes().unwrap();
+/// let sliced = SlicedPacket::from_ethernet(&bytes).unwrap();
+/// assert_eq!(&[1,2,3,4], sliced.payload);
+/// ```
+#[macro_export]
+macro_rules! packet {
+    (@header $header:path $(, $field:ident : $value:expr)*) => {{
+        #[allow(unused_mut)]
+        let mut header = <$header>::default();
+        $( header.$field = $value; )*
+        $crate::compose::Layer::from(header)
+    }};
+    (@static $header:ident $constructor:ident $len:literal $($field:ident : $value:expr,)*) => {{
+        const BYTES: [u8; $len] = {
+            #[allow(unused_mut)]
+            let mut header = $crate::compose::__private::$header::DEFAULT;
+            $( header.$field = $value; )*
+            header.to_bytes()
+        };
+        $crate::compose::Layer::Static($crate::compose::StaticHeader::$constructor(&BYTES))
+    }};
+    // checks field by field if all values are literals
+    (@classify $info:tt $fields:tt [$($done:tt)*] $field:ident : $value:literal $(, $($rest:tt)*)?) => {
+        $crate::packet!(@classify $info $fields [$($done)* $field : $value,] $($($rest)*)?)
+    };
+    (@classify $info:tt $fields:tt [$($done:tt)*] $field:ident : [$($elem:literal),*] $(, $($rest:tt)*)?) => {
+        $crate::packet!(@classify $info $fields [$($done)* $field : [$($elem),*],] $($($rest)*)?)
+    };
+    (@classify $info:tt $fields:tt [$($done:tt)*] $field:ident : [$elem:literal ; $count:literal] $(, $($rest:tt)*)?) => {
+        $crate::packet!(@classify $info $fields [$($done)* $field : [$elem ; $count],] $($($rest)*)?)
+    };
+    (@classify ($header:ident $constructor:ident $len:literal $typed:path) $fields:tt [$($done:tt)*]) => {
+        $crate::packet!(@static $header $constructor $len $($done)*)
+    };
+    (@classify ($header:ident $constructor:ident $len:literal $typed:path) { $($field:ident : $value:expr),* $(,)? } [$($done:tt)*] $($rest:tt)*) => {
+        $crate::packet!(@header $typed $(, $field : $value)*)
+    };
+    (@layer payload ($payload:expr)) => {
+        $crate::compose::Layer::from($payload)
+    };
+    (@layer ethernet2 { $($fields:tt)* }) => {
+        $crate::packet!(@classify (Ethernet2Header ethernet2 14 $crate::Ethernet2Header) { $($fields)* } [] $($fields)*)
+    };
+    (@layer single_vlan { $($field:ident : $value:expr),* $(,)? }) => {
+        $crate::packet!(@header $crate::SingleVlanHeader $(, $field : $value)*)
+    };
+    (@layer double_vlan { $($field:ident : $value:expr),* $(,)? }) => {
+        $crate::packet!(@header $crate::DoubleVlanHeader $(, $field : $value)*)
+    };
+    (@layer ipv4 { $($fields:tt)* }) => {
+        $crate::packet!(@classify (Ipv4Header ipv4 20 $crate::Ipv4Header) { $($fields)* } [] $($fields)*)
+    };
+    (@layer ipv6 { $($fields:tt)* }) => {
+        $crate::packet!(@classify (Ipv6Header ipv6 40 $crate::Ipv6Header) { $($fields)* } [] $($fields)*)
+    };
+    (@layer udp { $($fields:tt)* }) => {
+        $crate::packet!(@classify (UdpHeader udp 8 $crate::UdpHeader) { $($fields)* } [] $($fields)*)
+    };
+    (@layer tcp { $($fields:tt)* }) => {
+        $crate::packet!(@classify (TcpHeader tcp 20 $crate::TcpHeader) { $($fields)* } [] $($fields)*)
+    };
+    ($($layer:ident $({ $($body:tt)* })? $(( $payload:expr ))?),+ $(,)?) => {{
+        let mut stack = $crate::compose::LayerStack::new();
+        $(
+            stack.push($crate::packet!(@layer $layer $({ $($body)* })? $(( $payload ))?));
+        )+
+        stack
+    }};
+}
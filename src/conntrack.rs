@@ -0,0 +1,369 @@
+use super::*;
+
+use crate::stats::Counter;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Direction of a packet relative to the order of the endpoints in a [`FlowKey`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum FlowDirection {
+    /// Packet was sent from the `lower` to the `upper` endpoint.
+    LowerToUpper,
+    /// Packet was sent from the `upper` to the `lower` endpoint.
+    UpperToLower,
+}
+
+impl FlowDirection {
+    /// Returns the opposite direction.
+    #[inline]
+    pub fn reverse(self) -> FlowDirection {
+        use FlowDirection::*;
+        match self {
+            LowerToUpper => UpperToLower,
+            UpperToLower => LowerToUpper,
+        }
+    }
+}
+
+/// Bidirectional key identifying a flow (both directions of a connection
+/// result in the same key).
+///
+/// The endpoints (ip address & port) are sorted so that `lower` is always
+/// less or equal to `upper`. For protocols without ports (or packets where
+/// the transport header was not decoded, e.g. fragments) the ports are set to 0.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct FlowKey {
+    /// Ip protocol number of the ip payload (e.g. [`ip_number::TCP`]).
+    pub ip_number: u8,
+    /// Endpoint with the lower ip address & port.
+    pub lower: (IpAddr, u16),
+    /// Endpoint with the higher ip address & port.
+    pub upper: (IpAddr, u16),
+}
+
+impl FlowKey {
+    /// Creates a flow key based on the source & destination endpoints of a packet.
+    ///
+    /// Returns the key together with the direction of the packet.
+    pub fn new(ip_number: u8, source: (IpAddr, u16), destination: (IpAddr, u16)) -> (FlowKey, FlowDirection) {
+        if source <= destination {
+            (FlowKey{ ip_number, lower: source, upper: destination }, FlowDirection::LowerToUpper)
+        } else {
+            (FlowKey{ ip_number, lower: destination, upper: source }, FlowDirection::UpperToLower)
+        }
+    }
+
+    /// Creates a flow key based on the ip & transport layer of a sliced packet.
+    ///
+    /// Returns None if the packet contains no ip header.
+    pub fn from_sliced_packet(packet: &SlicedPacket) -> Option<(FlowKey, FlowDirection)> {
+        use InternetSlice::*;
+        let (source, destination) = match packet.ip.as_ref()? {
+            Ipv4(header, _) => (IpAddr::from(header.source()), IpAddr::from(header.destination())),
+            Ipv6(header, _) => (IpAddr::from(header.source()), IpAddr::from(header.destination())),
+        };
+        let (ip_number, source_port, destination_port) = match &packet.transport {
+            Some(TransportSlice::Udp(udp)) => (ip_number::UDP, udp.source_port(), udp.destination_port()),
+            Some(TransportSlice::Tcp(tcp)) => (ip_number::TCP, tcp.source_port(), tcp.destination_port()),
+            Some(TransportSlice::Igmp(_)) => (ip_number::IGMP, 0, 0),
+            Some(TransportSlice::Unknown(value)) => (*value, 0, 0),
+            None => (packet.ip.as_ref()?.payload_ip_number(), 0, 0),
+        };
+        Some(FlowKey::new(ip_number, (source, source_port), (destination, destination_port)))
+    }
+}
+
+/// Simplified TCP connection state tracked for TCP flows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TcpState {
+    /// SYN was sent by the originator.
+    SynSent,
+    /// SYN & ACK was sent by the responder.
+    SynReceived,
+    /// Handshake completed (or the connection was picked up
+    /// after the handshake).
+    Established,
+    /// One side has sent a FIN.
+    FinWait,
+    /// Both sides have sent a FIN or a RST was sent.
+    Closed,
+}
+
+/// Timeouts after which flows without any traffic are considered expired.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ConnTrackTimeouts {
+    /// Timeout for established tcp connections.
+    pub tcp_established: Duration,
+    /// Timeout for tcp connections during the handshake or teardown.
+    pub tcp_transitory: Duration,
+    /// Timeout for closed tcp connections.
+    pub tcp_closed: Duration,
+    /// Timeout for udp flows.
+    pub udp: Duration,
+    /// Timeout for flows of all other protocols.
+    pub other: Duration,
+}
+
+impl Default for ConnTrackTimeouts {
+    fn default() -> ConnTrackTimeouts {
+        ConnTrackTimeouts {
+            tcp_established: Duration::from_secs(2*60*60),
+            tcp_transitory: Duration::from_secs(2*60),
+            tcp_closed: Duration::from_secs(10),
+            udp: Duration::from_secs(30),
+            other: Duration::from_secs(60),
+        }
+    }
+}
+
+/// State of a tracked flow.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Flow {
+    /// Key identifying the flow.
+    pub key: FlowKey,
+    /// Direction of the first packet seen (direction from the originator
+    /// to the responder).
+    pub originator_direction: FlowDirection,
+    /// TCP connection state (None if the flow is not a tcp flow).
+    pub tcp_state: Option<TcpState>,
+    /// Timestamp of the first packet of the flow.
+    pub first_seen: Duration,
+    /// Timestamp of the last packet of the flow.
+    pub last_seen: Duration,
+    /// Packets & bytes sent by the originator.
+    pub originator: Counter,
+    /// Packets & bytes sent by the responder.
+    pub responder: Counter,
+    /// FIN seen from the originator & from the responder.
+    fin_seen: [bool;2],
+}
+
+impl Flow {
+    /// Returns true if the packet direction is the direction from the
+    /// originator to the responder.
+    #[inline]
+    pub fn is_from_originator(&self, direction: FlowDirection) -> bool {
+        direction == self.originator_direction
+    }
+
+    /// Returns the timeout for the flow based on its current state.
+    pub fn timeout(&self, timeouts: &ConnTrackTimeouts) -> Duration {
+        use TcpState::*;
+        match self.tcp_state {
+            Some(Established) => timeouts.tcp_established,
+            Some(SynSent) | Some(SynReceived) | Some(FinWait) => timeouts.tcp_transitory,
+            Some(Closed) => timeouts.tcp_closed,
+            None => if ip_number::UDP == self.key.ip_number {
+                timeouts.udp
+            } else {
+                timeouts.other
+            },
+        }
+    }
+
+    /// Returns true if the flow has expired at the given time.
+    pub fn is_expired(&self, now: Duration, timeouts: &ConnTrackTimeouts) -> bool {
+        now.saturating_sub(self.last_seen) > self.timeout(timeouts)
+    }
+
+    fn new(key: FlowKey, direction: FlowDirection, timestamp: Duration) -> Flow {
+        Flow {
+            key,
+            originator_direction: direction,
+            tcp_state: None,
+            first_seen: timestamp,
+            last_seen: timestamp,
+            originator: Default::default(),
+            responder: Default::default(),
+            fin_seen: [false, false],
+        }
+    }
+
+    fn update_tcp(&mut self, tcp: &TcpHeaderSlice, from_originator: bool) {
+        use TcpState::*;
+        let state = match self.tcp_state {
+            // new connection
+            None => if tcp.rst() {
+                Closed
+            } else if tcp.syn() && !tcp.ack() {
+                SynSent
+            } else {
+                // connection picked up after the handshake
+                Established
+            },
+            Some(state) => if tcp.rst() {
+                Closed
+            } else {
+                match state {
+                    SynSent if !from_originator && tcp.syn() && tcp.ack() => SynReceived,
+                    SynReceived if from_originator && tcp.ack() && !tcp.syn() => Established,
+                    other => other,
+                }
+            },
+        };
+        let state = if tcp.fin() && Closed != state {
+            self.fin_seen[if from_originator { 0 } else { 1 }] = true;
+            if self.fin_seen[0] && self.fin_seen[1] {
+                Closed
+            } else {
+                FinWait
+            }
+        } else {
+            state
+        };
+        self.tcp_state = Some(state);
+    }
+}
+
+/// Flow table tracking connections based on [`SlicedPacket`]s.
+///
+/// Flows are identified by a bidirectional [`FlowKey`]. For each flow the
+/// packets & bytes per direction, the first & last timestamp and (for tcp
+/// flows) the connection state are tracked.
+///
+/// The timestamps are passed in by the caller (e.g. the capture
+/// timestamps of a pcap file) and are only compared with each other.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .tcp(21, 1234, 1, 1024)
+/// #    .syn();
+/// # let mut syn = Vec::<u8>::with_capacity(builder.size(0));
+/// # builder.write(&mut syn, &[]).unwrap();
+/// use etherparse::{SlicedPacket, conntrack::{ConnTrack, TcpState}};
+/// use std::time::Duration;
+///
+/// let mut table = ConnTrack::new();
+/// let flow = table.update(
+///     &SlicedPacket::from_ethernet(&syn).unwrap(),
+///     Duration::from_secs(1)
+/// ).unwrap();
+/// assert_eq!(Some(TcpState::SynSent), flow.tcp_state);
+/// assert_eq!(1, flow.originator.packets);
+///
+/// // remove flows that have not seen any traffic for too long
+/// let expired = table.expire(Duration::from_secs(60*60));
+/// assert_eq!(1, expired.len());
+/// assert!(table.is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ConnTrack {
+    timeouts: ConnTrackTimeouts,
+    flows: HashMap<FlowKey, Flow>,
+}
+
+impl ConnTrack {
+
+    /// Creates an empty flow table with the default timeouts.
+    pub fn new() -> ConnTrack {
+        Default::default()
+    }
+
+    /// Creates an empty flow table with the given timeouts.
+    pub fn with_timeouts(timeouts: ConnTrackTimeouts) -> ConnTrack {
+        ConnTrack {
+            timeouts,
+            flows: HashMap::new(),
+        }
+    }
+
+    /// Timeouts used to determine if a flow has expired.
+    #[inline]
+    pub fn timeouts(&self) -> &ConnTrackTimeouts {
+        &self.timeouts
+    }
+
+    /// Updates the flow the packet belongs to (a new flow is created
+    /// if no flow for the packet exists yet) & returns it.
+    ///
+    /// If the existing flow has already expired or was closed and a new
+    /// connection is started (tcp SYN) the flow is replaced with a new flow.
+    ///
+    /// None is returned if the packet contains no ip header.
+    pub fn update(&mut self, packet: &SlicedPacket, timestamp: Duration) -> Option<&Flow> {
+        let (key, direction) = FlowKey::from_sliced_packet(packet)?;
+        let len = packet.header_bytes().iter().map(|s| s.len()).sum::<usize>()
+                  + packet.payload.len()
+                  + packet.trailer.len()
+                  + packet.padding.len();
+
+        let timeouts = self.timeouts;
+        let flow = self.flows
+            .entry(key)
+            .or_insert_with(|| Flow::new(key, direction, timestamp));
+
+        // restart flows that have expired or are reused after being closed
+        let restart = flow.is_expired(timestamp, &timeouts) || (
+            Some(TcpState::Closed) == flow.tcp_state &&
+            match &packet.transport {
+                Some(TransportSlice::Tcp(tcp)) => tcp.syn() && !tcp.ack(),
+                _ => false,
+            }
+        );
+        if restart {
+            *flow = Flow::new(key, direction, timestamp);
+        }
+
+        let from_originator = flow.is_from_originator(direction);
+        if from_originator {
+            flow.originator.add(len);
+        } else {
+            flow.responder.add(len);
+        }
+        if timestamp > flow.last_seen {
+            flow.last_seen = timestamp;
+        }
+        if let Some(TransportSlice::Tcp(tcp)) = &packet.transport {
+            flow.update_tcp(tcp, from_originator);
+        }
+        Some(flow)
+    }
+
+    /// Returns the flow with the given key if present.
+    #[inline]
+    pub fn get(&self, key: &FlowKey) -> Option<&Flow> {
+        self.flows.get(key)
+    }
+
+    /// Removes the flow with the given key from the table.
+    #[inline]
+    pub fn remove(&mut self, key: &FlowKey) -> Option<Flow> {
+        self.flows.remove(key)
+    }
+
+    /// Removes all flows that are expired at the given time from the
+    /// table & returns them.
+    pub fn expire(&mut self, now: Duration) -> Vec<Flow> {
+        let timeouts = self.timeouts;
+        let expired: Vec<FlowKey> = self.flows
+            .values()
+            .filter(|flow| flow.is_expired(now, &timeouts))
+            .map(|flow| flow.key)
+            .collect();
+        expired.iter().filter_map(|key| self.flows.remove(key)).collect()
+    }
+
+    /// Iterator over all tracked flows (in no specific order).
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Flow> {
+        self.flows.values()
+    }
+
+    /// Number of tracked flows.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.flows.len()
+    }
+
+    /// Returns true if no flows are tracked.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.flows.is_empty()
+    }
+}
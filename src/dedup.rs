@@ -0,0 +1,279 @@
+use super::*;
+
+use std::collections::{HashMap, VecDeque};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::time::Duration;
+
+/// Settings controlling which parts of a packet are considered when
+/// detecting duplicates & how long packets are remembered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct DedupConfig {
+    /// Maximum number of packets remembered in the sliding window.
+    pub window_len: usize,
+    /// Maximum age of the remembered packets (relative to the timestamp
+    /// of the newest packet). If set to None only `window_len` limits
+    /// the window.
+    pub max_age: Option<Duration>,
+    /// If set only the given number of payload bytes are considered
+    /// (e.g. for captures with differing snap lengths).
+    pub max_payload_len: Option<usize>,
+    /// If true the Ethernet II header is included in the hash (by default
+    /// it is ignored as mirror points on different network segments see
+    /// different mac addresses).
+    pub include_link: bool,
+    /// If true the vlan headers are included in the hash (by default
+    /// they are ignored).
+    pub include_vlan: bool,
+}
+
+impl Default for DedupConfig {
+    fn default() -> DedupConfig {
+        DedupConfig {
+            window_len: 1024,
+            max_age: Some(Duration::from_millis(100)),
+            max_payload_len: None,
+            include_link: false,
+            include_vlan: false,
+        }
+    }
+}
+
+/// Calculates a hash over a normalized view of a sliced packet.
+///
+/// The following fields are ignored when calculating the hash:
+///
+/// * IPv4 time to live & header checksum
+/// * IPv6 hop limit
+/// * UDP & TCP checksums
+/// * Bytes after the end of the ip payload (e.g. ethernet padding or the FCS)
+/// * The link & vlan headers (unless enabled in the config)
+/// * Payload bytes after `max_payload_len` (if set in the config)
+pub fn normalized_hash(packet: &SlicedPacket, config: &DedupConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    if config.include_link {
+        if let Some(link) = &packet.link {
+            hasher.write(link.slice());
+        }
+    }
+    if config.include_vlan {
+        if let Some(vlan) = &packet.vlan {
+            hasher.write(vlan.slice());
+        }
+    }
+
+    // end of the ip payload relative to the start of the ip header
+    let mut payload = packet.payload;
+    if let Some(ip) = &packet.ip {
+        let ip_end = match ip {
+            InternetSlice::Ipv4(header, _) => {
+                let s = header.slice();
+                // skip ttl (8) & header checksum (10..12)
+                hasher.write(&s[..8]);
+                hasher.write(&s[9..10]);
+                hasher.write(&s[12..]);
+                Some(usize::from(header.total_len()))
+            },
+            InternetSlice::Ipv6(header, _) => {
+                let s = header.slice();
+                // skip hop limit (7)
+                hasher.write(&s[..7]);
+                hasher.write(&s[8..]);
+                // a payload length of 0 indicates a jumbogram
+                match header.payload_length() {
+                    0 => None,
+                    len => Some(Ipv6Header::SERIALIZED_SIZE + usize::from(len)),
+                }
+            },
+        };
+        hasher.write(ip.extensions_slice());
+
+        let transport = packet.transport.as_ref().map(|t| t.slice()).unwrap_or(&[]);
+        match &packet.transport {
+            Some(TransportSlice::Udp(udp)) => {
+                // skip checksum (6..8)
+                hasher.write(&udp.slice()[..6]);
+            },
+            Some(TransportSlice::Tcp(tcp)) => {
+                // skip checksum (16..18)
+                let s = tcp.slice();
+                hasher.write(&s[..16]);
+                hasher.write(&s[18..]);
+            },
+            Some(TransportSlice::Igmp(igmp)) => {
+                // skip checksum (2..4)
+                let s = igmp.slice();
+                hasher.write(&s[..2]);
+                hasher.write(&s[4..]);
+            },
+            Some(TransportSlice::Unknown(value)) => hasher.write_u8(*value),
+            None => {},
+        }
+
+        // cut off everything after the ip payload
+        if let Some(ip_end) = ip_end {
+            let consumed = ip.header_slice().len() + ip.extensions_slice().len() + transport.len();
+            let len = ip_end.saturating_sub(consumed);
+            if len < payload.len() {
+                payload = &payload[..len];
+            }
+        }
+    }
+
+    if let Some(max) = config.max_payload_len {
+        if max < payload.len() {
+            payload = &payload[..max];
+        }
+    }
+    hasher.write(payload);
+    hasher.finish()
+}
+
+/// Detects duplicate packets (e.g. the same packet captured at multiple
+/// mirror points) within a sliding window.
+///
+/// Packets are compared via [`normalized_hash`], so packets only differing
+/// in fields that change while being forwarded (e.g. the time to live) are
+/// also detected as duplicates.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::dedup::Deduplicator;
+/// use std::time::Duration;
+///
+/// let mut dedup = Deduplicator::new(Default::default());
+/// assert!(false == dedup.is_duplicate_ethernet(&packet, Duration::from_millis(0)));
+/// assert!(dedup.is_duplicate_ethernet(&packet, Duration::from_millis(1)));
+///
+/// // the packet is outside of the time window
+/// assert!(false == dedup.is_duplicate_ethernet(&packet, Duration::from_secs(1)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Deduplicator {
+    config: DedupConfig,
+    /// Hashes & timestamps of the packets in the window (oldest first).
+    window: VecDeque<(u64, Duration)>,
+    /// Number of occurences of the hashes in the window.
+    counts: HashMap<u64, usize>,
+}
+
+impl Deduplicator {
+
+    /// Creates a deduplicator with an empty window.
+    pub fn new(config: DedupConfig) -> Deduplicator {
+        Deduplicator {
+            config,
+            window: VecDeque::with_capacity(config.window_len),
+            counts: HashMap::with_capacity(config.window_len),
+        }
+    }
+
+    /// Configuration of the deduplicator.
+    #[inline]
+    pub fn config(&self) -> &DedupConfig {
+        &self.config
+    }
+
+    /// Returns true if the packet is a duplicate of a packet in the window
+    /// & adds the packet to the window.
+    ///
+    /// The timestamps are expected to be monotonically increasing.
+    pub fn is_duplicate(&mut self, packet: &SlicedPacket, timestamp: Duration) -> bool {
+        let hash = normalized_hash(packet, &self.config);
+        self.check_hash(hash, timestamp)
+    }
+
+    /// Slices the packet starting with an Ethernet II header & checks
+    /// if it is a duplicate (see [`Deduplicator::is_duplicate`]).
+    ///
+    /// Packets that can not be sliced are compared based on all their bytes.
+    pub fn is_duplicate_ethernet(&mut self, packet: &[u8], timestamp: Duration) -> bool {
+        let hash = match SlicedPacket::from_ethernet(packet) {
+            Ok(value) => normalized_hash(&value, &self.config),
+            Err(_) => raw_hash(packet),
+        };
+        self.check_hash(hash, timestamp)
+    }
+
+    /// Slices the packet starting with an IPv4 or IPv6 header & checks
+    /// if it is a duplicate (see [`Deduplicator::is_duplicate`]).
+    ///
+    /// Packets that can not be sliced are compared based on all their bytes.
+    pub fn is_duplicate_ip(&mut self, packet: &[u8], timestamp: Duration) -> bool {
+        let hash = match SlicedPacket::from_ip(packet) {
+            Ok(value) => normalized_hash(&value, &self.config),
+            Err(_) => raw_hash(packet),
+        };
+        self.check_hash(hash, timestamp)
+    }
+
+    /// Number of packets currently in the window.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Returns true if the window contains no packets.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Removes all packets from the window.
+    pub fn clear(&mut self) {
+        self.window.clear();
+        self.counts.clear();
+    }
+
+    fn check_hash(&mut self, hash: u64, timestamp: Duration) -> bool {
+        // remove packets that are too old
+        if let Some(max_age) = self.config.max_age {
+            while let Some((_, oldest)) = self.window.front() {
+                if timestamp.saturating_sub(*oldest) > max_age {
+                    self.pop_oldest();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        let duplicate = self.counts.contains_key(&hash);
+
+        // add the packet to the window
+        if self.config.window_len > 0 {
+            while self.window.len() >= self.config.window_len {
+                self.pop_oldest();
+            }
+            self.window.push_back((hash, timestamp));
+            *self.counts.entry(hash).or_insert(0) += 1;
+        }
+        duplicate
+    }
+
+    fn pop_oldest(&mut self) {
+        if let Some((hash, _)) = self.window.pop_front() {
+            if let Some(count) = self.counts.get_mut(&hash) {
+                *count -= 1;
+                if 0 == *count {
+                    self.counts.remove(&hash);
+                }
+            }
+        }
+    }
+}
+
+/// Hash over all bytes of a packet that could not be sliced.
+fn raw_hash(packet: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(packet);
+    hasher.finish()
+}
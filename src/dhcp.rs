@@ -0,0 +1,370 @@
+use super::*;
+
+/// DHCPv4 option codes (RFC 2132, RFC 3046, RFC 3442).
+pub mod dhcp_option_code {
+    pub const PAD: u8 = 0;
+    pub const SUBNET_MASK: u8 = 1;
+    pub const ROUTER: u8 = 3;
+    pub const DOMAIN_NAME_SERVER: u8 = 6;
+    pub const HOST_NAME: u8 = 12;
+    pub const DOMAIN_NAME: u8 = 15;
+    pub const REQUESTED_IP_ADDRESS: u8 = 50;
+    pub const IP_ADDRESS_LEASE_TIME: u8 = 51;
+    pub const MESSAGE_TYPE: u8 = 53;
+    pub const SERVER_IDENTIFIER: u8 = 54;
+    pub const PARAMETER_REQUEST_LIST: u8 = 55;
+    pub const RELAY_AGENT_INFORMATION: u8 = 82;
+    pub const CLASSLESS_STATIC_ROUTE: u8 = 121;
+    pub const END: u8 = 255;
+}
+
+/// Sub-option codes of the relay agent information option (RFC 3046).
+pub mod relay_agent_sub_option_code {
+    pub const CIRCUIT_ID: u8 = 1;
+    pub const REMOTE_ID: u8 = 2;
+}
+
+/// Errors that can occur when reading or writing DHCP options.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DhcpOptionError {
+    /// The data ended before the end of an option or sub-option (expected
+    /// minimum size as argument).
+    UnexpectedEnd(usize),
+    /// The length of an option is not valid for the option code.
+    InvalidLength{ code: u8, length: u8 },
+    /// A classless static route has a prefix length larger than 32.
+    InvalidPrefixLength(u8),
+    /// The encoded data of an option or sub-option is larger than the 255
+    /// bytes the length field can represent.
+    ValueTooLarge{ code: u8, length: usize },
+}
+
+impl fmt::Display for DhcpOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DhcpOptionError::*;
+        match self {
+            UnexpectedEnd(len) => write!(f, "DhcpOptionError: Unexpected end of data. Expected at least {} bytes.", len),
+            InvalidLength{ code, length } => write!(f, "DhcpOptionError: Length {} is not valid for options with the code {}.", length, code),
+            InvalidPrefixLength(len) => write!(f, "DhcpOptionError: Prefix length {} of classless static route is larger than 32.", len),
+            ValueTooLarge{ code, length } => write!(f, "DhcpOptionError: Value of {} bytes of the option or sub-option with the code {} is larger than the maximum of 255 bytes.", length, code),
+        }
+    }
+}
+
+impl Error for DhcpOptionError {}
+
+impl DhcpOptionError {
+    /// Adds an offset to the expected size of `UnexpectedEnd` errors.
+    fn add_offset(self, offset: usize) -> DhcpOptionError {
+        match self {
+            DhcpOptionError::UnexpectedEnd(len) => DhcpOptionError::UnexpectedEnd(len + offset),
+            other => other,
+        }
+    }
+}
+
+/// Sub-option of the relay agent information option (RFC 3046).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RelayAgentSubOption {
+    /// Identifier of the circuit the request was received on (e.g. the
+    /// switch port or the ATM virtual circuit).
+    CircuitId(Vec<u8>),
+    /// Identifier of the remote host end of the circuit.
+    RemoteId(Vec<u8>),
+    /// Sub-option that is not decoded.
+    Unknown{ code: u8, data: Vec<u8> },
+}
+
+impl RelayAgentSubOption {
+    /// Code of the sub-option.
+    pub fn code(&self) -> u8 {
+        use RelayAgentSubOption::*;
+        match self {
+            CircuitId(_) => relay_agent_sub_option_code::CIRCUIT_ID,
+            RemoteId(_) => relay_agent_sub_option_code::REMOTE_ID,
+            Unknown{ code, .. } => *code,
+        }
+    }
+
+    /// Data of the sub-option (without code & length).
+    pub fn data(&self) -> &[u8] {
+        use RelayAgentSubOption::*;
+        match self {
+            CircuitId(data) => data,
+            RemoteId(data) => data,
+            Unknown{ data, .. } => data,
+        }
+    }
+}
+
+/// Relay agent information option (option 82, RFC 3046).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RelayAgentInformation {
+    pub sub_options: Vec<RelayAgentSubOption>,
+}
+
+impl RelayAgentInformation {
+    /// Decodes the sub-options contained in the data of the option.
+    pub fn from_slice(data: &[u8]) -> Result<RelayAgentInformation, DhcpOptionError> {
+        use DhcpOptionError::*;
+
+        let mut sub_options = Vec::new();
+        let mut rest = data;
+        while !rest.is_empty() {
+            if rest.len() < 2 {
+                return Err(UnexpectedEnd(data.len() - rest.len() + 2));
+            }
+            let code = rest[0];
+            let len = usize::from(rest[1]);
+            if rest.len() < 2 + len {
+                return Err(UnexpectedEnd(data.len() - rest.len() + 2 + len));
+            }
+            let value = rest[2..2 + len].to_vec();
+            sub_options.push(match code {
+                relay_agent_sub_option_code::CIRCUIT_ID |
+                relay_agent_sub_option_code::REMOTE_ID if value.is_empty() => {
+                    return Err(InvalidLength{ code, length: 0 });
+                },
+                relay_agent_sub_option_code::CIRCUIT_ID => RelayAgentSubOption::CircuitId(value),
+                relay_agent_sub_option_code::REMOTE_ID => RelayAgentSubOption::RemoteId(value),
+                _ => RelayAgentSubOption::Unknown{ code, data: value },
+            });
+            rest = &rest[2 + len..];
+        }
+        Ok(RelayAgentInformation{ sub_options })
+    }
+
+    /// Returns the data of the first circuit id sub-option.
+    pub fn circuit_id(&self) -> Option<&[u8]> {
+        self.sub_options.iter().find_map(|s| match s {
+            RelayAgentSubOption::CircuitId(data) => Some(&data[..]),
+            _ => None,
+        })
+    }
+
+    /// Returns the data of the first remote id sub-option.
+    pub fn remote_id(&self) -> Option<&[u8]> {
+        self.sub_options.iter().find_map(|s| match s {
+            RelayAgentSubOption::RemoteId(data) => Some(&data[..]),
+            _ => None,
+        })
+    }
+
+    /// Appends the encoded sub-options (without the option code & length).
+    fn write_data(&self, output: &mut Vec<u8>) -> Result<(), DhcpOptionError> {
+        for sub_option in &self.sub_options {
+            let data = sub_option.data();
+            if data.len() > usize::from(u8::MAX) {
+                return Err(DhcpOptionError::ValueTooLarge{ code: sub_option.code(), length: data.len() });
+            }
+            output.push(sub_option.code());
+            output.push(data.len() as u8);
+            output.extend_from_slice(data);
+        }
+        Ok(())
+    }
+}
+
+/// Route of the classless static route option (option 121, RFC 3442).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClasslessRoute {
+    /// Destination network (bits after the prefix length are ignored
+    /// when the route is encoded).
+    pub destination: [u8;4],
+    /// Length of the network prefix in bits (0 for the default route).
+    pub prefix_len: u8,
+    pub router: [u8;4],
+}
+
+impl ClasslessRoute {
+    /// Decodes the routes contained in the data of the option.
+    pub fn from_slice(data: &[u8]) -> Result<Vec<ClasslessRoute>, DhcpOptionError> {
+        use DhcpOptionError::*;
+
+        let mut routes = Vec::new();
+        let mut rest = data;
+        while !rest.is_empty() {
+            let prefix_len = rest[0];
+            if prefix_len > 32 {
+                return Err(InvalidPrefixLength(prefix_len));
+            }
+            // only the significant octets of the destination are encoded
+            let significant = usize::from(prefix_len).div_ceil(8);
+            let len = 1 + significant + 4;
+            if rest.len() < len {
+                return Err(UnexpectedEnd(data.len() - rest.len() + len));
+            }
+            let mut destination = [0u8;4];
+            destination[..significant].copy_from_slice(&rest[1..1 + significant]);
+            let mut router = [0u8;4];
+            router.copy_from_slice(&rest[1 + significant..len]);
+            routes.push(ClasslessRoute{ destination, prefix_len, router });
+            rest = &rest[len..];
+        }
+        Ok(routes)
+    }
+
+    /// Length of the encoded route in bytes.
+    pub fn encoded_len(&self) -> usize {
+        1 + usize::from(self.prefix_len).div_ceil(8) + 4
+    }
+
+    /// Appends the encoded route. The bits of the destination after the
+    /// prefix length are set to zero.
+    pub fn write(&self, output: &mut Vec<u8>) -> Result<(), DhcpOptionError> {
+        if self.prefix_len > 32 {
+            return Err(DhcpOptionError::InvalidPrefixLength(self.prefix_len));
+        }
+        let mask = if 0 == self.prefix_len {
+            0
+        } else {
+            u32::MAX << (32 - u32::from(self.prefix_len))
+        };
+        let destination = (u32::from_be_bytes(self.destination) & mask).to_be_bytes();
+        output.push(self.prefix_len);
+        output.extend_from_slice(&destination[..usize::from(self.prefix_len).div_ceil(8)]);
+        output.extend_from_slice(&self.router);
+        Ok(())
+    }
+}
+
+/// DHCPv4 option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DhcpOption<'a> {
+    RelayAgentInformation(RelayAgentInformation),
+    ClasslessStaticRoute(Vec<ClasslessRoute>),
+    /// Option that is not decoded (data without the code & length field).
+    Other{ code: u8, data: &'a [u8] },
+}
+
+impl<'a> DhcpOption<'a> {
+    /// Reads the option at the start of the slice & returns the option &
+    /// the rest of the slice. The slice is expected to not start with a pad
+    /// or end option.
+    pub fn from_slice(slice: &'a [u8]) -> Result<(DhcpOption<'a>, &'a [u8]), DhcpOptionError> {
+        use DhcpOptionError::*;
+
+        if slice.len() < 2 {
+            return Err(UnexpectedEnd(2));
+        }
+        let code = slice[0];
+        let length = slice[1];
+        let len = 2 + usize::from(length);
+        if slice.len() < len {
+            return Err(UnexpectedEnd(len));
+        }
+        let data = &slice[2..len];
+        let option = match code {
+            dhcp_option_code::RELAY_AGENT_INFORMATION => {
+                if data.is_empty() {
+                    return Err(InvalidLength{ code, length });
+                }
+                DhcpOption::RelayAgentInformation(
+                    RelayAgentInformation::from_slice(data).map_err(|err| err.add_offset(2))?
+                )
+            },
+            dhcp_option_code::CLASSLESS_STATIC_ROUTE => {
+                // at least one default route (prefix length & router)
+                if data.len() < 5 {
+                    return Err(InvalidLength{ code, length });
+                }
+                DhcpOption::ClasslessStaticRoute(
+                    ClasslessRoute::from_slice(data).map_err(|err| err.add_offset(2))?
+                )
+            },
+            _ => DhcpOption::Other{ code, data },
+        };
+        Ok((option, &slice[len..]))
+    }
+
+    /// Code of the option.
+    pub fn code(&self) -> u8 {
+        match self {
+            DhcpOption::RelayAgentInformation(_) => dhcp_option_code::RELAY_AGENT_INFORMATION,
+            DhcpOption::ClasslessStaticRoute(_) => dhcp_option_code::CLASSLESS_STATIC_ROUTE,
+            DhcpOption::Other{ code, .. } => *code,
+        }
+    }
+
+    /// Appends the option (including the code & length field). Nothing is
+    /// written if an error is returned.
+    pub fn write(&self, output: &mut Vec<u8>) -> Result<(), DhcpOptionError> {
+        let start = output.len();
+        output.extend_from_slice(&[self.code(), 0]);
+        let result = match self {
+            DhcpOption::RelayAgentInformation(info) => info.write_data(output),
+            DhcpOption::ClasslessStaticRoute(routes) => {
+                routes.iter().try_for_each(|route| route.write(output))
+            },
+            DhcpOption::Other{ data, .. } => {
+                output.extend_from_slice(data);
+                Ok(())
+            },
+        };
+        let len = output.len() - start - 2;
+        let result = result.and_then(|_| if len > usize::from(u8::MAX) {
+            Err(DhcpOptionError::ValueTooLarge{ code: self.code(), length: len })
+        } else {
+            Ok(())
+        });
+        match result {
+            Ok(()) => {
+                output[start + 1] = len as u8;
+                Ok(())
+            },
+            Err(err) => {
+                output.truncate(start);
+                Err(err)
+            },
+        }
+    }
+}
+
+/// Iterator over the options in the options field of a DHCPv4 message
+/// (pad options are skipped & the iteration stops at the end option).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DhcpOptionsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> DhcpOptionsIterator<'a> {
+    /// Creates an iterator over the options in the given slice (the data
+    /// after the magic cookie).
+    pub fn from_slice(options: &'a [u8]) -> DhcpOptionsIterator<'a> {
+        DhcpOptionsIterator{ rest: options }
+    }
+
+    /// Returns the slice containing the options that were not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for DhcpOptionsIterator<'a> {
+    type Item = Result<DhcpOption<'a>, DhcpOptionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.rest.first() {
+                None => return None,
+                Some(&dhcp_option_code::PAD) => self.rest = &self.rest[1..],
+                Some(&dhcp_option_code::END) => {
+                    self.rest = &[];
+                    return None;
+                },
+                Some(_) => break,
+            }
+        }
+        match DhcpOption::from_slice(self.rest) {
+            Ok((option, rest)) => {
+                self.rest = rest;
+                Some(Ok(option))
+            },
+            Err(err) => {
+                // stop the iteration after an error
+                self.rest = &[];
+                Some(Err(err))
+            },
+        }
+    }
+}
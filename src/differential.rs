@@ -0,0 +1,421 @@
+use super::*;
+
+use pnet_packet::Packet;
+use pnet_packet::ethernet::EthernetPacket;
+use pnet_packet::ipv4::Ipv4Packet;
+use pnet_packet::ipv6::Ipv6Packet;
+use pnet_packet::tcp::TcpPacket;
+use pnet_packet::udp::UdpPacket;
+use pnet_packet::vlan::VlanPacket;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Decoded header fields of a packet keyed by `layer.field` (e.g.
+/// `ipv4.time_to_live`) with the values formatted as strings.
+pub type Fields = BTreeMap<String, String>;
+
+/// Decoder that converts an ethernet II frame to comparable fields.
+pub trait Decoder {
+    /// Name of the decoder used in reports.
+    fn name(&self) -> &'static str;
+
+    /// Decodes the headers of the ethernet II frame. Headers that can not
+    /// be decoded are left out.
+    fn decode(&self, data: &[u8]) -> Fields;
+}
+
+/// Decodes packets with [`SlicedPacket::from_ethernet`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EtherparseDecoder;
+
+/// Reference decoder based on the `pnet_packet` crate.
+///
+/// Follows the same protocol chain as [`SlicedPacket`] (ethernet II, up to
+/// the default [`ParseLimits::max_vlan_depth`] vlan headers, IPv4 or IPv6 &
+/// UDP or TCP). The transport layer is only decoded if the IPv4 payload is
+/// not fragmented & if the IPv6 header is directly followed by the transport
+/// header (pnet has no generic extension header support).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PnetDecoder;
+
+/// Field with a different value (or only present) in one of the decoders.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Divergence {
+    /// Field name in the `layer.field` format.
+    pub field: String,
+    /// Value decoded by etherparse (`None` if etherparse did not decode the field).
+    pub etherparse: Option<String>,
+    /// Value decoded by the reference decoder (`None` if the reference did not decode the field).
+    pub reference: Option<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: etherparse={} reference={}",
+            self.field,
+            self.etherparse.as_deref().unwrap_or("<missing>"),
+            self.reference.as_deref().unwrap_or("<missing>")
+        )
+    }
+}
+
+/// Result of replaying a corpus.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    /// Number of inputs that were compared.
+    pub inputs: usize,
+    /// Inputs with at least one divergence.
+    pub divergent: Vec<(PathBuf, Vec<Divergence>)>,
+}
+
+/// Decodes the frame with etherparse & the reference decoder & returns
+/// all fields with differing values (sorted by field name).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::PacketBuilder;
+/// use etherparse::differential::{diff, PnetDecoder};
+///
+/// let builder = PacketBuilder::
+///     ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234);
+/// let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// builder.write(&mut packet, &[1,2,3,4]).unwrap();
+///
+/// assert!(diff(&packet, &PnetDecoder).is_empty());
+/// ```
+pub fn diff(data: &[u8], reference: &dyn Decoder) -> Vec<Divergence> {
+    compare(&EtherparseDecoder.decode(data), &reference.decode(data))
+}
+
+/// Compares the fields decoded by etherparse with the fields decoded by a
+/// reference decoder.
+pub fn compare(etherparse: &Fields, reference: &Fields) -> Vec<Divergence> {
+    let mut result = Vec::new();
+    for (field, value) in etherparse {
+        match reference.get(field) {
+            Some(other) if other == value => {},
+            other => result.push(Divergence{
+                field: field.clone(),
+                etherparse: Some(value.clone()),
+                reference: other.cloned(),
+            }),
+        }
+    }
+    for (field, value) in reference {
+        if !etherparse.contains_key(field) {
+            result.push(Divergence{
+                field: field.clone(),
+                etherparse: None,
+                reference: Some(value.clone()),
+            });
+        }
+    }
+    result.sort_by(|a, b| a.field.cmp(&b.field));
+    result
+}
+
+/// Compares the decoded fields of every file in the given directory (each
+/// file containing one ethernet II frame, e.g. a fuzzing corpus).
+/// Subdirectories are ignored & the files are processed in the order of
+/// their names.
+pub fn replay_dir(dir: &Path, reference: &dyn Decoder) -> Result<Report, std::io::Error> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+
+    let mut report = Report::default();
+    for path in paths {
+        let data = fs::read(&path)?;
+        report.inputs += 1;
+        let divergences = diff(&data, reference);
+        if !divergences.is_empty() {
+            report.divergent.push((path, divergences));
+        }
+    }
+    Ok(report)
+}
+
+fn mac(value: [u8;6]) -> String {
+    MacAddr(value).to_string()
+}
+
+/// Adds the fields of a header.
+fn insert(fields: &mut Fields, layer: &str, values: &[(&str, String)]) {
+    for (name, value) in values {
+        fields.insert(format!("{}.{}", layer, name), value.clone());
+    }
+}
+
+impl Decoder for EtherparseDecoder {
+    fn name(&self) -> &'static str {
+        "etherparse"
+    }
+
+    fn decode(&self, data: &[u8]) -> Fields {
+        match SlicedPacket::from_ethernet(data) {
+            Ok(sliced) => {
+                let mut fields = Fields::new();
+                add_sliced(&mut fields, &sliced);
+                fields
+            },
+            Err(_) => decode_layers(data),
+        }
+    }
+}
+
+/// Decodes the headers layer by layer, keeping the layers decoded before
+/// the first error.
+fn decode_layers(data: &[u8]) -> Fields {
+    let mut fields = Fields::new();
+    let eth = match Ethernet2HeaderSlice::from_slice(data) {
+        Ok(value) => value,
+        Err(_) => return fields,
+    };
+    add_ethernet(&mut fields, &eth);
+    let mut rest = &data[eth.slice().len()..];
+    let mut ether_type = eth.ether_type();
+
+    use ether_type::*;
+    for index in 0..ParseLimits::DEFAULT.max_vlan_depth {
+        if !matches!(ether_type, VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME) {
+            break;
+        }
+        let vlan = match SingleVlanHeaderSlice::from_slice(rest) {
+            Ok(value) => value,
+            Err(_) => return fields,
+        };
+        add_vlan(&mut fields, index, &vlan);
+        rest = &rest[vlan.slice().len()..];
+        ether_type = vlan.ether_type();
+    }
+    if matches!(ether_type, VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME) {
+        return fields;
+    }
+
+    let sliced = match SlicedPacket::from_ether_type(ether_type, rest) {
+        Ok(value) => value,
+        Err(_) => {
+            // keep the ip header if only the following layers failed
+            match ether_type {
+                IPV4 => if let Ok(ip) = Ipv4HeaderSlice::from_slice(rest) {
+                    add_ipv4(&mut fields, &ip);
+                },
+                IPV6 => if let Ok(ip) = Ipv6HeaderSlice::from_slice(rest) {
+                    add_ipv6(&mut fields, &ip);
+                },
+                _ => {},
+            }
+            return fields;
+        }
+    };
+    add_sliced(&mut fields, &sliced);
+    fields
+}
+
+fn add_sliced(fields: &mut Fields, sliced: &SlicedPacket) {
+    if let Some(LinkSlice::Ethernet2(eth)) = &sliced.link {
+        add_ethernet(fields, eth);
+    }
+    if let Some(vlan) = &sliced.vlan {
+        for (index, header) in vlan.iter().enumerate() {
+            add_vlan(fields, index, &header);
+        }
+    }
+    match &sliced.ip {
+        Some(InternetSlice::Ipv4(ip, _)) => add_ipv4(fields, ip),
+        Some(InternetSlice::Ipv6(ip, _)) => add_ipv6(fields, ip),
+        None => {},
+    }
+    match &sliced.transport {
+        Some(TransportSlice::Udp(udp)) => insert(fields, "udp", &[
+            ("source_port", udp.source_port().to_string()),
+            ("destination_port", udp.destination_port().to_string()),
+            ("length", udp.length().to_string()),
+            ("checksum", udp.checksum().to_string()),
+        ]),
+        Some(TransportSlice::Tcp(tcp)) => insert(fields, "tcp", &[
+            ("source_port", tcp.source_port().to_string()),
+            ("destination_port", tcp.destination_port().to_string()),
+            ("sequence_number", tcp.sequence_number().to_string()),
+            ("acknowledgment_number", tcp.acknowledgment_number().to_string()),
+            ("data_offset", tcp.data_offset().to_string()),
+            ("flags", tcp.slice()[13].to_string()),
+            ("window_size", tcp.window_size().to_string()),
+            ("checksum", tcp.checksum().to_string()),
+            ("urgent_pointer", tcp.urgent_pointer().to_string()),
+        ]),
+        _ => {},
+    }
+}
+
+fn add_ethernet(fields: &mut Fields, eth: &Ethernet2HeaderSlice) {
+    insert(fields, "ethernet2", &[
+        ("source", mac(eth.source())),
+        ("destination", mac(eth.destination())),
+        ("ether_type", eth.ether_type().to_string()),
+    ]);
+}
+
+fn add_vlan(fields: &mut Fields, index: usize, vlan: &SingleVlanHeaderSlice) {
+    insert(fields, &format!("vlan{}", index), &[
+        ("priority_code_point", vlan.priority_code_point().to_string()),
+        ("drop_eligible_indicator", vlan.drop_eligible_indicator().to_string()),
+        ("vlan_identifier", vlan.vlan_identifier().to_string()),
+        ("ether_type", vlan.ether_type().to_string()),
+    ]);
+}
+
+fn add_ipv4(fields: &mut Fields, ip: &Ipv4HeaderSlice) {
+    insert(fields, "ipv4", &[
+        ("ihl", ip.ihl().to_string()),
+        ("differentiated_services_code_point", ip.dcp().to_string()),
+        ("explicit_congestion_notification", ip.ecn().to_string()),
+        ("total_len", ip.total_len().to_string()),
+        ("identification", ip.identification().to_string()),
+        ("dont_fragment", ip.dont_fragment().to_string()),
+        ("more_fragments", ip.more_fragments().to_string()),
+        ("fragments_offset", ip.fragments_offset().to_string()),
+        ("time_to_live", ip.ttl().to_string()),
+        ("protocol", ip.protocol().to_string()),
+        ("header_checksum", ip.header_checksum().to_string()),
+        ("source", ip.source_addr().to_string()),
+        ("destination", ip.destination_addr().to_string()),
+    ]);
+}
+
+fn add_ipv6(fields: &mut Fields, ip: &Ipv6HeaderSlice) {
+    insert(fields, "ipv6", &[
+        ("traffic_class", ip.traffic_class().to_string()),
+        ("flow_label", ip.flow_label().to_string()),
+        ("payload_length", ip.payload_length().to_string()),
+        ("next_header", ip.next_header().to_string()),
+        ("hop_limit", ip.hop_limit().to_string()),
+        ("source", ip.source_addr().to_string()),
+        ("destination", ip.destination_addr().to_string()),
+    ]);
+}
+
+impl Decoder for PnetDecoder {
+    fn name(&self) -> &'static str {
+        "pnet"
+    }
+
+    fn decode(&self, data: &[u8]) -> Fields {
+        let mut fields = Fields::new();
+        let eth = match EthernetPacket::new(data) {
+            Some(value) => value,
+            None => return fields,
+        };
+        insert(&mut fields, "ethernet2", &[
+            ("source", mac(eth.get_source().octets())),
+            ("destination", mac(eth.get_destination().octets())),
+            ("ether_type", eth.get_ethertype().0.to_string()),
+        ]);
+
+        use ether_type::*;
+        let mut ether_type = eth.get_ethertype().0;
+        let mut payload = eth.payload().to_vec();
+        for index in 0..ParseLimits::DEFAULT.max_vlan_depth {
+            if !matches!(ether_type, VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME) {
+                break;
+            }
+            let vlan = match VlanPacket::new(&payload) {
+                Some(value) => value,
+                None => return fields,
+            };
+            insert(&mut fields, &format!("vlan{}", index), &[
+                ("priority_code_point", vlan.get_priority_code_point().0.to_string()),
+                ("drop_eligible_indicator", (1 == vlan.get_drop_eligible_indicator()).to_string()),
+                ("vlan_identifier", vlan.get_vlan_identifier().to_string()),
+                ("ether_type", vlan.get_ethertype().0.to_string()),
+            ]);
+            ether_type = vlan.get_ethertype().0;
+            payload = vlan.payload().to_vec();
+        }
+
+        let (protocol, transport) = match ether_type {
+            IPV4 => {
+                let ip = match Ipv4Packet::new(&payload) {
+                    Some(value) => value,
+                    None => return fields,
+                };
+                let flags = ip.get_flags();
+                insert(&mut fields, "ipv4", &[
+                    ("ihl", ip.get_header_length().to_string()),
+                    ("differentiated_services_code_point", ip.get_dscp().to_string()),
+                    ("explicit_congestion_notification", ip.get_ecn().to_string()),
+                    ("total_len", ip.get_total_length().to_string()),
+                    ("identification", ip.get_identification().to_string()),
+                    ("dont_fragment", (0 != flags & 0b010).to_string()),
+                    ("more_fragments", (0 != flags & 0b001).to_string()),
+                    ("fragments_offset", ip.get_fragment_offset().to_string()),
+                    ("time_to_live", ip.get_ttl().to_string()),
+                    ("protocol", ip.get_next_level_protocol().0.to_string()),
+                    ("header_checksum", ip.get_checksum().to_string()),
+                    ("source", ip.get_source().to_string()),
+                    ("destination", ip.get_destination().to_string()),
+                ]);
+                if 0 != flags & 0b001 || 0 != ip.get_fragment_offset() {
+                    return fields;
+                }
+                (ip.get_next_level_protocol().0, ip.payload().to_vec())
+            },
+            IPV6 => {
+                let ip = match Ipv6Packet::new(&payload) {
+                    Some(value) => value,
+                    None => return fields,
+                };
+                insert(&mut fields, "ipv6", &[
+                    ("traffic_class", ip.get_traffic_class().to_string()),
+                    ("flow_label", ip.get_flow_label().to_string()),
+                    ("payload_length", ip.get_payload_length().to_string()),
+                    ("next_header", ip.get_next_header().0.to_string()),
+                    ("hop_limit", ip.get_hop_limit().to_string()),
+                    ("source", ip.get_source().to_string()),
+                    ("destination", ip.get_destination().to_string()),
+                ]);
+                (ip.get_next_header().0, ip.payload().to_vec())
+            },
+            _ => return fields,
+        };
+
+        match protocol {
+            ip_number::UDP => if let Some(udp) = UdpPacket::new(&transport) {
+                insert(&mut fields, "udp", &[
+                    ("source_port", udp.get_source().to_string()),
+                    ("destination_port", udp.get_destination().to_string()),
+                    ("length", udp.get_length().to_string()),
+                    ("checksum", udp.get_checksum().to_string()),
+                ]);
+            },
+            ip_number::TCP => if let Some(tcp) = TcpPacket::new(&transport) {
+                insert(&mut fields, "tcp", &[
+                    ("source_port", tcp.get_source().to_string()),
+                    ("destination_port", tcp.get_destination().to_string()),
+                    ("sequence_number", tcp.get_sequence().to_string()),
+                    ("acknowledgment_number", tcp.get_acknowledgement().to_string()),
+                    ("data_offset", tcp.get_data_offset().to_string()),
+                    ("flags", tcp.get_flags().to_string()),
+                    ("window_size", tcp.get_window().to_string()),
+                    ("checksum", tcp.get_checksum().to_string()),
+                    ("urgent_pointer", tcp.get_urgent_ptr().to_string()),
+                ]);
+            },
+            _ => {},
+        }
+        fields
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+/// Position of the ECN bits in an IP packet.
+enum EcnPosition {
+    /// ECN bits in the lower 2 bits of the second byte of an IPv4 header.
+    Ipv4,
+    /// ECN bits in the upper nibble of the second byte of an IPv6 header.
+    Ipv6,
+}
+
+fn ecn_position(ip_packet: &[u8]) -> Result<EcnPosition, ReadError> {
+    use ReadError::*;
+
+    let first = match ip_packet.first() {
+        Some(value) => *value,
+        None => return Err(UnexpectedEndOfSlice(1)),
+    };
+    match first >> 4 {
+        4 => {
+            let header_len = usize::from(first & 0xf)*4;
+            if header_len < Ipv4Header::SERIALIZED_SIZE {
+                return Err(Ipv4HeaderLengthBad(first & 0xf));
+            }
+            if ip_packet.len() < header_len {
+                return Err(UnexpectedEndOfSlice(header_len));
+            }
+            Ok(EcnPosition::Ipv4)
+        },
+        6 => {
+            if ip_packet.len() < Ipv6Header::SERIALIZED_SIZE {
+                return Err(UnexpectedEndOfSlice(Ipv6Header::SERIALIZED_SIZE));
+            }
+            Ok(EcnPosition::Ipv6)
+        },
+        version => Err(IpUnsupportedVersion(version)),
+    }
+}
+
+/// Reads the ECN codepoint of an IPv4 or IPv6 packet.
+pub fn read_ecn(ip_packet: &[u8]) -> Result<Ecn, ReadError> {
+    Ok(match ecn_position(ip_packet)? {
+        EcnPosition::Ipv4 => Ecn::from_bits(ip_packet[1]),
+        EcnPosition::Ipv6 => Ecn::from_bits(ip_packet[1] >> 4),
+    })
+}
+
+/// Sets the ECN codepoint of an IPv4 or IPv6 packet & returns the previous
+/// codepoint. The IPv4 header checksum is updated incrementally (a wrong
+/// checksum stays wrong).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv4Header, IpNumber};
+/// use etherparse::ecn;
+/// use etherparse::Ecn;
+///
+/// let mut packet = Vec::new();
+/// Ipv4Header::new(0, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2])
+///     .write(&mut packet)
+///     .unwrap();
+///
+/// assert_eq!(Ecn::NotEct, ecn::set_ecn(&mut packet, Ecn::Ect1).unwrap());
+/// assert_eq!(Ecn::Ect1, ecn::read_ecn(&packet).unwrap());
+///
+/// let (header, _) = Ipv4Header::from_slice(&packet).unwrap();
+/// assert_eq!(header.header_checksum, header.calc_header_checksum().unwrap());
+/// ```
+pub fn set_ecn(ip_packet: &mut [u8], ecn: Ecn) -> Result<Ecn, ReadError> {
+    match ecn_position(ip_packet)? {
+        EcnPosition::Ipv4 => {
+            let old = [ip_packet[0], ip_packet[1]];
+            let new = [old[0], (old[1] & !0b11) | ecn as u8];
+            let checksum = checksum::incremental_update([ip_packet[10], ip_packet[11]], old, new);
+            ip_packet[1] = new[1];
+            ip_packet[10..12].copy_from_slice(&checksum);
+            Ok(Ecn::from_bits(old[1]))
+        },
+        EcnPosition::Ipv6 => {
+            let old = ip_packet[1];
+            ip_packet[1] = (old & !0b0011_0000) | ((ecn as u8) << 4);
+            Ok(Ecn::from_bits(old >> 4))
+        },
+    }
+}
+
+/// Marks an IPv4 or IPv6 packet with "Congestion Experienced" if the packet
+/// uses an ECN capable transport. Returns false if the packet is not ECN
+/// capable (in which case an AQM has to drop the packet instead).
+pub fn mark_ce(ip_packet: &mut [u8]) -> Result<bool, ReadError> {
+    if read_ecn(ip_packet)?.is_ect() {
+        set_ecn(ip_packet, Ecn::Ce)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Sets the ECE (ECN-Echo) & CWR (Congestion Window Reduced) flags of a TCP
+/// header & updates the TCP checksum incrementally.
+///
+/// A receiver reflects a received CE mark by setting ECE in its ACKs until
+/// the sender acknowledges the congestion signal with a segment carrying CWR.
+pub fn set_tcp_ecn_flags(tcp_header: &mut [u8], ece: bool, cwr: bool) -> Result<(), ReadError> {
+    const ECE: u8 = 0x40;
+    const CWR: u8 = 0x80;
+
+    if tcp_header.len() < TCP_MINIMUM_HEADER_SIZE {
+        return Err(ReadError::UnexpectedEndOfSlice(TCP_MINIMUM_HEADER_SIZE));
+    }
+    let old = [tcp_header[12], tcp_header[13]];
+    let mut flags = old[1] & !(ECE | CWR);
+    if ece {
+        flags |= ECE;
+    }
+    if cwr {
+        flags |= CWR;
+    }
+    let new = [old[0], flags];
+    let checksum = checksum::incremental_update([tcp_header[16], tcp_header[17]], old, new);
+    tcp_header[13] = flags;
+    tcp_header[16..18].copy_from_slice(&checksum);
+    Ok(())
+}
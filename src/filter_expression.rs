@@ -0,0 +1,567 @@
+use super::*;
+
+use std::net::IpAddr;
+
+/// Errors that can occur when parsing a filter expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FilterExpressionError {
+    /// The expression ended while more tokens were expected (e.g. `port` without a value).
+    UnexpectedEnd,
+    /// Token that is not allowed at its position (e.g. a closing bracket without an opening one).
+    UnexpectedToken(String),
+    /// Value that could not be parsed (e.g. an invalid ip address or port).
+    InvalidValue(String),
+}
+
+impl fmt::Display for FilterExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FilterExpressionError::*;
+        match self {
+            UnexpectedEnd => {
+                write!(f, "FilterExpressionError: Unexpected end of the filter expression.")
+            },
+            UnexpectedToken(token) => {
+                write!(f, "FilterExpressionError: Unexpected token '{}' in the filter expression.", token)
+            },
+            InvalidValue(value) => {
+                write!(f, "FilterExpressionError: Invalid value '{}' in the filter expression.", value)
+            },
+        }
+    }
+}
+
+impl Error for FilterExpressionError {}
+
+/// Filter compiled from a tcpdump style filter expression (a practical
+/// subset of the pcap-filter syntax) that can be applied to sliced packets.
+///
+/// The following primitives are supported:
+///
+/// * `ip`, `ip6`, `tcp`, `udp`, `icmp` & `icmp6`
+/// * `[src|dst] host <ipv4 or ipv6 address>`
+/// * `[src|dst] net <address>/<prefix length>`
+/// * `[tcp|udp] [src|dst] port <port>`
+/// * `[tcp|udp] [src|dst] portrange <port>-<port>`
+/// * `ip proto <number or name>`, `ip6 proto <number or name>` & `proto <number or name>`
+/// * `ether [src|dst] host <mac>` (or `ether src <mac>` & `ether dst <mac>`)
+/// * `ether proto <number or name>`
+/// * `vlan [<vlan id>]`
+/// * `less <length>` & `greater <length>`
+///
+/// Primitives can be combined with `and` (`&&`), `or` (`||`), `not` (`!`)
+/// & brackets. As in pcap-filter `and` & `or` have the same precedence and
+/// are evaluated from left to right, and values after `and` & `or` without
+/// a keyword reuse the keywords of the previous primitive (e.g. `port 80 or
+/// 443`). Protocol qualifiers in front of a primitive (e.g. `ip host
+/// 10.0.0.1`) additionally require the protocol to be present.
+///
+/// In contrast to libpcap the filter is applied to the already sliced
+/// packet, so vlan tags are skipped transparently (`ip` also matches
+/// vlan tagged packets) & `vlan <id>` matches the id of the outer vlan
+/// header.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([10,0,0,1], [192,168,1,2], 20)
+/// #    .tcp(1234, 443, 1, 1024);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{SlicedPacket, filter_expression::FilterExpression};
+///
+/// let filter = FilterExpression::parse("tcp and dst port 443 and net 10.0.0.0/8").unwrap();
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// assert!(filter.matches(&sliced));
+///
+/// let filter = FilterExpression::parse("udp or port 80").unwrap();
+/// assert!(!filter.matches(&sliced));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FilterExpression {
+    root: Node,
+}
+
+impl FilterExpression {
+
+    /// Parses a filter expression. An empty expression matches all packets.
+    pub fn parse(expression: &str) -> Result<FilterExpression, FilterExpressionError> {
+        let tokens = tokenize(expression);
+        if tokens.is_empty() {
+            return Ok(FilterExpression{ root: Node::True });
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            context: Context::default(),
+        };
+        let root = parser.parse_expression()?;
+        match parser.next() {
+            None => Ok(FilterExpression{ root }),
+            Some(token) => Err(FilterExpressionError::UnexpectedToken(token)),
+        }
+    }
+
+    /// Returns true if the sliced packet matches the filter.
+    pub fn matches(&self, packet: &SlicedPacket) -> bool {
+        self.root.matches(packet)
+    }
+}
+
+/// Node of the compiled expression tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Node {
+    True,
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Protocol(Protocol),
+    Host(Direction, IpAddr),
+    Net(Direction, IpAddr, u8),
+    Port(Direction, u16, u16),
+    IpProto(u8),
+    EtherHost(Direction, [u8;6]),
+    EtherProto(u16),
+    Vlan(Option<u16>),
+    Less(usize),
+    Greater(usize),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Protocol {
+    Ether,
+    Ip,
+    Ip6,
+    Tcp,
+    Udp,
+    Icmp,
+    Icmp6,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Direction {
+    Src,
+    Dst,
+    SrcOrDst,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    Host,
+    Net,
+    Port,
+    PortRange,
+    Proto,
+}
+
+/// Keywords of the last primitive (reused for values without keywords).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Context {
+    protocol: Option<Protocol>,
+    direction: Direction,
+    kind: Kind,
+}
+
+impl Default for Context {
+    fn default() -> Context {
+        Context {
+            protocol: None,
+            direction: Direction::SrcOrDst,
+            kind: Kind::Host,
+        }
+    }
+}
+
+impl Node {
+    fn matches(&self, packet: &SlicedPacket) -> bool {
+        use Node::*;
+        match self {
+            True => true,
+            And(a, b) => a.matches(packet) && b.matches(packet),
+            Or(a, b) => a.matches(packet) || b.matches(packet),
+            Not(a) => !a.matches(packet),
+            Protocol(protocol) => {
+                use self::Protocol::*;
+                match protocol {
+                    Ether => packet.link.is_some(),
+                    Ip => matches!(packet.ip, Some(InternetSlice::Ipv4(_, _))),
+                    Ip6 => matches!(packet.ip, Some(InternetSlice::Ipv6(_, _))),
+                    Tcp => matches!(packet.transport, Some(TransportSlice::Tcp(_))),
+                    Udp => matches!(packet.transport, Some(TransportSlice::Udp(_))),
+                    Icmp => matches!(&packet.ip, Some(ip @ InternetSlice::Ipv4(_, _)) if ip_number::ICMP == ip.payload_ip_number()),
+                    Icmp6 => matches!(&packet.ip, Some(ip @ InternetSlice::Ipv6(_, _)) if ip_number::IPV6_ICMP == ip.payload_ip_number()),
+                }
+            },
+            Host(direction, addr) => match ip_addresses(packet) {
+                Some((source, destination)) => direction.check(
+                    || source == *addr,
+                    || destination == *addr,
+                ),
+                None => false,
+            },
+            Net(direction, addr, prefix_len) => match ip_addresses(packet) {
+                Some((source, destination)) => direction.check(
+                    || in_net(&source, addr, *prefix_len),
+                    || in_net(&destination, addr, *prefix_len),
+                ),
+                None => false,
+            },
+            Port(direction, min, max) => {
+                let ports = match &packet.transport {
+                    Some(TransportSlice::Udp(udp)) => (udp.source_port(), udp.destination_port()),
+                    Some(TransportSlice::Tcp(tcp)) => (tcp.source_port(), tcp.destination_port()),
+                    _ => return false,
+                };
+                direction.check(
+                    || *min <= ports.0 && ports.0 <= *max,
+                    || *min <= ports.1 && ports.1 <= *max,
+                )
+            },
+            IpProto(number) => packet.ip.as_ref().map(|ip| ip.payload_ip_number() == *number).unwrap_or(false),
+            EtherHost(direction, addr) => match &packet.link {
+                Some(LinkSlice::Ethernet2(eth)) => direction.check(
+                    || eth.source() == *addr,
+                    || eth.destination() == *addr,
+                ),
+                _ => false,
+            },
+            EtherProto(ether_type) => {
+                let value = match (&packet.vlan, &packet.link) {
+                    (Some(vlan), _) => vlan.inner().ether_type(),
+                    (None, Some(link)) => match link.ether_type() {
+                        Some(value) => value,
+                        None => return false,
+                    },
+                    (None, None) => return false,
+                };
+                value == *ether_type
+            },
+            Vlan(id) => match (&packet.vlan, id) {
+                (None, _) => false,
+                (Some(_), None) => true,
+                (Some(vlan), Some(id)) => vlan.outer().vlan_identifier() == *id,
+            },
+            Less(len) => packet_len(packet) <= *len,
+            Greater(len) => packet_len(packet) >= *len,
+        }
+    }
+}
+
+impl Direction {
+    fn check<S: Fn() -> bool, D: Fn() -> bool>(self, source: S, destination: D) -> bool {
+        match self {
+            Direction::Src => source(),
+            Direction::Dst => destination(),
+            Direction::SrcOrDst => source() || destination(),
+        }
+    }
+}
+
+fn ip_addresses(packet: &SlicedPacket) -> Option<(IpAddr, IpAddr)> {
+    packet.ip.as_ref().map(|ip| (ip.source_addr(), ip.destination_addr()))
+}
+
+fn in_net(addr: &IpAddr, net: &IpAddr, prefix_len: u8) -> bool {
+    match (addr, net) {
+        (IpAddr::V4(addr), IpAddr::V4(net)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0);
+            u32::from(*addr) & mask == u32::from(*net) & mask
+        },
+        (IpAddr::V6(addr), IpAddr::V6(net)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len)).unwrap_or(0);
+            u128::from(*addr) & mask == u128::from(*net) & mask
+        },
+        _ => false,
+    }
+}
+
+fn packet_len(packet: &SlicedPacket) -> usize {
+    packet.header_bytes().iter().map(|v| v.len()).sum::<usize>() + packet.payload.len() + packet.trailer.len() + packet.padding.len()
+}
+
+/// Splits the expression into words, brackets & operators.
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut word = String::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(c) = chars.next() {
+        let operator = match c {
+            '(' | ')' | '!' => Some(c.to_string()),
+            '&' if Some(&'&') == chars.peek() => {
+                chars.next();
+                Some("&&".to_string())
+            },
+            '|' if Some(&'|') == chars.peek() => {
+                chars.next();
+                Some("||".to_string())
+            },
+            c if c.is_whitespace() => None,
+            c => {
+                word.push(c);
+                continue;
+            },
+        };
+        if !word.is_empty() {
+            result.push(std::mem::take(&mut word));
+        }
+        if let Some(operator) = operator {
+            result.push(operator);
+        }
+    }
+    if !word.is_empty() {
+        result.push(word);
+    }
+    result
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+    context: Context,
+}
+
+impl Parser {
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|v| v.as_str())
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let result = self.tokens.get(self.pos).cloned();
+        if result.is_some() {
+            self.pos += 1;
+        }
+        result
+    }
+
+    fn next_value(&mut self) -> Result<String, FilterExpressionError> {
+        match self.next() {
+            Some(token) if is_operator(&token) => Err(FilterExpressionError::UnexpectedToken(token)),
+            Some(token) => Ok(token),
+            None => Err(FilterExpressionError::UnexpectedEnd),
+        }
+    }
+
+    /// Parses primitives combined by `and` & `or` (same precedence, left to right).
+    fn parse_expression(&mut self) -> Result<Node, FilterExpressionError> {
+        let mut result = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some("and") | Some("&&") => {
+                    self.pos += 1;
+                    result = Node::And(Box::new(result), Box::new(self.parse_unary()?));
+                },
+                Some("or") | Some("||") => {
+                    self.pos += 1;
+                    result = Node::Or(Box::new(result), Box::new(self.parse_unary()?));
+                },
+                _ => return Ok(result),
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, FilterExpressionError> {
+        match self.peek() {
+            None => Err(FilterExpressionError::UnexpectedEnd),
+            Some("not") | Some("!") => {
+                self.pos += 1;
+                Ok(Node::Not(Box::new(self.parse_unary()?)))
+            },
+            Some("(") => {
+                self.pos += 1;
+                let result = self.parse_expression()?;
+                match self.next() {
+                    Some(ref token) if token == ")" => Ok(result),
+                    Some(token) => Err(FilterExpressionError::UnexpectedToken(token)),
+                    None => Err(FilterExpressionError::UnexpectedEnd),
+                }
+            },
+            Some(_) => self.parse_primitive(),
+        }
+    }
+
+    fn parse_primitive(&mut self) -> Result<Node, FilterExpressionError> {
+        let first = self.next_value()?;
+
+        // primitives without qualifiers
+        match first.as_str() {
+            "less" => return Ok(Node::Less(parse_number(&self.next_value()?)? as usize)),
+            "greater" => return Ok(Node::Greater(parse_number(&self.next_value()?)? as usize)),
+            "vlan" => {
+                let id = match self.peek() {
+                    Some(value) if value.starts_with(|c: char| c.is_ascii_digit()) => {
+                        let value = self.next_value()?;
+                        let id = parse_number(&value)?;
+                        if id > 0xfff {
+                            return Err(FilterExpressionError::InvalidValue(value));
+                        }
+                        Some(id as u16)
+                    },
+                    _ => None,
+                };
+                return Ok(Node::Vlan(id));
+            },
+            _ => {},
+        }
+
+        // qualifiers
+        let mut token = Some(first);
+        let protocol = match token.as_deref().and_then(parse_protocol) {
+            Some(protocol) => {
+                token = self.next_qualifier();
+                Some(protocol)
+            },
+            None => None,
+        };
+        let direction = match token.as_deref() {
+            Some("src") => Some(Direction::Src),
+            Some("dst") => Some(Direction::Dst),
+            _ => None,
+        };
+        if direction.is_some() {
+            token = Some(self.next_value()?);
+        }
+        let kind = match token.as_deref() {
+            Some("host") => Some(Kind::Host),
+            Some("net") => Some(Kind::Net),
+            Some("port") => Some(Kind::Port),
+            Some("portrange") => Some(Kind::PortRange),
+            Some("proto") => Some(Kind::Proto),
+            _ => None,
+        };
+        if kind.is_some() {
+            token = Some(self.next_value()?);
+        }
+
+        let value = match token {
+            Some(value) => value,
+            // protocol without further keywords (e.g. "tcp")
+            None => return Ok(Node::Protocol(protocol.unwrap())),
+        };
+
+        if protocol.is_some() || direction.is_some() || kind.is_some() {
+            self.context = Context {
+                protocol,
+                direction: direction.unwrap_or(Direction::SrcOrDst),
+                kind: kind.unwrap_or(Kind::Host),
+            };
+        }
+        // values without keywords reuse the keywords of the last primitive
+        let context = self.context;
+        let node = primitive_node(context, &value)?;
+        Ok(match context.protocol {
+            Some(Protocol::Ether) | None => node,
+            Some(protocol) => Node::And(Box::new(Node::Protocol(protocol)), Box::new(node)),
+        })
+    }
+
+    /// Returns the next token if it continues the current primitive
+    /// (a direction or kind keyword).
+    fn next_qualifier(&mut self) -> Option<String> {
+        match self.peek() {
+            Some("src") | Some("dst") | Some("host") | Some("net") | Some("port") | Some("portrange") | Some("proto") => self.next(),
+            _ => None,
+        }
+    }
+}
+
+fn primitive_node(context: Context, value: &str) -> Result<Node, FilterExpressionError> {
+    let invalid = || FilterExpressionError::InvalidValue(value.to_string());
+    let direction = context.direction;
+
+    if Some(Protocol::Ether) == context.protocol {
+        return match context.kind {
+            Kind::Host => Ok(Node::EtherHost(direction, value.parse::<MacAddr>().map_err(|_| invalid())?.octets())),
+            Kind::Proto => Ok(Node::EtherProto(
+                match value.trim_start_matches('\\') {
+                    "ip" => ether_type::IPV4,
+                    "ip6" => ether_type::IPV6,
+                    "arp" => 0x0806,
+                    other => {
+                        let number = parse_number(other)?;
+                        if number > 0xffff {
+                            return Err(invalid());
+                        }
+                        number as u16
+                    },
+                }
+            )),
+            _ => Err(invalid()),
+        };
+    }
+
+    match context.kind {
+        Kind::Host => Ok(Node::Host(direction, value.parse().map_err(|_| invalid())?)),
+        Kind::Net => {
+            let (addr, prefix_len) = match value.find('/') {
+                Some(index) => (&value[..index], Some(&value[index + 1..])),
+                None => (value, None),
+            };
+            let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+            let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+            let prefix_len = match prefix_len {
+                Some(v) => v.parse::<u8>().ok().filter(|v| *v <= max_prefix_len).ok_or_else(invalid)?,
+                None => max_prefix_len,
+            };
+            Ok(Node::Net(direction, addr, prefix_len))
+        },
+        Kind::Port => {
+            let port = parse_port(value)?;
+            Ok(Node::Port(direction, port, port))
+        },
+        Kind::PortRange => {
+            let index = value.find('-').ok_or_else(invalid)?;
+            let min = parse_port(&value[..index])?;
+            let max = parse_port(&value[index + 1..])?;
+            Ok(Node::Port(direction, min.min(max), min.max(max)))
+        },
+        Kind::Proto => Ok(Node::IpProto(
+            match value.trim_start_matches('\\') {
+                "icmp" => ip_number::ICMP,
+                "icmp6" => ip_number::IPV6_ICMP,
+                "tcp" => ip_number::TCP,
+                "udp" => ip_number::UDP,
+                other => {
+                    let number = parse_number(other)?;
+                    if number > 0xff {
+                        return Err(invalid());
+                    }
+                    number as u8
+                },
+            }
+        )),
+    }
+}
+
+fn parse_protocol(token: &str) -> Option<Protocol> {
+    use Protocol::*;
+    match token {
+        "ether" => Some(Ether),
+        "ip" => Some(Ip),
+        "ip6" => Some(Ip6),
+        "tcp" => Some(Tcp),
+        "udp" => Some(Udp),
+        "icmp" => Some(Icmp),
+        "icmp6" => Some(Icmp6),
+        _ => None,
+    }
+}
+
+fn is_operator(token: &str) -> bool {
+    matches!(token, "(" | ")" | "!" | "&&" | "||" | "and" | "or" | "not")
+}
+
+/// Parses a decimal or `0x` prefixed hex number.
+fn parse_number(value: &str) -> Result<u32, FilterExpressionError> {
+    let result = match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    result.map_err(|_| FilterExpressionError::InvalidValue(value.to_string()))
+}
+
+fn parse_port(value: &str) -> Result<u16, FilterExpressionError> {
+    value.parse().map_err(|_| FilterExpressionError::InvalidValue(value.to_string()))
+}
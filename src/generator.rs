@@ -0,0 +1,279 @@
+use super::*;
+
+/// Rule describing how a field of the base packet is varied for each
+/// packet generated by a [`TrafficGenerator`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FieldVariation {
+    /// Adds `(index % count) * step` to the source ip address (IPv4 & IPv6).
+    SourceIp { step: u32, count: u32 },
+    /// Adds `(index % count) * step` to the destination ip address (IPv4 & IPv6).
+    DestinationIp { step: u32, count: u32 },
+    /// Adds `(index % count) * step` to the udp or tcp source port.
+    SourcePort { step: u16, count: u16 },
+    /// Adds `(index % count) * step` to the udp or tcp destination port.
+    DestinationPort { step: u16, count: u16 },
+    /// Adds `index * step` to the tcp sequence number.
+    TcpSequenceNumber { step: u32 },
+    /// Adds the sum of the payload lengths of all previously generated
+    /// packets to the tcp sequence number (as in a tcp stream).
+    TcpSequenceNumberByPayload,
+    /// Uses a random payload length between `min` & `max` (inclusive). The
+    /// payload is filled by repeating the payload of the base packet (or
+    /// with zeros if the base packet has no payload).
+    RandomPayloadLen { min: usize, max: usize },
+}
+
+/// Iterator generating serialized packets based on a base packet & a list
+/// of field variations.
+///
+/// Lengths, next header fields & checksums are recalculated for each
+/// generated packet (ether types are taken as they are present in the base
+/// packet). Random values are generated via a pseudo random generator
+/// that can be seeded via [`TrafficGenerator::with_seed`] to make the
+/// generated traffic reproducible.
+///
+/// The iterator never ends, use [`Iterator::take`] to limit the number
+/// of generated packets.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(1000, 53);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{PacketHeaders, generator::{TrafficGenerator, FieldVariation}};
+///
+/// let base = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+/// let generator = TrafficGenerator::new(
+///     base,
+///     vec![
+///         FieldVariation::SourcePort{ step: 1, count: 100 },
+///         FieldVariation::RandomPayloadLen{ min: 0, max: 64 },
+///     ]
+/// );
+///
+/// for (i, packet) in generator.take(10).enumerate() {
+///     let packet = packet.unwrap();
+///     let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+///     let udp = decoded.transport.unwrap().udp().unwrap();
+///     assert_eq!(1000 + i as u16, udp.source_port);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrafficGenerator<'a> {
+    base: PacketHeaders<'a>,
+    variations: Vec<FieldVariation>,
+    /// Index of the next packet.
+    index: u64,
+    /// Sum of the payload lengths of the already generated packets.
+    payload_offset: u64,
+    /// State of the xorshift pseudo random number generator.
+    rng_state: u64,
+}
+
+impl<'a> TrafficGenerator<'a> {
+
+    /// Creates a generator with a fixed default seed.
+    pub fn new(base: PacketHeaders<'a>, variations: Vec<FieldVariation>) -> TrafficGenerator<'a> {
+        TrafficGenerator::with_seed(base, variations, 0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Creates a generator with the given seed for the random values.
+    pub fn with_seed(base: PacketHeaders<'a>, variations: Vec<FieldVariation>, seed: u64) -> TrafficGenerator<'a> {
+        TrafficGenerator {
+            base,
+            variations,
+            index: 0,
+            payload_offset: 0,
+            // xorshift does not work with a state of 0
+            rng_state: if 0 == seed { 1 } else { seed },
+        }
+    }
+
+    /// Index of the next generated packet.
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Generates the next packet.
+    pub fn next_packet(&mut self) -> Result<Vec<u8>, WriteError> {
+        use FieldVariation::*;
+
+        let mut ip = self.base.ip.clone();
+        let mut transport = self.base.transport.clone();
+        let mut payload_len = self.base.payload.len();
+
+        for variation in &self.variations {
+            match *variation {
+                SourceIp{ step, count } => if let Some(ip) = ip.as_mut() {
+                    let add = variation_offset(self.index, u64::from(step), u64::from(count));
+                    match ip {
+                        IpHeader::Version4(header, _) => add_to_ipv4(&mut header.source, add),
+                        IpHeader::Version6(header, _) => add_to_ipv6(&mut header.source, add),
+                    }
+                },
+                DestinationIp{ step, count } => if let Some(ip) = ip.as_mut() {
+                    let add = variation_offset(self.index, u64::from(step), u64::from(count));
+                    match ip {
+                        IpHeader::Version4(header, _) => add_to_ipv4(&mut header.destination, add),
+                        IpHeader::Version6(header, _) => add_to_ipv6(&mut header.destination, add),
+                    }
+                },
+                SourcePort{ step, count } => if let Some(transport) = transport.as_mut() {
+                    let add = variation_offset(self.index, u64::from(step), u64::from(count)) as u16;
+                    match transport {
+                        TransportHeader::Udp(udp) => udp.source_port = udp.source_port.wrapping_add(add),
+                        TransportHeader::Tcp(tcp) => tcp.source_port = tcp.source_port.wrapping_add(add),
+                        TransportHeader::Igmp(_) => {},
+                    }
+                },
+                DestinationPort{ step, count } => if let Some(transport) = transport.as_mut() {
+                    let add = variation_offset(self.index, u64::from(step), u64::from(count)) as u16;
+                    match transport {
+                        TransportHeader::Udp(udp) => udp.destination_port = udp.destination_port.wrapping_add(add),
+                        TransportHeader::Tcp(tcp) => tcp.destination_port = tcp.destination_port.wrapping_add(add),
+                        TransportHeader::Igmp(_) => {},
+                    }
+                },
+                TcpSequenceNumber{ step } => if let Some(TransportHeader::Tcp(tcp)) = transport.as_mut() {
+                    tcp.sequence_number = tcp.sequence_number.wrapping_add(
+                        (self.index as u32).wrapping_mul(step)
+                    );
+                },
+                TcpSequenceNumberByPayload => if let Some(TransportHeader::Tcp(tcp)) = transport.as_mut() {
+                    tcp.sequence_number = tcp.sequence_number.wrapping_add(self.payload_offset as u32);
+                },
+                RandomPayloadLen{ min, max } => {
+                    payload_len = if max <= min {
+                        min
+                    } else {
+                        min + (next_random(&mut self.rng_state) % ((max - min) as u64 + 1)) as usize
+                    };
+                },
+            }
+        }
+
+        // generate the payload
+        let payload: Vec<u8> = if self.base.payload.is_empty() {
+            vec![0;payload_len]
+        } else {
+            self.base.payload.slice().iter().cycle().take(payload_len).cloned().collect()
+        };
+
+        let mut result = Vec::with_capacity(
+            self.base.link.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+            self.base.vlan.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+            ip.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+            transport.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+            payload.len()
+        );
+        write_headers(&mut result, &self.base.link, &self.base.vlan, ip, transport, &payload)?;
+
+        self.index += 1;
+        self.payload_offset += payload_len as u64;
+        Ok(result)
+    }
+}
+
+impl<'a> Iterator for TrafficGenerator<'a> {
+    type Item = Result<Vec<u8>, WriteError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_packet())
+    }
+}
+
+/// xorshift64 pseudo random number generator.
+fn next_random(state: &mut u64) -> u64 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *state = x;
+    x
+}
+
+/// Returns `(index % count) * step` (with a count of 0 treated as no limit).
+fn variation_offset(index: u64, step: u64, count: u64) -> u64 {
+    if 0 == count {
+        index.wrapping_mul(step)
+    } else {
+        (index % count).wrapping_mul(step)
+    }
+}
+
+fn add_to_ipv4(address: &mut [u8;4], value: u64) {
+    *address = u32::from_be_bytes(*address).wrapping_add(value as u32).to_be_bytes();
+}
+
+fn add_to_ipv6(address: &mut [u8;16], value: u64) {
+    *address = u128::from_be_bytes(*address).wrapping_add(u128::from(value)).to_be_bytes();
+}
+
+/// Writes the headers & payload, updating the lengths, next header fields & checksums.
+fn write_headers<T: io::Write + Sized>(
+    writer: &mut T,
+    link: &Option<Ethernet2Header>,
+    vlan: &Option<VlanHeader>,
+    ip: Option<IpHeader>,
+    mut transport: Option<TransportHeader>,
+    payload: &[u8]
+) -> Result<(), WriteError> {
+    if let Some(link) = link {
+        link.write(writer)?;
+    }
+    if let Some(vlan) = vlan {
+        vlan.write(writer)?;
+    }
+
+    let transport_size = transport.as_ref().map(|t| t.header_len()).unwrap_or(0) + payload.len();
+    if let Some(TransportHeader::Udp(udp)) = transport.as_mut() {
+        if transport_size > usize::from(u16::MAX) {
+            return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()).into());
+        }
+        udp.length = transport_size as u16;
+    }
+
+    match ip {
+        Some(IpHeader::Version4(mut header, mut ext)) => {
+            header.set_payload_len(ext.header_len() + transport_size)?;
+            match &transport {
+                Some(TransportHeader::Udp(_)) => header.protocol = ext.set_next_headers(ip_number::UDP),
+                Some(TransportHeader::Tcp(_)) => header.protocol = ext.set_next_headers(ip_number::TCP),
+                Some(TransportHeader::Igmp(_)) => header.protocol = ext.set_next_headers(ip_number::IGMP),
+                None => {},
+            }
+            if let Some(transport) = transport.as_mut() {
+                transport.update_checksum_ipv4(&header, payload)?;
+            }
+            header.write(writer)?;
+            ext.write(writer, header.protocol)?;
+        },
+        Some(IpHeader::Version6(mut header, mut ext)) => {
+            header.set_payload_length(ext.header_len() + transport_size)?;
+            match &transport {
+                Some(TransportHeader::Udp(_)) => header.next_header = ext.set_next_headers(ip_number::UDP),
+                Some(TransportHeader::Tcp(_)) => header.next_header = ext.set_next_headers(ip_number::TCP),
+                Some(TransportHeader::Igmp(_)) => header.next_header = ext.set_next_headers(ip_number::IGMP),
+                None => {},
+            }
+            if let Some(transport) = transport.as_mut() {
+                transport.update_checksum_ipv6(&header, payload)?;
+            }
+            header.write(writer)?;
+            ext.write(writer, header.next_header)?;
+        },
+        None => {},
+    }
+
+    if let Some(transport) = transport {
+        transport.write(writer)?;
+    }
+    writer.write_all(payload)?;
+    Ok(())
+}
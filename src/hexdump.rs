@@ -0,0 +1,154 @@
+use super::*;
+
+/// Errors that can occur when parsing a hex dump.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HexDumpError {
+    /// The offset of a line does not match the number of bytes read so far
+    /// (bytes are missing or the offset went backwards without restarting
+    /// at 0).
+    UnexpectedOffset {
+        /// Line number (starting at 1).
+        line: usize,
+        /// Offset expected based on the bytes read so far.
+        expected: usize,
+        /// Offset present in the line.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for HexDumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use HexDumpError::*;
+        match self {
+            UnexpectedOffset{ line, expected, actual } => {
+                write!(f, "HexDumpError: Unexpected offset in line {}. Expected an offset of 0x{:x} (or 0 for a new packet) but the line starts with the offset 0x{:x}.", line, expected, actual)
+            },
+        }
+    }
+}
+
+impl Error for HexDumpError {}
+
+/// Packets read from an offset-prefixed hex dump (as consumed by
+/// text2pcap & emitted by `od -Ax -tx1`, Wireshark "Copy as Hex Dump",
+/// `tcpdump -xx` and many vendor logs).
+///
+/// Each line of the dump starts with an offset in hex (optionally prefixed
+/// with `0x` and/or followed by a `:`), followed by the bytes of the packet
+/// as hex groups of 2 or 4 digits. Everything after the hex bytes (e.g. an
+/// ascii dump) is ignored. A line with the offset 0 starts a new packet.
+/// Lines without an offset (e.g. timestamps or comments) are skipped.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::hexdump::HexDump;
+///
+/// let dump = HexDump::parse(
+///     "0000  00 0e b6 00 00 02 00 0e b6 00 00 01 08 00 45 00   ..............E.\n\
+///      0010  00 1f 00 00 40 00 40 11 b9 6f c0 a8 01 01 c0 a8   ....@.@..o......\n\
+///      0020  01 02 00 35 04 d2 00 0b 00 00 01 02 03            ...5.........\n"
+/// ).unwrap();
+/// assert_eq!(1, dump.packets.len());
+/// assert_eq!(45, dump.packets[0].len());
+///
+/// let sliced = dump.slice_ethernet();
+/// assert_eq!(&[1,2,3], sliced[0].as_ref().unwrap().payload);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct HexDump {
+    /// Bytes of the packets in the order they are present in the dump.
+    pub packets: Vec<Vec<u8>>,
+}
+
+impl HexDump {
+
+    /// Parses all packets contained in the given hex dump.
+    pub fn parse(text: &str) -> Result<HexDump, HexDumpError> {
+        let mut packets = Vec::new();
+        // packet currently being read & the offset of the last line (used to
+        // cut off hex looking ascii dumps based on the offset of the next line)
+        let mut current: Option<(Vec<u8>, usize)> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let mut tokens = line.split_whitespace();
+            let offset = match tokens.next().and_then(parse_offset) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            if 0 == offset {
+                if let Some((packet, _)) = current.take() {
+                    packets.push(packet);
+                }
+                current = Some((Vec::new(), 0));
+            }
+
+            let (packet, line_start) = match current.as_mut() {
+                Some(value) => value,
+                None => return Err(HexDumpError::UnexpectedOffset{
+                    line: index + 1,
+                    expected: 0,
+                    actual: offset,
+                }),
+            };
+
+            // the previous line may have contained hex looking ascii characters
+            if offset > *line_start && offset < packet.len() {
+                packet.truncate(offset);
+            }
+            if offset != packet.len() {
+                return Err(HexDumpError::UnexpectedOffset{
+                    line: index + 1,
+                    expected: packet.len(),
+                    actual: offset,
+                });
+            }
+
+            *line_start = offset;
+            for token in tokens {
+                if !parse_hex_group(token, packet) {
+                    break;
+                }
+            }
+        }
+
+        if let Some((packet, _)) = current {
+            packets.push(packet);
+        }
+        Ok(HexDump{ packets })
+    }
+
+    /// Slices all packets starting with an Ethernet II header (see [`SlicedPacket::from_ethernet`]).
+    pub fn slice_ethernet(&self) -> Vec<Result<SlicedPacket<'_>, ReadError>> {
+        self.packets.iter().map(|p| SlicedPacket::from_ethernet(p)).collect()
+    }
+
+    /// Slices all packets starting with an IPv4 or IPv6 header (see [`SlicedPacket::from_ip`]).
+    pub fn slice_ip(&self) -> Vec<Result<SlicedPacket<'_>, ReadError>> {
+        self.packets.iter().map(|p| SlicedPacket::from_ip(p)).collect()
+    }
+}
+
+/// Parses the offset at the start of a line (returns None if the token is not an offset).
+fn parse_offset(token: &str) -> Option<usize> {
+    let token = token.strip_suffix(':').unwrap_or(token);
+    let token = token.strip_prefix("0x").unwrap_or(token);
+    if token.len() < 2 || !token.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    usize::from_str_radix(token, 16).ok()
+}
+
+/// Parses a group of 2 or 4 hex digits & adds the bytes to the packet
+/// (returns false if the token is not a hex group).
+fn parse_hex_group(token: &str, packet: &mut Vec<u8>) -> bool {
+    if (2 != token.len() && 4 != token.len()) || !token.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return false;
+    }
+    for i in (0..token.len()).step_by(2) {
+        // can not fail as all characters were checked to be hex digits
+        packet.push(u8::from_str_radix(&token[i..i + 2], 16).unwrap());
+    }
+    true
+}
@@ -0,0 +1,793 @@
+use super::*;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Class values of ICMP extension objects (RFC 4950, RFC 5837 & RFC 8335).
+pub mod icmp_extension_class {
+    pub const MPLS_LABEL_STACK: u8 = 1;
+    pub const INTERFACE_INFORMATION: u8 = 2;
+    pub const INTERFACE_IDENTIFICATION: u8 = 3;
+}
+
+/// Version of the ICMP extension structure (RFC 4884).
+pub const VERSION: u8 = 2;
+
+/// Length of the header of the extension structure (version, reserved
+/// bits & checksum).
+pub const HEADER_LEN: usize = 4;
+
+/// Length of the header of an extension object (length, class-num & C-Type).
+pub const OBJECT_HEADER_LEN: usize = 4;
+
+/// Minimum length of the "original datagram" field of ICMP error messages
+/// containing extensions (RFC 4884 section 4.1 & 4.2).
+pub const MIN_ORIGINAL_DATAGRAM_LEN: usize = 128;
+
+/// Errors that can occur when reading or writing ICMP extensions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IcmpExtensionError {
+    /// The data ended before the end of the extension structure header or
+    /// an extension object (expected minimum size as argument).
+    UnexpectedEnd(usize),
+    /// The version of the extension structure is not 2.
+    UnsupportedVersion(u8),
+    /// An object has a length smaller than the object header.
+    ObjectLengthBad(u16),
+    /// The content of an object is not valid for its class & C-Type.
+    InvalidObject{ class_num: u8, c_type: u8 },
+    /// The object is too large to be represented in the length field.
+    ObjectTooLarge(usize),
+    /// The original datagram is too large to be represented in the length
+    /// field of the ICMP header.
+    OriginalDatagramTooLarge(usize),
+}
+
+impl fmt::Display for IcmpExtensionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use IcmpExtensionError::*;
+        match self {
+            UnexpectedEnd(len) => write!(f, "IcmpExtensionError: Unexpected end of data. Expected at least {} bytes.", len),
+            UnsupportedVersion(version) => write!(f, "IcmpExtensionError: Unsupported extension structure version {} (only version 2 is supported).", version),
+            ObjectLengthBad(len) => write!(f, "IcmpExtensionError: Object length {} is smaller than the object header.", len),
+            InvalidObject{ class_num, c_type } => write!(f, "IcmpExtensionError: Invalid object content for class {} & C-Type {}.", class_num, c_type),
+            ObjectTooLarge(len) => write!(f, "IcmpExtensionError: Object with a size of {} bytes is too large.", len),
+            OriginalDatagramTooLarge(len) => write!(f, "IcmpExtensionError: Original datagram with a size of {} bytes is too large.", len),
+        }
+    }
+}
+
+impl Error for IcmpExtensionError {}
+
+/// Entry of an MPLS label stack object (RFC 4950).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct MplsLabelStackEntry {
+    /// 20 bit label value.
+    pub label: u32,
+    /// 3 bit traffic class (formerly "experimental use").
+    pub traffic_class: u8,
+    /// Set for the last entry of the label stack.
+    pub bottom_of_stack: bool,
+    pub ttl: u8,
+}
+
+impl MplsLabelStackEntry {
+    /// Length of a serialized entry in bytes.
+    pub const LEN: usize = 4;
+
+    /// Decodes an entry.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;4]) -> MplsLabelStackEntry {
+        MplsLabelStackEntry{
+            label: u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 4,
+            traffic_class: (bytes[2] >> 1) & 0b111,
+            bottom_of_stack: 0 != bytes[2] & 1,
+            ttl: bytes[3],
+        }
+    }
+
+    /// Returns the serialized entry (bits outside of the label & traffic
+    /// class ranges are ignored).
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;4] {
+        let label = (self.label & 0xf_ffff).to_be_bytes();
+        [
+            (label[1] << 4) | (label[2] >> 4),
+            (label[2] << 4) | (label[3] >> 4),
+            (label[3] << 4) | ((self.traffic_class & 0b111) << 1) | u8::from(self.bottom_of_stack),
+            self.ttl,
+        ]
+    }
+}
+
+/// Role of the interface described by an interface information object
+/// (RFC 5837 section 4.1).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum InterfaceRole {
+    /// Interface the original packet arrived on (value 0).
+    IncomingIpInterface,
+    /// Sub-IP component of the interface the original packet arrived on (value 1).
+    SubIpComponent,
+    /// Interface the original packet would have been forwarded on (value 2).
+    OutgoingIpInterface,
+    /// Next hop the original packet would have been forwarded to (value 3).
+    IpNextHop,
+}
+
+impl InterfaceRole {
+    /// Converts the 2 bit role value to the enum.
+    pub fn from_u8(value: u8) -> InterfaceRole {
+        use InterfaceRole::*;
+        match value & 0b11 {
+            0 => IncomingIpInterface,
+            1 => SubIpComponent,
+            2 => OutgoingIpInterface,
+            _ => IpNextHop,
+        }
+    }
+
+    /// Returns the 2 bit role value.
+    #[inline]
+    pub fn to_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Interface information object (RFC 5837).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterfaceInformation<'a> {
+    pub role: InterfaceRole,
+    /// SNMP ifIndex of the interface.
+    pub if_index: Option<u32>,
+    pub ip_addr: Option<IpAddr>,
+    /// Name of the interface (UTF-8, at most 63 bytes).
+    pub name: Option<&'a str>,
+    pub mtu: Option<u32>,
+}
+
+impl<'a> InterfaceInformation<'a> {
+    const IF_INDEX_FLAG: u8 = 0b1000;
+    const IP_ADDR_FLAG: u8 = 0b0100;
+    const NAME_FLAG: u8 = 0b0010;
+    const MTU_FLAG: u8 = 0b0001;
+
+    /// Address family number of IPv4 addresses in the IP address sub-object.
+    pub const AFI_IPV4: u16 = 1;
+    /// Address family number of IPv6 addresses in the IP address sub-object.
+    pub const AFI_IPV6: u16 = 2;
+
+    /// Returns the C-Type of the object (role & flags indicating which
+    /// fields are present).
+    pub fn c_type(&self) -> u8 {
+        (self.role.to_u8() << 6) |
+        if self.if_index.is_some() { Self::IF_INDEX_FLAG } else { 0 } |
+        if self.ip_addr.is_some() { Self::IP_ADDR_FLAG } else { 0 } |
+        if self.name.is_some() { Self::NAME_FLAG } else { 0 } |
+        if self.mtu.is_some() { Self::MTU_FLAG } else { 0 }
+    }
+
+    /// Decodes the object data (without the object header).
+    fn from_data(c_type: u8, mut data: &'a [u8]) -> Result<InterfaceInformation<'a>, IcmpExtensionError> {
+        let invalid = || IcmpExtensionError::InvalidObject{
+            class_num: icmp_extension_class::INTERFACE_INFORMATION,
+            c_type,
+        };
+        let read_u32 = |data: &mut &'a [u8]| -> Result<u32, IcmpExtensionError> {
+            if data.len() < 4 {
+                return Err(invalid());
+            }
+            let value = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+            *data = &data[4..];
+            Ok(value)
+        };
+
+        let if_index = if 0 != c_type & Self::IF_INDEX_FLAG {
+            Some(read_u32(&mut data)?)
+        } else {
+            None
+        };
+        let ip_addr = if 0 != c_type & Self::IP_ADDR_FLAG {
+            let afi = read_u32(&mut data)? >> 16;
+            match afi as u16 {
+                Self::AFI_IPV4 => Some(IpAddr::V4(Ipv4Addr::from(read_u32(&mut data)?))),
+                Self::AFI_IPV6 if data.len() >= 16 => {
+                    let mut octets = [0u8;16];
+                    octets.copy_from_slice(&data[..16]);
+                    data = &data[16..];
+                    Some(IpAddr::V6(Ipv6Addr::from(octets)))
+                },
+                _ => return Err(invalid()),
+            }
+        } else {
+            None
+        };
+        let name = if 0 != c_type & Self::NAME_FLAG {
+            let len = usize::from(*data.first().ok_or_else(invalid)?);
+            if len < 4 || !len.is_multiple_of(4) || data.len() < len {
+                return Err(invalid());
+            }
+            let name = &data[1..len];
+            let name_len = name.iter().rposition(|v| 0 != *v).map(|i| i + 1).unwrap_or(0);
+            data = &data[len..];
+            Some(std::str::from_utf8(&name[..name_len]).map_err(|_| invalid())?)
+        } else {
+            None
+        };
+        let mtu = if 0 != c_type & Self::MTU_FLAG {
+            Some(read_u32(&mut data)?)
+        } else {
+            None
+        };
+
+        Ok(InterfaceInformation{
+            role: InterfaceRole::from_u8(c_type >> 6),
+            if_index,
+            ip_addr,
+            name,
+            mtu,
+        })
+    }
+
+    /// Writes the object data (without the object header).
+    fn write_data(&self, output: &mut Vec<u8>) -> Result<(), IcmpExtensionError> {
+        if let Some(if_index) = self.if_index {
+            output.extend_from_slice(&if_index.to_be_bytes());
+        }
+        match self.ip_addr {
+            Some(IpAddr::V4(addr)) => {
+                output.extend_from_slice(&Self::AFI_IPV4.to_be_bytes());
+                output.extend_from_slice(&[0, 0]);
+                output.extend_from_slice(&addr.octets());
+            },
+            Some(IpAddr::V6(addr)) => {
+                output.extend_from_slice(&Self::AFI_IPV6.to_be_bytes());
+                output.extend_from_slice(&[0, 0]);
+                output.extend_from_slice(&addr.octets());
+            },
+            None => {},
+        }
+        if let Some(name) = self.name {
+            // length octet & name padded to a multiple of 4 octets (max 64)
+            let len = (1 + name.len()).div_ceil(4)*4;
+            if len > 64 {
+                return Err(IcmpExtensionError::ObjectTooLarge(len));
+            }
+            let start = output.len();
+            output.push(len as u8);
+            output.extend_from_slice(name.as_bytes());
+            output.resize(start + len, 0);
+        }
+        if let Some(mtu) = self.mtu {
+            output.extend_from_slice(&mtu.to_be_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Interface identification object identifying the probed interface of
+/// an extended echo request (RFC 8335 section 2.1).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InterfaceIdentification<'a> {
+    /// Interface identified by its name (C-Type 1).
+    Name(&'a str),
+    /// Interface identified by its ifIndex (C-Type 2).
+    Index(u32),
+    /// Interface identified by an address (C-Type 3) with the given
+    /// address family number (e.g. [`InterfaceInformation::AFI_IPV4`]).
+    Address{ afi: u16, address: &'a [u8] },
+}
+
+impl<'a> InterfaceIdentification<'a> {
+    /// C-Type of objects identifying the interface by name.
+    pub const C_TYPE_NAME: u8 = 1;
+    /// C-Type of objects identifying the interface by index.
+    pub const C_TYPE_INDEX: u8 = 2;
+    /// C-Type of objects identifying the interface by address.
+    pub const C_TYPE_ADDRESS: u8 = 3;
+
+    /// Returns the C-Type of the object.
+    pub fn c_type(&self) -> u8 {
+        match self {
+            InterfaceIdentification::Name(_) => Self::C_TYPE_NAME,
+            InterfaceIdentification::Index(_) => Self::C_TYPE_INDEX,
+            InterfaceIdentification::Address{ .. } => Self::C_TYPE_ADDRESS,
+        }
+    }
+
+    /// Returns the address as ip address (None if the interface is not
+    /// identified by an IPv4 or IPv6 address).
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        match self {
+            InterfaceIdentification::Address{ afi: InterfaceInformation::AFI_IPV4, address } if 4 == address.len() => {
+                Some(IpAddr::V4(Ipv4Addr::new(address[0], address[1], address[2], address[3])))
+            },
+            InterfaceIdentification::Address{ afi: InterfaceInformation::AFI_IPV6, address } if 16 == address.len() => {
+                let mut octets = [0u8;16];
+                octets.copy_from_slice(address);
+                Some(IpAddr::V6(Ipv6Addr::from(octets)))
+            },
+            _ => None,
+        }
+    }
+
+    /// Decodes the object data (without the object header), None is
+    /// returned for unknown C-Types.
+    fn from_data(c_type: u8, data: &'a [u8]) -> Result<Option<InterfaceIdentification<'a>>, IcmpExtensionError> {
+        let invalid = || IcmpExtensionError::InvalidObject{
+            class_num: icmp_extension_class::INTERFACE_IDENTIFICATION,
+            c_type,
+        };
+        Ok(Some(match c_type {
+            Self::C_TYPE_NAME => {
+                // the name is padded with zeros to a multiple of 4 octets
+                let name_len = data.iter().rposition(|v| 0 != *v).map(|i| i + 1).unwrap_or(0);
+                InterfaceIdentification::Name(std::str::from_utf8(&data[..name_len]).map_err(|_| invalid())?)
+            },
+            Self::C_TYPE_INDEX => {
+                if 4 != data.len() {
+                    return Err(invalid());
+                }
+                InterfaceIdentification::Index(u32::from_be_bytes([data[0], data[1], data[2], data[3]]))
+            },
+            Self::C_TYPE_ADDRESS => {
+                if data.len() < 4 || data.len() < 4 + usize::from(data[2]) {
+                    return Err(invalid());
+                }
+                InterfaceIdentification::Address{
+                    afi: u16::from_be_bytes([data[0], data[1]]),
+                    address: &data[4..4 + usize::from(data[2])],
+                }
+            },
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Writes the object data (without the object header).
+    fn write_data(&self, output: &mut Vec<u8>) -> Result<(), IcmpExtensionError> {
+        let start = output.len();
+        match self {
+            InterfaceIdentification::Name(name) => output.extend_from_slice(name.as_bytes()),
+            InterfaceIdentification::Index(index) => output.extend_from_slice(&index.to_be_bytes()),
+            InterfaceIdentification::Address{ afi, address } => {
+                if address.len() > usize::from(u8::MAX) {
+                    return Err(IcmpExtensionError::ObjectTooLarge(OBJECT_HEADER_LEN + 4 + address.len()));
+                }
+                output.extend_from_slice(&afi.to_be_bytes());
+                output.extend_from_slice(&[address.len() as u8, 0]);
+                output.extend_from_slice(address);
+            },
+        }
+        // pad to a multiple of 4 octets
+        let len = output.len() - start;
+        output.resize(start + len.div_ceil(4)*4, 0);
+        Ok(())
+    }
+}
+
+/// ICMP extension object (RFC 4884 section 7).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IcmpExtensionObject<'a> {
+    /// MPLS label stack of the original packet (RFC 4950, C-Type 1).
+    MplsLabelStack(Vec<MplsLabelStackEntry>),
+    InterfaceInformation(InterfaceInformation<'a>),
+    InterfaceIdentification(InterfaceIdentification<'a>),
+    /// Object that is not decoded (data without the object header).
+    Unknown{ class_num: u8, c_type: u8, data: &'a [u8] },
+}
+
+impl<'a> IcmpExtensionObject<'a> {
+    /// Reads the object at the start of the slice & returns the object & the rest of the slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<(IcmpExtensionObject<'a>, &'a [u8]), IcmpExtensionError> {
+        use IcmpExtensionError::*;
+
+        if slice.len() < OBJECT_HEADER_LEN {
+            return Err(UnexpectedEnd(OBJECT_HEADER_LEN));
+        }
+        let length = u16::from_be_bytes([slice[0], slice[1]]);
+        let len = usize::from(length);
+        if len < OBJECT_HEADER_LEN {
+            return Err(ObjectLengthBad(length));
+        }
+        if slice.len() < len {
+            return Err(UnexpectedEnd(len));
+        }
+        let (object, rest) = slice.split_at(len);
+        let class_num = object[2];
+        let c_type = object[3];
+        let data = &object[OBJECT_HEADER_LEN..];
+        let result = match (class_num, c_type) {
+            (icmp_extension_class::MPLS_LABEL_STACK, 1) => {
+                if !data.len().is_multiple_of(MplsLabelStackEntry::LEN) {
+                    return Err(InvalidObject{ class_num, c_type });
+                }
+                IcmpExtensionObject::MplsLabelStack(
+                    data.chunks_exact(MplsLabelStackEntry::LEN)
+                        .map(|chunk| MplsLabelStackEntry::from_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                        .collect()
+                )
+            },
+            (icmp_extension_class::INTERFACE_INFORMATION, _) => IcmpExtensionObject::InterfaceInformation(
+                InterfaceInformation::from_data(c_type, data)?
+            ),
+            (icmp_extension_class::INTERFACE_IDENTIFICATION, _) => match InterfaceIdentification::from_data(c_type, data)? {
+                Some(value) => IcmpExtensionObject::InterfaceIdentification(value),
+                None => IcmpExtensionObject::Unknown{ class_num, c_type, data },
+            },
+            _ => IcmpExtensionObject::Unknown{ class_num, c_type, data },
+        };
+        Ok((result, rest))
+    }
+
+    /// Class value of the object.
+    pub fn class_num(&self) -> u8 {
+        match self {
+            IcmpExtensionObject::MplsLabelStack(_) => icmp_extension_class::MPLS_LABEL_STACK,
+            IcmpExtensionObject::InterfaceInformation(_) => icmp_extension_class::INTERFACE_INFORMATION,
+            IcmpExtensionObject::InterfaceIdentification(_) => icmp_extension_class::INTERFACE_IDENTIFICATION,
+            IcmpExtensionObject::Unknown{ class_num, .. } => *class_num,
+        }
+    }
+
+    /// C-Type value of the object.
+    pub fn c_type(&self) -> u8 {
+        match self {
+            IcmpExtensionObject::MplsLabelStack(_) => 1,
+            IcmpExtensionObject::InterfaceInformation(value) => value.c_type(),
+            IcmpExtensionObject::InterfaceIdentification(value) => value.c_type(),
+            IcmpExtensionObject::Unknown{ c_type, .. } => *c_type,
+        }
+    }
+
+    /// Writes the object (including the object header).
+    pub fn write(&self, output: &mut Vec<u8>) -> Result<(), IcmpExtensionError> {
+        let start = output.len();
+        output.extend_from_slice(&[0, 0, self.class_num(), self.c_type()]);
+        match self {
+            IcmpExtensionObject::MplsLabelStack(entries) => {
+                for entry in entries {
+                    output.extend_from_slice(&entry.to_bytes());
+                }
+            },
+            IcmpExtensionObject::InterfaceInformation(value) => {
+                if let Err(err) = value.write_data(output) {
+                    output.truncate(start);
+                    return Err(err);
+                }
+            },
+            IcmpExtensionObject::InterfaceIdentification(value) => {
+                if let Err(err) = value.write_data(output) {
+                    output.truncate(start);
+                    return Err(err);
+                }
+            },
+            IcmpExtensionObject::Unknown{ data, .. } => output.extend_from_slice(data),
+        }
+        let len = output.len() - start;
+        if len > usize::from(u16::MAX) {
+            output.truncate(start);
+            return Err(IcmpExtensionError::ObjectTooLarge(len));
+        }
+        output[start..start + 2].copy_from_slice(&(len as u16).to_be_bytes());
+        Ok(())
+    }
+}
+
+/// Iterator over the objects of an ICMP extension structure.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IcmpExtensionObjectsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> IcmpExtensionObjectsIterator<'a> {
+    /// Creates an iterator over the objects in the given slice (the data
+    /// after the extension structure header).
+    pub fn from_slice(objects: &'a [u8]) -> IcmpExtensionObjectsIterator<'a> {
+        IcmpExtensionObjectsIterator{ rest: objects }
+    }
+
+    /// Returns the slice containing the objects that were not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for IcmpExtensionObjectsIterator<'a> {
+    type Item = Result<IcmpExtensionObject<'a>, IcmpExtensionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        match IcmpExtensionObject::from_slice(self.rest) {
+            Ok((object, rest)) => {
+                self.rest = rest;
+                Some(Ok(object))
+            },
+            Err(err) => {
+                // stop the iteration after an error
+                self.rest = &[];
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// Slice containing an ICMP extension structure (RFC 4884 section 7),
+/// starting with the extension header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IcmpExtensionsSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IcmpExtensionsSlice<'a> {
+    /// Creates a slice containing an extension structure & checks the
+    /// length & version (the checksum is not verified).
+    pub fn from_slice(slice: &'a [u8]) -> Result<IcmpExtensionsSlice<'a>, IcmpExtensionError> {
+        if slice.len() < HEADER_LEN {
+            return Err(IcmpExtensionError::UnexpectedEnd(HEADER_LEN));
+        }
+        let version = slice[0] >> 4;
+        if VERSION != version {
+            return Err(IcmpExtensionError::UnsupportedVersion(version));
+        }
+        Ok(IcmpExtensionsSlice{ slice })
+    }
+
+    /// Returns the slice containing the extension structure.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Version of the extension structure (always 2).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Checksum field of the extension header.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Returns true if the checksum over the extension structure is valid.
+    pub fn is_checksum_valid(&self) -> bool {
+        0 == checksum::Sum16BitWords::new()
+            .add_slice(self.slice)
+            .ones_complement()
+    }
+
+    /// Returns an iterator over the extension objects.
+    pub fn objects(&self) -> IcmpExtensionObjectsIterator<'a> {
+        IcmpExtensionObjectsIterator::from_slice(&self.slice[HEADER_LEN..])
+    }
+}
+
+/// State values of the neighbor cache entry reported in extended echo
+/// replies (RFC 8335 section 3, RFC 4861 section 7.3.2).
+pub mod extended_echo_state {
+    pub const INCOMPLETE: u8 = 1;
+    pub const REACHABLE: u8 = 2;
+    pub const STALE: u8 = 3;
+    pub const DELAY: u8 = 4;
+    pub const PROBE: u8 = 5;
+    pub const FAILED: u8 = 6;
+}
+
+/// Type specific fields of an ICMPv4 or ICMPv6 extended echo request
+/// (RFC 8335 section 2).
+///
+/// The request is followed by an extension structure containing the
+/// interface identification object of the probed interface (see
+/// [`ExtendedEchoRequestHeader::interface_identification`] & [`write_extensions`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmp_extension::*;
+/// use etherparse::icmpv6::icmpv6_type;
+///
+/// let header = ExtendedEchoRequestHeader{ id: 1, seq: 2, local: true };
+/// let mut message = vec![icmpv6_type::EXTENDED_ECHO_REQUEST, 0, 0, 0];
+/// message.extend_from_slice(&header.to_bytes());
+/// write_extensions(
+///     &[IcmpExtensionObject::InterfaceIdentification(InterfaceIdentification::Name("eth0"))],
+///     &mut message
+/// ).unwrap();
+///
+/// let decoded = ExtendedEchoRequestHeader::from_bytes([message[4], message[5], message[6], message[7]]);
+/// assert_eq!(header, decoded);
+/// assert_eq!(
+///     Some(InterfaceIdentification::Name("eth0")),
+///     decoded.interface_identification(&message[8..]).unwrap()
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct ExtendedEchoRequestHeader {
+    pub id: u16,
+    /// 8 bit sequence number.
+    pub seq: u8,
+    /// Set if the probed interface resides on the probed node ("L" bit).
+    pub local: bool,
+}
+
+impl ExtendedEchoRequestHeader {
+    /// Decodes the type specific bytes 5-8 of the header.
+    #[inline]
+    pub fn from_bytes(bytes5to8: [u8;4]) -> ExtendedEchoRequestHeader {
+        ExtendedEchoRequestHeader{
+            id: u16::from_be_bytes([bytes5to8[0], bytes5to8[1]]),
+            seq: bytes5to8[2],
+            local: 0 != bytes5to8[3] & 1,
+        }
+    }
+
+    /// Returns the type specific bytes 5-8 of the header.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;4] {
+        let id = self.id.to_be_bytes();
+        [id[0], id[1], self.seq, u8::from(self.local)]
+    }
+
+    /// Decodes the interface identification object from the extension
+    /// structure following the header (None if the extension structure
+    /// contains no interface identification object).
+    pub fn interface_identification<'a>(&self, payload: &'a [u8]) -> Result<Option<InterfaceIdentification<'a>>, IcmpExtensionError> {
+        for object in IcmpExtensionsSlice::from_slice(payload)?.objects() {
+            if let IcmpExtensionObject::InterfaceIdentification(value) = object? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Code of an extended echo reply (RFC 8335 section 3).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ExtendedEchoReplyCode {
+    /// No error (code 0).
+    NoError,
+    /// Malformed query (code 1).
+    MalformedQuery,
+    /// No such interface (code 2).
+    NoSuchInterface,
+    /// No such table entry (code 3).
+    NoSuchTableEntry,
+    /// Multiple interfaces satisfy the query (code 4).
+    MultipleInterfacesSatisfyQuery,
+}
+
+impl ExtendedEchoReplyCode {
+    /// Converts the code to the enum (None for unknown codes).
+    pub fn from_u8(code: u8) -> Option<ExtendedEchoReplyCode> {
+        use ExtendedEchoReplyCode::*;
+        match code {
+            0 => Some(NoError),
+            1 => Some(MalformedQuery),
+            2 => Some(NoSuchInterface),
+            3 => Some(NoSuchTableEntry),
+            4 => Some(MultipleInterfacesSatisfyQuery),
+            _ => None,
+        }
+    }
+
+    /// Returns the code of the message.
+    #[inline]
+    pub fn code_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Code & type specific fields of an ICMPv4 or ICMPv6 extended echo reply
+/// (RFC 8335 section 3).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ExtendedEchoReplyHeader {
+    pub code: ExtendedEchoReplyCode,
+    pub id: u16,
+    /// 8 bit sequence number.
+    pub seq: u8,
+    /// 3 bit state of the neighbor cache entry of the probed interface
+    /// (see [`extended_echo_state`], only set if the interface is not local).
+    pub state: u8,
+    /// The probed interface is active ("A" bit).
+    pub active: bool,
+    /// The probed interface runs IPv4 ("4" bit).
+    pub ipv4: bool,
+    /// The probed interface runs IPv6 ("6" bit).
+    pub ipv6: bool,
+}
+
+impl ExtendedEchoReplyHeader {
+    /// Decodes the code & the type specific bytes 5-8 (None for unknown codes).
+    pub fn from_values(code: u8, bytes5to8: [u8;4]) -> Option<ExtendedEchoReplyHeader> {
+        Some(ExtendedEchoReplyHeader{
+            code: ExtendedEchoReplyCode::from_u8(code)?,
+            id: u16::from_be_bytes([bytes5to8[0], bytes5to8[1]]),
+            seq: bytes5to8[2],
+            state: bytes5to8[3] >> 5,
+            active: 0 != bytes5to8[3] & 0b100,
+            ipv4: 0 != bytes5to8[3] & 0b10,
+            ipv6: 0 != bytes5to8[3] & 0b1,
+        })
+    }
+
+    /// Returns the type specific bytes 5-8 of the header (bits of the
+    /// state outside of the 3 bit range are ignored).
+    pub fn to_bytes(&self) -> [u8;4] {
+        let id = self.id.to_be_bytes();
+        [
+            id[0],
+            id[1],
+            self.seq,
+            ((self.state & 0b111) << 5) |
+            if self.active { 0b100 } else { 0 } |
+            if self.ipv4 { 0b10 } else { 0 } |
+            if self.ipv6 { 0b1 } else { 0 },
+        ]
+    }
+}
+
+/// Writes an extension structure (header with the calculated checksum &
+/// the given objects).
+pub fn write_extensions(objects: &[IcmpExtensionObject], output: &mut Vec<u8>) -> Result<(), IcmpExtensionError> {
+    let start = output.len();
+    output.extend_from_slice(&[VERSION << 4, 0, 0, 0]);
+    for object in objects {
+        if let Err(err) = object.write(output) {
+            output.truncate(start);
+            return Err(err);
+        }
+    }
+    let checksum = checksum::Sum16BitWords::new()
+        .add_slice(&output[start..])
+        .ones_complement()
+        .to_be();
+    output[start + 2..start + 4].copy_from_slice(&checksum.to_be_bytes());
+    Ok(())
+}
+
+/// Splits the data after the header of an ICMP error message supporting
+/// extensions into the original datagram & the extension structure,
+/// based on the length field at `length_index` (in units of `unit` bytes).
+pub(crate) fn split_error_extensions(message: &[u8], length_index: usize, unit: usize) -> Result<(&[u8], Option<IcmpExtensionsSlice<'_>>), IcmpExtensionError> {
+    if message.len() < 8 {
+        return Err(IcmpExtensionError::UnexpectedEnd(8));
+    }
+    let data = &message[8..];
+    let original_len = usize::from(message[length_index])*unit;
+    if 0 == original_len {
+        return Ok((data, None));
+    }
+    if data.len() < original_len {
+        return Err(IcmpExtensionError::UnexpectedEnd(8 + original_len));
+    }
+    let (original, extensions) = data.split_at(original_len);
+    if extensions.is_empty() {
+        return Ok((original, None));
+    }
+    IcmpExtensionsSlice::from_slice(extensions)
+        .map(|value| (original, Some(value)))
+        .map_err(|err| match err {
+            IcmpExtensionError::UnexpectedEnd(len) => IcmpExtensionError::UnexpectedEnd(8 + original_len + len),
+            err => err,
+        })
+}
+
+/// Pads the original datagram of an ICMP error message, sets the length
+/// field at `length_index` (in units of `unit` bytes) & appends the
+/// extension structure.
+pub(crate) fn append_error_extensions(message: &mut Vec<u8>, length_index: usize, unit: usize, objects: &[IcmpExtensionObject]) -> Result<(), IcmpExtensionError> {
+    if message.len() < 8 {
+        return Err(IcmpExtensionError::UnexpectedEnd(8));
+    }
+    let original_len = (message.len() - 8).max(MIN_ORIGINAL_DATAGRAM_LEN).div_ceil(unit)*unit;
+    if original_len / unit > usize::from(u8::MAX) {
+        return Err(IcmpExtensionError::OriginalDatagramTooLarge(message.len() - 8));
+    }
+    let mut extensions = Vec::new();
+    write_extensions(objects, &mut extensions)?;
+    message.resize(8 + original_len, 0);
+    message[length_index] = (original_len / unit) as u8;
+    message.extend_from_slice(&extensions);
+    Ok(())
+}
@@ -0,0 +1,881 @@
+use super::*;
+
+/// ICMPv4 type values (RFC 792, RFC 950, RFC 1256 & RFC 8335).
+pub mod icmpv4_type {
+    pub const ECHO_REPLY: u8 = 0;
+    pub const DESTINATION_UNREACHABLE: u8 = 3;
+    pub const SOURCE_QUENCH: u8 = 4;
+    pub const REDIRECT: u8 = 5;
+    pub const ECHO_REQUEST: u8 = 8;
+    pub const ROUTER_ADVERTISEMENT: u8 = 9;
+    pub const ROUTER_SOLICITATION: u8 = 10;
+    pub const TIME_EXCEEDED: u8 = 11;
+    pub const PARAMETER_PROBLEM: u8 = 12;
+    pub const TIMESTAMP_REQUEST: u8 = 13;
+    pub const TIMESTAMP_REPLY: u8 = 14;
+    pub const INFORMATION_REQUEST: u8 = 15;
+    pub const INFORMATION_REPLY: u8 = 16;
+    pub const ADDRESS_MASK_REQUEST: u8 = 17;
+    pub const ADDRESS_MASK_REPLY: u8 = 18;
+    pub const EXTENDED_ECHO_REQUEST: u8 = 42;
+    pub const EXTENDED_ECHO_REPLY: u8 = 43;
+}
+
+/// Code values of destination unreachable messages (RFC 792, RFC 1122 & RFC 1812).
+pub mod dest_unreachable_code {
+    pub const NETWORK: u8 = 0;
+    pub const HOST: u8 = 1;
+    pub const PROTOCOL: u8 = 2;
+    pub const PORT: u8 = 3;
+    pub const FRAGMENTATION_NEEDED: u8 = 4;
+    pub const SOURCE_ROUTE_FAILED: u8 = 5;
+    pub const NETWORK_UNKNOWN: u8 = 6;
+    pub const HOST_UNKNOWN: u8 = 7;
+    pub const ISOLATED: u8 = 8;
+    pub const NETWORK_PROHIBITED: u8 = 9;
+    pub const HOST_PROHIBITED: u8 = 10;
+    pub const TOS_NETWORK: u8 = 11;
+    pub const TOS_HOST: u8 = 12;
+    pub const FILTER_PROHIBITED: u8 = 13;
+    pub const HOST_PRECEDENCE_VIOLATION: u8 = 14;
+    pub const PRECEDENCE_CUTOFF: u8 = 15;
+}
+
+/// Code values of redirect messages (RFC 792).
+pub mod redirect_code {
+    pub const NETWORK: u8 = 0;
+    pub const HOST: u8 = 1;
+    pub const TOS_NETWORK: u8 = 2;
+    pub const TOS_HOST: u8 = 3;
+}
+
+/// Code values of time exceeded messages (RFC 792).
+pub mod time_exceeded_code {
+    pub const TTL_EXCEEDED_IN_TRANSIT: u8 = 0;
+    pub const FRAGMENT_REASSEMBLY_TIME_EXCEEDED: u8 = 1;
+}
+
+/// Code values of parameter problem messages (RFC 792 & RFC 1108).
+pub mod parameter_problem_code {
+    pub const POINTER_INDICATES_ERROR: u8 = 0;
+    pub const MISSING_REQUIRED_OPTION: u8 = 1;
+    pub const BAD_LENGTH: u8 = 2;
+}
+
+/// Length of the ICMPv4 header (type, code, checksum & the 4 byte type
+/// specific field).
+pub const HEADER_LEN: usize = 8;
+
+/// Length of the ICMPv4 header of timestamp request & reply messages
+/// (including the three timestamps).
+pub const TIMESTAMP_HEADER_LEN: usize = 20;
+
+/// Length of the ICMPv4 header of address mask request & reply messages
+/// (including the address mask).
+pub const ADDRESS_MASK_HEADER_LEN: usize = 12;
+
+/// Length of an entry (router address & preference level) in a router
+/// advertisement message with the default address entry size of 2.
+pub const ROUTER_ADVERTISEMENT_ENTRY_LEN: usize = 8;
+
+/// Maximum length of the IPv4 packets generated by [`error_response`]
+/// (the datagram size every host has to accept, RFC 1812 section 4.3.2.3).
+/// The quoted original packet is truncated so that this size is not
+/// exceeded.
+pub const ERROR_RESPONSE_MAX_LEN: usize = 576;
+
+/// Identifier & sequence number of echo & timestamp messages.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct IcmpEchoHeader {
+    pub id: u16,
+    pub seq: u16,
+}
+
+impl IcmpEchoHeader {
+    /// Decodes the identifier & sequence number from the type specific
+    /// bytes 5-8 of the header.
+    #[inline]
+    pub fn from_bytes(bytes5to8: [u8;4]) -> IcmpEchoHeader {
+        IcmpEchoHeader{
+            id: u16::from_be_bytes([bytes5to8[0], bytes5to8[1]]),
+            seq: u16::from_be_bytes([bytes5to8[2], bytes5to8[3]]),
+        }
+    }
+
+    /// Returns the type specific bytes 5-8 of the header.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;4] {
+        let id = self.id.to_be_bytes();
+        let seq = self.seq.to_be_bytes();
+        [id[0], id[1], seq[0], seq[1]]
+    }
+}
+
+/// Timestamp request & reply message (RFC 792).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct TimestampMessage {
+    pub id: u16,
+    pub seq: u16,
+    /// Time the sender last touched the message (milliseconds since midnight UT).
+    pub originate_timestamp: u32,
+    /// Time the echoer first touched the message (milliseconds since midnight UT).
+    pub receive_timestamp: u32,
+    /// Time the echoer last touched the message (milliseconds since midnight UT).
+    pub transmit_timestamp: u32,
+}
+
+/// Address mask request & reply message (RFC 950).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct AddressMaskMessage {
+    pub id: u16,
+    pub seq: u16,
+    /// Subnet address mask (set to 0 in requests).
+    pub address_mask: [u8;4],
+}
+
+/// Header fields of a router advertisement message (RFC 1256).
+///
+/// The advertised router addresses follow the header & can be decoded
+/// via [`RouterAdvertisementHeader::entries`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct RouterAdvertisementHeader {
+    /// Number of router addresses advertised in the message.
+    pub num_addrs: u8,
+    /// Number of 32-bit words per router address entry (2 or larger).
+    pub addr_entry_size: u8,
+    /// Maximum number of seconds the router addresses are valid.
+    pub lifetime: u16,
+}
+
+impl RouterAdvertisementHeader {
+    /// Decodes the router address entries from the data following the
+    /// header. Entries not completely contained in `payload` are ignored.
+    pub fn entries<'a>(&self, payload: &'a [u8]) -> impl Iterator<Item = RouterAdvertisementEntry> + 'a {
+        let entry_len = usize::from(self.addr_entry_size).max(2)*4;
+        payload
+            .chunks_exact(entry_len)
+            .take(usize::from(self.num_addrs))
+            .map(|chunk| RouterAdvertisementEntry::from_bytes([
+                chunk[0], chunk[1], chunk[2], chunk[3],
+                chunk[4], chunk[5], chunk[6], chunk[7],
+            ]))
+    }
+}
+
+impl Default for RouterAdvertisementHeader {
+    fn default() -> RouterAdvertisementHeader {
+        RouterAdvertisementHeader{
+            num_addrs: 0,
+            addr_entry_size: 2,
+            lifetime: 0,
+        }
+    }
+}
+
+/// Router address & preference level advertised in a router
+/// advertisement message (RFC 1256).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct RouterAdvertisementEntry {
+    pub router_address: [u8;4],
+    /// Preferability of the router address relative to other addresses
+    /// (higher values are preferred).
+    pub preference_level: i32,
+}
+
+impl RouterAdvertisementEntry {
+    /// Decodes an entry with the default address entry size of 2.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;ROUTER_ADVERTISEMENT_ENTRY_LEN]) -> RouterAdvertisementEntry {
+        RouterAdvertisementEntry{
+            router_address: [bytes[0], bytes[1], bytes[2], bytes[3]],
+            preference_level: i32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        }
+    }
+
+    /// Returns the serialized entry.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;ROUTER_ADVERTISEMENT_ENTRY_LEN] {
+        let a = self.router_address;
+        let p = self.preference_level.to_be_bytes();
+        [a[0], a[1], a[2], a[3], p[0], p[1], p[2], p[3]]
+    }
+}
+
+/// Code of a destination unreachable message (RFC 792, RFC 1122 & RFC 1812).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum DestUnreachableHeader {
+    /// Network unreachable (code 0).
+    Network,
+    /// Host unreachable (code 1).
+    Host,
+    /// Protocol unreachable (code 2).
+    Protocol,
+    /// Port unreachable (code 3).
+    Port,
+    /// Fragmentation needed & don't fragment set (code 4). Contains the
+    /// MTU of the next hop (RFC 1191, 0 if not set by the router).
+    FragmentationNeeded{ next_hop_mtu: u16 },
+    /// Source route failed (code 5).
+    SourceRouteFailed,
+    /// Destination network unknown (code 6).
+    NetworkUnknown,
+    /// Destination host unknown (code 7).
+    HostUnknown,
+    /// Source host isolated (code 8).
+    Isolated,
+    /// Communication with destination network is administratively prohibited (code 9).
+    NetworkProhibited,
+    /// Communication with destination host is administratively prohibited (code 10).
+    HostProhibited,
+    /// Destination network unreachable for type of service (code 11).
+    TosNetwork,
+    /// Destination host unreachable for type of service (code 12).
+    TosHost,
+    /// Communication administratively prohibited (code 13).
+    FilterProhibited,
+    /// Host precedence violation (code 14).
+    HostPrecedenceViolation,
+    /// Precedence cutoff in effect (code 15).
+    PrecedenceCutoff,
+}
+
+impl DestUnreachableHeader {
+    /// Decodes the code & the type specific bytes 5-8 (None for unknown codes).
+    pub fn from_values(code: u8, bytes5to8: [u8;4]) -> Option<DestUnreachableHeader> {
+        use DestUnreachableHeader::*;
+        use dest_unreachable_code::*;
+        Some(match code {
+            NETWORK => Network,
+            HOST => Host,
+            PROTOCOL => Protocol,
+            PORT => Port,
+            FRAGMENTATION_NEEDED => FragmentationNeeded{ next_hop_mtu: u16::from_be_bytes([bytes5to8[2], bytes5to8[3]]) },
+            SOURCE_ROUTE_FAILED => SourceRouteFailed,
+            NETWORK_UNKNOWN => NetworkUnknown,
+            HOST_UNKNOWN => HostUnknown,
+            ISOLATED => Isolated,
+            NETWORK_PROHIBITED => NetworkProhibited,
+            HOST_PROHIBITED => HostProhibited,
+            TOS_NETWORK => TosNetwork,
+            TOS_HOST => TosHost,
+            FILTER_PROHIBITED => FilterProhibited,
+            HOST_PRECEDENCE_VIOLATION => HostPrecedenceViolation,
+            PRECEDENCE_CUTOFF => PrecedenceCutoff,
+            _ => return None,
+        })
+    }
+
+    /// Returns the code of the message.
+    pub fn code_u8(&self) -> u8 {
+        use DestUnreachableHeader::*;
+        use dest_unreachable_code::*;
+        match self {
+            Network => NETWORK,
+            Host => HOST,
+            Protocol => PROTOCOL,
+            Port => PORT,
+            FragmentationNeeded{ .. } => FRAGMENTATION_NEEDED,
+            SourceRouteFailed => SOURCE_ROUTE_FAILED,
+            NetworkUnknown => NETWORK_UNKNOWN,
+            HostUnknown => HOST_UNKNOWN,
+            Isolated => ISOLATED,
+            NetworkProhibited => NETWORK_PROHIBITED,
+            HostProhibited => HOST_PROHIBITED,
+            TosNetwork => TOS_NETWORK,
+            TosHost => TOS_HOST,
+            FilterProhibited => FILTER_PROHIBITED,
+            HostPrecedenceViolation => HOST_PRECEDENCE_VIOLATION,
+            PrecedenceCutoff => PRECEDENCE_CUTOFF,
+        }
+    }
+
+    /// Returns the type specific bytes 5-8 of the header (contains the
+    /// next hop MTU for [`DestUnreachableHeader::FragmentationNeeded`] &
+    /// is zero otherwise).
+    pub fn to_bytes(&self) -> [u8;4] {
+        match self {
+            DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu } => {
+                let mtu = next_hop_mtu.to_be_bytes();
+                [0, 0, mtu[0], mtu[1]]
+            },
+            _ => [0;4],
+        }
+    }
+}
+
+/// Code of a redirect message (RFC 792).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum RedirectCode {
+    /// Redirect datagrams for the network (code 0).
+    Network,
+    /// Redirect datagrams for the host (code 1).
+    Host,
+    /// Redirect datagrams for the type of service & network (code 2).
+    TosNetwork,
+    /// Redirect datagrams for the type of service & host (code 3).
+    TosHost,
+}
+
+impl RedirectCode {
+    /// Converts the code to the enum (None for unknown codes).
+    pub fn from_u8(code: u8) -> Option<RedirectCode> {
+        use RedirectCode::*;
+        use redirect_code::*;
+        match code {
+            NETWORK => Some(Network),
+            HOST => Some(Host),
+            TOS_NETWORK => Some(TosNetwork),
+            TOS_HOST => Some(TosHost),
+            _ => None,
+        }
+    }
+
+    /// Returns the code of the message.
+    #[inline]
+    pub fn code_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Redirect message (RFC 792).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct RedirectHeader {
+    pub code: RedirectCode,
+    /// Address of the gateway traffic should be sent to.
+    pub gateway_internet_address: [u8;4],
+}
+
+/// Code of a time exceeded message (RFC 792).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TimeExceededCode {
+    /// Time to live exceeded in transit (code 0).
+    TtlExceededInTransit,
+    /// Fragment reassembly time exceeded (code 1).
+    FragmentReassemblyTimeExceeded,
+}
+
+impl TimeExceededCode {
+    /// Converts the code to the enum (None for unknown codes).
+    pub fn from_u8(code: u8) -> Option<TimeExceededCode> {
+        use TimeExceededCode::*;
+        use time_exceeded_code::*;
+        match code {
+            TTL_EXCEEDED_IN_TRANSIT => Some(TtlExceededInTransit),
+            FRAGMENT_REASSEMBLY_TIME_EXCEEDED => Some(FragmentReassemblyTimeExceeded),
+            _ => None,
+        }
+    }
+
+    /// Returns the code of the message.
+    #[inline]
+    pub fn code_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Code & pointer of a parameter problem message (RFC 792 & RFC 1108).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ParameterProblemHeader {
+    /// The pointer identifies the octet where the error was detected (code 0).
+    PointerIndicatesError(u8),
+    /// A required option is missing (code 1).
+    MissingRequiredOption,
+    /// Bad length (code 2).
+    BadLength,
+}
+
+impl ParameterProblemHeader {
+    /// Decodes the code & the type specific bytes 5-8 (None for unknown codes).
+    pub fn from_values(code: u8, bytes5to8: [u8;4]) -> Option<ParameterProblemHeader> {
+        use ParameterProblemHeader::*;
+        use parameter_problem_code::*;
+        match code {
+            POINTER_INDICATES_ERROR => Some(PointerIndicatesError(bytes5to8[0])),
+            MISSING_REQUIRED_OPTION => Some(MissingRequiredOption),
+            BAD_LENGTH => Some(BadLength),
+            _ => None,
+        }
+    }
+
+    /// Returns the code of the message.
+    pub fn code_u8(&self) -> u8 {
+        use ParameterProblemHeader::*;
+        use parameter_problem_code::*;
+        match self {
+            PointerIndicatesError(_) => POINTER_INDICATES_ERROR,
+            MissingRequiredOption => MISSING_REQUIRED_OPTION,
+            BadLength => BAD_LENGTH,
+        }
+    }
+
+    /// Returns the type specific bytes 5-8 of the header (contains the
+    /// pointer for [`ParameterProblemHeader::PointerIndicatesError`] & is
+    /// zero otherwise).
+    pub fn to_bytes(&self) -> [u8;4] {
+        match self {
+            ParameterProblemHeader::PointerIndicatesError(pointer) => [*pointer, 0, 0, 0],
+            _ => [0;4],
+        }
+    }
+}
+
+/// ICMPv4 message type including the code & the type specific header fields.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv4::{DestUnreachableHeader, Icmp4Type};
+///
+/// let message = [3, 4, 0, 0, 0, 0, 0x05, 0xdc, 0x45];
+/// let (icmp_type, rest) = Icmp4Type::from_bytes(&message).unwrap();
+/// assert_eq!(
+///     Icmp4Type::DestinationUnreachable(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 1500 }),
+///     icmp_type
+/// );
+/// assert_eq!(&[0x45], rest);
+/// assert_eq!(&message[..8], &icmp_type.to_bytes()[..]);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Icmp4Type {
+    EchoReply(IcmpEchoHeader),
+    DestinationUnreachable(DestUnreachableHeader),
+    Redirect(RedirectHeader),
+    EchoRequest(IcmpEchoHeader),
+    TimeExceeded(TimeExceededCode),
+    ParameterProblem(ParameterProblemHeader),
+    TimestampRequest(TimestampMessage),
+    TimestampReply(TimestampMessage),
+    AddressMaskRequest(AddressMaskMessage),
+    AddressMaskReply(AddressMaskMessage),
+    RouterAdvertisement(RouterAdvertisementHeader),
+    RouterSolicitation,
+    /// Extended echo request (RFC 8335), followed by the extension
+    /// structure containing the interface identification object.
+    ExtendedEchoRequest(icmp_extension::ExtendedEchoRequestHeader),
+    ExtendedEchoReply(icmp_extension::ExtendedEchoReplyHeader),
+    /// Message with a type or code that is not decoded.
+    Unknown{
+        type_u8: u8,
+        code_u8: u8,
+        /// Type specific bytes 5-8 of the header.
+        bytes5to8: [u8;4],
+    },
+}
+
+impl Icmp4Type {
+    /// Decodes the header at the start of an ICMPv4 message & returns the
+    /// type & the data after the header (the checksum is not verified).
+    ///
+    /// Timestamp request & reply messages are only decoded as such if the
+    /// message contains the three timestamps & address mask request & reply
+    /// messages only if the message contains the address mask (otherwise
+    /// they are returned as [`Icmp4Type::Unknown`]). Router advertisements
+    /// with an address entry size smaller than 2 are also returned as
+    /// [`Icmp4Type::Unknown`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Icmp4Type, &[u8]), ReadError> {
+        use Icmp4Type::*;
+        use icmpv4_type::*;
+
+        if bytes.len() < HEADER_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(HEADER_LEN));
+        }
+        let (type_u8, code_u8) = (bytes[0], bytes[1]);
+        let bytes5to8 = [bytes[4], bytes[5], bytes[6], bytes[7]];
+        let echo = || IcmpEchoHeader::from_bytes(bytes5to8);
+        let unknown = (Unknown{ type_u8, code_u8, bytes5to8 }, &bytes[HEADER_LEN..]);
+
+        let result = match (type_u8, code_u8) {
+            (ECHO_REPLY, 0) => EchoReply(echo()),
+            (ECHO_REQUEST, 0) => EchoRequest(echo()),
+            (DESTINATION_UNREACHABLE, _) => match DestUnreachableHeader::from_values(code_u8, bytes5to8) {
+                Some(value) => DestinationUnreachable(value),
+                None => return Ok(unknown),
+            },
+            (REDIRECT, _) => match RedirectCode::from_u8(code_u8) {
+                Some(code) => Redirect(RedirectHeader{
+                    code,
+                    gateway_internet_address: bytes5to8,
+                }),
+                None => return Ok(unknown),
+            },
+            (TIME_EXCEEDED, _) => match TimeExceededCode::from_u8(code_u8) {
+                Some(code) => TimeExceeded(code),
+                None => return Ok(unknown),
+            },
+            (PARAMETER_PROBLEM, _) => match ParameterProblemHeader::from_values(code_u8, bytes5to8) {
+                Some(value) => ParameterProblem(value),
+                None => return Ok(unknown),
+            },
+            (TIMESTAMP_REQUEST, 0) | (TIMESTAMP_REPLY, 0) if bytes.len() >= TIMESTAMP_HEADER_LEN => {
+                let timestamp = |offset: usize| u32::from_be_bytes([
+                    bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]
+                ]);
+                let echo = echo();
+                let message = TimestampMessage{
+                    id: echo.id,
+                    seq: echo.seq,
+                    originate_timestamp: timestamp(8),
+                    receive_timestamp: timestamp(12),
+                    transmit_timestamp: timestamp(16),
+                };
+                let value = if TIMESTAMP_REQUEST == type_u8 {
+                    TimestampRequest(message)
+                } else {
+                    TimestampReply(message)
+                };
+                return Ok((value, &bytes[TIMESTAMP_HEADER_LEN..]));
+            },
+            (ADDRESS_MASK_REQUEST, 0) | (ADDRESS_MASK_REPLY, 0) if bytes.len() >= ADDRESS_MASK_HEADER_LEN => {
+                let echo = echo();
+                let message = AddressMaskMessage{
+                    id: echo.id,
+                    seq: echo.seq,
+                    address_mask: [bytes[8], bytes[9], bytes[10], bytes[11]],
+                };
+                let value = if ADDRESS_MASK_REQUEST == type_u8 {
+                    AddressMaskRequest(message)
+                } else {
+                    AddressMaskReply(message)
+                };
+                return Ok((value, &bytes[ADDRESS_MASK_HEADER_LEN..]));
+            },
+            (ROUTER_ADVERTISEMENT, 0) if bytes5to8[1] >= 2 => RouterAdvertisement(RouterAdvertisementHeader{
+                num_addrs: bytes5to8[0],
+                addr_entry_size: bytes5to8[1],
+                lifetime: u16::from_be_bytes([bytes5to8[2], bytes5to8[3]]),
+            }),
+            (ROUTER_SOLICITATION, 0) => RouterSolicitation,
+            (EXTENDED_ECHO_REQUEST, 0) => ExtendedEchoRequest(icmp_extension::ExtendedEchoRequestHeader::from_bytes(bytes5to8)),
+            (EXTENDED_ECHO_REPLY, _) => match icmp_extension::ExtendedEchoReplyHeader::from_values(code_u8, bytes5to8) {
+                Some(value) => ExtendedEchoReply(value),
+                None => return Ok(unknown),
+            },
+            _ => return Ok(unknown),
+        };
+        Ok((result, &bytes[HEADER_LEN..]))
+    }
+
+    /// Returns the type value of the message.
+    pub fn type_u8(&self) -> u8 {
+        use Icmp4Type::*;
+        use icmpv4_type::*;
+        match self {
+            EchoReply(_) => ECHO_REPLY,
+            DestinationUnreachable(_) => DESTINATION_UNREACHABLE,
+            Redirect(_) => REDIRECT,
+            EchoRequest(_) => ECHO_REQUEST,
+            TimeExceeded(_) => TIME_EXCEEDED,
+            ParameterProblem(_) => PARAMETER_PROBLEM,
+            TimestampRequest(_) => TIMESTAMP_REQUEST,
+            TimestampReply(_) => TIMESTAMP_REPLY,
+            AddressMaskRequest(_) => ADDRESS_MASK_REQUEST,
+            AddressMaskReply(_) => ADDRESS_MASK_REPLY,
+            RouterAdvertisement(_) => ROUTER_ADVERTISEMENT,
+            RouterSolicitation => ROUTER_SOLICITATION,
+            ExtendedEchoRequest(_) => EXTENDED_ECHO_REQUEST,
+            ExtendedEchoReply(_) => EXTENDED_ECHO_REPLY,
+            Unknown{ type_u8, .. } => *type_u8,
+        }
+    }
+
+    /// Returns the code value of the message.
+    pub fn code_u8(&self) -> u8 {
+        use Icmp4Type::*;
+        match self {
+            EchoReply(_) | EchoRequest(_) |
+            TimestampRequest(_) | TimestampReply(_) |
+            AddressMaskRequest(_) | AddressMaskReply(_) |
+            RouterAdvertisement(_) | RouterSolicitation |
+            ExtendedEchoRequest(_) => 0,
+            ExtendedEchoReply(value) => value.code.code_u8(),
+            DestinationUnreachable(value) => value.code_u8(),
+            Redirect(value) => value.code.code_u8(),
+            TimeExceeded(value) => value.code_u8(),
+            ParameterProblem(value) => value.code_u8(),
+            Unknown{ code_u8, .. } => *code_u8,
+        }
+    }
+
+    /// Length of the serialized header in bytes (20 for timestamp
+    /// messages, 12 for address mask messages, 8 for all other messages).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        use Icmp4Type::*;
+        match self {
+            TimestampRequest(_) | TimestampReply(_) => TIMESTAMP_HEADER_LEN,
+            AddressMaskRequest(_) | AddressMaskReply(_) => ADDRESS_MASK_HEADER_LEN,
+            _ => HEADER_LEN,
+        }
+    }
+
+    /// Returns the serialized header with the checksum field set to 0
+    /// (see [`Icmp4Type::calc_checksum`]).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        use Icmp4Type::*;
+
+        let bytes5to8 = match self {
+            EchoReply(value) | EchoRequest(value) => value.to_bytes(),
+            DestinationUnreachable(value) => value.to_bytes(),
+            TimeExceeded(_) | RouterSolicitation => [0;4],
+            Redirect(value) => value.gateway_internet_address,
+            ParameterProblem(value) => value.to_bytes(),
+            TimestampRequest(value) | TimestampReply(value) => IcmpEchoHeader{ id: value.id, seq: value.seq }.to_bytes(),
+            AddressMaskRequest(value) | AddressMaskReply(value) => IcmpEchoHeader{ id: value.id, seq: value.seq }.to_bytes(),
+            RouterAdvertisement(value) => {
+                let lifetime = value.lifetime.to_be_bytes();
+                [value.num_addrs, value.addr_entry_size, lifetime[0], lifetime[1]]
+            },
+            ExtendedEchoRequest(value) => value.to_bytes(),
+            ExtendedEchoReply(value) => value.to_bytes(),
+            Unknown{ bytes5to8, .. } => *bytes5to8,
+        };
+
+        let mut result = Vec::with_capacity(self.header_len());
+        result.extend_from_slice(&[self.type_u8(), self.code_u8(), 0, 0]);
+        result.extend_from_slice(&bytes5to8);
+        if let TimestampRequest(value) | TimestampReply(value) = self {
+            result.extend_from_slice(&value.originate_timestamp.to_be_bytes());
+            result.extend_from_slice(&value.receive_timestamp.to_be_bytes());
+            result.extend_from_slice(&value.transmit_timestamp.to_be_bytes());
+        }
+        if let AddressMaskRequest(value) | AddressMaskReply(value) = self {
+            result.extend_from_slice(&value.address_mask);
+        }
+        result
+    }
+
+    /// Calculates the checksum of the message consisting of the header &
+    /// the given payload.
+    pub fn calc_checksum(&self, payload: &[u8]) -> u16 {
+        checksum::Sum16BitWords::new()
+            .add_slice(&self.to_bytes())
+            .add_slice(payload)
+            .ones_complement()
+            .to_be()
+    }
+}
+
+/// Returns true if the checksum field of the ICMPv4 message (starting with
+/// the ICMPv4 header) is valid.
+///
+/// Messages too short to contain the ICMPv4 header are invalid.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv4::{self, Icmp4Type, IcmpEchoHeader};
+///
+/// let value = Icmp4Type::EchoRequest(IcmpEchoHeader{ id: 1, seq: 2 });
+/// let mut message = value.to_bytes();
+/// message.extend_from_slice(&[1,2,3,4]);
+/// assert!(!icmpv4::is_checksum_valid(&message));
+///
+/// let checksum = value.calc_checksum(&[1,2,3,4]);
+/// message[2..4].copy_from_slice(&checksum.to_be_bytes());
+/// assert!(icmpv4::is_checksum_valid(&message));
+/// ```
+pub fn is_checksum_valid(message: &[u8]) -> bool {
+    // the checksum over the complete message (including the checksum) is 0 if valid
+    message.len() >= HEADER_LEN &&
+    0 == checksum::Sum16BitWords::new().add_slice(message).ones_complement()
+}
+
+/// Splits the data after the header of a destination unreachable, time
+/// exceeded or parameter problem message (starting with the ICMPv4 header)
+/// into the original datagram & the ICMP extension structure (RFC 4884)
+/// based on the length field in the header.
+///
+/// For messages without a length (or of other types) the complete data
+/// after the header is returned as original datagram.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmp_extension::{IcmpExtensionObject, MplsLabelStackEntry};
+/// use etherparse::icmpv4::{append_error_extensions, icmpv4_type, split_error_extensions};
+///
+/// let entry = MplsLabelStackEntry{ label: 16, traffic_class: 0, bottom_of_stack: true, ttl: 1 };
+///
+/// // time exceeded message with a MPLS label stack object
+/// let mut message = vec![icmpv4_type::TIME_EXCEEDED, 0, 0, 0, 0, 0, 0, 0];
+/// message.extend_from_slice(&[0x45; 28]);
+/// append_error_extensions(&mut message, &[IcmpExtensionObject::MplsLabelStack(vec![entry])]).unwrap();
+///
+/// let (original, extensions) = split_error_extensions(&message).unwrap();
+/// assert_eq!(128, original.len());
+/// let objects = extensions.unwrap().objects().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec![IcmpExtensionObject::MplsLabelStack(vec![entry])], objects);
+/// ```
+pub fn split_error_extensions(message: &[u8]) -> Result<(&[u8], Option<icmp_extension::IcmpExtensionsSlice<'_>>), icmp_extension::IcmpExtensionError> {
+    use icmpv4_type::*;
+    match message.first() {
+        Some(&DESTINATION_UNREACHABLE) | Some(&TIME_EXCEEDED) | Some(&PARAMETER_PROBLEM) => {
+            icmp_extension::split_error_extensions(message, 5, 4)
+        },
+        _ if message.len() < HEADER_LEN => Err(icmp_extension::IcmpExtensionError::UnexpectedEnd(HEADER_LEN)),
+        _ => Ok((&message[HEADER_LEN..], None)),
+    }
+}
+
+/// Appends an ICMP extension structure (RFC 4884) with the given objects
+/// to a destination unreachable, time exceeded or parameter problem
+/// message (starting with the ICMPv4 header & containing the original
+/// datagram).
+///
+/// The original datagram is padded with zeros to at least 128 bytes & a
+/// multiple of 4 bytes & the length field in the header is set. The
+/// ICMPv4 checksum has to be calculated afterwards.
+pub fn append_error_extensions(message: &mut Vec<u8>, objects: &[icmp_extension::IcmpExtensionObject]) -> Result<(), icmp_extension::IcmpExtensionError> {
+    icmp_extension::append_error_extensions(message, 5, 4, objects)
+}
+
+/// Creates a "Fragmentation Needed" destination unreachable message (RFC
+/// 1191) as response to the given packet & returns the complete IPv4
+/// packet (IPv4 header & ICMPv4 message), the IPv4 equivalent of
+/// [`crate::icmpv6::packet_too_big`].
+///
+/// See [`error_response`] for the cases in which no response is created.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ipv4([192,168,1,2], [10,0,0,1], 64)
+/// #    .udp(1234, 5678);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(1400));
+/// # builder.write(&mut packet, &[0;1400]).unwrap();
+/// use etherparse::{icmpv4, SlicedPacket, Ipv4Header};
+/// use etherparse::icmpv4::{DestUnreachableHeader, Icmp4Type};
+///
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let response = icmpv4::fragmentation_needed([192,168,1,1], 64, 1300, &sliced).unwrap();
+/// assert_eq!(icmpv4::ERROR_RESPONSE_MAX_LEN, response.len());
+///
+/// let (ip, message) = Ipv4Header::from_slice(&response).unwrap();
+/// assert_eq!(ip.destination, [192,168,1,2]);
+/// let (icmp_type, quoted) = Icmp4Type::from_bytes(message).unwrap();
+/// assert_eq!(
+///     Icmp4Type::DestinationUnreachable(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 1300 }),
+///     icmp_type
+/// );
+/// assert_eq!(&packet[..quoted.len()], quoted);
+/// ```
+pub fn fragmentation_needed(source: [u8;4], time_to_live: u8, next_hop_mtu: u16, original: &SlicedPacket) -> Option<Vec<u8>> {
+    error_response(
+        source,
+        time_to_live,
+        Icmp4Type::DestinationUnreachable(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu }),
+        original
+    )
+}
+
+/// Creates an ICMPv4 error message with the given header as response to
+/// the given packet & returns the complete IPv4 packet (IPv4 header &
+/// ICMPv4 message with a valid checksum).
+///
+/// The response is sent from `source` to the source of the original packet
+/// & contains as much of the original packet (starting with the IPv4
+/// header) as possible without exceeding [`ERROR_RESPONSE_MAX_LEN`] bytes.
+///
+/// None is returned if no response must be sent (RFC 1122 section 3.2.2 &
+/// RFC 1812 section 4.3.2.7):
+///
+/// * the original packet is not an IPv4 packet
+/// * the original packet is an ICMPv4 error message
+/// * the original packet is not the first fragment of a datagram
+/// * the original packet was sent to a broadcast or multicast address (on
+///   the link layer or the limited broadcast or a multicast ip address)
+/// * the source address of the original packet does not identify a single
+///   host (unspecified, loopback, multicast, broadcast & reserved addresses)
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ipv4([192,168,1,2], [10,0,0,1], 1)
+/// #    .udp(1234, 5678);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{icmpv4, SlicedPacket, Ipv4Header};
+/// use etherparse::icmpv4::{Icmp4Type, TimeExceededCode};
+///
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let response = icmpv4::error_response(
+///     [192,168,1,1],
+///     64,
+///     Icmp4Type::TimeExceeded(TimeExceededCode::TtlExceededInTransit),
+///     &sliced
+/// ).unwrap();
+///
+/// let (ip, message) = Ipv4Header::from_slice(&response).unwrap();
+/// assert_eq!(ip.destination, [192,168,1,2]);
+/// assert_eq!(&[icmpv4::icmpv4_type::TIME_EXCEEDED, 0], &message[..2]);
+/// assert_eq!(&packet[..], &message[8..]);
+/// ```
+pub fn error_response(source: [u8;4], time_to_live: u8, icmp_type: Icmp4Type, original: &SlicedPacket) -> Option<Vec<u8>> {
+    let (original_source, original_destination) = match &original.ip {
+        Some(InternetSlice::Ipv4(header, _)) => {
+            // only the first fragment of a datagram gets a response
+            if 0 != header.fragments_offset() {
+                return None;
+            }
+            (header.source(), header.destination())
+        },
+        _ => return None,
+    };
+    // unspecified, loopback, multicast, reserved & broadcast source addresses
+    if original_source == [0;4] || 127 == original_source[0] || original_source[0] >= 224 {
+        return None;
+    }
+    // limited broadcast & multicast destinations
+    if original_destination[0] >= 224 && original_destination[0] < 240 || original_destination == [0xff;4] {
+        return None;
+    }
+    // link layer broadcast & multicast destinations
+    if let Some(LinkSlice::Ethernet2(ethernet)) = &original.link {
+        if 0 != ethernet.destination()[0] & 1 {
+            return None;
+        }
+    }
+    if is_icmpv4_error(original) {
+        return None;
+    }
+
+    // the transport & tunnel headers are in the order they appear in the packet
+    let [_, _, _, ip_header, ip_extensions, upper_first, upper_second, _] = original.header_bytes();
+    let max_quoted_len = ERROR_RESPONSE_MAX_LEN - Ipv4Header::SERIALIZED_SIZE - icmp_type.header_len();
+    let mut quoted = Vec::with_capacity(max_quoted_len);
+    for part in &[ip_header, ip_extensions, upper_first, upper_second, original.payload, original.trailer] {
+        let len = part.len().min(max_quoted_len - quoted.len());
+        quoted.extend_from_slice(&part[..len]);
+    }
+
+    let mut message = icmp_type.to_bytes();
+    message[2..4].copy_from_slice(&icmp_type.calc_checksum(&quoted).to_be_bytes());
+    message.extend_from_slice(&quoted);
+
+    let ip = Ipv4Header::new(message.len() as u16, time_to_live, IpNumber::Icmp, source, original_source);
+    let mut result = Vec::with_capacity(Ipv4Header::SERIALIZED_SIZE + message.len());
+    // the header values are within their ranges (payload length is limited by the maximum response length)
+    ip.write(&mut result).unwrap();
+    result.extend_from_slice(&message);
+    Some(result)
+}
+
+/// Returns true if the packet is an ICMPv4 error message (or an ICMPv4
+/// message too short to determine the type).
+fn is_icmpv4_error(packet: &SlicedPacket) -> bool {
+    use icmpv4_type::*;
+    match &packet.transport {
+        Some(TransportSlice::Unknown(ip_number::ICMP)) => {
+            matches!(
+                packet.payload.first(),
+                Some(&DESTINATION_UNREACHABLE) | Some(&SOURCE_QUENCH) | Some(&REDIRECT) |
+                Some(&TIME_EXCEEDED) | Some(&PARAMETER_PROBLEM) | None
+            )
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,322 @@
+use super::super::*;
+use super::icmpv6_type;
+
+/// MLDv1 message (query, report or done, RFC 2710 section 3).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct MldV1Message {
+    /// Maximum time in milliseconds a listener can wait before responding
+    /// to a query (0 in reports & done messages).
+    pub max_response_delay: u16,
+    /// Multicast address of the message (unspecified in general queries).
+    pub multicast_address: [u8;16],
+}
+
+impl MldV1Message {
+    /// Length of an MLDv1 message including the ICMPv6 header.
+    pub const LEN: usize = 24;
+}
+
+/// MLDv2 query (RFC 3810 section 5.1).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct MldV2Query {
+    /// Maximum response code (see [`MldV2Query::max_response_delay`] for
+    /// the decoded value in milliseconds).
+    pub max_response_code: u16,
+    /// Queried multicast address (unspecified in general queries).
+    pub multicast_address: [u8;16],
+    /// Suppress router-side processing ("S" flag).
+    pub suppress_router_side_processing: bool,
+    /// Querier's robustness variable (QRV, 3 bits).
+    pub querier_robustness_variable: u8,
+    /// Querier's query interval code (QQIC, see
+    /// [`MldV2Query::query_interval`] for the decoded value in seconds).
+    pub querier_query_interval_code: u8,
+    /// Queried sources (only set in multicast address & source specific queries).
+    pub sources: Vec<[u8;16]>,
+}
+
+impl MldV2Query {
+    /// Length of an MLDv2 query without sources including the ICMPv6 header.
+    pub const MIN_LEN: usize = 28;
+
+    /// Maximum robustness variable value that can be represented.
+    pub const MAX_QUERIER_ROBUSTNESS_VARIABLE: u8 = 0b111;
+
+    /// Decoded maximum response delay in milliseconds (RFC 3810 section 5.1.3).
+    pub fn max_response_delay(&self) -> u32 {
+        let code = self.max_response_code;
+        if code < 0x8000 {
+            u32::from(code)
+        } else {
+            let mantissa = u32::from(code & 0xfff) | 0x1000;
+            let exponent = u32::from((code >> 12) & 0x7);
+            mantissa << (exponent + 3)
+        }
+    }
+
+    /// Decoded query interval in seconds (RFC 3810 section 5.1.9).
+    pub fn query_interval(&self) -> u32 {
+        let code = self.querier_query_interval_code;
+        if code < 128 {
+            u32::from(code)
+        } else {
+            let mantissa = u32::from(code & 0xf) | 0x10;
+            let exponent = u32::from((code >> 4) & 0x7);
+            mantissa << (exponent + 3)
+        }
+    }
+
+    /// Length of the query including the ICMPv6 header & the sources.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        MldV2Query::MIN_LEN + self.sources.len()*16
+    }
+}
+
+/// Multicast address record of an MLDv2 report (RFC 3810 section 5.2.4).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct MldAddressRecord {
+    /// Type of the record (see [`crate::multicast::group_record_type`]).
+    pub record_type: u8,
+    /// Multicast address the record applies to.
+    pub multicast_address: [u8;16],
+    /// Sources of the record.
+    pub sources: Vec<[u8;16]>,
+    /// Auxiliary data of the record (length must be a multiple of 4).
+    pub aux_data: Vec<u8>,
+}
+
+impl MldAddressRecord {
+    /// Length of a record without sources & auxiliary data.
+    pub const MIN_LEN: usize = 20;
+
+    /// Length of the serialized record.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        MldAddressRecord::MIN_LEN + self.sources.len()*16 + self.aux_data.len()
+    }
+}
+
+/// Multicast listener discovery message (ICMPv6 types 130-132 & 143).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv6::{MldAddressRecord, MldMessage};
+/// use etherparse::multicast::group_record_type;
+///
+/// let report = MldMessage::ReportV2(vec![MldAddressRecord{
+///     record_type: group_record_type::CHANGE_TO_EXCLUDE_MODE,
+///     multicast_address: [0xff,0x02,0,0,0,0,0,0,0,0,0,0,0,0,0,0xfb],
+///     ..Default::default()
+/// }]);
+/// let bytes = report.to_bytes().unwrap();
+/// assert_eq!(report.header_len(), bytes.len());
+/// assert_eq!(Some(report), MldMessage::from_slice(&bytes).unwrap());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MldMessage {
+    /// MLDv1 query (a query with less then 28 bytes).
+    Query(MldV1Message),
+    /// MLDv2 query.
+    QueryV2(MldV2Query),
+    /// MLDv1 report.
+    Report(MldV1Message),
+    /// MLDv1 done message.
+    Done(MldV1Message),
+    /// MLDv2 report containing the multicast address records.
+    ReportV2(Vec<MldAddressRecord>),
+}
+
+impl MldMessage {
+    /// Length of the ICMPv6 header & the record count of an MLDv2 report.
+    pub const REPORT_V2_MIN_LEN: usize = 8;
+
+    /// Decodes the MLD message at the start of the slice (starting with the
+    /// ICMPv6 header, the checksum is not verified).
+    ///
+    /// `Ok(None)` is returned if the ICMPv6 type is not an MLD message type.
+    /// Queries with a length of at least 28 bytes are decoded as MLDv2
+    /// queries (RFC 3810 section 8.1).
+    pub fn from_slice(message: &[u8]) -> Result<Option<MldMessage>, ReadError> {
+        use crate::ReadError::UnexpectedEndOfSlice;
+        use icmpv6_type::*;
+
+        let icmp_type = match message.first() {
+            Some(value) => *value,
+            None => return Err(UnexpectedEndOfSlice(MldMessage::REPORT_V2_MIN_LEN)),
+        };
+        match icmp_type {
+            MULTICAST_LISTENER_QUERY |
+            MULTICAST_LISTENER_REPORT |
+            MULTICAST_LISTENER_DONE => {
+                if message.len() < MldV1Message::LEN {
+                    return Err(UnexpectedEndOfSlice(MldV1Message::LEN));
+                }
+                let max_response_code = u16::from_be_bytes([message[4], message[5]]);
+                let multicast_address = read_address(&message[8..]);
+                if MULTICAST_LISTENER_QUERY == icmp_type && message.len() >= MldV2Query::MIN_LEN {
+                    let count = usize::from(u16::from_be_bytes([message[26], message[27]]));
+                    let end = MldV2Query::MIN_LEN + count*16;
+                    if message.len() < end {
+                        return Err(UnexpectedEndOfSlice(end));
+                    }
+                    return Ok(Some(MldMessage::QueryV2(MldV2Query{
+                        max_response_code,
+                        multicast_address,
+                        suppress_router_side_processing: 0 != message[24] & 0b1000,
+                        querier_robustness_variable: message[24] & 0b111,
+                        querier_query_interval_code: message[25],
+                        sources: message[MldV2Query::MIN_LEN..end]
+                            .chunks_exact(16)
+                            .map(read_address)
+                            .collect(),
+                    })));
+                }
+                let value = MldV1Message{
+                    max_response_delay: max_response_code,
+                    multicast_address,
+                };
+                Ok(Some(match icmp_type {
+                    MULTICAST_LISTENER_QUERY => MldMessage::Query(value),
+                    MULTICAST_LISTENER_REPORT => MldMessage::Report(value),
+                    _ => MldMessage::Done(value),
+                }))
+            },
+            V2_MULTICAST_LISTENER_REPORT => {
+                if message.len() < MldMessage::REPORT_V2_MIN_LEN {
+                    return Err(UnexpectedEndOfSlice(MldMessage::REPORT_V2_MIN_LEN));
+                }
+                let count = usize::from(u16::from_be_bytes([message[6], message[7]]));
+                let mut offset = MldMessage::REPORT_V2_MIN_LEN;
+                let mut records = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let rest = &message[offset..];
+                    if rest.len() < MldAddressRecord::MIN_LEN {
+                        return Err(UnexpectedEndOfSlice(offset + MldAddressRecord::MIN_LEN));
+                    }
+                    let aux_len = usize::from(rest[1])*4;
+                    let source_count = usize::from(u16::from_be_bytes([rest[2], rest[3]]));
+                    let sources_end = MldAddressRecord::MIN_LEN + source_count*16;
+                    let len = sources_end + aux_len;
+                    if rest.len() < len {
+                        return Err(UnexpectedEndOfSlice(offset + len));
+                    }
+                    records.push(MldAddressRecord{
+                        record_type: rest[0],
+                        multicast_address: read_address(&rest[4..]),
+                        sources: rest[MldAddressRecord::MIN_LEN..sources_end]
+                            .chunks_exact(16)
+                            .map(read_address)
+                            .collect(),
+                        aux_data: rest[sources_end..len].to_vec(),
+                    });
+                    offset += len;
+                }
+                Ok(Some(MldMessage::ReportV2(records)))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// ICMPv6 type value of the message.
+    pub fn icmp_type(&self) -> u8 {
+        use icmpv6_type::*;
+        match self {
+            MldMessage::Query(_) | MldMessage::QueryV2(_) => MULTICAST_LISTENER_QUERY,
+            MldMessage::Report(_) => MULTICAST_LISTENER_REPORT,
+            MldMessage::Done(_) => MULTICAST_LISTENER_DONE,
+            MldMessage::ReportV2(_) => V2_MULTICAST_LISTENER_REPORT,
+        }
+    }
+
+    /// Length of the serialized message including the ICMPv6 header.
+    pub fn header_len(&self) -> usize {
+        match self {
+            MldMessage::Query(_) | MldMessage::Report(_) | MldMessage::Done(_) => MldV1Message::LEN,
+            MldMessage::QueryV2(value) => value.header_len(),
+            MldMessage::ReportV2(records) => {
+                records.iter().fold(MldMessage::REPORT_V2_MIN_LEN, |sum, record| sum + record.header_len())
+            },
+        }
+    }
+
+    /// Writes the message (including the ICMPv6 header with the checksum
+    /// set to 0) to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized message including the ICMPv6 header with the
+    /// checksum field set to 0 (the checksum can be calculated via
+    /// [`super::checksum`]).
+    ///
+    /// Returns a [`ValueError::MulticastRecordLengthBad`] error if a source
+    /// list, the record list or auxiliary data can not be represented.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        use crate::ValueError::MulticastRecordLengthBad;
+
+        let mut result = Vec::with_capacity(self.header_len());
+        result.extend_from_slice(&[self.icmp_type(), 0, 0, 0]);
+        match self {
+            MldMessage::Query(value) | MldMessage::Report(value) | MldMessage::Done(value) => {
+                result.extend_from_slice(&value.max_response_delay.to_be_bytes());
+                result.extend_from_slice(&[0, 0]);
+                result.extend_from_slice(&value.multicast_address);
+            },
+            MldMessage::QueryV2(value) => {
+                max_check_u8(
+                    value.querier_robustness_variable,
+                    MldV2Query::MAX_QUERIER_ROBUSTNESS_VARIABLE,
+                    ErrorField::MldQuerierRobustnessVariable
+                )?;
+                if value.sources.len() > usize::from(u16::MAX) {
+                    return Err(MulticastRecordLengthBad);
+                }
+                result.extend_from_slice(&value.max_response_code.to_be_bytes());
+                result.extend_from_slice(&[0, 0]);
+                result.extend_from_slice(&value.multicast_address);
+                result.push(
+                    if value.suppress_router_side_processing { 0b1000 } else { 0 } |
+                    value.querier_robustness_variable
+                );
+                result.push(value.querier_query_interval_code);
+                result.extend_from_slice(&(value.sources.len() as u16).to_be_bytes());
+                for source in &value.sources {
+                    result.extend_from_slice(source);
+                }
+            },
+            MldMessage::ReportV2(records) => {
+                if records.len() > usize::from(u16::MAX) {
+                    return Err(MulticastRecordLengthBad);
+                }
+                result.extend_from_slice(&[0, 0]);
+                result.extend_from_slice(&(records.len() as u16).to_be_bytes());
+                for record in records {
+                    if record.sources.len() > usize::from(u16::MAX) ||
+                       0 != record.aux_data.len() % 4 ||
+                       record.aux_data.len() > 0xff*4
+                    {
+                        return Err(MulticastRecordLengthBad);
+                    }
+                    result.push(record.record_type);
+                    result.push((record.aux_data.len() / 4) as u8);
+                    result.extend_from_slice(&(record.sources.len() as u16).to_be_bytes());
+                    result.extend_from_slice(&record.multicast_address);
+                    for source in &record.sources {
+                        result.extend_from_slice(source);
+                    }
+                    result.extend_from_slice(&record.aux_data);
+                }
+            },
+        }
+        Ok(result)
+    }
+}
+
+fn read_address(data: &[u8]) -> [u8;16] {
+    let mut result = [0u8;16];
+    result.copy_from_slice(&data[..16]);
+    result
+}
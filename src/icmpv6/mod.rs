@@ -0,0 +1,366 @@
+use super::*;
+
+mod mld;
+pub use self::mld::*;
+
+mod ndp_message;
+pub use self::ndp_message::*;
+
+mod ndp_option;
+pub use self::ndp_option::*;
+
+mod node_information;
+pub use self::node_information::*;
+
+mod router_renumbering;
+pub use self::router_renumbering::*;
+
+/// Minimum MTU every IPv6 link has to support (RFC 8200). ICMPv6 error
+/// messages are truncated so that they do not exceed this size.
+pub const IPV6_MIN_MTU: usize = 1280;
+
+/// Length of the ICMPv6 header of error messages (type, code, checksum &
+/// the 4 byte type specific field).
+pub const ERROR_HEADER_LEN: usize = 8;
+
+/// ICMPv6 type values (RFC 4443, RFC 4861, RFC 2710, RFC 2894, RFC 3810, RFC 4620 & RFC 8335).
+pub mod icmpv6_type {
+    pub const DESTINATION_UNREACHABLE: u8 = 1;
+    pub const PACKET_TOO_BIG: u8 = 2;
+    pub const TIME_EXCEEDED: u8 = 3;
+    pub const PARAMETER_PROBLEM: u8 = 4;
+    pub const ECHO_REQUEST: u8 = 128;
+    pub const ECHO_REPLY: u8 = 129;
+    pub const MULTICAST_LISTENER_QUERY: u8 = 130;
+    pub const MULTICAST_LISTENER_REPORT: u8 = 131;
+    pub const MULTICAST_LISTENER_DONE: u8 = 132;
+    pub const ROUTER_SOLICITATION: u8 = 133;
+    pub const ROUTER_ADVERTISEMENT: u8 = 134;
+    pub const NEIGHBOR_SOLICITATION: u8 = 135;
+    pub const NEIGHBOR_ADVERTISEMENT: u8 = 136;
+    pub const REDIRECT: u8 = 137;
+    pub const ROUTER_RENUMBERING: u8 = 138;
+    pub const NODE_INFORMATION_QUERY: u8 = 139;
+    pub const NODE_INFORMATION_REPLY: u8 = 140;
+    pub const V2_MULTICAST_LISTENER_REPORT: u8 = 143;
+    pub const EXTENDED_ECHO_REQUEST: u8 = 160;
+    pub const EXTENDED_ECHO_REPLY: u8 = 161;
+}
+
+/// Code values of parameter problem messages (RFC 4443, RFC 7112, RFC 8754
+/// & RFC 8883).
+pub mod parameter_problem_code {
+    /// Erroneous header field encountered (RFC 4443).
+    pub const ERRONEOUS_HEADER_FIELD: u8 = 0;
+    /// Unrecognized next header type encountered (RFC 4443).
+    pub const UNRECOGNIZED_NEXT_HEADER: u8 = 1;
+    /// Unrecognized IPv6 option encountered (RFC 4443).
+    pub const UNRECOGNIZED_IPV6_OPTION: u8 = 2;
+    /// IPv6 first fragment has incomplete IPv6 header chain (RFC 7112).
+    pub const IPV6_FIRST_FRAGMENT_INCOMPLETE_HEADER_CHAIN: u8 = 3;
+    /// Segment routing upper-layer header error (RFC 8754).
+    pub const SR_UPPER_LAYER_HEADER_ERROR: u8 = 4;
+    /// Unrecognized next header type encountered by an intermediate node (RFC 8883).
+    pub const UNRECOGNIZED_NEXT_HEADER_BY_INTERMEDIATE_NODE: u8 = 5;
+    /// Extension header too big (RFC 8883).
+    pub const EXTENSION_HEADER_TOO_BIG: u8 = 6;
+    /// Extension header chain too long (RFC 8883).
+    pub const EXTENSION_HEADER_CHAIN_TOO_LONG: u8 = 7;
+    /// Too many extension headers (RFC 8883).
+    pub const TOO_MANY_EXTENSION_HEADERS: u8 = 8;
+    /// Too many options in extension header (RFC 8883).
+    pub const TOO_MANY_OPTIONS_IN_EXTENSION_HEADER: u8 = 9;
+    /// Option too big (RFC 8883).
+    pub const OPTION_TOO_BIG: u8 = 10;
+}
+
+/// Code of a parameter problem message (RFC 4443, RFC 7112, RFC 8754 &
+/// RFC 8883).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv6::{parameter_problem_code, ParameterProblemCode};
+///
+/// let code = ParameterProblemCode::from_u8(parameter_problem_code::EXTENSION_HEADER_TOO_BIG).unwrap();
+/// assert_eq!(ParameterProblemCode::ExtensionHeaderTooBig, code);
+/// assert_eq!(6, code.code_u8());
+/// assert_eq!(None, ParameterProblemCode::from_u8(11));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ParameterProblemCode {
+    /// Erroneous header field encountered (code 0).
+    ErroneousHeaderField,
+    /// Unrecognized next header type encountered (code 1).
+    UnrecognizedNextHeader,
+    /// Unrecognized IPv6 option encountered (code 2).
+    UnrecognizedIpv6Option,
+    /// IPv6 first fragment has incomplete IPv6 header chain (code 3, RFC 7112).
+    Ipv6FirstFragmentIncompleteHeaderChain,
+    /// Segment routing upper-layer header error (code 4, RFC 8754).
+    SrUpperLayerHeaderError,
+    /// Unrecognized next header type encountered by an intermediate node
+    /// (code 5, RFC 8883).
+    UnrecognizedNextHeaderByIntermediateNode,
+    /// Extension header too big (code 6, RFC 8883).
+    ExtensionHeaderTooBig,
+    /// Extension header chain too long (code 7, RFC 8883).
+    ExtensionHeaderChainTooLong,
+    /// Too many extension headers (code 8, RFC 8883).
+    TooManyExtensionHeaders,
+    /// Too many options in extension header (code 9, RFC 8883).
+    TooManyOptionsInExtensionHeader,
+    /// Option too big (code 10, RFC 8883).
+    OptionTooBig,
+}
+
+impl ParameterProblemCode {
+    /// Converts the code to the enum (None for unknown codes).
+    pub fn from_u8(code: u8) -> Option<ParameterProblemCode> {
+        use ParameterProblemCode::*;
+        use parameter_problem_code::*;
+        match code {
+            ERRONEOUS_HEADER_FIELD => Some(ErroneousHeaderField),
+            UNRECOGNIZED_NEXT_HEADER => Some(UnrecognizedNextHeader),
+            UNRECOGNIZED_IPV6_OPTION => Some(UnrecognizedIpv6Option),
+            IPV6_FIRST_FRAGMENT_INCOMPLETE_HEADER_CHAIN => Some(Ipv6FirstFragmentIncompleteHeaderChain),
+            SR_UPPER_LAYER_HEADER_ERROR => Some(SrUpperLayerHeaderError),
+            UNRECOGNIZED_NEXT_HEADER_BY_INTERMEDIATE_NODE => Some(UnrecognizedNextHeaderByIntermediateNode),
+            EXTENSION_HEADER_TOO_BIG => Some(ExtensionHeaderTooBig),
+            EXTENSION_HEADER_CHAIN_TOO_LONG => Some(ExtensionHeaderChainTooLong),
+            TOO_MANY_EXTENSION_HEADERS => Some(TooManyExtensionHeaders),
+            TOO_MANY_OPTIONS_IN_EXTENSION_HEADER => Some(TooManyOptionsInExtensionHeader),
+            OPTION_TOO_BIG => Some(OptionTooBig),
+            _ => None,
+        }
+    }
+
+    /// Returns the code of the message.
+    #[inline]
+    pub fn code_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Calculates the ICMPv6 checksum of a message (the checksum field in the
+/// message is expected to be set to 0).
+pub fn checksum(source: [u8;16], destination: [u8;16], message: &[u8]) -> u16 {
+    checksum::Sum16BitWords::new()
+        .add_16bytes(source)
+        .add_16bytes(destination)
+        .add_4bytes((message.len() as u32).to_be_bytes())
+        .add_2bytes([0, ip_number::IPV6_ICMP])
+        .add_slice(message)
+        .ones_complement()
+        .to_be()
+}
+
+/// Returns true if the checksum field of the ICMPv6 message (starting with
+/// the ICMPv6 header) is valid for the given source & destination address.
+///
+/// Messages too short to contain the checksum field are invalid.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv6;
+///
+/// let localhost = [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1];
+/// let mut message = [128, 0, 0, 0, 0, 1, 0, 1];
+/// assert!(!icmpv6::is_checksum_valid(localhost, localhost, &message));
+///
+/// let checksum = icmpv6::checksum(localhost, localhost, &message);
+/// message[2..4].copy_from_slice(&checksum.to_be_bytes());
+/// assert!(icmpv6::is_checksum_valid(localhost, localhost, &message));
+/// ```
+pub fn is_checksum_valid(source: [u8;16], destination: [u8;16], message: &[u8]) -> bool {
+    // the checksum over the complete message (including the checksum) is 0 if valid
+    message.len() >= 4 && 0 == checksum(source, destination, message)
+}
+
+/// Calculates the ICMPv6 checksum of a message sent with the given IPv6
+/// header & extension headers, using the final destination in case a
+/// routing header is present (see [`Ipv6Header::final_destination`]).
+pub fn checksum_final_destination(ip_header: &Ipv6Header, extensions: &Ipv6Extensions, message: &[u8]) -> u16 {
+    checksum(ip_header.source, ip_header.final_destination(extensions), message)
+}
+
+/// Splits the data after the header of a destination unreachable or time
+/// exceeded message (starting with the ICMPv6 header) into the original
+/// datagram & the ICMP extension structure (RFC 4884) based on the length
+/// field in the header.
+///
+/// For messages without a length (or of other types) the complete data
+/// after the header is returned as original datagram.
+pub fn split_error_extensions(message: &[u8]) -> Result<(&[u8], Option<icmp_extension::IcmpExtensionsSlice<'_>>), icmp_extension::IcmpExtensionError> {
+    use icmpv6_type::*;
+    match message.first() {
+        Some(&DESTINATION_UNREACHABLE) | Some(&TIME_EXCEEDED) => {
+            icmp_extension::split_error_extensions(message, 4, 8)
+        },
+        _ if message.len() < ERROR_HEADER_LEN => Err(icmp_extension::IcmpExtensionError::UnexpectedEnd(ERROR_HEADER_LEN)),
+        _ => Ok((&message[ERROR_HEADER_LEN..], None)),
+    }
+}
+
+/// Appends an ICMP extension structure (RFC 4884) with the given objects
+/// to a destination unreachable or time exceeded message (starting with
+/// the ICMPv6 header & containing the original datagram).
+///
+/// The original datagram is padded with zeros to at least 128 bytes & a
+/// multiple of 8 bytes & the length field in the header is set. The
+/// ICMPv6 checksum has to be calculated afterwards (see [`checksum`]).
+pub fn append_error_extensions(message: &mut Vec<u8>, objects: &[icmp_extension::IcmpExtensionObject]) -> Result<(), icmp_extension::IcmpExtensionError> {
+    icmp_extension::append_error_extensions(message, 4, 8, objects)
+}
+
+/// Creates a "Packet Too Big" message (RFC 4443 section 3.2) as response
+/// to the given packet & returns the complete IPv6 packet (IPv6 header &
+/// ICMPv6 message).
+///
+/// The response is sent from `source` to the source of the original packet
+/// & contains as much of the original packet (starting with the IPv6
+/// header) as possible without exceeding the minimum IPv6 MTU.
+///
+/// None is returned if no response must be sent (RFC 4443 section 2.4):
+///
+/// * the original packet is not an IPv6 packet
+/// * the source address of the original packet is unspecified or a multicast address
+/// * the original packet is an ICMPv6 error message
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ipv6([0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1], [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,2], 64)
+/// #    .udp(1234, 5678);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(1400));
+/// # builder.write(&mut packet, &[0;1400]).unwrap();
+/// use etherparse::{icmpv6, SlicedPacket, Ipv6Header};
+///
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let response = icmpv6::packet_too_big([0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,0xfe], 64, 1400, &sliced).unwrap();
+/// assert_eq!(icmpv6::IPV6_MIN_MTU, response.len());
+///
+/// let (ip, message) = Ipv6Header::from_slice(&response).unwrap();
+/// assert_eq!(ip.destination, [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1]);
+/// assert_eq!(icmpv6::icmpv6_type::PACKET_TOO_BIG, message[0]);
+/// assert_eq!(1400u32.to_be_bytes(), message[4..8]);
+/// ```
+pub fn packet_too_big(source: [u8;16], hop_limit: u8, mtu: u32, original: &SlicedPacket) -> Option<Vec<u8>> {
+    error_response(source, hop_limit, icmpv6_type::PACKET_TOO_BIG, 0, mtu.to_be_bytes(), original)
+}
+
+/// Creates an ICMPv6 error message of the given type & code (RFC 4443) as
+/// response to the given packet & returns the complete IPv6 packet (IPv6
+/// header & ICMPv6 message with a valid checksum).
+///
+/// `bytes5to8` is the type specific field of the error header (unused for
+/// destination unreachable & time exceeded messages, the MTU for packet
+/// too big messages & the pointer for parameter problem messages). The
+/// response is sent from `source` to the source of the original packet &
+/// contains as much of the original packet (starting with the IPv6 header)
+/// as possible without exceeding the minimum IPv6 MTU.
+///
+/// None is returned if no response must be sent (RFC 4443 section 2.4):
+///
+/// * the original packet is not an IPv6 packet
+/// * the source address of the original packet is unspecified or a multicast address
+/// * the original packet is an ICMPv6 error message
+/// * the original packet was sent to a multicast address (except for
+///   packet too big messages & parameter problem messages with code 2)
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ipv6([0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1], [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,2], 1)
+/// #    .udp(1234, 5678);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{icmpv6, SlicedPacket, Ipv6Header};
+///
+/// // hop limit exceeded in transit
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let response = icmpv6::error_response(
+///     [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,0xfe],
+///     64,
+///     icmpv6::icmpv6_type::TIME_EXCEEDED,
+///     0,
+///     [0;4],
+///     &sliced
+/// ).unwrap();
+///
+/// let (ip, message) = Ipv6Header::from_slice(&response).unwrap();
+/// assert_eq!(ip.destination, [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1]);
+/// assert_eq!(&[icmpv6::icmpv6_type::TIME_EXCEEDED, 0], &message[..2]);
+/// assert_eq!(0, icmpv6::checksum(ip.source, ip.destination, message));
+/// assert_eq!(&packet[..], &message[8..]);
+/// ```
+pub fn error_response(source: [u8;16], hop_limit: u8, icmp_type: u8, code: u8, bytes5to8: [u8;4], original: &SlicedPacket) -> Option<Vec<u8>> {
+    let (original_source, original_destination) = match &original.ip {
+        Some(InternetSlice::Ipv6(header, _)) => (header.source(), header.destination()),
+        _ => return None,
+    };
+    // unspecified & multicast source addresses
+    if original_source == [0;16] || 0xff == original_source[0] {
+        return None;
+    }
+    // multicast destinations (except for path mtu discovery & unrecognized options)
+    if 0xff == original_destination[0] &&
+       icmpv6_type::PACKET_TOO_BIG != icmp_type &&
+       !(icmpv6_type::PARAMETER_PROBLEM == icmp_type && parameter_problem_code::UNRECOGNIZED_IPV6_OPTION == code)
+    {
+        return None;
+    }
+    if is_icmpv6_error(original) {
+        return None;
+    }
+
+    // the transport & tunnel headers are in the order they appear in the packet
+    let [_, _, _, ip_header, ip_extensions, upper_first, upper_second, _] = original.header_bytes();
+    let max_quoted_len = IPV6_MIN_MTU - Ipv6Header::SERIALIZED_SIZE - ERROR_HEADER_LEN;
+    let mut quoted = Vec::with_capacity(max_quoted_len);
+    for part in &[ip_header, ip_extensions, upper_first, upper_second, original.payload, original.trailer] {
+        let len = part.len().min(max_quoted_len - quoted.len());
+        quoted.extend_from_slice(&part[..len]);
+    }
+
+    let mut message = Vec::with_capacity(ERROR_HEADER_LEN + quoted.len());
+    message.extend_from_slice(&[icmp_type, code, 0, 0]);
+    message.extend_from_slice(&bytes5to8);
+    message.extend_from_slice(&quoted);
+    let checksum = checksum(source, original_source, &message);
+    message[2..4].copy_from_slice(&checksum.to_be_bytes());
+
+    let ip = Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: message.len() as u16,
+        next_header: ip_number::IPV6_ICMP,
+        hop_limit,
+        source,
+        destination: original_source,
+    };
+    let mut result = Vec::with_capacity(Ipv6Header::SERIALIZED_SIZE + message.len());
+    // the header values are within their ranges (payload length is limited by the minimum mtu)
+    ip.write(&mut result).unwrap();
+    result.extend_from_slice(&message);
+    Some(result)
+}
+
+/// Returns true if the packet is an ICMPv6 error message (or an ICMPv6
+/// message too short to determine the type).
+fn is_icmpv6_error(packet: &SlicedPacket) -> bool {
+    match &packet.transport {
+        Some(TransportSlice::Unknown(ip_number::IPV6_ICMP)) => {
+            match packet.payload.first() {
+                Some(icmp_type) => *icmp_type < 128,
+                None => true,
+            }
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,207 @@
+use super::icmpv6_type;
+
+/// Fixed fields of a router advertisement (RFC 4861 section 4.2).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct RouterAdvertisementHeader {
+    /// Default hop limit for outgoing packets (0 = unspecified).
+    pub cur_hop_limit: u8,
+    /// Addresses are available via DHCPv6 ("M" flag).
+    pub managed_address_configuration: bool,
+    /// Other configuration information is available via DHCPv6 ("O" flag).
+    pub other_configuration: bool,
+    /// Lifetime of the router as default router in seconds (0 = not a default router).
+    pub router_lifetime: u16,
+    /// Time in milliseconds a neighbor is considered reachable (0 = unspecified).
+    pub reachable_time: u32,
+    /// Time in milliseconds between retransmitted neighbor solicitations (0 = unspecified).
+    pub retrans_timer: u32,
+}
+
+/// Fixed fields of a neighbor solicitation (RFC 4861 section 4.3).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct NeighborSolicitationHeader {
+    /// Address of the target of the solicitation.
+    pub target_address: [u8;16],
+}
+
+/// Fixed fields of a neighbor advertisement (RFC 4861 section 4.4).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct NeighborAdvertisementHeader {
+    /// The sender is a router ("R" flag).
+    pub router_flag: bool,
+    /// The advertisement was sent in response to a solicitation ("S" flag).
+    pub solicited_flag: bool,
+    /// The advertisement should override existing cache entries ("O" flag).
+    pub override_flag: bool,
+    /// Address of the target (for solicited advertisements the target
+    /// address of the solicitation).
+    pub target_address: [u8;16],
+}
+
+/// Fixed fields of a redirect message (RFC 4861 section 4.5).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct RedirectHeader {
+    /// Address of the better first hop to use for the destination.
+    pub target_address: [u8;16],
+    /// Address of the destination that is redirected to the target.
+    pub destination_address: [u8;16],
+}
+
+/// Neighbor discovery message (ICMPv6 types 133-137) with the decoded
+/// fixed fields of the message.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv6::{NdpMessage, NdpOption, NdpOptionsIterator, RouterAdvertisementHeader};
+///
+/// let advertisement = NdpMessage::RouterAdvertisement(RouterAdvertisementHeader{
+///     cur_hop_limit: 64,
+///     managed_address_configuration: true,
+///     router_lifetime: 1800,
+///     ..Default::default()
+/// });
+/// let mut message = advertisement.to_bytes();
+/// NdpOption::Mtu(1500).write(&mut message).unwrap();
+///
+/// let (decoded, options) = NdpMessage::from_slice(&message).unwrap();
+/// assert_eq!(advertisement, decoded);
+/// assert_eq!(
+///     vec![Ok(NdpOption::Mtu(1500))],
+///     NdpOptionsIterator::from_slice(options).collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NdpMessage {
+    RouterSolicitation,
+    RouterAdvertisement(RouterAdvertisementHeader),
+    NeighborSolicitation(NeighborSolicitationHeader),
+    NeighborAdvertisement(NeighborAdvertisementHeader),
+    Redirect(RedirectHeader),
+}
+
+impl NdpMessage {
+    /// Decodes the neighbor discovery message at the start of the slice
+    /// (starting with the ICMPv6 header) & returns the message & the slice
+    /// containing the options (the checksum is not verified). The options
+    /// can be iterated via [`super::NdpOptionsIterator::from_slice`].
+    ///
+    /// None is returned if the message is not a neighbor discovery message
+    /// or too short to contain the fixed part of the message.
+    pub fn from_slice(message: &[u8]) -> Option<(NdpMessage, &[u8])> {
+        use icmpv6_type::*;
+        let address = |offset: usize| {
+            let mut result = [0u8;16];
+            result.copy_from_slice(&message[offset..offset + 16]);
+            result
+        };
+        let u32_at = |offset: usize| u32::from_be_bytes([
+            message[offset], message[offset + 1], message[offset + 2], message[offset + 3]
+        ]);
+
+        let icmp_type = *message.first()?;
+        let fixed_len = NdpMessage::fixed_len_of(icmp_type)?;
+        if message.len() < fixed_len {
+            return None;
+        }
+        let result = match icmp_type {
+            ROUTER_SOLICITATION => NdpMessage::RouterSolicitation,
+            ROUTER_ADVERTISEMENT => NdpMessage::RouterAdvertisement(RouterAdvertisementHeader{
+                cur_hop_limit: message[4],
+                managed_address_configuration: 0 != message[5] & 0x80,
+                other_configuration: 0 != message[5] & 0x40,
+                router_lifetime: u16::from_be_bytes([message[6], message[7]]),
+                reachable_time: u32_at(8),
+                retrans_timer: u32_at(12),
+            }),
+            NEIGHBOR_SOLICITATION => NdpMessage::NeighborSolicitation(NeighborSolicitationHeader{
+                target_address: address(8),
+            }),
+            NEIGHBOR_ADVERTISEMENT => NdpMessage::NeighborAdvertisement(NeighborAdvertisementHeader{
+                router_flag: 0 != message[4] & 0x80,
+                solicited_flag: 0 != message[4] & 0x40,
+                override_flag: 0 != message[4] & 0x20,
+                target_address: address(8),
+            }),
+            _ => NdpMessage::Redirect(RedirectHeader{
+                target_address: address(8),
+                destination_address: address(24),
+            }),
+        };
+        Some((result, &message[fixed_len..]))
+    }
+
+    /// Length of the ICMPv6 header & the fixed part of the message with
+    /// the given ICMPv6 type (None if the type is not a neighbor discovery
+    /// message type).
+    fn fixed_len_of(icmp_type: u8) -> Option<usize> {
+        use icmpv6_type::*;
+        match icmp_type {
+            ROUTER_SOLICITATION => Some(8),
+            ROUTER_ADVERTISEMENT => Some(16),
+            NEIGHBOR_SOLICITATION | NEIGHBOR_ADVERTISEMENT => Some(24),
+            REDIRECT => Some(40),
+            _ => None,
+        }
+    }
+
+    /// ICMPv6 type value of the message.
+    pub fn icmp_type(&self) -> u8 {
+        use icmpv6_type::*;
+        match self {
+            NdpMessage::RouterSolicitation => ROUTER_SOLICITATION,
+            NdpMessage::RouterAdvertisement(_) => ROUTER_ADVERTISEMENT,
+            NdpMessage::NeighborSolicitation(_) => NEIGHBOR_SOLICITATION,
+            NdpMessage::NeighborAdvertisement(_) => NEIGHBOR_ADVERTISEMENT,
+            NdpMessage::Redirect(_) => REDIRECT,
+        }
+    }
+
+    /// Length of the ICMPv6 header & the fixed part of the message in
+    /// bytes (the options start after this length).
+    pub fn fixed_len(&self) -> usize {
+        // all variants have a known length
+        NdpMessage::fixed_len_of(self.icmp_type()).unwrap_or(0)
+    }
+
+    /// Returns the ICMPv6 header & the fixed part of the message with the
+    /// checksum field set to 0 (options can be appended via
+    /// [`super::NdpOption::write`] before the checksum is calculated via
+    /// [`super::checksum`]).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.fixed_len());
+        result.extend_from_slice(&[self.icmp_type(), 0, 0, 0]);
+        match self {
+            NdpMessage::RouterSolicitation => result.extend_from_slice(&[0;4]),
+            NdpMessage::RouterAdvertisement(value) => {
+                result.push(value.cur_hop_limit);
+                result.push(
+                    if value.managed_address_configuration { 0x80 } else { 0 } |
+                    if value.other_configuration { 0x40 } else { 0 }
+                );
+                result.extend_from_slice(&value.router_lifetime.to_be_bytes());
+                result.extend_from_slice(&value.reachable_time.to_be_bytes());
+                result.extend_from_slice(&value.retrans_timer.to_be_bytes());
+            },
+            NdpMessage::NeighborSolicitation(value) => {
+                result.extend_from_slice(&[0;4]);
+                result.extend_from_slice(&value.target_address);
+            },
+            NdpMessage::NeighborAdvertisement(value) => {
+                result.push(
+                    if value.router_flag { 0x80 } else { 0 } |
+                    if value.solicited_flag { 0x40 } else { 0 } |
+                    if value.override_flag { 0x20 } else { 0 }
+                );
+                result.extend_from_slice(&[0;3]);
+                result.extend_from_slice(&value.target_address);
+            },
+            NdpMessage::Redirect(value) => {
+                result.extend_from_slice(&[0;4]);
+                result.extend_from_slice(&value.target_address);
+                result.extend_from_slice(&value.destination_address);
+            },
+        }
+        result
+    }
+}
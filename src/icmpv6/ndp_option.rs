@@ -0,0 +1,387 @@
+use super::super::*;
+
+/// Type values of neighbor discovery options (RFC 4861, RFC 8106).
+pub mod ndp_option_type {
+    pub const SOURCE_LINK_LAYER_ADDRESS: u8 = 1;
+    pub const TARGET_LINK_LAYER_ADDRESS: u8 = 2;
+    pub const PREFIX_INFORMATION: u8 = 3;
+    pub const REDIRECTED_HEADER: u8 = 4;
+    pub const MTU: u8 = 5;
+    pub const RECURSIVE_DNS_SERVER: u8 = 25;
+    pub const DNS_SEARCH_LIST: u8 = 31;
+}
+
+/// Errors that can occur when reading or writing neighbor discovery options.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NdpOptionError {
+    /// The data ended before the end of the option header or the option
+    /// (expected minimum size as argument).
+    UnexpectedEnd(usize),
+    /// An option has a length of 0 (not allowed according to RFC 4861).
+    ZeroLength,
+    /// The length of an option is not valid for the option type.
+    InvalidLength{ option_type: u8, length: u8 },
+    /// A domain name is not correctly encoded (or can not be encoded).
+    InvalidDomainName,
+    /// The option is too large to be represented in the length field.
+    OptionTooLarge(usize),
+}
+
+impl fmt::Display for NdpOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use NdpOptionError::*;
+        match self {
+            UnexpectedEnd(len) => write!(f, "NdpOptionError: Unexpected end of data. Expected at least {} bytes.", len),
+            ZeroLength => write!(f, "NdpOptionError: Option with a length of 0 encountered."),
+            InvalidLength{ option_type, length } => write!(f, "NdpOptionError: Length {} is not valid for options of type {}.", length, option_type),
+            InvalidDomainName => write!(f, "NdpOptionError: Invalid domain name."),
+            OptionTooLarge(len) => write!(f, "NdpOptionError: Option with a size of {} bytes is too large.", len),
+        }
+    }
+}
+
+impl Error for NdpOptionError {}
+
+/// Prefix Information option (RFC 4861 section 4.6.2).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrefixInformationOption {
+    /// Number of leading bits in the prefix that are valid.
+    pub prefix_length: u8,
+    /// The prefix can be used for on-link determination ("L" flag).
+    pub on_link: bool,
+    /// The prefix can be used for stateless address autoconfiguration ("A" flag).
+    pub autonomous: bool,
+    /// Time in seconds the prefix is valid for on-link determination (`u32::MAX` = infinity).
+    pub valid_lifetime: u32,
+    /// Time in seconds addresses generated from the prefix remain preferred (`u32::MAX` = infinity).
+    pub preferred_lifetime: u32,
+    pub prefix: [u8;16],
+}
+
+impl PrefixInformationOption {
+    /// Length of the option in bytes (including the type & length field).
+    pub const LEN: usize = 32;
+}
+
+/// Recursive DNS Server option (RFC 8106 section 5.1).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RdnssOption {
+    /// Maximum time in seconds the servers can be used (`u32::MAX` = infinity).
+    pub lifetime: u32,
+    pub addresses: Vec<[u8;16]>,
+}
+
+/// DNS Search List option (RFC 8106 section 5.2).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DnsslOption {
+    /// Maximum time in seconds the domains can be used (`u32::MAX` = infinity).
+    pub lifetime: u32,
+    /// Domain names in dotted notation without a trailing dot (e.g. `example.com`).
+    pub domains: Vec<String>,
+}
+
+/// Neighbor discovery option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NdpOption<'a> {
+    /// Link-layer address of the sender (including the padding of the
+    /// option, for ethernet the 6 byte mac address).
+    SourceLinkLayerAddress(&'a [u8]),
+    /// Link-layer address of the target (including the padding of the
+    /// option, for ethernet the 6 byte mac address).
+    TargetLinkLayerAddress(&'a [u8]),
+    PrefixInformation(PrefixInformationOption),
+    /// Redirected header option containing as much of the redirected
+    /// packet as possible (starting with the IPv6 header, data after the
+    /// 6 reserved bytes).
+    RedirectedHeader(&'a [u8]),
+    /// MTU option containing the recommended MTU of the link.
+    Mtu(u32),
+    RecursiveDnsServer(RdnssOption),
+    DnsSearchList(DnsslOption),
+    /// Option that is not decoded (data without the type & length field).
+    Unknown{ option_type: u8, data: &'a [u8] },
+}
+
+impl<'a> NdpOption<'a> {
+    /// Reads the option at the start of the slice & returns the option & the rest of the slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<(NdpOption<'a>, &'a [u8]), NdpOptionError> {
+        use NdpOptionError::*;
+
+        if slice.len() < 2 {
+            return Err(UnexpectedEnd(2));
+        }
+        let option_type = slice[0];
+        let length = slice[1];
+        if 0 == length {
+            return Err(ZeroLength);
+        }
+        let len = usize::from(length)*8;
+        if slice.len() < len {
+            return Err(UnexpectedEnd(len));
+        }
+        let (option, rest) = slice.split_at(len);
+        let data = &option[2..];
+        let result = match option_type {
+            ndp_option_type::SOURCE_LINK_LAYER_ADDRESS => NdpOption::SourceLinkLayerAddress(data),
+            ndp_option_type::TARGET_LINK_LAYER_ADDRESS => NdpOption::TargetLinkLayerAddress(data),
+            ndp_option_type::PREFIX_INFORMATION => {
+                if len != PrefixInformationOption::LEN {
+                    return Err(InvalidLength{ option_type, length });
+                }
+                let mut prefix = [0u8;16];
+                prefix.copy_from_slice(&data[14..30]);
+                NdpOption::PrefixInformation(PrefixInformationOption{
+                    prefix_length: data[0],
+                    on_link: 0 != data[1] & 0x80,
+                    autonomous: 0 != data[1] & 0x40,
+                    valid_lifetime: u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+                    preferred_lifetime: u32::from_be_bytes([data[6], data[7], data[8], data[9]]),
+                    prefix,
+                })
+            },
+            ndp_option_type::REDIRECTED_HEADER => NdpOption::RedirectedHeader(&data[6..]),
+            ndp_option_type::MTU => {
+                if 1 != length {
+                    return Err(InvalidLength{ option_type, length });
+                }
+                NdpOption::Mtu(u32::from_be_bytes([data[2], data[3], data[4], data[5]]))
+            },
+            ndp_option_type::RECURSIVE_DNS_SERVER => {
+                // reserved, lifetime & at least one address
+                if length < 3 || length.is_multiple_of(2) {
+                    return Err(InvalidLength{ option_type, length });
+                }
+                NdpOption::RecursiveDnsServer(RdnssOption{
+                    lifetime: u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+                    addresses: data[6..].chunks_exact(16).map(|chunk| {
+                        let mut address = [0u8;16];
+                        address.copy_from_slice(chunk);
+                        address
+                    }).collect(),
+                })
+            },
+            ndp_option_type::DNS_SEARCH_LIST => {
+                if length < 2 {
+                    return Err(InvalidLength{ option_type, length });
+                }
+                NdpOption::DnsSearchList(DnsslOption{
+                    lifetime: u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+                    domains: decode_domain_names(&data[6..])?,
+                })
+            },
+            _ => NdpOption::Unknown{ option_type, data },
+        };
+        Ok((result, rest))
+    }
+
+    /// Type value of the option.
+    pub fn option_type(&self) -> u8 {
+        match self {
+            NdpOption::SourceLinkLayerAddress(_) => ndp_option_type::SOURCE_LINK_LAYER_ADDRESS,
+            NdpOption::TargetLinkLayerAddress(_) => ndp_option_type::TARGET_LINK_LAYER_ADDRESS,
+            NdpOption::PrefixInformation(_) => ndp_option_type::PREFIX_INFORMATION,
+            NdpOption::RedirectedHeader(_) => ndp_option_type::REDIRECTED_HEADER,
+            NdpOption::Mtu(_) => ndp_option_type::MTU,
+            NdpOption::RecursiveDnsServer(_) => ndp_option_type::RECURSIVE_DNS_SERVER,
+            NdpOption::DnsSearchList(_) => ndp_option_type::DNS_SEARCH_LIST,
+            NdpOption::Unknown{ option_type, .. } => *option_type,
+        }
+    }
+
+    /// Returns the ethernet mac address contained in a source or target
+    /// link-layer address option (None for other options or if the
+    /// address is shorter than 6 bytes).
+    ///
+    /// As the address is padded to a multiple of 8 octets the first 6
+    /// bytes of the option data are returned.
+    pub fn mac_addr(&self) -> Option<MacAddr> {
+        match self {
+            NdpOption::SourceLinkLayerAddress(address) |
+            NdpOption::TargetLinkLayerAddress(address) => MacAddr::from_slice(address),
+            _ => None,
+        }
+    }
+
+    /// Writes the option (including the type & length field & the padding
+    /// to a multiple of 8 octets).
+    pub fn write(&self, output: &mut Vec<u8>) -> Result<(), NdpOptionError> {
+        let start = output.len();
+        output.extend_from_slice(&[self.option_type(), 0]);
+        match self {
+            NdpOption::SourceLinkLayerAddress(address) |
+            NdpOption::TargetLinkLayerAddress(address) => output.extend_from_slice(address),
+            NdpOption::PrefixInformation(option) => {
+                output.push(option.prefix_length);
+                output.push(
+                    if option.on_link { 0x80 } else { 0 } |
+                    if option.autonomous { 0x40 } else { 0 }
+                );
+                output.extend_from_slice(&option.valid_lifetime.to_be_bytes());
+                output.extend_from_slice(&option.preferred_lifetime.to_be_bytes());
+                output.extend_from_slice(&[0;4]);
+                output.extend_from_slice(&option.prefix);
+            },
+            NdpOption::RedirectedHeader(data) => {
+                output.extend_from_slice(&[0;6]);
+                output.extend_from_slice(data);
+            },
+            NdpOption::Mtu(mtu) => {
+                output.extend_from_slice(&[0, 0]);
+                output.extend_from_slice(&mtu.to_be_bytes());
+            },
+            NdpOption::RecursiveDnsServer(option) => {
+                output.extend_from_slice(&[0, 0]);
+                output.extend_from_slice(&option.lifetime.to_be_bytes());
+                for address in &option.addresses {
+                    output.extend_from_slice(address);
+                }
+            },
+            NdpOption::DnsSearchList(option) => {
+                output.extend_from_slice(&[0, 0]);
+                output.extend_from_slice(&option.lifetime.to_be_bytes());
+                for domain in &option.domains {
+                    if let Err(err) = encode_domain_name(domain, output) {
+                        output.truncate(start);
+                        return Err(err);
+                    }
+                }
+            },
+            NdpOption::Unknown{ data, .. } => output.extend_from_slice(data),
+        }
+        // pad to a multiple of 8 octets
+        let len = output.len() - start;
+        let padded_len = len.div_ceil(8)*8;
+        if padded_len > usize::from(u8::MAX)*8 {
+            output.truncate(start);
+            return Err(NdpOptionError::OptionTooLarge(padded_len));
+        }
+        output.resize(start + padded_len, 0);
+        output[start + 1] = (padded_len / 8) as u8;
+        Ok(())
+    }
+}
+
+/// Iterator over the neighbor discovery options in a slice.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NdpOptionsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> NdpOptionsIterator<'a> {
+    /// Creates an iterator over the options in the given slice (the
+    /// data after the fixed part of a neighbor discovery message).
+    pub fn from_slice(options: &'a [u8]) -> NdpOptionsIterator<'a> {
+        NdpOptionsIterator{ rest: options }
+    }
+
+    /// Creates an iterator over the options of a neighbor discovery
+    /// message (router solicitation & advertisement, neighbor
+    /// solicitation & advertisement or redirect) starting with the
+    /// ICMPv6 header.
+    ///
+    /// None is returned if the message is not a neighbor discovery
+    /// message or too short to contain the fixed part of the message
+    /// (see [`super::NdpMessage::from_slice`] to also decode the fixed
+    /// part).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::icmpv6::{icmpv6_type, NdpOption, NdpOptionsIterator};
+    ///
+    /// // router solicitation with a source link-layer address option
+    /// let message = [
+    ///     icmpv6_type::ROUTER_SOLICITATION, 0, 0, 0, 0, 0, 0, 0,
+    ///     1, 1, 2, 3, 4, 5, 6, 7,
+    /// ];
+    /// let mut options = NdpOptionsIterator::from_ndp_message(&message).unwrap();
+    /// assert_eq!(Some(Ok(NdpOption::SourceLinkLayerAddress(&[2, 3, 4, 5, 6, 7]))), options.next());
+    /// assert_eq!(None, options.next());
+    /// ```
+    pub fn from_ndp_message(message: &'a [u8]) -> Option<NdpOptionsIterator<'a>> {
+        super::NdpMessage::from_slice(message)
+            .map(|(_, options)| NdpOptionsIterator::from_slice(options))
+    }
+
+    /// Returns the slice containing the options that were not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for NdpOptionsIterator<'a> {
+    type Item = Result<NdpOption<'a>, NdpOptionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        match NdpOption::from_slice(self.rest) {
+            Ok((option, rest)) => {
+                self.rest = rest;
+                Some(Ok(option))
+            },
+            Err(err) => {
+                // stop the iteration after an error
+                self.rest = &[];
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// Decodes a list of uncompressed domain names (RFC 1035 section 3.1)
+/// followed by zero padding.
+fn decode_domain_names(mut data: &[u8]) -> Result<Vec<String>, NdpOptionError> {
+    let mut result = Vec::new();
+    while let Some(first) = data.first() {
+        // padding
+        if 0 == *first {
+            if data.iter().any(|v| 0 != *v) {
+                return Err(NdpOptionError::InvalidDomainName);
+            }
+            break;
+        }
+        let mut name = String::new();
+        loop {
+            let len = match data.first() {
+                Some(len) => usize::from(*len),
+                None => return Err(NdpOptionError::InvalidDomainName),
+            };
+            if 0 == len {
+                data = &data[1..];
+                break;
+            }
+            // compression pointers & extended label types are not allowed
+            if len > 63 || data.len() < 1 + len {
+                return Err(NdpOptionError::InvalidDomainName);
+            }
+            let label = std::str::from_utf8(&data[1..1 + len])
+                .map_err(|_| NdpOptionError::InvalidDomainName)?;
+            if !name.is_empty() {
+                name.push('.');
+            }
+            name.push_str(label);
+            data = &data[1 + len..];
+        }
+        result.push(name);
+    }
+    Ok(result)
+}
+
+/// Encodes a domain name in dotted notation as a sequence of labels.
+fn encode_domain_name(name: &str, output: &mut Vec<u8>) -> Result<(), NdpOptionError> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() || name.len() > 253 {
+        return Err(NdpOptionError::InvalidDomainName);
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(NdpOptionError::InvalidDomainName);
+        }
+        output.push(label.len() as u8);
+        output.extend_from_slice(label.as_bytes());
+    }
+    output.push(0);
+    Ok(())
+}
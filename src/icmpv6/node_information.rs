@@ -0,0 +1,159 @@
+use super::super::*;
+use super::icmpv6_type;
+
+/// Code values of node information queries (RFC 4620 section 4).
+pub mod node_information_query_code {
+    /// The subject of the query is an IPv6 address.
+    pub const SUBJECT_IPV6_ADDRESS: u8 = 0;
+    /// The subject of the query is a DNS name (or empty for NOOP queries).
+    pub const SUBJECT_NAME: u8 = 1;
+    /// The subject of the query is an IPv4 address.
+    pub const SUBJECT_IPV4_ADDRESS: u8 = 2;
+}
+
+/// Code values of node information replies (RFC 4620 section 4).
+pub mod node_information_reply_code {
+    pub const SUCCESS: u8 = 0;
+    pub const REFUSED: u8 = 1;
+    pub const UNKNOWN_QTYPE: u8 = 2;
+}
+
+/// Qtype values of node information messages (RFC 4620 section 4).
+pub mod node_information_qtype {
+    pub const NOOP: u16 = 0;
+    pub const NODE_NAME: u16 = 2;
+    pub const NODE_ADDRESSES: u16 = 3;
+    pub const IPV4_ADDRESSES: u16 = 4;
+}
+
+/// Flags of node addresses & IPv4 addresses queries & replies (RFC 4620
+/// section 6.3 & 6.4).
+pub mod node_information_flag {
+    /// The reply does not contain all addresses ("T" flag).
+    pub const TRUNCATED: u16 = 0x0001;
+    /// Request all unicast addresses of all interfaces ("A" flag).
+    pub const ALL: u16 = 0x0002;
+    /// Request IPv4-compatible & IPv4-mapped addresses ("C" flag).
+    pub const COMPATIBLE: u16 = 0x0004;
+    /// Request link-local addresses ("L" flag).
+    pub const LINK_LOCAL: u16 = 0x0008;
+    /// Request site-local addresses ("S" flag).
+    pub const SITE_LOCAL: u16 = 0x0010;
+    /// Request global addresses ("G" flag).
+    pub const GLOBAL: u16 = 0x0020;
+}
+
+/// Code & fixed fields of a node information query or reply (RFC 4620
+/// section 4).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct NodeInformationHeader {
+    /// Code of the message (see [`node_information_query_code`] &
+    /// [`node_information_reply_code`]).
+    pub code: u8,
+    /// Type of information requested (see [`node_information_qtype`]).
+    pub qtype: u16,
+    /// Qtype specific flags (see [`node_information_flag`]).
+    pub flags: u16,
+    /// Nonce of the query (copied to the reply).
+    pub nonce: [u8;8],
+}
+
+/// Node information query or reply (ICMPv6 types 139 & 140, RFC 4620).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv6::{
+///     node_information_qtype, node_information_query_code, NodeInformationHeader,
+///     NodeInformationMessage
+/// };
+///
+/// let query = NodeInformationMessage::Query(NodeInformationHeader{
+///     code: node_information_query_code::SUBJECT_IPV6_ADDRESS,
+///     qtype: node_information_qtype::NODE_NAME,
+///     flags: 0,
+///     nonce: [1,2,3,4,5,6,7,8],
+/// });
+/// let mut message = query.to_bytes();
+/// // subject address
+/// message.extend_from_slice(&[0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1]);
+///
+/// let (decoded, subject) = NodeInformationMessage::from_slice(&message).unwrap().unwrap();
+/// assert_eq!(query, decoded);
+/// assert_eq!(16, subject.len());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum NodeInformationMessage {
+    Query(NodeInformationHeader),
+    Reply(NodeInformationHeader),
+}
+
+impl NodeInformationMessage {
+    /// Length of the ICMPv6 header & the fixed fields of the message.
+    pub const LEN: usize = 16;
+
+    /// Decodes the node information message at the start of the slice
+    /// (starting with the ICMPv6 header) & returns the message & the data
+    /// after the fixed fields (the subject of queries or the reply data,
+    /// the checksum is not verified).
+    ///
+    /// `Ok(None)` is returned if the ICMPv6 type is not a node information
+    /// message type.
+    pub fn from_slice(message: &[u8]) -> Result<Option<(NodeInformationMessage, &[u8])>, ReadError> {
+        use crate::ReadError::UnexpectedEndOfSlice;
+        use icmpv6_type::*;
+
+        let icmp_type = match message.first() {
+            Some(value) => *value,
+            None => return Err(UnexpectedEndOfSlice(NodeInformationMessage::LEN)),
+        };
+        if NODE_INFORMATION_QUERY != icmp_type && NODE_INFORMATION_REPLY != icmp_type {
+            return Ok(None);
+        }
+        if message.len() < NodeInformationMessage::LEN {
+            return Err(UnexpectedEndOfSlice(NodeInformationMessage::LEN));
+        }
+        let mut nonce = [0u8;8];
+        nonce.copy_from_slice(&message[8..16]);
+        let header = NodeInformationHeader{
+            code: message[1],
+            qtype: u16::from_be_bytes([message[4], message[5]]),
+            flags: u16::from_be_bytes([message[6], message[7]]),
+            nonce,
+        };
+        let value = if NODE_INFORMATION_QUERY == icmp_type {
+            NodeInformationMessage::Query(header)
+        } else {
+            NodeInformationMessage::Reply(header)
+        };
+        Ok(Some((value, &message[NodeInformationMessage::LEN..])))
+    }
+
+    /// ICMPv6 type value of the message.
+    pub fn icmp_type(&self) -> u8 {
+        match self {
+            NodeInformationMessage::Query(_) => icmpv6_type::NODE_INFORMATION_QUERY,
+            NodeInformationMessage::Reply(_) => icmpv6_type::NODE_INFORMATION_REPLY,
+        }
+    }
+
+    /// Returns the code & fixed fields of the message.
+    pub fn header(&self) -> &NodeInformationHeader {
+        match self {
+            NodeInformationMessage::Query(value) | NodeInformationMessage::Reply(value) => value,
+        }
+    }
+
+    /// Returns the ICMPv6 header & the fixed fields of the message with the
+    /// checksum field set to 0 (the subject or reply data can be appended
+    /// before the checksum is calculated via [`super::checksum`]).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = self.header();
+        let mut result = Vec::with_capacity(NodeInformationMessage::LEN);
+        result.extend_from_slice(&[self.icmp_type(), header.code, 0, 0]);
+        result.extend_from_slice(&header.qtype.to_be_bytes());
+        result.extend_from_slice(&header.flags.to_be_bytes());
+        result.extend_from_slice(&header.nonce);
+        result
+    }
+}
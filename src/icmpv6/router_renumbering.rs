@@ -0,0 +1,380 @@
+use super::super::*;
+use super::icmpv6_type;
+
+/// Code values of router renumbering messages (RFC 2894 section 3.1).
+pub mod router_renumbering_code {
+    pub const COMMAND: u8 = 0;
+    pub const RESULT: u8 = 1;
+    pub const SEQUENCE_NUMBER_RESET: u8 = 255;
+}
+
+/// OpCode values of prefix control operations (RFC 2894 section 3.2).
+pub mod router_renumbering_op_code {
+    pub const ADD: u8 = 1;
+    pub const CHANGE: u8 = 2;
+    pub const SET_GLOBAL: u8 = 3;
+}
+
+/// Use-prefix part of a prefix control operation (RFC 2894 section 3.2).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct UsePrefix {
+    /// Number of leading bits of `use_prefix` to use in the new prefix.
+    pub use_len: u8,
+    /// Number of leading bits of the matched prefix to keep in the new prefix.
+    pub keep_len: u8,
+    /// Mask of the router advertisement flags to set from `ra_flags`.
+    pub flag_mask: u8,
+    /// Router advertisement flags ("L" & "A" flags) of the new prefix.
+    pub ra_flags: u8,
+    /// Valid lifetime of the new prefix in seconds.
+    pub valid_lifetime: u32,
+    /// Preferred lifetime of the new prefix in seconds.
+    pub preferred_lifetime: u32,
+    /// Decrement the valid lifetime in real time ("V" flag).
+    pub decrement_valid_lifetime: bool,
+    /// Decrement the preferred lifetime in real time ("P" flag).
+    pub decrement_preferred_lifetime: bool,
+    pub use_prefix: [u8;16],
+}
+
+impl UsePrefix {
+    /// Length of a serialized use-prefix part.
+    pub const LEN: usize = 32;
+}
+
+/// Prefix control operation of a router renumbering command (match-prefix
+/// part & use-prefix parts, RFC 2894 section 3.2).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct PrefixControlOperation {
+    /// Operation (see [`router_renumbering_op_code`]).
+    pub op_code: u8,
+    /// Ordinal used to order the operations.
+    pub ordinal: u8,
+    /// Number of leading bits of `match_prefix` to compare.
+    pub match_len: u8,
+    /// Minimum length of prefixes the operation applies to.
+    pub min_len: u8,
+    /// Maximum length of prefixes the operation applies to.
+    pub max_len: u8,
+    pub match_prefix: [u8;16],
+    pub use_prefixes: Vec<UsePrefix>,
+}
+
+impl PrefixControlOperation {
+    /// Length of a serialized match-prefix part.
+    pub const MATCH_PREFIX_LEN: usize = 24;
+
+    /// Maximum number of use-prefix parts that can be represented in the
+    /// OpLength field.
+    pub const MAX_USE_PREFIXES: usize = 63;
+
+    /// Length of the serialized operation in bytes.
+    pub fn header_len(&self) -> usize {
+        PrefixControlOperation::MATCH_PREFIX_LEN + self.use_prefixes.len()*UsePrefix::LEN
+    }
+}
+
+/// Match result of a router renumbering result message (RFC 2894 section 3.3).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct MatchResult {
+    /// The prefix was not updated as the operation was out of bounds ("B" flag).
+    pub out_of_bounds: bool,
+    /// The prefix was not updated as it is forbidden ("F" flag).
+    pub forbidden: bool,
+    /// Ordinal of the prefix control operation that matched.
+    pub ordinal: u8,
+    /// Length of the matched prefix.
+    pub matched_len: u8,
+    /// Interface the prefix was matched on.
+    pub interface_index: u32,
+    pub matched_prefix: [u8;16],
+}
+
+impl MatchResult {
+    /// Length of a serialized match result.
+    pub const LEN: usize = 24;
+}
+
+/// Body of a router renumbering message (determines the code).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RouterRenumberingBody {
+    Command(Vec<PrefixControlOperation>),
+    Result(Vec<MatchResult>),
+    SequenceNumberReset,
+}
+
+/// Router renumbering message (ICMPv6 type 138, RFC 2894).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::icmpv6::{
+///     router_renumbering_op_code, PrefixControlOperation, RouterRenumberingBody,
+///     RouterRenumberingMessage, UsePrefix
+/// };
+///
+/// let command = RouterRenumberingMessage{
+///     sequence_number: 1,
+///     max_delay: 100,
+///     body: RouterRenumberingBody::Command(vec![PrefixControlOperation{
+///         op_code: router_renumbering_op_code::CHANGE,
+///         match_len: 48,
+///         min_len: 64,
+///         max_len: 64,
+///         match_prefix: [0x20,1,0xd,0xb8,0,1,0,0,0,0,0,0,0,0,0,0],
+///         use_prefixes: vec![UsePrefix{
+///             use_len: 48,
+///             keep_len: 16,
+///             use_prefix: [0x20,1,0xd,0xb8,0,2,0,0,0,0,0,0,0,0,0,0],
+///             ..Default::default()
+///         }],
+///         ..Default::default()
+///     }]),
+///     ..Default::default()
+/// };
+/// let bytes = command.to_bytes().unwrap();
+/// assert_eq!(command.header_len(), bytes.len());
+/// assert_eq!(Some(command), RouterRenumberingMessage::from_slice(&bytes).unwrap());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouterRenumberingMessage {
+    pub sequence_number: u32,
+    pub segment_number: u8,
+    /// The command is a test command & must not be executed ("T" flag).
+    pub test_command: bool,
+    /// A result message is requested ("R" flag).
+    pub result_requested: bool,
+    /// The command applies to all interfaces ("A" flag).
+    pub all_interfaces: bool,
+    /// Site-specific operation ("S" flag).
+    pub site_specific: bool,
+    /// The command was previously processed ("P" flag, only set in results).
+    pub processed_previously: bool,
+    /// Maximum delay in milliseconds before sending a result message.
+    pub max_delay: u16,
+    pub body: RouterRenumberingBody,
+}
+
+impl Default for RouterRenumberingMessage {
+    fn default() -> RouterRenumberingMessage {
+        RouterRenumberingMessage{
+            sequence_number: 0,
+            segment_number: 0,
+            test_command: false,
+            result_requested: false,
+            all_interfaces: false,
+            site_specific: false,
+            processed_previously: false,
+            max_delay: 0,
+            body: RouterRenumberingBody::SequenceNumberReset,
+        }
+    }
+}
+
+impl RouterRenumberingMessage {
+    /// Length of the ICMPv6 header & the fixed fields of the message.
+    pub const MIN_LEN: usize = 16;
+
+    /// Decodes the router renumbering message at the start of the slice
+    /// (starting with the ICMPv6 header, the checksum is not verified).
+    ///
+    /// `Ok(None)` is returned if the ICMPv6 type is not 138 or the code is
+    /// unknown. The body of commands & results extends to the end of the
+    /// slice.
+    pub fn from_slice(message: &[u8]) -> Result<Option<RouterRenumberingMessage>, ReadError> {
+        use crate::ReadError::*;
+        use router_renumbering_code::*;
+
+        match message.first() {
+            Some(&icmpv6_type::ROUTER_RENUMBERING) => {},
+            Some(_) => return Ok(None),
+            None => return Err(UnexpectedEndOfSlice(RouterRenumberingMessage::MIN_LEN)),
+        }
+        if message.len() < RouterRenumberingMessage::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(RouterRenumberingMessage::MIN_LEN));
+        }
+        let u32_at = |data: &[u8], offset: usize| u32::from_be_bytes([
+            data[offset], data[offset + 1], data[offset + 2], data[offset + 3]
+        ]);
+        let address = |data: &[u8], offset: usize| {
+            let mut result = [0u8;16];
+            result.copy_from_slice(&data[offset..offset + 16]);
+            result
+        };
+
+        let mut offset = RouterRenumberingMessage::MIN_LEN;
+        let body = match message[1] {
+            COMMAND => {
+                let mut operations = Vec::new();
+                while offset < message.len() {
+                    let rest = &message[offset..];
+                    if rest.len() < PrefixControlOperation::MATCH_PREFIX_LEN {
+                        return Err(UnexpectedEndOfSlice(offset + PrefixControlOperation::MATCH_PREFIX_LEN));
+                    }
+                    let op_length = rest[1];
+                    if op_length < 3 || 3 != op_length % 4 {
+                        return Err(Icmpv6RouterRenumberingOpLengthBad(op_length));
+                    }
+                    let len = usize::from(op_length)*8;
+                    if rest.len() < len {
+                        return Err(UnexpectedEndOfSlice(offset + len));
+                    }
+                    operations.push(PrefixControlOperation{
+                        op_code: rest[0],
+                        ordinal: rest[2],
+                        match_len: rest[3],
+                        min_len: rest[4],
+                        max_len: rest[5],
+                        match_prefix: address(rest, 8),
+                        use_prefixes: rest[PrefixControlOperation::MATCH_PREFIX_LEN..len]
+                            .chunks_exact(UsePrefix::LEN)
+                            .map(|part| UsePrefix{
+                                use_len: part[0],
+                                keep_len: part[1],
+                                flag_mask: part[2],
+                                ra_flags: part[3],
+                                valid_lifetime: u32_at(part, 4),
+                                preferred_lifetime: u32_at(part, 8),
+                                decrement_valid_lifetime: 0 != part[12] & 0x80,
+                                decrement_preferred_lifetime: 0 != part[12] & 0x40,
+                                use_prefix: address(part, 16),
+                            })
+                            .collect(),
+                    });
+                    offset += len;
+                }
+                RouterRenumberingBody::Command(operations)
+            },
+            RESULT => {
+                let mut results = Vec::new();
+                while offset < message.len() {
+                    let rest = &message[offset..];
+                    if rest.len() < MatchResult::LEN {
+                        return Err(UnexpectedEndOfSlice(offset + MatchResult::LEN));
+                    }
+                    results.push(MatchResult{
+                        out_of_bounds: 0 != rest[1] & 0b10,
+                        forbidden: 0 != rest[1] & 0b1,
+                        ordinal: rest[2],
+                        matched_len: rest[3],
+                        interface_index: u32_at(rest, 4),
+                        matched_prefix: address(rest, 8),
+                    });
+                    offset += MatchResult::LEN;
+                }
+                RouterRenumberingBody::Result(results)
+            },
+            SEQUENCE_NUMBER_RESET => RouterRenumberingBody::SequenceNumberReset,
+            _ => return Ok(None),
+        };
+
+        Ok(Some(RouterRenumberingMessage{
+            sequence_number: u32_at(message, 4),
+            segment_number: message[8],
+            test_command: 0 != message[9] & 0x80,
+            result_requested: 0 != message[9] & 0x40,
+            all_interfaces: 0 != message[9] & 0x20,
+            site_specific: 0 != message[9] & 0x10,
+            processed_previously: 0 != message[9] & 0x08,
+            max_delay: u16::from_be_bytes([message[10], message[11]]),
+            body,
+        }))
+    }
+
+    /// Code value of the message.
+    pub fn code(&self) -> u8 {
+        use router_renumbering_code::*;
+        match self.body {
+            RouterRenumberingBody::Command(_) => COMMAND,
+            RouterRenumberingBody::Result(_) => RESULT,
+            RouterRenumberingBody::SequenceNumberReset => SEQUENCE_NUMBER_RESET,
+        }
+    }
+
+    /// Length of the serialized message including the ICMPv6 header.
+    pub fn header_len(&self) -> usize {
+        RouterRenumberingMessage::MIN_LEN + match &self.body {
+            RouterRenumberingBody::Command(operations) => {
+                operations.iter().map(|operation| operation.header_len()).sum()
+            },
+            RouterRenumberingBody::Result(results) => results.len()*MatchResult::LEN,
+            RouterRenumberingBody::SequenceNumberReset => 0,
+        }
+    }
+
+    /// Writes the message (including the ICMPv6 header with the checksum
+    /// set to 0) to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized message including the ICMPv6 header with the
+    /// checksum field set to 0 (the checksum can be calculated via
+    /// [`super::checksum`]).
+    ///
+    /// Returns a [`ValueError::Icmpv6RouterRenumberingTooManyUsePrefixes`]
+    /// error if a prefix control operation contains more then 63 use-prefix
+    /// parts.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        let mut result = Vec::with_capacity(self.header_len());
+        result.extend_from_slice(&[icmpv6_type::ROUTER_RENUMBERING, self.code(), 0, 0]);
+        result.extend_from_slice(&self.sequence_number.to_be_bytes());
+        result.push(self.segment_number);
+        result.push(
+            if self.test_command { 0x80 } else { 0 } |
+            if self.result_requested { 0x40 } else { 0 } |
+            if self.all_interfaces { 0x20 } else { 0 } |
+            if self.site_specific { 0x10 } else { 0 } |
+            if self.processed_previously { 0x08 } else { 0 }
+        );
+        result.extend_from_slice(&self.max_delay.to_be_bytes());
+        result.extend_from_slice(&[0;4]);
+        match &self.body {
+            RouterRenumberingBody::Command(operations) => {
+                for operation in operations {
+                    if operation.use_prefixes.len() > PrefixControlOperation::MAX_USE_PREFIXES {
+                        return Err(ValueError::Icmpv6RouterRenumberingTooManyUsePrefixes(operation.use_prefixes.len()));
+                    }
+                    result.push(operation.op_code);
+                    result.push((operation.header_len() / 8) as u8);
+                    result.push(operation.ordinal);
+                    result.push(operation.match_len);
+                    result.push(operation.min_len);
+                    result.push(operation.max_len);
+                    result.extend_from_slice(&[0, 0]);
+                    result.extend_from_slice(&operation.match_prefix);
+                    for part in &operation.use_prefixes {
+                        result.push(part.use_len);
+                        result.push(part.keep_len);
+                        result.push(part.flag_mask);
+                        result.push(part.ra_flags);
+                        result.extend_from_slice(&part.valid_lifetime.to_be_bytes());
+                        result.extend_from_slice(&part.preferred_lifetime.to_be_bytes());
+                        result.push(
+                            if part.decrement_valid_lifetime { 0x80 } else { 0 } |
+                            if part.decrement_preferred_lifetime { 0x40 } else { 0 }
+                        );
+                        result.extend_from_slice(&[0;3]);
+                        result.extend_from_slice(&part.use_prefix);
+                    }
+                }
+            },
+            RouterRenumberingBody::Result(results) => {
+                for value in results {
+                    result.push(0);
+                    result.push(
+                        if value.out_of_bounds { 0b10 } else { 0 } |
+                        if value.forbidden { 0b1 } else { 0 }
+                    );
+                    result.push(value.ordinal);
+                    result.push(value.matched_len);
+                    result.extend_from_slice(&value.interface_index.to_be_bytes());
+                    result.extend_from_slice(&value.matched_prefix);
+                }
+            },
+            RouterRenumberingBody::SequenceNumberReset => {},
+        }
+        Ok(result)
+    }
+}
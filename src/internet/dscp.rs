@@ -0,0 +1,108 @@
+use super::super::*;
+
+use std::convert::TryFrom;
+
+/// Differentiated services code point (RFC 2474) stored in the upper 6
+/// bits of the IPv4 "type of service" & the IPv6 "traffic class" field.
+///
+/// The value is guaranteed to fit into 6 bits. The standard code points
+/// are available as constants:
+///
+/// ```
+/// use etherparse::Dscp;
+/// use std::convert::TryFrom;
+///
+/// assert_eq!(Dscp::EF, Dscp::try_new(46).unwrap());
+/// assert_eq!(Dscp::AF41, Dscp::try_from(34).unwrap());
+/// assert!(Dscp::try_new(64).is_err());
+/// assert_eq!(10u8, Dscp::AF11.into());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Dscp(u8);
+
+impl Dscp {
+    /// Maximum value of a differentiated services code point.
+    pub const MAX_U8: u8 = 0b11_1111;
+
+    /// Class selector 0 ("default forwarding", RFC 2474).
+    pub const CS0: Dscp = Dscp(0);
+    /// Class selector 1 (RFC 2474).
+    pub const CS1: Dscp = Dscp(8);
+    /// Class selector 2 (RFC 2474).
+    pub const CS2: Dscp = Dscp(16);
+    /// Class selector 3 (RFC 2474).
+    pub const CS3: Dscp = Dscp(24);
+    /// Class selector 4 (RFC 2474).
+    pub const CS4: Dscp = Dscp(32);
+    /// Class selector 5 (RFC 2474).
+    pub const CS5: Dscp = Dscp(40);
+    /// Class selector 6 (RFC 2474).
+    pub const CS6: Dscp = Dscp(48);
+    /// Class selector 7 (RFC 2474).
+    pub const CS7: Dscp = Dscp(56);
+    /// Assured forwarding class 1, low drop probability (RFC 2597).
+    pub const AF11: Dscp = Dscp(10);
+    /// Assured forwarding class 1, medium drop probability (RFC 2597).
+    pub const AF12: Dscp = Dscp(12);
+    /// Assured forwarding class 1, high drop probability (RFC 2597).
+    pub const AF13: Dscp = Dscp(14);
+    /// Assured forwarding class 2, low drop probability (RFC 2597).
+    pub const AF21: Dscp = Dscp(18);
+    /// Assured forwarding class 2, medium drop probability (RFC 2597).
+    pub const AF22: Dscp = Dscp(20);
+    /// Assured forwarding class 2, high drop probability (RFC 2597).
+    pub const AF23: Dscp = Dscp(22);
+    /// Assured forwarding class 3, low drop probability (RFC 2597).
+    pub const AF31: Dscp = Dscp(26);
+    /// Assured forwarding class 3, medium drop probability (RFC 2597).
+    pub const AF32: Dscp = Dscp(28);
+    /// Assured forwarding class 3, high drop probability (RFC 2597).
+    pub const AF33: Dscp = Dscp(30);
+    /// Assured forwarding class 4, low drop probability (RFC 2597).
+    pub const AF41: Dscp = Dscp(34);
+    /// Assured forwarding class 4, medium drop probability (RFC 2597).
+    pub const AF42: Dscp = Dscp(36);
+    /// Assured forwarding class 4, high drop probability (RFC 2597).
+    pub const AF43: Dscp = Dscp(38);
+    /// Expedited forwarding (RFC 3246).
+    pub const EF: Dscp = Dscp(46);
+    /// Voice admit (RFC 5865).
+    pub const VOICE_ADMIT: Dscp = Dscp(44);
+    /// Lower effort (RFC 8622).
+    pub const LE: Dscp = Dscp(1);
+
+    /// Creates a code point from the given value or returns an
+    /// `Err(ValueError::U8TooLarge)` if the value does not fit into 6 bits.
+    pub fn try_new(value: u8) -> Result<Dscp, ValueError> {
+        max_check_u8(value, Dscp::MAX_U8, ErrorField::Dscp)?;
+        Ok(Dscp(value))
+    }
+
+    /// Converts the lower 6 bits of the given value to a code point.
+    #[inline]
+    pub fn from_bits(value: u8) -> Dscp {
+        Dscp(value & Dscp::MAX_U8)
+    }
+
+    /// Returns the code point as an `u8` (guaranteed to be at most [`Dscp::MAX_U8`]).
+    #[inline]
+    pub fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for Dscp {
+    type Error = ValueError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Dscp, ValueError> {
+        Dscp::try_new(value)
+    }
+}
+
+impl From<Dscp> for u8 {
+    #[inline]
+    fn from(value: Dscp) -> u8 {
+        value.0
+    }
+}
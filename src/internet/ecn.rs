@@ -0,0 +1,58 @@
+use super::super::*;
+
+use std::convert::TryFrom;
+
+/// Explicit congestion notification codepoint (RFC 3168).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Ecn {
+    /// Not ECN-Capable Transport.
+    #[default]
+    NotEct = 0,
+    /// ECN Capable Transport ECT(1) (used by L4S, RFC 9331).
+    Ect1 = 1,
+    /// ECN Capable Transport ECT(0).
+    Ect0 = 2,
+    /// Congestion Experienced.
+    Ce = 3,
+}
+
+impl Ecn {
+    /// Converts the lower 2 bits of the given value to an ECN codepoint.
+    pub fn from_bits(value: u8) -> Ecn {
+        match value & 0b11 {
+            0 => Ecn::NotEct,
+            1 => Ecn::Ect1,
+            2 => Ecn::Ect0,
+            _ => Ecn::Ce,
+        }
+    }
+
+    /// Returns true if the codepoint signals an ECN capable transport
+    /// (ECT(0), ECT(1) or CE).
+    pub fn is_ect(self) -> bool {
+        Ecn::NotEct != self
+    }
+
+    /// Creates a codepoint from the given value or returns an
+    /// `Err(ValueError::U8TooLarge)` if the value does not fit into 2 bits.
+    pub fn try_new(value: u8) -> Result<Ecn, ValueError> {
+        max_check_u8(value, 0b11, ErrorField::Ecn)?;
+        Ok(Ecn::from_bits(value))
+    }
+}
+
+impl TryFrom<u8> for Ecn {
+    type Error = ValueError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Ecn, ValueError> {
+        Ecn::try_new(value)
+    }
+}
+
+impl From<Ecn> for u8 {
+    #[inline]
+    fn from(value: Ecn) -> u8 {
+        value as u8
+    }
+}
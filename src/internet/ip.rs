@@ -1,5 +1,8 @@
 use super::super::*;
 
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
 ///Internet protocol headers version 4 & 6
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum IpHeader {
@@ -97,6 +100,24 @@ impl IpHeader {
         }
     }
 
+    /// Returns the source address of the ipv4 or ipv6 header.
+    pub fn source_addr(&self) -> IpAddr {
+        use crate::IpHeader::*;
+        match self {
+            Version4(header, _) => IpAddr::V4(header.source_addr()),
+            Version6(header, _) => IpAddr::V6(header.source_addr()),
+        }
+    }
+
+    /// Returns the destination address of the ipv4 or ipv6 header.
+    pub fn destination_addr(&self) -> IpAddr {
+        use crate::IpHeader::*;
+        match self {
+            Version4(header, _) => IpAddr::V4(header.destination_addr()),
+            Version6(header, _) => IpAddr::V6(header.destination_addr()),
+        }
+    }
+
     /// Returns the last next header number following the ip header
     /// and header extensions.
     pub fn next_header(&self) -> Result<u8, ValueError> {
@@ -152,6 +173,19 @@ pub type IpTrafficClass = IpNumber;
 /// Identifiers for the next_header field in ipv6 headers and protocol field in ipv4 headers.
 ///
 /// `u8` contants of the ip numbers can be found in the module [`ip_number`].
+/// Raw values can be converted via `TryFrom<u8>` (or [`IpNumber::from_u8`]).
+///
+/// ```
+/// use etherparse::IpNumber;
+/// use std::convert::TryFrom;
+///
+/// let ip_number = IpNumber::try_from(44).unwrap();
+/// assert_eq!(IpNumber::IPv6FragmentationHeader, ip_number);
+/// assert!(ip_number.is_ipv6_extension_header());
+/// assert_eq!(Some("IPv6-Frag"), ip_number.keyword());
+/// assert_eq!("Fragment Header for IPv6", ip_number.protocol_str());
+/// assert_eq!("IPv6-Frag", ip_number.to_string());
+/// ```
 ///
 /// The list was extracted from <https://www.iana.org/assignments/protocol-numbers/protocol-numbers.xhtml>
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -354,6 +388,8 @@ pub enum IpNumber {
     EtherIp = 97,
     ///Encapsulation Header \[[RFC1241](https://datatracker.ietf.org/doc/html/rfc1241)\]\[Robert_Woodburn\]
     Encap = 98,
+    ///any private encryption scheme \[Internet_Assigned_Numbers_Authority\]
+    AnyPrivateEncryptionScheme = 99,
     ///GMTP \[\[RXB5\]\]
     Gmtp = 100,
     ///Ipsilon Flow Management Protocol \[Bob_Hinden\]\[November 1995, 1997.\]
@@ -440,10 +476,18 @@ pub enum IpNumber {
     Wesp = 141,
     ///Robust Header Compression \[[RFC5858](https://datatracker.ietf.org/doc/html/rfc5858)\]
     Rohc = 142,
+    ///Ethernet \[[RFC8986](https://datatracker.ietf.org/doc/html/rfc8986)\]
+    Ethernet = 143,
+    ///AGGFRAG encapsulation payload for ESP \[[RFC9347](https://datatracker.ietf.org/doc/html/rfc9347)\]
+    Aggfrag = 144,
+    ///Network Service Header \[[RFC9491](https://datatracker.ietf.org/doc/html/rfc9491)\]
+    Nsh = 145,
     ///Use for experimentation and testing
     ExperimentalAndTesting0 = 253,
     ///Use for experimentation and testing
-    ExperimentalAndTesting1 = 254
+    ExperimentalAndTesting1 = 254,
+    ///Reserved \[Internet_Assigned_Numbers_Authority\]
+    Reserved = 255,
 }
 
 impl IpNumber {
@@ -457,9 +501,674 @@ impl IpNumber {
             | IPV6_DEST_OPTIONS | MOBILITY | HIP | SHIM6 | EXP0 | EXP1
         )
     }
+
+    /// All assigned ip numbers (sorted by their raw value).
+    pub const ALL: [IpNumber;149] = [
+        IpNumber::IPv6HeaderHopByHop,
+        IpNumber::Icmp,
+        IpNumber::Igmp,
+        IpNumber::Ggp,
+        IpNumber::IPv4,
+        IpNumber::Stream,
+        IpNumber::Tcp,
+        IpNumber::Cbt,
+        IpNumber::Egp,
+        IpNumber::Igp,
+        IpNumber::BbnRccMon,
+        IpNumber::NvpII,
+        IpNumber::Pup,
+        IpNumber::Argus,
+        IpNumber::Emcon,
+        IpNumber::Xnet,
+        IpNumber::Chaos,
+        IpNumber::Udp,
+        IpNumber::Mux,
+        IpNumber::DcnMeas,
+        IpNumber::Hmp,
+        IpNumber::Prm,
+        IpNumber::XnsIdp,
+        IpNumber::Trunk1,
+        IpNumber::Trunk2,
+        IpNumber::Leaf1,
+        IpNumber::Leaf2,
+        IpNumber::Rdp,
+        IpNumber::Irtp,
+        IpNumber::IsoTp4,
+        IpNumber::NetBlt,
+        IpNumber::MfeNsp,
+        IpNumber::MeritInp,
+        IpNumber::Dccp,
+        IpNumber::ThirdPartyConnectProtocol,
+        IpNumber::Idpr,
+        IpNumber::Xtp,
+        IpNumber::Ddp,
+        IpNumber::IdprCmtp,
+        IpNumber::TpPlusPlus,
+        IpNumber::Il,
+        IpNumber::Ipv6,
+        IpNumber::Sdrp,
+        IpNumber::IPv6RouteHeader,
+        IpNumber::IPv6FragmentationHeader,
+        IpNumber::Idrp,
+        IpNumber::Rsvp,
+        IpNumber::Gre,
+        IpNumber::Dsr,
+        IpNumber::Bna,
+        IpNumber::EncapsulatingSecurityPayload,
+        IpNumber::AuthenticationHeader,
+        IpNumber::Inlsp,
+        IpNumber::Swipe,
+        IpNumber::Narp,
+        IpNumber::Mobile,
+        IpNumber::Tlsp,
+        IpNumber::Skip,
+        IpNumber::IPv6Icmp,
+        IpNumber::IPv6NoNextHeader,
+        IpNumber::IPv6DestinationOptions,
+        IpNumber::AnyHostInternalProtocol,
+        IpNumber::Cftp,
+        IpNumber::AnyLocalNetwork,
+        IpNumber::SatExpak,
+        IpNumber::Krytolan,
+        IpNumber::Rvd,
+        IpNumber::Ippc,
+        IpNumber::AnyDistributedFileSystem,
+        IpNumber::SatMon,
+        IpNumber::Visa,
+        IpNumber::Ipcv,
+        IpNumber::Cpnx,
+        IpNumber::Cphb,
+        IpNumber::Wsn,
+        IpNumber::Pvp,
+        IpNumber::BrSatMon,
+        IpNumber::SunNd,
+        IpNumber::WbMon,
+        IpNumber::WbExpak,
+        IpNumber::IsoIp,
+        IpNumber::Vmtp,
+        IpNumber::SecureVmtp,
+        IpNumber::Vines,
+        IpNumber::TtpOrIptm,
+        IpNumber::NsfnetIgp,
+        IpNumber::Dgp,
+        IpNumber::Tcf,
+        IpNumber::Eigrp,
+        IpNumber::Ospfigp,
+        IpNumber::SpriteRpc,
+        IpNumber::Larp,
+        IpNumber::Mtp,
+        IpNumber::Ax25,
+        IpNumber::Ipip,
+        IpNumber::Micp,
+        IpNumber::SccSp,
+        IpNumber::EtherIp,
+        IpNumber::Encap,
+        IpNumber::AnyPrivateEncryptionScheme,
+        IpNumber::Gmtp,
+        IpNumber::Ifmp,
+        IpNumber::Pnni,
+        IpNumber::Pim,
+        IpNumber::Aris,
+        IpNumber::Scps,
+        IpNumber::Qnx,
+        IpNumber::ActiveNetworks,
+        IpNumber::IpComp,
+        IpNumber::SitraNetworksProtocol,
+        IpNumber::CompaqPeer,
+        IpNumber::IpxInIp,
+        IpNumber::Vrrp,
+        IpNumber::Pgm,
+        IpNumber::AnyZeroHopProtocol,
+        IpNumber::Layer2TunnelingProtocol,
+        IpNumber::Ddx,
+        IpNumber::Iatp,
+        IpNumber::Stp,
+        IpNumber::Srp,
+        IpNumber::Uti,
+        IpNumber::SimpleMessageProtocol,
+        IpNumber::Sm,
+        IpNumber::Ptp,
+        IpNumber::IsisOverIpv4,
+        IpNumber::Fire,
+        IpNumber::Crtp,
+        IpNumber::Crudp,
+        IpNumber::Sscopmce,
+        IpNumber::Iplt,
+        IpNumber::Sps,
+        IpNumber::Pipe,
+        IpNumber::Sctp,
+        IpNumber::Fc,
+        IpNumber::RsvpE2eIgnore,
+        IpNumber::MobilityHeader,
+        IpNumber::UdpLite,
+        IpNumber::MplsInIp,
+        IpNumber::Manet,
+        IpNumber::Hip,
+        IpNumber::Shim6,
+        IpNumber::Wesp,
+        IpNumber::Rohc,
+        IpNumber::Ethernet,
+        IpNumber::Aggfrag,
+        IpNumber::Nsh,
+        IpNumber::ExperimentalAndTesting0,
+        IpNumber::ExperimentalAndTesting1,
+        IpNumber::Reserved,
+    ];
+
+    /// Returns the ip number with the given raw value or `None` if the
+    /// value is not assigned.
+    #[inline]
+    pub fn from_u8(value: u8) -> Option<IpNumber> {
+        IpNumber::try_from(value).ok()
+    }
+
+    /// Returns true if the ip number identifies an IPV6 extension header.
+    #[inline]
+    pub fn is_ipv6_extension_header(self) -> bool {
+        IpNumber::is_ipv6_ext_header_value(self as u8)
+    }
+
+    /// Returns the keyword of the ip number as listed by the IANA (e.g.
+    /// "TCP" or "IPv6-Frag").
+    ///
+    /// `None` is returned for the ip numbers without a keyword (e.g. any
+    /// host internal protocol or the numbers used for experimentation &
+    /// testing).
+    pub fn keyword(self) -> Option<&'static str> {
+        use self::IpNumber::*;
+        match self {
+            IPv6HeaderHopByHop => Some("HOPOPT"),
+            Icmp => Some("ICMP"),
+            Igmp => Some("IGMP"),
+            Ggp => Some("GGP"),
+            IPv4 => Some("IPv4"),
+            Stream => Some("ST"),
+            Tcp => Some("TCP"),
+            Cbt => Some("CBT"),
+            Egp => Some("EGP"),
+            Igp => Some("IGP"),
+            BbnRccMon => Some("BBN-RCC-MON"),
+            NvpII => Some("NVP-II"),
+            Pup => Some("PUP"),
+            Argus => Some("ARGUS"),
+            Emcon => Some("EMCON"),
+            Xnet => Some("XNET"),
+            Chaos => Some("CHAOS"),
+            Udp => Some("UDP"),
+            Mux => Some("MUX"),
+            DcnMeas => Some("DCN-MEAS"),
+            Hmp => Some("HMP"),
+            Prm => Some("PRM"),
+            XnsIdp => Some("XNS-IDP"),
+            Trunk1 => Some("TRUNK-1"),
+            Trunk2 => Some("TRUNK-2"),
+            Leaf1 => Some("LEAF-1"),
+            Leaf2 => Some("LEAF-2"),
+            Rdp => Some("RDP"),
+            Irtp => Some("IRTP"),
+            IsoTp4 => Some("ISO-TP4"),
+            NetBlt => Some("NETBLT"),
+            MfeNsp => Some("MFE-NSP"),
+            MeritInp => Some("MERIT-INP"),
+            Dccp => Some("DCCP"),
+            ThirdPartyConnectProtocol => Some("3PC"),
+            Idpr => Some("IDPR"),
+            Xtp => Some("XTP"),
+            Ddp => Some("DDP"),
+            IdprCmtp => Some("IDPR-CMTP"),
+            TpPlusPlus => Some("TP++"),
+            Il => Some("IL"),
+            Ipv6 => Some("IPv6"),
+            Sdrp => Some("SDRP"),
+            IPv6RouteHeader => Some("IPv6-Route"),
+            IPv6FragmentationHeader => Some("IPv6-Frag"),
+            Idrp => Some("IDRP"),
+            Rsvp => Some("RSVP"),
+            Gre => Some("GRE"),
+            Dsr => Some("DSR"),
+            Bna => Some("BNA"),
+            EncapsulatingSecurityPayload => Some("ESP"),
+            AuthenticationHeader => Some("AH"),
+            Inlsp => Some("I-NLSP"),
+            Swipe => Some("SWIPE"),
+            Narp => Some("NARP"),
+            Mobile => Some("MOBILE"),
+            Tlsp => Some("TLSP"),
+            Skip => Some("SKIP"),
+            IPv6Icmp => Some("IPv6-ICMP"),
+            IPv6NoNextHeader => Some("IPv6-NoNxt"),
+            IPv6DestinationOptions => Some("IPv6-Opts"),
+            Cftp => Some("CFTP"),
+            SatExpak => Some("SAT-EXPAK"),
+            Krytolan => Some("KRYPTOLAN"),
+            Rvd => Some("RVD"),
+            Ippc => Some("IPPC"),
+            SatMon => Some("SAT-MON"),
+            Visa => Some("VISA"),
+            Ipcv => Some("IPCV"),
+            Cpnx => Some("CPNX"),
+            Cphb => Some("CPHB"),
+            Wsn => Some("WSN"),
+            Pvp => Some("PVP"),
+            BrSatMon => Some("BR-SAT-MON"),
+            SunNd => Some("SUN-ND"),
+            WbMon => Some("WB-MON"),
+            WbExpak => Some("WB-EXPAK"),
+            IsoIp => Some("ISO-IP"),
+            Vmtp => Some("VMTP"),
+            SecureVmtp => Some("SECURE-VMTP"),
+            Vines => Some("VINES"),
+            TtpOrIptm => Some("TTP"),
+            NsfnetIgp => Some("NSFNET-IGP"),
+            Dgp => Some("DGP"),
+            Tcf => Some("TCF"),
+            Eigrp => Some("EIGRP"),
+            Ospfigp => Some("OSPFIGP"),
+            SpriteRpc => Some("Sprite-RPC"),
+            Larp => Some("LARP"),
+            Mtp => Some("MTP"),
+            Ax25 => Some("AX.25"),
+            Ipip => Some("IPIP"),
+            Micp => Some("MICP"),
+            SccSp => Some("SCC-SP"),
+            EtherIp => Some("ETHERIP"),
+            Encap => Some("ENCAP"),
+            Gmtp => Some("GMTP"),
+            Ifmp => Some("IFMP"),
+            Pnni => Some("PNNI"),
+            Pim => Some("PIM"),
+            Aris => Some("ARIS"),
+            Scps => Some("SCPS"),
+            Qnx => Some("QNX"),
+            ActiveNetworks => Some("A/N"),
+            IpComp => Some("IPComp"),
+            SitraNetworksProtocol => Some("SNP"),
+            CompaqPeer => Some("Compaq-Peer"),
+            IpxInIp => Some("IPX-in-IP"),
+            Vrrp => Some("VRRP"),
+            Pgm => Some("PGM"),
+            Layer2TunnelingProtocol => Some("L2TP"),
+            Ddx => Some("DDX"),
+            Iatp => Some("IATP"),
+            Stp => Some("STP"),
+            Srp => Some("SRP"),
+            Uti => Some("UTI"),
+            SimpleMessageProtocol => Some("SMP"),
+            Sm => Some("SM"),
+            Ptp => Some("PTP"),
+            IsisOverIpv4 => Some("ISIS over IPv4"),
+            Fire => Some("FIRE"),
+            Crtp => Some("CRTP"),
+            Crudp => Some("CRUDP"),
+            Sscopmce => Some("SSCOPMCE"),
+            Iplt => Some("IPLT"),
+            Sps => Some("SPS"),
+            Pipe => Some("PIPE"),
+            Sctp => Some("SCTP"),
+            Fc => Some("FC"),
+            RsvpE2eIgnore => Some("RSVP-E2E-IGNORE"),
+            MobilityHeader => Some("Mobility Header"),
+            UdpLite => Some("UDPLite"),
+            MplsInIp => Some("MPLS-in-IP"),
+            Manet => Some("manet"),
+            Hip => Some("HIP"),
+            Shim6 => Some("Shim6"),
+            Wesp => Some("WESP"),
+            Rohc => Some("ROHC"),
+            Ethernet => Some("Ethernet"),
+            Aggfrag => Some("AGGFRAG"),
+            Nsh => Some("NSH"),
+            Reserved => Some("Reserved"),
+            AnyHostInternalProtocol |
+            AnyLocalNetwork |
+            AnyDistributedFileSystem |
+            AnyPrivateEncryptionScheme |
+            AnyZeroHopProtocol |
+            ExperimentalAndTesting0 |
+            ExperimentalAndTesting1 => None,
+        }
+    }
+
+    /// Returns the name of the protocol as listed by the IANA (e.g.
+    /// "Transmission Control" or "Fragment Header for IPv6").
+    pub fn protocol_str(self) -> &'static str {
+        use self::IpNumber::*;
+        match self {
+            IPv6HeaderHopByHop => "IPv6 Hop-by-Hop Option",
+            Icmp => "Internet Control Message",
+            Igmp => "Internet Group Management",
+            Ggp => "Gateway-to-Gateway",
+            IPv4 => "IPv4 encapsulation",
+            Stream => "Stream",
+            Tcp => "Transmission Control",
+            Cbt => "CBT",
+            Egp => "Exterior Gateway Protocol",
+            Igp => "any private interior gateway (used by Cisco for their IGRP)",
+            BbnRccMon => "BBN RCC Monitoring",
+            NvpII => "Network Voice Protocol",
+            Pup => "PUP",
+            Argus => "ARGUS",
+            Emcon => "EMCON",
+            Xnet => "Cross Net Debugger",
+            Chaos => "Chaos",
+            Udp => "User Datagram",
+            Mux => "Multiplexing",
+            DcnMeas => "DCN Measurement Subsystems",
+            Hmp => "Host Monitoring",
+            Prm => "Packet Radio Measurement",
+            XnsIdp => "XEROX NS IDP",
+            Trunk1 => "Trunk-1",
+            Trunk2 => "Trunk-2",
+            Leaf1 => "Leaf-1",
+            Leaf2 => "Leaf-2",
+            Rdp => "Reliable Data Protocol",
+            Irtp => "Internet Reliable Transaction",
+            IsoTp4 => "ISO Transport Protocol Class 4",
+            NetBlt => "Bulk Data Transfer Protocol",
+            MfeNsp => "MFE Network Services Protocol",
+            MeritInp => "MERIT Internodal Protocol",
+            Dccp => "Datagram Congestion Control Protocol",
+            ThirdPartyConnectProtocol => "Third Party Connect Protocol",
+            Idpr => "Inter-Domain Policy Routing Protocol",
+            Xtp => "XTP",
+            Ddp => "Datagram Delivery Protocol",
+            IdprCmtp => "IDPR Control Message Transport Proto",
+            TpPlusPlus => "TP++ Transport Protocol",
+            Il => "IL Transport Protocol",
+            Ipv6 => "IPv6 encapsulation",
+            Sdrp => "Source Demand Routing Protocol",
+            IPv6RouteHeader => "Routing Header for IPv6",
+            IPv6FragmentationHeader => "Fragment Header for IPv6",
+            Idrp => "Inter-Domain Routing Protocol",
+            Rsvp => "Reservation Protocol",
+            Gre => "Generic Routing Encapsulation",
+            Dsr => "Dynamic Source Routing Protocol",
+            Bna => "BNA",
+            EncapsulatingSecurityPayload => "Encap Security Payload",
+            AuthenticationHeader => "Authentication Header",
+            Inlsp => "Integrated Net Layer Security TUBA",
+            Swipe => "IP with Encryption",
+            Narp => "NBMA Address Resolution Protocol",
+            Mobile => "IP Mobility",
+            Tlsp => "Transport Layer Security Protocol using Kryptonet key management",
+            Skip => "SKIP",
+            IPv6Icmp => "ICMP for IPv6",
+            IPv6NoNextHeader => "No Next Header for IPv6",
+            IPv6DestinationOptions => "Destination Options for IPv6",
+            AnyHostInternalProtocol => "any host internal protocol",
+            Cftp => "CFTP",
+            AnyLocalNetwork => "any local network",
+            SatExpak => "SATNET and Backroom EXPAK",
+            Krytolan => "Kryptolan",
+            Rvd => "MIT Remote Virtual Disk Protocol",
+            Ippc => "Internet Pluribus Packet Core",
+            AnyDistributedFileSystem => "any distributed file system",
+            SatMon => "SATNET Monitoring",
+            Visa => "VISA Protocol",
+            Ipcv => "Internet Packet Core Utility",
+            Cpnx => "Computer Protocol Network Executive",
+            Cphb => "Computer Protocol Heart Beat",
+            Wsn => "Wang Span Network",
+            Pvp => "Packet Video Protocol",
+            BrSatMon => "Backroom SATNET Monitoring",
+            SunNd => "SUN ND PROTOCOL-Temporary",
+            WbMon => "WIDEBAND Monitoring",
+            WbExpak => "WIDEBAND EXPAK",
+            IsoIp => "ISO Internet Protocol",
+            Vmtp => "VMTP",
+            SecureVmtp => "SECURE-VMTP",
+            Vines => "VINES",
+            TtpOrIptm => "Transaction Transport Protocol",
+            NsfnetIgp => "NSFNET-IGP",
+            Dgp => "Dissimilar Gateway Protocol",
+            Tcf => "TCF",
+            Eigrp => "EIGRP",
+            Ospfigp => "OSPFIGP",
+            SpriteRpc => "Sprite RPC Protocol",
+            Larp => "Locus Address Resolution Protocol",
+            Mtp => "Multicast Transport Protocol",
+            Ax25 => "AX.25 Frames",
+            Ipip => "IP-within-IP Encapsulation Protocol",
+            Micp => "Mobile Internetworking Control Pro.",
+            SccSp => "Semaphore Communications Sec. Pro.",
+            EtherIp => "Ethernet-within-IP Encapsulation",
+            Encap => "Encapsulation Header",
+            AnyPrivateEncryptionScheme => "any private encryption scheme",
+            Gmtp => "GMTP",
+            Ifmp => "Ipsilon Flow Management Protocol",
+            Pnni => "PNNI over IP",
+            Pim => "Protocol Independent Multicast",
+            Aris => "ARIS",
+            Scps => "SCPS",
+            Qnx => "QNX",
+            ActiveNetworks => "Active Networks",
+            IpComp => "IP Payload Compression Protocol",
+            SitraNetworksProtocol => "Sitara Networks Protocol",
+            CompaqPeer => "Compaq Peer Protocol",
+            IpxInIp => "IPX in IP",
+            Vrrp => "Virtual Router Redundancy Protocol",
+            Pgm => "PGM Reliable Transport Protocol",
+            AnyZeroHopProtocol => "any 0-hop protocol",
+            Layer2TunnelingProtocol => "Layer Two Tunneling Protocol",
+            Ddx => "D-II Data Exchange (DDX)",
+            Iatp => "Interactive Agent Transfer Protocol",
+            Stp => "Schedule Transfer Protocol",
+            Srp => "SpectraLink Radio Protocol",
+            Uti => "UTI",
+            SimpleMessageProtocol => "Simple Message Protocol",
+            Sm => "Simple Multicast Protocol",
+            Ptp => "Performance Transparency Protocol",
+            IsisOverIpv4 => "ISIS over IPv4",
+            Fire => "FIRE",
+            Crtp => "Combat Radio Transport Protocol",
+            Crudp => "Combat Radio User Datagram",
+            Sscopmce => "SSCOPMCE",
+            Iplt => "IPLT",
+            Sps => "Secure Packet Shield",
+            Pipe => "Private IP Encapsulation within IP",
+            Sctp => "Stream Control Transmission Protocol",
+            Fc => "Fibre Channel",
+            RsvpE2eIgnore => "RSVP-E2E-IGNORE",
+            MobilityHeader => "Mobility Header",
+            UdpLite => "UDPLite",
+            MplsInIp => "MPLS-in-IP",
+            Manet => "MANET Protocols",
+            Hip => "Host Identity Protocol",
+            Shim6 => "Shim6 Protocol",
+            Wesp => "Wrapped Encapsulating Security Payload",
+            Rohc => "Robust Header Compression",
+            Ethernet => "Ethernet",
+            Aggfrag => "AGGFRAG encapsulation payload for ESP",
+            Nsh => "Network Service Header",
+            ExperimentalAndTesting0 => "Use for experimentation and testing",
+            ExperimentalAndTesting1 => "Use for experimentation and testing",
+            Reserved => "Reserved",
+        }
+    }
+}
+
+impl TryFrom<u8> for IpNumber {
+    type Error = UnknownIpNumberError;
+
+    fn try_from(value: u8) -> Result<IpNumber, UnknownIpNumberError> {
+        use self::IpNumber::*;
+        match value {
+            0 => Ok(IPv6HeaderHopByHop),
+            1 => Ok(Icmp),
+            2 => Ok(Igmp),
+            3 => Ok(Ggp),
+            4 => Ok(IPv4),
+            5 => Ok(Stream),
+            6 => Ok(Tcp),
+            7 => Ok(Cbt),
+            8 => Ok(Egp),
+            9 => Ok(Igp),
+            10 => Ok(BbnRccMon),
+            11 => Ok(NvpII),
+            12 => Ok(Pup),
+            13 => Ok(Argus),
+            14 => Ok(Emcon),
+            15 => Ok(Xnet),
+            16 => Ok(Chaos),
+            17 => Ok(Udp),
+            18 => Ok(Mux),
+            19 => Ok(DcnMeas),
+            20 => Ok(Hmp),
+            21 => Ok(Prm),
+            22 => Ok(XnsIdp),
+            23 => Ok(Trunk1),
+            24 => Ok(Trunk2),
+            25 => Ok(Leaf1),
+            26 => Ok(Leaf2),
+            27 => Ok(Rdp),
+            28 => Ok(Irtp),
+            29 => Ok(IsoTp4),
+            30 => Ok(NetBlt),
+            31 => Ok(MfeNsp),
+            32 => Ok(MeritInp),
+            33 => Ok(Dccp),
+            34 => Ok(ThirdPartyConnectProtocol),
+            35 => Ok(Idpr),
+            36 => Ok(Xtp),
+            37 => Ok(Ddp),
+            38 => Ok(IdprCmtp),
+            39 => Ok(TpPlusPlus),
+            40 => Ok(Il),
+            41 => Ok(Ipv6),
+            42 => Ok(Sdrp),
+            43 => Ok(IPv6RouteHeader),
+            44 => Ok(IPv6FragmentationHeader),
+            45 => Ok(Idrp),
+            46 => Ok(Rsvp),
+            47 => Ok(Gre),
+            48 => Ok(Dsr),
+            49 => Ok(Bna),
+            50 => Ok(EncapsulatingSecurityPayload),
+            51 => Ok(AuthenticationHeader),
+            52 => Ok(Inlsp),
+            53 => Ok(Swipe),
+            54 => Ok(Narp),
+            55 => Ok(Mobile),
+            56 => Ok(Tlsp),
+            57 => Ok(Skip),
+            58 => Ok(IPv6Icmp),
+            59 => Ok(IPv6NoNextHeader),
+            60 => Ok(IPv6DestinationOptions),
+            61 => Ok(AnyHostInternalProtocol),
+            62 => Ok(Cftp),
+            63 => Ok(AnyLocalNetwork),
+            64 => Ok(SatExpak),
+            65 => Ok(Krytolan),
+            66 => Ok(Rvd),
+            67 => Ok(Ippc),
+            68 => Ok(AnyDistributedFileSystem),
+            69 => Ok(SatMon),
+            70 => Ok(Visa),
+            71 => Ok(Ipcv),
+            72 => Ok(Cpnx),
+            73 => Ok(Cphb),
+            74 => Ok(Wsn),
+            75 => Ok(Pvp),
+            76 => Ok(BrSatMon),
+            77 => Ok(SunNd),
+            78 => Ok(WbMon),
+            79 => Ok(WbExpak),
+            80 => Ok(IsoIp),
+            81 => Ok(Vmtp),
+            82 => Ok(SecureVmtp),
+            83 => Ok(Vines),
+            84 => Ok(TtpOrIptm),
+            85 => Ok(NsfnetIgp),
+            86 => Ok(Dgp),
+            87 => Ok(Tcf),
+            88 => Ok(Eigrp),
+            89 => Ok(Ospfigp),
+            90 => Ok(SpriteRpc),
+            91 => Ok(Larp),
+            92 => Ok(Mtp),
+            93 => Ok(Ax25),
+            94 => Ok(Ipip),
+            95 => Ok(Micp),
+            96 => Ok(SccSp),
+            97 => Ok(EtherIp),
+            98 => Ok(Encap),
+            99 => Ok(AnyPrivateEncryptionScheme),
+            100 => Ok(Gmtp),
+            101 => Ok(Ifmp),
+            102 => Ok(Pnni),
+            103 => Ok(Pim),
+            104 => Ok(Aris),
+            105 => Ok(Scps),
+            106 => Ok(Qnx),
+            107 => Ok(ActiveNetworks),
+            108 => Ok(IpComp),
+            109 => Ok(SitraNetworksProtocol),
+            110 => Ok(CompaqPeer),
+            111 => Ok(IpxInIp),
+            112 => Ok(Vrrp),
+            113 => Ok(Pgm),
+            114 => Ok(AnyZeroHopProtocol),
+            115 => Ok(Layer2TunnelingProtocol),
+            116 => Ok(Ddx),
+            117 => Ok(Iatp),
+            118 => Ok(Stp),
+            119 => Ok(Srp),
+            120 => Ok(Uti),
+            121 => Ok(SimpleMessageProtocol),
+            122 => Ok(Sm),
+            123 => Ok(Ptp),
+            124 => Ok(IsisOverIpv4),
+            125 => Ok(Fire),
+            126 => Ok(Crtp),
+            127 => Ok(Crudp),
+            128 => Ok(Sscopmce),
+            129 => Ok(Iplt),
+            130 => Ok(Sps),
+            131 => Ok(Pipe),
+            132 => Ok(Sctp),
+            133 => Ok(Fc),
+            134 => Ok(RsvpE2eIgnore),
+            135 => Ok(MobilityHeader),
+            136 => Ok(UdpLite),
+            137 => Ok(MplsInIp),
+            138 => Ok(Manet),
+            139 => Ok(Hip),
+            140 => Ok(Shim6),
+            141 => Ok(Wesp),
+            142 => Ok(Rohc),
+            143 => Ok(Ethernet),
+            144 => Ok(Aggfrag),
+            145 => Ok(Nsh),
+            253 => Ok(ExperimentalAndTesting0),
+            254 => Ok(ExperimentalAndTesting1),
+            255 => Ok(Reserved),
+            value => Err(UnknownIpNumberError(value)),
+        }
+    }
+}
+
+impl fmt::Display for IpNumber {
+    /// Writes the IANA keyword of the ip number (or the protocol name if
+    /// no keyword is assigned).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.keyword().unwrap_or_else(|| self.protocol_str()))
+    }
+}
+
+/// Error when converting a raw ip number value that is not assigned
+/// (the raw value as argument).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnknownIpNumberError(pub u8);
+
+impl fmt::Display for UnknownIpNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnknownIpNumberError: The ip number {} is not assigned.", self.0)
+    }
 }
 
-/// `u8` constants for the most used ip protocol numbers.
+impl Error for UnknownIpNumberError {}
+
+/// `u8` constants for all assigned ip protocol numbers.
 ///
 /// The constants only exist for convenience. You can get equivalent values by 
 /// casting the enum values of [`IpNumber`] to a u8 value.
@@ -489,28 +1198,288 @@ pub mod ip_number {
     pub const STREAM: u8 = Stream as u8; //5
     ///Transmission Control \[[RFC793](https://datatracker.ietf.org/doc/html/rfc793)\]
     pub const TCP: u8 = Tcp as u8; //6
+    ///CBT \[Tony_Ballardie\]
+    pub const CBT: u8 = Cbt as u8; //7
+    ///Exterior Gateway Protocol \[[RFC888](https://datatracker.ietf.org/doc/html/rfc888)\] \[David_Mills\]
+    pub const EGP: u8 = Egp as u8; //8
+    ///any private interior gateway (used by Cisco for their IGRP) \[Internet_Assigned_Numbers_Authority\]
+    pub const IGP: u8 = Igp as u8; //9
+    ///BBN RCC Monitoring \[Steve_Chipman\]
+    pub const BBN_RCC_MON: u8 = BbnRccMon as u8; //10
+    ///Network Voice Protocol \[[RFC741](https://datatracker.ietf.org/doc/html/rfc741)\]\[Steve_Casner\]
+    pub const NVP_II: u8 = NvpII as u8; //11
+    ///PUP
+    pub const PUP: u8 = Pup as u8; //12
+    ///ARGUS (deprecated) \[Robert_W_Scheifler\]
+    pub const ARGUS: u8 = Argus as u8; //13
+    ///EMCON \[mystery contact\]
+    pub const EMCON: u8 = Emcon as u8; //14
+    ///Cross Net Debugger \[Haverty, J., "XNET Formats for Internet Protocol Version 4", IEN 158, October 1980.\]\[Jack_Haverty\]
+    pub const XNET: u8 = Xnet as u8; //15
+    ///Chaos \[J_Noel_Chiappa\]
+    pub const CHAOS: u8 = Chaos as u8; //16
     ///User Datagram \[[RFC768](https://datatracker.ietf.org/doc/html/rfc768)\] \[Jon_Postel\]
     pub const UDP: u8 = Udp as u8; //17
+    ///Multiplexing \[Cohen, D. and J. Postel, "Multiplexing Protocol", IEN 90, USC/Information Sciences Institute, May 1979.\]\[Jon_Postel\]
+    pub const MUX: u8 = Mux as u8; //18
+    ///DCN Measurement Subsystems \[David_Mills\]
+    pub const DCN_MEAS: u8 = DcnMeas as u8; //19
+    ///Host Monitoring \[[RFC869](https://datatracker.ietf.org/doc/html/rfc869)\]\[Bob_Hinden\]
+    pub const HMP: u8 = Hmp as u8; //20
+    ///Packet Radio Measurement \[Zaw_Sing_Su\]
+    pub const PRM: u8 = Prm as u8; //21
+    ///XEROX NS IDP
+    pub const XNS_IDP: u8 = XnsIdp as u8; //22
+    ///Trunk-1 \[Barry_Boehm\]
+    pub const TRUNK1: u8 = Trunk1 as u8; //23
+    ///Trunk-2 \[Barry_Boehm\]
+    pub const TRUNK2: u8 = Trunk2 as u8; //24
+    ///Leaf-1 \[Barry_Boehm\]
+    pub const LEAF1: u8 = Leaf1 as u8; //25
+    ///Leaf-2 \[Barry_Boehm\]
+    pub const LEAF2: u8 = Leaf2 as u8; //26
+    ///Reliable Data Protocol \[[RFC908](https://datatracker.ietf.org/doc/html/rfc908)\] \[Bob_Hinden\]
+    pub const RDP: u8 = Rdp as u8; //27
+    ///Internet Reliable Transaction \[[RFC938](https://datatracker.ietf.org/doc/html/rfc938)\] \[Trudy_Miller\]
+    pub const IRTP: u8 = Irtp as u8; //28
+    ///ISO Transport Protocol Class 4 \[[RFC905](https://datatracker.ietf.org/doc/html/rfc905)\] \[<mystery contact>\]
+    pub const ISO_TP4: u8 = IsoTp4 as u8; //29
+    ///Bulk Data Transfer Protocol \[[RFC969](https://datatracker.ietf.org/doc/html/rfc969)\] \[David_Clark\]
+    pub const NET_BLT: u8 = NetBlt as u8; //30
+    ///MFE Network Services Protocol \[Shuttleworth, B., "A Documentary of MFENet, a National Computer Network", UCRL-52317, Lawrence Livermore Labs, Livermore, California, June 1977.\] \[Barry_Howard\]
+    pub const MFE_NSP: u8 = MfeNsp as u8; //31
+    ///MERIT Internodal Protocol \[Hans_Werner_Braun\]
+    pub const MERIT_INP: u8 = MeritInp as u8; //32
+    ///Datagram Congestion Control Protocol \[[RFC4340](https://datatracker.ietf.org/doc/html/rfc4340)\]
+    pub const DCCP: u8 = Dccp as u8; //33
+    ///Third Party Connect Protocol \[Stuart_A_Friedberg\]
+    pub const THIRD_PARTY_CONNECT_PROTOCOL: u8 = ThirdPartyConnectProtocol as u8; //34
+    ///Inter-Domain Policy Routing Protocol \[Martha_Steenstrup\]
+    pub const IDPR: u8 = Idpr as u8; //35
+    ///XTP \[Greg_Chesson\]
+    pub const XTP: u8 = Xtp as u8; //36
+    ///Datagram Delivery Protocol \[Wesley_Craig\]
+    pub const DDP: u8 = Ddp as u8; //37
+    ///IDPR Control Message Transport Proto \[Martha_Steenstrup\]
+    pub const IDPR_CMTP: u8 = IdprCmtp as u8; //38
+    ///TP++ Transport Protocol \[Dirk_Fromhein\]
+    pub const TP_PLUS_PLUS: u8 = TpPlusPlus as u8; //39
+    ///IL Transport Protocol \[Dave_Presotto\]
+    pub const IL: u8 = Il as u8; //40
     ///IPv6 encapsulation \[[RFC2473](https://datatracker.ietf.org/doc/html/rfc2473)\]
     pub const IPV6: u8 = Ipv6 as u8; //41
+    ///Source Demand Routing Protocol \[Deborah_Estrin\]
+    pub const SDRP: u8 = Sdrp as u8; //42
     ///Routing Header for IPv6 \[Steve_Deering\]
     pub const IPV6_ROUTE: u8 = IPv6RouteHeader as u8; //43
     ///Fragment Header for IPv6 \[Steve_Deering\]
     pub const IPV6_FRAG: u8 = IPv6FragmentationHeader as u8; //44
+    ///Inter-Domain Routing Protocol \[Sue_Hares\]
+    pub const IDRP: u8 = Idrp as u8; //45
+    ///Reservation Protocol \[[RFC2205](https://datatracker.ietf.org/doc/html/rfc2205)\]\[[RFC3209](https://datatracker.ietf.org/doc/html/rfc3209)\]\[Bob_Braden\]
+    pub const RSVP: u8 = Rsvp as u8; //46
+    ///Generic Routing Encapsulation \[[RFC2784](https://datatracker.ietf.org/doc/html/rfc2784)\]
+    pub const GRE: u8 = Gre as u8; //47
+    ///Dynamic Source Routing Protocol \[[RFC4728](https://datatracker.ietf.org/doc/html/rfc4728)\]
+    pub const DSR: u8 = Dsr as u8; //48
+    ///BNA \[Gary Salamon\]
+    pub const BNA: u8 = Bna as u8; //49
     ///Encapsulating Security Payload \[[RFC4303](https://datatracker.ietf.org/doc/html/rfc4303)\]
     pub const ENCAP_SEC: u8 = EncapsulatingSecurityPayload as u8; //50
     ///Authentication Header \[[RFC4302](https://datatracker.ietf.org/doc/html/rfc4302)\]
     pub const AUTH: u8 = AuthenticationHeader as u8; //51
+    ///Integrated Net Layer Security  TUBA \[K_Robert_Glenn\]
+    pub const INLSP: u8 = Inlsp as u8; //52
+    ///IP with Encryption (deprecated) \[John_Ioannidis\]
+    pub const SWIPE: u8 = Swipe as u8; //53
+    ///NBMA Address Resolution Protocol \[[RFC1735](https://datatracker.ietf.org/doc/html/rfc1735)\]
+    pub const NARP: u8 = Narp as u8; //54
+    ///IP Mobility \[Charlie_Perkins\]
+    pub const MOBILE: u8 = Mobile as u8; //55
+    ///Transport Layer Security Protocol using Kryptonet key management \[Christer_Oberg\]
+    pub const TLSP: u8 = Tlsp as u8; //56
+    ///SKIP \[Tom_Markson\]
+    pub const SKIP: u8 = Skip as u8; //57
+    ///ICMP for IPv6 \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
+    pub const IPV6_ICMP: u8 = IPv6Icmp as u8; //58
+    ///No Next Header for IPv6 \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
+    pub const IPV6_NO_NEXT_HEADER: u8 = IPv6NoNextHeader as u8; //59
     ///Destination Options for IPv6 \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
     pub const IPV6_DEST_OPTIONS: u8 = IPv6DestinationOptions as u8; //60
+    ///any host internal protocol \[Internet_Assigned_Numbers_Authority\]
+    pub const ANY_HOST_INTERNAL_PROTOCOL: u8 = AnyHostInternalProtocol as u8; //61
+    ///CFTP \[Forsdick, H., "CFTP", Network Message, Bolt Beranek and Newman, January 1982.\]\[Harry_Forsdick\]
+    pub const CFTP: u8 = Cftp as u8; //62
+    ///any local network \[Internet_Assigned_Numbers_Authority\]
+    pub const ANY_LOCAL_NETWORK: u8 = AnyLocalNetwork as u8; //63
+    ///SATNET and Backroom EXPAK \[Steven_Blumenthal\]
+    pub const SAT_EXPAK: u8 = SatExpak as u8; //64
+    ///Kryptolan \[Paul Liu\]
+    pub const KRYTOLAN: u8 = Krytolan as u8; //65
+    ///MIT Remote Virtual Disk Protocol \[Michael_Greenwald\]
+    pub const RVD: u8 = Rvd as u8; //66
+    ///Internet Pluribus Packet Core \[Steven_Blumenthal\]
+    pub const IPPC: u8 = Ippc as u8; //67
+    ///any distributed file system \[Internet_Assigned_Numbers_Authority\]
+    pub const ANY_DISTRIBUTED_FILE_SYSTEM: u8 = AnyDistributedFileSystem as u8; //68
+    ///SATNET Monitoring \[Steven_Blumenthal\]
+    pub const SAT_MON: u8 = SatMon as u8; //69
+    ///VISA Protocol \[Gene_Tsudik\]
+    pub const VISA: u8 = Visa as u8; //70
+    ///Internet Packet Core Utility \[Steven_Blumenthal\]
+    pub const IPCV: u8 = Ipcv as u8; //71
+    ///Computer Protocol Network Executive \[David Mittnacht\]
+    pub const CPNX: u8 = Cpnx as u8; //72
+    ///Computer Protocol Heart Beat \[David Mittnacht\]
+    pub const CPHB: u8 = Cphb as u8; //73
+    ///Wang Span Network \[Victor Dafoulas\]
+    pub const WSN: u8 = Wsn as u8; //74
+    ///Packet Video Protocol \[Steve_Casner\]
+    pub const PVP: u8 = Pvp as u8; //75
+    ///Backroom SATNET Monitoring \[Steven_Blumenthal\]
+    pub const BR_SAT_MON: u8 = BrSatMon as u8; //76
+    ///SUN ND PROTOCOL-Temporary \[William_Melohn\]
+    pub const SUN_ND: u8 = SunNd as u8; //77
+    ///WIDEBAND Monitoring \[Steven_Blumenthal\]
+    pub const WB_MON: u8 = WbMon as u8; //78
+    ///WIDEBAND EXPAK \[Steven_Blumenthal\]
+    pub const WB_EXPAK: u8 = WbExpak as u8; //79
+    ///ISO Internet Protocol \[Marshall_T_Rose\]
+    pub const ISO_IP: u8 = IsoIp as u8; //80
+    ///VMTP \[Dave_Cheriton\]
+    pub const VMTP: u8 = Vmtp as u8; //81
+    ///SECURE-VMTP \[Dave_Cheriton\]
+    pub const SECURE_VMTP: u8 = SecureVmtp as u8; //82
+    ///VINES \[Brian Horn\]
+    pub const VINES: u8 = Vines as u8; //83
+    ///Transaction Transport Protocol or Internet Protocol Traffic Manager \[Jim_Stevens\]
+    pub const TTP_OR_IPTM: u8 = TtpOrIptm as u8; //84
+    ///NSFNET-IGP \[Hans_Werner_Braun\]
+    pub const NSFNET_IGP: u8 = NsfnetIgp as u8; //85
+    ///Dissimilar Gateway Protocol \[M/A-COM Government Systems, "Dissimilar Gateway Protocol Specification, Draft Version", Contract no. CS901145, November 16, 1987.\]\[Mike_Little\]
+    pub const DGP: u8 = Dgp as u8; //86
+    ///TCF \[Guillermo_A_Loyola\]
+    pub const TCF: u8 = Tcf as u8; //87
+    ///EIGRP \[[RFC7868](https://datatracker.ietf.org/doc/html/rfc7868)\]
+    pub const EIGRP: u8 = Eigrp as u8; //88
+    ///OSPFIGP \[[RFC1583](https://datatracker.ietf.org/doc/html/rfc1583)\]\[[RFC2328](https://datatracker.ietf.org/doc/html/rfc2328)\]\[[RFC5340](https://datatracker.ietf.org/doc/html/rfc5340)\]\[John_Moy\]
+    pub const OSPFIGP: u8 = Ospfigp as u8; //89
+    ///Sprite RPC Protocol \[Welch, B., "The Sprite Remote Procedure Call System", Technical Report, UCB/Computer Science Dept., 86/302, University of California at Berkeley, June 1986.\]\[Bruce Willins\]
+    pub const SPRITE_RPC: u8 = SpriteRpc as u8; //90
+    ///Locus Address Resolution Protocol \[Brian Horn\]
+    pub const LARP: u8 = Larp as u8; //91
+    ///Multicast Transport Protocol \[Susie_Armstrong\]
+    pub const MTP: u8 = Mtp as u8; //92
+    ///AX.25 Frames \[Brian_Kantor\]
+    pub const AX25: u8 = Ax25 as u8; //93
+    ///IP-within-IP Encapsulation Protocol \[John_Ioannidis\]
+    pub const IPIP: u8 = Ipip as u8; //94
+    ///Mobile Internetworking Control Pro. (deprecated) \[John_Ioannidis\]
+    pub const MICP: u8 = Micp as u8; //95
+    ///Semaphore Communications Sec. Pro. \[Howard_Hart\]
+    pub const SCC_SP: u8 = SccSp as u8; //96
+    ///Ethernet-within-IP Encapsulation \[[RFC3378](https://datatracker.ietf.org/doc/html/rfc3378)\]
+    pub const ETHER_IP: u8 = EtherIp as u8; //97
+    ///Encapsulation Header \[[RFC1241](https://datatracker.ietf.org/doc/html/rfc1241)\]\[Robert_Woodburn\]
+    pub const ENCAP: u8 = Encap as u8; //98
+    ///any private encryption scheme \[Internet_Assigned_Numbers_Authority\]
+    pub const ANY_PRIVATE_ENCRYPTION_SCHEME: u8 = AnyPrivateEncryptionScheme as u8; //99
+    ///GMTP \[\[RXB5\]\]
+    pub const GMTP: u8 = Gmtp as u8; //100
+    ///Ipsilon Flow Management Protocol \[Bob_Hinden\]\[November 1995, 1997.\]
+    pub const IFMP: u8 = Ifmp as u8; //101
+    ///PNNI over IP \[Ross_Callon\]
+    pub const PNNI: u8 = Pnni as u8; //102
+    ///Protocol Independent Multicast \[[RFC7761](https://datatracker.ietf.org/doc/html/rfc7761)\]\[Dino_Farinacci\]
+    pub const PIM: u8 = Pim as u8; //103
+    ///ARIS \[Nancy_Feldman\]
+    pub const ARIS: u8 = Aris as u8; //104
+    ///SCPS \[Robert_Durst\]
+    pub const SCPS: u8 = Scps as u8; //105
+    ///QNX \[Michael_Hunter\]
+    pub const QNX: u8 = Qnx as u8; //106
+    ///Active Networks \[Bob_Braden\]
+    pub const ACTIVE_NETWORKS: u8 = ActiveNetworks as u8; //107
+    ///IP Payload Compression Protocol \[[RFC2393](https://datatracker.ietf.org/doc/html/rfc2393)\]
+    pub const IP_COMP: u8 = IpComp as u8; //108
+    ///Sitara Networks Protocol \[Manickam_R_Sridhar\]
+    pub const SITRA_NETWORKS_PROTOCOL: u8 = SitraNetworksProtocol as u8; //109
+    ///Compaq Peer Protocol \[Victor_Volpe\]
+    pub const COMPAQ_PEER: u8 = CompaqPeer as u8; //110
+    ///IPX in IP \[CJ_Lee\]
+    pub const IPX_IN_IP: u8 = IpxInIp as u8; //111
+    ///Virtual Router Redundancy Protocol \[[RFC5798](https://datatracker.ietf.org/doc/html/rfc5798)\]
+    pub const VRRP: u8 = Vrrp as u8; //112
+    ///PGM Reliable Transport Protocol \[Tony_Speakman\]
+    pub const PGM: u8 = Pgm as u8; //113
+    ///any 0-hop protocol \[Internet_Assigned_Numbers_Authority\]
+    pub const ANY_ZERO_HOP_PROTOCOL: u8 = AnyZeroHopProtocol as u8; //114
+    ///Layer Two Tunneling Protocol \[[RFC3931](https://datatracker.ietf.org/doc/html/rfc3931)\]\[Bernard_Aboba\]
+    pub const L2TP: u8 = Layer2TunnelingProtocol as u8; //115
+    ///D-II Data Exchange (DDX) \[John_Worley\]
+    pub const DDX: u8 = Ddx as u8; //116
+    ///Interactive Agent Transfer Protocol \[John_Murphy\]
+    pub const IATP: u8 = Iatp as u8; //117
+    ///Schedule Transfer Protocol \[Jean_Michel_Pittet\]
+    pub const STP: u8 = Stp as u8; //118
+    ///SpectraLink Radio Protocol \[Mark_Hamilton\]
+    pub const SRP: u8 = Srp as u8; //119
+    ///UTI \[Peter_Lothberg\]
+    pub const UTI: u8 = Uti as u8; //120
+    ///Simple Message Protocol \[Leif_Ekblad\]
+    pub const SIMPLE_MESSAGE_PROTOCOL: u8 = SimpleMessageProtocol as u8; //121
+    ///Simple Multicast Protocol (deprecated) \[Jon_Crowcroft\]\[draft-perlman-simple-multicast\]
+    pub const SM: u8 = Sm as u8; //122
+    ///Performance Transparency Protocol \[Michael_Welzl\]
+    pub const PTP: u8 = Ptp as u8; //123
+    ///ISIS over IPv4 \[Tony_Przygienda\]
+    pub const ISIS_OVER_IPV4: u8 = IsisOverIpv4 as u8; //124
+    ///FIRE \[Criag_Partridge\]
+    pub const FIRE: u8 = Fire as u8; //125
+    ///Combat Radio Transport Protocol \[Robert_Sautter\]
+    pub const CRTP: u8 = Crtp as u8; //126
+    ///Combat Radio User Datagram \[Robert_Sautter\]
+    pub const CRUDP: u8 = Crudp as u8; //127
+    ///SSCOPMCE \[Kurt_Waber\]
+    pub const SSCOPMCE: u8 = Sscopmce as u8; //128
+    ///IPLT \[\[Hollbach\]\]
+    pub const IPLT: u8 = Iplt as u8; //129
+    ///Secure Packet Shield \[Bill_McIntosh\]
+    pub const SPS: u8 = Sps as u8; //130
+    ///Private IP Encapsulation within IP \[Bernhard_Petri\]
+    pub const PIPE: u8 = Pipe as u8; //131
+    ///Stream Control Transmission Protocol \[Randall_R_Stewart\]
+    pub const SCTP: u8 = Sctp as u8; //132
+    ///Fibre Channel \[Murali_Rajagopal\]\[[RFC6172](https://datatracker.ietf.org/doc/html/rfc6172)\]
+    pub const FC: u8 = Fc as u8; //133
+    ///RSVP-E2E-IGNORE \[[RFC3175](https://datatracker.ietf.org/doc/html/rfc3175)\]
+    pub const RSVP_E2E_IGNORE: u8 = RsvpE2eIgnore as u8; //134
     ///MobilityHeader \[[RFC6275](https://datatracker.ietf.org/doc/html/rfc6275)\]
     pub const MOBILITY: u8 = MobilityHeader as u8; //135
+    ///UDPLite \[[RFC3828](https://datatracker.ietf.org/doc/html/rfc3828)\]
+    pub const UDP_LITE: u8 = UdpLite as u8; //136
+    /// \[[RFC4023](https://datatracker.ietf.org/doc/html/rfc4023)\]
+    pub const MPLS_IN_IP: u8 = MplsInIp as u8; //137
+    ///MANET Protocols \[[RFC5498](https://datatracker.ietf.org/doc/html/rfc5498)\]
+    pub const MANET: u8 = Manet as u8; //138
     ///Host Identity Protocol \[[RFC7401](https://datatracker.ietf.org/doc/html/rfc7401)\]
     pub const HIP: u8 = Hip as u8; //139
     ///Shim6 Protocol \[[RFC5533](https://datatracker.ietf.org/doc/html/rfc5533)\]
     pub const SHIM6: u8 = Shim6 as u8; //140
+    ///Wrapped Encapsulating Security Payload \[[RFC5840](https://datatracker.ietf.org/doc/html/rfc5840)\]
+    pub const WESP: u8 = Wesp as u8; //141
+    ///Robust Header Compression \[[RFC5858](https://datatracker.ietf.org/doc/html/rfc5858)\]
+    pub const ROHC: u8 = Rohc as u8; //142
+    ///Ethernet \[[RFC8986](https://datatracker.ietf.org/doc/html/rfc8986)\]
+    pub const ETHERNET: u8 = Ethernet as u8; //143
+    ///AGGFRAG encapsulation payload for ESP \[[RFC9347](https://datatracker.ietf.org/doc/html/rfc9347)\]
+    pub const AGGFRAG: u8 = Aggfrag as u8; //144
+    ///Network Service Header \[[RFC9491](https://datatracker.ietf.org/doc/html/rfc9491)\]
+    pub const NSH: u8 = Nsh as u8; //145
     ///Use for experimentation and testing
     pub const EXP0: u8 = ExperimentalAndTesting0 as u8; //253
     ///Use for experimentation and testing
     pub const EXP1: u8 = ExperimentalAndTesting1 as u8; //254
+    ///Reserved \[Internet_Assigned_Numbers_Authority\]
+    pub const RESERVED: u8 = Reserved as u8; //255
 }
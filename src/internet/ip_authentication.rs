@@ -326,3 +326,192 @@ impl<'a> IpAuthenticationHeaderSlice<'a> {
         ).unwrap()
     }
 }
+
+/// Data authenticated by the integrity check value (ICV) of the first
+/// authentication header in an IP packet (RFC 4302 section 3.3.3).
+///
+/// The data contains the IP packet with all mutable fields & the ICV
+/// itself set to zero & can be passed to an externally implemented
+/// integrity algorithm (e.g. HMAC-SHA-256-128). The computed ICV can then
+/// be compared with the received one or spliced into the packet via
+/// [`IpAuthenticationIcvInput::splice_icv`].
+///
+/// Mutable fields that get zeroed:
+///
+/// * IPv4: type of service, flags, fragment offset, time to live, header
+///   checksum & all options except the immutable ones (end of list, no
+///   operation, security, extended security, commercial security, router
+///   alert & sender directed multi-destination delivery).
+/// * IPv6: traffic class, flow label, hop limit & the data of hop by hop &
+///   destination options with the "may change" bit set.
+///
+/// Routing headers are authenticated as they are, so packets have to be
+/// in the state expected at their final destination (as when verifying
+/// a received packet).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IpAuthenticationIcvInput {
+    data: Vec<u8>,
+    icv_offset: usize,
+    icv_len: usize,
+}
+
+impl IpAuthenticationIcvInput {
+    /// Types of the IPv4 options that are immutable in transit (RFC 4302 appendix A.1).
+    const IPV4_IMMUTABLE_OPTIONS: [u8;7] = [0, 1, 130, 133, 134, 148, 149];
+
+    /// Determines the authenticated data of the first authentication header
+    /// in the given IPv4 or IPv6 packet. Returns `Ok(None)` if the packet
+    /// contains no authentication header (directly following the IPv4
+    /// header or the IPv6 hop by hop, destination options, routing &
+    /// fragment headers).
+    pub fn from_ip_slice(packet: &[u8]) -> Result<Option<IpAuthenticationIcvInput>, ReadError> {
+        match packet.first().map(|value| value >> 4) {
+            Some(4) => IpAuthenticationIcvInput::from_ipv4_slice(packet),
+            Some(6) => IpAuthenticationIcvInput::from_ipv6_slice(packet),
+            Some(version) => Err(ReadError::IpUnsupportedVersion(version)),
+            None => Err(ReadError::UnexpectedEndOfSlice(1)),
+        }
+    }
+
+    fn from_ipv4_slice(packet: &[u8]) -> Result<Option<IpAuthenticationIcvInput>, ReadError> {
+        let ip = Ipv4HeaderSlice::from_slice(packet)?;
+        if ip_number::AUTH != ip.protocol() {
+            return Ok(None);
+        }
+        let header_len = ip.slice().len();
+        let total_len = std::cmp::max(
+            header_len,
+            std::cmp::min(usize::from(ip.total_len()), packet.len())
+        );
+        let auth = IpAuthenticationHeaderSlice::from_slice(&packet[header_len..total_len])?;
+
+        let mut data = packet[..total_len].to_vec();
+        // type of service, flags & fragment offset, time to live & checksum
+        data[1] = 0;
+        data[6..9].copy_from_slice(&[0;3]);
+        data[10..12].copy_from_slice(&[0;2]);
+
+        // mutable options
+        let mut offset = Ipv4Header::SERIALIZED_SIZE;
+        while offset < header_len {
+            let option_type = data[offset];
+            let len = match option_type {
+                0 => break,
+                1 => 1,
+                _ => usize::from(data.get(offset + 1).copied().unwrap_or(0)),
+            };
+            if len < 1 || offset + len > header_len {
+                // malformed option, treat the rest as mutable
+                data[offset..header_len].iter_mut().for_each(|value| *value = 0);
+                break;
+            }
+            if !IpAuthenticationIcvInput::IPV4_IMMUTABLE_OPTIONS.contains(&option_type) {
+                data[offset..offset + len].iter_mut().for_each(|value| *value = 0);
+            }
+            offset += len;
+        }
+
+        Ok(Some(IpAuthenticationIcvInput::new(data, header_len, &auth)))
+    }
+
+    fn from_ipv6_slice(packet: &[u8]) -> Result<Option<IpAuthenticationIcvInput>, ReadError> {
+        use crate::ip_number::*;
+
+        let ip = Ipv6HeaderSlice::from_slice(packet)?;
+        let total_len = if 0 == ip.payload_length() {
+            packet.len()
+        } else {
+            std::cmp::min(Ipv6Header::SERIALIZED_SIZE + usize::from(ip.payload_length()), packet.len())
+        };
+
+        let mut data = packet[..total_len].to_vec();
+        // traffic class, flow label & hop limit
+        data[0] &= 0xf0;
+        data[1..4].copy_from_slice(&[0;3]);
+        data[7] = 0;
+
+        let mut offset = Ipv6Header::SERIALIZED_SIZE;
+        let mut next_header = ip.next_header();
+        loop {
+            match next_header {
+                AUTH => {
+                    let auth = IpAuthenticationHeaderSlice::from_slice(&packet[offset..total_len])?;
+                    return Ok(Some(IpAuthenticationIcvInput::new(data, offset, &auth)));
+                },
+                IPV6_HOP_BY_HOP | IPV6_DEST_OPTIONS | IPV6_ROUTE | IPV6_FRAG => {
+                    let header = Ipv6RawExtensionHeaderSlice::from_slice(&packet[offset..total_len])?;
+                    let len = header.slice().len();
+                    if IPV6_HOP_BY_HOP == next_header || IPV6_DEST_OPTIONS == next_header {
+                        zero_mutable_ipv6_options(&mut data[offset + 2..offset + len]);
+                    }
+                    next_header = header.next_header();
+                    offset += len;
+                },
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// Zeroes the ICV of the given authentication header starting at the
+    /// given offset in the data.
+    fn new(mut data: Vec<u8>, auth_offset: usize, auth: &IpAuthenticationHeaderSlice) -> IpAuthenticationIcvInput {
+        let icv_offset = auth_offset + 12;
+        let icv_len = auth.raw_icv().len();
+        data[icv_offset..icv_offset + icv_len].iter_mut().for_each(|value| *value = 0);
+        IpAuthenticationIcvInput{
+            data,
+            icv_offset,
+            icv_len,
+        }
+    }
+
+    /// Returns the data that has to be passed to the integrity algorithm.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the range of the ICV in the IP packet.
+    #[inline]
+    pub fn icv_range(&self) -> std::ops::Range<usize> {
+        self.icv_offset..self.icv_offset + self.icv_len
+    }
+
+    /// Writes the given ICV into the authentication header of the IP packet
+    /// the input was created from.
+    ///
+    /// An `Err(ValueError::IpAuthenticationHeaderBadIcvLength)` is returned
+    /// if the length of the ICV differs from the length of the ICV field
+    /// or the packet is too short to contain it.
+    pub fn splice_icv(&self, packet: &mut [u8], icv: &[u8]) -> Result<(), ValueError> {
+        let range = self.icv_range();
+        if icv.len() != self.icv_len || packet.len() < range.end {
+            return Err(ValueError::IpAuthenticationHeaderBadIcvLength(icv.len()));
+        }
+        packet[range].copy_from_slice(icv);
+        Ok(())
+    }
+}
+
+/// Zeroes the data of all IPv6 options with the "may change" bit set in
+/// the given options area of a hop by hop or destination options header.
+fn zero_mutable_ipv6_options(options: &mut [u8]) {
+    let mut offset = 0;
+    while offset < options.len() {
+        let option_type = options[offset];
+        // pad1 has no length field
+        if 0 == option_type {
+            offset += 1;
+            continue;
+        }
+        let data_start = offset + 2;
+        let data_end = data_start + usize::from(options.get(offset + 1).copied().unwrap_or(0));
+        if data_end > options.len() {
+            break;
+        }
+        if 0 != option_type & 0b0010_0000 {
+            options[data_start..data_end].iter_mut().for_each(|value| *value = 0);
+        }
+        offset = data_end;
+    }
+}
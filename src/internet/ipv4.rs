@@ -7,8 +7,8 @@ use std::slice::from_raw_parts;
 /// IPv4 header without options.
 #[derive(Clone)]
 pub struct Ipv4Header {
-    pub differentiated_services_code_point: u8,
-    pub explicit_congestion_notification: u8,
+    pub differentiated_services_code_point: Dscp,
+    pub explicit_congestion_notification: Ecn,
     /// Length of the payload of the ipv4 packet in bytes (does not contain the options).
     ///
     /// This field does not directly exist in an ipv4 header but instead is decoded from
@@ -42,8 +42,8 @@ impl Ipv4Header {
     ///Constructs an Ipv4Header with standard values for non specified values.
     pub fn new(payload_len: u16, time_to_live: u8, protocol: IpNumber, source: [u8;4], destination: [u8;4]) -> Ipv4Header {
         Ipv4Header {
-            differentiated_services_code_point: 0,
-            explicit_congestion_notification: 0,
+            differentiated_services_code_point: Default::default(),
+            explicit_congestion_notification: Default::default(),
             payload_len,
             identification: 0,
             dont_fragment: true,
@@ -59,6 +59,25 @@ impl Ipv4Header {
         }
     }
 
+    ///Constructs an Ipv4Header with the given [`Ipv4Addr`] source & destination
+    ///addresses and standard values for non specified values (see [`Ipv4Header::new`]).
+    #[inline]
+    pub fn new_with_addrs(payload_len: u16, time_to_live: u8, protocol: IpNumber, source: Ipv4Addr, destination: Ipv4Addr) -> Ipv4Header {
+        Ipv4Header::new(payload_len, time_to_live, protocol, source.octets(), destination.octets())
+    }
+
+    ///Return the ipv4 source address as an std::net::Ipv4Addr
+    #[inline]
+    pub fn source_addr(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.source)
+    }
+
+    ///Return the ipv4 destination address as an std::net::Ipv4Addr
+    #[inline]
+    pub fn destination_addr(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.destination)
+    }
+
     ///Length of the header in 4 bytes (often also called IHL - Internet Header Lenght). 
     ///
     ///The minimum allowed length of a header is 5 (= 20 bytes) and the maximum length is 15 (= 60 bytes).
@@ -71,6 +90,19 @@ impl Ipv4Header {
         &self.options_buffer[..usize::from(self.options_len)]
     }
 
+    ///Returns an iterator over the options of the header.
+    #[inline]
+    pub fn options_iterator(&self) -> Ipv4OptionsIterator<'_> {
+        Ipv4OptionsIterator::from_slice(self.options())
+    }
+
+    /// Returns the "type of service" byte containing the differentiated
+    /// services code point & the explicit congestion notification.
+    #[inline]
+    pub fn type_of_service(&self) -> u8 {
+        (self.differentiated_services_code_point.value() << 2) | u8::from(self.explicit_congestion_notification)
+    }
+
     ///Length of the header (includes options) in bytes.
     #[inline]
     pub fn header_len(&self) -> usize {
@@ -120,6 +152,75 @@ impl Ipv4Header {
         }
     }
 
+    /// Returns the final destination address of the packet, as used in the
+    /// pseudo header of upper layer checksums (RFC 1122 section 4.1.3.4).
+    ///
+    /// If the options contain a "loose source and record route" or "strict
+    /// source and record route" option with addresses left to visit the
+    /// final destination is the last address of the route, otherwise it is
+    /// the destination of the header.
+    pub fn final_destination(&self) -> [u8;4] {
+        self.options_iterator()
+            .find_map(|option| match option {
+                Ok(Ipv4OptionElement::LooseSourceRoute(route)) |
+                Ok(Ipv4OptionElement::StrictSourceRoute(route)) => {
+                    // the pointer points to the next address (counted from
+                    // the start of the option, the route data starts at 4)
+                    let pointer = usize::from(route.pointer);
+                    if pointer >= 4 && pointer <= route.route_data.len() {
+                        route.addresses().last()
+                    } else {
+                        None
+                    }
+                },
+                _ => None,
+            })
+            .unwrap_or(self.destination)
+    }
+
+    /// Returns the value of the "router alert" option (RFC 2113) if the
+    /// options contain one.
+    pub fn router_alert(&self) -> Option<u16> {
+        self.options_iterator().find_map(|option| match option {
+            Ok(Ipv4OptionElement::RouterAlert(value)) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Sets the value of the "router alert" option (RFC 2113). If no such
+    /// option is present it is appended to the existing options (in front
+    /// of an "end of options list" option) & the options are padded to a
+    /// multiple of 4 bytes.
+    ///
+    /// In case the resulting options would be longer then 40 bytes an
+    /// `Err(ValueError::Ipv4OptionsLengthBad)` is returned and the header
+    /// is not changed.
+    pub fn set_router_alert(&mut self, value: u16) -> Result<(), ValueError> {
+        use ipv4_option::*;
+
+        // search for an existing router alert option & the end of the options
+        let options_len = self.options().len();
+        let mut iter = self.options_iterator();
+        let end = loop {
+            let offset = options_len - iter.rest().len();
+            match iter.next() {
+                Some(Ok(Ipv4OptionElement::RouterAlert(_))) => {
+                    self.options_buffer[offset + 2..offset + 4].copy_from_slice(&value.to_be_bytes());
+                    return Ok(());
+                },
+                Some(Ok(Ipv4OptionElement::End)) => break offset,
+                Some(Ok(_)) => {},
+                Some(Err(_)) | None => break options_len,
+            }
+        };
+
+        let mut options = self.options()[..end].to_vec();
+        options.extend_from_slice(&[TYPE_ROUTER_ALERT, LEN_ROUTER_ALERT]);
+        options.extend_from_slice(&value.to_be_bytes());
+        options.resize((options.len() + 3) & !0b11, TYPE_END);
+        self.set_options(&options)
+    }
+
     /// Renamed to `Ipv4Header::from_slice`
     #[deprecated(
         since = "0.10.1",
@@ -167,7 +268,7 @@ impl Ipv4Header {
 
         let (dscp, ecn) = {
             let value = header_raw[1];
-            (value >> 2, value & 0x3)
+            (Dscp::from_bits(value >> 2), Ecn::from_bits(value))
         };
         let header_length = u16::from(ihl)*4;
         let total_length = u16::from_be_bytes([header_raw[2], header_raw[3]]);
@@ -213,15 +314,11 @@ impl Ipv4Header {
     ///
     /// Specifically it will be checked, that:
     /// * payload_len + options_len is not too big to be encoded in the total_size header field
-    /// * differentiated_services_code_point is not greater then 0x3f
-    /// * explicit_congestion_notification is not greater then 0x3
     /// * fragments_offset is not greater then 0x1fff
     pub fn check_ranges(&self) -> Result<(), ValueError> {
         use crate::ErrorField::*;
         
         //check ranges
-        max_check_u8(self.differentiated_services_code_point, 0x3f, Ipv4Dscp)?;
-        max_check_u8(self.explicit_congestion_notification, 0x3, Ipv4Ecn)?;
         max_check_u16(self.fragments_offset, 0x1fff, Ipv4FragmentsOffset)?;
         max_check_u16(self.payload_len, self.max_payload_len(), Ipv4PayloadLength)?;
 
@@ -271,7 +368,7 @@ impl Ipv4Header {
 
         let header_raw = [
             (4 << 4) | self.ihl(),
-            (self.differentiated_services_code_point << 2) | self.explicit_congestion_notification,
+            self.type_of_service(),
             total_len_be[0],
             total_len_be[1],
 
@@ -320,7 +417,7 @@ impl Ipv4Header {
         .add_2bytes(
             [
                 (4 << 4) | self.ihl(),
-                (self.differentiated_services_code_point << 2) | self.explicit_congestion_notification 
+                self.type_of_service()
             ]
         )
         .add_2bytes(self.total_len().to_be_bytes())
@@ -373,8 +470,8 @@ impl Ipv4Header {
 impl Default for Ipv4Header {
     fn default() -> Ipv4Header {
         Ipv4Header {
-            differentiated_services_code_point: 0,
-            explicit_congestion_notification: 0,
+            differentiated_services_code_point: Default::default(),
+            explicit_congestion_notification: Default::default(),
             payload_len: 0,
             identification: 0,
             dont_fragment: true,
@@ -393,7 +490,7 @@ impl Default for Ipv4Header {
 
 impl Debug for Ipv4Header {
     fn fmt(&self, fotmatter: &mut Formatter) -> Result<(), std::fmt::Error> {
-        write!(fotmatter, "Ipv4Header {{ ihl: {}, differentiated_services_code_point: {}, explicit_congestion_notification: {}, payload_len: {}, identification: {}, dont_fragment: {}, more_fragments: {}, fragments_offset: {}, time_to_live: {}, protocol: {}, header_checksum: {}, source: {:?}, destination: {:?}, options: {:?} }}", 
+        write!(fotmatter, "Ipv4Header {{ ihl: {}, differentiated_services_code_point: {:?}, explicit_congestion_notification: {:?}, payload_len: {}, identification: {}, dont_fragment: {}, more_fragments: {}, fragments_offset: {}, time_to_live: {}, protocol: {}, header_checksum: {}, source: {:?}, destination: {:?}, options: {:?} }}", 
             self.ihl(),
             self.differentiated_services_code_point,
             self.explicit_congestion_notification,
@@ -694,6 +791,12 @@ impl<'a> Ipv4HeaderSlice<'a> {
         }
     }
 
+    /// Returns an iterator over the ipv4 header options.
+    #[inline]
+    pub fn options_iterator(&self) -> Ipv4OptionsIterator<'a> {
+        Ipv4OptionsIterator::from_slice(self.options())
+    }
+
     /// Returns true if the payload is fragmented.
     ///
     /// Either data is missing (more_fragments set) or there is
@@ -704,12 +807,33 @@ impl<'a> Ipv4HeaderSlice<'a> {
         (0 != self.fragments_offset())
     }
 
+    /// Returns true if the packet is a fragment (same as
+    /// [`Ipv4HeaderSlice::is_fragmenting_payload`]).
+    #[inline]
+    pub fn is_fragment(&self) -> bool {
+        self.is_fragmenting_payload()
+    }
+
+    /// Returns true if the packet is the first fragment of a fragmented
+    /// payload (offset 0 & the "more fragments" flag set).
+    #[inline]
+    pub fn is_first_fragment(&self) -> bool {
+        self.more_fragments() && 0 == self.fragments_offset()
+    }
+
+    /// Returns the offset of the fragment in bytes (the "fragment_offset"
+    /// field is in units of 8 octets).
+    #[inline]
+    pub fn fragment_byte_offset(&self) -> u16 {
+        self.fragments_offset()*8
+    }
+
     /// Decode all the fields and copy the results to a Ipv4Header struct
     pub fn to_header(&self) -> Ipv4Header {
         let options = self.options();
         Ipv4Header {
-            differentiated_services_code_point: self.dcp(),
-            explicit_congestion_notification: self.ecn(),
+            differentiated_services_code_point: Dscp::from_bits(self.dcp()),
+            explicit_congestion_notification: Ecn::from_bits(self.ecn()),
             payload_len: self.payload_len(),
             identification: self.identification(),
             dont_fragment: self.dont_fragment(),
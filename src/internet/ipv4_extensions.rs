@@ -146,6 +146,16 @@ impl<'a> Ipv4ExtensionsSlice<'a> {
         }
     }
 
+    /// Returns the slice containing all present ipv4 extension headers
+    /// (empty if no extension header is present).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        match &self.auth {
+            Some(auth) => auth.slice(),
+            None => &[]
+        }
+    }
+
     /// Convert the slices into actual headers.
     pub fn to_header(&self) -> Ipv4Extensions {
         Ipv4Extensions {
@@ -0,0 +1,172 @@
+use super::super::*;
+
+/// Iterator splitting an IPv4 packet into fragments fitting into a given
+/// MTU (RFC 791 section 3.2).
+///
+/// Each item contains the header of the fragment (with the payload
+/// length, fragment offset, "more fragments" flag & header checksum set)
+/// and the slice of the payload carried by the fragment. All options are
+/// part of the first fragment, following fragments only contain the
+/// options with the "copied" flag set.
+///
+/// Packets that are already fragments are split further (the offsets are
+/// relative to the original offset & the last fragment keeps the original
+/// "more fragments" flag).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// let mut header = Ipv4Header::new(0, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2]);
+/// header.dont_fragment = false;
+/// let payload = [0u8;100];
+///
+/// let mut packets = Vec::new();
+/// for (fragment_header, fragment_payload) in Ipv4Fragmenter::new(&header, &payload, 68).unwrap() {
+///     let mut packet = Vec::new();
+///     fragment_header.write(&mut packet).unwrap();
+///     packet.extend_from_slice(fragment_payload);
+///     packets.push(packet);
+/// }
+///
+/// // 48 + 48 + 4 bytes of payload
+/// assert_eq!(vec![68, 68, 24], packets.iter().map(|p| p.len()).collect::<Vec<_>>());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv4Fragmenter<'a> {
+    /// Header used for the first fragment.
+    first: Ipv4Header,
+    /// Header used for all following fragments (only containing the copied options).
+    subsequent: Ipv4Header,
+    /// Payload not yet returned as part of a fragment.
+    payload: &'a [u8],
+    /// Offset in bytes of the remaining payload relative to the start of the original datagram.
+    offset: usize,
+    /// Maximum length of the header & payload of a fragment.
+    mtu: usize,
+    /// "more fragments" flag of the original header (set in the last fragment).
+    more_fragments: bool,
+    /// True if the first fragment was not yet returned.
+    first_pending: bool,
+}
+
+impl<'a> Ipv4Fragmenter<'a> {
+    /// Creates an iterator over the fragments of the packet with the given
+    /// header & payload (the payload length of the header is ignored).
+    ///
+    /// Packets already fitting into the MTU result in a single fragment.
+    /// For packets bigger then the MTU the following errors are returned:
+    ///
+    /// * `ValueError::Ipv4DontFragmentSet` if the "don't fragment" flag is set.
+    /// * `ValueError::Ipv4FragmentMtuTooSmall` if a fragment can not contain
+    ///   the header & at least 8 bytes of payload.
+    ///
+    /// Additionally the header values have to be in range (see
+    /// [`Ipv4Header::check_ranges`]) & the reassembled packet has to fit
+    /// into the total length field (otherwise a
+    /// `ValueError::Ipv4PayloadLengthTooLarge` error is returned).
+    pub fn new(header: &Ipv4Header, payload: &'a [u8], mtu: usize) -> Result<Ipv4Fragmenter<'a>, ValueError> {
+        use crate::ValueError::*;
+
+        header.check_ranges()?;
+        let offset = usize::from(header.fragments_offset)*8;
+        let reassembled_len = offset + payload.len();
+        if reassembled_len > usize::from(header.max_payload_len()) {
+            return Err(Ipv4PayloadLengthTooLarge(reassembled_len));
+        }
+
+        let first = header.clone();
+        let mut subsequent = header.clone();
+        // can not fail as the copied options are at most as long as the original options
+        subsequent.set_options(&Ipv4Fragmenter::copied_options(header.options())).unwrap();
+
+        let mtu = std::cmp::min(mtu, usize::from(u16::MAX));
+        if first.header_len() + payload.len() > mtu {
+            if header.dont_fragment {
+                return Err(Ipv4DontFragmentSet(first.header_len() + payload.len()));
+            }
+            // the headers of the following fragments are never bigger
+            // then the header of the first fragment
+            if mtu < first.header_len() + 8 {
+                return Err(Ipv4FragmentMtuTooSmall(mtu));
+            }
+        }
+
+        Ok(Ipv4Fragmenter{
+            first,
+            subsequent,
+            payload,
+            offset,
+            mtu,
+            more_fragments: header.more_fragments,
+            first_pending: true,
+        })
+    }
+
+    /// Returns the options that have to be copied into all fragments
+    /// (options with the "copied" flag set) padded to a multiple of 4 bytes.
+    fn copied_options(options: &[u8]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(options.len());
+        let mut rest = options;
+        while let Some(option_type) = rest.first() {
+            let len = match option_type {
+                0 => break,
+                1 => 1,
+                _ => match rest.get(1) {
+                    Some(len) if usize::from(*len) >= 2 && usize::from(*len) <= rest.len() => usize::from(*len),
+                    // malformed options are not copied
+                    _ => break,
+                }
+            };
+            if 0 != option_type & 0b1000_0000 {
+                result.extend_from_slice(&rest[..len]);
+            }
+            rest = &rest[len..];
+        }
+        // pad with "end of options list"
+        result.resize((result.len() + 3) & !0b11, 0);
+        result
+    }
+}
+
+impl<'a> Iterator for Ipv4Fragmenter<'a> {
+    type Item = (Ipv4Header, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // packets without payload still result in one fragment
+        if self.payload.is_empty() && !self.first_pending {
+            return None;
+        }
+
+        let mut header = if self.first_pending {
+            self.first.clone()
+        } else {
+            self.subsequent.clone()
+        };
+        self.first_pending = false;
+
+        // fragments except the last one have to contain a multiple of 8 bytes
+        let space = self.mtu - header.header_len();
+        let (len, last) = if self.payload.len() <= space {
+            (self.payload.len(), true)
+        } else {
+            (space & !0b111, false)
+        };
+        let (fragment, rest) = self.payload.split_at(len);
+
+        // ranges & lengths are checked in the constructor
+        header.payload_len = len as u16;
+        header.fragments_offset = (self.offset / 8) as u16;
+        header.more_fragments = if last {
+            self.more_fragments
+        } else {
+            true
+        };
+        header.header_checksum = header.calc_header_checksum().unwrap();
+
+        self.payload = rest;
+        self.offset += len;
+        Some((header, fragment))
+    }
+}
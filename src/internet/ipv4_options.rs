@@ -0,0 +1,413 @@
+use super::super::*;
+
+use std::fmt::{Debug, Formatter};
+
+/// Module containing the constants for ipv4 options (option types & sizes).
+pub mod ipv4_option {
+    /// `u8` identifying the "end of options list" ipv4 option.
+    pub const TYPE_END: u8 = 0;
+    /// `u8` identifying a "no operation" ipv4 option.
+    pub const TYPE_NOOP: u8 = 1;
+    /// `u8` identifying a "record route" ipv4 option.
+    pub const TYPE_RECORD_ROUTE: u8 = 7;
+    /// `u8` identifying an "internet timestamp" ipv4 option.
+    pub const TYPE_TIMESTAMP: u8 = 68;
+    /// `u8` identifying a "loose source and record route" ipv4 option.
+    pub const TYPE_LOOSE_SOURCE_ROUTE: u8 = 131;
+    /// `u8` identifying a "strict source and record route" ipv4 option.
+    pub const TYPE_STRICT_SOURCE_ROUTE: u8 = 137;
+    /// `u8` identifying a "router alert" ipv4 option (RFC 2113).
+    pub const TYPE_ROUTER_ALERT: u8 = 148;
+    /// Minimum length in octets/bytes of the route ipv4 options (includes type, length & pointer).
+    pub const MIN_LEN_ROUTE: u8 = 3;
+    /// Minimum length in octets/bytes of the "internet timestamp" ipv4 option (includes type, length, pointer, overflow & flag).
+    pub const MIN_LEN_TIMESTAMP: u8 = 4;
+    /// Length in octets/bytes of the "router alert" ipv4 option (includes type & length).
+    pub const LEN_ROUTER_ALERT: u8 = 4;
+}
+
+/// Data of the "record route", "loose source and record route" &
+/// "strict source and record route" ipv4 options (RFC 791).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv4RouteOption<'a> {
+    /// Offset of the next address slot (counted in bytes from the start
+    /// of the option, starting at 4).
+    pub pointer: u8,
+    /// Raw route data containing the ipv4 addresses.
+    pub route_data: &'a [u8],
+}
+
+impl<'a> Ipv4RouteOption<'a> {
+    /// Returns an iterator over the addresses in the route data (trailing
+    /// bytes not forming a complete address are ignored).
+    pub fn addresses(&self) -> impl Iterator<Item = [u8;4]> + 'a {
+        self.route_data
+            .chunks_exact(4)
+            .map(|value| [value[0], value[1], value[2], value[3]])
+    }
+}
+
+/// Data of the "internet timestamp" ipv4 option (RFC 791).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv4TimestampOption<'a> {
+    /// Offset of the next timestamp slot (counted in bytes from the start
+    /// of the option, starting at 5).
+    pub pointer: u8,
+    /// Number of hosts that could not register timestamps due to lack of space (4 bits).
+    pub overflow: u8,
+    /// Format of the timestamp data (4 bits, 0 = timestamps only,
+    /// 1 = address & timestamp pairs, 3 = prespecified addresses).
+    pub flag: u8,
+    /// Raw timestamp data (timestamps and depending on the flag ipv4 addresses).
+    pub timestamp_data: &'a [u8],
+}
+
+/// Different kinds of options that can be present in the options part of an ipv4 header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Ipv4OptionElement<'a> {
+    /// "End of Option List" option (the following bytes are padding).
+    End,
+    /// "No Operation" option used to align following options.
+    Noop,
+    /// "Record Route" option.
+    RecordRoute(Ipv4RouteOption<'a>),
+    /// "Internet Timestamp" option.
+    Timestamp(Ipv4TimestampOption<'a>),
+    /// "Router Alert" option (RFC 2113) containing the alert value (0 = the
+    /// router shall examine the packet).
+    RouterAlert(u16),
+    /// "Loose Source and Record Route" option.
+    LooseSourceRoute(Ipv4RouteOption<'a>),
+    /// "Strict Source and Record Route" option.
+    StrictSourceRoute(Ipv4RouteOption<'a>),
+    /// Option with an unknown type (data contains the bytes after the length field).
+    Unknown{ option_type: u8, data: &'a [u8] },
+}
+
+///Errors that can occour while reading the options of an ipv4 header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Ipv4OptionReadError {
+    ///Returned if an option type was read, but there was not enough memory in the options left to completely read it.
+    UnexpectedEndOfSlice{ option_type: u8, expected_len: u8, actual_len: usize },
+
+    ///Returned if the option has an unexpected size argument (e.g. != 4 for router alert).
+    UnexpectedSize{ option_type: u8, size: u8 },
+}
+
+impl Error for Ipv4OptionReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for Ipv4OptionReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Ipv4OptionReadError::*;
+        match self {
+            UnexpectedEndOfSlice{option_type, expected_len, actual_len} => {
+                write!(f, "Ipv4OptionReadError: Not enough memory left in slice to read option of type {} (expected at least {} bytes, only {} bytes available).", option_type, expected_len, actual_len)
+            },
+            UnexpectedSize{option_type, size} => {
+                write!(f, "Ipv4OptionReadError: Length value of the option of type {} had unexpected value {}.", option_type, size)
+            }
+        }
+    }
+}
+
+///Allows iterating over the options of an ipv4 header.
+///
+///The iteration stops after an "end of option list" option or an error.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Ipv4OptionsIterator<'a> {
+    options: &'a [u8]
+}
+
+impl<'a> Ipv4OptionsIterator<'a> {
+    ///Creates an options iterator from a slice containing encoded ipv4 options.
+    pub fn from_slice(options: &'a [u8]) -> Ipv4OptionsIterator<'a> {
+        Ipv4OptionsIterator{ options }
+    }
+
+    ///Returns the non processed part of the options slice.
+    pub fn rest(&self) -> &'a [u8] {
+        self.options
+    }
+
+    /// Reads the length field of the option at the start of the options
+    /// & checks that the slice contains the whole option.
+    fn option_len(&self, min_len: u8) -> Result<usize, Ipv4OptionReadError> {
+        use crate::Ipv4OptionReadError::*;
+
+        let option_type = self.options[0];
+        if self.options.len() < 2 {
+            return Err(UnexpectedEndOfSlice{
+                option_type,
+                expected_len: 2,
+                actual_len: self.options.len(),
+            });
+        }
+        let len = self.options[1];
+        if len < min_len {
+            Err(UnexpectedSize{
+                option_type,
+                size: len,
+            })
+        } else if self.options.len() < usize::from(len) {
+            Err(UnexpectedEndOfSlice{
+                option_type,
+                expected_len: len,
+                actual_len: self.options.len(),
+            })
+        } else {
+            Ok(usize::from(len))
+        }
+    }
+}
+
+impl<'a> Iterator for Ipv4OptionsIterator<'a> {
+    type Item = Result<Ipv4OptionElement<'a>, Ipv4OptionReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use crate::Ipv4OptionReadError::*;
+        use crate::Ipv4OptionElement::*;
+        use ipv4_option::*;
+
+        if self.options.is_empty() {
+            return None;
+        }
+
+        let options = self.options;
+        let option_type = options[0];
+        let (result, len) = match option_type {
+            TYPE_END => (Ok(End), options.len()),
+            TYPE_NOOP => (Ok(Noop), 1),
+            TYPE_RECORD_ROUTE | TYPE_LOOSE_SOURCE_ROUTE | TYPE_STRICT_SOURCE_ROUTE => {
+                match self.option_len(MIN_LEN_ROUTE) {
+                    Ok(len) => {
+                        let route = Ipv4RouteOption{
+                            pointer: options[2],
+                            route_data: &options[3..len],
+                        };
+                        (
+                            Ok(match option_type {
+                                TYPE_RECORD_ROUTE => RecordRoute(route),
+                                TYPE_LOOSE_SOURCE_ROUTE => LooseSourceRoute(route),
+                                _ => StrictSourceRoute(route),
+                            }),
+                            len
+                        )
+                    },
+                    Err(err) => (Err(err), options.len()),
+                }
+            },
+            TYPE_TIMESTAMP => {
+                match self.option_len(MIN_LEN_TIMESTAMP) {
+                    Ok(len) => (
+                        Ok(Timestamp(Ipv4TimestampOption{
+                            pointer: options[2],
+                            overflow: options[3] >> 4,
+                            flag: options[3] & 0xf,
+                            timestamp_data: &options[4..len],
+                        })),
+                        len
+                    ),
+                    Err(err) => (Err(err), options.len()),
+                }
+            },
+            TYPE_ROUTER_ALERT => {
+                match self.option_len(LEN_ROUTER_ALERT) {
+                    Ok(len) if len != usize::from(LEN_ROUTER_ALERT) => (
+                        Err(UnexpectedSize{
+                            option_type,
+                            size: len as u8,
+                        }),
+                        options.len()
+                    ),
+                    Ok(len) => (
+                        Ok(RouterAlert(u16::from_be_bytes([options[2], options[3]]))),
+                        len
+                    ),
+                    Err(err) => (Err(err), options.len()),
+                }
+            },
+            _ => {
+                match self.option_len(2) {
+                    Ok(len) => (
+                        Ok(Unknown{
+                            option_type,
+                            data: &options[2..len],
+                        }),
+                        len
+                    ),
+                    Err(err) => (Err(err), options.len()),
+                }
+            },
+        };
+
+        // in case of an error or the end of the options list the remaining
+        // bytes are skipped
+        self.options = &options[len..];
+        Some(result)
+    }
+}
+
+impl<'a> Debug for Ipv4OptionsIterator<'a> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let mut list = fmt.debug_list();
+
+        // create a copy and iterate over all elements
+        for it in self.clone() {
+            match it {
+                Ok(e) => { list.entry(&e); },
+                Err(e) => {
+                    list.entry(&Result::<(), Ipv4OptionReadError>::Err(e.clone()));
+                }
+            }
+        }
+
+        list.finish()
+    }
+}
+
+/// Builder serializing typed ipv4 options into the options part of an
+/// ipv4 header.
+///
+/// The options are padded with "end of options list" bytes to a multiple
+/// of 4 bytes. As the internet header length & total length of an
+/// [`Ipv4Header`] are derived from the options length, both are updated
+/// when the options are applied to a header:
+///
+/// ```
+/// use etherparse::*;
+///
+/// let mut header = Ipv4Header::new(0, 1, IpNumber::Igmp, [192,168,1,1], [224,0,0,22]);
+/// Ipv4OptionsBuilder::new()
+///     .add(&Ipv4OptionElement::RouterAlert(0))
+///     .unwrap()
+///     .apply(&mut header);
+///
+/// assert_eq!(&[148, 4, 0, 0], header.options());
+/// assert_eq!(6, header.ihl());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv4OptionsBuilder {
+    /// Length of the serialized options (without padding).
+    len: usize,
+    buffer: [u8;40],
+}
+
+impl Default for Ipv4OptionsBuilder {
+    fn default() -> Ipv4OptionsBuilder {
+        Ipv4OptionsBuilder{
+            len: 0,
+            buffer: [0;40],
+        }
+    }
+}
+
+impl Ipv4OptionsBuilder {
+    /// Creates a builder without any options.
+    #[inline]
+    pub fn new() -> Ipv4OptionsBuilder {
+        Default::default()
+    }
+
+    /// Creates a builder containing only a "router alert" option (RFC 2113)
+    /// with the given value (0 = "router shall examine packet").
+    pub fn router_alert(value: u16) -> Ipv4OptionsBuilder {
+        let mut result = Ipv4OptionsBuilder::new();
+        // can not fail as the option fits into an empty builder
+        result.add(&Ipv4OptionElement::RouterAlert(value)).unwrap();
+        result
+    }
+
+    /// Appends the given option.
+    ///
+    /// An `Err(ValueError::Ipv4OptionsLengthBad)` is returned if the
+    /// options would no longer fit into the 40 bytes available in an ipv4
+    /// header & an `Err(ValueError::U8TooLarge)` if the overflow or flag
+    /// field of a timestamp option is bigger then 4 bits. In case of an
+    /// error the builder stays unchanged.
+    pub fn add(&mut self, option: &Ipv4OptionElement) -> Result<&mut Ipv4OptionsBuilder, ValueError> {
+        use crate::Ipv4OptionElement::*;
+        use ipv4_option::*;
+
+        let option_len = match option {
+            End | Noop => 1,
+            RecordRoute(value) | LooseSourceRoute(value) | StrictSourceRoute(value) => {
+                usize::from(MIN_LEN_ROUTE) + value.route_data.len()
+            },
+            Timestamp(value) => {
+                max_check_u8(value.overflow, 0xf, ErrorField::Ipv4TimestampOverflow)?;
+                max_check_u8(value.flag, 0xf, ErrorField::Ipv4TimestampFlag)?;
+                usize::from(MIN_LEN_TIMESTAMP) + value.timestamp_data.len()
+            },
+            RouterAlert(_) => usize::from(LEN_ROUTER_ALERT),
+            Unknown{ data, .. } => 2 + data.len(),
+        };
+        let end = self.len.saturating_add(option_len);
+        if end > self.buffer.len() {
+            return Err(ValueError::Ipv4OptionsLengthBad(end));
+        }
+
+        // the option length is at most 40 at this point & fits into the length field
+        let target = &mut self.buffer[self.len..end];
+        match option {
+            End => target[0] = TYPE_END,
+            Noop => target[0] = TYPE_NOOP,
+            RecordRoute(value) | LooseSourceRoute(value) | StrictSourceRoute(value) => {
+                target[0] = match option {
+                    RecordRoute(_) => TYPE_RECORD_ROUTE,
+                    LooseSourceRoute(_) => TYPE_LOOSE_SOURCE_ROUTE,
+                    _ => TYPE_STRICT_SOURCE_ROUTE,
+                };
+                target[1] = option_len as u8;
+                target[2] = value.pointer;
+                target[3..].copy_from_slice(value.route_data);
+            },
+            Timestamp(value) => {
+                target[0] = TYPE_TIMESTAMP;
+                target[1] = option_len as u8;
+                target[2] = value.pointer;
+                target[3] = (value.overflow << 4) | value.flag;
+                target[4..].copy_from_slice(value.timestamp_data);
+            },
+            RouterAlert(value) => {
+                target[0] = TYPE_ROUTER_ALERT;
+                target[1] = LEN_ROUTER_ALERT;
+                target[2..].copy_from_slice(&value.to_be_bytes());
+            },
+            Unknown{ option_type, data } => {
+                target[0] = *option_type;
+                target[1] = option_len as u8;
+                target[2..].copy_from_slice(data);
+            },
+        }
+        self.len = end;
+        Ok(self)
+    }
+
+    /// Length of the serialized options in bytes (including the padding to a multiple of 4 bytes).
+    #[inline]
+    pub fn len(&self) -> usize {
+        (self.len + 3) & !0b11
+    }
+
+    /// Returns true if no options were added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    /// Returns the serialized options (including the padding to a multiple of 4 bytes).
+    #[inline]
+    pub fn options(&self) -> &[u8] {
+        &self.buffer[..self.len()]
+    }
+
+    /// Sets the options of the given header (replacing all existing options).
+    pub fn apply(&self, header: &mut Ipv4Header) {
+        // can not fail as the length is limited to 40 bytes & padded to a multiple of 4
+        header.set_options(self.options()).unwrap();
+    }
+}
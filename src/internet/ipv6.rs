@@ -30,6 +30,32 @@ impl SerializedSize for Ipv6Header {
 
 impl Ipv6Header {
 
+    ///Constructs an Ipv6Header with the given [`Ipv6Addr`] source & destination
+    ///addresses (the traffic class & flow label are set to 0).
+    pub fn new_with_addrs(payload_length: u16, next_header: u8, hop_limit: u8, source: Ipv6Addr, destination: Ipv6Addr) -> Ipv6Header {
+        Ipv6Header {
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length,
+            next_header,
+            hop_limit,
+            source: source.octets(),
+            destination: destination.octets(),
+        }
+    }
+
+    ///Return the ipv6 source address as an std::net::Ipv6Addr
+    #[inline]
+    pub fn source_addr(&self) -> Ipv6Addr {
+        Ipv6Addr::from(self.source)
+    }
+
+    ///Return the ipv6 destination address as an std::net::Ipv6Addr
+    #[inline]
+    pub fn destination_addr(&self) -> Ipv6Addr {
+        Ipv6Addr::from(self.destination)
+    }
+
     /// Renamed to `Ipv6Header::from_slice`
     #[deprecated(
         since = "0.10.1",
@@ -287,6 +313,84 @@ impl Ipv6Header {
         self.payload_length = size as u16;
         Ok(())
     }
+
+    /// Returns the length of the extension headers & payload, using the
+    /// length of the "Jumbo Payload" option (RFC 2675) of the extension
+    /// headers in case the payload_length field is 0 (jumbogram). Jumbo
+    /// payload lengths not greater then 65535 are invalid & ignored.
+    pub fn payload_len_with_jumbo(&self, extensions: &Ipv6Extensions) -> usize {
+        match extensions.jumbo_payload_length() {
+            Some(len) if 0 == self.payload_length && len > u32::from(u16::MAX) => len as usize,
+            _ => usize::from(self.payload_length),
+        }
+    }
+
+    /// Sets the field payload_length based on the size of the extension
+    /// headers & the given payload size.
+    ///
+    /// If the size does not fit into the payload_length field the packet is
+    /// turned into a jumbogram (RFC 2675): The payload_length field is set to
+    /// 0 & the size is set in a "Jumbo Payload" option in the hop by hop
+    /// options header (see [`Ipv6Extensions::set_jumbo_payload_length`]).
+    /// Sizes above what fits into the "Jumbo Payload" option result in an
+    /// `Err(ValueError::Ipv6PayloadLengthTooLarge)` (the header & extension
+    /// headers are not changed in case of an error).
+    pub fn set_payload_length_with_jumbo(&mut self, extensions: &mut Ipv6Extensions, payload_size: usize) -> Result<(), ValueError> {
+        let size = extensions.header_len() + payload_size;
+        if size <= usize::from(u16::MAX) {
+            return self.set_payload_length(size);
+        }
+
+        // add the option first as it can increase the size of the extension headers
+        let mut jumbo_extensions = extensions.clone();
+        jumbo_extensions.set_jumbo_payload_length(0)?;
+        let size = jumbo_extensions.header_len() + payload_size;
+        if size > u32::MAX as usize {
+            return Err(ValueError::Ipv6PayloadLengthTooLarge(size));
+        }
+        jumbo_extensions.set_jumbo_payload_length(size as u32)?;
+        *extensions = jumbo_extensions;
+        self.payload_length = 0;
+        Ok(())
+    }
+
+    /// Returns the final destination address of the packet, as used in the
+    /// pseudo header of upper layer checksums (RFC 8200 section 8.1).
+    ///
+    /// If the extensions contain a routing header with segments left the
+    /// final destination is the last address of the route (see
+    /// [`Ipv6RoutingHeader::final_destination`]), otherwise it is the
+    /// destination of the header.
+    pub fn final_destination(&self, extensions: &Ipv6Extensions) -> [u8;16] {
+        extensions.routing.as_ref()
+            .and_then(|routing| Ipv6RoutingHeader::from_raw(&routing.routing).ok())
+            .map(|routing| routing.final_destination(&self.destination))
+            .unwrap_or(self.destination)
+    }
+
+    /// Returns the differentiated services code point (upper 6 bits of the traffic class).
+    #[inline]
+    pub fn dscp(&self) -> Dscp {
+        Dscp::from_bits(self.traffic_class >> 2)
+    }
+
+    /// Sets the differentiated services code point (upper 6 bits of the traffic class).
+    #[inline]
+    pub fn set_dscp(&mut self, dscp: Dscp) {
+        self.traffic_class = (dscp.value() << 2) | (self.traffic_class & 0b11);
+    }
+
+    /// Returns the explicit congestion notification (lower 2 bits of the traffic class).
+    #[inline]
+    pub fn ecn(&self) -> Ecn {
+        Ecn::from_bits(self.traffic_class)
+    }
+
+    /// Sets the explicit congestion notification (lower 2 bits of the traffic class).
+    #[inline]
+    pub fn set_ecn(&mut self, ecn: Ecn) {
+        self.traffic_class = (self.traffic_class & !0b11) | u8::from(ecn);
+    }
 }
 
 ///A slice containing an ipv6 header of a network package.
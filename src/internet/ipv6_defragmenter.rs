@@ -0,0 +1,318 @@
+use super::super::*;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// Limits enforced by an [`Ipv6Defragmenter`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6DefragmenterConfig {
+    /// Time after the first received fragment after which incomplete
+    /// packets are discarded (60 seconds as recommended by RFC 8200).
+    pub timeout: Duration,
+    /// Maximum length of a reassembled packet (IPv6 header, extension
+    /// headers & payload). Lengths above what can be represented by the
+    /// payload length field are always rejected.
+    pub max_packet_len: usize,
+    /// Maximum number of packets that are reassembled at the same time.
+    /// If the limit is reached the oldest incomplete packet is discarded.
+    pub max_packets: usize,
+}
+
+impl Default for Ipv6DefragmenterConfig {
+    fn default() -> Ipv6DefragmenterConfig {
+        Ipv6DefragmenterConfig {
+            timeout: Duration::from_secs(60),
+            max_packet_len: Ipv6Header::SERIALIZED_SIZE + usize::from(u16::MAX),
+            max_packets: 1024,
+        }
+    }
+}
+
+/// Errors that can occur when reassembling IPv6 fragments.
+#[derive(Debug)]
+pub enum Ipv6DefragmentError {
+    /// Error when reading the headers of a fragment.
+    Read(ReadError),
+    /// A fragment that is not the last one does not contain a multiple of
+    /// 8 bytes or the fragment contradicts the length of the packet given
+    /// by previously received fragments.
+    InvalidFragment,
+    /// The fragment overlaps a previously received fragment of the same
+    /// packet (the packet & all its received fragments are discarded as
+    /// required by RFC 8200 section 4.5 & RFC 5722).
+    OverlappingFragment,
+    /// The reassembled packet (length as argument) would be larger then
+    /// the configured maximum packet length.
+    PacketTooLarge(usize),
+}
+
+impl fmt::Display for Ipv6DefragmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Ipv6DefragmentError::*;
+        match self {
+            Read(err) => write!(f, "Ipv6DefragmentError: {}", err),
+            InvalidFragment => write!(f, "Ipv6DefragmentError: Fragment does not fit into the reassembled packet."),
+            OverlappingFragment => write!(f, "Ipv6DefragmentError: Fragment overlaps a previously received fragment, the packet was discarded."),
+            PacketTooLarge(len) => write!(f, "Ipv6DefragmentError: Reassembled packet length {} is too large.", len),
+        }
+    }
+}
+
+impl Error for Ipv6DefragmentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Ipv6DefragmentError::Read(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReadError> for Ipv6DefragmentError {
+    fn from(err: ReadError) -> Ipv6DefragmentError {
+        Ipv6DefragmentError::Read(err)
+    }
+}
+
+/// Reassembles fragmented IPv6 packets (RFC 8200 section 4.5).
+///
+/// Fragments are identified by the source & destination address and the
+/// identification value of the fragment header. Once all fragments of a
+/// packet were received the reassembled packet is returned, consisting of
+/// the IPv6 header & unfragmentable extension headers of the first
+/// fragment (without the fragment header & with the payload length
+/// updated) followed by the reassembled fragmentable part.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+/// use std::time::Duration;
+///
+/// let header = Ipv6Header{
+///     next_header: ip_number::UDP,
+///     hop_limit: 64,
+///     ..Default::default()
+/// };
+/// let payload = [1u8;100];
+///
+/// let mut defragmenter = Ipv6Defragmenter::new(Default::default());
+/// let mut result = None;
+/// for (fragment_header, fragment_extensions, fragment_payload) in Ipv6Fragmenter::new(&header, &Default::default(), &payload, 1234, 88).unwrap() {
+///     let mut packet = Vec::new();
+///     fragment_header.write(&mut packet).unwrap();
+///     fragment_extensions.write(&mut packet, fragment_header.next_header).unwrap();
+///     packet.extend_from_slice(fragment_payload);
+///     result = defragmenter.add(&packet, Duration::from_secs(0)).unwrap();
+/// }
+///
+/// let packet = result.unwrap();
+/// let (reassembled_header, reassembled_payload) = Ipv6Header::from_slice(&packet).unwrap();
+/// assert_eq!(ip_number::UDP, reassembled_header.next_header);
+/// assert_eq!(&payload[..], reassembled_payload);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Ipv6Defragmenter {
+    config: Ipv6DefragmenterConfig,
+    buffers: HashMap<([u8;16], [u8;16], u32), Ipv6ReassemblyBuffer>,
+}
+
+#[derive(Clone, Debug)]
+struct Ipv6ReassemblyBuffer {
+    /// Timestamp of the first received fragment.
+    start: Duration,
+    /// IPv6 header & unfragmentable extension headers of the first fragment.
+    unfragmentable: Option<Vec<u8>>,
+    /// Index of the next header field referencing the fragment header in `unfragmentable`.
+    next_header_index: usize,
+    /// Next header value of the fragment header of the first fragment.
+    next_header: u8,
+    /// Fragmentable part.
+    data: Vec<u8>,
+    /// Start & end offsets of the received fragments in the fragmentable part.
+    fragments: Vec<(usize, usize)>,
+    /// Length of the fragmentable part (known after the last fragment was received).
+    len: Option<usize>,
+}
+
+impl Ipv6Defragmenter {
+    /// Creates a defragmenter without any buffered fragments.
+    pub fn new(config: Ipv6DefragmenterConfig) -> Ipv6Defragmenter {
+        Ipv6Defragmenter {
+            config,
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Configuration of the defragmenter.
+    #[inline]
+    pub fn config(&self) -> &Ipv6DefragmenterConfig {
+        &self.config
+    }
+
+    /// Number of packets for which fragments are buffered.
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns true if no fragments are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Removes all buffered fragments.
+    pub fn clear(&mut self) {
+        self.buffers.clear()
+    }
+
+    /// Discards all incomplete packets whose first fragment was received
+    /// `timeout` or more before the given timestamp.
+    pub fn remove_expired(&mut self, timestamp: Duration) {
+        let timeout = self.config.timeout;
+        self.buffers.retain(|_, buffer| timestamp.saturating_sub(buffer.start) < timeout);
+    }
+
+    /// Adds an IPv6 packet (starting with the IPv6 header) & returns the
+    /// reassembled packet once all fragments were received.
+    ///
+    /// Packets without a fragment header in the unfragmentable part &
+    /// fragments containing the whole packet ("atomic fragments") are
+    /// returned directly. Bytes after the end of the IPv6 payload are
+    /// ignored.
+    ///
+    /// Exact duplicates of already received fragments are ignored. If a
+    /// fragment overlaps any other received fragment of the packet, the
+    /// packet is discarded & `Ipv6DefragmentError::OverlappingFragment` is
+    /// returned (RFC 8200 section 4.5 & RFC 5722).
+    ///
+    /// The timestamps are expected to be monotonically increasing.
+    /// Incomplete packets that exceeded the configured timeout are
+    /// discarded before the fragment is added.
+    pub fn add(&mut self, packet: &[u8], timestamp: Duration) -> Result<Option<Vec<u8>>, Ipv6DefragmentError> {
+        use ip_number::*;
+        use Ipv6DefragmentError::*;
+
+        let (header, rest) = Ipv6Header::from_slice(packet)?;
+        let payload_len = usize::from(header.payload_length);
+        if rest.len() < payload_len {
+            return Err(ReadError::UnexpectedEndOfSlice(Ipv6Header::SERIALIZED_SIZE + payload_len).into());
+        }
+        let packet = &packet[..Ipv6Header::SERIALIZED_SIZE + payload_len];
+
+        // skip the unfragmentable extension headers
+        let mut next_header = header.next_header;
+        let mut next_header_index = 6;
+        let mut fragment_start = Ipv6Header::SERIALIZED_SIZE;
+        loop {
+            match next_header {
+                IPV6_HOP_BY_HOP | IPV6_DEST_OPTIONS | IPV6_ROUTE => {
+                    let slice = Ipv6RawExtensionHeaderSlice::from_slice(&packet[fragment_start..])
+                        .map_err(|err| err.add_slice_offset(fragment_start))?;
+                    next_header = slice.next_header();
+                    next_header_index = fragment_start;
+                    fragment_start += slice.slice().len();
+                },
+                IPV6_FRAG => break,
+                // not fragmented
+                _ => return Ok(Some(packet.to_vec())),
+            }
+        }
+        let fragment = Ipv6FragmentHeaderSlice::from_slice(&packet[fragment_start..])
+            .map_err(|err| err.add_slice_offset(fragment_start))?;
+        let data = &packet[fragment_start + fragment.slice().len()..];
+
+        let offset = usize::from(fragment.fragment_offset())*8;
+        let end = offset + data.len();
+        let more_fragments = fragment.more_fragments();
+        // all fragments except the last have to contain a multiple of 8 bytes
+        if more_fragments && 0 != data.len() & 0b111 {
+            return Err(InvalidFragment);
+        }
+        let packet_len = fragment_start + end;
+        if packet_len > std::cmp::min(self.config.max_packet_len, Ipv6Header::SERIALIZED_SIZE + usize::from(u16::MAX)) {
+            return Err(PacketTooLarge(packet_len));
+        }
+
+        // atomic fragment
+        if 0 == offset && !more_fragments {
+            let mut result = packet[..fragment_start].to_vec();
+            result[next_header_index] = fragment.next_header();
+            result.extend_from_slice(data);
+            Ipv6Defragmenter::set_payload_len(&mut result);
+            return Ok(Some(result));
+        }
+
+        self.remove_expired(timestamp);
+
+        let key = (header.source, header.destination, fragment.identification());
+        if !self.buffers.contains_key(&key) && self.buffers.len() >= self.config.max_packets {
+            // discard the oldest packet
+            let oldest = self.buffers.iter()
+                .min_by_key(|(_, buffer)| buffer.start)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.buffers.remove(&oldest);
+            }
+        }
+        let buffer = self.buffers.entry(key).or_insert_with(|| Ipv6ReassemblyBuffer {
+            start: timestamp,
+            unfragmentable: None,
+            next_header_index: 0,
+            next_header: 0,
+            data: Vec::new(),
+            fragments: Vec::new(),
+            len: None,
+        });
+
+        // check the fragment against the length of the packet
+        match buffer.len {
+            Some(len) if end > len || (!more_fragments && end != len) => return Err(InvalidFragment),
+            None if !more_fragments && buffer.data.len() > end => return Err(InvalidFragment),
+            _ => {},
+        }
+
+        // exact duplicates are ignored, any other overlap discards the packet
+        if buffer.fragments.contains(&(offset, end)) {
+            return Ok(None);
+        }
+        if buffer.fragments.iter().any(|(start, stop)| offset < *stop && *start < end) {
+            self.buffers.remove(&key);
+            return Err(OverlappingFragment);
+        }
+        if !more_fragments {
+            buffer.len = Some(end);
+        }
+
+        if 0 == offset {
+            buffer.unfragmentable = Some(packet[..fragment_start].to_vec());
+            buffer.next_header_index = next_header_index;
+            buffer.next_header = fragment.next_header();
+        }
+        if buffer.data.len() < end {
+            buffer.data.resize(end, 0);
+        }
+        buffer.data[offset..end].copy_from_slice(data);
+        buffer.fragments.push((offset, end));
+
+        // as the fragments do not overlap the packet is complete once
+        // their lengths add up to the length of the packet
+        let received: usize = buffer.fragments.iter().map(|(start, stop)| stop - start).sum();
+        if buffer.unfragmentable.is_none() || Some(received) != buffer.len {
+            return Ok(None);
+        }
+        let buffer = self.buffers.remove(&key).unwrap();
+        let mut result = buffer.unfragmentable.unwrap();
+        result[buffer.next_header_index] = buffer.next_header;
+        result.extend_from_slice(&buffer.data);
+        Ipv6Defragmenter::set_payload_len(&mut result);
+        Ok(Some(result))
+    }
+
+    /// Sets the payload length field of a serialized IPv6 packet based on
+    /// its length (checked to be in range by the caller).
+    fn set_payload_len(packet: &mut [u8]) {
+        let payload_len = (packet.len() - Ipv6Header::SERIALIZED_SIZE) as u16;
+        packet[4..6].copy_from_slice(&payload_len.to_be_bytes());
+    }
+}
@@ -12,6 +12,13 @@ use std::slice::from_raw_parts;
 /// * Fragment
 /// * Authentication Header
 ///
+/// Destination options headers can be placed at two positions (RFC 8200
+/// section 4.1): Headers in front of the routing, fragment & authentication
+/// headers are stored in `destination_options`. Headers placed after a
+/// routing header are stored in [`Ipv6RoutingExtensions::final_destination_options`]
+/// & headers placed after a fragment or authentication header in packets
+/// without a routing header in `final_destination_options`.
+///
 /// Currently not supported:
 ////
 /// * Encapsulating Security Payload Header (ESP)
@@ -25,6 +32,11 @@ pub struct Ipv6Extensions {
     pub routing: Option<Ipv6RoutingExtensions>,
     pub fragment: Option<Ipv6FragmentHeader>,
     pub auth: Option<IpAuthenticationHeader>,
+    /// Destination options header located after the fragment or
+    /// authentication header in packets without a routing header (in
+    /// packets with a routing header the destination options after it are
+    /// stored in [`Ipv6RoutingExtensions::final_destination_options`]).
+    pub final_destination_options: Option<Ipv6RawExtensionHeader>,
 }
 
 impl Ipv6Extensions {
@@ -36,7 +48,8 @@ impl Ipv6Extensions {
     ///
     /// Note that this function can only handle ipv6 extensions if each extension header does 
     /// occur at most once, except for destination options headers which are allowed to 
-    /// exist once in front of a routing header and once after a routing header.
+    /// exist once in front of a routing header and once after a routing header (or once
+    /// after a fragment or authentication header in packets without a routing header).
     ///
     /// In case that more extension headers then can fit into a `Ipv6Extensions` struct are
     /// encountered, the parsing is stoped at the point where the data would no longer fit into
@@ -91,14 +104,21 @@ impl Ipv6Extensions {
                             next_header = slice.next_header();
                             routing.final_destination_options = Some(slice.to_header());
                         }
-                    } else if result.destination_options.is_some() {
+                    } else if result.destination_options.is_none() && result.fragment.is_none() && result.auth.is_none() {
+                        let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                        rest = &rest[slice.slice().len()..];
+                        next_header = slice.next_header();
+                        result.destination_options = Some(slice.to_header());
+                    } else if result.final_destination_options.is_some() {
                         // more then one header of this type found -> abort parsing
                         return Ok((result, next_header, rest));
                     } else {
+                        // destination options header before the upper layer
+                        // header in a packet without a routing header
                         let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
                         rest = &rest[slice.slice().len()..];
                         next_header = slice.next_header();
-                        result.destination_options = Some(slice.to_header());
+                        result.final_destination_options = Some(slice.to_header());
                     }
                 },
                 IPV6_ROUTE => {
@@ -156,7 +176,8 @@ impl Ipv6Extensions {
     ///
     /// Note that this function can only handle ipv6 extensions if each extension header does 
     /// occur at most once, except for destination options headers which are allowed to 
-    /// exist once in front of a routing header and once after a routing header.
+    /// exist once in front of a routing header and once after a routing header (or once
+    /// after a fragment or authentication header in packets without a routing header).
     ///
     /// In case that more extension headers then can fit into a `Ipv6Extensions` struct are
     /// encountered, the parsing is stoped at the point where the data would no longer fit into
@@ -208,13 +229,19 @@ impl Ipv6Extensions {
                             next_protocol = header.next_header;
                             routing.final_destination_options = Some(header);
                         }
-                    } else if result.destination_options.is_some() {
+                    } else if result.destination_options.is_none() && result.fragment.is_none() && result.auth.is_none() {
+                        let header = Ipv6RawExtensionHeader::read(reader)?;
+                        next_protocol = header.next_header;
+                        result.destination_options = Some(header);
+                    } else if result.final_destination_options.is_some() {
                         // more then one header of this type found -> abort parsing
                         return Ok((result, next_protocol));
                     } else {
+                        // destination options header before the upper layer
+                        // header in a packet without a routing header
                         let header = Ipv6RawExtensionHeader::read(reader)?;
                         next_protocol = header.next_header;
-                        result.destination_options = Some(header);
+                        result.final_destination_options = Some(header);
                     }
                 },
                 IPV6_ROUTE => {
@@ -279,6 +306,7 @@ impl Ipv6Extensions {
             pub routing: bool,
             pub fragment: bool,
             pub auth: bool,
+            pub routing_final_destination_options: bool,
             pub final_destination_options: bool
         }
 
@@ -288,11 +316,12 @@ impl Ipv6Extensions {
             routing: self.routing.is_some(),
             fragment: self.fragment.is_some(),
             auth: self.auth.is_some(),
-            final_destination_options: if let Some(ref routing) = self.routing {
+            routing_final_destination_options: if let Some(ref routing) = self.routing {
                 routing.final_destination_options.is_some()
             } else {
                 false
-            }
+            },
+            final_destination_options: self.final_destination_options.is_some(),
         };
 
         let mut next_header = first_header;
@@ -325,21 +354,23 @@ impl Ipv6Extensions {
                 },
                 IPV6_DEST_OPTIONS => {
                     // the destination options are allowed to be written twice
-                    // once before a routing header and once after.
-                    if route_written {
-                        if needs_write.final_destination_options {
-                            let header = &self.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap();
-                            header.write(writer)?;
-                            next_header = header.next_header;
-                            needs_write.final_destination_options = false;
-                        } else {
-                            break;
-                        }
-                    } else if needs_write.destination_options {
+                    // once before a routing header and once after (or once
+                    // before the upper layer header if no routing header is present).
+                    if route_written && needs_write.routing_final_destination_options {
+                        let header = &self.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap();
+                        header.write(writer)?;
+                        next_header = header.next_header;
+                        needs_write.routing_final_destination_options = false;
+                    } else if !route_written && needs_write.destination_options {
                         let header = &self.destination_options.as_ref().unwrap();
                         header.write(writer)?;
                         next_header = header.next_header;
                         needs_write.destination_options = false;
+                    } else if needs_write.final_destination_options {
+                        let header = &self.final_destination_options.as_ref().unwrap();
+                        header.write(writer)?;
+                        next_header = header.next_header;
+                        needs_write.final_destination_options = false;
                     } else {
                         break;
                     }
@@ -394,7 +425,7 @@ impl Ipv6Extensions {
             Err(Ipv6ExtensionNotReferenced(IPv6FragmentationHeader).into())
         } else if needs_write.auth {
             Err(Ipv6ExtensionNotReferenced(AuthenticationHeader).into())
-        } else if needs_write.final_destination_options {
+        } else if needs_write.routing_final_destination_options || needs_write.final_destination_options {
             Err(Ipv6ExtensionNotReferenced(IPv6DestinationOptions).into())
         } else {
             Ok(())
@@ -423,6 +454,9 @@ impl Ipv6Extensions {
         if let Some(ref header) = self.auth {
             result += header.header_len();
         }
+        if let Some(ref header) = self.final_destination_options {
+            result += header.header_len();
+        }
 
         result
     }
@@ -451,6 +485,10 @@ impl Ipv6Extensions {
         // * Destination Options header
         // * Upper-Layer header
         //
+        if let Some(ref mut header) = self.final_destination_options {
+            header.next_header = next;
+            next = IPV6_DEST_OPTIONS;
+        }
         if let Some(ref mut routing) = self.routing {
             if let Some(ref mut header) = routing.final_destination_options {
                 header.next_header = next;
@@ -494,6 +532,7 @@ impl Ipv6Extensions {
             pub routing: bool,
             pub fragment: bool,
             pub auth: bool,
+            pub routing_final_destination_options: bool,
             pub final_destination_options: bool
         }
 
@@ -503,11 +542,12 @@ impl Ipv6Extensions {
             routing: self.routing.is_some(),
             fragment: self.fragment.is_some(),
             auth: self.auth.is_some(),
-            final_destination_options: if let Some(ref routing) = self.routing {
+            routing_final_destination_options: if let Some(ref routing) = self.routing {
                 routing.final_destination_options.is_some()
             } else {
                 false
-            }
+            },
+            final_destination_options: self.final_destination_options.is_some(),
         };
 
         let mut next = first_next_header;
@@ -540,19 +580,20 @@ impl Ipv6Extensions {
                 },
                 IPV6_DEST_OPTIONS => {
                     // the destination options are allowed to be written twice
-                    // once before a routing header and once after.
-                    if route_refed {
-                        if outstanding_refs.final_destination_options {
-                            let header = &self.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap();
-                            next = header.next_header;
-                            outstanding_refs.final_destination_options = false;
-                        } else {
-                            break;
-                        }
-                    } else if outstanding_refs.destination_options {
+                    // once before a routing header and once after (or once
+                    // before the upper layer header if no routing header is present).
+                    if route_refed && outstanding_refs.routing_final_destination_options {
+                        let header = &self.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap();
+                        next = header.next_header;
+                        outstanding_refs.routing_final_destination_options = false;
+                    } else if !route_refed && outstanding_refs.destination_options {
                         let header = &self.destination_options.as_ref().unwrap();
                         next = header.next_header;
                         outstanding_refs.destination_options = false;
+                    } else if outstanding_refs.final_destination_options {
+                        let header = &self.final_destination_options.as_ref().unwrap();
+                        next = header.next_header;
+                        outstanding_refs.final_destination_options = false;
                     } else {
                         break;
                     }
@@ -616,7 +657,7 @@ impl Ipv6Extensions {
                 Ipv6ExtensionNotReferenced(IpNumber::AuthenticationHeader)
             );
         }
-        if outstanding_refs.final_destination_options {
+        if outstanding_refs.routing_final_destination_options || outstanding_refs.final_destination_options {
             return Err(
                 Ipv6ExtensionNotReferenced(IpNumber::IPv6DestinationOptions)
             );
@@ -625,6 +666,99 @@ impl Ipv6Extensions {
         Ok(next)
     }
 
+    /// Checks that the extension headers referenced via the `next_header`
+    /// fields (starting with the given ip number of the IPv6 header) are
+    /// ordered as recommended by RFC 8200 section 4.1:
+    ///
+    /// * Hop-by-Hop Options header
+    /// * Destination Options header
+    /// * Routing header
+    /// * Fragment header
+    /// * Authentication header
+    /// * Destination Options header (after the routing header)
+    ///
+    /// The first header placed after a header it should precede is returned
+    /// as a `ValueError::Ipv6ExtensionOutOfOrder` error. Additionally all
+    /// errors of [`Ipv6Extensions::next_header`] are returned (e.g. headers
+    /// that are not referenced or a hop by hop header that is not located
+    /// directly after the IPv6 header).
+    pub fn check_order(&self, first_header: u8) -> Result<(), ValueError> {
+        use ip_number::*;
+        use IpNumber::*;
+
+        // ensures that all present headers are referenced exactly once
+        self.next_header(first_header)?;
+
+        let routing_final_destination_options = self.routing.as_ref()
+            .and_then(|routing| routing.final_destination_options.as_ref());
+        let len = [
+            self.hop_by_hop_options.is_some(),
+            self.destination_options.is_some(),
+            self.routing.is_some(),
+            routing_final_destination_options.is_some(),
+            self.fragment.is_some(),
+            self.auth.is_some(),
+            self.final_destination_options.is_some(),
+        ].iter().filter(|present| **present).count();
+
+        // the destination options headers are assigned in the same way as
+        // in `next_header` & `write`
+        let mut next = first_header;
+        let mut route_seen = false;
+        let mut destination_options_seen = false;
+        let mut routing_final_destination_options_seen = false;
+        // position in the recommended order & ip number of the previous header
+        let mut previous: Option<(u8, IpNumber)> = None;
+        for _ in 0..len {
+            let (position, ip_number, next_header) = match next {
+                IPV6_HOP_BY_HOP => (0, IPv6HeaderHopByHop, self.hop_by_hop_options.as_ref().map(|h| h.next_header)),
+                IPV6_DEST_OPTIONS if !route_seen && !destination_options_seen && self.destination_options.is_some() => {
+                    destination_options_seen = true;
+                    (1, IPv6DestinationOptions, self.destination_options.as_ref().map(|h| h.next_header))
+                },
+                IPV6_DEST_OPTIONS if route_seen && !routing_final_destination_options_seen && routing_final_destination_options.is_some() => {
+                    routing_final_destination_options_seen = true;
+                    (5, IPv6DestinationOptions, routing_final_destination_options.map(|h| h.next_header))
+                },
+                IPV6_ROUTE => {
+                    route_seen = true;
+                    (2, IPv6RouteHeader, self.routing.as_ref().map(|r| r.routing.next_header))
+                },
+                IPV6_FRAG => (3, IPv6FragmentationHeader, self.fragment.as_ref().map(|h| h.next_header)),
+                AUTH => (4, AuthenticationHeader, self.auth.as_ref().map(|h| h.next_header)),
+                IPV6_DEST_OPTIONS => (5, IPv6DestinationOptions, self.final_destination_options.as_ref().map(|h| h.next_header)),
+                _ => break,
+            };
+            if let Some((previous_position, previous_ip_number)) = previous {
+                if previous_position > position {
+                    return Err(ValueError::Ipv6ExtensionOutOfOrder{
+                        header: ip_number,
+                        previous: previous_ip_number,
+                    });
+                }
+            }
+            previous = Some((position, ip_number));
+            match next_header {
+                Some(value) => next = value,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Reorders the extension headers into the order recommended by
+    /// RFC 8200 (see [`Ipv6Extensions::check_order`]) by updating the
+    /// `next_header` fields & returns the ip number that has to be set as
+    /// `next_header` in the IPv6 header.
+    ///
+    /// The ip number following the extension headers is determined via
+    /// [`Ipv6Extensions::next_header`] (starting with the given ip number
+    /// of the IPv6 header) and its errors are returned.
+    pub fn canonicalize(&mut self, first_header: u8) -> Result<u8, ValueError> {
+        let last_protocol_number = self.next_header(first_header)?;
+        Ok(self.set_next_headers(last_protocol_number))
+    }
+
     /// Returns true if a fragmentation header is present in
     /// the extensions that fragments the payload.
     ///
@@ -642,6 +776,52 @@ impl Ipv6Extensions {
         }
     }
 
+    /// Returns the length contained in the "Jumbo Payload" option (RFC 2675)
+    /// of the hop by hop options header (if present).
+    #[inline]
+    pub fn jumbo_payload_length(&self) -> Option<u32> {
+        self.hop_by_hop_options.as_ref().and_then(|header| header.jumbo_payload_length())
+    }
+
+    /// Sets the length in the "Jumbo Payload" option (RFC 2675) of the hop
+    /// by hop options header. If no hop by hop options header is present a
+    /// new one containing only the option is added (the `next_header` fields
+    /// have to be updated afterwards, e.g. via [`Ipv6Extensions::set_next_headers`]).
+    ///
+    /// See [`Ipv6RawExtensionHeader::set_jumbo_payload_length`] for the errors.
+    pub fn set_jumbo_payload_length(&mut self, jumbo_payload_length: u32) -> Result<(), ValueError> {
+        match self.hop_by_hop_options {
+            Some(ref mut header) => header.set_jumbo_payload_length(jumbo_payload_length),
+            None => {
+                self.hop_by_hop_options = Some(Ipv6RawExtensionHeader::new_jumbo_payload(0, jumbo_payload_length));
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the value contained in the "Router Alert" option (RFC 2711)
+    /// of the hop by hop options header (if present).
+    #[inline]
+    pub fn router_alert(&self) -> Option<u16> {
+        self.hop_by_hop_options.as_ref().and_then(|header| header.router_alert())
+    }
+
+    /// Sets the value of the "Router Alert" option (RFC 2711) of the hop by
+    /// hop options header. If no hop by hop options header is present a new
+    /// one containing only the option is added (the `next_header` fields
+    /// have to be updated afterwards, e.g. via [`Ipv6Extensions::set_next_headers`]).
+    ///
+    /// See [`Ipv6RawExtensionHeader::set_router_alert`] for the errors.
+    pub fn set_router_alert(&mut self, value: u16) -> Result<(), ValueError> {
+        match self.hop_by_hop_options {
+            Some(ref mut header) => header.set_router_alert(value),
+            None => {
+                self.hop_by_hop_options = Some(Ipv6RawExtensionHeader::new_router_alert(0, value));
+                Ok(())
+            }
+        }
+    }
+
     /// Returns true if no IPv6 extension header is present (all fields `None`).
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -649,7 +829,8 @@ impl Ipv6Extensions {
         self.destination_options.is_none() &&
         self.routing.is_none() &&
         self.fragment.is_none() &&
-        self.auth.is_none()
+        self.auth.is_none() &&
+        self.final_destination_options.is_none()
     }
 }
 
@@ -661,6 +842,15 @@ pub struct Ipv6RoutingExtensions {
     pub final_destination_options: Option<Ipv6RawExtensionHeader>
 }
 
+impl Ipv6RoutingExtensions {
+    /// Decodes the routing header (see [`Ipv6RoutingHeader::from_payload`]
+    /// for the errors).
+    #[inline]
+    pub fn routing_header(&self) -> Result<Ipv6RoutingHeader, ReadError> {
+        Ipv6RoutingHeader::from_raw(&self.routing)
+    }
+}
+
 /// Slice containing the IPv6 extension headers present after the ip header.
 ///
 /// Currently supported:
@@ -691,27 +881,48 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
     /// Collects all ipv6 extension headers in a slice & checks if
     /// a fragmentation header that fragments the packet is present.
     pub fn from_slice(start_ip_number: u8, start_slice: &'a [u8]) -> Result<(Ipv6ExtensionsSlice, u8, &'a[u8]), ReadError> {
+        Ipv6ExtensionsSlice::from_slice_with_limits(start_ip_number, start_slice, ParseLimits::DEFAULT)
+    }
+
+    /// Collects all ipv6 extension headers in a slice & checks if
+    /// a fragmentation header that fragments the packet is present.
+    ///
+    /// Returns a [`ReadError::ParseLimitExceeded`] error as soon as the
+    /// number of extension headers or the option bytes of a hop by hop or
+    /// destination options header exceed the given limits.
+    pub fn from_slice_with_limits(start_ip_number: u8, start_slice: &'a [u8], limits: ParseLimits) -> Result<(Ipv6ExtensionsSlice<'a>, u8, &'a[u8]), ReadError> {
         let mut rest = start_slice;
         let mut next_header = start_ip_number;
         let mut fragmented = false;
+        let mut count = 0;
 
         use ip_number::*;
         use ReadError::*;
 
         // the hop by hop header is required to occur directly after the ipv6 header
         if IPV6_HOP_BY_HOP == next_header {
+            count += 1;
+            limits.check_ipv6_extension_headers(count)?;
             let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+            limits.check_option_bytes(slice.payload().len())?;
             rest = &rest[slice.slice().len()..];
             next_header = slice.next_header();
         }
  
         loop {
+            if matches!(next_header, IPV6_DEST_OPTIONS | IPV6_ROUTE | IPV6_FRAG | AUTH) {
+                count += 1;
+                limits.check_ipv6_extension_headers(count)?;
+            }
             match next_header {
                 IPV6_HOP_BY_HOP => {
                     return Err(Ipv6HopByHopHeaderNotAtStart);
                 },
                 IPV6_DEST_OPTIONS | IPV6_ROUTE => {
                     let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                    if IPV6_DEST_OPTIONS == next_header {
+                        limits.check_option_bytes(slice.payload().len())?;
+                    }
                     // SAFETY:
                     // Ipv6RawExtensionHeaderSlice::from_slice always generates
                     // a subslice from the given slice rest. Therefor it is guranteed
@@ -808,6 +1019,144 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
     pub fn is_empty(&self) -> bool {
         self.slice.is_empty()
     }
+
+    /// Returns an iterator over the ip numbers of the extension headers in
+    /// the order they are present in the slice together with the byte range
+    /// of each header (relative to the start of the slice).
+    ///
+    /// See [`SlicedPacket::ip_number_chain`] for a chain that also contains
+    /// the upper layer protocol.
+    #[inline]
+    pub fn ip_number_chain(&self) -> Ipv6ExtensionChainIter<'a> {
+        Ipv6ExtensionChainIter{
+            iter: self.clone().into_iter(),
+            offset: 0,
+        }
+    }
+}
+
+/// Ordered list of all ipv6 extension headers present in a slice, collected
+/// without enforcing the header order or rejecting repeated headers.
+///
+/// In contrast to [`Ipv6ExtensionsSlice`] & [`Ipv6Extensions`] no header
+/// gets dropped & hop by hop options headers that are not located
+/// directly after the ipv6 header don't cause an error. This allows
+/// consumers (e.g. intrusion detection systems) to inspect abnormal or
+/// malicious packets exactly as they were present on the wire.
+///
+/// Errors encountered while decoding the headers (e.g. a cut off
+/// header) stop the collection. The headers decoded up to that point are
+/// kept & the error is stored in [`Ipv6ExtensionsLaxSlice::stop_err`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // two destination options headers separated by a hop by hop header
+/// let mut data = Vec::new();
+/// Ipv6RawExtensionHeader::new_raw(ip_number::IPV6_HOP_BY_HOP, &[0;6]).unwrap().write(&mut data).unwrap();
+/// Ipv6RawExtensionHeader::new_raw(ip_number::IPV6_DEST_OPTIONS, &[0;6]).unwrap().write(&mut data).unwrap();
+/// Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[0;6]).unwrap().write(&mut data).unwrap();
+///
+/// let lax = Ipv6ExtensionsLaxSlice::from_slice(ip_number::IPV6_DEST_OPTIONS, &data);
+/// assert!(lax.stop_err.is_none());
+/// assert_eq!(ip_number::UDP, lax.next_header);
+/// assert_eq!(
+///     vec![
+///         ip_number::IPV6_DEST_OPTIONS,
+///         ip_number::IPV6_HOP_BY_HOP,
+///         ip_number::IPV6_DEST_OPTIONS,
+///     ],
+///     lax.headers.iter().map(|h| h.ip_number()).collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Ipv6ExtensionsLaxSlice<'a> {
+    /// Extension headers in the order they are present in the slice.
+    pub headers: Vec<Ipv6ExtensionSlice<'a>>,
+    /// IP protocol number of the header following the last collected
+    /// extension header (or of the header that could not be decoded if
+    /// `stop_err` is set).
+    pub next_header: u8,
+    /// Data following the last collected extension header.
+    pub rest: &'a [u8],
+    /// Error that stopped the collection of the extension headers.
+    pub stop_err: Option<ReadError>,
+}
+
+impl<'a> Ipv6ExtensionsLaxSlice<'a> {
+
+    /// Collects all ipv6 extension headers in the given slice starting with
+    /// the header identified by `start_ip_number`.
+    pub fn from_slice(start_ip_number: u8, slice: &'a [u8]) -> Ipv6ExtensionsLaxSlice<'a> {
+        Ipv6ExtensionsLaxSlice::from_slice_with_limits(start_ip_number, slice, ParseLimits::DEFAULT)
+    }
+
+    /// Collects all ipv6 extension headers in the given slice starting with
+    /// the header identified by `start_ip_number`.
+    ///
+    /// The collection is stopped with a [`ReadError::ParseLimitExceeded`]
+    /// error as soon as the number of extension headers or the option bytes
+    /// of a hop by hop or destination options header exceed the given limits.
+    pub fn from_slice_with_limits(start_ip_number: u8, slice: &'a [u8], limits: ParseLimits) -> Ipv6ExtensionsLaxSlice<'a> {
+        let mut headers = Vec::new();
+        let mut next_header = start_ip_number;
+        let mut rest = slice;
+
+        let stop_err = loop {
+            let header = match Ipv6ExtensionsLaxSlice::read_header(next_header, rest, headers.len() + 1, &limits) {
+                Ok(Some(header)) => header,
+                Ok(None) => break None,
+                Err(err) => break Some(err),
+            };
+            next_header = header.next_header();
+            rest = &rest[header.slice().len()..];
+            headers.push(header);
+        };
+
+        Ipv6ExtensionsLaxSlice{
+            headers,
+            next_header,
+            rest,
+            stop_err,
+        }
+    }
+
+    /// Decodes the extension header identified by `ip_number` at the start
+    /// of the slice (`None` if the ip number is not a supported extension
+    /// header).
+    fn read_header(ip_number: u8, slice: &'a [u8], count: usize, limits: &ParseLimits) -> Result<Option<Ipv6ExtensionSlice<'a>>, ReadError> {
+        use ip_number::*;
+        use Ipv6ExtensionSlice::*;
+
+        if !matches!(ip_number, IPV6_HOP_BY_HOP | IPV6_DEST_OPTIONS | IPV6_ROUTE | IPV6_FRAG | AUTH) {
+            return Ok(None);
+        }
+        limits.check_ipv6_extension_headers(count)?;
+        Ok(Some(match ip_number {
+            IPV6_HOP_BY_HOP | IPV6_DEST_OPTIONS => {
+                let header = Ipv6RawExtensionHeaderSlice::from_slice(slice)?;
+                limits.check_option_bytes(header.payload().len())?;
+                if IPV6_HOP_BY_HOP == ip_number {
+                    HopByHop(header)
+                } else {
+                    DestinationOptions(header)
+                }
+            },
+            IPV6_ROUTE => Routing(Ipv6RawExtensionHeaderSlice::from_slice(slice)?),
+            IPV6_FRAG => Fragment(Ipv6FragmentHeaderSlice::from_slice(slice)?),
+            _ => Authentication(IpAuthenticationHeaderSlice::from_slice(slice)?),
+        }))
+    }
+
+    /// Returns true if a fragment header is present that fragments the payload.
+    pub fn is_fragmenting_payload(&self) -> bool {
+        self.headers.iter().any(|header| match header {
+            Ipv6ExtensionSlice::Fragment(fragment) => fragment.is_fragmenting_payload(),
+            _ => false,
+        })
+    }
 }
 
 /// Enum containing a slice of a supported ipv6 extension header.
@@ -839,6 +1188,41 @@ pub enum Ipv6ExtensionSlice<'a> {
     Authentication(IpAuthenticationHeaderSlice<'a>),
 }
 
+impl<'a> Ipv6ExtensionSlice<'a> {
+    /// Returns the IP protocol number identifying the extension header.
+    pub fn ip_number(&self) -> u8 {
+        use ip_number::*;
+        use Ipv6ExtensionSlice::*;
+        match self {
+            HopByHop(_) => IPV6_HOP_BY_HOP,
+            Routing(_) => IPV6_ROUTE,
+            Fragment(_) => IPV6_FRAG,
+            DestinationOptions(_) => IPV6_DEST_OPTIONS,
+            Authentication(_) => AUTH,
+        }
+    }
+
+    /// Returns the IP protocol number of the header following the extension header.
+    pub fn next_header(&self) -> u8 {
+        use Ipv6ExtensionSlice::*;
+        match self {
+            HopByHop(header) | Routing(header) | DestinationOptions(header) => header.next_header(),
+            Fragment(header) => header.next_header(),
+            Authentication(header) => header.next_header(),
+        }
+    }
+
+    /// Returns the slice containing the extension header.
+    pub fn slice(&self) -> &'a [u8] {
+        use Ipv6ExtensionSlice::*;
+        match self {
+            HopByHop(header) | Routing(header) | DestinationOptions(header) => header.slice(),
+            Fragment(header) => header.slice(),
+            Authentication(header) => header.slice(),
+        }
+    }
+}
+
 impl<'a> IntoIterator for Ipv6ExtensionsSlice<'a> {
     type Item = Ipv6ExtensionSlice<'a>;
     type IntoIter = Ipv6ExtensionSliceIter<'a>;
@@ -871,6 +1255,29 @@ impl<'a> Default for Ipv6ExtensionSliceIter<'a> {
     }
 }
 
+/// Iterator over the ip numbers & byte ranges of the extension headers in an
+/// [Ipv6ExtensionsSlice] (see [`Ipv6ExtensionsSlice::ip_number_chain`]).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct Ipv6ExtensionChainIter<'a> {
+    iter: Ipv6ExtensionSliceIter<'a>,
+    /// Offset of the next header in the extensions slice.
+    offset: usize,
+}
+
+impl<'a> Iterator for Ipv6ExtensionChainIter<'a> {
+    type Item = IpNumberChainEntry;
+
+    fn next(&mut self) -> Option<IpNumberChainEntry> {
+        let header = self.iter.next()?;
+        let start = self.offset;
+        self.offset += header.slice().len();
+        Some(IpNumberChainEntry{
+            ip_number: header.ip_number(),
+            range: start..self.offset,
+        })
+    }
+}
+
 impl<'a> Iterator for Ipv6ExtensionSliceIter<'a> {
     type Item = Ipv6ExtensionSlice<'a>;
 
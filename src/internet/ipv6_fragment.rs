@@ -58,10 +58,10 @@ impl Ipv6FragmentHeader {
                 [
                     (buffer[2] >> 3) & 0b0001_1111u8,
                     ((buffer[2] << 5) & 0b1110_0000u8) |
-                    (buffer[3] & 0b0001_1111u8)
+                    ((buffer[3] >> 3) & 0b0001_1111u8)
                 ]
             ),
-            more_fragments: 0 != buffer[3] & 0b1000_0000u8,
+            more_fragments: 0 != buffer[3] & 0b0000_0001u8,
             identification: u32::from_be_bytes(
                 [
                     buffer[4],
@@ -158,9 +158,9 @@ impl Ipv6FragmentHeader {
                     ((fo_be[1] >> 5) & 0b0000_0111u8)
                 ),
                 (
-                    (fo_be[1] & 0b0001_1111u8) |
+                    ((fo_be[1] << 3) & 0b1111_1000u8) |
                     if self.more_fragments {
-                        0b1000_0000u8
+                        0b0000_0001u8
                     } else {
                         0
                     }
@@ -252,7 +252,7 @@ impl<'a> Ipv6FragmentHeaderSlice<'a> {
                 [
                     (*self.slice.get_unchecked(2) >> 3) & 0b0001_1111u8,
                     ((*self.slice.get_unchecked(2) << 5) & 0b1110_0000u8) |
-                    (*self.slice.get_unchecked(3) & 0b0001_1111u8)
+                    ((*self.slice.get_unchecked(3) >> 3) & 0b0001_1111u8)
                 ]
             }
         )
@@ -264,7 +264,7 @@ impl<'a> Ipv6FragmentHeaderSlice<'a> {
         // SAFETY:
         // Slice size checked to be at least 8 bytes in constructor.
         unsafe {
-            0 != *self.slice.get_unchecked(3) & 0b1000_0000u8
+            0 != *self.slice.get_unchecked(3) & 0b0000_0001u8
         }
     }
 
@@ -322,10 +322,31 @@ impl<'a> Ipv6FragmentHeaderSlice<'a> {
         // Slice size checked to be at least 8 bytes in constructor.
         unsafe {
             0 != *self.slice.get_unchecked(2) ||
-            0 != (*self.slice.get_unchecked(3) & 0b1001_1111u8) // exclude the reserved bytes
+            0 != (*self.slice.get_unchecked(3) & 0b1111_1001u8) // exclude the reserved bits
         }
     }
 
+    /// Returns true if the header fragments the payload (same as
+    /// [`Ipv6FragmentHeaderSlice::is_fragmenting_payload`]).
+    #[inline]
+    pub fn is_fragment(&self) -> bool {
+        self.is_fragmenting_payload()
+    }
+
+    /// Returns true if the header belongs to the first fragment of a
+    /// fragmented payload (offset 0 & the "more fragments" flag set).
+    #[inline]
+    pub fn is_first_fragment(&self) -> bool {
+        self.more_fragments() && 0 == self.fragment_offset()
+    }
+
+    /// Returns the offset of the fragment in bytes (the "fragment_offset"
+    /// field is in units of 8 octets).
+    #[inline]
+    pub fn fragment_byte_offset(&self) -> u16 {
+        self.fragment_offset()*8
+    }
+
     /// Identifcation value generated by the source 
     pub fn identification(&self) -> u32 {
         // SAFETY:
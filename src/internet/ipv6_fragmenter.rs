@@ -0,0 +1,213 @@
+use super::super::*;
+
+/// Iterator splitting an IPv6 packet into fragments fitting into a given
+/// MTU (RFC 8200 section 4.5).
+///
+/// Each item contains the IPv6 header (with the payload length & next
+/// header set), the extension headers of the fragment & the slice of the
+/// payload carried by the fragment.
+///
+/// The extension headers of every fragment contain the "unfragmentable
+/// part" (hop by hop options, destination options & routing header) and a
+/// fragment header with the given identification value. The
+/// "fragmentable" extension headers (authentication header & the final
+/// destination options headers) are only part of the
+/// first fragment and have to fit into it. All `next_header` fields of the
+/// extension headers are set based on the order defined in RFC 8200 (see
+/// [`Ipv6Extensions::set_next_headers`]).
+///
+/// Packets already fitting into the MTU result in a single packet without
+/// an added fragment header. Packets that already contain a fragment
+/// header are passed through with it or split further (keeping the
+/// original identification value, the offsets are relative to the original
+/// offset & the last fragment keeps the original "more fragments" flag).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// let header = Ipv6Header{
+///     next_header: ip_number::UDP,
+///     hop_limit: 64,
+///     ..Default::default()
+/// };
+/// let payload = [0u8;100];
+///
+/// let mut packets = Vec::new();
+/// let fragmenter = Ipv6Fragmenter::new(&header, &Default::default(), &payload, 1234, 88).unwrap();
+/// for (fragment_header, fragment_extensions, fragment_payload) in fragmenter {
+///     let mut packet = Vec::new();
+///     fragment_header.write(&mut packet).unwrap();
+///     fragment_extensions.write(&mut packet, fragment_header.next_header).unwrap();
+///     packet.extend_from_slice(fragment_payload);
+///     packets.push(packet);
+/// }
+///
+/// // 40 + 40 + 20 bytes of payload
+/// assert_eq!(vec![88, 88, 68], packets.iter().map(|p| p.len()).collect::<Vec<_>>());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv6Fragmenter<'a> {
+    /// IPv6 header used for all fragments.
+    header: Ipv6Header,
+    /// Extension headers used for the first fragment (without a fragment header).
+    first: Ipv6Extensions,
+    /// Unfragmentable extension headers used for the following fragments.
+    subsequent: Ipv6Extensions,
+    /// Ip number of the header following the extension headers.
+    protocol: u8,
+    /// Fragment header already present in the packet.
+    fragment: Option<Ipv6FragmentHeader>,
+    /// Identification value set in the fragment headers.
+    identification: u32,
+    /// Payload not yet returned as part of a fragment.
+    payload: &'a [u8],
+    /// Offset in bytes of the remaining fragmentable part relative to the start of the original packet.
+    offset: usize,
+    /// Maximum length of the headers & payload of a fragment.
+    mtu: usize,
+    /// "more fragments" flag of the original fragment header (set in the last fragment).
+    more_fragments: bool,
+    /// True if the packet does not fit into the MTU and fragment headers have to be added.
+    fragmenting: bool,
+    /// True if the first fragment was not yet returned.
+    first_pending: bool,
+}
+
+impl<'a> Ipv6Fragmenter<'a> {
+    /// Creates an iterator over the fragments of the packet with the given
+    /// header, extension headers & payload (the payload length of the
+    /// header is ignored).
+    ///
+    /// The given identification value is only used if the extension
+    /// headers do not already contain a fragment header (otherwise the
+    /// identification value of the existing fragment header is kept).
+    ///
+    /// The `next_header` field of the header & the extension headers have
+    /// to reference all present extension headers (otherwise the error
+    /// of [`Ipv6Extensions::next_header`] is returned). If the packet has
+    /// to be fragmented a `ValueError::Ipv6FragmentMtuTooSmall` is returned
+    /// in case the first fragment can not contain the unfragmentable &
+    /// fragmentable extension headers or a fragment can not contain at
+    /// least 8 bytes of payload.
+    ///
+    /// Additionally the reassembled packet has to fit into the payload
+    /// length field (otherwise a `ValueError::Ipv6PayloadLengthTooLarge`
+    /// error is returned).
+    pub fn new(header: &Ipv6Header, extensions: &Ipv6Extensions, payload: &'a [u8], identification: u32, mtu: usize) -> Result<Ipv6Fragmenter<'a>, ValueError> {
+        use crate::ValueError::*;
+
+        let protocol = extensions.next_header(header.next_header)?;
+
+        let mut first = extensions.clone();
+        let fragment = first.fragment.take();
+        let (offset, more_fragments, identification) = match fragment {
+            Some(ref fragment) => {
+                max_check_u16(fragment.fragment_offset, 0b0001_1111_1111_1111, ErrorField::Ipv6FragmentOffset)?;
+                (usize::from(fragment.fragment_offset)*8, fragment.more_fragments, fragment.identification)
+            },
+            None => (0, false, identification),
+        };
+
+        let mut subsequent = first.clone();
+        subsequent.auth = None;
+        subsequent.final_destination_options = None;
+        if let Some(ref mut routing) = subsequent.routing {
+            routing.final_destination_options = None;
+        }
+        let fragmentable_len = first.header_len() - subsequent.header_len();
+
+        let reassembled_len = subsequent.header_len() + offset + fragmentable_len + payload.len();
+        if reassembled_len > usize::from(u16::MAX) {
+            return Err(Ipv6PayloadLengthTooLarge(reassembled_len));
+        }
+
+        let mtu = std::cmp::min(mtu, Ipv6Header::SERIALIZED_SIZE + usize::from(u16::MAX));
+        let fragmenting = header.header_len() + extensions.header_len() + payload.len() > mtu;
+        if fragmenting {
+            // the fragmentable extension headers have to fit into the first
+            // fragment & every fragment has to contain at least 8 bytes
+            let min_mtu = header.header_len() + subsequent.header_len() + 8 +
+                std::cmp::max(8, (fragmentable_len + 7) & !0b111);
+            if mtu < min_mtu {
+                return Err(Ipv6FragmentMtuTooSmall(mtu));
+            }
+        }
+
+        Ok(Ipv6Fragmenter{
+            header: header.clone(),
+            first,
+            subsequent,
+            protocol,
+            fragment,
+            identification,
+            payload,
+            offset,
+            mtu,
+            more_fragments,
+            fragmenting,
+            first_pending: true,
+        })
+    }
+}
+
+impl<'a> Iterator for Ipv6Fragmenter<'a> {
+    type Item = (Ipv6Header, Ipv6Extensions, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // packets without payload still result in one fragment
+        if self.payload.is_empty() && !self.first_pending {
+            return None;
+        }
+
+        let mut header = self.header.clone();
+        let mut extensions = if self.first_pending {
+            self.first.clone()
+        } else {
+            self.subsequent.clone()
+        };
+        self.first_pending = false;
+
+        // packets fitting into the mtu are passed through unchanged
+        if !self.fragmenting {
+            let payload = self.payload;
+            self.payload = &[];
+            extensions.fragment = self.fragment.clone();
+            header.next_header = extensions.set_next_headers(self.protocol);
+            // lengths are checked in the constructor
+            header.payload_length = (extensions.header_len() + payload.len()) as u16;
+            return Some((header, extensions, payload));
+        }
+
+        // fragmentable extension headers (only present in the first fragment)
+        let extensions_len = extensions.header_len() - self.subsequent.header_len();
+
+        // fragments except the last one have to contain a multiple of 8 bytes
+        let space = self.mtu - header.header_len() - self.subsequent.header_len() - 8;
+        let (data_len, last) = if extensions_len + self.payload.len() <= space {
+            (extensions_len + self.payload.len(), true)
+        } else {
+            (space & !0b111, false)
+        };
+        let (fragment, rest) = self.payload.split_at(data_len - extensions_len);
+
+        extensions.fragment = Some(Ipv6FragmentHeader::new(
+            0,
+            // ranges & lengths are checked in the constructor
+            (self.offset / 8) as u16,
+            if last {
+                self.more_fragments
+            } else {
+                true
+            },
+            self.identification
+        ));
+        header.next_header = extensions.set_next_headers(self.protocol);
+        header.payload_length = (extensions.header_len() + fragment.len()) as u16;
+
+        self.payload = rest;
+        self.offset += data_len;
+        Some((header, extensions, fragment))
+    }
+}
@@ -57,6 +57,26 @@ impl Ipv6RawExtensionHeader {
     /// Maximum length of a [Ipv6RawExtensionHeader] the payload
     pub const MAX_PAYLOAD_LEN: usize = 0xff*8 + 6;
 
+    /// Option type of the "Jumbo Payload" option (RFC 2675) contained in
+    /// hop by hop options headers of jumbograms.
+    pub const JUMBO_PAYLOAD_OPTION_TYPE: u8 = 0xc2;
+
+    /// Option type of the "Router Alert" option (RFC 2711) contained in
+    /// hop by hop options headers.
+    pub const ROUTER_ALERT_OPTION_TYPE: u8 = 0x05;
+
+    /// "Router Alert" option value for datagrams containing a Multicast
+    /// Listener Discovery message (RFC 2711).
+    pub const ROUTER_ALERT_MLD: u16 = 0;
+
+    /// "Router Alert" option value for datagrams containing a RSVP message
+    /// (RFC 2711).
+    pub const ROUTER_ALERT_RSVP: u16 = 1;
+
+    /// "Router Alert" option value for datagrams containing an Active
+    /// Networks message (RFC 2711).
+    pub const ROUTER_ALERT_ACTIVE_NETWORKS: u16 = 2;
+
     /// Returns true if the given header type ip number can be represented in an `Ipv6ExtensionHeader`.
     pub fn header_type_supported(next_header: u8) -> bool {
         use crate::ip_number::*;
@@ -94,6 +114,36 @@ impl Ipv6RawExtensionHeader {
         }
     }
 
+    /// Creates a header containing only a "Jumbo Payload" option (RFC 2675)
+    /// with the given length (only valid as a hop by hop options header).
+    pub fn new_jumbo_payload(next_header: u8, jumbo_payload_length: u32) -> Ipv6RawExtensionHeader {
+        let len_be = jumbo_payload_length.to_be_bytes();
+        // can not fail as the payload has a valid length
+        Ipv6RawExtensionHeader::new_raw(
+            next_header,
+            &[
+                Self::JUMBO_PAYLOAD_OPTION_TYPE, 4,
+                len_be[0], len_be[1], len_be[2], len_be[3],
+            ]
+        ).unwrap()
+    }
+
+    /// Creates a header containing only a "Router Alert" option (RFC 2711)
+    /// with the given value (only valid as a hop by hop options header).
+    pub fn new_router_alert(next_header: u8, value: u16) -> Ipv6RawExtensionHeader {
+        let value_be = value.to_be_bytes();
+        // can not fail as the payload has a valid length
+        Ipv6RawExtensionHeader::new_raw(
+            next_header,
+            &[
+                Self::ROUTER_ALERT_OPTION_TYPE, 2,
+                value_be[0], value_be[1],
+                // PadN option
+                1, 0,
+            ]
+        ).unwrap()
+    }
+
     /// Read an Ipv6ExtensionHeader from a slice and return the header & unused parts of the slice.
     pub fn from_slice(slice: &[u8]) -> Result<(Ipv6RawExtensionHeader, &[u8]), ReadError> {
         let s = Ipv6RawExtensionHeaderSlice::from_slice(slice)?;
@@ -164,6 +214,113 @@ impl Ipv6RawExtensionHeader {
     pub fn header_len(&self) -> usize {
         2 + (6 + usize::from(self.header_length)*8)
     }
+
+    /// Returns the length contained in the "Jumbo Payload" option (RFC 2675)
+    /// if the header contains one (interpreting the payload as options of a
+    /// hop by hop options header).
+    pub fn jumbo_payload_length(&self) -> Option<u32> {
+        find_option(self.payload(), Self::JUMBO_PAYLOAD_OPTION_TYPE, 4)
+            .map(|offset| u32::from_be_bytes([
+                self.payload_buffer[offset],
+                self.payload_buffer[offset + 1],
+                self.payload_buffer[offset + 2],
+                self.payload_buffer[offset + 3],
+            ]))
+    }
+
+    /// Sets the length in the "Jumbo Payload" option (RFC 2675) of a hop by
+    /// hop options header. If no such option is present it is appended to
+    /// the existing options (including the required alignment & padding).
+    ///
+    /// In case the resulting payload is larger then
+    /// `Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN` an
+    /// `Err(ValueError::Ipv6ExtensionPayloadTooLarge)` is returned and the
+    /// header is not changed.
+    pub fn set_jumbo_payload_length(&mut self, jumbo_payload_length: u32) -> Result<(), ValueError> {
+        let len_be = jumbo_payload_length.to_be_bytes();
+        if let Some(offset) = find_option(self.payload(), Self::JUMBO_PAYLOAD_OPTION_TYPE, 4) {
+            self.payload_buffer[offset..offset + 4].copy_from_slice(&len_be);
+            return Ok(());
+        }
+
+        // the option requires an alignment of 4n + 2 (relative to the start of the header)
+        let mut payload = self.payload().to_vec();
+        let alignment_len = (4 - (payload.len() & 0b11)) & 0b11;
+        push_option_padding(&mut payload, alignment_len);
+        payload.extend_from_slice(&[Self::JUMBO_PAYLOAD_OPTION_TYPE, 4]);
+        payload.extend_from_slice(&len_be);
+        let padding_len = (8 - ((payload.len() + 2) & 0b111)) & 0b111;
+        push_option_padding(&mut payload, padding_len);
+        self.set_payload(&payload)
+    }
+
+    /// Returns the value of the "Router Alert" option (RFC 2711) if the
+    /// header contains one (interpreting the payload as options of a hop
+    /// by hop options header).
+    pub fn router_alert(&self) -> Option<u16> {
+        find_option(self.payload(), Self::ROUTER_ALERT_OPTION_TYPE, 2)
+            .map(|offset| u16::from_be_bytes([
+                self.payload_buffer[offset],
+                self.payload_buffer[offset + 1],
+            ]))
+    }
+
+    /// Sets the value of the "Router Alert" option (RFC 2711) of a hop by
+    /// hop options header. If no such option is present it is appended to
+    /// the existing options (including the required alignment & padding).
+    ///
+    /// In case the resulting payload is larger then
+    /// `Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN` an
+    /// `Err(ValueError::Ipv6ExtensionPayloadTooLarge)` is returned and the
+    /// header is not changed.
+    pub fn set_router_alert(&mut self, value: u16) -> Result<(), ValueError> {
+        let value_be = value.to_be_bytes();
+        if let Some(offset) = find_option(self.payload(), Self::ROUTER_ALERT_OPTION_TYPE, 2) {
+            self.payload_buffer[offset..offset + 2].copy_from_slice(&value_be);
+            return Ok(());
+        }
+
+        // the option requires an alignment of 2n (relative to the start of the header)
+        let mut payload = self.payload().to_vec();
+        let alignment_len = payload.len() & 0b1;
+        push_option_padding(&mut payload, alignment_len);
+        payload.extend_from_slice(&[Self::ROUTER_ALERT_OPTION_TYPE, 2]);
+        payload.extend_from_slice(&value_be);
+        let padding_len = (8 - ((payload.len() + 2) & 0b111)) & 0b111;
+        push_option_padding(&mut payload, padding_len);
+        self.set_payload(&payload)
+    }
+}
+
+/// Returns the offset of the data of the option with the given type &
+/// data length in the given hop by hop or destination options.
+fn find_option(options: &[u8], option_type: u8, data_len: usize) -> Option<usize> {
+    let mut offset = 0;
+    while offset < options.len() {
+        // Pad1 option (no length field)
+        if 0 == options[offset] {
+            offset += 1;
+            continue;
+        }
+        let len = usize::from(*options.get(offset + 1)?);
+        if option_type == options[offset] && data_len == len && offset + 2 + len <= options.len() {
+            return Some(offset + 2);
+        }
+        offset += 2 + len;
+    }
+    None
+}
+
+/// Appends Pad1 or PadN options with the given length.
+fn push_option_padding(options: &mut Vec<u8>, len: usize) {
+    match len {
+        0 => {},
+        1 => options.push(0),
+        len => {
+            options.extend_from_slice(&[1, (len - 2) as u8]);
+            options.resize(options.len() + len - 2, 0);
+        }
+    }
 }
 
 /// Slice containing an IPv6 extension header without specific decoding methods (fallback in case no specific implementation is available).
@@ -276,6 +433,29 @@ impl<'a> Ipv6RawExtensionHeaderSlice<'a> {
         }
     }
 
+    /// Returns the length contained in the "Jumbo Payload" option (RFC 2675)
+    /// if the header contains one (interpreting the payload as options of a
+    /// hop by hop options header).
+    pub fn jumbo_payload_length(&self) -> Option<u32> {
+        let payload = self.payload();
+        find_option(payload, Ipv6RawExtensionHeader::JUMBO_PAYLOAD_OPTION_TYPE, 4)
+            .map(|offset| u32::from_be_bytes([
+                payload[offset],
+                payload[offset + 1],
+                payload[offset + 2],
+                payload[offset + 3],
+            ]))
+    }
+
+    /// Returns the value of the "Router Alert" option (RFC 2711) if the
+    /// header contains one (interpreting the payload as options of a hop
+    /// by hop options header).
+    pub fn router_alert(&self) -> Option<u16> {
+        let payload = self.payload();
+        find_option(payload, Ipv6RawExtensionHeader::ROUTER_ALERT_OPTION_TYPE, 2)
+            .map(|offset| u16::from_be_bytes([payload[offset], payload[offset + 1]]))
+    }
+
     /// Convert the slice to an [Ipv6RawExtensionHeader].
     ///
     /// Decode some of the fields and copy the results to a 
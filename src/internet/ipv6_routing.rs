@@ -0,0 +1,253 @@
+use super::super::*;
+
+/// Decoded content of an IPv6 routing header (the routing type specific
+/// part of an [`Ipv6RawExtensionHeader`] with the `next_header` value
+/// [`ip_number::IPV6_ROUTE`]).
+///
+/// Routing types without a specific decoding are returned as
+/// [`Ipv6RoutingHeader::Unknown`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // routing header of type 2 containing a home address
+/// let mut payload = [0u8;22];
+/// payload[0] = Ipv6RoutingHeader::TYPE_2;
+/// payload[1] = 1; // segments left
+/// payload[6..].copy_from_slice(&[1;16]);
+/// let raw = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &payload).unwrap();
+///
+/// let routing = Ipv6RoutingHeader::from_raw(&raw).unwrap();
+/// assert_eq!(
+///     Ipv6RoutingHeader::Type2{ segments_left: 1, home_address: [1;16] },
+///     routing
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Ipv6RoutingHeader {
+    /// Type 0 routing header (RFC 2460, deprecated by RFC 5095) containing
+    /// a list of addresses.
+    Type0 {
+        /// Number of route segments remaining.
+        segments_left: u8,
+        /// Addresses of the nodes to be visited.
+        addresses: Vec<[u8;16]>,
+    },
+    /// Type 2 routing header used by Mobile IPv6 (RFC 6275) containing the
+    /// home address of the mobile node.
+    Type2 {
+        /// Number of route segments remaining.
+        segments_left: u8,
+        /// Home address of the destination mobile node.
+        home_address: [u8;16],
+    },
+    /// Source routing header used by RPL (RFC 6554, routing type 3).
+    ///
+    /// The first `cmpr_i` octets of all addresses except the last one &
+    /// the first `cmpr_e` octets of the last address are elided and are
+    /// identical to the ones of the IPv6 destination address (see
+    /// [`Ipv6RoutingHeader::addresses`]).
+    Rpl {
+        /// Number of route segments remaining.
+        segments_left: u8,
+        /// Number of prefix octets elided from all addresses except the last one (0-15).
+        cmpr_i: u8,
+        /// Number of prefix octets elided from the last address (0-15).
+        cmpr_e: u8,
+        /// Addresses without the elided prefix octets (`16 - cmpr_i`
+        /// octets long, except the last one with `16 - cmpr_e` octets).
+        addresses: Vec<Vec<u8>>,
+    },
+    /// Segment routing header used by SRv6 (RFC 8754, routing type 4).
+    SegmentRouting(Ipv6SegmentRoutingHeader),
+    /// Routing header with a routing type that can not be decoded.
+    Unknown {
+        /// Routing type value.
+        routing_type: u8,
+        /// Number of route segments remaining.
+        segments_left: u8,
+        /// Routing type specific data.
+        data: Vec<u8>,
+    },
+}
+
+impl Ipv6RoutingHeader {
+    /// Routing type of the type 0 routing header (RFC 2460).
+    pub const TYPE_0: u8 = 0;
+
+    /// Routing type of the Mobile IPv6 type 2 routing header (RFC 6275).
+    pub const TYPE_2: u8 = 2;
+
+    /// Routing type of the RPL source routing header (RFC 6554).
+    pub const TYPE_RPL: u8 = 3;
+
+    /// Routing type of the segment routing header (RFC 8754).
+    pub const TYPE_SEGMENT_ROUTING: u8 = 4;
+
+    /// Decodes the payload of a routing header (starting with the routing
+    /// type, see [`Ipv6RawExtensionHeader::payload`]).
+    ///
+    /// A `ReadError::UnexpectedEndOfSlice` is returned if the payload is
+    /// smaller then the 6 bytes of the fixed part of a routing header &
+    /// a `ReadError::Ipv6RoutingHeaderLengthBad` if the length of the
+    /// payload does not fit the addresses of a type 0, type 2 or RPL
+    /// routing header (see [`Ipv6SegmentRoutingHeader::from_payload`] for
+    /// the errors of segment routing headers).
+    pub fn from_payload(payload: &[u8]) -> Result<Ipv6RoutingHeader, ReadError> {
+        use crate::ReadError::*;
+
+        if payload.len() < Ipv6RawExtensionHeader::MIN_PAYLOAD_LEN {
+            return Err(UnexpectedEndOfSlice(Ipv6RawExtensionHeader::MIN_PAYLOAD_LEN));
+        }
+        let routing_type = payload[0];
+        let segments_left = payload[1];
+        // skip the reserved (or routing type specific) 4 bytes
+        let data = &payload[6..];
+
+        match routing_type {
+            Ipv6RoutingHeader::TYPE_0 => {
+                if !data.len().is_multiple_of(16) {
+                    return Err(Ipv6RoutingHeaderLengthBad(routing_type));
+                }
+                Ok(Ipv6RoutingHeader::Type0 {
+                    segments_left,
+                    addresses: data.chunks(16).map(Ipv6RoutingHeader::address).collect(),
+                })
+            },
+            Ipv6RoutingHeader::TYPE_2 => {
+                if 16 != data.len() {
+                    return Err(Ipv6RoutingHeaderLengthBad(routing_type));
+                }
+                Ok(Ipv6RoutingHeader::Type2 {
+                    segments_left,
+                    home_address: Ipv6RoutingHeader::address(data),
+                })
+            },
+            Ipv6RoutingHeader::TYPE_RPL => {
+                let cmpr_i = payload[2] >> 4;
+                let cmpr_e = payload[2] & 0xf;
+                let pad = usize::from(payload[3] >> 4);
+                let len_i = 16 - usize::from(cmpr_i);
+                let len_e = 16 - usize::from(cmpr_e);
+
+                // the addresses are followed by the padding & at least
+                // one (the last) address has to be present
+                if data.len() < len_e + pad || !(data.len() - len_e - pad).is_multiple_of(len_i) {
+                    return Err(Ipv6RoutingHeaderLengthBad(routing_type));
+                }
+                let (data, _) = data.split_at(data.len() - pad);
+                let (data, last) = data.split_at(data.len() - len_e);
+                let mut addresses: Vec<Vec<u8>> = data.chunks(len_i).map(|a| a.to_vec()).collect();
+                addresses.push(last.to_vec());
+
+                Ok(Ipv6RoutingHeader::Rpl {
+                    segments_left,
+                    cmpr_i,
+                    cmpr_e,
+                    addresses,
+                })
+            },
+            Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING => Ok(Ipv6RoutingHeader::SegmentRouting(
+                Ipv6SegmentRoutingHeader::from_payload(payload)?
+            )),
+            _ => Ok(Ipv6RoutingHeader::Unknown {
+                routing_type,
+                segments_left,
+                data: payload[2..].to_vec(),
+            }),
+        }
+    }
+
+    /// Decodes the payload of the given raw routing header (see
+    /// [`Ipv6RoutingHeader::from_payload`] for the errors).
+    #[inline]
+    pub fn from_raw(header: &Ipv6RawExtensionHeader) -> Result<Ipv6RoutingHeader, ReadError> {
+        Ipv6RoutingHeader::from_payload(header.payload())
+    }
+
+    /// Decodes the payload of the given raw routing header slice (see
+    /// [`Ipv6RoutingHeader::from_payload`] for the errors).
+    #[inline]
+    pub fn from_raw_slice(slice: &Ipv6RawExtensionHeaderSlice) -> Result<Ipv6RoutingHeader, ReadError> {
+        Ipv6RoutingHeader::from_payload(slice.payload())
+    }
+
+    /// Returns the routing type value.
+    pub fn routing_type(&self) -> u8 {
+        use Ipv6RoutingHeader::*;
+        match self {
+            Type0{ .. } => Ipv6RoutingHeader::TYPE_0,
+            Type2{ .. } => Ipv6RoutingHeader::TYPE_2,
+            Rpl{ .. } => Ipv6RoutingHeader::TYPE_RPL,
+            SegmentRouting(_) => Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING,
+            Unknown{ routing_type, .. } => *routing_type,
+        }
+    }
+
+    /// Returns the number of route segments remaining.
+    pub fn segments_left(&self) -> u8 {
+        use Ipv6RoutingHeader::*;
+        match self {
+            Type0{ segments_left, .. } |
+            Type2{ segments_left, .. } |
+            Rpl{ segments_left, .. } |
+            Unknown{ segments_left, .. } => *segments_left,
+            SegmentRouting(header) => header.segments_left,
+        }
+    }
+
+    /// Returns the complete addresses contained in the routing header.
+    ///
+    /// For RPL routing headers the elided prefix octets are taken from the
+    /// given destination address of the IPv6 header (the destination is
+    /// ignored for all other routing types). For segment routing headers
+    /// the segment list is returned. Unknown routing types
+    /// result in an empty list.
+    pub fn addresses(&self, destination: &[u8;16]) -> Vec<[u8;16]> {
+        use Ipv6RoutingHeader::*;
+        match self {
+            Type0{ addresses, .. } => addresses.clone(),
+            Type2{ home_address, .. } => vec![*home_address],
+            Rpl{ addresses, .. } => addresses.iter()
+                .map(|address| {
+                    let mut result = *destination;
+                    // the decoded addresses are at most 16 bytes long
+                    let start = 16 - std::cmp::min(16, address.len());
+                    result[start..].copy_from_slice(&address[address.len() - (16 - start)..]);
+                    result
+                })
+                .collect(),
+            SegmentRouting(header) => header.segments.iter().map(|segment| segment.octets()).collect(),
+            Unknown{ .. } => Vec::new(),
+        }
+    }
+
+    /// Returns the final destination of a packet with the given IPv6
+    /// destination address & this routing header.
+    ///
+    /// If no segments are left the destination address of the IPv6 header
+    /// is the final destination. Otherwise it is the last address of the
+    /// route (the first element of the segment list for segment routing
+    /// headers). For unknown routing types the given destination is
+    /// returned.
+    pub fn final_destination(&self, destination: &[u8;16]) -> [u8;16] {
+        use Ipv6RoutingHeader::*;
+        if 0 == self.segments_left() {
+            return *destination;
+        }
+        match self {
+            SegmentRouting(header) => header.final_segment().map(|value| value.octets()),
+            _ => self.addresses(destination).last().copied(),
+        }.unwrap_or(*destination)
+    }
+
+    /// Copies a 16 byte address from the given slice (caller has to ensure
+    /// the slice is 16 bytes long).
+    fn address(slice: &[u8]) -> [u8;16] {
+        let mut result = [0;16];
+        result.copy_from_slice(slice);
+        result
+    }
+}
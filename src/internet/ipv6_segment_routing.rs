@@ -0,0 +1,316 @@
+use super::super::*;
+
+use std::net::Ipv6Addr;
+
+/// Type-length-value object contained in a segment routing header after
+/// the segment list (RFC 8754 section 2.1).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Ipv6SegmentRoutingTlv {
+    /// Single byte of padding (type 0).
+    Pad1,
+    /// Padding TLV (type 4) with the given number of padding bytes
+    /// following the type & length fields.
+    PadN(u8),
+    /// HMAC TLV (type 5, RFC 8754 section 2.1.2).
+    Hmac {
+        /// "D" flag, set if the destination address verification is disabled.
+        destination_address_check_disabled: bool,
+        /// Identifier of the pre-shared key & algorithm used to compute the HMAC.
+        key_id: u32,
+        /// HMAC value.
+        hmac: Vec<u8>,
+    },
+    /// TLV with a type that can not be decoded.
+    Unknown {
+        /// Type of the TLV.
+        tlv_type: u8,
+        /// Value of the TLV (without the type & length fields).
+        value: Vec<u8>,
+    },
+}
+
+impl Ipv6SegmentRoutingTlv {
+    /// Type of the Pad1 TLV.
+    pub const TYPE_PAD1: u8 = 0;
+
+    /// Type of the PadN TLV.
+    pub const TYPE_PADN: u8 = 4;
+
+    /// Type of the HMAC TLV.
+    pub const TYPE_HMAC: u8 = 5;
+
+    /// Returns the type of the TLV.
+    pub fn tlv_type(&self) -> u8 {
+        use Ipv6SegmentRoutingTlv::*;
+        match self {
+            Pad1 => Ipv6SegmentRoutingTlv::TYPE_PAD1,
+            PadN(_) => Ipv6SegmentRoutingTlv::TYPE_PADN,
+            Hmac{ .. } => Ipv6SegmentRoutingTlv::TYPE_HMAC,
+            Unknown{ tlv_type, .. } => *tlv_type,
+        }
+    }
+
+    /// Length of the serialized TLV (including the type & length fields).
+    pub fn header_len(&self) -> usize {
+        use Ipv6SegmentRoutingTlv::*;
+        match self {
+            Pad1 => 1,
+            PadN(len) => 2 + usize::from(*len),
+            Hmac{ hmac, .. } => 8 + hmac.len(),
+            Unknown{ value, .. } => 2 + value.len(),
+        }
+    }
+
+    /// Appends the serialized TLV to the given vector. A
+    /// `ValueError::Ipv6SegmentRoutingTlvTooLarge` is returned if the
+    /// value does not fit into the length field.
+    fn write(&self, target: &mut Vec<u8>) -> Result<(), ValueError> {
+        use Ipv6SegmentRoutingTlv::*;
+        // length of the value (Pad1 has no length field)
+        let len = self.header_len().saturating_sub(2);
+        if len > usize::from(u8::MAX) {
+            return Err(ValueError::Ipv6SegmentRoutingTlvTooLarge(len));
+        }
+        match self {
+            Pad1 => target.push(Ipv6SegmentRoutingTlv::TYPE_PAD1),
+            PadN(len) => {
+                target.extend_from_slice(&[Ipv6SegmentRoutingTlv::TYPE_PADN, *len]);
+                target.resize(target.len() + usize::from(*len), 0);
+            },
+            Hmac{ destination_address_check_disabled, key_id, hmac } => {
+                target.extend_from_slice(&[
+                    Ipv6SegmentRoutingTlv::TYPE_HMAC,
+                    len as u8,
+                    if *destination_address_check_disabled {
+                        0x80
+                    } else {
+                        0
+                    },
+                    0,
+                ]);
+                target.extend_from_slice(&key_id.to_be_bytes());
+                target.extend_from_slice(hmac);
+            },
+            Unknown{ tlv_type, value } => {
+                target.extend_from_slice(&[*tlv_type, len as u8]);
+                target.extend_from_slice(value);
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Segment routing header (SRH) used by SRv6 (routing header with the
+/// routing type 4, RFC 8754).
+///
+/// The segment list is stored in the order of the header, the first entry
+/// is the last segment of the path & the entry at the index `segments_left`
+/// is the currently active segment (see
+/// [`Ipv6SegmentRoutingHeader::active_segment`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+/// use std::net::Ipv6Addr;
+///
+/// let mut header = Ipv6SegmentRoutingHeader{
+///     segments_left: 1,
+///     flags: 0,
+///     tag: 0,
+///     segments: vec![
+///         "2001:db8::2".parse().unwrap(),
+///         "2001:db8::1".parse().unwrap(),
+///     ],
+///     tlvs: Vec::new(),
+/// };
+/// assert_eq!(1, header.last_entry());
+/// assert_eq!(Some("2001:db8::1".parse::<Ipv6Addr>().unwrap()), header.active_segment());
+///
+/// // serialize & decode the header
+/// let raw = header.to_raw(ip_number::UDP).unwrap();
+/// assert_eq!(header, Ipv6SegmentRoutingHeader::from_raw(&raw).unwrap());
+///
+/// // move to the next segment (e.g. when processing the packet at the segment endpoint)
+/// assert_eq!(Some("2001:db8::2".parse::<Ipv6Addr>().unwrap()), header.advance());
+/// assert_eq!(0, header.segments_left);
+/// assert_eq!(None, header.advance());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv6SegmentRoutingHeader {
+    /// Number of route segments remaining (index of the active segment in the segment list).
+    pub segments_left: u8,
+    /// Flags (no flags are defined by RFC 8754).
+    pub flags: u8,
+    /// Tag marking packets as part of a class or group of packets.
+    pub tag: u16,
+    /// Segment list (in the order of the header, the first entry is the last segment of the path).
+    pub segments: Vec<Ipv6Addr>,
+    /// TLVs following the segment list.
+    pub tlvs: Vec<Ipv6SegmentRoutingTlv>,
+}
+
+impl Ipv6SegmentRoutingHeader {
+    /// Decodes the payload of a routing header with the routing type 4
+    /// (starting with the routing type, see [`Ipv6RawExtensionHeader::payload`]).
+    ///
+    /// A `ReadError::UnexpectedEndOfSlice` is returned if the payload is
+    /// smaller then the 6 bytes of the fixed part of a routing header &
+    /// a `ReadError::Ipv6RoutingHeaderLengthBad` if the segment list given
+    /// by the "last entry" field or a TLV does not fit into the payload.
+    /// The routing type is not checked.
+    pub fn from_payload(payload: &[u8]) -> Result<Ipv6SegmentRoutingHeader, ReadError> {
+        use crate::ReadError::*;
+
+        if payload.len() < Ipv6RawExtensionHeader::MIN_PAYLOAD_LEN {
+            return Err(UnexpectedEndOfSlice(Ipv6RawExtensionHeader::MIN_PAYLOAD_LEN));
+        }
+        let length_bad = Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING);
+
+        let segments_len = (usize::from(payload[2]) + 1)*16;
+        if payload.len() < 6 + segments_len {
+            return Err(length_bad);
+        }
+        let segments = payload[6..6 + segments_len]
+            .chunks(16)
+            .map(|chunk| {
+                let mut octets = [0u8;16];
+                octets.copy_from_slice(chunk);
+                Ipv6Addr::from(octets)
+            })
+            .collect();
+
+        let mut tlvs = Vec::new();
+        let mut rest = &payload[6 + segments_len..];
+        while !rest.is_empty() {
+            let tlv_type = rest[0];
+            if Ipv6SegmentRoutingTlv::TYPE_PAD1 == tlv_type {
+                tlvs.push(Ipv6SegmentRoutingTlv::Pad1);
+                rest = &rest[1..];
+                continue;
+            }
+            if rest.len() < 2 || rest.len() < 2 + usize::from(rest[1]) {
+                return Err(length_bad);
+            }
+            let (tlv, next) = rest.split_at(2 + usize::from(rest[1]));
+            let value = &tlv[2..];
+            tlvs.push(match tlv_type {
+                Ipv6SegmentRoutingTlv::TYPE_PADN => Ipv6SegmentRoutingTlv::PadN(tlv[1]),
+                Ipv6SegmentRoutingTlv::TYPE_HMAC => {
+                    // D flag, reserved bits & key id
+                    if value.len() < 6 {
+                        return Err(length_bad);
+                    }
+                    Ipv6SegmentRoutingTlv::Hmac {
+                        destination_address_check_disabled: 0 != value[0] & 0x80,
+                        key_id: u32::from_be_bytes([value[2], value[3], value[4], value[5]]),
+                        hmac: value[6..].to_vec(),
+                    }
+                },
+                _ => Ipv6SegmentRoutingTlv::Unknown {
+                    tlv_type,
+                    value: value.to_vec(),
+                },
+            });
+            rest = next;
+        }
+
+        Ok(Ipv6SegmentRoutingHeader {
+            segments_left: payload[1],
+            flags: payload[3],
+            tag: u16::from_be_bytes([payload[4], payload[5]]),
+            segments,
+            tlvs,
+        })
+    }
+
+    /// Decodes the payload of the given raw routing header (see
+    /// [`Ipv6SegmentRoutingHeader::from_payload`] for the errors).
+    #[inline]
+    pub fn from_raw(header: &Ipv6RawExtensionHeader) -> Result<Ipv6SegmentRoutingHeader, ReadError> {
+        Ipv6SegmentRoutingHeader::from_payload(header.payload())
+    }
+
+    /// Serializes the segment routing header as a raw routing header with
+    /// the given next header value.
+    ///
+    /// Padding TLVs are appended in case the TLVs do not end at a multiple
+    /// of 8 octets. The following errors can be returned:
+    ///
+    /// * `ValueError::Ipv6SegmentRoutingNoSegments` if the segment list is empty.
+    /// * `ValueError::Ipv6SegmentRoutingTlvTooLarge` if the value of a TLV does not fit into its length field.
+    /// * `ValueError::Ipv6ExtensionPayloadTooLarge` if the header is larger then the maximum size of an extension header.
+    pub fn to_raw(&self, next_header: u8) -> Result<Ipv6RawExtensionHeader, ValueError> {
+        use crate::ValueError::*;
+
+        if self.segments.is_empty() {
+            return Err(Ipv6SegmentRoutingNoSegments);
+        }
+        // the length of the payload is checked by new_raw
+        let max_segments = Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN / 16;
+        if self.segments.len() > max_segments {
+            return Err(Ipv6ExtensionPayloadTooLarge(6 + self.segments.len()*16));
+        }
+
+        let mut payload = Vec::with_capacity(6 + self.segments.len()*16);
+        payload.extend_from_slice(&[
+            Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING,
+            self.segments_left,
+            self.last_entry(),
+            self.flags,
+        ]);
+        payload.extend_from_slice(&self.tag.to_be_bytes());
+        for segment in &self.segments {
+            payload.extend_from_slice(&segment.octets());
+        }
+        for tlv in &self.tlvs {
+            tlv.write(&mut payload)?;
+        }
+
+        // pad the tlvs to a multiple of 8 octets (the payload is
+        // preceded by the 2 bytes of the extension header)
+        let padding = (8 - (payload.len() + 2) % 8) % 8;
+        match padding {
+            0 => {},
+            1 => Ipv6SegmentRoutingTlv::Pad1.write(&mut payload)?,
+            _ => Ipv6SegmentRoutingTlv::PadN((padding - 2) as u8).write(&mut payload)?,
+        }
+
+        Ipv6RawExtensionHeader::new_raw(next_header, &payload)
+    }
+
+    /// Returns the value of the "last entry" field (index of the last
+    /// element of the segment list).
+    #[inline]
+    pub fn last_entry(&self) -> u8 {
+        // segment list lengths are checked when serializing
+        self.segments.len().saturating_sub(1) as u8
+    }
+
+    /// Returns the currently active segment (the segment list entry at the
+    /// index `segments_left`) or `None` if `segments_left` points behind
+    /// the end of the segment list.
+    #[inline]
+    pub fn active_segment(&self) -> Option<Ipv6Addr> {
+        self.segments.get(usize::from(self.segments_left)).copied()
+    }
+
+    /// Returns the final segment of the path (the first element of the
+    /// segment list).
+    #[inline]
+    pub fn final_segment(&self) -> Option<Ipv6Addr> {
+        self.segments.first().copied()
+    }
+
+    /// Decrements `segments_left` & returns the new active segment (which
+    /// should be set as the destination address of the IPv6 header). `None`
+    /// is returned & nothing is changed if no segments are left.
+    pub fn advance(&mut self) -> Option<Ipv6Addr> {
+        if 0 == self.segments_left {
+            None
+        } else {
+            self.segments_left -= 1;
+            self.active_segment()
+        }
+    }
+}
@@ -1,9 +1,17 @@
 
+pub mod dscp;
+pub mod ecn;
 pub mod ip;
 pub mod ip_authentication;
 pub mod ipv4;
 pub mod ipv4_extensions;
+pub mod ipv4_fragmenter;
+pub mod ipv4_options;
 pub mod ipv6;
 pub mod ipv6_extensions;
 pub mod ipv6_raw_extension;
 pub mod ipv6_fragment;
+pub mod ipv6_routing;
+pub mod ipv6_segment_routing;
+pub mod ipv6_fragmenter;
+pub mod ipv6_defragmenter;
@@ -0,0 +1,300 @@
+use super::*;
+
+use std::fmt::Write;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+/// Field naming scheme used when exporting packets as JSON.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum JsonMode {
+    /// Field names based on the names of the etherparse header fields
+    /// (e.g. `{"ipv4":{"source":"192.168.1.1", ...}}`).
+    Default,
+    /// Field names compatible with the Elasticsearch output of tshark
+    /// (`tshark -T ek`, e.g. `{"layers":{"ip":{"ip.src":"192.168.1.1", ...}}}`)
+    /// so existing Kibana dashboards & queries can be reused.
+    Ek,
+}
+
+/// Returns a JSON representation of the headers in the sliced packet.
+///
+/// In [`JsonMode::Ek`] the optional timestamp (time since the unix epoch)
+/// is added as `timestamp` field in milliseconds (as done by tshark).
+///
+/// Headers present multiple times (e.g. the tags of double vlan tagged
+/// packets) are exported as an array of objects under a single key (like
+/// tshark does for repeated protocols), so the keys of an object are
+/// always unique.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{SlicedPacket, json::{to_json, JsonMode}};
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+///
+/// let json = to_json(&sliced, JsonMode::Default, None);
+/// assert!(json.contains(r#""source":"192.168.1.1""#));
+///
+/// let ek = to_json(&sliced, JsonMode::Ek, None);
+/// assert!(ek.contains(r#""ip.src":"192.168.1.1""#));
+/// assert!(ek.contains(r#""udp.dstport":1234"#));
+/// ```
+pub fn to_json(packet: &SlicedPacket, mode: JsonMode, timestamp: Option<Duration>) -> String {
+    let mut layers = Vec::with_capacity(6);
+
+    if let Some(LinkSlice::Ethernet2(eth)) = &packet.link {
+        layers.push(Layer {
+            name: "ethernet2",
+            ek_name: "eth",
+            fields: vec![
+                Field::new("destination", "eth.dst", Value::Mac(eth.destination())),
+                Field::new("source", "eth.src", Value::Mac(eth.source())),
+                Field::new("ether_type", "eth.type", Value::Hex16(eth.ether_type())),
+            ],
+        });
+    }
+
+    if let Some(vlan) = &packet.vlan {
+        for header in vlan.iter() {
+            layers.push(vlan_layer(&header));
+        }
+    }
+
+    match &packet.ip {
+        Some(InternetSlice::Ipv4(ip, _)) => layers.push(Layer {
+            name: "ipv4",
+            ek_name: "ip",
+            fields: vec![
+                Field::new("version", "ip.version", Value::Num(4)),
+                Field::new("header_len", "ip.hdr_len", Value::Num(u64::from(ip.ihl())*4)),
+                Field::new("differentiated_services_code_point", "ip.dsfield.dscp", Value::Num(u64::from(ip.dcp()))),
+                Field::new("explicit_congestion_notification", "ip.dsfield.ecn", Value::Num(u64::from(ip.ecn()))),
+                Field::new("total_len", "ip.len", Value::Num(u64::from(ip.total_len()))),
+                Field::new("identification", "ip.id", Value::Hex16(ip.identification())),
+                Field::new("dont_fragment", "ip.flags.df", Value::Bool(ip.dont_fragment())),
+                Field::new("more_fragments", "ip.flags.mf", Value::Bool(ip.more_fragments())),
+                Field::new("fragments_offset", "ip.frag_offset", Value::Num(u64::from(ip.fragments_offset()))),
+                Field::new("time_to_live", "ip.ttl", Value::Num(u64::from(ip.ttl()))),
+                Field::new("protocol", "ip.proto", Value::Num(u64::from(ip.protocol()))),
+                Field::new("header_checksum", "ip.checksum", Value::Hex16(ip.header_checksum())),
+                Field::new("source", "ip.src", Value::Ipv4(ip.source_addr())),
+                Field::new("destination", "ip.dst", Value::Ipv4(ip.destination_addr())),
+            ],
+        }),
+        Some(InternetSlice::Ipv6(ip, _)) => layers.push(Layer {
+            name: "ipv6",
+            ek_name: "ipv6",
+            fields: vec![
+                Field::new("version", "ipv6.version", Value::Num(6)),
+                Field::new("traffic_class", "ipv6.tclass", Value::Num(u64::from(ip.traffic_class()))),
+                Field::new("flow_label", "ipv6.flow", Value::Num(u64::from(ip.flow_label()))),
+                Field::new("payload_length", "ipv6.plen", Value::Num(u64::from(ip.payload_length()))),
+                Field::new("next_header", "ipv6.nxt", Value::Num(u64::from(ip.next_header()))),
+                Field::new("hop_limit", "ipv6.hlim", Value::Num(u64::from(ip.hop_limit()))),
+                Field::new("source", "ipv6.src", Value::Ipv6(ip.source_addr())),
+                Field::new("destination", "ipv6.dst", Value::Ipv6(ip.destination_addr())),
+            ],
+        }),
+        None => {},
+    }
+
+    match &packet.transport {
+        Some(TransportSlice::Udp(udp)) => layers.push(Layer {
+            name: "udp",
+            ek_name: "udp",
+            fields: vec![
+                Field::new("source_port", "udp.srcport", Value::Num(u64::from(udp.source_port()))),
+                Field::new("destination_port", "udp.dstport", Value::Num(u64::from(udp.destination_port()))),
+                Field::new("length", "udp.length", Value::Num(u64::from(udp.length()))),
+                Field::new("checksum", "udp.checksum", Value::Hex16(udp.checksum())),
+            ],
+        }),
+        Some(TransportSlice::Tcp(tcp)) => layers.push(Layer {
+            name: "tcp",
+            ek_name: "tcp",
+            fields: vec![
+                Field::new("source_port", "tcp.srcport", Value::Num(u64::from(tcp.source_port()))),
+                Field::new("destination_port", "tcp.dstport", Value::Num(u64::from(tcp.destination_port()))),
+                Field::new("sequence_number", "tcp.seq_raw", Value::Num(u64::from(tcp.sequence_number()))),
+                Field::new("acknowledgment_number", "tcp.ack_raw", Value::Num(u64::from(tcp.acknowledgment_number()))),
+                Field::new("header_len", "tcp.hdr_len", Value::Num(u64::from(tcp.data_offset())*4)),
+                Field::new("ns", "tcp.flags.ns", Value::Bool(tcp.ns())),
+                Field::new("cwr", "tcp.flags.cwr", Value::Bool(tcp.cwr())),
+                Field::new("ece", "tcp.flags.ecn", Value::Bool(tcp.ece())),
+                Field::new("urg", "tcp.flags.urg", Value::Bool(tcp.urg())),
+                Field::new("ack", "tcp.flags.ack", Value::Bool(tcp.ack())),
+                Field::new("psh", "tcp.flags.push", Value::Bool(tcp.psh())),
+                Field::new("rst", "tcp.flags.reset", Value::Bool(tcp.rst())),
+                Field::new("syn", "tcp.flags.syn", Value::Bool(tcp.syn())),
+                Field::new("fin", "tcp.flags.fin", Value::Bool(tcp.fin())),
+                Field::new("window_size", "tcp.window_size_value", Value::Num(u64::from(tcp.window_size()))),
+                Field::new("checksum", "tcp.checksum", Value::Hex16(tcp.checksum())),
+                Field::new("urgent_pointer", "tcp.urgent_pointer", Value::Num(u64::from(tcp.urgent_pointer()))),
+            ],
+        }),
+        _ => {},
+    }
+
+    layers.push(Layer {
+        name: "payload",
+        ek_name: "data",
+        fields: vec![
+            Field::new("len", "data.len", Value::Num(packet.payload.len() as u64)),
+        ],
+    });
+
+    // serialize
+    let mut result = String::with_capacity(512);
+    result.push('{');
+    let write_layers = |result: &mut String, ek: bool| {
+        let mut start = 0;
+        while start < layers.len() {
+            // consecutive layers with the same name are grouped in an array
+            let count = layers[start..].iter().take_while(|layer| layer.name == layers[start].name).count();
+            let group = &layers[start..start + count];
+            if start > 0 {
+                result.push(',');
+            }
+            write!(result, "\"{}\":", if ek { group[0].ek_name } else { group[0].name }).unwrap();
+            if count > 1 {
+                result.push('[');
+            }
+            for (i, layer) in group.iter().enumerate() {
+                if i > 0 {
+                    result.push(',');
+                }
+                layer.write_fields(result, ek);
+            }
+            if count > 1 {
+                result.push(']');
+            }
+            start += count;
+        }
+    };
+    match mode {
+        JsonMode::Default => {
+            if let Some(timestamp) = timestamp {
+                write!(result, "\"timestamp\":{},", timestamp.as_millis()).unwrap();
+            }
+            write_layers(&mut result, false);
+        },
+        JsonMode::Ek => {
+            if let Some(timestamp) = timestamp {
+                write!(result, "\"timestamp\":\"{}\",", timestamp.as_millis()).unwrap();
+            }
+            result.push_str("\"layers\":{");
+            write_layers(&mut result, true);
+            result.push('}');
+        },
+    }
+    result.push('}');
+    result
+}
+
+fn vlan_layer(vlan: &SingleVlanHeaderSlice) -> Layer {
+    Layer {
+        name: "vlan",
+        ek_name: "vlan",
+        fields: vec![
+            Field::new("priority_code_point", "vlan.priority", Value::Num(u64::from(vlan.priority_code_point()))),
+            Field::new("drop_eligible_indicator", "vlan.dei", Value::Bool(vlan.drop_eligible_indicator())),
+            Field::new("vlan_identifier", "vlan.id", Value::Num(u64::from(vlan.vlan_identifier()))),
+            Field::new("ether_type", "vlan.etype", Value::Hex16(vlan.ether_type())),
+        ],
+    }
+}
+
+/// Parses a MAC address in the format used by the JSON export
+/// (`00:11:22:33:44:55`, `-` is also accepted as separator).
+#[cfg(any(feature = "wasm", feature = "python"))]
+pub(crate) fn parse_mac(text: &str) -> Option<[u8;6]> {
+    let mut result = [0u8;6];
+    let mut parts = text.split([':', '-']);
+    for byte in result.iter_mut() {
+        let part = parts.next()?;
+        if part.len() != 2 {
+            return None;
+        }
+        *byte = u8::from_str_radix(part, 16).ok()?;
+    }
+    match parts.next() {
+        Some(_) => None,
+        None => Some(result),
+    }
+}
+
+/// Header with the fields exported as JSON.
+struct Layer {
+    name: &'static str,
+    ek_name: &'static str,
+    fields: Vec<Field>,
+}
+
+impl Layer {
+    /// Writes the fields of the layer as JSON object.
+    fn write_fields(&self, result: &mut String, ek: bool) {
+        result.push('{');
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                result.push(',');
+            }
+            write!(result, "\"{}\":", if ek { field.ek_name } else { field.name }).unwrap();
+            field.value.write(result, ek);
+        }
+        result.push('}');
+    }
+}
+
+/// Exported header field.
+struct Field {
+    name: &'static str,
+    ek_name: &'static str,
+    value: Value,
+}
+
+impl Field {
+    fn new(name: &'static str, ek_name: &'static str, value: Value) -> Field {
+        Field { name, ek_name, value }
+    }
+}
+
+/// Value of an exported field.
+enum Value {
+    Num(u64),
+    Bool(bool),
+    /// 16 bit value displayed as number (default) or hex string (ek).
+    Hex16(u16),
+    Mac([u8;6]),
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+}
+
+impl Value {
+    fn write(&self, result: &mut String, ek: bool) {
+        use Value::*;
+        match self {
+            Num(value) => write!(result, "{}", value),
+            Bool(value) => write!(result, "{}", value),
+            Hex16(value) => if ek {
+                write!(result, "\"0x{:04x}\"", value)
+            } else {
+                write!(result, "{}", value)
+            },
+            Mac(value) => write!(
+                result,
+                "\"{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}\"",
+                value[0], value[1], value[2], value[3], value[4], value[5]
+            ),
+            Ipv4(value) => write!(result, "\"{}\"", value),
+            Ipv6(value) => write!(result, "\"{}\"", value),
+        }.unwrap();
+    }
+}
@@ -110,6 +110,7 @@
 //! * [`Ethernet2HeaderSlice::from_slice`]
 //! * [`SingleVlanHeaderSlice::from_slice`]
 //! * [`DoubleVlanHeaderSlice::from_slice`]
+//! * [`MultiVlanHeaderSlice::from_slice`]
 //! * [`Ipv4HeaderSlice::from_slice`]
 //! * [`Ipv4ExtensionsSlice::from_slice`]
 //! * [`Ipv6HeaderSlice::from_slice`]
@@ -219,24 +220,60 @@ use std::error::Error;
 
 mod link;
 pub use crate::link::LinkSlice;
+pub use crate::link::arp::*;
+pub use crate::link::eapol::*;
 pub use crate::link::ethernet::*;
+pub use crate::link::ieee80211::*;
+pub use crate::link::ieee802154::*;
+pub use crate::link::lacp::*;
+pub use crate::link::link_type::*;
+pub use crate::link::linux_sll::*;
+pub use crate::link::llc::*;
+pub use crate::link::lldp::*;
+pub use crate::link::mac_addr::*;
+pub use crate::link::mac_control::*;
+pub use crate::link::null::*;
+pub use crate::link::stp::*;
 pub use crate::link::vlan_tagging::*;
+pub use crate::link::wake_on_lan::*;
 
 mod internet;
+pub use crate::internet::dscp::*;
+pub use crate::internet::ecn::*;
 pub use crate::internet::ip::*;
 pub use crate::internet::ip_authentication::*;
 pub use crate::internet::ipv4::*;
 pub use crate::internet::ipv4_extensions::*;
+pub use crate::internet::ipv4_fragmenter::*;
+pub use crate::internet::ipv4_options::*;
 pub use crate::internet::ipv6::*;
 pub use crate::internet::ipv6_extensions::*;
 pub use crate::internet::ipv6_raw_extension::*;
 pub use crate::internet::ipv6_fragment::*;
+pub use crate::internet::ipv6_routing::*;
+pub use crate::internet::ipv6_segment_routing::*;
+pub use crate::internet::ipv6_fragmenter::*;
+pub use crate::internet::ipv6_defragmenter::*;
 
 mod transport;
+pub use crate::transport::igmp::*;
 pub use crate::transport::tcp::*;
 pub use crate::transport::udp::*;
 pub use crate::transport::TransportHeader;
 
+mod tunnel;
+pub use crate::tunnel::gre::*;
+pub use crate::tunnel::vxlan::*;
+pub use crate::tunnel::vxlan_gpe::*;
+pub use crate::tunnel::geneve::*;
+pub use crate::tunnel::gtpu::*;
+pub use crate::tunnel::gue::*;
+pub use crate::tunnel::teredo::*;
+pub use crate::tunnel::esp::*;
+pub use crate::tunnel::pppoe::*;
+pub use crate::tunnel::pbb::*;
+pub use crate::tunnel::{TunnelHeader, TunnelSlice};
+
 /// Helpers for calculating checksums.
 pub mod checksum;
 
@@ -246,11 +283,122 @@ pub use crate::packet_builder::*;
 mod packet_decoder;
 pub use crate::packet_decoder::*;
 
+mod payload;
+pub use crate::payload::*;
+
+mod owned_packet;
+pub use crate::owned_packet::*;
+
 mod packet_slicing;
 pub use crate::packet_slicing::*;
 
+mod parse_limits;
+pub use crate::parse_limits::*;
+
 pub mod packet_filter;
 
+/// Helpers for collecting per layer statistics (packets & bytes per ether type, ip protocol, port & vlan).
+pub mod stats;
+
+/// Helpers for detecting duplicate packets (e.g. in captures taken at multiple mirror points).
+pub mod dedup;
+
+/// Template based traffic generator (e.g. for load tests & replay tools).
+pub mod generator;
+
+/// JSON export of sliced packets (including a tshark "ek" compatible mode).
+pub mod json;
+
+/// Import of offset-prefixed hex dumps (as read by text2pcap) into packets.
+pub mod hexdump;
+
+/// Interpreter for classic BPF programs (e.g. compiled by `tcpdump -ddd`).
+pub mod bpf;
+
+/// Filters based on tcpdump style filter expressions (e.g. `tcp and dst port 443`).
+pub mod filter_expression;
+
+/// Anonymization of mac & ip addresses (prefix-preserving) so captures can be shared.
+pub mod anonymize;
+
+/// ROHC (RFC 3095) compressor & decompressor for the uncompressed & RTP/UDP/IP profiles.
+pub mod rohc;
+
+/// 6LoWPAN (RFC 4944 & RFC 6282) header compression, decompression & fragmentation.
+pub mod sixlowpan;
+
+/// Scapy style composition of headers & payloads into packets via the `/` operator.
+pub mod compose;
+
+/// ICMPv4 (RFC 792) message types with the decoded codes & type specific header fields.
+pub mod icmpv4;
+
+/// ICMPv6 (RFC 4443) type values, checksum calculation & generation of error messages.
+pub mod icmpv6;
+
+/// ICMP extension structures appended to ICMPv4 & ICMPv6 error messages (RFC 4884) with MPLS label stack (RFC 4950) & interface information (RFC 5837) objects and extended echo messages (RFC 8335).
+pub mod icmp_extension;
+
+/// DHCPv4 options with typed relay agent information (RFC 3046) & classless static routes (RFC 3442).
+pub mod dhcp;
+
+/// PTPv2 (IEEE 1588) message headers & timestamps (over ethernet & UDP).
+pub mod ptp;
+
+/// Normalized join, leave & query events of IGMP & MLD messages (e.g. for multicast snooping).
+pub mod multicast;
+
+/// Reading & setting the ECN codepoint of IP packets & the ECN flags of TCP headers with incremental checksum updates.
+pub mod ecn;
+
+/// `From`/`TryFrom` conversions between the headers & the packet types of the
+/// `pnet_packet` crate (requires the feature `pnet_packet`).
+#[cfg(feature = "pnet_packet")]
+mod pnet_compat;
+
+/// Conversions between the headers & the `smoltcp::wire` representations
+/// (requires the feature `smoltcp`).
+#[cfg(feature = "smoltcp")]
+mod smoltcp_compat;
+
+/// Differential testing of the decoders against the `pnet_packet` crate as reference implementation (requires the feature `differential`).
+#[cfg(feature = "differential")]
+pub mod differential;
+
+/// SOME/IP headers & SOME/IP-SD entries & options of automotive service communication (requires the feature `someip`).
+#[cfg(feature = "someip")]
+pub mod someip;
+
+/// Connection tracking (flow table with tcp state & counters) based on sliced packets (requires the feature `conntrack`).
+#[cfg(feature = "conntrack")]
+pub mod conntrack;
+
+/// Raw sockets to send & receive frames on a network interface (requires the
+/// feature `raw_socket`, supported on Linux, Android, macOS, FreeBSD & DragonFly).
+#[cfg(all(feature = "raw_socket", any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "dragonfly"
+)))]
+pub mod raw_socket;
+
+/// Zero-copy access to the blocks & frames of memory mapped TPACKET_V3 `AF_PACKET` receive rings.
+pub mod tpacket_v3;
+
+/// Helpers for decoding batches of packets in parallel (requires the feature `rayon`).
+#[cfg(feature = "rayon")]
+pub mod batch;
+
+/// wasm-bindgen wrappers for parsing & building packets from JavaScript (requires the feature `wasm`).
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// pyo3 bindings exposing the parse, build & pretty print functions to Python (requires the feature `python`).
+#[cfg(feature = "python")]
+pub mod python;
+
 ///Contains the size when serialized.
 pub trait SerializedSize {
     const SERIALIZED_SIZE: usize;
@@ -284,6 +432,34 @@ pub enum ReadError {
     IpAuthenticationHeaderTooSmallPayloadLength(u8),
     ///Error given if the data_offset field in a TCP header is smaller then the minimum size of the tcp header itself.
     TcpDataOffsetTooSmall(u8),
+    ///Error when the frame type of an IEEE 802.15.4 frame is not supported (multipurpose, fragment, extended & reserved frame types).
+    Ieee802154UnsupportedFrameType(u8),
+    ///Error when an IEEE 802.15.4 frame uses a reserved addressing mode.
+    Ieee802154ReservedAddressingMode(u8),
+    ///Error when a limit configured via [`ParseLimits`] is exceeded.
+    ParseLimitExceeded(ParseLimit),
+    ///Error when the version field of a GRE header is not 0 (e.g. the enhanced GRE header used by PPTP). The value is the version that was received.
+    GreUnsupportedVersion(u8),
+    /// Error when the length of a Geneve option (argument, in multiples of 4 bytes excluding the 4 byte option header) exceeds the options area of the Geneve header.
+    GeneveOptionLengthBad(u8),
+    /// Error when a GTP-U header with a version other then 1 is encountered (argument is the version).
+    GtpuUnsupportedVersion(u8),
+    /// Error when a GTP-U extension header with a length of 0 is encountered.
+    GtpuExtensionHeaderLengthZero,
+    /// Error when the frame type of an IEEE 802.11 frame is not supported (control & extension frames).
+    Ieee80211UnsupportedFrameType(u8),
+    /// Error when the type of a spanning tree BPDU is unknown (argument is the BPDU type).
+    StpUnknownBpduType(u8),
+    /// Error when the slow protocol subtype of a LACPDU is not LACP (argument is the subtype).
+    LacpUnexpectedSubtype(u8),
+    /// Error when the variant of a GUE header is not 0 (argument is the variant).
+    GueUnsupportedVariant(u8),
+    /// Error when the pad length in the trailer of a NULL encrypted ESP packet is bigger then the data preceding the trailer (argument is the pad length).
+    EspPadLengthTooLarge(u8),
+    /// Error when the length of an IPv6 routing header does not match the addresses contained in it (argument is the routing type).
+    Ipv6RoutingHeaderLengthBad(u8),
+    /// Error when the length of a prefix control operation in an ICMPv6 router renumbering command is not of the form 4N+3 (argument is the OpLength field).
+    Icmpv6RouterRenumberingOpLengthBad(u8),
 }
 
 impl ReadError {
@@ -311,6 +487,42 @@ impl ReadError {
             _ => None
         }
     }
+
+    /// Returns a stable numeric code identifying the error variant (in the
+    /// range 100 to 199, e.g. for reporting errors via FFI).
+    ///
+    /// The codes of existing variants never change, new variants get new codes.
+    pub fn error_code(&self) -> u16 {
+        use crate::ReadError::*;
+        match self {
+            IoError(_) => 100,
+            UnexpectedEndOfSlice(_) => 101,
+            DoubleVlanOuterNonVlanEtherType(_) => 102,
+            IpUnsupportedVersion(_) => 103,
+            Ipv4UnexpectedVersion(_) => 104,
+            Ipv4HeaderLengthBad(_) => 105,
+            Ipv4TotalLengthTooSmall(_) => 106,
+            Ipv6UnexpectedVersion(_) => 107,
+            Ipv6TooManyHeaderExtensions => 108,
+            Ipv6HopByHopHeaderNotAtStart => 109,
+            IpAuthenticationHeaderTooSmallPayloadLength(_) => 110,
+            TcpDataOffsetTooSmall(_) => 111,
+            Ieee802154UnsupportedFrameType(_) => 112,
+            Ieee802154ReservedAddressingMode(_) => 113,
+            ParseLimitExceeded(_) => 114,
+            GreUnsupportedVersion(_) => 115,
+            GeneveOptionLengthBad(_) => 116,
+            GtpuUnsupportedVersion(_) => 117,
+            GtpuExtensionHeaderLengthZero => 118,
+            Ieee80211UnsupportedFrameType(_) => 119,
+            StpUnknownBpduType(_) => 120,
+            LacpUnexpectedSubtype(_) => 121,
+            GueUnsupportedVariant(_) => 122,
+            EspPadLengthTooLarge(_) => 123,
+            Ipv6RoutingHeaderLengthBad(_) => 124,
+            Icmpv6RouterRenumberingOpLengthBad(_) => 125,
+        }
+    }
 }
 
 impl fmt::Display for ReadError {
@@ -352,6 +564,48 @@ impl fmt::Display for ReadError {
             TcpDataOffsetTooSmall(data_offset) => { //u8
                 write!(f, "ReadError: TCP data offset too small. The data offset value {} in the tcp header is smaller then the tcp header itself.", data_offset)
             },
+            Ieee802154UnsupportedFrameType(frame_type) => { //u8
+                write!(f, "ReadError: Unsupported IEEE 802.15.4 frame type {}.", frame_type)
+            },
+            Ieee802154ReservedAddressingMode(mode) => { //u8
+                write!(f, "ReadError: Reserved IEEE 802.15.4 addressing mode {}.", mode)
+            },
+            ParseLimitExceeded(limit) => {
+                write!(f, "ReadError: Parse limit exceeded. The packet exceeds the configured limit {:?}.", limit)
+            },
+            GreUnsupportedVersion(version) => { //u8
+                write!(f, "ReadError: Unsupported GRE version {}. Only GRE headers with the version 0 are supported.", version)
+            },
+            GeneveOptionLengthBad(length) => { //u8
+                write!(f, "ReadError: Geneve option length {} bad. The option exceeds the options area of the Geneve header.", length)
+            },
+            GtpuUnsupportedVersion(version) => { //u8
+                write!(f, "ReadError: Unsupported GTP-U version {}. Only GTP-U headers with the version 1 are supported.", version)
+            },
+            GtpuExtensionHeaderLengthZero => {
+                write!(f, "ReadError: GTP-U extension header with a length of 0 encountered.")
+            },
+            Ieee80211UnsupportedFrameType(frame_type) => { //u8
+                write!(f, "ReadError: Unsupported IEEE 802.11 frame type {}.", frame_type)
+            },
+            StpUnknownBpduType(bpdu_type) => { //u8
+                write!(f, "ReadError: Unknown spanning tree BPDU type {}.", bpdu_type)
+            },
+            LacpUnexpectedSubtype(subtype) => { //u8
+                write!(f, "ReadError: Unexpected slow protocol subtype {}. Expected the LACP subtype 1.", subtype)
+            },
+            GueUnsupportedVariant(variant) => { //u8
+                write!(f, "ReadError: Unsupported GUE variant {}. Only GUE headers with the variant 0 can be decoded.", variant)
+            },
+            EspPadLengthTooLarge(pad_length) => { //u8
+                write!(f, "ReadError: ESP pad length {} is larger then the data preceding the ESP trailer.", pad_length)
+            },
+            Ipv6RoutingHeaderLengthBad(routing_type) => { //u8
+                write!(f, "ReadError: The length of the IPv6 routing header with the routing type {} does not match the contained addresses.", routing_type)
+            },
+            Icmpv6RouterRenumberingOpLengthBad(op_length) => { //u8
+                write!(f, "ReadError: The length {} of an ICMPv6 router renumbering prefix control operation is not of the form 4N+3.", op_length)
+            },
         }
     }
 }
@@ -371,6 +625,21 @@ impl From<std::io::Error> for ReadError {
     }
 }
 
+/// Converts the error to an `std::io::Error`. `IoError` values are
+/// unwrapped, `UnexpectedEndOfSlice` is mapped to
+/// `std::io::ErrorKind::UnexpectedEof` & all other errors to
+/// `std::io::ErrorKind::InvalidData`.
+impl From<ReadError> for std::io::Error {
+    fn from(err: ReadError) -> std::io::Error {
+        use std::io::ErrorKind;
+        match err {
+            ReadError::IoError(err) => err,
+            ReadError::UnexpectedEndOfSlice(_) => std::io::Error::new(ErrorKind::UnexpectedEof, err),
+            err => std::io::Error::new(ErrorKind::InvalidData, err),
+        }
+    }
+}
+
 ///Errors that can occur when writing.
 #[derive(Debug)]
 pub enum WriteError {
@@ -406,6 +675,19 @@ impl WriteError {
             _ => None
         }
     }
+
+    /// Returns a stable numeric code identifying the error variant (in the
+    /// range 200 to 299). For `ValueError` the code of the contained
+    /// [`ValueError`] is returned.
+    ///
+    /// The codes of existing variants never change, new variants get new codes.
+    pub fn error_code(&self) -> u16 {
+        match self {
+            WriteError::IoError(_) => 200,
+            WriteError::ValueError(err) => err.error_code(),
+            WriteError::SliceTooSmall(_) => 201,
+        }
+    }
 }
 
 impl From<ValueError> for WriteError {
@@ -420,6 +702,20 @@ impl From<std::io::Error> for WriteError {
     }
 }
 
+/// Converts the error to an `std::io::Error`. `IoError` values are
+/// unwrapped, `SliceTooSmall` is mapped to `std::io::ErrorKind::WriteZero`
+/// & `ValueError` to `std::io::ErrorKind::InvalidInput`.
+impl From<WriteError> for std::io::Error {
+    fn from(err: WriteError) -> std::io::Error {
+        use std::io::ErrorKind;
+        match err {
+            WriteError::IoError(err) => err,
+            WriteError::ValueError(err) => std::io::Error::new(ErrorKind::InvalidInput, err),
+            err => std::io::Error::new(ErrorKind::WriteZero, err),
+        }
+    }
+}
+
 impl fmt::Display for WriteError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use WriteError::*;
@@ -482,7 +778,83 @@ pub enum ValueError {
     /// Error when a u16 field in a header has a larger value then supported.
     U16TooLarge{value: u16, max: u16, field: ErrorField},
     /// Error when a u32 field in a header has a larger value then supported.
-    U32TooLarge{value: u32, max: u32, field: ErrorField}
+    U32TooLarge{value: u32, max: u32, field: ErrorField},
+    /// Error when the sender & target addresses of an ARP packet differ in length or are longer then 255 bytes.
+    ArpAddressLengthBad,
+    /// Error when a multicast group record or query (IGMPv3 or MLDv2) contains more then 65535 sources or records or auxiliary data that is not a multiple of 4 bytes or longer then 1020 bytes.
+    MulticastRecordLengthBad,
+    /// Error when the data of a Geneve option is not a multiple of 4 bytes or bigger then 124 bytes and therefor can not be represented in the option length field.
+    GeneveOptionDataLengthBad(usize),
+    /// Error when the options of a Geneve header are bigger then 252 bytes and therefor can not be represented in the header options length field.
+    GeneveOptionsLengthTooLarge(usize),
+    /// Error when the content of a GTP-U extension header plus 2 bytes is not a multiple of 4 bytes or bigger then 1018 bytes and therefor can not be represented in the extension header length field.
+    GtpuExtensionHeaderLengthBad(usize),
+    /// Error when the optional fields, extension headers & payload of a GTP-U header are bigger then what fits inside the length field.
+    GtpuPayloadLengthTooLarge(usize),
+    /// Error when the PPP protocol field & payload of a PPPoE header are bigger then what fits inside the length field.
+    PppoePayloadLengthTooLarge(usize),
+    /// Error when the length of the GUE extension fields is not a multiple of 4 or bigger then what can be represented by the "hlen" field.
+    GueExtensionFieldsLengthBad(usize),
+    /// Error when the client identifier or authentication value of a Teredo authentication header is bigger then 255 bytes and therefor can not be represented in the length fields.
+    TeredoAuthenticationLengthTooLarge(usize),
+    /// Error when an IPv4 packet has to be fragmented but the MTU (argument) is too small to contain the IPv4 header & at least 8 bytes of payload.
+    Ipv4FragmentMtuTooSmall(usize),
+    /// Error when an IPv4 packet (length as argument) is bigger then the MTU but can not be fragmented as the "don't fragment" flag is set.
+    Ipv4DontFragmentSet(usize),
+    /// Error when an IPv6 packet has to be fragmented but the MTU (argument) is too small to contain the unfragmentable & fragmentable extension headers, a fragment header & at least 8 bytes of payload.
+    Ipv6FragmentMtuTooSmall(usize),
+    /// Error when a segment routing header without any segments is serialized.
+    Ipv6SegmentRoutingNoSegments,
+    /// Error when the value of a segment routing header TLV (length as argument) is bigger then 255 bytes and therefor can not be represented in the length field.
+    Ipv6SegmentRoutingTlvTooLarge(usize),
+    /// Error when an IPv6 extension header (`header`) is located after an extension header (`previous`) it should precede according to the order recommended by RFC 8200.
+    Ipv6ExtensionOutOfOrder{ header: IpNumber, previous: IpNumber },
+    /// Error when a prefix control operation of an ICMPv6 router renumbering command contains more use-prefix parts (argument) then can be represented in the OpLength field (max 63).
+    Icmpv6RouterRenumberingTooManyUsePrefixes(usize),
+}
+
+impl ValueError {
+    /// Returns a stable numeric code identifying the error variant (in the
+    /// range 300 to 399).
+    ///
+    /// The codes of existing variants never change, new variants get new codes.
+    pub fn error_code(&self) -> u16 {
+        use crate::ValueError::*;
+        match self {
+            Ipv4OptionsLengthBad(_) => 300,
+            Ipv4PayloadLengthTooLarge(_) => 301,
+            Ipv6PayloadLengthTooLarge(_) => 302,
+            Ipv6ExtensionPayloadTooSmall(_) => 303,
+            Ipv6ExtensionPayloadTooLarge(_) => 304,
+            Ipv6ExtensionPayloadLengthUnaligned(_) => 305,
+            IpAuthenticationHeaderBadIcvLength(_) => 306,
+            Ipv4ExtensionNotReferenced(_) => 307,
+            Ipv6ExtensionHopByHopNotAtStart => 308,
+            Ipv6ExtensionNotReferenced(_) => 309,
+            Ipv6ExtensionNotDefinedReference(_) => 310,
+            UdpPayloadLengthTooLarge(_) => 311,
+            TcpLengthTooLarge(_) => 312,
+            U8TooLarge{ .. } => 313,
+            U16TooLarge{ .. } => 314,
+            U32TooLarge{ .. } => 315,
+            ArpAddressLengthBad => 316,
+            MulticastRecordLengthBad => 317,
+            GeneveOptionDataLengthBad(_) => 318,
+            GeneveOptionsLengthTooLarge(_) => 319,
+            GtpuExtensionHeaderLengthBad(_) => 320,
+            GtpuPayloadLengthTooLarge(_) => 321,
+            PppoePayloadLengthTooLarge(_) => 322,
+            GueExtensionFieldsLengthBad(_) => 323,
+            TeredoAuthenticationLengthTooLarge(_) => 324,
+            Ipv4FragmentMtuTooSmall(_) => 325,
+            Ipv4DontFragmentSet(_) => 326,
+            Ipv6FragmentMtuTooSmall(_) => 327,
+            Ipv6SegmentRoutingNoSegments => 328,
+            Ipv6SegmentRoutingTlvTooLarge(_) => 329,
+            Ipv6ExtensionOutOfOrder{ .. } => 330,
+            Icmpv6RouterRenumberingTooManyUsePrefixes(_) => 331,
+        }
+    }
 }
 
 impl Error for ValueError {
@@ -540,6 +912,54 @@ impl fmt::Display for ValueError {
             },
             U32TooLarge{value, max, field} => {
                 write!(f, "The value {} of the field '{}' is larger then the allowed maximum of {}.", value, field, max)
+            },
+            ArpAddressLengthBad => {
+                write!(f, "ARP address lengths bad. The sender & target hardware addresses (and the sender & target protocol addresses) must have the same length of at most 255 bytes.")
+            },
+            MulticastRecordLengthBad => {
+                write!(f, "Multicast record length bad. Queries & group records can contain at most 65535 sources, reports at most 65535 records & the auxiliary data must be a multiple of 4 bytes & at most 1020 bytes long.")
+            },
+            GeneveOptionDataLengthBad(size) => { //usize
+                write!(f, "Geneve option data length bad. The option data ({} bytes) is either not a multiple of 4 bytes or bigger then the maximum of 124 bytes.", size)
+            },
+            GeneveOptionsLengthTooLarge(size) => { //usize
+                write!(f, "Geneve options length too large. The options ({} bytes) are bigger then the maximum of 252 bytes.", size)
+            },
+            GtpuExtensionHeaderLengthBad(size) => { //usize
+                write!(f, "GTP-U extension header length bad. The content length ({} bytes) plus 2 is either not a multiple of 4 bytes or the content is bigger then the maximum of 1018 bytes.", size)
+            },
+            GtpuPayloadLengthTooLarge(size) => { //usize
+                write!(f, "GTP-U 'length' too large. The GTP-U length ({} bytes) is larger then what can be be represented by the 'length' field in the GTP-U header.", size)
+            },
+            PppoePayloadLengthTooLarge(size) => { //usize
+                write!(f, "PPPoE 'length' too large. The PPPoE length ({} bytes) is larger then what can be be represented by the 'length' field in the PPPoE header.", size)
+            },
+            GueExtensionFieldsLengthBad(size) => { //usize
+                write!(f, "GUE extension fields length bad. The extension fields length ({} bytes) is not a multiple of 4 or larger then what can be represented by the 'hlen' field in the GUE header.", size)
+            },
+            TeredoAuthenticationLengthTooLarge(size) => { //usize
+                write!(f, "Teredo authentication field too large. The client identifier or authentication value ({} bytes) is larger then what can be represented by the length fields in the Teredo authentication header.", size)
+            },
+            Ipv4FragmentMtuTooSmall(mtu) => { //usize
+                write!(f, "IPv4 MTU too small. The MTU ({} bytes) is too small to contain an IPv4 fragment with at least 8 bytes of payload.", mtu)
+            },
+            Ipv4DontFragmentSet(size) => { //usize
+                write!(f, "IPv4 'dont_fragment' set. The IPv4 packet ({} bytes) is larger then the MTU but can not be fragmented as the 'dont_fragment' flag is set.", size)
+            },
+            Ipv6FragmentMtuTooSmall(mtu) => { //usize
+                write!(f, "IPv6 MTU too small. The MTU ({} bytes) is too small to contain an IPv6 fragment with the extension headers & at least 8 bytes of payload.", mtu)
+            },
+            Ipv6SegmentRoutingNoSegments => {
+                write!(f, "IPv6 segment routing header without segments. A segment routing header has to contain at least one segment.")
+            },
+            Ipv6SegmentRoutingTlvTooLarge(size) => { //usize
+                write!(f, "IPv6 segment routing TLV too large. The TLV value ({} bytes) is larger then what can be represented by the length field of the TLV.", size)
+            },
+            Ipv6ExtensionOutOfOrder{ header, previous } => {
+                write!(f, "IPv6 extensions '{:?}' is located after the extension header '{:?}' (deviates from the order recommended by RFC 8200).", header, previous)
+            },
+            Icmpv6RouterRenumberingTooManyUsePrefixes(count) => { //usize
+                write!(f, "ICMPv6 router renumbering prefix control operation with too many use-prefix parts. The {} use-prefix parts can not be represented in the OpLength field (max 63).", count)
             }
         }
     }
@@ -549,8 +969,6 @@ impl fmt::Display for ValueError {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ErrorField {
     Ipv4PayloadLength,
-    Ipv4Dscp,
-    Ipv4Ecn,
     Ipv4FragmentsOffset,
     Ipv6FlowLabel,
     /// Ipv6 fragment header fragment offset field.
@@ -559,6 +977,40 @@ pub enum ErrorField {
     VlanTagPriorityCodePoint,
     ///VlanTaggingHeader.vlan_identifier
     VlanTagVlanId,
+    ///MldV2Query.querier_robustness_variable
+    MldQuerierRobustnessVariable,
+    ///IgmpV3Query.querier_robustness_variable
+    IgmpQuerierRobustnessVariable,
+    ///VxlanHeader.vni
+    VxlanVni,
+    ///GeneveHeader.version
+    GeneveVersion,
+    ///GeneveHeader.vni
+    GeneveVni,
+    ///GtpuPduSessionContainer.pdu_type
+    GtpuPduType,
+    ///GtpuPduSessionContainer.qos_flow_identifier
+    GtpuQosFlowIdentifier,
+    ///PppoeHeader.version
+    PppoeVersion,
+    ///PppoeHeader.pppoe_type
+    PppoeType,
+    ///PbbItagHeader.priority_code_point
+    PbbItagPriorityCodePoint,
+    ///PbbItagHeader.i_sid
+    PbbItagIsid,
+    ///GreHeader::nvgre vsid argument
+    NvgreVsid,
+    ///VxlanGpeHeader.vni
+    VxlanGpeVni,
+    ///Ipv4TimestampOption.overflow
+    Ipv4TimestampOverflow,
+    ///Ipv4TimestampOption.flag
+    Ipv4TimestampFlag,
+    ///Dscp (IPv4 type of service or IPv6 traffic class)
+    Dscp,
+    ///Ecn (IPv4 type of service or IPv6 traffic class)
+    Ecn,
 }
 
 impl fmt::Display for ErrorField {
@@ -566,13 +1018,28 @@ impl fmt::Display for ErrorField {
         use ErrorField::*;
         match self {
             Ipv4PayloadLength => write!(f, "Ipv4Header.payload_len"),
-            Ipv4Dscp => write!(f, "Ipv4Header.differentiated_services_code_point"),
-            Ipv4Ecn => write!(f, "Ipv4Header.explicit_congestion_notification"),
             Ipv4FragmentsOffset => write!(f, "Ipv4Header.fragments_offset"),
             Ipv6FlowLabel => write!(f, "Ipv6Header.flow_label"),
             Ipv6FragmentOffset => write!(f, "Ipv6FragmentHeader.fragment_offset"),
             VlanTagPriorityCodePoint => write!(f, "SingleVlanHeader.priority_code_point"),
-            VlanTagVlanId => write!(f, "SingleVlanHeader.vlan_identifier")
+            VlanTagVlanId => write!(f, "SingleVlanHeader.vlan_identifier"),
+            MldQuerierRobustnessVariable => write!(f, "MldV2Query.querier_robustness_variable"),
+            IgmpQuerierRobustnessVariable => write!(f, "IgmpV3Query.querier_robustness_variable"),
+            VxlanVni => write!(f, "VxlanHeader.vni"),
+            GeneveVersion => write!(f, "GeneveHeader.version"),
+            GeneveVni => write!(f, "GeneveHeader.vni"),
+            GtpuPduType => write!(f, "GtpuPduSessionContainer.pdu_type"),
+            GtpuQosFlowIdentifier => write!(f, "GtpuPduSessionContainer.qos_flow_identifier"),
+            PppoeVersion => write!(f, "PppoeHeader.version"),
+            PppoeType => write!(f, "PppoeHeader.pppoe_type"),
+            PbbItagPriorityCodePoint => write!(f, "PbbItagHeader.priority_code_point"),
+            PbbItagIsid => write!(f, "PbbItagHeader.i_sid"),
+            NvgreVsid => write!(f, "GreHeader.key (NVGRE vsid)"),
+            VxlanGpeVni => write!(f, "VxlanGpeHeader.vni"),
+            Ipv4TimestampOverflow => write!(f, "Ipv4TimestampOption.overflow"),
+            Ipv4TimestampFlag => write!(f, "Ipv4TimestampOption.flag"),
+            Dscp => write!(f, "Dscp"),
+            Ecn => write!(f, "Ecn")
         }
     }
 }
@@ -603,6 +1070,19 @@ fn max_check_u16(value: u16, max: u16, field: ErrorField) -> Result<(), ValueErr
     }
 }
 
+fn max_check_u32(value: u32, max: u32, field: ErrorField) -> Result<(), ValueError> {
+    use crate::ValueError::U32TooLarge;
+    if value <= max {
+        Ok(())
+    } else {
+        Err(U32TooLarge{
+            value,
+            max,
+            field
+        })
+    }
+}
+
 /// Helper function for reading big endian u16 values from a ptr unchecked.
 ///
 /// # Safety
@@ -0,0 +1,315 @@
+use super::super::*;
+
+use std::io;
+use std::slice::from_raw_parts;
+
+/// `u16` constants for the ARP operation field.
+pub mod arp_operation {
+    /// ARP request (RFC 826).
+    pub const REQUEST: u16 = 1;
+    /// ARP reply (RFC 826).
+    pub const REPLY: u16 = 2;
+    /// Reverse ARP request (RFC 903).
+    pub const REVERSE_REQUEST: u16 = 3;
+    /// Reverse ARP reply (RFC 903).
+    pub const REVERSE_REPLY: u16 = 4;
+}
+
+/// `u16` constants for the most used ARP hardware types.
+pub mod arp_hardware_id {
+    /// Ethernet (10Mb) hardware type (also used by all other ethernet variants).
+    pub const ETHERNET: u16 = 1;
+    /// IEEE 802 networks (e.g. token ring).
+    pub const IEEE802: u16 = 6;
+}
+
+/// Address Resolution Protocol packet (RFC 826).
+///
+/// The address lengths are not restricted to ethernet & IPv4. The
+/// hardware & protocol address lengths written to the packet are
+/// taken from the lengths of the address fields, which requires the
+/// sender & target addresses of the same kind to have the same length.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ArpPacket {
+    /// Hardware type of the addresses (see [`arp_hardware_id`]).
+    pub hw_addr_type: u16,
+    /// Protocol type of the addresses (an ether type value, e.g. [`ether_type::IPV4`]).
+    pub proto_addr_type: u16,
+    /// Operation of the packet (see [`arp_operation`]).
+    pub operation: u16,
+    /// Hardware address of the sender.
+    pub sender_hw_addr: Vec<u8>,
+    /// Protocol address of the sender.
+    pub sender_protocol_addr: Vec<u8>,
+    /// Hardware address of the target (ignored in requests).
+    pub target_hw_addr: Vec<u8>,
+    /// Protocol address of the target.
+    pub target_protocol_addr: Vec<u8>,
+}
+
+impl ArpPacket {
+    /// Length of the fixed part of the packet (before the addresses) in bytes.
+    pub const MIN_LEN: usize = 8;
+
+    /// Creates an ARP packet mapping IPv4 addresses to ethernet mac addresses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{arp_operation, ArpPacket};
+    ///
+    /// // who has 192.168.1.2? tell 192.168.1.1
+    /// let request = ArpPacket::new_ethernet_ipv4(
+    ///     arp_operation::REQUEST,
+    ///     [1,2,3,4,5,6], [192,168,1,1],
+    ///     [0;6], [192,168,1,2]
+    /// );
+    /// assert_eq!(28, request.header_len());
+    /// ```
+    pub fn new_ethernet_ipv4(
+        operation: u16,
+        sender_hw_addr: [u8;6],
+        sender_protocol_addr: [u8;4],
+        target_hw_addr: [u8;6],
+        target_protocol_addr: [u8;4],
+    ) -> ArpPacket {
+        ArpPacket {
+            hw_addr_type: arp_hardware_id::ETHERNET,
+            proto_addr_type: ether_type::IPV4,
+            operation,
+            sender_hw_addr: sender_hw_addr.to_vec(),
+            sender_protocol_addr: sender_protocol_addr.to_vec(),
+            target_hw_addr: target_hw_addr.to_vec(),
+            target_protocol_addr: target_protocol_addr.to_vec(),
+        }
+    }
+
+    /// Read an ArpPacket from a slice and return the packet & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(ArpPacket, &[u8]), ReadError> {
+        let packet = ArpPacketSlice::from_slice(slice)?;
+        let len = packet.slice().len();
+        Ok((packet.to_packet(), &slice[len..]))
+    }
+
+    /// Reads an ARP packet from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<ArpPacket, io::Error> {
+        let mut start = [0u8;ArpPacket::MIN_LEN];
+        reader.read_exact(&mut start)?;
+
+        let hw_len = usize::from(start[4]);
+        let proto_len = usize::from(start[5]);
+        let mut addresses = vec![0u8;2*(hw_len + proto_len)];
+        reader.read_exact(&mut addresses)?;
+
+        let mut buffer = Vec::with_capacity(ArpPacket::MIN_LEN + addresses.len());
+        buffer.extend_from_slice(&start);
+        buffer.extend_from_slice(&addresses);
+        Ok(ArpPacketSlice{
+            slice: &buffer
+        }.to_packet())
+    }
+
+    /// Writes the ARP packet to the current position of the write argument.
+    ///
+    /// Returns a [`ValueError::ArpAddressLengthBad`] error if the sender &
+    /// target addresses differ in length or are longer then 255 bytes.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized packet in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        ArpPacket::MIN_LEN +
+        self.sender_hw_addr.len() +
+        self.sender_protocol_addr.len() +
+        self.target_hw_addr.len() +
+        self.target_protocol_addr.len()
+    }
+
+    /// Returns the hardware address of the sender as a [`MacAddr`] (None
+    /// if the address is not 6 bytes long).
+    #[inline]
+    pub fn sender_mac(&self) -> Option<MacAddr> {
+        mac_addr(&self.sender_hw_addr)
+    }
+
+    /// Returns the hardware address of the target as a [`MacAddr`] (None
+    /// if the address is not 6 bytes long).
+    #[inline]
+    pub fn target_mac(&self) -> Option<MacAddr> {
+        mac_addr(&self.target_hw_addr)
+    }
+
+    /// Returns the serialized form of the packet or an value error in case
+    /// the address lengths can not be represented.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        use crate::ValueError::ArpAddressLengthBad;
+        let hw_len = self.sender_hw_addr.len();
+        let proto_len = self.sender_protocol_addr.len();
+        if hw_len != self.target_hw_addr.len() ||
+           proto_len != self.target_protocol_addr.len() ||
+           hw_len > 0xff ||
+           proto_len > 0xff
+        {
+            return Err(ArpAddressLengthBad);
+        }
+
+        let mut result = Vec::with_capacity(self.header_len());
+        result.extend_from_slice(&self.hw_addr_type.to_be_bytes());
+        result.extend_from_slice(&self.proto_addr_type.to_be_bytes());
+        result.push(hw_len as u8);
+        result.push(proto_len as u8);
+        result.extend_from_slice(&self.operation.to_be_bytes());
+        result.extend_from_slice(&self.sender_hw_addr);
+        result.extend_from_slice(&self.sender_protocol_addr);
+        result.extend_from_slice(&self.target_hw_addr);
+        result.extend_from_slice(&self.target_protocol_addr);
+        Ok(result)
+    }
+}
+
+/// A slice containing an ARP packet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArpPacketSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> ArpPacketSlice<'a> {
+
+    /// Creates an ARP packet slice from a slice (data after the addresses,
+    /// e.g. ethernet padding, is not part of the resulting slice).
+    pub fn from_slice(slice: &'a [u8]) -> Result<ArpPacketSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < ArpPacket::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(ArpPacket::MIN_LEN));
+        }
+        let len = ArpPacket::MIN_LEN + 2*(usize::from(slice[4]) + usize::from(slice[5]));
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(ArpPacketSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), len)
+            }
+        })
+    }
+
+    /// Returns the slice containing the ARP packet.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the hardware type field.
+    #[inline]
+    pub fn hw_addr_type(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of ArpPacket::MIN_LEN (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr())
+        }
+    }
+
+    /// Read the protocol type field (an ether type value).
+    #[inline]
+    pub fn proto_addr_type(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of ArpPacket::MIN_LEN (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Read the length of the hardware addresses in bytes.
+    #[inline]
+    pub fn hw_addr_size(&self) -> u8 {
+        self.slice[4]
+    }
+
+    /// Read the length of the protocol addresses in bytes.
+    #[inline]
+    pub fn proto_addr_size(&self) -> u8 {
+        self.slice[5]
+    }
+
+    /// Read the operation field.
+    #[inline]
+    pub fn operation(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of ArpPacket::MIN_LEN (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(6))
+        }
+    }
+
+    /// Slice containing the hardware address of the sender.
+    #[inline]
+    pub fn sender_hw_addr(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN;
+        &self.slice[start..start + usize::from(self.hw_addr_size())]
+    }
+
+    /// Read the hardware address of the sender as a [`MacAddr`] (None if
+    /// the hardware addresses are not 6 bytes long).
+    #[inline]
+    pub fn sender_mac(&self) -> Option<MacAddr> {
+        mac_addr(self.sender_hw_addr())
+    }
+
+    /// Slice containing the protocol address of the sender.
+    #[inline]
+    pub fn sender_protocol_addr(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN + usize::from(self.hw_addr_size());
+        &self.slice[start..start + usize::from(self.proto_addr_size())]
+    }
+
+    /// Slice containing the hardware address of the target.
+    #[inline]
+    pub fn target_hw_addr(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN + usize::from(self.hw_addr_size()) + usize::from(self.proto_addr_size());
+        &self.slice[start..start + usize::from(self.hw_addr_size())]
+    }
+
+    /// Read the hardware address of the target as a [`MacAddr`] (None if
+    /// the hardware addresses are not 6 bytes long).
+    #[inline]
+    pub fn target_mac(&self) -> Option<MacAddr> {
+        mac_addr(self.target_hw_addr())
+    }
+
+    /// Slice containing the protocol address of the target.
+    #[inline]
+    pub fn target_protocol_addr(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN + 2*usize::from(self.hw_addr_size()) + usize::from(self.proto_addr_size());
+        &self.slice[start..start + usize::from(self.proto_addr_size())]
+    }
+
+    /// Decode all the fields and copy the results to an ArpPacket struct.
+    pub fn to_packet(&self) -> ArpPacket {
+        ArpPacket {
+            hw_addr_type: self.hw_addr_type(),
+            proto_addr_type: self.proto_addr_type(),
+            operation: self.operation(),
+            sender_hw_addr: self.sender_hw_addr().to_vec(),
+            sender_protocol_addr: self.sender_protocol_addr().to_vec(),
+            target_hw_addr: self.target_hw_addr().to_vec(),
+            target_protocol_addr: self.target_protocol_addr().to_vec(),
+        }
+    }
+}
+
+fn mac_addr(hw_addr: &[u8]) -> Option<MacAddr> {
+    if hw_addr.len() == 6 {
+        MacAddr::from_slice(hw_addr)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,335 @@
+use super::super::*;
+
+/// Packet types of EAPOL frames (IEEE 802.1X).
+pub mod eapol_packet_type {
+    pub const EAP_PACKET: u8 = 0;
+    pub const START: u8 = 1;
+    pub const LOGOFF: u8 = 2;
+    pub const KEY: u8 = 3;
+    pub const ENCAPSULATED_ASF_ALERT: u8 = 4;
+    /// MACsec key agreement (IEEE 802.1X-2010).
+    pub const MKA: u8 = 5;
+    pub const ANNOUNCEMENT_GENERIC: u8 = 6;
+    pub const ANNOUNCEMENT_SPECIFIC: u8 = 7;
+    pub const ANNOUNCEMENT_REQ: u8 = 8;
+}
+
+/// Descriptor types of EAPOL-Key frames.
+pub mod eapol_key_descriptor_type {
+    /// RC4 key descriptor (deprecated, not decoded by [`crate::EapolKeySlice`]).
+    pub const RC4: u8 = 1;
+    /// IEEE 802.11 key descriptor (WPA2 & WPA3).
+    pub const IEEE802_11: u8 = 2;
+    /// Key descriptor used by WPA (pre IEEE 802.11i).
+    pub const WPA: u8 = 254;
+}
+
+/// Bits of the key information field of EAPOL-Key frames (IEEE 802.11).
+pub mod eapol_key_info {
+    /// Mask of the 3 bit key descriptor version (1 HMAC-MD5 & RC4,
+    /// 2 HMAC-SHA1 & AES key wrap, 3 AES-CMAC & AES key wrap, 0 AKM defined).
+    pub const KEY_DESCRIPTOR_VERSION_MASK: u16 = 0x0007;
+    /// Set for pairwise keys & unset for group keys.
+    pub const KEY_TYPE: u16 = 0x0008;
+    /// Mask of the 2 bit key index (WPA only).
+    pub const KEY_INDEX_MASK: u16 = 0x0030;
+    pub const INSTALL: u16 = 0x0040;
+    pub const KEY_ACK: u16 = 0x0080;
+    pub const KEY_MIC: u16 = 0x0100;
+    pub const SECURE: u16 = 0x0200;
+    pub const ERROR: u16 = 0x0400;
+    pub const REQUEST: u16 = 0x0800;
+    pub const ENCRYPTED_KEY_DATA: u16 = 0x1000;
+    pub const SMK_MESSAGE: u16 = 0x2000;
+}
+
+/// A slice containing an EAPOL frame (payload of ethernet frames with the
+/// ether type [`ether_type::EAPOL`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{EapolSlice, eapol_packet_type};
+///
+/// let data = [
+///     2, // version
+///     eapol_packet_type::START,
+///     0, 0, // body length
+///     0, 0, // ethernet padding
+/// ];
+/// let eapol = EapolSlice::from_slice(&data).unwrap();
+/// assert_eq!(2, eapol.version());
+/// assert_eq!(eapol_packet_type::START, eapol.packet_type());
+/// assert!(eapol.body().is_empty());
+/// assert_eq!(None, eapol.key());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EapolSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> EapolSlice<'a> {
+    /// Length of the EAPOL header (version, packet type & body length).
+    pub const HEADER_LEN: usize = 4;
+
+    /// Creates an EAPOL slice from an other slice (data after the body,
+    /// e.g. ethernet padding, is not part of the resulting slice).
+    ///
+    /// The body of EAPOL-Key frames with an IEEE 802.11 or WPA key
+    /// descriptor is checked as well (see [`EapolKeySlice::from_slice`]).
+    pub fn from_slice(slice: &'a [u8]) -> Result<EapolSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < EapolSlice::HEADER_LEN {
+            return Err(UnexpectedEndOfSlice(EapolSlice::HEADER_LEN));
+        }
+        let len = EapolSlice::HEADER_LEN + usize::from(u16::from_be_bytes([slice[2], slice[3]]));
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        let result = EapolSlice{
+            slice: &slice[..len]
+        };
+        if result.is_ieee802_11_key() {
+            EapolKeySlice::from_slice(result.body())
+                .map_err(|err| err.add_slice_offset(EapolSlice::HEADER_LEN))?;
+        }
+        Ok(result)
+    }
+
+    /// Returns the slice containing the EAPOL frame.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the protocol version (1 for IEEE 802.1X-2001, 2 for
+    /// IEEE 802.1X-2004 & 3 for IEEE 802.1X-2010).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the packet type (see [`eapol_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the length of the body.
+    #[inline]
+    pub fn body_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Returns the slice containing the body.
+    #[inline]
+    pub fn body(&self) -> &'a [u8] {
+        &self.slice[EapolSlice::HEADER_LEN..]
+    }
+
+    /// Returns the EAPOL-Key frame contained in the body (None if the frame
+    /// is not an EAPOL-Key frame with an IEEE 802.11 or WPA key descriptor).
+    pub fn key(&self) -> Option<EapolKeySlice<'a>> {
+        if self.is_ieee802_11_key() {
+            // the key was already checked by EapolSlice::from_slice
+            EapolKeySlice::from_slice(self.body()).ok()
+        } else {
+            None
+        }
+    }
+
+    fn is_ieee802_11_key(&self) -> bool {
+        use eapol_key_descriptor_type::*;
+        eapol_packet_type::KEY == self.packet_type() &&
+        matches!(self.body().first(), Some(&IEEE802_11) | Some(&WPA))
+    }
+}
+
+/// A slice containing the body of an EAPOL-Key frame with an IEEE 802.11
+/// or WPA key descriptor (as used by the 4-way & group key handshakes).
+///
+/// The MIC is expected to have a length of 16 bytes (true for all AKMs
+/// except the 802.1X suite B 192 bit & FILS AKMs).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EapolKeySlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> EapolKeySlice<'a> {
+    /// Length of the key descriptor without the key data.
+    pub const MIN_LEN: usize = 95;
+
+    /// Creates an EAPOL-Key slice from the body of an EAPOL frame (data
+    /// after the key data is not part of the resulting slice).
+    pub fn from_slice(slice: &'a [u8]) -> Result<EapolKeySlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < EapolKeySlice::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(EapolKeySlice::MIN_LEN));
+        }
+        let len = EapolKeySlice::MIN_LEN + usize::from(u16::from_be_bytes([slice[93], slice[94]]));
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(EapolKeySlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the key descriptor.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the descriptor type (see [`eapol_key_descriptor_type`]).
+    #[inline]
+    pub fn descriptor_type(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the key information field (see [`eapol_key_info`]).
+    #[inline]
+    pub fn key_info(&self) -> u16 {
+        u16::from_be_bytes([self.slice[1], self.slice[2]])
+    }
+
+    /// Read the key descriptor version contained in the key information field.
+    #[inline]
+    pub fn key_descriptor_version(&self) -> u8 {
+        (self.key_info() & eapol_key_info::KEY_DESCRIPTOR_VERSION_MASK) as u8
+    }
+
+    /// Returns true if the key type flag is set (pairwise key).
+    #[inline]
+    pub fn is_pairwise(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::KEY_TYPE
+    }
+
+    /// Returns true if the install flag is set.
+    #[inline]
+    pub fn install(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::INSTALL
+    }
+
+    /// Returns true if the key ack flag is set (frame sent by the authenticator).
+    #[inline]
+    pub fn key_ack(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::KEY_ACK
+    }
+
+    /// Returns true if the key MIC flag is set.
+    #[inline]
+    pub fn key_mic(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::KEY_MIC
+    }
+
+    /// Returns true if the secure flag is set.
+    #[inline]
+    pub fn secure(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::SECURE
+    }
+
+    /// Returns true if the error flag is set.
+    #[inline]
+    pub fn error(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::ERROR
+    }
+
+    /// Returns true if the request flag is set.
+    #[inline]
+    pub fn request(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::REQUEST
+    }
+
+    /// Returns true if the encrypted key data flag is set.
+    #[inline]
+    pub fn encrypted_key_data(&self) -> bool {
+        0 != self.key_info() & eapol_key_info::ENCRYPTED_KEY_DATA
+    }
+
+    /// Read the length of the pairwise temporal key or the group temporal key.
+    #[inline]
+    pub fn key_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[3], self.slice[4]])
+    }
+
+    /// Read the replay counter.
+    #[inline]
+    pub fn replay_counter(&self) -> u64 {
+        u64::from_be_bytes([
+            self.slice[5],
+            self.slice[6],
+            self.slice[7],
+            self.slice[8],
+            self.slice[9],
+            self.slice[10],
+            self.slice[11],
+            self.slice[12],
+        ])
+    }
+
+    /// Returns the slice containing the nonce (ANonce or SNonce).
+    #[inline]
+    pub fn nonce(&self) -> &'a [u8] {
+        &self.slice[13..45]
+    }
+
+    /// Returns the slice containing the key IV.
+    #[inline]
+    pub fn key_iv(&self) -> &'a [u8] {
+        &self.slice[45..61]
+    }
+
+    /// Returns the slice containing the key receive sequence counter.
+    #[inline]
+    pub fn key_rsc(&self) -> &'a [u8] {
+        &self.slice[61..69]
+    }
+
+    /// Returns the slice containing the reserved key identifier field.
+    #[inline]
+    pub fn key_id(&self) -> &'a [u8] {
+        &self.slice[69..77]
+    }
+
+    /// Returns the slice containing the MIC.
+    #[inline]
+    pub fn mic(&self) -> &'a [u8] {
+        &self.slice[77..93]
+    }
+
+    /// Read the length of the key data.
+    #[inline]
+    pub fn key_data_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[93], self.slice[94]])
+    }
+
+    /// Returns the slice containing the key data.
+    #[inline]
+    pub fn key_data(&self) -> &'a [u8] {
+        &self.slice[EapolKeySlice::MIN_LEN..]
+    }
+
+    /// Returns the number of the 4-way handshake message (1 to 4) based on
+    /// the key information flags (None for group key handshake & other frames).
+    ///
+    /// Message 2 & 4 are distinguished via the secure flag & the nonce
+    /// (message 4 contains no nonce).
+    pub fn handshake_message(&self) -> Option<u8> {
+        if !self.is_pairwise() || self.request() || self.error() {
+            return None;
+        }
+        match (self.key_ack(), self.key_mic()) {
+            (true, false) => Some(1),
+            (true, true) if self.install() => Some(3),
+            (false, true) => if self.secure() || self.nonce().iter().all(|&b| 0 == b) {
+                Some(4)
+            } else {
+                Some(2)
+            },
+            _ => None,
+        }
+    }
+}
@@ -1,37 +1,302 @@
 use super::super::*;
 
+use std::convert::TryFrom;
 use std::slice::from_raw_parts;
 use std::io;
 
 /// Ether type enum present in ethernet II header.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+///
+/// Contains the most used values of the IANA "IEEE 802 Numbers" registry.
+/// The raw value can be obtained via a cast (`EtherType::Ipv4 as u16`) &
+/// raw values can be converted via `TryFrom<u16>` (or [`EtherType::from_u16`]).
+///
+/// ```
+/// use etherparse::EtherType;
+/// use std::convert::TryFrom;
+///
+/// let ether_type = EtherType::try_from(0x86dd).unwrap();
+/// assert_eq!(EtherType::Ipv6, ether_type);
+/// assert!(ether_type.is_ip());
+/// assert_eq!("IPv6", ether_type.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
 pub enum EtherType {
     Ipv4 = 0x0800,
-    Ipv6 = 0x86dd,
     Arp = 0x0806,
     WakeOnLan = 0x0842,
+    /// Stream Reservation Protocol (IEEE 802.1Qat).
+    Srp = 0x22EA,
+    /// Audio Video Transport Protocol (IEEE 1722).
+    Avtp = 0x22F0,
+    /// Transparent Interconnection of Lots of Links (RFC 6325).
+    Trill = 0x22F3,
+    DecnetPhaseIv = 0x6003,
+    TransparentEthernetBridging = 0x6558,
+    /// Reverse ARP (RFC 903).
+    Rarp = 0x8035,
+    AppleTalk = 0x809B,
+    /// AppleTalk ARP.
+    Aarp = 0x80F3,
+    /// Customer vlan tag (IEEE 802.1Q).
     VlanTaggedFrame = 0x8100,
+    Ipx = 0x8137,
+    Ipv6 = 0x86dd,
+    /// MAC control frames (e.g. pause frames, IEEE 802.3x).
+    EthernetFlowControl = 0x8808,
+    /// Slow protocols like LACP (IEEE 802.3 annex 57A).
+    SlowProtocols = 0x8809,
+    MplsUnicast = 0x8847,
+    MplsMulticast = 0x8848,
+    PppoeDiscovery = 0x8863,
+    PppoeSession = 0x8864,
+    /// EAP over LAN (IEEE 802.1X).
+    Eapol = 0x888E,
+    Profinet = 0x8892,
+    AtaOverEthernet = 0x88A2,
+    EtherCat = 0x88A4,
+    /// Service vlan tag (IEEE 802.1ad).
     ProviderBridging = 0x88A8,
-    VlanDoubleTaggedFrame = 0x9100
+    EthernetPowerlink = 0x88AB,
+    /// Generic Object Oriented Substation Event (IEC 61850).
+    Goose = 0x88B8,
+    Lldp = 0x88CC,
+    Sercos3 = 0x88CD,
+    /// MAC security (IEEE 802.1AE).
+    MacSec = 0x88E5,
+    /// Backbone service instance tag (IEEE 802.1ah).
+    ProviderBackboneBridging = 0x88E7,
+    /// Precision Time Protocol (IEEE 1588).
+    Ptp = 0x88F7,
+    /// Parallel Redundancy Protocol (IEC 62439-3).
+    Prp = 0x88FB,
+    /// Connectivity fault management (IEEE 802.1ag).
+    Cfm = 0x8902,
+    /// Fibre Channel over Ethernet.
+    Fcoe = 0x8906,
+    FcoeInitialization = 0x8914,
+    /// RDMA over Converged Ethernet.
+    Roce = 0x8915,
+    /// High-availability Seamless Redundancy (IEC 62439-3).
+    Hsr = 0x892F,
+    /// Network Service Header (RFC 8300).
+    Nsh = 0x894F,
+    /// Loopback (Ethernet Configuration Testing Protocol).
+    EthernetConfigurationTesting = 0x9000,
+    /// Non standard vlan tag used by legacy double tagging implementations.
+    VlanDoubleTaggedFrame = 0x9100,
+    /// Redundancy tag (IEEE 802.1CB).
+    RedundancyTag = 0xF1C1,
 }
 
 impl EtherType {
+    /// All values of the enum (ordered by their raw values).
+    pub const ALL: [EtherType;42] = [
+        EtherType::Ipv4,
+        EtherType::Arp,
+        EtherType::WakeOnLan,
+        EtherType::Srp,
+        EtherType::Avtp,
+        EtherType::Trill,
+        EtherType::DecnetPhaseIv,
+        EtherType::TransparentEthernetBridging,
+        EtherType::Rarp,
+        EtherType::AppleTalk,
+        EtherType::Aarp,
+        EtherType::VlanTaggedFrame,
+        EtherType::Ipx,
+        EtherType::Ipv6,
+        EtherType::EthernetFlowControl,
+        EtherType::SlowProtocols,
+        EtherType::MplsUnicast,
+        EtherType::MplsMulticast,
+        EtherType::PppoeDiscovery,
+        EtherType::PppoeSession,
+        EtherType::Eapol,
+        EtherType::Profinet,
+        EtherType::AtaOverEthernet,
+        EtherType::EtherCat,
+        EtherType::ProviderBridging,
+        EtherType::EthernetPowerlink,
+        EtherType::Goose,
+        EtherType::Lldp,
+        EtherType::Sercos3,
+        EtherType::MacSec,
+        EtherType::ProviderBackboneBridging,
+        EtherType::Ptp,
+        EtherType::Prp,
+        EtherType::Cfm,
+        EtherType::Fcoe,
+        EtherType::FcoeInitialization,
+        EtherType::Roce,
+        EtherType::Hsr,
+        EtherType::Nsh,
+        EtherType::EthernetConfigurationTesting,
+        EtherType::VlanDoubleTaggedFrame,
+        EtherType::RedundancyTag,
+    ];
+
     ///Tries to convert a raw ether type value to the enum. Returns None if the value does not exist in the enum.
+    #[inline]
     pub fn from_u16(value: u16) -> Option<EtherType> {
+        EtherType::try_from(value).ok()
+    }
+
+    /// Returns the name of the protocol identified by the ether type.
+    pub fn name(self) -> &'static str {
+        use self::EtherType::*;
+        match self {
+            Ipv4 => "IPv4",
+            Arp => "ARP",
+            WakeOnLan => "Wake-on-LAN",
+            Srp => "SRP",
+            Avtp => "AVTP",
+            Trill => "TRILL",
+            DecnetPhaseIv => "DECnet Phase IV",
+            TransparentEthernetBridging => "Transparent Ethernet Bridging",
+            Rarp => "RARP",
+            AppleTalk => "AppleTalk",
+            Aarp => "AARP",
+            VlanTaggedFrame => "802.1Q VLAN",
+            Ipx => "IPX",
+            Ipv6 => "IPv6",
+            EthernetFlowControl => "Ethernet flow control",
+            SlowProtocols => "Slow Protocols",
+            MplsUnicast => "MPLS unicast",
+            MplsMulticast => "MPLS multicast",
+            PppoeDiscovery => "PPPoE Discovery",
+            PppoeSession => "PPPoE Session",
+            Eapol => "EAPOL",
+            Profinet => "PROFINET",
+            AtaOverEthernet => "ATA over Ethernet",
+            EtherCat => "EtherCAT",
+            ProviderBridging => "802.1ad provider bridging",
+            EthernetPowerlink => "Ethernet Powerlink",
+            Goose => "GOOSE",
+            Lldp => "LLDP",
+            Sercos3 => "SERCOS III",
+            MacSec => "MACsec",
+            ProviderBackboneBridging => "802.1ah provider backbone bridging",
+            Ptp => "PTP",
+            Prp => "PRP",
+            Cfm => "CFM",
+            Fcoe => "FCoE",
+            FcoeInitialization => "FCoE Initialization",
+            Roce => "RoCE",
+            Hsr => "HSR",
+            Nsh => "NSH",
+            EthernetConfigurationTesting => "Ethernet Configuration Testing",
+            VlanDoubleTaggedFrame => "802.1Q double tagged VLAN",
+            RedundancyTag => "802.1CB redundancy tag",
+        }
+    }
+
+    /// Returns true if the ether type identifies a vlan tag (customer,
+    /// service or legacy double tagging tag).
+    #[inline]
+    pub fn is_vlan_tagging(self) -> bool {
+        use self::EtherType::*;
+        matches!(self, VlanTaggedFrame | ProviderBridging | VlanDoubleTaggedFrame)
+    }
+
+    /// Returns true if the ether type identifies an IPv4 or IPv6 packet.
+    #[inline]
+    pub fn is_ip(self) -> bool {
+        matches!(self, EtherType::Ipv4 | EtherType::Ipv6)
+    }
+
+    /// Returns true if the ether type identifies a MPLS (unicast or multicast) packet.
+    #[inline]
+    pub fn is_mpls(self) -> bool {
+        matches!(self, EtherType::MplsUnicast | EtherType::MplsMulticast)
+    }
+
+    /// Returns true if the ether type identifies a PPPoE discovery or session packet.
+    #[inline]
+    pub fn is_pppoe(self) -> bool {
+        matches!(self, EtherType::PppoeDiscovery | EtherType::PppoeSession)
+    }
+}
+
+impl TryFrom<u16> for EtherType {
+    type Error = UnknownEtherTypeError;
+
+    fn try_from(value: u16) -> Result<EtherType, UnknownEtherTypeError> {
         use self::EtherType::*;
         match value {
-            0x0800 => Some(Ipv4),
-            0x86dd => Some(Ipv6),
-            0x0806 => Some(Arp),
-            0x0842 => Some(WakeOnLan),
-            0x88A8 => Some(ProviderBridging),
-            0x8100 => Some(VlanTaggedFrame),
-            0x9100 => Some(VlanDoubleTaggedFrame),
-            _ => None
+            0x0800 => Ok(Ipv4),
+            0x0806 => Ok(Arp),
+            0x0842 => Ok(WakeOnLan),
+            0x22EA => Ok(Srp),
+            0x22F0 => Ok(Avtp),
+            0x22F3 => Ok(Trill),
+            0x6003 => Ok(DecnetPhaseIv),
+            0x6558 => Ok(TransparentEthernetBridging),
+            0x8035 => Ok(Rarp),
+            0x809B => Ok(AppleTalk),
+            0x80F3 => Ok(Aarp),
+            0x8100 => Ok(VlanTaggedFrame),
+            0x8137 => Ok(Ipx),
+            0x86dd => Ok(Ipv6),
+            0x8808 => Ok(EthernetFlowControl),
+            0x8809 => Ok(SlowProtocols),
+            0x8847 => Ok(MplsUnicast),
+            0x8848 => Ok(MplsMulticast),
+            0x8863 => Ok(PppoeDiscovery),
+            0x8864 => Ok(PppoeSession),
+            0x888E => Ok(Eapol),
+            0x8892 => Ok(Profinet),
+            0x88A2 => Ok(AtaOverEthernet),
+            0x88A4 => Ok(EtherCat),
+            0x88A8 => Ok(ProviderBridging),
+            0x88AB => Ok(EthernetPowerlink),
+            0x88B8 => Ok(Goose),
+            0x88CC => Ok(Lldp),
+            0x88CD => Ok(Sercos3),
+            0x88E5 => Ok(MacSec),
+            0x88E7 => Ok(ProviderBackboneBridging),
+            0x88F7 => Ok(Ptp),
+            0x88FB => Ok(Prp),
+            0x8902 => Ok(Cfm),
+            0x8906 => Ok(Fcoe),
+            0x8914 => Ok(FcoeInitialization),
+            0x8915 => Ok(Roce),
+            0x892F => Ok(Hsr),
+            0x894F => Ok(Nsh),
+            0x9000 => Ok(EthernetConfigurationTesting),
+            0x9100 => Ok(VlanDoubleTaggedFrame),
+            0xF1C1 => Ok(RedundancyTag),
+            value => Err(UnknownEtherTypeError(value)),
         }
     }
 }
 
+impl From<EtherType> for u16 {
+    #[inline]
+    fn from(value: EtherType) -> u16 {
+        value as u16
+    }
+}
+
+impl fmt::Display for EtherType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error when converting a raw ether type value that is not contained
+/// in [`EtherType`] (the raw value as argument).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnknownEtherTypeError(pub u16);
+
+impl fmt::Display for UnknownEtherTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnknownEtherTypeError: The ether type 0x{:04x} is not known.", self.0)
+    }
+}
+
+impl Error for UnknownEtherTypeError {}
+
 /// `u16` constants for the most used `ether_type` values.
 ///
 /// `ether_type` values are used in the Ethernet II header and the
@@ -48,12 +313,47 @@ impl EtherType {
 pub mod ether_type {
     use crate::EtherType::*;
     pub const IPV4: u16 = Ipv4 as u16;
-    pub const IPV6: u16 = Ipv6 as u16;
     pub const ARP: u16 = Arp as u16;
     pub const WAKE_ON_LAN: u16 = WakeOnLan as u16;
+    pub const SRP: u16 = Srp as u16;
+    pub const AVTP: u16 = Avtp as u16;
+    pub const TRILL: u16 = Trill as u16;
+    pub const DECNET_PHASE_IV: u16 = DecnetPhaseIv as u16;
+    pub const TRANSPARENT_ETHERNET_BRIDGING: u16 = TransparentEthernetBridging as u16;
+    pub const RARP: u16 = Rarp as u16;
+    pub const APPLE_TALK: u16 = AppleTalk as u16;
+    pub const AARP: u16 = Aarp as u16;
     pub const VLAN_TAGGED_FRAME: u16 = VlanTaggedFrame as u16;
+    pub const IPX: u16 = Ipx as u16;
+    pub const IPV6: u16 = Ipv6 as u16;
+    pub const ETHERNET_FLOW_CONTROL: u16 = EthernetFlowControl as u16;
+    pub const SLOW_PROTOCOLS: u16 = SlowProtocols as u16;
+    pub const MPLS_UNICAST: u16 = MplsUnicast as u16;
+    pub const MPLS_MULTICAST: u16 = MplsMulticast as u16;
+    pub const PPPOE_DISCOVERY: u16 = PppoeDiscovery as u16;
+    pub const PPPOE_SESSION: u16 = PppoeSession as u16;
+    pub const EAPOL: u16 = Eapol as u16;
+    pub const PROFINET: u16 = Profinet as u16;
+    pub const ATA_OVER_ETHERNET: u16 = AtaOverEthernet as u16;
+    pub const ETHER_CAT: u16 = EtherCat as u16;
     pub const PROVIDER_BRIDGING: u16 = ProviderBridging as u16;
+    pub const ETHERNET_POWERLINK: u16 = EthernetPowerlink as u16;
+    pub const GOOSE: u16 = Goose as u16;
+    pub const LLDP: u16 = Lldp as u16;
+    pub const SERCOS3: u16 = Sercos3 as u16;
+    pub const MAC_SEC: u16 = MacSec as u16;
+    pub const PROVIDER_BACKBONE_BRIDGING: u16 = ProviderBackboneBridging as u16;
+    pub const PTP: u16 = Ptp as u16;
+    pub const PRP: u16 = Prp as u16;
+    pub const CFM: u16 = Cfm as u16;
+    pub const FCOE: u16 = Fcoe as u16;
+    pub const FCOE_INITIALIZATION: u16 = FcoeInitialization as u16;
+    pub const ROCE: u16 = Roce as u16;
+    pub const HSR: u16 = Hsr as u16;
+    pub const NSH: u16 = Nsh as u16;
+    pub const ETHERNET_CONFIGURATION_TESTING: u16 = EthernetConfigurationTesting as u16;
     pub const VLAN_DOUBLE_TAGGED_FRAME: u16 = VlanDoubleTaggedFrame as u16;
+    pub const REDUNDANCY_TAG: u16 = RedundancyTag as u16;
 }
 
 ///Ethernet II header.
@@ -71,6 +371,10 @@ impl SerializedSize for Ethernet2Header {
 
 impl Ethernet2Header {
 
+    /// Minimum length of an ethernet frame without the frame check sequence
+    /// (shorter frames have to be padded).
+    pub const MIN_FRAME_LEN: usize = 60;
+
     /// Creates a ethernet slice from an other slice.
     #[deprecated(
         since = "0.10.1",
@@ -157,6 +461,25 @@ impl Ethernet2Header {
         14
     }
 
+    /// Returns the source mac address as a [`MacAddr`].
+    #[inline]
+    pub fn source_addr(&self) -> MacAddr {
+        MacAddr(self.source)
+    }
+
+    /// Returns the destination mac address as a [`MacAddr`].
+    #[inline]
+    pub fn destination_addr(&self) -> MacAddr {
+        MacAddr(self.destination)
+    }
+
+    /// Returns the `ether_type` field as an [`EtherType`] (None if the
+    /// value is not contained in the enum).
+    #[inline]
+    pub fn known_ether_type(&self) -> Option<EtherType> {
+        EtherType::from_u16(self.ether_type)
+    }
+
     /// Returns the serialized form of the header as a statically
     /// sized byte array.
     #[inline]
@@ -239,6 +562,18 @@ impl<'a> Ethernet2HeaderSlice<'a> {
         }
     }
 
+    /// Read the destination mac address as a [`MacAddr`].
+    #[inline]
+    pub fn destination_addr(&self) -> MacAddr {
+        MacAddr(self.destination())
+    }
+
+    /// Read the source mac address as a [`MacAddr`].
+    #[inline]
+    pub fn source_addr(&self) -> MacAddr {
+        MacAddr(self.source())
+    }
+
     /// Read the ether_type field of the header (in system native byte order).
     #[inline]
     pub fn ether_type(&self) -> u16 {
@@ -250,6 +585,13 @@ impl<'a> Ethernet2HeaderSlice<'a> {
         }
     }
 
+    /// Read the ether_type field as an [`EtherType`] (None if the value
+    /// is not contained in the enum).
+    #[inline]
+    pub fn known_ether_type(&self) -> Option<EtherType> {
+        EtherType::from_u16(self.ether_type())
+    }
+
     /// Decode all the fields and copy the results to a Ipv4Header struct
     pub fn to_header(&self) -> Ethernet2Header {
         Ethernet2Header {
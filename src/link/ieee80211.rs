@@ -0,0 +1,348 @@
+use super::super::*;
+
+use std::io;
+
+/// Frame types of IEEE 802.11 MAC frames (values of the type field in the
+/// frame control field).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Ieee80211FrameType {
+    Management = 0,
+    Control = 1,
+    Data = 2,
+    Extension = 3,
+}
+
+impl Ieee80211FrameType {
+    ///Tries to convert a raw frame type value to the enum. Returns None if the value does not exist in the enum.
+    pub fn from_u8(value: u8) -> Option<Ieee80211FrameType> {
+        use self::Ieee80211FrameType::*;
+        match value {
+            0 => Some(Management),
+            1 => Some(Control),
+            2 => Some(Data),
+            3 => Some(Extension),
+            _ => None
+        }
+    }
+}
+
+/// IEEE 802.11 MAC header of management & data frames (frame control,
+/// duration, addresses, sequence control, QoS control & HT control).
+///
+/// Which of the optional fields are present is determined by the frame
+/// control field:
+///
+/// * `address4` is present in data frames with the `to_ds` & `from_ds` flags set.
+/// * `qos_control` is present in QoS data frames (subtype bit `0b1000` set).
+/// * `ht_control` is present in management & QoS data frames with the `order` flag set.
+///
+/// Control & extension frames (which use different header formats) are not
+/// supported. The header is followed by the frame body & the FCS (if captured).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ieee80211Header, Ieee80211FrameType};
+///
+/// let frame = [
+///     0x88, 0x01, // qos data frame, to ds
+///     0x2c, 0x00, // duration
+///     1, 2, 3, 4, 5, 6, // address 1 (bssid)
+///     7, 8, 9, 10, 11, 12, // address 2 (source)
+///     13, 14, 15, 16, 17, 18, // address 3 (destination)
+///     0x10, 0x00, // sequence control
+///     0x05, 0x00, // qos control
+///     0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00, // body (llc/snap)
+/// ];
+/// let (header, body) = Ieee80211Header::from_slice(&frame).unwrap();
+/// assert_eq!(Some(Ieee80211FrameType::Data), Ieee80211FrameType::from_u8(header.frame_type));
+/// assert!(header.to_ds);
+/// assert_eq!(1, header.sequence_number());
+/// assert_eq!(Some(5), header.qos_control);
+/// assert_eq!(8, body.len());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct Ieee80211Header {
+    /// Protocol version (2 bits, always 0).
+    pub protocol_version: u8,
+    /// Frame type (2 bits, see [`Ieee80211FrameType`]).
+    pub frame_type: u8,
+    /// Frame subtype (4 bits).
+    pub subtype: u8,
+    pub to_ds: bool,
+    pub from_ds: bool,
+    pub more_fragments: bool,
+    pub retry: bool,
+    pub power_management: bool,
+    pub more_data: bool,
+    /// Set if the frame body is encrypted.
+    pub protected: bool,
+    /// Set if a HT control field is present (in management & QoS data frames).
+    pub order: bool,
+    pub duration_id: u16,
+    pub address1: [u8;6],
+    pub address2: [u8;6],
+    pub address3: [u8;6],
+    /// Sequence control field (4 bit fragment number & 12 bit sequence number).
+    pub sequence_control: u16,
+    /// Fourth address (only used if `to_ds` & `from_ds` are set in a data frame).
+    pub address4: Option<[u8;6]>,
+    /// QoS control field (only used in QoS data frames).
+    pub qos_control: Option<u16>,
+    /// HT control field (only used if the `order` flag is set in a management or QoS data frame).
+    pub ht_control: Option<u32>,
+}
+
+impl Ieee80211Header {
+    /// Length of the header without the optional fields.
+    pub const MIN_LEN: usize = 24;
+
+    /// Maximum length of a header (including the 4th address, QoS & HT control).
+    pub const MAX_LEN: usize = 36;
+
+    /// Reads a 802.11 MAC header from the start of the slice & returns
+    /// the header & the rest of the slice (frame body & FCS if present).
+    pub fn from_slice(slice: &[u8]) -> Result<(Ieee80211Header, &[u8]), ReadError> {
+        let len = serialized_len(slice)?;
+        let frame_type = (slice[0] >> 2) & 0b11;
+        let subtype = slice[0] >> 4;
+        let flags = slice[1];
+        let (address4_present, qos_present, ht_present) = optional_fields_present(frame_type, subtype, flags);
+
+        let address = |offset: usize| -> [u8;6] {
+            let mut result = [0u8;6];
+            result.copy_from_slice(&slice[offset..offset + 6]);
+            result
+        };
+        let mut offset = Ieee80211Header::MIN_LEN;
+        let address4 = if address4_present {
+            offset += 6;
+            Some(address(offset - 6))
+        } else {
+            None
+        };
+        let qos_control = if qos_present {
+            offset += 2;
+            Some(u16::from_le_bytes([slice[offset - 2], slice[offset - 1]]))
+        } else {
+            None
+        };
+        let ht_control = if ht_present {
+            offset += 4;
+            Some(u32::from_le_bytes([slice[offset - 4], slice[offset - 3], slice[offset - 2], slice[offset - 1]]))
+        } else {
+            None
+        };
+
+        Ok((
+            Ieee80211Header{
+                protocol_version: slice[0] & 0b11,
+                frame_type,
+                subtype,
+                to_ds: 0 != flags & 0x01,
+                from_ds: 0 != flags & 0x02,
+                more_fragments: 0 != flags & 0x04,
+                retry: 0 != flags & 0x08,
+                power_management: 0 != flags & 0x10,
+                more_data: 0 != flags & 0x20,
+                protected: 0 != flags & 0x40,
+                order: 0 != flags & 0x80,
+                duration_id: u16::from_le_bytes([slice[2], slice[3]]),
+                address1: address(4),
+                address2: address(10),
+                address3: address(16),
+                sequence_control: u16::from_le_bytes([slice[22], slice[23]]),
+                address4,
+                qos_control,
+                ht_control,
+            },
+            &slice[len..]
+        ))
+    }
+
+    /// Value of the frame control field (in host byte order, the field
+    /// is serialized in little endian byte order).
+    pub fn frame_control(&self) -> u16 {
+        u16::from(self.protocol_version & 0b11) |
+            (u16::from(self.frame_type & 0b11) << 2) |
+            (u16::from(self.subtype & 0b1111) << 4) |
+            (u16::from(self.flags()) << 8)
+    }
+
+    /// Returns the flags byte of the frame control field.
+    fn flags(&self) -> u8 {
+        let mut result = 0;
+        for &(set, bit) in &[
+            (self.to_ds, 0x01),
+            (self.from_ds, 0x02),
+            (self.more_fragments, 0x04),
+            (self.retry, 0x08),
+            (self.power_management, 0x10),
+            (self.more_data, 0x20),
+            (self.protected, 0x40),
+            (self.order, 0x80),
+        ] {
+            if set {
+                result |= bit;
+            }
+        }
+        result
+    }
+
+    /// Returns true if the frame is a data frame.
+    #[inline]
+    pub fn is_data(&self) -> bool {
+        Ieee80211FrameType::Data as u8 == self.frame_type
+    }
+
+    /// Returns true if the frame is a data frame containing a frame body
+    /// (false for "null" data frames without a body).
+    #[inline]
+    pub fn has_data_body(&self) -> bool {
+        self.is_data() && 0 == self.subtype & 0b0100
+    }
+
+    /// Fragment number (lower 4 bits of the sequence control field).
+    #[inline]
+    pub fn fragment_number(&self) -> u8 {
+        (self.sequence_control & 0xf) as u8
+    }
+
+    /// Sequence number (upper 12 bits of the sequence control field).
+    #[inline]
+    pub fn sequence_number(&self) -> u16 {
+        self.sequence_control >> 4
+    }
+
+    /// Returns which optional fields are present in the serialized header
+    /// (address 4, QoS control, HT control).
+    fn optional_fields_present(&self) -> (bool, bool, bool) {
+        optional_fields_present(self.frame_type, self.subtype, self.flags())
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        let (address4_present, qos_present, ht_present) = self.optional_fields_present();
+        Ieee80211Header::MIN_LEN +
+            if address4_present { 6 } else { 0 } +
+            if qos_present { 2 } else { 0 } +
+            if ht_present { 4 } else { 0 }
+    }
+
+    /// Writes the header to the given writer.
+    ///
+    /// Which optional fields are written is determined by the frame type,
+    /// subtype & flags. Optional fields that have to be written but are set
+    /// to None are written as zeros.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.frame_control().to_le_bytes())?;
+        writer.write_all(&self.duration_id.to_le_bytes())?;
+        writer.write_all(&self.address1)?;
+        writer.write_all(&self.address2)?;
+        writer.write_all(&self.address3)?;
+        writer.write_all(&self.sequence_control.to_le_bytes())?;
+        let (address4_present, qos_present, ht_present) = self.optional_fields_present();
+        if address4_present {
+            writer.write_all(&self.address4.unwrap_or([0;6]))?;
+        }
+        if qos_present {
+            writer.write_all(&self.qos_control.unwrap_or(0).to_le_bytes())?;
+        }
+        if ht_present {
+            writer.write_all(&self.ht_control.unwrap_or(0).to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// A slice containing an IEEE 802.11 MAC header of a management or data frame.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ieee80211HeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> Ieee80211HeaderSlice<'a> {
+
+    /// Creates a 802.11 MAC header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<Ieee80211HeaderSlice<'a>, ReadError> {
+        let len = serialized_len(slice)?;
+        Ok(Ieee80211HeaderSlice {
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the 802.11 MAC header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the frame type (see [`Ieee80211FrameType`]).
+    #[inline]
+    pub fn frame_type(&self) -> u8 {
+        (self.slice[0] >> 2) & 0b11
+    }
+
+    /// Read the frame subtype.
+    #[inline]
+    pub fn subtype(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Returns true if the frame body is encrypted.
+    #[inline]
+    pub fn protected(&self) -> bool {
+        0 != self.slice[1] & 0x40
+    }
+
+    /// Returns true if the frame is a data frame containing a frame body
+    /// (false for "null" data frames without a body).
+    #[inline]
+    pub fn has_data_body(&self) -> bool {
+        Ieee80211FrameType::Data as u8 == self.frame_type() && 0 == self.subtype() & 0b0100
+    }
+
+    /// Decode all the fields and copy the results to a Ieee80211Header struct.
+    pub fn to_header(&self) -> Ieee80211Header {
+        // the slice was already validated on creation
+        Ieee80211Header::from_slice(self.slice).unwrap().0
+    }
+}
+
+/// Returns the length of the header at the start of the slice & checks
+/// that the frame type is supported & the slice is long enough.
+fn serialized_len(slice: &[u8]) -> Result<usize, ReadError> {
+    use ReadError::*;
+
+    if slice.len() < 2 {
+        return Err(UnexpectedEndOfSlice(2));
+    }
+    let frame_type = (slice[0] >> 2) & 0b11;
+    if frame_type != Ieee80211FrameType::Management as u8 && frame_type != Ieee80211FrameType::Data as u8 {
+        return Err(Ieee80211UnsupportedFrameType(frame_type));
+    }
+    let (address4_present, qos_present, ht_present) = optional_fields_present(frame_type, slice[0] >> 4, slice[1]);
+    let len = Ieee80211Header::MIN_LEN +
+        if address4_present { 6 } else { 0 } +
+        if qos_present { 2 } else { 0 } +
+        if ht_present { 4 } else { 0 };
+    if slice.len() < len {
+        Err(UnexpectedEndOfSlice(len))
+    } else {
+        Ok(len)
+    }
+}
+
+/// Returns which optional fields are present based on the frame type,
+/// subtype & flags (address 4, QoS control, HT control).
+fn optional_fields_present(frame_type: u8, subtype: u8, flags: u8) -> (bool, bool, bool) {
+    let data = Ieee80211FrameType::Data as u8 == frame_type;
+    let management = Ieee80211FrameType::Management as u8 == frame_type;
+    let qos = data && 0 != subtype & 0b1000;
+    let order = 0 != flags & 0x80;
+    (
+        data && 0b11 == flags & 0b11,
+        qos,
+        order && (qos || management),
+    )
+}
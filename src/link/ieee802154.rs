@@ -0,0 +1,500 @@
+use super::super::*;
+
+use std::io;
+
+/// Frame types of IEEE 802.15.4 MAC frames (values of the frame type field
+/// in the frame control field).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Ieee802154FrameType {
+    Beacon = 0,
+    Data = 1,
+    Acknowledgment = 2,
+    MacCommand = 3,
+    Multipurpose = 5,
+    Fragment = 6,
+    Extended = 7,
+}
+
+impl Ieee802154FrameType {
+    ///Tries to convert a raw frame type value to the enum. Returns None if the value does not exist in the enum.
+    pub fn from_u8(value: u8) -> Option<Ieee802154FrameType> {
+        use self::Ieee802154FrameType::*;
+        match value {
+            0 => Some(Beacon),
+            1 => Some(Data),
+            2 => Some(Acknowledgment),
+            3 => Some(MacCommand),
+            5 => Some(Multipurpose),
+            6 => Some(Fragment),
+            7 => Some(Extended),
+            _ => None
+        }
+    }
+}
+
+/// Short or extended IEEE 802.15.4 address.
+///
+/// The bytes are stored in the usual "big endian" notation (e.g. the
+/// EUI-64 as printed on devices), on the wire the addresses are sent
+/// in little endian byte order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Ieee802154Address {
+    /// 16 bit short address (addressing mode `0b10`).
+    Short([u8;2]),
+    /// 64 bit extended address (addressing mode `0b11`).
+    Extended([u8;8]),
+}
+
+impl Ieee802154Address {
+    /// Value of the addressing mode field for the address.
+    fn addressing_mode(&self) -> u16 {
+        match self {
+            Ieee802154Address::Short(_) => 0b10,
+            Ieee802154Address::Extended(_) => 0b11,
+        }
+    }
+
+    /// Length of the address in bytes.
+    pub fn len(&self) -> usize {
+        match self {
+            Ieee802154Address::Short(_) => 2,
+            Ieee802154Address::Extended(_) => 8,
+        }
+    }
+
+    /// Always false (an address contains at least 2 bytes).
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+/// Key identifier of an IEEE 802.15.4 auxiliary security header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Ieee802154KeyIdentifier {
+    /// Key determined implicitly (key identifier mode `0b00`).
+    Implicit,
+    /// Key index only (key identifier mode `0b01`).
+    Index(u8),
+    /// 4 byte key source & key index (key identifier mode `0b10`).
+    Source4{ source: [u8;4], index: u8 },
+    /// 8 byte key source & key index (key identifier mode `0b11`).
+    Source8{ source: [u8;8], index: u8 },
+}
+
+impl Ieee802154KeyIdentifier {
+    /// Value of the key identifier mode field.
+    pub fn mode(&self) -> u8 {
+        use Ieee802154KeyIdentifier::*;
+        match self {
+            Implicit => 0,
+            Index(_) => 1,
+            Source4{ .. } => 2,
+            Source8{ .. } => 3,
+        }
+    }
+
+    /// Serialized length of the key identifier in bytes.
+    pub fn len(&self) -> usize {
+        use Ieee802154KeyIdentifier::*;
+        match self {
+            Implicit => 0,
+            Index(_) => 1,
+            Source4{ .. } => 5,
+            Source8{ .. } => 9,
+        }
+    }
+
+    /// Returns true if the key identifier has no serialized bytes ([`Ieee802154KeyIdentifier::Implicit`]).
+    pub fn is_empty(&self) -> bool {
+        0 == self.len()
+    }
+}
+
+/// IEEE 802.15.4 auxiliary security header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ieee802154SecurityHeader {
+    /// Security level (3 bits).
+    pub security_level: u8,
+    /// Set if the ASN is used in the nonce instead of the frame counter
+    /// (only defined for frame version 2).
+    pub asn_in_nonce: bool,
+    /// Frame counter (None if the frame counter is suppressed).
+    pub frame_counter: Option<u32>,
+    pub key_identifier: Ieee802154KeyIdentifier,
+}
+
+impl Ieee802154SecurityHeader {
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        1 + if self.frame_counter.is_some() { 4 } else { 0 } + self.key_identifier.len()
+    }
+
+    /// Reads an auxiliary security header from the start of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(Ieee802154SecurityHeader, &[u8]), ReadError> {
+        use Ieee802154KeyIdentifier::*;
+
+        if slice.is_empty() {
+            return Err(ReadError::UnexpectedEndOfSlice(1));
+        }
+        let control = slice[0];
+        let counter_len = if 0 != control & 0x20 { 0 } else { 4 };
+        let key_len = [0, 1, 5, 9][usize::from((control >> 3) & 0b11)];
+        let len = 1 + counter_len + key_len;
+        if slice.len() < len {
+            return Err(ReadError::UnexpectedEndOfSlice(len));
+        }
+        let frame_counter = if 0 == counter_len {
+            None
+        } else {
+            Some(u32::from_le_bytes([slice[1], slice[2], slice[3], slice[4]]))
+        };
+        let key = &slice[1 + counter_len..len];
+        let key_identifier = match key_len {
+            0 => Implicit,
+            1 => Index(key[0]),
+            5 => Source4{
+                source: [key[0], key[1], key[2], key[3]],
+                index: key[4],
+            },
+            _ => Source8{
+                source: [key[0], key[1], key[2], key[3], key[4], key[5], key[6], key[7]],
+                index: key[8],
+            },
+        };
+        Ok((
+            Ieee802154SecurityHeader{
+                security_level: control & 0b111,
+                asn_in_nonce: 0 != control & 0x40,
+                frame_counter,
+                key_identifier,
+            },
+            &slice[len..]
+        ))
+    }
+
+    /// Writes the header to the given writer.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        use Ieee802154KeyIdentifier::*;
+
+        let mut control = (self.security_level & 0b111) | (self.key_identifier.mode() << 3);
+        if self.frame_counter.is_none() {
+            control |= 0x20;
+        }
+        if self.asn_in_nonce {
+            control |= 0x40;
+        }
+        writer.write_all(&[control])?;
+        if let Some(frame_counter) = self.frame_counter {
+            writer.write_all(&frame_counter.to_le_bytes())?;
+        }
+        match &self.key_identifier {
+            Implicit => Ok(()),
+            Index(index) => writer.write_all(&[*index]),
+            Source4{ source, index } => {
+                writer.write_all(source)?;
+                writer.write_all(&[*index])
+            },
+            Source8{ source, index } => {
+                writer.write_all(source)?;
+                writer.write_all(&[*index])
+            },
+        }
+    }
+}
+
+/// IEEE 802.15.4 MAC header (frame control, sequence number, addressing
+/// fields & auxiliary security header).
+///
+/// Header information elements (present if `ie_present` is set) are not
+/// decoded & are part of the payload. Multipurpose, fragment & extended
+/// frames (which use a different frame control format) are not supported.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ieee802154Header, Ieee802154Address, Ieee802154FrameType};
+///
+/// let frame = [
+///     0x41, 0xcc, // data frame, pan id compression, extended addresses, version 0
+///     0x2a, // sequence number
+///     0x34, 0x12, // destination pan id
+///     8, 7, 6, 5, 4, 3, 2, 1, // destination address
+///     0x18, 0x17, 0x16, 0x15, 0x14, 0x13, 0x12, 0x11, // source address
+///     0x7e, 0x33, // payload (6LoWPAN)
+/// ];
+/// let (header, payload) = Ieee802154Header::from_slice(&frame).unwrap();
+/// assert_eq!(Some(Ieee802154FrameType::Data), Ieee802154FrameType::from_u8(header.frame_type));
+/// assert_eq!(Some(0x1234), header.destination_pan_id);
+/// assert_eq!(Some(0x1234), header.source_pan_id());
+/// assert_eq!(Some(Ieee802154Address::Extended([1, 2, 3, 4, 5, 6, 7, 8])), header.destination);
+/// assert_eq!(&[0x7e, 0x33], payload);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ieee802154Header {
+    /// Frame type (3 bits, see [`Ieee802154FrameType`]).
+    pub frame_type: u8,
+    pub frame_pending: bool,
+    pub ack_request: bool,
+    /// PAN ID compression flag (determines together with the frame version
+    /// & the addressing modes which PAN ids are present).
+    pub pan_id_compression: bool,
+    /// Set if header information elements follow the addressing fields.
+    pub ie_present: bool,
+    /// Frame version (2 bits, 0 = 802.15.4-2003, 1 = 802.15.4-2006, 2 = 802.15.4-2015).
+    pub frame_version: u8,
+    /// Sequence number (None if the sequence number is suppressed).
+    pub sequence_number: Option<u8>,
+    pub destination_pan_id: Option<u16>,
+    pub destination: Option<Ieee802154Address>,
+    /// Source PAN id if present in the header (see [`Ieee802154Header::source_pan_id`]
+    /// for the effective source PAN id).
+    pub source_pan_id: Option<u16>,
+    pub source: Option<Ieee802154Address>,
+    /// Auxiliary security header (present if the security enabled flag is set).
+    pub security: Option<Ieee802154SecurityHeader>,
+}
+
+impl Ieee802154Header {
+    /// Minimum length of a header (frame control only).
+    pub const MIN_LEN: usize = 2;
+
+    /// Reads a 802.15.4 MAC header from the start of the slice & returns
+    /// the header & the rest of the slice (payload & FCS if present).
+    pub fn from_slice(slice: &[u8]) -> Result<(Ieee802154Header, &[u8]), ReadError> {
+        use ReadError::*;
+
+        if slice.len() < Ieee802154Header::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(Ieee802154Header::MIN_LEN));
+        }
+        let control = u16::from_le_bytes([slice[0], slice[1]]);
+        let frame_type = (control & 0b111) as u8;
+        if frame_type > 3 {
+            return Err(Ieee802154UnsupportedFrameType(frame_type));
+        }
+        let frame_version = ((control >> 12) & 0b11) as u8;
+        let pan_id_compression = 0 != control & 0x40;
+        let destination_mode = ((control >> 10) & 0b11) as u8;
+        let source_mode = ((control >> 14) & 0b11) as u8;
+        if 0b01 == destination_mode || 0b01 == source_mode {
+            return Err(Ieee802154ReservedAddressingMode(0b01));
+        }
+
+        let (destination_pan_present, source_pan_present) = pan_ids_present(
+            frame_version,
+            destination_mode,
+            source_mode,
+            pan_id_compression
+        );
+
+        let sequence_number_present = 0 == control & 0x100;
+        let len = Ieee802154Header::MIN_LEN +
+            usize::from(sequence_number_present) +
+            if destination_pan_present { 2 } else { 0 } +
+            address_len(destination_mode) +
+            if source_pan_present { 2 } else { 0 } +
+            address_len(source_mode);
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        let mut rest = &slice[Ieee802154Header::MIN_LEN..];
+        let sequence_number = if sequence_number_present {
+            let value = rest[0];
+            rest = &rest[1..];
+            Some(value)
+        } else {
+            None
+        };
+        let destination_pan_id = read_pan_id(destination_pan_present, &mut rest);
+        let destination = read_address(destination_mode, &mut rest);
+        let source_pan_id = read_pan_id(source_pan_present, &mut rest);
+        let source = read_address(source_mode, &mut rest);
+
+        let security = if 0 != control & 0x8 {
+            let (security, after) = Ieee802154SecurityHeader::from_slice(rest)
+                .map_err(|err| err.add_slice_offset(len))?;
+            rest = after;
+            Some(security)
+        } else {
+            None
+        };
+
+        Ok((
+            Ieee802154Header{
+                frame_type,
+                frame_pending: 0 != control & 0x10,
+                ack_request: 0 != control & 0x20,
+                pan_id_compression,
+                ie_present: 0 != control & 0x200,
+                frame_version,
+                sequence_number,
+                destination_pan_id,
+                destination,
+                source_pan_id,
+                source,
+                security,
+            },
+            rest
+        ))
+    }
+
+    /// Returns the source PAN id (the destination PAN id if the source PAN
+    /// id is elided because of the PAN ID compression).
+    pub fn source_pan_id(&self) -> Option<u16> {
+        match self.source_pan_id {
+            Some(value) => Some(value),
+            None if self.source.is_some() => self.destination_pan_id,
+            None => None,
+        }
+    }
+
+    /// Value of the frame control field.
+    pub fn frame_control(&self) -> u16 {
+        let mut result = u16::from(self.frame_type & 0b111) |
+            (u16::from(self.frame_version & 0b11) << 12) |
+            (self.destination.map(|a| a.addressing_mode()).unwrap_or(0) << 10) |
+            (self.source.map(|a| a.addressing_mode()).unwrap_or(0) << 14);
+        if self.security.is_some() {
+            result |= 0x8;
+        }
+        if self.frame_pending {
+            result |= 0x10;
+        }
+        if self.ack_request {
+            result |= 0x20;
+        }
+        if self.pan_id_compression {
+            result |= 0x40;
+        }
+        if self.sequence_number.is_none() {
+            result |= 0x100;
+        }
+        if self.ie_present {
+            result |= 0x200;
+        }
+        result
+    }
+
+    /// Returns which PAN ids are present in the serialized header (destination, source).
+    fn pan_ids_present(&self) -> (bool, bool) {
+        pan_ids_present(
+            self.frame_version,
+            self.destination.map(|a| a.addressing_mode() as u8).unwrap_or(0),
+            self.source.map(|a| a.addressing_mode() as u8).unwrap_or(0),
+            self.pan_id_compression
+        )
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        let (destination_pan_present, source_pan_present) = self.pan_ids_present();
+        Ieee802154Header::MIN_LEN +
+            usize::from(self.sequence_number.is_some()) +
+            if destination_pan_present { 2 } else { 0 } +
+            self.destination.map(|a| a.len()).unwrap_or(0) +
+            if source_pan_present { 2 } else { 0 } +
+            self.source.map(|a| a.len()).unwrap_or(0) +
+            self.security.map(|s| s.header_len()).unwrap_or(0)
+    }
+
+    /// Writes the header to the given writer.
+    ///
+    /// Which PAN ids are written is determined by the frame version, the
+    /// addresses & the PAN ID compression flag. PAN ids that have to be
+    /// written but are set to None are written as the broadcast PAN id
+    /// `0xffff`.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.frame_control().to_le_bytes())?;
+        if let Some(sequence_number) = self.sequence_number {
+            writer.write_all(&[sequence_number])?;
+        }
+        let (destination_pan_present, source_pan_present) = self.pan_ids_present();
+        if destination_pan_present {
+            writer.write_all(&self.destination_pan_id.unwrap_or(0xffff).to_le_bytes())?;
+        }
+        if let Some(destination) = &self.destination {
+            write_address(destination, writer)?;
+        }
+        if source_pan_present {
+            writer.write_all(&self.source_pan_id.unwrap_or(0xffff).to_le_bytes())?;
+        }
+        if let Some(source) = &self.source {
+            write_address(source, writer)?;
+        }
+        if let Some(security) = &self.security {
+            security.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns which PAN ids are present based on the frame version, the
+/// addressing modes & the PAN ID compression flag (destination, source).
+fn pan_ids_present(frame_version: u8, destination_mode: u8, source_mode: u8, pan_id_compression: bool) -> (bool, bool) {
+    let destination = 0 != destination_mode;
+    let source = 0 != source_mode;
+    if frame_version < 2 {
+        (destination, source && !(destination && pan_id_compression))
+    } else {
+        // IEEE 802.15.4-2015 table 7-2
+        match (destination, source) {
+            (false, false) => (pan_id_compression, false),
+            (true, false) => (!pan_id_compression, false),
+            (false, true) => (false, !pan_id_compression),
+            (true, true) => {
+                if 0b11 == destination_mode && 0b11 == source_mode {
+                    (!pan_id_compression, false)
+                } else {
+                    (true, !pan_id_compression)
+                }
+            },
+        }
+    }
+}
+
+fn address_len(mode: u8) -> usize {
+    match mode {
+        0b10 => 2,
+        0b11 => 8,
+        _ => 0,
+    }
+}
+
+fn read_pan_id(present: bool, rest: &mut &[u8]) -> Option<u16> {
+    if present {
+        let value = u16::from_le_bytes([rest[0], rest[1]]);
+        *rest = &rest[2..];
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn read_address(mode: u8, rest: &mut &[u8]) -> Option<Ieee802154Address> {
+    match mode {
+        0b10 => {
+            let value = [rest[1], rest[0]];
+            *rest = &rest[2..];
+            Some(Ieee802154Address::Short(value))
+        },
+        0b11 => {
+            let mut value = [0u8;8];
+            value.copy_from_slice(&rest[..8]);
+            value.reverse();
+            *rest = &rest[8..];
+            Some(Ieee802154Address::Extended(value))
+        },
+        _ => None,
+    }
+}
+
+fn write_address<T: io::Write + Sized>(address: &Ieee802154Address, writer: &mut T) -> Result<(), io::Error> {
+    match address {
+        Ieee802154Address::Short(value) => writer.write_all(&[value[1], value[0]]),
+        Ieee802154Address::Extended(value) => {
+            let mut reversed = *value;
+            reversed.reverse();
+            writer.write_all(&reversed)
+        },
+    }
+}
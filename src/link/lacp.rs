@@ -0,0 +1,194 @@
+use super::super::*;
+
+/// Subtypes of slow protocol frames (first byte after the ethernet header
+/// of frames with the ether type [`ether_type::SLOW_PROTOCOLS`]).
+pub mod slow_protocol_subtype {
+    /// Link aggregation control protocol (IEEE 802.1AX).
+    pub const LACP: u8 = 1;
+    /// Link aggregation marker protocol (IEEE 802.1AX).
+    pub const MARKER: u8 = 2;
+    /// Ethernet OAM (IEEE 802.3 clause 57).
+    pub const OAM: u8 = 3;
+    /// Organization specific slow protocol (e.g. ESMC of ITU-T G.8264).
+    pub const ORGANIZATION_SPECIFIC: u8 = 10;
+}
+
+/// Bits of the actor & partner state fields of LACPDUs.
+pub mod lacp_state {
+    /// Set if the port is in active LACP mode (unset for passive mode).
+    pub const ACTIVITY: u8 = 0x01;
+    /// Set if the port uses the short timeout (fast LACPDU rate).
+    pub const TIMEOUT: u8 = 0x02;
+    /// Set if the link can be aggregated (unset for individual links).
+    pub const AGGREGATION: u8 = 0x04;
+    pub const SYNCHRONIZATION: u8 = 0x08;
+    pub const COLLECTING: u8 = 0x10;
+    pub const DISTRIBUTING: u8 = 0x20;
+    /// Set if the partner information is the administratively configured default.
+    pub const DEFAULTED: u8 = 0x40;
+    /// Set if the receive state machine is in the expired state.
+    pub const EXPIRED: u8 = 0x80;
+}
+
+/// Actor or partner information contained in a LACPDU.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct LacpPortInfo {
+    pub system_priority: u16,
+    /// MAC address identifying the system.
+    pub system: MacAddr,
+    /// Operational key (ports with the same key can be aggregated).
+    pub key: u16,
+    pub port_priority: u16,
+    pub port: u16,
+    /// State bits (see [`lacp_state`]).
+    pub state: u8,
+}
+
+impl LacpPortInfo {
+    fn from_bytes(bytes: &[u8]) -> LacpPortInfo {
+        let mut system = [0u8;6];
+        system.copy_from_slice(&bytes[2..8]);
+        LacpPortInfo{
+            system_priority: u16::from_be_bytes([bytes[0], bytes[1]]),
+            system: MacAddr(system),
+            key: u16::from_be_bytes([bytes[8], bytes[9]]),
+            port_priority: u16::from_be_bytes([bytes[10], bytes[11]]),
+            port: u16::from_be_bytes([bytes[12], bytes[13]]),
+            state: bytes[14],
+        }
+    }
+
+    /// Returns true if the activity bit is set (active LACP mode).
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        0 != self.state & lacp_state::ACTIVITY
+    }
+
+    /// Returns true if the timeout bit is set (short timeout).
+    #[inline]
+    pub fn is_short_timeout(&self) -> bool {
+        0 != self.state & lacp_state::TIMEOUT
+    }
+
+    /// Returns true if the aggregation bit is set.
+    #[inline]
+    pub fn is_aggregatable(&self) -> bool {
+        0 != self.state & lacp_state::AGGREGATION
+    }
+
+    /// Returns true if the synchronization bit is set.
+    #[inline]
+    pub fn is_in_sync(&self) -> bool {
+        0 != self.state & lacp_state::SYNCHRONIZATION
+    }
+
+    /// Returns true if the collecting bit is set.
+    #[inline]
+    pub fn is_collecting(&self) -> bool {
+        0 != self.state & lacp_state::COLLECTING
+    }
+
+    /// Returns true if the distributing bit is set.
+    #[inline]
+    pub fn is_distributing(&self) -> bool {
+        0 != self.state & lacp_state::DISTRIBUTING
+    }
+
+    /// Returns true if the defaulted bit is set.
+    #[inline]
+    pub fn is_defaulted(&self) -> bool {
+        0 != self.state & lacp_state::DEFAULTED
+    }
+
+    /// Returns true if the expired bit is set.
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        0 != self.state & lacp_state::EXPIRED
+    }
+}
+
+/// A slice containing a LACPDU (payload of ethernet frames with the ether
+/// type [`ether_type::SLOW_PROTOCOLS`] & the subtype
+/// [`slow_protocol_subtype::LACP`]).
+///
+/// The fields are decoded based on the fixed layout of version 1 LACPDUs
+/// (the actor, partner, collector & terminator TLVs). Additional TLVs of
+/// version 2 LACPDUs are not decoded.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{LacpduSlice, MacAddr};
+///
+/// let mut lacpdu = [0u8;110];
+/// lacpdu[..4].copy_from_slice(&[1, 1, 1, 20]); // subtype, version & actor tlv
+/// lacpdu[6..12].copy_from_slice(&[2, 0, 0, 0, 0, 1]); // actor system
+/// lacpdu[12..14].copy_from_slice(&[0, 42]); // actor key
+/// lacpdu[18] = 0x3d; // actor state
+///
+/// let slice = LacpduSlice::from_slice(&lacpdu).unwrap();
+/// let actor = slice.actor();
+/// assert_eq!(MacAddr([2, 0, 0, 0, 0, 1]), actor.system);
+/// assert_eq!(42, actor.key);
+/// assert!(actor.is_active() && actor.is_in_sync() && actor.is_distributing());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LacpduSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> LacpduSlice<'a> {
+    /// Length of a LACPDU (including the subtype & the reserved bytes at
+    /// the end).
+    pub const LEN: usize = 110;
+
+    /// Creates a LACPDU slice from an other slice (data after the LACPDU,
+    /// e.g. the FCS, is not part of the resulting slice).
+    ///
+    /// Returns a [`ReadError::LacpUnexpectedSubtype`] error if the slow
+    /// protocol subtype is not [`slow_protocol_subtype::LACP`].
+    pub fn from_slice(slice: &'a [u8]) -> Result<LacpduSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < LacpduSlice::LEN {
+            return Err(UnexpectedEndOfSlice(LacpduSlice::LEN));
+        }
+        if slow_protocol_subtype::LACP != slice[0] {
+            return Err(LacpUnexpectedSubtype(slice[0]));
+        }
+        Ok(LacpduSlice{
+            slice: &slice[..LacpduSlice::LEN]
+        })
+    }
+
+    /// Returns the slice containing the LACPDU.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version number of the LACPDU.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the actor information (information about the sending port).
+    #[inline]
+    pub fn actor(&self) -> LacpPortInfo {
+        LacpPortInfo::from_bytes(&self.slice[4..19])
+    }
+
+    /// Read the partner information (the senders view of the port at the
+    /// other end of the link).
+    #[inline]
+    pub fn partner(&self) -> LacpPortInfo {
+        LacpPortInfo::from_bytes(&self.slice[24..39])
+    }
+
+    /// Read the collector max delay in tens of microseconds.
+    #[inline]
+    pub fn collector_max_delay(&self) -> u16 {
+        u16::from_be_bytes([self.slice[44], self.slice[45]])
+    }
+}
@@ -0,0 +1,50 @@
+/// Link layer header types (`LINKTYPE_*`/`DLT_*` values used in pcap & pcapng files)
+/// supported by [`crate::SlicedPacket::from_link_type`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum LinkType {
+    /// BSD loopback encapsulation (4 byte protocol family in host byte order, `LINKTYPE_NULL`).
+    Null = 0,
+    /// Ethernet II (`LINKTYPE_ETHERNET`, `DLT_EN10MB`).
+    Ethernet = 1,
+    /// PPP (`LINKTYPE_PPP`).
+    Ppp = 9,
+    /// Raw IPv4 or IPv6 packets (`LINKTYPE_RAW`).
+    Raw = 101,
+    /// OpenBSD loopback encapsulation (4 byte protocol family in network byte order, `LINKTYPE_LOOP`).
+    Loop = 108,
+    /// IEEE 802.11 frames without a radiotap header (`LINKTYPE_IEEE802_11`).
+    Ieee80211 = 105,
+    /// Linux "cooked" capture encapsulation (`LINKTYPE_LINUX_SLL`).
+    LinuxSll = 113,
+    /// IEEE 802.11 frames preceded by a radiotap header (`LINKTYPE_IEEE802_11_RADIOTAP`).
+    Ieee80211Radiotap = 127,
+    /// IEEE 802.15.4 frames including the 2 byte FCS (`LINKTYPE_IEEE802_15_4_WITHFCS`).
+    Ieee802154 = 195,
+    /// IEEE 802.15.4 frames without the FCS (`LINKTYPE_IEEE802_15_4_NOFCS`).
+    Ieee802154NoFcs = 230,
+    /// Linux "cooked" capture encapsulation v2 (`LINKTYPE_LINUX_SLL2`).
+    LinuxSll2 = 276,
+}
+
+impl LinkType {
+    ///Tries to convert a raw link type value to the enum. Returns None if the value does not exist in the enum.
+    ///
+    ///Besides the `LINKTYPE_*` values the platform specific `DLT_RAW` values 12 & 14 are also accepted.
+    pub fn from_u32(value: u32) -> Option<LinkType> {
+        use self::LinkType::*;
+        match value {
+            0 => Some(Null),
+            1 => Some(Ethernet),
+            9 => Some(Ppp),
+            12 | 14 | 101 => Some(Raw),
+            105 => Some(Ieee80211),
+            108 => Some(Loop),
+            113 => Some(LinuxSll),
+            127 => Some(Ieee80211Radiotap),
+            195 => Some(Ieee802154),
+            230 => Some(Ieee802154NoFcs),
+            276 => Some(LinuxSll2),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use super::super::*;
+
+use std::io;
+
+/// Values of the "packet type" field in Linux cooked capture headers
+/// (`PACKET_*` values in `linux/if_packet.h`).
+pub mod linux_sll_packet_type {
+    /// Packet addressed to the capturing host.
+    pub const HOST: u8 = 0;
+    /// Broadcast packet sent by another host.
+    pub const BROADCAST: u8 = 1;
+    /// Multicast packet sent by another host.
+    pub const MULTICAST: u8 = 2;
+    /// Packet sent by another host to another host.
+    pub const OTHERHOST: u8 = 3;
+    /// Packet sent by the capturing host.
+    pub const OUTGOING: u8 = 4;
+}
+
+/// Values of the "ARPHRD type" field in Linux cooked capture headers
+/// (`ARPHRD_*` values in `linux/if_arp.h`) that affect the meaning of the
+/// protocol type field.
+pub mod arphrd_type {
+    /// Ethernet interface.
+    pub const ETHER: u16 = 1;
+    /// Loopback interface.
+    pub const LOOPBACK: u16 = 772;
+    /// GRE over IP interface (protocol type contains the GRE protocol type).
+    pub const IPGRE: u16 = 778;
+    /// Netlink messages (protocol type contains the netlink protocol & not an ether type).
+    pub const NETLINK: u16 = 824;
+}
+
+/// Returns the ether type contained in the protocol type field of a Linux
+/// cooked capture header (`None` for netlink messages & 802.3 frames
+/// without a LLC header).
+pub(crate) fn cooked_ether_type(arphrd_type: u16, protocol_type: u16) -> Option<u16> {
+    // ETH_P_802_3 indicates 802.3 frames without a LLC header (e.g. Novell IPX)
+    if arphrd_type::NETLINK == arphrd_type || 0x0001 == protocol_type {
+        None
+    } else {
+        Some(protocol_type)
+    }
+}
+
+/// Linux "cooked" capture header (`LINKTYPE_LINUX_SLL`) as used by
+/// `tcpdump -i any` on older versions of libpcap.
+///
+/// For most interfaces the `protocol_type` contains the ether type of the
+/// packet after the header.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct LinuxSllHeader {
+    /// Direction of the packet (see [`linux_sll_packet_type`]).
+    pub packet_type: u16,
+    /// Link layer device type (see [`arphrd_type`]).
+    pub arphrd_type: u16,
+    /// Length of the link layer address of the sender.
+    pub sender_address_len: u16,
+    /// Link layer address of the sender (first 8 bytes).
+    pub sender_address: [u8;8],
+    /// Protocol type of the payload (usually an ether type).
+    pub protocol_type: u16,
+}
+
+impl SerializedSize for LinuxSllHeader {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 16;
+}
+
+impl LinuxSllHeader {
+
+    /// Read a LinuxSllHeader from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(LinuxSllHeader, &[u8]), ReadError> {
+        Ok((
+            LinuxSllHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[LinuxSllHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a LinuxSllHeader from a static sized byte array.
+    pub fn from_bytes(bytes: [u8;16]) -> LinuxSllHeader {
+        LinuxSllHeader{
+            packet_type: u16::from_be_bytes([bytes[0], bytes[1]]),
+            arphrd_type: u16::from_be_bytes([bytes[2], bytes[3]]),
+            sender_address_len: u16::from_be_bytes([bytes[4], bytes[5]]),
+            sender_address: [
+                bytes[6], bytes[7], bytes[8], bytes[9],
+                bytes[10], bytes[11], bytes[12], bytes[13],
+            ],
+            protocol_type: u16::from_be_bytes([bytes[14], bytes[15]]),
+        }
+    }
+
+    /// Reads a Linux cooked capture header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<LinuxSllHeader, io::Error> {
+        let mut buffer = [0;LinuxSllHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(LinuxSllHeader::from_bytes(buffer))
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        LinuxSllHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    pub fn to_bytes(&self) -> [u8;16] {
+        let packet_type = self.packet_type.to_be_bytes();
+        let arphrd_type = self.arphrd_type.to_be_bytes();
+        let sender_address_len = self.sender_address_len.to_be_bytes();
+        let protocol_type = self.protocol_type.to_be_bytes();
+        [
+            packet_type[0], packet_type[1],
+            arphrd_type[0], arphrd_type[1],
+            sender_address_len[0], sender_address_len[1],
+            self.sender_address[0], self.sender_address[1],
+            self.sender_address[2], self.sender_address[3],
+            self.sender_address[4], self.sender_address[5],
+            self.sender_address[6], self.sender_address[7],
+            protocol_type[0], protocol_type[1],
+        ]
+    }
+}
+
+/// A slice containing a Linux "cooked" capture header (`LINKTYPE_LINUX_SLL`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinuxSllHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> LinuxSllHeaderSlice<'a> {
+
+    /// Creates a Linux cooked capture header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LinuxSllHeaderSlice<'a>, ReadError> {
+        if slice.len() < LinuxSllHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(LinuxSllHeader::SERIALIZED_SIZE));
+        }
+        Ok(LinuxSllHeaderSlice {
+            slice: &slice[..LinuxSllHeader::SERIALIZED_SIZE]
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "packet type" field (see [`linux_sll_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the "ARPHRD type" field (see [`arphrd_type`]).
+    #[inline]
+    pub fn arphrd_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "link layer address length" field.
+    #[inline]
+    pub fn sender_address_len(&self) -> u16 {
+        u16::from_be_bytes([self.slice[4], self.slice[5]])
+    }
+
+    /// Read the "link layer address" field (always 8 bytes, only the
+    /// first `sender_address_len` bytes are used).
+    #[inline]
+    pub fn sender_address(&self) -> &'a [u8] {
+        &self.slice[6..14]
+    }
+
+    /// Read the "protocol type" field.
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[14], self.slice[15]])
+    }
+
+    /// Decode all the fields and copy the results to a LinuxSllHeader struct.
+    pub fn to_header(&self) -> LinuxSllHeader {
+        let mut bytes = [0;16];
+        bytes.copy_from_slice(self.slice);
+        LinuxSllHeader::from_bytes(bytes)
+    }
+}
+
+/// Linux "cooked" capture v2 header (`LINKTYPE_LINUX_SLL2`) as used by
+/// `tcpdump -i any` on newer versions of libpcap.
+///
+/// Compared to [`LinuxSllHeader`] the protocol type is moved to the start
+/// of the header and the index of the capturing interface is added.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct LinuxSll2Header {
+    /// Protocol type of the payload (usually an ether type).
+    pub protocol_type: u16,
+    /// Index of the interface the packet was captured on.
+    pub interface_index: u32,
+    /// Link layer device type (see [`arphrd_type`]).
+    pub arphrd_type: u16,
+    /// Direction of the packet (see [`linux_sll_packet_type`]).
+    pub packet_type: u8,
+    /// Length of the link layer address of the sender.
+    pub sender_address_len: u8,
+    /// Link layer address of the sender (first 8 bytes).
+    pub sender_address: [u8;8],
+}
+
+impl SerializedSize for LinuxSll2Header {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 20;
+}
+
+impl LinuxSll2Header {
+
+    /// Read a LinuxSll2Header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(LinuxSll2Header, &[u8]), ReadError> {
+        Ok((
+            LinuxSll2HeaderSlice::from_slice(slice)?.to_header(),
+            &slice[LinuxSll2Header::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a LinuxSll2Header from a static sized byte array (the
+    /// reserved field is ignored).
+    pub fn from_bytes(bytes: [u8;20]) -> LinuxSll2Header {
+        LinuxSll2Header{
+            protocol_type: u16::from_be_bytes([bytes[0], bytes[1]]),
+            interface_index: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            arphrd_type: u16::from_be_bytes([bytes[8], bytes[9]]),
+            packet_type: bytes[10],
+            sender_address_len: bytes[11],
+            sender_address: [
+                bytes[12], bytes[13], bytes[14], bytes[15],
+                bytes[16], bytes[17], bytes[18], bytes[19],
+            ],
+        }
+    }
+
+    /// Reads a Linux cooked capture v2 header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<LinuxSll2Header, io::Error> {
+        let mut buffer = [0;LinuxSll2Header::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(LinuxSll2Header::from_bytes(buffer))
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        LinuxSll2Header::SERIALIZED_SIZE
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array (the reserved field is set to zero).
+    pub fn to_bytes(&self) -> [u8;20] {
+        let protocol_type = self.protocol_type.to_be_bytes();
+        let interface_index = self.interface_index.to_be_bytes();
+        let arphrd_type = self.arphrd_type.to_be_bytes();
+        [
+            protocol_type[0], protocol_type[1],
+            0, 0,
+            interface_index[0], interface_index[1],
+            interface_index[2], interface_index[3],
+            arphrd_type[0], arphrd_type[1],
+            self.packet_type,
+            self.sender_address_len,
+            self.sender_address[0], self.sender_address[1],
+            self.sender_address[2], self.sender_address[3],
+            self.sender_address[4], self.sender_address[5],
+            self.sender_address[6], self.sender_address[7],
+        ]
+    }
+}
+
+/// A slice containing a Linux "cooked" capture v2 header (`LINKTYPE_LINUX_SLL2`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinuxSll2HeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> LinuxSll2HeaderSlice<'a> {
+
+    /// Creates a Linux cooked capture v2 header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LinuxSll2HeaderSlice<'a>, ReadError> {
+        if slice.len() < LinuxSll2Header::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(LinuxSll2Header::SERIALIZED_SIZE));
+        }
+        Ok(LinuxSll2HeaderSlice {
+            slice: &slice[..LinuxSll2Header::SERIALIZED_SIZE]
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "protocol type" field.
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the "interface index" field.
+    #[inline]
+    pub fn interface_index(&self) -> u32 {
+        u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Read the "ARPHRD type" field (see [`arphrd_type`]).
+    #[inline]
+    pub fn arphrd_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[8], self.slice[9]])
+    }
+
+    /// Read the "packet type" field (see [`linux_sll_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        self.slice[10]
+    }
+
+    /// Read the "link layer address length" field.
+    #[inline]
+    pub fn sender_address_len(&self) -> u8 {
+        self.slice[11]
+    }
+
+    /// Read the "link layer address" field (always 8 bytes, only the
+    /// first `sender_address_len` bytes are used).
+    #[inline]
+    pub fn sender_address(&self) -> &'a [u8] {
+        &self.slice[12..20]
+    }
+
+    /// Decode all the fields and copy the results to a LinuxSll2Header struct.
+    pub fn to_header(&self) -> LinuxSll2Header {
+        let mut bytes = [0;20];
+        bytes.copy_from_slice(self.slice);
+        LinuxSll2Header::from_bytes(bytes)
+    }
+}
@@ -0,0 +1,251 @@
+use super::super::*;
+
+use std::io;
+
+/// Well known IEEE 802.2 LLC service access point (SAP) values used in the
+/// DSAP & SSAP fields of a LLC header.
+pub mod llc_sap {
+    /// Null SAP.
+    pub const NULL: u8 = 0x00;
+    /// Spanning tree protocol (IEEE 802.1D).
+    pub const STP: u8 = 0x42;
+    /// Subnetwork access protocol (SNAP header follows the LLC header).
+    pub const SNAP: u8 = 0xaa;
+    /// Novell IPX.
+    pub const IPX: u8 = 0xe0;
+    /// NetBIOS.
+    pub const NETBIOS: u8 = 0xf0;
+    /// Global SAP (only valid as DSAP).
+    pub const GLOBAL: u8 = 0xff;
+}
+
+/// SNAP organizationally unique identifier (OUI) values for which the SNAP
+/// protocol id contains an ether type.
+pub mod snap_oui {
+    /// RFC 1042 encapsulation of ether type based protocols.
+    pub const RFC1042: [u8;3] = [0x00, 0x00, 0x00];
+    /// IEEE 802.1H bridge tunnel encapsulation.
+    pub const BRIDGE_TUNNEL: [u8;3] = [0x00, 0x00, 0xf8];
+}
+
+/// Control field value of an unnumbered information (UI) frame.
+const CONTROL_UI: u16 = 0x03;
+
+/// SNAP header (organizationally unique identifier & protocol id) following
+/// a LLC header with the DSAP & SSAP [`llc_sap::SNAP`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub struct SnapHeader {
+    /// Organizationally unique identifier (see [`snap_oui`]).
+    pub oui: [u8;3],
+    /// Protocol id (an ether type if the OUI is [`snap_oui::RFC1042`] or
+    /// [`snap_oui::BRIDGE_TUNNEL`]).
+    pub protocol_id: u16,
+}
+
+impl SnapHeader {
+    /// Serialized length of a SNAP header in bytes.
+    pub const LEN: usize = 5;
+
+    /// Returns the ether type of the payload if the OUI indicates that the
+    /// protocol id contains an ether type.
+    pub fn ether_type(&self) -> Option<u16> {
+        if snap_oui::RFC1042 == self.oui || snap_oui::BRIDGE_TUNNEL == self.oui {
+            Some(self.protocol_id)
+        } else {
+            None
+        }
+    }
+}
+
+/// IEEE 802.2 LLC header including the SNAP header (if present).
+///
+/// A LLC header follows an ethernet header if the ether type field contains
+/// a length (values smaller then [`LlcHeader::ETHER_TYPE_MIN`]) and an 802.11
+/// data frame header. A SNAP header is present if the DSAP & SSAP are set to
+/// [`llc_sap::SNAP`] and the frame is an unnumbered information frame
+/// (control field `0x03`).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{LlcHeader, ether_type};
+///
+/// let bytes = [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00, 1, 2];
+/// let (header, rest) = LlcHeader::from_slice(&bytes).unwrap();
+/// assert_eq!(0xaa, header.dsap);
+/// assert_eq!(Some(ether_type::IPV4), header.ether_type());
+/// assert_eq!(&[1, 2], rest);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct LlcHeader {
+    /// Destination service access point (see [`llc_sap`]).
+    pub dsap: u8,
+    /// Source service access point (see [`llc_sap`], the lowest bit
+    /// indicates a response).
+    pub ssap: u8,
+    /// Control field. U-format frames (lowest 2 bits set) have a 1 byte
+    /// control field, I- & S-format frames a 2 byte control field (the
+    /// first byte is stored in the lower 8 bits).
+    pub control: u16,
+    /// SNAP header (present if the DSAP & SSAP are [`llc_sap::SNAP`] in an
+    /// unnumbered information frame).
+    pub snap: Option<SnapHeader>,
+}
+
+impl LlcHeader {
+    /// Smallest value of an ethernet "ether type" field that is interpreted
+    /// as an ether type. Smaller values are the length of an IEEE 802.3 frame
+    /// whose payload starts with a LLC header.
+    pub const ETHER_TYPE_MIN: u16 = 0x0600;
+
+    /// Minimum length of a LLC header (U-format frame without a SNAP header).
+    pub const MIN_LEN: usize = 3;
+
+    /// Maximum length of a LLC header (U-format frame with a SNAP header).
+    pub const MAX_LEN: usize = 8;
+
+    /// Read a LLC header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(LlcHeader, &[u8]), ReadError> {
+        let slice_header = LlcHeaderSlice::from_slice(slice)?;
+        Ok((
+            slice_header.to_header(),
+            &slice[slice_header.slice().len()..]
+        ))
+    }
+
+    /// Returns the ether type of the payload if a SNAP header containing
+    /// an ether type is present.
+    #[inline]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.snap.and_then(|snap| snap.ether_type())
+    }
+
+    /// Returns true if the header is followed by a spanning tree BPDU
+    /// (DSAP & SSAP [`llc_sap::STP`] in an unnumbered information frame).
+    #[inline]
+    pub fn is_stp(&self) -> bool {
+        llc_sap::STP == self.dsap && llc_sap::STP == self.ssap && CONTROL_UI == self.control
+    }
+
+    /// Length of the control field (1 for U-format frames, 2 otherwise).
+    #[inline]
+    fn control_len(&self) -> usize {
+        control_len(self.control as u8)
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        2 + self.control_len() + if self.snap.is_some() { SnapHeader::LEN } else { 0 }
+    }
+
+    /// Writes the header to the current position of the write argument.
+    ///
+    /// Note that the SNAP header is written if it is set, independent of
+    /// the DSAP, SSAP & control values.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&[self.dsap, self.ssap])?;
+        writer.write_all(&self.control.to_le_bytes()[..self.control_len()])?;
+        if let Some(snap) = &self.snap {
+            writer.write_all(&snap.oui)?;
+            writer.write_all(&snap.protocol_id.to_be_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Length of the control field based on its first byte.
+#[inline]
+fn control_len(first: u8) -> usize {
+    if 0b11 == first & 0b11 {
+        1
+    } else {
+        2
+    }
+}
+
+/// A slice containing an IEEE 802.2 LLC header (including the SNAP
+/// header if present).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LlcHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> LlcHeaderSlice<'a> {
+
+    /// Creates a LLC header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LlcHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < LlcHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(LlcHeader::MIN_LEN));
+        }
+        let control_len = control_len(slice[2]);
+        let snap = llc_sap::SNAP == slice[0] &&
+            llc_sap::SNAP == slice[1] &&
+            CONTROL_UI == u16::from(slice[2]);
+        let len = 2 + control_len + if snap { SnapHeader::LEN } else { 0 };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(LlcHeaderSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the LLC header (including the SNAP header).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the destination service access point.
+    #[inline]
+    pub fn dsap(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the source service access point.
+    #[inline]
+    pub fn ssap(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the control field (1 or 2 bytes, see [`LlcHeader::control`]).
+    #[inline]
+    pub fn control(&self) -> u16 {
+        if 1 == control_len(self.slice[2]) {
+            u16::from(self.slice[2])
+        } else {
+            u16::from_le_bytes([self.slice[2], self.slice[3]])
+        }
+    }
+
+    /// Read the SNAP header (if present).
+    pub fn snap(&self) -> Option<SnapHeader> {
+        if self.slice.len() == LlcHeader::MAX_LEN {
+            Some(SnapHeader{
+                oui: [self.slice[3], self.slice[4], self.slice[5]],
+                protocol_id: u16::from_be_bytes([self.slice[6], self.slice[7]]),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ether type of the payload if a SNAP header containing
+    /// an ether type is present.
+    #[inline]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.snap().and_then(|snap| snap.ether_type())
+    }
+
+    /// Decode all the fields and copy the results to a LlcHeader struct.
+    pub fn to_header(&self) -> LlcHeader {
+        LlcHeader{
+            dsap: self.dsap(),
+            ssap: self.ssap(),
+            control: self.control(),
+            snap: self.snap(),
+        }
+    }
+}
@@ -0,0 +1,459 @@
+use super::super::*;
+
+/// Type values of LLDP TLVs (IEEE 802.1AB).
+pub mod lldp_tlv_type {
+    pub const END: u8 = 0;
+    pub const CHASSIS_ID: u8 = 1;
+    pub const PORT_ID: u8 = 2;
+    pub const TTL: u8 = 3;
+    pub const PORT_DESCRIPTION: u8 = 4;
+    pub const SYSTEM_NAME: u8 = 5;
+    pub const SYSTEM_DESCRIPTION: u8 = 6;
+    pub const SYSTEM_CAPABILITIES: u8 = 7;
+    pub const MANAGEMENT_ADDRESS: u8 = 8;
+    pub const ORGANIZATIONALLY_SPECIFIC: u8 = 127;
+}
+
+/// Subtype values of the LLDP chassis ID TLV.
+pub mod lldp_chassis_id_subtype {
+    pub const CHASSIS_COMPONENT: u8 = 1;
+    pub const INTERFACE_ALIAS: u8 = 2;
+    pub const PORT_COMPONENT: u8 = 3;
+    pub const MAC_ADDRESS: u8 = 4;
+    pub const NETWORK_ADDRESS: u8 = 5;
+    pub const INTERFACE_NAME: u8 = 6;
+    pub const LOCALLY_ASSIGNED: u8 = 7;
+}
+
+/// Subtype values of the LLDP port ID TLV.
+pub mod lldp_port_id_subtype {
+    pub const INTERFACE_ALIAS: u8 = 1;
+    pub const PORT_COMPONENT: u8 = 2;
+    pub const MAC_ADDRESS: u8 = 3;
+    pub const NETWORK_ADDRESS: u8 = 4;
+    pub const INTERFACE_NAME: u8 = 5;
+    pub const AGENT_CIRCUIT_ID: u8 = 6;
+    pub const LOCALLY_ASSIGNED: u8 = 7;
+}
+
+/// Errors that can occur when reading LLDP data units.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LldpError {
+    /// The data ended before the end of a TLV header or value (expected
+    /// minimum size as argument).
+    UnexpectedEnd(usize),
+    /// The length of a TLV is not valid for the TLV type.
+    InvalidLength{ tlv_type: u8, length: u16 },
+    /// A mandatory TLV (chassis ID, port ID & TTL at the start of the data
+    /// unit) is missing (type of the expected TLV as argument).
+    MissingMandatoryTlv(u8),
+}
+
+impl fmt::Display for LldpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use LldpError::*;
+        match self {
+            UnexpectedEnd(len) => write!(f, "LldpError: Unexpected end of data. Expected at least {} bytes.", len),
+            InvalidLength{ tlv_type, length } => write!(f, "LldpError: Length {} is not valid for TLVs of type {}.", length, tlv_type),
+            MissingMandatoryTlv(tlv_type) => write!(f, "LldpError: Mandatory TLV of type {} is missing.", tlv_type),
+        }
+    }
+}
+
+impl Error for LldpError {}
+
+/// Value of a LLDP chassis ID TLV.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LldpChassisId<'a> {
+    /// Type of the id (see [`lldp_chassis_id_subtype`]).
+    pub subtype: u8,
+    pub id: &'a [u8],
+}
+
+impl<'a> LldpChassisId<'a> {
+    /// Returns the MAC address if the id is a MAC address.
+    pub fn mac_address(&self) -> Option<[u8;6]> {
+        mac_address(self.subtype == lldp_chassis_id_subtype::MAC_ADDRESS, self.id)
+    }
+}
+
+/// Value of a LLDP port ID TLV.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LldpPortId<'a> {
+    /// Type of the id (see [`lldp_port_id_subtype`]).
+    pub subtype: u8,
+    pub id: &'a [u8],
+}
+
+impl<'a> LldpPortId<'a> {
+    /// Returns the MAC address if the id is a MAC address.
+    pub fn mac_address(&self) -> Option<[u8;6]> {
+        mac_address(self.subtype == lldp_port_id_subtype::MAC_ADDRESS, self.id)
+    }
+}
+
+fn mac_address(is_mac: bool, id: &[u8]) -> Option<[u8;6]> {
+    if is_mac && 6 == id.len() {
+        let mut result = [0u8;6];
+        result.copy_from_slice(id);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Value of a LLDP management address TLV.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LldpManagementAddress<'a> {
+    /// IANA address family number of the address (e.g. 1 for IPv4 & 2 for IPv6).
+    pub address_subtype: u8,
+    pub address: &'a [u8],
+    /// Numbering method of the interface number (1 unknown, 2 ifIndex,
+    /// 3 system port number).
+    pub interface_numbering_subtype: u8,
+    pub interface_number: u32,
+    /// Object identifier of the hardware component or protocol entity
+    /// associated with the address (BER encoded, can be empty).
+    pub oid: &'a [u8],
+}
+
+impl<'a> LldpManagementAddress<'a> {
+    /// Returns the address if it is an IPv4 address.
+    pub fn ipv4(&self) -> Option<[u8;4]> {
+        if 1 == self.address_subtype && 4 == self.address.len() {
+            let mut result = [0u8;4];
+            result.copy_from_slice(self.address);
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address if it is an IPv6 address.
+    pub fn ipv6(&self) -> Option<[u8;16]> {
+        if 2 == self.address_subtype && 16 == self.address.len() {
+            let mut result = [0u8;16];
+            result.copy_from_slice(self.address);
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    fn from_value(value: &'a [u8]) -> Option<LldpManagementAddress<'a>> {
+        // address string length (includes the subtype)
+        let address_len = usize::from(*value.first()?);
+        if !(2..=32).contains(&address_len) {
+            return None;
+        }
+        let interface_start = 1 + address_len;
+        let oid_start = interface_start + 6;
+        if value.len() < oid_start {
+            return None;
+        }
+        let oid_len = usize::from(value[oid_start - 1]);
+        if oid_len > 128 || value.len() != oid_start + oid_len {
+            return None;
+        }
+        Some(LldpManagementAddress{
+            address_subtype: value[1],
+            address: &value[2..interface_start],
+            interface_numbering_subtype: value[interface_start],
+            interface_number: u32::from_be_bytes([
+                value[interface_start + 1],
+                value[interface_start + 2],
+                value[interface_start + 3],
+                value[interface_start + 4],
+            ]),
+            oid: &value[oid_start..],
+        })
+    }
+}
+
+/// Decoded LLDP TLV.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LldpTlv<'a> {
+    /// End of LLDPDU TLV.
+    End,
+    ChassisId(LldpChassisId<'a>),
+    PortId(LldpPortId<'a>),
+    /// Time in seconds the information is valid (0 indicates that the
+    /// information should be removed).
+    Ttl(u16),
+    PortDescription(&'a [u8]),
+    SystemName(&'a [u8]),
+    SystemDescription(&'a [u8]),
+    SystemCapabilities{ capabilities: u16, enabled: u16 },
+    ManagementAddress(LldpManagementAddress<'a>),
+    OrganizationallySpecific{ oui: [u8;3], subtype: u8, info: &'a [u8] },
+    /// TLV with a reserved type.
+    Unknown{ tlv_type: u8, value: &'a [u8] },
+}
+
+impl<'a> LldpTlv<'a> {
+    /// Length of the TLV header (7 bit type & 9 bit length).
+    pub const HEADER_LEN: usize = 2;
+
+    /// Reads a TLV from the start of the slice & returns the TLV & the rest
+    /// of the slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<(LldpTlv<'a>, &'a [u8]), LldpError> {
+        use lldp_tlv_type::*;
+        use LldpError::*;
+        use LldpTlv::*;
+
+        if slice.len() < LldpTlv::HEADER_LEN {
+            return Err(UnexpectedEnd(LldpTlv::HEADER_LEN));
+        }
+        let tlv_type = slice[0] >> 1;
+        let length = u16::from_be_bytes([slice[0] & 1, slice[1]]);
+        let end = LldpTlv::HEADER_LEN + usize::from(length);
+        if slice.len() < end {
+            return Err(UnexpectedEnd(end));
+        }
+        let value = &slice[LldpTlv::HEADER_LEN..end];
+        let invalid_length = InvalidLength{ tlv_type, length };
+
+        let tlv = match tlv_type {
+            END => {
+                if 0 != length {
+                    return Err(invalid_length);
+                }
+                End
+            },
+            CHASSIS_ID | PORT_ID => {
+                if !(2..=256).contains(&length) {
+                    return Err(invalid_length);
+                }
+                if CHASSIS_ID == tlv_type {
+                    ChassisId(LldpChassisId{ subtype: value[0], id: &value[1..] })
+                } else {
+                    PortId(LldpPortId{ subtype: value[0], id: &value[1..] })
+                }
+            },
+            TTL => {
+                if 2 != length {
+                    return Err(invalid_length);
+                }
+                Ttl(u16::from_be_bytes([value[0], value[1]]))
+            },
+            PORT_DESCRIPTION | SYSTEM_NAME | SYSTEM_DESCRIPTION => {
+                if length > 255 {
+                    return Err(invalid_length);
+                }
+                match tlv_type {
+                    PORT_DESCRIPTION => PortDescription(value),
+                    SYSTEM_NAME => SystemName(value),
+                    _ => SystemDescription(value),
+                }
+            },
+            SYSTEM_CAPABILITIES => {
+                if 4 != length {
+                    return Err(invalid_length);
+                }
+                SystemCapabilities{
+                    capabilities: u16::from_be_bytes([value[0], value[1]]),
+                    enabled: u16::from_be_bytes([value[2], value[3]]),
+                }
+            },
+            MANAGEMENT_ADDRESS => ManagementAddress(
+                LldpManagementAddress::from_value(value).ok_or(invalid_length)?
+            ),
+            ORGANIZATIONALLY_SPECIFIC => {
+                if length < 4 {
+                    return Err(invalid_length);
+                }
+                OrganizationallySpecific{
+                    oui: [value[0], value[1], value[2]],
+                    subtype: value[3],
+                    info: &value[4..],
+                }
+            },
+            _ => Unknown{ tlv_type, value },
+        };
+        Ok((tlv, &slice[end..]))
+    }
+
+    /// Returns the type of the TLV (see [`lldp_tlv_type`]).
+    pub fn tlv_type(&self) -> u8 {
+        use lldp_tlv_type::*;
+        use LldpTlv::*;
+        match self {
+            End => END,
+            ChassisId(_) => CHASSIS_ID,
+            PortId(_) => PORT_ID,
+            Ttl(_) => TTL,
+            PortDescription(_) => PORT_DESCRIPTION,
+            SystemName(_) => SYSTEM_NAME,
+            SystemDescription(_) => SYSTEM_DESCRIPTION,
+            SystemCapabilities{ .. } => SYSTEM_CAPABILITIES,
+            ManagementAddress(_) => MANAGEMENT_ADDRESS,
+            OrganizationallySpecific{ .. } => ORGANIZATIONALLY_SPECIFIC,
+            Unknown{ tlv_type, .. } => *tlv_type,
+        }
+    }
+}
+
+/// A slice containing a LLDP data unit (payload of ethernet frames with
+/// the ether type [`ether_type::LLDP`]).
+///
+/// The TLVs are checked when the slice is created. The data unit has to
+/// start with the mandatory chassis ID, port ID & TTL TLVs & ends with the
+/// end TLV (or the end of the data if no end TLV is present).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{LldpSlice, LldpTlv};
+///
+/// let data = [
+///     0x02, 0x07, 4, 1, 2, 3, 4, 5, 6, // chassis id (mac address)
+///     0x04, 0x03, 5, b'e', b'0', // port id (interface name)
+///     0x06, 0x02, 0x00, 0x78, // ttl
+///     0x0a, 0x02, b's', b'w', // system name
+///     0x00, 0x00, // end
+/// ];
+/// let lldp = LldpSlice::from_slice(&data).unwrap();
+/// assert_eq!(Some([1, 2, 3, 4, 5, 6]), lldp.chassis_id().mac_address());
+/// assert_eq!(&b"e0"[..], lldp.port_id().id);
+/// assert_eq!(120, lldp.ttl());
+/// assert_eq!(Some(&b"sw"[..]), lldp.system_name());
+/// assert_eq!(5, lldp.tlvs().count());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LldpSlice<'a> {
+    slice: &'a [u8],
+    chassis_id: LldpChassisId<'a>,
+    port_id: LldpPortId<'a>,
+    ttl: u16,
+}
+
+impl<'a> LldpSlice<'a> {
+    /// Reads & checks a LLDP data unit. Data after the end TLV (e.g.
+    /// ethernet padding) is ignored.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LldpSlice<'a>, LldpError> {
+        use LldpError::*;
+
+        let mut chassis_id = None;
+        let mut port_id = None;
+        let mut ttl = None;
+        let mut rest = slice;
+        let mut index = 0;
+        while !rest.is_empty() {
+            let offset = slice.len() - rest.len();
+            let (tlv, next) = LldpTlv::from_slice(rest).map_err(|err| match err {
+                UnexpectedEnd(len) => UnexpectedEnd(offset + len),
+                err => err,
+            })?;
+            match (index, tlv) {
+                (0, LldpTlv::ChassisId(value)) => chassis_id = Some(value),
+                (1, LldpTlv::PortId(value)) => port_id = Some(value),
+                (2, LldpTlv::Ttl(value)) => ttl = Some(value),
+                (0, _) => return Err(MissingMandatoryTlv(lldp_tlv_type::CHASSIS_ID)),
+                (1, _) => return Err(MissingMandatoryTlv(lldp_tlv_type::PORT_ID)),
+                (2, _) => return Err(MissingMandatoryTlv(lldp_tlv_type::TTL)),
+                _ => {},
+            }
+            rest = next;
+            index += 1;
+            if LldpTlv::End == tlv {
+                break;
+            }
+        }
+
+        Ok(LldpSlice{
+            slice: &slice[..slice.len() - rest.len()],
+            chassis_id: chassis_id.ok_or(MissingMandatoryTlv(lldp_tlv_type::CHASSIS_ID))?,
+            port_id: port_id.ok_or(MissingMandatoryTlv(lldp_tlv_type::PORT_ID))?,
+            ttl: ttl.ok_or(MissingMandatoryTlv(lldp_tlv_type::TTL))?,
+        })
+    }
+
+    /// Returns the slice containing the data unit (up to & including the
+    /// end TLV).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the value of the chassis ID TLV.
+    #[inline]
+    pub fn chassis_id(&self) -> LldpChassisId<'a> {
+        self.chassis_id
+    }
+
+    /// Returns the value of the port ID TLV.
+    #[inline]
+    pub fn port_id(&self) -> LldpPortId<'a> {
+        self.port_id
+    }
+
+    /// Returns the value of the TTL TLV (in seconds).
+    #[inline]
+    pub fn ttl(&self) -> u16 {
+        self.ttl
+    }
+
+    /// Returns the value of the first port description TLV (if present).
+    pub fn port_description(&self) -> Option<&'a [u8]> {
+        self.tlvs().find_map(|tlv| match tlv {
+            LldpTlv::PortDescription(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the first system name TLV (if present).
+    pub fn system_name(&self) -> Option<&'a [u8]> {
+        self.tlvs().find_map(|tlv| match tlv {
+            LldpTlv::SystemName(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the first system description TLV (if present).
+    pub fn system_description(&self) -> Option<&'a [u8]> {
+        self.tlvs().find_map(|tlv| match tlv {
+            LldpTlv::SystemDescription(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the values of all management address TLVs.
+    pub fn management_addresses(&self) -> impl Iterator<Item = LldpManagementAddress<'a>> {
+        self.tlvs().filter_map(|tlv| match tlv {
+            LldpTlv::ManagementAddress(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over all TLVs (including the mandatory & end TLVs).
+    #[inline]
+    pub fn tlvs(&self) -> LldpTlvIterator<'a> {
+        LldpTlvIterator{
+            rest: self.slice
+        }
+    }
+}
+
+/// Iterator over the TLVs of a [`LldpSlice`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LldpTlvIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> LldpTlvIterator<'a> {
+    /// Returns the slice containing the TLVs that were not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for LldpTlvIterator<'a> {
+    type Item = LldpTlv<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the tlvs were already checked by LldpSlice::from_slice
+        let (tlv, rest) = LldpTlv::from_slice(self.rest).ok()?;
+        self.rest = rest;
+        Some(tlv)
+    }
+}
@@ -0,0 +1,167 @@
+use super::super::*;
+
+use std::str::FromStr;
+
+/// 48 bit IEEE 802 MAC address (used by ethernet, ARP & neighbor
+/// discovery link-layer address options).
+///
+/// The address is displayed & parsed in the colon notation
+/// (e.g. `00:11:22:33:44:55`). When parsing `-` is accepted as
+/// separator as well.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::MacAddr;
+///
+/// let addr: MacAddr = "02:00:5e:10:00:01".parse().unwrap();
+/// assert_eq!([0x02, 0x00, 0x5e, 0x10, 0x00, 0x01], addr.octets());
+/// assert!(addr.is_locally_administered());
+/// assert!(addr.is_unicast());
+/// assert_eq!("02:00:5e:10:00:01", addr.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Default)]
+pub struct MacAddr(pub [u8;6]);
+
+impl MacAddr {
+    /// Broadcast address (`ff:ff:ff:ff:ff:ff`).
+    pub const BROADCAST: MacAddr = MacAddr([0xff;6]);
+
+    /// Unspecified address (`00:00:00:00:00:00`).
+    pub const UNSPECIFIED: MacAddr = MacAddr([0;6]);
+
+    /// Creates an address from its six octets.
+    #[inline]
+    pub const fn new(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8) -> MacAddr {
+        MacAddr([a, b, c, d, e, f])
+    }
+
+    /// Reads an address from the first 6 bytes of a slice (None if the
+    /// slice is shorter than 6 bytes).
+    pub fn from_slice(slice: &[u8]) -> Option<MacAddr> {
+        if slice.len() < 6 {
+            None
+        } else {
+            let mut result = [0u8;6];
+            result.copy_from_slice(&slice[..6]);
+            Some(MacAddr(result))
+        }
+    }
+
+    /// Returns the six octets of the address.
+    #[inline]
+    pub const fn octets(&self) -> [u8;6] {
+        self.0
+    }
+
+    /// Returns true if the address is the broadcast address.
+    #[inline]
+    pub fn is_broadcast(&self) -> bool {
+        *self == MacAddr::BROADCAST
+    }
+
+    /// Returns true if the address consists only of zeros.
+    #[inline]
+    pub fn is_unspecified(&self) -> bool {
+        *self == MacAddr::UNSPECIFIED
+    }
+
+    /// Returns true if the group bit (least significant bit of the first
+    /// octet) is set. This includes the broadcast address.
+    #[inline]
+    pub fn is_multicast(&self) -> bool {
+        0 != self.0[0] & 0b01
+    }
+
+    /// Returns true if the group bit is not set.
+    #[inline]
+    pub fn is_unicast(&self) -> bool {
+        !self.is_multicast()
+    }
+
+    /// Returns true if the locally administered bit (second least
+    /// significant bit of the first octet) is set.
+    #[inline]
+    pub fn is_locally_administered(&self) -> bool {
+        0 != self.0[0] & 0b10
+    }
+
+    /// Returns true if the address is universally administered (assigned
+    /// by the manufacturer based on an OUI).
+    #[inline]
+    pub fn is_universal(&self) -> bool {
+        !self.is_locally_administered()
+    }
+
+    /// Returns the organizationally unique identifier (first 3 octets).
+    #[inline]
+    pub fn oui(&self) -> [u8;3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+}
+
+impl From<[u8;6]> for MacAddr {
+    #[inline]
+    fn from(octets: [u8;6]) -> MacAddr {
+        MacAddr(octets)
+    }
+}
+
+impl From<MacAddr> for [u8;6] {
+    #[inline]
+    fn from(addr: MacAddr) -> [u8;6] {
+        addr.0
+    }
+}
+
+impl AsRef<[u8]> for MacAddr {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let o = &self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            o[0], o[1], o[2], o[3], o[4], o[5]
+        )
+    }
+}
+
+/// Error when parsing a [`MacAddr`] from a string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MacAddrParseError(pub String);
+
+impl fmt::Display for MacAddrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MacAddrParseError: '{}' is not a valid MAC address.", self.0)
+    }
+}
+
+impl Error for MacAddrParseError {}
+
+impl FromStr for MacAddr {
+    type Err = MacAddrParseError;
+
+    /// Parses six hex octets (one or two digits each) separated by `:` or `-`.
+    fn from_str(text: &str) -> Result<MacAddr, MacAddrParseError> {
+        let invalid = || MacAddrParseError(text.to_string());
+        let mut result = [0u8;6];
+        let mut parts = text.split([':', '-']);
+        for byte in result.iter_mut() {
+            let part = parts.next().ok_or_else(invalid)?;
+            if part.is_empty() || part.len() > 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            *byte = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+        }
+        match parts.next() {
+            None => Ok(MacAddr(result)),
+            Some(_) => Err(invalid()),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use super::super::*;
+
+use std::io;
+
+/// Opcodes of MAC control frames (IEEE 802.3 annex 31A).
+pub mod mac_control_opcode {
+    /// PAUSE frame (IEEE 802.3x).
+    pub const PAUSE: u16 = 0x0001;
+    /// EPON GATE message (IEEE 802.3ah).
+    pub const GATE: u16 = 0x0002;
+    /// EPON REPORT message (IEEE 802.3ah).
+    pub const REPORT: u16 = 0x0003;
+    /// EPON REGISTER_REQ message (IEEE 802.3ah).
+    pub const REGISTER_REQ: u16 = 0x0004;
+    /// EPON REGISTER message (IEEE 802.3ah).
+    pub const REGISTER: u16 = 0x0005;
+    /// EPON REGISTER_ACK message (IEEE 802.3ah).
+    pub const REGISTER_ACK: u16 = 0x0006;
+    /// Priority-based flow control frame (IEEE 802.1Qbb).
+    pub const PRIORITY_FLOW_CONTROL: u16 = 0x0101;
+}
+
+/// PAUSE or priority flow control frame (payload of ethernet frames with
+/// the ether type [`ether_type::ETHERNET_FLOW_CONTROL`]).
+///
+/// Pause times are given in quanta of 512 bit times.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ethernet2Header, MacControlFrame, ether_type};
+///
+/// // pause priorities 3 & 4 (e.g. lossless RoCE traffic)
+/// let frame = MacControlFrame::PriorityFlowControl{
+///     class_enable_vector: 0b0001_1000,
+///     quanta: [0, 0, 0, 0xffff, 0xffff, 0, 0, 0],
+/// };
+/// let mut packet = Ethernet2Header{
+///     source: [1, 2, 3, 4, 5, 6],
+///     destination: MacControlFrame::DESTINATION.octets(),
+///     ether_type: ether_type::ETHERNET_FLOW_CONTROL,
+/// }.to_bytes().to_vec();
+/// packet.extend_from_slice(&frame.to_bytes());
+/// assert_eq!(60, packet.len());
+///
+/// let (decoded, _) = MacControlFrame::from_slice(&packet[14..]).unwrap();
+/// assert_eq!(Some(frame), decoded);
+/// assert_eq!(Some(0xffff), frame.priority_quanta(3));
+/// assert_eq!(None, frame.priority_quanta(0));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MacControlFrame {
+    /// PAUSE frame stopping the transmission of all frames (IEEE 802.3x).
+    Pause{ quanta: u16 },
+    /// Priority-based flow control frame (IEEE 802.1Qbb) stopping the
+    /// transmission of frames with the priorities enabled in the class
+    /// enable vector (bit 0 for priority 0).
+    PriorityFlowControl{
+        class_enable_vector: u8,
+        /// Pause times of the priorities 0 to 7 (only valid if the
+        /// corresponding class enable bit is set).
+        quanta: [u16;8],
+    },
+}
+
+impl MacControlFrame {
+    /// Length of a serialized MAC control frame (minimum ethernet payload
+    /// length, the reserved bytes after the parameters are set to zero).
+    pub const LEN: usize = 46;
+
+    /// Multicast destination address of MAC control frames (frames sent
+    /// to this address are not forwarded by bridges).
+    pub const DESTINATION: MacAddr = MacAddr([0x01, 0x80, 0xc2, 0x00, 0x00, 0x01]);
+
+    /// Decodes a PAUSE or priority flow control frame & returns the frame &
+    /// the rest of the slice after the parameters (None for other opcodes).
+    pub fn from_slice(slice: &[u8]) -> Result<(Option<MacControlFrame>, &[u8]), ReadError> {
+        let value = MacControlSlice::from_slice(slice)?;
+        let len = value.slice().len();
+        Ok((value.to_frame(), &slice[len..]))
+    }
+
+    /// Returns the opcode of the frame.
+    #[inline]
+    pub fn opcode(&self) -> u16 {
+        match self {
+            MacControlFrame::Pause{ .. } => mac_control_opcode::PAUSE,
+            MacControlFrame::PriorityFlowControl{ .. } => mac_control_opcode::PRIORITY_FLOW_CONTROL,
+        }
+    }
+
+    /// Returns the pause time in quanta applying to frames with the given
+    /// priority (None if the frame does not pause the priority).
+    pub fn priority_quanta(&self, priority: u8) -> Option<u16> {
+        match self {
+            MacControlFrame::Pause{ quanta } => Some(*quanta),
+            MacControlFrame::PriorityFlowControl{ class_enable_vector, quanta } => {
+                let index = usize::from(priority);
+                if index < quanta.len() && 0 != class_enable_vector & (1 << index) {
+                    Some(quanta[index])
+                } else {
+                    None
+                }
+            },
+        }
+    }
+
+    /// Converts a pause time in quanta to nanoseconds for a link with the
+    /// given speed in bits per second (one quantum is 512 bit times).
+    #[inline]
+    pub fn quanta_to_nanos(quanta: u16, bits_per_second: u64) -> u64 {
+        (u128::from(quanta)*512*1_000_000_000 / u128::from(bits_per_second.max(1))) as u64
+    }
+
+    /// Returns the serialized form of the frame (padded with zeros to the
+    /// minimum ethernet payload length).
+    pub fn to_bytes(&self) -> [u8;MacControlFrame::LEN] {
+        let mut result = [0u8;MacControlFrame::LEN];
+        result[..2].copy_from_slice(&self.opcode().to_be_bytes());
+        match self {
+            MacControlFrame::Pause{ quanta } => {
+                result[2..4].copy_from_slice(&quanta.to_be_bytes());
+            },
+            MacControlFrame::PriorityFlowControl{ class_enable_vector, quanta } => {
+                result[3] = *class_enable_vector;
+                for (index, value) in quanta.iter().enumerate() {
+                    let start = 4 + index*2;
+                    result[start..start + 2].copy_from_slice(&value.to_be_bytes());
+                }
+            },
+        }
+        result
+    }
+
+    /// Writes the frame to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+/// A slice containing the opcode & parameters of a MAC control frame.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MacControlSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> MacControlSlice<'a> {
+    /// Length of the opcode field.
+    pub const MIN_LEN: usize = 2;
+
+    /// Length of the opcode & parameters of a PAUSE frame.
+    pub const PAUSE_LEN: usize = 4;
+
+    /// Length of the opcode & parameters of a priority flow control frame.
+    pub const PRIORITY_FLOW_CONTROL_LEN: usize = 20;
+
+    /// Creates a MAC control slice from an other slice.
+    ///
+    /// For PAUSE & priority flow control frames the resulting slice only
+    /// contains the opcode & the parameters (the reserved bytes after them
+    /// are not part of the slice). For other opcodes the complete slice is
+    /// used.
+    pub fn from_slice(slice: &'a [u8]) -> Result<MacControlSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < MacControlSlice::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(MacControlSlice::MIN_LEN));
+        }
+        let len = match u16::from_be_bytes([slice[0], slice[1]]) {
+            mac_control_opcode::PAUSE => MacControlSlice::PAUSE_LEN,
+            mac_control_opcode::PRIORITY_FLOW_CONTROL => MacControlSlice::PRIORITY_FLOW_CONTROL_LEN,
+            _ => slice.len(),
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(MacControlSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the opcode & parameters.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the opcode (see [`mac_control_opcode`]).
+    #[inline]
+    pub fn opcode(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Returns the slice containing the parameters after the opcode.
+    #[inline]
+    pub fn parameters(&self) -> &'a [u8] {
+        &self.slice[MacControlSlice::MIN_LEN..]
+    }
+
+    /// Decodes PAUSE & priority flow control frames (None for other opcodes).
+    pub fn to_frame(&self) -> Option<MacControlFrame> {
+        let p = self.parameters();
+        match self.opcode() {
+            mac_control_opcode::PAUSE => Some(MacControlFrame::Pause{
+                quanta: u16::from_be_bytes([p[0], p[1]]),
+            }),
+            mac_control_opcode::PRIORITY_FLOW_CONTROL => {
+                let mut quanta = [0u16;8];
+                for (index, value) in quanta.iter_mut().enumerate() {
+                    *value = u16::from_be_bytes([p[2 + index*2], p[3 + index*2]]);
+                }
+                Some(MacControlFrame::PriorityFlowControl{
+                    class_enable_vector: p[1],
+                    quanta,
+                })
+            },
+            _ => None,
+        }
+    }
+}
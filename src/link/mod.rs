@@ -1,21 +1,72 @@
+pub mod arp;
+pub mod eapol;
 pub mod ethernet;
+pub mod ieee80211;
+pub mod ieee802154;
+pub mod lacp;
+pub mod link_type;
+pub mod linux_sll;
+pub mod llc;
+pub mod lldp;
+pub mod mac_addr;
+pub mod mac_control;
+pub mod null;
+pub mod stp;
 pub mod vlan_tagging;
+pub mod wake_on_lan;
 
-/// A slice containing the link layer header (currently only Ethernet II is supported).
+/// A slice containing the link layer header.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum LinkSlice<'a> {
     /// A slice containing an Ethernet II header.
-    Ethernet2(ethernet::Ethernet2HeaderSlice<'a>)
+    Ethernet2(ethernet::Ethernet2HeaderSlice<'a>),
+    /// A slice containing a Linux "cooked" capture header (`LINKTYPE_LINUX_SLL`).
+    LinuxSll(linux_sll::LinuxSllHeaderSlice<'a>),
+    /// A slice containing a Linux "cooked" capture v2 header (`LINKTYPE_LINUX_SLL2`).
+    LinuxSll2(linux_sll::LinuxSll2HeaderSlice<'a>),
+    /// A slice containing a BSD loopback header (`LINKTYPE_NULL` & `LINKTYPE_LOOP`).
+    Null(null::NullHeaderSlice<'a>),
+    /// A slice containing an IEEE 802.11 MAC header.
+    Ieee80211(ieee80211::Ieee80211HeaderSlice<'a>),
 }
 
 impl<'a> LinkSlice<'a> {
 
-    /// Convert the link slice to a header (currently just the
-    /// ethernet2 header as this is the only value it can take).
-    pub fn to_header(&self) -> ethernet::Ethernet2Header {
+    /// Convert the link slice to an ethernet2 header (`None` if the
+    /// slice contains a different link layer header).
+    pub fn to_header(&self) -> Option<ethernet::Ethernet2Header> {
         use LinkSlice::*;
         match self {
-            Ethernet2(slice) => slice.to_header(),
+            Ethernet2(slice) => Some(slice.to_header()),
+            LinuxSll(_) | LinuxSll2(_) | Null(_) | Ieee80211(_) => None,
+        }
+    }
+
+    /// Returns the ether type identifying the header after the link layer
+    /// header (`None` if the link layer header contains no ether type, e.g.
+    /// for Linux cooked captures of netlink messages).
+    pub fn ether_type(&self) -> Option<u16> {
+        use LinkSlice::*;
+        match self {
+            Ethernet2(slice) => Some(slice.ether_type()),
+            LinuxSll(slice) => linux_sll::cooked_ether_type(slice.arphrd_type(), slice.protocol_type()),
+            LinuxSll2(slice) => linux_sll::cooked_ether_type(slice.arphrd_type(), slice.protocol_type()),
+            Null(slice) => slice.ether_type(),
+            // the ether type is part of the following LLC/SNAP header
+            Ieee80211(_) => None,
+        }
+    }
+
+    /// Returns the slice containing the link layer header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        use LinkSlice::*;
+        match self {
+            Ethernet2(slice) => slice.slice(),
+            LinuxSll(slice) => slice.slice(),
+            LinuxSll2(slice) => slice.slice(),
+            Null(slice) => slice.slice(),
+            Ieee80211(slice) => slice.slice(),
         }
     }
 }
@@ -0,0 +1,70 @@
+use super::super::*;
+
+/// Protocol family values of BSD loopback headers (`LINKTYPE_NULL` &
+/// `LINKTYPE_LOOP`) identifying the header after it.
+pub mod null_protocol_family {
+    /// IPv4 (`AF_INET` on all operating systems).
+    pub const INET: u32 = 2;
+    /// IPv6 (`AF_INET6` on Linux).
+    pub const INET6_LINUX: u32 = 10;
+    /// IPv6 (`AF_INET6` on NetBSD & OpenBSD).
+    pub const INET6_BSD: u32 = 24;
+    /// IPv6 (`AF_INET6` on FreeBSD).
+    pub const INET6_FREEBSD: u32 = 28;
+    /// IPv6 (`AF_INET6` on macOS).
+    pub const INET6_DARWIN: u32 = 30;
+}
+
+/// A slice containing a BSD loopback header (`LINKTYPE_NULL` &
+/// `LINKTYPE_LOOP`), consisting of the 4 byte protocol family of the packet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NullHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> NullHeaderSlice<'a> {
+
+    /// Length of the header in bytes.
+    pub const LEN: usize = 4;
+
+    /// Creates a BSD loopback header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<NullHeaderSlice<'a>, ReadError> {
+        if slice.len() < NullHeaderSlice::LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(NullHeaderSlice::LEN));
+        }
+        Ok(NullHeaderSlice {
+            slice: &slice[..NullHeaderSlice::LEN]
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the protocol family (see [`null_protocol_family`]).
+    ///
+    /// The field is written in the byte order of the capturing host (network
+    /// byte order for `LINKTYPE_LOOP`). As all valid values are smaller then
+    /// 2^16 the byte order is detected based on the first two bytes.
+    pub fn protocol_family(&self) -> u32 {
+        let bytes = [self.slice[0], self.slice[1], self.slice[2], self.slice[3]];
+        if 0 == bytes[0] && 0 == bytes[1] {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+
+    /// Returns the ether type matching the protocol family (`None` for
+    /// protocol families other then IPv4 & IPv6).
+    pub fn ether_type(&self) -> Option<u16> {
+        use null_protocol_family::*;
+        match self.protocol_family() {
+            INET => Some(ether_type::IPV4),
+            INET6_LINUX | INET6_BSD | INET6_FREEBSD | INET6_DARWIN => Some(ether_type::IPV6),
+            _ => None,
+        }
+    }
+}
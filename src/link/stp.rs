@@ -0,0 +1,244 @@
+use super::super::*;
+
+/// Protocol version identifiers of spanning tree BPDUs.
+pub mod stp_version {
+    /// Spanning tree protocol (IEEE 802.1D).
+    pub const STP: u8 = 0;
+    /// Rapid spanning tree protocol (IEEE 802.1w).
+    pub const RSTP: u8 = 2;
+    /// Multiple spanning tree protocol (IEEE 802.1s).
+    pub const MSTP: u8 = 3;
+}
+
+/// Types of spanning tree BPDUs.
+pub mod stp_bpdu_type {
+    /// Configuration BPDU (STP).
+    pub const CONFIG: u8 = 0x00;
+    /// Rapid spanning tree BPDU (RSTP & MSTP).
+    pub const RST: u8 = 0x02;
+    /// Topology change notification BPDU (STP).
+    pub const TOPOLOGY_CHANGE_NOTIFICATION: u8 = 0x80;
+}
+
+/// Bits of the flags field of configuration & rapid spanning tree BPDUs.
+pub mod stp_flags {
+    pub const TOPOLOGY_CHANGE: u8 = 0x01;
+    /// Proposal flag (RSTP & MSTP only).
+    pub const PROPOSAL: u8 = 0x02;
+    /// Mask of the 2 bit port role (RSTP & MSTP only).
+    pub const PORT_ROLE_MASK: u8 = 0x0c;
+    /// Learning flag (RSTP & MSTP only).
+    pub const LEARNING: u8 = 0x10;
+    /// Forwarding flag (RSTP & MSTP only).
+    pub const FORWARDING: u8 = 0x20;
+    /// Agreement flag (RSTP & MSTP only).
+    pub const AGREEMENT: u8 = 0x40;
+    pub const TOPOLOGY_CHANGE_ACK: u8 = 0x80;
+}
+
+/// Root or bridge identifier contained in a spanning tree BPDU.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub struct StpBridgeId {
+    /// Bridge priority. Since IEEE 802.1t only the upper 4 bits contain the
+    /// priority & the lower 12 bits the system id extension (usually the
+    /// vlan or MST instance).
+    pub priority: u16,
+    /// MAC address of the bridge.
+    pub address: [u8;6],
+}
+
+impl StpBridgeId {
+    /// Returns the system id extension (lower 12 bits of the priority field).
+    #[inline]
+    pub fn system_id_extension(&self) -> u16 {
+        self.priority & 0x0fff
+    }
+
+    fn from_bytes(bytes: &[u8]) -> StpBridgeId {
+        let mut address = [0u8;6];
+        address.copy_from_slice(&bytes[2..8]);
+        StpBridgeId{
+            priority: u16::from_be_bytes([bytes[0], bytes[1]]),
+            address,
+        }
+    }
+}
+
+/// A slice containing a spanning tree BPDU (STP, RSTP or MSTP), which is
+/// sent after a LLC header with the DSAP & SSAP [`llc_sap::STP`].
+///
+/// Topology change notification BPDUs only contain the protocol id,
+/// version & type, which is why the other fields are returned as options.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{StpBpduSlice, stp_bpdu_type, stp_flags};
+///
+/// let mut bpdu = [0u8;36];
+/// bpdu[2] = 2; // rstp
+/// bpdu[3] = stp_bpdu_type::RST;
+/// bpdu[4] = stp_flags::TOPOLOGY_CHANGE;
+/// bpdu[5..13].copy_from_slice(&[0x80, 0x01, 1, 2, 3, 4, 5, 6]); // root id
+/// bpdu[16] = 4; // root path cost
+///
+/// let slice = StpBpduSlice::from_slice(&bpdu).unwrap();
+/// assert_eq!(Some(stp_flags::TOPOLOGY_CHANGE), slice.flags());
+/// assert_eq!(Some(1), slice.root_id().map(|id| id.system_id_extension()));
+/// assert_eq!(Some(4), slice.root_path_cost());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StpBpduSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> StpBpduSlice<'a> {
+    /// Length of a topology change notification BPDU.
+    pub const TCN_LEN: usize = 4;
+
+    /// Length of a configuration BPDU.
+    pub const CONFIG_LEN: usize = 35;
+
+    /// Length of a rapid spanning tree BPDU (without the MSTP specific part).
+    pub const RST_LEN: usize = 36;
+
+    /// Length of the fixed part of a multiple spanning tree BPDU (up to &
+    /// including the version 3 length field).
+    pub const MST_MIN_LEN: usize = 38;
+
+    /// Creates a BPDU slice from an other slice (data after the BPDU, e.g.
+    /// ethernet padding, is not part of the resulting slice).
+    pub fn from_slice(slice: &'a [u8]) -> Result<StpBpduSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < StpBpduSlice::TCN_LEN {
+            return Err(UnexpectedEndOfSlice(StpBpduSlice::TCN_LEN));
+        }
+        let len = match slice[3] {
+            stp_bpdu_type::TOPOLOGY_CHANGE_NOTIFICATION => StpBpduSlice::TCN_LEN,
+            stp_bpdu_type::CONFIG => StpBpduSlice::CONFIG_LEN,
+            stp_bpdu_type::RST => if slice[2] >= stp_version::MSTP {
+                if slice.len() < StpBpduSlice::MST_MIN_LEN {
+                    return Err(UnexpectedEndOfSlice(StpBpduSlice::MST_MIN_LEN));
+                }
+                StpBpduSlice::MST_MIN_LEN + usize::from(u16::from_be_bytes([slice[36], slice[37]]))
+            } else {
+                StpBpduSlice::RST_LEN
+            },
+            value => return Err(StpUnknownBpduType(value)),
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(StpBpduSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the BPDU.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the protocol identifier (always 0 for spanning tree BPDUs).
+    #[inline]
+    pub fn protocol_id(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the protocol version identifier (see [`stp_version`]).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[2]
+    }
+
+    /// Read the BPDU type (see [`stp_bpdu_type`]).
+    #[inline]
+    pub fn bpdu_type(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Returns true if the BPDU is a topology change notification.
+    #[inline]
+    pub fn is_topology_change_notification(&self) -> bool {
+        stp_bpdu_type::TOPOLOGY_CHANGE_NOTIFICATION == self.bpdu_type()
+    }
+
+    /// Returns the fields of configuration & rapid spanning tree BPDUs
+    /// (None for topology change notifications).
+    #[inline]
+    fn config(&self) -> Option<&'a [u8]> {
+        if self.slice.len() >= StpBpduSlice::CONFIG_LEN {
+            Some(self.slice)
+        } else {
+            None
+        }
+    }
+
+    /// Read the flags (see [`stp_flags`]).
+    pub fn flags(&self) -> Option<u8> {
+        self.config().map(|c| c[4])
+    }
+
+    /// Read the port role (RSTP & MSTP only, 0 unknown, 1 alternate or
+    /// backup, 2 root & 3 designated).
+    pub fn port_role(&self) -> Option<u8> {
+        if stp_bpdu_type::RST == self.bpdu_type() {
+            self.flags().map(|f| (f & stp_flags::PORT_ROLE_MASK) >> 2)
+        } else {
+            None
+        }
+    }
+
+    /// Read the root identifier (CIST root identifier in MSTP).
+    pub fn root_id(&self) -> Option<StpBridgeId> {
+        self.config().map(|c| StpBridgeId::from_bytes(&c[5..13]))
+    }
+
+    /// Read the root path cost (CIST external root path cost in MSTP).
+    pub fn root_path_cost(&self) -> Option<u32> {
+        self.config().map(|c| u32::from_be_bytes([c[13], c[14], c[15], c[16]]))
+    }
+
+    /// Read the bridge identifier (CIST regional root identifier in MSTP).
+    pub fn bridge_id(&self) -> Option<StpBridgeId> {
+        self.config().map(|c| StpBridgeId::from_bytes(&c[17..25]))
+    }
+
+    /// Read the port identifier.
+    pub fn port_id(&self) -> Option<u16> {
+        self.config().map(|c| u16::from_be_bytes([c[25], c[26]]))
+    }
+
+    /// Read the message age (in 1/256 seconds).
+    pub fn message_age(&self) -> Option<u16> {
+        self.config().map(|c| u16::from_be_bytes([c[27], c[28]]))
+    }
+
+    /// Read the max age (in 1/256 seconds).
+    pub fn max_age(&self) -> Option<u16> {
+        self.config().map(|c| u16::from_be_bytes([c[29], c[30]]))
+    }
+
+    /// Read the hello time (in 1/256 seconds).
+    pub fn hello_time(&self) -> Option<u16> {
+        self.config().map(|c| u16::from_be_bytes([c[31], c[32]]))
+    }
+
+    /// Read the forward delay (in 1/256 seconds).
+    pub fn forward_delay(&self) -> Option<u16> {
+        self.config().map(|c| u16::from_be_bytes([c[33], c[34]]))
+    }
+
+    /// Returns the MSTP specific data after the version 3 length field
+    /// (MST configuration identifier, CIST fields & MSTI configuration
+    /// messages). None for all other BPDUs.
+    pub fn mst_data(&self) -> Option<&'a [u8]> {
+        if self.slice.len() >= StpBpduSlice::MST_MIN_LEN {
+            Some(&self.slice[StpBpduSlice::MST_MIN_LEN..])
+        } else {
+            None
+        }
+    }
+}
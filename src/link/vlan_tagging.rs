@@ -3,13 +3,15 @@ use super::super::*;
 use std::io;
 use std::slice::from_raw_parts;
 
-/// IEEE 802.1Q VLAN Tagging Header (can be single or double tagged).
+/// IEEE 802.1Q VLAN Tagging Header (can be single, double or multi tagged).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum VlanHeader {
     /// IEEE 802.1Q VLAN Tagging Header
     Single(SingleVlanHeader),
     /// IEEE 802.1Q double VLAN Tagging Header
-    Double(DoubleVlanHeader)
+    Double(DoubleVlanHeader),
+    /// Three or more stacked IEEE 802.1Q VLAN Tagging Headers (outermost first).
+    Multi(Vec<SingleVlanHeader>),
 }
 
 impl VlanHeader {
@@ -20,6 +22,17 @@ impl VlanHeader {
         ether_type::VLAN_DOUBLE_TAGGED_FRAME,
     ];
 
+    /// Vlan ether types including the non standard TPIDs 0x9200 & 0x9300
+    /// still used by some switches for service tags (can be set as
+    /// [`ParseLimits::vlan_tpids`]).
+    pub const LEGACY_VLAN_ETHER_TYPES: [u16;5] = [
+        ether_type::VLAN_TAGGED_FRAME,
+        ether_type::PROVIDER_BRIDGING,
+        ether_type::VLAN_DOUBLE_TAGGED_FRAME,
+        0x9200,
+        0x9300,
+    ];
+
     /// Write the IEEE 802.1Q VLAN single or double tagging header
     #[inline]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
@@ -27,6 +40,12 @@ impl VlanHeader {
         match &self {
             Single(header) => header.write(writer),
             Double(header) => header.write(writer),
+            Multi(headers) => {
+                for header in headers {
+                    header.write(writer)?;
+                }
+                Ok(())
+            },
         }
     }
 
@@ -37,32 +56,179 @@ impl VlanHeader {
         match &self {
             Single(_) => SingleVlanHeader::SERIALIZED_SIZE,
             Double(_) => DoubleVlanHeader::SERIALIZED_SIZE,
+            Multi(headers) => headers.len()*SingleVlanHeader::SERIALIZED_SIZE,
+        }
+    }
+
+    /// Number of stacked vlan headers.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        use VlanHeader::*;
+        match &self {
+            Single(_) => 1,
+            Double(_) => 2,
+            Multi(headers) => headers.len(),
+        }
+    }
+
+    /// Returns the vlan header at the given position in the stack (0 is
+    /// the outermost header).
+    pub fn get(&self, index: usize) -> Option<&SingleVlanHeader> {
+        use VlanHeader::*;
+        match &self {
+            Single(header) => if 0 == index { Some(header) } else { None },
+            Double(header) => match index {
+                0 => Some(&header.outer),
+                1 => Some(&header.inner),
+                _ => None,
+            },
+            Multi(headers) => headers.get(index),
+        }
+    }
+
+    /// Returns a mutable reference to the vlan header at the given position
+    /// in the stack (0 is the outermost header).
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut SingleVlanHeader> {
+        use VlanHeader::*;
+        match self {
+            Single(header) => if 0 == index { Some(header) } else { None },
+            Double(header) => match index {
+                0 => Some(&mut header.outer),
+                1 => Some(&mut header.inner),
+                _ => None,
+            },
+            Multi(headers) => headers.get_mut(index),
+        }
+    }
+
+    /// Returns the ether type of the content after the innermost vlan
+    /// header as an [`EtherType`] (None if the value is not contained in
+    /// the enum).
+    pub fn known_ether_type(&self) -> Option<EtherType> {
+        self.get(self.depth().checked_sub(1)?)?.known_ether_type()
+    }
+
+    /// Returns an iterator over all stacked vlan headers (outermost first).
+    #[inline]
+    pub fn iter(&self) -> VlanHeaderIterator<'_> {
+        VlanHeaderIterator{
+            header: self,
+            index: 0,
         }
     }
 }
 
-/// A slice containing a single or double vlan header.
+/// Iterator over the stacked headers of a [`VlanHeader`] (outermost first).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VlanHeaderIterator<'a> {
+    header: &'a VlanHeader,
+    index: usize,
+}
+
+impl<'a> Iterator for VlanHeaderIterator<'a> {
+    type Item = &'a SingleVlanHeader;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.header.get(self.index);
+        if result.is_some() {
+            self.index += 1;
+        }
+        result
+    }
+}
+
+/// A slice containing a single, double or multi vlan header.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum VlanSlice<'a> {
     SingleVlan(SingleVlanHeaderSlice<'a>),
     DoubleVlan(DoubleVlanHeaderSlice<'a>),
+    /// Three or more stacked vlan headers.
+    MultiVlan(MultiVlanHeaderSlice<'a>),
 }
 
 impl<'a> VlanSlice<'a> {
-    /// Decode all the fields and copy the results to a VlanHeader struct
+    /// Decode all the fields and copy the results to a VlanHeader struct.
+    ///
+    /// The TPIDs of the inner headers are set to the ether types of the
+    /// preceding headers. The TPID of the outermost header is not part of
+    /// the slice & set to [`ether_type::VLAN_TAGGED_FRAME`].
     #[inline]
     pub fn to_header(&self) -> VlanHeader {
         use crate::VlanHeader::*;
         use crate::VlanSlice::*;
         match self {
             SingleVlan(value) => Single(value.to_header()),
-            DoubleVlan(value) => Double(value.to_header())
+            DoubleVlan(value) => Double(value.to_header()),
+            MultiVlan(value) => Multi(value.to_header()),
+        }
+    }
+
+    /// Returns the slice containing the single, double or multi vlan header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        use crate::VlanSlice::*;
+        match self {
+            SingleVlan(value) => value.slice(),
+            DoubleVlan(value) => value.slice(),
+            MultiVlan(value) => value.slice(),
+        }
+    }
+
+    /// Number of stacked vlan headers.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.slice().len() / SingleVlanHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the outermost vlan header.
+    #[inline]
+    pub fn outer(&self) -> SingleVlanHeaderSlice<'a> {
+        SingleVlanHeaderSlice{
+            slice: &self.slice()[..SingleVlanHeader::SERIALIZED_SIZE]
+        }
+    }
+
+    /// Returns the innermost vlan header (containing the ether type of
+    /// the payload).
+    #[inline]
+    pub fn inner(&self) -> SingleVlanHeaderSlice<'a> {
+        let slice = self.slice();
+        SingleVlanHeaderSlice{
+            slice: &slice[slice.len() - SingleVlanHeader::SERIALIZED_SIZE..]
+        }
+    }
+
+    /// Returns an iterator over all stacked vlan headers (outermost first).
+    #[inline]
+    pub fn iter(&self) -> VlanSliceIterator<'a> {
+        VlanSliceIterator{
+            rest: self.slice()
+        }
+    }
+}
+
+/// Iterator over stacked vlan header slices (outermost first).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VlanSliceIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for VlanSliceIterator<'a> {
+    type Item = SingleVlanHeaderSlice<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.len() < SingleVlanHeader::SERIALIZED_SIZE {
+            None
+        } else {
+            let (slice, rest) = self.rest.split_at(SingleVlanHeader::SERIALIZED_SIZE);
+            self.rest = rest;
+            Some(SingleVlanHeaderSlice{ slice })
         }
     }
 }
 
 /// IEEE 802.1Q VLAN Tagging Header
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SingleVlanHeader {
     /// A 3 bit number which refers to the IEEE 802.1p class of service and maps to the frame priority level.
     pub priority_code_point: u8,
@@ -72,6 +238,25 @@ pub struct SingleVlanHeader {
     pub vlan_identifier: u16,
     /// "Tag protocol identifier": Type id of content after this header. Refer to the "EtherType" for a list of possible supported values.
     pub ether_type: u16,
+    /// Ether type that identified this header (e.g. 0x8100 or 0x88A8).
+    ///
+    /// The TPID is not part of the serialized header, it is written as ether
+    /// type of the preceding header. The decoders set it to the ether type
+    /// that preceded the header & the `PacketBuilder` uses it as ether type
+    /// of the preceding header. Defaults to [`ether_type::VLAN_TAGGED_FRAME`].
+    pub tpid: u16,
+}
+
+impl Default for SingleVlanHeader {
+    fn default() -> Self {
+        SingleVlanHeader {
+            priority_code_point: 0,
+            drop_eligible_indicator: false,
+            vlan_identifier: 0,
+            ether_type: 0,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        }
+    }
 }
 
 impl SerializedSize for SingleVlanHeader {
@@ -118,6 +303,7 @@ impl SingleVlanHeader {
                     bytes[3],
                 ]
             ),
+            tpid: ether_type::VLAN_TAGGED_FRAME,
         }
     }
 
@@ -147,6 +333,20 @@ impl SingleVlanHeader {
         4
     }
 
+    /// Returns the `ether_type` field as an [`EtherType`] (None if the
+    /// value is not contained in the enum).
+    #[inline]
+    pub fn known_ether_type(&self) -> Option<EtherType> {
+        EtherType::from_u16(self.ether_type)
+    }
+
+    /// Returns the `tpid` field as an [`EtherType`] (None if the value is
+    /// not contained in the enum, e.g. the legacy TPIDs 0x9200 & 0x9300).
+    #[inline]
+    pub fn known_tpid(&self) -> Option<EtherType> {
+        EtherType::from_u16(self.tpid)
+    }
+
     /// Returns the serialized form of the header or an value error in case
     /// the header values are outside of range.
     #[inline]
@@ -219,9 +419,13 @@ impl DoubleVlanHeader {
         //check that outer ethertype is matching
         match outer.ether_type {
             VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
+                let inner = SingleVlanHeader{
+                    tpid: outer.ether_type,
+                    ..SingleVlanHeader::read(reader)?
+                };
                 Ok(DoubleVlanHeader{
                     outer,
-                    inner: SingleVlanHeader::read(reader)?
+                    inner
                 })
             },
             value => {
@@ -272,6 +476,7 @@ impl Default for DoubleVlanHeader {
                 drop_eligible_indicator: false,
                 vlan_identifier: 0,
                 ether_type: ether_type::VLAN_TAGGED_FRAME,
+                tpid: ether_type::PROVIDER_BRIDGING,
             },
             inner: Default::default()
         }
@@ -359,6 +564,13 @@ impl<'a> SingleVlanHeaderSlice<'a> {
         }
     }
 
+    /// Read the "Tag protocol identifier" field as an [`EtherType`] (None
+    /// if the value is not contained in the enum).
+    #[inline]
+    pub fn known_ether_type(&self) -> Option<EtherType> {
+        EtherType::from_u16(self.ether_type())
+    }
+
     /// Decode all the fields and copy the results to a SingleVlanHeader struct
     #[inline]
     pub fn to_header(&self) -> SingleVlanHeader {
@@ -367,6 +579,7 @@ impl<'a> SingleVlanHeaderSlice<'a> {
             drop_eligible_indicator: self.drop_eligible_indicator(),
             vlan_identifier: self.vlan_identifier(),
             ether_type: self.ether_type(),
+            tpid: ether_type::VLAN_TAGGED_FRAME,
         }
     }
 }
@@ -454,10 +667,104 @@ impl<'a> DoubleVlanHeaderSlice<'a> {
     }
 
     /// Decode all the fields and copy the results to a DoubleVlanHeader struct
+    /// (the TPID of the inner header is set to the ether type of the outer header).
     pub fn to_header(&self) -> DoubleVlanHeader {
+        let outer = self.outer().to_header();
+        let inner = SingleVlanHeader{
+            tpid: outer.ether_type,
+            ..self.inner().to_header()
+        };
         DoubleVlanHeader {
-            outer: self.outer().to_header(),
-            inner: self.inner().to_header()
+            outer,
+            inner
+        }
+    }
+}
+
+/// A slice containing a stack of vlan headers of a network package
+/// (used by the decoders for three or more stacked vlan headers).
+///
+/// The stack ends with the first header that contains a non vlan ether type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultiVlanHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> MultiVlanHeaderSlice<'a> {
+    /// Creates a multi vlan header slice from a slice (reads vlan headers
+    /// until a header with a non vlan ether type is encountered).
+    #[inline]
+    pub fn from_slice(slice: &'a[u8]) -> Result<MultiVlanHeaderSlice<'a>, ReadError> {
+        MultiVlanHeaderSlice::from_slice_with_limits(
+            slice,
+            ParseLimits{
+                max_vlan_depth: usize::MAX,
+                ..ParseLimits::DEFAULT
+            }
+        )
+    }
+
+    /// Creates a multi vlan header slice from a slice & returns an error
+    /// if the number of vlan headers exceeds [`ParseLimits::max_vlan_depth`]
+    /// (ether types in [`ParseLimits::vlan_tpids`] identify further vlan headers).
+    pub(crate) fn from_slice_with_limits(slice: &'a[u8], limits: ParseLimits) -> Result<MultiVlanHeaderSlice<'a>, ReadError> {
+        let mut len = 0;
+        loop {
+            limits.check_vlan_depth(len/SingleVlanHeader::SERIALIZED_SIZE + 1)?;
+            let header = SingleVlanHeaderSlice::from_slice(&slice[len..])
+                .map_err(|err| err.add_slice_offset(len))?;
+            len += SingleVlanHeader::SERIALIZED_SIZE;
+            if !limits.is_vlan_tpid(header.ether_type()) {
+                break;
+            }
+        }
+        Ok(MultiVlanHeaderSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Converts the slice to a [`VlanSlice`] (single, double or multi
+    /// vlan slice depending on the number of vlan headers).
+    pub(crate) fn into_vlan_slice(self) -> VlanSlice<'a> {
+        match self.depth() {
+            1 => VlanSlice::SingleVlan(SingleVlanHeaderSlice{ slice: self.slice }),
+            2 => VlanSlice::DoubleVlan(DoubleVlanHeaderSlice{ slice: self.slice }),
+            _ => VlanSlice::MultiVlan(self),
         }
     }
-}
\ No newline at end of file
+
+    /// Returns the slice containing the vlan headers.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Number of stacked vlan headers.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.slice.len() / SingleVlanHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns an iterator over all stacked vlan headers (outermost first).
+    #[inline]
+    pub fn iter(&self) -> VlanSliceIterator<'a> {
+        VlanSliceIterator{
+            rest: self.slice
+        }
+    }
+
+    /// Decode all the fields and copy the results to a vector of
+    /// SingleVlanHeader structs (outermost first, the TPIDs of the inner
+    /// headers are set to the ether types of the preceding headers).
+    pub fn to_header(&self) -> Vec<SingleVlanHeader> {
+        let mut tpid = ether_type::VLAN_TAGGED_FRAME;
+        self.iter().map(|slice| {
+            let header = SingleVlanHeader{
+                tpid,
+                ..slice.to_header()
+            };
+            tpid = header.ether_type;
+            header
+        }).collect()
+    }
+}
@@ -0,0 +1,194 @@
+use super::super::*;
+
+use std::io;
+
+/// SecureOn password appended to a Wake-on-LAN magic packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum WakeOnLanPassword {
+    /// 4 byte password (often written in IPv4 address notation).
+    Four([u8;4]),
+    /// 6 byte password (often written in MAC address notation).
+    Six([u8;6]),
+}
+
+impl WakeOnLanPassword {
+    /// Returns the bytes of the password.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            WakeOnLanPassword::Four(value) => value,
+            WakeOnLanPassword::Six(value) => value,
+        }
+    }
+}
+
+/// Wake-on-LAN magic packet (6 bytes `0xff` followed by 16 repetitions of
+/// the target MAC address & an optional SecureOn password).
+///
+/// Magic packets are sent as payload of ethernet frames with the ether type
+/// [`ether_type::WAKE_ON_LAN`] or as payload of UDP packets (usually to the
+/// port [`WakeOnLanPacket::UDP_PORT`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{MacAddr, PacketBuilder, WakeOnLanPacket};
+///
+/// let magic = WakeOnLanPacket::new(MacAddr([1, 2, 3, 4, 5, 6])).to_bytes();
+/// assert_eq!(WakeOnLanPacket::MIN_LEN, magic.len());
+///
+/// // send the packet as an ipv4 broadcast
+/// let builder = PacketBuilder::
+///     ethernet2([7, 8, 9, 10, 11, 12], [0xff;6])
+///     .ipv4([192, 168, 1, 2], [255, 255, 255, 255], 64)
+///     .udp(40000, WakeOnLanPacket::UDP_PORT);
+/// let mut packet = Vec::with_capacity(builder.size(magic.len()));
+/// builder.write(&mut packet, &magic).unwrap();
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WakeOnLanPacket {
+    /// MAC address of the device that should be woken up.
+    pub target: MacAddr,
+    pub password: Option<WakeOnLanPassword>,
+}
+
+impl WakeOnLanPacket {
+    /// Length of a magic packet without a password.
+    pub const MIN_LEN: usize = 6 + 16*6;
+
+    /// Length of a magic packet with a 6 byte password.
+    pub const MAX_LEN: usize = WakeOnLanPacket::MIN_LEN + 6;
+
+    /// UDP port commonly used for magic packets (discard port, port 7 is also used).
+    pub const UDP_PORT: u16 = 9;
+
+    /// Creates a magic packet for the given target without a password.
+    #[inline]
+    pub fn new(target: MacAddr) -> WakeOnLanPacket {
+        WakeOnLanPacket{
+            target,
+            password: None,
+        }
+    }
+
+    /// Length of the serialized packet in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        WakeOnLanPacket::MIN_LEN + self.password.map(|p| p.as_slice().len()).unwrap_or(0)
+    }
+
+    /// Returns the serialized form of the packet.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.header_len());
+        result.extend_from_slice(&[0xff;6]);
+        for _ in 0..16 {
+            result.extend_from_slice(self.target.as_ref());
+        }
+        if let Some(password) = &self.password {
+            result.extend_from_slice(password.as_slice());
+        }
+        result
+    }
+
+    /// Writes the packet to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+/// A slice containing a Wake-on-LAN magic packet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WakeOnLanSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> WakeOnLanSlice<'a> {
+    /// Creates a magic packet slice from a slice starting with the
+    /// synchronization stream (6 bytes `0xff`).
+    ///
+    /// Returns None if the slice does not start with a magic packet. If
+    /// exactly 4 or 6 bytes follow the repetitions of the MAC address they
+    /// are interpreted as password, otherwise data after the repetitions is
+    /// not part of the resulting slice.
+    pub fn from_slice(slice: &'a [u8]) -> Option<WakeOnLanSlice<'a>> {
+        if slice.len() < WakeOnLanPacket::MIN_LEN || slice[..6] != [0xff;6] {
+            return None;
+        }
+        let target = &slice[6..12];
+        if !slice[12..WakeOnLanPacket::MIN_LEN].chunks_exact(6).all(|c| c == target) {
+            return None;
+        }
+        let len = match slice.len() - WakeOnLanPacket::MIN_LEN {
+            rest @ 4 | rest @ 6 => WakeOnLanPacket::MIN_LEN + rest,
+            _ => WakeOnLanPacket::MIN_LEN,
+        };
+        Some(WakeOnLanSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Searches a magic packet at any position in the given data (e.g.
+    /// the payload of an ethernet frame or UDP packet).
+    pub fn find(data: &'a [u8]) -> Option<WakeOnLanSlice<'a>> {
+        (0..data.len().saturating_sub(WakeOnLanPacket::MIN_LEN - 1))
+            .find_map(|start| WakeOnLanSlice::from_slice(&data[start..]))
+    }
+
+    /// Searches a magic packet in the payload of an ethernet frame with
+    /// the ether type [`ether_type::WAKE_ON_LAN`] (directly or after vlan
+    /// headers) or in the payload of an UDP packet.
+    pub fn from_sliced_packet(packet: &SlicedPacket<'a>) -> Option<WakeOnLanSlice<'a>> {
+        if packet.tunnel.is_some() {
+            return None;
+        }
+        let is_candidate = match (&packet.transport, &packet.ip, &packet.vlan, &packet.link) {
+            (Some(TransportSlice::Udp(_)), _, _, _) => true,
+            (None, None, Some(vlan), _) => ether_type::WAKE_ON_LAN == vlan.inner().ether_type(),
+            (None, None, None, Some(LinkSlice::Ethernet2(eth))) => ether_type::WAKE_ON_LAN == eth.ether_type(),
+            _ => false,
+        };
+        if is_candidate {
+            WakeOnLanSlice::find(packet.payload)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the slice containing the magic packet.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the MAC address of the device that should be woken up.
+    #[inline]
+    pub fn target(&self) -> MacAddr {
+        let mut result = [0u8;6];
+        result.copy_from_slice(&self.slice[6..12]);
+        MacAddr(result)
+    }
+
+    /// Read the SecureOn password (None if no password is present).
+    pub fn password(&self) -> Option<WakeOnLanPassword> {
+        let password = &self.slice[WakeOnLanPacket::MIN_LEN..];
+        match password.len() {
+            4 => Some(WakeOnLanPassword::Four([password[0], password[1], password[2], password[3]])),
+            6 => {
+                let mut result = [0u8;6];
+                result.copy_from_slice(password);
+                Some(WakeOnLanPassword::Six(result))
+            },
+            _ => None,
+        }
+    }
+
+    /// Decode all the fields and copy the results to a WakeOnLanPacket struct.
+    #[inline]
+    pub fn to_packet(&self) -> WakeOnLanPacket {
+        WakeOnLanPacket{
+            target: self.target(),
+            password: self.password(),
+        }
+    }
+}
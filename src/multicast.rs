@@ -0,0 +1,330 @@
+use super::*;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+/// IGMP message types (RFC 1112, RFC 2236, RFC 3376).
+pub mod igmp_type {
+    pub const MEMBERSHIP_QUERY: u8 = 0x11;
+    pub const V1_MEMBERSHIP_REPORT: u8 = 0x12;
+    pub const V2_MEMBERSHIP_REPORT: u8 = 0x16;
+    pub const LEAVE_GROUP: u8 = 0x17;
+    pub const V3_MEMBERSHIP_REPORT: u8 = 0x22;
+}
+
+/// MLD message types (ICMPv6 types, RFC 2710 & RFC 3810).
+pub mod mld_type {
+    pub const MULTICAST_LISTENER_QUERY: u8 = 130;
+    pub const MULTICAST_LISTENER_REPORT: u8 = 131;
+    pub const MULTICAST_LISTENER_DONE: u8 = 132;
+    pub const V2_MULTICAST_LISTENER_REPORT: u8 = 143;
+}
+
+/// Record types of IGMPv3 & MLDv2 reports (RFC 3376 section 4.2.12).
+pub mod group_record_type {
+    pub const MODE_IS_INCLUDE: u8 = 1;
+    pub const MODE_IS_EXCLUDE: u8 = 2;
+    pub const CHANGE_TO_INCLUDE_MODE: u8 = 3;
+    pub const CHANGE_TO_EXCLUDE_MODE: u8 = 4;
+    pub const ALLOW_NEW_SOURCES: u8 = 5;
+    pub const BLOCK_OLD_SOURCES: u8 = 6;
+}
+
+/// Sources a host wants to receive the traffic of a group from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SourceFilter {
+    /// Only traffic from the listed sources is requested.
+    Include(Vec<IpAddr>),
+    /// Traffic from all but the listed sources is requested (an empty list
+    /// is a classic "any source" join).
+    Exclude(Vec<IpAddr>),
+}
+
+/// Normalized membership event of an IGMP or MLD message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MembershipEvent {
+    /// A host wants to receive the traffic of a group.
+    Join{
+        /// Address of the host that sent the report.
+        host: IpAddr,
+        group: IpAddr,
+        filter: SourceFilter,
+    },
+    /// A host no longer wants to receive the traffic of a group.
+    Leave{
+        /// Address of the host that sent the report.
+        host: IpAddr,
+        group: IpAddr,
+        /// Sources that are no longer requested (empty if the host leaves
+        /// the group completely).
+        sources: Vec<IpAddr>,
+    },
+    /// A querier asks for the memberships of the hosts on the link.
+    Query{
+        /// Address of the querier.
+        querier: IpAddr,
+        /// Queried group (None for general queries).
+        group: Option<IpAddr>,
+        /// Queried sources (only set in group & source specific queries).
+        sources: Vec<IpAddr>,
+        /// Maximum time a host can wait before responding.
+        max_response_time: Duration,
+    },
+}
+
+/// Extracts the membership events of an IGMP or MLD message contained in
+/// a sliced packet.
+///
+/// An empty list is returned if the packet does not contain an IGMP or MLD
+/// message. Messages & group records with unknown types are ignored. The
+/// checksum of the message is not verified.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv4Header, IpNumber, SlicedPacket};
+/// use etherparse::multicast::{membership_events, MembershipEvent, SourceFilter};
+///
+/// // IGMPv2 membership report for the group 239.1.2.3
+/// let report = [0x16, 0, 0, 0, 239, 1, 2, 3];
+/// let ip = Ipv4Header::new(report.len() as u16, 1, IpNumber::Igmp, [192,168,1,2], [239,1,2,3]);
+/// let mut packet = Vec::new();
+/// ip.write(&mut packet).unwrap();
+/// packet.extend_from_slice(&report);
+///
+/// let events = membership_events(&SlicedPacket::from_ip(&packet).unwrap()).unwrap();
+/// assert_eq!(
+///     vec![MembershipEvent::Join{
+///         host: [192,168,1,2].into(),
+///         group: [239,1,2,3].into(),
+///         filter: SourceFilter::Exclude(Vec::new()),
+///     }],
+///     events
+/// );
+/// ```
+pub fn membership_events(packet: &SlicedPacket) -> Result<Vec<MembershipEvent>, ReadError> {
+    match (&packet.ip, &packet.transport) {
+        (Some(InternetSlice::Ipv4(header, _)), Some(TransportSlice::Igmp(igmp))) => {
+            igmp_events(header.source(), igmp.slice())
+        },
+        (Some(InternetSlice::Ipv6(header, _)), Some(TransportSlice::Unknown(ip_number::IPV6_ICMP))) => {
+            mld_events(header.source(), packet.payload)
+        },
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Extracts the membership events of an IGMP message (starting with the
+/// IGMP type) sent by the given source address.
+pub fn igmp_events(source: [u8;4], message: &[u8]) -> Result<Vec<MembershipEvent>, ReadError> {
+    use ReadError::UnexpectedEndOfSlice;
+
+    const HEADER_LEN: usize = 8;
+    const V3_QUERY_LEN: usize = 12;
+
+    if message.len() < HEADER_LEN {
+        return Err(UnexpectedEndOfSlice(HEADER_LEN));
+    }
+    let host = IpAddr::V4(Ipv4Addr::from(source));
+    let group = IpAddr::V4(Ipv4Addr::from(read_ipv4(&message[4..])));
+    match message[0] {
+        igmp_type::MEMBERSHIP_QUERY => {
+            let (max_response_time, sources) = if message.len() >= V3_QUERY_LEN {
+                // IGMPv3 query
+                let count = usize::from(u16::from_be_bytes([message[10], message[11]]));
+                let end = V3_QUERY_LEN + count*4;
+                if message.len() < end {
+                    return Err(UnexpectedEndOfSlice(end));
+                }
+                let sources = message[V3_QUERY_LEN..end]
+                    .chunks_exact(4)
+                    .map(|chunk| IpAddr::V4(Ipv4Addr::from(read_ipv4(chunk))))
+                    .collect();
+                (Duration::from_millis(100*u64::from(decode_float_u8(message[1]))), sources)
+            } else if 0 == message[1] {
+                // IGMPv1 queries have no max response time (fixed to 10 seconds)
+                (Duration::from_secs(10), Vec::new())
+            } else {
+                (Duration::from_millis(100*u64::from(message[1])), Vec::new())
+            };
+            Ok(vec![MembershipEvent::Query{
+                querier: host,
+                group: if group.is_unspecified() { None } else { Some(group) },
+                sources,
+                max_response_time,
+            }])
+        },
+        igmp_type::V1_MEMBERSHIP_REPORT | igmp_type::V2_MEMBERSHIP_REPORT => {
+            Ok(vec![MembershipEvent::Join{ host, group, filter: SourceFilter::Exclude(Vec::new()) }])
+        },
+        igmp_type::LEAVE_GROUP => {
+            Ok(vec![MembershipEvent::Leave{ host, group, sources: Vec::new() }])
+        },
+        igmp_type::V3_MEMBERSHIP_REPORT => {
+            let count = usize::from(u16::from_be_bytes([message[6], message[7]]));
+            group_record_events(host, &message[HEADER_LEN..], count, 4)
+                .map_err(|err| err.add_slice_offset(HEADER_LEN))
+        },
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Extracts the membership events of an MLD message (starting with the
+/// ICMPv6 type) sent by the given source address.
+pub fn mld_events(source: [u8;16], message: &[u8]) -> Result<Vec<MembershipEvent>, ReadError> {
+    use ReadError::UnexpectedEndOfSlice;
+
+    const HEADER_LEN: usize = 8;
+    const V1_LEN: usize = 24;
+    const V2_QUERY_LEN: usize = 28;
+
+    let host = IpAddr::V6(Ipv6Addr::from(source));
+    let message_type = match message.first() {
+        Some(value) => *value,
+        None => return Err(UnexpectedEndOfSlice(HEADER_LEN)),
+    };
+    match message_type {
+        mld_type::MULTICAST_LISTENER_QUERY |
+        mld_type::MULTICAST_LISTENER_REPORT |
+        mld_type::MULTICAST_LISTENER_DONE => {
+            if message.len() < V1_LEN {
+                return Err(UnexpectedEndOfSlice(V1_LEN));
+            }
+            let group = IpAddr::V6(Ipv6Addr::from(read_ipv6(&message[HEADER_LEN..])));
+            let code = u16::from_be_bytes([message[4], message[5]]);
+            Ok(vec![match message_type {
+                mld_type::MULTICAST_LISTENER_QUERY => {
+                    let (max_response_time, sources) = if message.len() >= V2_QUERY_LEN {
+                        // MLDv2 query
+                        let count = usize::from(u16::from_be_bytes([message[26], message[27]]));
+                        let end = V2_QUERY_LEN + count*16;
+                        if message.len() < end {
+                            return Err(UnexpectedEndOfSlice(end));
+                        }
+                        let sources = message[V2_QUERY_LEN..end]
+                            .chunks_exact(16)
+                            .map(|chunk| IpAddr::V6(Ipv6Addr::from(read_ipv6(chunk))))
+                            .collect();
+                        (Duration::from_millis(u64::from(decode_float_u16(code))), sources)
+                    } else {
+                        (Duration::from_millis(u64::from(code)), Vec::new())
+                    };
+                    MembershipEvent::Query{
+                        querier: host,
+                        group: if group.is_unspecified() { None } else { Some(group) },
+                        sources,
+                        max_response_time,
+                    }
+                },
+                mld_type::MULTICAST_LISTENER_REPORT => MembershipEvent::Join{
+                    host,
+                    group,
+                    filter: SourceFilter::Exclude(Vec::new()),
+                },
+                _ => MembershipEvent::Leave{ host, group, sources: Vec::new() },
+            }])
+        },
+        mld_type::V2_MULTICAST_LISTENER_REPORT => {
+            if message.len() < HEADER_LEN {
+                return Err(UnexpectedEndOfSlice(HEADER_LEN));
+            }
+            let count = usize::from(u16::from_be_bytes([message[6], message[7]]));
+            group_record_events(host, &message[HEADER_LEN..], count, 16)
+                .map_err(|err| err.add_slice_offset(HEADER_LEN))
+        },
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Decodes the group records of IGMPv3 & MLDv2 reports (which only differ
+/// in the address length).
+fn group_record_events(host: IpAddr, mut records: &[u8], count: usize, address_len: usize) -> Result<Vec<MembershipEvent>, ReadError> {
+    use group_record_type::*;
+
+    let to_addr = |data: &[u8]| if 4 == address_len {
+        IpAddr::V4(Ipv4Addr::from(read_ipv4(data)))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(read_ipv6(data)))
+    };
+
+    let mut offset = 0;
+    let mut result = Vec::with_capacity(count);
+    for _ in 0..count {
+        // record type, aux data len, number of sources & multicast address
+        let header_len = 4 + address_len;
+        if records.len() < header_len {
+            return Err(ReadError::UnexpectedEndOfSlice(offset + header_len));
+        }
+        let record_type = records[0];
+        let aux_len = usize::from(records[1])*4;
+        let source_count = usize::from(u16::from_be_bytes([records[2], records[3]]));
+        let sources_end = header_len + source_count*address_len;
+        let len = sources_end + aux_len;
+        if records.len() < len {
+            return Err(ReadError::UnexpectedEndOfSlice(offset + len));
+        }
+        let group = to_addr(&records[4..]);
+        let sources: Vec<IpAddr> = records[header_len..sources_end]
+            .chunks_exact(address_len)
+            .map(to_addr)
+            .collect();
+
+        match record_type {
+            // an include mode without sources means the host left the group
+            MODE_IS_INCLUDE | CHANGE_TO_INCLUDE_MODE if sources.is_empty() => {
+                result.push(MembershipEvent::Leave{ host, group, sources });
+            },
+            MODE_IS_INCLUDE | CHANGE_TO_INCLUDE_MODE | ALLOW_NEW_SOURCES => {
+                result.push(MembershipEvent::Join{ host, group, filter: SourceFilter::Include(sources) });
+            },
+            MODE_IS_EXCLUDE | CHANGE_TO_EXCLUDE_MODE => {
+                result.push(MembershipEvent::Join{ host, group, filter: SourceFilter::Exclude(sources) });
+            },
+            BLOCK_OLD_SOURCES => {
+                result.push(MembershipEvent::Leave{ host, group, sources });
+            },
+            // unknown record types are ignored (RFC 3376 section 4.2.12)
+            _ => {},
+        }
+
+        offset += len;
+        records = &records[len..];
+    }
+    Ok(result)
+}
+
+/// Decodes the 8 bit floating point format of the IGMPv3 max response
+/// code (RFC 3376 section 4.1.1).
+fn decode_float_u8(code: u8) -> u32 {
+    if code < 128 {
+        u32::from(code)
+    } else {
+        let mantissa = u32::from(code & 0xf) | 0x10;
+        let exponent = u32::from((code >> 4) & 0x7);
+        mantissa << (exponent + 3)
+    }
+}
+
+/// Decodes the 16 bit floating point format of the MLDv2 max response
+/// code (RFC 3810 section 5.1.3).
+fn decode_float_u16(code: u16) -> u32 {
+    if code < 0x8000 {
+        u32::from(code)
+    } else {
+        let mantissa = u32::from(code & 0xfff) | 0x1000;
+        let exponent = u32::from((code >> 12) & 0x7);
+        mantissa << (exponent + 3)
+    }
+}
+
+fn read_ipv4(data: &[u8]) -> [u8;4] {
+    let mut result = [0u8;4];
+    result.copy_from_slice(&data[..4]);
+    result
+}
+
+fn read_ipv6(data: &[u8]) -> [u8;16] {
+    let mut result = [0u8;16];
+    result.copy_from_slice(&data[..16]);
+    result
+}
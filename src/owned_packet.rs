@@ -0,0 +1,166 @@
+use super::*;
+
+use std::convert::TryFrom;
+
+/// Decoded packet headers & a copy of the payload that do not borrow the
+/// original packet data (e.g. to send parsed packets to other threads).
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{OwnedPacket, SlicedPacket};
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+///
+/// // the owned packet can be moved to an other thread
+/// let handle = std::thread::spawn(move || owned.payload.len());
+/// assert_eq!(4, handle.join().unwrap());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnedPacket {
+    /// Ethernet II header if present (other link layer headers, e.g. Linux
+    /// cooked capture headers, are not copied).
+    pub link: Option<Ethernet2Header>,
+    /// Single or double vlan headers if present.
+    pub vlan: Option<VlanHeader>,
+    /// LLC header (including the SNAP header) if present.
+    pub llc: Option<LlcHeader>,
+    /// ARP packet if present.
+    pub arp: Option<ArpPacket>,
+    /// IPv4 or IPv6 header and IP extension headers if present.
+    pub ip: Option<IpHeader>,
+    /// TCP or UDP header if present.
+    pub transport: Option<TransportHeader>,
+    /// Tunnel header (e.g. GRE) if present.
+    pub tunnel: Option<TunnelHeader>,
+    /// Copy of the rest of the packet that could not be decoded as a header.
+    pub payload: Vec<u8>,
+    /// Copy of the trailer after the payload (see [`SlicedPacket::trailer`]).
+    /// Always empty if the packet was created from [`PacketHeaders`].
+    pub trailer: Vec<u8>,
+    /// Copy of the data after the end of the ip packet (see
+    /// [`SlicedPacket::padding`]). Always empty if the packet was created
+    /// from [`PacketHeaders`], which keep the padding in the payload.
+    pub padding: Vec<u8>,
+    /// The packet encapsulated in the tunnel payload (see [`SlicedPacket::inner`]).
+    pub inner: Option<Box<OwnedPacket>>,
+}
+
+impl OwnedPacket {
+    /// Decodes a packet starting with an Ethernet II header (see
+    /// [`PacketHeaders::from_ethernet_slice`]).
+    pub fn from_ethernet(data: &[u8]) -> Result<OwnedPacket, ReadError> {
+        PacketHeaders::from_ethernet_slice(data).map(OwnedPacket::from)
+    }
+
+    /// Decodes a packet starting with an IPv4 or IPv6 header (see
+    /// [`PacketHeaders::from_ip_slice`]).
+    pub fn from_ip(data: &[u8]) -> Result<OwnedPacket, ReadError> {
+        PacketHeaders::from_ip_slice(data).map(OwnedPacket::from)
+    }
+
+    /// Decodes the headers of a sliced packet & copies the payload.
+    ///
+    /// An error is only returned if the IPv6 extension headers in the
+    /// slice can not be represented by [`Ipv6Extensions`] (e.g. if an
+    /// extension header occurs multiple times).
+    pub fn from_sliced(sliced: &SlicedPacket) -> Result<OwnedPacket, ReadError> {
+        Ok(OwnedPacket {
+            link: sliced.link.as_ref().and_then(|link| link.to_header()),
+            vlan: sliced.vlan.as_ref().map(|vlan| {
+                let mut header = vlan.to_header();
+                // the tpid of the outermost vlan header is part of the link header
+                if let (Some(ether_type), Some(outer)) = (sliced.link.as_ref().and_then(|link| link.ether_type()), header.get_mut(0)) {
+                    outer.tpid = ether_type;
+                }
+                header
+            }),
+            llc: sliced.llc.as_ref().map(|llc| llc.to_header()),
+            arp: sliced.arp.as_ref().map(|arp| arp.to_packet()),
+            ip: match &sliced.ip {
+                Some(InternetSlice::Ipv4(header, extensions)) => Some(IpHeader::Version4(
+                    header.to_header(),
+                    extensions.to_header()
+                )),
+                Some(InternetSlice::Ipv6(header, extensions)) => Some(IpHeader::Version6(
+                    header.to_header(),
+                    Ipv6Extensions::from_slice(header.next_header(), extensions.slice())?.0
+                )),
+                None => None,
+            },
+            transport: match &sliced.transport {
+                Some(TransportSlice::Udp(header)) => Some(TransportHeader::Udp(header.to_header())),
+                Some(TransportSlice::Tcp(header)) => Some(TransportHeader::Tcp(header.to_header())),
+                Some(TransportSlice::Igmp(header)) => Some(TransportHeader::Igmp(header.to_header())),
+                Some(TransportSlice::Unknown(_)) | None => None,
+            },
+            tunnel: sliced.tunnel.as_ref().map(|tunnel| match tunnel {
+                TunnelSlice::Gre(header) => TunnelHeader::Gre(header.to_header()),
+                TunnelSlice::Vxlan(header) => TunnelHeader::Vxlan(header.to_header()),
+                TunnelSlice::VxlanGpe(header) => TunnelHeader::VxlanGpe(header.to_header()),
+                TunnelSlice::Geneve(header) => TunnelHeader::Geneve(header.to_header()),
+                TunnelSlice::Gtpu(header) => TunnelHeader::Gtpu(header.to_header()),
+                TunnelSlice::Gue(header) => TunnelHeader::Gue(header.to_header()),
+                TunnelSlice::Teredo(header) => TunnelHeader::Teredo(header.to_header()),
+                TunnelSlice::Esp(header) => TunnelHeader::Esp(header.to_header()),
+                TunnelSlice::Pppoe(header) => TunnelHeader::Pppoe(header.to_header()),
+                TunnelSlice::Pbb(header) => TunnelHeader::Pbb(header.to_header()),
+            }),
+            payload: sliced.payload.to_vec(),
+            trailer: sliced.trailer.to_vec(),
+            padding: sliced.padding.to_vec(),
+            inner: match &sliced.inner {
+                Some(inner) => Some(Box::new(OwnedPacket::from_sliced(inner)?)),
+                None => None,
+            },
+        })
+    }
+}
+
+impl<'a> From<PacketHeaders<'a>> for OwnedPacket {
+    fn from(value: PacketHeaders<'a>) -> OwnedPacket {
+        OwnedPacket {
+            link: value.link,
+            vlan: value.vlan,
+            llc: value.llc,
+            arp: value.arp,
+            ip: value.ip,
+            transport: value.transport,
+            tunnel: value.tunnel,
+            payload: value.payload.slice().to_vec(),
+            trailer: Vec::new(),
+            padding: Vec::new(),
+            inner: value.inner.map(|inner| Box::new(OwnedPacket::from(*inner))),
+        }
+    }
+}
+
+impl<'a> From<&PacketHeaders<'a>> for OwnedPacket {
+    fn from(value: &PacketHeaders<'a>) -> OwnedPacket {
+        OwnedPacket::from(value.clone())
+    }
+}
+
+impl<'a> TryFrom<&SlicedPacket<'a>> for OwnedPacket {
+    type Error = ReadError;
+
+    fn try_from(value: &SlicedPacket<'a>) -> Result<OwnedPacket, ReadError> {
+        OwnedPacket::from_sliced(value)
+    }
+}
+
+impl<'a> TryFrom<SlicedPacket<'a>> for OwnedPacket {
+    type Error = ReadError;
+
+    fn try_from(value: SlicedPacket<'a>) -> Result<OwnedPacket, ReadError> {
+        OwnedPacket::from_sliced(&value)
+    }
+}
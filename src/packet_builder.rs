@@ -1,6 +1,8 @@
 use super::*;
 
 use std::{io, marker};
+use std::convert::TryFrom;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 
 /// Helper for building packets.
 ///
@@ -78,7 +80,8 @@ impl PacketBuilder {
                 }),
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                pad_ethernet: false
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }
@@ -110,13 +113,14 @@ impl PacketBuilder {
     /// //serialize
     /// builder.write(&mut result, &payload).unwrap();
     /// ```
-    pub fn ipv4(source: [u8;4], destination: [u8;4], time_to_live: u8) -> PacketBuilderStep<IpHeader> {
+    pub fn ipv4(source: impl Into<Ipv4Addr>, destination: impl Into<Ipv4Addr>, time_to_live: u8) -> PacketBuilderStep<IpHeader> {
         PacketBuilderStep {
             state: PacketImpl {
                 ethernet2_header: None,
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                pad_ethernet: false
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ipv4(source, destination, time_to_live)
@@ -152,13 +156,14 @@ impl PacketBuilder {
     /// //serialize
     /// builder.write(&mut result, &payload).unwrap();
     /// ```
-    pub fn ipv6(source: [u8;16], destination: [u8;16], hop_limit: u8) -> PacketBuilderStep<IpHeader> {
+    pub fn ipv6(source: impl Into<Ipv6Addr>, destination: impl Into<Ipv6Addr>, hop_limit: u8) -> PacketBuilderStep<IpHeader> {
         PacketBuilderStep {
             state: PacketImpl {
                 ethernet2_header: None,
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                pad_ethernet: false
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ipv6(source, destination, hop_limit)
@@ -234,7 +239,8 @@ impl PacketBuilder {
                 ethernet2_header: None,
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                pad_ethernet: false
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ip(ip_header)
@@ -245,7 +251,8 @@ struct PacketImpl {
     ethernet2_header: Option<Ethernet2Header>,
     ip_header: Option<IpHeader>,
     vlan_header: Option<VlanHeader>,
-    transport_header: Option<TransportHeader>
+    transport_header: Option<TransportHeader>,
+    pad_ethernet: bool
 }
 
 ///An unfinished packet that is build with the packet builder
@@ -255,13 +262,40 @@ pub struct PacketBuilderStep<LastStep> {
 }
 
 impl PacketBuilderStep<Ethernet2Header> {
+    /// Pads frames smaller then [`Ethernet2Header::MIN_FRAME_LEN`] with zeros
+    /// after the payload (the length fields of the ip & udp headers do not
+    /// include the padding).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::*;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    ///    .pad_to_min_frame_len()
+    ///    .ipv4([192,168,1,1], [192,168,1,2], 20)
+    ///    .udp(21, 1234);
+    ///
+    /// let payload = [1,2,3,4];
+    /// assert_eq!(Ethernet2Header::MIN_FRAME_LEN, builder.size(payload.len()));
+    ///
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    /// builder.write(&mut result, &payload).unwrap();
+    /// assert_eq!(Ethernet2Header::MIN_FRAME_LEN, result.len());
+    /// ```
+    pub fn pad_to_min_frame_len(mut self) -> PacketBuilderStep<Ethernet2Header> {
+        self.state.pad_ethernet = true;
+        self
+    }
+
     ///Add an ip v4 header
-    pub fn ipv4(mut self, source: [u8;4], destination: [u8;4], time_to_live: u8) -> PacketBuilderStep<IpHeader> {
+    pub fn ipv4(mut self, source: impl Into<Ipv4Addr>, destination: impl Into<Ipv4Addr>, time_to_live: u8) -> PacketBuilderStep<IpHeader> {
         //add ip header
         self.state.ip_header = Some(IpHeader::Version4({
             let mut value: Ipv4Header = Default::default();
-            value.source = source;
-            value.destination = destination;
+            value.source = source.into().octets();
+            value.destination = destination.into().octets();
             value.time_to_live = time_to_live;
             value
         }, Default::default()));
@@ -327,15 +361,15 @@ impl PacketBuilderStep<Ethernet2Header> {
     }
 
     ///Add an ip v6 header
-    pub fn ipv6(mut self, source: [u8;16], destination: [u8;16], hop_limit: u8) -> PacketBuilderStep<IpHeader> {
+    pub fn ipv6(mut self, source: impl Into<Ipv6Addr>, destination: impl Into<Ipv6Addr>, hop_limit: u8) -> PacketBuilderStep<IpHeader> {
         self.state.ip_header = Some(IpHeader::Version6(Ipv6Header{
             traffic_class: 0,
             flow_label: 0,
             payload_length: 0, //filled in on write
             next_header: 0, //filled in on write
             hop_limit,
-            source,
-            destination
+            source: source.into().octets(),
+            destination: destination.into().octets()
         }, Default::default()));
         
         //return for next step
@@ -362,6 +396,7 @@ impl PacketBuilderStep<Ethernet2Header> {
             drop_eligible_indicator: false,
             vlan_identifier,
             ether_type: 0, //will be set automatically during write
+            tpid: ether_type::VLAN_TAGGED_FRAME,
         }));
         //return for next step
         PacketBuilderStep {
@@ -378,12 +413,14 @@ impl PacketBuilderStep<Ethernet2Header> {
                 drop_eligible_indicator: false,
                 vlan_identifier: outer_vlan_identifier,
                 ether_type: 0, //will be set automatically during write
+                tpid: ether_type::PROVIDER_BRIDGING,
             },
             inner: SingleVlanHeader {
                 priority_code_point: 0,
                 drop_eligible_indicator: false,
                 vlan_identifier: inner_vlan_identifier,
                 ether_type: 0, //will be set automatically during write
+                tpid: ether_type::VLAN_TAGGED_FRAME,
             }
         }));
         //return for next step
@@ -445,7 +482,7 @@ impl PacketBuilderStep<VlanHeader> {
     }
 
     ///Add a ip v6 header
-    pub fn ipv6(self, source: [u8;16], destination: [u8;16], hop_limit: u8) -> PacketBuilderStep<IpHeader> {
+    pub fn ipv6(self, source: impl Into<Ipv6Addr>, destination: impl Into<Ipv6Addr>, hop_limit: u8) -> PacketBuilderStep<IpHeader> {
         //use the method from the Ethernet2Header implementation
         PacketBuilderStep {
             state: self.state,
@@ -454,7 +491,7 @@ impl PacketBuilderStep<VlanHeader> {
     }
 
     ///Add a ip v4 header
-    pub fn ipv4(self, source: [u8;4], destination: [u8;4], time_to_live: u8) -> PacketBuilderStep<IpHeader> {
+    pub fn ipv4(self, source: impl Into<Ipv4Addr>, destination: impl Into<Ipv4Addr>, time_to_live: u8) -> PacketBuilderStep<IpHeader> {
         //use the method from the Ethernet2Header implementation
         PacketBuilderStep {
             state: self.state,
@@ -464,6 +501,41 @@ impl PacketBuilderStep<VlanHeader> {
 }
 
 impl PacketBuilderStep<IpHeader> {
+    /// Adds a "router alert" option with the given value to the ip header
+    /// (the ipv4 option defined in RFC 2113 or the ipv6 hop by hop option
+    /// defined in RFC 2711), as required for IGMP, MLD & RSVP messages.
+    ///
+    /// In case the option does not fit into the existing ipv4 options or
+    /// ipv6 hop by hop options header an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::*;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6([1;16], [0xff,2,0,0,0,0,0,0,0,0,0,0,0,0,0,0x16], 1)
+    ///    .router_alert(Ipv6RawExtensionHeader::ROUTER_ALERT_MLD)
+    ///    .unwrap()
+    ///    .udp(21, 1234);
+    ///
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    /// builder.write(&mut result, &[]).unwrap();
+    ///
+    /// let (_, rest) = Ipv6Header::from_slice(&result).unwrap();
+    /// let (extensions, _, _) = Ipv6Extensions::from_slice(ip_number::IPV6_HOP_BY_HOP, rest).unwrap();
+    /// assert_eq!(Some(Ipv6RawExtensionHeader::ROUTER_ALERT_MLD), extensions.router_alert());
+    /// ```
+    pub fn router_alert(mut self, value: u16) -> Result<PacketBuilderStep<IpHeader>, ValueError> {
+        use crate::IpHeader::*;
+        match self.state.ip_header.as_mut() {
+            Some(Version4(header, _)) => header.set_router_alert(value)?,
+            Some(Version6(_, extensions)) => extensions.set_router_alert(value)?,
+            None => {},
+        }
+        Ok(self)
+    }
+
     pub fn udp(mut self, source_port: u16, destination_port: u16) -> PacketBuilderStep<UdpHeader> {
         self.state.transport_header = Some(TransportHeader::Udp(UdpHeader{
             source_port,
@@ -488,6 +560,39 @@ impl PacketBuilderStep<IpHeader> {
             _marker: marker::PhantomData::<TcpHeader>{}
         }
     }
+
+    /// Add an udp header using the ports of the given socket addresses.
+    ///
+    /// Only the ports are taken from the socket addresses, the ip addresses
+    /// are the ones passed to the ip step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::*;
+    /// use std::net::SocketAddrV4;
+    ///
+    /// let source: SocketAddrV4 = "192.168.1.1:21".parse().unwrap();
+    /// let destination: SocketAddrV4 = "192.168.1.2:1234".parse().unwrap();
+    ///
+    /// let builder = PacketBuilder::
+    ///     ipv4(*source.ip(), *destination.ip(), 20)
+    ///    .udp_socket_addrs(source, destination);
+    ///
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    /// builder.write(&mut result, &[]).unwrap();
+    /// ```
+    pub fn udp_socket_addrs(self, source: impl Into<SocketAddr>, destination: impl Into<SocketAddr>) -> PacketBuilderStep<UdpHeader> {
+        self.udp(source.into().port(), destination.into().port())
+    }
+
+    /// Add a tcp header using the ports of the given socket addresses.
+    ///
+    /// Only the ports are taken from the socket addresses, the ip addresses
+    /// are the ones passed to the ip step.
+    pub fn tcp_socket_addrs(self, source: impl Into<SocketAddr>, destination: impl Into<SocketAddr>, sequence_number: u32, window_size: u16) -> PacketBuilderStep<TcpHeader> {
+        self.tcp(source.into().port(), destination.into().port(), sequence_number, window_size)
+    }
 }
 
 impl PacketBuilderStep<UdpHeader> {
@@ -590,6 +695,8 @@ impl PacketBuilderStep<TcpHeader> {
 ///Write all the headers and the payload.
 fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
     
+    let padding_len = final_padding_len(&builder, payload.len());
+
     let ip_ether_type = {
         use crate::IpHeader::*;
         match builder.state.ip_header {
@@ -603,11 +710,10 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
     if let Some(mut eth) = builder.state.ethernet2_header {
         eth.ether_type = {
             
-            use crate::VlanHeader::*;
             //determine the ether type depending on if there is a vlan tagging header
-            match builder.state.vlan_header {
-                Some(Single(_)) => ether_type::VLAN_TAGGED_FRAME,
-                Some(Double(_)) => ether_type::PROVIDER_BRIDGING,
+            match builder.state.vlan_header.as_ref().and_then(|vlan| vlan.get(0)) {
+                //tpid of the outermost vlan header
+                Some(outer) => outer.tpid,
                 //if no vlan header exists, the id is purely defined by the ip type
                 None => ip_ether_type
             }
@@ -626,11 +732,22 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
         },
         Some(Double(mut value)) => {
             //set ether types
-            value.outer.ether_type = ether_type::VLAN_TAGGED_FRAME;
+            value.outer.ether_type = value.inner.tpid;
             value.inner.ether_type = ip_ether_type;
             //serialize
             value.write(writer)?;
         },
+        Some(Multi(mut values)) => {
+            //set ether types (tpid of the following header or the ip type)
+            for index in 0..values.len() {
+                values[index].ether_type = match values.get(index + 1) {
+                    Some(next) => next.tpid,
+                    None => ip_ether_type,
+                };
+                //serialize
+                values[index].write(writer)?;
+            }
+        },
         None => {}
     }
 
@@ -648,44 +765,47 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
             use crate::TransportHeader::*;
             match transport {
                 Udp(ref mut udp) => { udp.length = transport_size as u16; }
-                Tcp(_) => {}
+                Tcp(_) | Igmp(_) => {}
             }
 
             //ip protocol number & next header values of the extension header
             ip.protocol = ext.set_next_headers(
                 match transport {
                     Udp(_) => ip_number::UDP,
-                    Tcp(_) => ip_number::TCP
+                    Tcp(_) => ip_number::TCP,
+                    Igmp(_) => ip_number::IGMP
                 }
             );
 
             //calculate the udp checksum
-            transport.update_checksum_ipv4(&ip, payload)?;
+            transport.update_checksum_ipv4_final_destination(&ip, payload)?;
 
             //write (will automatically calculate the checksum)
             ip.write(writer)?;
             ext.write(writer, ip.protocol)?
         },
         Version6(mut ip, mut ext) => {
-            //set total length
+            //set total length (switches to a jumbogram if the payload is too large)
             let transport_size = transport.header_len() + payload.len();
-            ip.set_payload_length(ext.header_len() + transport_size)?;
+            ip.set_payload_length_with_jumbo(&mut ext, transport_size)?;
             use crate::TransportHeader::*;
             match transport {
-                Udp(ref mut udp) => { udp.length = transport_size as u16; }
-                Tcp(_) => {}
+                //udp jumbograms have a length of 0 (RFC 2675)
+                Udp(ref mut udp) => { udp.length = u16::try_from(transport_size).unwrap_or(0); }
+                Tcp(_) | Igmp(_) => {}
             }
 
             //set the protocol
             ip.next_header = ext.set_next_headers(
                 match transport {
                     Udp(_) => ip_number::UDP as u8,
-                    Tcp(_) => ip_number::TCP as u8
+                    Tcp(_) => ip_number::TCP as u8,
+                    Igmp(_) => ip_number::IGMP
                 }
             );
 
             //calculate the udp checksum
-            transport.update_checksum_ipv6(&ip, payload)?;
+            transport.update_checksum_ipv6_final_destination(&ip, &ext, payload)?;
 
             //write (will automatically calculate the checksum)
             ip.write(writer)?;
@@ -696,30 +816,57 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
     //finaly write the udp header & payload
     transport.write(writer)?;
     writer.write_all(payload)?;
+
+    //ethernet padding (if enabled)
+    writer.write_all(&[0u8;Ethernet2Header::MIN_FRAME_LEN][..padding_len])?;
     Ok(())
 }
 
 ///Returns the size of the packet when it is serialized
 fn final_size<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
+    final_unpadded_size(builder, payload_size) + final_padding_len(builder, payload_size)
+}
+
+///Returns the number of zeros added after the payload to reach the minimum ethernet frame length
+fn final_padding_len<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
+    if builder.state.pad_ethernet {
+        Ethernet2Header::MIN_FRAME_LEN.saturating_sub(final_unpadded_size(builder, payload_size))
+    } else {
+        0
+    }
+}
+
+///Returns the size of the headers & payload (without the ethernet padding)
+fn final_unpadded_size<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
     use crate::IpHeader::*;
     use crate::VlanHeader::*;
     use crate::TransportHeader::*;
+    let transport_size = match builder.state.transport_header {
+        Some(Udp(_)) => UdpHeader::SERIALIZED_SIZE,
+        Some(Tcp(ref value)) => value.header_len() as usize,
+        Some(Igmp(ref value)) => value.header_len(),
+        None => 0
+    } + payload_size;
     (match builder.state.ethernet2_header {
         Some(_) => Ethernet2Header::SERIALIZED_SIZE,
         None => 0
     }) + match builder.state.vlan_header {
         Some(Single(_)) => SingleVlanHeader::SERIALIZED_SIZE,
         Some(Double(_)) => DoubleVlanHeader::SERIALIZED_SIZE,
+        Some(Multi(ref values)) => values.len()*SingleVlanHeader::SERIALIZED_SIZE,
         None => 0 
     } + match builder.state.ip_header {
         Some(Version4(ref value, ref ext)) => value.header_len() + ext.header_len(),
-        Some(Version6(_, ref ext)) => Ipv6Header::SERIALIZED_SIZE + ext.header_len(),
-        None => 0
-    } + match builder.state.transport_header {
-        Some(Udp(_)) => UdpHeader::SERIALIZED_SIZE,
-        Some(Tcp(ref value)) => value.header_len() as usize,
+        Some(Version6(ref value, ref ext)) => Ipv6Header::SERIALIZED_SIZE + if ext.header_len() + transport_size > usize::from(u16::MAX) {
+            //jumbograms contain an additional "Jumbo Payload" option
+            let mut ext = ext.clone();
+            let _ = value.clone().set_payload_length_with_jumbo(&mut ext, transport_size);
+            ext.header_len()
+        } else {
+            ext.header_len()
+        },
         None => 0
-    } + payload_size
+    } + transport_size
 }
 
 #[cfg(test)]
@@ -735,7 +882,8 @@ mod whitebox_tests {
                 ethernet2_header: None,
                 ip_header: None,
                 vlan_header: None,
-                transport_header: None
+                transport_header: None,
+                pad_ethernet: false
             },
             _marker: marker::PhantomData::<UdpHeader>{}
         }.size(0));
@@ -16,12 +16,40 @@ pub struct PacketHeaders<'a> {
     pub link: Option<Ethernet2Header>,
     /// Single or double vlan headers if present.
     pub vlan: Option<VlanHeader>,
+    /// LLC header (including the SNAP header) if present (IEEE 802.3 frames
+    /// with a length instead of an ether type).
+    pub llc: Option<LlcHeader>,
+    /// ARP packet if present (the ip & transport fields are `None` in this case).
+    pub arp: Option<ArpPacket>,
     /// IPv4 or IPv6 header and IP extension headers if present.
     pub ip: Option<IpHeader>,
     /// TCP or UDP header if present.
     pub transport: Option<TransportHeader>,
-    /// Rest of the packet that could not be decoded as a header (usually the payload).
-    pub payload: &'a [u8]
+    /// Tunnel header if present. GRE headers directly follow the ip header
+    /// (the transport field is `None` in this case), UDP based tunnels like
+    /// VXLAN, VXLAN-GPE, Geneve, GTP-U, GUE & Teredo follow the UDP header (only decoded if enabled via
+    /// [`ParseLimits::decode_udp_tunnels`]) & PPPoE headers follow the link
+    /// or vlan header (the ip field is `None` in this case). ESP headers
+    /// directly follow the ip header & precede the transport header in
+    /// transport mode (only decoded if enabled via
+    /// [`ParseLimits::esp_null_icv_len`]).
+    pub tunnel: Option<TunnelHeader>,
+    /// Rest of the packet that could not be decoded as a header, classified
+    /// by the protocol it belongs to.
+    pub payload: Payload<'a>,
+    /// The packet encapsulated in the tunnel payload, decoded based on the
+    /// protocol type of the tunnel header (`None` if no tunnel header is
+    /// present, the encapsulated protocol is not supported or the
+    /// encapsulated packet could not be decoded, e.g. because it is cut
+    /// off). Only exceeded [`ParseLimits`] of the encapsulated packet
+    /// result in an error.
+    ///
+    /// The `payload` field still contains the complete tunnel payload.
+    ///
+    /// Also contains IPv4 & IPv6 packets directly encapsulated in an ip
+    /// payload if enabled via [`ParseLimits::decode_ip_in_ip`] (the ip
+    /// payload is kept in the `payload` field).
+    pub inner: Option<Box<PacketHeaders<'a>>>,
 }
 
 impl<'a> PacketHeaders<'a> {
@@ -67,102 +95,16 @@ impl<'a> PacketHeaders<'a> {
     /// }
     /// ```
     pub fn from_ethernet_slice(packet: &[u8]) -> Result<PacketHeaders, ReadError> {
-        
-        let (ethernet, mut rest) = Ethernet2Header::from_slice(packet)?;
-        let mut ether_type = ethernet.ether_type;
-
-        let mut result = PacketHeaders{
-            link: Some(ethernet),
-            vlan: None,
-            ip: None,
-            transport: None,
-            payload: &[]
-        };
-
-        //parse vlan header(s)
-        use ether_type::*;
-
-        result.vlan = match ether_type {
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-                use crate::VlanHeader::*;
-                let (outer, outer_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                //set the rest & ether_type for the following operations
-                rest = outer_rest;
-                ether_type = outer.ether_type;
-
-                //parse second vlan header if present
-                match ether_type {
-                    //second vlan tagging header
-                    VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-
-                        let (inner, inner_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                        //set the rest & ether_type for the following operations
-                        rest = inner_rest;
-                        ether_type = inner.ether_type;
-
-                        Some(Double(DoubleVlanHeader{
-                            outer,
-                            inner
-                        }))
-                    },
-                    //no second vlan header detected -> single vlan header
-                    _ => Some(Single(outer))
-                }
-            },
-            //no vlan header
-            _ => None
-        };
-
-        //parse ip (if present)
-        match ether_type {
-            IPV4 => {
-                let (ip, ip_rest) = Ipv4Header::from_slice(rest)?;
-                let fragmented = ip.is_fragmenting_payload();
-                let (ip_ext, ip_protocol, ip_ext_rest) = Ipv4Extensions::from_slice(ip.protocol, ip_rest)?;
-
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version4(ip, ip_ext));
-
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(ip_protocol, rest)?;
-
-                    //assign to the output
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-            },
-            IPV6 => {
-                let (ip, ip_rest) = Ipv6Header::from_slice(rest)?;
-                let (ip_ext, next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
-                let fragmented = ip_ext.is_fragmenting_payload();
-
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version6(ip, ip_ext));
-
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(next_header, rest)?;
-
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-
-            },
-            _ => {}
-        }
-
-        //finally update the rest slice based on the cursor position
-        result.payload = rest;
+        PacketHeaders::from_ethernet_slice_with_limits(packet, ParseLimits::DEFAULT)
+    }
 
+    /// Tries to decode as much as possible of a packet (same as
+    /// [`PacketHeaders::from_ethernet_slice`]) & returns an error if the
+    /// packet exceeds one of the given [`ParseLimits`].
+    pub fn from_ethernet_slice_with_limits(packet: &[u8], limits: ParseLimits) -> Result<PacketHeaders<'_>, ReadError> {
+        let (ethernet, rest) = Ethernet2Header::from_slice(packet)?;
+        let mut result = PacketHeaders::from_ether_type_with_limits(ethernet.ether_type, rest, limits)?;
+        result.link = Some(ethernet);
         Ok(result)
     }
 
@@ -174,9 +116,15 @@ impl<'a> PacketHeaders<'a> {
     ///
     /// * `ether_type::IPV4`
     /// * `ether_type::IPV6`
+    /// * `ether_type::ARP`
     /// * `ether_type::VLAN_TAGGED_FRAME`
     /// * `ether_type::PROVIDER_BRIDGING`
     /// * `ether_type::VLAN_DOUBLE_TAGGED_FRAME`
+    /// * other ether types in [`ParseLimits::vlan_tpids`] (only for the `*_with_limits` variant)
+    /// * `ether_type::PPPOE_DISCOVERY`
+    /// * `ether_type::PPPOE_SESSION`
+    /// * `ether_type::PROVIDER_BACKBONE_BRIDGING`
+    /// * values smaller then [`LlcHeader::ETHER_TYPE_MIN`] (length of an IEEE 802.3 frame starting with a LLC header)
     ///
     /// If an unsupported ether type is given the given slice will be set as payload
     /// and all other fields will be set to `None`.
@@ -218,100 +166,154 @@ impl<'a> PacketHeaders<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ether_type(mut ether_type: u16, data: &'a [u8]) -> Result<PacketHeaders, ReadError> {
+    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<PacketHeaders, ReadError> {
+        PacketHeaders::from_ether_type_with_limits(ether_type, data, ParseLimits::DEFAULT)
+    }
+
+    /// Tries to decode a network packet into different headers using the
+    /// given `ether_type` number to identify the first header (same as
+    /// [`PacketHeaders::from_ether_type`]) & returns an error if the packet
+    /// exceeds one of the given [`ParseLimits`].
+    pub fn from_ether_type_with_limits(mut ether_type: u16, data: &'a [u8], limits: ParseLimits) -> Result<PacketHeaders<'a>, ReadError> {
         let mut rest = data;
         let mut result = PacketHeaders{
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             transport: None,
-            payload: &[]
+            tunnel: None,
+            payload: Payload::Ether{ ether_type, payload: &[] },
+            inner: None,
         };
 
-        //parse vlan header(s)
         use ether_type::*;
 
-        result.vlan = match ether_type {
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-                use crate::VlanHeader::*;
-                let (outer, outer_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                //set the rest & ether_type for the following operations
-                rest = outer_rest;
-                ether_type = outer.ether_type;
-
-                //parse second vlan header if present
-                match ether_type {
-                    //second vlan tagging header
-                    VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-
-                        let (inner, inner_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                        //set the rest & ether_type for the following operations
-                        rest = inner_rest;
-                        ether_type = inner.ether_type;
+        //parse vlan header(s)
+        while limits.is_vlan_tpid(ether_type) {
+            use crate::VlanHeader::*;
+            let depth = result.vlan.as_ref().map(|vlan| vlan.depth()).unwrap_or(0) + 1;
+            limits.check_vlan_depth(depth)?;
+            let (mut header, header_rest) = SingleVlanHeader::from_slice(rest)?;
+            header.tpid = ether_type;
+
+            //set the rest & ether_type for the following operations
+            rest = header_rest;
+            ether_type = header.ether_type;
+
+            result.vlan = Some(match result.vlan.take() {
+                None => Single(header),
+                Some(Single(outer)) => Double(DoubleVlanHeader{
+                    outer,
+                    inner: header
+                }),
+                Some(Double(double)) => Multi(vec![double.outer, double.inner, header]),
+                Some(Multi(mut headers)) => {
+                    headers.push(header);
+                    Multi(headers)
+                },
+            });
+        }
 
-                        Some(Double(DoubleVlanHeader{
-                            outer,
-                            inner
-                        }))
-                    },
-                    //no second vlan header detected -> single vlan header
-                    _ => Some(Single(outer))
-                }
-            },
-            //no vlan header
-            _ => None
-        };
+        //parse llc header (if the ether type field contains a length)
+        if ether_type < LlcHeader::ETHER_TYPE_MIN {
+            let (llc, llc_rest) = LlcHeader::from_slice(rest)?;
+            rest = llc_rest;
+            if let Some(value) = llc.ether_type() {
+                ether_type = value;
+            }
+            result.llc = Some(llc);
+        }
 
         //parse ip (if present)
         match ether_type {
             IPV4 => {
                 let (ip, ip_rest) = Ipv4Header::from_slice(rest)?;
                 let fragmented = ip.is_fragmenting_payload();
+                let (fragment_offset, more_fragments) = (ip.fragments_offset, ip.more_fragments);
                 let (ip_ext, ip_protocol, ip_ext_rest) = Ipv4Extensions::from_slice(ip.protocol, ip_rest)?;
 
                 //set the ip result & rest
                 rest = ip_ext_rest;
+                let payload_len = usize::from(ip.payload_len).saturating_sub(ip_ext.header_len());
                 result.ip = Some(IpHeader::Version4(ip, ip_ext));
 
                 // only try to decode the transport layer if the payload
                 // is not fragmented
                 if false == fragmented {
                     //parse the transport layer
-                    let (transport, transport_rest) = read_transport(ip_protocol, rest)?;
-
-                    //assign to the output
-                    rest = transport_rest;
-                    result.transport = transport;
+                    result.read_ip_payload(ip_protocol, rest, payload_len, limits)?;
+                } else {
+                    result.payload = Payload::IpFragment{
+                        ip_number: ip_protocol,
+                        fragment_offset,
+                        more_fragments,
+                        payload: rest,
+                    };
                 }
             },
             IPV6 => {
                 let (ip, ip_rest) = Ipv6Header::from_slice(rest)?;
                 let (ip_ext, next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
                 let fragmented = ip_ext.is_fragmenting_payload();
+                let (fragment_offset, more_fragments) = ip_ext.fragment.as_ref()
+                    .map(|f| (f.fragment_offset, f.more_fragments))
+                    .unwrap_or((0, false));
 
                 //set the ip result & rest
                 rest = ip_ext_rest;
+                let payload_len = ip.payload_len_with_jumbo(&ip_ext).saturating_sub(ip_ext.header_len());
                 result.ip = Some(IpHeader::Version6(ip, ip_ext));
 
                 // only try to decode the transport layer if the payload
                 // is not fragmented
                 if false == fragmented {
                     //parse the transport layer
-                    let (transport, transport_rest) = read_transport(next_header, rest)?;
-
-                    rest = transport_rest;
-                    result.transport = transport;
+                    result.read_ip_payload(next_header, rest, payload_len, limits)?;
+                } else {
+                    result.payload = Payload::IpFragment{
+                        ip_number: next_header,
+                        fragment_offset,
+                        more_fragments,
+                        payload: rest,
+                    };
                 }
 
             },
-            _ => {}
+            ARP => {
+                let (arp, arp_rest) = ArpPacket::from_slice(rest)?;
+                result.arp = Some(arp);
+                // data after the ARP packet (e.g. ethernet padding)
+                result.payload = Payload::Ether{ ether_type, payload: arp_rest };
+            },
+            PPPOE_DISCOVERY | PPPOE_SESSION => {
+                let (pppoe, pppoe_rest) = PppoeHeader::from_slice(rest)?;
+                let inner_ether_type = pppoe.ppp_protocol.and_then(crate::tunnel::pppoe::ppp_protocol_ether_type);
+                result.tunnel = Some(TunnelHeader::Pppoe(pppoe));
+                match inner_ether_type {
+                    Some(value) => result.read_tunnel_payload(value, pppoe_rest, limits)?,
+                    None => result.payload = Payload::Ether{ ether_type, payload: pppoe_rest },
+                }
+            },
+            PROVIDER_BACKBONE_BRIDGING => {
+                let (pbb, pbb_rest) = PbbItagHeader::from_slice(rest)?;
+                let inner_ether_type = pbb.ether_type;
+                result.tunnel = Some(TunnelHeader::Pbb(pbb));
+                result.read_tunnel_payload(inner_ether_type, pbb_rest, limits)?;
+            },
+            _ => {
+                result.payload = match &result.llc {
+                    Some(llc) if llc.is_stp() => Payload::Stp{
+                        bpdu: StpBpduSlice::from_slice(rest)?,
+                        payload: rest,
+                    },
+                    _ => Payload::Ether{ ether_type, payload: rest },
+                };
+            }
         }
 
-        //finally update the rest slice based on the cursor position
-        result.payload = rest;
-
+        result.check_limits(limits)?;
         Ok(result)
     }
 
@@ -356,43 +358,278 @@ impl<'a> PacketHeaders<'a> {
     /// }
     /// ```
     pub fn from_ip_slice(packet: &[u8]) -> Result<PacketHeaders, ReadError> {
+        PacketHeaders::from_ip_slice_with_limits(packet, ParseLimits::DEFAULT)
+    }
+
+    /// Tries to decode an ip packet and its transport headers (same as
+    /// [`PacketHeaders::from_ip_slice`]) & returns an error if the packet
+    /// exceeds one of the given [`ParseLimits`].
+    pub fn from_ip_slice_with_limits(packet: &[u8], limits: ParseLimits) -> Result<PacketHeaders<'_>, ReadError> {
         let mut result = PacketHeaders {
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             transport: None,
-            payload: &[],
+            tunnel: None,
+            payload: Payload::Unknown(0, &[]),
+            inner: None,
         };
 
-        let (transport_proto, rest) = {
+        let (transport_proto, rest, payload_len, fragment) = {
             let (ip, transport_proto, rest) = IpHeader::from_slice(packet)?;
+            let (payload_len, fragment) = match &ip {
+                IpHeader::Version4(header, ext) => (
+                    usize::from(header.payload_len).saturating_sub(ext.header_len()),
+                    if header.is_fragmenting_payload() {
+                        Some((header.fragments_offset, header.more_fragments))
+                    } else {
+                        None
+                    }
+                ),
+                IpHeader::Version6(header, ext) => (
+                    header.payload_len_with_jumbo(ext).saturating_sub(ext.header_len()),
+                    if ext.is_fragmenting_payload() {
+                        ext.fragment.as_ref().map(|f| (f.fragment_offset, f.more_fragments))
+                    } else {
+                        None
+                    }
+                ),
+            };
             // update output
             result.ip = Some(ip);
-            (transport_proto, rest)
+            (transport_proto, rest, payload_len, fragment)
         };
 
-        // try to parse the transport header
-        let (transport, rest) = read_transport(transport_proto, rest)?;
-
-        // update output
-        result.transport = transport;
-        result.payload = rest;
+        // only try to parse the transport header if the payload
+        // is not fragmented
+        match fragment {
+            None => result.read_ip_payload(transport_proto, rest, payload_len, limits)?,
+            Some((fragment_offset, more_fragments)) => {
+                result.payload = Payload::IpFragment{
+                    ip_number: transport_proto,
+                    fragment_offset,
+                    more_fragments,
+                    payload: rest,
+                };
+            },
+        }
 
+        result.check_limits(limits)?;
         Ok(result)
     }
+
+    /// Decodes the transport or tunnel header (including the encapsulated
+    /// packet) of an unfragmented ip payload & sets the payload.
+    fn read_ip_payload(&mut self, protocol: u8, rest: &'a [u8], ip_payload_len: usize, limits: ParseLimits) -> Result<(), ReadError> {
+        if let (ip_number::ENCAP_SEC, Some(icv_len)) = (protocol, limits.esp_null_icv_len) {
+            // limit to the ip payload as the trailer is located at its end
+            let (esp, esp_rest) = EspHeader::from_slice(&rest[..std::cmp::min(ip_payload_len, rest.len())])?;
+            let null = EspNullSlice::from_slice(esp_rest, icv_len)?;
+            let payload = null.payload();
+            self.tunnel = Some(TunnelHeader::Esp(esp));
+            return match null.next_header() {
+                ip_number::IPV4 => self.read_tunnel_payload(ether_type::IPV4, payload, limits),
+                ip_number::IPV6 => self.read_tunnel_payload(ether_type::IPV6, payload, limits),
+                next_header => self.read_ip_payload(next_header, payload, payload.len(), limits),
+            };
+        }
+        if ip_number::GRE == protocol {
+            match GreHeader::from_slice(rest) {
+                Ok((gre, gre_rest)) => {
+                    let protocol_type = gre.protocol_type;
+                    self.tunnel = Some(TunnelHeader::Gre(gre));
+                    return self.read_tunnel_payload(protocol_type, gre_rest, limits);
+                },
+                // unsupported GRE versions are treated as unknown protocol
+                Err(ReadError::GreUnsupportedVersion(_)) => {},
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (transport, payload) = read_transport(protocol, rest, ip_payload_len)?;
+        self.transport = transport;
+        self.payload = payload;
+
+        // ip packets encapsulated in ip packets (if enabled)
+        if limits.decode_ip_in_ip {
+            match protocol {
+                ip_number::IPV4 => return self.read_inner(ether_type::IPV4, rest, limits),
+                ip_number::IPV6 => return self.read_inner(ether_type::IPV6, rest, limits),
+                _ => {},
+            }
+        }
+
+        // udp based tunnels (if enabled)
+        if let (Some(TransportHeader::Udp(udp)), Payload::Udp(udp_payload)) = (&self.transport, &self.payload) {
+            if limits.decode_udp_tunnels && VxlanHeader::PORT == udp.destination_port {
+                let (vxlan, vxlan_rest) = VxlanHeader::from_slice(udp_payload)?;
+                self.tunnel = Some(TunnelHeader::Vxlan(vxlan));
+                return self.read_tunnel_payload(ether_type::TRANSPARENT_ETHERNET_BRIDGING, vxlan_rest, limits);
+            }
+            if limits.decode_udp_tunnels && VxlanGpeHeader::PORT == udp.destination_port {
+                let (vxlan_gpe, vxlan_gpe_rest) = VxlanGpeHeader::from_slice(udp_payload)?;
+                let payload_ether_type = vxlan_gpe.payload_ether_type();
+                self.tunnel = Some(TunnelHeader::VxlanGpe(vxlan_gpe));
+                return match payload_ether_type {
+                    Some(ether_type) => self.read_tunnel_payload(ether_type, vxlan_gpe_rest, limits),
+                    None => {
+                        self.payload = Payload::Udp(vxlan_gpe_rest);
+                        Ok(())
+                    },
+                };
+            }
+            if limits.decode_udp_tunnels && GeneveHeader::PORT == udp.destination_port {
+                let (geneve, geneve_rest) = GeneveHeader::from_slice(udp_payload)?;
+                let protocol_type = geneve.protocol_type;
+                self.tunnel = Some(TunnelHeader::Geneve(geneve));
+                return self.read_tunnel_payload(protocol_type, geneve_rest, limits);
+            }
+            if limits.decode_udp_tunnels && GtpuHeader::PORT == udp.destination_port {
+                let (gtpu, gtpu_rest) = GtpuHeader::from_slice(udp_payload)?;
+                let inner_ether_type = crate::tunnel::gtpu::payload_ether_type(gtpu.message_type, gtpu_rest);
+                self.tunnel = Some(TunnelHeader::Gtpu(gtpu));
+                return match inner_ether_type {
+                    Some(ether_type) => self.read_tunnel_payload(ether_type, gtpu_rest, limits),
+                    None => {
+                        self.payload = Payload::Udp(gtpu_rest);
+                        Ok(())
+                    },
+                };
+            }
+            if limits.decode_udp_tunnels && GueHeader::PORT == udp.destination_port {
+                match GueHeader::variant(udp_payload) {
+                    Some(0) => {
+                        let (gue, gue_rest) = GueHeader::from_slice(udp_payload)?;
+                        let payload_ether_type = gue.payload_ether_type();
+                        self.tunnel = Some(TunnelHeader::Gue(gue));
+                        return match payload_ether_type {
+                            Some(ether_type) => self.read_tunnel_payload(ether_type, gue_rest, limits),
+                            None => {
+                                self.payload = Payload::Udp(gue_rest);
+                                Ok(())
+                            },
+                        };
+                    },
+                    // ip packets directly encapsulated in udp (no gue header)
+                    Some(1) => {
+                        if let Some(ether_type) = crate::tunnel::gue::direct_ip_ether_type(udp_payload) {
+                            return self.read_tunnel_payload(ether_type, udp_payload, limits);
+                        }
+                    },
+                    _ => {},
+                }
+            }
+            if limits.decode_udp_tunnels && (TeredoHeader::PORT == udp.destination_port || TeredoHeader::PORT == udp.source_port) {
+                let (teredo, teredo_rest) = TeredoHeader::from_slice(udp_payload)?;
+                if crate::tunnel::teredo::is_ipv6(teredo_rest) {
+                    self.tunnel = Some(TunnelHeader::Teredo(teredo));
+                    return self.read_tunnel_payload(ether_type::IPV6, teredo_rest, limits);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the tunnel payload & decodes the encapsulated packet if the
+    /// given ether type is supported.
+    fn read_tunnel_payload(&mut self, ether_type: u16, payload: &'a [u8], limits: ParseLimits) -> Result<(), ReadError> {
+        self.read_inner(ether_type, payload, limits)?;
+        self.payload = Payload::Ether{ ether_type, payload };
+        Ok(())
+    }
+
+    /// Decodes the encapsulated packet if the given ether type is supported
+    /// (the payload is left unchanged). Errors in the encapsulated packet
+    /// leave `inner` empty, only exceeded limits are returned.
+    fn read_inner(&mut self, ether_type: u16, payload: &'a [u8], limits: ParseLimits) -> Result<(), ReadError> {
+        if crate::tunnel::is_decodable_ether_type(ether_type, &limits) {
+            let inner_limits = limits.enter_tunnel()?;
+            let inner = if ether_type::TRANSPARENT_ETHERNET_BRIDGING == ether_type {
+                PacketHeaders::from_ethernet_slice_with_limits(payload, inner_limits)
+            } else {
+                PacketHeaders::from_ether_type_with_limits(ether_type, payload, inner_limits)
+            };
+            match inner {
+                Ok(inner) => self.inner = Some(Box::new(inner)),
+                Err(err @ ReadError::ParseLimitExceeded(_)) => return Err(err),
+                Err(_) => {},
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the limits applying to the decoded ip & transport headers
+    /// (the vlan depth is checked while decoding).
+    fn check_limits(&self, limits: ParseLimits) -> Result<(), ReadError> {
+        if let Some(ip) = &self.ip {
+            limits.check_ip_header(ip)?;
+        }
+        if let Some(transport) = &self.transport {
+            limits.check_transport_header(transport)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the summed up serialized length of all present headers
+    /// (link, vlan, llc, arp, ip including the ip extensions, transport & tunnel) in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    /// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    /// use etherparse::PacketHeaders;
+    ///
+    /// let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    /// // ethernet II (14) + ipv4 (20) + udp (8)
+    /// assert_eq!(42, headers.header_len());
+    /// assert_eq!(packet.len(), headers.total_len(headers.payload.len()));
+    /// ```
+    pub fn header_len(&self) -> usize {
+        self.link.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        self.vlan.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        self.llc.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        self.arp.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        self.ip.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        self.transport.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        self.tunnel.as_ref().map(|v| v.header_len()).unwrap_or(0)
+    }
+
+    /// Returns the length of the serialized packet in bytes if the headers
+    /// are followed by a payload with the given length.
+    #[inline]
+    pub fn total_len(&self, payload_len: usize) -> usize {
+        self.header_len() + payload_len
+    }
 }
 
 /// helper function to process transport headers
 fn read_transport(
     protocol: u8,
     rest: &[u8],
-) -> Result<(Option<TransportHeader>, &[u8]), ReadError> {
+    ip_payload_len: usize,
+) -> Result<(Option<TransportHeader>, Payload<'_>), ReadError> {
     use crate::ip_number::*;
     match protocol {
         UDP => Ok(UdpHeader::from_slice(rest)
-            .map(|value| (Some(TransportHeader::Udp(value.0)), value.1))?),
+            .map(|value| (Some(TransportHeader::Udp(value.0)), Payload::Udp(value.1)))?),
         TCP => Ok(TcpHeader::from_slice(rest)
-            .map(|value| (Some(TransportHeader::Tcp(value.0)), value.1))?),
-        _ => Ok((None, rest)),
+            .map(|value| (Some(TransportHeader::Tcp(value.0)), Payload::Tcp(value.1)))?),
+        IGMP => {
+            // limit to the ip payload (data after it, e.g. ethernet padding, is part of the payload)
+            let header = IgmpSlice::from_slice(&rest[..std::cmp::min(ip_payload_len, rest.len())])?;
+            Ok((
+                Some(TransportHeader::Igmp(header.to_header())),
+                Payload::Igmp(&rest[header.slice().len()..])
+            ))
+        },
+        _ => Ok((None, Payload::from_ip_number(protocol, rest))),
     }
 }
\ No newline at end of file
@@ -76,7 +76,8 @@ impl LinkFilter {
                 }) && (match expected_destination {
                     Some(e) => header.destination() == *e,
                     None => true
-                })
+                }),
+                _ => false
             }
         }
     }
@@ -1,5 +1,7 @@
 use super::*;
 
+use std::net::IpAddr;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum InternetSlice<'a> {
     /// The ipv6 header & the decoded extension headers.
@@ -8,16 +10,151 @@ pub enum InternetSlice<'a> {
     Ipv6(Ipv6HeaderSlice<'a>, Ipv6ExtensionsSlice<'a>),
 }
 
+impl<'a> InternetSlice<'a> {
+    /// Returns the slice containing the ipv4 or ipv6 header (without
+    /// the extension headers).
+    #[inline]
+    pub fn header_slice(&self) -> &'a [u8] {
+        use InternetSlice::*;
+        match self {
+            Ipv4(header, _) => header.slice(),
+            Ipv6(header, _) => header.slice(),
+        }
+    }
+
+    /// Returns the slice containing the ip extension headers (empty
+    /// if no extension headers are present).
+    #[inline]
+    pub fn extensions_slice(&self) -> &'a [u8] {
+        use InternetSlice::*;
+        match self {
+            Ipv4(_, exts) => exts.slice(),
+            Ipv6(_, exts) => exts.slice(),
+        }
+    }
+
+    /// Returns the source address of the ipv4 or ipv6 header.
+    pub fn source_addr(&self) -> IpAddr {
+        use InternetSlice::*;
+        match self {
+            Ipv4(header, _) => IpAddr::V4(header.source_addr()),
+            Ipv6(header, _) => IpAddr::V6(header.source_addr()),
+        }
+    }
+
+    /// Returns the destination address of the ipv4 or ipv6 header.
+    pub fn destination_addr(&self) -> IpAddr {
+        use InternetSlice::*;
+        match self {
+            Ipv4(header, _) => IpAddr::V4(header.destination_addr()),
+            Ipv6(header, _) => IpAddr::V6(header.destination_addr()),
+        }
+    }
+
+    /// Returns the ip protocol number of the ip payload (the last
+    /// "next header" value after the supported extension headers).
+    pub fn payload_ip_number(&self) -> u8 {
+        use InternetSlice::*;
+        match self {
+            Ipv4(header, exts) => match &exts.auth {
+                Some(auth) => auth.next_header(),
+                None => header.protocol(),
+            },
+            Ipv6(header, exts) => {
+                use Ipv6ExtensionSlice::*;
+                exts.clone().into_iter().last().map(|ext| match ext {
+                    HopByHop(s) | Routing(s) | DestinationOptions(s) => s.next_header(),
+                    Fragment(s) => s.next_header(),
+                    Authentication(s) => s.next_header(),
+                }).unwrap_or_else(|| header.next_header())
+            },
+        }
+    }
+
+    /// Returns the fragmentation information if the ip payload is
+    /// fragmented (None if the packet is not a fragment).
+    ///
+    /// For IPv6 the first fragment header in the extension headers
+    /// is used.
+    pub fn fragment_info(&self) -> Option<IpFragmentInfo> {
+        use InternetSlice::*;
+        match self {
+            Ipv4(header, _) => if header.is_fragment() {
+                Some(IpFragmentInfo{
+                    identification: u32::from(header.identification()),
+                    byte_offset: header.fragment_byte_offset(),
+                    more_fragments: header.more_fragments(),
+                    payload_ip_number: self.payload_ip_number(),
+                })
+            } else {
+                None
+            },
+            Ipv6(_, exts) => exts.clone().into_iter().find_map(|ext| match ext {
+                Ipv6ExtensionSlice::Fragment(frag) if frag.is_fragment() => Some(IpFragmentInfo{
+                    identification: frag.identification(),
+                    byte_offset: frag.fragment_byte_offset(),
+                    more_fragments: frag.more_fragments(),
+                    payload_ip_number: frag.next_header(),
+                }),
+                _ => None,
+            }),
+        }
+    }
+}
+
+/// Fragmentation information of an IPv4 or IPv6 packet (see
+/// [`InternetSlice::fragment_info`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IpFragmentInfo {
+    /// Identification of the fragmented packet (for IPv4 the 16 bit
+    /// identification field).
+    pub identification: u32,
+    /// Offset of the fragment data in the reassembled payload in bytes.
+    pub byte_offset: u16,
+    /// True if more fragments follow.
+    pub more_fragments: bool,
+    /// IP number of the fragmented payload (for IPv6 the "next header"
+    /// value of the fragment header).
+    pub payload_ip_number: u8,
+}
+
+/// Header in a chain of ip protocol numbers (see
+/// [`SlicedPacket::ip_number_chain`] & [`Ipv6ExtensionsSlice::ip_number_chain`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IpNumberChainEntry {
+    /// IP protocol number identifying the header.
+    pub ip_number: u8,
+    /// Byte range of the header (empty if the header was not decoded).
+    pub range: std::ops::Range<usize>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TransportSlice<'a> {
     /// A slice containing an UDP header.
     Udp(UdpHeaderSlice<'a>),
     /// A slice containing a TCP header.
     Tcp(TcpHeaderSlice<'a>),
+    /// A slice containing an IGMP message.
+    Igmp(IgmpSlice<'a>),
     /// Unknonwn transport layer protocol. The value is the last parsed ip protocol number.
     Unknown(u8),
 }
 
+impl<'a> TransportSlice<'a> {
+    /// Returns the slice containing the transport header (empty
+    /// in case of an unknown transport protocol).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        use TransportSlice::*;
+        match self {
+            Udp(header) => header.slice(),
+            Tcp(header) => header.slice(),
+            Igmp(header) => header.slice(),
+            Unknown(_) => &[],
+        }
+    }
+}
+
 /// Packet slice split into multiple slices containing the different headers & payload.
 ///
 /// Everything that could not be parsed is stored in a slice in the field "payload".
@@ -66,10 +203,24 @@ pub struct SlicedPacket<'a> {
     pub link: Option<LinkSlice<'a>>,
     /// Single or double vlan headers if present.
     pub vlan: Option<VlanSlice<'a>>,
+    /// LLC header (including the SNAP header) if present (IEEE 802.3 frames
+    /// with a length instead of an ether type & 802.11 data frames).
+    pub llc: Option<LlcHeaderSlice<'a>>,
+    /// ARP packet if present (the ip & transport fields are `None` in this case).
+    pub arp: Option<ArpPacketSlice<'a>>,
     /// IPv4 or IPv6 header and IP extension headers if present.
     pub ip: Option<InternetSlice<'a>>,
     /// TCP or UDP header if present.
     pub transport: Option<TransportSlice<'a>>,
+    /// Tunnel header if present. GRE headers directly follow the ip header
+    /// (the transport field is `None` in this case), UDP based tunnels like
+    /// VXLAN, VXLAN-GPE, Geneve, GTP-U, GUE & Teredo follow the UDP header (only decoded if enabled via
+    /// [`ParseLimits::decode_udp_tunnels`]) & PPPoE headers follow the link
+    /// or vlan header (the ip field is `None` in this case). ESP headers
+    /// directly follow the ip header & precede the transport header in
+    /// transport mode (only decoded if enabled via
+    /// [`ParseLimits::esp_null_icv_len`]).
+    pub tunnel: Option<TunnelSlice<'a>>,
     /// The payload field points to the rest of the packet that could not be parsed by etherparse.
     ///
     /// Depending on what other fields contain a "Some" values the payload contains the corresponding 
@@ -77,8 +228,30 @@ pub struct SlicedPacket<'a> {
     ///
     /// For example if transport field contains Some(Udp(_)) then the payload field points to the udp payload.
     /// On the other hand if the transport field contains None then the payload contains the payload of
-    /// next field containing a Some value (in order of transport, ip, vlan, link).
-    pub payload: &'a [u8]
+    /// next field containing a Some value (in order of tunnel, transport, ip, arp, vlan, link).
+    pub payload: &'a [u8],
+    /// Trailer located between the payload & the padding (the ESP trailer
+    /// & integrity check value of NULL encrypted ESP packets decoded via
+    /// [`ParseLimits::esp_null_icv_len`], empty otherwise).
+    pub trailer: &'a [u8],
+    /// Data after the end of the IPv4 or IPv6 packet (e.g. ethernet padding
+    /// or the FCS). The `payload` field is limited to the length given in
+    /// the ip header, the rest of the data is placed in this field (empty
+    /// if no ip header is present).
+    pub padding: &'a [u8],
+    /// The packet encapsulated in the tunnel payload, sliced based on the
+    /// protocol type of the tunnel header (`None` if no tunnel header is
+    /// present, the encapsulated protocol is not supported or the
+    /// encapsulated packet could not be sliced, e.g. because it is cut off).
+    /// Only exceeded [`ParseLimits`] of the encapsulated packet result in
+    /// an error.
+    ///
+    /// The `payload` field still points to the complete tunnel payload.
+    ///
+    /// Also contains IPv4 & IPv6 packets directly encapsulated in an ip
+    /// payload if enabled via [`ParseLimits::decode_ip_in_ip`] (the ip
+    /// payload is kept in the `payload` field).
+    pub inner: Option<Box<SlicedPacket<'a>>>,
 }
 
 impl<'a> SlicedPacket<'a> {
@@ -118,7 +291,14 @@ impl<'a> SlicedPacket<'a> {
     /// }
     /// ```
     pub fn from_ethernet(data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        CursorSlice::new(data).slice_ethernet2()
+        SlicedPacket::from_ethernet_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers
+    /// (same as [`SlicedPacket::from_ethernet`]) & returns an error if the packet
+    /// exceeds one of the given [`ParseLimits`].
+    pub fn from_ethernet_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, limits).slice_ethernet2()
     }
 
     /// Seperates a network packet slice into different slices containing the headers using
@@ -129,9 +309,15 @@ impl<'a> SlicedPacket<'a> {
     ///
     /// * `ether_type::IPV4`
     /// * `ether_type::IPV6`
+    /// * `ether_type::ARP`
     /// * `ether_type::VLAN_TAGGED_FRAME`
     /// * `ether_type::PROVIDER_BRIDGING`
     /// * `ether_type::VLAN_DOUBLE_TAGGED_FRAME`
+    /// * other ether types in [`ParseLimits::vlan_tpids`] (only for the `*_with_limits` variant)
+    /// * `ether_type::PPPOE_DISCOVERY`
+    /// * `ether_type::PPPOE_SESSION`
+    /// * `ether_type::PROVIDER_BACKBONE_BRIDGING`
+    /// * values smaller then [`LlcHeader::ETHER_TYPE_MIN`] (length of an IEEE 802.3 frame starting with a LLC header)
     ///
     /// If an unsupported ether type is given the given slice will be set as payload
     /// and all other fields will be set to `None`.
@@ -174,22 +360,15 @@ impl<'a> SlicedPacket<'a> {
     /// }
     /// ```
     pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        use ether_type::*;
-        match ether_type {
-            IPV4 => CursorSlice::new(data).slice_ipv4(),
-            IPV6 => CursorSlice::new(data).slice_ipv6(),
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME =>
-                CursorSlice::new(data).slice_vlan(),
-            _ => Ok(
-                SlicedPacket {
-                    link: None,
-                    vlan: None,
-                    ip: None,
-                    transport: None,
-                    payload: data
-                }
-            ),
-        }
+        SlicedPacket::from_ether_type_with_limits(ether_type, data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers
+    /// using the given `ether_type` number to identify the first header (same as
+    /// [`SlicedPacket::from_ether_type`]) & returns an error if the packet exceeds
+    /// one of the given [`ParseLimits`].
+    pub fn from_ether_type_with_limits(ether_type: u16, data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, limits).slice_ether_type(ether_type)
     }
 
     /// Seperates a network packet slice into different slices containing the headers from the ip header downwards. 
@@ -229,30 +408,640 @@ impl<'a> SlicedPacket<'a> {
     /// }
     /// ```
     pub fn from_ip(data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        CursorSlice::new(data).slice_ip()
+        SlicedPacket::from_ip_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers
+    /// from the ip header downwards (same as [`SlicedPacket::from_ip`]) & returns an
+    /// error if the packet exceeds one of the given [`ParseLimits`].
+    pub fn from_ip_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, limits).slice_ip()
+    }
+
+    /// Seperates the original packet quoted in the payload of an ICMPv4 or
+    /// ICMPv6 error message (e.g. destination unreachable or time exceeded)
+    /// into different slices containing the headers from the ip header
+    /// downwards.
+    ///
+    /// ICMP error messages only quote the start of the original packet
+    /// (for ICMPv4 often only the ip header & the first 8 bytes of the ip
+    /// payload). The ip header & extension headers have to be complete, but
+    /// if the transport header (or a tunnel header) is cut off, the `transport`
+    /// field is set to `None` & the quoted ip payload is set as `payload`
+    /// (e.g. the ports & sequence number of a quoted TCP header can still
+    /// be read from the first 8 bytes of the payload).
+    ///
+    /// # Example
+    ///
+    ///```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ipv4([192,168,1,1], [192,168,1,2], 1)
+    /// #    .tcp(21, 1234, 1, 4000);
+    /// # let mut original = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut original, &[1,2,3,4]).unwrap();
+    /// use etherparse::{icmpv4::Icmp4Type, SlicedPacket};
+    ///
+    /// // time exceeded message quoting the ip header & 8 bytes of the tcp header
+    /// let mut message = vec![11, 0, 0, 0, 0, 0, 0, 0];
+    /// message.extend_from_slice(&original[..20 + 8]);
+    ///
+    /// let (icmp_type, payload) = Icmp4Type::from_bytes(&message).unwrap();
+    /// assert_eq!(11, icmp_type.type_u8());
+    ///
+    /// let quoted = SlicedPacket::from_icmp_error_payload(payload).unwrap();
+    /// assert!(quoted.ip.is_some());
+    /// assert!(quoted.transport.is_none());
+    /// assert_eq!(&[0, 21, 0x04, 0xd2], &quoted.payload[..4]);
+    /// ```
+    pub fn from_icmp_error_payload(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_icmp_error_payload_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates the original packet quoted in the payload of an ICMP error
+    /// message (same as [`SlicedPacket::from_icmp_error_payload`]) & returns
+    /// an error if the packet exceeds one of the given [`ParseLimits`].
+    pub fn from_icmp_error_payload_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        let mut cursor = CursorSlice::new(data, limits);
+        cursor.allow_truncated_ip_payload = true;
+        cursor.slice_ip()
+    }
+
+    /// Seperates a packet captured with the given link type (e.g. the link
+    /// type of a pcap file) into different slices containing the headers.
+    ///
+    /// The link layer header is represented in the `link` field for
+    /// Ethernet II ([`LinkSlice::Ethernet2`]), Linux "cooked" captures
+    /// ([`LinkSlice::LinuxSll`] & [`LinkSlice::LinuxSll2`]), BSD loopback
+    /// headers ([`LinkSlice::Null`]) & IEEE 802.11 frames
+    /// ([`LinkSlice::Ieee80211`]). For all other link types (PPP & IEEE
+    /// 802.15.4) & the radiotap header the link layer header is skipped.
+    /// The packet is sliced starting with the protocol identified by the
+    /// link layer header:
+    ///
+    /// * [`LinkType::Null`] & [`LinkType::Loop`]: IPv4 & IPv6 (see [`SlicedPacket::from_null`])
+    /// * [`LinkType::Ppp`]: IPv4 & IPv6 (PPP protocol `0x0021` & `0x0057`)
+    /// * [`LinkType::LinuxSll`] & [`LinkType::LinuxSll2`]: see [`SlicedPacket::from_linux_sll`]
+    ///   & [`SlicedPacket::from_linux_sll2`]
+    /// * [`LinkType::Ieee80211`]: see [`SlicedPacket::from_ieee80211`]
+    /// * [`LinkType::Ieee80211Radiotap`]: same as [`LinkType::Ieee80211`]
+    ///   after the radiotap header (the FCS is removed if the radiotap
+    ///   flags indicate it is present)
+    /// * [`LinkType::Ieee802154`] & [`LinkType::Ieee802154NoFcs`]: IPv6
+    ///   packets using the uncompressed 6LoWPAN dispatch (the FCS is removed
+    ///   if present). 6LoWPAN compressed packets are set as payload & can be
+    ///   decompressed via [`crate::sixlowpan::Iphc`].
+    ///
+    /// If the protocol after the link layer header is not supported (e.g.
+    /// 802.11 management frames or encrypted 802.11 data frames) the data
+    /// after the link layer header is set as payload and all other fields
+    /// are set to `None`.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ipv4([192,168,1,1], [192,168,1,2], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    /// use etherparse::{LinkType, SlicedPacket};
+    ///
+    /// // e.g. the link type from a pcap file header
+    /// let link_type = LinkType::from_u32(101).unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_link_type(link_type, &packet).unwrap();
+    /// assert!(sliced.ip.is_some());
+    /// ```
+    pub fn from_link_type(link_type: LinkType, data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_link_type_with_limits(link_type, data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a packet captured with the given link type into different
+    /// slices containing the headers (same as [`SlicedPacket::from_link_type`])
+    /// & returns an error if the packet exceeds one of the given [`ParseLimits`].
+    pub fn from_link_type_with_limits(link_type: LinkType, data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        use LinkType::*;
+        match link_type {
+            Ethernet => SlicedPacket::from_ethernet_with_limits(data, limits),
+            Raw => SlicedPacket::from_ip_with_limits(data, limits),
+            Null | Loop => SlicedPacket::from_null_with_limits(data, limits),
+            Ppp => {
+                // address & control field are optional
+                let (rest, header_len) = if data.len() >= 2 && 0xff == data[0] && 0x03 == data[1] {
+                    (&data[2..], 2)
+                } else {
+                    (data, 0)
+                };
+                // the protocol field is compressed to one byte if the lowest bit is set
+                let (protocol, rest) = match rest.first() {
+                    Some(value) if 1 == value & 1 => (u16::from(*value), &rest[1..]),
+                    _ if rest.len() >= 2 => (u16::from_be_bytes([rest[0], rest[1]]), &rest[2..]),
+                    _ => return Err(ReadError::UnexpectedEndOfSlice(header_len + 2)),
+                };
+                match crate::tunnel::pppoe::ppp_protocol_ether_type(protocol) {
+                    Some(value) => SlicedPacket::from_ether_type_with_limits(value, rest, limits)
+                        .map_err(|err| err.add_slice_offset(data.len() - rest.len())),
+                    None => Ok(SlicedPacket::payload_only(rest)),
+                }
+            },
+            LinuxSll => SlicedPacket::from_linux_sll_with_limits(data, limits),
+            LinuxSll2 => SlicedPacket::from_linux_sll2_with_limits(data, limits),
+            Ieee80211 => SlicedPacket::from_ieee80211_with_limits(data, limits),
+            Ieee80211Radiotap => slice_ieee80211_radiotap(data, limits),
+            Ieee802154 | Ieee802154NoFcs => {
+                let frame = if Ieee802154 == link_type {
+                    if data.len() < 2 {
+                        return Err(ReadError::UnexpectedEndOfSlice(2));
+                    }
+                    &data[..data.len() - 2]
+                } else {
+                    data
+                };
+                let (header, rest) = Ieee802154Header::from_slice(frame)?;
+                match rest.first() {
+                    Some(&crate::sixlowpan::DISPATCH_IPV6) if !header.ie_present && header.security.is_none() => {
+                        SlicedPacket::from_ip_with_limits(&rest[1..], limits)
+                            .map_err(|err| err.add_slice_offset(frame.len() - rest.len() + 1))
+                    },
+                    _ => Ok(SlicedPacket::payload_only(rest)),
+                }
+            },
+        }
+    }
+
+    /// Seperates a packet starting with a BSD loopback header (`LINKTYPE_NULL`
+    /// or `LINKTYPE_LOOP`, e.g. captured on the loopback interface of macOS
+    /// or a BSD) into different slices containing the headers.
+    ///
+    /// The 4 byte header contains the protocol family (`AF_*` value) of the
+    /// packet. As `LINKTYPE_NULL` stores the family in the byte order of the
+    /// capturing host & `LINKTYPE_LOOP` in network byte order both byte orders
+    /// are accepted. The header is set as `link` (see [`LinkSlice::Null`]) and the rest of the packet is sliced
+    /// starting with the IPv4 (`AF_INET`) or IPv6 header (`AF_INET6`, which
+    /// has the value 10, 24, 28 or 30 depending on the operating system). For
+    /// all other protocol families the data after the header is set as payload.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ipv6([1;16], [2;16], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut ip_packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut ip_packet, &[1,2,3,4]).unwrap();
+    /// use etherparse::SlicedPacket;
+    ///
+    /// // AF_INET6 on macOS in little endian byte order
+    /// let mut packet = 30u32.to_le_bytes().to_vec();
+    /// packet.extend_from_slice(&ip_packet);
+    ///
+    /// let sliced = SlicedPacket::from_null(&packet).unwrap();
+    /// assert!(sliced.link.is_some());
+    /// assert!(sliced.ip.is_some());
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_null(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_null_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a packet starting with a BSD loopback header (same as
+    /// [`SlicedPacket::from_null`]) & returns an error if the packet exceeds
+    /// one of the given [`ParseLimits`].
+    pub fn from_null_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, limits).slice_null()
+    }
+
+    /// Seperates an IEEE 802.11 frame (without FCS, e.g. captured in
+    /// monitor mode) into different slices containing the headers.
+    ///
+    /// The 802.11 MAC header of data frames is set as `link` (see
+    /// [`LinkSlice::Ieee80211`]). For unprotected data frames starting with a
+    /// LLC/SNAP header the rest of the frame is sliced based on the ether
+    /// type in the SNAP header (all ether types supported by
+    /// [`SlicedPacket::from_ether_type`] are supported). For protected data
+    /// frames & data frames without a LLC/SNAP header the frame body is set
+    /// as payload and for management, control & extension frames the
+    /// complete frame.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ipv4([192,168,1,1], [192,168,1,2], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut ip_packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut ip_packet, &[1,2,3,4]).unwrap();
+    /// use etherparse::SlicedPacket;
+    ///
+    /// let mut frame = vec![
+    ///     0x08, 0x01, // data frame, to ds
+    ///     0, 0, // duration
+    ///     1, 2, 3, 4, 5, 6, // address 1
+    ///     7, 8, 9, 10, 11, 12, // address 2
+    ///     13, 14, 15, 16, 17, 18, // address 3
+    ///     0, 0, // sequence control
+    ///     0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00, // llc/snap (ipv4)
+    /// ];
+    /// frame.extend_from_slice(&ip_packet);
+    ///
+    /// let sliced = SlicedPacket::from_ieee80211(&frame).unwrap();
+    /// assert!(sliced.link.is_some());
+    /// assert!(sliced.ip.is_some());
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_ieee80211(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_ieee80211_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates an IEEE 802.11 frame into different slices containing the
+    /// headers (same as [`SlicedPacket::from_ieee80211`]) & returns an error
+    /// if the packet exceeds one of the given [`ParseLimits`].
+    pub fn from_ieee80211_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        if data.len() < 2 {
+            return Err(ReadError::UnexpectedEndOfSlice(2));
+        }
+        if Ieee80211FrameType::Data as u8 != (data[0] >> 2) & 0b11 {
+            return Ok(SlicedPacket::payload_only(data));
+        }
+        CursorSlice::new(data, limits).slice_ieee80211()
+    }
+
+    /// Seperates a packet starting with a Linux "cooked" capture header
+    /// (`LINKTYPE_LINUX_SLL`, e.g. captured via `tcpdump -i any`) into
+    /// different slices containing the headers.
+    ///
+    /// The Linux cooked capture header is set as `link` (see
+    /// [`LinkSlice::LinuxSll`]) and the rest of the packet is
+    /// sliced based on the protocol type field, which contains an ether type
+    /// (all ether types supported by [`SlicedPacket::from_ether_type`] are
+    /// supported). For netlink captures the data after the header is set as
+    /// payload.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ipv4([192,168,1,1], [192,168,1,2], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut ip_packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut ip_packet, &[1,2,3,4]).unwrap();
+    /// use etherparse::{ether_type, LinuxSllHeader, SlicedPacket};
+    ///
+    /// let mut packet = LinuxSllHeader{
+    ///     packet_type: 0,
+    ///     arphrd_type: 1,
+    ///     sender_address_len: 6,
+    ///     sender_address: [1,2,3,4,5,6,0,0],
+    ///     protocol_type: ether_type::IPV4,
+    /// }.to_bytes().to_vec();
+    /// packet.extend_from_slice(&ip_packet);
+    ///
+    /// let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+    /// assert!(sliced.link.is_some());
+    /// assert!(sliced.ip.is_some());
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_linux_sll(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_linux_sll_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a packet starting with a Linux "cooked" capture header
+    /// (same as [`SlicedPacket::from_linux_sll`]) & returns an error if the
+    /// packet exceeds one of the given [`ParseLimits`].
+    pub fn from_linux_sll_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, limits).slice_linux_sll()
+    }
+
+    /// Seperates a packet starting with a Linux "cooked" capture v2 header
+    /// (`LINKTYPE_LINUX_SLL2`, e.g. captured via `tcpdump -i any` with newer
+    /// libpcap versions) into different slices containing the headers.
+    ///
+    /// The header is handled the same way as the v1 header in
+    /// [`SlicedPacket::from_linux_sll`] (it is set as `link`, see [`LinkSlice::LinuxSll2`]).
+    pub fn from_linux_sll2(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_linux_sll2_with_limits(data, ParseLimits::DEFAULT)
+    }
+
+    /// Seperates a packet starting with a Linux "cooked" capture v2 header
+    /// (same as [`SlicedPacket::from_linux_sll2`]) & returns an error if the
+    /// packet exceeds one of the given [`ParseLimits`].
+    pub fn from_linux_sll2_with_limits(data: &'a [u8], limits: ParseLimits) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, limits).slice_linux_sll2()
+    }
+
+    /// Packet only consisting of a payload.
+    fn payload_only(payload: &'a [u8]) -> SlicedPacket<'a> {
+        SlicedPacket {
+            link: None,
+            vlan: None,
+            llc: None,
+            arp: None,
+            ip: None,
+            transport: None,
+            tunnel: None,
+            payload,
+            trailer: &[],
+            padding: &[],
+            inner: None,
+        }
+    }
+
+    /// Returns the slices of all headers in the order they appear in
+    /// the packet (link, vlan, llc, ip, ip extensions, transport, tunnel & arp).
+    ///
+    /// Tunnel headers directly following the ip header (ESP & GRE) are
+    /// placed before the transport header (e.g. the UDP header in an ESP
+    /// payload). Headers that are not present are represented by empty slices.
+    /// As an ARP packet is never followed by ip or transport headers
+    /// the ARP slice is placed at the end.
+    /// All returned slices point directly into the originally sliced
+    /// data, no data is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{SlicedPacket, PacketBuilder};
+    /// # let builder = PacketBuilder::
+    /// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    /// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    /// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    ///
+    /// // concatenating all headers & the payload results in the original packet
+    /// let mut copy = sliced.header_bytes().concat();
+    /// copy.extend_from_slice(sliced.payload_bytes());
+    /// assert_eq!(copy, packet);
+    /// ```
+    pub fn header_bytes(&self) -> [&'a [u8];8] {
+        let transport = self.transport.as_ref().map(|v| v.slice()).unwrap_or(&[]);
+        let tunnel = self.tunnel.as_ref().map(|v| v.slice()).unwrap_or(&[]);
+        let (first, second) = if self.ip_tunnel_slice().is_some() {
+            (tunnel, transport)
+        } else {
+            (transport, tunnel)
+        };
+        [
+            self.link.as_ref().map(|v| v.slice()).unwrap_or(&[]),
+            self.vlan.as_ref().map(|v| v.slice()).unwrap_or(&[]),
+            self.llc.as_ref().map(|v| v.slice()).unwrap_or(&[]),
+            self.ip.as_ref().map(|v| v.header_slice()).unwrap_or(&[]),
+            self.ip.as_ref().map(|v| v.extensions_slice()).unwrap_or(&[]),
+            first,
+            second,
+            self.arp.as_ref().map(|v| v.slice()).unwrap_or(&[]),
+        ]
+    }
+
+    /// Returns the slice of the tunnel header if it directly follows the
+    /// ip header (ESP & GRE).
+    pub(crate) fn ip_tunnel_slice(&self) -> Option<&'a [u8]> {
+        let ip = self.ip.as_ref()?;
+        match (ip.payload_ip_number(), &self.tunnel) {
+            (ip_number::ENCAP_SEC, Some(TunnelSlice::Esp(esp))) => Some(esp.slice()),
+            (ip_number::GRE, Some(TunnelSlice::Gre(gre))) => Some(gre.slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns the chain of ip protocol numbers traversed from the ip header
+    /// to the upper layer protocol (e.g. hop by hop options, routing,
+    /// fragment & TCP) together with the byte range of each header relative
+    /// to the start of the ip header.
+    ///
+    /// The chain contains the ip extension headers, the ESP or GRE header
+    /// (if decoded) & the transport protocol. The range of the last entry is
+    /// empty if its header was not decoded (e.g. unknown protocols or
+    /// fragmented payloads). An empty list is returned if no ip header is
+    /// present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::*;
+    /// # let builder = PacketBuilder::
+    /// #    ipv6([1;16], [2;16], 20)
+    /// #    .tcp(21, 1234, 1, 1024);
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(0));
+    /// # builder.write(&mut packet, &[]).unwrap();
+    /// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    ///
+    /// let chain = sliced.ip_number_chain();
+    /// assert_eq!(1, chain.len());
+    /// assert_eq!(ip_number::TCP, chain[0].ip_number);
+    /// // the tcp header directly follows the 40 byte ipv6 header
+    /// assert_eq!(40..60, chain[0].range);
+    /// ```
+    pub fn ip_number_chain(&self) -> Vec<IpNumberChainEntry> {
+        let ip = match &self.ip {
+            Some(ip) => ip,
+            None => return Vec::new(),
+        };
+        let header_len = ip.header_slice().len();
+        let mut result: Vec<IpNumberChainEntry> = match ip {
+            InternetSlice::Ipv4(_, exts) => exts.auth.iter().map(|auth| IpNumberChainEntry{
+                ip_number: ip_number::AUTH,
+                range: header_len..header_len + auth.slice().len(),
+            }).collect(),
+            InternetSlice::Ipv6(_, exts) => exts.ip_number_chain().map(|entry| IpNumberChainEntry{
+                ip_number: entry.ip_number,
+                range: header_len + entry.range.start..header_len + entry.range.end,
+            }).collect(),
+        };
+        let mut offset = header_len + ip.extensions_slice().len();
+        let payload_ip_number = ip.payload_ip_number();
+
+        // tunnel headers directly following the ip header
+        let tunnel = self.ip_tunnel_slice();
+        if let Some(slice) = tunnel {
+            result.push(IpNumberChainEntry{
+                ip_number: payload_ip_number,
+                range: offset..offset + slice.len(),
+            });
+            offset += slice.len();
+        }
+
+        match &self.transport {
+            Some(transport) => {
+                use TransportSlice::*;
+                let ip_number = match transport {
+                    Udp(_) => ip_number::UDP,
+                    Tcp(_) => ip_number::TCP,
+                    Igmp(_) => ip_number::IGMP,
+                    Unknown(ip_number) => *ip_number,
+                };
+                result.push(IpNumberChainEntry{
+                    ip_number,
+                    range: offset..offset + transport.slice().len(),
+                });
+            },
+            None => if tunnel.is_none() {
+                result.push(IpNumberChainEntry{
+                    ip_number: payload_ip_number,
+                    range: offset..offset,
+                });
+            },
+        }
+        result
+    }
+
+    /// Returns the summed up length of all present header slices (link,
+    /// vlan, llc, ip, ip extensions, transport, tunnel & arp) in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{SlicedPacket, PacketBuilder};
+    /// # let builder = PacketBuilder::
+    /// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    /// #    .ipv6([1;16], [2;16], 20)
+    /// #    .tcp(21, 1234, 1, 1024);
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    /// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    ///
+    /// // ethernet II (14) + ipv6 (40) + tcp (20)
+    /// assert_eq!(74, sliced.headers_len());
+    /// assert_eq!(packet.len(), sliced.total_len(sliced.payload.len()));
+    /// ```
+    pub fn headers_len(&self) -> usize {
+        self.header_bytes().iter().map(|v| v.len()).sum()
+    }
+
+    /// Returns the length of the packet in bytes if the headers are
+    /// followed by a payload with the given length.
+    #[inline]
+    pub fn total_len(&self, payload_len: usize) -> usize {
+        self.headers_len() + payload_len
+    }
+
+    /// Returns the slice containing the payload (identical to the
+    /// `payload` field).
+    #[inline]
+    pub fn payload_bytes(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns the header slices followed by the payload, the trailer & the
+    /// padding as [`std::io::IoSlice`]s.
+    ///
+    /// This allows to forward a packet (or a packet with replaced
+    /// headers) via vectored writes (e.g. `writev`) without first
+    /// copying the parts into one continous buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{SlicedPacket, PacketBuilder};
+    /// # let builder = PacketBuilder::
+    /// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    /// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+    /// #    .udp(21, 1234);
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+    /// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    /// use std::io::Write;
+    ///
+    /// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// let written = out.write_vectored(&sliced.io_slices()).unwrap();
+    /// assert_eq!(written, packet.len());
+    /// assert_eq!(out, packet);
+    /// ```
+    pub fn io_slices(&self) -> [io::IoSlice<'a>;11] {
+        let headers = self.header_bytes();
+        [
+            io::IoSlice::new(headers[0]),
+            io::IoSlice::new(headers[1]),
+            io::IoSlice::new(headers[2]),
+            io::IoSlice::new(headers[3]),
+            io::IoSlice::new(headers[4]),
+            io::IoSlice::new(headers[5]),
+            io::IoSlice::new(headers[6]),
+            io::IoSlice::new(headers[7]),
+            io::IoSlice::new(self.payload),
+            io::IoSlice::new(self.trailer),
+            io::IoSlice::new(self.padding),
+        ]
     }
 }
 
+/// Skips the radiotap & 802.11 header and slices the payload of 802.11 data frames.
+fn slice_ieee80211_radiotap(data: &[u8], limits: ParseLimits) -> Result<SlicedPacket<'_>, ReadError> {
+    // radiotap header
+    if data.len() < 8 {
+        return Err(ReadError::UnexpectedEndOfSlice(8));
+    }
+    let radiotap_len = usize::from(u16::from_le_bytes([data[2], data[3]]));
+    if data.len() < radiotap_len {
+        return Err(ReadError::UnexpectedEndOfSlice(radiotap_len));
+    }
+    let present = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+
+    // the flags field indicates if the FCS is present at the end of the frame
+    let has_fcs = if 0 != present & 0b10 {
+        // skip additional present words (indicated by the highest bit)
+        let mut offset = 8;
+        while offset + 4 <= radiotap_len && 0 != data[offset - 1] & 0x80 {
+            offset += 4;
+        }
+        // skip the tsft field (8 byte value aligned to 8 bytes)
+        if 0 != present & 0b1 {
+            offset = ((offset + 7) & !7) + 8;
+        }
+        offset < radiotap_len && 0 != data[offset] & 0x10
+    } else {
+        false
+    };
+    let frame = &data[radiotap_len..];
+    let frame = if has_fcs && frame.len() >= 4 {
+        &frame[..frame.len() - 4]
+    } else {
+        frame
+    };
+
+    SlicedPacket::from_ieee80211_with_limits(frame, limits)
+        .map_err(|err| err.add_slice_offset(radiotap_len))
+}
+
 ///Helper class for slicing packets
 struct CursorSlice<'a> {
     pub slice: &'a [u8],
     pub offset: usize,
-    pub result: SlicedPacket<'a>
+    pub limits: ParseLimits,
+    pub result: SlicedPacket<'a>,
+    /// Set the ip payload as payload if the headers in it are cut off
+    /// (instead of returning an error).
+    pub allow_truncated_ip_payload: bool,
 }
 
 impl<'a> CursorSlice<'a> {
 
-    pub fn new(slice: &'a [u8]) -> CursorSlice<'a> {
+    pub fn new(slice: &'a [u8], limits: ParseLimits) -> CursorSlice<'a> {
         CursorSlice {
             offset: 0,
             slice,
+            limits,
             result: SlicedPacket {
                 link: None,
                 vlan: None,
+                llc: None,
+                arp: None,
                 ip: None,
                 transport: None,
-                payload: slice
-            }
+                tunnel: None,
+                payload: slice,
+                trailer: &[],
+                padding: &[],
+                inner: None,
+            },
+            allow_truncated_ip_payload: false,
         }
     }
 
@@ -272,9 +1061,19 @@ impl<'a> CursorSlice<'a> {
         self.slice = other;
     }
 
+    /// Limits the slice to the given ip payload length & sets the data
+    /// after it (e.g. ethernet padding) as padding (if enabled via
+    /// [`ParseLimits::trim_ip_payload`]).
+    fn limit_to_ip_payload(&mut self, payload_len: usize) {
+        if self.limits.trim_ip_payload && payload_len < self.slice.len() {
+            let (payload, padding) = self.slice.split_at(payload_len);
+            self.slice = payload;
+            self.result.padding = padding;
+        }
+    }
+
     pub fn slice_ethernet2(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         use LinkSlice::*;
-        use ether_type::*;
 
         let result = Ethernet2HeaderSlice::from_slice(self.slice)
                      .map_err(|err| 
@@ -288,66 +1087,222 @@ impl<'a> CursorSlice<'a> {
         self.move_by_slice(result.slice());
         self.result.link = Some(Ethernet2(result));
 
+        //continue parsing (if required)
+        self.slice_ether_type(ether_type)
+    }
+
+    /// Slices a Linux cooked capture header & the packet after it.
+    pub fn slice_linux_sll(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = LinuxSllHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether_type for later
+        let ether_type = crate::link::linux_sll::cooked_ether_type(result.arphrd_type(), result.protocol_type());
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.link = Some(LinkSlice::LinuxSll(result));
+
+        //continue parsing (if required)
+        match ether_type {
+            Some(value) => self.slice_ether_type(value),
+            None => self.slice_payload(),
+        }
+    }
+
+    /// Slices a Linux cooked capture v2 header & the packet after it.
+    pub fn slice_linux_sll2(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = LinuxSll2HeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether_type for later
+        let ether_type = crate::link::linux_sll::cooked_ether_type(result.arphrd_type(), result.protocol_type());
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.link = Some(LinkSlice::LinuxSll2(result));
+
         //continue parsing (if required)
         match ether_type {
+            Some(value) => self.slice_ether_type(value),
+            None => self.slice_payload(),
+        }
+    }
+
+    /// Slices a BSD loopback header & the ip packet after it.
+    pub fn slice_null(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = NullHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether_type for later
+        let ether_type = result.ether_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.link = Some(LinkSlice::Null(result));
+
+        //continue parsing (if required)
+        match ether_type {
+            Some(ether_type::IPV4) => self.slice_ipv4(),
+            Some(ether_type::IPV6) => self.slice_ipv6(),
+            _ => self.slice_payload(),
+        }
+    }
+
+    /// Slices the 802.11 MAC header of a data frame & the LLC/SNAP header
+    /// & packet after it (if the frame body is not encrypted).
+    pub fn slice_ieee80211(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = Ieee80211HeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the flags for later
+        let decode_body = !result.protected() && result.has_data_body();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.link = Some(LinkSlice::Ieee80211(result));
+
+        //continue parsing (if required)
+        let snap = LlcHeaderSlice::from_slice(self.slice).ok().and_then(|llc| llc.ether_type());
+        match snap {
+            Some(_) if decode_body => self.slice_llc(),
+            _ => self.slice_payload(),
+        }
+    }
+
+    /// Slices the data based on the ether type of the previous header.
+    pub fn slice_ether_type(self, ether_type: u16) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+        match ether_type {
+            value if self.limits.is_vlan_tpid(value) => self.slice_vlan(),
             IPV4 => self.slice_ipv4(),
             IPV6 => self.slice_ipv6(),
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => self.slice_vlan(),
+            ARP => self.slice_arp(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => self.slice_pppoe(),
+            PROVIDER_BACKBONE_BRIDGING => self.slice_pbb(),
+            value if value < LlcHeader::ETHER_TYPE_MIN => self.slice_llc(),
             _ => self.slice_payload()
         }
     }
 
+    /// Slices all stacked vlan headers (ether types in
+    /// [`ParseLimits::vlan_tpids`] identify further vlan headers).
     pub fn slice_vlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
-        use VlanSlice::*;
         use ether_type::*;
 
-        let single = SingleVlanHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
+        let result = MultiVlanHeaderSlice::from_slice_with_limits(self.slice, self.limits)
+                     .map_err(|err|
                         err.add_slice_offset(self.offset)
                      )?;
 
-        //check if it is a double vlan header
-        match single.ether_type() {
-            //in case of a double vlan header continue with the inner
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => self.slice_double_vlan(),
-            value => {
-                //set the vlan header and continue the normal parsing
-                self.move_by_slice(single.slice());
-                self.result.vlan = Some(SingleVlan(single));
-
-                match value {
-                    IPV4 => self.slice_ipv4(),
-                    IPV6 => self.slice_ipv6(),
-                    _ => self.slice_payload()
-                }
-            }
+        //cache the ether_type of the innermost vlan header for later
+        let vlan = result.into_vlan_slice();
+        let ether_type = vlan.inner().ether_type();
+
+        //set the new data
+        self.move_by_slice(vlan.slice());
+        self.result.vlan = Some(vlan);
+
+        //continue parsing (if required)
+        match ether_type {
+            IPV4 => self.slice_ipv4(),
+            IPV6 => self.slice_ipv6(),
+            ARP => self.slice_arp(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => self.slice_pppoe(),
+            PROVIDER_BACKBONE_BRIDGING => self.slice_pbb(),
+            value if value < LlcHeader::ETHER_TYPE_MIN => self.slice_llc(),
+            _ => self.slice_payload()
         }
     }
 
-    pub fn slice_double_vlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
-        use VlanSlice::*;
+    /// Slices a LLC header (including the SNAP header) & the following
+    /// packet if the SNAP header contains an ether type.
+    pub fn slice_llc(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         use ether_type::*;
 
-        let result = DoubleVlanHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
+        let result = LlcHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
                         err.add_slice_offset(self.offset)
                      )?;
 
-        //cache ether_type for later
-        let ether_type = result.inner().ether_type();
+        //cache the ether_type for later
+        let ether_type = result.ether_type();
 
         //set the new data
         self.move_by_slice(result.slice());
-        self.result.vlan = Some(DoubleVlan(result));
+        self.result.llc = Some(result);
 
         //continue parsing (if required)
         match ether_type {
-            IPV4 => self.slice_ipv4(),
-            IPV6 => self.slice_ipv6(),
+            Some(value) if self.limits.is_vlan_tpid(value) => self.slice_vlan(),
+            Some(IPV4) => self.slice_ipv4(),
+            Some(IPV6) => self.slice_ipv6(),
+            Some(ARP) => self.slice_arp(),
+            Some(PPPOE_DISCOVERY) | Some(PPPOE_SESSION) => self.slice_pppoe(),
+            Some(PROVIDER_BACKBONE_BRIDGING) => self.slice_pbb(),
             _ => self.slice_payload()
         }
     }
 
+    /// Slices a PPPoE header & the encapsulated IP packet (only session
+    /// packets containing IPv4 or IPv6 are decoded further).
+    pub fn slice_pppoe(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = PppoeHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the inner ether type for later
+        let inner_ether_type = result.ppp_protocol()
+            .and_then(crate::tunnel::pppoe::ppp_protocol_ether_type);
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Pppoe(result));
+
+        match inner_ether_type {
+            Some(value) => self.slice_tunnel_payload(value),
+            None => self.slice_payload(),
+        }
+    }
+
+    /// Slices a PBB I-TAG & the encapsulated customer frame.
+    pub fn slice_pbb(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = PbbItagHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the customer ether type for later
+        let inner_ether_type = result.ether_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Pbb(result));
+
+        self.slice_tunnel_payload(inner_ether_type)
+    }
+
+    pub fn slice_arp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = ArpPacketSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data (the rest is padding)
+        self.move_by_slice(result.slice());
+        self.result.arp = Some(result);
+        self.slice_payload()
+    }
+
     pub fn slice_ip(self) -> Result<SlicedPacket<'a>, ReadError> {
         use ReadError::*;
 
@@ -369,10 +1324,13 @@ impl<'a> CursorSlice<'a> {
                         .map_err(|err| 
                             err.add_slice_offset(self.offset)
                         )?;
+        self.limits.check_option_bytes(ip_header.options().len())?;
         let fragmented = ip_header.is_fragmenting_payload();
+        let payload_len = usize::from(ip_header.payload_len());
 
-        // move the slice
+        // move the slice & separate the padding after the ip payload
         self.move_by_slice(ip_header.slice());
+        self.limit_to_ip_payload(payload_len);
 
         // slice extensions
         let (ip_ext, protocol, rest) = Ipv4ExtensionsSlice::from_slice(ip_header.protocol(), self.slice)
@@ -381,21 +1339,14 @@ impl<'a> CursorSlice<'a> {
                                        )?;
 
         // set the new data
+        let ext_len = self.slice.len() - rest.len();
         self.move_to_slice(rest);
         self.result.ip = Some(Ipv4(ip_header, ip_ext));
 
         if fragmented {
             self.slice_payload()
         } else {
-            match protocol {
-                ip_number::UDP => self.slice_udp(),
-                ip_number::TCP => self.slice_tcp(),
-                value => {
-                    use TransportSlice::*;
-                    self.result.transport = Some(Unknown(value));
-                    self.slice_payload()
-                }
-            }
+            self.slice_ip_payload(protocol, payload_len.saturating_sub(ext_len))
         }
     }
 
@@ -407,17 +1358,36 @@ impl<'a> CursorSlice<'a> {
                     err.add_slice_offset(self.offset)
                  )?;
 
-        //move the slice
+        //move the slice & separate the padding after the ip payload (a payload
+        //length of zero with a hop by hop header indicates a jumbogram, in
+        //which case the length of the "Jumbo Payload" option is used)
         self.move_by_slice(ip.slice());
+        let is_jumbogram = 0 == ip.payload_length() && ip_number::IPV6_HOP_BY_HOP == ip.next_header();
+        let jumbo_payload_len = if is_jumbogram {
+            Ipv6RawExtensionHeaderSlice::from_slice(self.slice)
+                .ok()
+                .and_then(|header| header.jumbo_payload_length())
+                .filter(|len| *len > u32::from(u16::MAX))
+        } else {
+            None
+        };
+        let payload_len = match jumbo_payload_len {
+            Some(len) => len as usize,
+            None => usize::from(ip.payload_length()),
+        };
+        if !is_jumbogram || jumbo_payload_len.is_some() {
+            self.limit_to_ip_payload(payload_len);
+        }
 
         //extension headers
-        let (ip_ext, next_header, rest) = Ipv6ExtensionsSlice::from_slice(ip.next_header(), self.slice)
+        let (ip_ext, next_header, rest) = Ipv6ExtensionsSlice::from_slice_with_limits(ip.next_header(), self.slice, self.limits)
                                           .map_err(|err| 
                                               err.add_slice_offset(self.offset)
                                           )?;
         let fragmented = ip_ext.is_fragmenting_payload();
 
         // set the new data 
+        let ext_len = self.slice.len() - rest.len();
         self.move_to_slice(rest);
         self.result.ip = Some(Ipv6(ip, ip_ext));
 
@@ -427,15 +1397,53 @@ impl<'a> CursorSlice<'a> {
             self.slice_payload()
         } else {
             //parse the data bellow
-            match next_header {
-                ip_number::UDP => self.slice_udp(),
-                ip_number::TCP => self.slice_tcp(),
-                value => {
-                    use TransportSlice::*;
-                    self.result.transport = Some(Unknown(value));
-                    self.slice_payload()
-                }
-            }
+            self.slice_ip_payload(next_header, payload_len.saturating_sub(ext_len))
+        }
+    }
+
+    /// Slices the transport or tunnel header (including the encapsulated
+    /// packet) of an unfragmented ip payload with the given length (falls
+    /// back to setting the ip payload as payload if the headers are cut off
+    /// & `allow_truncated_ip_payload` is set).
+    fn slice_ip_payload(self, protocol: u8, payload_len: usize) -> Result<SlicedPacket<'a>, ReadError> {
+        if self.allow_truncated_ip_payload {
+            let fallback = CursorSlice{
+                slice: self.slice,
+                offset: self.offset,
+                limits: self.limits,
+                result: self.result.clone(),
+                allow_truncated_ip_payload: true,
+            };
+            return match self.slice_ip_payload_headers(protocol, payload_len) {
+                Err(ReadError::UnexpectedEndOfSlice(_)) => fallback.slice_payload(),
+                other => other,
+            };
+        }
+        self.slice_ip_payload_headers(protocol, payload_len)
+    }
+
+    /// Slices the transport or tunnel header of an unfragmented ip payload
+    /// (see [`CursorSlice::slice_ip_payload`]).
+    fn slice_ip_payload_headers(self, protocol: u8, payload_len: usize) -> Result<SlicedPacket<'a>, ReadError> {
+        match protocol {
+            ip_number::UDP => self.slice_udp(),
+            ip_number::TCP => self.slice_tcp(),
+            ip_number::IGMP => self.slice_igmp(payload_len),
+            ip_number::GRE => self.slice_gre(),
+            ip_number::ENCAP_SEC if self.limits.esp_null_icv_len.is_some() => self.slice_esp(),
+            value => self.slice_unknown_ip_payload(value),
+        }
+    }
+
+    /// Sets the ip number of a not decoded ip payload as transport &
+    /// slices IPv4 & IPv6 packets encapsulated in it (if enabled via
+    /// [`ParseLimits::decode_ip_in_ip`]).
+    fn slice_unknown_ip_payload(mut self, protocol: u8) -> Result<SlicedPacket<'a>, ReadError> {
+        self.result.transport = Some(TransportSlice::Unknown(protocol));
+        match protocol {
+            ip_number::IPV4 if self.limits.decode_ip_in_ip => self.slice_tunnel_payload(ether_type::IPV4),
+            ip_number::IPV6 if self.limits.decode_ip_in_ip => self.slice_tunnel_payload(ether_type::IPV6),
+            _ => self.slice_payload(),
         }
     }
 
@@ -447,12 +1455,30 @@ impl<'a> CursorSlice<'a> {
                         err.add_slice_offset(self.offset)
                      )?;
 
+        //cache the ports for later
+        let source_port = result.source_port();
+        let destination_port = result.destination_port();
+
         //set the new data
         self.move_by_slice(result.slice());
         self.result.transport = Some(Udp(result));
 
-        //done
-        self.slice_payload()
+        //continue with udp based tunnels (if enabled)
+        if self.limits.decode_udp_tunnels && VxlanHeader::PORT == destination_port {
+            self.slice_vxlan()
+        } else if self.limits.decode_udp_tunnels && VxlanGpeHeader::PORT == destination_port {
+            self.slice_vxlan_gpe()
+        } else if self.limits.decode_udp_tunnels && GeneveHeader::PORT == destination_port {
+            self.slice_geneve()
+        } else if self.limits.decode_udp_tunnels && GtpuHeader::PORT == destination_port {
+            self.slice_gtpu()
+        } else if self.limits.decode_udp_tunnels && GueHeader::PORT == destination_port {
+            self.slice_gue()
+        } else if self.limits.decode_udp_tunnels && (TeredoHeader::PORT == destination_port || TeredoHeader::PORT == source_port) {
+            self.slice_teredo()
+        } else {
+            self.slice_payload()
+        }
     }
 
     pub fn slice_tcp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
@@ -462,6 +1488,7 @@ impl<'a> CursorSlice<'a> {
                      .map_err(|err| 
                         err.add_slice_offset(self.offset)
                      )?;
+        self.limits.check_option_bytes(result.options().len())?;
 
         //set the new data
         self.move_by_slice(result.slice());
@@ -471,6 +1498,226 @@ impl<'a> CursorSlice<'a> {
         self.slice_payload()
     }
 
+    /// Slices an IGMP message with the length of the IP payload.
+    pub fn slice_igmp(mut self, ip_payload_len: usize) -> Result<SlicedPacket<'a>, ReadError> {
+        use crate::TransportSlice::*;
+
+        let len = std::cmp::min(ip_payload_len, self.slice.len());
+        let result = IgmpSlice::from_slice(&self.slice[..len])
+                     .map_err(|err| 
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.transport = Some(Igmp(result));
+
+        //done
+        self.slice_payload()
+    }
+
+    /// Slices a GRE header & the encapsulated packet. GRE headers with
+    /// an unsupported version are treated as unknown transport protocol.
+    pub fn slice_gre(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = match GreHeaderSlice::from_slice(self.slice) {
+            Ok(value) => value,
+            Err(ReadError::GreUnsupportedVersion(_)) => {
+                self.result.transport = Some(TransportSlice::Unknown(ip_number::GRE));
+                return self.slice_payload();
+            },
+            Err(err) => return Err(err.add_slice_offset(self.offset)),
+        };
+
+        //cache the protocol type for later
+        let protocol_type = result.protocol_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Gre(result));
+
+        self.slice_tunnel_payload(protocol_type)
+    }
+
+    /// Slices a VXLAN header & the encapsulated ethernet frame.
+    pub fn slice_vxlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = VxlanHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Vxlan(result));
+
+        self.slice_tunnel_payload(ether_type::TRANSPARENT_ETHERNET_BRIDGING)
+    }
+
+    /// Slices a VXLAN-GPE header & the packet identified by the next
+    /// protocol field (unknown next protocols are not decoded further).
+    pub fn slice_vxlan_gpe(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = VxlanGpeHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether type for later
+        let payload_ether_type = result.payload_ether_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::VxlanGpe(result));
+
+        match payload_ether_type {
+            Some(ether_type) => self.slice_tunnel_payload(ether_type),
+            None => self.slice_payload(),
+        }
+    }
+
+    /// Slices a Geneve header & the encapsulated packet.
+    pub fn slice_geneve(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = GeneveHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the protocol type for later
+        let protocol_type = result.protocol_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Geneve(result));
+
+        self.slice_tunnel_payload(protocol_type)
+    }
+
+    /// Slices a GTP-U header & the encapsulated IP packet (only G-PDU
+    /// messages containing an IPv4 or IPv6 packet are decoded further).
+    pub fn slice_gtpu(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = GtpuHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data
+        let message_type = result.message_type();
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Gtpu(result));
+
+        match crate::tunnel::gtpu::payload_ether_type(message_type, self.slice) {
+            Some(ether_type) => self.slice_tunnel_payload(ether_type),
+            None => self.slice_payload(),
+        }
+    }
+
+    /// Slices a GUE header & the encapsulated IP packet (only data messages
+    /// containing an IPv4 or IPv6 packet are decoded further). IP packets
+    /// directly encapsulated in UDP (GUE variant 1) are decoded without a
+    /// tunnel header & payloads of other variants are not decoded.
+    pub fn slice_gue(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        match GueHeader::variant(self.slice) {
+            Some(0) => {},
+            Some(1) => {
+                return match crate::tunnel::gue::direct_ip_ether_type(self.slice) {
+                    Some(ether_type) => self.slice_tunnel_payload(ether_type),
+                    None => self.slice_payload(),
+                };
+            },
+            _ => return self.slice_payload(),
+        }
+
+        let result = GueHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether type for later
+        let payload_ether_type = result.payload_ether_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Gue(result));
+
+        match payload_ether_type {
+            Some(ether_type) => self.slice_tunnel_payload(ether_type),
+            None => self.slice_payload(),
+        }
+    }
+
+    /// Slices an ESP header & the payload protected by it assuming the
+    /// NULL encryption algorithm & the ICV length configured via
+    /// [`ParseLimits::esp_null_icv_len`]. IPv4 & IPv6 payloads are sliced
+    /// as inner packet (tunnel mode), other payloads like transport
+    /// headers (transport mode). The ESP trailer & ICV are not part of the
+    /// resulting payload.
+    pub fn slice_esp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = EspHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+        self.move_by_slice(result.slice());
+
+        let icv_len = self.limits.esp_null_icv_len.unwrap_or(0);
+        let null = EspNullSlice::from_slice(self.slice, icv_len)
+                   .map_err(|err|
+                       err.add_slice_offset(self.offset)
+                   )?;
+
+        //set the new data (the payload starts directly after the header &
+        //is followed by the trailer & icv)
+        self.result.trailer = &self.slice[null.payload().len()..];
+        self.slice = null.payload();
+        self.result.tunnel = Some(TunnelSlice::Esp(result));
+
+        match null.next_header() {
+            ip_number::IPV4 => self.slice_tunnel_payload(ether_type::IPV4),
+            ip_number::IPV6 => self.slice_tunnel_payload(ether_type::IPV6),
+            next_header => {
+                let payload_len = self.slice.len();
+                self.slice_ip_payload(next_header, payload_len)
+            },
+        }
+    }
+
+    /// Slices the optional Teredo headers & the encapsulated IPv6 packet.
+    /// Payloads not containing an IPv6 packet after the optional headers
+    /// are not treated as Teredo packets.
+    pub fn slice_teredo(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = TeredoHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        if !crate::tunnel::teredo::is_ipv6(&self.slice[result.slice().len()..]) {
+            return self.slice_payload();
+        }
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.tunnel = Some(TunnelSlice::Teredo(result));
+
+        self.slice_tunnel_payload(ether_type::IPV6)
+    }
+
+    /// Sets the tunnel payload & slices the encapsulated packet if the
+    /// given ether type is supported (errors in the encapsulated packet
+    /// leave `inner` empty, only exceeded limits are returned).
+    fn slice_tunnel_payload(mut self, ether_type: u16) -> Result<SlicedPacket<'a>, ReadError> {
+        if crate::tunnel::is_decodable_ether_type(ether_type, &self.limits) {
+            let limits = self.limits.enter_tunnel()?;
+            let inner = if ether_type::TRANSPARENT_ETHERNET_BRIDGING == ether_type {
+                SlicedPacket::from_ethernet_with_limits(self.slice, limits)
+            } else {
+                SlicedPacket::from_ether_type_with_limits(ether_type, self.slice, limits)
+            };
+            match inner {
+                Ok(inner) => self.result.inner = Some(Box::new(inner)),
+                Err(err @ ReadError::ParseLimitExceeded(_)) => return Err(err),
+                Err(_) => {},
+            }
+        }
+        self.slice_payload()
+    }
+
     pub fn slice_payload(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         self.result.payload = self.slice;
         Ok(self.result)
@@ -0,0 +1,192 @@
+use super::*;
+
+/// Limits bounding the work done when decoding a packet.
+///
+/// The default limits accept everything the decoders support except for
+/// more then 8 stacked vlan headers or nested tunnels. Services
+/// processing untrusted traffic can lower the limits & pass them to
+/// [`SlicedPacket::from_ethernet_with_limits`],
+/// [`PacketHeaders::from_ethernet_slice_with_limits`] & the other
+/// `*_with_limits` functions. Exceeding a limit results in a
+/// [`ReadError::ParseLimitExceeded`] error.
+///
+/// Additionally the limits control if tunnels identified by UDP ports, IP
+/// packets encapsulated in IP packets & NULL encrypted ESP packets get
+/// decoded (see [`ParseLimits::decode_udp_tunnels`],
+/// [`ParseLimits::decode_ip_in_ip`] & [`ParseLimits::esp_null_icv_len`]).
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .single_vlan(0x123)
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{ParseLimit, ParseLimits, ReadError, SlicedPacket};
+///
+/// let limits = ParseLimits{
+///     max_vlan_depth: 0,
+///     ..Default::default()
+/// };
+/// match SlicedPacket::from_ethernet_with_limits(&packet, limits) {
+///     Err(ReadError::ParseLimitExceeded(ParseLimit::VlanDepth)) => {},
+///     other => panic!("unexpected result {:?}", other),
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ParseLimits {
+    /// Maximum number of stacked vlan headers (default 8).
+    pub max_vlan_depth: usize,
+    /// Maximum number of IPv6 extension headers (default unlimited).
+    pub max_ipv6_extension_headers: usize,
+    /// Maximum number of nested tunnel encapsulations (e.g. GRE, PPPoE or
+    /// PBB, which are always decoded) that get decoded (default 8). A value
+    /// of 0 results in an error as soon as the decoders would descend into
+    /// an encapsulated packet.
+    pub max_tunnel_depth: usize,
+    /// Maximum number of option bytes in a single IPv4 header, TCP header or
+    /// IPv6 hop by hop & destination options header (default unlimited).
+    pub max_option_bytes: usize,
+    /// Decode tunnel headers identified by the UDP destination port (VXLAN
+    /// on port 4789, VXLAN-GPE on port 4790, Geneve on port 6081, GTP-U on port 2152, GUE on port 6080 & Teredo on the source or destination port 3544) & the encapsulated packets (default false, as the
+    /// ports might also be used by other protocols).
+    pub decode_udp_tunnels: bool,
+    /// Decode IPv4 & IPv6 packets directly encapsulated in IPv4 or IPv6
+    /// packets (IP number 4 or 41, e.g. IP-in-IP, 6in4 & 4in6) into the
+    /// `inner` field (default false). The nesting is bounded by
+    /// [`ParseLimits::max_tunnel_depth`].
+    pub decode_ip_in_ip: bool,
+    /// Decode ESP packets as packets using the NULL encryption algorithm
+    /// (RFC 2410) with an integrity check value of the given length in
+    /// bytes (default None, ESP payloads are not decoded). If set the ESP
+    /// header is decoded into the `tunnel` field & the protected payload
+    /// (located via the ESP trailer) based on its next header field.
+    pub esp_null_icv_len: Option<usize>,
+    /// Ether types identifying vlan headers (default
+    /// [`VlanHeader::VLAN_ETHER_TYPES`]). Set it to
+    /// [`VlanHeader::LEGACY_VLAN_ETHER_TYPES`] to also decode service tags
+    /// using the non standard TPIDs 0x9200 & 0x9300.
+    pub vlan_tpids: &'static [u16],
+    /// Limit the `payload` of a [`SlicedPacket`] to the length given in the
+    /// IPv4 total length or IPv6 payload length field & place the data after
+    /// the ip packet (e.g. ethernet padding or the FCS) in the `padding`
+    /// field (default true). If disabled everything after the headers is
+    /// part of the `payload` & `padding` stays empty.
+    pub trim_ip_payload: bool,
+}
+
+impl ParseLimits {
+    /// Limits used by the decoding functions without explicit limits.
+    pub const DEFAULT: ParseLimits = ParseLimits{
+        max_vlan_depth: 8,
+        max_ipv6_extension_headers: usize::MAX,
+        max_tunnel_depth: 8,
+        max_option_bytes: usize::MAX,
+        decode_udp_tunnels: false,
+        decode_ip_in_ip: false,
+        esp_null_icv_len: None,
+        vlan_tpids: &VlanHeader::VLAN_ETHER_TYPES,
+        trim_ip_payload: true,
+    };
+
+    /// Returns an error if the number of vlan headers exceeds the limit.
+    pub(crate) fn check_vlan_depth(&self, depth: usize) -> Result<(), ReadError> {
+        check(depth <= self.max_vlan_depth, ParseLimit::VlanDepth)
+    }
+
+    /// Returns true if the given ether type identifies a vlan header.
+    #[inline]
+    pub(crate) fn is_vlan_tpid(&self, ether_type: u16) -> bool {
+        self.vlan_tpids.contains(&ether_type)
+    }
+
+    /// Returns an error if the number of IPv6 extension headers exceeds the limit.
+    pub(crate) fn check_ipv6_extension_headers(&self, count: usize) -> Result<(), ReadError> {
+        check(count <= self.max_ipv6_extension_headers, ParseLimit::Ipv6ExtensionHeaders)
+    }
+
+    /// Returns an error if the number of option bytes exceeds the limit.
+    pub(crate) fn check_option_bytes(&self, len: usize) -> Result<(), ReadError> {
+        check(len <= self.max_option_bytes, ParseLimit::OptionBytes)
+    }
+
+    /// Returns the limits for decoding an encapsulated packet (the tunnel
+    /// depth reduced by one) or an error if the tunnel depth is exceeded.
+    pub(crate) fn enter_tunnel(&self) -> Result<ParseLimits, ReadError> {
+        check(self.max_tunnel_depth > 0, ParseLimit::TunnelDepth)?;
+        Ok(ParseLimits{
+            max_tunnel_depth: self.max_tunnel_depth - 1,
+            ..*self
+        })
+    }
+
+    /// Checks the limits applying to the already decoded ip header.
+    pub(crate) fn check_ip_header(&self, header: &IpHeader) -> Result<(), ReadError> {
+        match header {
+            IpHeader::Version4(ip, _) => self.check_option_bytes(ip.options().len()),
+            IpHeader::Version6(_, exts) => {
+                let mut count = 0;
+                if let Some(value) = &exts.hop_by_hop_options {
+                    self.check_option_bytes(value.payload().len())?;
+                    count += 1;
+                }
+                if let Some(value) = &exts.destination_options {
+                    self.check_option_bytes(value.payload().len())?;
+                    count += 1;
+                }
+                if let Some(value) = &exts.routing {
+                    count += 1;
+                    if let Some(value) = &value.final_destination_options {
+                        self.check_option_bytes(value.payload().len())?;
+                        count += 1;
+                    }
+                }
+                if let Some(value) = &exts.final_destination_options {
+                    self.check_option_bytes(value.payload().len())?;
+                    count += 1;
+                }
+                count += exts.fragment.iter().count() + exts.auth.iter().count();
+                self.check_ipv6_extension_headers(count)
+            },
+        }
+    }
+
+    /// Checks the limits applying to the already decoded transport header.
+    pub(crate) fn check_transport_header(&self, header: &TransportHeader) -> Result<(), ReadError> {
+        match header {
+            TransportHeader::Tcp(tcp) => self.check_option_bytes(tcp.options().len()),
+            TransportHeader::Udp(_) | TransportHeader::Igmp(_) => Ok(()),
+        }
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> ParseLimits {
+        ParseLimits::DEFAULT
+    }
+}
+
+fn check(ok: bool, limit: ParseLimit) -> Result<(), ReadError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(ReadError::ParseLimitExceeded(limit))
+    }
+}
+
+/// Identifies the limit in [`ParseLimits`] that was exceeded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ParseLimit {
+    /// [`ParseLimits::max_vlan_depth`] was exceeded.
+    VlanDepth,
+    /// [`ParseLimits::max_ipv6_extension_headers`] was exceeded.
+    Ipv6ExtensionHeaders,
+    /// [`ParseLimits::max_tunnel_depth`] was exceeded.
+    TunnelDepth,
+    /// [`ParseLimits::max_option_bytes`] was exceeded.
+    OptionBytes,
+}
@@ -0,0 +1,117 @@
+use super::*;
+
+/// Payload of a decoded packet, classified by the protocol it belongs to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Payload<'a> {
+    /// Payload following an ethernet or vlan header with an ether type that
+    /// could not be decoded or payload of a tunnel header (e.g. GRE).
+    Ether{
+        ether_type: u16,
+        payload: &'a [u8],
+    },
+    /// Spanning tree BPDU following a LLC header with the DSAP & SSAP
+    /// [`llc_sap::STP`].
+    Stp{
+        bpdu: StpBpduSlice<'a>,
+        /// Complete data after the LLC header (BPDU & ethernet padding).
+        payload: &'a [u8],
+    },
+    /// Payload of an UDP packet.
+    Udp(&'a [u8]),
+    /// Payload of a TCP packet.
+    Tcp(&'a [u8]),
+    /// Data after an IGMP message (IGMP messages have no payload, so this
+    /// is usually empty or ethernet padding).
+    Igmp(&'a [u8]),
+    /// ICMPv4 message.
+    Icmpv4{
+        icmp_type: u8,
+        code: u8,
+        /// Data after the identifier & sequence number of echo request &
+        /// echo reply messages (None for all other messages).
+        echo_data: Option<&'a [u8]>,
+        /// Complete ICMP message (including the ICMP header).
+        message: &'a [u8],
+    },
+    /// ICMPv6 message.
+    Icmpv6{
+        icmp_type: u8,
+        code: u8,
+        /// Data after the identifier & sequence number of echo request &
+        /// echo reply messages (None for all other messages).
+        echo_data: Option<&'a [u8]>,
+        /// Complete ICMP message (including the ICMP header).
+        message: &'a [u8],
+    },
+    /// Fragment of an IP payload (the transport layer is not decoded).
+    IpFragment{
+        /// IP number of the fragmented payload.
+        ip_number: u8,
+        /// Offset of the fragment in 8 octets.
+        fragment_offset: u16,
+        more_fragments: bool,
+        payload: &'a [u8],
+    },
+    /// IP payload with an ip number that could not be decoded (or an ICMP
+    /// message that is too short to contain the ICMP header).
+    Unknown(u8, &'a [u8]),
+}
+
+impl<'a> Payload<'a> {
+    /// Classifies the payload of an unfragmented IP packet that does not
+    /// contain a UDP or TCP header.
+    pub(crate) fn from_ip_number(ip_number: u8, data: &'a [u8]) -> Payload<'a> {
+        // type, code, checksum, identifier & sequence number
+        const ICMP_ECHO_HEADER_LEN: usize = 8;
+
+        match ip_number {
+            ip_number::ICMP if data.len() >= 4 => Payload::Icmpv4{
+                icmp_type: data[0],
+                code: data[1],
+                echo_data: if (0 == data[0] || 8 == data[0]) && data.len() >= ICMP_ECHO_HEADER_LEN {
+                    Some(&data[ICMP_ECHO_HEADER_LEN..])
+                } else {
+                    None
+                },
+                message: data,
+            },
+            ip_number::IPV6_ICMP if data.len() >= 4 => Payload::Icmpv6{
+                icmp_type: data[0],
+                code: data[1],
+                echo_data: if (128 == data[0] || 129 == data[0]) && data.len() >= ICMP_ECHO_HEADER_LEN {
+                    Some(&data[ICMP_ECHO_HEADER_LEN..])
+                } else {
+                    None
+                },
+                message: data,
+            },
+            _ => Payload::Unknown(ip_number, data),
+        }
+    }
+
+    /// Returns the raw bytes of the payload (for ICMP the complete message).
+    pub fn slice(&self) -> &'a [u8] {
+        use Payload::*;
+        match self {
+            Ether{ payload, .. } => payload,
+            Stp{ payload, .. } => payload,
+            Udp(payload) => payload,
+            Tcp(payload) => payload,
+            Igmp(payload) => payload,
+            Icmpv4{ message, .. } => message,
+            Icmpv6{ message, .. } => message,
+            IpFragment{ payload, .. } => payload,
+            Unknown(_, payload) => payload,
+        }
+    }
+
+    /// Length of the payload in bytes.
+    pub fn len(&self) -> usize {
+        self.slice().len()
+    }
+
+    /// Returns true if the payload contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.slice().is_empty()
+    }
+}
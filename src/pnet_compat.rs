@@ -0,0 +1,105 @@
+use super::*;
+
+use pnet_packet::Packet;
+use pnet_packet::ethernet::EthernetPacket;
+use pnet_packet::ipv4::Ipv4Packet;
+use pnet_packet::tcp::TcpPacket;
+
+use std::convert::TryFrom;
+
+impl<'a, 'p> TryFrom<&'p EthernetPacket<'a>> for Ethernet2HeaderSlice<'p> {
+    type Error = ReadError;
+
+    fn try_from(value: &'p EthernetPacket<'a>) -> Result<Ethernet2HeaderSlice<'p>, ReadError> {
+        Ethernet2HeaderSlice::from_slice(value.packet())
+    }
+}
+
+impl<'a> TryFrom<&EthernetPacket<'a>> for Ethernet2Header {
+    type Error = ReadError;
+
+    fn try_from(value: &EthernetPacket<'a>) -> Result<Ethernet2Header, ReadError> {
+        Ethernet2Header::from_slice(value.packet()).map(|(header, _)| header)
+    }
+}
+
+impl<'a> From<&Ethernet2HeaderSlice<'a>> for EthernetPacket<'a> {
+    fn from(value: &Ethernet2HeaderSlice<'a>) -> EthernetPacket<'a> {
+        // the slice always has the minimum length required by EthernetPacket
+        EthernetPacket::new(value.slice()).unwrap()
+    }
+}
+
+impl From<&Ethernet2Header> for EthernetPacket<'static> {
+    fn from(value: &Ethernet2Header) -> EthernetPacket<'static> {
+        // the serialized header always has the minimum length required by EthernetPacket
+        EthernetPacket::owned(value.to_bytes().to_vec()).unwrap()
+    }
+}
+
+impl<'a, 'p> TryFrom<&'p Ipv4Packet<'a>> for Ipv4HeaderSlice<'p> {
+    type Error = ReadError;
+
+    fn try_from(value: &'p Ipv4Packet<'a>) -> Result<Ipv4HeaderSlice<'p>, ReadError> {
+        Ipv4HeaderSlice::from_slice(value.packet())
+    }
+}
+
+impl<'a> TryFrom<&Ipv4Packet<'a>> for Ipv4Header {
+    type Error = ReadError;
+
+    fn try_from(value: &Ipv4Packet<'a>) -> Result<Ipv4Header, ReadError> {
+        Ipv4Header::from_slice(value.packet()).map(|(header, _)| header)
+    }
+}
+
+impl<'a> From<&Ipv4HeaderSlice<'a>> for Ipv4Packet<'a> {
+    fn from(value: &Ipv4HeaderSlice<'a>) -> Ipv4Packet<'a> {
+        // the slice always has the minimum length required by Ipv4Packet
+        Ipv4Packet::new(value.slice()).unwrap()
+    }
+}
+
+impl TryFrom<&Ipv4Header> for Ipv4Packet<'static> {
+    type Error = WriteError;
+
+    fn try_from(value: &Ipv4Header) -> Result<Ipv4Packet<'static>, WriteError> {
+        let mut buffer = Vec::with_capacity(value.header_len());
+        value.write(&mut buffer)?;
+        // the serialized header always has the minimum length required by Ipv4Packet
+        Ok(Ipv4Packet::owned(buffer).unwrap())
+    }
+}
+
+impl<'a, 'p> TryFrom<&'p TcpPacket<'a>> for TcpHeaderSlice<'p> {
+    type Error = ReadError;
+
+    fn try_from(value: &'p TcpPacket<'a>) -> Result<TcpHeaderSlice<'p>, ReadError> {
+        TcpHeaderSlice::from_slice(value.packet())
+    }
+}
+
+impl<'a> TryFrom<&TcpPacket<'a>> for TcpHeader {
+    type Error = ReadError;
+
+    fn try_from(value: &TcpPacket<'a>) -> Result<TcpHeader, ReadError> {
+        TcpHeader::from_slice(value.packet()).map(|(header, _)| header)
+    }
+}
+
+impl<'a> From<&TcpHeaderSlice<'a>> for TcpPacket<'a> {
+    fn from(value: &TcpHeaderSlice<'a>) -> TcpPacket<'a> {
+        // the slice always has the minimum length required by TcpPacket
+        TcpPacket::new(value.slice()).unwrap()
+    }
+}
+
+impl From<&TcpHeader> for TcpPacket<'static> {
+    fn from(value: &TcpHeader) -> TcpPacket<'static> {
+        let mut buffer = Vec::with_capacity(value.header_len().into());
+        // writing to a vec can not fail
+        value.write(&mut buffer).unwrap();
+        // the serialized header always has the minimum length required by TcpPacket
+        TcpPacket::owned(buffer).unwrap()
+    }
+}
@@ -0,0 +1,315 @@
+use super::*;
+
+use std::time::Duration;
+
+/// Message types of PTPv2 messages (IEEE 1588-2008 & IEEE 1588-2019).
+pub mod ptp_message_type {
+    pub const SYNC: u8 = 0x0;
+    pub const DELAY_REQ: u8 = 0x1;
+    pub const PDELAY_REQ: u8 = 0x2;
+    pub const PDELAY_RESP: u8 = 0x3;
+    pub const FOLLOW_UP: u8 = 0x8;
+    pub const DELAY_RESP: u8 = 0x9;
+    pub const PDELAY_RESP_FOLLOW_UP: u8 = 0xA;
+    pub const ANNOUNCE: u8 = 0xB;
+    pub const SIGNALING: u8 = 0xC;
+    pub const MANAGEMENT: u8 = 0xD;
+}
+
+/// Bits of the flag field of PTPv2 messages.
+pub mod ptp_flags {
+    pub const LEAP61: u16 = 0x0001;
+    pub const LEAP59: u16 = 0x0002;
+    pub const CURRENT_UTC_OFFSET_VALID: u16 = 0x0004;
+    pub const PTP_TIMESCALE: u16 = 0x0008;
+    pub const TIME_TRACEABLE: u16 = 0x0010;
+    pub const FREQUENCY_TRACEABLE: u16 = 0x0020;
+    pub const ALTERNATE_MASTER: u16 = 0x0100;
+    /// Set if the timestamp of the message is sent in a follow up message.
+    pub const TWO_STEP: u16 = 0x0200;
+    pub const UNICAST: u16 = 0x0400;
+}
+
+/// PTP timestamp (48 bit seconds & 32 bit nanoseconds since the PTP epoch).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Default)]
+pub struct PtpTimestamp {
+    pub seconds: u64,
+    pub nanoseconds: u32,
+}
+
+impl PtpTimestamp {
+    /// Length of a serialized timestamp in bytes.
+    pub const LEN: usize = 10;
+
+    /// Decodes a timestamp from its serialized form.
+    pub fn from_bytes(bytes: [u8;10]) -> PtpTimestamp {
+        PtpTimestamp{
+            seconds: u64::from_be_bytes([0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]]),
+            nanoseconds: u32::from_be_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]),
+        }
+    }
+
+    /// Returns the time since the PTP epoch as a duration.
+    #[inline]
+    pub fn to_duration(&self) -> Duration {
+        Duration::new(self.seconds, 0) + Duration::from_nanos(u64::from(self.nanoseconds))
+    }
+}
+
+/// A slice containing a PTPv2 message (payload of ethernet frames with the
+/// ether type [`ether_type::PTP`] or of UDP packets with the ports
+/// [`PtpMessageSlice::EVENT_PORT`] & [`PtpMessageSlice::GENERAL_PORT`]).
+///
+/// Only the layout of version 2 messages is decoded, [`PtpMessageSlice::version`]
+/// should be checked before interpreting the fields.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::ptp::{PtpMessageSlice, PtpTimestamp, ptp_message_type};
+///
+/// let mut data = vec![
+///     ptp_message_type::SYNC, 2, // message type & version
+///     0, 44, // message length
+///     0, 0, // domain number & minor sdo id
+///     0x02, 0x00, // flags (two step)
+///     0, 0, 0, 0, 0, 0, 0, 0, // correction field
+///     0, 0, 0, 0, // message type specific
+///     1, 2, 3, 4, 5, 6, 7, 8, 0, 1, // source port identity
+///     0, 42, // sequence id
+///     0, 0, // control field & log message interval
+/// ];
+/// // origin timestamp
+/// data.extend_from_slice(&[0, 0, 0, 0, 0, 10, 0, 0, 0, 5]);
+///
+/// let message = PtpMessageSlice::from_slice(&data).unwrap();
+/// assert_eq!(ptp_message_type::SYNC, message.message_type());
+/// assert_eq!(42, message.sequence_id());
+/// assert!(message.two_step());
+/// assert_eq!(
+///     Some(PtpTimestamp{ seconds: 10, nanoseconds: 5 }),
+///     message.origin_timestamp()
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PtpMessageSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> PtpMessageSlice<'a> {
+    /// Length of the common message header in bytes.
+    pub const HEADER_LEN: usize = 34;
+
+    /// UDP port used by event messages (Sync, Delay_Req, Pdelay_Req & Pdelay_Resp).
+    pub const EVENT_PORT: u16 = 319;
+
+    /// UDP port used by general messages (e.g. Follow_Up & Announce).
+    pub const GENERAL_PORT: u16 = 320;
+
+    /// Creates a PTP message slice from an other slice (data after the
+    /// message length given in the header is not part of the resulting slice).
+    ///
+    /// For messages starting with a timestamp (all message types except
+    /// signaling & management messages) the timestamp is required to be
+    /// present.
+    pub fn from_slice(slice: &'a [u8]) -> Result<PtpMessageSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < PtpMessageSlice::HEADER_LEN {
+            return Err(UnexpectedEndOfSlice(PtpMessageSlice::HEADER_LEN));
+        }
+        let min_len = if has_timestamp(slice[0] & 0xf) {
+            PtpMessageSlice::HEADER_LEN + PtpTimestamp::LEN
+        } else {
+            PtpMessageSlice::HEADER_LEN
+        };
+        let len = usize::from(u16::from_be_bytes([slice[2], slice[3]]));
+        if slice.len() < min_len || len < min_len {
+            return Err(UnexpectedEndOfSlice(min_len));
+        }
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(PtpMessageSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the PTP message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the transport specific nibble (major SDO id, e.g. 1 for
+    /// IEEE 802.1AS).
+    #[inline]
+    pub fn transport_specific(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Read the message type (see [`ptp_message_type`]).
+    #[inline]
+    pub fn message_type(&self) -> u8 {
+        self.slice[0] & 0xf
+    }
+
+    /// Returns true if the message is an event message (timestamped on
+    /// transmission & reception).
+    #[inline]
+    pub fn is_event(&self) -> bool {
+        self.message_type() < ptp_message_type::FOLLOW_UP
+    }
+
+    /// Read the minor version of the protocol (IEEE 1588-2019).
+    #[inline]
+    pub fn minor_version(&self) -> u8 {
+        self.slice[1] >> 4
+    }
+
+    /// Read the version of the protocol (2 for PTPv2).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[1] & 0xf
+    }
+
+    /// Read the length of the message in bytes (including the header).
+    #[inline]
+    pub fn message_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the number of the PTP domain.
+    #[inline]
+    pub fn domain_number(&self) -> u8 {
+        self.slice[4]
+    }
+
+    /// Read the minor SDO id (reserved in IEEE 1588-2008).
+    #[inline]
+    pub fn minor_sdo_id(&self) -> u8 {
+        self.slice[5]
+    }
+
+    /// Read the flag field (see [`ptp_flags`]).
+    #[inline]
+    pub fn flags(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Returns true if the two step flag is set.
+    #[inline]
+    pub fn two_step(&self) -> bool {
+        0 != self.flags() & ptp_flags::TWO_STEP
+    }
+
+    /// Returns true if the unicast flag is set.
+    #[inline]
+    pub fn unicast(&self) -> bool {
+        0 != self.flags() & ptp_flags::UNICAST
+    }
+
+    /// Read the correction field in nanoseconds multiplied by 2^16.
+    #[inline]
+    pub fn correction_field(&self) -> i64 {
+        i64::from_be_bytes([
+            self.slice[8],
+            self.slice[9],
+            self.slice[10],
+            self.slice[11],
+            self.slice[12],
+            self.slice[13],
+            self.slice[14],
+            self.slice[15],
+        ])
+    }
+
+    /// Read the correction field in whole nanoseconds (fractional
+    /// nanoseconds are rounded towards negative infinity).
+    #[inline]
+    pub fn correction_ns(&self) -> i64 {
+        self.correction_field() >> 16
+    }
+
+    /// Returns the slice containing the message type specific field.
+    #[inline]
+    pub fn message_type_specific(&self) -> &'a [u8] {
+        &self.slice[16..20]
+    }
+
+    /// Read the clock identity of the source port.
+    #[inline]
+    pub fn clock_identity(&self) -> [u8;8] {
+        let mut result = [0u8;8];
+        result.copy_from_slice(&self.slice[20..28]);
+        result
+    }
+
+    /// Read the port number of the source port.
+    #[inline]
+    pub fn port_number(&self) -> u16 {
+        u16::from_be_bytes([self.slice[28], self.slice[29]])
+    }
+
+    /// Read the sequence id.
+    #[inline]
+    pub fn sequence_id(&self) -> u16 {
+        u16::from_be_bytes([self.slice[30], self.slice[31]])
+    }
+
+    /// Read the control field (deprecated in PTPv2, kept for PTPv1 compatibility).
+    #[inline]
+    pub fn control_field(&self) -> u8 {
+        self.slice[32]
+    }
+
+    /// Read the logarithm to the base 2 of the message interval in seconds.
+    #[inline]
+    pub fn log_message_interval(&self) -> i8 {
+        self.slice[33] as i8
+    }
+
+    /// Returns the slice containing the message body (data after the header).
+    #[inline]
+    pub fn body(&self) -> &'a [u8] {
+        &self.slice[PtpMessageSlice::HEADER_LEN..]
+    }
+
+    /// Read the timestamp at the start of the message body (None for
+    /// signaling & management messages).
+    ///
+    /// Depending on the message type this is the origin timestamp (Sync,
+    /// Delay_Req, Pdelay_Req & Announce), the precise origin timestamp
+    /// (Follow_Up), the receive timestamp (Delay_Resp), the request receipt
+    /// timestamp (Pdelay_Resp) or the response origin timestamp
+    /// (Pdelay_Resp_Follow_Up).
+    pub fn timestamp(&self) -> Option<PtpTimestamp> {
+        if has_timestamp(self.message_type()) {
+            let mut bytes = [0u8;PtpTimestamp::LEN];
+            bytes.copy_from_slice(&self.body()[..PtpTimestamp::LEN]);
+            Some(PtpTimestamp::from_bytes(bytes))
+        } else {
+            None
+        }
+    }
+
+    /// Read the (precise) origin timestamp of Sync, Delay_Req, Pdelay_Req,
+    /// Announce & Follow_Up messages (None for other message types).
+    pub fn origin_timestamp(&self) -> Option<PtpTimestamp> {
+        use ptp_message_type::*;
+        match self.message_type() {
+            SYNC | DELAY_REQ | PDELAY_REQ | ANNOUNCE | FOLLOW_UP => self.timestamp(),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if messages of the given type start with a timestamp.
+fn has_timestamp(message_type: u8) -> bool {
+    use ptp_message_type::*;
+    matches!(
+        message_type,
+        SYNC | DELAY_REQ | PDELAY_REQ | PDELAY_RESP | FOLLOW_UP | DELAY_RESP |
+        PDELAY_RESP_FOLLOW_UP | ANNOUNCE
+    )
+}
@@ -0,0 +1,231 @@
+// the pyo3 0.22 function macros expand to `?` on values that already are a `PyErr`
+#![allow(clippy::useless_conversion)]
+use super::*;
+use super::compose::{Layer, LayerStack};
+use super::json::{self, JsonMode};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+/// pcap link type of ethernet II frames.
+const LINKTYPE_ETHERNET: u32 = 1;
+/// pcap link type of raw IPv4 & IPv6 packets.
+const LINKTYPE_RAW: u32 = 101;
+
+/// Parses an ethernet II frame & returns the decoded layers as a dict
+/// (same layout as [`json::to_json`] in the default mode).
+#[pyfunction]
+pub fn parse_ethernet<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    to_py(py, SlicedPacket::from_ethernet(data))
+}
+
+/// Parses an IPv4 or IPv6 packet & returns the decoded layers as a dict.
+#[pyfunction]
+pub fn parse_ip<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    to_py(py, SlicedPacket::from_ip(data))
+}
+
+/// Parses a packet based on a pcap link type (1 = ethernet, 101 = raw ip)
+/// & returns the decoded layers as a dict.
+#[pyfunction]
+pub fn parse_link_type<'py>(py: Python<'py>, link_type: u32, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    to_py(py, slice(link_type, data)?)
+}
+
+/// Returns a human readable multi line description of all headers in the
+/// packet.
+#[pyfunction]
+#[pyo3(signature = (data, link_type = LINKTYPE_ETHERNET))]
+pub fn pretty_print(data: &[u8], link_type: u32) -> PyResult<String> {
+    let headers = match link_type {
+        LINKTYPE_ETHERNET => PacketHeaders::from_ethernet_slice(data),
+        LINKTYPE_RAW => PacketHeaders::from_ip_slice(data),
+        _ => return Err(unsupported_link_type(link_type)),
+    };
+    headers
+        .map(|headers| format!("{:#?}", headers))
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Serializes a list of layer dicts to a packet.
+///
+/// Every entry is a dict with a single key naming the layer (`ethernet2`,
+/// `single_vlan`, `ipv4`, `ipv6`, `udp`, `tcp` or `payload`). Field names
+/// match the ones returned by the parse functions. Lengths, checksums,
+/// ether types & ip numbers are resolved during serialization.
+#[pyfunction]
+pub fn build_packet<'py>(py: Python<'py>, layers: Vec<Bound<'py, PyAny>>) -> PyResult<Bound<'py, PyBytes>> {
+    let mut stack = LayerStack::new();
+    for entry in layers.iter() {
+        stack.push(layer(entry)?);
+    }
+    let bytes = stack.to_bytes().map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(PyBytes::new_bound(py, &bytes))
+}
+
+/// Python module containing the parse, build & pretty print functions.
+///
+/// The module is registered as `etherparse`. To build an importable
+/// extension compile a `cdylib` crate depending on etherparse with the
+/// feature `python` enabled (e.g. via maturin):
+///
+/// ```python
+/// import etherparse
+///
+/// packet = etherparse.build_packet([
+///     {"ethernet2": {"source": "00:11:22:33:44:55", "destination": "66:77:88:99:aa:bb"}},
+///     {"ipv4": {"source": "192.168.1.1", "destination": "192.168.1.2", "time_to_live": 64}},
+///     {"udp": {"source_port": 1234, "destination_port": 53}},
+///     {"payload": b"\x01\x02\x03\x04"},
+/// ])
+/// assert etherparse.parse_ethernet(packet)["udp"]["destination_port"] == 53
+/// print(etherparse.pretty_print(packet))
+/// ```
+#[pymodule]
+pub fn etherparse(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse_ethernet, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_ip, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_link_type, module)?)?;
+    module.add_function(wrap_pyfunction!(pretty_print, module)?)?;
+    module.add_function(wrap_pyfunction!(build_packet, module)?)?;
+    Ok(())
+}
+
+fn slice(link_type: u32, data: &[u8]) -> PyResult<Result<SlicedPacket<'_>, ReadError>> {
+    match link_type {
+        LINKTYPE_ETHERNET => Ok(SlicedPacket::from_ethernet(data)),
+        LINKTYPE_RAW => Ok(SlicedPacket::from_ip(data)),
+        _ => Err(unsupported_link_type(link_type)),
+    }
+}
+
+fn unsupported_link_type(link_type: u32) -> PyErr {
+    PyValueError::new_err(format!("unsupported link type {}", link_type))
+}
+
+fn to_py<'py>(py: Python<'py>, sliced: Result<SlicedPacket, ReadError>) -> PyResult<Bound<'py, PyAny>> {
+    let sliced = sliced.map_err(|err| PyValueError::new_err(err.to_string()))?;
+    py.import_bound("json")?.call_method1("loads", (json::to_json(&sliced, JsonMode::Default, None),))
+}
+
+fn layer(entry: &Bound<'_, PyAny>) -> PyResult<Layer> {
+    if let Some(value) = member(entry, "ethernet2")? {
+        Ok(Layer::Ethernet2(Ethernet2Header{
+            source: mac(&field(&value, "source")?)?,
+            destination: mac(&field(&value, "destination")?)?,
+            ether_type: 0,
+        }))
+    } else if let Some(value) = member(entry, "single_vlan")? {
+        let priority_code_point = optional(&value, "priority_code_point", 0u8)?;
+        let vlan_identifier: u16 = field(&value, "vlan_identifier")?.extract()?;
+        if priority_code_point > 0b111 || vlan_identifier > 0xfff {
+            return Err(PyValueError::new_err("priority_code_point or vlan_identifier out of range"));
+        }
+        Ok(Layer::SingleVlan(SingleVlanHeader{
+            priority_code_point,
+            drop_eligible_indicator: optional(&value, "drop_eligible_indicator", false)?,
+            vlan_identifier,
+            ether_type: 0,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        }))
+    } else if let Some(value) = member(entry, "ipv4")? {
+        Ok(Layer::Ipv4(Ipv4Header::new(
+            0,
+            optional(&value, "time_to_live", 64)?,
+            IpNumber::Udp,
+            ipv4(&field(&value, "source")?)?,
+            ipv4(&field(&value, "destination")?)?,
+        )))
+    } else if let Some(value) = member(entry, "ipv6")? {
+        let flow_label = optional(&value, "flow_label", 0u32)?;
+        if flow_label > 0xfffff {
+            return Err(PyValueError::new_err("flow_label out of range"));
+        }
+        Ok(Layer::Ipv6(Ipv6Header{
+            traffic_class: optional(&value, "traffic_class", 0)?,
+            flow_label,
+            payload_length: 0,
+            next_header: 0,
+            hop_limit: optional(&value, "hop_limit", 64)?,
+            source: ipv6(&field(&value, "source")?)?,
+            destination: ipv6(&field(&value, "destination")?)?,
+        }))
+    } else if let Some(value) = member(entry, "udp")? {
+        Ok(Layer::Udp(UdpHeader{
+            source_port: field(&value, "source_port")?.extract()?,
+            destination_port: field(&value, "destination_port")?.extract()?,
+            length: 0,
+            checksum: 0,
+        }))
+    } else if let Some(value) = member(entry, "tcp")? {
+        let mut header = TcpHeader::new(
+            field(&value, "source_port")?.extract()?,
+            field(&value, "destination_port")?.extract()?,
+            optional(&value, "sequence_number", 0)?,
+            optional(&value, "window_size", 0xffff)?,
+        );
+        header.syn = optional(&value, "syn", false)?;
+        header.fin = optional(&value, "fin", false)?;
+        header.rst = optional(&value, "rst", false)?;
+        header.psh = optional(&value, "psh", false)?;
+        if let Some(ack) = member(&value, "acknowledgment_number")? {
+            header.ack = true;
+            header.acknowledgment_number = ack.extract()?;
+        }
+        Ok(Layer::Tcp(header))
+    } else if let Some(value) = member(entry, "payload")? {
+        Ok(Layer::Payload(value.extract()?))
+    } else {
+        Err(PyValueError::new_err("unknown layer (expected ethernet2, single_vlan, ipv4, ipv6, udp, tcp or payload)"))
+    }
+}
+
+/// Returns the entry with the given key if it is set (and not `None`).
+fn member<'py>(object: &Bound<'py, PyAny>, name: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let dict = object.downcast::<PyDict>()?;
+    Ok(dict.get_item(name)?.filter(|value| !value.is_none()))
+}
+
+fn field<'py>(object: &Bound<'py, PyAny>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    member(object, name)?.ok_or_else(|| PyValueError::new_err(format!("missing field '{}'", name)))
+}
+
+fn optional<'py, T: FromPyObject<'py>>(object: &Bound<'py, PyAny>, name: &str, default: T) -> PyResult<T> {
+    match member(object, name)? {
+        Some(value) => value.extract(),
+        None => Ok(default),
+    }
+}
+
+/// Converts a string (using the given parser) or bytes to an address.
+fn address<const N: usize>(value: &Bound<'_, PyAny>, name: &str, parse: fn(&str) -> Option<[u8;N]>) -> PyResult<[u8;N]> {
+    let result = match value.extract::<String>() {
+        Ok(text) => parse(&text),
+        Err(_) => {
+            let bytes: Vec<u8> = value.extract()?;
+            let mut result = [0u8;N];
+            if bytes.len() == N {
+                result.copy_from_slice(&bytes);
+                Some(result)
+            } else {
+                None
+            }
+        }
+    };
+    result.ok_or_else(|| PyValueError::new_err(format!("invalid {} address", name)))
+}
+
+fn mac(value: &Bound<'_, PyAny>) -> PyResult<[u8;6]> {
+    address(value, "mac", json::parse_mac)
+}
+
+fn ipv4(value: &Bound<'_, PyAny>) -> PyResult<[u8;4]> {
+    address(value, "ipv4", |text| Ipv4Addr::from_str(text).ok().map(|addr| addr.octets()))
+}
+
+fn ipv6(value: &Bound<'_, PyAny>) -> PyResult<[u8;16]> {
+    address(value, "ipv6", |text| Ipv6Addr::from_str(text).ok().map(|addr| addr.octets()))
+}
@@ -0,0 +1,295 @@
+use super::*;
+
+use std::ffi::CString;
+use std::mem;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// Default size of the receive buffer (large enough for frames with the
+/// maximum ip packet size).
+const DEFAULT_BUFFER_LEN: usize = 0x10000 + 64;
+
+/// Socket sending & receiving raw link layer frames on a single network
+/// interface (requires the feature `raw_socket`).
+///
+/// On Linux & Android an `AF_PACKET` socket is used, on macOS, FreeBSD &
+/// DragonFly a BPF device (`/dev/bpf*`). Opening the socket usually
+/// requires elevated privileges (e.g. root or `CAP_NET_RAW` on Linux).
+///
+/// # Example
+///
+/// ```no_run
+/// use etherparse::{PacketBuilder, raw_socket::RawSocket};
+///
+/// let mut socket = RawSocket::open("eth0").unwrap();
+///
+/// // send an udp packet
+/// let builder = PacketBuilder::
+///     ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234);
+/// socket.send_udp(builder, &[1,2,3,4]).unwrap();
+///
+/// // receive frames
+/// loop {
+///     match socket.recv_sliced() {
+///         Ok(packet) => println!("{:?}", packet.ip),
+///         Err(err) => println!("Err {:?}", err),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RawSocket {
+    fd: RawFd,
+    /// Buffer frames are received into.
+    recv_buffer: Vec<u8>,
+    /// Start & end of the not yet returned data in the receive buffer
+    /// (a read from a BPF device can return multiple frames).
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    recv_pos: (usize, usize),
+    /// Buffer packets created by a packet builder are serialized into.
+    send_buffer: Vec<u8>,
+}
+
+impl RawSocket {
+
+    /// Opens a raw socket for the network interface with the given name (e.g. `eth0`).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn open(interface: &str) -> io::Result<RawSocket> {
+        let name = interface_name(interface)?;
+        let protocol = (libc::ETH_P_ALL as u16).to_be();
+
+        // SAFETY: All pointers passed to the libc functions point to valid
+        // values & the file descriptor is owned by the returned socket (or
+        // closed on error).
+        unsafe {
+            let index = libc::if_nametoindex(name.as_ptr());
+            if 0 == index {
+                return Err(io::Error::last_os_error());
+            }
+
+            let fd = libc::socket(libc::AF_PACKET, libc::SOCK_RAW | libc::SOCK_CLOEXEC, i32::from(protocol));
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // owns the fd from here on & closes it on errors
+            let result = RawSocket::with_fd(fd, DEFAULT_BUFFER_LEN);
+
+            let mut addr: libc::sockaddr_ll = mem::zeroed();
+            addr.sll_family = libc::AF_PACKET as u16;
+            addr.sll_protocol = protocol;
+            addr.sll_ifindex = index as i32;
+            if 0 != libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t
+            ) {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(result)
+        }
+    }
+
+    /// Opens a raw socket for the network interface with the given name (e.g. `en0`).
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn open(interface: &str) -> io::Result<RawSocket> {
+        let name = interface_name(interface)?;
+
+        // find an unused bpf device
+        let mut fd = -1;
+        for i in 0..256 {
+            let path = CString::new(format!("/dev/bpf{}", i)).unwrap();
+            // SAFETY: The path is a valid null terminated string.
+            fd = unsafe { libc::open(path.as_ptr(), libc::O_RDWR | libc::O_CLOEXEC) };
+            if fd >= 0 {
+                break;
+            }
+            let err = io::Error::last_os_error();
+            if Some(libc::EBUSY) != err.raw_os_error() {
+                return Err(err);
+            }
+        }
+        if fd < 0 {
+            return Err(io::Error::new(io::ErrorKind::Other, "no unused bpf device available"));
+        }
+
+        // SAFETY: All pointers passed to ioctl point to valid values of the
+        // type expected by the request & the file descriptor is owned by the
+        // returned socket (or closed on error).
+        unsafe {
+            let mut result = RawSocket::with_fd(fd, 0);
+
+            // the buffer size has to match the size used by the device
+            let mut buffer_len: libc::c_uint = 0;
+            if libc::ioctl(fd, libc::BIOCGBLEN, &mut buffer_len as *mut libc::c_uint) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            result.recv_buffer = vec![0;buffer_len as usize];
+
+            let mut request: libc::ifreq = mem::zeroed();
+            let bytes = name.as_bytes_with_nul();
+            if bytes.len() > request.ifr_name.len() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "interface name too long"));
+            }
+            for (dst, src) in request.ifr_name.iter_mut().zip(bytes) {
+                *dst = *src as libc::c_char;
+            }
+            if libc::ioctl(fd, libc::BIOCSETIF, &request as *const libc::ifreq) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // return frames as soon as they are received
+            let enable: libc::c_uint = 1;
+            if libc::ioctl(fd, libc::BIOCIMMEDIATE, &enable as *const libc::c_uint) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // use the source mac address from sent frames
+            if libc::ioctl(fd, libc::BIOCSHDRCMPLT, &enable as *const libc::c_uint) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(result)
+        }
+    }
+
+    fn with_fd(fd: RawFd, recv_buffer_len: usize) -> RawSocket {
+        RawSocket {
+            fd,
+            recv_buffer: vec![0;recv_buffer_len],
+            #[cfg(not(any(target_os = "linux", target_os = "android")))]
+            recv_pos: (0, 0),
+            send_buffer: Vec::new(),
+        }
+    }
+
+    /// Blocks until a frame is received & returns the frame.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn recv(&mut self) -> io::Result<&[u8]> {
+        // SAFETY: The buffer pointer & length describe a valid writeable buffer.
+        let len = unsafe {
+            libc::recv(
+                self.fd,
+                self.recv_buffer.as_mut_ptr() as *mut libc::c_void,
+                self.recv_buffer.len(),
+                0
+            )
+        };
+        if len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(&self.recv_buffer[..len as usize])
+    }
+
+    /// Blocks until a frame is received & returns the frame.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn recv(&mut self) -> io::Result<&[u8]> {
+        // a read can return multiple frames, each prefixed with a bpf_hdr
+        // and aligned to BPF_ALIGNMENT
+        while self.recv_pos.0 >= self.recv_pos.1 {
+            // SAFETY: The buffer pointer & length describe a valid writeable buffer.
+            let len = unsafe {
+                libc::read(
+                    self.fd,
+                    self.recv_buffer.as_mut_ptr() as *mut libc::c_void,
+                    self.recv_buffer.len()
+                )
+            };
+            if len < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            self.recv_pos = (0, len as usize);
+        }
+
+        let (start, end) = self.recv_pos;
+        if end - start < mem::size_of::<libc::bpf_hdr>() {
+            self.recv_pos = (0, 0);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "incomplete bpf header"));
+        }
+        // SAFETY: The buffer contains at least size_of::<bpf_hdr>() bytes after start.
+        let header: libc::bpf_hdr = unsafe {
+            std::ptr::read_unaligned(self.recv_buffer[start..].as_ptr() as *const libc::bpf_hdr)
+        };
+        let frame_start = start + usize::from(header.bh_hdrlen);
+        let frame_end = frame_start + header.bh_caplen as usize;
+        if frame_end > end {
+            self.recv_pos = (0, 0);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "bpf frame exceeds the read data"));
+        }
+        self.recv_pos.0 = bpf_word_align(frame_end);
+        Ok(&self.recv_buffer[frame_start..frame_end])
+    }
+
+    /// Blocks until a frame is received & slices it starting with an
+    /// Ethernet II header (see [`SlicedPacket::from_ethernet`]).
+    ///
+    /// Errors when receiving the frame are returned as [`ReadError::IoError`].
+    pub fn recv_sliced(&mut self) -> Result<SlicedPacket<'_>, ReadError> {
+        SlicedPacket::from_ethernet(self.recv()?)
+    }
+
+    /// Sends a complete frame (including the Ethernet II header).
+    pub fn send(&self, frame: &[u8]) -> io::Result<()> {
+        // SAFETY: The pointer & length describe a valid readable buffer.
+        let len = unsafe {
+            libc::write(self.fd, frame.as_ptr() as *const libc::c_void, frame.len())
+        };
+        if len < 0 {
+            Err(io::Error::last_os_error())
+        } else if len as usize != frame.len() {
+            Err(io::Error::new(io::ErrorKind::WriteZero, "frame was only partially sent"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Serializes the udp packet & sends it (the packet has to start with
+    /// an Ethernet II header).
+    pub fn send_udp(&mut self, builder: PacketBuilderStep<UdpHeader>, payload: &[u8]) -> Result<(), WriteError> {
+        let mut buffer = mem::take(&mut self.send_buffer);
+        buffer.clear();
+        buffer.reserve(builder.size(payload.len()));
+        let result = builder.write(&mut buffer, payload)
+            .and_then(|_| Ok(self.send(&buffer)?));
+        self.send_buffer = buffer;
+        result
+    }
+
+    /// Serializes the tcp packet & sends it (the packet has to start with
+    /// an Ethernet II header).
+    pub fn send_tcp(&mut self, builder: PacketBuilderStep<TcpHeader>, payload: &[u8]) -> Result<(), WriteError> {
+        let mut buffer = mem::take(&mut self.send_buffer);
+        buffer.clear();
+        buffer.reserve(builder.size(payload.len()));
+        let result = builder.write(&mut buffer, payload)
+            .and_then(|_| Ok(self.send(&buffer)?));
+        self.send_buffer = buffer;
+        result
+    }
+}
+
+impl AsRawFd for RawSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for RawSocket {
+    fn drop(&mut self) {
+        // SAFETY: The file descriptor is owned by the socket.
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+fn interface_name(interface: &str) -> io::Result<CString> {
+    CString::new(interface).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "interface name contains a null byte"))
+}
+
+/// Aligns the offset to the alignment of the frames returned by a BPF device (`BPF_WORDALIGN`).
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn bpf_word_align(offset: usize) -> usize {
+    #[cfg(target_os = "macos")]
+    const ALIGNMENT: usize = mem::size_of::<i32>();
+    #[cfg(not(target_os = "macos"))]
+    const ALIGNMENT: usize = mem::size_of::<libc::c_long>();
+    (offset + ALIGNMENT - 1) & !(ALIGNMENT - 1)
+}
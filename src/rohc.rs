@@ -0,0 +1,1019 @@
+use super::*;
+
+/// Profile identifier of the uncompressed profile (RFC 3095 section 5.10).
+pub const PROFILE_UNCOMPRESSED: u8 = 0x00;
+
+/// Profile identifier of the RTP/UDP/IP profile (RFC 3095 section 5.7).
+pub const PROFILE_RTP: u8 = 0x01;
+
+/// Largest supported context identifier (only small CIDs are supported).
+pub const MAX_CID: u8 = 15;
+
+/// Number of IR (or IR-DYN) packets sent before the compressor
+/// transitions to a higher compression state.
+const REPETITIONS: u8 = 3;
+
+/// Number of packets after which the compressor falls back to sending
+/// IR packets (periodic refresh as no feedback is used in U-mode).
+const REFRESH_INTERVAL: usize = 256;
+
+/// Errors that can occur when decompressing a ROHC packet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RohcError {
+    /// The packet ended before all expected fields could be read.
+    UnexpectedEnd,
+    /// The packet type (first octet after the optional Add-CID octet) is not supported.
+    UnsupportedPacketType(u8),
+    /// The profile of an IR packet is not supported.
+    UnsupportedProfile(u8),
+    /// A compressed packet referenced a context identifier (CID) for which
+    /// no (matching) context was established by an IR packet.
+    UnknownContext(u8),
+    /// The IP version in the static chain is neither 4 nor 6.
+    UnsupportedIpVersion(u8),
+    /// The CRC in the packet does not match the decompressed headers.
+    CrcMismatch,
+    /// The payload is too large to be represented in the length fields of
+    /// the decompressed headers.
+    PayloadTooLarge(usize),
+}
+
+impl fmt::Display for RohcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RohcError::*;
+        match self {
+            UnexpectedEnd => write!(f, "RohcError: Packet ended before all expected fields could be read."),
+            UnsupportedPacketType(value) => write!(f, "RohcError: Packet type 0x{:02x} is not supported.", value),
+            UnsupportedProfile(value) => write!(f, "RohcError: Profile 0x{:02x} is not supported.", value),
+            UnknownContext(cid) => write!(f, "RohcError: No context established for the CID {}.", cid),
+            UnsupportedIpVersion(value) => write!(f, "RohcError: IP version {} in the static chain is not supported.", value),
+            CrcMismatch => write!(f, "RohcError: CRC does not match the decompressed headers."),
+            PayloadTooLarge(len) => write!(f, "RohcError: Payload length {} is too large for the decompressed headers.", len),
+        }
+    }
+}
+
+impl Error for RohcError {}
+
+/// Fixed part of a RTP header (RFC 3550, without the CSRC list & extension).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct RtpHeader {
+    pub padding: bool,
+    pub extension: bool,
+    /// Number of CSRC identifiers following the fixed header.
+    pub csrc_count: u8,
+    pub marker: bool,
+    pub payload_type: u8,
+    pub sequence_number: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+}
+
+impl RtpHeader {
+    /// Size of the fixed part of the header in bytes.
+    pub const SERIALIZED_SIZE: usize = 12;
+
+    /// Decodes the fixed part of a RTP header. Returns None if the slice is
+    /// too short or the version is not 2.
+    pub fn from_slice(slice: &[u8]) -> Option<RtpHeader> {
+        if slice.len() < RtpHeader::SERIALIZED_SIZE || 2 != slice[0] >> 6 {
+            return None;
+        }
+        Some(RtpHeader {
+            padding: 0 != slice[0] & 0x20,
+            extension: 0 != slice[0] & 0x10,
+            csrc_count: slice[0] & 0xf,
+            marker: 0 != slice[1] & 0x80,
+            payload_type: slice[1] & 0x7f,
+            sequence_number: u16::from_be_bytes([slice[2], slice[3]]),
+            timestamp: u32::from_be_bytes([slice[4], slice[5], slice[6], slice[7]]),
+            ssrc: u32::from_be_bytes([slice[8], slice[9], slice[10], slice[11]]),
+        })
+    }
+
+    /// Returns the serialized header.
+    pub fn to_bytes(&self) -> [u8;12] {
+        let sn = self.sequence_number.to_be_bytes();
+        let ts = self.timestamp.to_be_bytes();
+        let ssrc = self.ssrc.to_be_bytes();
+        [
+            (2 << 6) |
+            if self.padding { 0x20 } else { 0 } |
+            if self.extension { 0x10 } else { 0 } |
+            (self.csrc_count & 0xf),
+            if self.marker { 0x80 } else { 0 } | (self.payload_type & 0x7f),
+            sn[0], sn[1],
+            ts[0], ts[1], ts[2], ts[3],
+            ssrc[0], ssrc[1], ssrc[2], ssrc[3],
+        ]
+    }
+}
+
+/// State of a compressor context.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CompressorState {
+    /// Initialization & refresh (IR packets containing all fields are sent).
+    InitializationAndRefresh,
+    /// First order (IR-DYN packets are sent to communicate changed dynamic fields).
+    FirstOrder,
+    /// Second order (UO-0, UO-1 & UOR-2 packets are sent, for the uncompressed
+    /// profile the packets are sent without a ROHC header).
+    SecondOrder,
+}
+
+/// ROHC compressor for the uncompressed & the RTP/UDP/IP profile
+/// (RFC 3095) operating in unidirectional mode (U-mode).
+///
+/// IPv4 packets without options & fragmentation and IPv6 packets without
+/// extension headers containing an UDP datagram with a RTP header (version
+/// 2 without CSRCs & header extension) are compressed with the RTP profile.
+/// All other packets are sent using the uncompressed profile.
+///
+/// The packet formats follow RFC 3095 with the following simplifications:
+///
+/// * Only small CIDs (0-15) are supported.
+/// * Extension header & CSRC lists are not part of the dynamic chains
+///   (packets containing them are sent with the uncompressed profile).
+/// * UOR-2 packets are sent without extensions.
+/// * The CRCs of UO packets are calculated over the complete uncompressed
+///   headers & the sequence number interpretation interval is `(ref, ref + 2^k]`.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::PacketBuilder;
+/// use etherparse::rohc::{Compressor, Decompressor};
+///
+/// let mut compressor = Compressor::new(15);
+/// let mut decompressor = Decompressor::new();
+///
+/// for i in 0..10u16 {
+///     // rtp header (version 2, payload type 0, sequence number, timestamp & ssrc)
+///     let mut rtp = vec![0x80, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4];
+///     rtp[2..4].copy_from_slice(&i.to_be_bytes());
+///     rtp[4..8].copy_from_slice(&(u32::from(i)*160).to_be_bytes());
+///     rtp.extend_from_slice(&[0xaa;160]);
+///
+///     let builder = PacketBuilder::
+///         ipv4([192,168,1,1], [192,168,1,2], 64)
+///         .udp(5004, 5004);
+///     let mut packet = Vec::<u8>::with_capacity(builder.size(rtp.len()));
+///     builder.write(&mut packet, &rtp).unwrap();
+///
+///     let mut compressed = Vec::new();
+///     compressor.compress(&packet, &mut compressed);
+///
+///     let mut decompressed = Vec::new();
+///     decompressor.decompress(&compressed, &mut decompressed).unwrap();
+///     assert_eq!(packet, decompressed);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Compressor {
+    max_cid: u8,
+    contexts: Vec<CompressorContext>,
+    /// Number of compressed packets (used to find the least recently used context).
+    packet_count: u64,
+}
+
+#[derive(Clone, Debug)]
+struct CompressorContext {
+    cid: u8,
+    /// Static fields of the flow (None for the context of the uncompressed profile).
+    flow: Option<StaticFields>,
+    /// Dynamic fields of the last packet sent with the RTP profile.
+    reference: Option<DynamicFields>,
+    state: CompressorState,
+    /// Number of packets sent in the current state.
+    state_count: u8,
+    /// Number of packets sent using the context since the last refresh.
+    packet_count: usize,
+    last_used: u64,
+}
+
+impl Compressor {
+    /// Creates a compressor using the context identifiers from 0 up to
+    /// `max_cid` (at most [`MAX_CID`]). If more flows are compressed then
+    /// contexts are available, the least recently used context is replaced.
+    pub fn new(max_cid: u8) -> Compressor {
+        Compressor {
+            max_cid: max_cid.min(MAX_CID),
+            contexts: Vec::new(),
+            packet_count: 0,
+        }
+    }
+
+    /// Returns the profile & the state of the context with the given CID
+    /// (None if no context is established).
+    pub fn context_state(&self, cid: u8) -> Option<(u8, CompressorState)> {
+        self.contexts.iter().find(|c| c.cid == cid).map(|c| (
+            if c.flow.is_some() { PROFILE_RTP } else { PROFILE_UNCOMPRESSED },
+            c.state
+        ))
+    }
+
+    /// Compresses an IPv4 or IPv6 packet & appends the ROHC packet to `output`.
+    pub fn compress(&mut self, packet: &[u8], output: &mut Vec<u8>) {
+        self.packet_count += 1;
+        let parsed = parse_rtp(packet);
+        let flow = parsed.map(|(flow, _, _)| flow);
+
+        let index = match self.contexts.iter().position(|c| c.flow == flow) {
+            Some(index) => index,
+            None => {
+                let cid = if self.contexts.len() <= usize::from(self.max_cid) {
+                    self.contexts.len() as u8
+                } else {
+                    // replace the least recently used context
+                    let (index, _) = self.contexts.iter().enumerate().min_by_key(|(_, c)| c.last_used).unwrap();
+                    self.contexts.swap_remove(index).cid
+                };
+                self.contexts.push(CompressorContext {
+                    cid,
+                    flow,
+                    reference: None,
+                    state: CompressorState::InitializationAndRefresh,
+                    state_count: 0,
+                    packet_count: 0,
+                    last_used: 0,
+                });
+                self.contexts.len() - 1
+            }
+        };
+
+        let context = &mut self.contexts[index];
+        context.last_used = self.packet_count;
+        context.packet_count += 1;
+        if context.packet_count >= REFRESH_INTERVAL {
+            context.packet_count = 0;
+            context.state = CompressorState::InitializationAndRefresh;
+            context.state_count = 0;
+        }
+        if 0 != context.cid {
+            output.push(0xe0 | context.cid);
+        }
+        match parsed {
+            Some((flow, dynamic, header_len)) => context.compress_rtp(&flow, dynamic, &packet[..header_len], &packet[header_len..], output),
+            None => context.compress_uncompressed(packet, output),
+        }
+    }
+}
+
+impl CompressorContext {
+    fn compress_uncompressed(&mut self, packet: &[u8], output: &mut Vec<u8>) {
+        // packets starting with an octet that could be confused with a
+        // ROHC packet type have to be sent as IR packets
+        let ambiguous = packet.first().map(|value| *value >= 0xe0).unwrap_or(true);
+        if CompressorState::InitializationAndRefresh == self.state || ambiguous {
+            let start = output.len();
+            output.extend_from_slice(&[0xfc, PROFILE_UNCOMPRESSED, 0]);
+            output[start + 2] = crc8(&output[start..]);
+            if CompressorState::InitializationAndRefresh == self.state {
+                self.next_state_after_repetitions(CompressorState::SecondOrder);
+            }
+        }
+        output.extend_from_slice(packet);
+    }
+
+    fn compress_rtp(&mut self, flow: &StaticFields, mut dynamic: DynamicFields, header: &[u8], payload: &[u8], output: &mut Vec<u8>) {
+        let ipv4 = flow.is_ipv4();
+
+        if let Some(reference) = self.reference {
+            // the timestamp stride is derived from the change relative to the last packet
+            let sn_delta = u32::from(dynamic.sequence_number.wrapping_sub(reference.sequence_number));
+            let ts_delta = dynamic.timestamp.wrapping_sub(reference.timestamp);
+            dynamic.ts_stride = match ts_delta.checked_div(sn_delta) {
+                Some(stride) if 0 != stride && stride*sn_delta == ts_delta && sdvl_len(stride).is_some() => stride,
+                _ => reference.ts_stride,
+            };
+            // the ip id is considered random if its offset to the sequence number changes
+            dynamic.random_ip_id = ipv4 && (
+                dynamic.ip_id.wrapping_sub(dynamic.sequence_number) !=
+                reference.ip_id.wrapping_sub(reference.sequence_number)
+            );
+        }
+
+        match (self.state, self.reference) {
+            (CompressorState::SecondOrder, Some(reference)) if !dynamic_changed(ipv4, &reference, &dynamic) => {
+                if let Some(uo) = Uo::encode(&reference, &dynamic) {
+                    uo.write(crc_for(&uo, header), output);
+                    // fields appended to the base header
+                    if reference.random_ip_id {
+                        output.extend_from_slice(&dynamic.ip_id.to_be_bytes());
+                    }
+                    if 0 != reference.udp_checksum {
+                        output.extend_from_slice(&dynamic.udp_checksum.to_be_bytes());
+                    }
+                    output.extend_from_slice(payload);
+                    dynamic.ts_stride = reference.ts_stride;
+                    dynamic.random_ip_id = reference.random_ip_id;
+                    self.reference = Some(dynamic);
+                    return;
+                }
+                // the changes can not be represented in an UO packet
+                self.state = CompressorState::FirstOrder;
+                self.state_count = 0;
+            },
+            (CompressorState::SecondOrder, _) => {
+                self.state = CompressorState::FirstOrder;
+                self.state_count = 0;
+            },
+            (CompressorState::FirstOrder, Some(reference)) if dynamic_changed(ipv4, &reference, &dynamic) => {
+                self.state_count = 0;
+            },
+            _ => {},
+        }
+
+        // IR or IR-DYN packet
+        let start = output.len();
+        let ir = CompressorState::InitializationAndRefresh == self.state;
+        output.extend_from_slice(&[if ir { 0xfd } else { 0xf8 }, PROFILE_RTP, 0]);
+        if ir {
+            flow.write_static_chain(output);
+        }
+        dynamic.write_dynamic_chain(ipv4, output);
+        output[start + 2] = crc8(&output[start..]);
+        output.extend_from_slice(payload);
+
+        self.reference = Some(dynamic);
+        self.next_state_after_repetitions(CompressorState::SecondOrder);
+    }
+
+    /// Counts the sent packet & transitions to the next state after
+    /// the packet was sent often enough.
+    fn next_state_after_repetitions(&mut self, next: CompressorState) {
+        self.state_count += 1;
+        if self.state_count >= REPETITIONS {
+            self.state = next;
+            self.state_count = 0;
+        }
+    }
+}
+
+/// ROHC decompressor for the packets created by the [`Compressor`]
+/// (uncompressed & RTP/UDP/IP profile in U-mode with small CIDs).
+///
+/// Contexts are established by IR packets. Packets with an invalid CRC
+/// are rejected without modifying the context.
+#[derive(Clone, Debug, Default)]
+pub struct Decompressor {
+    contexts: [Option<DecompressorContext>;16],
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DecompressorContext {
+    Uncompressed,
+    Rtp(StaticFields, DynamicFields),
+}
+
+impl Decompressor {
+    /// Creates a decompressor without any established contexts.
+    pub fn new() -> Decompressor {
+        Default::default()
+    }
+
+    /// Returns the profile of the context with the given CID (None if
+    /// no context is established).
+    pub fn context_profile(&self, cid: u8) -> Option<u8> {
+        self.contexts.get(usize::from(cid)).and_then(|c| c.as_ref()).map(|c| match c {
+            DecompressorContext::Uncompressed => PROFILE_UNCOMPRESSED,
+            DecompressorContext::Rtp(_, _) => PROFILE_RTP,
+        })
+    }
+
+    /// Decompresses a ROHC packet & appends the resulting IP packet to `output`.
+    ///
+    /// On error nothing is appended to `output`.
+    pub fn decompress(&mut self, packet: &[u8], output: &mut Vec<u8>) -> Result<(), RohcError> {
+        use RohcError::*;
+
+        // skip padding octets
+        let mut rest = packet;
+        while let Some(0xe0) = rest.first() {
+            rest = &rest[1..];
+        }
+        // optional add-cid octet
+        let cid = match rest.first() {
+            Some(value) if 0xe0 == value & 0xf0 => {
+                rest = &rest[1..];
+                value & 0xf
+            },
+            Some(_) => 0,
+            None => return Err(UnexpectedEnd),
+        };
+        let packet_type = *rest.first().ok_or(UnexpectedEnd)?;
+
+        match packet_type {
+            // IR
+            0xfc | 0xfd => {
+                if rest.len() < 3 {
+                    return Err(UnexpectedEnd);
+                }
+                match rest[1] {
+                    PROFILE_UNCOMPRESSED => {
+                        check_crc8(&rest[..3])?;
+                        self.contexts[usize::from(cid)] = Some(DecompressorContext::Uncompressed);
+                        output.extend_from_slice(&rest[3..]);
+                        Ok(())
+                    },
+                    PROFILE_RTP => {
+                        // only IR packets containing the dynamic chain are supported
+                        if 0 == packet_type & 1 {
+                            return Err(UnsupportedPacketType(packet_type));
+                        }
+                        let (flow, static_len) = StaticFields::read_static_chain(&rest[3..])?;
+                        let (dynamic, dynamic_len) = DynamicFields::read_dynamic_chain(flow.is_ipv4(), &rest[3 + static_len..])?;
+                        let header_len = 3 + static_len + dynamic_len;
+                        check_crc8(&rest[..header_len])?;
+                        write_headers(&flow, &dynamic, &rest[header_len..], output)?;
+                        self.contexts[usize::from(cid)] = Some(DecompressorContext::Rtp(flow, dynamic));
+                        Ok(())
+                    },
+                    profile => Err(UnsupportedProfile(profile)),
+                }
+            },
+            // IR-DYN
+            0xf8 => {
+                if rest.len() < 3 {
+                    return Err(UnexpectedEnd);
+                }
+                let flow = match self.contexts[usize::from(cid)] {
+                    Some(DecompressorContext::Rtp(flow, _)) if PROFILE_RTP == rest[1] => flow,
+                    _ => return Err(UnknownContext(cid)),
+                };
+                let (dynamic, dynamic_len) = DynamicFields::read_dynamic_chain(flow.is_ipv4(), &rest[3..])?;
+                let header_len = 3 + dynamic_len;
+                check_crc8(&rest[..header_len])?;
+                write_headers(&flow, &dynamic, &rest[header_len..], output)?;
+                self.contexts[usize::from(cid)] = Some(DecompressorContext::Rtp(flow, dynamic));
+                Ok(())
+            },
+            // feedback, segments & reserved types
+            0xf0..=0xff => Err(UnsupportedPacketType(packet_type)),
+            _ => match self.contexts[usize::from(cid)] {
+                None => Err(UnknownContext(cid)),
+                Some(DecompressorContext::Uncompressed) => {
+                    output.extend_from_slice(rest);
+                    Ok(())
+                },
+                Some(DecompressorContext::Rtp(flow, reference)) => {
+                    let (uo, crc, base_len) = Uo::read(rest)?;
+                    let mut dynamic = uo.decode(&reference);
+                    let mut rest = &rest[base_len..];
+                    if reference.random_ip_id {
+                        if rest.len() < 2 {
+                            return Err(UnexpectedEnd);
+                        }
+                        dynamic.ip_id = u16::from_be_bytes([rest[0], rest[1]]);
+                        rest = &rest[2..];
+                    }
+                    if 0 != reference.udp_checksum {
+                        if rest.len() < 2 {
+                            return Err(UnexpectedEnd);
+                        }
+                        dynamic.udp_checksum = u16::from_be_bytes([rest[0], rest[1]]);
+                        rest = &rest[2..];
+                    }
+
+                    let start = output.len();
+                    write_headers(&flow, &dynamic, rest, output)?;
+                    let header_end = output.len() - rest.len();
+                    if crc != crc_for(&uo, &output[start..header_end]) {
+                        output.truncate(start);
+                        return Err(CrcMismatch);
+                    }
+                    self.contexts[usize::from(cid)] = Some(DecompressorContext::Rtp(flow, dynamic));
+                    Ok(())
+                },
+            },
+        }
+    }
+}
+
+/// Addresses in the static chain of the IP header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum StaticIp {
+    V4 {
+        source: [u8;4],
+        destination: [u8;4],
+    },
+    V6 {
+        flow_label: u32,
+        source: [u8;16],
+        destination: [u8;16],
+    },
+}
+
+/// Fields that identify a flow compressed with the RTP profile.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct StaticFields {
+    ip: StaticIp,
+    source_port: u16,
+    destination_port: u16,
+    ssrc: u32,
+}
+
+impl StaticFields {
+    fn is_ipv4(&self) -> bool {
+        matches!(self.ip, StaticIp::V4{ .. })
+    }
+
+    /// Writes the IP, UDP & RTP static chain (RFC 3095 section 5.7.7).
+    fn write_static_chain(&self, output: &mut Vec<u8>) {
+        match &self.ip {
+            StaticIp::V4{ source, destination } => {
+                output.extend_from_slice(&[4 << 4, ip_number::UDP]);
+                output.extend_from_slice(source);
+                output.extend_from_slice(destination);
+            },
+            StaticIp::V6{ flow_label, source, destination } => {
+                let flow_label = flow_label.to_be_bytes();
+                output.extend_from_slice(&[(6 << 4) | (flow_label[1] & 0xf), flow_label[2], flow_label[3], ip_number::UDP]);
+                output.extend_from_slice(source);
+                output.extend_from_slice(destination);
+            },
+        }
+        output.extend_from_slice(&self.source_port.to_be_bytes());
+        output.extend_from_slice(&self.destination_port.to_be_bytes());
+        output.extend_from_slice(&self.ssrc.to_be_bytes());
+    }
+
+    /// Reads the static chain & returns the fields & the length of the chain.
+    fn read_static_chain(slice: &[u8]) -> Result<(StaticFields, usize), RohcError> {
+        use RohcError::*;
+        let version = slice.first().ok_or(UnexpectedEnd)? >> 4;
+        let (ip, ip_len) = match version {
+            4 => {
+                if slice.len() < 10 {
+                    return Err(UnexpectedEnd);
+                }
+                let mut source = [0u8;4];
+                source.copy_from_slice(&slice[2..6]);
+                let mut destination = [0u8;4];
+                destination.copy_from_slice(&slice[6..10]);
+                (StaticIp::V4{ source, destination }, 10)
+            },
+            6 => {
+                if slice.len() < 36 {
+                    return Err(UnexpectedEnd);
+                }
+                let mut source = [0u8;16];
+                source.copy_from_slice(&slice[4..20]);
+                let mut destination = [0u8;16];
+                destination.copy_from_slice(&slice[20..36]);
+                (
+                    StaticIp::V6{
+                        flow_label: u32::from_be_bytes([0, slice[0] & 0xf, slice[1], slice[2]]),
+                        source,
+                        destination,
+                    },
+                    36
+                )
+            },
+            value => return Err(UnsupportedIpVersion(value)),
+        };
+        let rest = &slice[ip_len..];
+        if rest.len() < 8 {
+            return Err(UnexpectedEnd);
+        }
+        Ok((
+            StaticFields {
+                ip,
+                source_port: u16::from_be_bytes([rest[0], rest[1]]),
+                destination_port: u16::from_be_bytes([rest[2], rest[3]]),
+                ssrc: u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]),
+            },
+            ip_len + 8
+        ))
+    }
+}
+
+/// Fields of a flow compressed with the RTP profile that can change
+/// from packet to packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct DynamicFields {
+    /// IPv4 type of service or IPv6 traffic class.
+    tos: u8,
+    /// IPv4 time to live or IPv6 hop limit.
+    ttl: u8,
+    ip_id: u16,
+    dont_fragment: bool,
+    /// True if the IPv4 identification is not incremented together
+    /// with the RTP sequence number (sent in every packet).
+    random_ip_id: bool,
+    udp_checksum: u16,
+    padding: bool,
+    marker: bool,
+    payload_type: u8,
+    sequence_number: u16,
+    timestamp: u32,
+    /// Increase of the RTP timestamp per sequence number (0 if unknown).
+    ts_stride: u32,
+}
+
+impl DynamicFields {
+    /// Writes the IP, UDP & RTP dynamic chain (RFC 3095 section 5.7.7).
+    fn write_dynamic_chain(&self, ipv4: bool, output: &mut Vec<u8>) {
+        output.extend_from_slice(&[self.tos, self.ttl]);
+        if ipv4 {
+            output.extend_from_slice(&self.ip_id.to_be_bytes());
+            output.push(
+                if self.dont_fragment { 0x80 } else { 0 } |
+                if self.random_ip_id { 0x40 } else { 0 } |
+                // NBO (ip id in network byte order)
+                0x20
+            );
+        }
+        output.extend_from_slice(&self.udp_checksum.to_be_bytes());
+        // version 2, RX always set
+        output.push((2 << 6) | if self.padding { 0x20 } else { 0 } | 0x10);
+        output.push(if self.marker { 0x80 } else { 0 } | self.payload_type);
+        output.extend_from_slice(&self.sequence_number.to_be_bytes());
+        output.extend_from_slice(&self.timestamp.to_be_bytes());
+        // mode U & TSS (ts stride present)
+        let tss = sdvl_len(self.ts_stride).is_some() && 0 != self.ts_stride;
+        output.push((1 << 2) | if tss { 1 } else { 0 });
+        if tss {
+            write_sdvl(self.ts_stride, output);
+        }
+    }
+
+    /// Reads the dynamic chain & returns the fields & the length of the chain.
+    fn read_dynamic_chain(ipv4: bool, slice: &[u8]) -> Result<(DynamicFields, usize), RohcError> {
+        use RohcError::*;
+        let ip_len = if ipv4 { 5 } else { 2 };
+        if slice.len() < ip_len + 2 + 8 {
+            return Err(UnexpectedEnd);
+        }
+        let udp = &slice[ip_len..];
+        let rtp = &udp[2..];
+        let mut result = DynamicFields {
+            tos: slice[0],
+            ttl: slice[1],
+            ip_id: if ipv4 { u16::from_be_bytes([slice[2], slice[3]]) } else { 0 },
+            dont_fragment: ipv4 && 0 != slice[4] & 0x80,
+            random_ip_id: ipv4 && 0 != slice[4] & 0x40,
+            udp_checksum: u16::from_be_bytes([udp[0], udp[1]]),
+            padding: 0 != rtp[0] & 0x20,
+            marker: 0 != rtp[1] & 0x80,
+            payload_type: rtp[1] & 0x7f,
+            sequence_number: u16::from_be_bytes([rtp[2], rtp[3]]),
+            timestamp: u32::from_be_bytes([rtp[4], rtp[5], rtp[6], rtp[7]]),
+            ts_stride: 0,
+        };
+        // csrc lists are not supported
+        if 2 != rtp[0] >> 6 || 0 != rtp[0] & 0xf {
+            return Err(UnsupportedPacketType(rtp[0]));
+        }
+        let mut len = ip_len + 2 + 8;
+        // optional RX fields
+        if 0 != rtp[0] & 0x10 {
+            let flags = *slice.get(len).ok_or(UnexpectedEnd)?;
+            len += 1;
+            // time stride & header extensions are not supported
+            if 0 != flags & 0b1_0010 {
+                return Err(UnsupportedPacketType(flags));
+            }
+            if 0 != flags & 1 {
+                let (value, value_len) = read_sdvl(&slice[len..])?;
+                result.ts_stride = value;
+                len += value_len;
+            }
+        }
+        Ok((result, len))
+    }
+}
+
+/// Returns true if a dynamic field changed in a way that can not be
+/// represented by an UO packet.
+fn dynamic_changed(ipv4: bool, reference: &DynamicFields, dynamic: &DynamicFields) -> bool {
+    reference.tos != dynamic.tos ||
+    reference.ttl != dynamic.ttl ||
+    reference.dont_fragment != dynamic.dont_fragment ||
+    (0 == reference.udp_checksum) != (0 == dynamic.udp_checksum) ||
+    reference.padding != dynamic.padding ||
+    reference.payload_type != dynamic.payload_type ||
+    (ipv4 && !reference.random_ip_id && dynamic.random_ip_id)
+}
+
+/// UO packet base header (sequence number & timestamp bits).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Uo {
+    /// UO-0 (4 bits sequence number).
+    Uo0 { sn: u16 },
+    /// UO-1 (6 bits scaled timestamp, marker & 4 bits sequence number).
+    Uo1 { ts: u32, marker: bool, sn: u16 },
+    /// UOR-2 without extension (6 bits scaled timestamp, marker & 6 bits sequence number).
+    Uor2 { ts: u32, marker: bool, sn: u16 },
+}
+
+impl Uo {
+    /// Returns the smallest UO packet from which the decompressor can
+    /// reconstruct the sequence number, timestamp & marker.
+    fn encode(reference: &DynamicFields, dynamic: &DynamicFields) -> Option<Uo> {
+        let ts_scaled = dynamic.timestamp.checked_div(reference.ts_stride).unwrap_or(0);
+        let candidates = [
+            Uo::Uo0{ sn: dynamic.sequence_number & 0xf },
+            Uo::Uo1{ ts: ts_scaled & 0x3f, marker: dynamic.marker, sn: dynamic.sequence_number & 0xf },
+            Uo::Uor2{ ts: ts_scaled & 0x3f, marker: dynamic.marker, sn: dynamic.sequence_number & 0x3f },
+        ];
+        candidates.iter().find(|uo| {
+            let decoded = uo.decode(reference);
+            decoded.sequence_number == dynamic.sequence_number &&
+            decoded.timestamp == dynamic.timestamp &&
+            decoded.marker == dynamic.marker
+        }).copied()
+    }
+
+    /// Reconstructs the dynamic fields based on the reference & the bits in the packet.
+    fn decode(&self, reference: &DynamicFields) -> DynamicFields {
+        let (sn_bits, sn_k) = match self {
+            Uo::Uo0{ sn } | Uo::Uo1{ sn, .. } => (*sn, 4),
+            Uo::Uor2{ sn, .. } => (*sn, 6),
+        };
+        // interpretation interval (ref, ref + 2^k]
+        let sn_start = reference.sequence_number.wrapping_add(1);
+        let sequence_number = sn_start.wrapping_add(sn_bits.wrapping_sub(sn_start) & ((1 << sn_k) - 1));
+        let sn_delta = u32::from(sequence_number.wrapping_sub(reference.sequence_number));
+
+        let mut result = *reference;
+        result.sequence_number = sequence_number;
+        if !reference.random_ip_id {
+            result.ip_id = reference.ip_id.wrapping_add(sequence_number.wrapping_sub(reference.sequence_number));
+        }
+        match self {
+            Uo::Uo0{ .. } => {
+                result.marker = false;
+                result.timestamp = reference.timestamp.wrapping_add(reference.ts_stride.wrapping_mul(sn_delta));
+            },
+            Uo::Uo1{ ts, marker, .. } | Uo::Uor2{ ts, marker, .. } => {
+                result.marker = *marker;
+                let stride = reference.ts_stride;
+                if let Some(reference_scaled) = reference.timestamp.checked_div(stride) {
+                    // interpretation interval [ref - p, ref + 2^k - 1 - p] with p = 2^(k-2) - 1
+                    let start = reference_scaled.wrapping_sub((1 << 4) - 1);
+                    let scaled = start.wrapping_add(ts.wrapping_sub(start) & 0x3f);
+                    result.timestamp = scaled.wrapping_mul(stride).wrapping_add(reference.timestamp % stride);
+                }
+            },
+        }
+        result
+    }
+
+    fn write(&self, crc: u8, output: &mut Vec<u8>) {
+        match self {
+            Uo::Uo0{ sn } => output.push(((*sn as u8) << 3) | crc),
+            Uo::Uo1{ ts, marker, sn } => output.extend_from_slice(&[
+                0x80 | (*ts as u8),
+                if *marker { 0x80 } else { 0 } | ((*sn as u8) << 3) | crc
+            ]),
+            Uo::Uor2{ ts, marker, sn } => output.extend_from_slice(&[
+                0xc0 | ((*ts as u8) >> 1),
+                ((*ts as u8) << 7) | if *marker { 0x40 } else { 0 } | (*sn as u8),
+                crc
+            ]),
+        }
+    }
+
+    /// Reads the base header & returns the header, the CRC & the length of the base header.
+    fn read(slice: &[u8]) -> Result<(Uo, u8, usize), RohcError> {
+        use RohcError::*;
+        let first = *slice.first().ok_or(UnexpectedEnd)?;
+        if 0 == first & 0x80 {
+            Ok((Uo::Uo0{ sn: u16::from((first >> 3) & 0xf) }, first & 0x7, 1))
+        } else if 0x80 == first & 0xc0 {
+            if slice.len() < 2 {
+                return Err(UnexpectedEnd);
+            }
+            Ok((
+                Uo::Uo1{
+                    ts: u32::from(first & 0x3f),
+                    marker: 0 != slice[1] & 0x80,
+                    sn: u16::from((slice[1] >> 3) & 0xf),
+                },
+                slice[1] & 0x7,
+                2
+            ))
+        } else if 0xc0 == first & 0xe0 {
+            if slice.len() < 3 {
+                return Err(UnexpectedEnd);
+            }
+            // extensions are not supported
+            if 0 != slice[2] & 0x80 {
+                return Err(UnsupportedPacketType(first));
+            }
+            Ok((
+                Uo::Uor2{
+                    ts: u32::from(((first & 0x1f) << 1) | (slice[1] >> 7)),
+                    marker: 0 != slice[1] & 0x40,
+                    sn: u16::from(slice[1] & 0x3f),
+                },
+                slice[2] & 0x7f,
+                3
+            ))
+        } else {
+            Err(UnsupportedPacketType(first))
+        }
+    }
+}
+
+/// CRC of the uncompressed headers as used by the given UO packet type.
+fn crc_for(uo: &Uo, header: &[u8]) -> u8 {
+    match uo {
+        Uo::Uo0{ .. } | Uo::Uo1{ .. } => crc_update(0x7, 0x6, header),
+        Uo::Uor2{ .. } => crc_update(0x7f, 0x79, header),
+    }
+}
+
+/// CRC-8 (RFC 3095 section 5.9.1) over the IR header (the CRC field is expected to be zero).
+fn crc8(data: &[u8]) -> u8 {
+    crc_update(0xff, 0xe0, data)
+}
+
+/// Checks the CRC-8 of an IR or IR-DYN header (CRC in the third octet).
+fn check_crc8(header: &[u8]) -> Result<(), RohcError> {
+    let crc = crc_update(crc_update(crc_update(0xff, 0xe0, &header[..2]), 0xe0, &[0]), 0xe0, &header[3..]);
+    if crc == header[2] {
+        Ok(())
+    } else {
+        Err(RohcError::CrcMismatch)
+    }
+}
+
+/// Bitwise calculation of a reflected CRC with the given polynomial.
+fn crc_update(mut crc: u8, polynomial: u8, data: &[u8]) -> u8 {
+    for byte in data {
+        for i in 0..8 {
+            let bit = (byte >> i) & 1;
+            let feedback = (crc ^ bit) & 1;
+            crc >>= 1;
+            if 0 != feedback {
+                crc ^= polynomial;
+            }
+        }
+    }
+    crc
+}
+
+/// Returns the number of octets needed to encode the value as a
+/// self-describing variable-length value (None if the value is too large).
+fn sdvl_len(value: u32) -> Option<usize> {
+    match value {
+        0..=0x7f => Some(1),
+        0x80..=0x3fff => Some(2),
+        0x4000..=0x1f_ffff => Some(3),
+        0x20_0000..=0x1fff_ffff => Some(4),
+        _ => None,
+    }
+}
+
+/// Writes a self-describing variable-length value (RFC 3095 section 4.5.6).
+fn write_sdvl(value: u32, output: &mut Vec<u8>) {
+    let bytes = value.to_be_bytes();
+    match sdvl_len(value) {
+        Some(1) => output.push(bytes[3]),
+        Some(2) => output.extend_from_slice(&[0x80 | bytes[2], bytes[3]]),
+        Some(3) => output.extend_from_slice(&[0xc0 | bytes[1], bytes[2], bytes[3]]),
+        _ => output.extend_from_slice(&[0xe0 | bytes[0], bytes[1], bytes[2], bytes[3]]),
+    }
+}
+
+/// Reads a self-describing variable-length value & returns the value & its length.
+fn read_sdvl(slice: &[u8]) -> Result<(u32, usize), RohcError> {
+    let first = *slice.first().ok_or(RohcError::UnexpectedEnd)?;
+    let (len, mask) = if 0 == first & 0x80 {
+        (1, 0x7f)
+    } else if 0x80 == first & 0xc0 {
+        (2, 0x3f)
+    } else if 0xc0 == first & 0xe0 {
+        (3, 0x1f)
+    } else {
+        (4, 0x1f)
+    };
+    if slice.len() < len {
+        return Err(RohcError::UnexpectedEnd);
+    }
+    let value = slice[1..len].iter().fold(u32::from(first & mask), |acc, byte| (acc << 8) | u32::from(*byte));
+    Ok((value, len))
+}
+
+/// Decodes an IP/UDP/RTP packet that can be compressed with the RTP
+/// profile & returns the fields & the length of the headers.
+///
+/// Returns None if the packet can not be reconstructed from the fields
+/// (e.g. if it contains options, trailing bytes or invalid checksums).
+fn parse_rtp(packet: &[u8]) -> Option<(StaticFields, DynamicFields, usize)> {
+    let sliced = SlicedPacket::from_ip(packet).ok()?;
+    let headers = sliced.header_bytes();
+    if !headers[4].is_empty() {
+        return None;
+    }
+    let ip = headers[3];
+    let udp = match sliced.transport {
+        Some(TransportSlice::Udp(udp)) => udp,
+        _ => return None,
+    };
+    let rtp = RtpHeader::from_slice(sliced.payload)?;
+    if rtp.extension || 0 != rtp.csrc_count {
+        return None;
+    }
+
+    let (static_ip, tos, ttl, ip_id, dont_fragment) = match sliced.ip? {
+        InternetSlice::Ipv4(header, _) => (
+            StaticIp::V4{ source: header.source(), destination: header.destination() },
+            ip[1],
+            header.ttl(),
+            header.identification(),
+            header.dont_fragment(),
+        ),
+        InternetSlice::Ipv6(header, _) => (
+            StaticIp::V6{ flow_label: header.flow_label(), source: header.source(), destination: header.destination() },
+            header.traffic_class(),
+            header.hop_limit(),
+            0,
+            false,
+        ),
+    };
+    let flow = StaticFields {
+        ip: static_ip,
+        source_port: udp.source_port(),
+        destination_port: udp.destination_port(),
+        ssrc: rtp.ssrc,
+    };
+    let dynamic = DynamicFields {
+        tos,
+        ttl,
+        ip_id,
+        dont_fragment,
+        random_ip_id: false,
+        udp_checksum: udp.checksum(),
+        padding: rtp.padding,
+        marker: rtp.marker,
+        payload_type: rtp.payload_type,
+        sequence_number: rtp.sequence_number,
+        timestamp: rtp.timestamp,
+        ts_stride: 0,
+    };
+
+    // only compress packets that can be reconstructed bit by bit
+    let header_len = ip.len() + UdpHeader::SERIALIZED_SIZE + RtpHeader::SERIALIZED_SIZE;
+    let mut reconstructed = Vec::with_capacity(packet.len());
+    write_headers(&flow, &dynamic, &packet[header_len..], &mut reconstructed).ok()?;
+    if reconstructed[..] == packet[..] {
+        Some((flow, dynamic, header_len))
+    } else {
+        None
+    }
+}
+
+/// Writes the uncompressed IP, UDP & RTP headers followed by the payload.
+fn write_headers(flow: &StaticFields, dynamic: &DynamicFields, payload: &[u8], output: &mut Vec<u8>) -> Result<(), RohcError> {
+    let udp_len = UdpHeader::SERIALIZED_SIZE + RtpHeader::SERIALIZED_SIZE + payload.len();
+    if udp_len > usize::from(u16::MAX) {
+        return Err(RohcError::PayloadTooLarge(payload.len()));
+    }
+    let start = output.len();
+    let ip_result = match flow.ip {
+        StaticIp::V4{ source, destination } => {
+            let mut header = Ipv4Header::new(0, dynamic.ttl, IpNumber::Udp, source, destination);
+            header.differentiated_services_code_point = Dscp::from_bits(dynamic.tos >> 2);
+            header.explicit_congestion_notification = Ecn::from_bits(dynamic.tos);
+            header.identification = dynamic.ip_id;
+            header.dont_fragment = dynamic.dont_fragment;
+            header.set_payload_len(udp_len)
+                .map_err(WriteError::from)
+                .and_then(|_| header.write(output))
+        },
+        StaticIp::V6{ flow_label, source, destination } => Ipv6Header {
+            traffic_class: dynamic.tos,
+            flow_label,
+            payload_length: udp_len as u16,
+            next_header: ip_number::UDP,
+            hop_limit: dynamic.ttl,
+            source,
+            destination,
+        }.write(output),
+    };
+    if ip_result.is_err() {
+        output.truncate(start);
+        return Err(RohcError::PayloadTooLarge(payload.len()));
+    }
+    output.extend_from_slice(&UdpHeader {
+        source_port: flow.source_port,
+        destination_port: flow.destination_port,
+        length: udp_len as u16,
+        checksum: dynamic.udp_checksum,
+    }.to_bytes());
+    output.extend_from_slice(&RtpHeader {
+        padding: dynamic.padding,
+        extension: false,
+        csrc_count: 0,
+        marker: dynamic.marker,
+        payload_type: dynamic.payload_type,
+        sequence_number: dynamic.sequence_number,
+        timestamp: dynamic.timestamp,
+        ssrc: flow.ssrc,
+    }.to_bytes());
+    output.extend_from_slice(payload);
+    Ok(())
+}
@@ -0,0 +1,845 @@
+use super::*;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Dispatch value of an uncompressed IPv6 header (RFC 4944).
+pub const DISPATCH_IPV6: u8 = 0x41;
+
+/// Largest datagram size that can be represented in a fragment header (11 bits).
+pub const MAX_DATAGRAM_SIZE: usize = 0x7ff;
+
+/// Errors that can occur when compressing, decompressing or reassembling 6LoWPAN packets.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SixlowpanError {
+    /// The data ended before all expected fields could be read.
+    UnexpectedEnd,
+    /// The dispatch value is not supported (e.g. mesh or broadcast headers).
+    UnsupportedDispatch(u8),
+    /// The next header compression (NHC) id is not supported (only UDP is supported).
+    UnsupportedNextHeader(u8),
+    /// An address is compressed using a context that is not configured.
+    UnknownContext(u8),
+    /// The combination of the address compression flags is reserved.
+    ReservedAddressMode,
+    /// The datagram is too large to be represented in the length fields.
+    DatagramTooLarge(usize),
+    /// The maximum frame length is too small to fit the fragment headers
+    /// & the compressed headers.
+    FrameTooSmall(usize),
+    /// A fragment does not fit into the datagram size given in its
+    /// fragment header (or contradicts previously received fragments).
+    InvalidFragment,
+}
+
+impl fmt::Display for SixlowpanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SixlowpanError::*;
+        match self {
+            UnexpectedEnd => write!(f, "SixlowpanError: Data ended before all expected fields could be read."),
+            UnsupportedDispatch(value) => write!(f, "SixlowpanError: Dispatch value 0x{:02x} is not supported.", value),
+            UnsupportedNextHeader(value) => write!(f, "SixlowpanError: Next header compression id 0x{:02x} is not supported.", value),
+            UnknownContext(id) => write!(f, "SixlowpanError: Address compression context {} is not configured.", id),
+            ReservedAddressMode => write!(f, "SixlowpanError: Reserved address compression mode."),
+            DatagramTooLarge(len) => write!(f, "SixlowpanError: Datagram length {} is too large.", len),
+            FrameTooSmall(len) => write!(f, "SixlowpanError: Maximum frame length {} is too small for the headers.", len),
+            InvalidFragment => write!(f, "SixlowpanError: Fragment does not fit into the datagram."),
+        }
+    }
+}
+
+impl Error for SixlowpanError {}
+
+/// IEEE 802.15.4 link layer address (used to derive the interface
+/// identifiers of elided IPv6 addresses).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum LinkAddress {
+    /// 16 bit short address.
+    Short([u8;2]),
+    /// 64 bit extended address (EUI-64).
+    Extended([u8;8]),
+}
+
+impl LinkAddress {
+    /// Returns the interface identifier derived from the link layer
+    /// address (RFC 6282 section 3.2.2).
+    pub fn interface_identifier(&self) -> [u8;8] {
+        match self {
+            LinkAddress::Short(value) => [0, 0, 0, 0xff, 0xfe, 0, value[0], value[1]],
+            LinkAddress::Extended(value) => {
+                let mut result = *value;
+                // invert the universal/local bit
+                result[0] ^= 0x02;
+                result
+            },
+        }
+    }
+}
+
+impl From<Ieee802154Address> for LinkAddress {
+    fn from(value: Ieee802154Address) -> LinkAddress {
+        match value {
+            Ieee802154Address::Short(value) => LinkAddress::Short(value),
+            Ieee802154Address::Extended(value) => LinkAddress::Extended(value),
+        }
+    }
+}
+
+/// 6LoWPAN fragment header (RFC 4944 section 5.3).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SixlowpanFragmentHeader {
+    /// Header of the first fragment (FRAG1).
+    First {
+        /// Size of the uncompressed IPv6 datagram.
+        datagram_size: u16,
+        datagram_tag: u16,
+    },
+    /// Header of all following fragments (FRAGN).
+    Subsequent {
+        /// Size of the uncompressed IPv6 datagram.
+        datagram_size: u16,
+        datagram_tag: u16,
+        /// Offset in the uncompressed IPv6 datagram in units of 8 octets.
+        datagram_offset: u8,
+    },
+}
+
+impl SixlowpanFragmentHeader {
+    /// Reads a fragment header at the start of the slice. Returns None if
+    /// the slice does not start with a fragment header dispatch value.
+    pub fn from_slice(slice: &[u8]) -> Result<Option<(SixlowpanFragmentHeader, &[u8])>, SixlowpanError> {
+        let first = match slice.first() {
+            Some(value) => *value,
+            None => return Err(SixlowpanError::UnexpectedEnd),
+        };
+        let len = match first & 0xf8 {
+            0xc0 => 4,
+            0xe0 => 5,
+            _ => return Ok(None),
+        };
+        if slice.len() < len {
+            return Err(SixlowpanError::UnexpectedEnd);
+        }
+        let datagram_size = u16::from_be_bytes([first & 0x7, slice[1]]);
+        let datagram_tag = u16::from_be_bytes([slice[2], slice[3]]);
+        let header = if 4 == len {
+            SixlowpanFragmentHeader::First{ datagram_size, datagram_tag }
+        } else {
+            SixlowpanFragmentHeader::Subsequent{ datagram_size, datagram_tag, datagram_offset: slice[4] }
+        };
+        Ok(Some((header, &slice[len..])))
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        match self {
+            SixlowpanFragmentHeader::First{ .. } => 4,
+            SixlowpanFragmentHeader::Subsequent{ .. } => 5,
+        }
+    }
+
+    /// Size of the uncompressed IPv6 datagram.
+    pub fn datagram_size(&self) -> u16 {
+        match self {
+            SixlowpanFragmentHeader::First{ datagram_size, .. } |
+            SixlowpanFragmentHeader::Subsequent{ datagram_size, .. } => *datagram_size,
+        }
+    }
+
+    /// Tag identifying the fragments of one datagram.
+    pub fn datagram_tag(&self) -> u16 {
+        match self {
+            SixlowpanFragmentHeader::First{ datagram_tag, .. } |
+            SixlowpanFragmentHeader::Subsequent{ datagram_tag, .. } => *datagram_tag,
+        }
+    }
+
+    /// Writes the header (the datagram size is truncated to 11 bits).
+    pub fn write(&self, output: &mut Vec<u8>) {
+        let size = (self.datagram_size() & 0x7ff).to_be_bytes();
+        let tag = self.datagram_tag().to_be_bytes();
+        match self {
+            SixlowpanFragmentHeader::First{ .. } => output.extend_from_slice(&[0xc0 | size[0], size[1], tag[0], tag[1]]),
+            SixlowpanFragmentHeader::Subsequent{ datagram_offset, .. } => output.extend_from_slice(&[0xe0 | size[0], size[1], tag[0], tag[1], *datagram_offset]),
+        }
+    }
+}
+
+/// IPHC & NHC (RFC 6282) compression & decompression of IPv6 & UDP headers.
+///
+/// Addresses are compressed stateless (link-local addresses, addresses
+/// derived from the link layer addresses & multicast addresses) or based
+/// on the configured 64 bit prefixes of the address compression contexts.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv6Header, UdpHeader, ip_number};
+/// use etherparse::sixlowpan::{Iphc, LinkAddress};
+///
+/// let source = LinkAddress::Extended([0, 0x12, 0x4b, 0, 1, 2, 3, 4]);
+/// let destination = LinkAddress::Short([0, 1]);
+/// let ip = Ipv6Header{
+///     traffic_class: 0,
+///     flow_label: 0,
+///     payload_length: 8 + 4,
+///     next_header: ip_number::UDP,
+///     hop_limit: 64,
+///     // link local addresses derived from the link layer addresses
+///     source: [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+///     destination: [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0, 1],
+/// };
+/// let udp = UdpHeader::with_ipv6_checksum(0xf0b1, 0xf0b2, &ip, &[1,2,3,4]).unwrap();
+///
+/// let iphc = Iphc::default();
+/// let mut frame = Vec::new();
+/// iphc.compress(&ip, Some(&udp), source, destination, &mut frame).unwrap();
+/// frame.extend_from_slice(&[1,2,3,4]);
+/// // 2 bytes iphc, 1 byte nhc, 1 byte ports & 2 bytes checksum
+/// assert_eq!(6 + 4, frame.len());
+///
+/// let (decompressed_ip, decompressed_udp, payload) = iphc.decompress(&frame, source, destination).unwrap();
+/// assert_eq!(ip, decompressed_ip);
+/// assert_eq!(Some(udp), decompressed_udp);
+/// assert_eq!(&[1,2,3,4], payload);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Iphc {
+    /// 64 bit prefixes of the address compression contexts 0-15.
+    pub contexts: [Option<[u8;8]>;16],
+}
+
+/// Link local prefix fe80::/64.
+const LINK_LOCAL_PREFIX: [u8;8] = [0xfe, 0x80, 0, 0, 0, 0, 0, 0];
+
+impl Iphc {
+    /// Writes the compressed IPv6 header (followed by the compressed UDP
+    /// header if one is given).
+    ///
+    /// The payload length of the IPv6 header & the length of the UDP
+    /// header are elided (they are derived from the frame or fragment
+    /// headers when decompressing). If an UDP header is given the next
+    /// header field of the IPv6 header is ignored (UDP is assumed).
+    pub fn compress(&self, ip: &Ipv6Header, udp: Option<&UdpHeader>, source: LinkAddress, destination: LinkAddress, output: &mut Vec<u8>) -> Result<(), SixlowpanError> {
+        let start = output.len();
+        output.extend_from_slice(&[0b0110_0000, 0]);
+
+        // traffic class & flow label (ecn is moved in front of the dscp)
+        let ecn = ip.traffic_class & 0b11;
+        let dscp = ip.traffic_class >> 2;
+        let flow_label = (ip.flow_label & 0xf_ffff).to_be_bytes();
+        let tf = match (0 == dscp, 0 == ip.flow_label & 0xf_ffff) {
+            (true, true) if 0 == ecn => 0b11,
+            (_, true) => {
+                output.push((ecn << 6) | dscp);
+                0b10
+            },
+            (true, false) => {
+                output.extend_from_slice(&[(ecn << 6) | flow_label[1], flow_label[2], flow_label[3]]);
+                0b01
+            },
+            (false, false) => {
+                output.extend_from_slice(&[(ecn << 6) | dscp, flow_label[1], flow_label[2], flow_label[3]]);
+                0b00
+            },
+        };
+
+        // next header
+        let nh = if udp.is_some() {
+            1
+        } else {
+            output.push(ip.next_header);
+            0
+        };
+
+        // hop limit
+        let hlim = match ip.hop_limit {
+            1 => 0b01,
+            64 => 0b10,
+            255 => 0b11,
+            value => {
+                output.push(value);
+                0b00
+            },
+        };
+
+        // addresses
+        let (sac, sam, sci) = self.compress_unicast(&ip.source, source, true, output);
+        let (m, dac, dam, dci) = if 0xff == ip.destination[0] {
+            let d = &ip.destination;
+            let dam = if 0x02 == d[1] && d[2..15].iter().all(|v| 0 == *v) {
+                output.push(d[15]);
+                0b11
+            } else if d[2..13].iter().all(|v| 0 == *v) {
+                output.extend_from_slice(&[d[1], d[13], d[14], d[15]]);
+                0b10
+            } else if d[2..11].iter().all(|v| 0 == *v) {
+                output.push(d[1]);
+                output.extend_from_slice(&d[11..16]);
+                0b01
+            } else {
+                output.extend_from_slice(d);
+                0b00
+            };
+            (1, 0, dam, 0)
+        } else {
+            let (dac, dam, dci) = self.compress_unicast(&ip.destination, destination, false, output);
+            (0, dac, dam, dci)
+        };
+
+        // context identifier extension
+        let cid = if 0 != sci || 0 != dci {
+            output.insert(start + 2, (sci << 4) | dci);
+            1
+        } else {
+            0
+        };
+
+        output[start] |= (tf << 3) | (nh << 2) | hlim;
+        output[start + 1] = (cid << 7) | (sac << 6) | (sam << 4) | (m << 3) | (dac << 2) | dam;
+
+        // udp header
+        if let Some(udp) = udp {
+            let src = udp.source_port;
+            let dst = udp.destination_port;
+            if 0xf0b0 == src & 0xfff0 && 0xf0b0 == dst & 0xfff0 {
+                output.push(0b1111_0011);
+                output.push(((src as u8 & 0xf) << 4) | (dst as u8 & 0xf));
+            } else if 0xf000 == dst & 0xff00 {
+                output.push(0b1111_0001);
+                output.extend_from_slice(&src.to_be_bytes());
+                output.push(dst as u8);
+            } else if 0xf000 == src & 0xff00 {
+                output.push(0b1111_0010);
+                output.push(src as u8);
+                output.extend_from_slice(&dst.to_be_bytes());
+            } else {
+                output.push(0b1111_0000);
+                output.extend_from_slice(&src.to_be_bytes());
+                output.extend_from_slice(&dst.to_be_bytes());
+            }
+            output.extend_from_slice(&udp.checksum.to_be_bytes());
+        }
+        Ok(())
+    }
+
+    /// Compresses an unicast address & returns the address context flag,
+    /// the address mode & the context id.
+    fn compress_unicast(&self, address: &[u8;16], link: LinkAddress, source: bool, output: &mut Vec<u8>) -> (u8, u8, u8) {
+        // the unspecified address can only be compressed as source address
+        if source && address.iter().all(|v| 0 == *v) {
+            return (1, 0b00, 0);
+        }
+        let (ac, ci) = if address[..8] == LINK_LOCAL_PREFIX {
+            (0, 0)
+        } else if let Some(ci) = self.contexts.iter().position(|c| Some(&address[..8]) == c.as_ref().map(|c| &c[..])) {
+            (1, ci as u8)
+        } else {
+            output.extend_from_slice(address);
+            return (0, 0b00, 0);
+        };
+        let iid = &address[8..];
+        if iid == link.interface_identifier() {
+            (ac, 0b11, ci)
+        } else if iid[..6] == [0, 0, 0, 0xff, 0xfe, 0] {
+            output.extend_from_slice(&iid[6..]);
+            (ac, 0b10, ci)
+        } else {
+            output.extend_from_slice(iid);
+            (ac, 0b01, ci)
+        }
+    }
+
+    /// Decompresses the headers of an unfragmented 6LoWPAN packet (starting
+    /// with an IPHC or an uncompressed IPv6 dispatch) & returns the headers
+    /// & the payload.
+    ///
+    /// The length fields are derived from the length of the packet & an
+    /// elided UDP checksum is recalculated.
+    pub fn decompress<'a>(&self, packet: &'a [u8], source: LinkAddress, destination: LinkAddress) -> Result<(Ipv6Header, Option<UdpHeader>, &'a [u8]), SixlowpanError> {
+        let (ip, udp, rest) = self.decompress_headers(packet, source, destination, None)?;
+        let udp = match udp {
+            Some((mut udp, checksum_elided)) => {
+                if checksum_elided {
+                    udp.checksum = udp.calc_checksum_ipv6(&ip, rest)
+                        .map_err(|_| SixlowpanError::DatagramTooLarge(packet.len()))?;
+                }
+                Some(udp)
+            },
+            None => None,
+        };
+        Ok((ip, udp, rest))
+    }
+
+    /// Decompresses the headers & sets the length fields based on the given
+    /// IPv6 payload length (or the remaining data if None). Returns the UDP
+    /// header together with a flag indicating if the checksum was elided.
+    fn decompress_headers<'a>(&self, packet: &'a [u8], source: LinkAddress, destination: LinkAddress, ip_payload_len: Option<usize>) -> Result<DecompressedHeaders<'a>, SixlowpanError> {
+        use SixlowpanError::*;
+
+        let dispatch = *packet.first().ok_or(UnexpectedEnd)?;
+        if DISPATCH_IPV6 == dispatch {
+            let (ip, rest) = Ipv6Header::from_slice(&packet[1..]).map_err(|_| UnexpectedEnd)?;
+            return Ok((ip, None, rest));
+        }
+        if 0b0110_0000 != dispatch & 0xe0 {
+            return Err(UnsupportedDispatch(dispatch));
+        }
+        if packet.len() < 2 {
+            return Err(UnexpectedEnd);
+        }
+        let flags = packet[1];
+        let mut reader = Reader(&packet[2..]);
+
+        let (sci, dci) = if 0 != flags & 0x80 {
+            let value = reader.read(1)?[0];
+            (value >> 4, value & 0xf)
+        } else {
+            (0, 0)
+        };
+
+        // traffic class & flow label
+        let (ecn, dscp, flow_label) = match (dispatch >> 3) & 0b11 {
+            0b00 => {
+                let v = reader.read(4)?;
+                (v[0] >> 6, v[0] & 0x3f, u32::from_be_bytes([0, v[1] & 0xf, v[2], v[3]]))
+            },
+            0b01 => {
+                let v = reader.read(3)?;
+                (v[0] >> 6, 0, u32::from_be_bytes([0, v[0] & 0xf, v[1], v[2]]))
+            },
+            0b10 => {
+                let v = reader.read(1)?;
+                (v[0] >> 6, v[0] & 0x3f, 0)
+            },
+            _ => (0, 0, 0),
+        };
+
+        let next_header = if 0 == dispatch & 0b100 {
+            Some(reader.read(1)?[0])
+        } else {
+            None
+        };
+
+        let hop_limit = match dispatch & 0b11 {
+            0b00 => reader.read(1)?[0],
+            0b01 => 1,
+            0b10 => 64,
+            _ => 255,
+        };
+
+        let source_address = self.decompress_unicast(
+            &mut reader,
+            0 != flags & 0x40,
+            (flags >> 4) & 0b11,
+            sci,
+            source,
+            true
+        )?;
+        let destination_address = if 0 != flags & 0x08 {
+            if 0 != flags & 0x04 {
+                return Err(ReservedAddressMode);
+            }
+            let mut d = [0u8;16];
+            d[0] = 0xff;
+            match flags & 0b11 {
+                0b00 => d.copy_from_slice(reader.read(16)?),
+                0b01 => {
+                    let v = reader.read(6)?;
+                    d[1] = v[0];
+                    d[11..].copy_from_slice(&v[1..]);
+                },
+                0b10 => {
+                    let v = reader.read(4)?;
+                    d[1] = v[0];
+                    d[13..].copy_from_slice(&v[1..]);
+                },
+                _ => {
+                    d[1] = 0x02;
+                    d[15] = reader.read(1)?[0];
+                },
+            }
+            d
+        } else {
+            self.decompress_unicast(
+                &mut reader,
+                0 != flags & 0x04,
+                flags & 0b11,
+                dci,
+                destination,
+                false
+            )?
+        };
+
+        // udp header
+        let udp = match next_header {
+            Some(_) => None,
+            None => {
+                let id = reader.read(1)?[0];
+                if 0b1111_0000 != id & 0xf8 {
+                    return Err(UnsupportedNextHeader(id));
+                }
+                let (source_port, destination_port) = match id & 0b11 {
+                    0b00 => {
+                        let v = reader.read(4)?;
+                        (u16::from_be_bytes([v[0], v[1]]), u16::from_be_bytes([v[2], v[3]]))
+                    },
+                    0b01 => {
+                        let v = reader.read(3)?;
+                        (u16::from_be_bytes([v[0], v[1]]), 0xf000 | u16::from(v[2]))
+                    },
+                    0b10 => {
+                        let v = reader.read(3)?;
+                        (0xf000 | u16::from(v[0]), u16::from_be_bytes([v[1], v[2]]))
+                    },
+                    _ => {
+                        let v = reader.read(1)?[0];
+                        (0xf0b0 | u16::from(v >> 4), 0xf0b0 | u16::from(v & 0xf))
+                    },
+                };
+                let checksum_elided = 0 != id & 0b100;
+                let checksum = if checksum_elided {
+                    0
+                } else {
+                    let v = reader.read(2)?;
+                    u16::from_be_bytes([v[0], v[1]])
+                };
+                Some((
+                    UdpHeader{
+                        source_port,
+                        destination_port,
+                        length: 0,
+                        checksum,
+                    },
+                    checksum_elided
+                ))
+            },
+        };
+        let rest = reader.0;
+
+        // length fields
+        let payload_len = match ip_payload_len {
+            Some(value) => value,
+            None => rest.len() + if udp.is_some() { UdpHeader::SERIALIZED_SIZE } else { 0 },
+        };
+        if payload_len > usize::from(u16::MAX) {
+            return Err(DatagramTooLarge(payload_len + Ipv6Header::SERIALIZED_SIZE));
+        }
+        let udp = udp.map(|(mut udp, checksum_elided)| {
+            udp.length = payload_len as u16;
+            (udp, checksum_elided)
+        });
+
+        Ok((
+            Ipv6Header{
+                traffic_class: (dscp << 2) | ecn,
+                flow_label,
+                payload_length: payload_len as u16,
+                next_header: next_header.unwrap_or(ip_number::UDP),
+                hop_limit,
+                source: source_address,
+                destination: destination_address,
+            },
+            udp,
+            rest
+        ))
+    }
+
+    fn decompress_unicast(&self, reader: &mut Reader, context_based: bool, mode: u8, context_id: u8, link: LinkAddress, source: bool) -> Result<[u8;16], SixlowpanError> {
+        let mut result = [0u8;16];
+        if context_based && 0b00 == mode {
+            // unspecified address (source) or reserved (destination)
+            return if source {
+                Ok(result)
+            } else {
+                Err(SixlowpanError::ReservedAddressMode)
+            };
+        }
+        if !context_based && 0b00 == mode {
+            result.copy_from_slice(reader.read(16)?);
+            return Ok(result);
+        }
+        let prefix = if context_based {
+            self.contexts[usize::from(context_id)].ok_or(SixlowpanError::UnknownContext(context_id))?
+        } else {
+            LINK_LOCAL_PREFIX
+        };
+        result[..8].copy_from_slice(&prefix);
+        match mode {
+            0b01 => result[8..].copy_from_slice(reader.read(8)?),
+            0b10 => {
+                result[11] = 0xff;
+                result[12] = 0xfe;
+                result[14..].copy_from_slice(reader.read(2)?);
+            },
+            _ => result[8..].copy_from_slice(&link.interface_identifier()),
+        }
+        Ok(result)
+    }
+
+    /// Compresses the headers & splits the packet into frames with the
+    /// given maximum length (adding fragment headers if the packet does not
+    /// fit into a single frame).
+    ///
+    /// If the next header of the IPv6 header is UDP, the UDP header at the
+    /// start of `ip_payload` gets compressed as well.
+    pub fn compress_fragmented(
+        &self,
+        ip: &Ipv6Header,
+        ip_payload: &[u8],
+        source: LinkAddress,
+        destination: LinkAddress,
+        datagram_tag: u16,
+        max_frame_len: usize
+    ) -> Result<Vec<Vec<u8>>, SixlowpanError> {
+        let (udp, payload) = if ip_number::UDP == ip.next_header {
+            let (udp, payload) = UdpHeader::from_slice(ip_payload).map_err(|_| SixlowpanError::UnexpectedEnd)?;
+            (Some(udp), payload)
+        } else {
+            (None, ip_payload)
+        };
+        let udp = udp.as_ref();
+        let mut headers = Vec::new();
+        self.compress(ip, udp, source, destination, &mut headers)?;
+
+        // unfragmented
+        if headers.len() + payload.len() <= max_frame_len {
+            headers.extend_from_slice(payload);
+            return Ok(vec![headers]);
+        }
+
+        let uncompressed_header_len = Ipv6Header::SERIALIZED_SIZE + if udp.is_some() { UdpHeader::SERIALIZED_SIZE } else { 0 };
+        let datagram_size = uncompressed_header_len + payload.len();
+        if datagram_size > MAX_DATAGRAM_SIZE {
+            return Err(SixlowpanError::DatagramTooLarge(datagram_size));
+        }
+
+        // the offsets of all following fragments have to be multiples of 8
+        let first_space = max_frame_len.checked_sub(4 + headers.len()).ok_or(SixlowpanError::FrameTooSmall(max_frame_len))?;
+        let first_len = ((uncompressed_header_len + first_space) / 8 * 8).saturating_sub(uncompressed_header_len);
+        let subsequent_len = max_frame_len.saturating_sub(5) / 8 * 8;
+        if (first_len + uncompressed_header_len) < 8 || 0 == subsequent_len {
+            return Err(SixlowpanError::FrameTooSmall(max_frame_len));
+        }
+
+        let mut result = Vec::new();
+        let mut frame = Vec::with_capacity(max_frame_len);
+        SixlowpanFragmentHeader::First{ datagram_size: datagram_size as u16, datagram_tag }.write(&mut frame);
+        frame.extend_from_slice(&headers);
+        frame.extend_from_slice(&payload[..first_len]);
+        result.push(frame);
+
+        let mut offset = first_len;
+        while offset < payload.len() {
+            let end = (offset + subsequent_len).min(payload.len());
+            let mut frame = Vec::with_capacity(max_frame_len);
+            SixlowpanFragmentHeader::Subsequent{
+                datagram_size: datagram_size as u16,
+                datagram_tag,
+                datagram_offset: ((uncompressed_header_len + offset) / 8) as u8,
+            }.write(&mut frame);
+            frame.extend_from_slice(&payload[offset..end]);
+            result.push(frame);
+            offset = end;
+        }
+        Ok(result)
+    }
+}
+
+/// Decompressed IPv6 header, UDP header (with a flag set if the checksum
+/// was elided) & the remaining data.
+type DecompressedHeaders<'a> = (Ipv6Header, Option<(UdpHeader, bool)>, &'a [u8]);
+
+/// Helper for reading the inline fields of a compressed header.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, len: usize) -> Result<&'a [u8], SixlowpanError> {
+        if self.0.len() < len {
+            return Err(SixlowpanError::UnexpectedEnd);
+        }
+        let (result, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(result)
+    }
+}
+
+/// Limits enforced by a [`Reassembler`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ReassemblerConfig {
+    /// Time after the first received fragment after which incomplete
+    /// datagrams are discarded (60 seconds as required by RFC 4944).
+    pub timeout: Duration,
+    /// Maximum number of datagrams that are reassembled at the same time.
+    /// If the limit is reached the oldest incomplete datagram is discarded.
+    pub max_datagrams: usize,
+}
+
+impl Default for ReassemblerConfig {
+    fn default() -> ReassemblerConfig {
+        ReassemblerConfig {
+            timeout: Duration::from_secs(60),
+            max_datagrams: 64,
+        }
+    }
+}
+
+/// Reassembles fragmented 6LoWPAN packets into uncompressed IPv6 packets.
+///
+/// Fragments are identified by the link layer addresses, the datagram size
+/// & the datagram tag (RFC 4944 section 5.3). If a fragment overlaps a
+/// previously received fragment without having the same offset & size,
+/// the previously received fragments of the datagram are discarded & the
+/// reassembly is restarted with the new fragment.
+#[derive(Clone, Debug, Default)]
+pub struct Reassembler {
+    config: ReassemblerConfig,
+    buffers: HashMap<(LinkAddress, LinkAddress, u16, u16), ReassemblyBuffer>,
+}
+
+#[derive(Clone, Debug)]
+struct ReassemblyBuffer {
+    /// Timestamp of the first received fragment.
+    start: Duration,
+    data: Vec<u8>,
+    /// Start & end offsets of the received fragments.
+    fragments: Vec<(usize, usize)>,
+    /// Set to true if the udp checksum has to be calculated after reassembly.
+    udp_checksum_elided: bool,
+}
+
+impl ReassemblyBuffer {
+    fn new(start: Duration, size: usize) -> ReassemblyBuffer {
+        ReassemblyBuffer {
+            start,
+            data: vec![0;size],
+            fragments: Vec::new(),
+            udp_checksum_elided: false,
+        }
+    }
+}
+
+impl Reassembler {
+    /// Creates a reassembler without any buffered fragments.
+    pub fn new(config: ReassemblerConfig) -> Reassembler {
+        Reassembler {
+            config,
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Configuration of the reassembler.
+    #[inline]
+    pub fn config(&self) -> &ReassemblerConfig {
+        &self.config
+    }
+
+    /// Number of datagrams for which fragments are buffered.
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns true if no fragments are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Removes all buffered fragments.
+    pub fn clear(&mut self) {
+        self.buffers.clear()
+    }
+
+    /// Discards all incomplete datagrams whose first fragment was received
+    /// `timeout` or more before the given timestamp.
+    pub fn remove_expired(&mut self, timestamp: Duration) {
+        let timeout = self.config.timeout;
+        self.buffers.retain(|_, buffer| timestamp.saturating_sub(buffer.start) < timeout);
+    }
+
+    /// Adds a frame payload (starting with the 6LoWPAN dispatch) & returns
+    /// the uncompressed IPv6 packet once all fragments were received (or
+    /// directly if the packet is not fragmented).
+    ///
+    /// The timestamps are expected to be monotonically increasing.
+    /// Incomplete datagrams that exceeded the configured timeout are
+    /// discarded before the fragment is added.
+    pub fn add(&mut self, iphc: &Iphc, frame: &[u8], source: LinkAddress, destination: LinkAddress, timestamp: Duration) -> Result<Option<Vec<u8>>, SixlowpanError> {
+        let (fragment, rest) = match SixlowpanFragmentHeader::from_slice(frame)? {
+            Some(value) => value,
+            None => {
+                let (ip, udp, payload) = iphc.decompress(frame, source, destination)?;
+                let mut result = Vec::with_capacity(Ipv6Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE + payload.len());
+                write_uncompressed(&ip, udp.as_ref(), &mut result);
+                result.extend_from_slice(payload);
+                return Ok(Some(result));
+            },
+        };
+
+        let size = usize::from(fragment.datagram_size());
+        let key = (source, destination, fragment.datagram_size(), fragment.datagram_tag());
+        let (offset, data, udp_checksum_elided) = match fragment {
+            SixlowpanFragmentHeader::First{ .. } => {
+                let payload_len = size.checked_sub(Ipv6Header::SERIALIZED_SIZE).ok_or(SixlowpanError::InvalidFragment)?;
+                let (ip, udp, payload) = iphc.decompress_headers(rest, source, destination, Some(payload_len))?;
+                let mut data = Vec::with_capacity(Ipv6Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE + payload.len());
+                write_uncompressed(&ip, udp.as_ref().map(|(udp, _)| udp), &mut data);
+                data.extend_from_slice(payload);
+                (0, data, udp.map(|(_, elided)| elided).unwrap_or(false))
+            },
+            SixlowpanFragmentHeader::Subsequent{ datagram_offset, .. } => (usize::from(datagram_offset)*8, rest.to_vec(), false),
+        };
+        let end = offset + data.len();
+        // all fragments except the last have to end at a multiple of 8
+        if end > size || (end < size && 0 != end % 8) {
+            return Err(SixlowpanError::InvalidFragment);
+        }
+
+        self.remove_expired(timestamp);
+
+        if !self.buffers.contains_key(&key) && self.buffers.len() >= self.config.max_datagrams {
+            // discard the oldest datagram
+            let oldest = self.buffers.iter()
+                .min_by_key(|(_, buffer)| buffer.start)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.buffers.remove(&oldest);
+            }
+        }
+        let buffer = self.buffers.entry(key).or_insert_with(|| ReassemblyBuffer::new(timestamp, size));
+
+        // duplicates are ignored & overlapping fragments restart the
+        // reassembly (RFC 4944 section 5.3)
+        if buffer.fragments.contains(&(offset, end)) {
+            return Ok(None);
+        }
+        if buffer.fragments.iter().any(|(start, stop)| offset < *stop && *start < end) {
+            *buffer = ReassemblyBuffer::new(timestamp, size);
+        }
+        buffer.data[offset..end].copy_from_slice(&data);
+        buffer.fragments.push((offset, end));
+        buffer.udp_checksum_elided |= udp_checksum_elided;
+
+        // the fragments don't overlap, so the datagram is complete once their lengths add up
+        if buffer.fragments.iter().map(|(start, stop)| stop - start).sum::<usize>() < size {
+            return Ok(None);
+        }
+        let mut buffer = self.buffers.remove(&key).unwrap();
+        if buffer.udp_checksum_elided && buffer.data.len() >= Ipv6Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE {
+            let (ip, rest) = Ipv6Header::from_slice(&buffer.data).map_err(|_| SixlowpanError::InvalidFragment)?;
+            let (udp, payload) = UdpHeader::from_slice(rest).map_err(|_| SixlowpanError::InvalidFragment)?;
+            let checksum = udp.calc_checksum_ipv6(&ip, payload).map_err(|_| SixlowpanError::DatagramTooLarge(size))?;
+            let offset = Ipv6Header::SERIALIZED_SIZE + 6;
+            buffer.data[offset..offset + 2].copy_from_slice(&checksum.to_be_bytes());
+        }
+        Ok(Some(buffer.data))
+    }
+}
+
+/// Writes the uncompressed IPv6 & UDP header.
+fn write_uncompressed(ip: &Ipv6Header, udp: Option<&UdpHeader>, output: &mut Vec<u8>) {
+    // the header values are within their ranges (decoded from smaller fields)
+    ip.write(output).unwrap();
+    if let Some(udp) = udp {
+        output.extend_from_slice(&udp.to_bytes());
+    }
+}
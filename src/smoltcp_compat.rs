@@ -0,0 +1,193 @@
+use super::*;
+
+use smoltcp::wire::{
+    IpProtocol,
+    Ipv4Address,
+    Ipv4Repr,
+    Ipv6Address,
+    Ipv6Repr,
+    TcpControl,
+    TcpRepr,
+    TcpSeqNumber,
+    UdpRepr,
+};
+
+use std::convert::TryFrom;
+
+impl From<&Ipv4Header> for Ipv4Repr {
+    fn from(value: &Ipv4Header) -> Ipv4Repr {
+        Ipv4Repr {
+            src_addr: Ipv4Address(value.source),
+            dst_addr: Ipv4Address(value.destination),
+            next_header: IpProtocol::from(value.protocol),
+            payload_len: usize::from(value.payload_len),
+            hop_limit: value.time_to_live,
+        }
+    }
+}
+
+impl TryFrom<&Ipv4Repr> for Ipv4Header {
+    type Error = ValueError;
+
+    /// Converts the smoltcp representation into an ipv4 header (fails if
+    /// the payload length is too large to be represented in the header).
+    fn try_from(value: &Ipv4Repr) -> Result<Ipv4Header, ValueError> {
+        let mut result = Ipv4Header::new(0, value.hop_limit, IpNumber::Udp, value.src_addr.0, value.dst_addr.0);
+        result.protocol = u8::from(value.next_header);
+        result.set_payload_len(value.payload_len)?;
+        Ok(result)
+    }
+}
+
+impl From<&Ipv6Header> for Ipv6Repr {
+    fn from(value: &Ipv6Header) -> Ipv6Repr {
+        Ipv6Repr {
+            src_addr: Ipv6Address(value.source),
+            dst_addr: Ipv6Address(value.destination),
+            next_header: IpProtocol::from(value.next_header),
+            payload_len: usize::from(value.payload_length),
+            hop_limit: value.hop_limit,
+        }
+    }
+}
+
+impl TryFrom<&Ipv6Repr> for Ipv6Header {
+    type Error = ValueError;
+
+    /// Converts the smoltcp representation into an ipv6 header (fails if
+    /// the payload length is too large to be represented in the header).
+    fn try_from(value: &Ipv6Repr) -> Result<Ipv6Header, ValueError> {
+        let mut result = Ipv6Header {
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 0,
+            next_header: u8::from(value.next_header),
+            hop_limit: value.hop_limit,
+            source: value.src_addr.0,
+            destination: value.dst_addr.0,
+        };
+        result.set_payload_length(value.payload_len)?;
+        Ok(result)
+    }
+}
+
+impl From<&UdpHeader> for UdpRepr {
+    fn from(value: &UdpHeader) -> UdpRepr {
+        UdpRepr {
+            src_port: value.source_port,
+            dst_port: value.destination_port,
+        }
+    }
+}
+
+impl From<&UdpRepr> for UdpHeader {
+    /// Converts the smoltcp representation into an udp header.
+    ///
+    /// As the smoltcp representation contains no length & checksum the
+    /// length is set to the header length (no payload) & the checksum to 0.
+    fn from(value: &UdpRepr) -> UdpHeader {
+        UdpHeader {
+            source_port: value.src_port,
+            destination_port: value.dst_port,
+            length: UdpHeader::SERIALIZED_SIZE as u16,
+            checksum: 0,
+        }
+    }
+}
+
+impl TcpHeader {
+    /// Converts the header & payload into a smoltcp tcp representation
+    /// (requires the feature `smoltcp`).
+    ///
+    /// None is returned if the header can not be represented by smoltcp
+    /// (more then one of the flags SYN, FIN & RST is set or the options
+    /// contain errors).
+    pub fn to_smoltcp_repr<'a>(&self, payload: &'a [u8]) -> Option<TcpRepr<'a>> {
+        let control = match (self.syn, self.fin, self.rst, self.psh) {
+            (false, false, false, false) => TcpControl::None,
+            (false, false, false, true) => TcpControl::Psh,
+            (true, false, false, _) => TcpControl::Syn,
+            (false, true, false, _) => TcpControl::Fin,
+            (false, false, true, _) => TcpControl::Rst,
+            _ => return None,
+        };
+
+        let mut result = TcpRepr {
+            src_port: self.source_port,
+            dst_port: self.destination_port,
+            control,
+            seq_number: TcpSeqNumber(self.sequence_number as i32),
+            ack_number: if self.ack {
+                Some(TcpSeqNumber(self.acknowledgment_number as i32))
+            } else {
+                None
+            },
+            window_len: self.window_size,
+            window_scale: None,
+            max_seg_size: None,
+            sack_permitted: false,
+            sack_ranges: [None;3],
+            payload,
+        };
+
+        for option in self.options_iterator() {
+            use TcpOptionElement::*;
+            match option.ok()? {
+                MaximumSegmentSize(value) => result.max_seg_size = Some(value),
+                WindowScale(value) => result.window_scale = Some(value),
+                SelectiveAcknowledgementPermitted => result.sack_permitted = true,
+                SelectiveAcknowledgement(first, rest) => {
+                    result.sack_ranges = [Some(first), rest[0], rest[1]];
+                },
+                Noop | Timestamp(_, _) => {},
+            }
+        }
+        Some(result)
+    }
+
+    /// Creates a tcp header based on a smoltcp tcp representation
+    /// (requires the feature `smoltcp`).
+    ///
+    /// The checksum is set to 0 and has to be calculated by the caller
+    /// (e.g. via [`TcpHeader::calc_checksum_ipv4`]).
+    pub fn from_smoltcp_repr(value: &TcpRepr) -> Result<TcpHeader, TcpOptionWriteError> {
+        let mut result = TcpHeader::new(
+            value.src_port,
+            value.dst_port,
+            value.seq_number.0 as u32,
+            value.window_len
+        );
+        match value.control {
+            TcpControl::None => {},
+            TcpControl::Psh => result.psh = true,
+            TcpControl::Syn => result.syn = true,
+            TcpControl::Fin => result.fin = true,
+            TcpControl::Rst => result.rst = true,
+        }
+        if let Some(ack) = value.ack_number {
+            result.ack = true;
+            result.acknowledgment_number = ack.0 as u32;
+        }
+
+        let mut options = Vec::with_capacity(4);
+        if let Some(value) = value.max_seg_size {
+            options.push(TcpOptionElement::MaximumSegmentSize(value));
+        }
+        if let Some(value) = value.window_scale {
+            options.push(TcpOptionElement::WindowScale(value));
+        }
+        if value.sack_permitted {
+            options.push(TcpOptionElement::SelectiveAcknowledgementPermitted);
+        }
+        if let Some(first) = value.sack_ranges[0] {
+            options.push(TcpOptionElement::SelectiveAcknowledgement(
+                first,
+                [value.sack_ranges[1], value.sack_ranges[2], None]
+            ));
+        }
+        if !options.is_empty() {
+            result.set_options(&options)?;
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,636 @@
+use super::*;
+
+use std::io;
+
+/// Message types of SOME/IP messages (AUTOSAR PRS_SOMEIP).
+pub mod someip_message_type {
+    pub const REQUEST: u8 = 0x00;
+    pub const REQUEST_NO_RETURN: u8 = 0x01;
+    pub const NOTIFICATION: u8 = 0x02;
+    pub const RESPONSE: u8 = 0x80;
+    pub const ERROR: u8 = 0x81;
+    /// Bit set in the message type of segmented messages (SOME/IP-TP).
+    pub const TP_FLAG: u8 = 0x20;
+    pub const TP_REQUEST: u8 = 0x20;
+    pub const TP_REQUEST_NO_RETURN: u8 = 0x21;
+    pub const TP_NOTIFICATION: u8 = 0x22;
+    pub const TP_RESPONSE: u8 = 0xa0;
+    pub const TP_ERROR: u8 = 0xa1;
+}
+
+/// Return codes of SOME/IP messages (AUTOSAR PRS_SOMEIP).
+pub mod someip_return_code {
+    pub const E_OK: u8 = 0x00;
+    pub const E_NOT_OK: u8 = 0x01;
+    pub const E_UNKNOWN_SERVICE: u8 = 0x02;
+    pub const E_UNKNOWN_METHOD: u8 = 0x03;
+    pub const E_NOT_READY: u8 = 0x04;
+    pub const E_NOT_REACHABLE: u8 = 0x05;
+    pub const E_TIMEOUT: u8 = 0x06;
+    pub const E_WRONG_PROTOCOL_VERSION: u8 = 0x07;
+    pub const E_WRONG_INTERFACE_VERSION: u8 = 0x08;
+    pub const E_MALFORMED_MESSAGE: u8 = 0x09;
+    pub const E_WRONG_MESSAGE_TYPE: u8 = 0x0a;
+}
+
+/// Bits of the flags field of SOME/IP-SD messages.
+pub mod someip_sd_flags {
+    /// Set until the session id of the sender wraps around for the first
+    /// time after a reboot.
+    pub const REBOOT: u8 = 0x80;
+    /// Set if the sender supports receiving SD messages via unicast.
+    pub const UNICAST: u8 = 0x40;
+}
+
+/// Entry types of SOME/IP-SD entries (stop offer & stop subscribe entries
+/// use the offer & subscribe types with a TTL of 0, negative acknowledges
+/// use the acknowledge type with a TTL of 0).
+pub mod someip_sd_entry_type {
+    pub const FIND_SERVICE: u8 = 0x00;
+    pub const OFFER_SERVICE: u8 = 0x01;
+    pub const SUBSCRIBE_EVENTGROUP: u8 = 0x06;
+    pub const SUBSCRIBE_EVENTGROUP_ACK: u8 = 0x07;
+}
+
+/// Option types of SOME/IP-SD options.
+pub mod someip_sd_option_type {
+    pub const CONFIGURATION: u8 = 0x01;
+    pub const LOAD_BALANCING: u8 = 0x02;
+    pub const IPV4_ENDPOINT: u8 = 0x04;
+    pub const IPV6_ENDPOINT: u8 = 0x06;
+    pub const IPV4_MULTICAST: u8 = 0x14;
+    pub const IPV6_MULTICAST: u8 = 0x16;
+    pub const IPV4_SD_ENDPOINT: u8 = 0x24;
+    pub const IPV6_SD_ENDPOINT: u8 = 0x26;
+}
+
+/// SOME/IP header (the first 16 bytes of SOME/IP messages transported via
+/// UDP or TCP).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::someip::{SomeIpHeader, someip_message_type, someip_return_code};
+///
+/// let header = SomeIpHeader{
+///     message_id: 0x1234_8001,
+///     length: 8 + 4,
+///     request_id: 0x0001_0002,
+///     protocol_version: SomeIpHeader::PROTOCOL_VERSION,
+///     interface_version: 1,
+///     message_type: someip_message_type::NOTIFICATION,
+///     return_code: someip_return_code::E_OK,
+/// };
+/// let mut data = header.to_bytes().to_vec();
+/// data.extend_from_slice(&[1, 2, 3, 4]);
+///
+/// let (decoded, payload) = SomeIpHeader::from_slice(&data).unwrap();
+/// assert_eq!(header, decoded);
+/// assert_eq!(0x1234, decoded.service_id());
+/// assert_eq!(0x8001, decoded.method_id());
+/// assert!(decoded.is_event());
+/// assert_eq!(4, decoded.payload_len());
+/// assert_eq!(&[1, 2, 3, 4], payload);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct SomeIpHeader {
+    /// Service id (upper 16 bits) & method or event id (lower 16 bits).
+    pub message_id: u32,
+    /// Length of the message after the length field in bytes (8 + payload length).
+    pub length: u32,
+    /// Client id (upper 16 bits) & session id (lower 16 bits).
+    pub request_id: u32,
+    pub protocol_version: u8,
+    pub interface_version: u8,
+    /// Message type (see [`someip_message_type`]).
+    pub message_type: u8,
+    /// Return code (see [`someip_return_code`]).
+    pub return_code: u8,
+}
+
+impl SomeIpHeader {
+    /// Length of the serialized header in bytes.
+    pub const LEN: usize = 16;
+
+    /// Number of header bytes included in the length field.
+    pub const LENGTH_OFFSET: usize = 8;
+
+    /// Protocol version of the SOME/IP header format.
+    pub const PROTOCOL_VERSION: u8 = 1;
+
+    /// Message id of SOME/IP-SD messages (service id 0xFFFF & method id 0x8100).
+    pub const SD_MESSAGE_ID: u32 = 0xffff_8100;
+
+    /// UDP port used by SOME/IP-SD messages.
+    pub const SD_PORT: u16 = 30490;
+
+    /// Decodes a header & returns it together with the rest of the slice
+    /// after the header.
+    ///
+    /// The payload is not required to be present (see [`SomeIpMessageSlice`]
+    /// for a slice checking the length of the complete message).
+    pub fn from_slice(slice: &[u8]) -> Result<(SomeIpHeader, &[u8]), ReadError> {
+        if slice.len() < SomeIpHeader::LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(SomeIpHeader::LEN));
+        }
+        let mut bytes = [0u8;SomeIpHeader::LEN];
+        bytes.copy_from_slice(&slice[..SomeIpHeader::LEN]);
+        Ok((SomeIpHeader::from_bytes(bytes), &slice[SomeIpHeader::LEN..]))
+    }
+
+    /// Decodes a header from its serialized form.
+    pub fn from_bytes(bytes: [u8;SomeIpHeader::LEN]) -> SomeIpHeader {
+        SomeIpHeader{
+            message_id: u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            length: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            request_id: u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+            protocol_version: bytes[12],
+            interface_version: bytes[13],
+            message_type: bytes[14],
+            return_code: bytes[15],
+        }
+    }
+
+    /// Returns the service id (upper 16 bits of the message id).
+    #[inline]
+    pub fn service_id(&self) -> u16 {
+        (self.message_id >> 16) as u16
+    }
+
+    /// Returns the method or event id (lower 16 bits of the message id).
+    #[inline]
+    pub fn method_id(&self) -> u16 {
+        self.message_id as u16
+    }
+
+    /// Returns true if the message id refers to an event (highest bit of
+    /// the method id set).
+    #[inline]
+    pub fn is_event(&self) -> bool {
+        0 != self.message_id & 0x8000
+    }
+
+    /// Returns the client id (upper 16 bits of the request id).
+    #[inline]
+    pub fn client_id(&self) -> u16 {
+        (self.request_id >> 16) as u16
+    }
+
+    /// Returns the session id (lower 16 bits of the request id).
+    #[inline]
+    pub fn session_id(&self) -> u16 {
+        self.request_id as u16
+    }
+
+    /// Returns true if the TP flag is set in the message type (segmented message).
+    #[inline]
+    pub fn is_tp(&self) -> bool {
+        0 != self.message_type & someip_message_type::TP_FLAG
+    }
+
+    /// Returns true if the message is a SOME/IP-SD message.
+    #[inline]
+    pub fn is_sd(&self) -> bool {
+        SomeIpHeader::SD_MESSAGE_ID == self.message_id
+    }
+
+    /// Returns the length of the payload based on the length field (0 if
+    /// the length field is smaller than 8).
+    #[inline]
+    pub fn payload_len(&self) -> usize {
+        (self.length as usize).saturating_sub(SomeIpHeader::LENGTH_OFFSET)
+    }
+
+    /// Returns the serialized form of the header.
+    pub fn to_bytes(&self) -> [u8;SomeIpHeader::LEN] {
+        let m = self.message_id.to_be_bytes();
+        let l = self.length.to_be_bytes();
+        let r = self.request_id.to_be_bytes();
+        [
+            m[0], m[1], m[2], m[3],
+            l[0], l[1], l[2], l[3],
+            r[0], r[1], r[2], r[3],
+            self.protocol_version,
+            self.interface_version,
+            self.message_type,
+            self.return_code,
+        ]
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+/// A slice containing a SOME/IP message (header & payload).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SomeIpMessageSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> SomeIpMessageSlice<'a> {
+    /// Creates a SOME/IP message slice from an other slice (data after the
+    /// length given in the header is not part of the resulting slice).
+    pub fn from_slice(slice: &'a [u8]) -> Result<SomeIpMessageSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        let (header, _) = SomeIpHeader::from_slice(slice)?;
+        let len = (header.length as usize).saturating_add(SomeIpHeader::LENGTH_OFFSET);
+        if len < SomeIpHeader::LEN {
+            return Err(UnexpectedEndOfSlice(SomeIpHeader::LEN));
+        }
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(SomeIpMessageSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the SOME/IP message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Decode the header.
+    pub fn header(&self) -> SomeIpHeader {
+        SomeIpHeader::from_slice(self.slice).unwrap().0
+    }
+
+    /// Returns the slice containing the payload after the header.
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.slice[SomeIpHeader::LEN..]
+    }
+
+    /// Decodes the payload as SOME/IP-SD message (None if the message id
+    /// is not [`SomeIpHeader::SD_MESSAGE_ID`]).
+    pub fn sd(&self) -> Option<Result<SomeIpSdSlice<'a>, ReadError>> {
+        if self.header().is_sd() {
+            Some(SomeIpSdSlice::from_slice(self.payload()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Iterator over the SOME/IP messages contained in an UDP or TCP payload.
+///
+/// The iteration stops after the first error (e.g. a message continuing
+/// in the next TCP segment).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SomeIpMessagesIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> SomeIpMessagesIterator<'a> {
+    /// Creates an iterator over the messages in the given payload.
+    pub fn from_slice(payload: &'a [u8]) -> SomeIpMessagesIterator<'a> {
+        SomeIpMessagesIterator{ rest: payload }
+    }
+
+    /// Returns the slice containing the data that was not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for SomeIpMessagesIterator<'a> {
+    type Item = Result<SomeIpMessageSlice<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        match SomeIpMessageSlice::from_slice(self.rest) {
+            Ok(message) => {
+                self.rest = &self.rest[message.slice().len()..];
+                Some(Ok(message))
+            },
+            Err(err) => {
+                // stop the iteration after an error
+                self.rest = &[];
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// A slice containing a SOME/IP-SD message (the payload of SOME/IP
+/// messages with the message id [`SomeIpHeader::SD_MESSAGE_ID`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::someip::*;
+///
+/// let sd = vec![
+///     someip_sd_flags::REBOOT | someip_sd_flags::UNICAST, 0, 0, 0, // flags & reserved
+///     0, 0, 0, 16, // length of the entries array
+///     someip_sd_entry_type::OFFER_SERVICE, 0, 0, 0x10, // type, option indices & counts
+///     0x12, 0x34, 0, 1, // service & instance id
+///     1, 0, 0, 3, // major version & ttl
+///     0, 0, 0, 0, // minor version
+///     0, 0, 0, 12, // length of the options array
+///     0, 9, someip_sd_option_type::IPV4_ENDPOINT, 0, // length, type & reserved
+///     192, 168, 0, 1, 0, 17, 0x77, 0x1a, // address, reserved, protocol & port
+/// ];
+///
+/// let slice = SomeIpSdSlice::from_slice(&sd).unwrap();
+/// assert!(slice.reboot());
+///
+/// let entry = slice.entries().next().unwrap();
+/// assert_eq!(someip_sd_entry_type::OFFER_SERVICE, entry.entry_type);
+/// assert_eq!(0x1234, entry.service_id);
+/// assert_eq!(3, entry.ttl);
+///
+/// assert_eq!(
+///     Some(SomeIpSdOption::Ipv4Endpoint{
+///         option_type: someip_sd_option_type::IPV4_ENDPOINT,
+///         address: [192, 168, 0, 1],
+///         ip_number: 17,
+///         port: 30490,
+///     }),
+///     slice.options().next()
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SomeIpSdSlice<'a> {
+    slice: &'a [u8],
+    entries_len: usize,
+}
+
+impl<'a> SomeIpSdSlice<'a> {
+    /// Minimum length of a SOME/IP-SD message (no entries & no options).
+    pub const MIN_LEN: usize = 12;
+
+    /// Creates a SOME/IP-SD slice from an other slice (data after the
+    /// options array is not part of the resulting slice).
+    ///
+    /// The lengths of the options in the options array are checked to not
+    /// exceed the array.
+    pub fn from_slice(slice: &'a [u8]) -> Result<SomeIpSdSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < SomeIpSdSlice::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(SomeIpSdSlice::MIN_LEN));
+        }
+        let entries_len = u32::from_be_bytes([slice[4], slice[5], slice[6], slice[7]]) as usize;
+        let options_start = entries_len.saturating_add(SomeIpSdSlice::MIN_LEN);
+        if slice.len() < options_start {
+            return Err(UnexpectedEndOfSlice(options_start));
+        }
+        let options_len = u32::from_be_bytes([
+            slice[options_start - 4],
+            slice[options_start - 3],
+            slice[options_start - 2],
+            slice[options_start - 1],
+        ]) as usize;
+        let len = options_start.saturating_add(options_len);
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        // check the options fit into the options array
+        let mut offset = options_start;
+        while offset < len {
+            if len - offset < 3 {
+                return Err(UnexpectedEndOfSlice(offset + 3));
+            }
+            let option_len = 3 + usize::from(u16::from_be_bytes([slice[offset], slice[offset + 1]]));
+            if len - offset < option_len {
+                return Err(UnexpectedEndOfSlice(offset + option_len));
+            }
+            offset += option_len;
+        }
+
+        Ok(SomeIpSdSlice{
+            slice: &slice[..len],
+            entries_len,
+        })
+    }
+
+    /// Returns the slice containing the SOME/IP-SD message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the flags (see [`someip_sd_flags`]).
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Returns true if the reboot flag is set.
+    #[inline]
+    pub fn reboot(&self) -> bool {
+        0 != self.flags() & someip_sd_flags::REBOOT
+    }
+
+    /// Returns true if the unicast flag is set.
+    #[inline]
+    pub fn unicast(&self) -> bool {
+        0 != self.flags() & someip_sd_flags::UNICAST
+    }
+
+    /// Returns the slice containing the entries array.
+    #[inline]
+    pub fn entries_slice(&self) -> &'a [u8] {
+        &self.slice[8..8 + self.entries_len]
+    }
+
+    /// Returns the slice containing the options array.
+    #[inline]
+    pub fn options_slice(&self) -> &'a [u8] {
+        &self.slice[SomeIpSdSlice::MIN_LEN + self.entries_len..]
+    }
+
+    /// Returns an iterator over the entries.
+    pub fn entries(&self) -> SomeIpSdEntriesIterator<'a> {
+        SomeIpSdEntriesIterator{
+            rest: self.entries_slice()
+        }
+    }
+
+    /// Returns an iterator over the options (referenced by the entries
+    /// via their index in the options array).
+    pub fn options(&self) -> SomeIpSdOptionsIterator<'a> {
+        SomeIpSdOptionsIterator{
+            rest: self.options_slice()
+        }
+    }
+}
+
+/// Entry type specific fields of a SOME/IP-SD entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SomeIpSdEntryData {
+    /// Find & offer service entries.
+    Service{ minor_version: u32 },
+    /// Subscribe eventgroup & subscribe eventgroup acknowledge entries.
+    Eventgroup{ counter: u8, eventgroup_id: u16 },
+    /// Entries with an unknown entry type.
+    Unknown([u8;4]),
+}
+
+/// Entry of a SOME/IP-SD message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SomeIpSdEntry {
+    /// Entry type (see [`someip_sd_entry_type`]).
+    pub entry_type: u8,
+    pub index_first_options: u8,
+    pub index_second_options: u8,
+    pub number_of_first_options: u8,
+    pub number_of_second_options: u8,
+    pub service_id: u16,
+    pub instance_id: u16,
+    pub major_version: u8,
+    /// Lifetime of the entry in seconds (24 bits, 0 for stop offer & stop
+    /// subscribe entries).
+    pub ttl: u32,
+    pub data: SomeIpSdEntryData,
+}
+
+impl SomeIpSdEntry {
+    /// Length of a serialized entry in bytes.
+    pub const LEN: usize = 16;
+
+    /// Decodes an entry from its serialized form.
+    pub fn from_bytes(bytes: [u8;SomeIpSdEntry::LEN]) -> SomeIpSdEntry {
+        use self::someip_sd_entry_type::*;
+
+        SomeIpSdEntry{
+            entry_type: bytes[0],
+            index_first_options: bytes[1],
+            index_second_options: bytes[2],
+            number_of_first_options: bytes[3] >> 4,
+            number_of_second_options: bytes[3] & 0xf,
+            service_id: u16::from_be_bytes([bytes[4], bytes[5]]),
+            instance_id: u16::from_be_bytes([bytes[6], bytes[7]]),
+            major_version: bytes[8],
+            ttl: u32::from_be_bytes([0, bytes[9], bytes[10], bytes[11]]),
+            data: match bytes[0] {
+                FIND_SERVICE | OFFER_SERVICE => SomeIpSdEntryData::Service{
+                    minor_version: u32::from_be_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]),
+                },
+                SUBSCRIBE_EVENTGROUP | SUBSCRIBE_EVENTGROUP_ACK => SomeIpSdEntryData::Eventgroup{
+                    counter: bytes[13] & 0xf,
+                    eventgroup_id: u16::from_be_bytes([bytes[14], bytes[15]]),
+                },
+                _ => SomeIpSdEntryData::Unknown([bytes[12], bytes[13], bytes[14], bytes[15]]),
+            },
+        }
+    }
+}
+
+/// Iterator over the entries of a [`SomeIpSdSlice`] (incomplete entries
+/// at the end of the entries array are ignored).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SomeIpSdEntriesIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for SomeIpSdEntriesIterator<'a> {
+    type Item = SomeIpSdEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.len() < SomeIpSdEntry::LEN {
+            return None;
+        }
+        let mut bytes = [0u8;SomeIpSdEntry::LEN];
+        bytes.copy_from_slice(&self.rest[..SomeIpSdEntry::LEN]);
+        self.rest = &self.rest[SomeIpSdEntry::LEN..];
+        Some(SomeIpSdEntry::from_bytes(bytes))
+    }
+}
+
+/// Option of a SOME/IP-SD message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SomeIpSdOption<'a> {
+    /// Configuration strings (DNS TXT style, each prefixed with its length).
+    Configuration(&'a [u8]),
+    LoadBalancing{ priority: u16, weight: u16 },
+    /// IPv4 endpoint, multicast or SD endpoint option (distinguished by
+    /// the option type).
+    Ipv4Endpoint{ option_type: u8, address: [u8;4], ip_number: u8, port: u16 },
+    /// IPv6 endpoint, multicast or SD endpoint option (distinguished by
+    /// the option type).
+    Ipv6Endpoint{ option_type: u8, address: [u8;16], ip_number: u8, port: u16 },
+    /// Options with an unknown type or an unexpected length (data after
+    /// the reserved byte).
+    Other{ option_type: u8, data: &'a [u8] },
+}
+
+impl<'a> SomeIpSdOption<'a> {
+    /// Decodes an option with the given type & the data after the reserved byte.
+    fn from_data(option_type: u8, data: &'a [u8]) -> SomeIpSdOption<'a> {
+        use self::someip_sd_option_type::*;
+
+        match (option_type, data.len()) {
+            (CONFIGURATION, _) => SomeIpSdOption::Configuration(data),
+            (LOAD_BALANCING, 4) => SomeIpSdOption::LoadBalancing{
+                priority: u16::from_be_bytes([data[0], data[1]]),
+                weight: u16::from_be_bytes([data[2], data[3]]),
+            },
+            (IPV4_ENDPOINT, 8) | (IPV4_MULTICAST, 8) | (IPV4_SD_ENDPOINT, 8) => {
+                let mut address = [0u8;4];
+                address.copy_from_slice(&data[..4]);
+                SomeIpSdOption::Ipv4Endpoint{
+                    option_type,
+                    address,
+                    ip_number: data[5],
+                    port: u16::from_be_bytes([data[6], data[7]]),
+                }
+            },
+            (IPV6_ENDPOINT, 20) | (IPV6_MULTICAST, 20) | (IPV6_SD_ENDPOINT, 20) => {
+                let mut address = [0u8;16];
+                address.copy_from_slice(&data[..16]);
+                SomeIpSdOption::Ipv6Endpoint{
+                    option_type,
+                    address,
+                    ip_number: data[17],
+                    port: u16::from_be_bytes([data[18], data[19]]),
+                }
+            },
+            _ => SomeIpSdOption::Other{ option_type, data },
+        }
+    }
+
+    /// Returns the option type (see [`someip_sd_option_type`]).
+    pub fn option_type(&self) -> u8 {
+        use self::SomeIpSdOption::*;
+        match self {
+            Configuration(_) => someip_sd_option_type::CONFIGURATION,
+            LoadBalancing{ .. } => someip_sd_option_type::LOAD_BALANCING,
+            Ipv4Endpoint{ option_type, .. } => *option_type,
+            Ipv6Endpoint{ option_type, .. } => *option_type,
+            Other{ option_type, .. } => *option_type,
+        }
+    }
+}
+
+/// Iterator over the options of a [`SomeIpSdSlice`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SomeIpSdOptionsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for SomeIpSdOptionsIterator<'a> {
+    type Item = SomeIpSdOption<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the option lengths were already checked by SomeIpSdSlice::from_slice
+        if self.rest.len() < 3 {
+            return None;
+        }
+        let len = 3 + usize::from(u16::from_be_bytes([self.rest[0], self.rest[1]]));
+        let option_type = self.rest[2];
+        // the length includes the reserved byte after the type
+        let data = &self.rest[len.min(4)..len];
+        self.rest = &self.rest[len..];
+        Some(SomeIpSdOption::from_data(option_type, data))
+    }
+}
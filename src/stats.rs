@@ -0,0 +1,260 @@
+use super::*;
+
+use std::collections::BTreeMap;
+
+/// Number of packets & bytes counted for one key (e.g. an ether type or a port).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Counter {
+    /// Number of packets counted.
+    pub packets: u64,
+    /// Sum of the lengths (in bytes) of the counted packets.
+    pub bytes: u64,
+}
+
+impl Counter {
+    /// Counts one packet with the given length in bytes.
+    #[inline]
+    pub fn add(&mut self, packet_len: usize) {
+        self.packets += 1;
+        self.bytes += packet_len as u64;
+    }
+
+    /// Adds the values of another counter to this counter.
+    #[inline]
+    pub fn merge(&mut self, other: &Counter) {
+        self.packets += other.packets;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Counters collected by a [`StatsCollector`] split per layer.
+///
+/// Snapshots can be merged (e.g. when multiple threads each
+/// collect statistics for a part of the traffic) via [`StatsSnapshot::merge`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatsSnapshot {
+    /// Counter over all packets (including packets that could not be decoded).
+    pub total: Counter,
+    /// Packets that could not be decoded.
+    pub errors: Counter,
+    /// Packets per ether type of the payload of the link layer (in case
+    /// vlan headers are present the ether type after the vlan headers is used).
+    pub ether_types: BTreeMap<u16, Counter>,
+    /// Packets per vlan identifier (packets with double vlan headers
+    /// are counted for the outer & inner identifier).
+    pub vlan_ids: BTreeMap<u16, Counter>,
+    /// Packets per ip protocol number of the ip payload (the last
+    /// "next header" value after all supported ip extension headers).
+    pub ip_protocols: BTreeMap<u8, Counter>,
+    /// Packets per udp port (packets are counted for the source &
+    /// destination port).
+    pub udp_ports: BTreeMap<u16, Counter>,
+    /// Packets per tcp port (packets are counted for the source &
+    /// destination port).
+    pub tcp_ports: BTreeMap<u16, Counter>,
+}
+
+impl StatsSnapshot {
+    /// Adds all counters of another snapshot to this snapshot.
+    pub fn merge(&mut self, other: &StatsSnapshot) {
+        fn merge_map<K: Ord + Copy>(target: &mut BTreeMap<K, Counter>, source: &BTreeMap<K, Counter>) {
+            for (key, counter) in source {
+                target.entry(*key).or_default().merge(counter);
+            }
+        }
+        self.total.merge(&other.total);
+        self.errors.merge(&other.errors);
+        merge_map(&mut self.ether_types, &other.ether_types);
+        merge_map(&mut self.vlan_ids, &other.vlan_ids);
+        merge_map(&mut self.ip_protocols, &other.ip_protocols);
+        merge_map(&mut self.udp_ports, &other.udp_ports);
+        merge_map(&mut self.tcp_ports, &other.tcp_ports);
+    }
+}
+
+/// Collects per layer statistics (packets & bytes per ether type, vlan id,
+/// ip protocol number & port) of decoded or sliced packets.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #    .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #    .udp(21, 1234);
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+/// # builder.write(&mut packet, &[1,2,3,4]).unwrap();
+/// use etherparse::{ether_type, ip_number, stats::StatsCollector};
+///
+/// let mut collector = StatsCollector::new();
+/// collector.add_ethernet_slice(&packet);
+/// collector.add_ethernet_slice(&[1,2,3]); // counted as error
+///
+/// let stats = collector.snapshot();
+/// assert_eq!(2, stats.total.packets);
+/// assert_eq!(1, stats.errors.packets);
+/// assert_eq!(1, stats.ether_types[&ether_type::IPV4].packets);
+/// assert_eq!(1, stats.ip_protocols[&ip_number::UDP].packets);
+/// assert_eq!(packet.len() as u64, stats.udp_ports[&1234].bytes);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatsCollector {
+    stats: StatsSnapshot,
+}
+
+impl StatsCollector {
+
+    /// Creates a collector with all counters set to zero.
+    pub fn new() -> StatsCollector {
+        Default::default()
+    }
+
+    /// Slices the given packet starting with an Ethernet II header & adds
+    /// the result to the statistics (counted as an error if the packet
+    /// could not be sliced).
+    pub fn add_ethernet_slice(&mut self, packet: &[u8]) {
+        match SlicedPacket::from_ethernet(packet) {
+            Ok(value) => self.add_sliced_packet(&value),
+            Err(_) => self.add_error(packet.len()),
+        }
+    }
+
+    /// Slices the given packet starting with an IPv4 or IPv6 header & adds
+    /// the result to the statistics (counted as an error if the packet
+    /// could not be sliced).
+    pub fn add_ip_slice(&mut self, packet: &[u8]) {
+        match SlicedPacket::from_ip(packet) {
+            Ok(value) => self.add_sliced_packet(&value),
+            Err(_) => self.add_error(packet.len()),
+        }
+    }
+
+    /// Adds a sliced packet to the statistics.
+    pub fn add_sliced_packet(&mut self, packet: &SlicedPacket) {
+        let len = packet.header_bytes().iter().map(|s| s.len()).sum::<usize>()
+                  + packet.payload.len()
+                  + packet.trailer.len()
+                  + packet.padding.len();
+        self.stats.total.add(len);
+
+        // ether type
+        let ether_type = match &packet.vlan {
+            Some(vlan) => Some(vlan.inner().ether_type()),
+            None => packet.link.as_ref().and_then(|link| link.ether_type()),
+        };
+        if let Some(ether_type) = ether_type {
+            self.stats.ether_types.entry(ether_type).or_default().add(len);
+        }
+
+        // vlan ids
+        if let Some(vlan) = &packet.vlan {
+            let ids: Vec<u16> = vlan.iter().map(|v| v.vlan_identifier()).collect();
+            self.add_vlan_ids(&ids, len);
+        }
+
+        // ip protocol
+        let ip_protocol = match &packet.transport {
+            Some(TransportSlice::Udp(_)) => Some(ip_number::UDP),
+            Some(TransportSlice::Tcp(_)) => Some(ip_number::TCP),
+            Some(TransportSlice::Igmp(_)) => Some(ip_number::IGMP),
+            Some(TransportSlice::Unknown(value)) => Some(*value),
+            // no transport slice present (e.g. fragmented payload)
+            None => packet.ip.as_ref().map(|ip| ip.payload_ip_number()),
+        };
+        if let Some(ip_protocol) = ip_protocol {
+            self.stats.ip_protocols.entry(ip_protocol).or_default().add(len);
+        }
+
+        // ports
+        match &packet.transport {
+            Some(TransportSlice::Udp(udp)) => {
+                add_ports(&mut self.stats.udp_ports, udp.source_port(), udp.destination_port(), len);
+            },
+            Some(TransportSlice::Tcp(tcp)) => {
+                add_ports(&mut self.stats.tcp_ports, tcp.source_port(), tcp.destination_port(), len);
+            },
+            _ => {},
+        }
+    }
+
+    /// Adds decoded packet headers to the statistics.
+    pub fn add_packet_headers(&mut self, packet: &PacketHeaders) {
+        let len = packet.link.as_ref().map(|v| v.header_len()).unwrap_or(0)
+                  + packet.vlan.as_ref().map(|v| v.header_len()).unwrap_or(0)
+                  + packet.ip.as_ref().map(|v| v.header_len()).unwrap_or(0)
+                  + packet.transport.as_ref().map(|v| v.header_len()).unwrap_or(0)
+                  + packet.payload.len();
+        self.stats.total.add(len);
+
+        // ether type
+        let ether_type = match &packet.vlan {
+            Some(vlan) => vlan.iter().last().map(|v| v.ether_type),
+            None => packet.link.as_ref().map(|link| link.ether_type),
+        };
+        if let Some(ether_type) = ether_type {
+            self.stats.ether_types.entry(ether_type).or_default().add(len);
+        }
+
+        // vlan ids
+        if let Some(vlan) = &packet.vlan {
+            let ids: Vec<u16> = vlan.iter().map(|v| v.vlan_identifier).collect();
+            self.add_vlan_ids(&ids, len);
+        }
+
+        // ip protocol
+        if let Some(Ok(ip_protocol)) = packet.ip.as_ref().map(|ip| ip.next_header()) {
+            self.stats.ip_protocols.entry(ip_protocol).or_default().add(len);
+        }
+
+        // ports
+        match &packet.transport {
+            Some(TransportHeader::Udp(udp)) => {
+                add_ports(&mut self.stats.udp_ports, udp.source_port, udp.destination_port, len);
+            },
+            Some(TransportHeader::Tcp(tcp)) => {
+                add_ports(&mut self.stats.tcp_ports, tcp.source_port, tcp.destination_port, len);
+            },
+            Some(TransportHeader::Igmp(_)) | None => {},
+        }
+    }
+
+    /// Counts a packet that could not be decoded.
+    pub fn add_error(&mut self, packet_len: usize) {
+        self.stats.total.add(packet_len);
+        self.stats.errors.add(packet_len);
+    }
+
+    /// Returns a copy of the currently collected statistics.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        self.stats.clone()
+    }
+
+    /// Returns the currently collected statistics and resets all counters
+    /// in the collector to zero.
+    pub fn take_snapshot(&mut self) -> StatsSnapshot {
+        std::mem::take(&mut self.stats)
+    }
+
+    /// Adds the counters of a snapshot to the collector.
+    pub fn merge(&mut self, snapshot: &StatsSnapshot) {
+        self.stats.merge(snapshot);
+    }
+
+    fn add_vlan_ids(&mut self, ids: &[u16], len: usize) {
+        for (i, id) in ids.iter().enumerate() {
+            // don't count a packet twice for the same id
+            if !ids[..i].contains(id) {
+                self.stats.vlan_ids.entry(*id).or_default().add(len);
+            }
+        }
+    }
+}
+
+/// Counts a packet for the source & destination port (only once if both ports are identical).
+fn add_ports(ports: &mut BTreeMap<u16, Counter>, source_port: u16, destination_port: u16, len: usize) {
+    ports.entry(source_port).or_default().add(len);
+    if source_port != destination_port {
+        ports.entry(destination_port).or_default().add(len);
+    }
+}
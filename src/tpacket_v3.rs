@@ -0,0 +1,341 @@
+use super::*;
+
+use std::sync::atomic;
+use std::time::Duration;
+
+/// Value of the `version` field of TPACKET_V3 blocks (`TPACKET_V3` in `linux/if_packet.h`).
+pub const TPACKET_V3: u32 = 2;
+
+/// Status flag indicating that a block or frame is owned by the user space (`TP_STATUS_USER`).
+pub const TP_STATUS_USER: u32 = 1 << 0;
+/// Status flag indicating that a frame was truncated to the snap length (`TP_STATUS_COPY`).
+pub const TP_STATUS_COPY: u32 = 1 << 1;
+/// Status flag indicating that frames were dropped since the last frame (`TP_STATUS_LOSING`).
+pub const TP_STATUS_LOSING: u32 = 1 << 2;
+/// Status flag indicating that the vlan tci of a frame is valid (`TP_STATUS_VLAN_VALID`).
+pub const TP_STATUS_VLAN_VALID: u32 = 1 << 4;
+/// Status flag indicating that a block was retired due to a timeout (`TP_STATUS_BLK_TMO`).
+pub const TP_STATUS_BLK_TMO: u32 = 1 << 5;
+/// Status flag indicating that the vlan tpid of a frame is valid (`TP_STATUS_VLAN_TPID_VALID`).
+pub const TP_STATUS_VLAN_TPID_VALID: u32 = 1 << 6;
+
+/// Size of the block descriptor (`struct tpacket_block_desc` with a `struct tpacket_hdr_v1`).
+const BLOCK_HEADER_LEN: usize = 48;
+/// Size of the frame header (`struct tpacket3_hdr`).
+const FRAME_HEADER_LEN: usize = 48;
+/// Offset of the `block_status` field in the block descriptor.
+const BLOCK_STATUS_OFFSET: usize = 8;
+
+/// Block of a memory mapped TPACKET_V3 `AF_PACKET` receive ring (`PACKET_RX_RING`).
+///
+/// The headers are read in the native byte order (as written by the kernel).
+///
+/// # Example
+///
+/// ```no_run
+/// # fn ring() -> &'static mut [u8] { unimplemented!() }
+/// use etherparse::tpacket_v3::{TpacketV3Block, TP_STATUS_USER};
+///
+/// // memory mapped ring set up via setsockopt(PACKET_RX_RING) & mmap
+/// let ring: &mut [u8] = ring();
+/// let block_size = 1 << 22;
+///
+/// for block in ring.chunks_mut(block_size) {
+///     {
+///         let block = TpacketV3Block::from_slice(block).unwrap();
+///         if 0 == block.block_status() & TP_STATUS_USER {
+///             // block is still owned by the kernel
+///             break;
+///         }
+///         for frame in block.frames() {
+///             let frame = frame.unwrap();
+///             println!("{:?} {:?} {:?}", frame.timestamp(), frame.vlan(), frame.slice_ethernet());
+///         }
+///     }
+///     // return the block to the kernel
+///     TpacketV3Block::release(block);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TpacketV3Block<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> TpacketV3Block<'a> {
+
+    /// Creates a block from the memory of a ring block.
+    ///
+    /// If the `block_len` field is set the slice is limited to the block length.
+    pub fn from_slice(slice: &'a [u8]) -> Result<TpacketV3Block<'a>, ReadError> {
+        if slice.len() < BLOCK_HEADER_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(BLOCK_HEADER_LEN));
+        }
+        let block_len = read_u32(slice, 20) as usize;
+        if block_len > slice.len() {
+            return Err(ReadError::UnexpectedEndOfSlice(block_len));
+        }
+        Ok(TpacketV3Block {
+            slice: if block_len >= BLOCK_HEADER_LEN {
+                &slice[..block_len]
+            } else {
+                slice
+            },
+        })
+    }
+
+    /// Returns the slice containing the block.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Version of the block descriptor (expected to be [`TPACKET_V3`]).
+    #[inline]
+    pub fn version(&self) -> u32 {
+        read_u32(self.slice, 0)
+    }
+
+    /// Status flags of the block (see [`TP_STATUS_USER`] & [`TP_STATUS_BLK_TMO`]).
+    #[inline]
+    pub fn block_status(&self) -> u32 {
+        read_u32(self.slice, BLOCK_STATUS_OFFSET)
+    }
+
+    /// Number of frames in the block.
+    #[inline]
+    pub fn num_packets(&self) -> u32 {
+        read_u32(self.slice, 12)
+    }
+
+    /// Offset of the first frame header relative to the start of the block.
+    #[inline]
+    pub fn offset_to_first_packet(&self) -> u32 {
+        read_u32(self.slice, 16)
+    }
+
+    /// Number of bytes used in the block.
+    #[inline]
+    pub fn block_len(&self) -> u32 {
+        read_u32(self.slice, 20)
+    }
+
+    /// Sequence number of the block.
+    #[inline]
+    pub fn seq_num(&self) -> u64 {
+        let s = &self.slice[24..32];
+        u64::from_ne_bytes([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]])
+    }
+
+    /// Timestamp of the first frame in the block.
+    #[inline]
+    pub fn first_timestamp(&self) -> Duration {
+        read_timestamp(self.slice, 32)
+    }
+
+    /// Timestamp of the last frame in the block.
+    #[inline]
+    pub fn last_timestamp(&self) -> Duration {
+        read_timestamp(self.slice, 40)
+    }
+
+    /// Returns an iterator over the frames in the block.
+    pub fn frames(&self) -> TpacketV3FrameIterator<'a> {
+        TpacketV3FrameIterator {
+            block: self.slice,
+            offset: self.offset_to_first_packet() as usize,
+            remaining: self.num_packets(),
+        }
+    }
+
+    /// Returns the block to the kernel by setting the block status to
+    /// `TP_STATUS_KERNEL` (all frames of the block have to be processed
+    /// before).
+    pub fn release(block: &mut [u8]) {
+        assert!(block.len() >= BLOCK_HEADER_LEN);
+        // make sure all reads of the block are completed before the
+        // kernel is allowed to reuse the block
+        atomic::fence(atomic::Ordering::Release);
+        let status = &mut block[BLOCK_STATUS_OFFSET..BLOCK_STATUS_OFFSET + 4];
+        // SAFETY: The pointer points to 4 writeable bytes (checked above)
+        // & the write is unaligned.
+        unsafe {
+            std::ptr::write_volatile(status.as_mut_ptr() as *mut [u8;4], 0u32.to_ne_bytes());
+        }
+    }
+}
+
+/// Iterator over the frames of a [`TpacketV3Block`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TpacketV3FrameIterator<'a> {
+    block: &'a [u8],
+    /// Offset of the next frame header relative to the start of the block.
+    offset: usize,
+    /// Number of frames that have not yet been returned.
+    remaining: u32,
+}
+
+impl<'a> Iterator for TpacketV3FrameIterator<'a> {
+    type Item = Result<TpacketV3Frame<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if 0 == self.remaining {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let result = TpacketV3Frame::from_slice(&self.block[self.offset.min(self.block.len())..]);
+        match &result {
+            Ok(frame) if 0 != frame.next_offset() => {
+                self.offset += frame.next_offset() as usize;
+            },
+            _ => {
+                // last frame or error
+                self.remaining = 0;
+            },
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
+    }
+}
+
+/// Frame of a TPACKET_V3 block (a `struct tpacket3_hdr` followed by the frame data).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TpacketV3Frame<'a> {
+    header: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> TpacketV3Frame<'a> {
+
+    /// Reads a frame from a slice starting with the frame header (the slice
+    /// has to contain all data up to the end of the captured frame).
+    pub fn from_slice(slice: &'a [u8]) -> Result<TpacketV3Frame<'a>, ReadError> {
+        if slice.len() < FRAME_HEADER_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(FRAME_HEADER_LEN));
+        }
+        let mac = usize::from(read_u16(slice, 24));
+        let snap_len = read_u32(slice, 12) as usize;
+        let end = mac + snap_len;
+        if slice.len() < end {
+            return Err(ReadError::UnexpectedEndOfSlice(end));
+        }
+        Ok(TpacketV3Frame {
+            header: &slice[..FRAME_HEADER_LEN],
+            data: &slice[mac..end],
+        })
+    }
+
+    /// Returns the slice containing the frame header (`struct tpacket3_hdr`).
+    #[inline]
+    pub fn header_slice(&self) -> &'a [u8] {
+        self.header
+    }
+
+    /// Captured data of the frame (starting at the link layer header).
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Offset of the next frame relative to this frame (0 for the last frame in a block).
+    #[inline]
+    pub fn next_offset(&self) -> u32 {
+        read_u32(self.header, 0)
+    }
+
+    /// Timestamp at which the frame was received.
+    #[inline]
+    pub fn timestamp(&self) -> Duration {
+        read_timestamp(self.header, 4)
+    }
+
+    /// Number of captured bytes.
+    #[inline]
+    pub fn snap_len(&self) -> u32 {
+        read_u32(self.header, 12)
+    }
+
+    /// Original length of the frame (can be bigger then the snap length).
+    #[inline]
+    pub fn len(&self) -> u32 {
+        read_u32(self.header, 16)
+    }
+
+    /// Returns true if the original frame had a length of 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        0 == self.len()
+    }
+
+    /// Status flags of the frame (e.g. [`TP_STATUS_VLAN_VALID`]).
+    #[inline]
+    pub fn status(&self) -> u32 {
+        read_u32(self.header, 20)
+    }
+
+    /// Receive hash calculated by the kernel or nic.
+    #[inline]
+    pub fn rx_hash(&self) -> u32 {
+        read_u32(self.header, 28)
+    }
+
+    /// Tag control information of the vlan tag stripped by the kernel or nic (if present).
+    pub fn vlan_tci(&self) -> Option<u16> {
+        if 0 != self.status() & TP_STATUS_VLAN_VALID {
+            Some(read_u32(self.header, 32) as u16)
+        } else {
+            None
+        }
+    }
+
+    /// Tag protocol identifier of the vlan tag stripped by the kernel
+    /// (if present and reported by the kernel).
+    pub fn vlan_tpid(&self) -> Option<u16> {
+        if 0 != self.status() & TP_STATUS_VLAN_VALID && 0 != self.status() & TP_STATUS_VLAN_TPID_VALID {
+            Some(read_u16(self.header, 36))
+        } else {
+            None
+        }
+    }
+
+    /// Vlan header stripped by the kernel or nic (recovered from the tpacket auxdata).
+    ///
+    /// The ether type of the returned header is the ether type of the
+    /// Ethernet II header in the frame data (the type of the content after the
+    /// stripped vlan tag).
+    pub fn vlan(&self) -> Option<SingleVlanHeader> {
+        self.vlan_tci().map(|tci| SingleVlanHeader {
+            priority_code_point: (tci >> 13) as u8,
+            drop_eligible_indicator: 0 != tci & 0x1000,
+            vlan_identifier: tci & 0xfff,
+            ether_type: match Ethernet2HeaderSlice::from_slice(self.data) {
+                Ok(eth) => eth.ether_type(),
+                Err(_) => 0,
+            },
+            tpid: self.vlan_tpid().unwrap_or(ether_type::VLAN_TAGGED_FRAME),
+        })
+    }
+
+    /// Slices the frame data starting with an Ethernet II header (see [`SlicedPacket::from_ethernet`]).
+    pub fn slice_ethernet(&self) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_ethernet(self.data)
+    }
+}
+
+/// Reads a native endian u32 (the offset has to be checked by the caller).
+fn read_u32(slice: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([slice[offset], slice[offset + 1], slice[offset + 2], slice[offset + 3]])
+}
+
+/// Reads a native endian u16 (the offset has to be checked by the caller).
+fn read_u16(slice: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([slice[offset], slice[offset + 1]])
+}
+
+/// Reads a timestamp consisting of seconds & nanoseconds.
+fn read_timestamp(slice: &[u8], offset: usize) -> Duration {
+    Duration::new(u64::from(read_u32(slice, offset)), read_u32(slice, offset + 4))
+}
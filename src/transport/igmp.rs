@@ -0,0 +1,406 @@
+use super::super::*;
+
+use crate::multicast::igmp_type;
+
+/// IGMPv3 membership query (RFC 3376 section 4.1).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct IgmpV3Query {
+    /// Maximum response code (see [`IgmpV3Query::max_response_time`] for
+    /// the decoded value in 1/10 seconds).
+    pub max_response_code: u8,
+    /// Queried group (unspecified in general queries).
+    pub group_address: [u8;4],
+    /// Suppress router-side processing ("S" flag).
+    pub suppress_router_side_processing: bool,
+    /// Querier's robustness variable (QRV, 3 bits).
+    pub querier_robustness_variable: u8,
+    /// Querier's query interval code (QQIC, see
+    /// [`IgmpV3Query::query_interval`] for the decoded value in seconds).
+    pub querier_query_interval_code: u8,
+    /// Queried sources (only set in group & source specific queries).
+    pub sources: Vec<[u8;4]>,
+}
+
+impl IgmpV3Query {
+    /// Length of an IGMPv3 query without sources.
+    pub const MIN_LEN: usize = 12;
+
+    /// Maximum robustness variable value that can be represented.
+    pub const MAX_QUERIER_ROBUSTNESS_VARIABLE: u8 = 0b111;
+
+    /// Decoded maximum response time in 1/10 seconds (RFC 3376 section 4.1.1).
+    pub fn max_response_time(&self) -> u32 {
+        decode_float_u8(self.max_response_code)
+    }
+
+    /// Decoded query interval in seconds (RFC 3376 section 4.1.7).
+    pub fn query_interval(&self) -> u32 {
+        decode_float_u8(self.querier_query_interval_code)
+    }
+
+    /// Length of the serialized query including the sources.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        IgmpV3Query::MIN_LEN + self.sources.len()*4
+    }
+}
+
+/// Group record of an IGMPv3 membership report (RFC 3376 section 4.2.4).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct IgmpGroupRecord {
+    /// Type of the record (see [`crate::multicast::group_record_type`]).
+    pub record_type: u8,
+    /// Group the record applies to.
+    pub group_address: [u8;4],
+    /// Sources of the record.
+    pub sources: Vec<[u8;4]>,
+    /// Auxiliary data of the record (length must be a multiple of 4).
+    pub aux_data: Vec<u8>,
+}
+
+impl IgmpGroupRecord {
+    /// Length of a record without sources & auxiliary data.
+    pub const MIN_LEN: usize = 8;
+
+    /// Length of the serialized record.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        IgmpGroupRecord::MIN_LEN + self.sources.len()*4 + self.aux_data.len()
+    }
+}
+
+/// Type & type specific fields of an IGMP message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IgmpType {
+    /// IGMPv1 or IGMPv2 membership query (IGMPv1 queries have a max
+    /// response time of 0).
+    MembershipQuery{
+        /// Maximum response time in 1/10 seconds.
+        max_response_time: u8,
+        /// Queried group (unspecified in general queries).
+        group_address: [u8;4],
+    },
+    /// IGMPv3 membership query (a query with a length of at least 12 bytes).
+    MembershipQueryV3(IgmpV3Query),
+    /// IGMPv1 membership report.
+    MembershipReportV1{ group_address: [u8;4] },
+    /// IGMPv2 membership report.
+    MembershipReportV2{ group_address: [u8;4] },
+    /// IGMPv2 leave group message.
+    LeaveGroup{ group_address: [u8;4] },
+    /// IGMPv3 membership report containing the group records.
+    MembershipReportV3(Vec<IgmpGroupRecord>),
+    /// Message with an unknown type (the fixed 8 byte header is assumed).
+    Unknown{
+        type_u8: u8,
+        code_u8: u8,
+        bytes5to8: [u8;4],
+    },
+}
+
+impl IgmpType {
+    /// Returns the IGMP type value.
+    pub fn type_u8(&self) -> u8 {
+        use IgmpType::*;
+        match self {
+            MembershipQuery{ .. } | MembershipQueryV3(_) => igmp_type::MEMBERSHIP_QUERY,
+            MembershipReportV1{ .. } => igmp_type::V1_MEMBERSHIP_REPORT,
+            MembershipReportV2{ .. } => igmp_type::V2_MEMBERSHIP_REPORT,
+            LeaveGroup{ .. } => igmp_type::LEAVE_GROUP,
+            MembershipReportV3(_) => igmp_type::V3_MEMBERSHIP_REPORT,
+            Unknown{ type_u8, .. } => *type_u8,
+        }
+    }
+}
+
+/// IGMP message (RFC 1112, RFC 2236 & RFC 3376). As IGMP messages have
+/// no payload the complete message is treated as header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IgmpHeader {
+    pub igmp_type: IgmpType,
+    /// Checksum over the complete IGMP message.
+    pub checksum: u16,
+}
+
+impl IgmpHeader {
+    /// Length of the smallest IGMP message (all messages except IGMPv3
+    /// queries & reports).
+    pub const MIN_LEN: usize = 8;
+
+    /// Creates an IGMP header with the checksum set to 0.
+    pub fn new(igmp_type: IgmpType) -> IgmpHeader {
+        IgmpHeader{
+            igmp_type,
+            checksum: 0,
+        }
+    }
+
+    /// Creates an IGMP header with a calculated checksum.
+    pub fn with_checksum(igmp_type: IgmpType) -> Result<IgmpHeader, ValueError> {
+        let mut result = IgmpHeader::new(igmp_type);
+        result.checksum = result.calc_checksum(&[])?;
+        Ok(result)
+    }
+
+    /// Reads an IGMP message from a slice & returns the header & the rest
+    /// of the slice.
+    ///
+    /// Queries with a length of at least 12 bytes are decoded as IGMPv3
+    /// queries (RFC 3376 section 7.1), the slice should therefore not
+    /// contain data after the IP payload (e.g. ethernet padding).
+    pub fn from_slice(slice: &[u8]) -> Result<(IgmpHeader, &[u8]), ReadError> {
+        let header = IgmpSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Length of the serialized message in bytes.
+    pub fn header_len(&self) -> usize {
+        use IgmpType::*;
+        match &self.igmp_type {
+            MembershipQueryV3(value) => value.header_len(),
+            MembershipReportV3(records) => {
+                records.iter().fold(IgmpHeader::MIN_LEN, |sum, record| sum + record.header_len())
+            },
+            _ => IgmpHeader::MIN_LEN,
+        }
+    }
+
+    /// Calculates the checksum of the message & the given payload (the
+    /// checksum field of the header is ignored).
+    pub fn calc_checksum(&self, payload: &[u8]) -> Result<u16, ValueError> {
+        let mut bytes = self.to_bytes()?;
+        bytes[2] = 0;
+        bytes[3] = 0;
+        Ok(
+            checksum::Sum16BitWords::new()
+            .add_slice(&bytes)
+            .add_slice(payload)
+            .ones_complement()
+            .to_be()
+        )
+    }
+
+    /// Writes the message to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized message.
+    ///
+    /// Returns a [`ValueError::MulticastRecordLengthBad`] error if a source
+    /// list, the record list or auxiliary data can not be represented.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        use crate::ValueError::MulticastRecordLengthBad;
+        use IgmpType::*;
+
+        let mut result = Vec::with_capacity(self.header_len());
+        result.push(self.igmp_type.type_u8());
+        result.push(match &self.igmp_type {
+            MembershipQuery{ max_response_time, .. } => *max_response_time,
+            MembershipQueryV3(value) => value.max_response_code,
+            Unknown{ code_u8, .. } => *code_u8,
+            _ => 0,
+        });
+        result.extend_from_slice(&self.checksum.to_be_bytes());
+        match &self.igmp_type {
+            MembershipQuery{ group_address, .. } |
+            MembershipReportV1{ group_address } |
+            MembershipReportV2{ group_address } |
+            LeaveGroup{ group_address } => {
+                result.extend_from_slice(group_address);
+            },
+            MembershipQueryV3(value) => {
+                max_check_u8(
+                    value.querier_robustness_variable,
+                    IgmpV3Query::MAX_QUERIER_ROBUSTNESS_VARIABLE,
+                    ErrorField::IgmpQuerierRobustnessVariable
+                )?;
+                if value.sources.len() > usize::from(u16::MAX) {
+                    return Err(MulticastRecordLengthBad);
+                }
+                result.extend_from_slice(&value.group_address);
+                result.push(
+                    if value.suppress_router_side_processing { 0b1000 } else { 0 } |
+                    value.querier_robustness_variable
+                );
+                result.push(value.querier_query_interval_code);
+                result.extend_from_slice(&(value.sources.len() as u16).to_be_bytes());
+                for source in &value.sources {
+                    result.extend_from_slice(source);
+                }
+            },
+            MembershipReportV3(records) => {
+                if records.len() > usize::from(u16::MAX) {
+                    return Err(MulticastRecordLengthBad);
+                }
+                result.extend_from_slice(&[0, 0]);
+                result.extend_from_slice(&(records.len() as u16).to_be_bytes());
+                for record in records {
+                    if record.sources.len() > usize::from(u16::MAX) ||
+                       0 != record.aux_data.len() % 4 ||
+                       record.aux_data.len() > 0xff*4
+                    {
+                        return Err(MulticastRecordLengthBad);
+                    }
+                    result.push(record.record_type);
+                    result.push((record.aux_data.len() / 4) as u8);
+                    result.extend_from_slice(&(record.sources.len() as u16).to_be_bytes());
+                    result.extend_from_slice(&record.group_address);
+                    for source in &record.sources {
+                        result.extend_from_slice(source);
+                    }
+                    result.extend_from_slice(&record.aux_data);
+                }
+            },
+            Unknown{ bytes5to8, .. } => {
+                result.extend_from_slice(bytes5to8);
+            },
+        }
+        Ok(result)
+    }
+}
+
+/// A slice containing an IGMP message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IgmpSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> IgmpSlice<'a> {
+
+    /// Creates a slice containing an IGMP message (the length of IGMPv3
+    /// queries & reports is determined based on the source & record counts).
+    ///
+    /// Queries with a length of at least 12 bytes are decoded as IGMPv3
+    /// queries (RFC 3376 section 7.1), the slice should therefore not
+    /// contain data after the IP payload (e.g. ethernet padding).
+    pub fn from_slice(slice: &'a [u8]) -> Result<IgmpSlice<'a>, ReadError> {
+        use crate::ReadError::UnexpectedEndOfSlice;
+
+        if slice.len() < IgmpHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(IgmpHeader::MIN_LEN));
+        }
+        let len = match slice[0] {
+            igmp_type::MEMBERSHIP_QUERY if slice.len() >= IgmpV3Query::MIN_LEN => {
+                let count = usize::from(u16::from_be_bytes([slice[10], slice[11]]));
+                IgmpV3Query::MIN_LEN + count*4
+            },
+            igmp_type::V3_MEMBERSHIP_REPORT => {
+                let count = usize::from(u16::from_be_bytes([slice[6], slice[7]]));
+                let mut offset = IgmpHeader::MIN_LEN;
+                for _ in 0..count {
+                    let rest = slice.get(offset..).unwrap_or(&[]);
+                    if rest.len() < IgmpGroupRecord::MIN_LEN {
+                        return Err(UnexpectedEndOfSlice(offset + IgmpGroupRecord::MIN_LEN));
+                    }
+                    let aux_len = usize::from(rest[1])*4;
+                    let source_count = usize::from(u16::from_be_bytes([rest[2], rest[3]]));
+                    offset += IgmpGroupRecord::MIN_LEN + source_count*4 + aux_len;
+                }
+                offset
+            },
+            _ => IgmpHeader::MIN_LEN,
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(IgmpSlice{
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the IGMP message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "type" field of the message.
+    #[inline]
+    pub fn type_u8(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the second byte of the message (max response time or code
+    /// depending on the type of the message).
+    #[inline]
+    pub fn code_u8(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the "checksum" field of the message.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Decode all the fields & copy the data into a new IgmpHeader.
+    pub fn to_header(&self) -> IgmpHeader {
+        let s = self.slice;
+        let group_address = read_ipv4(&s[4..]);
+        let igmp_type = match s[0] {
+            igmp_type::MEMBERSHIP_QUERY if s.len() >= IgmpV3Query::MIN_LEN => {
+                IgmpType::MembershipQueryV3(IgmpV3Query{
+                    max_response_code: s[1],
+                    group_address,
+                    suppress_router_side_processing: 0 != s[8] & 0b1000,
+                    querier_robustness_variable: s[8] & 0b111,
+                    querier_query_interval_code: s[9],
+                    sources: s[IgmpV3Query::MIN_LEN..].chunks_exact(4).map(read_ipv4).collect(),
+                })
+            },
+            igmp_type::MEMBERSHIP_QUERY => IgmpType::MembershipQuery{
+                max_response_time: s[1],
+                group_address,
+            },
+            igmp_type::V1_MEMBERSHIP_REPORT => IgmpType::MembershipReportV1{ group_address },
+            igmp_type::V2_MEMBERSHIP_REPORT => IgmpType::MembershipReportV2{ group_address },
+            igmp_type::LEAVE_GROUP => IgmpType::LeaveGroup{ group_address },
+            igmp_type::V3_MEMBERSHIP_REPORT => {
+                // the record lengths were already validated in from_slice
+                let mut rest = &s[IgmpHeader::MIN_LEN..];
+                let mut records = Vec::new();
+                while !rest.is_empty() {
+                    let aux_len = usize::from(rest[1])*4;
+                    let sources_end = IgmpGroupRecord::MIN_LEN + usize::from(u16::from_be_bytes([rest[2], rest[3]]))*4;
+                    let len = sources_end + aux_len;
+                    records.push(IgmpGroupRecord{
+                        record_type: rest[0],
+                        group_address: read_ipv4(&rest[4..]),
+                        sources: rest[IgmpGroupRecord::MIN_LEN..sources_end].chunks_exact(4).map(read_ipv4).collect(),
+                        aux_data: rest[sources_end..len].to_vec(),
+                    });
+                    rest = &rest[len..];
+                }
+                IgmpType::MembershipReportV3(records)
+            },
+            type_u8 => IgmpType::Unknown{
+                type_u8,
+                code_u8: s[1],
+                bytes5to8: group_address,
+            },
+        };
+        IgmpHeader{
+            igmp_type,
+            checksum: self.checksum(),
+        }
+    }
+}
+
+/// Decodes the 8 bit floating point format of IGMPv3 (RFC 3376 section 4.1.1).
+fn decode_float_u8(code: u8) -> u32 {
+    if code < 128 {
+        u32::from(code)
+    } else {
+        let mantissa = u32::from(code & 0xf) | 0x10;
+        let exponent = u32::from((code >> 4) & 0x7);
+        mantissa << (exponent + 3)
+    }
+}
+
+fn read_ipv4(data: &[u8]) -> [u8;4] {
+    let mut result = [0u8;4];
+    result.copy_from_slice(&data[..4]);
+    result
+}
@@ -1,5 +1,6 @@
 pub mod udp;
 pub mod tcp;
+pub mod igmp;
 
 use super::*;
 
@@ -9,7 +10,8 @@ use std::io;
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TransportHeader {
     Udp(udp::UdpHeader),
-    Tcp(tcp::TcpHeader)
+    Tcp(tcp::TcpHeader),
+    Igmp(igmp::IgmpHeader)
 }
 
 impl TransportHeader {
@@ -20,7 +22,7 @@ impl TransportHeader {
         use crate::TransportHeader::*;
         match self {
             Udp(value) => Some(value),
-            Tcp(_) => None
+            _ => None
         }
     }
 
@@ -30,7 +32,7 @@ impl TransportHeader {
         use crate::TransportHeader::*;
         match self {
             Udp(ref mut value) => Some(value),
-            Tcp(_) => None
+            _ => None
         }
     }
 
@@ -39,8 +41,8 @@ impl TransportHeader {
     pub fn tcp(self) -> Option<tcp::TcpHeader> {
         use crate::TransportHeader::*;
         match self {
-            Udp(_) => None,
-            Tcp(value) => Some(value)
+            Tcp(value) => Some(value),
+            _ => None
         }
     }
 
@@ -49,18 +51,39 @@ impl TransportHeader {
     pub fn mut_tcp(&mut self) -> Option<&mut tcp::TcpHeader> {
         use crate::TransportHeader::*;
         match self {
-            Udp(_) => None,
-            Tcp(ref mut value) => Some(value)
+            Tcp(ref mut value) => Some(value),
+            _ => None
+        }
+    }
+
+    ///Returns Result::Some containing the igmp header if self has the value Igmp.
+    ///Otherwise None is returned.
+    pub fn igmp(self) -> Option<igmp::IgmpHeader> {
+        use crate::TransportHeader::*;
+        match self {
+            Igmp(value) => Some(value),
+            _ => None
+        }
+    }
+
+    ///Returns Result::Some containing a mutable refernce to the igmp header if self has the value Igmp.
+    ///Otherwise None is returned.
+    pub fn mut_igmp(&mut self) -> Option<&mut igmp::IgmpHeader> {
+        use crate::TransportHeader::*;
+        match self {
+            Igmp(ref mut value) => Some(value),
+            _ => None
         }
     }
 
     ///Returns the size of the transport header (in case of UDP fixed, 
-    ///in case of TCP cotanining the options).dd
+    ///in case of TCP cotanining the options, in case of IGMP the complete message).
     pub fn header_len(&self) -> usize {
         use crate::TransportHeader::*;
         match self {
             Udp(_) => udp::UdpHeader::SERIALIZED_SIZE,
-            Tcp(value) => usize::from(value.header_len())
+            Tcp(value) => usize::from(value.header_len()),
+            Igmp(value) => value.header_len()
         }
     }
 
@@ -74,6 +97,9 @@ impl TransportHeader {
             },
             Tcp(header) => {
                 header.checksum = header.calc_checksum_ipv4(ip_header, payload)?;
+            },
+            Igmp(header) => {
+                header.checksum = header.calc_checksum(payload)?;
             }
         }
         Ok(())
@@ -89,6 +115,49 @@ impl TransportHeader {
             },
             Tcp(header) => {
                 header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
+            },
+            Igmp(header) => {
+                // igmp checksums don't include a pseudo header
+                header.checksum = header.calc_checksum(payload)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Calculates the checksum for the transport header & sets it in the header for
+    ///an ipv4 header, using the final destination of source routed packets
+    ///(see [`Ipv4Header::final_destination`]).
+    pub fn update_checksum_ipv4_final_destination(&mut self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<(), ValueError> {
+        use crate::TransportHeader::*;
+        match self {
+            Udp(header) => {
+                header.checksum = header.calc_checksum_ipv4_final_destination(ip_header, payload)?;
+            },
+            Tcp(header) => {
+                header.checksum = header.calc_checksum_ipv4_final_destination(ip_header, payload)?;
+            },
+            Igmp(header) => {
+                header.checksum = header.calc_checksum(payload)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Calculates the checksum for the transport header & sets it in the header for
+    ///an ipv6 header & its extension headers, using the final destination in case
+    ///a routing header is present (see [`Ipv6Header::final_destination`]).
+    pub fn update_checksum_ipv6_final_destination(&mut self, ip_header: &Ipv6Header, extensions: &Ipv6Extensions, payload: &[u8]) -> Result<(), ValueError> {
+        use crate::TransportHeader::*;
+        match self {
+            Udp(header) => {
+                header.checksum = header.calc_checksum_ipv6_final_destination(ip_header, extensions, payload)?;
+            },
+            Tcp(header) => {
+                header.checksum = header.calc_checksum_ipv6_final_destination(ip_header, extensions, payload)?;
+            },
+            Igmp(header) => {
+                // igmp checksums don't include a pseudo header
+                header.checksum = header.calc_checksum(payload)?;
             }
         }
         Ok(())
@@ -99,7 +168,8 @@ impl TransportHeader {
         use crate::TransportHeader::*;
         match self {
             Udp(value) => value.write(writer),
-            Tcp(value) => value.write(writer).map_err(WriteError::from)
+            Tcp(value) => value.write(writer).map_err(WriteError::from),
+            Igmp(value) => value.write(writer)
         }
     }
 }
@@ -424,6 +424,13 @@ impl TcpHeader {
         self.calc_checksum_ipv4_raw(ip_header.source, ip_header.destination, payload)
     }
 
+    /// Calculates the tcp header checksum based on a ipv4 header using the
+    /// final destination of source routed packets (see
+    /// [`Ipv4Header::final_destination`]) and returns the result. This does NOT set the checksum.
+    pub fn calc_checksum_ipv4_final_destination(&self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv4_raw(ip_header.source, ip_header.final_destination(), payload)
+    }
+
     /// Calculates the checksum for the current header in ipv4 mode and returns the result. This does NOT set the checksum.
     pub fn calc_checksum_ipv4_raw(&self, source_ip: [u8;4], destination_ip: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
         
@@ -451,6 +458,13 @@ impl TcpHeader {
         self.calc_checksum_ipv6_raw(ip_header.source, ip_header.destination, payload)
     }
 
+    /// Calculates the tcp header checksum based on a ipv6 header & its
+    /// extension headers using the final destination in case a routing
+    /// header is present (see [`Ipv6Header::final_destination`]) and returns the result. This does NOT set the checksum.
+    pub fn calc_checksum_ipv6_final_destination(&self, ip_header: &Ipv6Header, extensions: &Ipv6Extensions, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv6_raw(ip_header.source, ip_header.final_destination(extensions), payload)
+    }
+
     /// Calculates the checksum for the current header in ipv6 mode and returns the result. This does NOT set the checksum.
     pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
 
@@ -968,6 +982,16 @@ impl<'a> TcpHeaderSlice<'a> {
         )
     }
 
+    ///Returns true if the checksum of the header & payload is valid for the given ipv4 source & destination address.
+    pub fn is_checksum_valid_ipv4(&self, source_ip: [u8;4], destination_ip: [u8;4], payload: &[u8]) -> bool {
+        Ok(self.checksum()) == self.calc_checksum_ipv4_raw(source_ip, destination_ip, payload)
+    }
+
+    ///Returns true if the checksum of the header & payload is valid for the given ipv6 source & destination address.
+    pub fn is_checksum_valid_ipv6(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> bool {
+        Ok(self.checksum()) == self.calc_checksum_ipv6_raw(source, destination, payload)
+    }
+
     /// This method takes the sum of the pseudo ip header and calculates the rest of the checksum.
     fn calc_checksum_post_ip(&self, ip_pseudo_header_sum: checksum::Sum16BitWords, payload: &[u8]) -> u16 {
         ip_pseudo_header_sum
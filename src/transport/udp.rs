@@ -57,6 +57,13 @@ impl UdpHeader {
         self.calc_checksum_ipv4_raw(ip_header.source, ip_header.destination, payload)
     }
 
+    /// Calculates the upd header checksum based on a ipv4 header using the
+    /// final destination of source routed packets (see
+    /// [`Ipv4Header::final_destination`]).
+    pub fn calc_checksum_ipv4_final_destination(&self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv4_raw(ip_header.source, ip_header.final_destination(), payload)
+    }
+
     /// Calculates the upd header checksum based on a ipv4 header.
     pub fn calc_checksum_ipv4_raw(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
         //check that the total length fits into the field
@@ -106,6 +113,14 @@ impl UdpHeader {
         self.calc_checksum_ipv6_raw(ip_header.source, ip_header.destination, payload)
     }
 
+    /// Calculates the checksum of the current udp header given an ipv6
+    /// header, its extension headers and the payload, using the final
+    /// destination in case a routing header is present (see
+    /// [`Ipv6Header::final_destination`]).
+    pub fn calc_checksum_ipv6_final_destination(&self, ip_header: &Ipv6Header, extensions: &Ipv6Extensions, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv6_raw(ip_header.source, ip_header.final_destination(extensions), payload)
+    }
+
     /// Calculates the checksum of the current udp header given an ipv6 source & destination address plus the payload.
     pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
         //check that the total length fits into the field
@@ -124,7 +139,7 @@ impl UdpHeader {
             .add_16bytes(source)
             .add_16bytes(destination)
             .add_2bytes([0, ip_number::UDP])
-            .add_2bytes(self.length.to_be_bytes()),
+            .add_4bytes(ipv6_pseudo_header_length(self.length, payload).to_be_bytes()),
             payload
         )
     }
@@ -386,11 +401,28 @@ impl<'a> UdpHeaderSlice<'a> {
                 .add_16bytes(source)
                 .add_16bytes(destination)
                 .add_2bytes([0, ip_number::UDP])
-                .add_2bytes(self.length().to_be_bytes()),
+                .add_4bytes(ipv6_pseudo_header_length(self.length(), payload).to_be_bytes()),
             payload
         )
     }
 
+    /// Returns true if the checksum of the header & payload is valid for
+    /// the given ipv4 source & destination address.
+    ///
+    /// A checksum of 0 (no checksum calculated, RFC 768) is considered valid
+    /// in ipv4.
+    pub fn is_checksum_valid_ipv4(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> bool {
+        0 == self.checksum() ||
+        Ok(self.checksum()) == self.calc_checksum_ipv4_raw(source, destination, payload)
+    }
+
+    /// Returns true if the checksum of the header & payload is valid for
+    /// the given ipv6 source & destination address (a checksum of 0 is
+    /// invalid in ipv6, RFC 8200 section 8.1).
+    pub fn is_checksum_valid_ipv6(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> bool {
+        Ok(self.checksum()) == self.calc_checksum_ipv6_raw(source, destination, payload)
+    }
+
     /// This method takes the sum of the pseudo ip header and calculates the rest of the checksum.
     fn calc_checksum_post_ip(&self, ip_pseudo_header_sum: checksum::Sum16BitWords, payload: &[u8]) -> u16 {
         ip_pseudo_header_sum
@@ -402,3 +434,17 @@ impl<'a> UdpHeaderSlice<'a> {
             .to_be()
     }
 }
+
+/// Returns the upper-layer packet length used in the ipv6 pseudo header.
+///
+/// UDP jumbograms have a length field of 0 and use the actual length
+/// instead (RFC 2675 section 4).
+fn ipv6_pseudo_header_length(length: u16, payload: &[u8]) -> u32 {
+    let len = UdpHeader::SERIALIZED_SIZE + payload.len();
+    if 0 == length && len > usize::from(u16::MAX) {
+        // the payload length is checked by the callers to fit into an u32
+        len as u32
+    } else {
+        u32::from(length)
+    }
+}
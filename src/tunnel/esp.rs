@@ -0,0 +1,196 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Encapsulating Security Payload (ESP) header (RFC 4303) containing the
+/// security parameters index & the sequence number.
+///
+/// The rest of an ESP packet is usually encrypted. For packets using the
+/// NULL encryption algorithm (RFC 2410) the payload & trailer can be
+/// located via [`EspNullSlice`].
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct EspHeader {
+    /// Security parameters index.
+    pub spi: u32,
+    pub sequence_number: u32,
+}
+
+impl SerializedSize for EspHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl EspHeader {
+    /// Read an ESP header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(EspHeader, &[u8]), ReadError> {
+        Ok((
+            EspHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[EspHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read an ESP header from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;8]) -> EspHeader {
+        EspHeaderSlice{
+            slice: &bytes
+        }.to_header()
+    }
+
+    /// Reads an ESP header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<EspHeader, io::Error> {
+        let mut buffer = [0;EspHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(EspHeader::from_bytes(buffer))
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant EspHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        EspHeader::SERIALIZED_SIZE
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the serialized form of the header.
+    pub fn to_bytes(&self) -> [u8;8] {
+        let spi = self.spi.to_be_bytes();
+        let sequence_number = self.sequence_number.to_be_bytes();
+        [
+            spi[0], spi[1], spi[2], spi[3],
+            sequence_number[0], sequence_number[1], sequence_number[2], sequence_number[3],
+        ]
+    }
+}
+
+/// A slice containing an ESP header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EspHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> EspHeaderSlice<'a> {
+    /// Creates an ESP header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<EspHeaderSlice<'a>, ReadError> {
+        if slice.len() < EspHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(EspHeader::SERIALIZED_SIZE));
+        }
+        Ok(EspHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // EspHeader::SERIALIZED_SIZE (8) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    EspHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the ESP header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "security parameters index" field.
+    #[inline]
+    pub fn spi(&self) -> u32 {
+        u32::from_be_bytes([self.slice[0], self.slice[1], self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "sequence number" field.
+    #[inline]
+    pub fn sequence_number(&self) -> u32 {
+        u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Decode all the fields & copy the data into a new EspHeader.
+    pub fn to_header(&self) -> EspHeader {
+        EspHeader{
+            spi: self.spi(),
+            sequence_number: self.sequence_number(),
+        }
+    }
+}
+
+/// Payload & trailer of an ESP packet using the NULL encryption algorithm
+/// (RFC 2410), located based on the length of the integrity check value
+/// (ICV) negotiated for the security association.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EspNullSlice<'a> {
+    payload: &'a [u8],
+    padding: &'a [u8],
+    next_header: u8,
+    icv: &'a [u8],
+}
+
+impl<'a> EspNullSlice<'a> {
+    /// Length of the pad length & next header fields of the trailer.
+    pub const TRAILER_LEN: usize = 2;
+
+    /// Locates the payload, padding, trailer & ICV in the data following
+    /// an ESP header.
+    ///
+    /// Returns a [`ReadError::EspPadLengthTooLarge`] error if the pad
+    /// length is bigger then the data before the trailer.
+    pub fn from_slice(slice: &'a [u8], icv_len: usize) -> Result<EspNullSlice<'a>, ReadError> {
+        let min_len = icv_len.saturating_add(EspNullSlice::TRAILER_LEN);
+        if slice.len() < min_len {
+            return Err(ReadError::UnexpectedEndOfSlice(min_len));
+        }
+        let (rest, icv) = slice.split_at(slice.len() - icv_len);
+        let (rest, trailer) = rest.split_at(rest.len() - EspNullSlice::TRAILER_LEN);
+        let pad_length = usize::from(trailer[0]);
+        if rest.len() < pad_length {
+            return Err(ReadError::EspPadLengthTooLarge(trailer[0]));
+        }
+        let (payload, padding) = rest.split_at(rest.len() - pad_length);
+        Ok(EspNullSlice{
+            payload,
+            padding,
+            next_header: trailer[1],
+            icv,
+        })
+    }
+
+    /// Returns the protected payload (without padding, trailer & ICV).
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns the padding preceding the trailer.
+    #[inline]
+    pub fn padding(&self) -> &'a [u8] {
+        self.padding
+    }
+
+    /// Read the "pad length" field of the trailer.
+    #[inline]
+    pub fn pad_length(&self) -> u8 {
+        self.padding.len() as u8
+    }
+
+    /// Read the "next header" field of the trailer (IP protocol number of
+    /// the payload).
+    #[inline]
+    pub fn next_header(&self) -> u8 {
+        self.next_header
+    }
+
+    /// Returns the integrity check value.
+    #[inline]
+    pub fn icv(&self) -> &'a [u8] {
+        self.icv
+    }
+}
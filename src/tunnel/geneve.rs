@@ -0,0 +1,360 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Geneve header according to RFC 8926 including the variable length options.
+///
+/// Geneve headers are transported via UDP (destination port
+/// [`GeneveHeader::PORT`]) & are followed by the packet identified by the
+/// `protocol_type` field (e.g. an ethernet frame in case of
+/// [`ether_type::TRANSPARENT_ETHERNET_BRIDGING`]). The reserved bits are
+/// ignored when decoding & set to 0 when serializing.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GeneveHeader {
+    /// Version of the header (2 bits, currently only 0 is defined).
+    pub version: u8,
+    /// Set if the packet contains a control message ("O" bit).
+    pub control_packet: bool,
+    /// Set if at least one option has the critical bit set ("C" bit).
+    pub critical_options_present: bool,
+    /// Protocol type of the payload (an ether type, e.g. [`ether_type::IPV4`]
+    /// or [`ether_type::TRANSPARENT_ETHERNET_BRIDGING`] for ethernet frames).
+    pub protocol_type: u16,
+    /// Virtual network identifier (24 bits).
+    pub vni: u32,
+    /// Options following the fixed part of the header.
+    pub options: Vec<GeneveOption>,
+}
+
+impl GeneveHeader {
+    /// UDP destination port assigned to Geneve by IANA.
+    pub const PORT: u16 = 6081;
+
+    /// Length of a Geneve header without options.
+    pub const MIN_LEN: usize = 8;
+
+    /// Maximum length of the options of a Geneve header (in bytes).
+    pub const MAX_OPTIONS_LEN: usize = 0b11_1111*4;
+
+    /// Length of a Geneve header with the maximum options length.
+    pub const MAX_LEN: usize = GeneveHeader::MIN_LEN + GeneveHeader::MAX_OPTIONS_LEN;
+
+    /// Maximum value of the version field.
+    pub const MAX_VERSION: u8 = 0b11;
+
+    /// Maximum value of the VNI field.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    /// Flag indicating a control message ("O" bit in the second byte).
+    pub const FLAG_CONTROL_PACKET: u8 = 0b1000_0000;
+
+    /// Flag indicating that critical options are present ("C" bit in the
+    /// second byte).
+    pub const FLAG_CRITICAL_OPTIONS_PRESENT: u8 = 0b0100_0000;
+
+    /// Reads a Geneve header from a slice & returns the header & the rest
+    /// of the slice (the Geneve payload).
+    pub fn from_slice(slice: &[u8]) -> Result<(GeneveHeader, &[u8]), ReadError> {
+        let header = GeneveHeaderSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Reads a Geneve header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GeneveHeader, ReadError> {
+        let mut buffer = [0u8;GeneveHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..GeneveHeader::MIN_LEN])?;
+        let len = GeneveHeaderSlice::len_from_start(&buffer);
+        reader.read_exact(&mut buffer[GeneveHeader::MIN_LEN..len])?;
+        Ok(GeneveHeaderSlice::from_slice(&buffer[..len])?.to_header())
+    }
+
+    /// Length of the options in bytes.
+    pub fn options_len(&self) -> usize {
+        self.options.iter().map(|o| o.option_len()).sum()
+    }
+
+    /// Length of the serialized header in bytes (including the options).
+    pub fn header_len(&self) -> usize {
+        GeneveHeader::MIN_LEN + self.options_len()
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized header or an value error in case a field
+    /// value or the options can not be represented in the header.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        use crate::ValueError::*;
+
+        max_check_u8(self.version, GeneveHeader::MAX_VERSION, ErrorField::GeneveVersion)?;
+        max_check_u32(self.vni, GeneveHeader::MAX_VNI, ErrorField::GeneveVni)?;
+        for option in &self.options {
+            option.check_data_len()?;
+        }
+        let options_len = self.options_len();
+        if options_len > GeneveHeader::MAX_OPTIONS_LEN {
+            return Err(GeneveOptionsLengthTooLarge(options_len));
+        }
+
+        let mut result = Vec::with_capacity(GeneveHeader::MIN_LEN + options_len);
+        result.push((self.version << 6) | (options_len / 4) as u8);
+        result.push(
+            if self.control_packet { GeneveHeader::FLAG_CONTROL_PACKET } else { 0 } |
+            if self.critical_options_present { GeneveHeader::FLAG_CRITICAL_OPTIONS_PRESENT } else { 0 }
+        );
+        result.extend_from_slice(&self.protocol_type.to_be_bytes());
+        let vni_be = self.vni.to_be_bytes();
+        result.extend_from_slice(&[vni_be[1], vni_be[2], vni_be[3], 0]);
+        for option in &self.options {
+            result.extend_from_slice(&option.class.to_be_bytes());
+            result.push(option.option_type);
+            result.push((option.data.len() / 4) as u8);
+            result.extend_from_slice(&option.data);
+        }
+        Ok(result)
+    }
+}
+
+/// Option (type-length-value element) of a Geneve header.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GeneveOption {
+    /// Namespace of the option type ("option class").
+    pub class: u16,
+    /// Type of the option (the highest bit marks the option as critical).
+    pub option_type: u8,
+    /// Data of the option (must be a multiple of 4 bytes & at most
+    /// [`GeneveOption::MAX_DATA_LEN`] bytes long).
+    pub data: Vec<u8>,
+}
+
+impl GeneveOption {
+    /// Length of the option header (class, type & length fields).
+    pub const HEADER_LEN: usize = 4;
+
+    /// Maximum length of the data of an option.
+    pub const MAX_DATA_LEN: usize = 0b1_1111*4;
+
+    /// Bit in the option type marking an option as critical.
+    pub const TYPE_CRITICAL: u8 = 0b1000_0000;
+
+    /// Returns true if the critical bit in the option type is set.
+    #[inline]
+    pub fn is_critical(&self) -> bool {
+        0 != self.option_type & GeneveOption::TYPE_CRITICAL
+    }
+
+    /// Length of the serialized option in bytes (including the option header).
+    #[inline]
+    pub fn option_len(&self) -> usize {
+        GeneveOption::HEADER_LEN + self.data.len()
+    }
+
+    fn check_data_len(&self) -> Result<(), ValueError> {
+        if !self.data.len().is_multiple_of(4) || self.data.len() > GeneveOption::MAX_DATA_LEN {
+            Err(ValueError::GeneveOptionDataLengthBad(self.data.len()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A slice containing a Geneve header (including the options).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneveHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GeneveHeaderSlice<'a> {
+    /// Creates a slice containing a Geneve header (the length is
+    /// determined based on the options length field).
+    ///
+    /// Returns a [`ReadError::GeneveOptionLengthBad`] error if an option
+    /// exceeds the options area of the header.
+    pub fn from_slice(slice: &'a [u8]) -> Result<GeneveHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < GeneveHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GeneveHeader::MIN_LEN));
+        }
+        let len = GeneveHeaderSlice::len_from_start(slice);
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        // check that the options fill the options area exactly (the
+        // area & all options are multiples of 4 bytes, so only the
+        // length of the options has to be checked)
+        let mut rest = &slice[GeneveHeader::MIN_LEN..len];
+        while !rest.is_empty() {
+            let length = rest[3] & 0b1_1111;
+            let option_len = GeneveOption::HEADER_LEN + usize::from(length)*4;
+            if rest.len() < option_len {
+                return Err(GeneveOptionLengthBad(length));
+            }
+            rest = &rest[option_len..];
+        }
+
+        Ok(GeneveHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Determines the header length based on the options length field
+    /// (the slice must be at least 1 byte long).
+    fn len_from_start(slice: &[u8]) -> usize {
+        GeneveHeader::MIN_LEN + usize::from(slice[0] & 0b11_1111)*4
+    }
+
+    /// Returns the slice containing the Geneve header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "version" field.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 6
+    }
+
+    /// Returns true if the "O" bit is set (control message).
+    #[inline]
+    pub fn control_packet(&self) -> bool {
+        0 != self.slice[1] & GeneveHeader::FLAG_CONTROL_PACKET
+    }
+
+    /// Returns true if the "C" bit is set (critical options present).
+    #[inline]
+    pub fn critical_options_present(&self) -> bool {
+        0 != self.slice[1] & GeneveHeader::FLAG_CRITICAL_OPTIONS_PRESENT
+    }
+
+    /// Read the "protocol type" field (ether type of the payload).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "virtual network identifier" field.
+    #[inline]
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[4], self.slice[5], self.slice[6]])
+    }
+
+    /// Returns the slice containing the options.
+    #[inline]
+    pub fn options_slice(&self) -> &'a [u8] {
+        &self.slice[GeneveHeader::MIN_LEN..]
+    }
+
+    /// Returns an iterator over the options of the header.
+    #[inline]
+    pub fn options(&self) -> GeneveOptionsIterator<'a> {
+        GeneveOptionsIterator{
+            rest: self.options_slice()
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new GeneveHeader.
+    pub fn to_header(&self) -> GeneveHeader {
+        GeneveHeader{
+            version: self.version(),
+            control_packet: self.control_packet(),
+            critical_options_present: self.critical_options_present(),
+            protocol_type: self.protocol_type(),
+            vni: self.vni(),
+            options: self.options().map(|o| o.to_option()).collect(),
+        }
+    }
+}
+
+/// A slice containing a single Geneve option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneveOptionSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GeneveOptionSlice<'a> {
+    /// Returns the slice containing the option (including the option header).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "option class" field.
+    #[inline]
+    pub fn class(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the "type" field.
+    #[inline]
+    pub fn option_type(&self) -> u8 {
+        self.slice[2]
+    }
+
+    /// Returns true if the critical bit in the option type is set.
+    #[inline]
+    pub fn is_critical(&self) -> bool {
+        0 != self.option_type() & GeneveOption::TYPE_CRITICAL
+    }
+
+    /// Read the "length" field (length of the data in multiples of 4 bytes).
+    #[inline]
+    pub fn length(&self) -> u8 {
+        self.slice[3] & 0b1_1111
+    }
+
+    /// Returns the slice containing the option data.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        &self.slice[GeneveOption::HEADER_LEN..]
+    }
+
+    /// Decode all the fields & copy the data into a new GeneveOption.
+    pub fn to_option(&self) -> GeneveOption {
+        GeneveOption{
+            class: self.class(),
+            option_type: self.option_type(),
+            data: self.data().to_vec(),
+        }
+    }
+}
+
+/// Iterator over the options of a Geneve header (created via
+/// [`GeneveHeaderSlice::options`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneveOptionsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> GeneveOptionsIterator<'a> {
+    /// Returns the slice containing the options that were not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for GeneveOptionsIterator<'a> {
+    type Item = GeneveOptionSlice<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        // the option lengths were already checked by GeneveHeaderSlice::from_slice
+        let len = GeneveOption::HEADER_LEN + usize::from(self.rest[3] & 0b1_1111)*4;
+        let (option, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(GeneveOptionSlice{ slice: option })
+    }
+}
@@ -0,0 +1,331 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// GRE header according to RFC 2784 including the key & sequence number
+/// extensions of RFC 2890.
+///
+/// Only GRE headers with the version 0 are supported (the enhanced GRE
+/// header used by PPTP has the version 1). The reserved bits are ignored
+/// when decoding & set to 0 when serializing.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GreHeader {
+    /// Protocol type of the payload (an ether type, e.g. [`ether_type::IPV4`]
+    /// or [`ether_type::TRANSPARENT_ETHERNET_BRIDGING`] for ethernet frames).
+    pub protocol_type: u16,
+    /// Checksum over the GRE header & payload (present if the "C" bit is set).
+    pub checksum: Option<u16>,
+    /// Key identifying a flow within the tunnel (present if the "K" bit is set).
+    pub key: Option<u32>,
+    /// Sequence number of the packet (present if the "S" bit is set).
+    pub sequence_number: Option<u32>,
+}
+
+impl GreHeader {
+    /// Length of a GRE header without the optional fields.
+    pub const MIN_LEN: usize = 4;
+
+    /// Length of a GRE header with all optional fields present.
+    pub const MAX_LEN: usize = 16;
+
+    /// Flag indicating that the checksum & reserved1 fields are present.
+    pub const CHECKSUM_PRESENT: u8 = 0b1000_0000;
+
+    /// Flag indicating that the key field is present.
+    pub const KEY_PRESENT: u8 = 0b0010_0000;
+
+    /// Flag indicating that the sequence number field is present.
+    pub const SEQUENCE_NUMBER_PRESENT: u8 = 0b0001_0000;
+
+    /// Maximum value of the NVGRE virtual subnet id (24 bits).
+    pub const MAX_NVGRE_VSID: u32 = 0xff_ffff;
+
+    /// Creates a NVGRE header (RFC 7637) with the given virtual subnet id
+    /// & flow id in the key field and the protocol type
+    /// [`ether_type::TRANSPARENT_ETHERNET_BRIDGING`].
+    ///
+    /// Returns a value error if the VSID is bigger then [`GreHeader::MAX_NVGRE_VSID`].
+    pub fn nvgre(vsid: u32, flow_id: u8) -> Result<GreHeader, ValueError> {
+        max_check_u32(vsid, GreHeader::MAX_NVGRE_VSID, ErrorField::NvgreVsid)?;
+        Ok(GreHeader{
+            protocol_type: ether_type::TRANSPARENT_ETHERNET_BRIDGING,
+            checksum: None,
+            key: Some((vsid << 8) | u32::from(flow_id)),
+            sequence_number: None,
+        })
+    }
+
+    /// Reads a GRE header from a slice & returns the header & the rest of
+    /// the slice (the GRE payload).
+    pub fn from_slice(slice: &[u8]) -> Result<(GreHeader, &[u8]), ReadError> {
+        let header = GreHeaderSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Reads a GRE header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GreHeader, ReadError> {
+        let mut buffer = [0u8;GreHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..GreHeader::MIN_LEN])?;
+        let len = GreHeaderSlice::len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[GreHeader::MIN_LEN..len])?;
+        Ok(GreHeaderSlice{
+            slice: &buffer[..len]
+        }.to_header())
+    }
+
+    /// Returns true if the header has the format of a NVGRE header (key
+    /// present, no checksum & sequence number & the protocol type
+    /// [`ether_type::TRANSPARENT_ETHERNET_BRIDGING`]).
+    pub fn is_nvgre(&self) -> bool {
+        self.key.is_some() &&
+        self.checksum.is_none() &&
+        self.sequence_number.is_none() &&
+        ether_type::TRANSPARENT_ETHERNET_BRIDGING == self.protocol_type
+    }
+
+    /// Returns the NVGRE virtual subnet id (upper 24 bits of the key) if
+    /// the header is a NVGRE header.
+    pub fn nvgre_vsid(&self) -> Option<u32> {
+        if self.is_nvgre() {
+            self.key.map(|key| key >> 8)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the NVGRE flow id (lower 8 bits of the key) if the header
+    /// is a NVGRE header.
+    pub fn nvgre_flow_id(&self) -> Option<u8> {
+        if self.is_nvgre() {
+            self.key.map(|key| key as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        GreHeader::MIN_LEN +
+        if self.checksum.is_some() { 4 } else { 0 } +
+        if self.key.is_some() { 4 } else { 0 } +
+        if self.sequence_number.is_some() { 4 } else { 0 }
+    }
+
+    /// Calculates the checksum over the header & the given payload (the
+    /// value of the checksum field is ignored).
+    ///
+    /// Note that the checksum is only transmitted if the `checksum` field
+    /// is set to `Some`.
+    pub fn calc_checksum(&self, payload: &[u8]) -> u16 {
+        let mut bytes = self.to_bytes();
+        if self.checksum.is_some() {
+            bytes[4] = 0;
+            bytes[5] = 0;
+        }
+        checksum::Sum16BitWords::new()
+            .add_slice(&bytes)
+            .add_slice(payload)
+            .ones_complement()
+            .to_be()
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the serialized header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.header_len());
+        result.push(
+            if self.checksum.is_some() { GreHeader::CHECKSUM_PRESENT } else { 0 } |
+            if self.key.is_some() { GreHeader::KEY_PRESENT } else { 0 } |
+            if self.sequence_number.is_some() { GreHeader::SEQUENCE_NUMBER_PRESENT } else { 0 }
+        );
+        // reserved0 & version 0
+        result.push(0);
+        result.extend_from_slice(&self.protocol_type.to_be_bytes());
+        if let Some(value) = self.checksum {
+            result.extend_from_slice(&value.to_be_bytes());
+            // reserved1
+            result.extend_from_slice(&[0, 0]);
+        }
+        if let Some(value) = self.key {
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+        if let Some(value) = self.sequence_number {
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+        result
+    }
+}
+
+/// A slice containing a GRE header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GreHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GreHeaderSlice<'a> {
+    /// Creates a slice containing a GRE header (the length is determined
+    /// based on the flags).
+    ///
+    /// Returns a [`ReadError::GreUnsupportedVersion`] error if the version
+    /// field is not 0.
+    pub fn from_slice(slice: &'a [u8]) -> Result<GreHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < GreHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GreHeader::MIN_LEN));
+        }
+        let len = GreHeaderSlice::len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(GreHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Checks the version & determines the header length based on the
+    /// first 4 bytes (the slice must be at least 4 bytes long).
+    fn len_from_start(slice: &[u8]) -> Result<usize, ReadError> {
+        let version = slice[1] & 0b111;
+        if 0 != version {
+            return Err(ReadError::GreUnsupportedVersion(version));
+        }
+        let flags = slice[0];
+        Ok(
+            GreHeader::MIN_LEN +
+            if 0 != flags & GreHeader::CHECKSUM_PRESENT { 4 } else { 0 } +
+            if 0 != flags & GreHeader::KEY_PRESENT { 4 } else { 0 } +
+            if 0 != flags & GreHeader::SEQUENCE_NUMBER_PRESENT { 4 } else { 0 }
+        )
+    }
+
+    /// Returns the slice containing the GRE header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns true if the "C" bit is set (checksum present).
+    #[inline]
+    pub fn checksum_present(&self) -> bool {
+        0 != self.slice[0] & GreHeader::CHECKSUM_PRESENT
+    }
+
+    /// Returns true if the "K" bit is set (key present).
+    #[inline]
+    pub fn key_present(&self) -> bool {
+        0 != self.slice[0] & GreHeader::KEY_PRESENT
+    }
+
+    /// Returns true if the "S" bit is set (sequence number present).
+    #[inline]
+    pub fn sequence_number_present(&self) -> bool {
+        0 != self.slice[0] & GreHeader::SEQUENCE_NUMBER_PRESENT
+    }
+
+    /// Read the "version" field (always 0 for supported headers).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[1] & 0b111
+    }
+
+    /// Read the "protocol type" field (ether type of the payload).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "checksum" field if present.
+    pub fn checksum(&self) -> Option<u16> {
+        if self.checksum_present() {
+            Some(u16::from_be_bytes([self.slice[4], self.slice[5]]))
+        } else {
+            None
+        }
+    }
+
+    /// Read the "key" field if present.
+    pub fn key(&self) -> Option<u32> {
+        if self.key_present() {
+            Some(self.read_u32(self.optional_offset(1)))
+        } else {
+            None
+        }
+    }
+
+    /// Read the "sequence number" field if present.
+    pub fn sequence_number(&self) -> Option<u32> {
+        if self.sequence_number_present() {
+            Some(self.read_u32(self.optional_offset(2)))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the header has the format of a NVGRE header (key
+    /// present, no checksum & sequence number & the protocol type
+    /// [`ether_type::TRANSPARENT_ETHERNET_BRIDGING`]).
+    pub fn is_nvgre(&self) -> bool {
+        self.key_present() &&
+        !self.checksum_present() &&
+        !self.sequence_number_present() &&
+        ether_type::TRANSPARENT_ETHERNET_BRIDGING == self.protocol_type()
+    }
+
+    /// Read the NVGRE virtual subnet id (upper 24 bits of the key) if the
+    /// header is a NVGRE header.
+    pub fn nvgre_vsid(&self) -> Option<u32> {
+        if self.is_nvgre() {
+            self.key().map(|key| key >> 8)
+        } else {
+            None
+        }
+    }
+
+    /// Read the NVGRE flow id (lower 8 bits of the key) if the header is
+    /// a NVGRE header.
+    pub fn nvgre_flow_id(&self) -> Option<u8> {
+        if self.is_nvgre() {
+            self.key().map(|key| key as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new GreHeader.
+    pub fn to_header(&self) -> GreHeader {
+        GreHeader{
+            protocol_type: self.protocol_type(),
+            checksum: self.checksum(),
+            key: self.key(),
+            sequence_number: self.sequence_number(),
+        }
+    }
+
+    /// Offset of the optional field with the given index (0 checksum,
+    /// 1 key & 2 sequence number).
+    fn optional_offset(&self, index: usize) -> usize {
+        let present = [self.checksum_present(), self.key_present()];
+        present[..index].iter().fold(GreHeader::MIN_LEN, |offset, &p| if p { offset + 4 } else { offset })
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_be_bytes([
+            self.slice[offset],
+            self.slice[offset + 1],
+            self.slice[offset + 2],
+            self.slice[offset + 3],
+        ])
+    }
+}
@@ -0,0 +1,540 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Message types of GTP-U headers (3GPP TS 29.281 section 6.1).
+pub mod gtpu_message_type {
+    pub const ECHO_REQUEST: u8 = 1;
+    pub const ECHO_RESPONSE: u8 = 2;
+    pub const ERROR_INDICATION: u8 = 26;
+    pub const SUPPORTED_EXTENSION_HEADERS_NOTIFICATION: u8 = 31;
+    pub const END_MARKER: u8 = 254;
+    /// Message containing a user plane packet (T-PDU).
+    pub const G_PDU: u8 = 255;
+}
+
+/// Types of GTP-U extension headers (3GPP TS 29.281 section 5.2.1).
+pub mod gtpu_extension_header_type {
+    pub const NO_MORE_EXTENSION_HEADERS: u8 = 0;
+    pub const UDP_PORT: u8 = 0x40;
+    pub const LONG_PDCP_PDU_NUMBER: u8 = 0x82;
+    pub const SERVICE_CLASS_INDICATOR: u8 = 0x20;
+    pub const RAN_CONTAINER: u8 = 0x81;
+    pub const NR_RAN_CONTAINER: u8 = 0x84;
+    pub const PDU_SESSION_CONTAINER: u8 = 0x85;
+    pub const PDCP_PDU_NUMBER: u8 = 0xc0;
+}
+
+/// GTP-U header (GTPv1 user plane) according to 3GPP TS 29.281.
+///
+/// GTP-U headers are transported via UDP (destination port
+/// [`GtpuHeader::PORT`]). G-PDU messages are followed by the user plane
+/// packet (usually an IPv4 or IPv6 packet). The optional sequence number,
+/// N-PDU number & extension header fields are present if the corresponding
+/// flags are set (all three share one 4 byte block that is present if at
+/// least one of the flags is set). The protocol type flag is always set
+/// & the reserved bit is set to 0 when serializing.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GtpuHeader {
+    /// Type of the message (e.g. [`gtpu_message_type::G_PDU`]).
+    pub message_type: u8,
+    /// Length of the data following the mandatory 8 byte part of the header
+    /// (including the optional fields, extension headers & the payload).
+    pub length: u16,
+    /// Tunnel endpoint identifier.
+    pub teid: u32,
+    /// Sequence number (present if the "S" flag is set).
+    pub sequence_number: Option<u16>,
+    /// N-PDU number (present if the "PN" flag is set).
+    pub n_pdu_number: Option<u8>,
+    /// Extension headers (the "E" flag is set if at least one is present).
+    pub extension_headers: Vec<GtpuExtensionHeader>,
+}
+
+impl GtpuHeader {
+    /// UDP destination port assigned to GTP-U by IANA.
+    pub const PORT: u16 = 2152;
+
+    /// Length of the mandatory part of the header.
+    pub const MIN_LEN: usize = 8;
+
+    /// Version of the GTP-U header (the only supported version).
+    pub const VERSION: u8 = 1;
+
+    /// Flag indicating that the header is a GTP header (and not GTP').
+    pub const FLAG_PROTOCOL_TYPE: u8 = 0b0001_0000;
+
+    /// Flag indicating that extension headers are present ("E" flag).
+    pub const FLAG_EXTENSION_HEADER: u8 = 0b0000_0100;
+
+    /// Flag indicating that the sequence number is present ("S" flag).
+    pub const FLAG_SEQUENCE_NUMBER: u8 = 0b0000_0010;
+
+    /// Flag indicating that the N-PDU number is present ("PN" flag).
+    pub const FLAG_N_PDU_NUMBER: u8 = 0b0000_0001;
+
+    /// Reads a GTP-U header from a slice & returns the header & the rest
+    /// of the slice (the GTP-U payload).
+    pub fn from_slice(slice: &[u8]) -> Result<(GtpuHeader, &[u8]), ReadError> {
+        let header = GtpuHeaderSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Reads a GTP-U header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GtpuHeader, ReadError> {
+        let mut buffer = vec![0u8;GtpuHeader::MIN_LEN];
+        reader.read_exact(&mut buffer)?;
+        GtpuHeaderSlice::check_version(&buffer)?;
+        if 0 != buffer[0] & GtpuHeader::OPTIONAL_FLAGS {
+            buffer.resize(GtpuHeader::MIN_LEN + 4, 0);
+            reader.read_exact(&mut buffer[GtpuHeader::MIN_LEN..])?;
+            if 0 != buffer[0] & GtpuHeader::FLAG_EXTENSION_HEADER {
+                let mut next = buffer[buffer.len() - 1];
+                while gtpu_extension_header_type::NO_MORE_EXTENSION_HEADERS != next {
+                    let start = buffer.len();
+                    buffer.push(0);
+                    reader.read_exact(&mut buffer[start..])?;
+                    let len = GtpuHeaderSlice::extension_len(buffer[start])?;
+                    buffer.resize(start + len, 0);
+                    reader.read_exact(&mut buffer[start + 1..])?;
+                    next = buffer[buffer.len() - 1];
+                }
+            }
+        }
+        Ok(GtpuHeaderSlice{
+            slice: &buffer
+        }.to_header())
+    }
+
+    /// Flags indicating the presence of the optional 4 byte block.
+    const OPTIONAL_FLAGS: u8 = GtpuHeader::FLAG_EXTENSION_HEADER |
+                               GtpuHeader::FLAG_SEQUENCE_NUMBER |
+                               GtpuHeader::FLAG_N_PDU_NUMBER;
+
+    /// Returns true if the optional block containing the sequence number,
+    /// N-PDU number & next extension header type is present.
+    fn optional_present(&self) -> bool {
+        self.sequence_number.is_some() ||
+        self.n_pdu_number.is_some() ||
+        !self.extension_headers.is_empty()
+    }
+
+    /// Length of the serialized header in bytes (including the optional
+    /// fields & extension headers).
+    pub fn header_len(&self) -> usize {
+        GtpuHeader::MIN_LEN +
+        if self.optional_present() { 4 } else { 0 } +
+        self.extension_headers.iter().map(|e| e.header_len()).sum::<usize>()
+    }
+
+    /// Sets the length field based on the given payload length (the length
+    /// of the optional fields & extension headers is added).
+    pub fn set_payload_len(&mut self, payload_len: usize) -> Result<(), ValueError> {
+        let value = self.header_len() - GtpuHeader::MIN_LEN + payload_len;
+        if value > usize::from(u16::MAX) {
+            return Err(ValueError::GtpuPayloadLengthTooLarge(value));
+        }
+        self.length = value as u16;
+        Ok(())
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized header or an value error if the content of
+    /// an extension header can not be represented in its length field.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        for extension in &self.extension_headers {
+            extension.check_content_len()?;
+        }
+        let mut result = Vec::with_capacity(self.header_len());
+        result.push(
+            (GtpuHeader::VERSION << 5) |
+            GtpuHeader::FLAG_PROTOCOL_TYPE |
+            if self.extension_headers.is_empty() { 0 } else { GtpuHeader::FLAG_EXTENSION_HEADER } |
+            if self.sequence_number.is_some() { GtpuHeader::FLAG_SEQUENCE_NUMBER } else { 0 } |
+            if self.n_pdu_number.is_some() { GtpuHeader::FLAG_N_PDU_NUMBER } else { 0 }
+        );
+        result.push(self.message_type);
+        result.extend_from_slice(&self.length.to_be_bytes());
+        result.extend_from_slice(&self.teid.to_be_bytes());
+        if self.optional_present() {
+            result.extend_from_slice(&self.sequence_number.unwrap_or(0).to_be_bytes());
+            result.push(self.n_pdu_number.unwrap_or(0));
+            // the type of an extension header is stored in the preceding header
+            let mut types = self.extension_headers.iter()
+                .map(|e| e.extension_type)
+                .chain(std::iter::once(gtpu_extension_header_type::NO_MORE_EXTENSION_HEADERS));
+            result.push(types.next().unwrap_or(0));
+            for (extension, next_type) in self.extension_headers.iter().zip(types) {
+                result.push((extension.header_len() / 4) as u8);
+                result.extend_from_slice(&extension.content);
+                result.push(next_type);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Extension header of a GTP-U header.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GtpuExtensionHeader {
+    /// Type of the extension header (see [`gtpu_extension_header_type`]).
+    pub extension_type: u8,
+    /// Content of the extension header (without the length & next extension
+    /// header type fields). The length plus 2 must be a multiple of 4 & at
+    /// most [`GtpuExtensionHeader::MAX_CONTENT_LEN`].
+    pub content: Vec<u8>,
+}
+
+impl GtpuExtensionHeader {
+    /// Maximum length of the content of an extension header.
+    pub const MAX_CONTENT_LEN: usize = 255*4 - 2;
+
+    /// Length of the serialized extension header (including the length &
+    /// next extension header type fields).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        self.content.len() + 2
+    }
+
+    /// Decodes the content as PDU session container if the extension
+    /// header has the type [`gtpu_extension_header_type::PDU_SESSION_CONTAINER`].
+    pub fn pdu_session_container(&self) -> Option<GtpuPduSessionContainer> {
+        if gtpu_extension_header_type::PDU_SESSION_CONTAINER == self.extension_type {
+            GtpuPduSessionContainer::from_content(&self.content)
+        } else {
+            None
+        }
+    }
+
+    fn check_content_len(&self) -> Result<(), ValueError> {
+        if !self.header_len().is_multiple_of(4) || self.content.len() > GtpuExtensionHeader::MAX_CONTENT_LEN {
+            Err(ValueError::GtpuExtensionHeaderLengthBad(self.content.len()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// PDU session container extension header (3GPP TS 38.415) identifying
+/// the QoS flow of a packet in 5G networks.
+///
+/// Only the PDU type & QoS flow identifier are decoded, all other fields
+/// are set to 0 when serializing.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GtpuPduSessionContainer {
+    /// Type of the PDU session information ([`GtpuPduSessionContainer::DOWNLINK`]
+    /// or [`GtpuPduSessionContainer::UPLINK`], 4 bits).
+    pub pdu_type: u8,
+    /// QoS flow identifier (6 bits).
+    pub qos_flow_identifier: u8,
+}
+
+impl GtpuPduSessionContainer {
+    /// PDU type of downlink PDU session information.
+    pub const DOWNLINK: u8 = 0;
+
+    /// PDU type of uplink PDU session information.
+    pub const UPLINK: u8 = 1;
+
+    /// Maximum value of the PDU type.
+    pub const MAX_PDU_TYPE: u8 = 0b1111;
+
+    /// Maximum value of the QoS flow identifier.
+    pub const MAX_QOS_FLOW_IDENTIFIER: u8 = 0b11_1111;
+
+    /// Decodes a PDU session container from the content of an extension
+    /// header (None is returned if the content is too short).
+    pub fn from_content(content: &[u8]) -> Option<GtpuPduSessionContainer> {
+        if content.len() < 2 {
+            None
+        } else {
+            Some(GtpuPduSessionContainer{
+                pdu_type: content[0] >> 4,
+                qos_flow_identifier: content[1] & 0b11_1111,
+            })
+        }
+    }
+
+    /// Returns an extension header containing the PDU session container or
+    /// an value error if a field value is too large.
+    pub fn to_extension_header(&self) -> Result<GtpuExtensionHeader, ValueError> {
+        max_check_u8(self.pdu_type, GtpuPduSessionContainer::MAX_PDU_TYPE, ErrorField::GtpuPduType)?;
+        max_check_u8(self.qos_flow_identifier, GtpuPduSessionContainer::MAX_QOS_FLOW_IDENTIFIER, ErrorField::GtpuQosFlowIdentifier)?;
+        Ok(GtpuExtensionHeader{
+            extension_type: gtpu_extension_header_type::PDU_SESSION_CONTAINER,
+            content: vec![self.pdu_type << 4, self.qos_flow_identifier],
+        })
+    }
+}
+
+/// A slice containing a GTP-U header (including the optional fields &
+/// extension headers).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GtpuHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GtpuHeaderSlice<'a> {
+    /// Creates a slice containing a GTP-U header (the length is determined
+    /// based on the flags & extension headers).
+    ///
+    /// Returns a [`ReadError::GtpuUnsupportedVersion`] error if the version
+    /// field is not 1 & a [`ReadError::GtpuExtensionHeaderLengthZero`] error
+    /// if an extension header has a length of 0.
+    pub fn from_slice(slice: &'a [u8]) -> Result<GtpuHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < GtpuHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GtpuHeader::MIN_LEN));
+        }
+        GtpuHeaderSlice::check_version(slice)?;
+
+        let mut len = GtpuHeader::MIN_LEN;
+        if 0 != slice[0] & GtpuHeader::OPTIONAL_FLAGS {
+            len += 4;
+            if slice.len() < len {
+                return Err(UnexpectedEndOfSlice(len));
+            }
+            if 0 != slice[0] & GtpuHeader::FLAG_EXTENSION_HEADER {
+                let mut next = slice[len - 1];
+                while gtpu_extension_header_type::NO_MORE_EXTENSION_HEADERS != next {
+                    if slice.len() < len + 1 {
+                        return Err(UnexpectedEndOfSlice(len + 1));
+                    }
+                    len += GtpuHeaderSlice::extension_len(slice[len])?;
+                    if slice.len() < len {
+                        return Err(UnexpectedEndOfSlice(len));
+                    }
+                    next = slice[len - 1];
+                }
+            }
+        }
+
+        Ok(GtpuHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Checks the version field (the slice must be at least 1 byte long).
+    fn check_version(slice: &[u8]) -> Result<(), ReadError> {
+        let version = slice[0] >> 5;
+        if GtpuHeader::VERSION != version {
+            Err(ReadError::GtpuUnsupportedVersion(version))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Determines the length of an extension header in bytes based on the
+    /// value of its length field.
+    fn extension_len(length: u8) -> Result<usize, ReadError> {
+        if 0 == length {
+            Err(ReadError::GtpuExtensionHeaderLengthZero)
+        } else {
+            Ok(usize::from(length)*4)
+        }
+    }
+
+    /// Returns the slice containing the GTP-U header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "version" field (always 1 for supported headers).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 5
+    }
+
+    /// Returns true if the "PT" flag is set (GTP instead of GTP').
+    #[inline]
+    pub fn protocol_type(&self) -> bool {
+        0 != self.slice[0] & GtpuHeader::FLAG_PROTOCOL_TYPE
+    }
+
+    /// Returns true if the "E" flag is set (extension headers present).
+    #[inline]
+    pub fn extension_header_present(&self) -> bool {
+        0 != self.slice[0] & GtpuHeader::FLAG_EXTENSION_HEADER
+    }
+
+    /// Returns true if the "S" flag is set (sequence number present).
+    #[inline]
+    pub fn sequence_number_present(&self) -> bool {
+        0 != self.slice[0] & GtpuHeader::FLAG_SEQUENCE_NUMBER
+    }
+
+    /// Returns true if the "PN" flag is set (N-PDU number present).
+    #[inline]
+    pub fn n_pdu_number_present(&self) -> bool {
+        0 != self.slice[0] & GtpuHeader::FLAG_N_PDU_NUMBER
+    }
+
+    /// Read the "message type" field.
+    #[inline]
+    pub fn message_type(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the "length" field.
+    #[inline]
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "tunnel endpoint identifier" field.
+    #[inline]
+    pub fn teid(&self) -> u32 {
+        u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Read the "sequence number" field if present.
+    pub fn sequence_number(&self) -> Option<u16> {
+        if self.sequence_number_present() {
+            Some(u16::from_be_bytes([self.slice[8], self.slice[9]]))
+        } else {
+            None
+        }
+    }
+
+    /// Read the "N-PDU number" field if present.
+    pub fn n_pdu_number(&self) -> Option<u8> {
+        if self.n_pdu_number_present() {
+            Some(self.slice[10])
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the extension headers.
+    pub fn extension_headers(&self) -> GtpuExtensionHeadersIterator<'a> {
+        if self.extension_header_present() {
+            GtpuExtensionHeadersIterator{
+                next_type: self.slice[GtpuHeader::MIN_LEN + 3],
+                rest: &self.slice[GtpuHeader::MIN_LEN + 4..],
+            }
+        } else {
+            GtpuExtensionHeadersIterator{
+                next_type: gtpu_extension_header_type::NO_MORE_EXTENSION_HEADERS,
+                rest: &[],
+            }
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new GtpuHeader.
+    pub fn to_header(&self) -> GtpuHeader {
+        GtpuHeader{
+            message_type: self.message_type(),
+            length: self.length(),
+            teid: self.teid(),
+            sequence_number: self.sequence_number(),
+            n_pdu_number: self.n_pdu_number(),
+            extension_headers: self.extension_headers().map(|e| e.to_header()).collect(),
+        }
+    }
+}
+
+/// A slice containing a single GTP-U extension header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GtpuExtensionHeaderSlice<'a> {
+    extension_type: u8,
+    slice: &'a [u8],
+}
+
+impl<'a> GtpuExtensionHeaderSlice<'a> {
+    /// Returns the slice containing the extension header (starting with
+    /// the length field & ending with the next extension header type).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Type of the extension header (taken from the preceding header).
+    #[inline]
+    pub fn extension_type(&self) -> u8 {
+        self.extension_type
+    }
+
+    /// Returns the content of the extension header.
+    #[inline]
+    pub fn content(&self) -> &'a [u8] {
+        &self.slice[1..self.slice.len() - 1]
+    }
+
+    /// Read the "next extension header type" field.
+    #[inline]
+    pub fn next_extension_type(&self) -> u8 {
+        self.slice[self.slice.len() - 1]
+    }
+
+    /// Decodes the content as PDU session container if the extension
+    /// header has the type [`gtpu_extension_header_type::PDU_SESSION_CONTAINER`].
+    pub fn pdu_session_container(&self) -> Option<GtpuPduSessionContainer> {
+        if gtpu_extension_header_type::PDU_SESSION_CONTAINER == self.extension_type {
+            GtpuPduSessionContainer::from_content(self.content())
+        } else {
+            None
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new GtpuExtensionHeader.
+    pub fn to_header(&self) -> GtpuExtensionHeader {
+        GtpuExtensionHeader{
+            extension_type: self.extension_type,
+            content: self.content().to_vec(),
+        }
+    }
+}
+
+/// Iterator over the extension headers of a GTP-U header (created via
+/// [`GtpuHeaderSlice::extension_headers`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GtpuExtensionHeadersIterator<'a> {
+    next_type: u8,
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for GtpuExtensionHeadersIterator<'a> {
+    type Item = GtpuExtensionHeaderSlice<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if gtpu_extension_header_type::NO_MORE_EXTENSION_HEADERS == self.next_type {
+            return None;
+        }
+        // the extension header lengths were already checked by GtpuHeaderSlice::from_slice
+        let (extension, rest) = self.rest.split_at(usize::from(self.rest[0])*4);
+        let result = GtpuExtensionHeaderSlice{
+            extension_type: self.next_type,
+            slice: extension,
+        };
+        self.next_type = result.next_extension_type();
+        self.rest = rest;
+        Some(result)
+    }
+}
+
+/// Returns the ether type of the packet contained in a GTP-U message
+/// (G-PDU messages containing an IPv4 or IPv6 packet) or None if the
+/// payload is not a known packet.
+pub(crate) fn payload_ether_type(message_type: u8, payload: &[u8]) -> Option<u16> {
+    if gtpu_message_type::G_PDU != message_type {
+        return None;
+    }
+    match payload.first().map(|value| value >> 4) {
+        Some(4) => Some(ether_type::IPV4),
+        Some(6) => Some(ether_type::IPV6),
+        _ => None,
+    }
+}
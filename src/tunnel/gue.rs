@@ -0,0 +1,228 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Generic UDP Encapsulation (GUE) header (variant 0, draft-ietf-intarea-gue).
+///
+/// GUE headers are transported via UDP (destination port [`GueHeader::PORT`]).
+/// Data messages are followed by the packet identified by the IP protocol
+/// number in the "proto/ctype" field, control messages by a control
+/// message of the type given in the same field.
+///
+/// Packets of variant 1 (direct IP encapsulation) contain no GUE header,
+/// the IPv4 or IPv6 packet directly follows the UDP header (see
+/// [`GueHeader::variant`]).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GueHeader {
+    /// Set for control messages ("C" bit).
+    pub control: bool,
+    /// IP protocol number of the payload for data messages or the type of
+    /// the control message for control messages.
+    pub proto_ctype: u8,
+    pub flags: u16,
+    /// Optional extension fields (indicated by the flags) & private data
+    /// (multiple of 4 bytes, at most [`GueHeader::MAX_EXTENSION_FIELDS_LEN`]).
+    pub extension_fields: Vec<u8>,
+}
+
+impl GueHeader {
+    /// UDP destination port assigned to GUE by IANA.
+    pub const PORT: u16 = 6080;
+
+    /// Length of a GUE header without extension fields.
+    pub const MIN_LEN: usize = 4;
+
+    /// Maximum length of the extension fields (the "hlen" field counts 4
+    /// byte words & has 5 bits).
+    pub const MAX_EXTENSION_FIELDS_LEN: usize = 0b1_1111*4;
+
+    /// Flag indicating that the "C" bit is set (in the first byte).
+    pub const CONTROL_FLAG: u8 = 0b0010_0000;
+
+    /// Returns the variant of a GUE packet based on the first two bits of
+    /// the UDP payload (0 for packets with a GUE header, 1 for IPv4 or IPv6
+    /// packets directly encapsulated in UDP). None is returned if the
+    /// payload is empty.
+    #[inline]
+    pub fn variant(udp_payload: &[u8]) -> Option<u8> {
+        udp_payload.first().map(|value| value >> 6)
+    }
+
+    /// Read a GUE header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(GueHeader, &[u8]), ReadError> {
+        let header = GueHeaderSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Reads a GUE header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GueHeader, ReadError> {
+        let mut buffer = [0u8;GueHeader::MIN_LEN + GueHeader::MAX_EXTENSION_FIELDS_LEN];
+        reader.read_exact(&mut buffer[..GueHeader::MIN_LEN])?;
+        let len = GueHeaderSlice::len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[GueHeader::MIN_LEN..len])?;
+        Ok(GueHeaderSlice{
+            slice: &buffer[..len]
+        }.to_header())
+    }
+
+    /// Returns the ether type of the encapsulated packet for data messages
+    /// containing an IPv4 or IPv6 packet (None otherwise).
+    pub fn payload_ether_type(&self) -> Option<u16> {
+        if self.control {
+            None
+        } else {
+            ip_payload_ether_type(self.proto_ctype)
+        }
+    }
+
+    /// Length of the serialized header in bytes (including the extension fields).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        GueHeader::MIN_LEN + self.extension_fields.len()
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized header or an value error in case the
+    /// extension fields can not be represented by the "hlen" field.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        let extension_len = self.extension_fields.len();
+        if 0 != extension_len & 0b11 || extension_len > GueHeader::MAX_EXTENSION_FIELDS_LEN {
+            return Err(ValueError::GueExtensionFieldsLengthBad(extension_len));
+        }
+        let mut result = Vec::with_capacity(self.header_len());
+        result.push(
+            if self.control { GueHeader::CONTROL_FLAG } else { 0 } |
+            (extension_len / 4) as u8
+        );
+        result.push(self.proto_ctype);
+        result.extend_from_slice(&self.flags.to_be_bytes());
+        result.extend_from_slice(&self.extension_fields);
+        Ok(result)
+    }
+}
+
+/// A slice containing a GUE header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GueHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GueHeaderSlice<'a> {
+    /// Creates a slice containing a GUE header (the length is determined
+    /// based on the "hlen" field).
+    ///
+    /// Returns a [`ReadError::GueUnsupportedVariant`] error if the variant
+    /// is not 0 (variant 1 packets contain no GUE header).
+    pub fn from_slice(slice: &'a [u8]) -> Result<GueHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < GueHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GueHeader::MIN_LEN));
+        }
+        let len = GueHeaderSlice::len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(GueHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Checks the variant & determines the header length based on the
+    /// first 4 bytes (the slice must be at least 4 bytes long).
+    fn len_from_start(slice: &[u8]) -> Result<usize, ReadError> {
+        let variant = slice[0] >> 6;
+        if 0 != variant {
+            return Err(ReadError::GueUnsupportedVariant(variant));
+        }
+        Ok(GueHeader::MIN_LEN + usize::from(slice[0] & 0b1_1111)*4)
+    }
+
+    /// Returns the slice containing the GUE header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns true if the "C" bit is set (control message).
+    #[inline]
+    pub fn control(&self) -> bool {
+        0 != self.slice[0] & GueHeader::CONTROL_FLAG
+    }
+
+    /// Read the "hlen" field (length of the extension fields in 4 byte words).
+    #[inline]
+    pub fn hlen(&self) -> u8 {
+        self.slice[0] & 0b1_1111
+    }
+
+    /// Read the "proto/ctype" field (IP protocol number of the payload for
+    /// data messages or the control message type for control messages).
+    #[inline]
+    pub fn proto_ctype(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the "flags" field.
+    #[inline]
+    pub fn flags(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Returns the slice containing the extension fields.
+    #[inline]
+    pub fn extension_fields(&self) -> &'a [u8] {
+        &self.slice[GueHeader::MIN_LEN..]
+    }
+
+    /// Returns the ether type of the encapsulated packet for data messages
+    /// containing an IPv4 or IPv6 packet (see [`GueHeader::payload_ether_type`]).
+    pub fn payload_ether_type(&self) -> Option<u16> {
+        if self.control() {
+            None
+        } else {
+            ip_payload_ether_type(self.proto_ctype())
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new GueHeader.
+    pub fn to_header(&self) -> GueHeader {
+        GueHeader{
+            control: self.control(),
+            proto_ctype: self.proto_ctype(),
+            flags: self.flags(),
+            extension_fields: self.extension_fields().to_vec(),
+        }
+    }
+}
+
+/// Maps the ip number of a GUE payload to an ether type (IPv4 & IPv6 only).
+fn ip_payload_ether_type(protocol: u8) -> Option<u16> {
+    match protocol {
+        ip_number::IPV4 => Some(ether_type::IPV4),
+        ip_number::IPV6 => Some(ether_type::IPV6),
+        _ => None,
+    }
+}
+
+/// Returns the ether type of the IP packet contained in a variant 1 GUE
+/// payload (None if the payload is not a variant 1 payload or contains
+/// neither an IPv4 nor an IPv6 packet).
+pub(crate) fn direct_ip_ether_type(udp_payload: &[u8]) -> Option<u16> {
+    match udp_payload.first().map(|value| value >> 4) {
+        Some(4) => Some(ether_type::IPV4),
+        Some(6) => Some(ether_type::IPV6),
+        _ => None,
+    }
+}
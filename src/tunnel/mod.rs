@@ -0,0 +1,304 @@
+pub mod gre;
+pub mod vxlan;
+pub mod vxlan_gpe;
+pub mod geneve;
+pub mod gtpu;
+pub mod gue;
+pub mod teredo;
+pub mod esp;
+pub mod pppoe;
+pub mod pbb;
+
+use super::*;
+
+use std::io;
+
+/// Tunnel encapsulation headers (headers directly followed by an
+/// encapsulated packet).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TunnelHeader {
+    Gre(gre::GreHeader),
+    Vxlan(vxlan::VxlanHeader),
+    VxlanGpe(vxlan_gpe::VxlanGpeHeader),
+    Geneve(geneve::GeneveHeader),
+    Gtpu(gtpu::GtpuHeader),
+    Gue(gue::GueHeader),
+    Teredo(teredo::TeredoHeader),
+    Esp(esp::EspHeader),
+    Pppoe(pppoe::PppoeHeader),
+    Pbb(pbb::PbbItagHeader),
+}
+
+impl TunnelHeader {
+    /// Returns Some containing the GRE header if self has the value Gre.
+    /// Otherwise None is returned.
+    pub fn gre(self) -> Option<gre::GreHeader> {
+        match self {
+            TunnelHeader::Gre(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the GRE header if
+    /// self has the value Gre. Otherwise None is returned.
+    pub fn mut_gre(&mut self) -> Option<&mut gre::GreHeader> {
+        match self {
+            TunnelHeader::Gre(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the VXLAN header if self has the value Vxlan.
+    /// Otherwise None is returned.
+    pub fn vxlan(self) -> Option<vxlan::VxlanHeader> {
+        match self {
+            TunnelHeader::Vxlan(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the VXLAN header if
+    /// self has the value Vxlan. Otherwise None is returned.
+    pub fn mut_vxlan(&mut self) -> Option<&mut vxlan::VxlanHeader> {
+        match self {
+            TunnelHeader::Vxlan(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the VXLAN-GPE header if self has the value
+    /// VxlanGpe. Otherwise None is returned.
+    pub fn vxlan_gpe(self) -> Option<vxlan_gpe::VxlanGpeHeader> {
+        match self {
+            TunnelHeader::VxlanGpe(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the VXLAN-GPE header
+    /// if self has the value VxlanGpe. Otherwise None is returned.
+    pub fn mut_vxlan_gpe(&mut self) -> Option<&mut vxlan_gpe::VxlanGpeHeader> {
+        match self {
+            TunnelHeader::VxlanGpe(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the Geneve header if self has the value Geneve.
+    /// Otherwise None is returned.
+    pub fn geneve(self) -> Option<geneve::GeneveHeader> {
+        match self {
+            TunnelHeader::Geneve(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the Geneve header if
+    /// self has the value Geneve. Otherwise None is returned.
+    pub fn mut_geneve(&mut self) -> Option<&mut geneve::GeneveHeader> {
+        match self {
+            TunnelHeader::Geneve(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the GTP-U header if self has the value Gtpu.
+    /// Otherwise None is returned.
+    pub fn gtpu(self) -> Option<gtpu::GtpuHeader> {
+        match self {
+            TunnelHeader::Gtpu(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the GTP-U header if
+    /// self has the value Gtpu. Otherwise None is returned.
+    pub fn mut_gtpu(&mut self) -> Option<&mut gtpu::GtpuHeader> {
+        match self {
+            TunnelHeader::Gtpu(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the GUE header if self has the value Gue.
+    /// Otherwise None is returned.
+    pub fn gue(self) -> Option<gue::GueHeader> {
+        match self {
+            TunnelHeader::Gue(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the GUE header if
+    /// self has the value Gue. Otherwise None is returned.
+    pub fn mut_gue(&mut self) -> Option<&mut gue::GueHeader> {
+        match self {
+            TunnelHeader::Gue(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the Teredo headers if self has the value
+    /// Teredo. Otherwise None is returned.
+    pub fn teredo(self) -> Option<teredo::TeredoHeader> {
+        match self {
+            TunnelHeader::Teredo(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the Teredo headers
+    /// if self has the value Teredo. Otherwise None is returned.
+    pub fn mut_teredo(&mut self) -> Option<&mut teredo::TeredoHeader> {
+        match self {
+            TunnelHeader::Teredo(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the ESP header if self has the value Esp.
+    /// Otherwise None is returned.
+    pub fn esp(self) -> Option<esp::EspHeader> {
+        match self {
+            TunnelHeader::Esp(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the ESP header if
+    /// self has the value Esp. Otherwise None is returned.
+    pub fn mut_esp(&mut self) -> Option<&mut esp::EspHeader> {
+        match self {
+            TunnelHeader::Esp(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the PPPoE header if self has the value Pppoe.
+    /// Otherwise None is returned.
+    pub fn pppoe(self) -> Option<pppoe::PppoeHeader> {
+        match self {
+            TunnelHeader::Pppoe(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the PPPoE header if
+    /// self has the value Pppoe. Otherwise None is returned.
+    pub fn mut_pppoe(&mut self) -> Option<&mut pppoe::PppoeHeader> {
+        match self {
+            TunnelHeader::Pppoe(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing the PBB I-TAG if self has the value Pbb.
+    /// Otherwise None is returned.
+    pub fn pbb(self) -> Option<pbb::PbbItagHeader> {
+        match self {
+            TunnelHeader::Pbb(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns Some containing a mutable reference to the PBB I-TAG if
+    /// self has the value Pbb. Otherwise None is returned.
+    pub fn mut_pbb(&mut self) -> Option<&mut pbb::PbbItagHeader> {
+        match self {
+            TunnelHeader::Pbb(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of the tunnel header (in case of GRE, GTP-U &
+    /// PPPoE including the optional fields, in case of Geneve including
+    /// the options, in case of GUE including the extension fields, in case
+    /// of Teredo the length of the optional authentication header & origin
+    /// indication & in case of PBB including the customer addresses).
+    pub fn header_len(&self) -> usize {
+        match self {
+            TunnelHeader::Gre(value) => value.header_len(),
+            TunnelHeader::Vxlan(value) => value.header_len(),
+            TunnelHeader::VxlanGpe(value) => value.header_len(),
+            TunnelHeader::Geneve(value) => value.header_len(),
+            TunnelHeader::Gtpu(value) => value.header_len(),
+            TunnelHeader::Gue(value) => value.header_len(),
+            TunnelHeader::Teredo(value) => value.header_len(),
+            TunnelHeader::Esp(value) => value.header_len(),
+            TunnelHeader::Pppoe(value) => value.header_len(),
+            TunnelHeader::Pbb(value) => value.header_len(),
+        }
+    }
+
+    /// Writes the tunnel header to the given writer.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        match self {
+            TunnelHeader::Gre(value) => value.write(writer),
+            TunnelHeader::Vxlan(value) => value.write(writer),
+            TunnelHeader::VxlanGpe(value) => value.write(writer),
+            TunnelHeader::Geneve(value) => value.write(writer),
+            TunnelHeader::Gtpu(value) => value.write(writer),
+            TunnelHeader::Gue(value) => value.write(writer),
+            TunnelHeader::Teredo(value) => value.write(writer),
+            TunnelHeader::Esp(value) => value.write(writer),
+            TunnelHeader::Pppoe(value) => value.write(writer),
+            TunnelHeader::Pbb(value) => value.write(writer),
+        }
+    }
+}
+
+/// Slice containing a tunnel encapsulation header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TunnelSlice<'a> {
+    /// A slice containing a GRE header.
+    Gre(gre::GreHeaderSlice<'a>),
+    /// A slice containing a VXLAN header.
+    Vxlan(vxlan::VxlanHeaderSlice<'a>),
+    /// A slice containing a VXLAN-GPE header.
+    VxlanGpe(vxlan_gpe::VxlanGpeHeaderSlice<'a>),
+    /// A slice containing a Geneve header.
+    Geneve(geneve::GeneveHeaderSlice<'a>),
+    /// A slice containing a GTP-U header.
+    Gtpu(gtpu::GtpuHeaderSlice<'a>),
+    /// A slice containing a GUE header.
+    Gue(gue::GueHeaderSlice<'a>),
+    /// A slice containing the optional Teredo headers.
+    Teredo(teredo::TeredoHeaderSlice<'a>),
+    /// A slice containing an ESP header.
+    Esp(esp::EspHeaderSlice<'a>),
+    /// A slice containing a PPPoE header.
+    Pppoe(pppoe::PppoeHeaderSlice<'a>),
+    /// A slice containing a PBB I-TAG.
+    Pbb(pbb::PbbItagHeaderSlice<'a>),
+}
+
+impl<'a> TunnelSlice<'a> {
+    /// Returns the slice containing the tunnel header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        match self {
+            TunnelSlice::Gre(header) => header.slice(),
+            TunnelSlice::Vxlan(header) => header.slice(),
+            TunnelSlice::VxlanGpe(header) => header.slice(),
+            TunnelSlice::Geneve(header) => header.slice(),
+            TunnelSlice::Gtpu(header) => header.slice(),
+            TunnelSlice::Gue(header) => header.slice(),
+            TunnelSlice::Teredo(header) => header.slice(),
+            TunnelSlice::Esp(header) => header.slice(),
+            TunnelSlice::Pppoe(header) => header.slice(),
+            TunnelSlice::Pbb(header) => header.slice(),
+        }
+    }
+}
+
+/// Returns true if the encapsulated packet identified by the given ether
+/// type can be decoded (used to decide if the decoders descend into the
+/// payload of a tunnel).
+pub(crate) fn is_decodable_ether_type(value: u16, limits: &ParseLimits) -> bool {
+    use ether_type::*;
+    limits.is_vlan_tpid(value) || matches!(
+        value,
+        IPV4 | IPV6 | ARP | TRANSPARENT_ETHERNET_BRIDGING |
+        PPPOE_DISCOVERY | PPPOE_SESSION | PROVIDER_BACKBONE_BRIDGING
+    )
+}
@@ -0,0 +1,215 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// IEEE 802.1ah provider backbone bridging (MAC-in-MAC) I-TAG (ether type
+/// [`ether_type::PROVIDER_BACKBONE_BRIDGING`]).
+///
+/// The I-TAG contains the backbone service instance identifier (I-SID)
+/// & the MAC addresses of the encapsulated customer frame. It is followed
+/// by the ether type of the customer frame, which is decoded as part of
+/// the header (similar to the vlan headers).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PbbItagHeader, ether_type};
+///
+/// let header = PbbItagHeader{
+///     i_sid: 0x12_3456,
+///     customer_destination: [1, 2, 3, 4, 5, 6],
+///     customer_source: [7, 8, 9, 10, 11, 12],
+///     ether_type: ether_type::IPV4,
+///     ..Default::default()
+/// };
+/// let bytes = header.to_bytes().unwrap();
+/// assert_eq!(&[0x00, 0x12, 0x34, 0x56], &bytes[..4]);
+/// assert_eq!(header, PbbItagHeader::from_slice(&bytes).unwrap().0);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct PbbItagHeader {
+    /// Backbone service instance priority code point (3 bits).
+    pub priority_code_point: u8,
+    /// Backbone service instance drop eligible indicator.
+    pub drop_eligible_indicator: bool,
+    /// Use customer addresses ("UCA" flag, indicates that the customer
+    /// addresses were used for the backbone encapsulation).
+    pub use_customer_address: bool,
+    /// Backbone service instance identifier (24 bits).
+    pub i_sid: u32,
+    /// Destination MAC address of the encapsulated customer frame.
+    pub customer_destination: [u8;6],
+    /// Source MAC address of the encapsulated customer frame.
+    pub customer_source: [u8;6],
+    /// Ether type of the encapsulated customer frame.
+    pub ether_type: u16,
+}
+
+impl SerializedSize for PbbItagHeader {
+    /// Serialized size of the header in bytes (including the customer
+    /// MAC addresses & ether type).
+    const SERIALIZED_SIZE: usize = 18;
+}
+
+impl PbbItagHeader {
+    /// Maximum value of the I-SID field.
+    pub const MAX_I_SID: u32 = 0xff_ffff;
+
+    /// Read an I-TAG from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(PbbItagHeader, &[u8]), ReadError> {
+        Ok((
+            PbbItagHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[PbbItagHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read an I-TAG from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;18]) -> PbbItagHeader {
+        PbbItagHeaderSlice{
+            slice: &bytes
+        }.to_header()
+    }
+
+    /// Reads an I-TAG from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<PbbItagHeader, io::Error> {
+        let mut buffer = [0;PbbItagHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(PbbItagHeader::from_bytes(buffer))
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant PbbItagHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        PbbItagHeader::SERIALIZED_SIZE
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized form of the header or an value error in case
+    /// the priority code point or I-SID are outside of their range.
+    pub fn to_bytes(&self) -> Result<[u8;18], ValueError> {
+        use crate::ErrorField::*;
+        max_check_u8(self.priority_code_point, 0x7, PbbItagPriorityCodePoint)?;
+        max_check_u32(self.i_sid, PbbItagHeader::MAX_I_SID, PbbItagIsid)?;
+
+        let i_sid_be = self.i_sid.to_be_bytes();
+        let ether_type_be = self.ether_type.to_be_bytes();
+        let d = &self.customer_destination;
+        let s = &self.customer_source;
+        Ok([
+            (self.priority_code_point << 5) |
+                if self.drop_eligible_indicator { 0x10 } else { 0 } |
+                if self.use_customer_address { 0x08 } else { 0 },
+            i_sid_be[1], i_sid_be[2], i_sid_be[3],
+            d[0], d[1], d[2], d[3], d[4], d[5],
+            s[0], s[1], s[2], s[3], s[4], s[5],
+            ether_type_be[0], ether_type_be[1],
+        ])
+    }
+}
+
+/// A slice containing an IEEE 802.1ah I-TAG.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PbbItagHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> PbbItagHeaderSlice<'a> {
+    /// Creates an I-TAG slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<PbbItagHeaderSlice<'a>, ReadError> {
+        if slice.len() < PbbItagHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(PbbItagHeader::SERIALIZED_SIZE));
+        }
+        Ok(PbbItagHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // PbbItagHeader::SERIALIZED_SIZE (18) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    PbbItagHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the I-TAG.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the priority code point (3 bits).
+    #[inline]
+    pub fn priority_code_point(&self) -> u8 {
+        self.slice[0] >> 5
+    }
+
+    /// Read the drop eligible indicator.
+    #[inline]
+    pub fn drop_eligible_indicator(&self) -> bool {
+        0 != self.slice[0] & 0x10
+    }
+
+    /// Read the "use customer address" flag.
+    #[inline]
+    pub fn use_customer_address(&self) -> bool {
+        0 != self.slice[0] & 0x08
+    }
+
+    /// Read the backbone service instance identifier (24 bits).
+    #[inline]
+    pub fn i_sid(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[1], self.slice[2], self.slice[3]])
+    }
+
+    /// Read the destination MAC address of the customer frame.
+    #[inline]
+    pub fn customer_destination(&self) -> [u8;6] {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of PbbItagHeader::SERIALIZED_SIZE (18).
+        unsafe {
+            get_unchecked_6_byte_array(self.slice.as_ptr().add(4))
+        }
+    }
+
+    /// Read the source MAC address of the customer frame.
+    #[inline]
+    pub fn customer_source(&self) -> [u8;6] {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of PbbItagHeader::SERIALIZED_SIZE (18).
+        unsafe {
+            get_unchecked_6_byte_array(self.slice.as_ptr().add(10))
+        }
+    }
+
+    /// Read the ether type of the customer frame.
+    #[inline]
+    pub fn ether_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[16], self.slice[17]])
+    }
+
+    /// Decode all the fields and copy the results to a PbbItagHeader struct.
+    pub fn to_header(&self) -> PbbItagHeader {
+        PbbItagHeader{
+            priority_code_point: self.priority_code_point(),
+            drop_eligible_indicator: self.drop_eligible_indicator(),
+            use_customer_address: self.use_customer_address(),
+            i_sid: self.i_sid(),
+            customer_destination: self.customer_destination(),
+            customer_source: self.customer_source(),
+            ether_type: self.ether_type(),
+        }
+    }
+}
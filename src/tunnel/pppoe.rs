@@ -0,0 +1,368 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Code values of PPPoE headers (RFC 2516).
+pub mod pppoe_code {
+    /// Session stage packet containing a PPP frame.
+    pub const SESSION_DATA: u8 = 0x00;
+    /// PPPoE Active Discovery Offer.
+    pub const PADO: u8 = 0x07;
+    /// PPPoE Active Discovery Initiation.
+    pub const PADI: u8 = 0x09;
+    /// PPPoE Active Discovery Request.
+    pub const PADR: u8 = 0x19;
+    /// PPPoE Active Discovery Session-confirmation.
+    pub const PADS: u8 = 0x65;
+    /// PPPoE Active Discovery Terminate.
+    pub const PADT: u8 = 0xa7;
+}
+
+/// Tag types of PPPoE discovery packets (RFC 2516 appendix A).
+pub mod pppoe_tag_type {
+    pub const END_OF_LIST: u16 = 0x0000;
+    pub const SERVICE_NAME: u16 = 0x0101;
+    pub const AC_NAME: u16 = 0x0102;
+    pub const HOST_UNIQ: u16 = 0x0103;
+    pub const AC_COOKIE: u16 = 0x0104;
+    pub const VENDOR_SPECIFIC: u16 = 0x0105;
+    pub const RELAY_SESSION_ID: u16 = 0x0110;
+    pub const SERVICE_NAME_ERROR: u16 = 0x0201;
+    pub const AC_SYSTEM_ERROR: u16 = 0x0202;
+    pub const GENERIC_ERROR: u16 = 0x0203;
+}
+
+/// Values of the PPP protocol field (identifying the content of a PPP frame).
+pub mod ppp_protocol {
+    pub const IPV4: u16 = 0x0021;
+    pub const IPV6: u16 = 0x0057;
+    /// IP control protocol.
+    pub const IPCP: u16 = 0x8021;
+    /// IPv6 control protocol.
+    pub const IPV6CP: u16 = 0x8057;
+    /// Link control protocol.
+    pub const LCP: u16 = 0xc021;
+    /// Password authentication protocol.
+    pub const PAP: u16 = 0xc023;
+    /// Challenge handshake authentication protocol.
+    pub const CHAP: u16 = 0xc223;
+}
+
+/// PPPoE header according to RFC 2516 (ether types
+/// [`ether_type::PPPOE_DISCOVERY`] & [`ether_type::PPPOE_SESSION`]).
+///
+/// In session packets ([`pppoe_code::SESSION_DATA`]) the header is followed
+/// by the PPP protocol field, which is decoded as part of the header. The
+/// payload of discovery packets consists of tags that can be decoded via
+/// [`PppoeTagsIterator`].
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct PppoeHeader {
+    /// Version of the header (4 bits, has to be 1).
+    pub version: u8,
+    /// Type of the header (4 bits, has to be 1).
+    pub pppoe_type: u8,
+    /// Code identifying the packet (see [`pppoe_code`]).
+    pub code: u8,
+    /// Session identifier (0 during the discovery stage before a session
+    /// is established).
+    pub session_id: u16,
+    /// Length of the PPPoE payload (including the PPP protocol field).
+    pub length: u16,
+    /// PPP protocol field (present in session packets, see [`ppp_protocol`]).
+    pub ppp_protocol: Option<u16>,
+}
+
+impl PppoeHeader {
+    /// Length of the PPPoE header without the PPP protocol field.
+    pub const MIN_LEN: usize = 6;
+
+    /// Length of the PPPoE header including the PPP protocol field.
+    pub const MAX_LEN: usize = 8;
+
+    /// Maximum value of the version & type fields.
+    pub const MAX_VERSION: u8 = 0b1111;
+
+    /// Creates a session packet header with the given session id & PPP
+    /// protocol (version & type are set to 1).
+    pub fn new_session(session_id: u16, ppp_protocol: u16) -> PppoeHeader {
+        PppoeHeader{
+            version: 1,
+            pppoe_type: 1,
+            code: pppoe_code::SESSION_DATA,
+            session_id,
+            length: 2,
+            ppp_protocol: Some(ppp_protocol),
+        }
+    }
+
+    /// Creates a discovery packet header with the given code & session id
+    /// (version & type are set to 1).
+    pub fn new_discovery(code: u8, session_id: u16) -> PppoeHeader {
+        PppoeHeader{
+            version: 1,
+            pppoe_type: 1,
+            code,
+            session_id,
+            length: 0,
+            ppp_protocol: None,
+        }
+    }
+
+    /// Reads a PPPoE header from a slice & returns the header & the rest
+    /// of the slice (the PPP payload or the discovery tags).
+    pub fn from_slice(slice: &[u8]) -> Result<(PppoeHeader, &[u8]), ReadError> {
+        let header = PppoeHeaderSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Reads a PPPoE header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<PppoeHeader, io::Error> {
+        let mut buffer = [0u8;PppoeHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..PppoeHeader::MIN_LEN])?;
+        let len = if pppoe_code::SESSION_DATA == buffer[1] {
+            reader.read_exact(&mut buffer[PppoeHeader::MIN_LEN..])?;
+            PppoeHeader::MAX_LEN
+        } else {
+            PppoeHeader::MIN_LEN
+        };
+        Ok(PppoeHeaderSlice{
+            slice: &buffer[..len]
+        }.to_header())
+    }
+
+    /// Length of the serialized header in bytes (including the PPP
+    /// protocol field if present).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        if self.ppp_protocol.is_some() {
+            PppoeHeader::MAX_LEN
+        } else {
+            PppoeHeader::MIN_LEN
+        }
+    }
+
+    /// Sets the length field based on the given payload length (the length
+    /// of the PPP protocol field is added if present).
+    pub fn set_payload_len(&mut self, payload_len: usize) -> Result<(), ValueError> {
+        let value = self.header_len() - PppoeHeader::MIN_LEN + payload_len;
+        if value > usize::from(u16::MAX) {
+            return Err(ValueError::PppoePayloadLengthTooLarge(value));
+        }
+        self.length = value as u16;
+        Ok(())
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized header or an value error if the version or
+    /// type are bigger then [`PppoeHeader::MAX_VERSION`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        max_check_u8(self.version, PppoeHeader::MAX_VERSION, ErrorField::PppoeVersion)?;
+        max_check_u8(self.pppoe_type, PppoeHeader::MAX_VERSION, ErrorField::PppoeType)?;
+        let mut result = Vec::with_capacity(self.header_len());
+        result.push((self.version << 4) | self.pppoe_type);
+        result.push(self.code);
+        result.extend_from_slice(&self.session_id.to_be_bytes());
+        result.extend_from_slice(&self.length.to_be_bytes());
+        if let Some(value) = self.ppp_protocol {
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+        Ok(result)
+    }
+}
+
+/// A slice containing a PPPoE header (including the PPP protocol field
+/// in case of session packets).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PppoeHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> PppoeHeaderSlice<'a> {
+    /// Creates a slice containing a PPPoE header (the PPP protocol field is
+    /// included if the code is [`pppoe_code::SESSION_DATA`]).
+    pub fn from_slice(slice: &'a [u8]) -> Result<PppoeHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < PppoeHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(PppoeHeader::MIN_LEN));
+        }
+        let len = if pppoe_code::SESSION_DATA == slice[1] {
+            PppoeHeader::MAX_LEN
+        } else {
+            PppoeHeader::MIN_LEN
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(PppoeHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the PPPoE header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "version" field.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Read the "type" field.
+    #[inline]
+    pub fn pppoe_type(&self) -> u8 {
+        self.slice[0] & 0b1111
+    }
+
+    /// Read the "code" field.
+    #[inline]
+    pub fn code(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the "session id" field.
+    #[inline]
+    pub fn session_id(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "length" field.
+    #[inline]
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[4], self.slice[5]])
+    }
+
+    /// Read the PPP protocol field (present in session packets).
+    pub fn ppp_protocol(&self) -> Option<u16> {
+        if self.slice.len() == PppoeHeader::MAX_LEN {
+            Some(u16::from_be_bytes([self.slice[6], self.slice[7]]))
+        } else {
+            None
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new PppoeHeader.
+    pub fn to_header(&self) -> PppoeHeader {
+        PppoeHeader{
+            version: self.version(),
+            pppoe_type: self.pppoe_type(),
+            code: self.code(),
+            session_id: self.session_id(),
+            length: self.length(),
+            ppp_protocol: self.ppp_protocol(),
+        }
+    }
+}
+
+/// A slice containing a tag of a PPPoE discovery packet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PppoeTagSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> PppoeTagSlice<'a> {
+    /// Returns the slice containing the tag (including the type & length fields).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "tag type" field (see [`pppoe_tag_type`]).
+    #[inline]
+    pub fn tag_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the "tag length" field.
+    #[inline]
+    pub fn tag_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Returns the value of the tag.
+    #[inline]
+    pub fn value(&self) -> &'a [u8] {
+        &self.slice[4..]
+    }
+}
+
+/// Iterator over the tags in the payload of a PPPoE discovery packet.
+///
+/// The iteration ends at the end of the slice or at an
+/// [`pppoe_tag_type::END_OF_LIST`] tag (which is not returned). Note that
+/// the payload should be limited to the PPPoE `length` to avoid decoding
+/// ethernet padding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PppoeTagsIterator<'a> {
+    offset: usize,
+    rest: &'a [u8],
+}
+
+impl<'a> PppoeTagsIterator<'a> {
+    /// Creates an iterator over the tags in the given slice.
+    pub fn from_slice(tags: &'a [u8]) -> PppoeTagsIterator<'a> {
+        PppoeTagsIterator{
+            offset: 0,
+            rest: tags,
+        }
+    }
+
+    /// Returns the slice containing the tags that were not yet read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for PppoeTagsIterator<'a> {
+    type Item = Result<PppoeTagSlice<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        if self.rest.len() < 4 {
+            let err = ReadError::UnexpectedEndOfSlice(self.offset + 4);
+            self.rest = &[];
+            return Some(Err(err));
+        }
+        let len = 4 + usize::from(u16::from_be_bytes([self.rest[2], self.rest[3]]));
+        if self.rest.len() < len {
+            let err = ReadError::UnexpectedEndOfSlice(self.offset + len);
+            self.rest = &[];
+            return Some(Err(err));
+        }
+        let (tag, rest) = self.rest.split_at(len);
+        let result = PppoeTagSlice{ slice: tag };
+        if pppoe_tag_type::END_OF_LIST == result.tag_type() {
+            self.rest = &[];
+            return None;
+        }
+        self.offset += len;
+        self.rest = rest;
+        Some(Ok(result))
+    }
+}
+
+/// Returns the ether type corresponding to a PPP protocol (if the packet
+/// can be decoded).
+pub(crate) fn ppp_protocol_ether_type(protocol: u16) -> Option<u16> {
+    match protocol {
+        ppp_protocol::IPV4 => Some(ether_type::IPV4),
+        ppp_protocol::IPV6 => Some(ether_type::IPV6),
+        _ => None,
+    }
+}
@@ -0,0 +1,292 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Authentication header of Teredo packets (RFC 4380 section 5.1.1).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct TeredoAuthentication {
+    /// Client identifier (at most 255 bytes).
+    pub client_id: Vec<u8>,
+    /// Authentication value (at most 255 bytes).
+    pub authentication_value: Vec<u8>,
+    pub nonce: [u8;8],
+    pub confirmation: u8,
+}
+
+impl TeredoAuthentication {
+    /// Length of an authentication header without client identifier &
+    /// authentication value.
+    pub const MIN_LEN: usize = 13;
+
+    /// Indicator identifying an authentication header.
+    pub const INDICATOR: u16 = 0x0001;
+
+    /// Length of the serialized authentication header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        TeredoAuthentication::MIN_LEN + self.client_id.len() + self.authentication_value.len()
+    }
+
+    /// Returns the serialized authentication header or an value error in
+    /// case the client identifier or authentication value are longer then
+    /// 255 bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        for len in &[self.client_id.len(), self.authentication_value.len()] {
+            if *len > usize::from(u8::MAX) {
+                return Err(ValueError::TeredoAuthenticationLengthTooLarge(*len));
+            }
+        }
+        let mut result = Vec::with_capacity(self.header_len());
+        result.extend_from_slice(&TeredoAuthentication::INDICATOR.to_be_bytes());
+        result.push(self.client_id.len() as u8);
+        result.push(self.authentication_value.len() as u8);
+        result.extend_from_slice(&self.client_id);
+        result.extend_from_slice(&self.authentication_value);
+        result.extend_from_slice(&self.nonce);
+        result.push(self.confirmation);
+        Ok(result)
+    }
+}
+
+/// A slice containing a Teredo authentication header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TeredoAuthenticationSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> TeredoAuthenticationSlice<'a> {
+    /// Creates a slice containing a Teredo authentication header (the
+    /// indicator is not checked).
+    pub fn from_slice(slice: &'a [u8]) -> Result<TeredoAuthenticationSlice<'a>, ReadError> {
+        if slice.len() < TeredoAuthentication::MIN_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(TeredoAuthentication::MIN_LEN));
+        }
+        let len = TeredoAuthentication::MIN_LEN + usize::from(slice[2]) + usize::from(slice[3]);
+        if slice.len() < len {
+            return Err(ReadError::UnexpectedEndOfSlice(len));
+        }
+        Ok(TeredoAuthenticationSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the authentication header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the slice containing the client identifier.
+    #[inline]
+    pub fn client_id(&self) -> &'a [u8] {
+        &self.slice[4..4 + usize::from(self.slice[2])]
+    }
+
+    /// Returns the slice containing the authentication value.
+    #[inline]
+    pub fn authentication_value(&self) -> &'a [u8] {
+        let start = 4 + usize::from(self.slice[2]);
+        &self.slice[start..start + usize::from(self.slice[3])]
+    }
+
+    /// Read the "nonce" field.
+    #[inline]
+    pub fn nonce(&self) -> [u8;8] {
+        let start = self.slice.len() - 9;
+        let mut result = [0;8];
+        result.copy_from_slice(&self.slice[start..start + 8]);
+        result
+    }
+
+    /// Read the "confirmation" field.
+    #[inline]
+    pub fn confirmation(&self) -> u8 {
+        self.slice[self.slice.len() - 1]
+    }
+
+    /// Decode all the fields & copy the data into a new TeredoAuthentication.
+    pub fn to_header(&self) -> TeredoAuthentication {
+        TeredoAuthentication{
+            client_id: self.client_id().to_vec(),
+            authentication_value: self.authentication_value().to_vec(),
+            nonce: self.nonce(),
+            confirmation: self.confirmation(),
+        }
+    }
+}
+
+/// Origin indication of Teredo packets (RFC 4380 section 5.1.1) containing
+/// the mapped address & port of a client (the obfuscation is removed when
+/// decoding & applied when serializing).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub struct TeredoOriginIndication {
+    pub port: u16,
+    pub address: [u8;4],
+}
+
+impl SerializedSize for TeredoOriginIndication {
+    /// Serialized size of the origin indication in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl TeredoOriginIndication {
+    /// Indicator identifying an origin indication.
+    pub const INDICATOR: u16 = 0x0000;
+
+    /// Read an origin indication from a static sized byte array (the
+    /// indicator is not checked).
+    pub fn from_bytes(bytes: [u8;8]) -> TeredoOriginIndication {
+        TeredoOriginIndication{
+            port: !u16::from_be_bytes([bytes[2], bytes[3]]),
+            address: [!bytes[4], !bytes[5], !bytes[6], !bytes[7]],
+        }
+    }
+
+    /// Returns the serialized form of the origin indication.
+    pub fn to_bytes(&self) -> [u8;8] {
+        let port = (!self.port).to_be_bytes();
+        [
+            0, 0, port[0], port[1],
+            !self.address[0], !self.address[1], !self.address[2], !self.address[3],
+        ]
+    }
+}
+
+/// Optional Teredo headers preceding the IPv6 packet in the UDP payload
+/// of Teredo packets (RFC 4380).
+///
+/// Teredo packets are identified via the UDP source or destination port
+/// [`TeredoHeader::PORT`] & an IPv6 packet following the optional
+/// authentication header & origin indication (packets without both are
+/// represented by a header with both fields set to None).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct TeredoHeader {
+    pub authentication: Option<TeredoAuthentication>,
+    pub origin_indication: Option<TeredoOriginIndication>,
+}
+
+impl TeredoHeader {
+    /// UDP port of Teredo servers assigned by IANA.
+    pub const PORT: u16 = 3544;
+
+    /// Read the Teredo headers from a slice and return the header & unused
+    /// parts of the slice (starting with the IPv6 packet).
+    pub fn from_slice(slice: &[u8]) -> Result<(TeredoHeader, &[u8]), ReadError> {
+        let header = TeredoHeaderSlice::from_slice(slice)?;
+        Ok((header.to_header(), &slice[header.slice().len()..]))
+    }
+
+    /// Length of the serialized headers in bytes.
+    pub fn header_len(&self) -> usize {
+        self.authentication.as_ref().map(|value| value.header_len()).unwrap_or(0) +
+        self.origin_indication.map(|_| TeredoOriginIndication::SERIALIZED_SIZE).unwrap_or(0)
+    }
+
+    /// Writes the headers to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized headers (authentication header first) or an
+    /// value error in case the authentication header can not be serialized.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ValueError> {
+        let mut result = Vec::with_capacity(self.header_len());
+        if let Some(authentication) = &self.authentication {
+            result.extend_from_slice(&authentication.to_bytes()?);
+        }
+        if let Some(origin_indication) = &self.origin_indication {
+            result.extend_from_slice(&origin_indication.to_bytes());
+        }
+        Ok(result)
+    }
+}
+
+/// A slice containing the optional Teredo headers (authentication header
+/// & origin indication).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TeredoHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> TeredoHeaderSlice<'a> {
+    /// Creates a slice containing the authentication header & origin
+    /// indication at the start of the given slice (the resulting slice is
+    /// empty if neither is present).
+    pub fn from_slice(slice: &'a [u8]) -> Result<TeredoHeaderSlice<'a>, ReadError> {
+        let mut len = 0;
+        if TeredoHeaderSlice::has_indicator(slice, TeredoAuthentication::INDICATOR) {
+            len += TeredoAuthenticationSlice::from_slice(slice)?.slice().len();
+        }
+        if TeredoHeaderSlice::has_indicator(&slice[len..], TeredoOriginIndication::INDICATOR) {
+            if slice.len() < len + TeredoOriginIndication::SERIALIZED_SIZE {
+                return Err(ReadError::UnexpectedEndOfSlice(len + TeredoOriginIndication::SERIALIZED_SIZE));
+            }
+            len += TeredoOriginIndication::SERIALIZED_SIZE;
+        }
+        Ok(TeredoHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Returns true if the slice starts with the given 2 byte indicator.
+    fn has_indicator(slice: &[u8], indicator: u16) -> bool {
+        slice.len() >= 2 && indicator == u16::from_be_bytes([slice[0], slice[1]])
+    }
+
+    /// Returns the slice containing the Teredo headers.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the authentication header if present.
+    pub fn authentication(&self) -> Option<TeredoAuthenticationSlice<'a>> {
+        if TeredoHeaderSlice::has_indicator(self.slice, TeredoAuthentication::INDICATOR) {
+            // length already checked in from_slice
+            TeredoAuthenticationSlice::from_slice(self.slice).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the origin indication if present.
+    pub fn origin_indication(&self) -> Option<TeredoOriginIndication> {
+        let start = self.authentication().map(|value| value.slice().len()).unwrap_or(0);
+        if start < self.slice.len() {
+            let mut bytes = [0;8];
+            bytes.copy_from_slice(&self.slice[start..start + TeredoOriginIndication::SERIALIZED_SIZE]);
+            Some(TeredoOriginIndication::from_bytes(bytes))
+        } else {
+            None
+        }
+    }
+
+    /// Decode all the fields & copy the data into a new TeredoHeader.
+    pub fn to_header(&self) -> TeredoHeader {
+        TeredoHeader{
+            authentication: self.authentication().map(|value| value.to_header()),
+            origin_indication: self.origin_indication(),
+        }
+    }
+}
+
+/// Returns true if the given slice starts with an IPv6 packet (used to
+/// recognize Teredo payloads after the optional headers).
+pub(crate) fn is_ipv6(slice: &[u8]) -> bool {
+    slice.first().map(|value| 6 == value >> 4).unwrap_or(false)
+}
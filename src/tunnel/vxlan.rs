@@ -0,0 +1,154 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// VXLAN header according to RFC 7348.
+///
+/// VXLAN headers are transported via UDP (destination port
+/// [`VxlanHeader::PORT`]) & are always followed by an ethernet frame.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct VxlanHeader {
+    /// Flags (the "I" flag [`VxlanHeader::FLAG_VNI_VALID`] has to be set
+    /// for a valid VNI, all other bits are reserved).
+    pub flags: u8,
+    /// VXLAN network identifier (24 bits).
+    pub vni: u32,
+}
+
+impl SerializedSize for VxlanHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl VxlanHeader {
+    /// UDP destination port assigned to VXLAN by IANA.
+    pub const PORT: u16 = 4789;
+
+    /// Flag indicating that the VNI is valid ("I" flag).
+    pub const FLAG_VNI_VALID: u8 = 0b0000_1000;
+
+    /// Maximum value of the VNI field.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    /// Creates a VXLAN header with the given VNI & the "I" flag set.
+    pub fn new(vni: u32) -> VxlanHeader {
+        VxlanHeader{
+            flags: VxlanHeader::FLAG_VNI_VALID,
+            vni,
+        }
+    }
+
+    /// Read a VXLAN header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(VxlanHeader, &[u8]), ReadError> {
+        Ok((
+            VxlanHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[VxlanHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a VXLAN header from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;8]) -> VxlanHeader {
+        VxlanHeaderSlice{
+            slice: &bytes
+        }.to_header()
+    }
+
+    /// Reads a VXLAN header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<VxlanHeader, io::Error> {
+        let mut buffer = [0;VxlanHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(VxlanHeader::from_bytes(buffer))
+    }
+
+    /// Returns true if the "I" flag is set (the VNI is valid).
+    #[inline]
+    pub fn vni_valid(&self) -> bool {
+        0 != self.flags & VxlanHeader::FLAG_VNI_VALID
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant VxlanHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        VxlanHeader::SERIALIZED_SIZE
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized form of the header or an value error in case
+    /// the VNI is bigger then [`VxlanHeader::MAX_VNI`].
+    pub fn to_bytes(&self) -> Result<[u8;8], ValueError> {
+        max_check_u32(self.vni, VxlanHeader::MAX_VNI, ErrorField::VxlanVni)?;
+        let vni_be = self.vni.to_be_bytes();
+        Ok([
+            self.flags, 0, 0, 0,
+            vni_be[1], vni_be[2], vni_be[3], 0,
+        ])
+    }
+}
+
+/// A slice containing a VXLAN header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VxlanHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> VxlanHeaderSlice<'a> {
+    /// Creates a VXLAN header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<VxlanHeaderSlice<'a>, ReadError> {
+        if slice.len() < VxlanHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(VxlanHeader::SERIALIZED_SIZE));
+        }
+        Ok(VxlanHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // VxlanHeader::SERIALIZED_SIZE (8) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    VxlanHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the VXLAN header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "flags" field.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Returns true if the "I" flag is set (the VNI is valid).
+    #[inline]
+    pub fn vni_valid(&self) -> bool {
+        0 != self.flags() & VxlanHeader::FLAG_VNI_VALID
+    }
+
+    /// Read the "VXLAN network identifier" field.
+    #[inline]
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[4], self.slice[5], self.slice[6]])
+    }
+
+    /// Decode all the fields & copy the data into a new VxlanHeader.
+    pub fn to_header(&self) -> VxlanHeader {
+        VxlanHeader{
+            flags: self.flags(),
+            vni: self.vni(),
+        }
+    }
+}
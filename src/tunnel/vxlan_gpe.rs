@@ -0,0 +1,245 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Values of the "next protocol" field of VXLAN-GPE headers.
+pub mod vxlan_gpe_next_protocol {
+    pub const IPV4: u8 = 0x01;
+    pub const IPV6: u8 = 0x02;
+    pub const ETHERNET: u8 = 0x03;
+    /// Network Service Header (RFC 8300).
+    pub const NSH: u8 = 0x04;
+    pub const MPLS: u8 = 0x05;
+}
+
+/// VXLAN generic protocol extension (VXLAN-GPE) header.
+///
+/// VXLAN-GPE headers are transported via UDP (destination port
+/// [`VxlanGpeHeader::PORT`]) & are followed by the packet identified by
+/// the "next protocol" field (see [`vxlan_gpe_next_protocol`]).
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct VxlanGpeHeader {
+    /// Flags including the version (the "I" flag [`VxlanGpeHeader::FLAG_VNI_VALID`]
+    /// & the "P" flag [`VxlanGpeHeader::FLAG_NEXT_PROTOCOL`] have to be set
+    /// for a valid VNI & next protocol).
+    pub flags: u8,
+    /// Protocol of the encapsulated packet (see [`vxlan_gpe_next_protocol`]).
+    pub next_protocol: u8,
+    /// VXLAN network identifier (24 bits).
+    pub vni: u32,
+}
+
+impl SerializedSize for VxlanGpeHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl VxlanGpeHeader {
+    /// UDP destination port assigned to VXLAN-GPE by IANA.
+    pub const PORT: u16 = 4790;
+
+    /// Flag indicating that the VNI is valid ("I" flag).
+    pub const FLAG_VNI_VALID: u8 = 0b0000_1000;
+
+    /// Flag indicating that the next protocol field is present ("P" flag).
+    pub const FLAG_NEXT_PROTOCOL: u8 = 0b0000_0100;
+
+    /// Flag indicating that the packet is a broadcast, unknown unicast or
+    /// multicast packet ("B" flag).
+    pub const FLAG_BUM: u8 = 0b0000_0010;
+
+    /// Flag indicating an OAM packet ("O" flag).
+    pub const FLAG_OAM: u8 = 0b0000_0001;
+
+    /// Maximum value of the VNI field.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    /// Creates a VXLAN-GPE header with the given VNI & next protocol and
+    /// the "I" & "P" flags set.
+    pub fn new(vni: u32, next_protocol: u8) -> VxlanGpeHeader {
+        VxlanGpeHeader{
+            flags: VxlanGpeHeader::FLAG_VNI_VALID | VxlanGpeHeader::FLAG_NEXT_PROTOCOL,
+            next_protocol,
+            vni,
+        }
+    }
+
+    /// Read a VXLAN-GPE header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(VxlanGpeHeader, &[u8]), ReadError> {
+        Ok((
+            VxlanGpeHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[VxlanGpeHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a VXLAN-GPE header from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;8]) -> VxlanGpeHeader {
+        VxlanGpeHeaderSlice{
+            slice: &bytes
+        }.to_header()
+    }
+
+    /// Reads a VXLAN-GPE header from the current position of the reader.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<VxlanGpeHeader, io::Error> {
+        let mut buffer = [0;VxlanGpeHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(VxlanGpeHeader::from_bytes(buffer))
+    }
+
+    /// Returns the version contained in the flags field.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        (self.flags >> 4) & 0b11
+    }
+
+    /// Returns true if the "I" flag is set (the VNI is valid).
+    #[inline]
+    pub fn vni_valid(&self) -> bool {
+        0 != self.flags & VxlanGpeHeader::FLAG_VNI_VALID
+    }
+
+    /// Returns true if the "P" flag is set (the next protocol field is present).
+    #[inline]
+    pub fn next_protocol_present(&self) -> bool {
+        0 != self.flags & VxlanGpeHeader::FLAG_NEXT_PROTOCOL
+    }
+
+    /// Returns the ether type of the encapsulated packet based on the
+    /// next protocol field (None for unknown next protocols).
+    ///
+    /// If the "P" flag is not set, the header is interpreted like a VXLAN
+    /// header & an ethernet frame is assumed to follow.
+    #[inline]
+    pub fn payload_ether_type(&self) -> Option<u16> {
+        payload_ether_type(self.flags, self.next_protocol)
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant VxlanGpeHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        VxlanGpeHeader::SERIALIZED_SIZE
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the serialized form of the header or an value error in case
+    /// the VNI is bigger then [`VxlanGpeHeader::MAX_VNI`].
+    pub fn to_bytes(&self) -> Result<[u8;8], ValueError> {
+        max_check_u32(self.vni, VxlanGpeHeader::MAX_VNI, ErrorField::VxlanGpeVni)?;
+        let vni_be = self.vni.to_be_bytes();
+        Ok([
+            self.flags, 0, 0, self.next_protocol,
+            vni_be[1], vni_be[2], vni_be[3], 0,
+        ])
+    }
+}
+
+/// A slice containing a VXLAN-GPE header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VxlanGpeHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> VxlanGpeHeaderSlice<'a> {
+    /// Creates a VXLAN-GPE header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<VxlanGpeHeaderSlice<'a>, ReadError> {
+        if slice.len() < VxlanGpeHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(VxlanGpeHeader::SERIALIZED_SIZE));
+        }
+        Ok(VxlanGpeHeaderSlice{
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // VxlanGpeHeader::SERIALIZED_SIZE (8) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    VxlanGpeHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the VXLAN-GPE header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "flags" field.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the version contained in the flags field.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        (self.flags() >> 4) & 0b11
+    }
+
+    /// Returns true if the "I" flag is set (the VNI is valid).
+    #[inline]
+    pub fn vni_valid(&self) -> bool {
+        0 != self.flags() & VxlanGpeHeader::FLAG_VNI_VALID
+    }
+
+    /// Returns true if the "P" flag is set (the next protocol field is present).
+    #[inline]
+    pub fn next_protocol_present(&self) -> bool {
+        0 != self.flags() & VxlanGpeHeader::FLAG_NEXT_PROTOCOL
+    }
+
+    /// Read the "next protocol" field.
+    #[inline]
+    pub fn next_protocol(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Read the "VXLAN network identifier" field.
+    #[inline]
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[4], self.slice[5], self.slice[6]])
+    }
+
+    /// Returns the ether type of the encapsulated packet based on the
+    /// next protocol field (see [`VxlanGpeHeader::payload_ether_type`]).
+    #[inline]
+    pub fn payload_ether_type(&self) -> Option<u16> {
+        payload_ether_type(self.flags(), self.next_protocol())
+    }
+
+    /// Decode all the fields & copy the data into a new VxlanGpeHeader.
+    pub fn to_header(&self) -> VxlanGpeHeader {
+        VxlanGpeHeader{
+            flags: self.flags(),
+            next_protocol: self.next_protocol(),
+            vni: self.vni(),
+        }
+    }
+}
+
+/// Maps the next protocol field to the ether type of the encapsulated packet.
+fn payload_ether_type(flags: u8, next_protocol: u8) -> Option<u16> {
+    use self::vxlan_gpe_next_protocol::*;
+
+    if 0 == flags & VxlanGpeHeader::FLAG_NEXT_PROTOCOL {
+        return Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING);
+    }
+    match next_protocol {
+        IPV4 => Some(ether_type::IPV4),
+        IPV6 => Some(ether_type::IPV6),
+        ETHERNET => Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING),
+        NSH => Some(ether_type::NSH),
+        MPLS => Some(ether_type::MPLS_UNICAST),
+        _ => None,
+    }
+}
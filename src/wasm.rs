@@ -0,0 +1,214 @@
+use super::*;
+use super::compose::{Layer, LayerStack};
+use super::json::{self, JsonMode};
+
+use js_sys::{Array, Reflect, Uint8Array, JSON};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Parses an ethernet II frame & returns the decoded layers as a JS object
+/// (same layout as [`json::to_json`] in the default mode).
+#[wasm_bindgen(js_name = parseEthernet)]
+pub fn parse_ethernet(data: &[u8]) -> Result<JsValue, JsValue> {
+    to_js(SlicedPacket::from_ethernet(data))
+}
+
+/// Parses an IPv4 or IPv6 packet & returns the decoded layers as a JS object.
+#[wasm_bindgen(js_name = parseIp)]
+pub fn parse_ip(data: &[u8]) -> Result<JsValue, JsValue> {
+    to_js(SlicedPacket::from_ip(data))
+}
+
+/// Parses a packet based on a pcap link type (1 = ethernet, 101 = raw ip)
+/// & returns the decoded layers as a JS object.
+#[wasm_bindgen(js_name = parseLinkType)]
+pub fn parse_link_type(link_type: u32, data: &[u8]) -> Result<JsValue, JsValue> {
+    match link_type {
+        1 => parse_ethernet(data),
+        101 => parse_ip(data),
+        _ => Err(error(&format!("unsupported link type {}", link_type))),
+    }
+}
+
+/// Serializes an array of layer objects to a packet.
+///
+/// Every entry is an object with a single key naming the layer (`ethernet2`,
+/// `single_vlan`, `ipv4`, `ipv6`, `udp`, `tcp` or `payload`). Field names
+/// match the ones returned by the parse functions. Lengths, checksums,
+/// ether types & ip numbers are resolved during serialization:
+///
+/// ```js
+/// buildPacket([
+///     { ethernet2: { source: "00:11:22:33:44:55", destination: "66:77:88:99:aa:bb" } },
+///     { ipv4: { source: "192.168.1.1", destination: "192.168.1.2", time_to_live: 64 } },
+///     { udp: { source_port: 1234, destination_port: 53 } },
+///     { payload: new Uint8Array([1, 2, 3, 4]) },
+/// ]);
+/// ```
+#[wasm_bindgen(js_name = buildPacket)]
+pub fn build_packet(layers: &JsValue) -> Result<Vec<u8>, JsValue> {
+    if !Array::is_array(layers) {
+        return Err(error("expected an array of layers"));
+    }
+    let mut stack = LayerStack::new();
+    for entry in Array::from(layers).iter() {
+        stack.push(layer(&entry)?);
+    }
+    stack.to_bytes().map_err(|err| error(&err.to_string()))
+}
+
+fn to_js(sliced: Result<SlicedPacket, ReadError>) -> Result<JsValue, JsValue> {
+    let sliced = sliced.map_err(|err| error(&err.to_string()))?;
+    JSON::parse(&json::to_json(&sliced, JsonMode::Default, None))
+}
+
+fn error(message: &str) -> JsValue {
+    js_sys::Error::new(message).into()
+}
+
+fn layer(entry: &JsValue) -> Result<Layer, JsValue> {
+    if let Some(value) = member(entry, "ethernet2")? {
+        Ok(Layer::Ethernet2(Ethernet2Header{
+            source: mac(&field(&value, "source")?)?,
+            destination: mac(&field(&value, "destination")?)?,
+            ether_type: 0,
+        }))
+    } else if let Some(value) = member(entry, "single_vlan")? {
+        Ok(Layer::SingleVlan(SingleVlanHeader{
+            priority_code_point: optional_number(&value, "priority_code_point", 0, 0b111)? as u8,
+            drop_eligible_indicator: optional_bool(&value, "drop_eligible_indicator")?,
+            vlan_identifier: number(&field(&value, "vlan_identifier")?, "vlan_identifier", 0xfff)? as u16,
+            ether_type: 0,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        }))
+    } else if let Some(value) = member(entry, "ipv4")? {
+        Ok(Layer::Ipv4(Ipv4Header::new(
+            0,
+            optional_number(&value, "time_to_live", 64, 0xff)? as u8,
+            IpNumber::Udp,
+            ipv4(&field(&value, "source")?)?,
+            ipv4(&field(&value, "destination")?)?,
+        )))
+    } else if let Some(value) = member(entry, "ipv6")? {
+        Ok(Layer::Ipv6(Ipv6Header{
+            traffic_class: optional_number(&value, "traffic_class", 0, 0xff)? as u8,
+            flow_label: optional_number(&value, "flow_label", 0, 0xfffff)? as u32,
+            payload_length: 0,
+            next_header: 0,
+            hop_limit: optional_number(&value, "hop_limit", 64, 0xff)? as u8,
+            source: ipv6(&field(&value, "source")?)?,
+            destination: ipv6(&field(&value, "destination")?)?,
+        }))
+    } else if let Some(value) = member(entry, "udp")? {
+        Ok(Layer::Udp(UdpHeader{
+            source_port: number(&field(&value, "source_port")?, "source_port", 0xffff)? as u16,
+            destination_port: number(&field(&value, "destination_port")?, "destination_port", 0xffff)? as u16,
+            length: 0,
+            checksum: 0,
+        }))
+    } else if let Some(value) = member(entry, "tcp")? {
+        let mut header = TcpHeader::new(
+            number(&field(&value, "source_port")?, "source_port", 0xffff)? as u16,
+            number(&field(&value, "destination_port")?, "destination_port", 0xffff)? as u16,
+            optional_number(&value, "sequence_number", 0, u32::MAX.into())? as u32,
+            optional_number(&value, "window_size", 0xffff, 0xffff)? as u16,
+        );
+        header.syn = optional_bool(&value, "syn")?;
+        header.fin = optional_bool(&value, "fin")?;
+        header.rst = optional_bool(&value, "rst")?;
+        header.psh = optional_bool(&value, "psh")?;
+        if let Some(ack) = member(&value, "acknowledgment_number")? {
+            header.ack = true;
+            header.acknowledgment_number = number(&ack, "acknowledgment_number", u32::MAX.into())? as u32;
+        }
+        Ok(Layer::Tcp(header))
+    } else if let Some(value) = member(entry, "payload")? {
+        Ok(Layer::Payload(bytes(&value, "payload")?))
+    } else {
+        Err(error("unknown layer (expected ethernet2, single_vlan, ipv4, ipv6, udp, tcp or payload)"))
+    }
+}
+
+/// Returns the property with the given name if it is set.
+fn member(object: &JsValue, name: &str) -> Result<Option<JsValue>, JsValue> {
+    if !object.is_object() {
+        return Err(error("expected an object"));
+    }
+    let value = Reflect::get(object, &JsValue::from_str(name))?;
+    Ok(if value.is_undefined() || value.is_null() {
+        None
+    } else {
+        Some(value)
+    })
+}
+
+fn field(object: &JsValue, name: &str) -> Result<JsValue, JsValue> {
+    member(object, name)?.ok_or_else(|| error(&format!("missing field '{}'", name)))
+}
+
+fn number(value: &JsValue, name: &str, max: u64) -> Result<u64, JsValue> {
+    match value.as_f64() {
+        Some(value) if value >= 0.0 && value.fract() == 0.0 && value <= max as f64 => Ok(value as u64),
+        _ => Err(error(&format!("field '{}' has to be an integer between 0 and {}", name, max))),
+    }
+}
+
+fn optional_number(object: &JsValue, name: &str, default: u64, max: u64) -> Result<u64, JsValue> {
+    match member(object, name)? {
+        Some(value) => number(&value, name, max),
+        None => Ok(default),
+    }
+}
+
+fn optional_bool(object: &JsValue, name: &str) -> Result<bool, JsValue> {
+    match member(object, name)? {
+        Some(value) => value.as_bool().ok_or_else(|| error(&format!("field '{}' has to be a boolean", name))),
+        None => Ok(false),
+    }
+}
+
+/// Converts an `Uint8Array` or an array of numbers to bytes.
+fn bytes(value: &JsValue, name: &str) -> Result<Vec<u8>, JsValue> {
+    if let Some(array) = value.dyn_ref::<Uint8Array>() {
+        Ok(array.to_vec())
+    } else if Array::is_array(value) {
+        Array::from(value)
+            .iter()
+            .map(|entry| number(&entry, name, 0xff).map(|v| v as u8))
+            .collect()
+    } else {
+        Err(error(&format!("field '{}' has to be an Uint8Array or an array of numbers", name)))
+    }
+}
+
+/// Converts a string (using the given parser) or a byte array to an address.
+fn address<const N: usize>(value: &JsValue, name: &str, parse: fn(&str) -> Option<[u8;N]>) -> Result<[u8;N], JsValue> {
+    let result = match value.as_string() {
+        Some(text) => parse(&text),
+        None => {
+            let bytes = bytes(value, name)?;
+            let mut result = [0u8;N];
+            if bytes.len() == N {
+                result.copy_from_slice(&bytes);
+                Some(result)
+            } else {
+                None
+            }
+        }
+    };
+    result.ok_or_else(|| error(&format!("invalid {} address", name)))
+}
+
+fn mac(value: &JsValue) -> Result<[u8;6], JsValue> {
+    address(value, "mac", json::parse_mac)
+}
+
+fn ipv4(value: &JsValue) -> Result<[u8;4], JsValue> {
+    address(value, "ipv4", |text| Ipv4Addr::from_str(text).ok().map(|addr| addr.octets()))
+}
+
+fn ipv6(value: &JsValue) -> Result<[u8;16], JsValue> {
+    address(value, "ipv6", |text| Ipv6Addr::from_str(text).ok().map(|addr| addr.octets()))
+}
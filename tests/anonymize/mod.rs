@@ -0,0 +1,372 @@
+use super::*;
+use etherparse::anonymize::*;
+
+const KEY: [u8;32] = [
+    1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,
+    17,18,19,20,21,22,23,24,25,26,27,28,29,30,31,32,
+];
+
+fn udp_ipv4(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([0x10,2,3,4,5,6], [0x20,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, payload).unwrap();
+    packet
+}
+
+fn tcp_ipv6(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([0x10,2,3,4,5,6], [0xff;6])
+        .ipv6([0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1], [0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2], 20)
+        .tcp(21, 1234, 1, 2000);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, payload).unwrap();
+    packet
+}
+
+fn ipv4(protocol: IpNumber, message: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ipv4Header::new(message.len() as u16, 20, protocol, [192,168,1,1], [192,168,1,2])
+        .write(&mut packet)
+        .unwrap();
+    packet.extend_from_slice(message);
+    packet
+}
+
+/// ICMPv4 echo request with a valid checksum.
+fn icmpv4_echo(payload: &[u8]) -> Vec<u8> {
+    let mut message = vec![8, 0, 0, 0, 0, 1, 0, 2];
+    message.extend_from_slice(payload);
+    let checksum = etherparse::checksum::Sum16BitWords::new()
+        .add_slice(&message)
+        .ones_complement()
+        .to_be();
+    message[2..4].copy_from_slice(&checksum.to_be_bytes());
+    ipv4(IpNumber::Icmp, &message)
+}
+
+/// ICMPv6 echo request with a valid checksum.
+fn icmpv6_echo(payload: &[u8]) -> Vec<u8> {
+    let source = [0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1];
+    let destination = [0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2];
+    let mut message = vec![128, 0, 0, 0, 0, 1, 0, 2];
+    message.extend_from_slice(payload);
+    let checksum = etherparse::icmpv6::checksum(source, destination, &message);
+    message[2..4].copy_from_slice(&checksum.to_be_bytes());
+    let mut packet = Vec::new();
+    Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: message.len() as u16,
+        next_header: ip_number::IPV6_ICMP,
+        hop_limit: 20,
+        source,
+        destination,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&message);
+    packet
+}
+
+/// Number of equal leading bits.
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    let mut result = 0;
+    for (a, b) in a.iter().zip(b.iter()) {
+        let diff = a ^ b;
+        if 0 == diff {
+            result += 8;
+        } else {
+            return result + diff.leading_zeros() as usize;
+        }
+    }
+    result
+}
+
+#[test]
+fn config_default() {
+    assert_eq!(
+        AnonymizeConfig{
+            mac_addresses: true,
+            ip_addresses: true,
+            max_payload_len: None,
+            checksums: ChecksumMode::Recalculate,
+        },
+        AnonymizeConfig::default()
+    );
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    assert_eq!(&AnonymizeConfig::default(), anonymizer.config());
+}
+
+proptest! {
+    #[test]
+    fn prefix_preserving(
+        a in any::<[u8;4]>(),
+        b in any::<[u8;4]>(),
+        c in any::<[u8;16]>(),
+        d in any::<[u8;16]>()
+    ) {
+        let anonymizer = Anonymizer::new(KEY, Default::default());
+        // deterministic
+        assert_eq!(anonymizer.ipv4(a), anonymizer.ipv4(a));
+        assert_eq!(anonymizer.ipv6(c), anonymizer.ipv6(c));
+        // prefix-preserving
+        assert_eq!(
+            common_prefix_len(&a, &b),
+            common_prefix_len(&anonymizer.ipv4(a), &anonymizer.ipv4(b))
+        );
+        assert_eq!(
+            common_prefix_len(&c, &d),
+            common_prefix_len(&anonymizer.ipv6(c), &anonymizer.ipv6(d))
+        );
+    }
+}
+
+#[test]
+fn key_dependent() {
+    let a = Anonymizer::new(KEY, Default::default());
+    let b = Anonymizer::new([0;32], Default::default());
+    assert_ne!(a.ipv4([192,168,1,1]), [192,168,1,1]);
+    assert_ne!(a.ipv4([192,168,1,1]), b.ipv4([192,168,1,1]));
+    assert_ne!(a.ipv6([1;16]), b.ipv6([1;16]));
+    assert_ne!(a.mac([0x10,2,3,4,5,6]), b.mac([0x10,2,3,4,5,6]));
+}
+
+#[test]
+fn mac() {
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    // unicast addresses stay unicast addresses
+    for i in 0..=255u8 {
+        let address = [i & !1, i, 2, 3, 4, 5];
+        let result = anonymizer.mac(address);
+        assert_ne!(address, result);
+        assert_eq!(0, result[0] & 1);
+    }
+    // multicast & broadcast addresses are not modified
+    assert_eq!([0xff;6], anonymizer.mac([0xff;6]));
+    assert_eq!([1,0,0x5e,0,0,1], anonymizer.mac([1,0,0x5e,0,0,1]));
+}
+
+#[test]
+fn anonymize_ethernet_ipv4_udp() {
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    let mut packet = udp_ipv4(&[1,2,3,4]);
+    anonymizer.anonymize_ethernet(&mut packet).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let eth = assert_matches!(sliced.link, Some(LinkSlice::Ethernet2(eth)) => eth);
+    assert_eq!(anonymizer.mac([0x10,2,3,4,5,6]), eth.source());
+    assert_eq!(anonymizer.mac([0x20,8,9,10,11,12]), eth.destination());
+    let ip = match sliced.ip.unwrap() {
+        InternetSlice::Ipv4(ip, _) => ip,
+        _ => panic!("expected ipv4"),
+    };
+    assert_eq!(anonymizer.ipv4([192,168,1,1]), ip.source());
+    assert_eq!(anonymizer.ipv4([192,168,1,2]), ip.destination());
+    assert_eq!(ip.to_header().calc_header_checksum().unwrap(), ip.header_checksum());
+    let udp = assert_matches!(sliced.transport, Some(TransportSlice::Udp(udp)) => udp);
+    assert_eq!(udp.calc_checksum_ipv4(&ip, &[1,2,3,4]).unwrap(), udp.checksum());
+    assert_eq!(&[1,2,3,4], sliced.payload);
+}
+
+#[test]
+fn anonymize_ethernet_ipv6_tcp() {
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    let mut packet = tcp_ipv6(&[1,2,3,4]);
+    anonymizer.anonymize_ethernet(&mut packet).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let eth = assert_matches!(sliced.link, Some(LinkSlice::Ethernet2(eth)) => eth);
+    // broadcast address unchanged
+    assert_eq!([0xff;6], eth.destination());
+    let ip = match sliced.ip.unwrap() {
+        InternetSlice::Ipv6(ip, _) => ip,
+        _ => panic!("expected ipv6"),
+    };
+    let source = anonymizer.ipv6([0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1]);
+    let destination = anonymizer.ipv6([0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2]);
+    assert_eq!(source, ip.source());
+    assert_eq!(destination, ip.destination());
+    assert_eq!(126, common_prefix_len(&source, &destination));
+    let tcp = assert_matches!(sliced.transport, Some(TransportSlice::Tcp(tcp)) => tcp);
+    assert_eq!(tcp.calc_checksum_ipv6(&ip, &[1,2,3,4]).unwrap(), tcp.checksum());
+}
+
+#[test]
+fn anonymize_ip() {
+    let anonymizer = Anonymizer::new(KEY, AnonymizeConfig{
+        ip_addresses: false,
+        ..Default::default()
+    });
+    let original = udp_ipv4(&[1,2,3,4]);
+    let mut packet = original[Ethernet2Header::SERIALIZED_SIZE..].to_vec();
+    anonymizer.anonymize_ip(&mut packet).unwrap();
+    // nothing changed as the ip addresses are excluded
+    assert_eq!(&original[Ethernet2Header::SERIALIZED_SIZE..], &packet[..]);
+
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    anonymizer.anonymize_ip(&mut packet).unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let ip = match sliced.ip.unwrap() {
+        InternetSlice::Ipv4(ip, _) => ip,
+        _ => panic!("expected ipv4"),
+    };
+    assert_eq!(anonymizer.ipv4([192,168,1,1]), ip.source());
+}
+
+#[test]
+fn truncate_payload() {
+    let anonymizer = Anonymizer::new(KEY, AnonymizeConfig{
+        max_payload_len: Some(2),
+        ..Default::default()
+    });
+    // udp & ipv4
+    {
+        let mut packet = udp_ipv4(&[1,2,3,4,5,6]);
+        anonymizer.anonymize_ethernet(&mut packet).unwrap();
+        assert_eq!(14 + 20 + 8 + 2, packet.len());
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(&[1,2], sliced.payload);
+        let ip = match sliced.ip.unwrap() {
+            InternetSlice::Ipv4(ip, _) => ip,
+            _ => panic!("expected ipv4"),
+        };
+        assert_eq!(20 + 8 + 2, ip.total_len());
+        assert_eq!(ip.to_header().calc_header_checksum().unwrap(), ip.header_checksum());
+        let udp = assert_matches!(sliced.transport, Some(TransportSlice::Udp(udp)) => udp);
+        assert_eq!(8 + 2, udp.length());
+        assert_eq!(udp.calc_checksum_ipv4(&ip, &[1,2]).unwrap(), udp.checksum());
+    }
+    // tcp & ipv6
+    {
+        let mut packet = tcp_ipv6(&[1,2,3,4,5,6]);
+        anonymizer.anonymize_ethernet(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(&[1,2], sliced.payload);
+        let ip = match sliced.ip.unwrap() {
+            InternetSlice::Ipv6(ip, _) => ip,
+            _ => panic!("expected ipv6"),
+        };
+        assert_eq!(20 + 2, ip.payload_length());
+        let tcp = assert_matches!(sliced.transport, Some(TransportSlice::Tcp(tcp)) => tcp);
+        assert_eq!(tcp.calc_checksum_ipv6(&ip, &[1,2]).unwrap(), tcp.checksum());
+    }
+    // shorter payloads are not modified
+    {
+        let mut packet = udp_ipv4(&[1]);
+        anonymizer.anonymize_ethernet(&mut packet).unwrap();
+        assert_eq!(&[1], SlicedPacket::from_ethernet(&packet).unwrap().payload);
+    }
+}
+
+#[test]
+fn icmpv4_checksum() {
+    let anonymizer = Anonymizer::new(KEY, AnonymizeConfig{
+        max_payload_len: Some(10),
+        ..Default::default()
+    });
+    let mut packet = icmpv4_echo(&[1,2,3,4,5,6]);
+    anonymizer.anonymize_ip(&mut packet).unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_eq!(&[8, 0], &sliced.payload[..2]);
+    assert_eq!(10, sliced.payload.len());
+    assert!(etherparse::icmpv4::is_checksum_valid(sliced.payload));
+}
+
+#[test]
+fn icmpv6_checksum() {
+    // anonymized addresses
+    {
+        let anonymizer = Anonymizer::new(KEY, Default::default());
+        let mut packet = icmpv6_echo(&[1,2,3,4]);
+        anonymizer.anonymize_ip(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let ip = match sliced.ip.unwrap() {
+            InternetSlice::Ipv6(ip, _) => ip,
+            _ => panic!("expected ipv6"),
+        };
+        assert_eq!(anonymizer.ipv6([0x20,1,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1]), ip.source());
+        assert_eq!(12, sliced.payload.len());
+        assert!(etherparse::icmpv6::is_checksum_valid(ip.source(), ip.destination(), sliced.payload));
+    }
+    // anonymized addresses & truncated message
+    {
+        let anonymizer = Anonymizer::new(KEY, AnonymizeConfig{
+            max_payload_len: Some(9),
+            ..Default::default()
+        });
+        let mut packet = icmpv6_echo(&[1,2,3,4]);
+        anonymizer.anonymize_ip(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let ip = match sliced.ip.unwrap() {
+            InternetSlice::Ipv6(ip, _) => ip,
+            _ => panic!("expected ipv6"),
+        };
+        assert_eq!(9, sliced.payload.len());
+        assert!(etherparse::icmpv6::is_checksum_valid(ip.source(), ip.destination(), sliced.payload));
+    }
+}
+
+#[test]
+fn igmp_checksum() {
+    // membership report with an invalid checksum
+    let mut packet = ipv4(IpNumber::Igmp, &[0x16, 0, 0, 0, 224, 0, 0, 1]);
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    anonymizer.anonymize_ip(&mut packet).unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let igmp = assert_matches!(sliced.transport, Some(TransportSlice::Igmp(igmp)) => igmp);
+    assert_ne!(0, igmp.checksum());
+    assert_eq!(igmp.to_header().calc_checksum(&[]).unwrap(), igmp.checksum());
+}
+
+#[test]
+fn zero_checksums() {
+    let anonymizer = Anonymizer::new(KEY, AnonymizeConfig{
+        checksums: ChecksumMode::Zero,
+        ..Default::default()
+    });
+    {
+        let mut packet = udp_ipv4(&[1,2,3,4]);
+        anonymizer.anonymize_ethernet(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let ip = match sliced.ip.unwrap() {
+            InternetSlice::Ipv4(ip, _) => ip,
+            _ => panic!("expected ipv4"),
+        };
+        assert_eq!(0, ip.header_checksum());
+        let udp = assert_matches!(sliced.transport, Some(TransportSlice::Udp(udp)) => udp);
+        assert_eq!(0, udp.checksum());
+    }
+    {
+        let mut packet = tcp_ipv6(&[1,2,3,4]);
+        anonymizer.anonymize_ethernet(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let tcp = assert_matches!(sliced.transport, Some(TransportSlice::Tcp(tcp)) => tcp);
+        assert_eq!(0, tcp.checksum());
+    }
+    {
+        let mut packet = icmpv4_echo(&[1,2,3,4]);
+        anonymizer.anonymize_ip(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(&[0, 0], &sliced.payload[2..4]);
+    }
+    {
+        let mut packet = icmpv6_echo(&[1,2,3,4]);
+        anonymizer.anonymize_ip(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(&[0, 0], &sliced.payload[2..4]);
+    }
+}
+
+#[test]
+fn slice_error() {
+    let anonymizer = Anonymizer::new(KEY, Default::default());
+    let original = udp_ipv4(&[1,2,3,4]);
+    let mut packet = original[..20].to_vec();
+    assert_matches!(
+        anonymizer.anonymize_ethernet(&mut packet),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+    // unmodified
+    assert_eq!(&original[..20], &packet[..]);
+}
@@ -0,0 +1,109 @@
+use super::*;
+
+/// Builds an ethernet II + ipv4 + udp packet with the given udp source port.
+fn udp_packet(source_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(source_port, 1234);
+    let mut packet = Vec::<u8>::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+#[test]
+fn headers_from_ethernet_slices() {
+    // empty batch
+    {
+        let frames: Vec<Vec<u8>> = Vec::new();
+        assert!(etherparse::batch::headers_from_ethernet_slices(&frames).is_empty());
+    }
+    // order & per packet errors
+    {
+        let frames: Vec<Vec<u8>> = (0..100u16)
+            .map(|i| if 0 == i % 7 {
+                // too short to contain an ethernet header
+                vec![0u8;(i % 14) as usize]
+            } else {
+                udp_packet(i)
+            })
+            .collect();
+
+        let actual = etherparse::batch::headers_from_ethernet_slices(&frames);
+        assert_eq!(frames.len(), actual.len());
+        for (i, (frame, result)) in frames.iter().zip(actual.iter()).enumerate() {
+            match result {
+                Ok(value) => {
+                    assert_ne!(0, i % 7);
+                    assert_eq!(
+                        i as u16,
+                        value.transport.clone().unwrap().udp().unwrap().source_port
+                    );
+                    assert_eq!(
+                        PacketHeaders::from_ethernet_slice(frame).unwrap(),
+                        *value
+                    );
+                },
+                Err(err) => {
+                    assert_eq!(0, i % 7);
+                    assert_matches!(err, ReadError::UnexpectedEndOfSlice(_));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn headers_from_ip_slices() {
+    let packets: Vec<&[u8]> = vec![];
+    assert!(etherparse::batch::headers_from_ip_slices(&packets).is_empty());
+
+    let frames: Vec<Vec<u8>> = (0..10u16).map(udp_packet).collect();
+    let packets: Vec<&[u8]> = frames.iter().map(|f| &f[Ethernet2Header::SERIALIZED_SIZE..]).collect();
+    let actual = etherparse::batch::headers_from_ip_slices(&packets);
+    assert_eq!(packets.len(), actual.len());
+    for (packet, result) in packets.iter().zip(actual.iter()) {
+        assert_eq!(
+            &PacketHeaders::from_ip_slice(packet).unwrap(),
+            result.as_ref().unwrap()
+        );
+    }
+}
+
+#[test]
+fn slice_ethernet_slices() {
+    let frames: Vec<Vec<u8>> = (0..10u16).map(udp_packet).chain(std::iter::once(vec![1,2])).collect();
+    let actual = etherparse::batch::slice_ethernet_slices(&frames);
+    assert_eq!(frames.len(), actual.len());
+    for (i, result) in actual.iter().enumerate() {
+        if i < 10 {
+            match &result.as_ref().unwrap().transport {
+                Some(TransportSlice::Udp(udp)) => assert_eq!(i as u16, udp.source_port()),
+                _ => panic!("expected udp slice"),
+            }
+        } else {
+            assert_matches!(result, Err(ReadError::UnexpectedEndOfSlice(_)));
+        }
+    }
+}
+
+#[test]
+fn slice_ip_slices() {
+    let frames: Vec<Vec<u8>> = (0..10u16).map(udp_packet).collect();
+    let packets: Vec<&[u8]> = frames.iter().map(|f| &f[Ethernet2Header::SERIALIZED_SIZE..]).collect();
+    let actual = etherparse::batch::slice_ip_slices(&packets);
+    assert_eq!(packets.len(), actual.len());
+    for (packet, result) in packets.iter().zip(actual.iter()) {
+        assert_eq!(
+            SlicedPacket::from_ip(packet).unwrap().payload,
+            result.as_ref().unwrap().payload
+        );
+    }
+}
+
+#[test]
+fn map_slices() {
+    let frames: Vec<Vec<u8>> = (0..50usize).map(|i| vec![0u8;i]).collect();
+    let actual = etherparse::batch::map_slices(&frames, |slice| slice.len());
+    assert_eq!((0..50usize).collect::<Vec<_>>(), actual);
+}
@@ -0,0 +1,226 @@
+use super::*;
+use etherparse::bpf::*;
+
+/// Output of `tcpdump -ddd udp port 53` (for ethernet frames).
+const UDP_PORT_53: &str = "20
+40 0 0 12
+21 0 6 34525
+48 0 0 20
+21 0 15 17
+40 0 0 54
+21 12 0 53
+40 0 0 56
+21 10 11 53
+21 0 10 2048
+48 0 0 23
+21 0 8 17
+40 0 0 20
+69 6 0 8191
+177 0 0 14
+72 0 0 14
+21 2 0 53
+72 0 0 16
+21 0 1 53
+6 0 0 262144
+6 0 0 0
+";
+
+fn udp_ipv4(source_port: u16, destination_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(source_port, destination_port);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn udp_ipv6(source_port: u16, destination_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv6([0;16], [1;16], 20)
+        .udp(source_port, destination_port);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn tcp_ipv4(source_port: u16, destination_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(source_port, destination_port, 1, 1024);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+#[test]
+fn tcpdump_program() {
+    let program = BpfProgram::from_tcpdump_ddd(UDP_PORT_53).unwrap();
+    assert_eq!(20, program.instructions().len());
+
+    let accepted = [udp_ipv4(53, 1234), udp_ipv4(1234, 53), udp_ipv6(53, 1234), udp_ipv6(1234, 53)];
+    for packet in accepted.iter() {
+        assert_eq!(262144, program.run(packet));
+        assert!(program.matches(packet));
+        let sliced = SlicedPacket::from_ethernet(packet).unwrap();
+        assert_eq!(262144, program.run_sliced(&sliced));
+        assert!(program.matches_sliced(&sliced));
+    }
+
+    let rejected = [udp_ipv4(54, 1234), udp_ipv6(54, 1234), tcp_ipv4(53, 1234)];
+    for packet in rejected.iter() {
+        assert_eq!(0, program.run(packet));
+        assert!(!program.matches(packet));
+        let sliced = SlicedPacket::from_ethernet(packet).unwrap();
+        assert!(!program.matches_sliced(&sliced));
+    }
+
+    // loads outside of the packet reject the packet
+    assert!(!program.matches(&[0;12]));
+}
+
+#[test]
+fn from_tcpdump_dd() {
+    let program = BpfProgram::from_tcpdump_dd(
+        "{ 0x28, 0, 0, 0x0000000c },\n\
+         { 0x15, 0, 1, 0x00000800 },\n\
+         { 0x6, 0, 0, 0x00040000 },\n\
+         { 0x6, 0, 0, 0x00000000 },\n"
+    ).unwrap();
+    assert_eq!(
+        &[
+            BpfInstruction::new(0x28, 0, 0, 12),
+            BpfInstruction::new(0x15, 0, 1, 0x800),
+            BpfInstruction::new(0x6, 0, 0, 0x40000),
+            BpfInstruction::new(0x6, 0, 0, 0),
+        ],
+        program.instructions()
+    );
+    assert!(program.matches(&udp_ipv4(1, 2)));
+    assert!(!program.matches(&udp_ipv6(1, 2)));
+
+    assert_eq!(
+        BpfError::ParseError(2),
+        BpfProgram::from_tcpdump_dd("{ 0x6, 0, 0, 0 },\n{ 0x6, 0, 0 },\n").unwrap_err()
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(BpfError::EmptyProgram, BpfProgram::from_tcpdump_ddd("").unwrap_err());
+    assert_eq!(BpfError::ParseError(1), BpfProgram::from_tcpdump_ddd("a\n6 0 0 0").unwrap_err());
+    assert_eq!(BpfError::ParseError(2), BpfProgram::from_tcpdump_ddd("1\n6 0 0").unwrap_err());
+    assert_eq!(BpfError::ParseError(2), BpfProgram::from_tcpdump_ddd("1\n6 256 0 0").unwrap_err());
+    assert_eq!(
+        BpfError::InstructionCountMismatch{ expected: 2, actual: 1 },
+        BpfProgram::from_tcpdump_ddd("2\n6 0 0 0").unwrap_err()
+    );
+}
+
+#[test]
+fn validation() {
+    let ret = BpfInstruction::new(0x06, 0, 0, 0);
+    assert_eq!(BpfError::EmptyProgram, BpfProgram::new(Vec::new()).unwrap_err());
+    assert_eq!(
+        BpfError::TooManyInstructions(MAX_INSTRUCTIONS + 1),
+        BpfProgram::new(vec![ret; MAX_INSTRUCTIONS + 1]).unwrap_err()
+    );
+    // unknown opcode
+    assert_eq!(
+        BpfError::InvalidOpcode{ index: 0, code: 0x18 },
+        BpfProgram::new(vec![BpfInstruction::new(0x18, 0, 0, 0), ret]).unwrap_err()
+    );
+    // jumps outside of the program
+    assert_eq!(
+        BpfError::JumpOutOfBounds(0),
+        BpfProgram::new(vec![BpfInstruction::new(0x05, 0, 0, 1), ret]).unwrap_err()
+    );
+    assert_eq!(
+        BpfError::JumpOutOfBounds(0),
+        BpfProgram::new(vec![BpfInstruction::new(0x15, 0, 1, 1), ret]).unwrap_err()
+    );
+    // scratch memory
+    assert_eq!(
+        BpfError::InvalidMemoryIndex(0),
+        BpfProgram::new(vec![BpfInstruction::new(0x02, 0, 0, 16), ret]).unwrap_err()
+    );
+    // constant division by zero
+    assert_eq!(
+        BpfError::DivisionByZero(0),
+        BpfProgram::new(vec![BpfInstruction::new(0x34, 0, 0, 0), ret]).unwrap_err()
+    );
+    // missing return
+    assert_eq!(
+        BpfError::MissingReturn,
+        BpfProgram::new(vec![BpfInstruction::new(0x00, 0, 0, 0)]).unwrap_err()
+    );
+    // display
+    assert!(format!("{}", BpfError::MissingReturn).starts_with("BpfError:"));
+}
+
+#[test]
+fn arithmetic_and_memory() {
+    let program = |instructions: Vec<BpfInstruction>| BpfProgram::new(instructions).unwrap();
+
+    // ld #10; st M[3]; ldx #3; ld M[3]; mul x; add #2; sub #1; ret a => 31
+    assert_eq!(31, program(vec![
+        BpfInstruction::new(0x00, 0, 0, 10),
+        BpfInstruction::new(0x02, 0, 0, 3),
+        BpfInstruction::new(0x01, 0, 0, 3),
+        BpfInstruction::new(0x60, 0, 0, 3),
+        BpfInstruction::new(0x2c, 0, 0, 0),
+        BpfInstruction::new(0x04, 0, 0, 2),
+        BpfInstruction::new(0x14, 0, 0, 1),
+        BpfInstruction::new(0x16, 0, 0, 0),
+    ]).run(&[]));
+
+    // ld len; lsh #4; rsh #2; mod #7; xor #1; or #8; and #0xc; tax; txa; ret x => ((3*4) % 7 ^ 1 | 8) & 0xc = 12
+    assert_eq!(12, program(vec![
+        BpfInstruction::new(0x80, 0, 0, 0),
+        BpfInstruction::new(0x64, 0, 0, 4),
+        BpfInstruction::new(0x74, 0, 0, 2),
+        BpfInstruction::new(0x94, 0, 0, 7),
+        BpfInstruction::new(0xa4, 0, 0, 1),
+        BpfInstruction::new(0x44, 0, 0, 8),
+        BpfInstruction::new(0x54, 0, 0, 0xc),
+        BpfInstruction::new(0x07, 0, 0, 0),
+        BpfInstruction::new(0x87, 0, 0, 0),
+        BpfInstruction::new(0x0e, 0, 0, 0),
+    ]).run(&[1,2,3]));
+
+    // neg & oversized shifts
+    assert_eq!(0, program(vec![
+        BpfInstruction::new(0x00, 0, 0, 1),
+        BpfInstruction::new(0x64, 0, 0, 32),
+        BpfInstruction::new(0x16, 0, 0, 0),
+    ]).run(&[]));
+    assert_eq!(u32::MAX, program(vec![
+        BpfInstruction::new(0x00, 0, 0, 1),
+        BpfInstruction::new(0x84, 0, 0, 0),
+        BpfInstruction::new(0x16, 0, 0, 0),
+    ]).run(&[]));
+
+    // division by a zero index register rejects the packet
+    assert_eq!(0, program(vec![
+        BpfInstruction::new(0x00, 0, 0, 1),
+        BpfInstruction::new(0x3c, 0, 0, 0),
+        BpfInstruction::new(0x06, 0, 0, 1),
+    ]).run(&[]));
+
+    // word load & jgt/jge/ja
+    let jumps = program(vec![
+        BpfInstruction::new(0x20, 0, 0, 0),
+        BpfInstruction::new(0x25, 0, 2, 0x01020304),
+        BpfInstruction::new(0x35, 2, 0, 0x01020306),
+        BpfInstruction::new(0x05, 0, 0, 2),
+        BpfInstruction::new(0x06, 0, 0, 1),
+        BpfInstruction::new(0x06, 0, 0, 2),
+        BpfInstruction::new(0x06, 0, 0, 3),
+    ]);
+    assert_eq!(1, jumps.run(&[1,2,3,4]));
+    assert_eq!(3, jumps.run(&[1,2,3,5]));
+    assert_eq!(2, jumps.run(&[1,2,3,6]));
+    assert_eq!(0, jumps.run(&[1,2,3]));
+}
@@ -22,3 +22,32 @@ proptest! {
         assert_eq!(u32_oc, struct_oc);
     }
 }
+
+proptest! {
+    #[test]
+    fn incremental_update(
+        data in proptest::collection::vec(any::<u8>(), 2..0xffusize),
+        index in any::<usize>(),
+        new in any::<[u8;2]>()
+    ) {
+        use super::etherparse::checksum::*;
+
+        let mut data = data;
+        // only full 16 bit words
+        data.truncate(data.len() & !1);
+        let index = (index % (data.len() / 2))*2;
+
+        let before = Sum16BitWords::new().add_slice(&data).ones_complement().to_be().to_be_bytes();
+        let old = [data[index], data[index + 1]];
+        data[index..index + 2].copy_from_slice(&new);
+        let expected = Sum16BitWords::new().add_slice(&data).ones_complement().to_be().to_be_bytes();
+
+        let result = etherparse::checksum::incremental_update(before, old, new);
+        // 0x0000 & 0xffff are both representations of zero
+        if expected == [0, 0] || expected == [0xff, 0xff] {
+            assert!(result == [0, 0] || result == [0xff, 0xff]);
+        } else {
+            assert_eq!(expected, result);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use super::*;
+use etherparse::compose::*;
+
+fn ethernet() -> Ethernet2Header {
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: 0,
+    }
+}
+
+fn udp() -> UdpHeader {
+    UdpHeader{ source_port: 1234, destination_port: 5678, length: 0, checksum: 0 }
+}
+
+#[test]
+fn same_as_packet_builder() {
+    // ipv4 udp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1234, 5678);
+        let mut expected = Vec::with_capacity(builder.size(4));
+        builder.write(&mut expected, &[1,2,3,4]).unwrap();
+
+        let stack = ethernet() /
+            Ipv4Header::new(0, 20, IpNumber::Tcp, [192,168,1,1], [192,168,1,2]) /
+            udp() /
+            [1,2,3,4];
+        assert_eq!(expected.len(), stack.len());
+        assert_eq!(expected, stack.to_bytes().unwrap());
+
+        let mut written = Vec::new();
+        stack.write(&mut written).unwrap();
+        assert_eq!(expected, written);
+    }
+    // vlan ipv6 tcp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(0x123)
+            .ipv6([1;16], [2;16], 47)
+            .tcp(1, 2, 3, 4);
+        let mut expected = Vec::with_capacity(builder.size(3));
+        builder.write(&mut expected, &[1,2,3]).unwrap();
+
+        let vlan = SingleVlanHeader{ vlan_identifier: 0x123, ..Default::default() };
+        let ip = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 0,
+            next_header: 0,
+            hop_limit: 47,
+            source: [1;16],
+            destination: [2;16],
+        };
+        let tcp = TcpHeader::new(1, 2, 3, 4);
+        let stack = ethernet() / vlan / ip / tcp / vec![1,2,3];
+        assert_eq!(expected, stack.to_bytes().unwrap());
+    }
+}
+
+#[test]
+fn tunnel() {
+    let inner = Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 0,
+        next_header: 0,
+        hop_limit: 64,
+        source: [1;16],
+        destination: [2;16],
+    };
+    let outer = Ipv4Header::new(0, 64, IpNumber::Udp, [10,0,0,1], [10,0,0,2]);
+    let stack = ethernet() / outer / inner / udp() / &[9u8;10][..];
+    let bytes = stack.to_bytes().unwrap();
+
+    let resolved = stack.resolved().unwrap();
+    assert_matches!(&resolved[1], Layer::Ipv4(ip) if ip_number::IPV6 == ip.protocol && 40 + 8 + 10 == ip.payload_len);
+    assert_matches!(&resolved[2], Layer::Ipv6(ip) if ip_number::UDP == ip.next_header && 8 + 10 == ip.payload_length);
+
+    // the udp checksum is calculated based on the inner ip header
+    let (_, rest) = Ipv4Header::from_slice(&bytes[14..]).unwrap();
+    let (ip, rest) = Ipv6Header::from_slice(rest).unwrap();
+    let (udp, payload) = UdpHeader::from_slice(rest).unwrap();
+    assert_eq!(18, udp.length);
+    assert_eq!(udp.calc_checksum_ipv6(&ip, payload).unwrap(), udp.checksum);
+}
+
+#[test]
+fn unresolved_fields() {
+    // without an ip header the transport checksum & unknown next headers are kept
+    let mut udp = udp();
+    udp.checksum = 0x1234;
+    let mut stack = LayerStack::new();
+    assert!(stack.is_empty());
+    stack.push(ethernet());
+    stack.push(udp.clone());
+    let stack = stack / Vec::new();
+    assert!(!stack.is_empty());
+    assert_eq!(3, stack.layers.len());
+    assert!(stack.layers[2].is_empty());
+
+    let resolved = stack.resolved().unwrap();
+    assert_eq!(Layer::Ethernet2(ethernet()), resolved[0]);
+    udp.length = 8;
+    assert_eq!(Layer::Udp(udp), resolved[1]);
+
+    // double vlan
+    let vlan = DoubleVlanHeader::default();
+    let resolved = (vlan / Ipv4Header::default()).resolved().unwrap();
+    assert_matches!(
+        &resolved[0],
+        Layer::DoubleVlan(v) if ether_type::VLAN_TAGGED_FRAME == v.outer.ether_type && ether_type::IPV4 == v.inner.ether_type
+    );
+}
+
+#[test]
+fn errors() {
+    let payload = vec![0u8; 0xffff];
+    assert_matches!(
+        (udp() / payload.clone()).to_bytes(),
+        Err(WriteError::ValueError(ValueError::UdpPayloadLengthTooLarge(0xffff)))
+    );
+    assert_matches!(
+        (Ipv4Header::default() / payload.clone()).to_bytes(),
+        Err(WriteError::ValueError(ValueError::Ipv4PayloadLengthTooLarge(_)))
+    );
+    assert_matches!(
+        (Ipv6Header::default() / payload.clone() / [1]).to_bytes(),
+        Err(WriteError::ValueError(ValueError::Ipv6PayloadLengthTooLarge(_)))
+    );
+
+    // static headers
+    assert_matches!(
+        etherparse::packet![udp {}, payload(payload.clone())].to_bytes(),
+        Err(WriteError::ValueError(ValueError::UdpPayloadLengthTooLarge(0xffff)))
+    );
+    assert_matches!(
+        etherparse::packet![ipv4 {}, payload(payload.clone())].to_bytes(),
+        Err(WriteError::ValueError(ValueError::Ipv4PayloadLengthTooLarge(_)))
+    );
+    assert_matches!(
+        etherparse::packet![ipv6 {}, payload(payload.clone()), payload([1])].to_bytes(),
+        Err(WriteError::ValueError(ValueError::Ipv6PayloadLengthTooLarge(_)))
+    );
+}
+
+#[test]
+fn packet_macro() {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .double_vlan(0x123, 0x234)
+        .ipv6([1;16], [2;16], 47)
+        .tcp(1, 2, 3, 4);
+    let mut expected = Vec::with_capacity(builder.size(3));
+    builder.write(&mut expected, &[1,2,3]).unwrap();
+
+    let destination_port = 2;
+    let stack = etherparse::packet![
+        ethernet2 { source: [1,2,3,4,5,6], destination: [7,8,9,10,11,12] },
+        double_vlan {
+            outer: SingleVlanHeader{ vlan_identifier: 0x123, tpid: ether_type::PROVIDER_BRIDGING, ..Default::default() },
+            inner: SingleVlanHeader{ vlan_identifier: 0x234, ..Default::default() },
+        },
+        ipv6 { source: [1;16], destination: [2;16], hop_limit: 47 },
+        tcp { source_port: 1, destination_port: destination_port, sequence_number: 3, window_size: 4 },
+        payload(vec![1,2,3]),
+    ];
+    assert_eq!(5, stack.layers.len());
+    assert_eq!(expected, stack.to_bytes().unwrap());
+
+    // layers without fields
+    let stack = etherparse::packet![ipv4 {}, udp {}, payload([0u8;2])];
+    assert_matches!(&stack.layers[..], [Layer::Static(_), Layer::Static(_), Layer::Payload(p)] if p.len() == 2);
+}
+
+#[test]
+fn packet_macro_static_layers() {
+    // literal only layers are serialized at compile time & produce the
+    // same bytes as the layers created at runtime
+    let one = 1;
+    let ipv4_static = etherparse::packet![
+        ethernet2 { source: [1,2,3,4,5,6], destination: [7,8,9,10,11,12] },
+        ipv4 { source: [192,168,1,1], destination: [192,168,1,2], time_to_live: 20, identification: 0x1234, dont_fragment: false, more_fragments: true, fragments_offset: 0x123 },
+        udp { source_port: 1234, destination_port: 5678 },
+        payload([1,2,3,4]),
+    ];
+    let ipv4_dynamic = etherparse::packet![
+        ethernet2 { source: [one,2,3,4,5,6], destination: [7,8,9,10,11,12] },
+        ipv4 { source: [192,168,1,one], destination: [192,168,1,2], time_to_live: 20, identification: 0x1234, dont_fragment: false, more_fragments: true, fragments_offset: 0x123 },
+        udp { source_port: 1233 + u16::from(one), destination_port: 5678 },
+        payload([1,2,3,4]),
+    ];
+    assert_matches!(&ipv4_static.layers[..3], [Layer::Static(_), Layer::Static(_), Layer::Static(_)]);
+    assert_matches!(&ipv4_dynamic.layers[..3], [Layer::Ethernet2(_), Layer::Ipv4(_), Layer::Udp(_)]);
+    assert_eq!(ipv4_dynamic.len(), ipv4_static.len());
+    assert_eq!(ipv4_dynamic.to_bytes().unwrap(), ipv4_static.to_bytes().unwrap());
+
+    let ipv6_static = etherparse::packet![
+        ethernet2 { ether_type: 0x1234 },
+        ipv6 { source: [1;16], destination: [2;16], hop_limit: 47, traffic_class: 0x12, flow_label: 0x12345 },
+        tcp { source_port: 1, destination_port: 2, sequence_number: 3, acknowledgment_number: 4, window_size: 5, urgent_pointer: 6, ns: true, syn: true, ack: true, urg: true, cwr: true },
+        payload([1,2,3]),
+    ];
+    let ipv6_dynamic = etherparse::packet![
+        ethernet2 { ether_type: 0x1233 + u16::from(one) },
+        ipv6 { source: [one;16], destination: [2;16], hop_limit: 47, traffic_class: 0x12, flow_label: 0x12345 },
+        tcp { source_port: u16::from(one), destination_port: 2, sequence_number: 3, acknowledgment_number: 4, window_size: 5, urgent_pointer: 6, ns: true, syn: true, ack: true, urg: true, cwr: true },
+        payload([1,2,3]),
+    ];
+    assert_matches!(&ipv6_static.layers[..3], [Layer::Static(_), Layer::Static(_), Layer::Static(_)]);
+    assert_matches!(&ipv6_dynamic.layers[..3], [Layer::Ethernet2(_), Layer::Ipv6(_), Layer::Tcp(_)]);
+    assert_eq!(ipv6_dynamic.to_bytes().unwrap(), ipv6_static.to_bytes().unwrap());
+
+    // next header fields of static headers get patched
+    let bytes = ipv6_static.to_bytes().unwrap();
+    assert_eq!(ether_type::IPV6.to_be_bytes(), bytes[12..14]);
+    let sliced = SlicedPacket::from_ethernet(&bytes).unwrap();
+    assert_matches!(sliced.transport, Some(TransportSlice::Tcp(_)));
+    assert_eq!(&[1,2,3], sliced.payload);
+}
+
+#[test]
+fn static_header() {
+    const BYTES: [u8;8] = [0,1,0,2,0,0,0,0];
+    let header = StaticHeader::udp(&BYTES);
+    assert_eq!(StaticHeaderKind::Udp, header.kind());
+    assert_eq!(&BYTES, header.bytes());
+    assert_eq!(8, Layer::Static(header).len());
+}
@@ -0,0 +1,211 @@
+use super::*;
+use etherparse::conntrack::*;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+const CLIENT: [u8;4] = [192,168,1,1];
+const SERVER: [u8;4] = [192,168,1,2];
+
+/// Tcp flags set in generated test packets.
+#[derive(Default)]
+struct Flags {
+    syn: bool,
+    ack: bool,
+    fin: bool,
+    rst: bool,
+}
+
+fn tcp_packet(from_client: bool, flags: Flags, payload_len: usize) -> Vec<u8> {
+    let (source, destination, source_port, destination_port) = if from_client {
+        (CLIENT, SERVER, 4321, 80)
+    } else {
+        (SERVER, CLIENT, 80, 4321)
+    };
+    let mut builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4(source, destination, 20)
+        .tcp(source_port, destination_port, 1, 1024);
+    if flags.syn { builder = builder.syn(); }
+    if flags.ack { builder = builder.ack(1); }
+    if flags.fin { builder = builder.fin(); }
+    if flags.rst { builder = builder.rst(); }
+    let mut packet = Vec::with_capacity(builder.size(payload_len));
+    builder.write(&mut packet, &vec![0;payload_len]).unwrap();
+    packet
+}
+
+fn udp_packet(from_client: bool) -> Vec<u8> {
+    let (source, destination, source_port, destination_port) = if from_client {
+        (CLIENT, SERVER, 4321, 53)
+    } else {
+        (SERVER, CLIENT, 53, 4321)
+    };
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4(source, destination, 20)
+        .udp(source_port, destination_port);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn update(table: &mut ConnTrack, packet: &[u8], secs: u64) -> Flow {
+    table.update(
+        &SlicedPacket::from_ethernet(packet).unwrap(),
+        Duration::from_secs(secs)
+    ).unwrap().clone()
+}
+
+#[test]
+fn flow_key() {
+    let a = (IpAddr::V4(Ipv4Addr::from(CLIENT)), 4321);
+    let b = (IpAddr::V4(Ipv4Addr::from(SERVER)), 80);
+
+    let (key_ab, dir_ab) = FlowKey::new(ip_number::TCP, a, b);
+    let (key_ba, dir_ba) = FlowKey::new(ip_number::TCP, b, a);
+    assert_eq!(key_ab, key_ba);
+    assert_eq!(a, key_ab.lower);
+    assert_eq!(b, key_ab.upper);
+    assert_eq!(FlowDirection::LowerToUpper, dir_ab);
+    assert_eq!(FlowDirection::UpperToLower, dir_ba);
+    assert_eq!(dir_ab, dir_ba.reverse());
+    assert_eq!(dir_ba, dir_ab.reverse());
+
+    // from sliced packet
+    {
+        let request = udp_packet(true);
+        let response = udp_packet(false);
+        let (req_key, req_dir) = FlowKey::from_sliced_packet(&SlicedPacket::from_ethernet(&request).unwrap()).unwrap();
+        let (res_key, res_dir) = FlowKey::from_sliced_packet(&SlicedPacket::from_ethernet(&response).unwrap()).unwrap();
+        assert_eq!(req_key, res_key);
+        assert_eq!(ip_number::UDP, req_key.ip_number);
+        assert_eq!(req_dir, res_dir.reverse());
+    }
+
+    // no ip header
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header{
+            source: [0;6],
+            destination: [0;6],
+            ether_type: 0x1234,
+        }.write(&mut packet).unwrap();
+        assert_eq!(None, FlowKey::from_sliced_packet(&SlicedPacket::from_ethernet(&packet).unwrap()));
+        let mut table = ConnTrack::new();
+        assert!(table.update(&SlicedPacket::from_ethernet(&packet).unwrap(), Duration::from_secs(0)).is_none());
+        assert!(table.is_empty());
+    }
+}
+
+#[test]
+fn tcp_lifecycle() {
+    use TcpState::*;
+
+    let mut table = ConnTrack::new();
+
+    let syn = tcp_packet(true, Flags{ syn: true, ..Default::default() }, 0);
+    let flow = update(&mut table, &syn, 1);
+    assert_eq!(Some(SynSent), flow.tcp_state);
+    assert_eq!(1, table.len());
+
+    let syn_ack = tcp_packet(false, Flags{ syn: true, ack: true, ..Default::default() }, 0);
+    assert_eq!(Some(SynReceived), update(&mut table, &syn_ack, 2).tcp_state);
+
+    let ack = tcp_packet(true, Flags{ ack: true, ..Default::default() }, 0);
+    assert_eq!(Some(Established), update(&mut table, &ack, 3).tcp_state);
+
+    let data = tcp_packet(false, Flags{ ack: true, ..Default::default() }, 100);
+    let flow = update(&mut table, &data, 4);
+    assert_eq!(Some(Established), flow.tcp_state);
+    assert_eq!(Duration::from_secs(1), flow.first_seen);
+    assert_eq!(Duration::from_secs(4), flow.last_seen);
+    assert_eq!(2, flow.originator.packets);
+    assert_eq!((syn.len() + ack.len()) as u64, flow.originator.bytes);
+    assert_eq!(2, flow.responder.packets);
+    assert_eq!((syn_ack.len() + data.len()) as u64, flow.responder.bytes);
+
+    let fin_client = tcp_packet(true, Flags{ fin: true, ack: true, ..Default::default() }, 0);
+    assert_eq!(Some(FinWait), update(&mut table, &fin_client, 5).tcp_state);
+    // repeated fin from the same side
+    assert_eq!(Some(FinWait), update(&mut table, &fin_client, 5).tcp_state);
+
+    let fin_server = tcp_packet(false, Flags{ fin: true, ack: true, ..Default::default() }, 0);
+    assert_eq!(Some(Closed), update(&mut table, &fin_server, 6).tcp_state);
+
+    // closed connection gets reused
+    let flow = update(&mut table, &syn, 7);
+    assert_eq!(Some(SynSent), flow.tcp_state);
+    assert_eq!(Duration::from_secs(7), flow.first_seen);
+    assert_eq!(1, flow.originator.packets);
+    assert_eq!(0, flow.responder.packets);
+
+    // reset
+    let rst = tcp_packet(false, Flags{ rst: true, ..Default::default() }, 0);
+    assert_eq!(Some(Closed), update(&mut table, &rst, 8).tcp_state);
+    assert_eq!(1, table.len());
+}
+
+#[test]
+fn tcp_pickup() {
+    // connections where the handshake was not seen are considered established
+    let mut table = ConnTrack::new();
+    let data = tcp_packet(false, Flags{ ack: true, ..Default::default() }, 10);
+    let flow = update(&mut table, &data, 0);
+    assert_eq!(Some(TcpState::Established), flow.tcp_state);
+    assert!(flow.is_from_originator(
+        FlowKey::from_sliced_packet(&SlicedPacket::from_ethernet(&data).unwrap()).unwrap().1
+    ));
+
+    // a new connection starting with a reset is directly closed
+    let mut table = ConnTrack::new();
+    let rst = tcp_packet(false, Flags{ rst: true, ..Default::default() }, 0);
+    assert_eq!(Some(TcpState::Closed), update(&mut table, &rst, 0).tcp_state);
+}
+
+#[test]
+fn udp_expire() {
+    let timeouts = ConnTrackTimeouts{
+        udp: Duration::from_secs(10),
+        ..Default::default()
+    };
+    let mut table = ConnTrack::with_timeouts(timeouts);
+    assert_eq!(&timeouts, table.timeouts());
+
+    let request = udp_packet(true);
+    let response = udp_packet(false);
+    let flow = update(&mut table, &request, 1);
+    assert_eq!(None, flow.tcp_state);
+    assert_eq!(Duration::from_secs(10), flow.timeout(&timeouts));
+    let flow = update(&mut table, &response, 2);
+    assert_eq!(1, flow.originator.packets);
+    assert_eq!(1, flow.responder.packets);
+
+    // not yet expired
+    assert!(table.expire(Duration::from_secs(12)).is_empty());
+    assert_eq!(1, table.iter().count());
+
+    // expired flows get restarted on update
+    {
+        let mut restarted = table.clone();
+        let flow = update(&mut restarted, &response, 13);
+        assert_eq!(Duration::from_secs(13), flow.first_seen);
+        assert_eq!(1, flow.originator.packets);
+        assert_eq!(0, flow.responder.packets);
+    }
+
+    // expire
+    let expired = table.expire(Duration::from_secs(13));
+    assert_eq!(1, expired.len());
+    assert!(table.is_empty());
+    assert_eq!(None, table.get(&expired[0].key));
+}
+
+#[test]
+fn get_remove() {
+    let mut table = ConnTrack::new();
+    let flow = update(&mut table, &udp_packet(true), 1);
+    assert_eq!(Some(&flow), table.get(&flow.key));
+    assert_eq!(Some(flow.clone()), table.remove(&flow.key));
+    assert_eq!(None, table.remove(&flow.key));
+    assert_eq!(0, table.len());
+}
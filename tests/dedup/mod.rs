@@ -0,0 +1,160 @@
+use super::*;
+use etherparse::dedup::*;
+use std::time::Duration;
+
+fn ipv4_udp(ttl: u8, payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], ttl)
+        .udp(53, 1234);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, payload).unwrap();
+    packet
+}
+
+fn ipv6_tcp(hop_limit: u8, payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(12)
+        .ipv6([0;16], [1;16], hop_limit)
+        .tcp(80, 4321, 1, 1024);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, payload).unwrap();
+    packet
+}
+
+fn hash(packet: &[u8], config: &DedupConfig) -> u64 {
+    normalized_hash(&SlicedPacket::from_ethernet(packet).unwrap(), config)
+}
+
+#[test]
+fn normalized_hash_ignored_fields() {
+    let config = DedupConfig::default();
+
+    // ttl & header checksum
+    assert_eq!(hash(&ipv4_udp(20, &[1,2,3]), &config), hash(&ipv4_udp(21, &[1,2,3]), &config));
+    // hop limit
+    assert_eq!(hash(&ipv6_tcp(20, &[1,2,3]), &config), hash(&ipv6_tcp(21, &[1,2,3]), &config));
+
+    // transport checksums
+    {
+        let a = ipv4_udp(20, &[1,2,3]);
+        let mut b = a.clone();
+        let udp_start = Ethernet2Header::SERIALIZED_SIZE + Ipv4Header::SERIALIZED_SIZE;
+        b[udp_start + 6] = !b[udp_start + 6];
+        assert_eq!(hash(&a, &config), hash(&b, &config));
+
+        let a = ipv6_tcp(20, &[1,2,3]);
+        let mut b = a.clone();
+        let tcp_start = Ethernet2Header::SERIALIZED_SIZE + SingleVlanHeader::SERIALIZED_SIZE + Ipv6Header::SERIALIZED_SIZE;
+        b[tcp_start + 16] = !b[tcp_start + 16];
+        assert_eq!(hash(&a, &config), hash(&b, &config));
+    }
+
+    // padding & fcs after the ip payload
+    {
+        let a = ipv4_udp(20, &[1,2,3]);
+        let mut b = a.clone();
+        b.extend_from_slice(&[0xde,0xad,0xbe,0xef]);
+        assert_eq!(hash(&a, &config), hash(&b, &config));
+
+        let a = ipv6_tcp(20, &[1,2,3]);
+        let mut b = a.clone();
+        b.extend_from_slice(&[0xde,0xad,0xbe,0xef]);
+        assert_eq!(hash(&a, &config), hash(&b, &config));
+    }
+
+    // link & vlan header
+    {
+        let a = ipv6_tcp(20, &[1,2,3]);
+        let mut b = a.clone();
+        b[0] = 0xff; // mac
+        b[Ethernet2Header::SERIALIZED_SIZE + 1] = 13; // vlan id
+        assert_eq!(hash(&a, &config), hash(&b, &config));
+
+        let with_link = DedupConfig{ include_link: true, ..Default::default() };
+        assert_ne!(hash(&a, &with_link), hash(&b, &with_link));
+        let with_vlan = DedupConfig{ include_vlan: true, ..Default::default() };
+        assert_ne!(hash(&a, &with_vlan), hash(&b, &with_vlan));
+    }
+}
+
+#[test]
+fn normalized_hash_relevant_fields() {
+    let config = DedupConfig::default();
+    assert_ne!(hash(&ipv4_udp(20, &[1,2,3]), &config), hash(&ipv4_udp(20, &[1,2,4]), &config));
+    assert_ne!(hash(&ipv6_tcp(20, &[1,2,3]), &config), hash(&ipv6_tcp(20, &[1,2,4]), &config));
+    assert_ne!(hash(&ipv4_udp(20, &[1,2,3]), &config), hash(&ipv6_tcp(20, &[1,2,3]), &config));
+
+    // truncated payload
+    let truncated = DedupConfig{ max_payload_len: Some(2), ..Default::default() };
+    assert_eq!(hash(&ipv4_udp(20, &[1,2,3]), &truncated), hash(&ipv4_udp(20, &[1,2,4]), &truncated));
+    assert_ne!(hash(&ipv4_udp(20, &[1,2,3]), &truncated), hash(&ipv4_udp(20, &[1,3,3]), &truncated));
+}
+
+#[test]
+fn deduplicator_window_len() {
+    let config = DedupConfig{
+        window_len: 2,
+        max_age: None,
+        ..Default::default()
+    };
+    let mut dedup = Deduplicator::new(config);
+    assert_eq!(&config, dedup.config());
+    assert!(dedup.is_empty());
+
+    let a = ipv4_udp(20, &[1]);
+    let b = ipv4_udp(20, &[2]);
+    let c = ipv4_udp(20, &[3]);
+    let t = Duration::from_secs(0);
+
+    assert!(!dedup.is_duplicate_ethernet(&a, t));
+    assert!(dedup.is_duplicate_ethernet(&ipv4_udp(30, &[1]), t));
+    assert_eq!(2, dedup.len());
+    assert!(!dedup.is_duplicate_ethernet(&b, t));
+    assert!(!dedup.is_duplicate_ethernet(&c, t));
+    // a was pushed out of the window
+    assert!(!dedup.is_duplicate_ethernet(&a, t));
+    assert_eq!(2, dedup.len());
+
+    dedup.clear();
+    assert!(dedup.is_empty());
+    assert!(!dedup.is_duplicate_ethernet(&a, t));
+
+    // zero window length never detects duplicates
+    let mut dedup = Deduplicator::new(DedupConfig{ window_len: 0, ..config });
+    assert!(!dedup.is_duplicate_ethernet(&a, t));
+    assert!(!dedup.is_duplicate_ethernet(&a, t));
+}
+
+#[test]
+fn deduplicator_max_age() {
+    let mut dedup = Deduplicator::new(DedupConfig{
+        max_age: Some(Duration::from_millis(10)),
+        ..Default::default()
+    });
+    let a = ipv4_udp(20, &[1]);
+    assert!(!dedup.is_duplicate_ethernet(&a, Duration::from_millis(0)));
+    assert!(dedup.is_duplicate_ethernet(&a, Duration::from_millis(10)));
+    // the first packet is too old but the second one is still in the window
+    assert!(dedup.is_duplicate_ethernet(&a, Duration::from_millis(15)));
+    assert_eq!(2, dedup.len());
+    assert!(!dedup.is_duplicate_ethernet(&a, Duration::from_millis(100)));
+    assert_eq!(1, dedup.len());
+}
+
+#[test]
+fn deduplicator_slice_variants() {
+    let mut dedup = Deduplicator::new(Default::default());
+    let t = Duration::from_secs(0);
+    let a = ipv4_udp(20, &[1]);
+    assert!(!dedup.is_duplicate(&SlicedPacket::from_ethernet(&a).unwrap(), t));
+    // ip only packet is detected as duplicate as link headers are ignored
+    assert!(dedup.is_duplicate_ip(&a[Ethernet2Header::SERIALIZED_SIZE..], t));
+
+    // packets that can not be sliced are compared by their raw bytes
+    assert!(!dedup.is_duplicate_ethernet(&[1,2,3], t));
+    assert!(dedup.is_duplicate_ethernet(&[1,2,3], t));
+    assert!(!dedup.is_duplicate_ip(&[1,2,4], t));
+    assert!(dedup.is_duplicate_ip(&[1,2,4], t));
+}
@@ -0,0 +1,200 @@
+use etherparse::dhcp::*;
+
+#[test]
+fn relay_agent_information_read_write() {
+    let data = [
+        dhcp_option_code::RELAY_AGENT_INFORMATION, 12,
+        relay_agent_sub_option_code::CIRCUIT_ID, 4, b'e', b't', b'h', b'0',
+        relay_agent_sub_option_code::REMOTE_ID, 2, 0xab, 0xcd,
+        9, 0,
+    ];
+    let (option, rest) = DhcpOption::from_slice(&data).unwrap();
+    assert!(rest.is_empty());
+    let expected = RelayAgentInformation{
+        sub_options: vec![
+            RelayAgentSubOption::CircuitId(b"eth0".to_vec()),
+            RelayAgentSubOption::RemoteId(vec![0xab, 0xcd]),
+            RelayAgentSubOption::Unknown{ code: 9, data: Vec::new() },
+        ],
+    };
+    assert_eq!(DhcpOption::RelayAgentInformation(expected.clone()), option);
+    assert_eq!(Some(&b"eth0"[..]), expected.circuit_id());
+    assert_eq!(Some(&[0xab, 0xcd][..]), expected.remote_id());
+
+    let mut buffer = Vec::new();
+    option.write(&mut buffer).unwrap();
+    assert_eq!(&data[..], &buffer[..]);
+}
+
+#[test]
+fn relay_agent_information_errors() {
+    use DhcpOptionError::*;
+
+    // no sub-options
+    assert_eq!(
+        Err(InvalidLength{ code: dhcp_option_code::RELAY_AGENT_INFORMATION, length: 0 }),
+        DhcpOption::from_slice(&[dhcp_option_code::RELAY_AGENT_INFORMATION, 0])
+    );
+    // empty circuit id
+    assert_eq!(
+        Err(InvalidLength{ code: relay_agent_sub_option_code::CIRCUIT_ID, length: 0 }),
+        DhcpOption::from_slice(&[dhcp_option_code::RELAY_AGENT_INFORMATION, 2, relay_agent_sub_option_code::CIRCUIT_ID, 0])
+    );
+    // sub-option length exceeds the option
+    assert_eq!(
+        Err(UnexpectedEnd(7)),
+        DhcpOption::from_slice(&[dhcp_option_code::RELAY_AGENT_INFORMATION, 3, relay_agent_sub_option_code::REMOTE_ID, 3, 1])
+    );
+    // sub-option header incomplete
+    assert_eq!(
+        Err(UnexpectedEnd(4)),
+        DhcpOption::from_slice(&[dhcp_option_code::RELAY_AGENT_INFORMATION, 1, relay_agent_sub_option_code::REMOTE_ID])
+    );
+
+    // sub-option too large to write
+    let mut buffer = vec![1];
+    assert_eq!(
+        Err(ValueTooLarge{ code: relay_agent_sub_option_code::CIRCUIT_ID, length: 256 }),
+        DhcpOption::RelayAgentInformation(RelayAgentInformation{
+            sub_options: vec![RelayAgentSubOption::CircuitId(vec![0;256])],
+        }).write(&mut buffer)
+    );
+    // option too large to write
+    assert_eq!(
+        Err(ValueTooLarge{ code: dhcp_option_code::RELAY_AGENT_INFORMATION, length: 2*(2 + 200) }),
+        DhcpOption::RelayAgentInformation(RelayAgentInformation{
+            sub_options: vec![
+                RelayAgentSubOption::CircuitId(vec![0;200]),
+                RelayAgentSubOption::RemoteId(vec![0;200]),
+            ],
+        }).write(&mut buffer)
+    );
+    // nothing written on error
+    assert_eq!(vec![1], buffer);
+}
+
+#[test]
+fn classless_static_route_read_write() {
+    let data = [
+        dhcp_option_code::CLASSLESS_STATIC_ROUTE, 19,
+        // default route
+        0, 192, 168, 1, 1,
+        // 10.0.0.0/8
+        8, 10, 192, 168, 1, 2,
+        // 172.16.128.0/17
+        17, 172, 16, 128, 192, 168, 1, 3,
+    ];
+    let (option, rest) = DhcpOption::from_slice(&data).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        DhcpOption::ClasslessStaticRoute(vec![
+            ClasslessRoute{ destination: [0, 0, 0, 0], prefix_len: 0, router: [192, 168, 1, 1] },
+            ClasslessRoute{ destination: [10, 0, 0, 0], prefix_len: 8, router: [192, 168, 1, 2] },
+            ClasslessRoute{ destination: [172, 16, 128, 0], prefix_len: 17, router: [192, 168, 1, 3] },
+        ]),
+        option
+    );
+
+    let mut buffer = Vec::new();
+    option.write(&mut buffer).unwrap();
+    assert_eq!(&data[..], &buffer[..]);
+}
+
+#[test]
+fn classless_static_route_host_bits_cleared() {
+    let route = ClasslessRoute{ destination: [10, 1, 2, 3], prefix_len: 12, router: [1, 2, 3, 4] };
+    assert_eq!(7, route.encoded_len());
+    let mut buffer = Vec::new();
+    route.write(&mut buffer).unwrap();
+    assert_eq!(vec![12, 10, 0, 1, 2, 3, 4], buffer);
+
+    let route = ClasslessRoute{ destination: [10, 1, 2, 3], prefix_len: 32, router: [1, 2, 3, 4] };
+    buffer.clear();
+    route.write(&mut buffer).unwrap();
+    assert_eq!(vec![32, 10, 1, 2, 3, 1, 2, 3, 4], buffer);
+}
+
+#[test]
+fn classless_static_route_errors() {
+    use DhcpOptionError::*;
+
+    // shorter than a default route
+    assert_eq!(
+        Err(InvalidLength{ code: dhcp_option_code::CLASSLESS_STATIC_ROUTE, length: 4 }),
+        DhcpOption::from_slice(&[dhcp_option_code::CLASSLESS_STATIC_ROUTE, 4, 0, 1, 2, 3])
+    );
+    // prefix length too large
+    assert_eq!(
+        Err(InvalidPrefixLength(33)),
+        DhcpOption::from_slice(&[dhcp_option_code::CLASSLESS_STATIC_ROUTE, 9, 33, 1, 2, 3, 4, 1, 2, 3, 4])
+    );
+    // route exceeds the option
+    assert_eq!(
+        Err(UnexpectedEnd(10)),
+        DhcpOption::from_slice(&[dhcp_option_code::CLASSLESS_STATIC_ROUTE, 6, 24, 10, 0, 0, 1, 2])
+    );
+    // prefix length too large on write
+    let mut buffer = Vec::new();
+    assert_eq!(
+        Err(InvalidPrefixLength(40)),
+        DhcpOption::ClasslessStaticRoute(vec![
+            ClasslessRoute{ destination: [0;4], prefix_len: 40, router: [0;4] },
+        ]).write(&mut buffer)
+    );
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn other_option_read_write() {
+    let data = [dhcp_option_code::MESSAGE_TYPE, 1, 1, 0xff];
+    let (option, rest) = DhcpOption::from_slice(&data).unwrap();
+    assert_eq!(DhcpOption::Other{ code: dhcp_option_code::MESSAGE_TYPE, data: &[1] }, option);
+    assert_eq!(&[0xff], rest);
+    let mut buffer = Vec::new();
+    option.write(&mut buffer).unwrap();
+    assert_eq!(&data[..3], &buffer[..]);
+
+    assert_eq!(Err(DhcpOptionError::UnexpectedEnd(2)), DhcpOption::from_slice(&[1]));
+    assert_eq!(Err(DhcpOptionError::UnexpectedEnd(6)), DhcpOption::from_slice(&[1, 4, 0]));
+}
+
+#[test]
+fn iterator() {
+    let data = [
+        dhcp_option_code::PAD,
+        dhcp_option_code::MESSAGE_TYPE, 1, 5,
+        dhcp_option_code::PAD,
+        dhcp_option_code::CLASSLESS_STATIC_ROUTE, 5, 0, 192, 168, 1, 1,
+        dhcp_option_code::END,
+        // ignored after the end option
+        1, 2, 3,
+    ];
+    let mut iter = DhcpOptionsIterator::from_slice(&data);
+    assert_eq!(Some(Ok(DhcpOption::Other{ code: dhcp_option_code::MESSAGE_TYPE, data: &[5] })), iter.next());
+    assert_eq!(
+        Some(Ok(DhcpOption::ClasslessStaticRoute(vec![
+            ClasslessRoute{ destination: [0;4], prefix_len: 0, router: [192, 168, 1, 1] },
+        ]))),
+        iter.next()
+    );
+    assert_eq!(None, iter.next());
+    assert_eq!(None, iter.next());
+
+    // the iteration stops after an error
+    let mut iter = DhcpOptionsIterator::from_slice(&[dhcp_option_code::MESSAGE_TYPE, 2, 5]);
+    assert_eq!(Some(Err(DhcpOptionError::UnexpectedEnd(4))), iter.next());
+    assert_eq!(None, iter.next());
+    assert!(iter.rest().is_empty());
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(
+        "DhcpOptionError: Prefix length 33 of classless static route is larger than 32.",
+        format!("{}", DhcpOptionError::InvalidPrefixLength(33))
+    );
+    assert_eq!(
+        "DhcpOptionError: Length 0 is not valid for options with the code 82.",
+        format!("{}", DhcpOptionError::InvalidLength{ code: 82, length: 0 })
+    );
+}
@@ -0,0 +1,134 @@
+use super::*;
+use etherparse::differential::*;
+
+fn udp_ipv4_packet() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .double_vlan(0x123, 0x234)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn tcp_ipv6_packet() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv6([1;16], [2;16], 47)
+        .tcp(1000, 80, 100, 1024)
+        .syn()
+        .ack(23);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+/// Tcp packet with a data offset that is too small (pnet still decodes
+/// the tcp header, etherparse does not).
+fn divergent_packet() -> Vec<u8> {
+    let mut packet = tcp_ipv6_packet();
+    let data_offset = 14 + 40 + 12;
+    packet[data_offset] = (4 << 4) | (packet[data_offset] & 0xf);
+    packet
+}
+
+#[test]
+fn decode() {
+    let packet = udp_ipv4_packet();
+    let fields = EtherparseDecoder.decode(&packet);
+    assert_eq!("etherparse", EtherparseDecoder.name());
+    assert_eq!("pnet", PnetDecoder.name());
+    assert_eq!(Some(&"01:02:03:04:05:06".to_string()), fields.get("ethernet2.source"));
+    assert_eq!(Some(&"291".to_string()), fields.get("vlan0.vlan_identifier"));
+    assert_eq!(Some(&"564".to_string()), fields.get("vlan1.vlan_identifier"));
+    assert_eq!(Some(&"192.168.1.2".to_string()), fields.get("ipv4.destination"));
+    assert_eq!(Some(&"1234".to_string()), fields.get("udp.destination_port"));
+    assert_eq!(fields, PnetDecoder.decode(&packet));
+
+    let packet = tcp_ipv6_packet();
+    let fields = EtherparseDecoder.decode(&packet);
+    assert_eq!(Some(&"47".to_string()), fields.get("ipv6.hop_limit"));
+    assert_eq!(Some(&"18".to_string()), fields.get("tcp.flags"));
+    assert_eq!(fields, PnetDecoder.decode(&packet));
+}
+
+#[test]
+fn no_divergence() {
+    assert!(diff(&udp_ipv4_packet(), &PnetDecoder).is_empty());
+    assert!(diff(&tcp_ipv6_packet(), &PnetDecoder).is_empty());
+
+    // truncated packets keep the layers decoded before the error
+    let packet = tcp_ipv6_packet();
+    for len in 0..packet.len() {
+        assert_eq!(Vec::<Divergence>::new(), diff(&packet[..len], &PnetDecoder), "len {}", len);
+    }
+    let packet = udp_ipv4_packet();
+    for len in 0..packet.len() {
+        assert_eq!(Vec::<Divergence>::new(), diff(&packet[..len], &PnetDecoder), "len {}", len);
+    }
+}
+
+#[test]
+fn divergence() {
+    let packet = divergent_packet();
+    let result = diff(&packet, &PnetDecoder);
+    assert_eq!(9, result.len());
+    assert_eq!(
+        Divergence{
+            field: "tcp.acknowledgment_number".to_string(),
+            etherparse: None,
+            reference: Some("23".to_string()),
+        },
+        result[0]
+    );
+    assert!(result.iter().all(|d| d.field.starts_with("tcp.")));
+    assert_eq!(
+        "tcp.acknowledgment_number: etherparse=<missing> reference=23",
+        format!("{}", result[0])
+    );
+}
+
+#[test]
+fn compare_fields() {
+    let mut a = Fields::new();
+    a.insert("a.x".to_string(), "1".to_string());
+    a.insert("a.y".to_string(), "2".to_string());
+    let mut b = Fields::new();
+    b.insert("a.x".to_string(), "1".to_string());
+    b.insert("a.y".to_string(), "3".to_string());
+    b.insert("b.z".to_string(), "4".to_string());
+    assert_eq!(
+        vec![
+            Divergence{ field: "a.y".to_string(), etherparse: Some("2".to_string()), reference: Some("3".to_string()) },
+            Divergence{ field: "b.z".to_string(), etherparse: None, reference: Some("4".to_string()) },
+        ],
+        compare(&a, &b)
+    );
+    assert!(compare(&a, &a).is_empty());
+    assert_eq!(
+        "b.z: etherparse=<missing> reference=4",
+        format!("{}", compare(&a, &b)[1])
+    );
+}
+
+#[test]
+fn replay() {
+    let dir = std::env::temp_dir().join(format!("etherparse_differential_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("subdir")).unwrap();
+    std::fs::write(dir.join("a"), udp_ipv4_packet()).unwrap();
+    let divergent = divergent_packet();
+    std::fs::write(dir.join("b"), &divergent).unwrap();
+    std::fs::write(dir.join("c"), tcp_ipv6_packet()).unwrap();
+
+    let report = replay_dir(&dir, &PnetDecoder).unwrap();
+    assert_eq!(3, report.inputs);
+    assert_eq!(1, report.divergent.len());
+    assert_eq!(dir.join("b"), report.divergent[0].0);
+    assert_eq!(diff(&divergent, &PnetDecoder), report.divergent[0].1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // missing directory
+    assert!(replay_dir(&dir, &PnetDecoder).is_err());
+}
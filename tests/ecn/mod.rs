@@ -0,0 +1,109 @@
+use super::*;
+use etherparse::ecn::*;
+
+fn ipv4_packet(ecn: u8) -> Vec<u8> {
+    let mut header = Ipv4Header::new(4, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2]);
+    header.differentiated_services_code_point = Dscp::EF;
+    header.explicit_congestion_notification = Ecn::try_new(ecn).unwrap();
+    header.header_checksum = header.calc_header_checksum().unwrap();
+    let mut packet = Vec::new();
+    header.write(&mut packet).unwrap();
+    packet.extend_from_slice(&[1,2,3,4]);
+    packet
+}
+
+fn ipv6_packet(traffic_class: u8) -> Vec<u8> {
+    let header = Ipv6Header{
+        traffic_class,
+        flow_label: 0x12345,
+        payload_length: 0,
+        next_header: ip_number::UDP,
+        hop_limit: 64,
+        source: [1;16],
+        destination: [2;16],
+    };
+    let mut packet = Vec::new();
+    header.write(&mut packet).unwrap();
+    packet
+}
+
+#[test]
+fn ipv4_read_set() {
+    for old in 0..4u8 {
+        for new in [Ecn::NotEct, Ecn::Ect1, Ecn::Ect0, Ecn::Ce].iter() {
+            let mut packet = ipv4_packet(old);
+            assert_eq!(Ecn::from_bits(old), read_ecn(&packet).unwrap());
+            assert_eq!(Ecn::from_bits(old), set_ecn(&mut packet, *new).unwrap());
+            assert_eq!(*new, read_ecn(&packet).unwrap());
+            assert_eq!(ipv4_packet(*new as u8), packet);
+        }
+    }
+}
+
+#[test]
+fn ipv6_read_set() {
+    for old in 0..4u8 {
+        for new in [Ecn::NotEct, Ecn::Ect1, Ecn::Ect0, Ecn::Ce].iter() {
+            let mut packet = ipv6_packet(0b1011_1000 | old);
+            assert_eq!(Ecn::from_bits(old), read_ecn(&packet).unwrap());
+            assert_eq!(Ecn::from_bits(old), set_ecn(&mut packet, *new).unwrap());
+            assert_eq!(*new, read_ecn(&packet).unwrap());
+            assert_eq!(ipv6_packet(0b1011_1000 | *new as u8), packet);
+        }
+    }
+}
+
+#[test]
+fn mark_ce_packets() {
+    let mut packet = ipv4_packet(Ecn::Ect0 as u8);
+    assert!(mark_ce(&mut packet).unwrap());
+    assert_eq!(ipv4_packet(Ecn::Ce as u8), packet);
+
+    let mut packet = ipv6_packet(Ecn::Ect1 as u8);
+    assert!(mark_ce(&mut packet).unwrap());
+    assert_eq!(ipv6_packet(Ecn::Ce as u8), packet);
+
+    // not ecn capable packets stay unchanged
+    let mut packet = ipv4_packet(Ecn::NotEct as u8);
+    assert!(!mark_ce(&mut packet).unwrap());
+    assert_eq!(ipv4_packet(Ecn::NotEct as u8), packet);
+}
+
+#[test]
+fn errors() {
+    let mut empty: [u8;0] = [];
+    assert_matches!(read_ecn(&empty), Err(ReadError::UnexpectedEndOfSlice(1)));
+    assert_matches!(set_ecn(&mut empty, Ecn::Ce), Err(ReadError::UnexpectedEndOfSlice(1)));
+
+    let packet = ipv4_packet(0);
+    assert_matches!(read_ecn(&packet[..19]), Err(ReadError::UnexpectedEndOfSlice(20)));
+    assert_matches!(read_ecn(&[0x44, 0]), Err(ReadError::Ipv4HeaderLengthBad(4)));
+
+    let packet = ipv6_packet(0);
+    assert_matches!(read_ecn(&packet[..39]), Err(ReadError::UnexpectedEndOfSlice(40)));
+
+    assert_matches!(read_ecn(&[0x50, 0]), Err(ReadError::IpUnsupportedVersion(5)));
+}
+
+#[test]
+fn tcp_ecn_flags() {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 64)
+        .tcp(80, 1234, 1, 1024)
+        .ack(2);
+    let mut packet = Vec::new();
+    builder.write(&mut packet, &[1,2,3]).unwrap();
+
+    let (ip, _) = Ipv4Header::from_slice(&packet).unwrap();
+    let tcp_start = ip.header_len();
+    for (ece, cwr) in [(true, false), (false, true), (true, true), (false, false)].iter() {
+        set_tcp_ecn_flags(&mut packet[tcp_start..], *ece, *cwr).unwrap();
+        let (tcp, payload) = TcpHeader::from_slice(&packet[tcp_start..]).unwrap();
+        assert_eq!(*ece, tcp.ece);
+        assert_eq!(*cwr, tcp.cwr);
+        assert!(tcp.ack);
+        assert_eq!(tcp.checksum, tcp.calc_checksum_ipv4(&ip, payload).unwrap());
+    }
+
+    assert_matches!(set_tcp_ecn_flags(&mut packet[tcp_start..tcp_start + 19], true, false), Err(ReadError::UnexpectedEndOfSlice(20)));
+}
@@ -85,6 +85,90 @@ proptest! {
             &format!("ReadError: TCP data offset too small. The data offset value {} in the tcp header is smaller then the tcp header itself.", arg_u8),
             &format!("{}", TcpDataOffsetTooSmall(arg_u8))
         );
+
+        //Ieee802154UnsupportedFrameType
+        assert_eq!(
+            &format!("ReadError: Unsupported IEEE 802.15.4 frame type {}.", arg_u8),
+            &format!("{}", Ieee802154UnsupportedFrameType(arg_u8))
+        );
+
+        //Ieee802154ReservedAddressingMode
+        assert_eq!(
+            &format!("ReadError: Reserved IEEE 802.15.4 addressing mode {}.", arg_u8),
+            &format!("{}", Ieee802154ReservedAddressingMode(arg_u8))
+        );
+
+        //ParseLimitExceeded
+        assert_eq!(
+            "ReadError: Parse limit exceeded. The packet exceeds the configured limit VlanDepth.",
+            &format!("{}", ParseLimitExceeded(ParseLimit::VlanDepth))
+        );
+
+        //GreUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported GRE version {}. Only GRE headers with the version 0 are supported.", arg_u8),
+            &format!("{}", GreUnsupportedVersion(arg_u8))
+        );
+
+        //GeneveOptionLengthBad
+        assert_eq!(
+            &format!("ReadError: Geneve option length {} bad. The option exceeds the options area of the Geneve header.", arg_u8),
+            &format!("{}", GeneveOptionLengthBad(arg_u8))
+        );
+
+        //GtpuUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported GTP-U version {}. Only GTP-U headers with the version 1 are supported.", arg_u8),
+            &format!("{}", GtpuUnsupportedVersion(arg_u8))
+        );
+
+        //GtpuExtensionHeaderLengthZero
+        assert_eq!(
+            "ReadError: GTP-U extension header with a length of 0 encountered.",
+            &format!("{}", GtpuExtensionHeaderLengthZero)
+        );
+
+        //Ieee80211UnsupportedFrameType
+        assert_eq!(
+            &format!("ReadError: Unsupported IEEE 802.11 frame type {}.", arg_u8),
+            &format!("{}", Ieee80211UnsupportedFrameType(arg_u8))
+        );
+
+        //StpUnknownBpduType
+        assert_eq!(
+            &format!("ReadError: Unknown spanning tree BPDU type {}.", arg_u8),
+            &format!("{}", StpUnknownBpduType(arg_u8))
+        );
+
+        //LacpUnexpectedSubtype
+        assert_eq!(
+            &format!("ReadError: Unexpected slow protocol subtype {}. Expected the LACP subtype 1.", arg_u8),
+            &format!("{}", LacpUnexpectedSubtype(arg_u8))
+        );
+
+        //GueUnsupportedVariant
+        assert_eq!(
+            &format!("ReadError: Unsupported GUE variant {}. Only GUE headers with the variant 0 can be decoded.", arg_u8),
+            &format!("{}", GueUnsupportedVariant(arg_u8))
+        );
+
+        //EspPadLengthTooLarge
+        assert_eq!(
+            &format!("ReadError: ESP pad length {} is larger then the data preceding the ESP trailer.", arg_u8),
+            &format!("{}", EspPadLengthTooLarge(arg_u8))
+        );
+
+        //Ipv6RoutingHeaderLengthBad
+        assert_eq!(
+            &format!("ReadError: The length of the IPv6 routing header with the routing type {} does not match the contained addresses.", arg_u8),
+            &format!("{}", Ipv6RoutingHeaderLengthBad(arg_u8))
+        );
+
+        //Icmpv6RouterRenumberingOpLengthBad
+        assert_eq!(
+            &format!("ReadError: The length {} of an ICMPv6 router renumbering prefix control operation is not of the form 4N+3.", arg_u8),
+            &format!("{}", Icmpv6RouterRenumberingOpLengthBad(arg_u8))
+        );
     }
 }
 
@@ -111,6 +195,20 @@ fn read_error_source() {
         Ipv6HopByHopHeaderNotAtStart,
         IpAuthenticationHeaderTooSmallPayloadLength(0),
         TcpDataOffsetTooSmall(0),
+        Ieee802154UnsupportedFrameType(0),
+        Ieee802154ReservedAddressingMode(0),
+        ParseLimitExceeded(ParseLimit::OptionBytes),
+        GreUnsupportedVersion(0),
+        GeneveOptionLengthBad(0),
+        GtpuUnsupportedVersion(0),
+        GtpuExtensionHeaderLengthZero,
+        Ieee80211UnsupportedFrameType(0),
+        StpUnknownBpduType(0),
+        LacpUnexpectedSubtype(0),
+        GueUnsupportedVariant(0),
+        EspPadLengthTooLarge(0),
+        Ipv6RoutingHeaderLengthBad(0),
+        Icmpv6RouterRenumberingOpLengthBad(0),
     ];
 
     for value in &none_values {
@@ -135,6 +233,20 @@ fn read_error_debug() {
         Ipv6HopByHopHeaderNotAtStart,
         IpAuthenticationHeaderTooSmallPayloadLength(0),
         TcpDataOffsetTooSmall(0),
+        Ieee802154UnsupportedFrameType(0),
+        Ieee802154ReservedAddressingMode(0),
+        ParseLimitExceeded(ParseLimit::OptionBytes),
+        GreUnsupportedVersion(0),
+        GeneveOptionLengthBad(0),
+        GtpuUnsupportedVersion(0),
+        GtpuExtensionHeaderLengthZero,
+        Ieee80211UnsupportedFrameType(0),
+        StpUnknownBpduType(0),
+        LacpUnexpectedSubtype(0),
+        GueUnsupportedVariant(0),
+        EspPadLengthTooLarge(0),
+        Ipv6RoutingHeaderLengthBad(0),
+        Icmpv6RouterRenumberingOpLengthBad(0),
     ];
 
     for value in &values {
@@ -216,9 +328,25 @@ fn value_error_source() {
         Ipv6ExtensionNotDefinedReference(IpNumber::Icmp),
         UdpPayloadLengthTooLarge(0),
         TcpLengthTooLarge(0),
-        U8TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
-        U16TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
-        U32TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
+        U8TooLarge{ value:0, max:0, field:ErrorField::Dscp },
+        U16TooLarge{ value:0, max:0, field:ErrorField::Dscp },
+        U32TooLarge{ value:0, max:0, field:ErrorField::Dscp },
+        ArpAddressLengthBad,
+        MulticastRecordLengthBad,
+        GeneveOptionDataLengthBad(0),
+        GeneveOptionsLengthTooLarge(0),
+        GtpuExtensionHeaderLengthBad(0),
+        GtpuPayloadLengthTooLarge(0),
+        PppoePayloadLengthTooLarge(0),
+        GueExtensionFieldsLengthBad(0),
+        TeredoAuthenticationLengthTooLarge(0),
+        Ipv4FragmentMtuTooSmall(0),
+        Ipv4DontFragmentSet(0),
+        Ipv6FragmentMtuTooSmall(0),
+        Ipv6SegmentRoutingNoSegments,
+        Ipv6SegmentRoutingTlvTooLarge(0),
+        Ipv6ExtensionOutOfOrder{ header: IpNumber::Udp, previous: IpNumber::Udp },
+        Icmpv6RouterRenumberingTooManyUsePrefixes(0),
     ];
 
     for value in &none_values {
@@ -241,9 +369,25 @@ fn value_error_debug() {
         Ipv6ExtensionNotDefinedReference(IpNumber::Icmp),
         UdpPayloadLengthTooLarge(0),
         TcpLengthTooLarge(0),
-        U8TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
-        U16TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
-        U32TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
+        U8TooLarge{ value:0, max:0, field:ErrorField::Dscp },
+        U16TooLarge{ value:0, max:0, field:ErrorField::Dscp },
+        U32TooLarge{ value:0, max:0, field:ErrorField::Dscp },
+        ArpAddressLengthBad,
+        MulticastRecordLengthBad,
+        GeneveOptionDataLengthBad(0),
+        GeneveOptionsLengthTooLarge(0),
+        GtpuExtensionHeaderLengthBad(0),
+        GtpuPayloadLengthTooLarge(0),
+        PppoePayloadLengthTooLarge(0),
+        GueExtensionFieldsLengthBad(0),
+        TeredoAuthenticationLengthTooLarge(0),
+        Ipv4FragmentMtuTooSmall(0),
+        Ipv4DontFragmentSet(0),
+        Ipv6FragmentMtuTooSmall(0),
+        Ipv6SegmentRoutingNoSegments,
+        Ipv6SegmentRoutingTlvTooLarge(0),
+        Ipv6ExtensionOutOfOrder{ header: IpNumber::Udp, previous: IpNumber::Udp },
+        Icmpv6RouterRenumberingTooManyUsePrefixes(0),
     ];
 
     for value in &values {
@@ -373,6 +517,105 @@ proptest! {
                 field: field.clone()
             })
         );
+
+        //ArpAddressLengthBad
+        assert_eq!(
+            "ARP address lengths bad. The sender & target hardware addresses (and the sender & target protocol addresses) must have the same length of at most 255 bytes.",
+            &format!("{}", ArpAddressLengthBad)
+        );
+
+        //MulticastRecordLengthBad
+        assert_eq!(
+            "Multicast record length bad. Queries & group records can contain at most 65535 sources, reports at most 65535 records & the auxiliary data must be a multiple of 4 bytes & at most 1020 bytes long.",
+            &format!("{}", MulticastRecordLengthBad)
+        );
+
+        //GeneveOptionDataLengthBad
+        assert_eq!(
+            &format!("Geneve option data length bad. The option data ({} bytes) is either not a multiple of 4 bytes or bigger then the maximum of 124 bytes.", arg_usize),
+            &format!("{}", GeneveOptionDataLengthBad(arg_usize))
+        );
+
+        //GeneveOptionsLengthTooLarge
+        assert_eq!(
+            &format!("Geneve options length too large. The options ({} bytes) are bigger then the maximum of 252 bytes.", arg_usize),
+            &format!("{}", GeneveOptionsLengthTooLarge(arg_usize))
+        );
+
+        //GtpuExtensionHeaderLengthBad
+        assert_eq!(
+            &format!("GTP-U extension header length bad. The content length ({} bytes) plus 2 is either not a multiple of 4 bytes or the content is bigger then the maximum of 1018 bytes.", arg_usize),
+            &format!("{}", GtpuExtensionHeaderLengthBad(arg_usize))
+        );
+
+        //GtpuPayloadLengthTooLarge
+        assert_eq!(
+            &format!("GTP-U 'length' too large. The GTP-U length ({} bytes) is larger then what can be be represented by the 'length' field in the GTP-U header.", arg_usize),
+            &format!("{}", GtpuPayloadLengthTooLarge(arg_usize))
+        );
+
+        //PppoePayloadLengthTooLarge
+        assert_eq!(
+            &format!("PPPoE 'length' too large. The PPPoE length ({} bytes) is larger then what can be be represented by the 'length' field in the PPPoE header.", arg_usize),
+            &format!("{}", PppoePayloadLengthTooLarge(arg_usize))
+        );
+
+        //GueExtensionFieldsLengthBad
+        assert_eq!(
+            &format!("GUE extension fields length bad. The extension fields length ({} bytes) is not a multiple of 4 or larger then what can be represented by the 'hlen' field in the GUE header.", arg_usize),
+            &format!("{}", GueExtensionFieldsLengthBad(arg_usize))
+        );
+
+        //TeredoAuthenticationLengthTooLarge
+        assert_eq!(
+            &format!("Teredo authentication field too large. The client identifier or authentication value ({} bytes) is larger then what can be represented by the length fields in the Teredo authentication header.", arg_usize),
+            &format!("{}", TeredoAuthenticationLengthTooLarge(arg_usize))
+        );
+
+        //Ipv4FragmentMtuTooSmall
+        assert_eq!(
+            &format!("IPv4 MTU too small. The MTU ({} bytes) is too small to contain an IPv4 fragment with at least 8 bytes of payload.", arg_usize),
+            &format!("{}", Ipv4FragmentMtuTooSmall(arg_usize))
+        );
+
+        //Ipv4DontFragmentSet
+        assert_eq!(
+            &format!("IPv4 'dont_fragment' set. The IPv4 packet ({} bytes) is larger then the MTU but can not be fragmented as the 'dont_fragment' flag is set.", arg_usize),
+            &format!("{}", Ipv4DontFragmentSet(arg_usize))
+        );
+
+        //Ipv6FragmentMtuTooSmall
+        assert_eq!(
+            &format!("IPv6 MTU too small. The MTU ({} bytes) is too small to contain an IPv6 fragment with the extension headers & at least 8 bytes of payload.", arg_usize),
+            &format!("{}", Ipv6FragmentMtuTooSmall(arg_usize))
+        );
+
+        //Ipv6SegmentRoutingNoSegments
+        assert_eq!(
+            "IPv6 segment routing header without segments. A segment routing header has to contain at least one segment.",
+            &format!("{}", Ipv6SegmentRoutingNoSegments)
+        );
+
+        //Ipv6SegmentRoutingTlvTooLarge
+        assert_eq!(
+            &format!("IPv6 segment routing TLV too large. The TLV value ({} bytes) is larger then what can be represented by the length field of the TLV.", arg_usize),
+            &format!("{}", Ipv6SegmentRoutingTlvTooLarge(arg_usize))
+        );
+
+        //Ipv6ExtensionOutOfOrder
+        assert_eq!(
+            "IPv6 extensions 'IPv6FragmentationHeader' is located after the extension header 'AuthenticationHeader' (deviates from the order recommended by RFC 8200).",
+            &format!("{}", Ipv6ExtensionOutOfOrder{
+                header: IpNumber::IPv6FragmentationHeader,
+                previous: IpNumber::AuthenticationHeader,
+            })
+        );
+
+        //Icmpv6RouterRenumberingTooManyUsePrefixes
+        assert_eq!(
+            &format!("ICMPv6 router renumbering prefix control operation with too many use-prefix parts. The {} use-prefix parts can not be represented in the OpLength field (max 63).", arg_usize),
+            &format!("{}", Icmpv6RouterRenumberingTooManyUsePrefixes(arg_usize))
+        );
     }
 }
 
@@ -381,11 +624,26 @@ fn error_field_display() {
     use ErrorField::*;
 
     assert_eq!("Ipv4Header.payload_len", &format!("{}", Ipv4PayloadLength));
-    assert_eq!("Ipv4Header.differentiated_services_code_point", &format!("{}", Ipv4Dscp));
-    assert_eq!("Ipv4Header.explicit_congestion_notification", &format!("{}", Ipv4Ecn));
     assert_eq!("Ipv4Header.fragments_offset", &format!("{}", Ipv4FragmentsOffset));
     assert_eq!("Ipv6Header.flow_label", &format!("{}", Ipv6FlowLabel));
     assert_eq!("Ipv6FragmentHeader.fragment_offset", &format!("{}", Ipv6FragmentOffset));
     assert_eq!("SingleVlanHeader.priority_code_point", &format!("{}", VlanTagPriorityCodePoint));
     assert_eq!("SingleVlanHeader.vlan_identifier", &format!("{}", VlanTagVlanId));
+    assert_eq!("MldV2Query.querier_robustness_variable", &format!("{}", MldQuerierRobustnessVariable));
+    assert_eq!("IgmpV3Query.querier_robustness_variable", &format!("{}", IgmpQuerierRobustnessVariable));
+    assert_eq!("VxlanHeader.vni", &format!("{}", VxlanVni));
+    assert_eq!("GeneveHeader.version", &format!("{}", GeneveVersion));
+    assert_eq!("GeneveHeader.vni", &format!("{}", GeneveVni));
+    assert_eq!("GtpuPduSessionContainer.pdu_type", &format!("{}", GtpuPduType));
+    assert_eq!("GtpuPduSessionContainer.qos_flow_identifier", &format!("{}", GtpuQosFlowIdentifier));
+    assert_eq!("PppoeHeader.version", &format!("{}", PppoeVersion));
+    assert_eq!("PppoeHeader.pppoe_type", &format!("{}", PppoeType));
+    assert_eq!("PbbItagHeader.priority_code_point", &format!("{}", PbbItagPriorityCodePoint));
+    assert_eq!("PbbItagHeader.i_sid", &format!("{}", PbbItagIsid));
+    assert_eq!("GreHeader.key (NVGRE vsid)", &format!("{}", NvgreVsid));
+    assert_eq!("VxlanGpeHeader.vni", &format!("{}", VxlanGpeVni));
+    assert_eq!("Ipv4TimestampOption.overflow", &format!("{}", Ipv4TimestampOverflow));
+    assert_eq!("Ipv4TimestampOption.flag", &format!("{}", Ipv4TimestampFlag));
+    assert_eq!("Dscp", &format!("{}", Dscp));
+    assert_eq!("Ecn", &format!("{}", Ecn));
 }
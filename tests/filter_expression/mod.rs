@@ -0,0 +1,171 @@
+use super::*;
+use etherparse::filter_expression::*;
+
+fn ipv4_tcp(source: [u8;4], destination: [u8;4], source_port: u16, destination_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4(source, destination, 20)
+        .tcp(source_port, destination_port, 1, 1024);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn ipv6_udp_vlan(source: [u8;16], destination: [u8;16], source_port: u16, destination_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(100)
+        .ipv6(source, destination, 20)
+        .udp(source_port, destination_port);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn matches(expression: &str, packet: &[u8]) -> bool {
+    let filter = FilterExpression::parse(expression).unwrap();
+    filter.matches(&SlicedPacket::from_ethernet(packet).unwrap())
+}
+
+#[test]
+fn protocols() {
+    let tcp = ipv4_tcp([10,0,0,1], [192,168,1,2], 1234, 443);
+    let udp = ipv6_udp_vlan([0;16], [1;16], 53, 1234);
+
+    assert!(matches("", &tcp));
+    assert!(matches("ether", &tcp));
+    assert!(matches("ip", &tcp));
+    assert!(!matches("ip6", &tcp));
+    assert!(matches("tcp", &tcp));
+    assert!(!matches("udp", &tcp));
+    assert!(!matches("icmp", &tcp));
+
+    assert!(!matches("ip", &udp));
+    assert!(matches("ip6", &udp));
+    assert!(matches("udp", &udp));
+    assert!(!matches("icmp6", &udp));
+
+    assert!(matches("ip proto 6", &tcp));
+    assert!(matches("ip proto \\tcp", &tcp));
+    assert!(!matches("ip6 proto tcp", &tcp));
+    assert!(matches("proto udp", &udp));
+    assert!(matches("ether proto 0x800", &tcp));
+    assert!(matches("ether proto \\ip6", &udp));
+    assert!(!matches("ether proto arp", &udp));
+}
+
+#[test]
+fn hosts_and_nets() {
+    let tcp = ipv4_tcp([10,0,0,1], [192,168,1,2], 1234, 443);
+    let mut dst = [0;16];
+    dst[0] = 0xfe;
+    dst[1] = 0x80;
+    dst[15] = 1;
+    let udp = ipv6_udp_vlan([0;16], dst, 53, 1234);
+
+    assert!(matches("host 10.0.0.1", &tcp));
+    assert!(matches("src host 10.0.0.1", &tcp));
+    assert!(!matches("dst host 10.0.0.1", &tcp));
+    assert!(matches("dst 192.168.1.2", &tcp));
+    assert!(matches("192.168.1.2", &tcp));
+    assert!(!matches("host ::1", &tcp));
+    assert!(matches("host fe80::1", &udp));
+    assert!(matches("ip6 dst host fe80::1", &udp));
+    assert!(!matches("ip host fe80::1", &udp));
+
+    assert!(matches("net 10.0.0.0/8", &tcp));
+    assert!(matches("src net 10.0.0.0/8", &tcp));
+    assert!(!matches("dst net 10.0.0.0/8", &tcp));
+    assert!(matches("net 0.0.0.0/0", &tcp));
+    assert!(matches("net 10.0.0.1", &tcp));
+    assert!(matches("dst net fe80::/10", &udp));
+    assert!(!matches("net fe80::/10", &tcp));
+
+    assert!(matches("ether host 01:02:03:04:05:06", &tcp));
+    assert!(matches("ether src 01:02:03:04:05:06", &tcp));
+    assert!(!matches("ether dst 01:02:03:04:05:06", &tcp));
+    assert!(matches("ether dst host 07-08-09-0a-0b-0c", &tcp));
+}
+
+#[test]
+fn ports_vlan_and_length() {
+    let tcp = ipv4_tcp([10,0,0,1], [192,168,1,2], 1234, 443);
+    let udp = ipv6_udp_vlan([0;16], [1;16], 53, 1234);
+
+    assert!(matches("port 443", &tcp));
+    assert!(matches("dst port 443", &tcp));
+    assert!(!matches("src port 443", &tcp));
+    assert!(matches("tcp port 1234", &tcp));
+    assert!(!matches("udp port 1234", &tcp));
+    assert!(matches("udp src port 53", &udp));
+    assert!(matches("portrange 400-500", &tcp));
+    assert!(!matches("src portrange 400-500", &tcp));
+    assert!(matches("udp dst portrange 1234-1234", &udp));
+
+    assert!(matches("vlan", &udp));
+    assert!(matches("vlan 100", &udp));
+    assert!(!matches("vlan 101", &udp));
+    assert!(!matches("vlan", &tcp));
+    assert!(matches("vlan and udp", &udp));
+
+    let len = tcp.len();
+    assert!(matches(&format!("less {}", len), &tcp));
+    assert!(!matches(&format!("less {}", len - 1), &tcp));
+    assert!(matches(&format!("greater {}", len), &tcp));
+    assert!(!matches(&format!("greater {}", len + 1), &tcp));
+}
+
+#[test]
+fn operators() {
+    let tcp = ipv4_tcp([10,0,0,1], [192,168,1,2], 1234, 443);
+
+    assert!(matches("tcp and dst port 443 and net 10.0.0.0/8", &tcp));
+    assert!(matches("tcp && dst port 443 && net 10.0.0.0/8", &tcp));
+    assert!(!matches("tcp and dst port 80", &tcp));
+    assert!(matches("udp or tcp", &tcp));
+    assert!(matches("udp||tcp", &tcp));
+    assert!(!matches("not tcp", &tcp));
+    assert!(matches("!udp", &tcp));
+    assert!(matches("not (udp or port 80)", &tcp));
+    assert!(matches("(udp or tcp) and (port 80 or port 443)", &tcp));
+
+    // values without keywords reuse the previous keywords
+    assert!(matches("port 80 or 443", &tcp));
+    assert!(!matches("dst port 80 or 1234", &tcp));
+    assert!(matches("host 1.1.1.1 or 10.0.0.1", &tcp));
+    assert!(!matches("udp port 80 or 443", &tcp));
+
+    // and & or have the same precedence (evaluated from left to right)
+    assert!(!matches("tcp or udp and port 80", &tcp));
+    assert!(matches("port 80 and udp or tcp", &tcp));
+}
+
+#[test]
+fn from_ip() {
+    let tcp = ipv4_tcp([10,0,0,1], [192,168,1,2], 1234, 443);
+    let filter = FilterExpression::parse("ip and port 443").unwrap();
+    assert!(filter.matches(&SlicedPacket::from_ip(&tcp[14..]).unwrap()));
+    let filter = FilterExpression::parse("ether or ether proto 0x800").unwrap();
+    assert!(!filter.matches(&SlicedPacket::from_ip(&tcp[14..]).unwrap()));
+}
+
+#[test]
+fn parse_errors() {
+    use FilterExpressionError::*;
+
+    assert_eq!(UnexpectedEnd, FilterExpression::parse("port").unwrap_err());
+    assert_eq!(UnexpectedEnd, FilterExpression::parse("tcp and").unwrap_err());
+    assert_eq!(UnexpectedEnd, FilterExpression::parse("(tcp").unwrap_err());
+    assert_eq!(UnexpectedToken(")".to_string()), FilterExpression::parse("tcp)").unwrap_err());
+    assert_eq!(UnexpectedToken("and".to_string()), FilterExpression::parse("port and").unwrap_err());
+    assert_eq!(UnexpectedToken("10.0.0.1".to_string()), FilterExpression::parse("ip 10.0.0.1").unwrap_err());
+    assert_eq!(InvalidValue("65536".to_string()), FilterExpression::parse("port 65536").unwrap_err());
+    assert_eq!(InvalidValue("80".to_string()), FilterExpression::parse("portrange 80").unwrap_err());
+    assert_eq!(InvalidValue("10.0.0.0/33".to_string()), FilterExpression::parse("net 10.0.0.0/33").unwrap_err());
+    assert_eq!(InvalidValue("foo".to_string()), FilterExpression::parse("host foo").unwrap_err());
+    assert_eq!(InvalidValue("4096".to_string()), FilterExpression::parse("vlan 4096").unwrap_err());
+    assert_eq!(InvalidValue("01:02".to_string()), FilterExpression::parse("ether host 01:02").unwrap_err());
+    assert_eq!(InvalidValue("256".to_string()), FilterExpression::parse("ip proto 256").unwrap_err());
+
+    assert!(format!("{}", UnexpectedEnd).starts_with("FilterExpressionError:"));
+}
@@ -0,0 +1,216 @@
+use super::*;
+use etherparse::generator::*;
+
+fn udp_base() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(12)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(1000, 53);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn tcp_base() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv6([0;16], [1;16], 20)
+        .tcp(1000, 80, 100, 1024);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    packet
+}
+
+#[test]
+fn no_variations() {
+    // without variations the base packet is reproduced
+    for base in [udp_base(), tcp_base()].iter() {
+        let mut generator = TrafficGenerator::new(
+            PacketHeaders::from_ethernet_slice(base).unwrap(),
+            Vec::new()
+        );
+        assert_eq!(0, generator.index());
+        for packet in generator.by_ref().take(3) {
+            assert_eq!(base, &packet.unwrap());
+        }
+        assert_eq!(3, generator.index());
+    }
+}
+
+#[test]
+fn ip_and_port_variations() {
+    let base = udp_base();
+    let generator = TrafficGenerator::new(
+        PacketHeaders::from_ethernet_slice(&base).unwrap(),
+        vec![
+            FieldVariation::SourceIp{ step: 1, count: 2 },
+            FieldVariation::DestinationIp{ step: 256, count: 0 },
+            FieldVariation::SourcePort{ step: 10, count: 3 },
+            FieldVariation::DestinationPort{ step: 1, count: 0 },
+        ]
+    );
+    for (i, packet) in generator.take(6).enumerate() {
+        let packet = packet.unwrap();
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(VlanHeader::Single(SingleVlanHeader{
+            priority_code_point: 0,
+            drop_eligible_indicator: false,
+            vlan_identifier: 12,
+            ether_type: ether_type::IPV4,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        })), decoded.vlan);
+        match decoded.ip.unwrap() {
+            IpHeader::Version4(ip, _) => {
+                assert_eq!([192,168,1,1 + (i % 2) as u8], ip.source);
+                assert_eq!([192,168,1 + i as u8,2], ip.destination);
+                // checksum must be valid
+                assert_eq!(ip.calc_header_checksum().unwrap(), ip.header_checksum);
+            },
+            _ => panic!("expected ipv4"),
+        }
+        let udp = decoded.transport.unwrap().udp().unwrap();
+        assert_eq!(1000 + 10*(i % 3) as u16, udp.source_port);
+        assert_eq!(53 + i as u16, udp.destination_port);
+
+        // udp checksum must be valid
+        let ip = SlicedPacket::from_ethernet(&packet).unwrap();
+        if let (Some(InternetSlice::Ipv4(ip, _)), Some(TransportSlice::Udp(udp_slice))) = (ip.ip, ip.transport) {
+            assert_eq!(
+                udp.calc_checksum_ipv4_raw(ip.source(), ip.destination(), decoded.payload.slice()).unwrap(),
+                udp_slice.checksum()
+            );
+        } else {
+            panic!("expected ipv4 & udp slices");
+        }
+    }
+}
+
+#[test]
+fn ipv6_variations() {
+    let base = tcp_base();
+    let generator = TrafficGenerator::new(
+        PacketHeaders::from_ethernet_slice(&base).unwrap(),
+        vec![
+            FieldVariation::SourceIp{ step: 1, count: 0 },
+            FieldVariation::DestinationIp{ step: 2, count: 0 },
+        ]
+    );
+    for (i, packet) in generator.take(3).enumerate() {
+        let packet = packet.unwrap();
+        match PacketHeaders::from_ethernet_slice(&packet).unwrap().ip.unwrap() {
+            IpHeader::Version6(ip, _) => {
+                let mut source = [0;16];
+                source[15] = i as u8;
+                assert_eq!(source, ip.source);
+                let mut destination = [1;16];
+                destination[15] = 1 + 2*i as u8;
+                assert_eq!(destination, ip.destination);
+            },
+            _ => panic!("expected ipv6"),
+        }
+    }
+}
+
+#[test]
+fn tcp_sequence_numbers() {
+    let base = tcp_base();
+
+    // fixed step
+    {
+        let generator = TrafficGenerator::new(
+            PacketHeaders::from_ethernet_slice(&base).unwrap(),
+            vec![FieldVariation::TcpSequenceNumber{ step: 5 }]
+        );
+        for (i, packet) in generator.take(3).enumerate() {
+            let tcp = PacketHeaders::from_ethernet_slice(&packet.unwrap()).unwrap().transport.unwrap().tcp().unwrap();
+            assert_eq!(100 + 5*i as u32, tcp.sequence_number);
+        }
+    }
+
+    // by payload
+    {
+        let generator = TrafficGenerator::new(
+            PacketHeaders::from_ethernet_slice(&base).unwrap(),
+            vec![
+                FieldVariation::RandomPayloadLen{ min: 1, max: 100 },
+                FieldVariation::TcpSequenceNumberByPayload,
+            ]
+        );
+        let mut expected = 100;
+        for packet in generator.take(10) {
+            let packet = packet.unwrap();
+            let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            let tcp = decoded.transport.unwrap().tcp().unwrap();
+            assert_eq!(expected, tcp.sequence_number);
+            expected += decoded.payload.len() as u32;
+        }
+    }
+}
+
+#[test]
+fn random_payload_len() {
+    let base = udp_base();
+    let headers = PacketHeaders::from_ethernet_slice(&base).unwrap();
+
+    // payload is filled by repeating the base payload
+    let mut lens = Vec::new();
+    for packet in TrafficGenerator::new(
+        headers.clone(),
+        vec![FieldVariation::RandomPayloadLen{ min: 2, max: 10 }]
+    ).take(50) {
+        let packet = packet.unwrap();
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        let len = decoded.payload.len();
+        assert!((2..=10).contains(&len));
+        let expected: Vec<u8> = [1,2,3,4].iter().cycle().take(len).cloned().collect();
+        assert_eq!(Payload::Udp(&expected[..]), decoded.payload);
+        assert_eq!(
+            (len + UdpHeader::SERIALIZED_SIZE) as u16,
+            decoded.transport.unwrap().udp().unwrap().length
+        );
+        lens.push(len);
+    }
+    assert!(lens.iter().any(|l| *l != lens[0]));
+
+    // same seed results in the same packets
+    {
+        let a: Vec<_> = TrafficGenerator::with_seed(
+            headers.clone(), vec![FieldVariation::RandomPayloadLen{ min: 0, max: 1000 }], 0
+        ).take(10).map(|p| p.unwrap()).collect();
+        let b: Vec<_> = TrafficGenerator::with_seed(
+            headers.clone(), vec![FieldVariation::RandomPayloadLen{ min: 0, max: 1000 }], 0
+        ).take(10).map(|p| p.unwrap()).collect();
+        assert_eq!(a, b);
+    }
+
+    // min >= max results in a fixed length
+    for packet in TrafficGenerator::new(
+        headers.clone(),
+        vec![FieldVariation::RandomPayloadLen{ min: 7, max: 3 }]
+    ).take(3) {
+        let packet = packet.unwrap();
+        assert_eq!(7, PacketHeaders::from_ethernet_slice(&packet).unwrap().payload.len());
+    }
+
+    // zero filled if the base packet has no payload
+    {
+        let base = tcp_base();
+        let packet = TrafficGenerator::new(
+            PacketHeaders::from_ethernet_slice(&base).unwrap(),
+            vec![FieldVariation::RandomPayloadLen{ min: 3, max: 3 }]
+        ).next().unwrap().unwrap();
+        assert_eq!(Payload::Tcp(&[0,0,0]), PacketHeaders::from_ethernet_slice(&packet).unwrap().payload);
+    }
+
+    // too large payloads result in an error
+    {
+        let mut generator = TrafficGenerator::new(
+            headers,
+            vec![FieldVariation::RandomPayloadLen{ min: 0x10000, max: 0x10000 }]
+        );
+        assert_matches!(generator.next(), Some(Err(_)));
+        // the index is not incremented on errors
+        assert_eq!(0, generator.index());
+    }
+}
@@ -0,0 +1,102 @@
+use super::*;
+use etherparse::hexdump::*;
+
+fn udp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(53, 1234);
+    let mut packet = Vec::with_capacity(builder.size(3));
+    builder.write(&mut packet, &[1,2,3]).unwrap();
+    packet
+}
+
+/// Formats the packet like text2pcap expects it (including an ascii dump).
+fn to_text2pcap(packet: &[u8]) -> String {
+    let mut result = String::new();
+    for (i, chunk) in packet.chunks(16).enumerate() {
+        result.push_str(&format!("{:04x}  ", i*16));
+        for b in chunk {
+            result.push_str(&format!("{:02x} ", b));
+        }
+        result.push_str("  ");
+        for b in chunk {
+            result.push(if b.is_ascii_graphic() { *b as char } else { '.' });
+        }
+        result.push('\n');
+    }
+    result
+}
+
+#[test]
+fn parse_text2pcap() {
+    let packet = udp_packet();
+    let text = format!(
+        "# first packet\n{}\n12:00:00.000000\n{}",
+        to_text2pcap(&packet),
+        to_text2pcap(&packet[..20])
+    );
+    let dump = HexDump::parse(&text).unwrap();
+    assert_eq!(vec![packet.clone(), packet[..20].to_vec()], dump.packets);
+
+    let sliced = dump.slice_ethernet();
+    assert_eq!(2, sliced.len());
+    assert_eq!(&[1,2,3], sliced[0].as_ref().unwrap().payload);
+    assert!(sliced[1].is_err());
+
+    // slicing starting with the ip header
+    let dump = HexDump::parse(&to_text2pcap(&packet[14..])).unwrap();
+    let sliced = dump.slice_ip();
+    assert_eq!(&[1,2,3], sliced[0].as_ref().unwrap().payload);
+}
+
+#[test]
+fn parse_other_formats() {
+    // tcpdump -xx style with 4 digit groups
+    assert_eq!(
+        vec![vec![0x45, 0x00, 0x00, 0x3c, 0x1c, 0x46], vec![0x12]],
+        HexDump::parse(
+            "IP 10.0.0.1 > 10.0.0.2: ICMP echo request\n\
+             \t0x0000:  4500 003c\n\
+             \t0x0004:  1c46\n\
+             \t0x0000:  12\n"
+        ).unwrap().packets
+    );
+
+    // od -Ax -tx1 style (6 digit offsets & final offset line without data)
+    assert_eq!(
+        vec![vec![0xaa, 0xbb, 0xcc]],
+        HexDump::parse("000000 aa bb\n000002 cc\n000003\n").unwrap().packets
+    );
+
+    // empty dump
+    assert_eq!(HexDump::default(), HexDump::parse("").unwrap());
+}
+
+#[test]
+fn parse_hex_looking_ascii() {
+    // the ascii dump "cafe" of the first line looks like a hex group and is
+    // cut off based on the offset of the next line
+    assert_eq!(
+        vec![vec![0xca, 0xfe, 0x01, 0x02]],
+        HexDump::parse("0000  ca fe   cafe\n0002  01 02   ..\n").unwrap().packets
+    );
+}
+
+#[test]
+fn parse_unexpected_offset() {
+    // missing bytes
+    assert_eq!(
+        HexDumpError::UnexpectedOffset{ line: 2, expected: 2, actual: 4 },
+        HexDump::parse("0000 01 02\n0004 03 04\n").unwrap_err()
+    );
+    // offset going backwards
+    assert_eq!(
+        HexDumpError::UnexpectedOffset{ line: 3, expected: 4, actual: 2 },
+        HexDump::parse("0000 01 02\n0002 03 04\n0002 05\n").unwrap_err()
+    );
+    // first line not starting at 0
+    let err = HexDump::parse("0010 01 02\n").unwrap_err();
+    assert_eq!(HexDumpError::UnexpectedOffset{ line: 1, expected: 0, actual: 0x10 }, err);
+    assert!(format!("{}", err).contains("line 1"));
+}
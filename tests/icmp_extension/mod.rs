@@ -0,0 +1,437 @@
+use super::*;
+
+use etherparse::icmp_extension::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn mpls_label_stack_entry() {
+    let entry = MplsLabelStackEntry{
+        label: 0xa_bcde,
+        traffic_class: 0b101,
+        bottom_of_stack: true,
+        ttl: 0x12,
+    };
+    assert_eq!([0xab, 0xcd, 0xeb, 0x12], entry.to_bytes());
+    assert_eq!(entry, MplsLabelStackEntry::from_bytes(entry.to_bytes()));
+
+    // bits outside of the value ranges are ignored
+    let entry = MplsLabelStackEntry{
+        label: 0xff_ffff,
+        traffic_class: 0xff,
+        bottom_of_stack: false,
+        ttl: 0,
+    };
+    assert_eq!([0xff, 0xff, 0xfe, 0], entry.to_bytes());
+}
+
+#[test]
+fn interface_role() {
+    for value in 0..4u8 {
+        assert_eq!(value, InterfaceRole::from_u8(value).to_u8());
+    }
+    assert_eq!(InterfaceRole::IpNextHop, InterfaceRole::from_u8(0xff));
+}
+
+#[test]
+fn object_write_from_slice() {
+    let objects = [
+        IcmpExtensionObject::MplsLabelStack(vec![
+            MplsLabelStackEntry{ label: 16, traffic_class: 0, bottom_of_stack: false, ttl: 1 },
+            MplsLabelStackEntry{ label: 17, traffic_class: 1, bottom_of_stack: true, ttl: 2 },
+        ]),
+        IcmpExtensionObject::InterfaceInformation(InterfaceInformation{
+            role: InterfaceRole::IncomingIpInterface,
+            if_index: Some(3),
+            ip_addr: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))),
+            name: Some("eth0"),
+            mtu: Some(1500),
+        }),
+        IcmpExtensionObject::InterfaceInformation(InterfaceInformation{
+            role: InterfaceRole::OutgoingIpInterface,
+            if_index: None,
+            ip_addr: Some(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+            name: None,
+            mtu: None,
+        }),
+        IcmpExtensionObject::InterfaceInformation(InterfaceInformation{
+            role: InterfaceRole::IpNextHop,
+            if_index: None,
+            ip_addr: None,
+            name: Some(""),
+            mtu: None,
+        }),
+        IcmpExtensionObject::Unknown{ class_num: 4, c_type: 1, data: &[1, 2, 3, 4] },
+    ];
+    for object in &objects {
+        let mut bytes = Vec::new();
+        object.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), usize::from(u16::from_be_bytes([bytes[0], bytes[1]])));
+        assert_eq!(object.class_num(), bytes[2]);
+        assert_eq!(object.c_type(), bytes[3]);
+        bytes.extend_from_slice(&[9, 9]);
+        assert_eq!((object.clone(), &[9u8, 9][..]), IcmpExtensionObject::from_slice(&bytes).unwrap());
+    }
+
+    // interface information layout
+    {
+        let mut bytes = Vec::new();
+        objects[1].write(&mut bytes).unwrap();
+        assert_eq!(
+            &[
+                0, 28, 2, 0b0000_1111,
+                0, 0, 0, 3,
+                0, 1, 0, 0, 192, 168, 1, 1,
+                8, b'e', b't', b'h', b'0', 0, 0, 0,
+                0, 0, 0x05, 0xdc,
+            ][..],
+            &bytes[..]
+        );
+    }
+
+    // name too long
+    {
+        let name = "a".repeat(64);
+        let object = IcmpExtensionObject::InterfaceInformation(InterfaceInformation{
+            role: InterfaceRole::IncomingIpInterface,
+            if_index: None,
+            ip_addr: None,
+            name: Some(&name),
+            mtu: None,
+        });
+        let mut bytes = vec![1];
+        assert_eq!(Err(IcmpExtensionError::ObjectTooLarge(68)), object.write(&mut bytes));
+        assert_eq!(vec![1], bytes);
+    }
+}
+
+#[test]
+fn object_from_slice_errors() {
+    use IcmpExtensionError::*;
+
+    assert_eq!(Err(UnexpectedEnd(4)), IcmpExtensionObject::from_slice(&[0, 8, 1]));
+    assert_eq!(Err(ObjectLengthBad(3)), IcmpExtensionObject::from_slice(&[0, 3, 1, 1]));
+    assert_eq!(Err(UnexpectedEnd(8)), IcmpExtensionObject::from_slice(&[0, 8, 1, 1, 0, 0]));
+
+    // mpls label stack not a multiple of 4 bytes
+    assert_eq!(
+        Err(InvalidObject{ class_num: 1, c_type: 1 }),
+        IcmpExtensionObject::from_slice(&[0, 6, 1, 1, 0, 0])
+    );
+    // interface information with missing if index
+    assert_eq!(
+        Err(InvalidObject{ class_num: 2, c_type: 0b1000 }),
+        IcmpExtensionObject::from_slice(&[0, 6, 2, 0b1000, 0, 0])
+    );
+    // interface information with unknown address family
+    assert_eq!(
+        Err(InvalidObject{ class_num: 2, c_type: 0b0100 }),
+        IcmpExtensionObject::from_slice(&[0, 12, 2, 0b0100, 0, 3, 0, 0, 1, 2, 3, 4])
+    );
+    // interface information with bad name length
+    assert_eq!(
+        Err(InvalidObject{ class_num: 2, c_type: 0b0010 }),
+        IcmpExtensionObject::from_slice(&[0, 8, 2, 0b0010, 5, b'a', 0, 0])
+    );
+    // interface information with invalid utf-8 name
+    assert_eq!(
+        Err(InvalidObject{ class_num: 2, c_type: 0b0010 }),
+        IcmpExtensionObject::from_slice(&[0, 8, 2, 0b0010, 4, 0xff, 0xfe, 0])
+    );
+}
+
+#[test]
+fn extensions_slice() {
+    let objects = [
+        IcmpExtensionObject::MplsLabelStack(vec![
+            MplsLabelStackEntry{ label: 16, traffic_class: 0, bottom_of_stack: true, ttl: 1 },
+        ]),
+        IcmpExtensionObject::Unknown{ class_num: 4, c_type: 1, data: &[1, 2, 3, 4] },
+    ];
+    let mut bytes = Vec::new();
+    write_extensions(&objects, &mut bytes).unwrap();
+    assert_eq!(HEADER_LEN + 8 + 8, bytes.len());
+
+    let slice = IcmpExtensionsSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..], slice.slice());
+    assert_eq!(VERSION, slice.version());
+    assert_eq!(u16::from_be_bytes([bytes[2], bytes[3]]), slice.checksum());
+    assert!(slice.is_checksum_valid());
+    assert_eq!(
+        &objects[..],
+        &slice.objects().collect::<Result<Vec<_>, _>>().unwrap()[..]
+    );
+
+    // bad checksum
+    {
+        let mut bytes = bytes.clone();
+        bytes[3] ^= 1;
+        assert!(!IcmpExtensionsSlice::from_slice(&bytes).unwrap().is_checksum_valid());
+    }
+
+    // errors
+    assert_eq!(
+        Err(IcmpExtensionError::UnexpectedEnd(HEADER_LEN)),
+        IcmpExtensionsSlice::from_slice(&bytes[..HEADER_LEN - 1])
+    );
+    {
+        let mut bytes = bytes.clone();
+        bytes[0] = 1 << 4;
+        assert_eq!(
+            Err(IcmpExtensionError::UnsupportedVersion(1)),
+            IcmpExtensionsSlice::from_slice(&bytes)
+        );
+    }
+
+    // the iteration stops after an error
+    {
+        let slice = IcmpExtensionsSlice::from_slice(&bytes[..bytes.len() - 1]).unwrap();
+        let mut objects_iter = slice.objects();
+        assert_eq!(Some(Ok(objects[0].clone())), objects_iter.next());
+        assert_eq!(Some(Err(IcmpExtensionError::UnexpectedEnd(8))), objects_iter.next());
+        assert_eq!(None, objects_iter.next());
+        assert!(objects_iter.rest().is_empty());
+    }
+}
+
+#[test]
+fn icmpv4_error_extensions() {
+    use etherparse::icmpv4::*;
+
+    let objects = [
+        IcmpExtensionObject::MplsLabelStack(vec![
+            MplsLabelStackEntry{ label: 16, traffic_class: 0, bottom_of_stack: true, ttl: 1 },
+        ]),
+    ];
+    let original = [0x45u8; 28];
+
+    for type_u8 in &[icmpv4_type::DESTINATION_UNREACHABLE, icmpv4_type::TIME_EXCEEDED, icmpv4_type::PARAMETER_PROBLEM] {
+        let mut message = vec![*type_u8, 0, 0, 0, 0, 0, 0, 0];
+        message.extend_from_slice(&original);
+
+        // without extensions
+        assert_eq!(Ok((&original[..], None)), split_error_extensions(&message));
+
+        append_error_extensions(&mut message, &objects).unwrap();
+        assert_eq!(128/4, message[5]);
+        let (data, extensions) = split_error_extensions(&message).unwrap();
+        assert_eq!(&original[..], &data[..original.len()]);
+        assert!(data[original.len()..].iter().all(|v| 0 == *v));
+        assert_eq!(128, data.len());
+        let extensions = extensions.unwrap();
+        assert!(extensions.is_checksum_valid());
+        assert_eq!(
+            &objects[..],
+            &extensions.objects().collect::<Result<Vec<_>, _>>().unwrap()[..]
+        );
+
+        // original datagram longer than the message
+        assert_eq!(
+            Err(IcmpExtensionError::UnexpectedEnd(8 + 128)),
+            split_error_extensions(&message[..8 + 127])
+        );
+        // length set but no extensions present
+        assert_eq!(Ok((&message[8..8 + 128], None)), split_error_extensions(&message[..8 + 128]));
+        // incomplete extension header
+        assert_eq!(
+            Err(IcmpExtensionError::UnexpectedEnd(8 + 128 + etherparse::icmp_extension::HEADER_LEN)),
+            split_error_extensions(&message[..8 + 128 + 1])
+        );
+    }
+
+    // messages of other types
+    let message = [icmpv4_type::ECHO_REQUEST, 0, 0, 0, 0, 32, 0, 0, 1, 2];
+    assert_eq!(Ok((&message[8..], None)), split_error_extensions(&message));
+
+    // too short
+    assert_eq!(
+        Err(IcmpExtensionError::UnexpectedEnd(8)),
+        split_error_extensions(&[icmpv4_type::TIME_EXCEEDED, 0, 0, 0])
+    );
+    assert_eq!(
+        Err(IcmpExtensionError::UnexpectedEnd(8)),
+        append_error_extensions(&mut vec![icmpv4_type::TIME_EXCEEDED, 0, 0, 0], &objects)
+    );
+
+    // original datagram too large
+    {
+        let mut message = vec![icmpv4_type::TIME_EXCEEDED, 0, 0, 0, 0, 0, 0, 0];
+        message.resize(8 + 256*4, 0);
+        assert_eq!(
+            Err(IcmpExtensionError::OriginalDatagramTooLarge(256*4)),
+            append_error_extensions(&mut message, &objects)
+        );
+        assert_eq!(8 + 256*4, message.len());
+    }
+}
+
+#[test]
+fn icmpv6_error_extensions() {
+    use etherparse::icmpv6::*;
+
+    let objects = [
+        IcmpExtensionObject::InterfaceInformation(InterfaceInformation{
+            role: InterfaceRole::IncomingIpInterface,
+            if_index: Some(1),
+            ip_addr: None,
+            name: None,
+            mtu: None,
+        }),
+    ];
+    let original = [0x60u8; 130];
+
+    for type_u8 in &[icmpv6_type::DESTINATION_UNREACHABLE, icmpv6_type::TIME_EXCEEDED] {
+        let mut message = vec![*type_u8, 0, 0, 0, 0, 0, 0, 0];
+        message.extend_from_slice(&original);
+        assert_eq!(Ok((&original[..], None)), split_error_extensions(&message));
+
+        // padded to a multiple of 8 bytes
+        append_error_extensions(&mut message, &objects).unwrap();
+        assert_eq!(136/8, message[4]);
+        let (data, extensions) = split_error_extensions(&message).unwrap();
+        assert_eq!(136, data.len());
+        assert_eq!(
+            &objects[..],
+            &extensions.unwrap().objects().collect::<Result<Vec<_>, _>>().unwrap()[..]
+        );
+    }
+
+    // messages of other types
+    let message = [icmpv6_type::PACKET_TOO_BIG, 0, 0, 0, 16, 0, 0, 0, 1, 2];
+    assert_eq!(Ok((&message[8..], None)), split_error_extensions(&message));
+}
+
+#[test]
+fn interface_identification() {
+    let ipv4 = [192u8, 168, 1, 1];
+    let ipv6 = [1u8; 16];
+    let mac = [1u8, 2, 3, 4, 5, 6];
+    let values = [
+        (InterfaceIdentification::Name("eth0"), 8),
+        (InterfaceIdentification::Name("eth10"), 12),
+        (InterfaceIdentification::Index(3), 8),
+        (InterfaceIdentification::Address{ afi: InterfaceInformation::AFI_IPV4, address: &ipv4 }, 12),
+        (InterfaceIdentification::Address{ afi: InterfaceInformation::AFI_IPV6, address: &ipv6 }, 24),
+        (InterfaceIdentification::Address{ afi: 6, address: &mac }, 16),
+    ];
+    for (value, len) in &values {
+        let object = IcmpExtensionObject::InterfaceIdentification(value.clone());
+        let mut bytes = Vec::new();
+        object.write(&mut bytes).unwrap();
+        assert_eq!(*len, bytes.len());
+        assert_eq!(icmp_extension_class::INTERFACE_IDENTIFICATION, bytes[2]);
+        assert_eq!(value.c_type(), bytes[3]);
+        assert_eq!((object, &[][..]), IcmpExtensionObject::from_slice(&bytes).unwrap());
+    }
+
+    // address layout
+    {
+        let mut bytes = Vec::new();
+        IcmpExtensionObject::InterfaceIdentification(values[3].0.clone()).write(&mut bytes).unwrap();
+        assert_eq!(&[0, 12, 3, 3, 0, 1, 4, 0, 192, 168, 1, 1][..], &bytes[..]);
+    }
+
+    // ip addr
+    assert_eq!(Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))), values[3].0.ip_addr());
+    assert_eq!(Some(IpAddr::V6(Ipv6Addr::from(ipv6))), values[4].0.ip_addr());
+    assert_eq!(None, values[5].0.ip_addr());
+    assert_eq!(None, values[0].0.ip_addr());
+
+    // unknown c-types
+    assert_eq!(
+        (IcmpExtensionObject::Unknown{ class_num: 3, c_type: 4, data: &[1, 2, 3, 4] }, &[][..]),
+        IcmpExtensionObject::from_slice(&[0, 8, 3, 4, 1, 2, 3, 4]).unwrap()
+    );
+
+    // errors
+    assert_eq!(
+        Err(IcmpExtensionError::InvalidObject{ class_num: 3, c_type: 2 }),
+        IcmpExtensionObject::from_slice(&[0, 6, 3, 2, 0, 0])
+    );
+    assert_eq!(
+        Err(IcmpExtensionError::InvalidObject{ class_num: 3, c_type: 3 }),
+        IcmpExtensionObject::from_slice(&[0, 8, 3, 3, 0, 1, 4, 0])
+    );
+    assert_eq!(
+        Err(IcmpExtensionError::InvalidObject{ class_num: 3, c_type: 1 }),
+        IcmpExtensionObject::from_slice(&[0, 8, 3, 1, 0xff, 0xfe, 0, 0])
+    );
+    {
+        let address = [0u8; 256];
+        let object = IcmpExtensionObject::InterfaceIdentification(InterfaceIdentification::Address{ afi: 1, address: &address });
+        let mut bytes = Vec::new();
+        assert_eq!(Err(IcmpExtensionError::ObjectTooLarge(264)), object.write(&mut bytes));
+        assert!(bytes.is_empty());
+    }
+}
+
+#[test]
+fn extended_echo_request() {
+    let header = ExtendedEchoRequestHeader{ id: 0x1234, seq: 5, local: true };
+    assert_eq!([0x12, 0x34, 5, 1], header.to_bytes());
+    assert_eq!(header, ExtendedEchoRequestHeader::from_bytes(header.to_bytes()));
+    assert!(!ExtendedEchoRequestHeader::from_bytes([0, 0, 0, 0xfe]).local);
+
+    // interface identification
+    let mut payload = Vec::new();
+    write_extensions(
+        &[
+            IcmpExtensionObject::Unknown{ class_num: 4, c_type: 1, data: &[] },
+            IcmpExtensionObject::InterfaceIdentification(InterfaceIdentification::Index(7)),
+        ],
+        &mut payload
+    ).unwrap();
+    assert_eq!(
+        Ok(Some(InterfaceIdentification::Index(7))),
+        header.interface_identification(&payload)
+    );
+
+    // no identification object
+    let mut payload = Vec::new();
+    write_extensions(&[], &mut payload).unwrap();
+    assert_eq!(Ok(None), header.interface_identification(&payload));
+
+    // errors
+    assert_eq!(
+        Err(IcmpExtensionError::UnexpectedEnd(HEADER_LEN)),
+        header.interface_identification(&[])
+    );
+    assert_eq!(
+        Err(IcmpExtensionError::ObjectLengthBad(0)),
+        header.interface_identification(&[VERSION << 4, 0, 0, 0, 0, 0, 3, 2])
+    );
+}
+
+#[test]
+fn extended_echo_reply() {
+    use ExtendedEchoReplyCode::*;
+
+    for code in 0..=4u8 {
+        assert_eq!(code, ExtendedEchoReplyCode::from_u8(code).unwrap().code_u8());
+    }
+    assert_eq!(None, ExtendedEchoReplyCode::from_u8(5));
+
+    let header = ExtendedEchoReplyHeader{
+        code: NoError,
+        id: 0x1234,
+        seq: 5,
+        state: extended_echo_state::REACHABLE,
+        active: true,
+        ipv4: false,
+        ipv6: true,
+    };
+    assert_eq!([0x12, 0x34, 5, 0b0100_0101], header.to_bytes());
+    assert_eq!(Some(header), ExtendedEchoReplyHeader::from_values(0, header.to_bytes()));
+    assert_eq!(None, ExtendedEchoReplyHeader::from_values(5, header.to_bytes()));
+
+    // state bits outside of the 3 bit range are ignored
+    let header = ExtendedEchoReplyHeader{
+        code: MalformedQuery,
+        id: 0,
+        seq: 0,
+        state: 0xff,
+        active: false,
+        ipv4: true,
+        ipv6: false,
+    };
+    assert_eq!([0, 0, 0, 0b1110_0010], header.to_bytes());
+}
@@ -0,0 +1,338 @@
+use super::*;
+
+use etherparse::icmpv4::*;
+
+#[test]
+fn from_bytes_to_bytes() {
+    use Icmp4Type::*;
+
+    let values = [
+        EchoReply(IcmpEchoHeader{ id: 0x1234, seq: 0x5678 }),
+        EchoRequest(IcmpEchoHeader{ id: 0x1234, seq: 0x5678 }),
+        DestinationUnreachable(DestUnreachableHeader::Port),
+        DestinationUnreachable(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 1400 }),
+        Redirect(RedirectHeader{ code: RedirectCode::TosHost, gateway_internet_address: [192,168,1,1] }),
+        TimeExceeded(TimeExceededCode::FragmentReassemblyTimeExceeded),
+        ParameterProblem(ParameterProblemHeader::PointerIndicatesError(9)),
+        ParameterProblem(ParameterProblemHeader::BadLength),
+        TimestampRequest(TimestampMessage{
+            id: 1,
+            seq: 2,
+            originate_timestamp: 3,
+            receive_timestamp: 4,
+            transmit_timestamp: 5,
+        }),
+        TimestampReply(TimestampMessage{
+            id: 1,
+            seq: 2,
+            originate_timestamp: 3,
+            receive_timestamp: 4,
+            transmit_timestamp: 5,
+        }),
+        AddressMaskRequest(AddressMaskMessage{ id: 1, seq: 2, address_mask: [0;4] }),
+        AddressMaskReply(AddressMaskMessage{ id: 1, seq: 2, address_mask: [255,255,255,0] }),
+        RouterAdvertisement(RouterAdvertisementHeader{ num_addrs: 2, addr_entry_size: 2, lifetime: 1800 }),
+        RouterSolicitation,
+        ExtendedEchoRequest(etherparse::icmp_extension::ExtendedEchoRequestHeader{ id: 1, seq: 2, local: true }),
+        ExtendedEchoReply(etherparse::icmp_extension::ExtendedEchoReplyHeader{
+            code: etherparse::icmp_extension::ExtendedEchoReplyCode::NoSuchTableEntry,
+            id: 1,
+            seq: 2,
+            state: 0,
+            active: true,
+            ipv4: true,
+            ipv6: false,
+        }),
+        Unknown{ type_u8: 42, code_u8: 1, bytes5to8: [1,2,3,4] },
+    ];
+    for value in &values {
+        let mut bytes = value.to_bytes();
+        assert_eq!(value.header_len(), bytes.len());
+        assert_eq!(value.type_u8(), bytes[0]);
+        assert_eq!(value.code_u8(), bytes[1]);
+        assert_eq!([0, 0], bytes[2..4]);
+        bytes.extend_from_slice(&[9, 9]);
+        assert_eq!((*value, &[9u8, 9][..]), Icmp4Type::from_bytes(&bytes).unwrap());
+    }
+}
+
+#[test]
+fn dest_unreachable_codes() {
+    for code in 0..=15u8 {
+        let value = DestUnreachableHeader::from_values(code, [0;4]).unwrap();
+        assert_eq!(code, value.code_u8());
+        let (decoded, _) = Icmp4Type::from_bytes(&[icmpv4_type::DESTINATION_UNREACHABLE, code, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(Icmp4Type::DestinationUnreachable(value), decoded);
+    }
+    assert_eq!(None, DestUnreachableHeader::from_values(16, [0;4]));
+    assert_eq!(
+        Some(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 0x1234 }),
+        DestUnreachableHeader::from_values(4, [0, 0, 0x12, 0x34])
+    );
+}
+
+#[test]
+fn unknown_codes() {
+    use icmpv4_type::*;
+    for (type_u8, code_u8) in &[
+        (ECHO_REPLY, 1),
+        (ECHO_REQUEST, 1),
+        (DESTINATION_UNREACHABLE, 16),
+        (REDIRECT, 4),
+        (TIME_EXCEEDED, 2),
+        (PARAMETER_PROBLEM, 3),
+        (TIMESTAMP_REQUEST, 1),
+        (TIMESTAMP_REPLY, 1),
+        (ADDRESS_MASK_REQUEST, 1),
+        (ADDRESS_MASK_REPLY, 1),
+        (ROUTER_ADVERTISEMENT, 16),
+        (ROUTER_SOLICITATION, 1),
+        (EXTENDED_ECHO_REQUEST, 1),
+        (EXTENDED_ECHO_REPLY, 5),
+    ] {
+        let bytes = [*type_u8, *code_u8, 0, 0, 1, 2, 3, 4];
+        assert_eq!(
+            (Icmp4Type::Unknown{ type_u8: *type_u8, code_u8: *code_u8, bytes5to8: [1,2,3,4] }, &[][..]),
+            Icmp4Type::from_bytes(&bytes).unwrap()
+        );
+    }
+}
+
+#[test]
+fn from_bytes_too_short() {
+    for len in 0..HEADER_LEN {
+        assert_matches!(
+            Icmp4Type::from_bytes(&[icmpv4_type::ECHO_REQUEST, 0, 0, 0, 0, 0, 0, 0][..len]),
+            Err(ReadError::UnexpectedEndOfSlice(HEADER_LEN))
+        );
+    }
+    // timestamp messages without the timestamps
+    let bytes = [icmpv4_type::TIMESTAMP_REQUEST, 0, 0, 0, 0, 1, 0, 2, 0, 0];
+    assert_eq!(
+        (Icmp4Type::Unknown{ type_u8: icmpv4_type::TIMESTAMP_REQUEST, code_u8: 0, bytes5to8: [0,1,0,2] }, &[0u8, 0][..]),
+        Icmp4Type::from_bytes(&bytes).unwrap()
+    );
+    // address mask messages without the address mask
+    let bytes = [icmpv4_type::ADDRESS_MASK_REPLY, 0, 0, 0, 0, 1, 0, 2, 255, 255];
+    assert_eq!(
+        (Icmp4Type::Unknown{ type_u8: icmpv4_type::ADDRESS_MASK_REPLY, code_u8: 0, bytes5to8: [0,1,0,2] }, &[255u8, 255][..]),
+        Icmp4Type::from_bytes(&bytes).unwrap()
+    );
+}
+
+#[test]
+fn router_advertisement() {
+    let entries = [
+        RouterAdvertisementEntry{ router_address: [192,168,1,1], preference_level: 0x1234 },
+        RouterAdvertisementEntry{ router_address: [192,168,1,2], preference_level: -1 },
+    ];
+    assert_eq!([192,168,1,2,0xff,0xff,0xff,0xff], entries[1].to_bytes());
+
+    // default entry size
+    {
+        let header = RouterAdvertisementHeader{ num_addrs: 2, ..Default::default() };
+        let mut bytes = Icmp4Type::RouterAdvertisement(header).to_bytes();
+        assert_eq!([9, 0, 0, 0, 2, 2, 0, 0], bytes[..]);
+        for entry in &entries {
+            bytes.extend_from_slice(&entry.to_bytes());
+        }
+        let (decoded, rest) = Icmp4Type::from_bytes(&bytes).unwrap();
+        assert_eq!(Icmp4Type::RouterAdvertisement(header), decoded);
+        assert_eq!(&entries[..], &header.entries(rest).collect::<Vec<_>>()[..]);
+
+        // incomplete entries are ignored
+        assert_eq!(&entries[..1], &header.entries(&rest[..rest.len() - 1]).collect::<Vec<_>>()[..]);
+    }
+
+    // larger entry size
+    {
+        let header = RouterAdvertisementHeader{ num_addrs: 1, addr_entry_size: 3, lifetime: 10 };
+        let mut payload = entries[0].to_bytes().to_vec();
+        payload.extend_from_slice(&[0;4]);
+        payload.extend_from_slice(&entries[1].to_bytes());
+        assert_eq!(&entries[..1], &header.entries(&payload).collect::<Vec<_>>()[..]);
+    }
+
+    // entry sizes smaller than 2 are not decoded
+    let bytes = [icmpv4_type::ROUTER_ADVERTISEMENT, 0, 0, 0, 1, 1, 0, 0];
+    assert_eq!(
+        (Icmp4Type::Unknown{ type_u8: icmpv4_type::ROUTER_ADVERTISEMENT, code_u8: 0, bytes5to8: [1,1,0,0] }, &[][..]),
+        Icmp4Type::from_bytes(&bytes).unwrap()
+    );
+}
+
+#[test]
+fn calc_checksum() {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut original = Vec::new();
+    builder.write(&mut original, &[1,2,3,4]).unwrap();
+
+    let value = Icmp4Type::DestinationUnreachable(DestUnreachableHeader::Port);
+    let checksum = value.calc_checksum(&original);
+
+    // the checksum of the complete message including the checksum is 0
+    let mut message = value.to_bytes();
+    message[2..4].copy_from_slice(&checksum.to_be_bytes());
+    message.extend_from_slice(&original);
+    assert_eq!(
+        0,
+        etherparse::checksum::Sum16BitWords::new().add_slice(&message).ones_complement()
+    );
+    assert!(is_checksum_valid(&message));
+    assert!(!is_checksum_valid(&message[..message.len() - 1]));
+    assert!(!is_checksum_valid(&[0;8]));
+    assert!(!is_checksum_valid(&message[..4]));
+}
+
+#[test]
+fn code_constants() {
+    assert_eq!(dest_unreachable_code::PRECEDENCE_CUTOFF, DestUnreachableHeader::PrecedenceCutoff.code_u8());
+    assert_eq!(
+        dest_unreachable_code::FRAGMENTATION_NEEDED,
+        DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 0 }.code_u8()
+    );
+    assert_eq!(redirect_code::TOS_HOST, RedirectCode::TosHost.code_u8());
+    assert_eq!(
+        time_exceeded_code::FRAGMENT_REASSEMBLY_TIME_EXCEEDED,
+        TimeExceededCode::FragmentReassemblyTimeExceeded.code_u8()
+    );
+    assert_eq!(parameter_problem_code::BAD_LENGTH, ParameterProblemHeader::BadLength.code_u8());
+}
+
+#[test]
+fn raw_bytes5to8() {
+    // echo
+    let echo = IcmpEchoHeader{ id: 0x1234, seq: 0x5678 };
+    assert_eq!([0x12, 0x34, 0x56, 0x78], echo.to_bytes());
+    assert_eq!(echo, IcmpEchoHeader::from_bytes(echo.to_bytes()));
+
+    // destination unreachable
+    let mtu = DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 0x1234 };
+    assert_eq!([0, 0, 0x12, 0x34], mtu.to_bytes());
+    assert_eq!(Some(mtu), DestUnreachableHeader::from_values(mtu.code_u8(), mtu.to_bytes()));
+    assert_eq!([0;4], DestUnreachableHeader::Port.to_bytes());
+
+    // parameter problem
+    let pointer = ParameterProblemHeader::PointerIndicatesError(9);
+    assert_eq!([9, 0, 0, 0], pointer.to_bytes());
+    assert_eq!(Some(pointer), ParameterProblemHeader::from_values(pointer.code_u8(), pointer.to_bytes()));
+    assert_eq!([0;4], ParameterProblemHeader::BadLength.to_bytes());
+}
+
+fn udp_packet(source: [u8;4], destination: [u8;4], payload_len: usize) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4(source, destination, 64)
+        .udp(1234, 5678);
+    let payload: Vec<u8> = (0..payload_len).map(|v| v as u8).collect();
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+    packet
+}
+
+#[test]
+fn error_response() {
+    let router = [192,168,1,1];
+    for payload_len in [0, 100, 548, 1400] {
+        let packet = udp_packet([192,168,1,2], [10,0,0,1], payload_len);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        for value in [
+            Icmp4Type::TimeExceeded(TimeExceededCode::TtlExceededInTransit),
+            Icmp4Type::ParameterProblem(ParameterProblemHeader::PointerIndicatesError(8)),
+            Icmp4Type::DestinationUnreachable(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 1300 }),
+        ] {
+            let response = etherparse::icmpv4::error_response(router, 255, value, &sliced).unwrap();
+            assert_eq!((20 + HEADER_LEN + packet.len()).min(ERROR_RESPONSE_MAX_LEN), response.len());
+
+            let (ip, message) = Ipv4Header::from_slice(&response).unwrap();
+            assert_eq!(router, ip.source);
+            assert_eq!([192,168,1,2], ip.destination);
+            assert_eq!(255, ip.time_to_live);
+            assert_eq!(ip_number::ICMP, ip.protocol);
+            assert_eq!(message.len(), usize::from(ip.payload_len));
+            assert_eq!(ip.calc_header_checksum().unwrap(), ip.header_checksum);
+
+            let (icmp_type, quoted) = Icmp4Type::from_bytes(message).unwrap();
+            assert_eq!(value, icmp_type);
+            assert_eq!(&packet[..quoted.len()], quoted);
+            assert_eq!(
+                0,
+                etherparse::checksum::Sum16BitWords::new().add_slice(message).ones_complement()
+            );
+        }
+        assert_eq!(
+            etherparse::icmpv4::error_response(
+                router,
+                64,
+                Icmp4Type::DestinationUnreachable(DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 1300 }),
+                &sliced
+            ),
+            fragmentation_needed(router, 64, 1300, &sliced)
+        );
+    }
+}
+
+#[test]
+fn error_response_not_sent() {
+    let router = [192,168,1,1];
+    let value = Icmp4Type::DestinationUnreachable(DestUnreachableHeader::Host);
+    // ipv6
+    {
+        let builder = PacketBuilder::
+            ipv6([1;16], [2;16], 64)
+            .udp(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv4::error_response(router, 64, value, &sliced));
+    }
+    // invalid sources
+    for source in [[0,0,0,0], [127,0,0,1], [224,0,0,1], [240,0,0,1], [255,255,255,255]] {
+        let packet = udp_packet(source, [10,0,0,1], 10);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv4::error_response(router, 64, value, &sliced));
+    }
+    // broadcast & multicast destinations
+    for destination in [[255,255,255,255], [224,0,0,251], [239,1,2,3]] {
+        let packet = udp_packet([192,168,1,2], destination, 10);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv4::error_response(router, 64, value, &sliced));
+    }
+    // link layer broadcast
+    for (destination, expected) in [([0xff;6], false), ([1,0,0x5e,0,0,1], false), ([2,0,0,0,0,1], true)] {
+        let builder = PacketBuilder::
+            ethernet2([2,0,0,0,0,2], destination)
+            .ipv4([192,168,1,2], [10,0,0,1], 64)
+            .udp(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(expected, etherparse::icmpv4::error_response(router, 64, value, &sliced).is_some());
+    }
+    // non first fragments
+    {
+        let mut packet = udp_packet([192,168,1,2], [10,0,0,1], 10);
+        // set the fragment offset to 8 bytes & recalculate the checksum
+        packet[7] = 1;
+        let (mut header, _) = Ipv4Header::from_slice(&packet).unwrap();
+        header.header_checksum = header.calc_header_checksum().unwrap();
+        packet[10..12].copy_from_slice(&header.header_checksum.to_be_bytes());
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv4::error_response(router, 64, value, &sliced));
+    }
+    // icmpv4 error messages (informational messages get a response)
+    for (message, expected) in [
+        (&[3u8, 1, 0, 0, 0, 0, 0, 0][..], false),
+        (&[4, 0, 0, 0, 0, 0, 0, 0][..], false),
+        (&[][..], false),
+        (&[8, 0, 0, 0, 0, 0, 0, 0][..], true),
+    ] {
+        let ip = Ipv4Header::new(message.len() as u16, 64, IpNumber::Icmp, [192,168,1,2], [10,0,0,1]);
+        let mut packet = Vec::new();
+        ip.write(&mut packet).unwrap();
+        packet.extend_from_slice(message);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(expected, etherparse::icmpv4::error_response(router, 64, value, &sliced).is_some());
+    }
+}
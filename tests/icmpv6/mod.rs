@@ -0,0 +1,959 @@
+use super::*;
+
+const ROUTER: [u8;16] = [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,0xfe];
+const SOURCE: [u8;16] = [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1];
+const DESTINATION: [u8;16] = [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,2];
+
+fn udp_packet(source: [u8;16], payload_len: usize) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv6(source, DESTINATION, 64)
+        .udp(1234, 5678);
+    let payload: Vec<u8> = (0..payload_len).map(|v| v as u8).collect();
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+    packet
+}
+
+fn icmpv6_packet(message: &[u8]) -> Vec<u8> {
+    let ip = Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: message.len() as u16,
+        next_header: ip_number::IPV6_ICMP,
+        hop_limit: 64,
+        source: SOURCE,
+        destination: DESTINATION,
+    };
+    let mut packet = Vec::new();
+    ip.write(&mut packet).unwrap();
+    packet.extend_from_slice(message);
+    packet
+}
+
+#[test]
+fn checksum() {
+    // echo request captured from "ping -6 ::1"
+    let mut message = [
+        0x80, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01,
+        0x61, 0x62, 0x63, 0x64,
+    ];
+    let mut localhost = [0;16];
+    localhost[15] = 1;
+    let checksum = etherparse::icmpv6::checksum(localhost, localhost, &message);
+    message[2..4].copy_from_slice(&checksum.to_be_bytes());
+    // verifying the complete message results in 0
+    assert_eq!(0, etherparse::icmpv6::checksum(localhost, localhost, &message));
+
+    assert!(etherparse::icmpv6::is_checksum_valid(localhost, localhost, &message));
+    assert!(!etherparse::icmpv6::is_checksum_valid(localhost, [0;16], &message));
+    assert!(!etherparse::icmpv6::is_checksum_valid(localhost, localhost, &message[..11]));
+    assert!(!etherparse::icmpv6::is_checksum_valid(localhost, localhost, &[]));
+}
+
+#[test]
+fn packet_too_big() {
+    for payload_len in [0, 100, 1232, 1500] {
+        let packet = udp_packet(SOURCE, payload_len);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let response = etherparse::icmpv6::packet_too_big(ROUTER, 255, 1280, &sliced).unwrap();
+
+        assert_eq!(
+            (Ipv6Header::SERIALIZED_SIZE + etherparse::icmpv6::ERROR_HEADER_LEN + packet.len()).min(etherparse::icmpv6::IPV6_MIN_MTU),
+            response.len()
+        );
+
+        let (ip, message) = Ipv6Header::from_slice(&response).unwrap();
+        assert_eq!(ROUTER, ip.source);
+        assert_eq!(SOURCE, ip.destination);
+        assert_eq!(255, ip.hop_limit);
+        assert_eq!(ip_number::IPV6_ICMP, ip.next_header);
+        assert_eq!(message.len(), usize::from(ip.payload_length));
+
+        assert_eq!(&[etherparse::icmpv6::icmpv6_type::PACKET_TOO_BIG, 0], &message[..2]);
+        assert_eq!(&1280u32.to_be_bytes(), &message[4..8]);
+        assert_eq!(&packet[..message.len() - 8], &message[8..]);
+        assert_eq!(0, etherparse::icmpv6::checksum(ROUTER, SOURCE, message));
+    }
+}
+
+#[test]
+fn packet_too_big_not_sent() {
+    // ipv4
+    {
+        let builder = PacketBuilder::
+            ipv4([1,2,3,4], [5,6,7,8], 64)
+            .udp(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv6::packet_too_big(ROUTER, 64, 1280, &sliced));
+    }
+    // unspecified & multicast source
+    for source in [[0;16], [0xff,2,0,0,0,0,0,0,0,0,0,0,0,0,0,1]] {
+        let packet = udp_packet(source, 10);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv6::packet_too_big(ROUTER, 64, 1280, &sliced));
+    }
+    // icmpv6 error messages
+    for message in [&[1u8, 0, 0, 0, 0, 0, 0, 0][..], &[]] {
+        let packet = icmpv6_packet(message);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, etherparse::icmpv6::packet_too_big(ROUTER, 64, 1280, &sliced));
+    }
+    // icmpv6 informational messages get a response
+    {
+        let packet = icmpv6_packet(&[128, 0, 0, 0, 0, 0, 0, 0]);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(etherparse::icmpv6::packet_too_big(ROUTER, 64, 1280, &sliced).is_some());
+    }
+}
+
+#[test]
+fn error_response() {
+    use etherparse::icmpv6::icmpv6_type::*;
+
+    let packet = udp_packet(SOURCE, 1400);
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    for (icmp_type, code, bytes5to8) in [
+        (DESTINATION_UNREACHABLE, 3, [0;4]),
+        (TIME_EXCEEDED, 0, [0;4]),
+        (PARAMETER_PROBLEM, 0, [0, 0, 0, 6]),
+        (PACKET_TOO_BIG, 0, [0, 0, 5, 0]),
+    ] {
+        let response = etherparse::icmpv6::error_response(ROUTER, 64, icmp_type, code, bytes5to8, &sliced).unwrap();
+        assert_eq!(etherparse::icmpv6::IPV6_MIN_MTU, response.len());
+
+        let (ip, message) = Ipv6Header::from_slice(&response).unwrap();
+        assert_eq!(ROUTER, ip.source);
+        assert_eq!(SOURCE, ip.destination);
+        assert_eq!(&[icmp_type, code], &message[..2]);
+        assert_eq!(&bytes5to8, &message[4..8]);
+        assert_eq!(&packet[..message.len() - 8], &message[8..]);
+        assert_eq!(0, etherparse::icmpv6::checksum(ROUTER, SOURCE, message));
+    }
+    assert_eq!(
+        etherparse::icmpv6::error_response(ROUTER, 64, PACKET_TOO_BIG, 0, 1280u32.to_be_bytes(), &sliced),
+        etherparse::icmpv6::packet_too_big(ROUTER, 64, 1280, &sliced)
+    );
+
+    // multicast destinations only get packet too big & unrecognized option responses
+    let builder = PacketBuilder::
+        ipv6(SOURCE, [0xff,2,0,0,0,0,0,0,0,0,0,0,0,0,0,1], 64)
+        .udp(1234, 5678);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    for (icmp_type, code, expected) in [
+        (DESTINATION_UNREACHABLE, 0, false),
+        (TIME_EXCEEDED, 0, false),
+        (PARAMETER_PROBLEM, 1, false),
+        (PARAMETER_PROBLEM, 2, true),
+        (PACKET_TOO_BIG, 0, true),
+    ] {
+        assert_eq!(
+            expected,
+            etherparse::icmpv6::error_response(ROUTER, 64, icmp_type, code, [0;4], &sliced).is_some()
+        );
+    }
+}
+
+#[test]
+fn parameter_problem_code() {
+    use etherparse::icmpv6::{parameter_problem_code, ParameterProblemCode};
+    use ParameterProblemCode::*;
+
+    let codes = [
+        (parameter_problem_code::ERRONEOUS_HEADER_FIELD, ErroneousHeaderField),
+        (parameter_problem_code::UNRECOGNIZED_NEXT_HEADER, UnrecognizedNextHeader),
+        (parameter_problem_code::UNRECOGNIZED_IPV6_OPTION, UnrecognizedIpv6Option),
+        (parameter_problem_code::IPV6_FIRST_FRAGMENT_INCOMPLETE_HEADER_CHAIN, Ipv6FirstFragmentIncompleteHeaderChain),
+        (parameter_problem_code::SR_UPPER_LAYER_HEADER_ERROR, SrUpperLayerHeaderError),
+        (parameter_problem_code::UNRECOGNIZED_NEXT_HEADER_BY_INTERMEDIATE_NODE, UnrecognizedNextHeaderByIntermediateNode),
+        (parameter_problem_code::EXTENSION_HEADER_TOO_BIG, ExtensionHeaderTooBig),
+        (parameter_problem_code::EXTENSION_HEADER_CHAIN_TOO_LONG, ExtensionHeaderChainTooLong),
+        (parameter_problem_code::TOO_MANY_EXTENSION_HEADERS, TooManyExtensionHeaders),
+        (parameter_problem_code::TOO_MANY_OPTIONS_IN_EXTENSION_HEADER, TooManyOptionsInExtensionHeader),
+        (parameter_problem_code::OPTION_TOO_BIG, OptionTooBig),
+    ];
+    for (index, (code_u8, code)) in codes.iter().enumerate() {
+        assert_eq!(index, usize::from(*code_u8));
+        assert_eq!(*code_u8, code.code_u8());
+        assert_eq!(Some(*code), ParameterProblemCode::from_u8(*code_u8));
+    }
+    for code_u8 in 11..=255 {
+        assert_eq!(None, ParameterProblemCode::from_u8(code_u8));
+    }
+}
+
+mod ndp_option {
+    use super::*;
+    use etherparse::icmpv6::{ndp_option_type, DnsslOption, NdpOption, NdpOptionError, NdpOptionsIterator, PrefixInformationOption, RdnssOption};
+
+    #[test]
+    fn rdnss_write_read() {
+        let option = NdpOption::RecursiveDnsServer(RdnssOption{
+            lifetime: 600,
+            addresses: vec![SOURCE, DESTINATION],
+        });
+        let mut buffer = Vec::new();
+        option.write(&mut buffer).unwrap();
+        assert_eq!(40, buffer.len());
+        assert_eq!(&[ndp_option_type::RECURSIVE_DNS_SERVER, 5, 0, 0, 0, 0, 2, 0x58], &buffer[..8]);
+        assert_eq!(&SOURCE, &buffer[8..24]);
+        assert_eq!(&DESTINATION, &buffer[24..40]);
+
+        let (decoded, rest) = NdpOption::from_slice(&buffer).unwrap();
+        assert_eq!(option, decoded);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn rdnss_invalid_length() {
+        // no address
+        assert_eq!(
+            NdpOption::from_slice(&[ndp_option_type::RECURSIVE_DNS_SERVER, 1, 0, 0, 0, 0, 0, 0]),
+            Err(NdpOptionError::InvalidLength{ option_type: ndp_option_type::RECURSIVE_DNS_SERVER, length: 1 })
+        );
+        // half an address
+        let data = [ndp_option_type::RECURSIVE_DNS_SERVER, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(
+            NdpOption::from_slice(&data),
+            Err(NdpOptionError::InvalidLength{ option_type: ndp_option_type::RECURSIVE_DNS_SERVER, length: 4 })
+        );
+    }
+
+    #[test]
+    fn dnssl_write_read() {
+        let option = NdpOption::DnsSearchList(DnsslOption{
+            lifetime: u32::MAX,
+            domains: vec!["example.com".to_string(), "lan".to_string()],
+        });
+        let mut buffer = Vec::new();
+        option.write(&mut buffer).unwrap();
+        assert_eq!(
+            &buffer[..],
+            &[
+                ndp_option_type::DNS_SEARCH_LIST, 4, 0, 0, 0xff, 0xff, 0xff, 0xff,
+                7, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+                3, b'c', b'o', b'm', 0, 3, b'l', b'a',
+                b'n', 0, 0, 0, 0, 0, 0, 0,
+            ][..]
+        );
+
+        let (decoded, rest) = NdpOption::from_slice(&buffer).unwrap();
+        assert_eq!(option, decoded);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn dnssl_trailing_dot() {
+        let mut with_dot = Vec::new();
+        NdpOption::DnsSearchList(DnsslOption{
+            lifetime: 1,
+            domains: vec!["example.com.".to_string()],
+        }).write(&mut with_dot).unwrap();
+        let mut without_dot = Vec::new();
+        NdpOption::DnsSearchList(DnsslOption{
+            lifetime: 1,
+            domains: vec!["example.com".to_string()],
+        }).write(&mut without_dot).unwrap();
+        assert_eq!(with_dot, without_dot);
+    }
+
+    #[test]
+    fn dnssl_invalid_domain_write() {
+        let long_label = "a".repeat(64);
+        for domain in ["", ".", "a..b", long_label.as_str()].iter() {
+            let mut buffer = vec![1, 2, 3];
+            assert_eq!(
+                Err(NdpOptionError::InvalidDomainName),
+                NdpOption::DnsSearchList(DnsslOption{
+                    lifetime: 1,
+                    domains: vec![domain.to_string()],
+                }).write(&mut buffer)
+            );
+            // nothing written on error
+            assert_eq!(vec![1, 2, 3], buffer);
+        }
+    }
+
+    #[test]
+    fn dnssl_invalid_domain_read() {
+        // compression pointer
+        assert_eq!(
+            NdpOption::from_slice(&[ndp_option_type::DNS_SEARCH_LIST, 2, 0, 0, 0, 0, 0, 1, 0xc0, 0x0c, 0, 0, 0, 0, 0, 0]),
+            Err(NdpOptionError::InvalidDomainName)
+        );
+        // label exceeds the option
+        assert_eq!(
+            NdpOption::from_slice(&[ndp_option_type::DNS_SEARCH_LIST, 2, 0, 0, 0, 0, 0, 1, 8, b'a', b'b', b'c', b'd', b'e', b'f', b'g']),
+            Err(NdpOptionError::InvalidDomainName)
+        );
+        // data after padding
+        assert_eq!(
+            NdpOption::from_slice(&[ndp_option_type::DNS_SEARCH_LIST, 2, 0, 0, 0, 0, 0, 1, 1, b'a', 0, 0, 1, 0, 0, 0]),
+            Err(NdpOptionError::InvalidDomainName)
+        );
+    }
+
+    #[test]
+    fn unknown_write_read() {
+        let data = [1, 2, 3, 4, 5, 6];
+        let option = NdpOption::Unknown{ option_type: 200, data: &data };
+        let mut buffer = Vec::new();
+        option.write(&mut buffer).unwrap();
+        assert_eq!(vec![200, 1, 1, 2, 3, 4, 5, 6], buffer);
+        assert_eq!(Ok((option, &[][..])), NdpOption::from_slice(&buffer));
+    }
+
+    #[test]
+    fn link_layer_address_write_read() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        for option in &[NdpOption::SourceLinkLayerAddress(&mac), NdpOption::TargetLinkLayerAddress(&mac)] {
+            let mut buffer = Vec::new();
+            option.write(&mut buffer).unwrap();
+            assert_eq!(vec![option.option_type(), 1, 1, 2, 3, 4, 5, 6], buffer);
+            assert_eq!(Ok((option.clone(), &[][..])), NdpOption::from_slice(&buffer));
+            assert_eq!(Some(MacAddr(mac)), option.mac_addr());
+        }
+        assert_eq!(None, NdpOption::SourceLinkLayerAddress(&mac[..5]).mac_addr());
+        assert_eq!(None, NdpOption::Mtu(1500).mac_addr());
+    }
+
+    #[test]
+    fn prefix_information_write_read() {
+        let option = NdpOption::PrefixInformation(PrefixInformationOption{
+            prefix_length: 64,
+            on_link: true,
+            autonomous: false,
+            valid_lifetime: 0x01020304,
+            preferred_lifetime: 0x05060708,
+            prefix: ROUTER,
+        });
+        let mut buffer = Vec::new();
+        option.write(&mut buffer).unwrap();
+        assert_eq!(PrefixInformationOption::LEN, buffer.len());
+        assert_eq!(
+            &[ndp_option_type::PREFIX_INFORMATION, 4, 64, 0x80, 1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0],
+            &buffer[..16]
+        );
+        assert_eq!(&ROUTER, &buffer[16..]);
+        assert_eq!(Ok((option, &[][..])), NdpOption::from_slice(&buffer));
+
+        // autonomous flag
+        buffer[3] = 0x40;
+        match NdpOption::from_slice(&buffer) {
+            Ok((NdpOption::PrefixInformation(value), _)) => {
+                assert!(!value.on_link);
+                assert!(value.autonomous);
+            },
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        // bad length
+        let mut data = vec![0u8;24];
+        data[0] = ndp_option_type::PREFIX_INFORMATION;
+        data[1] = 3;
+        assert_eq!(
+            Err(NdpOptionError::InvalidLength{ option_type: ndp_option_type::PREFIX_INFORMATION, length: 3 }),
+            NdpOption::from_slice(&data)
+        );
+    }
+
+    #[test]
+    fn redirected_header_write_read() {
+        let data = [0x60, 0, 0, 0, 0, 0, 0, 0];
+        let option = NdpOption::RedirectedHeader(&data);
+        let mut buffer = Vec::new();
+        option.write(&mut buffer).unwrap();
+        assert_eq!(vec![ndp_option_type::REDIRECTED_HEADER, 2, 0, 0, 0, 0, 0, 0, 0x60, 0, 0, 0, 0, 0, 0, 0], buffer);
+        assert_eq!(Ok((option, &[][..])), NdpOption::from_slice(&buffer));
+    }
+
+    #[test]
+    fn mtu_write_read() {
+        let option = NdpOption::Mtu(1500);
+        let mut buffer = Vec::new();
+        option.write(&mut buffer).unwrap();
+        assert_eq!(vec![ndp_option_type::MTU, 1, 0, 0, 0, 0, 0x05, 0xdc], buffer);
+        assert_eq!(Ok((option, &[][..])), NdpOption::from_slice(&buffer));
+
+        assert_eq!(
+            Err(NdpOptionError::InvalidLength{ option_type: ndp_option_type::MTU, length: 2 }),
+            NdpOption::from_slice(&[ndp_option_type::MTU, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        assert_eq!(Err(NdpOptionError::UnexpectedEnd(2)), NdpOption::from_slice(&[1]));
+        assert_eq!(Err(NdpOptionError::ZeroLength), NdpOption::from_slice(&[1, 0]));
+        assert_eq!(Err(NdpOptionError::UnexpectedEnd(16)), NdpOption::from_slice(&[1, 2, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn option_too_large() {
+        let option = NdpOption::RecursiveDnsServer(RdnssOption{
+            lifetime: 0,
+            addresses: vec![SOURCE; 128],
+        });
+        let mut buffer = Vec::new();
+        assert_eq!(Err(NdpOptionError::OptionTooLarge(8 + 128*16)), option.write(&mut buffer));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn iterator() {
+        let mut buffer = Vec::new();
+        let rdnss = NdpOption::RecursiveDnsServer(RdnssOption{
+            lifetime: 10,
+            addresses: vec![ROUTER],
+        });
+        let dnssl = NdpOption::DnsSearchList(DnsslOption{
+            lifetime: 20,
+            domains: vec!["example.org".to_string()],
+        });
+        rdnss.write(&mut buffer).unwrap();
+        dnssl.write(&mut buffer).unwrap();
+
+        let mut iter = NdpOptionsIterator::from_slice(&buffer);
+        assert_eq!(Some(Ok(rdnss)), iter.next());
+        assert_eq!(Some(Ok(dnssl)), iter.next());
+        assert_eq!(None, iter.next());
+
+        // the iteration stops after an error
+        buffer.extend_from_slice(&[1, 0, 1, 1]);
+        let mut iter = NdpOptionsIterator::from_slice(&buffer);
+        assert!(iter.next().unwrap().is_ok());
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(Some(Err(NdpOptionError::ZeroLength)), iter.next());
+        assert_eq!(None, iter.next());
+        assert!(iter.rest().is_empty());
+    }
+
+    #[test]
+    fn iterator_from_ndp_message() {
+        use etherparse::icmpv6::icmpv6_type::*;
+
+        let mut options = Vec::new();
+        NdpOption::Mtu(1280).write(&mut options).unwrap();
+
+        for (icmp_type, fixed_len) in &[
+            (ROUTER_SOLICITATION, 8),
+            (ROUTER_ADVERTISEMENT, 16),
+            (NEIGHBOR_SOLICITATION, 24),
+            (NEIGHBOR_ADVERTISEMENT, 24),
+            (REDIRECT, 40),
+        ] {
+            let mut message = vec![0u8;*fixed_len];
+            message[0] = *icmp_type;
+            message.extend_from_slice(&options);
+            let mut iter = NdpOptionsIterator::from_ndp_message(&message).unwrap();
+            assert_eq!(Some(Ok(NdpOption::Mtu(1280))), iter.next());
+            assert_eq!(None, iter.next());
+
+            // too short for the fixed part
+            assert_eq!(None, NdpOptionsIterator::from_ndp_message(&message[..fixed_len - 1]));
+        }
+
+        // no neighbor discovery message
+        assert_eq!(None, NdpOptionsIterator::from_ndp_message(&[ECHO_REQUEST, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(None, NdpOptionsIterator::from_ndp_message(&[]));
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            "NdpOptionError: Length 1 is not valid for options of type 25.",
+            format!("{}", NdpOptionError::InvalidLength{ option_type: 25, length: 1 })
+        );
+        assert_eq!(
+            "NdpOptionError: Invalid domain name.",
+            format!("{}", NdpOptionError::InvalidDomainName)
+        );
+    }
+}
+
+mod ndp_message {
+    use super::*;
+    use etherparse::icmpv6::*;
+
+    fn messages() -> Vec<NdpMessage> {
+        vec![
+            NdpMessage::RouterSolicitation,
+            NdpMessage::RouterAdvertisement(RouterAdvertisementHeader{
+                cur_hop_limit: 64,
+                managed_address_configuration: true,
+                other_configuration: false,
+                router_lifetime: 1800,
+                reachable_time: 30_000,
+                retrans_timer: 1000,
+            }),
+            NdpMessage::RouterAdvertisement(RouterAdvertisementHeader{
+                other_configuration: true,
+                ..Default::default()
+            }),
+            NdpMessage::NeighborSolicitation(NeighborSolicitationHeader{
+                target_address: DESTINATION,
+            }),
+            NdpMessage::NeighborAdvertisement(NeighborAdvertisementHeader{
+                router_flag: true,
+                solicited_flag: false,
+                override_flag: true,
+                target_address: SOURCE,
+            }),
+            NdpMessage::NeighborAdvertisement(NeighborAdvertisementHeader{
+                solicited_flag: true,
+                ..Default::default()
+            }),
+            NdpMessage::Redirect(RedirectHeader{
+                target_address: SOURCE,
+                destination_address: DESTINATION,
+            }),
+        ]
+    }
+
+    #[test]
+    fn to_bytes_from_slice() {
+        for message in messages() {
+            let mut bytes = message.to_bytes();
+            assert_eq!(message.fixed_len(), bytes.len());
+            assert_eq!(message.icmp_type(), bytes[0]);
+            assert_eq!(&[0, 0, 0], &bytes[1..4]);
+
+            // without options
+            assert_eq!(Some((message.clone(), &[][..])), NdpMessage::from_slice(&bytes));
+
+            // with options
+            NdpOption::Mtu(1280).write(&mut bytes).unwrap();
+            let (decoded, options) = NdpMessage::from_slice(&bytes).unwrap();
+            assert_eq!(message, decoded);
+            assert_eq!(
+                vec![Ok(NdpOption::Mtu(1280))],
+                NdpOptionsIterator::from_slice(options).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![Ok(NdpOption::Mtu(1280))],
+                NdpOptionsIterator::from_ndp_message(&bytes).unwrap().collect::<Vec<_>>()
+            );
+
+            // too short
+            for len in 0..message.fixed_len() {
+                assert_eq!(None, NdpMessage::from_slice(&bytes[..len]));
+            }
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let bytes = NdpMessage::NeighborAdvertisement(NeighborAdvertisementHeader{
+            router_flag: true,
+            solicited_flag: true,
+            override_flag: true,
+            target_address: [0;16],
+        }).to_bytes();
+        assert_eq!(0xe0, bytes[4]);
+
+        let bytes = NdpMessage::RouterAdvertisement(RouterAdvertisementHeader{
+            managed_address_configuration: true,
+            other_configuration: true,
+            ..Default::default()
+        }).to_bytes();
+        assert_eq!(0xc0, bytes[5]);
+    }
+
+    #[test]
+    fn from_slice_other_type() {
+        // echo request
+        assert_eq!(None, NdpMessage::from_slice(&[128, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(None, NdpMessage::from_slice(&[]));
+    }
+}
+
+mod mld {
+    use super::*;
+    use etherparse::icmpv6::*;
+    use etherparse::multicast::{group_record_type, membership_events, MembershipEvent};
+
+    const GROUP: [u8;16] = [0xff,0x02,0,0,0,0,0,0,0,0,0,0,0,0,0,0xfb];
+
+    fn messages() -> Vec<MldMessage> {
+        let v1 = MldV1Message{
+            max_response_delay: 10_000,
+            multicast_address: GROUP,
+        };
+        vec![
+            MldMessage::Query(v1.clone()),
+            MldMessage::Report(MldV1Message{ max_response_delay: 0, ..v1.clone() }),
+            MldMessage::Done(MldV1Message{ max_response_delay: 0, ..v1 }),
+            MldMessage::QueryV2(MldV2Query{
+                max_response_code: 0x8123,
+                multicast_address: GROUP,
+                suppress_router_side_processing: true,
+                querier_robustness_variable: 2,
+                querier_query_interval_code: 125,
+                sources: vec![SOURCE, DESTINATION],
+            }),
+            MldMessage::QueryV2(MldV2Query::default()),
+            MldMessage::ReportV2(vec![
+                MldAddressRecord{
+                    record_type: group_record_type::MODE_IS_INCLUDE,
+                    multicast_address: GROUP,
+                    sources: vec![SOURCE],
+                    aux_data: vec![1,2,3,4],
+                },
+                MldAddressRecord{
+                    record_type: group_record_type::CHANGE_TO_EXCLUDE_MODE,
+                    multicast_address: GROUP,
+                    sources: Vec::new(),
+                    aux_data: Vec::new(),
+                },
+            ]),
+            MldMessage::ReportV2(Vec::new()),
+        ]
+    }
+
+    #[test]
+    fn to_bytes_from_slice() {
+        for message in messages() {
+            let bytes = message.to_bytes().unwrap();
+            assert_eq!(message.header_len(), bytes.len());
+            assert_eq!(message.icmp_type(), bytes[0]);
+            assert_eq!(&[0, 0, 0], &bytes[1..4]);
+            assert_eq!(Some(message.clone()), MldMessage::from_slice(&bytes).unwrap());
+
+            let mut written = Vec::new();
+            message.write(&mut written).unwrap();
+            assert_eq!(bytes, written);
+        }
+    }
+
+    #[test]
+    fn from_slice_too_short() {
+        for message in messages() {
+            let bytes = message.to_bytes().unwrap();
+            for len in 0..bytes.len() {
+                match &message {
+                    // shorter MLDv2 queries are MLDv1 queries
+                    MldMessage::QueryV2(_) if len >= MldV2Query::MIN_LEN => {},
+                    MldMessage::QueryV2(_) if len >= MldV1Message::LEN => {
+                        assert_matches!(MldMessage::from_slice(&bytes[..len]), Ok(Some(MldMessage::Query(_))));
+                    },
+                    _ => {
+                        assert_matches!(MldMessage::from_slice(&bytes[..len]), Err(ReadError::UnexpectedEndOfSlice(_)));
+                    }
+                }
+            }
+        }
+        // source list exceeding the slice
+        let bytes = MldMessage::QueryV2(MldV2Query{
+            sources: vec![SOURCE],
+            ..Default::default()
+        }).to_bytes().unwrap();
+        assert_matches!(
+            MldMessage::from_slice(&bytes[..bytes.len() - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(44))
+        );
+    }
+
+    #[test]
+    fn from_slice_other_type() {
+        assert_eq!(None, MldMessage::from_slice(&[128, 0, 0, 0, 0, 0, 0, 0]).unwrap());
+    }
+
+    #[test]
+    fn query_v2_fields() {
+        let query = MldV2Query{
+            max_response_code: 0x8123,
+            suppress_router_side_processing: true,
+            querier_robustness_variable: 7,
+            querier_query_interval_code: 0x81,
+            ..Default::default()
+        };
+        let bytes = MldMessage::QueryV2(query.clone()).to_bytes().unwrap();
+        assert_eq!(&[0x81, 0x23], &bytes[4..6]);
+        assert_eq!(0b1111, bytes[24]);
+        assert_eq!(0x81, bytes[25]);
+        assert_eq!(0x1123 << 3, query.max_response_delay());
+        assert_eq!(0x11 << 3, query.query_interval());
+        assert_eq!(1000, MldV2Query{ max_response_code: 1000, ..Default::default() }.max_response_delay());
+        assert_eq!(125, MldV2Query{ querier_query_interval_code: 125, ..Default::default() }.query_interval());
+    }
+
+    #[test]
+    fn to_bytes_errors() {
+        use ValueError::*;
+
+        assert_eq!(
+            Err(U8TooLarge{ value: 8, max: 7, field: ErrorField::MldQuerierRobustnessVariable }),
+            MldMessage::QueryV2(MldV2Query{
+                querier_robustness_variable: 8,
+                ..Default::default()
+            }).to_bytes()
+        );
+        assert_eq!(
+            Err(MulticastRecordLengthBad),
+            MldMessage::QueryV2(MldV2Query{
+                sources: vec![[0;16]; 0x10000],
+                ..Default::default()
+            }).to_bytes()
+        );
+        assert_eq!(
+            Err(MulticastRecordLengthBad),
+            MldMessage::ReportV2(vec![MldAddressRecord::default(); 0x10000]).to_bytes()
+        );
+        for aux_len in &[3, 0xff*4 + 4] {
+            let message = MldMessage::ReportV2(vec![MldAddressRecord{
+                aux_data: vec![0; *aux_len],
+                ..Default::default()
+            }]);
+            assert_eq!(Err(MulticastRecordLengthBad), message.to_bytes());
+            assert_matches!(
+                message.write(&mut Vec::new()),
+                Err(WriteError::ValueError(MulticastRecordLengthBad))
+            );
+        }
+    }
+
+    #[test]
+    fn membership_events_match() {
+        // the serialized messages are understood by the membership event extraction
+        let report = MldMessage::ReportV2(vec![MldAddressRecord{
+            record_type: group_record_type::CHANGE_TO_EXCLUDE_MODE,
+            multicast_address: GROUP,
+            ..Default::default()
+        }]);
+        let packet = icmpv6_packet(&report.to_bytes().unwrap());
+        let events = membership_events(&SlicedPacket::from_ip(&packet).unwrap()).unwrap();
+        assert_matches!(&events[..], [MembershipEvent::Join{ .. }]);
+    }
+}
+
+mod router_renumbering {
+    use super::*;
+    use etherparse::icmpv6::*;
+
+    fn messages() -> Vec<RouterRenumberingMessage> {
+        let use_prefix = UsePrefix{
+            use_len: 48,
+            keep_len: 16,
+            flag_mask: 0xc0,
+            ra_flags: 0x80,
+            valid_lifetime: 0x12345678,
+            preferred_lifetime: 0x9abcdef0,
+            decrement_valid_lifetime: true,
+            decrement_preferred_lifetime: false,
+            use_prefix: DESTINATION,
+        };
+        vec![
+            RouterRenumberingMessage{
+                sequence_number: 0x01020304,
+                segment_number: 5,
+                test_command: true,
+                result_requested: true,
+                all_interfaces: false,
+                site_specific: true,
+                processed_previously: false,
+                max_delay: 0x0607,
+                body: RouterRenumberingBody::Command(vec![
+                    PrefixControlOperation{
+                        op_code: router_renumbering_op_code::CHANGE,
+                        ordinal: 1,
+                        match_len: 48,
+                        min_len: 64,
+                        max_len: 64,
+                        match_prefix: SOURCE,
+                        use_prefixes: vec![use_prefix.clone(), UsePrefix::default()],
+                    },
+                    PrefixControlOperation{
+                        op_code: router_renumbering_op_code::SET_GLOBAL,
+                        ..Default::default()
+                    },
+                ]),
+            },
+            RouterRenumberingMessage{
+                body: RouterRenumberingBody::Command(Vec::new()),
+                ..Default::default()
+            },
+            RouterRenumberingMessage{
+                sequence_number: 1,
+                processed_previously: true,
+                body: RouterRenumberingBody::Result(vec![
+                    MatchResult{
+                        out_of_bounds: true,
+                        forbidden: false,
+                        ordinal: 1,
+                        matched_len: 64,
+                        interface_index: 0x0a0b0c0d,
+                        matched_prefix: SOURCE,
+                    },
+                    MatchResult{
+                        forbidden: true,
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            },
+            RouterRenumberingMessage::default(),
+        ]
+    }
+
+    #[test]
+    fn to_bytes_from_slice() {
+        for message in messages() {
+            let bytes = message.to_bytes().unwrap();
+            assert_eq!(message.header_len(), bytes.len());
+            assert_eq!(icmpv6_type::ROUTER_RENUMBERING, bytes[0]);
+            assert_eq!(message.code(), bytes[1]);
+            assert_eq!(Some(message.clone()), RouterRenumberingMessage::from_slice(&bytes).unwrap());
+
+            let mut written = Vec::new();
+            message.write(&mut written).unwrap();
+            assert_eq!(bytes, written);
+        }
+    }
+
+    #[test]
+    fn field_layout() {
+        let bytes = messages()[0].to_bytes().unwrap();
+        assert_eq!(
+            &[138, 0, 0, 0, 1, 2, 3, 4, 5, 0b1101_0000, 6, 7, 0, 0, 0, 0],
+            &bytes[..16]
+        );
+        // match-prefix part with 2 use-prefix parts (3 + 2*4 units)
+        assert_eq!(
+            &[router_renumbering_op_code::CHANGE, 11, 1, 48, 64, 64, 0, 0],
+            &bytes[16..24]
+        );
+        assert_eq!(&SOURCE, &bytes[24..40]);
+        assert_eq!(
+            &[48, 16, 0xc0, 0x80, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x80, 0, 0, 0],
+            &bytes[40..56]
+        );
+        assert_eq!(&DESTINATION, &bytes[56..72]);
+
+        let bytes = messages()[2].to_bytes().unwrap();
+        assert_eq!(router_renumbering_code::RESULT, bytes[1]);
+        assert_eq!(0b0000_1000, bytes[9]);
+        assert_eq!(&[0, 0b10, 1, 64, 0x0a, 0x0b, 0x0c, 0x0d], &bytes[16..24]);
+        assert_eq!(&[0, 0b01], &bytes[40..42]);
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        // too short (truncation at an operation or result boundary results
+        // in a valid message with less body elements)
+        for message in messages() {
+            let bytes = message.to_bytes().unwrap();
+            for len in 0..RouterRenumberingMessage::MIN_LEN {
+                assert_matches!(
+                    RouterRenumberingMessage::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(RouterRenumberingMessage::MIN_LEN))
+                );
+            }
+            if bytes.len() > RouterRenumberingMessage::MIN_LEN {
+                assert_matches!(
+                    RouterRenumberingMessage::from_slice(&bytes[..bytes.len() - 1]),
+                    Err(ReadError::UnexpectedEndOfSlice(_))
+                );
+            }
+        }
+        // bad op length
+        let mut bytes = messages()[0].to_bytes().unwrap();
+        for op_length in [0, 1, 2, 4, 10, 12] {
+            bytes[17] = op_length;
+            assert_matches!(
+                RouterRenumberingMessage::from_slice(&bytes),
+                Err(ReadError::Icmpv6RouterRenumberingOpLengthBad(value)) if value == op_length
+            );
+        }
+        // op length exceeding the slice
+        bytes[17] = 15;
+        assert_matches!(
+            RouterRenumberingMessage::from_slice(&bytes),
+            Err(ReadError::UnexpectedEndOfSlice(136))
+        );
+    }
+
+    #[test]
+    fn from_slice_other() {
+        assert_eq!(None, RouterRenumberingMessage::from_slice(&[128, 0, 0, 0]).unwrap());
+        let mut bytes = RouterRenumberingMessage::default().to_bytes().unwrap();
+        bytes[1] = 2;
+        assert_eq!(None, RouterRenumberingMessage::from_slice(&bytes).unwrap());
+    }
+
+    #[test]
+    fn to_bytes_too_many_use_prefixes() {
+        let message = |count| RouterRenumberingMessage{
+            body: RouterRenumberingBody::Command(vec![PrefixControlOperation{
+                use_prefixes: vec![UsePrefix::default(); count],
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        let bytes = message(63).to_bytes().unwrap();
+        assert_eq!(255, bytes[17]);
+        assert_eq!(Some(message(63)), RouterRenumberingMessage::from_slice(&bytes).unwrap());
+        assert_eq!(
+            Err(ValueError::Icmpv6RouterRenumberingTooManyUsePrefixes(64)),
+            message(64).to_bytes()
+        );
+        assert_matches!(
+            message(64).write(&mut Vec::new()),
+            Err(WriteError::ValueError(ValueError::Icmpv6RouterRenumberingTooManyUsePrefixes(64)))
+        );
+    }
+}
+
+mod node_information {
+    use super::*;
+    use etherparse::icmpv6::*;
+
+    #[test]
+    fn to_bytes_from_slice() {
+        let header = NodeInformationHeader{
+            code: node_information_query_code::SUBJECT_IPV4_ADDRESS,
+            qtype: node_information_qtype::NODE_ADDRESSES,
+            flags: node_information_flag::ALL | node_information_flag::GLOBAL,
+            nonce: [1,2,3,4,5,6,7,8],
+        };
+        for message in [
+            NodeInformationMessage::Query(header),
+            NodeInformationMessage::Reply(NodeInformationHeader{
+                code: node_information_reply_code::REFUSED,
+                ..header
+            }),
+        ] {
+            let mut bytes = message.to_bytes();
+            assert_eq!(NodeInformationMessage::LEN, bytes.len());
+            assert_eq!(message.icmp_type(), bytes[0]);
+            assert_eq!(message.header().code, bytes[1]);
+            assert_eq!(&[0, 0, 0, 3, 0, 0x22, 1, 2, 3, 4, 5, 6, 7, 8], &bytes[2..]);
+            assert_eq!(
+                Some((message, &[][..])),
+                NodeInformationMessage::from_slice(&bytes).unwrap()
+            );
+
+            bytes.extend_from_slice(&[192, 168, 1, 1]);
+            assert_eq!(
+                Some((message, &[192, 168, 1, 1][..])),
+                NodeInformationMessage::from_slice(&bytes).unwrap()
+            );
+
+            for len in 0..NodeInformationMessage::LEN {
+                assert_matches!(
+                    NodeInformationMessage::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(NodeInformationMessage::LEN))
+                );
+            }
+        }
+        assert_eq!(139, icmpv6_type::NODE_INFORMATION_QUERY);
+        assert_eq!(140, icmpv6_type::NODE_INFORMATION_REPLY);
+    }
+
+    #[test]
+    fn from_slice_other_type() {
+        assert_eq!(None, NodeInformationMessage::from_slice(&[138, 0]).unwrap());
+    }
+}
@@ -0,0 +1,78 @@
+use super::super::*;
+
+use std::convert::TryFrom;
+
+#[test]
+fn constants() {
+    let values = [
+        (Dscp::CS0, 0), (Dscp::CS1, 8), (Dscp::CS2, 16), (Dscp::CS3, 24),
+        (Dscp::CS4, 32), (Dscp::CS5, 40), (Dscp::CS6, 48), (Dscp::CS7, 56),
+        (Dscp::AF11, 10), (Dscp::AF12, 12), (Dscp::AF13, 14),
+        (Dscp::AF21, 18), (Dscp::AF22, 20), (Dscp::AF23, 22),
+        (Dscp::AF31, 26), (Dscp::AF32, 28), (Dscp::AF33, 30),
+        (Dscp::AF41, 34), (Dscp::AF42, 36), (Dscp::AF43, 38),
+        (Dscp::EF, 46), (Dscp::VOICE_ADMIT, 44), (Dscp::LE, 1),
+    ];
+    for (dscp, value) in values.iter() {
+        assert_eq!(*value, dscp.value());
+    }
+    assert_eq!(Dscp::CS0, Dscp::default());
+}
+
+proptest! {
+    #[test]
+    fn conversions(value in any::<u8>()) {
+        if value <= Dscp::MAX_U8 {
+            let dscp = Dscp::try_new(value).unwrap();
+            assert_eq!(value, dscp.value());
+            assert_eq!(value, u8::from(dscp));
+            assert_eq!(Ok(dscp), Dscp::try_from(value));
+            assert_eq!(dscp, Dscp::from_bits(value));
+        } else {
+            let expected = ValueError::U8TooLarge{
+                value,
+                max: Dscp::MAX_U8,
+                field: ErrorField::Dscp
+            };
+            assert_eq!(Err(expected.clone()), Dscp::try_new(value));
+            assert_eq!(Err(expected), Dscp::try_from(value));
+            assert_eq!(value & 0x3f, Dscp::from_bits(value).value());
+        }
+    }
+}
+
+#[test]
+fn ipv4_header() {
+    let mut header = Ipv4Header::new(0, 1, IpNumber::Udp, [0;4], [0;4]);
+    header.differentiated_services_code_point = Dscp::AF41;
+    header.explicit_congestion_notification = Ecn::Ce;
+    assert_eq!((34 << 2) | 3, header.type_of_service());
+
+    let mut buffer = Vec::new();
+    header.write(&mut buffer).unwrap();
+    assert_eq!((34 << 2) | 3, buffer[1]);
+    let (decoded, _) = Ipv4Header::from_slice(&buffer).unwrap();
+    assert_eq!(Dscp::AF41, decoded.differentiated_services_code_point);
+    assert_eq!(Ecn::Ce, decoded.explicit_congestion_notification);
+}
+
+#[test]
+fn ipv6_header() {
+    let mut header = Ipv6Header{
+        traffic_class: 0,
+        ..Default::default()
+    };
+    header.set_dscp(Dscp::EF);
+    assert_eq!(46 << 2, header.traffic_class);
+    header.set_ecn(Ecn::Ect0);
+    assert_eq!((46 << 2) | 2, header.traffic_class);
+    assert_eq!(Dscp::EF, header.dscp());
+    assert_eq!(Ecn::Ect0, header.ecn());
+
+    // setting one part keeps the other
+    header.set_dscp(Dscp::CS1);
+    assert_eq!(Ecn::Ect0, header.ecn());
+    header.set_ecn(Ecn::NotEct);
+    assert_eq!(Dscp::CS1, header.dscp());
+    assert_eq!(8 << 2, header.traffic_class);
+}
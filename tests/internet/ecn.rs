@@ -0,0 +1,32 @@
+use super::super::*;
+
+#[test]
+fn from_bits() {
+    assert_eq!(Ecn::NotEct, Ecn::from_bits(0));
+    assert_eq!(Ecn::Ect1, Ecn::from_bits(1));
+    assert_eq!(Ecn::Ect0, Ecn::from_bits(2));
+    assert_eq!(Ecn::Ce, Ecn::from_bits(3));
+    assert_eq!(Ecn::Ce, Ecn::from_bits(0xff));
+    assert!(!Ecn::NotEct.is_ect());
+    assert!(Ecn::Ect1.is_ect());
+    assert!(Ecn::Ect0.is_ect());
+    assert!(Ecn::Ce.is_ect());
+}
+
+#[test]
+fn conversions() {
+    use std::convert::TryFrom;
+
+    for value in 0..4u8 {
+        let ecn = Ecn::try_new(value).unwrap();
+        assert_eq!(Ecn::from_bits(value), ecn);
+        assert_eq!(Ok(ecn), Ecn::try_from(value));
+        assert_eq!(value, u8::from(ecn));
+    }
+    assert_eq!(
+        Err(ValueError::U8TooLarge{ value: 4, max: 3, field: ErrorField::Ecn }),
+        Ecn::try_new(4)
+    );
+    assert!(Ecn::try_from(0xff).is_err());
+    assert_eq!(Ecn::NotEct, Ecn::default());
+}
@@ -349,6 +349,31 @@ mod ip_header {
         }
     }
 
+    #[test]
+    fn source_destination_addr() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+        {
+            let header = IpHeader::Version4(
+                Ipv4Header::new(0, 1, IpNumber::Udp, [1, 2, 3, 4], [5, 6, 7, 8]),
+                Default::default()
+            );
+            assert_eq!(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), header.source_addr());
+            assert_eq!(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8)), header.destination_addr());
+        }
+        {
+            let header = IpHeader::Version6(
+                Ipv6Header{
+                    source: [1;16],
+                    destination: [2;16],
+                    ..Default::default()
+                },
+                Default::default()
+            );
+            assert_eq!(IpAddr::V6(Ipv6Addr::from([1;16])), header.source_addr());
+            assert_eq!(IpAddr::V6(Ipv6Addr::from([2;16])), header.destination_addr());
+        }
+    }
+
     #[test]
     fn read_ip_header_version_error() {
         use std::io::Cursor;
@@ -444,12 +469,96 @@ mod ip_number {
             (SHIM6, Shim6),
             (EXP0, ExperimentalAndTesting0),
             (EXP1, ExperimentalAndTesting1),
+            (CBT, Cbt),
+            (SCTP, Sctp),
+            (UDP_LITE, UdpLite),
+            (L2TP, Layer2TunnelingProtocol),
+            (ANY_PRIVATE_ENCRYPTION_SCHEME, AnyPrivateEncryptionScheme),
+            (ETHERNET, Ethernet),
+            (AGGFRAG, Aggfrag),
+            (NSH, Nsh),
+            (RESERVED, Reserved),
         ];
         for (raw, enum_value) in pairs {
             assert_eq!(*raw, *enum_value as u8);
         }
     }
 
+    #[test]
+    fn try_from_all() {
+        use std::convert::TryFrom;
+
+        // raw values are unique & sorted
+        for pair in IpNumber::ALL.windows(2) {
+            assert!((pair[0] as u8) < (pair[1] as u8));
+        }
+        for value in &IpNumber::ALL {
+            assert_eq!(Ok(*value), IpNumber::try_from(*value as u8));
+            assert_eq!(Some(*value), IpNumber::from_u8(*value as u8));
+        }
+        // all numbers except the unassigned ones are known
+        for raw in 0..=u8::MAX {
+            if (146..=252).contains(&raw) {
+                assert_eq!(Err(UnknownIpNumberError(raw)), IpNumber::try_from(raw));
+                assert_eq!(None, IpNumber::from_u8(raw));
+            } else {
+                assert!(IpNumber::try_from(raw).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn is_ipv6_extension_header() {
+        for value in &IpNumber::ALL {
+            assert_eq!(
+                IpNumber::is_ipv6_ext_header_value(*value as u8),
+                value.is_ipv6_extension_header()
+            );
+        }
+        assert!(IpNumber::IPv6RouteHeader.is_ipv6_extension_header());
+        assert!(!IpNumber::Udp.is_ipv6_extension_header());
+    }
+
+    #[test]
+    fn keyword_protocol_str() {
+        use crate::IpNumber::*;
+        assert_eq!(Some("HOPOPT"), IPv6HeaderHopByHop.keyword());
+        assert_eq!("IPv6 Hop-by-Hop Option", IPv6HeaderHopByHop.protocol_str());
+        assert_eq!(Some("TCP"), Tcp.keyword());
+        assert_eq!("Transmission Control", Tcp.protocol_str());
+        assert_eq!(Some("IPv6-ICMP"), IPv6Icmp.keyword());
+        assert_eq!("ICMP for IPv6", IPv6Icmp.protocol_str());
+        assert_eq!(Some("ESP"), EncapsulatingSecurityPayload.keyword());
+        assert_eq!(Some("NSH"), Nsh.keyword());
+        assert_eq!("Network Service Header", Nsh.protocol_str());
+        assert_eq!(None, AnyLocalNetwork.keyword());
+        assert_eq!("any local network", AnyLocalNetwork.protocol_str());
+        assert_eq!(None, ExperimentalAndTesting0.keyword());
+        assert_eq!("Use for experimentation and testing", ExperimentalAndTesting1.protocol_str());
+
+        // every ip number has a name
+        for value in &IpNumber::ALL {
+            assert!(!value.protocol_str().is_empty());
+            assert_ne!(Some(""), value.keyword());
+        }
+    }
+
+    #[test]
+    fn display() {
+        use crate::IpNumber::*;
+        assert_eq!("UDP", Udp.to_string());
+        assert_eq!("IPv6-Frag", IPv6FragmentationHeader.to_string());
+        assert_eq!("any 0-hop protocol", AnyZeroHopProtocol.to_string());
+    }
+
+    #[test]
+    fn unknown_error_display() {
+        assert_eq!(
+            "UnknownIpNumberError: The ip number 200 is not assigned.",
+            UnknownIpNumberError(200).to_string()
+        );
+    }
+
     #[test]
     fn debug() {
         assert_eq!(
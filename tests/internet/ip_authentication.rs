@@ -254,3 +254,129 @@ proptest! {
         );
     }
 }
+
+#[test]
+fn icv_input_ipv4() {
+    let auth = IpAuthenticationHeader::new(ip_number::UDP, 1, 2, &[9;12]).unwrap();
+    let packet = {
+        let mut ip = Ipv4Header::new(
+            (auth.header_len() + 4) as u16,
+            20,
+            IpNumber::AuthenticationHeader,
+            [1,2,3,4],
+            [5,6,7,8]
+        );
+        ip.differentiated_services_code_point = Dscp::from_bits(3);
+        ip.more_fragments = true;
+        // nop, timestamp (mutable) & router alert (immutable)
+        ip.set_options(&[1, 68, 4, 5, 6, 148, 4, 0, 0, 1, 0, 0]).unwrap();
+        let mut result = Vec::new();
+        ip.write(&mut result).unwrap();
+        auth.write(&mut result).unwrap();
+        result.extend_from_slice(&[1,2,3,4]);
+        // ethernet padding
+        result.extend_from_slice(&[0;2]);
+        result
+    };
+
+    let input = IpAuthenticationIcvInput::from_ip_slice(&packet).unwrap().unwrap();
+    assert_eq!(32 + 12..32 + 24, input.icv_range());
+    let expected = {
+        let mut expected = packet[..packet.len() - 2].to_vec();
+        expected[1] = 0;
+        expected[6..9].copy_from_slice(&[0;3]);
+        expected[10..12].copy_from_slice(&[0;2]);
+        expected[21..25].copy_from_slice(&[0;4]);
+        expected[44..56].copy_from_slice(&[0;12]);
+        expected
+    };
+    assert_eq!(&expected[..], input.data());
+    assert_eq!(input.clone(), input);
+
+    // splice a new icv
+    let mut spliced = packet.clone();
+    input.splice_icv(&mut spliced, &[7;12]).unwrap();
+    assert_eq!(&[7;12], &spliced[44..56]);
+    assert_eq!(&packet[..44], &spliced[..44]);
+    assert_eq!(&packet[56..], &spliced[56..]);
+    assert_eq!(
+        Err(ValueError::IpAuthenticationHeaderBadIcvLength(8)),
+        input.splice_icv(&mut spliced, &[7;8])
+    );
+    assert_eq!(
+        Err(ValueError::IpAuthenticationHeaderBadIcvLength(12)),
+        input.splice_icv(&mut spliced[..50], &[7;12])
+    );
+
+    // malformed options are treated as mutable
+    {
+        let mut packet = packet.clone();
+        packet[25..27].copy_from_slice(&[148, 10]);
+        let input = IpAuthenticationIcvInput::from_ip_slice(&packet).unwrap().unwrap();
+        assert_eq!(&[0;7], &input.data()[25..32]);
+    }
+
+    // no authentication header
+    {
+        let mut packet = Vec::new();
+        Ipv4Header::new(0, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]).write(&mut packet).unwrap();
+        assert_eq!(None, IpAuthenticationIcvInput::from_ip_slice(&packet).unwrap());
+    }
+
+    // truncated authentication header
+    assert_matches!(
+        IpAuthenticationIcvInput::from_ip_slice(&packet[..40]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+}
+
+#[test]
+fn icv_input_ipv6() {
+    let auth = IpAuthenticationHeader::new(ip_number::UDP, 1, 2, &[9;8]).unwrap();
+    // pad1, a mutable option (0x3e) & an immutable option (0x1e)
+    let hop_by_hop = [ip_number::AUTH, 0, 0, 0x3e, 1, 5, 0x1e, 0];
+    let packet = {
+        let mut result = Vec::new();
+        Ipv6Header{
+            traffic_class: 1,
+            flow_label: 2,
+            payload_length: (hop_by_hop.len() + auth.header_len()) as u16,
+            next_header: ip_number::IPV6_HOP_BY_HOP,
+            hop_limit: 3,
+            source: [4;16],
+            destination: [5;16],
+        }.write(&mut result).unwrap();
+        result.extend_from_slice(&hop_by_hop);
+        auth.write(&mut result).unwrap();
+        result
+    };
+
+    let input = IpAuthenticationIcvInput::from_ip_slice(&packet).unwrap().unwrap();
+    assert_eq!(40 + 8 + 12..40 + 8 + 20, input.icv_range());
+    let expected = {
+        let mut expected = packet.clone();
+        expected[0..4].copy_from_slice(&[0x60, 0, 0, 0]);
+        expected[7] = 0;
+        expected[45] = 0;
+        expected[60..68].copy_from_slice(&[0;8]);
+        expected
+    };
+    assert_eq!(&expected[..], input.data());
+
+    // no authentication header
+    {
+        let mut packet = packet.clone();
+        packet[40] = ip_number::UDP;
+        assert_eq!(None, IpAuthenticationIcvInput::from_ip_slice(&packet).unwrap());
+    }
+
+    // unsupported ip version & empty slice
+    assert_matches!(
+        IpAuthenticationIcvInput::from_ip_slice(&[0x50]),
+        Err(ReadError::IpUnsupportedVersion(5))
+    );
+    assert_matches!(
+        IpAuthenticationIcvInput::from_ip_slice(&[]),
+        Err(ReadError::UnexpectedEndOfSlice(1))
+    );
+}
@@ -10,8 +10,8 @@ mod header {
     fn default() {
         let default : Ipv4Header = Default::default();
         assert_eq!(5, default.ihl());
-        assert_eq!(0, default.differentiated_services_code_point);
-        assert_eq!(0, default.explicit_congestion_notification);
+        assert_eq!(Dscp::CS0, default.differentiated_services_code_point);
+        assert_eq!(Ecn::NotEct, default.explicit_congestion_notification);
         assert_eq!(0, default.payload_len);
         assert_eq!(0, default.identification);
         assert_eq!(true, default.dont_fragment);
@@ -158,7 +158,7 @@ mod header {
     proptest! {
         #[test]
         fn debug(input in ipv4_any()) {
-            assert_eq!(&format!("Ipv4Header {{ ihl: {}, differentiated_services_code_point: {}, explicit_congestion_notification: {}, payload_len: {}, identification: {}, dont_fragment: {}, more_fragments: {}, fragments_offset: {}, time_to_live: {}, protocol: {}, header_checksum: {}, source: {:?}, destination: {:?}, options: {:?} }}",
+            assert_eq!(&format!("Ipv4Header {{ ihl: {}, differentiated_services_code_point: {:?}, explicit_congestion_notification: {:?}, payload_len: {}, identification: {}, dont_fragment: {}, more_fragments: {}, fragments_offset: {}, time_to_live: {}, protocol: {}, header_checksum: {}, source: {:?}, destination: {:?}, options: {:?} }}",
                     input.ihl(),
                     input.differentiated_services_code_point,
                     input.explicit_congestion_notification,
@@ -194,8 +194,8 @@ mod header {
                 dest_ip
             );
 
-            assert_eq!(result.differentiated_services_code_point, 0);
-            assert_eq!(result.explicit_congestion_notification, 0);
+            assert_eq!(result.differentiated_services_code_point, Dscp::CS0);
+            assert_eq!(result.explicit_congestion_notification, Ecn::NotEct);
             assert_eq!(result.payload_len, payload_len);
             assert_eq!(result.identification, 0);
             assert_eq!(result.dont_fragment, true);
@@ -210,6 +210,21 @@ mod header {
         }
     }
 
+    #[test]
+    fn new_with_addrs() {
+        use std::net::Ipv4Addr;
+        let header = Ipv4Header::new_with_addrs(
+            12,
+            34,
+            IpNumber::Udp,
+            Ipv4Addr::new(1, 2, 3, 4),
+            Ipv4Addr::new(5, 6, 7, 8)
+        );
+        assert_eq!(header, Ipv4Header::new(12, 34, IpNumber::Udp, [1, 2, 3, 4], [5, 6, 7, 8]));
+        assert_eq!(Ipv4Addr::new(1, 2, 3, 4), header.source_addr());
+        assert_eq!(Ipv4Addr::new(5, 6, 7, 8), header.destination_addr());
+    }
+
     #[test]
     fn set_payload_len() {
         let mut header = Ipv4Header::new(0, 0, IpNumber::Udp, [0;4], [0;4]);
@@ -477,6 +492,30 @@ mod slice {
             assert!(slice.is_fragmenting_payload());
         }
     }
+
+    #[test]
+    fn fragment_introspection() {
+        // (offset, more fragments, is fragment, is first fragment)
+        for (offset, more, is_fragment, is_first) in [
+            (0u16, false, false, false),
+            (0, true, true, true),
+            (185, true, true, false),
+            (0x1fff, false, true, false),
+        ].iter() {
+            let buffer = {
+                let mut header : Ipv4Header = Default::default();
+                header.fragments_offset = *offset;
+                header.more_fragments = *more;
+                let mut buffer = Vec::with_capacity(header.header_len());
+                header.write(&mut buffer).unwrap();
+                buffer
+            };
+            let slice = Ipv4HeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(*is_fragment, slice.is_fragment());
+            assert_eq!(*is_first, slice.is_first_fragment());
+            assert_eq!(*offset*8, slice.fragment_byte_offset());
+        }
+    }
 }
 
 #[test]
@@ -511,30 +550,6 @@ fn range_errors() {
                        .value_error());
         }
     }
-    //dscp
-    {
-        let value = {
-            let mut value: Ipv4Header = Default::default();
-            value.differentiated_services_code_point = 0x40;
-            value
-        };
-        test_range_methods(
-            &value,
-            U8TooLarge{value: 0x40, max: 0x3f, field: Ipv4Dscp}
-        );
-    }
-    //ecn
-    {
-        let value = {
-            let mut value: Ipv4Header = Default::default();
-            value.explicit_congestion_notification = 0x4;
-            value
-        };
-        test_range_methods(
-            &value,
-            U8TooLarge{value: 0x4, max: 0x3, field: Ipv4Ecn}
-        );
-    }
     //fragmentation offset
     {
         let value = {
@@ -768,8 +783,8 @@ proptest! {
 
             assert_eq!(slice.version(), 4);
             assert_eq!(slice.ihl(), input.ihl());
-            assert_eq!(slice.dcp(), input.differentiated_services_code_point);
-            assert_eq!(slice.ecn(), input.explicit_congestion_notification);
+            assert_eq!(slice.dcp(), input.differentiated_services_code_point.value());
+            assert_eq!(slice.ecn(), u8::from(input.explicit_congestion_notification));
             assert_eq!(slice.payload_len(), input.payload_len);
             assert_eq!(slice.total_len(), input.total_len());
             assert_eq!(slice.identification(), input.identification);
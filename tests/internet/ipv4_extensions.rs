@@ -439,6 +439,26 @@ mod slice {
         }
     }
 
+    proptest! {
+        #[test]
+        fn slice(auth in ip_authentication_any()) {
+            // None
+            assert!(Ipv4ExtensionsSlice{ auth: None }.slice().is_empty());
+
+            // Some
+            {
+                let mut buffer = Vec::with_capacity(auth.header_len());
+                auth.write(&mut buffer).unwrap();
+                let slice = Ipv4ExtensionsSlice{
+                    auth: Some(
+                        IpAuthenticationHeaderSlice::from_slice(&buffer).unwrap()
+                    ),
+                };
+                assert_eq!(slice.slice(), &buffer[..]);
+            }
+        }
+    }
+
     #[test]
     fn is_empty() {
         // empty
@@ -0,0 +1,154 @@
+use super::super::*;
+
+/// Header allowing fragmentation with a security option (copied) & a
+/// timestamp option (not copied).
+fn header() -> Ipv4Header {
+    let mut header = Ipv4Header::new(0, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2]);
+    header.dont_fragment = false;
+    header.identification = 1234;
+    header.set_options(&[
+        130, 11, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        68, 4, 5, 0,
+        1,
+    ]).unwrap();
+    header
+}
+
+/// Reassembles the given fragments & checks the fragment headers.
+fn reassemble(original: &Ipv4Header, fragments: &[(Ipv4Header, &[u8])], mtu: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    for (index, (header, payload)) in fragments.iter().enumerate() {
+        let last = index + 1 == fragments.len();
+        assert!(header.header_len() + payload.len() <= mtu);
+        assert_eq!(usize::from(header.payload_len), payload.len());
+        assert_eq!(original.fragments_offset*8 + result.len() as u16, header.fragments_offset*8);
+        assert_eq!(if last { original.more_fragments } else { true }, header.more_fragments);
+        if !last {
+            assert_eq!(0, payload.len() % 8);
+        }
+        assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+        assert_eq!(original.identification, header.identification);
+        assert_eq!(original.source, header.source);
+        assert_eq!(original.destination, header.destination);
+        assert_eq!(original.protocol, header.protocol);
+        result.extend_from_slice(payload);
+    }
+    result
+}
+
+#[test]
+fn fragment() {
+    let payload: Vec<u8> = (0..200u8).collect();
+    let header = header();
+    let mtu = 100;
+
+    let fragments: Vec<_> = Ipv4Fragmenter::new(&header, &payload, mtu).unwrap().collect();
+    // first fragment 36 bytes header & 64 bytes payload, following
+    // fragments 32 bytes header & 64 bytes payload
+    assert_eq!(
+        vec![64, 64, 64, 8],
+        fragments.iter().map(|(_, p)| p.len()).collect::<Vec<_>>()
+    );
+    assert_eq!(payload, reassemble(&header, &fragments, mtu));
+
+    // options (only the security option is copied)
+    assert_eq!(header.options(), fragments[0].0.options());
+    for (fragment, _) in &fragments[1..] {
+        assert_eq!(&header.options()[..11], &fragment.options()[..11]);
+        assert_eq!(&[0], &fragment.options()[11..]);
+    }
+}
+
+#[test]
+fn fragment_fragment() {
+    // already fragmented packets keep the offset & more fragments flag
+    let payload = [1u8;40];
+    for more_fragments in [false, true].iter() {
+        let mut header = Ipv4Header::new(0, 64, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+        header.dont_fragment = false;
+        header.fragments_offset = 10;
+        header.more_fragments = *more_fragments;
+
+        let fragments: Vec<_> = Ipv4Fragmenter::new(&header, &payload, 36).unwrap().collect();
+        assert_eq!(
+            vec![10, 12, 14],
+            fragments.iter().map(|(h, _)| h.fragments_offset).collect::<Vec<_>>()
+        );
+        assert_eq!(&payload[..], &reassemble(&header, &fragments, 36)[..]);
+    }
+}
+
+#[test]
+fn no_fragmentation_needed() {
+    for payload in [&[][..], &[1,2,3][..]].iter() {
+        let mut header = header();
+        // the don't fragment flag is irrelevant if the packet fits
+        header.dont_fragment = true;
+        header.payload_len = 1000;
+
+        let fragments: Vec<_> = Ipv4Fragmenter::new(&header, payload, header.header_len() + payload.len()).unwrap().collect();
+        assert_eq!(1, fragments.len());
+        let (fragment, fragment_payload) = &fragments[0];
+        assert_eq!(*payload, *fragment_payload);
+        assert_eq!(payload.len() as u16, fragment.payload_len);
+        assert_eq!(header.options(), fragment.options());
+        assert!(!fragment.more_fragments);
+        assert_eq!(0, fragment.fragments_offset);
+        assert_eq!(fragment.calc_header_checksum().unwrap(), fragment.header_checksum);
+    }
+}
+
+#[test]
+fn errors() {
+    use ValueError::*;
+
+    let payload = [0u8;100];
+
+    // don't fragment set
+    {
+        let mut header = header();
+        header.dont_fragment = true;
+        assert_eq!(
+            Err(Ipv4DontFragmentSet(136)),
+            Ipv4Fragmenter::new(&header, &payload, 100)
+        );
+    }
+
+    // mtu too small for a header & 8 bytes of payload
+    assert_eq!(
+        Err(Ipv4FragmentMtuTooSmall(43)),
+        Ipv4Fragmenter::new(&header(), &payload, 43)
+    );
+    {
+        let mut header = header();
+        // maximum header length
+        let mut options = [0u8;40];
+        options[..2].copy_from_slice(&[130, 40]);
+        header.set_options(&options).unwrap();
+        assert_eq!(
+            Err(Ipv4FragmentMtuTooSmall(67)),
+            Ipv4Fragmenter::new(&header, &payload, 67)
+        );
+        assert!(Ipv4Fragmenter::new(&header, &payload, 68).is_ok());
+    }
+
+    // reassembled packet too big
+    {
+        let mut header = header();
+        header.fragments_offset = 0x1fff;
+        assert_eq!(
+            Err(Ipv4PayloadLengthTooLarge(0x1fff*8 + 100)),
+            Ipv4Fragmenter::new(&header, &payload, 1500)
+        );
+    }
+
+    // header values out of range
+    {
+        let mut header = header();
+        header.fragments_offset = 0x2000;
+        assert_eq!(
+            Err(U16TooLarge{ value: 0x2000, max: 0x1fff, field: ErrorField::Ipv4FragmentsOffset }),
+            Ipv4Fragmenter::new(&header, &[], 1500)
+        );
+    }
+}
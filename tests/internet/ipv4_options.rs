@@ -0,0 +1,270 @@
+use super::super::*;
+
+#[test]
+fn iterate_options() {
+    use Ipv4OptionElement::*;
+
+    let options = [
+        // nop
+        1,
+        // record route with one recorded & one free address slot
+        7, 11, 8, 1, 2, 3, 4, 0, 0, 0, 0,
+        // timestamp (address & timestamp pairs, overflow 2)
+        68, 12, 13, 0x21, 5, 6, 7, 8, 0, 0, 0, 9,
+        // router alert
+        148, 4, 0, 1,
+        // loose & strict source route
+        131, 7, 4, 9, 9, 9, 9,
+        137, 3, 4,
+        // unknown option
+        25, 3, 42,
+        // end of options list & padding
+        0, 0, 0,
+    ];
+
+    let mut iterator = Ipv4OptionsIterator::from_slice(&options);
+    assert_eq!(&options[..], iterator.rest());
+    assert_eq!(Some(Ok(Noop)), iterator.next());
+
+    let record_route = match iterator.next() {
+        Some(Ok(RecordRoute(value))) => value,
+        value => panic!("unexpected value {:?}", value),
+    };
+    assert_eq!(8, record_route.pointer);
+    assert_eq!(vec![[1,2,3,4], [0;4]], record_route.addresses().collect::<Vec<_>>());
+
+    assert_eq!(
+        Some(Ok(Timestamp(Ipv4TimestampOption{
+            pointer: 13,
+            overflow: 2,
+            flag: 1,
+            timestamp_data: &[5,6,7,8,0,0,0,9],
+        }))),
+        iterator.next()
+    );
+    assert_eq!(Some(Ok(RouterAlert(1))), iterator.next());
+    assert_eq!(
+        Some(Ok(LooseSourceRoute(Ipv4RouteOption{ pointer: 4, route_data: &[9;4] }))),
+        iterator.next()
+    );
+    assert_eq!(
+        Some(Ok(StrictSourceRoute(Ipv4RouteOption{ pointer: 4, route_data: &[] }))),
+        iterator.next()
+    );
+    assert_eq!(Some(Ok(Unknown{ option_type: 25, data: &[42] })), iterator.next());
+    assert_eq!(&[0,0,0], iterator.rest());
+    assert_eq!(Some(Ok(End)), iterator.next());
+    assert_eq!(None, iterator.next());
+    assert!(iterator.rest().is_empty());
+}
+
+#[test]
+fn iterate_errors() {
+    use Ipv4OptionReadError::*;
+
+    let tests: [(&[u8], Ipv4OptionReadError); 7] = [
+        // length field missing
+        (&[7], UnexpectedEndOfSlice{ option_type: 7, expected_len: 2, actual_len: 1 }),
+        (&[25], UnexpectedEndOfSlice{ option_type: 25, expected_len: 2, actual_len: 1 }),
+        // option longer then the slice
+        (&[68, 8, 5, 0], UnexpectedEndOfSlice{ option_type: 68, expected_len: 8, actual_len: 4 }),
+        // lengths too small
+        (&[131, 2, 0, 0], UnexpectedSize{ option_type: 131, size: 2 }),
+        (&[68, 3, 0, 0], UnexpectedSize{ option_type: 68, size: 3 }),
+        (&[25, 1, 0, 0], UnexpectedSize{ option_type: 25, size: 1 }),
+        // router alert with a length other then 4
+        (&[148, 6, 0, 0, 0, 0], UnexpectedSize{ option_type: 148, size: 6 }),
+    ];
+    for (options, expected) in tests.iter() {
+        // errors stop the iteration
+        let mut iterator = Ipv4OptionsIterator::from_slice(options);
+        assert_eq!(Some(Err(expected.clone())), iterator.next());
+        assert_eq!(None, iterator.next());
+        assert!(iterator.rest().is_empty());
+    }
+}
+
+#[test]
+fn header_options_iterator() {
+    let options = [148, 4, 0, 0];
+    let mut header = Ipv4Header::new(0, 1, IpNumber::Udp, [0;4], [0;4]);
+    header.set_options(&options).unwrap();
+    assert_eq!(
+        vec![Ok(Ipv4OptionElement::RouterAlert(0))],
+        header.options_iterator().collect::<Vec<_>>()
+    );
+
+    let mut buffer = Vec::new();
+    header.write(&mut buffer).unwrap();
+    let slice = Ipv4HeaderSlice::from_slice(&buffer).unwrap();
+    assert_eq!(
+        vec![Ok(Ipv4OptionElement::RouterAlert(0))],
+        slice.options_iterator().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn debug() {
+    assert_eq!(
+        "[Noop, RouterAlert(0), Err(UnexpectedSize { option_type: 68, size: 0 })]",
+        format!("{:?}", Ipv4OptionsIterator::from_slice(&[1, 148, 4, 0, 0, 68, 0]))
+    );
+}
+
+#[test]
+fn error_display() {
+    use Ipv4OptionReadError::*;
+    assert_eq!(
+        "Ipv4OptionReadError: Not enough memory left in slice to read option of type 7 (expected at least 3 bytes, only 2 bytes available).",
+        &format!("{}", UnexpectedEndOfSlice{ option_type: 7, expected_len: 3, actual_len: 2 })
+    );
+    assert_eq!(
+        "Ipv4OptionReadError: Length value of the option of type 148 had unexpected value 5.",
+        &format!("{}", UnexpectedSize{ option_type: 148, size: 5 })
+    );
+    assert!(std::error::Error::source(&UnexpectedSize{ option_type: 148, size: 5 }).is_none());
+}
+
+#[test]
+fn builder() {
+    use Ipv4OptionElement::*;
+
+    let options = [
+        Noop,
+        RecordRoute(Ipv4RouteOption{ pointer: 4, route_data: &[0;4] }),
+        Timestamp(Ipv4TimestampOption{ pointer: 5, overflow: 0xf, flag: 1, timestamp_data: &[1,2,3,4] }),
+        RouterAlert(0x1234),
+        LooseSourceRoute(Ipv4RouteOption{ pointer: 4, route_data: &[5,6,7,8] }),
+        StrictSourceRoute(Ipv4RouteOption{ pointer: 4, route_data: &[] }),
+        Unknown{ option_type: 25, data: &[42] },
+    ];
+
+    let mut builder = Ipv4OptionsBuilder::new();
+    assert!(builder.is_empty());
+    assert_eq!(0, builder.len());
+    for option in options.iter() {
+        builder.add(option).unwrap();
+    }
+    assert!(!builder.is_empty());
+    // 33 bytes padded to 36
+    assert_eq!(36, builder.len());
+    assert_eq!(
+        &[
+            1,
+            7, 7, 4, 0, 0, 0, 0,
+            68, 8, 5, 0xf1, 1, 2, 3, 4,
+            148, 4, 0x12, 0x34,
+            131, 7, 4, 5, 6, 7, 8,
+            137, 3, 4,
+            25, 3, 42,
+            0, 0, 0,
+        ],
+        builder.options()
+    );
+
+    // read back
+    let mut expected = options.to_vec();
+    expected.push(End);
+    assert_eq!(
+        expected,
+        Ipv4OptionsIterator::from_slice(builder.options())
+            .map(|value| value.unwrap())
+            .collect::<Vec<_>>()
+    );
+
+    // apply to a header (ihl & total length are updated)
+    let mut header = Ipv4Header::new(10, 1, IpNumber::Udp, [0;4], [0;4]);
+    builder.apply(&mut header);
+    assert_eq!(builder.options(), header.options());
+    assert_eq!(5 + 9, header.ihl());
+    assert_eq!(20 + 36 + 10, header.total_len());
+
+    // options not fitting into the header are rejected
+    let before = builder.clone();
+    assert_eq!(
+        Err(ValueError::Ipv4OptionsLengthBad(41)),
+        builder.add(&Unknown{ option_type: 25, data: &[1,2,3,4,5,6] }).map(|_| ())
+    );
+    assert_eq!(before, builder);
+    builder.add(&End).unwrap();
+    assert_eq!(36, builder.len());
+}
+
+#[test]
+fn builder_timestamp_bad_values() {
+    let mut builder = Ipv4OptionsBuilder::default();
+    assert_eq!(
+        Err(ValueError::U8TooLarge{ value: 0x10, max: 0xf, field: ErrorField::Ipv4TimestampOverflow }),
+        builder.add(
+            &Ipv4OptionElement::Timestamp(Ipv4TimestampOption{ pointer: 5, overflow: 0x10, flag: 0, timestamp_data: &[] })
+        ).map(|_| ())
+    );
+    assert_eq!(
+        Err(ValueError::U8TooLarge{ value: 0x10, max: 0xf, field: ErrorField::Ipv4TimestampFlag }),
+        builder.add(
+            &Ipv4OptionElement::Timestamp(Ipv4TimestampOption{ pointer: 5, overflow: 0, flag: 0x10, timestamp_data: &[] })
+        ).map(|_| ())
+    );
+    assert!(builder.is_empty());
+}
+
+#[test]
+fn router_alert() {
+    // builder
+    assert_eq!(&[148, 4, 0x12, 0x34], Ipv4OptionsBuilder::router_alert(0x1234).options());
+
+    // header without options
+    let mut header = Ipv4Header::new(10, 1, IpNumber::Igmp, [0;4], [0;4]);
+    assert_eq!(None, header.router_alert());
+    header.set_router_alert(0).unwrap();
+    assert_eq!(&[148, 4, 0, 0], header.options());
+    assert_eq!(Some(0), header.router_alert());
+    assert_eq!(20 + 4 + 10, header.total_len());
+
+    // overwrite an existing option
+    header.set_router_alert(0x1234).unwrap();
+    assert_eq!(&[148, 4, 0x12, 0x34], header.options());
+    assert_eq!(Some(0x1234), header.router_alert());
+
+    // append after other options (in front of the end of options list)
+    header.set_options(&[1, 25, 3, 42, 0, 0, 0, 0]).unwrap();
+    header.set_router_alert(5).unwrap();
+    assert_eq!(&[1, 25, 3, 42, 148, 4, 0, 5], header.options());
+    assert_eq!(Some(5), header.router_alert());
+
+    header.set_options(&[1, 1, 1, 0]).unwrap();
+    header.set_router_alert(5).unwrap();
+    assert_eq!(&[1, 1, 1, 148, 4, 0, 5, 0], header.options());
+
+    // option does not fit into the header
+    header.set_options(&[1;40]).unwrap();
+    let before = header.clone();
+    assert_eq!(Err(ValueError::Ipv4OptionsLengthBad(44)), header.set_router_alert(0));
+    assert_eq!(before, header);
+}
+
+#[test]
+fn final_destination() {
+    use Ipv4OptionElement::*;
+
+    let mut header = Ipv4Header::new(0, 1, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+    assert_eq!([5,6,7,8], header.final_destination());
+
+    let route_data = [9,9,9,9, 10,10,10,10];
+    for (option, expected) in [
+        // addresses left
+        (LooseSourceRoute(Ipv4RouteOption{ pointer: 4, route_data: &route_data }), [10;4]),
+        (StrictSourceRoute(Ipv4RouteOption{ pointer: 8, route_data: &route_data }), [10;4]),
+        // route completed
+        (LooseSourceRoute(Ipv4RouteOption{ pointer: 12, route_data: &route_data }), [5,6,7,8]),
+        // invalid pointer
+        (LooseSourceRoute(Ipv4RouteOption{ pointer: 3, route_data: &route_data }), [5,6,7,8]),
+        // record route options are not relevant
+        (RecordRoute(Ipv4RouteOption{ pointer: 4, route_data: &route_data }), [5,6,7,8]),
+    ].iter() {
+        let mut builder = Ipv4OptionsBuilder::new();
+        builder.add(option).unwrap();
+        builder.apply(&mut header);
+        assert_eq!(*expected, header.final_destination());
+    }
+}
@@ -146,6 +146,28 @@ fn read_error() {
     }
 }
 
+#[test]
+fn new_with_addrs() {
+    use std::net::Ipv6Addr;
+    let source = Ipv6Addr::from([1;16]);
+    let destination = Ipv6Addr::from([2;16]);
+    let header = Ipv6Header::new_with_addrs(12, ip_number::UDP, 34, source, destination);
+    assert_eq!(
+        Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 12,
+            next_header: ip_number::UDP,
+            hop_limit: 34,
+            source: [1;16],
+            destination: [2;16],
+        },
+        header
+    );
+    assert_eq!(source, header.source_addr());
+    assert_eq!(destination, header.destination_addr());
+}
+
 #[test]
 fn header_len() {
     let header : Ipv6Header = Default::default();
@@ -417,6 +439,74 @@ fn set_payload_lengt() {
                     Err(ValueError::Ipv6PayloadLengthTooLarge(OVER_MAX)));
 }
 
+#[test]
+fn set_payload_length_with_jumbo() {
+    let mut header: Ipv6Header = Default::default();
+
+    // payloads fitting into the payload length field
+    {
+        let mut extensions = Ipv6Extensions{
+            destination_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[0;6]).unwrap()),
+            ..Default::default()
+        };
+        header.set_payload_length_with_jumbo(&mut extensions, 0xffff - 8).unwrap();
+        assert_eq!(0xffff, header.payload_length);
+        assert_eq!(None, extensions.jumbo_payload_length());
+        assert_eq!(0xffff, header.payload_len_with_jumbo(&extensions));
+    }
+
+    // jumbogram (hop by hop options header gets added)
+    {
+        let mut extensions: Ipv6Extensions = Default::default();
+        header.set_payload_length_with_jumbo(&mut extensions, 0x10000).unwrap();
+        assert_eq!(0, header.payload_length);
+        assert_eq!(
+            Some(&Ipv6RawExtensionHeader::new_jumbo_payload(0, 0x10000 + 8)),
+            extensions.hop_by_hop_options.as_ref()
+        );
+        assert_eq!(Some(0x10000 + 8), extensions.jumbo_payload_length());
+        assert_eq!(0x10000 + 8, header.payload_len_with_jumbo(&extensions));
+
+        // updates existing options
+        header.set_payload_length_with_jumbo(&mut extensions, 0x20000).unwrap();
+        assert_eq!(Some(0x20000 + 8), extensions.jumbo_payload_length());
+        assert_eq!(8, extensions.header_len());
+    }
+
+    // jumbogram with an existing hop by hop options header
+    {
+        let mut extensions = Ipv6Extensions{
+            hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[5, 2, 0, 0, 1, 0]).unwrap()),
+            ..Default::default()
+        };
+        header.set_payload_length_with_jumbo(&mut extensions, 0x10000).unwrap();
+        assert_eq!(0, header.payload_length);
+        assert_eq!(16, extensions.header_len());
+        assert_eq!(Some(0x10000 + 16), extensions.jumbo_payload_length());
+    }
+
+    // payload too large for the "Jumbo Payload" option
+    #[cfg(target_pointer_width = "64")]
+    {
+        let mut extensions: Ipv6Extensions = Default::default();
+        header.payload_length = 1;
+        assert_eq!(
+            Err(ValueError::Ipv6PayloadLengthTooLarge(u32::MAX as usize + 8)),
+            header.set_payload_length_with_jumbo(&mut extensions, u32::MAX as usize)
+        );
+        assert_eq!(1, header.payload_length);
+        assert!(extensions.is_empty());
+    }
+
+    // payload length field is used if it is not zero
+    {
+        let mut extensions: Ipv6Extensions = Default::default();
+        extensions.set_jumbo_payload_length(0x10000).unwrap();
+        header.payload_length = 1;
+        assert_eq!(1, header.payload_len_with_jumbo(&extensions));
+    }
+}
+
 proptest! {
     #[test]
     fn from_slice(ref input in ipv6_any()) {
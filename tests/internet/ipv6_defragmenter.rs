@@ -0,0 +1,379 @@
+use super::super::*;
+
+use std::time::Duration;
+
+/// Serialized packet with a 24 byte unfragmentable part (hop by hop
+/// options, destination options & routing) & a 24 byte fragmentable part
+/// (authentication header & final destination options).
+fn packet(payload: &[u8]) -> Vec<u8> {
+    let mut extensions = Ipv6Extensions{
+        hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[1;6]).unwrap()),
+        destination_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[2;6]).unwrap()),
+        routing: Some(Ipv6RoutingExtensions{
+            routing: Ipv6RawExtensionHeader::new_raw(0, &[3;6]).unwrap(),
+            final_destination_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[4;6]).unwrap()),
+        }),
+        fragment: None,
+        auth: Some(IpAuthenticationHeader::new(0, 1, 2, &[5;4]).unwrap()),
+        final_destination_options: None,
+    };
+    let header = Ipv6Header{
+        traffic_class: 1,
+        flow_label: 2,
+        payload_length: (extensions.header_len() + payload.len()) as u16,
+        next_header: extensions.set_next_headers(ip_number::UDP),
+        hop_limit: 64,
+        source: [3;16],
+        destination: [4;16],
+    };
+    let mut result = Vec::new();
+    header.write(&mut result).unwrap();
+    extensions.write(&mut result, header.next_header).unwrap();
+    result.extend_from_slice(payload);
+    result
+}
+
+/// Splits the given packet into serialized fragments.
+fn fragments(packet: &[u8], identification: u32, mtu: usize) -> Vec<Vec<u8>> {
+    let (header, rest) = Ipv6Header::from_slice(packet).unwrap();
+    let (extensions, _, payload) = Ipv6Extensions::from_slice(header.next_header, rest).unwrap();
+    Ipv6Fragmenter::new(&header, &extensions, payload, identification, mtu)
+        .unwrap()
+        .map(|(fragment_header, fragment_extensions, fragment_payload)| {
+            let mut result = Vec::new();
+            fragment_header.write(&mut result).unwrap();
+            fragment_extensions.write(&mut result, fragment_header.next_header).unwrap();
+            result.extend_from_slice(fragment_payload);
+            result
+        })
+        .collect()
+}
+
+#[test]
+fn reassemble() {
+    let payload: Vec<u8> = (0..200u8).collect();
+    let packet = packet(&payload);
+    let fragments = fragments(&packet, 1234, 120);
+    assert_eq!(5, fragments.len());
+
+    // all orders of the fragments
+    let orders: [[usize;5];4] = [
+        [0, 1, 2, 3, 4],
+        [4, 3, 2, 1, 0],
+        [2, 4, 0, 1, 3],
+        [1, 0, 3, 4, 2],
+    ];
+    for order in orders.iter() {
+        let mut defragmenter = Ipv6Defragmenter::new(Default::default());
+        for (index, fragment_index) in order.iter().enumerate() {
+            let result = defragmenter.add(&fragments[*fragment_index], Duration::from_secs(index as u64)).unwrap();
+            if index + 1 == order.len() {
+                assert_eq!(Some(&packet), result.as_ref());
+                assert!(defragmenter.is_empty());
+            } else {
+                assert_eq!(None, result);
+                assert_eq!(1, defragmenter.len());
+            }
+        }
+    }
+
+    // duplicate fragments
+    {
+        let mut defragmenter = Ipv6Defragmenter::default();
+        for fragment in &fragments[..4] {
+            assert_eq!(None, defragmenter.add(fragment, Duration::from_secs(0)).unwrap());
+            assert_eq!(None, defragmenter.add(fragment, Duration::from_secs(0)).unwrap());
+        }
+        assert_eq!(Some(packet.clone()), defragmenter.add(&fragments[4], Duration::from_secs(0)).unwrap());
+    }
+
+    // interleaved packets with different identification values
+    {
+        let other_packet = self::packet(&[1;40]);
+        let other = self::fragments(&other_packet, 1235, 96);
+        assert_eq!(3, other.len());
+
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(None, defragmenter.add(&other[2], Duration::from_secs(0)).unwrap());
+        for fragment in &fragments[..4] {
+            assert_eq!(None, defragmenter.add(fragment, Duration::from_secs(0)).unwrap());
+        }
+        assert_eq!(None, defragmenter.add(&other[1], Duration::from_secs(0)).unwrap());
+        assert_eq!(2, defragmenter.len());
+        assert_eq!(Some(other_packet), defragmenter.add(&other[0], Duration::from_secs(0)).unwrap());
+        assert_eq!(Some(packet.clone()), defragmenter.add(&fragments[4], Duration::from_secs(0)).unwrap());
+    }
+}
+
+/// Fragment of an ICMPv6 echo request from fe80::1 to fe80::2 with the
+/// identification 0x8d3c2a1b as captured on the wire (the given fragment
+/// offset & "more fragments" field are the raw bytes 2 & 3 of the fragment
+/// header).
+fn raw_fragment(offset_flags: [u8;2], data: &[u8]) -> Vec<u8> {
+    let payload_len = (8 + data.len()) as u16;
+    let mut result = vec![
+        // ipv6 header (next header fragment, hop limit 64)
+        0x60, 0x00, 0x00, 0x00,
+        payload_len.to_be_bytes()[0], payload_len.to_be_bytes()[1], 0x2c, 0x40,
+        0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01,
+        0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+        // fragment header (next header ICMPv6)
+        0x3a, 0x00, offset_flags[0], offset_flags[1],
+        0x8d, 0x3c, 0x2a, 0x1b,
+    ];
+    result.extend_from_slice(data);
+    result
+}
+
+/// ICMPv6 echo request (identifier 1, sequence number 1) with the 32 byte
+/// payload pattern of ping.
+fn raw_echo_request() -> Vec<u8> {
+    let mut result = vec![0x80, 0x00, 0x1c, 0x4f, 0x00, 0x01, 0x00, 0x01];
+    result.extend(0x10..0x30u8);
+    result
+}
+
+#[test]
+fn reassemble_raw() {
+    let icmp = raw_echo_request();
+    let mut expected = vec![
+        0x60, 0x00, 0x00, 0x00, 0x00, 0x28, 0x3a, 0x40,
+        0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01,
+        0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+    ];
+    expected.extend_from_slice(&icmp);
+
+    // offset 0, 2 & 4 (in 8 octet units)
+    let first = raw_fragment([0x00, 0x01], &icmp[..16]);
+    let second = raw_fragment([0x00, 0x11], &icmp[16..32]);
+    let last = raw_fragment([0x00, 0x20], &icmp[32..]);
+
+    let mut defragmenter = Ipv6Defragmenter::default();
+    assert_eq!(None, defragmenter.add(&last, Duration::from_secs(0)).unwrap());
+    assert_eq!(None, defragmenter.add(&first, Duration::from_secs(0)).unwrap());
+    assert_eq!(Some(expected), defragmenter.add(&second, Duration::from_secs(0)).unwrap());
+    assert!(defragmenter.is_empty());
+}
+
+#[test]
+fn overlapping_fragments() {
+    use Ipv6DefragmentError::*;
+
+    let icmp = raw_echo_request();
+    let first = raw_fragment([0x00, 0x01], &icmp[..16]);
+    let second = raw_fragment([0x00, 0x11], &icmp[16..32]);
+    let last = raw_fragment([0x00, 0x20], &icmp[32..]);
+
+    // fragment at offset 1 overlapping the first & second fragment
+    {
+        let overlapping = raw_fragment([0x00, 0x09], &[0xff;16]);
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(None, defragmenter.add(&first, Duration::from_secs(0)).unwrap());
+        assert_matches!(defragmenter.add(&overlapping, Duration::from_secs(0)), Err(OverlappingFragment));
+        // the previously received fragments were discarded
+        assert!(defragmenter.is_empty());
+        assert_eq!(None, defragmenter.add(&second, Duration::from_secs(0)).unwrap());
+        assert_eq!(None, defragmenter.add(&last, Duration::from_secs(0)).unwrap());
+        assert!(defragmenter.add(&first, Duration::from_secs(0)).unwrap().is_some());
+    }
+
+    // last fragment at offset 3 overlapping the end of the second fragment
+    {
+        let overlapping = raw_fragment([0x00, 0x18], &[0xff;16]);
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(None, defragmenter.add(&first, Duration::from_secs(0)).unwrap());
+        assert_eq!(None, defragmenter.add(&second, Duration::from_secs(0)).unwrap());
+        assert_matches!(defragmenter.add(&overlapping, Duration::from_secs(0)), Err(OverlappingFragment));
+        assert!(defragmenter.is_empty());
+    }
+
+    // fragment with the offset of the second fragment but a different length
+    {
+        let overlapping = raw_fragment([0x00, 0x11], &icmp[16..24]);
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(None, defragmenter.add(&second, Duration::from_secs(0)).unwrap());
+        assert_matches!(defragmenter.add(&overlapping, Duration::from_secs(0)), Err(OverlappingFragment));
+        assert!(defragmenter.is_empty());
+    }
+}
+
+#[test]
+fn not_fragmented() {
+    let packet = packet(&[1,2,3]);
+    let mut defragmenter = Ipv6Defragmenter::default();
+
+    // packets without a fragment header are returned directly (without trailing bytes)
+    let mut padded = packet.clone();
+    padded.extend_from_slice(&[0;4]);
+    assert_eq!(Some(packet.clone()), defragmenter.add(&padded, Duration::from_secs(0)).unwrap());
+
+    // atomic fragments
+    {
+        let (header, rest) = Ipv6Header::from_slice(&packet).unwrap();
+        let (mut extensions, _, payload) = Ipv6Extensions::from_slice(header.next_header, rest).unwrap();
+        extensions.fragment = Some(Ipv6FragmentHeader::new(0, 0, false, 1));
+        let mut header = header.clone();
+        header.next_header = extensions.set_next_headers(ip_number::UDP);
+        header.payload_length = (extensions.header_len() + payload.len()) as u16;
+
+        let mut fragment = Vec::new();
+        header.write(&mut fragment).unwrap();
+        extensions.write(&mut fragment, header.next_header).unwrap();
+        fragment.extend_from_slice(payload);
+        assert_eq!(Some(packet.clone()), defragmenter.add(&fragment, Duration::from_secs(0)).unwrap());
+    }
+    assert!(defragmenter.is_empty());
+}
+
+#[test]
+fn limits() {
+    let packet = packet(&[0;200]);
+    let fragments = fragments(&packet, 1, 120);
+
+    // timeout
+    {
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(Duration::from_secs(60), defragmenter.config().timeout);
+        for fragment in &fragments[..4] {
+            assert_eq!(None, defragmenter.add(fragment, Duration::from_secs(10)).unwrap());
+        }
+        defragmenter.remove_expired(Duration::from_secs(69));
+        assert_eq!(1, defragmenter.len());
+        // fragments of the expired packet start a new reassembly
+        assert_eq!(None, defragmenter.add(&fragments[4], Duration::from_secs(70)).unwrap());
+        assert_eq!(1, defragmenter.len());
+        for fragment in &fragments[1..4] {
+            assert_eq!(None, defragmenter.add(fragment, Duration::from_secs(71)).unwrap());
+        }
+        defragmenter.remove_expired(Duration::from_secs(130));
+        assert!(defragmenter.is_empty());
+
+        assert_eq!(None, defragmenter.add(&fragments[0], Duration::from_secs(130)).unwrap());
+        defragmenter.clear();
+        assert!(defragmenter.is_empty());
+    }
+
+    // maximum number of packets
+    {
+        let mut defragmenter = Ipv6Defragmenter::new(Ipv6DefragmenterConfig{
+            max_packets: 2,
+            ..Default::default()
+        });
+        let first = self::fragments(&packet, 2, 120);
+        let second = self::fragments(&packet, 3, 120);
+        assert_eq!(None, defragmenter.add(&fragments[0], Duration::from_secs(0)).unwrap());
+        assert_eq!(None, defragmenter.add(&first[0], Duration::from_secs(1)).unwrap());
+        // the oldest packet is discarded
+        assert_eq!(None, defragmenter.add(&second[0], Duration::from_secs(2)).unwrap());
+        assert_eq!(2, defragmenter.len());
+        let mut result = None;
+        for fragment in &first[1..] {
+            result = defragmenter.add(fragment, Duration::from_secs(3)).unwrap();
+        }
+        assert_eq!(Some(packet.clone()), result);
+        assert_eq!(1, defragmenter.len());
+        // the first fragment of the discarded packet is missing
+        for fragment in &fragments[1..] {
+            assert_eq!(None, defragmenter.add(fragment, Duration::from_secs(4)).unwrap());
+        }
+        assert_eq!(2, defragmenter.len());
+    }
+
+    // maximum packet length
+    {
+        let mut defragmenter = Ipv6Defragmenter::new(Ipv6DefragmenterConfig{
+            max_packet_len: 200,
+            ..Default::default()
+        });
+        assert_eq!(None, defragmenter.add(&fragments[0], Duration::from_secs(0)).unwrap());
+        assert_matches!(
+            defragmenter.add(&fragments[4], Duration::from_secs(0)),
+            Err(Ipv6DefragmentError::PacketTooLarge(len)) if len == packet.len()
+        );
+    }
+}
+
+#[test]
+fn errors() {
+    use Ipv6DefragmentError::*;
+
+    let packet = packet(&[0;200]);
+    let fragments = fragments(&packet, 1, 120);
+
+    // fragment not a multiple of 8 bytes
+    {
+        let mut fragment = fragments[1].clone();
+        fragment.pop();
+        fragment[5] -= 1;
+        assert_matches!(
+            Ipv6Defragmenter::default().add(&fragment, Duration::from_secs(0)),
+            Err(InvalidFragment)
+        );
+    }
+
+    // fragments after the end of the packet
+    {
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(None, defragmenter.add(&fragments[4], Duration::from_secs(0)).unwrap());
+        assert_matches!(defragmenter.add(&fragments[3], Duration::from_secs(0)), Ok(None));
+        // last fragment with a different offset
+        let mut fragment = fragments[4].clone();
+        // increase the fragment offset (upper bits of the fourth byte of
+        // the fragment header after 24 bytes of extension headers) by one
+        fragment[40 + 24 + 3] += 8;
+        assert_matches!(defragmenter.add(&fragment, Duration::from_secs(0)), Err(InvalidFragment));
+    }
+    {
+        // last fragment received after fragments behind its end
+        let mut defragmenter = Ipv6Defragmenter::default();
+        assert_eq!(None, defragmenter.add(&fragments[3], Duration::from_secs(0)).unwrap());
+        let mut fragment = fragments[4].clone();
+        fragment[40 + 24 + 3] -= 5*8;
+        assert_matches!(defragmenter.add(&fragment, Duration::from_secs(0)), Err(InvalidFragment));
+    }
+
+    // read errors
+    assert_matches!(
+        Ipv6Defragmenter::default().add(&fragments[0][..39], Duration::from_secs(0)),
+        Err(Read(ReadError::UnexpectedEndOfSlice(40)))
+    );
+    assert_matches!(
+        Ipv6Defragmenter::default().add(&fragments[0][..fragments[0].len() - 1], Duration::from_secs(0)),
+        Err(Read(ReadError::UnexpectedEndOfSlice(_)))
+    );
+    {
+        // fragment header cut off
+        let mut fragment = fragments[0][..40 + 24 + 4].to_vec();
+        fragment[4..6].copy_from_slice(&28u16.to_be_bytes());
+        assert_matches!(
+            Ipv6Defragmenter::default().add(&fragment, Duration::from_secs(0)),
+            Err(Read(ReadError::UnexpectedEndOfSlice(72)))
+        );
+    }
+}
+
+#[test]
+fn error_display() {
+    use Ipv6DefragmentError::*;
+    use std::error::Error;
+
+    assert_eq!(
+        "Ipv6DefragmentError: Fragment does not fit into the reassembled packet.",
+        &format!("{}", InvalidFragment)
+    );
+    assert_eq!(
+        "Ipv6DefragmentError: Fragment overlaps a previously received fragment, the packet was discarded.",
+        &format!("{}", OverlappingFragment)
+    );
+    assert_eq!(
+        "Ipv6DefragmentError: Reassembled packet length 12 is too large.",
+        &format!("{}", PacketTooLarge(12))
+    );
+    let read = ReadError::UnexpectedEndOfSlice(40);
+    assert_eq!(
+        format!("Ipv6DefragmentError: {}", read),
+        format!("{}", Read(read))
+    );
+    assert!(Read(ReadError::UnexpectedEndOfSlice(40)).source().is_some());
+    assert!(InvalidFragment.source().is_none());
+}
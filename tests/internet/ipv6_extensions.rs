@@ -89,6 +89,7 @@ impl ExtensionTestPayload {
             dest_opt: bool,
             routing: bool,
             final_dest_opt: bool,
+            unrouted_final_dest_opt: bool,
             frag: bool,
             auth: bool,
         }
@@ -98,6 +99,7 @@ impl ExtensionTestPayload {
             dest_opt: false,
             routing: false,
             final_dest_opt: false,
+            unrouted_final_dest_opt: false,
             frag: false,
             auth: false,
         };
@@ -121,12 +123,15 @@ impl ExtensionTestPayload {
                         } else {
                             read.final_dest_opt = true;
                         }
-                    } else {
+                    } else if !read.dest_opt && !read.frag && !read.auth {
                         // dst opt
-                        if read.dest_opt {
+                        read.dest_opt = true;
+                    } else {
+                        // final dest opt without routing header
+                        if read.unrouted_final_dest_opt {
                             return false;
                         } else {
-                            read.dest_opt = true;
+                            read.unrouted_final_dest_opt = true;
                         }
                     }
                 }
@@ -155,6 +160,7 @@ impl ExtensionTestPayload {
             dest_opt: bool,
             routing: bool,
             final_dest_opt: bool,
+            unrouted_final_dest_opt: bool,
             frag: bool,
             auth: bool,
         }
@@ -165,6 +171,7 @@ impl ExtensionTestPayload {
             dest_opt: false,
             routing: false,
             final_dest_opt: false,
+            unrouted_final_dest_opt: false,
             frag: false,
             auth: false,
         };
@@ -204,15 +211,21 @@ impl ExtensionTestPayload {
                             slice = rest;
                             read.final_dest_opt = true;
                         }
-                    } else {
+                    } else if !read.dest_opt && !read.frag && !read.auth {
                         // dst opt
-                        if read.dest_opt {
+                        let (header, rest) = Ipv6RawExtensionHeader::from_slice(slice).unwrap();
+                        assert_eq!(&header, exts.destination_options.as_ref().unwrap());
+                        slice = rest;
+                        read.dest_opt = true;
+                    } else {
+                        // final dest opt without routing header
+                        if read.unrouted_final_dest_opt {
                             stop = true;
                         } else {
                             let (header, rest) = Ipv6RawExtensionHeader::from_slice(slice).unwrap();
-                            assert_eq!(&header, exts.destination_options.as_ref().unwrap());
+                            assert_eq!(&header, exts.final_destination_options.as_ref().unwrap());
                             slice = rest;
-                            read.dest_opt = true;
+                            read.unrouted_final_dest_opt = true;
                         }
                     }
                 }
@@ -271,6 +284,9 @@ impl ExtensionTestPayload {
         if false == read.auth {
             assert!(exts.auth.is_none());
         }
+        if false == read.unrouted_final_dest_opt {
+            assert!(exts.final_destination_options.is_none());
+        }
 
         (self.data.len() - slice.len(), post_header)
     }
@@ -320,10 +336,13 @@ impl ExtensionTestHeaders {
                     self.data.hop_by_hop_options.as_mut().unwrap().next_header = new_header;
                 },
                 IPV6_DEST_OPTIONS => {
-                    if self.ip_numbers[..self.ip_numbers.len()-3].iter().any(|&x| x == IPV6_ROUTE) {
+                    let previous = &self.ip_numbers[..self.ip_numbers.len()-3];
+                    if previous.iter().any(|&x| x == IPV6_ROUTE) {
                         self.data.routing.as_mut().unwrap()
                             .final_destination_options.as_mut().unwrap()
                             .next_header = new_header;
+                    } else if previous.iter().any(|&x| x == IPV6_DEST_OPTIONS || x == IPV6_FRAG || x == AUTH) {
+                        self.data.final_destination_options.as_mut().unwrap().next_header = new_header;
                     } else {
                         self.data.destination_options.as_mut().unwrap().next_header = new_header;
                     }
@@ -398,14 +417,19 @@ impl ExtensionTestHeaders {
                         } else {
                             false
                         }
-                    } else {
+                    } else if self.data.destination_options.is_none() &&
+                              self.data.fragment.is_none() &&
+                              self.data.auth.is_none()
+                    {
                         // dest option
-                        if self.data.destination_options.is_none() {
-                            self.data.destination_options = Some(raw);
-                            true
-                        } else {
-                            false
-                        }
+                        self.data.destination_options = Some(raw);
+                        true
+                    } else if self.data.final_destination_options.is_none() {
+                        // final dest option without a routing header
+                        self.data.final_destination_options = Some(raw);
+                        true
+                    } else {
+                        false
                     },
                     _ => unreachable!(),
                 }
@@ -752,6 +776,7 @@ pub mod header {
                     ),
                     fragment: Some(fragment.clone()),
                     auth: Some(auth.clone()),
+                    final_destination_options: None,
                 };
                 assert_eq!(
                     exts.header_len(),
@@ -779,6 +804,7 @@ pub mod header {
                     ),
                     fragment: Some(fragment.clone()),
                     auth: Some(auth.clone()),
+                    final_destination_options: None,
                 };
                 assert_eq!(
                     exts.header_len(),
@@ -832,6 +858,7 @@ pub mod header {
                     ),
                     fragment: Some(fragment.clone()),
                     auth: Some(auth.clone()),
+                    final_destination_options: None,
                 };
                 assert_eq!(IPV6_HOP_BY_HOP, exts.set_next_headers(post_header));
 
@@ -938,6 +965,7 @@ pub mod header {
                 routing: None,
                 fragment: None,
                 auth: None,
+                final_destination_options: None,
             }.is_fragmenting_payload()
         );
 
@@ -950,6 +978,7 @@ pub mod header {
                 routing: None,
                 fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0, false, 0)),
                 auth: None,
+                final_destination_options: None,
             }.is_fragmenting_payload()
         );
 
@@ -961,10 +990,159 @@ pub mod header {
                 routing: None,
                 fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0, true, 0)),
                 auth: None,
+                final_destination_options: None,
             }.is_fragmenting_payload()
         );
     }
 
+    #[test]
+    fn check_order_and_canonicalize() {
+        use ip_number::*;
+        use ValueError::*;
+
+        let raw = |next_header| Ipv6RawExtensionHeader::new_raw(next_header, &[1,2,3,4,5,6]).unwrap();
+        let all = Ipv6Extensions{
+            hop_by_hop_options: Some(raw(IPV6_DEST_OPTIONS)),
+            destination_options: Some(raw(IPV6_ROUTE)),
+            routing: Some(Ipv6RoutingExtensions{
+                routing: raw(IPV6_FRAG),
+                final_destination_options: Some(raw(UDP)),
+            }),
+            fragment: Some(Ipv6FragmentHeader::new(AUTH, 0, false, 0)),
+            auth: Some(IpAuthenticationHeader::new(IPV6_DEST_OPTIONS, 0, 0, &[]).unwrap()),
+            final_destination_options: None,
+        };
+
+        // recommended order
+        assert_eq!(Ok(()), all.check_order(IPV6_HOP_BY_HOP));
+        assert_eq!(Ok(()), Ipv6Extensions::default().check_order(UDP));
+        {
+            let mut canonical = all.clone();
+            assert_eq!(Ok(IPV6_HOP_BY_HOP), canonical.canonicalize(IPV6_HOP_BY_HOP));
+            assert_eq!(all, canonical);
+        }
+
+        // fragment header after the authentication header
+        {
+            let mut ext = all.clone();
+            ext.routing.as_mut().unwrap().routing.next_header = AUTH;
+            ext.auth.as_mut().unwrap().next_header = IPV6_FRAG;
+            ext.fragment.as_mut().unwrap().next_header = IPV6_DEST_OPTIONS;
+            assert_eq!(
+                Err(Ipv6ExtensionOutOfOrder{
+                    header: IpNumber::IPv6FragmentationHeader,
+                    previous: IpNumber::AuthenticationHeader,
+                }),
+                ext.check_order(IPV6_HOP_BY_HOP)
+            );
+            assert_eq!(Ok(IPV6_HOP_BY_HOP), ext.canonicalize(IPV6_HOP_BY_HOP));
+            assert_eq!(all, ext);
+            assert_eq!(Ok(()), ext.check_order(IPV6_HOP_BY_HOP));
+        }
+
+        // destination options after the fragment header
+        {
+            let mut ext = Ipv6Extensions{
+                destination_options: Some(raw(TCP)),
+                fragment: Some(Ipv6FragmentHeader::new(IPV6_DEST_OPTIONS, 0, false, 0)),
+                ..Default::default()
+            };
+            assert_eq!(
+                Err(Ipv6ExtensionOutOfOrder{
+                    header: IpNumber::IPv6DestinationOptions,
+                    previous: IpNumber::IPv6FragmentationHeader,
+                }),
+                ext.check_order(IPV6_FRAG)
+            );
+            assert_eq!(Ok(IPV6_DEST_OPTIONS), ext.canonicalize(IPV6_FRAG));
+            assert_eq!(IPV6_FRAG, ext.destination_options.as_ref().unwrap().next_header);
+            assert_eq!(TCP, ext.fragment.as_ref().unwrap().next_header);
+            assert_eq!(Ok(()), ext.check_order(IPV6_DEST_OPTIONS));
+        }
+
+        // errors of next_header
+        {
+            let ext = Ipv6Extensions{
+                fragment: Some(Ipv6FragmentHeader::new(UDP, 0, false, 0)),
+                ..Default::default()
+            };
+            assert_eq!(
+                Err(Ipv6ExtensionNotReferenced(IpNumber::IPv6FragmentationHeader)),
+                ext.check_order(UDP)
+            );
+            assert_eq!(
+                Err(Ipv6ExtensionNotReferenced(IpNumber::IPv6FragmentationHeader)),
+                ext.clone().canonicalize(UDP)
+            );
+            let ext = Ipv6Extensions{
+                hop_by_hop_options: Some(raw(UDP)),
+                destination_options: Some(raw(IPV6_HOP_BY_HOP)),
+                ..Default::default()
+            };
+            assert_eq!(
+                Err(Ipv6ExtensionHopByHopNotAtStart),
+                ext.check_order(IPV6_DEST_OPTIONS)
+            );
+        }
+    }
+
+    #[test]
+    fn final_destination_options_without_routing() {
+        use ip_number::*;
+
+        let raw = |next_header, value| Ipv6RawExtensionHeader::new_raw(next_header, &[value;6]).unwrap();
+        let mut expected = Ipv6Extensions{
+            destination_options: Some(raw(IPV6_FRAG, 1)),
+            fragment: Some(Ipv6FragmentHeader::new(AUTH, 0, false, 0)),
+            auth: Some(IpAuthenticationHeader::new(IPV6_DEST_OPTIONS, 0, 0, &[]).unwrap()),
+            final_destination_options: Some(raw(UDP, 2)),
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), expected.check_order(IPV6_DEST_OPTIONS));
+        assert_eq!(IPV6_DEST_OPTIONS, expected.clone().set_next_headers(UDP));
+
+        let mut bytes = Vec::new();
+        expected.write(&mut bytes, IPV6_DEST_OPTIONS).unwrap();
+        assert_eq!(8 + 8 + 12 + 8, bytes.len());
+        bytes.extend_from_slice(&[9, 9]);
+
+        // from_slice & read
+        {
+            let (actual, next, rest) = Ipv6Extensions::from_slice(IPV6_DEST_OPTIONS, &bytes).unwrap();
+            assert_eq!(expected, actual);
+            assert_eq!(UDP, next);
+            assert_eq!(&[9, 9], rest);
+
+            let mut cursor = Cursor::new(&bytes);
+            let (actual, next) = Ipv6Extensions::read(&mut cursor, IPV6_DEST_OPTIONS).unwrap();
+            assert_eq!(expected, actual);
+            assert_eq!(UDP, next);
+            assert_eq!(bytes.len() - 2, cursor.position() as usize);
+        }
+
+        // without preceding destination options
+        {
+            expected.destination_options = None;
+            let mut bytes = Vec::new();
+            expected.write(&mut bytes, IPV6_FRAG).unwrap();
+            let (actual, next, _) = Ipv6Extensions::from_slice(IPV6_FRAG, &bytes).unwrap();
+            assert_eq!(expected, actual);
+            assert_eq!(UDP, next);
+        }
+
+        // a second destination options header after the final one is not parsed
+        {
+            let mut bytes = Vec::new();
+            expected.final_destination_options = Some(raw(IPV6_DEST_OPTIONS, 2));
+            expected.write(&mut bytes, IPV6_FRAG).unwrap();
+            raw(UDP, 3).write(&mut bytes).unwrap();
+            let (actual, next, rest) = Ipv6Extensions::from_slice(IPV6_FRAG, &bytes).unwrap();
+            assert_eq!(expected, actual);
+            assert_eq!(IPV6_DEST_OPTIONS, next);
+            assert_eq!(8, rest.len());
+        }
+    }
+
     #[test]
     fn is_empty() {
         // empty
@@ -975,6 +1153,7 @@ pub mod header {
                 routing: None,
                 fragment: None,
                 auth: None,
+                final_destination_options: None,
             }.is_empty()
         );
 
@@ -989,6 +1168,7 @@ pub mod header {
                 routing: None,
                 fragment: None,
                 auth: None,
+                final_destination_options: None,
             }.is_empty()
         );
 
@@ -1003,6 +1183,7 @@ pub mod header {
                 routing: None,
                 fragment: None,
                 auth: None,
+                final_destination_options: None,
             }.is_empty()
         );
 
@@ -1020,6 +1201,7 @@ pub mod header {
                 ),
                 fragment: None,
                 auth: None,
+                final_destination_options: None,
             }.is_empty()
         );
 
@@ -1032,6 +1214,7 @@ pub mod header {
                 routing: None,
                 fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0, true, 0)),
                 auth: None,
+                final_destination_options: None,
             }.is_empty()
         );
 
@@ -1044,6 +1227,7 @@ pub mod header {
                 routing: None,
                 fragment: None,
                 auth: Some(IpAuthenticationHeader::new(ip_number::UDP, 0, 0, &[]).unwrap()),
+                final_destination_options: None,
             }.is_empty()
         );
     }
@@ -1053,12 +1237,13 @@ pub mod header {
         let a : Ipv6Extensions = Default::default();
         assert_eq!(
             &format!(
-                "Ipv6Extensions {{ hop_by_hop_options: {:?}, destination_options: {:?}, routing: {:?}, fragment: {:?}, auth: {:?} }}",
+                "Ipv6Extensions {{ hop_by_hop_options: {:?}, destination_options: {:?}, routing: {:?}, fragment: {:?}, auth: {:?}, final_destination_options: {:?} }}",
                 a.hop_by_hop_options,
                 a.destination_options,
                 a.routing,
                 a.fragment,
                 a.auth,
+                a.final_destination_options,
             ),
             &format!("{:?}", a)
         );
@@ -1185,6 +1370,7 @@ pub mod slice {
                     ),
                     fragment: None,
                     auth: Some(auth),
+                    final_destination_options: None,
                 };
                 let first_ip_number = exts.set_next_headers(UDP);
 
@@ -1376,6 +1562,163 @@ pub mod ipv6_extension_slice {
 
         assert_ne!(route, hop);
     }
+
+    #[test]
+    fn ip_number_next_header_slice() {
+        use Ipv6ExtensionSlice::*;
+
+        let raw = Ipv6RawExtensionHeader::new_raw(TCP, &[1,2,3,4,5,6]).unwrap();
+        let mut raw_buffer = Vec::new();
+        raw.write(&mut raw_buffer).unwrap();
+        let raw_slice = Ipv6RawExtensionHeaderSlice::from_slice(&raw_buffer).unwrap();
+
+        let frag = Ipv6FragmentHeader::new(UDP, 1, true, 2);
+        let mut frag_buffer = Vec::new();
+        frag.write(&mut frag_buffer).unwrap();
+        let frag_slice = Ipv6FragmentHeaderSlice::from_slice(&frag_buffer).unwrap();
+
+        let auth = IpAuthenticationHeader::new(ICMP, 1, 2, &[1,2,3,4]).unwrap();
+        let mut auth_buffer = Vec::new();
+        auth.write(&mut auth_buffer).unwrap();
+        let auth_slice = IpAuthenticationHeaderSlice::from_slice(&auth_buffer).unwrap();
+
+        for (header, ip_number, next_header, slice) in [
+            (HopByHop(raw_slice.clone()), IPV6_HOP_BY_HOP, TCP, &raw_buffer[..]),
+            (Routing(raw_slice.clone()), IPV6_ROUTE, TCP, &raw_buffer[..]),
+            (DestinationOptions(raw_slice.clone()), IPV6_DEST_OPTIONS, TCP, &raw_buffer[..]),
+            (Fragment(frag_slice), IPV6_FRAG, UDP, &frag_buffer[..]),
+            (Authentication(auth_slice), AUTH, ICMP, &auth_buffer[..]),
+        ].iter() {
+            assert_eq!(*ip_number, header.ip_number());
+            assert_eq!(*next_header, header.next_header());
+            assert_eq!(*slice, header.slice());
+        }
+    }
+}
+
+pub mod lax_slice {
+    use super::*;
+
+    /// Serializes raw extension headers with the given ip numbers (the
+    /// first one identifying the first header & the last one the header
+    /// following the extension headers). Fragment headers located after
+    /// the second position have the more fragments flag set.
+    fn serialize(ip_numbers: &[u8]) -> Vec<u8> {
+        let mut result = Vec::new();
+        for (i, ip_number) in ip_numbers[..ip_numbers.len() - 1].iter().enumerate() {
+            let next_header = ip_numbers[i + 1];
+            match *ip_number {
+                IPV6_FRAG => Ipv6FragmentHeader::new(next_header, 0, i > 1, 1).write(&mut result).unwrap(),
+                AUTH => IpAuthenticationHeader::new(next_header, 1, 2, &[3;4]).unwrap().write(&mut result).unwrap(),
+                _ => Ipv6RawExtensionHeader::new_raw(next_header, &[*ip_number;6]).unwrap().write(&mut result).unwrap(),
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn from_slice() {
+        // duplicate & out of order headers
+        {
+            let ip_numbers = [
+                IPV6_DEST_OPTIONS,
+                IPV6_FRAG,
+                IPV6_HOP_BY_HOP,
+                IPV6_DEST_OPTIONS,
+                AUTH,
+                IPV6_ROUTE,
+                IPV6_FRAG,
+                AUTH,
+                UDP,
+            ];
+            let mut data = serialize(&ip_numbers);
+            let headers_len = data.len();
+            data.extend_from_slice(&[1,2,3]);
+
+            let actual = Ipv6ExtensionsLaxSlice::from_slice(ip_numbers[0], &data);
+            assert!(actual.stop_err.is_none());
+            assert_eq!(UDP, actual.next_header);
+            assert_eq!(&[1,2,3], actual.rest);
+            assert_eq!(
+                &ip_numbers[..ip_numbers.len() - 1],
+                &actual.headers.iter().map(|h| h.ip_number()).collect::<Vec<_>>()[..]
+            );
+            assert_eq!(
+                &data[..headers_len],
+                &actual.headers.iter().flat_map(|h| h.slice().iter().copied()).collect::<Vec<_>>()[..]
+            );
+            // only the second fragment header fragments the payload
+            assert!(actual.is_fragmenting_payload());
+
+            // the strict parsing rejects the hop by hop header
+            assert_matches!(
+                Ipv6ExtensionsSlice::from_slice(ip_numbers[0], &data),
+                Err(ReadError::Ipv6HopByHopHeaderNotAtStart)
+            );
+        }
+
+        // no extension headers
+        {
+            let actual = Ipv6ExtensionsLaxSlice::from_slice(TCP, &[1,2]);
+            assert!(actual.headers.is_empty());
+            assert!(actual.stop_err.is_none());
+            assert_eq!(TCP, actual.next_header);
+            assert_eq!(&[1,2], actual.rest);
+            assert!(!actual.is_fragmenting_payload());
+        }
+
+        // cut off header
+        {
+            let data = serialize(&[IPV6_ROUTE, IPV6_DEST_OPTIONS, UDP]);
+            let actual = Ipv6ExtensionsLaxSlice::from_slice(IPV6_ROUTE, &data[..data.len() - 1]);
+            assert_eq!(1, actual.headers.len());
+            assert_eq!(IPV6_DEST_OPTIONS, actual.next_header);
+            assert_eq!(&data[8..data.len() - 1], actual.rest);
+            assert_matches!(actual.stop_err, Some(ReadError::UnexpectedEndOfSlice(_)));
+        }
+    }
+
+    #[test]
+    fn from_slice_with_limits() {
+        let data = serialize(&[IPV6_HOP_BY_HOP, IPV6_DEST_OPTIONS, IPV6_ROUTE, UDP]);
+
+        // extension header count
+        {
+            let actual = Ipv6ExtensionsLaxSlice::from_slice_with_limits(
+                IPV6_HOP_BY_HOP,
+                &data,
+                ParseLimits{
+                    max_ipv6_extension_headers: 2,
+                    ..Default::default()
+                }
+            );
+            assert_eq!(2, actual.headers.len());
+            assert_eq!(IPV6_ROUTE, actual.next_header);
+            assert_eq!(&data[16..], actual.rest);
+            assert_matches!(
+                actual.stop_err,
+                Some(ReadError::ParseLimitExceeded(ParseLimit::Ipv6ExtensionHeaders))
+            );
+        }
+
+        // option bytes
+        {
+            let actual = Ipv6ExtensionsLaxSlice::from_slice_with_limits(
+                IPV6_HOP_BY_HOP,
+                &data,
+                ParseLimits{
+                    max_option_bytes: 5,
+                    ..Default::default()
+                }
+            );
+            assert!(actual.headers.is_empty());
+            assert_eq!(IPV6_HOP_BY_HOP, actual.next_header);
+            assert_matches!(
+                actual.stop_err,
+                Some(ReadError::ParseLimitExceeded(ParseLimit::OptionBytes))
+            );
+        }
+    }
 }
 
 pub mod slice_iter {
@@ -217,9 +217,9 @@ pub mod header {
                             (fragment_offset_be[1] >> 5 & 0b0000_0111u8)
                         ),
                         (
-                            (fragment_offset_be[1] & 0b0001_1111u8) |
+                            (fragment_offset_be[1] << 3 & 0b1111_1000u8) |
                             if input.more_fragments {
-                                0b1000_0000u8
+                                0b0000_0001u8
                             } else {
                                 0u8
                             }
@@ -348,6 +348,21 @@ pub mod slice {
         }
     }
 
+    proptest! {
+        #[test]
+        fn fragment_introspection(input in ipv6_fragment_any()) {
+            let buffer = input.to_bytes().unwrap();
+            let slice = Ipv6FragmentHeaderSlice::from_slice(&buffer[..]).unwrap();
+
+            assert_eq!(slice.is_fragmenting_payload(), slice.is_fragment());
+            assert_eq!(
+                input.more_fragments && 0 == input.fragment_offset,
+                slice.is_first_fragment()
+            );
+            assert_eq!(input.fragment_offset*8, slice.fragment_byte_offset());
+        }
+    }
+
     proptest! {
         #[test]
         fn is_fragmenting_payload(
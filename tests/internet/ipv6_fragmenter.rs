@@ -0,0 +1,253 @@
+use super::super::*;
+
+/// Extension headers with a 24 byte unfragmentable part (hop by hop
+/// options, destination options & routing) & a 24 byte fragmentable part
+/// (authentication header & final destination options).
+fn extensions() -> Ipv6Extensions {
+    let mut result = Ipv6Extensions{
+        hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[1;6]).unwrap()),
+        destination_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[2;6]).unwrap()),
+        routing: Some(Ipv6RoutingExtensions{
+            routing: Ipv6RawExtensionHeader::new_raw(0, &[3;6]).unwrap(),
+            final_destination_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[4;6]).unwrap()),
+        }),
+        fragment: None,
+        auth: Some(IpAuthenticationHeader::new(0, 1, 2, &[5;4]).unwrap()),
+        final_destination_options: None,
+    };
+    result.set_next_headers(ip_number::UDP);
+    result
+}
+
+fn header(next_header: u8) -> Ipv6Header {
+    Ipv6Header{
+        traffic_class: 1,
+        flow_label: 2,
+        payload_length: 0,
+        next_header,
+        hop_limit: 64,
+        source: [3;16],
+        destination: [4;16],
+    }
+}
+
+/// Serializes the given fragments, checks the fragment headers & returns
+/// the reassembled fragmentable part.
+fn reassemble(original: &Ipv6Header, fragments: &[(Ipv6Header, Ipv6Extensions, &[u8])], identification: u32, mtu: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    for (index, (header, extensions, payload)) in fragments.iter().enumerate() {
+        let last = index + 1 == fragments.len();
+
+        let mut packet = Vec::new();
+        header.write(&mut packet).unwrap();
+        extensions.write(&mut packet, header.next_header).unwrap();
+        let data_start = packet.len() - extensions.header_len();
+        packet.extend_from_slice(payload);
+        assert!(packet.len() <= mtu);
+
+        // read back
+        let (read_header, rest) = Ipv6Header::from_slice(&packet).unwrap();
+        assert_eq!(header, &read_header);
+        assert_eq!(usize::from(header.payload_length), rest.len());
+        let (read_extensions, protocol, read_payload) = Ipv6Extensions::from_slice(header.next_header, rest).unwrap();
+        assert_eq!(extensions, &read_extensions);
+        assert_eq!(ip_number::UDP, protocol);
+        assert_eq!(*payload, read_payload);
+
+        // fragment header
+        let fragment = extensions.fragment.as_ref().unwrap();
+        assert_eq!(identification, fragment.identification);
+        assert_eq!(result.len(), usize::from(fragment.fragment_offset)*8);
+        assert_eq!(!last, fragment.more_fragments);
+
+        // the fragmentable part starts after the fragment header
+        let mut unfragmentable = extensions.clone();
+        unfragmentable.auth = None;
+        if let Some(ref mut routing) = unfragmentable.routing {
+            routing.final_destination_options = None;
+        }
+        let data = &packet[data_start + unfragmentable.header_len()..];
+        if !last {
+            assert_eq!(0, data.len() % 8);
+        }
+        result.extend_from_slice(data);
+
+        assert_eq!(original.traffic_class, header.traffic_class);
+        assert_eq!(original.flow_label, header.flow_label);
+        assert_eq!(original.hop_limit, header.hop_limit);
+        assert_eq!(original.source, header.source);
+        assert_eq!(original.destination, header.destination);
+    }
+    result
+}
+
+#[test]
+fn fragment() {
+    let payload: Vec<u8> = (0..100u8).collect();
+    let extensions = extensions();
+    let header = header(ip_number::IPV6_HOP_BY_HOP);
+    let mtu = 120;
+
+    let fragments: Vec<_> = Ipv6Fragmenter::new(&header, &extensions, &payload, 1234, mtu).unwrap().collect();
+    // 48 bytes of fragmentable data per fragment (the first one
+    // containing 24 bytes of fragmentable extension headers)
+    assert_eq!(
+        vec![24, 48, 28],
+        fragments.iter().map(|(_, _, p)| p.len()).collect::<Vec<_>>()
+    );
+
+    let mut expected = Vec::new();
+    extensions.auth.as_ref().unwrap().write(&mut expected).unwrap();
+    extensions.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap().write(&mut expected).unwrap();
+    expected.extend_from_slice(&payload);
+    assert_eq!(expected, reassemble(&header, &fragments, 1234, mtu));
+
+    // fragmentable extension headers are only part of the first fragment
+    assert!(fragments[0].1.auth.is_some());
+    for (_, fragment_extensions, _) in &fragments[1..] {
+        assert_eq!(extensions.hop_by_hop_options, fragment_extensions.hop_by_hop_options);
+        assert_eq!(extensions.destination_options, fragment_extensions.destination_options);
+        // the routing header is followed by the fragment header
+        assert_eq!(
+            Ipv6RawExtensionHeader::new_raw(ip_number::IPV6_FRAG, &[3;6]).unwrap(),
+            fragment_extensions.routing.as_ref().unwrap().routing
+        );
+        assert!(fragment_extensions.routing.as_ref().unwrap().final_destination_options.is_none());
+        assert!(fragment_extensions.auth.is_none());
+    }
+}
+
+#[test]
+fn fragment_without_extensions() {
+    let payload = [1u8;100];
+    let header = header(ip_number::UDP);
+
+    let fragments: Vec<_> = Ipv6Fragmenter::new(&header, &Default::default(), &payload, 5, 88).unwrap().collect();
+    assert_eq!(
+        vec![40, 40, 20],
+        fragments.iter().map(|(_, _, p)| p.len()).collect::<Vec<_>>()
+    );
+    assert_eq!(&payload[..], &reassemble(&header, &fragments, 5, 88)[..]);
+}
+
+#[test]
+fn fragment_fragment() {
+    // already fragmented packets keep the identification, offset & more
+    // fragments flag
+    let payload = [1u8;40];
+    for more_fragments in [false, true].iter() {
+        let extensions = Ipv6Extensions{
+            fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 10, *more_fragments, 7)),
+            ..Default::default()
+        };
+        let header = header(ip_number::IPV6_FRAG);
+
+        let fragments: Vec<_> = Ipv6Fragmenter::new(&header, &extensions, &payload, 8, 64).unwrap().collect();
+        assert_eq!(
+            vec![(10, true, 7), (12, true, 7), (14, *more_fragments, 7)],
+            fragments.iter()
+                .map(|(_, e, _)| e.fragment.as_ref().unwrap())
+                .map(|f| (f.fragment_offset, f.more_fragments, f.identification))
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn no_fragmentation_needed() {
+    let extensions = extensions();
+    for payload in [&[][..], &[1,2,3][..]].iter() {
+        let mut header = header(ip_number::IPV6_HOP_BY_HOP);
+        header.payload_length = 1000;
+        let len = header.header_len() + extensions.header_len() + payload.len();
+
+        let fragments: Vec<_> = Ipv6Fragmenter::new(&header, &extensions, payload, 1, len).unwrap().collect();
+        assert_eq!(1, fragments.len());
+        let (fragment_header, fragment_extensions, fragment_payload) = &fragments[0];
+        assert_eq!(*payload, *fragment_payload);
+        assert_eq!((extensions.header_len() + payload.len()) as u16, fragment_header.payload_length);
+        assert_eq!(ip_number::IPV6_HOP_BY_HOP, fragment_header.next_header);
+        assert_eq!(&extensions, fragment_extensions);
+    }
+
+    // packets without extension headers
+    let fragments: Vec<_> = Ipv6Fragmenter::new(&header(ip_number::UDP), &Default::default(), &[], 1, 40).unwrap().collect();
+    assert_eq!(
+        vec![(header(ip_number::UDP), Default::default(), &[][..])],
+        fragments
+    );
+}
+
+#[test]
+fn no_fragmentation_needed_fragment() {
+    // already fragmented packets are passed through with their fragment header
+    let payload = [1u8;16];
+    for more_fragments in [false, true].iter() {
+        let extensions = Ipv6Extensions{
+            fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 10, *more_fragments, 7)),
+            ..Default::default()
+        };
+        let header = header(ip_number::IPV6_FRAG);
+
+        let fragments: Vec<_> = Ipv6Fragmenter::new(&header, &extensions, &payload, 8, 64).unwrap().collect();
+        assert_eq!(1, fragments.len());
+        let (fragment_header, fragment_extensions, fragment_payload) = &fragments[0];
+        assert_eq!(&payload[..], *fragment_payload);
+        assert_eq!(24, fragment_header.payload_length);
+        assert_eq!(ip_number::IPV6_FRAG, fragment_header.next_header);
+        assert_eq!(&extensions, fragment_extensions);
+    }
+}
+
+#[test]
+fn errors() {
+    use ValueError::*;
+
+    let payload = [0u8;100];
+    let extensions = extensions();
+    let header = header(ip_number::IPV6_HOP_BY_HOP);
+    let udp_header = self::header(ip_number::UDP);
+    let frag_header = self::header(ip_number::IPV6_FRAG);
+
+    // mtu too small for the extension headers & 8 bytes of payload
+    assert_eq!(
+        Err(Ipv6FragmentMtuTooSmall(95)),
+        Ipv6Fragmenter::new(&header, &extensions, &payload, 1, 95)
+    );
+    assert!(Ipv6Fragmenter::new(&header, &extensions, &payload, 1, 96).is_ok());
+    assert_eq!(
+        Err(Ipv6FragmentMtuTooSmall(55)),
+        Ipv6Fragmenter::new(&udp_header, &Default::default(), &payload, 1, 55)
+    );
+    assert!(Ipv6Fragmenter::new(&udp_header, &Default::default(), &payload, 1, 56).is_ok());
+
+    // reassembled packet too big
+    {
+        let extensions = Ipv6Extensions{
+            fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0x1fff, false, 0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(Ipv6PayloadLengthTooLarge(0x1fff*8 + 100)),
+            Ipv6Fragmenter::new(&frag_header, &extensions, &payload, 1, 1500)
+        );
+    }
+
+    // fragment offset out of range
+    {
+        let extensions = Ipv6Extensions{
+            fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0x2000, false, 0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(U16TooLarge{ value: 0x2000, max: 0x1fff, field: ErrorField::Ipv6FragmentOffset }),
+            Ipv6Fragmenter::new(&frag_header, &extensions, &[], 1, 1500)
+        );
+    }
+
+    // extension headers not referenced
+    assert_eq!(
+        Err(Ipv6ExtensionNotReferenced(IpNumber::IPv6HeaderHopByHop)),
+        Ipv6Fragmenter::new(&udp_header, &extensions, &payload, 1, 1500)
+    );
+}
@@ -348,3 +348,156 @@ fn partial_equal() {
         assert_ne!(a, b);
     }
 }
+
+#[test]
+fn jumbo_payload() {
+    // header only containing the option
+    {
+        let header = Ipv6RawExtensionHeader::new_jumbo_payload(ip_number::UDP, 0x1234_5678);
+        assert_eq!(ip_number::UDP, header.next_header);
+        assert_eq!(&[0xc2, 4, 0x12, 0x34, 0x56, 0x78], header.payload());
+        assert_eq!(Some(0x1234_5678), header.jumbo_payload_length());
+
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        let slice = Ipv6RawExtensionHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(Some(0x1234_5678), slice.jumbo_payload_length());
+    }
+
+    // option after other options & padding
+    {
+        let header = Ipv6RawExtensionHeader::new_raw(
+            ip_number::UDP,
+            &[
+                0, 1, 0, 5, 2, 0, 0,
+                0xc2, 4, 0, 1, 0, 0,
+                0,
+            ]
+        ).unwrap();
+        assert_eq!(Some(0x10000), header.jumbo_payload_length());
+    }
+
+    // no option or malformed options
+    for payload in [
+        &[1, 4, 0, 0, 0, 0][..],
+        &[0xc2, 3, 0, 0, 0, 0],
+        &[1, 2, 0, 0, 0xc2, 4],
+        &[0, 0, 0, 0, 0, 1],
+    ].iter() {
+        let header = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, payload).unwrap();
+        assert_eq!(None, header.jumbo_payload_length());
+    }
+
+    // overwrite an existing option
+    {
+        let mut header = Ipv6RawExtensionHeader::new_jumbo_payload(ip_number::UDP, 0);
+        header.set_jumbo_payload_length(0x10000).unwrap();
+        assert_eq!(&[0xc2, 4, 0, 1, 0, 0], header.payload());
+    }
+
+    // append the option (aligned to 4n + 2)
+    {
+        let mut header = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[5, 2, 0, 0, 1, 0]).unwrap();
+        header.set_jumbo_payload_length(0x10000).unwrap();
+        assert_eq!(
+            &[
+                5, 2, 0, 0, 1, 0,
+                1, 0, 0xc2, 4, 0, 1, 0, 0,
+            ],
+            header.payload()
+        );
+        assert_eq!(Some(0x10000), header.jumbo_payload_length());
+    }
+
+    // option does not fit into the header
+    {
+        // maximum length header filled with Pad1 options
+        let mut header = Ipv6RawExtensionHeader::new_raw(
+            ip_number::UDP,
+            &[0;Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN]
+        ).unwrap();
+        let before = header.clone();
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadTooLarge(Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN + 8)),
+            header.set_jumbo_payload_length(0x10000)
+        );
+        assert_eq!(before, header);
+    }
+}
+
+#[test]
+fn router_alert() {
+    // header only containing the option
+    {
+        let header = Ipv6RawExtensionHeader::new_router_alert(ip_number::UDP, Ipv6RawExtensionHeader::ROUTER_ALERT_RSVP);
+        assert_eq!(ip_number::UDP, header.next_header);
+        assert_eq!(&[5, 2, 0, 1, 1, 0], header.payload());
+        assert_eq!(Some(Ipv6RawExtensionHeader::ROUTER_ALERT_RSVP), header.router_alert());
+        assert_eq!(None, header.jumbo_payload_length());
+
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        let slice = Ipv6RawExtensionHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(Some(Ipv6RawExtensionHeader::ROUTER_ALERT_RSVP), slice.router_alert());
+    }
+
+    // no option or malformed options
+    for payload in [
+        &[1, 4, 0, 0, 0, 0][..],
+        &[5, 3, 0, 0, 0, 0],
+        &[1, 3, 0, 0, 0, 5],
+    ].iter() {
+        let header = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, payload).unwrap();
+        assert_eq!(None, header.router_alert());
+    }
+
+    // overwrite an existing option
+    {
+        let mut header = Ipv6RawExtensionHeader::new_router_alert(ip_number::UDP, 0);
+        header.set_router_alert(0x1234).unwrap();
+        assert_eq!(&[5, 2, 0x12, 0x34, 1, 0], header.payload());
+    }
+
+    // append the option
+    {
+        let mut header = Ipv6RawExtensionHeader::new_jumbo_payload(ip_number::UDP, 0x10000);
+        header.set_router_alert(2).unwrap();
+        assert_eq!(
+            &[
+                0xc2, 4, 0, 1, 0, 0,
+                5, 2, 0, 2, 1, 2, 0, 0,
+            ],
+            header.payload()
+        );
+        assert_eq!(Some(2), header.router_alert());
+        assert_eq!(Some(0x10000), header.jumbo_payload_length());
+    }
+
+    // option does not fit into the header
+    {
+        let mut header = Ipv6RawExtensionHeader::new_raw(
+            ip_number::UDP,
+            &[0;Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN]
+        ).unwrap();
+        let before = header.clone();
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadTooLarge(Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN + 8)),
+            header.set_router_alert(0)
+        );
+        assert_eq!(before, header);
+    }
+
+    // extensions
+    {
+        let mut extensions = Ipv6Extensions::default();
+        assert_eq!(None, extensions.router_alert());
+        extensions.set_router_alert(0).unwrap();
+        assert_eq!(
+            Some(&Ipv6RawExtensionHeader::new_router_alert(0, 0)),
+            extensions.hop_by_hop_options.as_ref()
+        );
+        extensions.set_router_alert(1).unwrap();
+        assert_eq!(Some(1), extensions.router_alert());
+        assert_eq!(8, extensions.header_len());
+    }
+}
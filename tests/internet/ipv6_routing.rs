@@ -0,0 +1,261 @@
+use super::super::*;
+
+/// Routing header payload of an RPL source routing header with two
+/// addresses (the first one with 8 & the second one with 14 elided
+/// octets) & 6 bytes of padding.
+const RPL_PAYLOAD: [u8;22] = [
+    Ipv6RoutingHeader::TYPE_RPL, 2, 0x8e, 0x60, 0, 0,
+    1, 2, 3, 4, 5, 6, 7, 8,
+    9, 10,
+    0, 0, 0, 0, 0, 0,
+];
+
+#[test]
+fn type0() {
+    let mut payload = vec![Ipv6RoutingHeader::TYPE_0, 1, 0, 0, 0, 0];
+    payload.extend_from_slice(&[1;16]);
+    payload.extend_from_slice(&[2;16]);
+
+    let actual = Ipv6RoutingHeader::from_payload(&payload).unwrap();
+    assert_eq!(
+        Ipv6RoutingHeader::Type0{
+            segments_left: 1,
+            addresses: vec![[1;16], [2;16]],
+        },
+        actual
+    );
+    assert_eq!(Ipv6RoutingHeader::TYPE_0, actual.routing_type());
+    assert_eq!(1, actual.segments_left());
+    assert_eq!(vec![[1;16], [2;16]], actual.addresses(&[0;16]));
+
+    // no addresses
+    assert_eq!(
+        Ipv6RoutingHeader::Type0{
+            segments_left: 0,
+            addresses: Vec::new(),
+        },
+        Ipv6RoutingHeader::from_payload(&[0;6]).unwrap()
+    );
+
+    // length not a multiple of the address size
+    payload.truncate(6 + 24);
+    assert_matches!(
+        Ipv6RoutingHeader::from_payload(&payload),
+        Err(ReadError::Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_0))
+    );
+}
+
+#[test]
+fn type2() {
+    let mut payload = vec![Ipv6RoutingHeader::TYPE_2, 1, 0, 0, 0, 0];
+    payload.extend_from_slice(&[3;16]);
+
+    let actual = Ipv6RoutingHeader::from_payload(&payload).unwrap();
+    assert_eq!(
+        Ipv6RoutingHeader::Type2{
+            segments_left: 1,
+            home_address: [3;16],
+        },
+        actual
+    );
+    assert_eq!(Ipv6RoutingHeader::TYPE_2, actual.routing_type());
+    assert_eq!(1, actual.segments_left());
+    assert_eq!(vec![[3;16]], actual.addresses(&[0;16]));
+
+    // exactly one address has to be present
+    for len in [6, 6 + 8, 6 + 32].iter() {
+        payload.resize(*len, 0);
+        assert_matches!(
+            Ipv6RoutingHeader::from_payload(&payload),
+            Err(ReadError::Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_2))
+        );
+    }
+}
+
+#[test]
+fn rpl() {
+    let actual = Ipv6RoutingHeader::from_payload(&RPL_PAYLOAD).unwrap();
+    assert_eq!(
+        Ipv6RoutingHeader::Rpl{
+            segments_left: 2,
+            cmpr_i: 8,
+            cmpr_e: 14,
+            addresses: vec![vec![1,2,3,4,5,6,7,8], vec![9,10]],
+        },
+        actual
+    );
+    assert_eq!(Ipv6RoutingHeader::TYPE_RPL, actual.routing_type());
+    assert_eq!(2, actual.segments_left());
+
+    // the elided octets are taken from the destination address
+    assert_eq!(
+        vec![
+            [0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,1,2,3,4,5,6,7,8],
+            [0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,0xfe,9,10],
+        ],
+        actual.addresses(&[0xfe;16])
+    );
+
+    // uncompressed addresses without padding
+    {
+        let mut payload = vec![Ipv6RoutingHeader::TYPE_RPL, 0, 0, 0, 0, 0];
+        payload.extend_from_slice(&[4;16]);
+        assert_eq!(
+            Ipv6RoutingHeader::Rpl{
+                segments_left: 0,
+                cmpr_i: 0,
+                cmpr_e: 0,
+                addresses: vec![vec![4;16]],
+            },
+            Ipv6RoutingHeader::from_payload(&payload).unwrap()
+        );
+    }
+
+    // length not matching the address sizes & padding
+    {
+        let mut payload = RPL_PAYLOAD;
+        // padding of 7 bytes
+        payload[3] = 0x70;
+        assert_matches!(
+            Ipv6RoutingHeader::from_payload(&payload),
+            Err(ReadError::Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_RPL))
+        );
+        // padding longer then the data
+        payload[3] = 0xf0;
+        assert_matches!(
+            Ipv6RoutingHeader::from_payload(&payload),
+            Err(ReadError::Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_RPL))
+        );
+    }
+}
+
+#[test]
+fn unknown() {
+    let payload = [253, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+    let actual = Ipv6RoutingHeader::from_payload(&payload).unwrap();
+    assert_eq!(
+        Ipv6RoutingHeader::Unknown{
+            routing_type: 253,
+            segments_left: 5,
+            data: vec![6, 7, 8, 9, 10, 11, 12, 13],
+        },
+        actual
+    );
+    assert_eq!(253, actual.routing_type());
+    assert_eq!(5, actual.segments_left());
+    assert!(actual.addresses(&[0;16]).is_empty());
+}
+
+#[test]
+fn from_payload_too_small() {
+    for len in 0..6 {
+        assert_matches!(
+            Ipv6RoutingHeader::from_payload(&RPL_PAYLOAD[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(6))
+        );
+    }
+}
+
+#[test]
+fn from_raw() {
+    let expected = Ipv6RoutingHeader::from_payload(&RPL_PAYLOAD).unwrap();
+    let raw = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &RPL_PAYLOAD).unwrap();
+    assert_eq!(expected, Ipv6RoutingHeader::from_raw(&raw).unwrap());
+
+    // slice
+    {
+        let mut bytes = Vec::new();
+        raw.write(&mut bytes).unwrap();
+        let slice = Ipv6RawExtensionHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(expected, Ipv6RoutingHeader::from_raw_slice(&slice).unwrap());
+    }
+
+    // routing extensions
+    {
+        let routing = Ipv6RoutingExtensions{
+            routing: raw,
+            final_destination_options: None,
+        };
+        assert_eq!(expected, routing.routing_header().unwrap());
+    }
+}
+
+#[test]
+fn final_destination() {
+    use std::net::Ipv6Addr;
+
+    let destination = [9;16];
+
+    // type 0
+    let type0 = Ipv6RoutingHeader::Type0{
+        segments_left: 2,
+        addresses: vec![[1;16], [2;16]],
+    };
+    assert_eq!([2;16], type0.final_destination(&destination));
+    assert_eq!(
+        destination,
+        Ipv6RoutingHeader::Type0{ segments_left: 0, addresses: vec![[1;16], [2;16]] }.final_destination(&destination)
+    );
+    assert_eq!(
+        destination,
+        Ipv6RoutingHeader::Type0{ segments_left: 1, addresses: Vec::new() }.final_destination(&destination)
+    );
+
+    // type 2
+    assert_eq!(
+        [3;16],
+        Ipv6RoutingHeader::Type2{ segments_left: 1, home_address: [3;16] }.final_destination(&destination)
+    );
+
+    // rpl (elided octets are taken from the destination)
+    {
+        let rpl = Ipv6RoutingHeader::from_payload(&RPL_PAYLOAD).unwrap();
+        let mut expected = destination;
+        expected[14..].copy_from_slice(&[9, 10]);
+        assert_eq!(expected, rpl.final_destination(&destination));
+    }
+
+    // segment routing (final segment is the first entry of the segment list)
+    {
+        let header = Ipv6SegmentRoutingHeader{
+            segments_left: 1,
+            flags: 0,
+            tag: 0,
+            segments: vec![Ipv6Addr::from([4;16]), Ipv6Addr::from([5;16])],
+            tlvs: Vec::new(),
+        };
+        assert_eq!([4;16], Ipv6RoutingHeader::SegmentRouting(header).final_destination(&destination));
+    }
+
+    // unknown
+    assert_eq!(
+        destination,
+        Ipv6RoutingHeader::Unknown{ routing_type: 200, segments_left: 1, data: vec![0;4] }.final_destination(&destination)
+    );
+
+    // ipv6 header & extensions
+    {
+        let header = Ipv6Header{
+            destination,
+            ..Default::default()
+        };
+        let mut extensions = Ipv6Extensions::default();
+        assert_eq!(destination, header.final_destination(&extensions));
+
+        let mut payload = vec![Ipv6RoutingHeader::TYPE_0, 2, 0, 0, 0, 0];
+        payload.extend_from_slice(&[1;16]);
+        payload.extend_from_slice(&[2;16]);
+        extensions.routing = Some(Ipv6RoutingExtensions{
+            routing: Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &payload).unwrap(),
+            final_destination_options: None,
+        });
+        assert_eq!([2;16], header.final_destination(&extensions));
+
+        // routing headers that can not be decoded are ignored
+        extensions.routing = Some(Ipv6RoutingExtensions{
+            routing: Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[Ipv6RoutingHeader::TYPE_2, 1, 0, 0, 0, 0]).unwrap(),
+            final_destination_options: None,
+        });
+        assert_eq!(destination, header.final_destination(&extensions));
+    }
+}
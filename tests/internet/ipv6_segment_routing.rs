@@ -0,0 +1,210 @@
+use super::super::*;
+
+use std::net::Ipv6Addr;
+
+fn header() -> Ipv6SegmentRoutingHeader {
+    Ipv6SegmentRoutingHeader{
+        segments_left: 2,
+        flags: 0x12,
+        tag: 0x3456,
+        segments: vec![
+            Ipv6Addr::from([1;16]),
+            Ipv6Addr::from([2;16]),
+            Ipv6Addr::from([3;16]),
+        ],
+        tlvs: vec![
+            Ipv6SegmentRoutingTlv::Hmac{
+                destination_address_check_disabled: true,
+                key_id: 0x789a_bcde,
+                hmac: vec![7;32],
+            },
+            Ipv6SegmentRoutingTlv::Unknown{
+                tlv_type: 123,
+                value: vec![8;4],
+            },
+            Ipv6SegmentRoutingTlv::PadN(0),
+        ],
+    }
+}
+
+#[test]
+fn to_raw_and_from_payload() {
+    let header = header();
+    let raw = header.to_raw(ip_number::UDP).unwrap();
+    assert_eq!(ip_number::UDP, raw.next_header);
+
+    let mut expected = vec![
+        Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING, 2, 2, 0x12, 0x34, 0x56,
+    ];
+    expected.extend_from_slice(&[1;16]);
+    expected.extend_from_slice(&[2;16]);
+    expected.extend_from_slice(&[3;16]);
+    expected.extend_from_slice(&[5, 38, 0x80, 0, 0x78, 0x9a, 0xbc, 0xde]);
+    expected.extend_from_slice(&[7;32]);
+    expected.extend_from_slice(&[123, 4, 8, 8, 8, 8]);
+    expected.extend_from_slice(&[4, 0]);
+    assert_eq!(&expected[..], raw.payload());
+    assert_eq!(104, raw.header_len());
+
+    assert_eq!(header, Ipv6SegmentRoutingHeader::from_raw(&raw).unwrap());
+    assert_eq!(header, Ipv6SegmentRoutingHeader::from_payload(raw.payload()).unwrap());
+    assert_eq!(
+        Ipv6RoutingHeader::SegmentRouting(header.clone()),
+        Ipv6RoutingHeader::from_raw(&raw).unwrap()
+    );
+}
+
+#[test]
+fn to_raw_padding() {
+    // no padding needed
+    {
+        let header = Ipv6SegmentRoutingHeader{
+            segments_left: 0,
+            flags: 0,
+            tag: 0,
+            segments: vec![Ipv6Addr::from([1;16])],
+            tlvs: Vec::new(),
+        };
+        let raw = header.to_raw(ip_number::UDP).unwrap();
+        assert_eq!(24, raw.header_len());
+        assert_eq!(header, Ipv6SegmentRoutingHeader::from_raw(&raw).unwrap());
+    }
+
+    // Pad1 & PadN appended
+    for (value_len, padding) in [
+        (5, Ipv6SegmentRoutingTlv::Pad1),
+        (0, Ipv6SegmentRoutingTlv::PadN(4)),
+        (4, Ipv6SegmentRoutingTlv::PadN(0)),
+    ].iter() {
+        let tlv = Ipv6SegmentRoutingTlv::Unknown{
+            tlv_type: 1,
+            value: vec![0;*value_len],
+        };
+        let header = Ipv6SegmentRoutingHeader{
+            segments_left: 0,
+            flags: 0,
+            tag: 0,
+            segments: vec![Ipv6Addr::from([1;16])],
+            tlvs: vec![tlv.clone()],
+        };
+        let raw = header.to_raw(ip_number::UDP).unwrap();
+        assert_eq!(32, raw.header_len());
+        assert_eq!(
+            vec![tlv, padding.clone()],
+            Ipv6SegmentRoutingHeader::from_raw(&raw).unwrap().tlvs
+        );
+    }
+}
+
+#[test]
+fn to_raw_errors() {
+    use ValueError::*;
+
+    // no segments
+    {
+        let mut header = header();
+        header.segments.clear();
+        assert_eq!(Err(Ipv6SegmentRoutingNoSegments), header.to_raw(0));
+    }
+    // tlv too large
+    {
+        let mut header = header();
+        header.tlvs = vec![Ipv6SegmentRoutingTlv::Unknown{ tlv_type: 1, value: vec![0;256] }];
+        assert_eq!(Err(Ipv6SegmentRoutingTlvTooLarge(256)), header.to_raw(0));
+
+        header.tlvs = vec![Ipv6SegmentRoutingTlv::Hmac{
+            destination_address_check_disabled: false,
+            key_id: 0,
+            hmac: vec![0;250],
+        }];
+        assert_eq!(Err(Ipv6SegmentRoutingTlvTooLarge(256)), header.to_raw(0));
+    }
+    // too many segments
+    {
+        let mut header = header();
+        header.tlvs.clear();
+        header.segments = vec![Ipv6Addr::from([0;16]);127];
+        assert!(header.to_raw(0).is_ok());
+        header.segments.push(Ipv6Addr::from([0;16]));
+        assert_eq!(Err(Ipv6ExtensionPayloadTooLarge(6 + 128*16)), header.to_raw(0));
+    }
+}
+
+#[test]
+fn from_payload_errors() {
+    let raw = header().to_raw(ip_number::UDP).unwrap();
+    let payload = raw.payload();
+
+    // fixed part
+    for len in 0..6 {
+        assert_matches!(
+            Ipv6SegmentRoutingHeader::from_payload(&payload[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(6))
+        );
+    }
+    // segment list & tlvs cut off
+    for len in [6, 6 + 47, 6 + 48 + 1, 6 + 48 + 39, 6 + 48 + 40 + 5].iter() {
+        assert_matches!(
+            Ipv6SegmentRoutingHeader::from_payload(&payload[..*len]),
+            Err(ReadError::Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING))
+        );
+    }
+    // hmac tlv too small for the key id
+    {
+        let mut payload = payload[..6 + 48].to_vec();
+        payload.extend_from_slice(&[5, 5, 0, 0, 0, 0, 0]);
+        assert_matches!(
+            Ipv6SegmentRoutingHeader::from_payload(&payload),
+            Err(ReadError::Ipv6RoutingHeaderLengthBad(Ipv6RoutingHeader::TYPE_SEGMENT_ROUTING))
+        );
+    }
+}
+
+#[test]
+fn segments() {
+    let mut header = header();
+    assert_eq!(2, header.last_entry());
+    assert_eq!(Some(Ipv6Addr::from([3;16])), header.active_segment());
+    assert_eq!(Some(Ipv6Addr::from([1;16])), header.final_segment());
+    assert_eq!(
+        vec![[1;16], [2;16], [3;16]],
+        Ipv6RoutingHeader::SegmentRouting(header.clone()).addresses(&[0;16])
+    );
+
+    assert_eq!(Some(Ipv6Addr::from([2;16])), header.advance());
+    assert_eq!(1, header.segments_left);
+    assert_eq!(Some(Ipv6Addr::from([1;16])), header.advance());
+    assert_eq!(0, header.segments_left);
+    assert_eq!(None, header.advance());
+    assert_eq!(0, header.segments_left);
+
+    // segments left behind the end of the segment list
+    header.segments_left = 3;
+    assert_eq!(None, header.active_segment());
+
+    // no segments
+    header.segments.clear();
+    assert_eq!(0, header.last_entry());
+    assert_eq!(None, header.final_segment());
+}
+
+#[test]
+fn tlv() {
+    use Ipv6SegmentRoutingTlv::*;
+    let hmac = Hmac{
+        destination_address_check_disabled: false,
+        key_id: 1,
+        hmac: vec![0;8],
+    };
+    let unknown = Unknown{ tlv_type: 12, value: vec![0;3] };
+
+    assert_eq!(0, Pad1.tlv_type());
+    assert_eq!(4, PadN(1).tlv_type());
+    assert_eq!(5, hmac.tlv_type());
+    assert_eq!(12, unknown.tlv_type());
+
+    assert_eq!(1, Pad1.header_len());
+    assert_eq!(3, PadN(1).header_len());
+    assert_eq!(16, hmac.header_len());
+    assert_eq!(5, unknown.header_len());
+}
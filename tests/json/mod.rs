@@ -0,0 +1,88 @@
+use super::*;
+use etherparse::json::*;
+use std::time::Duration;
+
+#[test]
+fn default_mode() {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(12)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(53, 1234);
+    let mut packet = Vec::with_capacity(builder.size(3));
+    builder.write(&mut packet, &[1,2,3]).unwrap();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+
+    let json = to_json(&sliced, JsonMode::Default, None);
+    assert!(json.starts_with(r#"{"ethernet2":{"destination":"07:08:09:0a:0b:0c","source":"01:02:03:04:05:06","ether_type":33024},"vlan":{"#));
+    assert!(json.contains(r#""vlan_identifier":12,"ether_type":2048}"#));
+    assert!(json.contains(r#""source":"192.168.1.1","destination":"192.168.1.2"}"#));
+    assert!(json.contains(r#""time_to_live":20,"#));
+    assert!(json.contains(r#""udp":{"source_port":53,"destination_port":1234,"length":11,"#));
+    assert!(json.ends_with(r#""payload":{"len":3}}"#));
+
+    // timestamp
+    let json = to_json(&sliced, JsonMode::Default, Some(Duration::from_millis(1234)));
+    assert!(json.starts_with(r#"{"timestamp":1234,"ethernet2":"#));
+}
+
+#[test]
+fn double_vlan() {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .double_vlan(12, 34)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(53, 1234);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+
+    // the tags are exported as array (no duplicate keys)
+    let json = to_json(&sliced, JsonMode::Default, None);
+    assert_eq!(1, json.matches(r#""vlan":"#).count());
+    assert!(json.contains(r#""vlan":[{"priority_code_point":0,"drop_eligible_indicator":false,"vlan_identifier":12,"ether_type":33024},{"#));
+    assert!(json.contains(r#""vlan_identifier":34,"ether_type":2048}],"ipv4":{"#));
+
+    let ek = to_json(&sliced, JsonMode::Ek, None);
+    assert_eq!(1, ek.matches(r#""vlan":"#).count());
+    assert!(ek.contains(r#""vlan":[{"vlan.priority":0,"vlan.dei":false,"vlan.id":12,"vlan.etype":"0x8100"},{"#));
+    assert!(ek.contains(r#""vlan.id":34,"vlan.etype":"0x0800"}],"ip":{"#));
+}
+
+#[test]
+fn ek_mode() {
+    // ipv4 & udp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(53, 1234);
+        let mut packet = Vec::with_capacity(builder.size(3));
+        builder.write(&mut packet, &[1,2,3]).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+
+        let json = to_json(&sliced, JsonMode::Ek, Some(Duration::from_millis(1234)));
+        assert!(json.starts_with(r#"{"timestamp":"1234","layers":{"eth":{"eth.dst":"07:08:09:0a:0b:0c","eth.src":"01:02:03:04:05:06","eth.type":"0x0800"},"ip":{"ip.version":4,"ip.hdr_len":20,"#));
+        assert!(json.contains(r#""ip.ttl":20,"ip.proto":17,"#));
+        assert!(json.contains(r#""ip.src":"192.168.1.1","ip.dst":"192.168.1.2"}"#));
+        assert!(json.contains(r#""udp":{"udp.srcport":53,"udp.dstport":1234,"udp.length":11,"#));
+        assert!(json.ends_with(r#""data":{"data.len":3}}}"#));
+    }
+    // ipv6 & tcp
+    {
+        let builder = PacketBuilder::
+            ipv6([0;16], [1;16], 30)
+            .tcp(80, 4321, 1, 1024)
+            .syn();
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+
+        let json = to_json(&sliced, JsonMode::Ek, None);
+        assert!(json.starts_with(r#"{"layers":{"ipv6":{"ipv6.version":6,"#));
+        assert!(json.contains(r#""ipv6.hlim":30,"ipv6.src":"::","ipv6.dst":"101:101:101:101:101:101:101:101"}"#));
+        assert!(json.contains(r#""tcp":{"tcp.srcport":80,"tcp.dstport":4321,"tcp.seq_raw":1,"#));
+        assert!(json.contains(r#""tcp.flags.syn":true,"tcp.flags.fin":false,"tcp.window_size_value":1024,"#));
+        assert!(json.ends_with(r#""data":{"data.len":0}}}"#));
+    }
+}
@@ -0,0 +1,196 @@
+use super::super::*;
+
+use std::io::{Cursor, ErrorKind};
+
+fn request() -> ArpPacket {
+    ArpPacket::new_ethernet_ipv4(
+        arp_operation::REQUEST,
+        [1,2,3,4,5,6], [192,168,1,1],
+        [0;6], [192,168,1,2]
+    )
+}
+
+#[test]
+fn to_bytes() {
+    assert_eq!(
+        vec![
+            0, 1, 8, 0, 6, 4, 0, 1,
+            1, 2, 3, 4, 5, 6, 192, 168, 1, 1,
+            0, 0, 0, 0, 0, 0, 192, 168, 1, 2,
+        ],
+        request().to_bytes().unwrap()
+    );
+    assert_eq!(28, request().header_len());
+    assert_eq!(Some(MacAddr([1,2,3,4,5,6])), request().sender_mac());
+    assert_eq!(Some(MacAddr::UNSPECIFIED), request().target_mac());
+}
+
+#[test]
+fn to_bytes_address_length_bad() {
+    use ValueError::ArpAddressLengthBad;
+
+    // sender & target hardware address length differ
+    {
+        let mut packet = request();
+        packet.target_hw_addr.pop();
+        assert_eq!(Some(ArpAddressLengthBad), packet.to_bytes().err());
+        assert_matches!(packet.write(&mut Vec::new()), Err(WriteError::ValueError(ArpAddressLengthBad)));
+    }
+    // sender & target protocol address length differ
+    {
+        let mut packet = request();
+        packet.sender_protocol_addr.push(0);
+        assert_eq!(Some(ArpAddressLengthBad), packet.to_bytes().err());
+    }
+    // too long to be represented
+    {
+        let mut packet = request();
+        packet.sender_hw_addr = vec![0;256];
+        packet.target_hw_addr = vec![0;256];
+        assert_eq!(Some(ArpAddressLengthBad), packet.to_bytes().err());
+        packet.sender_hw_addr.pop();
+        packet.target_hw_addr.pop();
+        assert_eq!(8 + 2*(255 + 4), packet.to_bytes().unwrap().len());
+    }
+}
+
+#[test]
+fn from_slice_write_read() {
+    let packet = request();
+    let mut buffer = Vec::new();
+    packet.write(&mut buffer).unwrap();
+    // ethernet padding
+    buffer.extend_from_slice(&[0;18]);
+
+    // from_slice
+    {
+        let (decoded, rest) = ArpPacket::from_slice(&buffer).unwrap();
+        assert_eq!(packet, decoded);
+        assert_eq!(&[0;18], rest);
+    }
+    // read
+    {
+        let mut cursor = Cursor::new(&buffer);
+        assert_eq!(packet, ArpPacket::read(&mut cursor).unwrap());
+        assert_eq!(28, cursor.position());
+    }
+    // slice
+    {
+        let slice = ArpPacketSlice::from_slice(&buffer).unwrap();
+        assert_eq!(&buffer[..28], slice.slice());
+        assert_eq!(arp_hardware_id::ETHERNET, slice.hw_addr_type());
+        assert_eq!(ether_type::IPV4, slice.proto_addr_type());
+        assert_eq!(6, slice.hw_addr_size());
+        assert_eq!(4, slice.proto_addr_size());
+        assert_eq!(arp_operation::REQUEST, slice.operation());
+        assert_eq!(&[1,2,3,4,5,6], slice.sender_hw_addr());
+        assert_eq!(&[192,168,1,1], slice.sender_protocol_addr());
+        assert_eq!(&[0;6], slice.target_hw_addr());
+        assert_eq!(&[192,168,1,2], slice.target_protocol_addr());
+        assert_eq!(Some(MacAddr([1,2,3,4,5,6])), slice.sender_mac());
+        assert_eq!(Some(MacAddr::UNSPECIFIED), slice.target_mac());
+        assert_eq!(packet, slice.to_packet());
+        assert_eq!(slice.clone(), slice);
+    }
+}
+
+#[test]
+fn from_slice_other_address_lengths() {
+    let packet = ArpPacket {
+        hw_addr_type: arp_hardware_id::IEEE802,
+        proto_addr_type: ether_type::IPV6,
+        operation: arp_operation::REPLY,
+        sender_hw_addr: vec![1,2],
+        sender_protocol_addr: vec![3;16],
+        target_hw_addr: vec![4,5],
+        target_protocol_addr: vec![6;16],
+    };
+    let bytes = packet.to_bytes().unwrap();
+    assert_eq!(8 + 2*(2 + 16), bytes.len());
+    assert_eq!(packet, ArpPacket::from_slice(&bytes).unwrap().0);
+    assert_eq!(None, packet.sender_mac());
+    assert_eq!(None, packet.target_mac());
+    let slice = ArpPacketSlice::from_slice(&bytes).unwrap();
+    assert_eq!(None, slice.sender_mac());
+    assert_eq!(None, slice.target_mac());
+
+    // no addresses
+    let empty = ArpPacket::default();
+    assert_eq!(empty, ArpPacket::from_slice(&empty.to_bytes().unwrap()).unwrap().0);
+}
+
+#[test]
+fn from_slice_too_small() {
+    let bytes = request().to_bytes().unwrap();
+    for len in 0..bytes.len() {
+        let expected = if len < 8 { 8 } else { 28 };
+        assert_matches!(
+            ArpPacketSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(value)) if value == expected
+        );
+        assert_matches!(
+            ArpPacket::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(value)) if value == expected
+        );
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            ArpPacket::read(&mut Cursor::new(&bytes[..len])).unwrap_err().kind()
+        );
+    }
+}
+
+#[test]
+fn sliced_packet_packet_headers() {
+    let eth = Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [0xff;6],
+        ether_type: ether_type::ARP,
+    };
+    let mut frame = Vec::new();
+    eth.write(&mut frame).unwrap();
+    request().write(&mut frame).unwrap();
+    // padding to the minimum ethernet frame size
+    frame.resize(60, 0);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&frame).unwrap();
+        assert_eq!(request(), sliced.arp.as_ref().unwrap().to_packet());
+        assert_eq!(None, sliced.ip);
+        assert_eq!(None, sliced.transport);
+        assert_eq!(&frame[42..], sliced.payload);
+        assert_eq!(&frame[14..42], sliced.header_bytes()[7]);
+        assert_eq!(42, sliced.headers_len());
+
+        let from_ether_type = SlicedPacket::from_ether_type(ether_type::ARP, &frame[14..]).unwrap();
+        assert_eq!(sliced.arp, from_ether_type.arp);
+
+        assert_matches!(
+            SlicedPacket::from_ethernet(&frame[..41]),
+            Err(ReadError::UnexpectedEndOfSlice(42))
+        );
+    }
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice(&frame).unwrap();
+        assert_eq!(Some(eth.clone()), headers.link);
+        assert_eq!(Some(request()), headers.arp);
+        assert_eq!(None, headers.ip);
+        assert_eq!(Payload::Ether{ ether_type: ether_type::ARP, payload: &frame[42..] }, headers.payload);
+        assert_eq!(42, headers.header_len());
+
+        assert_matches!(
+            PacketHeaders::from_ethernet_slice(&frame[..41]),
+            Err(ReadError::UnexpectedEndOfSlice(28))
+        );
+    }
+    // vlan tagged
+    {
+        let mut tagged = Vec::new();
+        Ethernet2Header{ ether_type: ether_type::VLAN_TAGGED_FRAME, ..eth }.write(&mut tagged).unwrap();
+        SingleVlanHeader{ vlan_identifier: 1, ether_type: ether_type::ARP, ..Default::default() }.write(&mut tagged).unwrap();
+        request().write(&mut tagged).unwrap();
+        assert_eq!(Some(request()), SlicedPacket::from_ethernet(&tagged).unwrap().arp.map(|v| v.to_packet()));
+        assert_eq!(Some(request()), PacketHeaders::from_ethernet_slice(&tagged).unwrap().arp);
+    }
+}
@@ -0,0 +1,145 @@
+use super::super::*;
+
+/// EAPOL-Key body with the given key info, nonce & key data.
+fn key_body(key_info: u16, nonce: u8, key_data: &[u8]) -> Vec<u8> {
+    let mut result = vec![eapol_key_descriptor_type::IEEE802_11];
+    result.extend_from_slice(&key_info.to_be_bytes());
+    result.extend_from_slice(&[0x00, 0x10]); // key length
+    result.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]); // replay counter
+    result.extend_from_slice(&[nonce;32]);
+    result.extend_from_slice(&[2;16]); // key iv
+    result.extend_from_slice(&[3;8]); // key rsc
+    result.extend_from_slice(&[0;8]); // key id
+    result.extend_from_slice(&[4;16]); // mic
+    result.extend_from_slice(&(key_data.len() as u16).to_be_bytes());
+    result.extend_from_slice(key_data);
+    result
+}
+
+/// EAPOL frame with the given packet type & body.
+fn frame(packet_type: u8, body: &[u8]) -> Vec<u8> {
+    let mut result = vec![2, packet_type];
+    result.extend_from_slice(&(body.len() as u16).to_be_bytes());
+    result.extend_from_slice(body);
+    result
+}
+
+#[test]
+fn eapol_slice() {
+    let mut bytes = frame(eapol_packet_type::EAP_PACKET, &[1, 2, 0, 5, 1]);
+    bytes.extend_from_slice(&[0, 0]);
+
+    let eapol = EapolSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..9], eapol.slice());
+    assert_eq!(2, eapol.version());
+    assert_eq!(eapol_packet_type::EAP_PACKET, eapol.packet_type());
+    assert_eq!(5, eapol.body_length());
+    assert_eq!(&[1, 2, 0, 5, 1], eapol.body());
+    assert_eq!(None, eapol.key());
+    assert_eq!(eapol.clone(), eapol);
+
+    for len in 0..9 {
+        assert_matches!(
+            EapolSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+    assert_matches!(
+        EapolSlice::from_slice(&bytes[..8]),
+        Err(ReadError::UnexpectedEndOfSlice(9))
+    );
+
+    // rc4 key descriptors are not decoded
+    let bytes = frame(eapol_packet_type::KEY, &[eapol_key_descriptor_type::RC4, 0, 0]);
+    let eapol = EapolSlice::from_slice(&bytes).unwrap();
+    assert_eq!(None, eapol.key());
+}
+
+#[test]
+fn key_slice() {
+    use crate::eapol_key_info::*;
+
+    let key_info = 2 | KEY_TYPE | INSTALL | KEY_ACK | KEY_MIC | SECURE | ENCRYPTED_KEY_DATA;
+    let mut bytes = frame(eapol_packet_type::KEY, &key_body(key_info, 1, &[5, 6, 7]));
+    bytes.extend_from_slice(&[0, 0]);
+
+    let eapol = EapolSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..bytes.len() - 2], eapol.slice());
+    let key = eapol.key().unwrap();
+    assert_eq!(eapol.body(), key.slice());
+    assert_eq!(eapol_key_descriptor_type::IEEE802_11, key.descriptor_type());
+    assert_eq!(key_info, key.key_info());
+    assert_eq!(2, key.key_descriptor_version());
+    assert!(key.is_pairwise());
+    assert!(key.install());
+    assert!(key.key_ack());
+    assert!(key.key_mic());
+    assert!(key.secure());
+    assert!(!key.error());
+    assert!(!key.request());
+    assert!(key.encrypted_key_data());
+    assert_eq!(16, key.key_length());
+    assert_eq!(1, key.replay_counter());
+    assert_eq!(&[1;32], key.nonce());
+    assert_eq!(&[2;16], key.key_iv());
+    assert_eq!(&[3;8], key.key_rsc());
+    assert_eq!(&[0;8], key.key_id());
+    assert_eq!(&[4;16], key.mic());
+    assert_eq!(3, key.key_data_length());
+    assert_eq!(&[5, 6, 7], key.key_data());
+
+    // unexpected end (offset of the eapol header is added)
+    let body = key_body(key_info, 1, &[5, 6, 7]);
+    for len in 0..body.len() {
+        assert_matches!(
+            EapolKeySlice::from_slice(&body[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+    assert_matches!(
+        EapolKeySlice::from_slice(&body[..EapolKeySlice::MIN_LEN - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(EapolKeySlice::MIN_LEN))
+    );
+    let mut short_key_data = frame(eapol_packet_type::KEY, &body);
+    short_key_data[3] -= 1;
+    assert_matches!(
+        EapolSlice::from_slice(&short_key_data),
+        Err(ReadError::UnexpectedEndOfSlice(102))
+    );
+}
+
+#[test]
+fn handshake_message() {
+    use crate::eapol_key_info::*;
+
+    for &(key_info, nonce, expected) in &[
+        (KEY_TYPE | KEY_ACK, 1, Some(1)),
+        (KEY_TYPE | KEY_MIC, 1, Some(2)),
+        (KEY_TYPE | INSTALL | KEY_ACK | KEY_MIC | SECURE, 1, Some(3)),
+        (KEY_TYPE | KEY_MIC | SECURE, 0, Some(4)),
+        // wpa message 4 (without the secure flag)
+        (KEY_TYPE | KEY_MIC, 0, Some(4)),
+        // group key handshake
+        (KEY_ACK | KEY_MIC | SECURE, 1, None),
+        (KEY_TYPE | KEY_ACK | KEY_MIC, 1, None),
+        (KEY_TYPE | KEY_MIC | REQUEST | ERROR, 0, None),
+    ] {
+        let body = key_body(key_info, nonce, &[]);
+        let key = EapolKeySlice::from_slice(&body).unwrap();
+        assert_eq!(expected, key.handshake_message());
+    }
+}
+
+#[test]
+fn from_ethernet() {
+    let mut packet = Ethernet2Header{
+        source: [1;6],
+        destination: [2;6],
+        ether_type: ether_type::EAPOL,
+    }.to_bytes().to_vec();
+    packet.extend_from_slice(&frame(eapol_packet_type::KEY, &key_body(eapol_key_info::KEY_TYPE | eapol_key_info::KEY_ACK, 1, &[])));
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let eapol = EapolSlice::from_slice(sliced.payload).unwrap();
+    assert_eq!(Some(1), eapol.key().unwrap().handshake_message());
+}
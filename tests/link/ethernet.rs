@@ -15,6 +15,12 @@ mod ether_type {
         assert_eq!(0x8100, VlanTaggedFrame as u16);
         assert_eq!(0x88A8, ProviderBridging as u16);
         assert_eq!(0x9100, VlanDoubleTaggedFrame as u16);
+        assert_eq!(0x6558, TransparentEthernetBridging as u16);
+        assert_eq!(0x8863, PppoeDiscovery as u16);
+        assert_eq!(0x8864, PppoeSession as u16);
+        assert_eq!(0x88CC, Lldp as u16);
+        assert_eq!(0x88E7, ProviderBackboneBridging as u16);
+        assert_eq!(0x888E, Eapol as u16);
     }
 
     #[test]
@@ -27,21 +33,122 @@ mod ether_type {
         assert_eq!(EtherType::from_u16(0x8100), Some(VlanTaggedFrame));
         assert_eq!(EtherType::from_u16(0x88A8), Some(ProviderBridging));
         assert_eq!(EtherType::from_u16(0x9100), Some(VlanDoubleTaggedFrame));
+        assert_eq!(EtherType::from_u16(0x6558), Some(TransparentEthernetBridging));
+        assert_eq!(EtherType::from_u16(0x8863), Some(PppoeDiscovery));
+        assert_eq!(EtherType::from_u16(0x8864), Some(PppoeSession));
+        assert_eq!(EtherType::from_u16(0x88CC), Some(Lldp));
+        assert_eq!(EtherType::from_u16(0x88E7), Some(ProviderBackboneBridging));
+        assert_eq!(EtherType::from_u16(0x888E), Some(Eapol));
         assert_eq!(EtherType::from_u16(0x1234), None);
     }
 
+    #[test]
+    fn try_from_all() {
+        use std::convert::TryFrom;
+
+        // raw values are unique & sorted
+        for pair in EtherType::ALL.windows(2) {
+            assert!((pair[0] as u16) < (pair[1] as u16));
+        }
+        for value in &EtherType::ALL {
+            assert_eq!(Ok(*value), EtherType::try_from(*value as u16));
+            assert_eq!(Some(*value), EtherType::from_u16(u16::from(*value)));
+        }
+        let known: Vec<u16> = EtherType::ALL.iter().map(|v| *v as u16).collect();
+        for raw in 0..=u16::MAX {
+            if !known.contains(&raw) {
+                assert_eq!(Err(UnknownEtherTypeError(raw)), EtherType::try_from(raw));
+            }
+        }
+    }
+
+    #[test]
+    fn display() {
+        use crate::EtherType::*;
+        assert_eq!("IPv4", Ipv4.to_string());
+        assert_eq!("IPv6", Ipv6.to_string());
+        assert_eq!("802.1Q VLAN", VlanTaggedFrame.to_string());
+        assert_eq!("802.1ad provider bridging", ProviderBridging.to_string());
+        assert_eq!("MPLS unicast", MplsUnicast.name());
+        assert_eq!("PTP", Ptp.name());
+        for value in &EtherType::ALL {
+            assert!(!value.name().is_empty());
+        }
+    }
+
+    #[test]
+    fn predicates() {
+        use crate::EtherType::*;
+        for value in &EtherType::ALL {
+            assert_eq!(
+                matches!(value, VlanTaggedFrame | ProviderBridging | VlanDoubleTaggedFrame),
+                value.is_vlan_tagging()
+            );
+            assert_eq!(
+                VlanHeader::VLAN_ETHER_TYPES.contains(&(*value as u16)),
+                value.is_vlan_tagging()
+            );
+            assert_eq!(matches!(value, Ipv4 | Ipv6), value.is_ip());
+            assert_eq!(matches!(value, MplsUnicast | MplsMulticast), value.is_mpls());
+            assert_eq!(matches!(value, PppoeDiscovery | PppoeSession), value.is_pppoe());
+        }
+    }
+
+    #[test]
+    fn unknown_error_display() {
+        assert_eq!(
+            "UnknownEtherTypeError: The ether type 0x1234 is not known.",
+            UnknownEtherTypeError(0x1234).to_string()
+        );
+    }
+
     #[test]
     fn constants() {
         use crate::EtherType::*;
         use crate::ether_type::*;
         let pairs = &[
             (Ipv4, IPV4),
-            (Ipv6, IPV6),
             (Arp, ARP),
             (WakeOnLan, WAKE_ON_LAN),
+            (Srp, SRP),
+            (Avtp, AVTP),
+            (Trill, TRILL),
+            (DecnetPhaseIv, DECNET_PHASE_IV),
+            (TransparentEthernetBridging, TRANSPARENT_ETHERNET_BRIDGING),
+            (Rarp, RARP),
+            (AppleTalk, APPLE_TALK),
+            (Aarp, AARP),
             (VlanTaggedFrame, VLAN_TAGGED_FRAME),
+            (Ipx, IPX),
+            (Ipv6, IPV6),
+            (EthernetFlowControl, ETHERNET_FLOW_CONTROL),
+            (SlowProtocols, SLOW_PROTOCOLS),
+            (MplsUnicast, MPLS_UNICAST),
+            (MplsMulticast, MPLS_MULTICAST),
+            (PppoeDiscovery, PPPOE_DISCOVERY),
+            (PppoeSession, PPPOE_SESSION),
+            (Eapol, EAPOL),
+            (Profinet, PROFINET),
+            (AtaOverEthernet, ATA_OVER_ETHERNET),
+            (EtherCat, ETHER_CAT),
             (ProviderBridging, PROVIDER_BRIDGING),
-            (VlanDoubleTaggedFrame, VLAN_DOUBLE_TAGGED_FRAME)
+            (EthernetPowerlink, ETHERNET_POWERLINK),
+            (Goose, GOOSE),
+            (Lldp, LLDP),
+            (Sercos3, SERCOS3),
+            (MacSec, MAC_SEC),
+            (ProviderBackboneBridging, PROVIDER_BACKBONE_BRIDGING),
+            (Ptp, PTP),
+            (Prp, PRP),
+            (Cfm, CFM),
+            (Fcoe, FCOE),
+            (FcoeInitialization, FCOE_INITIALIZATION),
+            (Roce, ROCE),
+            (Hsr, HSR),
+            (Nsh, NSH),
+            (EthernetConfigurationTesting, ETHERNET_CONFIGURATION_TESTING),
+            (VlanDoubleTaggedFrame, VLAN_DOUBLE_TAGGED_FRAME),
+            (RedundancyTag, REDUNDANCY_TAG)
         ];
 
         for (enum_value, constant) in pairs {
@@ -59,7 +166,13 @@ mod ether_type {
             (WakeOnLan, "WakeOnLan"),
             (VlanTaggedFrame, "VlanTaggedFrame"),
             (ProviderBridging, "ProviderBridging"),
-            (VlanDoubleTaggedFrame, "VlanDoubleTaggedFrame")
+            (VlanDoubleTaggedFrame, "VlanDoubleTaggedFrame"),
+            (TransparentEthernetBridging, "TransparentEthernetBridging"),
+            (PppoeDiscovery, "PppoeDiscovery"),
+            (PppoeSession, "PppoeSession"),
+            (Lldp, "Lldp"),
+            (ProviderBackboneBridging, "ProviderBackboneBridging"),
+            (Eapol, "Eapol")
         ];
 
         for (enum_value, str_value) in pairs {
@@ -234,6 +347,14 @@ mod ethernet2_header {
         }
     }
 
+    proptest! {
+        #[test]
+        fn addr(input in ethernet_2_any()) {
+            assert_eq!(MacAddr(input.source), input.source_addr());
+            assert_eq!(MacAddr(input.destination), input.destination_addr());
+        }
+    }
+
     proptest! {
         #[test]
         fn to_bytes(input in ethernet_2_any()) {
@@ -320,7 +441,11 @@ mod ethernet2_header_slice {
             let slice = Ethernet2HeaderSlice::from_slice(&buffer).unwrap();
             assert_eq!(input.destination, slice.destination());
             assert_eq!(input.source, slice.source());
+            assert_eq!(input.destination_addr(), slice.destination_addr());
+            assert_eq!(MacAddr(input.source), slice.source_addr());
             assert_eq!(input.ether_type, slice.ether_type());
+            assert_eq!(input.known_ether_type(), slice.known_ether_type());
+            assert_eq!(EtherType::from_u16(input.ether_type), slice.known_ether_type());
         }
     }
 
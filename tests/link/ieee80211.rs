@@ -0,0 +1,347 @@
+use super::super::*;
+
+#[test]
+fn frame_type_from_u8() {
+    use crate::Ieee80211FrameType::*;
+    for value in &[Management, Control, Data, Extension] {
+        assert_eq!(Some(*value), Ieee80211FrameType::from_u8(*value as u8));
+    }
+    assert_eq!(None, Ieee80211FrameType::from_u8(4));
+}
+
+fn assert_round_trip(header: &Ieee80211Header, expected: &[u8]) {
+    let mut bytes = Vec::new();
+    header.write(&mut bytes).unwrap();
+    assert_eq!(expected, &bytes[..]);
+    assert_eq!(expected.len(), header.header_len());
+
+    bytes.extend_from_slice(&[1, 2]);
+    let (decoded, rest) = Ieee80211Header::from_slice(&bytes).unwrap();
+    assert_eq!(header, &decoded);
+    assert_eq!(&[1, 2], rest);
+
+    // too short
+    for len in 0..expected.len() {
+        assert_matches!(
+            Ieee80211Header::from_slice(&expected[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
+
+fn data_header() -> Ieee80211Header {
+    Ieee80211Header{
+        frame_type: Ieee80211FrameType::Data as u8,
+        to_ds: true,
+        duration_id: 0x1234,
+        address1: [1,2,3,4,5,6],
+        address2: [7,8,9,10,11,12],
+        address3: [13,14,15,16,17,18],
+        sequence_control: 0x0121,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn data_frame() {
+    let header = data_header();
+    assert!(header.is_data());
+    assert!(header.has_data_body());
+    assert_eq!(1, header.fragment_number());
+    assert_eq!(0x12, header.sequence_number());
+    assert_eq!(0x0108, header.frame_control());
+    assert_round_trip(
+        &header,
+        &[
+            0x08, 0x01,
+            0x34, 0x12,
+            1, 2, 3, 4, 5, 6,
+            7, 8, 9, 10, 11, 12,
+            13, 14, 15, 16, 17, 18,
+            0x21, 0x01,
+        ]
+    );
+}
+
+#[test]
+fn optional_fields() {
+    // 4 addresses, qos & ht control
+    let header = Ieee80211Header{
+        subtype: 0b1000,
+        from_ds: true,
+        order: true,
+        address4: Some([21,22,23,24,25,26]),
+        qos_control: Some(0x0007),
+        ht_control: Some(0x44332211),
+        ..data_header()
+    };
+    assert_eq!(Ieee80211Header::MAX_LEN, header.header_len());
+    assert_round_trip(
+        &header,
+        &[
+            0x88, 0x83,
+            0x34, 0x12,
+            1, 2, 3, 4, 5, 6,
+            7, 8, 9, 10, 11, 12,
+            13, 14, 15, 16, 17, 18,
+            0x21, 0x01,
+            21, 22, 23, 24, 25, 26,
+            0x07, 0x00,
+            0x11, 0x22, 0x33, 0x44,
+        ]
+    );
+
+    // order flag without qos (no ht control)
+    let header = Ieee80211Header{
+        order: true,
+        ..data_header()
+    };
+    assert_eq!(Ieee80211Header::MIN_LEN, header.header_len());
+
+    // management frame with ht control
+    let header = Ieee80211Header{
+        frame_type: Ieee80211FrameType::Management as u8,
+        subtype: 0b1000,
+        to_ds: false,
+        order: true,
+        ht_control: Some(1),
+        ..data_header()
+    };
+    assert!(!header.is_data());
+    assert!(!header.has_data_body());
+    assert_eq!(Ieee80211Header::MIN_LEN + 4, header.header_len());
+    let mut bytes = Vec::new();
+    header.write(&mut bytes).unwrap();
+    assert_eq!(header, Ieee80211Header::from_slice(&bytes).unwrap().0);
+
+    // null data frame
+    let header = Ieee80211Header{
+        subtype: 0b0100,
+        ..data_header()
+    };
+    assert!(!header.has_data_body());
+}
+
+#[test]
+fn write_missing_optional_fields() {
+    let header = Ieee80211Header{
+        subtype: 0b1000,
+        from_ds: true,
+        ..data_header()
+    };
+    let mut bytes = Vec::new();
+    header.write(&mut bytes).unwrap();
+    assert_eq!(Ieee80211Header::MIN_LEN + 8, bytes.len());
+    assert_eq!(&[0;8], &bytes[Ieee80211Header::MIN_LEN..]);
+
+    let (decoded, _) = Ieee80211Header::from_slice(&bytes).unwrap();
+    assert_eq!(Some([0;6]), decoded.address4);
+    assert_eq!(Some(0), decoded.qos_control);
+}
+
+#[test]
+fn unsupported_frame_type() {
+    // ack (control frame)
+    assert_matches!(
+        Ieee80211Header::from_slice(&[0xd4, 0, 0, 0, 1, 2, 3, 4, 5, 6]),
+        Err(ReadError::Ieee80211UnsupportedFrameType(1))
+    );
+    assert_matches!(
+        Ieee80211Header::from_slice(&[0x0c, 0]),
+        Err(ReadError::Ieee80211UnsupportedFrameType(3))
+    );
+}
+
+#[test]
+fn header_slice() {
+    let header = Ieee80211Header{
+        subtype: 0b1000,
+        protected: true,
+        qos_control: Some(5),
+        ..data_header()
+    };
+    let bytes = frame_bytes(&header, &[1,2,3]);
+    let slice = Ieee80211HeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..26], slice.slice());
+    assert_eq!(Ieee80211FrameType::Data as u8, slice.frame_type());
+    assert_eq!(0b1000, slice.subtype());
+    assert!(slice.protected());
+    assert!(slice.has_data_body());
+    assert_eq!(header, slice.to_header());
+    assert_eq!(slice.clone(), slice);
+
+    // null data frame
+    let header = Ieee80211Header{
+        subtype: 0b0100,
+        ..data_header()
+    };
+    let bytes = frame_bytes(&header, &[]);
+    assert!(!Ieee80211HeaderSlice::from_slice(&bytes).unwrap().has_data_body());
+
+    // errors
+    assert_matches!(
+        Ieee80211HeaderSlice::from_slice(&bytes[..23]),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+    assert_matches!(
+        Ieee80211HeaderSlice::from_slice(&[0xd4, 0]),
+        Err(ReadError::Ieee80211UnsupportedFrameType(1))
+    );
+}
+
+fn frame_bytes(header: &Ieee80211Header, body: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    header.write(&mut result).unwrap();
+    result.extend_from_slice(body);
+    result
+}
+
+mod sliced_packet {
+    use super::*;
+
+    fn ipv4_udp() -> Vec<u8> {
+        let builder = PacketBuilder::
+            ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        packet
+    }
+
+    fn frame(header: &Ieee80211Header, body: &[u8]) -> Vec<u8> {
+        let mut result = Vec::new();
+        header.write(&mut result).unwrap();
+        result.extend_from_slice(body);
+        result
+    }
+
+    fn snap(ether_type: u16, payload: &[u8]) -> Vec<u8> {
+        let mut result = vec![0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00];
+        result.extend_from_slice(&ether_type.to_be_bytes());
+        result.extend_from_slice(payload);
+        result
+    }
+
+    #[test]
+    fn data() {
+        let header = Ieee80211Header{
+            subtype: 0b1000,
+            to_ds: true,
+            from_ds: true,
+            address4: Some([9;6]),
+            qos_control: Some(0),
+            ..data_header()
+        };
+        let packet = frame(&header, &snap(ether_type::IPV4, &ipv4_udp()));
+        let sliced = SlicedPacket::from_ieee80211(&packet).unwrap();
+        let link = assert_matches!(&sliced.link, Some(LinkSlice::Ieee80211(link)) => link);
+        assert_eq!(header, link.to_header());
+        assert_eq!(None, sliced.link.as_ref().unwrap().ether_type());
+        assert_eq!(None, sliced.link.as_ref().unwrap().to_header());
+        assert_eq!(Some(ether_type::IPV4), sliced.llc.as_ref().and_then(|llc| llc.ether_type()));
+        assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], sliced.payload);
+
+        // the slices add up to the packet
+        let mut bytes = sliced.header_bytes().concat();
+        bytes.extend_from_slice(sliced.payload);
+        bytes.extend_from_slice(sliced.padding);
+        assert_eq!(packet, bytes);
+
+        // error offsets include the 802.11 header
+        assert_matches!(
+            SlicedPacket::from_ieee80211(&packet[..header.header_len() + 8 + 10]),
+            Err(ReadError::UnexpectedEndOfSlice(60))
+        );
+
+        let sliced = SlicedPacket::from_link_type(LinkType::Ieee80211, &packet).unwrap();
+        assert_eq!(&[1,2,3,4], sliced.payload);
+
+        // bridge tunnel encapsulation
+        let mut body = snap(ether_type::IPV4, &ipv4_udp());
+        body[5] = 0xf8;
+        let packet = frame(&data_header(), &body);
+        assert_eq!(&[1,2,3,4], SlicedPacket::from_ieee80211(&packet).unwrap().payload);
+    }
+
+    #[test]
+    fn payload_only() {
+        let body = snap(ether_type::IPV4, &ipv4_udp());
+
+        // protected
+        {
+            let header = Ieee80211Header{
+                protected: true,
+                ..data_header()
+            };
+            let packet = frame(&header, &body);
+            let sliced = SlicedPacket::from_ieee80211(&packet).unwrap();
+            assert_eq!(Some(&packet[..24]), sliced.link.as_ref().map(|link| link.slice()));
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&body[..], sliced.payload);
+        }
+        // no llc/snap header
+        {
+            let packet = frame(&data_header(), &[1,2,3]);
+            assert_eq!(&[1,2,3], SlicedPacket::from_ieee80211(&packet).unwrap().payload);
+        }
+        // null data frame
+        {
+            let header = Ieee80211Header{
+                subtype: 0b0100,
+                ..data_header()
+            };
+            let packet = frame(&header, &[]);
+            assert_eq!(None, SlicedPacket::from_ieee80211(&packet).unwrap().ip);
+        }
+        // management & control frames
+        {
+            let header = Ieee80211Header{
+                frame_type: Ieee80211FrameType::Management as u8,
+                ..data_header()
+            };
+            let packet = frame(&header, &body);
+            let sliced = SlicedPacket::from_ieee80211(&packet).unwrap();
+            assert_eq!(None, sliced.link);
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&packet[..], sliced.payload);
+
+            let packet = [0xd4, 0, 0, 0, 1, 2, 3, 4, 5, 6];
+            assert_eq!(&packet[..], SlicedPacket::from_ieee80211(&packet).unwrap().payload);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_matches!(
+            SlicedPacket::from_ieee80211(&[0x08]),
+            Err(ReadError::UnexpectedEndOfSlice(2))
+        );
+        let packet = frame(&data_header(), &[]);
+        assert_matches!(
+            SlicedPacket::from_ieee80211(&packet[..23]),
+            Err(ReadError::UnexpectedEndOfSlice(24))
+        );
+
+        // limits
+        let mut vlan = SingleVlanHeader{
+            priority_code_point: 0,
+            drop_eligible_indicator: false,
+            vlan_identifier: 1,
+            ether_type: ether_type::IPV4,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        }.to_bytes().unwrap().to_vec();
+        vlan.extend_from_slice(&ipv4_udp());
+        let packet = frame(&data_header(), &snap(ether_type::VLAN_TAGGED_FRAME, &vlan));
+        let limits = ParseLimits{
+            max_vlan_depth: 0,
+            ..Default::default()
+        };
+        assert_matches!(
+            SlicedPacket::from_ieee80211_with_limits(&packet, limits),
+            Err(ReadError::ParseLimitExceeded(ParseLimit::VlanDepth))
+        );
+        assert!(SlicedPacket::from_ieee80211(&packet).unwrap().vlan.is_some());
+    }
+}
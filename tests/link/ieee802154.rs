@@ -0,0 +1,198 @@
+use super::super::*;
+
+#[test]
+fn frame_type_from_u8() {
+    use crate::Ieee802154FrameType::*;
+    for value in &[Beacon, Data, Acknowledgment, MacCommand, Multipurpose, Fragment, Extended] {
+        assert_eq!(Some(*value), Ieee802154FrameType::from_u8(*value as u8));
+    }
+    assert_eq!(None, Ieee802154FrameType::from_u8(4));
+    assert_eq!(None, Ieee802154FrameType::from_u8(8));
+}
+
+fn assert_round_trip(header: &Ieee802154Header, expected: &[u8]) {
+    let mut bytes = Vec::new();
+    header.write(&mut bytes).unwrap();
+    assert_eq!(expected, &bytes[..]);
+    assert_eq!(expected.len(), header.header_len());
+
+    bytes.extend_from_slice(&[1, 2]);
+    let (decoded, rest) = Ieee802154Header::from_slice(&bytes).unwrap();
+    assert_eq!(header, &decoded);
+    assert_eq!(&[1, 2], rest);
+
+    // too short
+    for len in 0..expected.len() {
+        assert_matches!(
+            Ieee802154Header::from_slice(&expected[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
+
+fn data_header(frame_version: u8) -> Ieee802154Header {
+    Ieee802154Header{
+        frame_type: Ieee802154FrameType::Data as u8,
+        frame_pending: false,
+        ack_request: true,
+        pan_id_compression: true,
+        ie_present: false,
+        frame_version,
+        sequence_number: Some(0x2a),
+        destination_pan_id: Some(0xabcd),
+        destination: Some(Ieee802154Address::Short([0x12, 0x34])),
+        source_pan_id: None,
+        source: Some(Ieee802154Address::Extended([1, 2, 3, 4, 5, 6, 7, 8])),
+        security: None,
+    }
+}
+
+#[test]
+fn short_and_extended_addresses() {
+    let header = data_header(1);
+    assert_round_trip(
+        &header,
+        &[
+            0x61, 0xd8, 0x2a,
+            0xcd, 0xab,
+            0x34, 0x12,
+            8, 7, 6, 5, 4, 3, 2, 1,
+        ]
+    );
+    assert_eq!(Some(0xabcd), header.source_pan_id());
+    assert_eq!(0xd861, header.frame_control());
+}
+
+#[test]
+fn pan_id_presence() {
+    // version 0/1 without pan id compression
+    {
+        let mut header = data_header(0);
+        header.pan_id_compression = false;
+        header.source_pan_id = Some(0x1111);
+        header.sequence_number = None;
+        assert_round_trip(
+            &header,
+            &[
+                0x21, 0xc9,
+                0xcd, 0xab,
+                0x34, 0x12,
+                0x11, 0x11,
+                8, 7, 6, 5, 4, 3, 2, 1,
+            ]
+        );
+        assert_eq!(Some(0x1111), header.source_pan_id());
+    }
+    // version 2, both extended addresses & pan id compression (no pan ids)
+    {
+        let mut header = data_header(2);
+        header.destination = Some(Ieee802154Address::Extended([9;8]));
+        header.destination_pan_id = None;
+        assert_round_trip(
+            &header,
+            &[
+                0x61, 0xec, 0x2a,
+                9, 9, 9, 9, 9, 9, 9, 9,
+                8, 7, 6, 5, 4, 3, 2, 1,
+            ]
+        );
+        assert_eq!(None, header.source_pan_id());
+    }
+    // version 2, no addresses & pan id compression (destination pan id only)
+    {
+        let mut header = data_header(2);
+        header.destination = None;
+        header.source = None;
+        assert_round_trip(&header, &[0x61, 0x20, 0x2a, 0xcd, 0xab]);
+    }
+    // missing pan ids are written as broadcast pan id
+    {
+        let mut header = data_header(1);
+        header.destination_pan_id = None;
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+        assert_eq!(&[0xff, 0xff], &bytes[3..5]);
+    }
+}
+
+#[test]
+fn security_header() {
+    use crate::Ieee802154KeyIdentifier::*;
+    for (key_identifier, key_bytes) in [
+        (Implicit, &[][..]),
+        (Index(5), &[5][..]),
+        (Source4{ source: [1, 2, 3, 4], index: 6 }, &[1, 2, 3, 4, 6][..]),
+        (Source8{ source: [1, 2, 3, 4, 5, 6, 7, 8], index: 7 }, &[1, 2, 3, 4, 5, 6, 7, 8, 7][..]),
+    ] {
+        assert_eq!(key_bytes.len(), key_identifier.len());
+        assert_eq!(key_bytes.is_empty(), key_identifier.is_empty());
+
+        let mut header = data_header(1);
+        header.security = Some(Ieee802154SecurityHeader{
+            security_level: 5,
+            asn_in_nonce: false,
+            frame_counter: Some(0x0102_0304),
+            key_identifier,
+        });
+        let mut expected = vec![
+            0x69, 0xd8, 0x2a,
+            0xcd, 0xab,
+            0x34, 0x12,
+            8, 7, 6, 5, 4, 3, 2, 1,
+            5 | (key_identifier.mode() << 3),
+            4, 3, 2, 1,
+        ];
+        expected.extend_from_slice(key_bytes);
+        assert_round_trip(&header, &expected);
+    }
+    // suppressed frame counter
+    let header = Ieee802154SecurityHeader{
+        security_level: 7,
+        asn_in_nonce: true,
+        frame_counter: None,
+        key_identifier: Index(1),
+    };
+    let mut bytes = Vec::new();
+    header.write(&mut bytes).unwrap();
+    assert_eq!(&[0x6f, 1], &bytes[..]);
+    assert_eq!(bytes.len(), header.header_len());
+    assert_eq!((header, &[][..]), Ieee802154SecurityHeader::from_slice(&bytes).unwrap());
+    assert_matches!(Ieee802154SecurityHeader::from_slice(&[]), Err(ReadError::UnexpectedEndOfSlice(1)));
+    assert_matches!(Ieee802154SecurityHeader::from_slice(&[0x18]), Err(ReadError::UnexpectedEndOfSlice(14)));
+}
+
+#[test]
+fn from_slice_errors() {
+    assert_matches!(
+        Ieee802154Header::from_slice(&[0x05, 0x00]),
+        Err(ReadError::Ieee802154UnsupportedFrameType(5))
+    );
+    assert_matches!(
+        Ieee802154Header::from_slice(&[0x01, 0x04, 0, 0, 0]),
+        Err(ReadError::Ieee802154ReservedAddressingMode(1))
+    );
+    assert_matches!(
+        Ieee802154Header::from_slice(&[0x01, 0x40, 0, 0, 0]),
+        Err(ReadError::Ieee802154ReservedAddressingMode(1))
+    );
+    // error in the security header contains the offset
+    assert_matches!(
+        Ieee802154Header::from_slice(&[0x09, 0x00, 0x01]),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+}
+
+#[test]
+fn address() {
+    assert_eq!(2, Ieee802154Address::Short([0;2]).len());
+    assert_eq!(8, Ieee802154Address::Extended([0;8]).len());
+    assert!(!Ieee802154Address::Short([0;2]).is_empty());
+    assert_eq!(
+        etherparse::sixlowpan::LinkAddress::Extended([1, 2, 3, 4, 5, 6, 7, 8]),
+        Ieee802154Address::Extended([1, 2, 3, 4, 5, 6, 7, 8]).into()
+    );
+    assert_eq!(
+        etherparse::sixlowpan::LinkAddress::Short([1, 2]),
+        Ieee802154Address::Short([1, 2]).into()
+    );
+}
@@ -0,0 +1,123 @@
+use super::super::*;
+
+/// LACPDU as sent by an active port in a synchronized aggregation.
+fn lacpdu() -> Vec<u8> {
+    let mut result = vec![
+        slow_protocol_subtype::LACP,
+        0x01, // version
+        0x01, 0x14, // actor tlv
+        0x80, 0x00, // actor system priority
+        0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c, // actor system
+        0x00, 0x0f, // actor key
+        0x00, 0xff, // actor port priority
+        0x00, 0x03, // actor port
+        0x3f, // actor state
+        0x00, 0x00, 0x00, // reserved
+        0x02, 0x14, // partner tlv
+        0x00, 0x01, // partner system priority
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, // partner system
+        0x01, 0x00, // partner key
+        0x00, 0x80, // partner port priority
+        0x00, 0x17, // partner port
+        0x47, // partner state
+        0x00, 0x00, 0x00, // reserved
+        0x03, 0x10, // collector tlv
+        0x00, 0x05, // collector max delay
+    ];
+    result.extend_from_slice(&[0;12]); // reserved
+    result.extend_from_slice(&[0, 0]); // terminator tlv
+    result.extend_from_slice(&[0;50]); // reserved
+    result
+}
+
+#[test]
+fn from_slice() {
+    let mut bytes = lacpdu();
+    assert_eq!(LacpduSlice::LEN, bytes.len());
+    // fcs
+    bytes.extend_from_slice(&[1, 2, 3, 4]);
+
+    let slice = LacpduSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..LacpduSlice::LEN], slice.slice());
+    assert_eq!(1, slice.version());
+    assert_eq!(
+        LacpPortInfo{
+            system_priority: 0x8000,
+            system: MacAddr([0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c]),
+            key: 15,
+            port_priority: 255,
+            port: 3,
+            state: 0x3f,
+        },
+        slice.actor()
+    );
+    assert_eq!(
+        LacpPortInfo{
+            system_priority: 1,
+            system: MacAddr([0x02, 0, 0, 0, 0, 0x01]),
+            key: 0x100,
+            port_priority: 0x80,
+            port: 0x17,
+            state: 0x47,
+        },
+        slice.partner()
+    );
+    assert_eq!(5, slice.collector_max_delay());
+    assert_eq!(slice.clone(), slice);
+}
+
+#[test]
+fn from_slice_errors() {
+    let bytes = lacpdu();
+    for len in 0..LacpduSlice::LEN {
+        assert_matches!(
+            LacpduSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(LacpduSlice::LEN))
+        );
+    }
+
+    let mut marker = bytes.clone();
+    marker[0] = slow_protocol_subtype::MARKER;
+    assert_matches!(
+        LacpduSlice::from_slice(&marker),
+        Err(ReadError::LacpUnexpectedSubtype(slow_protocol_subtype::MARKER))
+    );
+}
+
+#[test]
+fn state() {
+    use crate::lacp_state::*;
+
+    let flags = [
+        ACTIVITY, TIMEOUT, AGGREGATION, SYNCHRONIZATION,
+        COLLECTING, DISTRIBUTING, DEFAULTED, EXPIRED,
+    ];
+    for &flag in &flags {
+        for &state in &[flag, !flag] {
+            let info = LacpPortInfo{ state, ..Default::default() };
+            assert_eq!(0 != state & ACTIVITY, info.is_active());
+            assert_eq!(0 != state & TIMEOUT, info.is_short_timeout());
+            assert_eq!(0 != state & AGGREGATION, info.is_aggregatable());
+            assert_eq!(0 != state & SYNCHRONIZATION, info.is_in_sync());
+            assert_eq!(0 != state & COLLECTING, info.is_collecting());
+            assert_eq!(0 != state & DISTRIBUTING, info.is_distributing());
+            assert_eq!(0 != state & DEFAULTED, info.is_defaulted());
+            assert_eq!(0 != state & EXPIRED, info.is_expired());
+        }
+    }
+}
+
+#[test]
+fn from_ethernet() {
+    let mut packet = Ethernet2Header{
+        source: [0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c],
+        destination: [0x01, 0x80, 0xc2, 0x00, 0x00, 0x02],
+        ether_type: ether_type::SLOW_PROTOCOLS,
+    }.to_bytes().to_vec();
+    packet.extend_from_slice(&lacpdu());
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let lacpdu = LacpduSlice::from_slice(sliced.payload).unwrap();
+    assert!(lacpdu.actor().is_distributing());
+    assert!(lacpdu.partner().is_defaulted());
+}
@@ -0,0 +1,414 @@
+use super::*;
+
+/// Udp packet starting with the ipv4 header.
+fn ipv4_udp() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+/// Udp packet starting with the ipv6 header.
+fn ipv6_udp() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv6([1;16], [2;16], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn concat(prefix: &[u8], packet: &[u8]) -> Vec<u8> {
+    let mut result = prefix.to_vec();
+    result.extend_from_slice(packet);
+    result
+}
+
+fn assert_udp(packet: &SlicedPacket, ipv4: bool) {
+    if ipv4 {
+        assert_matches!(packet.ip, Some(InternetSlice::Ipv4(_, _)));
+    } else {
+        assert_matches!(packet.ip, Some(InternetSlice::Ipv6(_, _)));
+    }
+    assert_matches!(packet.transport, Some(TransportSlice::Udp(_)));
+    assert_eq!(packet.payload, &[1,2,3,4]);
+}
+
+#[test]
+fn from_u32() {
+    use LinkType::*;
+    assert_eq!(Some(Null), LinkType::from_u32(0));
+    assert_eq!(Some(Ethernet), LinkType::from_u32(1));
+    assert_eq!(Some(Ppp), LinkType::from_u32(9));
+    assert_eq!(Some(Raw), LinkType::from_u32(12));
+    assert_eq!(Some(Raw), LinkType::from_u32(14));
+    assert_eq!(Some(Raw), LinkType::from_u32(101));
+    assert_eq!(Some(Ieee80211), LinkType::from_u32(105));
+    assert_eq!(Some(Loop), LinkType::from_u32(108));
+    assert_eq!(Some(LinuxSll), LinkType::from_u32(113));
+    assert_eq!(Some(Ieee80211Radiotap), LinkType::from_u32(127));
+    assert_eq!(Some(Ieee802154), LinkType::from_u32(195));
+    assert_eq!(Some(Ieee802154NoFcs), LinkType::from_u32(230));
+    assert_eq!(Some(LinuxSll2), LinkType::from_u32(276));
+    assert_eq!(None, LinkType::from_u32(2));
+    assert_eq!(None, LinkType::from_u32(228));
+    for value in &[Null, Ethernet, Ppp, Raw, Ieee80211, Loop, LinuxSll, Ieee80211Radiotap, Ieee802154, Ieee802154NoFcs, LinuxSll2] {
+        assert_eq!(Some(*value), LinkType::from_u32(*value as u32));
+    }
+}
+
+#[test]
+fn ethernet() {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+
+    let sliced = SlicedPacket::from_link_type(LinkType::Ethernet, &packet).unwrap();
+    assert_matches!(sliced.link, Some(LinkSlice::Ethernet2(_)));
+    assert_udp(&sliced, true);
+}
+
+#[test]
+fn raw() {
+    let packet = ipv4_udp();
+    let sliced = SlicedPacket::from_link_type(LinkType::Raw, &packet).unwrap();
+    assert_eq!(None, sliced.link);
+    assert_udp(&sliced, true);
+
+    let packet = ipv6_udp();
+    assert_udp(&SlicedPacket::from_link_type(LinkType::Raw, &packet).unwrap(), false);
+}
+
+#[test]
+fn null() {
+    // little & big endian ipv4
+    for prefix in &[[2,0,0,0], [0,0,0,2]] {
+        let packet = concat(prefix, &ipv4_udp());
+        let sliced = SlicedPacket::from_link_type(LinkType::Null, &packet).unwrap();
+        assert_matches!(&sliced.link, Some(LinkSlice::Null(link)) if 2 == link.protocol_family());
+        assert_udp(&sliced, true);
+    }
+    // ipv6 protocol families of the different operating systems
+    for family in &[10u32, 24, 28, 30] {
+        for prefix in &[family.to_le_bytes(), family.to_be_bytes()] {
+            let packet = concat(prefix, &ipv6_udp());
+            assert_udp(&SlicedPacket::from_link_type(LinkType::Null, &packet).unwrap(), false);
+        }
+    }
+    // unknown protocol family
+    {
+        let packet = [7,0,0,0,1,2,3];
+        let sliced = SlicedPacket::from_link_type(LinkType::Null, &packet).unwrap();
+        assert_eq!(None, sliced.link.as_ref().unwrap().ether_type());
+        assert_eq!(None, sliced.ip);
+        assert_eq!(&[1,2,3], sliced.payload);
+    }
+    // too short
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Null, &[2,0,0]),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+}
+
+#[test]
+fn from_null() {
+    // loop uses network byte order
+    {
+        let packet = concat(&24u32.to_be_bytes(), &ipv6_udp());
+        let sliced = SlicedPacket::from_link_type(LinkType::Loop, &packet).unwrap();
+        assert_matches!(&sliced.link, Some(LinkSlice::Null(link)) if 24 == link.protocol_family());
+        assert_udp(&sliced, false);
+        assert_udp(&SlicedPacket::from_null(&packet).unwrap(), false);
+    }
+    // the slices add up to the packet
+    {
+        let packet = concat(&2u32.to_le_bytes(), &ipv4_udp());
+        let sliced = SlicedPacket::from_null(&packet).unwrap();
+        let mut bytes = sliced.header_bytes().concat();
+        bytes.extend_from_slice(sliced.payload);
+        bytes.extend_from_slice(sliced.padding);
+        assert_eq!(packet, bytes);
+
+        // error offsets include the header
+        assert_matches!(
+            SlicedPacket::from_null(&packet[..4 + 10]),
+            Err(ReadError::UnexpectedEndOfSlice(24))
+        );
+    }
+    // ipv4 in both byte orders
+    for prefix in &[2u32.to_le_bytes(), 2u32.to_be_bytes()] {
+        let packet = concat(prefix, &ipv4_udp());
+        assert_udp(&SlicedPacket::from_null(&packet).unwrap(), true);
+    }
+    // limits
+    {
+        let mut ip = Ipv4Header::new(8, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+        ip.set_options(&[0;4]).unwrap();
+        let mut packet = 2u32.to_le_bytes().to_vec();
+        ip.write(&mut packet).unwrap();
+        packet.extend_from_slice(&UdpHeader{ source_port: 1, destination_port: 2, length: 8, checksum: 0 }.to_bytes());
+        let limits = ParseLimits{
+            max_option_bytes: 0,
+            ..Default::default()
+        };
+        assert_matches!(
+            SlicedPacket::from_null_with_limits(&packet, limits),
+            Err(ReadError::ParseLimitExceeded(ParseLimit::OptionBytes))
+        );
+        assert!(SlicedPacket::from_null(&packet).is_ok());
+    }
+    // too short
+    assert_matches!(
+        SlicedPacket::from_null(&[0,0,0]),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+}
+
+#[test]
+fn ppp() {
+    // with & without address & control field, compressed & uncompressed protocol
+    for (prefix, ipv4) in &[
+        (&[0xff,0x03,0x00,0x21][..], true),
+        (&[0xff,0x03,0x21][..], true),
+        (&[0x00,0x21][..], true),
+        (&[0x21][..], true),
+        (&[0xff,0x03,0x00,0x57][..], false),
+        (&[0x57][..], false),
+    ] {
+        let packet = concat(prefix, &if *ipv4 { ipv4_udp() } else { ipv6_udp() });
+        let sliced = SlicedPacket::from_link_type(LinkType::Ppp, &packet).unwrap();
+        assert_eq!(None, sliced.link);
+        assert_udp(&sliced, *ipv4);
+    }
+    // unknown protocol (lcp)
+    {
+        let packet = [0xff,0x03,0xc0,0x21,1,2];
+        let sliced = SlicedPacket::from_link_type(LinkType::Ppp, &packet).unwrap();
+        assert_eq!(None, sliced.ip);
+        assert_eq!(&[1,2], sliced.payload);
+    }
+    // too short
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ppp, &[0xff,0x03,0x00]),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ppp, &[]),
+        Err(ReadError::UnexpectedEndOfSlice(2))
+    );
+}
+
+#[test]
+fn linux_sll() {
+    let header = [
+        0,0, // packet type
+        0,1, // arphrd type
+        0,6, // address length
+        1,2,3,4,5,6,0,0, // address
+    ];
+    {
+        let packet = concat(&concat(&header, &[0x08,0x00]), &ipv4_udp());
+        let sliced = SlicedPacket::from_link_type(LinkType::LinuxSll, &packet).unwrap();
+        assert_matches!(sliced.link, Some(LinkSlice::LinuxSll(_)));
+        assert_udp(&sliced, true);
+    }
+    {
+        let packet = concat(&concat(&header, &[0x86,0xdd]), &ipv6_udp());
+        assert_udp(&SlicedPacket::from_link_type(LinkType::LinuxSll, &packet).unwrap(), false);
+    }
+    // too short
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::LinuxSll, &concat(&header, &[0x08])),
+        Err(ReadError::UnexpectedEndOfSlice(16))
+    );
+}
+
+#[test]
+fn linux_sll2() {
+    let header = [
+        0x86,0xdd, // protocol type
+        0,0, // reserved
+        0,0,0,2, // interface index
+        0,1, // arphrd type
+        4, // packet type
+        6, // address length
+        1,2,3,4,5,6,0,0, // address
+    ];
+    let packet = concat(&header, &ipv6_udp());
+    let sliced = SlicedPacket::from_link_type(LinkType::LinuxSll2, &packet).unwrap();
+    assert_matches!(sliced.link, Some(LinkSlice::LinuxSll2(_)));
+    assert_udp(&sliced, false);
+
+    // too short
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::LinuxSll2, &header[..19]),
+        Err(ReadError::UnexpectedEndOfSlice(20))
+    );
+}
+
+/// Radiotap header followed by an 802.11 data frame (to ds) containing the packet.
+fn radiotap_80211(radiotap: &[u8], qos: bool, protected: bool, packet: &[u8]) -> Vec<u8> {
+    let mut result = radiotap.to_vec();
+    // frame control
+    result.push(if qos { 0x88 } else { 0x08 });
+    result.push(0x01 | if protected { 0x40 } else { 0 });
+    result.extend_from_slice(&[0;2]); // duration
+    result.extend_from_slice(&[1;18]); // addresses
+    result.extend_from_slice(&[0;2]); // sequence control
+    if qos {
+        result.extend_from_slice(&[0;2]);
+    }
+    // llc/snap
+    result.extend_from_slice(&[0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00]);
+    result.extend_from_slice(packet);
+    result
+}
+
+#[test]
+fn ieee80211_radiotap() {
+    // minimal radiotap header
+    let radiotap = [0, 0, 8, 0, 0, 0, 0, 0];
+    for qos in &[false, true] {
+        let packet = radiotap_80211(&radiotap, *qos, false, &ipv4_udp());
+        let sliced = SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &packet).unwrap();
+        assert_matches!(sliced.link, Some(LinkSlice::Ieee80211(_)));
+        assert_udp(&sliced, true);
+    }
+    // tsft & flags field with the fcs flag set
+    {
+        let radiotap = [
+            0, 0, 17, 0, // version, pad, length
+            0b11, 0, 0, 0, // present (tsft & flags)
+            1, 2, 3, 4, 5, 6, 7, 8, // tsft
+            0x10, // flags (fcs at end)
+        ];
+        let mut packet = radiotap_80211(&radiotap, false, false, &ipv4_udp());
+        packet.extend_from_slice(&[0xff;4]);
+        assert_udp(&SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &packet).unwrap(), true);
+    }
+    // protected frame
+    {
+        let packet = radiotap_80211(&radiotap, false, true, &ipv4_udp());
+        let sliced = SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &packet).unwrap();
+        assert_eq!(None, sliced.ip);
+        assert_eq!(&packet[8 + 24..], sliced.payload);
+    }
+    // management frame (beacon)
+    {
+        let mut packet = radiotap.to_vec();
+        packet.extend_from_slice(&[0x80, 0, 1, 2, 3]);
+        let sliced = SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &packet).unwrap();
+        assert_eq!(None, sliced.ip);
+        assert_eq!(&packet[8..], sliced.payload);
+    }
+    // too short
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &[0, 0, 8, 0, 0, 0, 0]),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &[0, 0, 12, 0, 0, 0, 0, 0]),
+        Err(ReadError::UnexpectedEndOfSlice(12))
+    );
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ieee80211Radiotap, &[0, 0, 8, 0, 0, 0, 0, 0, 0x08, 0, 0]),
+        Err(ReadError::UnexpectedEndOfSlice(32))
+    );
+}
+
+#[test]
+fn from_link_type_ieee802154() {
+    // data frame, pan id compression, short addresses, version 1
+    let header = [0x41, 0x98, 0x01, 0xcd, 0xab, 0x02, 0x00, 0x01, 0x00];
+    let mut frame = header.to_vec();
+    frame.push(0x41);
+    frame.extend_from_slice(&ipv6_udp());
+
+    assert_udp(&SlicedPacket::from_link_type(LinkType::Ieee802154NoFcs, &frame).unwrap(), false);
+    frame.extend_from_slice(&[0x12, 0x34]);
+    assert_udp(&SlicedPacket::from_link_type(LinkType::Ieee802154, &frame).unwrap(), false);
+
+    // compressed 6lowpan packet
+    let frame = concat(&header, &[0x7e, 0x33, 0xf3, 0x12, 0, 0, 0x12, 0x34]);
+    let sliced = SlicedPacket::from_link_type(LinkType::Ieee802154, &frame).unwrap();
+    assert!(sliced.ip.is_none());
+    assert_eq!(&[0x7e, 0x33, 0xf3, 0x12, 0, 0], sliced.payload);
+
+    // errors
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ieee802154, &[0x41]),
+        Err(ReadError::UnexpectedEndOfSlice(2))
+    );
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ieee802154NoFcs, &header[..5]),
+        Err(ReadError::UnexpectedEndOfSlice(9))
+    );
+}
+
+#[test]
+fn from_link_type_with_limits() {
+    let limits = ParseLimits{
+        max_option_bytes: 0,
+        max_ipv6_extension_headers: 0,
+        ..Default::default()
+    };
+
+    // ipv4 packet with options
+    let ipv4 = {
+        let mut ip = Ipv4Header::new(8, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+        ip.set_options(&[0;4]).unwrap();
+        let mut packet = Vec::new();
+        ip.write(&mut packet).unwrap();
+        packet.extend_from_slice(&UdpHeader{ source_port: 1, destination_port: 2, length: 8, checksum: 0 }.to_bytes());
+        packet
+    };
+    // ipv6 packet with a hop by hop options header
+    let ipv6 = {
+        let mut packet = ipv6_udp();
+        packet[5] += 8;
+        packet[6] = 0;
+        let udp = packet.split_off(Ipv6Header::SERIALIZED_SIZE);
+        packet.extend_from_slice(&[17, 0, 1, 4, 0, 0, 0, 0]);
+        packet.extend_from_slice(&udp);
+        packet
+    };
+
+    let sll = concat(&[0,0, 0,1, 0,6, 1,2,3,4,5,6,0,0, 0x08,0x00], &ipv4);
+    let sll2 = concat(&[0x08,0x00, 0,0, 0,0,0,2, 0,1, 4, 6, 1,2,3,4,5,6,0,0], &ipv4);
+    let radiotap = radiotap_80211(&[0, 0, 8, 0, 0, 0, 0, 0], false, false, &ipv4);
+    let ieee802154 = concat(&[0x41, 0x98, 0x01, 0xcd, 0xab, 0x02, 0x00, 0x01, 0x00, 0x41], &ipv6);
+    let packets = [
+        (LinkType::Ethernet, concat(&[1,2,3,4,5,6, 7,8,9,10,11,12, 0x08,0x00], &ipv4)),
+        (LinkType::Raw, ipv4.clone()),
+        (LinkType::Null, concat(&2u32.to_le_bytes(), &ipv4)),
+        (LinkType::Loop, concat(&24u32.to_be_bytes(), &ipv6)),
+        (LinkType::Ppp, concat(&[0xff,0x03,0x00,0x21], &ipv4)),
+        (LinkType::LinuxSll, sll),
+        (LinkType::LinuxSll2, sll2),
+        (LinkType::Ieee80211, radiotap[8..].to_vec()),
+        (LinkType::Ieee80211Radiotap, radiotap.clone()),
+        (LinkType::Ieee802154NoFcs, ieee802154),
+    ];
+    for (link_type, packet) in &packets {
+        assert!(SlicedPacket::from_link_type(*link_type, packet).is_ok());
+        assert_matches!(
+            SlicedPacket::from_link_type_with_limits(*link_type, packet, limits),
+            Err(ReadError::ParseLimitExceeded(_))
+        );
+    }
+
+    // error offsets include the ppp & 802.15.4 headers
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ppp, &concat(&[0xff,0x03,0x00,0x21], &ipv4[..10])),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+    assert_matches!(
+        SlicedPacket::from_link_type(LinkType::Ieee802154NoFcs, &packets[9].1[..20]),
+        Err(ReadError::UnexpectedEndOfSlice(50))
+    );
+}
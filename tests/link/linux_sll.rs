@@ -0,0 +1,326 @@
+use super::*;
+
+use std::io::Cursor;
+
+/// Udp packet starting with the ipv4 header.
+fn ipv4_udp() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+/// Udp packet starting with a single vlan header.
+fn vlan_ipv4_udp() -> Vec<u8> {
+    let mut packet = SingleVlanHeader{
+        priority_code_point: 0,
+        drop_eligible_indicator: false,
+        vlan_identifier: 1,
+        ether_type: ether_type::IPV4,
+        tpid: ether_type::VLAN_TAGGED_FRAME,
+    }.to_bytes().unwrap().to_vec();
+    packet.extend_from_slice(&ipv4_udp());
+    packet
+}
+
+mod linux_sll_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice_to_bytes(
+            packet_type in any::<u16>(),
+            arphrd_type in any::<u16>(),
+            sender_address_len in any::<u16>(),
+            sender_address in any::<[u8;8]>(),
+            protocol_type in any::<u16>(),
+            rest in proptest::collection::vec(any::<u8>(), 0..10),
+        ) {
+            let header = LinuxSllHeader{
+                packet_type,
+                arphrd_type,
+                sender_address_len,
+                sender_address,
+                protocol_type,
+            };
+            let mut bytes = header.to_bytes().to_vec();
+            bytes.extend_from_slice(&rest);
+
+            // header
+            {
+                let (decoded, decoded_rest) = LinuxSllHeader::from_slice(&bytes).unwrap();
+                assert_eq!(header, decoded);
+                assert_eq!(&rest[..], decoded_rest);
+                assert_eq!(16, decoded.header_len());
+            }
+
+            // slice
+            {
+                let slice = LinuxSllHeaderSlice::from_slice(&bytes).unwrap();
+                assert_eq!(&bytes[..16], slice.slice());
+                assert_eq!(packet_type, slice.packet_type());
+                assert_eq!(arphrd_type, slice.arphrd_type());
+                assert_eq!(sender_address_len, slice.sender_address_len());
+                assert_eq!(&sender_address[..], slice.sender_address());
+                assert_eq!(protocol_type, slice.protocol_type());
+                assert_eq!(header, slice.to_header());
+                assert_eq!(slice.clone(), slice);
+            }
+
+            // read & write
+            {
+                let mut buffer = Vec::new();
+                header.write(&mut buffer).unwrap();
+                assert_eq!(&bytes[..16], &buffer[..]);
+                assert_eq!(header, LinuxSllHeader::read(&mut Cursor::new(&buffer)).unwrap());
+            }
+
+            // unexpected end of slice
+            for cut in 0..16 {
+                assert_matches!(
+                    LinuxSllHeader::from_slice(&bytes[..cut]),
+                    Err(ReadError::UnexpectedEndOfSlice(16))
+                );
+                assert!(LinuxSllHeader::read(&mut Cursor::new(&bytes[..cut])).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn serialized_size() {
+        assert_eq!(16, LinuxSllHeader::SERIALIZED_SIZE);
+    }
+}
+
+mod linux_sll2_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice_to_bytes(
+            protocol_type in any::<u16>(),
+            interface_index in any::<u32>(),
+            arphrd_type in any::<u16>(),
+            packet_type in any::<u8>(),
+            sender_address_len in any::<u8>(),
+            sender_address in any::<[u8;8]>(),
+            rest in proptest::collection::vec(any::<u8>(), 0..10),
+        ) {
+            let header = LinuxSll2Header{
+                protocol_type,
+                interface_index,
+                arphrd_type,
+                packet_type,
+                sender_address_len,
+                sender_address,
+            };
+            let mut bytes = header.to_bytes().to_vec();
+            assert_eq!(&[0,0], &bytes[2..4]);
+            bytes.extend_from_slice(&rest);
+
+            // header
+            {
+                let (decoded, decoded_rest) = LinuxSll2Header::from_slice(&bytes).unwrap();
+                assert_eq!(header, decoded);
+                assert_eq!(&rest[..], decoded_rest);
+                assert_eq!(20, decoded.header_len());
+            }
+
+            // slice
+            {
+                let slice = LinuxSll2HeaderSlice::from_slice(&bytes).unwrap();
+                assert_eq!(&bytes[..20], slice.slice());
+                assert_eq!(protocol_type, slice.protocol_type());
+                assert_eq!(interface_index, slice.interface_index());
+                assert_eq!(arphrd_type, slice.arphrd_type());
+                assert_eq!(packet_type, slice.packet_type());
+                assert_eq!(sender_address_len, slice.sender_address_len());
+                assert_eq!(&sender_address[..], slice.sender_address());
+                assert_eq!(header, slice.to_header());
+                assert_eq!(slice.clone(), slice);
+            }
+
+            // read & write
+            {
+                let mut buffer = Vec::new();
+                header.write(&mut buffer).unwrap();
+                assert_eq!(&bytes[..20], &buffer[..]);
+                assert_eq!(header, LinuxSll2Header::read(&mut Cursor::new(&buffer)).unwrap());
+            }
+
+            // unexpected end of slice
+            for cut in 0..20 {
+                assert_matches!(
+                    LinuxSll2Header::from_slice(&bytes[..cut]),
+                    Err(ReadError::UnexpectedEndOfSlice(20))
+                );
+                assert!(LinuxSll2Header::read(&mut Cursor::new(&bytes[..cut])).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn reserved_ignored() {
+        let mut bytes = LinuxSll2Header::default().to_bytes();
+        bytes[2] = 0xff;
+        bytes[3] = 0xff;
+        assert_eq!(LinuxSll2Header::default(), LinuxSll2Header::from_bytes(bytes));
+    }
+}
+
+mod sliced_packet {
+    use super::*;
+
+    fn sll(arphrd_type: u16, protocol_type: u16) -> LinuxSllHeader {
+        LinuxSllHeader{
+            packet_type: u16::from(linux_sll_packet_type::OUTGOING),
+            arphrd_type,
+            sender_address_len: 6,
+            sender_address: [1,2,3,4,5,6,0,0],
+            protocol_type,
+        }
+    }
+
+    fn sll2(arphrd_type: u16, protocol_type: u16) -> LinuxSll2Header {
+        LinuxSll2Header{
+            protocol_type,
+            interface_index: 3,
+            arphrd_type,
+            packet_type: linux_sll_packet_type::HOST,
+            sender_address_len: 6,
+            sender_address: [1,2,3,4,5,6,0,0],
+        }
+    }
+
+    fn concat(prefix: &[u8], packet: &[u8]) -> Vec<u8> {
+        let mut result = prefix.to_vec();
+        result.extend_from_slice(packet);
+        result
+    }
+
+    /// Asserts that the slices of the sliced packet add up to the packet.
+    fn assert_round_trip(sliced: &SlicedPacket, packet: &[u8]) {
+        let mut bytes = sliced.header_bytes().concat();
+        bytes.extend_from_slice(sliced.payload);
+        bytes.extend_from_slice(sliced.padding);
+        assert_eq!(packet, &bytes[..]);
+    }
+
+    #[test]
+    fn from_linux_sll() {
+        // ipv4
+        {
+            let header = sll(arphrd_type::ETHER, ether_type::IPV4);
+            let packet = concat(&header.to_bytes(), &ipv4_udp());
+            let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+            let link = assert_matches!(&sliced.link, Some(LinkSlice::LinuxSll(link)) => link);
+            assert_eq!(header, link.to_header());
+            assert_eq!(Some(ether_type::IPV4), sliced.link.as_ref().unwrap().ether_type());
+            assert_eq!(None, sliced.link.as_ref().unwrap().to_header());
+            assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+            assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+            assert_eq!(&[1,2,3,4], sliced.payload);
+            assert_round_trip(&sliced, &packet);
+
+            // error offsets include the header
+            assert_matches!(
+                SlicedPacket::from_linux_sll(&packet[..LinuxSllHeader::SERIALIZED_SIZE + 10]),
+                Err(ReadError::UnexpectedEndOfSlice(36))
+            );
+        }
+        // arp
+        {
+            let arp = ArpPacket::new_ethernet_ipv4(
+                arp_operation::REQUEST,
+                [1;6], [2;4],
+                [0;6], [4;4]
+            );
+            let packet = concat(&sll(arphrd_type::ETHER, ether_type::ARP).to_bytes(), &arp.to_bytes().unwrap());
+            let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+            assert!(sliced.arp.is_some());
+        }
+        // netlink
+        {
+            let packet = concat(&sll(arphrd_type::NETLINK, ether_type::IPV4).to_bytes(), &ipv4_udp());
+            let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+            assert_matches!(sliced.link, Some(LinkSlice::LinuxSll(_)));
+            assert_eq!(None, sliced.link.as_ref().unwrap().ether_type());
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&ipv4_udp()[..], sliced.payload);
+            assert_round_trip(&sliced, &packet);
+        }
+        // limits
+        {
+            let packet = concat(&sll(arphrd_type::ETHER, ether_type::VLAN_TAGGED_FRAME).to_bytes(), &vlan_ipv4_udp());
+            let limits = ParseLimits{
+                max_vlan_depth: 0,
+                ..Default::default()
+            };
+            assert_matches!(
+                SlicedPacket::from_linux_sll_with_limits(&packet, limits),
+                Err(ReadError::ParseLimitExceeded(ParseLimit::VlanDepth))
+            );
+        }
+        // too short
+        assert_matches!(
+            SlicedPacket::from_linux_sll(&[0;15]),
+            Err(ReadError::UnexpectedEndOfSlice(16))
+        );
+    }
+
+    #[test]
+    fn from_linux_sll2() {
+        // ipv4
+        {
+            let header = sll2(arphrd_type::LOOPBACK, ether_type::IPV4);
+            let packet = concat(&header.to_bytes(), &ipv4_udp());
+            let sliced = SlicedPacket::from_linux_sll2(&packet).unwrap();
+            let link = assert_matches!(&sliced.link, Some(LinkSlice::LinuxSll2(link)) => link);
+            assert_eq!(header, link.to_header());
+            assert_eq!(Some(ether_type::IPV4), sliced.link.as_ref().unwrap().ether_type());
+            assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+            assert_eq!(&[1,2,3,4], sliced.payload);
+            assert_round_trip(&sliced, &packet);
+
+            // error offsets include the header
+            assert_matches!(
+                SlicedPacket::from_linux_sll2(&packet[..LinuxSll2Header::SERIALIZED_SIZE + 10]),
+                Err(ReadError::UnexpectedEndOfSlice(40))
+            );
+        }
+        // unknown protocol
+        {
+            let packet = concat(&sll2(arphrd_type::ETHER, 0x0001).to_bytes(), &[1,2,3]);
+            let sliced = SlicedPacket::from_linux_sll2(&packet).unwrap();
+            assert_eq!(None, sliced.link.as_ref().unwrap().ether_type());
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&[1,2,3], sliced.payload);
+            assert_round_trip(&sliced, &packet);
+        }
+        // netlink
+        {
+            let packet = concat(&sll2(arphrd_type::NETLINK, 0).to_bytes(), &[1,2,3]);
+            let sliced = SlicedPacket::from_linux_sll2(&packet).unwrap();
+            assert_eq!(&[1,2,3], sliced.payload);
+        }
+        // limits
+        {
+            let packet = concat(&sll2(arphrd_type::ETHER, ether_type::VLAN_TAGGED_FRAME).to_bytes(), &vlan_ipv4_udp());
+            let limits = ParseLimits{
+                max_vlan_depth: 0,
+                ..Default::default()
+            };
+            assert_matches!(
+                SlicedPacket::from_linux_sll2_with_limits(&packet, limits),
+                Err(ReadError::ParseLimitExceeded(ParseLimit::VlanDepth))
+            );
+        }
+        // too short
+        assert_matches!(
+            SlicedPacket::from_linux_sll2(&[0;19]),
+            Err(ReadError::UnexpectedEndOfSlice(20))
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use super::super::*;
+
+fn assert_round_trip(header: &LlcHeader, expected: &[u8]) {
+    let mut bytes = Vec::new();
+    header.write(&mut bytes).unwrap();
+    assert_eq!(expected, &bytes[..]);
+    assert_eq!(expected.len(), header.header_len());
+
+    bytes.extend_from_slice(&[1, 2]);
+
+    // header
+    let (decoded, rest) = LlcHeader::from_slice(&bytes).unwrap();
+    assert_eq!(header, &decoded);
+    assert_eq!(&[1, 2], rest);
+
+    // slice
+    let slice = LlcHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(expected, slice.slice());
+    assert_eq!(header.dsap, slice.dsap());
+    assert_eq!(header.ssap, slice.ssap());
+    assert_eq!(header.control, slice.control());
+    assert_eq!(header.snap, slice.snap());
+    assert_eq!(header.ether_type(), slice.ether_type());
+    assert_eq!(header, &slice.to_header());
+    assert_eq!(slice.clone(), slice);
+
+    // too short
+    for len in 0..expected.len() {
+        assert_matches!(
+            LlcHeader::from_slice(&expected[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
+
+#[test]
+fn unnumbered() {
+    // spanning tree (no snap header)
+    let header = LlcHeader{
+        dsap: llc_sap::STP,
+        ssap: llc_sap::STP,
+        control: 0x03,
+        snap: None,
+    };
+    assert_eq!(None, header.ether_type());
+    assert_round_trip(&header, &[0x42, 0x42, 0x03]);
+    assert_eq!(LlcHeader::MIN_LEN, header.header_len());
+}
+
+#[test]
+fn information() {
+    // I-format frames have a 2 byte control field
+    let header = LlcHeader{
+        dsap: llc_sap::NETBIOS,
+        ssap: llc_sap::NETBIOS,
+        control: 0x0102,
+        snap: None,
+    };
+    assert_round_trip(&header, &[0xf0, 0xf0, 0x02, 0x01]);
+
+    // snap saps without an unnumbered information frame -> no snap header
+    let header = LlcHeader{
+        dsap: llc_sap::SNAP,
+        ssap: llc_sap::SNAP,
+        control: 0x0100,
+        snap: None,
+    };
+    assert_round_trip(&header, &[0xaa, 0xaa, 0x00, 0x01]);
+}
+
+#[test]
+fn snap() {
+    let header = LlcHeader{
+        dsap: llc_sap::SNAP,
+        ssap: llc_sap::SNAP,
+        control: 0x03,
+        snap: Some(SnapHeader{
+            oui: snap_oui::RFC1042,
+            protocol_id: ether_type::IPV6,
+        }),
+    };
+    assert_eq!(Some(ether_type::IPV6), header.ether_type());
+    assert_eq!(LlcHeader::MAX_LEN, header.header_len());
+    assert_round_trip(&header, &[0xaa, 0xaa, 0x03, 0, 0, 0, 0x86, 0xdd]);
+
+    // bridge tunnel
+    let header = LlcHeader{
+        snap: Some(SnapHeader{
+            oui: snap_oui::BRIDGE_TUNNEL,
+            protocol_id: ether_type::IPV4,
+        }),
+        ..header
+    };
+    assert_eq!(Some(ether_type::IPV4), header.ether_type());
+    assert_round_trip(&header, &[0xaa, 0xaa, 0x03, 0, 0, 0xf8, 0x08, 0x00]);
+
+    // vendor specific oui (no ether type)
+    let header = LlcHeader{
+        snap: Some(SnapHeader{
+            oui: [0x00, 0x00, 0x0c],
+            protocol_id: 0x2000,
+        }),
+        ..header
+    };
+    assert_eq!(None, header.ether_type());
+    assert_round_trip(&header, &[0xaa, 0xaa, 0x03, 0, 0, 0x0c, 0x20, 0x00]);
+}
+
+mod ieee802_3 {
+    use super::*;
+
+    /// Ethernet 802.3 frame (length field) containing a LLC/SNAP header
+    /// followed by an ipv4 udp packet.
+    fn snap_ipv4_udp(vlan: bool) -> Vec<u8> {
+        let builder = PacketBuilder::
+            ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let mut ip = Vec::with_capacity(builder.size(4));
+        builder.write(&mut ip, &[1,2,3,4]).unwrap();
+
+        let length = (LlcHeader::MAX_LEN + ip.len()) as u16;
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: if vlan { ether_type::VLAN_TAGGED_FRAME } else { length },
+        }.to_bytes().to_vec();
+        if vlan {
+            packet.extend_from_slice(&SingleVlanHeader{
+                priority_code_point: 0,
+                drop_eligible_indicator: false,
+                vlan_identifier: 1,
+                ether_type: length,
+                tpid: ether_type::VLAN_TAGGED_FRAME,
+            }.to_bytes().unwrap());
+        }
+        packet.extend_from_slice(&[0xaa, 0xaa, 0x03, 0, 0, 0, 0x08, 0x00]);
+        packet.extend_from_slice(&ip);
+        packet
+    }
+
+    #[test]
+    fn sliced_snap() {
+        let packet = snap_ipv4_udp(false);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(&packet[14..22], sliced.llc.as_ref().unwrap().slice());
+        assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], sliced.payload);
+
+        // header bytes
+        let headers = sliced.header_bytes();
+        assert_eq!(&packet[14..22], headers[2]);
+        assert_eq!(&packet[..], &headers.concat().iter().chain(sliced.payload).copied().collect::<Vec<_>>()[..]);
+        assert_eq!(packet.len() - 4, sliced.headers_len());
+
+        // vlan followed by a length
+        let packet = snap_ipv4_udp(true);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(sliced.vlan.is_some());
+        assert_eq!(&packet[18..26], sliced.llc.as_ref().unwrap().slice());
+        assert_eq!(&[1,2,3,4], sliced.payload);
+    }
+
+    #[test]
+    fn decoded_snap() {
+        let packet = snap_ipv4_udp(false);
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(ether_type::IPV4), decoded.llc.as_ref().unwrap().ether_type());
+        assert_matches!(decoded.ip, Some(IpHeader::Version4(_, _)));
+        assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1,2,3,4], decoded.payload.slice());
+        assert_eq!(packet.len() - 4, decoded.header_len());
+
+        let packet = snap_ipv4_udp(true);
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert!(decoded.vlan.is_some());
+        assert!(decoded.llc.is_some());
+        assert_eq!(&[1,2,3,4], decoded.payload.slice());
+    }
+
+    #[test]
+    fn non_snap() {
+        // netbios (payload is left undecoded)
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: 7,
+        }.to_bytes().to_vec();
+        packet.extend_from_slice(&[0xf0, 0xf0, 0x03, 0, 0, 0, 0]);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(Some(llc_sap::NETBIOS), sliced.llc.as_ref().map(|llc| llc.dsap()));
+        assert_eq!(None, sliced.ip);
+        assert_eq!(&[0, 0, 0, 0], sliced.payload);
+
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(llc_sap::NETBIOS), decoded.llc.as_ref().map(|llc| llc.dsap));
+        assert_eq!(
+            Payload::Ether{ ether_type: 7, payload: &[0, 0, 0, 0] },
+            decoded.payload
+        );
+    }
+
+    #[test]
+    fn unexpected_end() {
+        let packet = snap_ipv4_udp(false);
+        assert_matches!(
+            SlicedPacket::from_ethernet(&packet[..20]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+        assert_matches!(
+            PacketHeaders::from_ethernet_slice(&packet[..20]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
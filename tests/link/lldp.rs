@@ -0,0 +1,248 @@
+use super::super::*;
+
+/// Serializes a TLV with the given type & value.
+fn tlv(tlv_type: u8, value: &[u8]) -> Vec<u8> {
+    let header = (u16::from(tlv_type) << 9) | (value.len() as u16);
+    let mut result = header.to_be_bytes().to_vec();
+    result.extend_from_slice(value);
+    result
+}
+
+/// Mandatory chassis id, port id & ttl TLVs.
+fn mandatory() -> Vec<u8> {
+    let mut result = tlv(lldp_tlv_type::CHASSIS_ID, &[lldp_chassis_id_subtype::MAC_ADDRESS, 1, 2, 3, 4, 5, 6]);
+    result.extend_from_slice(&tlv(lldp_tlv_type::PORT_ID, &[lldp_port_id_subtype::INTERFACE_NAME, b'e', b'0']));
+    result.extend_from_slice(&tlv(lldp_tlv_type::TTL, &[0x00, 0x78]));
+    result
+}
+
+fn management_address() -> Vec<u8> {
+    vec![
+        5, 1, 192, 168, 1, 1, // ipv4 address
+        2, 0, 0, 0, 3, // ifIndex 3
+        3, 0x2b, 0x06, 0x01, // oid
+    ]
+}
+
+#[test]
+fn tlv_from_slice() {
+    use crate::LldpTlv::*;
+
+    for &(tlv_type, ref value, ref expected) in &[
+        (lldp_tlv_type::END, vec![], End),
+        (lldp_tlv_type::CHASSIS_ID, vec![7, 1, 2], ChassisId(LldpChassisId{ subtype: 7, id: &[1, 2] })),
+        (lldp_tlv_type::PORT_ID, vec![3, 1, 2, 3, 4, 5, 6], PortId(LldpPortId{ subtype: 3, id: &[1, 2, 3, 4, 5, 6] })),
+        (lldp_tlv_type::TTL, vec![0x12, 0x34], Ttl(0x1234)),
+        (lldp_tlv_type::PORT_DESCRIPTION, vec![b'a'], PortDescription(&b"a"[..])),
+        (lldp_tlv_type::SYSTEM_NAME, vec![], SystemName(&[])),
+        (lldp_tlv_type::SYSTEM_DESCRIPTION, vec![b'b'], SystemDescription(&b"b"[..])),
+        (lldp_tlv_type::SYSTEM_CAPABILITIES, vec![0, 0x14, 0, 0x04], SystemCapabilities{ capabilities: 0x14, enabled: 0x04 }),
+        (lldp_tlv_type::ORGANIZATIONALLY_SPECIFIC, vec![0x00, 0x80, 0xc2, 1, 0, 1], OrganizationallySpecific{ oui: [0x00, 0x80, 0xc2], subtype: 1, info: &[0, 1] }),
+        (9, vec![1, 2], Unknown{ tlv_type: 9, value: &[1, 2] }),
+    ] {
+        let mut bytes = tlv(tlv_type, value);
+        bytes.extend_from_slice(&[1, 2]);
+        let (decoded, rest) = LldpTlv::from_slice(&bytes).unwrap();
+        assert_eq!(expected, &decoded);
+        assert_eq!(tlv_type, decoded.tlv_type());
+        assert_eq!(&[1, 2], rest);
+
+        // unexpected end
+        for len in 0..bytes.len() - 2 {
+            assert_matches!(
+                LldpTlv::from_slice(&bytes[..len]),
+                Err(LldpError::UnexpectedEnd(_))
+            );
+        }
+    }
+}
+
+#[test]
+fn management_address_tlv() {
+    let bytes = tlv(lldp_tlv_type::MANAGEMENT_ADDRESS, &management_address());
+    let address = match LldpTlv::from_slice(&bytes).unwrap().0 {
+        LldpTlv::ManagementAddress(value) => value,
+        value => panic!("unexpected tlv {:?}", value),
+    };
+    assert_eq!(1, address.address_subtype);
+    assert_eq!(Some([192, 168, 1, 1]), address.ipv4());
+    assert_eq!(None, address.ipv6());
+    assert_eq!(2, address.interface_numbering_subtype);
+    assert_eq!(3, address.interface_number);
+    assert_eq!(&[0x2b, 0x06, 0x01], address.oid);
+
+    // ipv6
+    let mut value = vec![17, 2];
+    value.extend_from_slice(&[1;16]);
+    value.extend_from_slice(&[1, 0, 0, 0, 0, 0]);
+    let bytes = tlv(lldp_tlv_type::MANAGEMENT_ADDRESS, &value);
+    let address = match LldpTlv::from_slice(&bytes).unwrap().0 {
+        LldpTlv::ManagementAddress(value) => value,
+        value => panic!("unexpected tlv {:?}", value),
+    };
+    assert_eq!(Some([1;16]), address.ipv6());
+    assert_eq!(None, address.ipv4());
+    assert!(address.oid.is_empty());
+
+    // bad lengths
+    let mut bad_oid_len = management_address();
+    bad_oid_len[11] = 4;
+    let mut bad_address_len = management_address();
+    bad_address_len[0] = 1;
+    for value in &[vec![], management_address()[..14].to_vec(), bad_oid_len, bad_address_len] {
+        assert_eq!(
+            Err(LldpError::InvalidLength{ tlv_type: lldp_tlv_type::MANAGEMENT_ADDRESS, length: value.len() as u16 }),
+            LldpTlv::from_slice(&tlv(lldp_tlv_type::MANAGEMENT_ADDRESS, value))
+        );
+    }
+}
+
+#[test]
+fn tlv_invalid_length() {
+    for &(tlv_type, len) in &[
+        (lldp_tlv_type::END, 1),
+        (lldp_tlv_type::CHASSIS_ID, 1),
+        (lldp_tlv_type::CHASSIS_ID, 257),
+        (lldp_tlv_type::PORT_ID, 0),
+        (lldp_tlv_type::TTL, 3),
+        (lldp_tlv_type::PORT_DESCRIPTION, 256),
+        (lldp_tlv_type::SYSTEM_NAME, 256),
+        (lldp_tlv_type::SYSTEM_DESCRIPTION, 256),
+        (lldp_tlv_type::SYSTEM_CAPABILITIES, 2),
+        (lldp_tlv_type::ORGANIZATIONALLY_SPECIFIC, 3),
+    ] {
+        let bytes = tlv(tlv_type, &vec![0;len]);
+        assert_eq!(
+            Err(LldpError::InvalidLength{ tlv_type, length: len as u16 }),
+            LldpTlv::from_slice(&bytes)
+        );
+    }
+}
+
+#[test]
+fn lldp_slice() {
+    let mut data = mandatory();
+    data.extend_from_slice(&tlv(lldp_tlv_type::SYSTEM_NAME, b"switch"));
+    data.extend_from_slice(&tlv(lldp_tlv_type::SYSTEM_DESCRIPTION, b"desc"));
+    data.extend_from_slice(&tlv(lldp_tlv_type::PORT_DESCRIPTION, b"uplink"));
+    data.extend_from_slice(&tlv(lldp_tlv_type::MANAGEMENT_ADDRESS, &management_address()));
+    data.extend_from_slice(&tlv(lldp_tlv_type::MANAGEMENT_ADDRESS, &management_address()));
+    data.extend_from_slice(&tlv(lldp_tlv_type::END, &[]));
+    let len = data.len();
+    // padding
+    data.extend_from_slice(&[0;4]);
+
+    let lldp = LldpSlice::from_slice(&data).unwrap();
+    assert_eq!(&data[..len], lldp.slice());
+    assert_eq!(Some([1, 2, 3, 4, 5, 6]), lldp.chassis_id().mac_address());
+    assert_eq!(lldp_port_id_subtype::INTERFACE_NAME, lldp.port_id().subtype);
+    assert_eq!(&b"e0"[..], lldp.port_id().id);
+    assert_eq!(None, lldp.port_id().mac_address());
+    assert_eq!(120, lldp.ttl());
+    assert_eq!(Some(&b"switch"[..]), lldp.system_name());
+    assert_eq!(Some(&b"desc"[..]), lldp.system_description());
+    assert_eq!(Some(&b"uplink"[..]), lldp.port_description());
+    assert_eq!(2, lldp.management_addresses().count());
+    assert_eq!(Some([192, 168, 1, 1]), lldp.management_addresses().next().unwrap().ipv4());
+
+    let mut tlvs = lldp.tlvs();
+    assert_eq!(lldp_tlv_type::CHASSIS_ID, tlvs.next().unwrap().tlv_type());
+    assert_eq!(&data[9..len], tlvs.rest());
+    assert_eq!(
+        vec![
+            lldp_tlv_type::PORT_ID,
+            lldp_tlv_type::TTL,
+            lldp_tlv_type::SYSTEM_NAME,
+            lldp_tlv_type::SYSTEM_DESCRIPTION,
+            lldp_tlv_type::PORT_DESCRIPTION,
+            lldp_tlv_type::MANAGEMENT_ADDRESS,
+            lldp_tlv_type::MANAGEMENT_ADDRESS,
+            lldp_tlv_type::END,
+        ],
+        tlvs.map(|tlv| tlv.tlv_type()).collect::<Vec<_>>()
+    );
+
+    // without optional & end tlvs
+    let data = mandatory();
+    let lldp = LldpSlice::from_slice(&data).unwrap();
+    assert_eq!(&data[..], lldp.slice());
+    assert_eq!(None, lldp.system_name());
+    assert_eq!(None, lldp.system_description());
+    assert_eq!(None, lldp.port_description());
+    assert_eq!(0, lldp.management_addresses().count());
+    assert_eq!(3, lldp.tlvs().count());
+}
+
+#[test]
+fn lldp_slice_errors() {
+    use crate::LldpError::*;
+
+    // unexpected end (offset of the tlv is added)
+    let data = mandatory();
+    assert_eq!(
+        Err(UnexpectedEnd(data.len())),
+        LldpSlice::from_slice(&data[..data.len() - 1])
+    );
+    assert_eq!(
+        Err(UnexpectedEnd(11)),
+        LldpSlice::from_slice(&data[..10])
+    );
+
+    // invalid length
+    let mut bad_ttl = data.clone();
+    bad_ttl.extend_from_slice(&tlv(lldp_tlv_type::TTL, &[0]));
+    assert_eq!(
+        Err(InvalidLength{ tlv_type: lldp_tlv_type::TTL, length: 1 }),
+        LldpSlice::from_slice(&bad_ttl)
+    );
+
+    // missing mandatory tlvs
+    assert_eq!(Err(MissingMandatoryTlv(lldp_tlv_type::CHASSIS_ID)), LldpSlice::from_slice(&[]));
+    assert_eq!(
+        Err(MissingMandatoryTlv(lldp_tlv_type::CHASSIS_ID)),
+        LldpSlice::from_slice(&data[9..])
+    );
+    assert_eq!(
+        Err(MissingMandatoryTlv(lldp_tlv_type::PORT_ID)),
+        LldpSlice::from_slice(&data[..9])
+    );
+    let mut end_after_port = data[..14].to_vec();
+    end_after_port.extend_from_slice(&tlv(lldp_tlv_type::END, &[]));
+    assert_eq!(
+        Err(MissingMandatoryTlv(lldp_tlv_type::TTL)),
+        LldpSlice::from_slice(&end_after_port)
+    );
+}
+
+#[test]
+fn error_display() {
+    use crate::LldpError::*;
+    assert_eq!(
+        "LldpError: Unexpected end of data. Expected at least 2 bytes.",
+        &format!("{}", UnexpectedEnd(2))
+    );
+    assert_eq!(
+        "LldpError: Length 3 is not valid for TLVs of type 3.",
+        &format!("{}", InvalidLength{ tlv_type: 3, length: 3 })
+    );
+    assert_eq!(
+        "LldpError: Mandatory TLV of type 1 is missing.",
+        &format!("{}", MissingMandatoryTlv(1))
+    );
+}
+
+#[test]
+fn from_ethernet() {
+    let mut data = mandatory();
+    data.extend_from_slice(&tlv(lldp_tlv_type::END, &[]));
+    let mut packet = Ethernet2Header{
+        source: [1;6],
+        destination: [0x01, 0x80, 0xc2, 0x00, 0x00, 0x0e],
+        ether_type: ether_type::LLDP,
+    }.to_bytes().to_vec();
+    packet.extend_from_slice(&data);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let lldp = LldpSlice::from_slice(sliced.payload).unwrap();
+    assert_eq!(120, lldp.ttl());
+}
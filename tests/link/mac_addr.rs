@@ -0,0 +1,100 @@
+use super::super::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn constants() {
+    assert_eq!(MacAddr([0xff;6]), MacAddr::BROADCAST);
+    assert_eq!(MacAddr([0;6]), MacAddr::UNSPECIFIED);
+    assert_eq!(MacAddr::UNSPECIFIED, MacAddr::default());
+    assert_eq!(MacAddr([1,2,3,4,5,6]), MacAddr::new(1,2,3,4,5,6));
+}
+
+#[test]
+fn predicates() {
+    // (address, broadcast, unspecified, multicast, locally administered)
+    for &(octets, broadcast, unspecified, multicast, local) in &[
+        ([0xff;6], true, false, true, true),
+        ([0;6], false, true, false, false),
+        ([0x01, 0x00, 0x5e, 0x00, 0x00, 0x01], false, false, true, false),
+        ([0x33, 0x33, 0x00, 0x00, 0x00, 0x01], false, false, true, true),
+        ([0x02, 0x42, 0xac, 0x11, 0x00, 0x02], false, false, false, true),
+        ([0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c], false, false, false, false),
+    ] {
+        let addr = MacAddr(octets);
+        assert_eq!(broadcast, addr.is_broadcast());
+        assert_eq!(unspecified, addr.is_unspecified());
+        assert_eq!(multicast, addr.is_multicast());
+        assert_eq!(!multicast, addr.is_unicast());
+        assert_eq!(local, addr.is_locally_administered());
+        assert_eq!(!local, addr.is_universal());
+    }
+    assert_eq!([0x00, 0x1b, 0x21], MacAddr([0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c]).oui());
+}
+
+#[test]
+fn conversions() {
+    let octets = [1, 2, 3, 4, 5, 6];
+    let addr = MacAddr::from(octets);
+    assert_eq!(octets, addr.octets());
+    assert_eq!(octets, <[u8;6]>::from(addr));
+    assert_eq!(&octets[..], addr.as_ref());
+
+    assert_eq!(Some(addr), MacAddr::from_slice(&[1, 2, 3, 4, 5, 6, 7]));
+    assert_eq!(None, MacAddr::from_slice(&octets[..5]));
+}
+
+#[test]
+fn display() {
+    assert_eq!("00:1b:21:3a:4b:5c", MacAddr([0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c]).to_string());
+    assert_eq!("ff:ff:ff:ff:ff:ff", MacAddr::BROADCAST.to_string());
+}
+
+#[test]
+fn from_str() {
+    let expected = MacAddr([0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c]);
+    assert_eq!(Ok(expected), "00:1b:21:3a:4b:5c".parse());
+    assert_eq!(Ok(expected), "00-1B-21-3A-4B-5C".parse());
+    assert_eq!(Ok(expected), "0:1b:21:3a:4b:5c".parse());
+
+    for text in &[
+        "",
+        "00:1b:21:3a:4b",
+        "00:1b:21:3a:4b:5c:6d",
+        "00:1b:21:3a:4b:",
+        "000:1b:21:3a:4b:5c",
+        "00:1b:21:3a:4b:xx",
+        "00:1b:21:3a:4b:+c",
+    ] {
+        assert_eq!(
+            Err(MacAddrParseError(text.to_string())),
+            text.parse::<MacAddr>()
+        );
+    }
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(
+        "MacAddrParseError: 'abc' is not a valid MAC address.",
+        MacAddrParseError("abc".to_string()).to_string()
+    );
+}
+
+#[test]
+fn debug_clone_eq_hash() {
+    let addr = MacAddr([1, 2, 3, 4, 5, 6]);
+    assert_eq!("MacAddr([1, 2, 3, 4, 5, 6])", format!("{:?}", addr));
+    assert_eq!(addr, addr.clone());
+    assert!(addr < MacAddr::BROADCAST);
+    let set: HashSet<MacAddr> = [addr, addr, MacAddr::BROADCAST].iter().cloned().collect();
+    assert_eq!(2, set.len());
+}
+
+proptest! {
+    #[test]
+    fn display_from_str(octets in any::<[u8;6]>()) {
+        let addr = MacAddr(octets);
+        assert_eq!(Ok(addr), addr.to_string().parse());
+    }
+}
@@ -0,0 +1,114 @@
+use super::super::*;
+
+#[test]
+fn pause() {
+    let frame = MacControlFrame::Pause{ quanta: 0x1234 };
+    assert_eq!(mac_control_opcode::PAUSE, frame.opcode());
+
+    let bytes = frame.to_bytes();
+    assert_eq!(&[0x00, 0x01, 0x12, 0x34], &bytes[..4]);
+    assert!(bytes[4..].iter().all(|&b| 0 == b));
+
+    let mut written = Vec::new();
+    frame.write(&mut written).unwrap();
+    assert_eq!(&bytes[..], &written[..]);
+
+    let (decoded, rest) = MacControlFrame::from_slice(&bytes).unwrap();
+    assert_eq!(Some(frame), decoded);
+    assert_eq!(&bytes[4..], rest);
+
+    let slice = MacControlSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..MacControlSlice::PAUSE_LEN], slice.slice());
+    assert_eq!(mac_control_opcode::PAUSE, slice.opcode());
+    assert_eq!(&[0x12, 0x34], slice.parameters());
+    assert_eq!(slice.clone(), slice);
+
+    // a pause frame applies to all priorities
+    for priority in 0..8 {
+        assert_eq!(Some(0x1234), frame.priority_quanta(priority));
+    }
+}
+
+#[test]
+fn priority_flow_control() {
+    let frame = MacControlFrame::PriorityFlowControl{
+        class_enable_vector: 0b1000_0010,
+        quanta: [1, 2, 3, 4, 5, 6, 7, 8],
+    };
+    assert_eq!(mac_control_opcode::PRIORITY_FLOW_CONTROL, frame.opcode());
+
+    let bytes = frame.to_bytes();
+    assert_eq!(
+        &[
+            0x01, 0x01, 0x00, 0b1000_0010,
+            0, 1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0, 7, 0, 8,
+        ],
+        &bytes[..MacControlSlice::PRIORITY_FLOW_CONTROL_LEN]
+    );
+
+    let (decoded, rest) = MacControlFrame::from_slice(&bytes).unwrap();
+    assert_eq!(Some(frame), decoded);
+    assert_eq!(MacControlFrame::LEN - MacControlSlice::PRIORITY_FLOW_CONTROL_LEN, rest.len());
+
+    assert_eq!(None, frame.priority_quanta(0));
+    assert_eq!(Some(2), frame.priority_quanta(1));
+    assert_eq!(Some(8), frame.priority_quanta(7));
+    assert_eq!(None, frame.priority_quanta(8));
+}
+
+#[test]
+fn other_opcodes() {
+    let bytes = [0x00, 0x02, 1, 2, 3];
+    let slice = MacControlSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..], slice.slice());
+    assert_eq!(mac_control_opcode::GATE, slice.opcode());
+    assert_eq!(&[1, 2, 3], slice.parameters());
+    assert_eq!(None, slice.to_frame());
+    assert_eq!((None, &[][..]), MacControlFrame::from_slice(&bytes).unwrap());
+}
+
+#[test]
+fn from_slice_errors() {
+    assert_matches!(
+        MacControlSlice::from_slice(&[0]),
+        Err(ReadError::UnexpectedEndOfSlice(MacControlSlice::MIN_LEN))
+    );
+    assert_matches!(
+        MacControlSlice::from_slice(&[0, 1, 0]),
+        Err(ReadError::UnexpectedEndOfSlice(MacControlSlice::PAUSE_LEN))
+    );
+    let bytes = MacControlFrame::PriorityFlowControl{
+        class_enable_vector: 1,
+        quanta: [0;8],
+    }.to_bytes();
+    assert_matches!(
+        MacControlFrame::from_slice(&bytes[..MacControlSlice::PRIORITY_FLOW_CONTROL_LEN - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(MacControlSlice::PRIORITY_FLOW_CONTROL_LEN))
+    );
+}
+
+#[test]
+fn quanta_to_nanos() {
+    // 10 Gbit/s: one quantum = 51.2 ns
+    assert_eq!(51, MacControlFrame::quanta_to_nanos(1, 10_000_000_000));
+    assert_eq!(3_355_392, MacControlFrame::quanta_to_nanos(0xffff, 10_000_000_000));
+    // 1 Gbit/s: one quantum = 512 ns
+    assert_eq!(512, MacControlFrame::quanta_to_nanos(1, 1_000_000_000));
+    // speed 0 is treated as 1 bit/s
+    assert_eq!(512_000_000_000, MacControlFrame::quanta_to_nanos(1, 0));
+}
+
+#[test]
+fn from_ethernet() {
+    let mut packet = Ethernet2Header{
+        source: [1, 2, 3, 4, 5, 6],
+        destination: MacControlFrame::DESTINATION.octets(),
+        ether_type: ether_type::ETHERNET_FLOW_CONTROL,
+    }.to_bytes().to_vec();
+    packet.extend_from_slice(&MacControlFrame::Pause{ quanta: 0xffff }.to_bytes());
+    assert_eq!(Ethernet2Header::MIN_FRAME_LEN, packet.len());
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let (frame, _) = MacControlFrame::from_slice(sliced.payload).unwrap();
+    assert_eq!(Some(MacControlFrame::Pause{ quanta: 0xffff }), frame);
+}
@@ -1,5 +1,19 @@
+pub mod arp;
+pub mod eapol;
 pub mod ethernet;
+pub mod ieee80211;
+pub mod ieee802154;
+pub mod lacp;
+pub mod link_type;
+pub mod linux_sll;
+pub mod llc;
+pub mod lldp;
+pub mod mac_addr;
+pub mod mac_control;
+pub mod null;
+pub mod stp;
 pub mod vlan_tagging;
+pub mod wake_on_lan;
 
 use super::*;
 
@@ -26,6 +40,17 @@ mod link_slice {
         }
     }
 
+    proptest! {
+        #[test]
+        fn slice(ref eth in ethernet_2_unknown()) {
+            let bytes = eth.to_bytes();
+            let slice = LinkSlice::Ethernet2(
+                Ethernet2HeaderSlice::from_slice(&bytes).unwrap()
+            );
+            assert_eq!(slice.slice(), &bytes[..]);
+        }
+    }
+
     proptest! {
         #[test]
         fn to_header(ref eth in ethernet_2_unknown()) {
@@ -37,8 +62,55 @@ mod link_slice {
             // clone & eq
             assert_eq!(
                 slice.to_header(),
-                *eth
+                Some(eth.clone())
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn ether_type(ref eth in ethernet_2_unknown()) {
+            let bytes = eth.to_bytes();
+            let slice = LinkSlice::Ethernet2(
+                Ethernet2HeaderSlice::from_slice(&bytes).unwrap()
             );
+            assert_eq!(Some(eth.ether_type), slice.ether_type());
         }
     }
+
+    #[test]
+    fn linux_sll() {
+        let header = LinuxSllHeader{
+            packet_type: 0,
+            arphrd_type: arphrd_type::ETHER,
+            sender_address_len: 6,
+            sender_address: [1,2,3,4,5,6,0,0],
+            protocol_type: ether_type::IPV6,
+        };
+        let bytes = header.to_bytes();
+        let slice = LinkSlice::LinuxSll(LinuxSllHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+        assert_eq!(None, slice.to_header());
+        assert_eq!(Some(ether_type::IPV6), slice.ether_type());
+        assert_eq!(slice.clone(), slice);
+        assert!(format!("{:?}", slice).starts_with("LinuxSll("));
+    }
+
+    #[test]
+    fn linux_sll2() {
+        let header = LinuxSll2Header{
+            protocol_type: 0,
+            interface_index: 1,
+            arphrd_type: arphrd_type::NETLINK,
+            packet_type: 0,
+            sender_address_len: 0,
+            sender_address: [0;8],
+        };
+        let bytes = header.to_bytes();
+        let slice = LinkSlice::LinuxSll2(LinuxSll2HeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+        assert_eq!(None, slice.to_header());
+        assert_eq!(None, slice.ether_type());
+        assert!(format!("{:?}", slice).starts_with("LinuxSll2("));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn from_slice() {
+    let slice = NullHeaderSlice::from_slice(&[2,0,0,0,1,2]).unwrap();
+    assert_eq!(&[2,0,0,0], slice.slice());
+    assert_eq!(slice.clone(), slice);
+
+    // too short
+    assert_matches!(
+        NullHeaderSlice::from_slice(&[2,0,0]),
+        Err(ReadError::UnexpectedEndOfSlice(NullHeaderSlice::LEN))
+    );
+}
+
+#[test]
+fn protocol_family() {
+    use null_protocol_family::*;
+    for (family, ether_type) in &[
+        (INET, Some(ether_type::IPV4)),
+        (INET6_LINUX, Some(ether_type::IPV6)),
+        (INET6_BSD, Some(ether_type::IPV6)),
+        (INET6_FREEBSD, Some(ether_type::IPV6)),
+        (INET6_DARWIN, Some(ether_type::IPV6)),
+        (7, None),
+    ] {
+        // both byte orders
+        for bytes in &[family.to_le_bytes(), family.to_be_bytes()] {
+            let slice = NullHeaderSlice::from_slice(bytes).unwrap();
+            assert_eq!(*family, slice.protocol_family());
+            assert_eq!(*ether_type, slice.ether_type());
+            assert_eq!(*ether_type, LinkSlice::Null(slice.clone()).ether_type());
+            assert_eq!(None, LinkSlice::Null(slice.clone()).to_header());
+            assert_eq!(&bytes[..], LinkSlice::Null(slice).slice());
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use super::super::*;
+
+/// Rapid spanning tree BPDU (version & type can be changed afterwards).
+fn rst_bpdu() -> Vec<u8> {
+    vec![
+        0x00, 0x00, // protocol id
+        stp_version::RSTP,
+        stp_bpdu_type::RST,
+        stp_flags::TOPOLOGY_CHANGE | stp_flags::LEARNING | 0b1100, // flags (designated port)
+        0x80, 0x01, 1, 2, 3, 4, 5, 6, // root id
+        0x00, 0x00, 0x4e, 0x20, // root path cost
+        0x90, 0x02, 7, 8, 9, 10, 11, 12, // bridge id
+        0x80, 0x03, // port id
+        0x01, 0x00, // message age
+        0x14, 0x00, // max age
+        0x02, 0x00, // hello time
+        0x0f, 0x00, // forward delay
+        0x00, // version 1 length
+    ]
+}
+
+#[test]
+fn rst() {
+    let mut bytes = rst_bpdu();
+    bytes.extend_from_slice(&[0, 0]);
+
+    let bpdu = StpBpduSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..StpBpduSlice::RST_LEN], bpdu.slice());
+    assert_eq!(0, bpdu.protocol_id());
+    assert_eq!(stp_version::RSTP, bpdu.version());
+    assert_eq!(stp_bpdu_type::RST, bpdu.bpdu_type());
+    assert!(!bpdu.is_topology_change_notification());
+    assert_eq!(Some(0x1d), bpdu.flags());
+    assert_eq!(Some(3), bpdu.port_role());
+    assert_eq!(Some(StpBridgeId{ priority: 0x8001, address: [1, 2, 3, 4, 5, 6] }), bpdu.root_id());
+    assert_eq!(Some(1), bpdu.root_id().map(|id| id.system_id_extension()));
+    assert_eq!(Some(20000), bpdu.root_path_cost());
+    assert_eq!(Some(StpBridgeId{ priority: 0x9002, address: [7, 8, 9, 10, 11, 12] }), bpdu.bridge_id());
+    assert_eq!(Some(0x8003), bpdu.port_id());
+    assert_eq!(Some(0x100), bpdu.message_age());
+    assert_eq!(Some(0x1400), bpdu.max_age());
+    assert_eq!(Some(0x200), bpdu.hello_time());
+    assert_eq!(Some(0xf00), bpdu.forward_delay());
+    assert_eq!(None, bpdu.mst_data());
+    assert_eq!(bpdu.clone(), bpdu);
+
+    for len in 0..StpBpduSlice::RST_LEN {
+        assert_matches!(
+            StpBpduSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
+
+#[test]
+fn config() {
+    let mut bytes = rst_bpdu();
+    bytes[2] = stp_version::STP;
+    bytes[3] = stp_bpdu_type::CONFIG;
+    let bpdu = StpBpduSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..StpBpduSlice::CONFIG_LEN], bpdu.slice());
+    assert_eq!(Some(0x1d), bpdu.flags());
+    assert_eq!(None, bpdu.port_role());
+    assert_eq!(Some(20000), bpdu.root_path_cost());
+    assert_eq!(Some(0xf00), bpdu.forward_delay());
+    assert_matches!(
+        StpBpduSlice::from_slice(&bytes[..StpBpduSlice::CONFIG_LEN - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(35))
+    );
+}
+
+#[test]
+fn topology_change_notification() {
+    let bytes = [0, 0, stp_version::STP, stp_bpdu_type::TOPOLOGY_CHANGE_NOTIFICATION, 0, 0];
+    let bpdu = StpBpduSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..4], bpdu.slice());
+    assert!(bpdu.is_topology_change_notification());
+    assert_eq!(None, bpdu.flags());
+    assert_eq!(None, bpdu.port_role());
+    assert_eq!(None, bpdu.root_id());
+    assert_eq!(None, bpdu.root_path_cost());
+    assert_eq!(None, bpdu.bridge_id());
+    assert_eq!(None, bpdu.port_id());
+    assert_eq!(None, bpdu.message_age());
+    assert_eq!(None, bpdu.max_age());
+    assert_eq!(None, bpdu.hello_time());
+    assert_eq!(None, bpdu.forward_delay());
+    assert_eq!(None, bpdu.mst_data());
+    assert_matches!(
+        StpBpduSlice::from_slice(&bytes[..3]),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+}
+
+#[test]
+fn mst() {
+    let mut bytes = rst_bpdu();
+    bytes[2] = stp_version::MSTP;
+    bytes.extend_from_slice(&[0x00, 0x03, 1, 2, 3, 0]);
+    let bpdu = StpBpduSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..StpBpduSlice::MST_MIN_LEN + 3], bpdu.slice());
+    assert_eq!(Some(&[1, 2, 3][..]), bpdu.mst_data());
+    assert_eq!(Some(3), bpdu.port_role());
+
+    assert_matches!(
+        StpBpduSlice::from_slice(&bytes[..StpBpduSlice::MST_MIN_LEN - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(38))
+    );
+    assert_matches!(
+        StpBpduSlice::from_slice(&bytes[..StpBpduSlice::MST_MIN_LEN + 2]),
+        Err(ReadError::UnexpectedEndOfSlice(41))
+    );
+}
+
+#[test]
+fn unknown_bpdu_type() {
+    let mut bytes = rst_bpdu();
+    bytes[3] = 0x01;
+    assert_matches!(
+        StpBpduSlice::from_slice(&bytes),
+        Err(ReadError::StpUnknownBpduType(1))
+    );
+}
+
+#[test]
+fn decoded_payload() {
+    let bpdu = rst_bpdu();
+    let mut packet = Ethernet2Header{
+        source: [1;6],
+        destination: [0x01, 0x80, 0xc2, 0, 0, 0],
+        ether_type: (LlcHeader::MIN_LEN + bpdu.len()) as u16,
+    }.to_bytes().to_vec();
+    packet.extend_from_slice(&[llc_sap::STP, llc_sap::STP, 0x03]);
+    packet.extend_from_slice(&bpdu);
+    // padding
+    packet.extend_from_slice(&[0;3]);
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert!(decoded.llc.as_ref().unwrap().is_stp());
+    match &decoded.payload {
+        Payload::Stp{ bpdu: value, payload } => {
+            assert_eq!(&bpdu[..], value.slice());
+            assert_eq!(Some(20000), value.root_path_cost());
+            assert_eq!(&packet[17..], *payload);
+        },
+        value => panic!("unexpected payload {:?}", value),
+    }
+    assert_eq!(&packet[17..], decoded.payload.slice());
+    assert_eq!(packet.len(), decoded.total_len(decoded.payload.len()));
+
+    // the sliced packet contains the bpdu as payload
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_eq!(&packet[17..], sliced.payload);
+
+    // malformed bpdu
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(&packet[..17 + 10]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+}
@@ -121,13 +121,118 @@ mod vlan_header {
                 };
                 assert_eq!(expected, actual);
             }
+
+            // multi
+            {
+                let value = VlanHeader::Multi(vec![double.outer.clone(), double.inner.clone(), single.clone()]);
+                let mut expected = double.to_bytes().unwrap().to_vec();
+                expected.extend_from_slice(&single.to_bytes().unwrap());
+                let mut actual = Vec::with_capacity(value.header_len());
+                value.write(&mut actual).unwrap();
+                assert_eq!(expected, actual);
+                assert_eq!(12, value.header_len());
+            }
         }
     }
+
+    proptest!{
+        #[test]
+        fn depth_get_iter(
+            single in vlan_single_any(),
+            double in vlan_double_any(),
+        ) {
+            // single
+            {
+                let value = VlanHeader::Single(single.clone());
+                assert_eq!(1, value.depth());
+                assert_eq!(Some(&single), value.get(0));
+                assert_eq!(None, value.get(1));
+                assert_eq!(vec![&single], value.iter().collect::<Vec<_>>());
+                assert_eq!(single.known_ether_type(), value.known_ether_type());
+            }
+            // double
+            {
+                let value = VlanHeader::Double(double.clone());
+                assert_eq!(2, value.depth());
+                assert_eq!(Some(&double.outer), value.get(0));
+                assert_eq!(Some(&double.inner), value.get(1));
+                assert_eq!(None, value.get(2));
+                assert_eq!(vec![&double.outer, &double.inner], value.iter().collect::<Vec<_>>());
+                assert_eq!(double.inner.known_ether_type(), value.known_ether_type());
+            }
+            // multi
+            {
+                let headers = vec![double.outer.clone(), double.inner.clone(), single.clone()];
+                let value = VlanHeader::Multi(headers.clone());
+                assert_eq!(3, value.depth());
+                assert_eq!(Some(&single), value.get(2));
+                assert_eq!(None, value.get(3));
+                assert_eq!(headers.iter().collect::<Vec<_>>(), value.iter().collect::<Vec<_>>());
+                assert_eq!(single.known_ether_type(), value.known_ether_type());
+                assert_eq!(
+                    &format!("Multi({:?})", headers),
+                    &format!("{:?}", value)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn write_multi_error() {
+        let value = VlanHeader::Multi(vec![
+            Default::default(),
+            SingleVlanHeader{ vlan_identifier: 0x1000, ..Default::default() },
+        ]);
+        assert_matches!(
+            value.write(&mut Vec::new()),
+            Err(WriteError::ValueError(_))
+        );
+    }
 }
 
 mod vlan_slice {
     use super::*;
 
+    proptest!{
+        #[test]
+        fn depth_outer_inner_iter(
+            single in vlan_single_any(),
+            double in vlan_double_any(),
+        ) {
+            // single
+            {
+                let raw = single.to_bytes().unwrap();
+                let slice = VlanSlice::SingleVlan(
+                    SingleVlanHeaderSlice::from_slice(&raw).unwrap()
+                );
+                assert_eq!(1, slice.depth());
+                assert_eq!(single, slice.outer().to_header());
+                assert_eq!(single, slice.inner().to_header());
+                assert_eq!(vec![single.clone()], slice.iter().map(|v| v.to_header()).collect::<Vec<_>>());
+            }
+
+            // double
+            {
+                let raw = double.to_bytes().unwrap();
+                let slice = VlanSlice::DoubleVlan(
+                    DoubleVlanHeaderSlice::from_slice(&raw).unwrap()
+                );
+                assert_eq!(2, slice.depth());
+                // the single slices do not know the tpid of the inner header
+                let inner = SingleVlanHeader{
+                    tpid: ether_type::VLAN_TAGGED_FRAME,
+                    ..double.inner.clone()
+                };
+                assert_eq!(double.outer, slice.outer().to_header());
+                assert_eq!(inner, slice.inner().to_header());
+                assert_eq!(
+                    vec![double.outer.clone(), inner],
+                    slice.iter().map(|v| v.to_header()).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
     proptest!{
         #[test]
         fn to_header(
@@ -160,6 +265,32 @@ mod vlan_slice {
         }
     }
 
+    proptest!{
+        #[test]
+        fn slice(
+            single in vlan_single_any(),
+            double in vlan_double_any(),
+        ) {
+            // single
+            {
+                let raw = single.to_bytes().unwrap();
+                let slice = VlanSlice::SingleVlan(
+                    SingleVlanHeaderSlice::from_slice(&raw).unwrap()
+                );
+                assert_eq!(slice.slice(), &raw[..]);
+            }
+
+            // double
+            {
+                let raw = double.to_bytes().unwrap();
+                let slice = VlanSlice::DoubleVlan(
+                    DoubleVlanHeaderSlice::from_slice(&raw).unwrap()
+                );
+                assert_eq!(slice.slice(), &raw[..]);
+            }
+        }
+    }
+
     proptest!{
         #[test]
         fn debug(
@@ -412,6 +543,19 @@ mod single_vlan_header {
         assert_eq!(false, actual.drop_eligible_indicator);
         assert_eq!(0, actual.vlan_identifier);
         assert_eq!(0, actual.ether_type);
+        assert_eq!(None, actual.known_ether_type());
+        assert_eq!(Some(EtherType::VlanTaggedFrame), actual.known_tpid());
+    }
+
+    #[test]
+    fn known_ether_type() {
+        let header = SingleVlanHeader{
+            ether_type: ether_type::IPV6,
+            tpid: 0x9200,
+            ..Default::default()
+        };
+        assert_eq!(Some(EtherType::Ipv6), header.known_ether_type());
+        assert_eq!(None, header.known_tpid());
     }
 
     proptest!{
@@ -426,11 +570,12 @@ mod single_vlan_header {
         fn dbg(input in vlan_single_any()) {
             assert_eq!(
                 &format!(
-                    "SingleVlanHeader {{ priority_code_point: {}, drop_eligible_indicator: {}, vlan_identifier: {}, ether_type: {} }}",
+                    "SingleVlanHeader {{ priority_code_point: {}, drop_eligible_indicator: {}, vlan_identifier: {}, ether_type: {}, tpid: {} }}",
                     input.priority_code_point,
                     input.drop_eligible_indicator,
                     input.vlan_identifier,
                     input.ether_type,
+                    input.tpid,
                 ),
                 &format!("{:?}", input)
             );
@@ -650,6 +795,7 @@ mod double_vlan_header {
             {
                 let mut outer : SingleVlanHeader = Default::default();
                 outer.ether_type = ether_type::VLAN_TAGGED_FRAME;
+                outer.tpid = ether_type::PROVIDER_BRIDGING;
                 outer
             }
         );
@@ -721,6 +867,7 @@ mod single_vlan_header_slice {
             assert_eq!(input.drop_eligible_indicator, slice.drop_eligible_indicator());
             assert_eq!(input.vlan_identifier, slice.vlan_identifier());
             assert_eq!(input.ether_type, slice.ether_type());
+            assert_eq!(EtherType::from_u16(input.ether_type), slice.known_ether_type());
         }
     }
 
@@ -815,7 +962,11 @@ mod double_vlan_header_slice {
             let slice = DoubleVlanHeaderSlice::from_slice(&bytes).unwrap();
 
             assert_eq!(input.outer, slice.outer().to_header());
-            assert_eq!(input.inner, slice.inner().to_header());
+            // the single slice does not know the tpid of the inner header
+            assert_eq!(
+                SingleVlanHeader{ tpid: ether_type::VLAN_TAGGED_FRAME, ..input.inner.clone() },
+                slice.inner().to_header()
+            );
         }
     }
 
@@ -859,3 +1010,232 @@ mod double_vlan_header_slice {
         }
     }
 }
+
+mod multi_vlan_header_slice {
+    use super::*;
+
+    /// Vlan tags with the given ids (the last one containing the ether type IPv4).
+    fn tags(ids: &[u16]) -> Vec<u8> {
+        let mut result = Vec::new();
+        for (index, id) in ids.iter().enumerate() {
+            result.extend_from_slice(&SingleVlanHeader{
+                priority_code_point: 0,
+                drop_eligible_indicator: false,
+                vlan_identifier: *id,
+                ether_type: if index + 1 == ids.len() {
+                    ether_type::IPV4
+                } else {
+                    ether_type::VLAN_TAGGED_FRAME
+                },
+                tpid: ether_type::VLAN_TAGGED_FRAME,
+            }.to_bytes().unwrap());
+        }
+        result
+    }
+
+    #[test]
+    fn from_slice() {
+        let mut raw = tags(&[1, 2, 3]);
+        raw.extend_from_slice(&[1, 2, 3, 4]);
+
+        let slice = MultiVlanHeaderSlice::from_slice(&raw).unwrap();
+        assert_eq!(&raw[..12], slice.slice());
+        assert_eq!(3, slice.depth());
+        assert_eq!(vec![1, 2, 3], slice.iter().map(|v| v.vlan_identifier()).collect::<Vec<_>>());
+        assert_eq!(
+            slice.iter().map(|v| v.to_header()).collect::<Vec<_>>(),
+            slice.to_header()
+        );
+        assert_eq!(slice.clone(), slice);
+
+        let vlan = VlanSlice::MultiVlan(slice);
+        assert_eq!(3, vlan.depth());
+        assert_eq!(1, vlan.outer().vlan_identifier());
+        assert_eq!(3, vlan.inner().vlan_identifier());
+        assert_eq!(ether_type::IPV4, vlan.inner().ether_type());
+        assert_eq!(&raw[..12], vlan.slice());
+        assert_matches!(vlan.to_header(), VlanHeader::Multi(ref v) if 3 == v.len());
+
+        // a single tag is also accepted
+        let slice = MultiVlanHeaderSlice::from_slice(&raw[8..]).unwrap();
+        assert_eq!(1, slice.depth());
+
+        // unexpected end
+        for len in 0..12 {
+            assert_matches!(
+                MultiVlanHeaderSlice::from_slice(&raw[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+        }
+        assert_matches!(
+            MultiVlanHeaderSlice::from_slice(&raw[..10]),
+            Err(ReadError::UnexpectedEndOfSlice(12))
+        );
+    }
+
+    #[test]
+    fn packet_slicing_and_decoding() {
+        let ids = [1, 2, 3, 4, 5];
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: ether_type::PROVIDER_BRIDGING,
+        }.to_bytes().to_vec();
+        packet.extend_from_slice(&tags(&ids));
+        packet.extend_from_slice(&{
+            let builder = PacketBuilder::
+                ipv4([192,168,1,1], [192,168,1,2], 20)
+                .udp(21, 1234);
+            let mut result = Vec::with_capacity(builder.size(4));
+            builder.write(&mut result, &[1,2,3,4]).unwrap();
+            result
+        });
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        match &sliced.vlan {
+            Some(VlanSlice::MultiVlan(vlan)) => {
+                assert_eq!(&ids[..], &vlan.iter().map(|v| v.vlan_identifier()).collect::<Vec<_>>()[..]);
+            },
+            value => panic!("unexpected vlan {:?}", value),
+        }
+        assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_eq!(&[1,2,3,4], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(
+            Some({
+                // the tpid of the outermost header is part of the ethernet header
+                let mut expected = sliced.vlan.as_ref().unwrap().to_header();
+                expected.get_mut(0).unwrap().tpid = ether_type::PROVIDER_BRIDGING;
+                expected
+            }),
+            headers.vlan
+        );
+        assert_eq!(5, headers.vlan.as_ref().unwrap().depth());
+        assert_eq!(Payload::Udp(&[1,2,3,4]), headers.payload);
+
+        // build the same packet
+        let builder = PacketBuilder::
+            ethernet2([1;6], [2;6])
+            .vlan(headers.vlan.clone().unwrap())
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        assert_eq!(packet.len(), builder.size(4));
+        let mut built = Vec::with_capacity(builder.size(4));
+        builder.write(&mut built, &[1,2,3,4]).unwrap();
+        assert_eq!(packet, built);
+
+        // truncated third vlan header
+        assert_matches!(
+            SlicedPacket::from_ethernet(&packet[..14 + 11]),
+            Err(ReadError::UnexpectedEndOfSlice(26))
+        );
+        assert_matches!(
+            PacketHeaders::from_ethernet_slice(&packet[..14 + 11]),
+            Err(ReadError::UnexpectedEndOfSlice(4))
+        );
+    }
+
+    #[test]
+    fn legacy_tpids() {
+        // service tag with the legacy tpid 0x9200 followed by a customer tag
+        let builder = PacketBuilder::
+            ethernet2([1;6], [2;6])
+            .vlan(VlanHeader::Double(DoubleVlanHeader{
+                outer: SingleVlanHeader{
+                    vlan_identifier: 1,
+                    tpid: 0x9200,
+                    ..Default::default()
+                },
+                inner: SingleVlanHeader{
+                    vlan_identifier: 2,
+                    tpid: ether_type::VLAN_TAGGED_FRAME,
+                    ..Default::default()
+                },
+            }))
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+
+        // the tpids are written as ether types of the preceding headers
+        assert_eq!(&[0x92, 0x00], &packet[12..14]);
+        assert_eq!(&[0x81, 0x00], &packet[16..18]);
+
+        // not decoded as vlan with the default tpids
+        {
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(None, sliced.vlan);
+            assert_eq!(None, sliced.ip);
+            let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            assert_eq!(None, headers.vlan);
+            assert_eq!(None, headers.ip);
+        }
+
+        // decoded with the legacy tpids
+        let limits = ParseLimits{
+            vlan_tpids: &VlanHeader::LEGACY_VLAN_ETHER_TYPES,
+            ..Default::default()
+        };
+        {
+            let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits).unwrap();
+            match &sliced.vlan {
+                Some(VlanSlice::DoubleVlan(vlan)) => {
+                    assert_eq!(1, vlan.outer().vlan_identifier());
+                    assert_eq!(2, vlan.inner().vlan_identifier());
+                },
+                value => panic!("unexpected vlan {:?}", value),
+            }
+            assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+            assert_eq!(&[1,2,3,4], sliced.payload);
+        }
+        {
+            let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits).unwrap();
+            match &headers.vlan {
+                Some(VlanHeader::Double(vlan)) => {
+                    assert_eq!(0x9200, vlan.outer.tpid);
+                    assert_eq!(ether_type::VLAN_TAGGED_FRAME, vlan.inner.tpid);
+                },
+                value => panic!("unexpected vlan {:?}", value),
+            }
+            assert_eq!(Payload::Udp(&[1,2,3,4]), headers.payload);
+
+            // rebuilding the packet results in the same tpids
+            let builder = PacketBuilder::
+                ethernet2([1;6], [2;6])
+                .vlan(headers.vlan.clone().unwrap())
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .udp(21, 1234);
+            let mut built = Vec::with_capacity(builder.size(4));
+            builder.write(&mut built, &[1,2,3,4]).unwrap();
+            assert_eq!(packet, built);
+        }
+
+        // legacy tpid between other tags
+        {
+            let ip_udp = &packet[22..];
+            let mut packet = Ethernet2Header{
+                source: [1;6],
+                destination: [2;6],
+                ether_type: ether_type::PROVIDER_BRIDGING,
+            }.to_bytes().to_vec();
+            packet.extend_from_slice(&SingleVlanHeader{
+                vlan_identifier: 1,
+                ether_type: 0x9300,
+                ..Default::default()
+            }.to_bytes().unwrap());
+            packet.extend_from_slice(&tags(&[2, 3]));
+            packet.extend_from_slice(ip_udp);
+
+            let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits).unwrap();
+            assert_eq!(3, sliced.vlan.as_ref().unwrap().depth());
+
+            let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits).unwrap();
+            assert_eq!(
+                vec![ether_type::PROVIDER_BRIDGING, 0x9300, ether_type::VLAN_TAGGED_FRAME],
+                headers.vlan.as_ref().unwrap().iter().map(|v| v.tpid).collect::<Vec<_>>()
+            );
+            assert_eq!(sliced.vlan.unwrap().to_header().get(2), headers.vlan.as_ref().unwrap().get(2));
+        }
+    }
+}
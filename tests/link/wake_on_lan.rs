@@ -0,0 +1,153 @@
+use super::super::*;
+
+const TARGET: MacAddr = MacAddr([0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c]);
+
+#[test]
+fn to_bytes() {
+    let packet = WakeOnLanPacket::new(TARGET);
+    let bytes = packet.to_bytes();
+    assert_eq!(WakeOnLanPacket::MIN_LEN, bytes.len());
+    assert_eq!(packet.header_len(), bytes.len());
+    assert_eq!(&[0xff;6], &bytes[..6]);
+    for repetition in bytes[6..].chunks(6) {
+        assert_eq!(TARGET.as_ref(), repetition);
+    }
+
+    let mut written = Vec::new();
+    packet.write(&mut written).unwrap();
+    assert_eq!(bytes, written);
+
+    for (password, expected) in &[
+        (WakeOnLanPassword::Four([1, 2, 3, 4]), &[1, 2, 3, 4][..]),
+        (WakeOnLanPassword::Six([1, 2, 3, 4, 5, 6]), &[1, 2, 3, 4, 5, 6][..]),
+    ] {
+        let packet = WakeOnLanPacket{ target: TARGET, password: Some(*password) };
+        let bytes = packet.to_bytes();
+        assert_eq!(packet.header_len(), bytes.len());
+        assert_eq!(*expected, &bytes[WakeOnLanPacket::MIN_LEN..]);
+        assert_eq!(*expected, password.as_slice());
+    }
+}
+
+#[test]
+fn from_slice() {
+    // without password
+    {
+        let bytes = WakeOnLanPacket::new(TARGET).to_bytes();
+        let slice = WakeOnLanSlice::from_slice(&bytes).unwrap();
+        assert_eq!(&bytes[..], slice.slice());
+        assert_eq!(TARGET, slice.target());
+        assert_eq!(None, slice.password());
+        assert_eq!(WakeOnLanPacket::new(TARGET), slice.to_packet());
+        assert_eq!(slice.clone(), slice);
+    }
+    // with passwords
+    for password in &[WakeOnLanPassword::Four([1, 2, 3, 4]), WakeOnLanPassword::Six([1, 2, 3, 4, 5, 6])] {
+        let packet = WakeOnLanPacket{ target: TARGET, password: Some(*password) };
+        let bytes = packet.to_bytes();
+        let slice = WakeOnLanSlice::from_slice(&bytes).unwrap();
+        assert_eq!(&bytes[..], slice.slice());
+        assert_eq!(Some(*password), slice.password());
+        assert_eq!(packet, slice.to_packet());
+    }
+    // trailing data that is not a password
+    {
+        let mut bytes = WakeOnLanPacket::new(TARGET).to_bytes();
+        bytes.extend_from_slice(&[0;5]);
+        let slice = WakeOnLanSlice::from_slice(&bytes).unwrap();
+        assert_eq!(WakeOnLanPacket::MIN_LEN, slice.slice().len());
+        assert_eq!(None, slice.password());
+    }
+}
+
+#[test]
+fn from_slice_not_magic() {
+    let bytes = WakeOnLanPacket::new(TARGET).to_bytes();
+    // too short
+    assert_eq!(None, WakeOnLanSlice::from_slice(&bytes[..bytes.len() - 1]));
+    // broken synchronization stream
+    {
+        let mut bytes = bytes.clone();
+        bytes[5] = 0xfe;
+        assert_eq!(None, WakeOnLanSlice::from_slice(&bytes));
+    }
+    // differing repetition
+    {
+        let mut bytes = bytes.clone();
+        bytes[WakeOnLanPacket::MIN_LEN - 1] = 0;
+        assert_eq!(None, WakeOnLanSlice::from_slice(&bytes));
+    }
+}
+
+#[test]
+fn find() {
+    let magic = WakeOnLanPacket::new(TARGET).to_bytes();
+    let mut data = vec![0xff;3];
+    data.extend_from_slice(&magic);
+    let slice = WakeOnLanSlice::find(&data).unwrap();
+    assert_eq!(&data[3..], slice.slice());
+    assert_eq!(TARGET, slice.target());
+
+    assert_eq!(None, WakeOnLanSlice::find(&data[..data.len() - 1]));
+    assert_eq!(None, WakeOnLanSlice::find(&[]));
+}
+
+#[test]
+fn from_sliced_packet() {
+    let magic = WakeOnLanPacket::new(TARGET).to_bytes();
+
+    // ethernet
+    {
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [0xff;6],
+            ether_type: ether_type::WAKE_ON_LAN,
+        }.to_bytes().to_vec();
+        packet.extend_from_slice(&magic);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(Some(TARGET), WakeOnLanSlice::from_sliced_packet(&sliced).map(|s| s.target()));
+
+        // other ether type
+        packet[13] = 0x43;
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, WakeOnLanSlice::from_sliced_packet(&sliced));
+    }
+    // vlan
+    {
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [0xff;6],
+            ether_type: ether_type::VLAN_TAGGED_FRAME,
+        }.to_bytes().to_vec();
+        packet.extend_from_slice(&SingleVlanHeader{
+            vlan_identifier: 10,
+            ether_type: ether_type::WAKE_ON_LAN,
+            ..Default::default()
+        }.to_bytes().unwrap());
+        packet.extend_from_slice(&magic);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(Some(TARGET), WakeOnLanSlice::from_sliced_packet(&sliced).map(|s| s.target()));
+    }
+    // udp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1;6], [0xff;6])
+            .ipv4([192, 168, 1, 2], [255, 255, 255, 255], 64)
+            .udp(40000, WakeOnLanPacket::UDP_PORT);
+        let mut packet = Vec::with_capacity(builder.size(magic.len()));
+        builder.write(&mut packet, &magic).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(Some(TARGET), WakeOnLanSlice::from_sliced_packet(&sliced).map(|s| s.target()));
+    }
+    // tcp payloads are not searched
+    {
+        let builder = PacketBuilder::
+            ethernet2([1;6], [0xff;6])
+            .ipv4([192, 168, 1, 2], [192, 168, 1, 3], 64)
+            .tcp(40000, 9, 0, 1024);
+        let mut packet = Vec::with_capacity(builder.size(magic.len()));
+        builder.write(&mut packet, &magic).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, WakeOnLanSlice::from_sliced_packet(&sliced));
+    }
+}
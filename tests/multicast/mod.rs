@@ -0,0 +1,209 @@
+use super::*;
+use etherparse::multicast::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+const HOST4: [u8;4] = [192,168,1,2];
+const HOST6: [u8;16] = [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,2];
+const GROUP6: [u8;16] = [0xff,0x3e,0,0,0,0,0,0,0,0,0,0,0,0,0x12,0x34];
+const SOURCE6: [u8;16] = [0x20,1,0xd,0xb8,0,0,0,0,0,0,0,0,0,0,0,1];
+
+fn v4(value: [u8;4]) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::from(value))
+}
+
+fn v6(value: [u8;16]) -> IpAddr {
+    IpAddr::V6(Ipv6Addr::from(value))
+}
+
+#[test]
+fn igmp_v1_v2() {
+    // v1 query
+    assert_eq!(
+        vec![MembershipEvent::Query{ querier: v4(HOST4), group: None, sources: Vec::new(), max_response_time: Duration::from_secs(10) }],
+        igmp_events(HOST4, &[igmp_type::MEMBERSHIP_QUERY, 0, 0, 0, 0, 0, 0, 0]).unwrap()
+    );
+    // v2 group specific query
+    assert_eq!(
+        vec![MembershipEvent::Query{ querier: v4(HOST4), group: Some(v4([239,1,2,3])), sources: Vec::new(), max_response_time: Duration::from_millis(1500) }],
+        igmp_events(HOST4, &[igmp_type::MEMBERSHIP_QUERY, 15, 0, 0, 239, 1, 2, 3]).unwrap()
+    );
+    // reports
+    for report_type in [igmp_type::V1_MEMBERSHIP_REPORT, igmp_type::V2_MEMBERSHIP_REPORT].iter() {
+        assert_eq!(
+            vec![MembershipEvent::Join{ host: v4(HOST4), group: v4([239,1,2,3]), filter: SourceFilter::Exclude(Vec::new()) }],
+            igmp_events(HOST4, &[*report_type, 0, 0, 0, 239, 1, 2, 3]).unwrap()
+        );
+    }
+    // leave
+    assert_eq!(
+        vec![MembershipEvent::Leave{ host: v4(HOST4), group: v4([239,1,2,3]), sources: Vec::new() }],
+        igmp_events(HOST4, &[igmp_type::LEAVE_GROUP, 0, 0, 0, 239, 1, 2, 3]).unwrap()
+    );
+    // unknown type
+    assert!(igmp_events(HOST4, &[0x30, 0, 0, 0, 0, 0, 0, 0]).unwrap().is_empty());
+    // too short
+    assert_matches!(igmp_events(HOST4, &[igmp_type::LEAVE_GROUP, 0, 0]), Err(ReadError::UnexpectedEndOfSlice(8)));
+}
+
+#[test]
+fn igmp_v3_query() {
+    let query = [
+        igmp_type::MEMBERSHIP_QUERY, 0x8a, 0, 0, 232, 1, 1, 1,
+        0x02, 125, 0, 2,
+        10, 0, 0, 1,
+        10, 0, 0, 2,
+    ];
+    assert_eq!(
+        vec![MembershipEvent::Query{
+            querier: v4(HOST4),
+            group: Some(v4([232,1,1,1])),
+            sources: vec![v4([10,0,0,1]), v4([10,0,0,2])],
+            // mantissa 0xa | 0x10 = 26, exponent 0 => 26 << 3 = 208 tenths of a second
+            max_response_time: Duration::from_millis(20800),
+        }],
+        igmp_events(HOST4, &query).unwrap()
+    );
+    assert_matches!(igmp_events(HOST4, &query[..16]), Err(ReadError::UnexpectedEndOfSlice(20)));
+}
+
+#[test]
+fn igmp_v3_report() {
+    let report = [
+        igmp_type::V3_MEMBERSHIP_REPORT, 0, 0, 0, 0, 0, 0, 5,
+        // ssm join with aux data
+        group_record_type::ALLOW_NEW_SOURCES, 1, 0, 1, 232, 1, 1, 1, 10, 0, 0, 1, 0xaa, 0xaa, 0xaa, 0xaa,
+        // asm join
+        group_record_type::CHANGE_TO_EXCLUDE_MODE, 0, 0, 0, 239, 1, 1, 1,
+        // leave
+        group_record_type::CHANGE_TO_INCLUDE_MODE, 0, 0, 0, 239, 1, 1, 2,
+        // block of a source
+        group_record_type::BLOCK_OLD_SOURCES, 0, 0, 1, 232, 1, 1, 2, 10, 0, 0, 2,
+        // unknown record type
+        42, 0, 0, 0, 239, 1, 1, 3,
+    ];
+    assert_eq!(
+        vec![
+            MembershipEvent::Join{ host: v4(HOST4), group: v4([232,1,1,1]), filter: SourceFilter::Include(vec![v4([10,0,0,1])]) },
+            MembershipEvent::Join{ host: v4(HOST4), group: v4([239,1,1,1]), filter: SourceFilter::Exclude(Vec::new()) },
+            MembershipEvent::Leave{ host: v4(HOST4), group: v4([239,1,1,2]), sources: Vec::new() },
+            MembershipEvent::Leave{ host: v4(HOST4), group: v4([232,1,1,2]), sources: vec![v4([10,0,0,2])] },
+        ],
+        igmp_events(HOST4, &report).unwrap()
+    );
+
+    // record exceeds the message
+    assert_matches!(igmp_events(HOST4, &report[..20]), Err(ReadError::UnexpectedEndOfSlice(24)));
+    assert_matches!(igmp_events(HOST4, &report[..26]), Err(ReadError::UnexpectedEndOfSlice(32)));
+}
+
+#[test]
+fn mld_v1() {
+    let mut message = vec![mld_type::MULTICAST_LISTENER_QUERY, 0, 0, 0, 0x03, 0xe8, 0, 0];
+    message.extend_from_slice(&[0;16]);
+    assert_eq!(
+        vec![MembershipEvent::Query{ querier: v6(HOST6), group: None, sources: Vec::new(), max_response_time: Duration::from_millis(1000) }],
+        mld_events(HOST6, &message).unwrap()
+    );
+
+    message[0] = mld_type::MULTICAST_LISTENER_REPORT;
+    message[8..24].copy_from_slice(&GROUP6);
+    assert_eq!(
+        vec![MembershipEvent::Join{ host: v6(HOST6), group: v6(GROUP6), filter: SourceFilter::Exclude(Vec::new()) }],
+        mld_events(HOST6, &message).unwrap()
+    );
+
+    message[0] = mld_type::MULTICAST_LISTENER_DONE;
+    assert_eq!(
+        vec![MembershipEvent::Leave{ host: v6(HOST6), group: v6(GROUP6), sources: Vec::new() }],
+        mld_events(HOST6, &message).unwrap()
+    );
+
+    assert_matches!(mld_events(HOST6, &message[..20]), Err(ReadError::UnexpectedEndOfSlice(24)));
+    assert_matches!(mld_events(HOST6, &[]), Err(ReadError::UnexpectedEndOfSlice(8)));
+    // other icmpv6 messages
+    assert!(mld_events(HOST6, &[128, 0, 0, 0, 0, 0, 0, 0]).unwrap().is_empty());
+}
+
+#[test]
+fn mld_v2_query() {
+    let mut message = vec![mld_type::MULTICAST_LISTENER_QUERY, 0, 0, 0, 0x80, 0x01, 0, 0];
+    message.extend_from_slice(&GROUP6);
+    message.extend_from_slice(&[0x02, 125, 0, 1]);
+    message.extend_from_slice(&SOURCE6);
+    assert_eq!(
+        vec![MembershipEvent::Query{
+            querier: v6(HOST6),
+            group: Some(v6(GROUP6)),
+            sources: vec![v6(SOURCE6)],
+            // mantissa 0x001 | 0x1000, exponent 0 => 0x1001 << 3
+            max_response_time: Duration::from_millis(0x1001 << 3),
+        }],
+        mld_events(HOST6, &message).unwrap()
+    );
+    assert_matches!(mld_events(HOST6, &message[..40]), Err(ReadError::UnexpectedEndOfSlice(44)));
+}
+
+#[test]
+fn mld_v2_report() {
+    let mut message = vec![mld_type::V2_MULTICAST_LISTENER_REPORT, 0, 0, 0, 0, 0, 0, 2];
+    message.extend_from_slice(&[group_record_type::MODE_IS_INCLUDE, 0, 0, 1]);
+    message.extend_from_slice(&GROUP6);
+    message.extend_from_slice(&SOURCE6);
+    message.extend_from_slice(&[group_record_type::MODE_IS_EXCLUDE, 0, 0, 0]);
+    message.extend_from_slice(&GROUP6);
+    assert_eq!(
+        vec![
+            MembershipEvent::Join{ host: v6(HOST6), group: v6(GROUP6), filter: SourceFilter::Include(vec![v6(SOURCE6)]) },
+            MembershipEvent::Join{ host: v6(HOST6), group: v6(GROUP6), filter: SourceFilter::Exclude(Vec::new()) },
+        ],
+        mld_events(HOST6, &message).unwrap()
+    );
+    assert_matches!(mld_events(HOST6, &message[..50]), Err(ReadError::UnexpectedEndOfSlice(64)));
+}
+
+#[test]
+fn membership_events_from_packets() {
+    // igmp
+    {
+        let report = [igmp_type::LEAVE_GROUP, 0, 0, 0, 239, 1, 2, 3];
+        let ip = Ipv4Header::new(report.len() as u16, 1, IpNumber::Igmp, HOST4, [224,0,0,2]);
+        let mut packet = Vec::new();
+        ip.write(&mut packet).unwrap();
+        packet.extend_from_slice(&report);
+        assert_eq!(
+            vec![MembershipEvent::Leave{ host: v4(HOST4), group: v4([239,1,2,3]), sources: Vec::new() }],
+            membership_events(&SlicedPacket::from_ip(&packet).unwrap()).unwrap()
+        );
+    }
+    // mld
+    {
+        let mut report = vec![mld_type::MULTICAST_LISTENER_REPORT, 0, 0, 0, 0, 0, 0, 0];
+        report.extend_from_slice(&GROUP6);
+        let ip = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: report.len() as u16,
+            next_header: ip_number::IPV6_ICMP,
+            hop_limit: 1,
+            source: HOST6,
+            destination: GROUP6,
+        };
+        let mut packet = Vec::new();
+        ip.write(&mut packet).unwrap();
+        packet.extend_from_slice(&report);
+        assert_eq!(
+            vec![MembershipEvent::Join{ host: v6(HOST6), group: v6(GROUP6), filter: SourceFilter::Exclude(Vec::new()) }],
+            membership_events(&SlicedPacket::from_ip(&packet).unwrap()).unwrap()
+        );
+    }
+    // udp
+    {
+        let builder = PacketBuilder::
+            ipv4(HOST4, [239,1,2,3], 1)
+            .udp(1234, 5678);
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[igmp_type::LEAVE_GROUP, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert!(membership_events(&SlicedPacket::from_ip(&packet).unwrap()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+
+use std::convert::TryFrom;
+
+fn assert_send<T: Send + 'static>(_: &T) {}
+
+#[test]
+fn from_sliced() {
+    let mut packets = Vec::new();
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(0x123)
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        packets.push(packet);
+    }
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([1;16], [2;16], 20)
+            .tcp(1, 2, 3, 4);
+        let mut packet = Vec::with_capacity(builder.size(2));
+        builder.write(&mut packet, &[5,6]).unwrap();
+        packets.push(packet);
+    }
+    {
+        // ipv6 with an extension header & unknown payload
+        let ip = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 8 + 3,
+            next_header: ip_number::IPV6_DEST_OPTIONS,
+            hop_limit: 1,
+            source: [1;16],
+            destination: [2;16],
+        };
+        let ext = Ipv6RawExtensionHeader::new_raw(IpNumber::Sctp as u8, &[0;6]).unwrap();
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: ether_type::IPV6,
+        }.to_bytes().to_vec();
+        ip.write(&mut packet).unwrap();
+        ext.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[7,8,9]);
+        packets.push(packet);
+    }
+    {
+        // ipv4 with a gre header & an unknown protocol type
+        let gre = GreHeader{
+            protocol_type: 0x1234,
+            checksum: None,
+            key: Some(5),
+            sequence_number: None,
+        };
+        let ip = Ipv4Header::new(
+            (gre.header_len() + 2) as u16, 20, IpNumber::Gre, [1;4], [2;4]
+        );
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: ether_type::IPV4,
+        }.to_bytes().to_vec();
+        ip.write(&mut packet).unwrap();
+        gre.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[7,8]);
+        packets.push(packet);
+    }
+
+    for packet in &packets {
+        let expected = PacketHeaders::from_ethernet_slice(packet).unwrap();
+        let sliced = SlicedPacket::from_ethernet(packet).unwrap();
+
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(expected.link, owned.link);
+        assert_eq!(expected.vlan, owned.vlan);
+        assert_eq!(expected.ip, owned.ip);
+        assert_eq!(expected.transport, owned.transport);
+        assert_eq!(expected.tunnel, owned.tunnel);
+        assert_eq!(expected.payload.slice(), &owned.payload[..]);
+
+        assert_eq!(owned, OwnedPacket::try_from(&sliced).unwrap());
+        assert_eq!(owned, OwnedPacket::try_from(sliced).unwrap());
+        assert_eq!(owned, OwnedPacket::from(&expected));
+        assert_eq!(owned, OwnedPacket::from(expected));
+        assert_eq!(owned, OwnedPacket::from_ethernet(packet).unwrap());
+        assert_send(&owned);
+    }
+
+    // ip
+    let ip_packet = &packets[1][Ethernet2Header::SERIALIZED_SIZE..];
+    let owned = OwnedPacket::from_ip(ip_packet).unwrap();
+    assert!(owned.link.is_none());
+    assert_eq!(&[5,6], &owned.payload[..]);
+}
+
+/// Length of all headers, the payload, the trailer & the padding of an owned packet
+/// (without the inner packet, which is part of the payload).
+fn owned_len(owned: &OwnedPacket) -> usize {
+    owned.link.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.vlan.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.llc.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.arp.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.ip.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.transport.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.tunnel.as_ref().map(|v| v.header_len()).unwrap_or(0) +
+        owned.payload.len() +
+        owned.trailer.len() +
+        owned.padding.len()
+}
+
+#[test]
+fn from_sliced_keeps_all_parts() {
+    let eth = |ether_type: u16| Ethernet2Header{
+        source: [1;6],
+        destination: [2;6],
+        ether_type,
+    }.to_bytes().to_vec();
+    let udp = {
+        let builder = PacketBuilder::
+            ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        packet
+    };
+
+    // llc
+    {
+        let llc = LlcHeader{
+            dsap: llc_sap::NETBIOS,
+            ssap: llc_sap::NETBIOS,
+            control: 0x03,
+            snap: None,
+        };
+        let mut packet = eth(5);
+        llc.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[1,2]);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(Some(llc), owned.llc);
+        assert_eq!(&[1,2], &owned.payload[..]);
+        assert_eq!(packet.len(), owned_len(&owned));
+        assert_eq!(owned, OwnedPacket::from(PacketHeaders::from_ethernet_slice(&packet).unwrap()));
+    }
+    // arp
+    {
+        let arp = ArpPacket::new_ethernet_ipv4(
+            arp_operation::REQUEST,
+            [1;6], [2;4],
+            [0;6], [4;4]
+        );
+        let mut packet = eth(ether_type::ARP);
+        packet.extend_from_slice(&arp.to_bytes().unwrap());
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(Some(arp), owned.arp);
+        assert_eq!(packet.len(), owned_len(&owned));
+        assert_eq!(owned, OwnedPacket::from(PacketHeaders::from_ethernet_slice(&packet).unwrap()));
+    }
+    // ethernet padding
+    {
+        let mut packet = eth(ether_type::IPV4);
+        packet.extend_from_slice(&udp);
+        packet.extend_from_slice(&[0;4]);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(&[1,2,3,4], &owned.payload[..]);
+        assert_eq!(&[0;4], &owned.padding[..]);
+        assert_eq!(packet.len(), owned_len(&owned));
+    }
+    // inner packet (ip in ip)
+    {
+        let mut packet = eth(ether_type::IPV4);
+        Ipv4Header::new(udp.len() as u16, 20, IpNumber::IPv4, [1;4], [2;4]).write(&mut packet).unwrap();
+        packet.extend_from_slice(&udp);
+        let limits = ParseLimits{
+            decode_ip_in_ip: true,
+            ..Default::default()
+        };
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(&udp[..], &owned.payload[..]);
+        assert_eq!(packet.len(), owned_len(&owned));
+
+        let inner = owned.inner.as_ref().unwrap();
+        assert_eq!(OwnedPacket::from_ip(&udp).unwrap(), **inner);
+        assert_eq!(udp.len(), owned_len(inner));
+        assert_eq!(owned, OwnedPacket::from(PacketHeaders::from_ethernet_slice_with_limits(&packet, limits).unwrap()));
+    }
+}
+
+#[test]
+fn errors() {
+    assert_matches!(OwnedPacket::from_ethernet(&[0;5]), Err(ReadError::UnexpectedEndOfSlice(_)));
+    assert_matches!(OwnedPacket::from_ip(&[]), Err(ReadError::UnexpectedEndOfSlice(_)));
+}
@@ -107,6 +107,165 @@ fn ipv4_udp() {
     assert_eq!(actual_payload, in_payload);
 }
 
+#[test]
+fn std_net_addrs() {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    fn serialize<T>(builder: PacketBuilderStep<T>, write: fn(PacketBuilderStep<T>, &mut Vec<u8>)) -> Vec<u8> {
+        let mut result = Vec::new();
+        write(builder, &mut result);
+        result
+    }
+    let write_udp = |builder: PacketBuilderStep<UdpHeader>, out: &mut Vec<u8>| builder.write(out, &[1,2,3]).unwrap();
+    let write_tcp = |builder: PacketBuilderStep<TcpHeader>, out: &mut Vec<u8>| builder.write(out, &[1,2,3]).unwrap();
+
+    // ipv4
+    {
+        let source = SocketAddrV4::new(Ipv4Addr::new(13,14,15,16), 22);
+        let destination = SocketAddrV4::new(Ipv4Addr::new(17,18,19,20), 23);
+        let expected = serialize(
+            PacketBuilder::ipv4([13,14,15,16], [17,18,19,20], 21).udp(22, 23),
+            write_udp
+        );
+        assert_eq!(
+            expected,
+            serialize(PacketBuilder::ipv4(*source.ip(), *destination.ip(), 21).udp(22, 23), write_udp)
+        );
+        assert_eq!(
+            expected,
+            serialize(PacketBuilder::ipv4(*source.ip(), [17,18,19,20], 21).udp_socket_addrs(source, destination), write_udp)
+        );
+        assert_eq!(
+            serialize(
+                PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                    .single_vlan(0x123)
+                    .ipv4([13,14,15,16], [17,18,19,20], 21)
+                    .tcp(22, 23, 24, 25),
+                write_tcp
+            ),
+            serialize(
+                PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                    .single_vlan(0x123)
+                    .ipv4(*source.ip(), *destination.ip(), 21)
+                    .tcp_socket_addrs(SocketAddr::V4(source), SocketAddr::V4(destination), 24, 25),
+                write_tcp
+            )
+        );
+    }
+
+    // ipv6
+    {
+        let source = SocketAddrV6::new(Ipv6Addr::from([1;16]), 22, 0, 0);
+        let destination = SocketAddrV6::new(Ipv6Addr::from([2;16]), 23, 0, 0);
+        assert_eq!(
+            serialize(PacketBuilder::ipv6([1;16], [2;16], 21).udp(22, 23), write_udp),
+            serialize(PacketBuilder::ipv6(*source.ip(), *destination.ip(), 21).udp_socket_addrs(source, destination), write_udp)
+        );
+        assert_eq!(
+            serialize(
+                PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                    .ipv6([1;16], [2;16], 21)
+                    .tcp(22, 23, 24, 25),
+                write_tcp
+            ),
+            serialize(
+                PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                    .ipv6(*source.ip(), *destination.ip(), 21)
+                    .tcp_socket_addrs(source, destination, 24, 25),
+                write_tcp
+            )
+        );
+    }
+}
+
+#[test]
+fn router_alert() {
+    // ipv4
+    {
+        let mut serialized = Vec::new();
+        PacketBuilder::ipv4([13,14,15,16], [224,0,0,22], 1)
+            .router_alert(0)
+            .unwrap()
+            .udp(22, 23)
+            .write(&mut serialized, &[1,2,3,4])
+            .unwrap();
+        let (header, rest) = Ipv4Header::from_slice(&serialized).unwrap();
+        assert_eq!(Some(0), header.router_alert());
+        assert_eq!(6, header.ihl());
+        assert_eq!(usize::from(header.total_len()), serialized.len());
+        let (udp, _) = UdpHeader::from_slice(rest).unwrap();
+        assert_eq!(UdpHeader::with_ipv4_checksum(22, 23, &header, &[1,2,3,4]).unwrap(), udp);
+    }
+    // ipv6
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([1;16], [2;16], 1)
+            .router_alert(Ipv6RawExtensionHeader::ROUTER_ALERT_MLD)
+            .unwrap()
+            .tcp(22, 23, 24, 25);
+        let size = builder.size(4);
+        let mut serialized = Vec::new();
+        builder.write(&mut serialized, &[1,2,3,4]).unwrap();
+        assert_eq!(size, serialized.len());
+
+        let packet = PacketHeaders::from_ethernet_slice(&serialized).unwrap();
+        match packet.ip {
+            Some(IpHeader::Version6(header, extensions)) => {
+                assert_eq!(ip_number::IPV6_HOP_BY_HOP, header.next_header);
+                assert_eq!(Some(Ipv6RawExtensionHeader::ROUTER_ALERT_MLD), extensions.router_alert());
+            },
+            value => panic!("unexpected ip header {:?}", value),
+        }
+        assert_matches!(packet.transport, Some(TransportHeader::Tcp(_)));
+    }
+    // options not fitting into the ipv4 header
+    {
+        let mut header = Ipv4Header::new(0, 1, IpNumber::Udp, [0;4], [0;4]);
+        header.set_options(&[1;40]).unwrap();
+        assert_eq!(
+            Some(ValueError::Ipv4OptionsLengthBad(44)),
+            PacketBuilder::ip(IpHeader::Version4(header, Default::default())).router_alert(0).err()
+        );
+    }
+}
+
+#[test]
+fn checksum_final_destination() {
+    let mut routing_payload = vec![Ipv6RoutingHeader::TYPE_0, 1, 0, 0, 0, 0];
+    routing_payload.extend_from_slice(&[3;16]);
+    let extensions = Ipv6Extensions{
+        routing: Some(Ipv6RoutingExtensions{
+            routing: Ipv6RawExtensionHeader::new_raw(0, &routing_payload).unwrap(),
+            final_destination_options: None,
+        }),
+        ..Default::default()
+    };
+    let header = Ipv6Header{
+        source: [1;16],
+        destination: [2;16],
+        hop_limit: 4,
+        ..Default::default()
+    };
+
+    let payload = [1,2,3,4];
+    let mut serialized = Vec::new();
+    PacketBuilder::ip(IpHeader::Version6(header, extensions))
+        .udp(22, 23)
+        .write(&mut serialized, &payload)
+        .unwrap();
+
+    let sliced = SlicedPacket::from_ip(&serialized).unwrap();
+    match sliced.transport {
+        Some(TransportSlice::Udp(udp)) => {
+            assert_eq!(
+                udp.to_header().calc_checksum_ipv6_raw([1;16], [3;16], &payload).unwrap(),
+                udp.checksum()
+            );
+        },
+        value => panic!("unexpected transport {:?}", value),
+    }
+}
+
 #[test]
 fn ipv6_udp() {
     //generate
@@ -164,6 +323,56 @@ fn ipv6_udp() {
     assert_eq!(actual_payload, in_payload);
 }
 
+#[test]
+fn ipv6_udp_jumbogram() {
+    //generate a payload not fitting into the payload length field
+    let in_payload: Vec<u8> = (0..70000u32).map(|v| v as u8).collect();
+    let builder = PacketBuilder::
+        ipv6(
+            [11,12,13,14,15,16,17,18,19,10,21,22,23,24,25,26],
+            [31,32,33,34,35,36,37,38,39,40,41,42,43,44,45,46],
+            47)
+        .udp(22,23);
+    let expected_size = Ipv6Header::SERIALIZED_SIZE + 8 + UdpHeader::SERIALIZED_SIZE + in_payload.len();
+    assert_eq!(expected_size, builder.size(in_payload.len()));
+
+    let mut serialized = Vec::new();
+    builder.write(&mut serialized, &in_payload).unwrap();
+    assert_eq!(expected_size, serialized.len());
+
+    //check the headers (with padding after the jumbogram)
+    serialized.extend_from_slice(&[1,2,3]);
+    let sliced = SlicedPacket::from_ip(&serialized).unwrap();
+    assert_eq!(&in_payload[..], sliced.payload);
+    assert_eq!(&[1,2,3], sliced.padding);
+    let (ip, ip_ext) = match sliced.ip {
+        Some(InternetSlice::Ipv6(ip, ext)) => (ip.to_header(), ext),
+        value => panic!("unexpected ip slice {:?}", value),
+    };
+    assert_eq!(0, ip.payload_length);
+    assert_eq!(ip_number::IPV6_HOP_BY_HOP, ip.next_header);
+    let hop_by_hop = Ipv6RawExtensionHeaderSlice::from_slice(ip_ext.slice()).unwrap();
+    assert_eq!(ip_number::UDP, hop_by_hop.next_header());
+    assert_eq!(Some((8 + UdpHeader::SERIALIZED_SIZE + in_payload.len()) as u32), hop_by_hop.jumbo_payload_length());
+
+    //udp jumbograms have a length of 0 & use the actual length in the checksum
+    let udp = match sliced.transport {
+        Some(TransportSlice::Udp(udp)) => udp.to_header(),
+        value => panic!("unexpected transport slice {:?}", value),
+    };
+    assert_eq!(0, udp.length);
+    assert_eq!(udp.calc_checksum_ipv6(&ip, &in_payload).unwrap(), udp.checksum);
+    assert_ne!(
+        UdpHeader{ length: 0xffff, ..udp.clone() }.calc_checksum_ipv6(&ip, &in_payload).unwrap(),
+        udp.checksum
+    );
+
+    //decoding
+    let headers = PacketHeaders::from_ip_slice(&serialized).unwrap();
+    assert_eq!(Some(TransportHeader::Udp(udp)), headers.transport);
+    assert_eq!(Payload::Udp(&serialized[expected_size - in_payload.len()..]), headers.payload);
+}
+
 #[test]
 fn ipv_custom_udp() {
     //generate
@@ -322,7 +531,8 @@ fn udp_builder_eth_single_vlan_ipv4_udp() {
                     priority_code_point: 0,
                     drop_eligible_indicator: false,
                     vlan_identifier: 0x123,
-                    ether_type: ether_type::IPV4
+                    ether_type: ether_type::IPV4,
+                    tpid: ether_type::VLAN_TAGGED_FRAME,
                });
 
     //ip header
@@ -393,7 +603,8 @@ fn udp_builder_eth_double_vlan_ipv6_udp() {
                     priority_code_point: 0,
                     drop_eligible_indicator: false,
                     vlan_identifier: 0x123,
-                    ether_type: EtherType::VlanTaggedFrame as u16
+                    ether_type: EtherType::VlanTaggedFrame as u16,
+                    tpid: ether_type::VLAN_TAGGED_FRAME,
                });
 
     //inner vlan header
@@ -402,7 +613,8 @@ fn udp_builder_eth_double_vlan_ipv6_udp() {
                     priority_code_point: 0,
                     drop_eligible_indicator: false,
                     vlan_identifier: 0x234,
-                    ether_type: EtherType::Ipv6 as u16
+                    ether_type: EtherType::Ipv6 as u16,
+                    tpid: ether_type::VLAN_TAGGED_FRAME,
                });
 
     //ip header
@@ -508,7 +720,8 @@ fn udp_builder_eth_vlan_ip_udp() {
                       priority_code_point: 1,
                       drop_eligible_indicator: true,
                       vlan_identifier: 0x123,
-                      ether_type: 0 //should be overwritten
+                      ether_type: 0, //should be overwritten
+                      tpid: ether_type::VLAN_TAGGED_FRAME,
                   }))
                   .ip(IpHeader::Version6(Ipv6Header{
                         traffic_class: 1,
@@ -552,7 +765,8 @@ fn udp_builder_eth_vlan_ip_udp() {
                     priority_code_point: 1,
                     drop_eligible_indicator: true,
                     vlan_identifier: 0x123,
-                    ether_type: EtherType::Ipv6 as u16
+                    ether_type: EtherType::Ipv6 as u16,
+                    tpid: ether_type::VLAN_TAGGED_FRAME,
                });
 
     //ip header
@@ -824,6 +1038,58 @@ fn tcp_options() {
     );
 }
 
+#[test]
+fn eth_padding() {
+    let builder = || PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12])
+                                   .pad_to_min_frame_len()
+                                   .single_vlan(0x123)
+                                   .ipv4([13,14,15,16], [17,18,19,20], 21)
+                                   .udp(22,23);
+    let header_len = Ethernet2Header::SERIALIZED_SIZE +
+                     SingleVlanHeader::SERIALIZED_SIZE +
+                     Ipv4Header::SERIALIZED_SIZE +
+                     UdpHeader::SERIALIZED_SIZE;
+
+    for payload_len in 0..Ethernet2Header::MIN_FRAME_LEN + 2 {
+        let payload: Vec<u8> = (0..payload_len).map(|v| v as u8 + 1).collect();
+        let expected_len = std::cmp::max(Ethernet2Header::MIN_FRAME_LEN, header_len + payload_len);
+        assert_eq!(expected_len, builder().size(payload_len));
+
+        let mut serialized = Vec::new();
+        builder().write(&mut serialized, &payload).unwrap();
+        assert_eq!(expected_len, serialized.len());
+
+        // zeros after the payload
+        assert!(serialized[header_len + payload_len..].iter().all(|&v| 0 == v));
+
+        // the length fields do not contain the padding
+        let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+        match sliced.ip.unwrap() {
+            InternetSlice::Ipv4(ip, _) => assert_eq!(
+                (Ipv4Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE + payload_len) as u16,
+                ip.total_len()
+            ),
+            value => panic!("unexpected ip slice {:?}", value),
+        }
+        match sliced.transport.unwrap() {
+            TransportSlice::Udp(udp) => assert_eq!(
+                (UdpHeader::SERIALIZED_SIZE + payload_len) as u16,
+                udp.length()
+            ),
+            value => panic!("unexpected transport slice {:?}", value),
+        }
+    }
+
+    // no padding by default
+    assert_eq!(
+        Ethernet2Header::SERIALIZED_SIZE + Ipv4Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE,
+        PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12])
+                      .ipv4([13,14,15,16], [17,18,19,20], 21)
+                      .udp(22,23)
+                      .size(0)
+    );
+}
+
 #[test]
 fn size() {
     //ipv4 no vlan
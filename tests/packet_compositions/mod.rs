@@ -17,6 +17,18 @@ static VLAN_ETHER_TYPES: &'static [u16] = &[
 
 impl ComponentTest {
 
+    /// Sets the payload length of the ip header to the length of the
+    /// extensions, transport header & payload (otherwise the data after
+    /// the ip payload is sliced as padding).
+    fn set_ip_payload_len(&mut self) {
+        let len = self.transport.as_ref().map(|t| t.header_len()).unwrap_or(0) + self.payload.len();
+        match self.ip.as_mut() {
+            Some(IpHeader::Version4(ip, exts)) => ip.set_payload_len(exts.header_len() + len).unwrap(),
+            Some(IpHeader::Version6(ip, exts)) => ip.set_payload_length(exts.header_len() + len).unwrap(),
+            None => {},
+        }
+    }
+
     fn serialize(&self) -> Vec<u8> {
         let mut buffer = Vec::<u8>::with_capacity(
             match &self.link {
@@ -43,6 +55,7 @@ impl ComponentTest {
         match &self.vlan {
             Some(Single(header)) => header.write(&mut buffer).unwrap(),
             Some(Double(header)) => header.write(&mut buffer).unwrap(),
+            Some(header @ Multi(_)) => header.write(&mut buffer).unwrap(),
             None => {},
         }
         match &self.ip {
@@ -59,6 +72,7 @@ impl ComponentTest {
         match &self.transport {
             Some(TransportHeader::Udp(header)) => header.write(&mut buffer).unwrap(),
             Some(TransportHeader::Tcp(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::Igmp(header)) => header.write(&mut buffer).unwrap(),
             None => {}
         }
         use std::io::Write;
@@ -229,6 +243,11 @@ impl ComponentTest {
                 Double(double) => {
                     builder.add(double.outer.header_len());
                     builder.add(double.inner.header_len());
+                },
+                Multi(headers) => {
+                    for header in headers {
+                        builder.add(header.header_len());
+                    }
                 }
             }
         }
@@ -261,6 +280,9 @@ impl ComponentTest {
                     if let Some(e) = exts.auth.as_ref() {
                         builder.add(e.header_len());
                     }
+                    if let Some(e) = exts.final_destination_options.as_ref() {
+                        builder.add(e.header_len());
+                    }
                 },
             }
         }
@@ -276,7 +298,7 @@ impl ComponentTest {
         assert_eq!(self.vlan, actual.vlan);
         assert_eq!(self.ip, self.ip);
         assert_eq!(self.transport, actual.transport);
-        assert_eq!(self.payload[..], actual.payload[..]);
+        assert_eq!(self.payload[..], actual.payload.slice()[..]);
     }
 
     fn assert_sliced_packet(&self, result: SlicedPacket) {
@@ -284,8 +306,24 @@ impl ComponentTest {
         assert_eq!(result, result);
 
         //ethernet & vlan
-        assert_eq!(self.link, result.link.map(|ref x| x.to_header()));
-        assert_eq!(self.vlan, result.vlan.map(|ref x| x.to_header()));
+        assert_eq!(self.link, result.link.and_then(|ref x| x.to_header()));
+        assert_eq!(
+            self.vlan,
+            result.vlan.map(|ref x| {
+                // the tpid of the outermost vlan header is part of the link header
+                // (or the ether type passed to from_ether_type)
+                let mut header = x.to_header();
+                let outer_tpid = match (&self.link, &self.vlan) {
+                    (Some(link), _) => Some(link.ether_type),
+                    (None, Some(vlan)) => vlan.get(0).map(|outer| outer.tpid),
+                    (None, None) => None,
+                };
+                if let (Some(tpid), Some(outer)) = (outer_tpid, header.get_mut(0)) {
+                    outer.tpid = tpid;
+                }
+                header
+            })
+        );
 
         //ip
         assert_eq!(self.ip,
@@ -323,6 +361,7 @@ impl ComponentTest {
             match result.transport.as_ref() {
                 Some(TransportSlice::Udp(actual)) => Some(TransportHeader::Udp(actual.to_header())),
                 Some(TransportSlice::Tcp(actual)) => Some(TransportHeader::Tcp(actual.to_header())),
+                Some(TransportSlice::Igmp(actual)) => Some(TransportHeader::Igmp(actual.to_header())),
                 Some(TransportSlice::Unknown(_)) => None,
                 None => None
             }
@@ -356,6 +395,7 @@ impl ComponentTest {
             result.vlan = Some(VlanHeader::Single({
                 let mut v = inner_vlan.clone();
                 v.ether_type = ether_type;
+                v.tpid = self.link.as_ref().unwrap().ether_type;
                 v
             }));
             result
@@ -366,11 +406,13 @@ impl ComponentTest {
                 outer: {
                     let mut v = outer_vlan.clone();
                     v.ether_type = outer_ether_type;
+                    v.tpid = self.link.as_ref().unwrap().ether_type;
                     v
                 },
                 inner: {
                     let mut v = inner_vlan.clone();
                     v.ether_type = inner_ether_type;
+                    v.tpid = outer_ether_type;
                     v
                 }}));
             result
@@ -403,6 +445,7 @@ impl ComponentTest {
                 header.set_next_headers(ip.protocol);
                 header
             });
+            test.set_ip_payload_len();
 
             // run without transport header
             test.run();
@@ -441,6 +484,7 @@ impl ComponentTest {
                 header.set_next_headers(ip.next_header);
                 header
             });
+            test.set_ip_payload_len();
             test.run();
         }
 
@@ -450,6 +494,11 @@ impl ComponentTest {
             test.ip = Some({
                 let mut non_frag = ip_exts.clone();
                 non_frag.fragment = None;
+                // without the fragment header the final destination options
+                // can become the only destination options header
+                if non_frag.destination_options.is_none() && non_frag.auth.is_none() {
+                    non_frag.destination_options = non_frag.final_destination_options.take();
+                }
                 let mut header = IpHeader::Version6(ip.clone(), non_frag);
                 header.set_next_headers(ip.next_header);
                 header
@@ -460,13 +509,18 @@ impl ComponentTest {
 
     fn run_transport(&self, udp: &UdpHeader, tcp: &TcpHeader) {
         // unknown transport layer
-        self.run();
+        {
+            let mut test = self.clone();
+            test.set_ip_payload_len();
+            test.run();
+        }
 
         // udp
         {
             let mut test = self.clone();
             test.ip.as_mut().unwrap().set_next_headers(ip_number::UDP);
             test.transport = Some(TransportHeader::Udp(udp.clone()));
+            test.set_ip_payload_len();
             test.run()
         }
 
@@ -475,6 +529,7 @@ impl ComponentTest {
             let mut test = self.clone();
             test.ip.as_mut().unwrap().set_next_headers(ip_number::TCP);
             test.transport = Some(TransportHeader::Tcp(tcp.clone()));
+            test.set_ip_payload_len();
             test.run()
         }
     }
@@ -528,9 +583,15 @@ fn test_packet_slicing_panics() {
     let s = SlicedPacket {
         link: None,
         vlan: None,
+        llc: None,
+        arp: None,
         ip: None,
         transport: None,
-        payload: &v[..]
+        tunnel: None,
+        payload: &v[..],
+        trailer: &[],
+        padding: &[],
+        inner: None,
     };
     ComponentTest {
         link: Some(
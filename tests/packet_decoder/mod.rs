@@ -9,24 +9,31 @@ mod header {
         pub vlan: Option<VlanHeader>,
         pub ip: Option<IpHeader>,
         pub transport: Option<TransportHeader>,
-        /// Rest of the packet that could not be decoded as a header (usually the payload).
-        pub payload: &'a [u8]*/
+        pub payload: Payload<'a>*/
         let header = PacketHeaders{
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             transport: None,
-            payload: &[]
+            tunnel: None,
+            payload: Payload::Unknown(0, &[]),
+            inner: None,
         };
         assert_eq!(
             &format!("{:?}", header),
             &format!(
-                "PacketHeaders {{ link: {:?}, vlan: {:?}, ip: {:?}, transport: {:?}, payload: {:?} }}",
+                "PacketHeaders {{ link: {:?}, vlan: {:?}, llc: {:?}, arp: {:?}, ip: {:?}, transport: {:?}, tunnel: {:?}, payload: {:?}, inner: {:?} }}",
                 header.link,
                 header.vlan,
+                header.llc,
+                header.arp,
                 header.ip,
                 header.transport,
-                header.payload
+                header.tunnel,
+                header.payload,
+                header.inner
             )
         );
     }
@@ -36,11 +43,220 @@ mod header {
         let header = PacketHeaders{
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             transport: None,
-            payload: &[]
+            tunnel: None,
+            payload: Payload::Unknown(0, &[]),
+            inner: None,
         };
         assert_eq!(header.clone(), header);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn header_len_total_len() {
+        // no headers
+        {
+            let header = PacketHeaders{
+                link: None,
+                vlan: None,
+                llc: None,
+                arp: None,
+                ip: None,
+                transport: None,
+                tunnel: None,
+                payload: Payload::Unknown(0, &[]),
+                inner: None,
+            };
+            assert_eq!(0, header.header_len());
+            assert_eq!(10, header.total_len(10));
+        }
+
+        // all headers including ip extensions & tcp options
+        {
+            let payload = [1,2,3,4];
+            let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .single_vlan(123)
+                .ip(IpHeader::Version6(
+                    Ipv6Header {
+                        traffic_class: 0,
+                        flow_label: 0,
+                        payload_length: 0,
+                        next_header: 0,
+                        hop_limit: 4,
+                        source: [0;16],
+                        destination: [1;16]
+                    },
+                    Ipv6Extensions {
+                        hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[0;6]).unwrap()),
+                        ..Default::default()
+                    }
+                ))
+                .tcp(1, 2, 3, 4)
+                .options(&[TcpOptionElement::Noop, TcpOptionElement::WindowScale(2)])
+                .unwrap();
+            let mut packet = Vec::with_capacity(builder.size(payload.len()));
+            builder.write(&mut packet, &payload).unwrap();
+
+            let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            assert_eq!(packet.len() - payload.len(), headers.header_len());
+            assert_eq!(packet.len(), headers.total_len(payload.len()));
+        }
+    }
+}
+mod payload {
+    use super::*;
+
+    fn ipv4(protocol: u8, payload: &[u8]) -> Vec<u8> {
+        let mut ip = Ipv4Header::new(payload.len() as u16, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+        ip.protocol = protocol;
+        let mut result = Vec::new();
+        ip.write(&mut result).unwrap();
+        result.extend_from_slice(payload);
+        result
+    }
+
+    fn ipv6(next_header: u8, payload: &[u8]) -> Vec<u8> {
+        let ip = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: payload.len() as u16,
+            next_header,
+            hop_limit: 20,
+            source: [1;16],
+            destination: [2;16],
+        };
+        let mut result = Vec::new();
+        ip.write(&mut result).unwrap();
+        result.extend_from_slice(payload);
+        result
+    }
+
+    #[test]
+    fn transport() {
+        let builder = PacketBuilder::
+            ipv4([1,2,3,4], [5,6,7,8], 20)
+            .udp(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(3));
+        builder.write(&mut packet, &[1,2,3]).unwrap();
+        let payload = PacketHeaders::from_ip_slice(&packet).unwrap().payload;
+        assert_eq!(Payload::Udp(&[1,2,3]), payload);
+        assert_eq!(&[1,2,3], payload.slice());
+        assert_eq!(3, payload.len());
+        assert!(!payload.is_empty());
+
+        let builder = PacketBuilder::
+            ipv6([1;16], [2;16], 20)
+            .tcp(1, 2, 3, 4);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let payload = PacketHeaders::from_ip_slice(&packet).unwrap().payload;
+        assert_eq!(Payload::Tcp(&[]), payload);
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn icmp() {
+        // echo request with data
+        let message = [8, 0, 0, 0, 0, 1, 0, 2, 0xaa, 0xbb];
+        assert_eq!(
+            Payload::Icmpv4{ icmp_type: 8, code: 0, echo_data: Some(&[0xaa, 0xbb]), message: &message },
+            PacketHeaders::from_ip_slice(&ipv4(ip_number::ICMP, &message)).unwrap().payload
+        );
+        // destination unreachable
+        let message = [3, 1, 0, 0, 0, 0, 0, 0, 0x45];
+        assert_eq!(
+            Payload::Icmpv4{ icmp_type: 3, code: 1, echo_data: None, message: &message },
+            PacketHeaders::from_ip_slice(&ipv4(ip_number::ICMP, &message)).unwrap().payload
+        );
+        // echo reply v6
+        let message = [129, 0, 0, 0, 0, 1, 0, 2];
+        assert_eq!(
+            Payload::Icmpv6{ icmp_type: 129, code: 0, echo_data: Some(&[]), message: &message },
+            PacketHeaders::from_ip_slice(&ipv6(ip_number::IPV6_ICMP, &message)).unwrap().payload
+        );
+        // too short echo request
+        let message = [128, 0, 0, 0, 0];
+        assert_eq!(
+            Payload::Icmpv6{ icmp_type: 128, code: 0, echo_data: None, message: &message },
+            PacketHeaders::from_ip_slice(&ipv6(ip_number::IPV6_ICMP, &message)).unwrap().payload
+        );
+        // too short for the icmp header
+        assert_eq!(
+            Payload::Unknown(ip_number::ICMP, &[8, 0]),
+            PacketHeaders::from_ip_slice(&ipv4(ip_number::ICMP, &[8, 0])).unwrap().payload
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            Payload::Unknown(IpNumber::Sctp as u8, &[1, 2]),
+            PacketHeaders::from_ip_slice(&ipv4(IpNumber::Sctp as u8, &[1, 2])).unwrap().payload
+        );
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::WAKE_ON_LAN, payload: &[1, 2] },
+            PacketHeaders::from_ether_type(ether_type::WAKE_ON_LAN, &[1, 2]).unwrap().payload
+        );
+        let mut packet = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: 0x1234,
+        }.to_bytes().to_vec();
+        packet.push(9);
+        assert_eq!(
+            Payload::Ether{ ether_type: 0x1234, payload: &[9] },
+            PacketHeaders::from_ethernet_slice(&packet).unwrap().payload
+        );
+    }
+
+    #[test]
+    fn ip_fragment() {
+        // ipv4
+        {
+            let mut ip = Ipv4Header::new(4, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+            ip.more_fragments = true;
+            ip.fragments_offset = 3;
+            let mut packet = Vec::new();
+            ip.write(&mut packet).unwrap();
+            packet.extend_from_slice(&[1,2,3,4]);
+            let decoded = PacketHeaders::from_ether_type(ether_type::IPV4, &packet).unwrap();
+            assert!(decoded.transport.is_none());
+            assert_eq!(
+                Payload::IpFragment{ ip_number: ip_number::UDP, fragment_offset: 3, more_fragments: true, payload: &[1,2,3,4] },
+                decoded.payload
+            );
+            let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+            assert!(decoded.transport.is_none());
+            assert_eq!(
+                Payload::IpFragment{ ip_number: ip_number::UDP, fragment_offset: 3, more_fragments: true, payload: &[1,2,3,4] },
+                decoded.payload
+            );
+        }
+        // ipv6
+        {
+            let fragment = Ipv6FragmentHeader::new(ip_number::TCP, 5, false, 1234);
+            let mut payload = Vec::new();
+            fragment.write(&mut payload).unwrap();
+            payload.extend_from_slice(&[1,2]);
+            let mut packet = Ethernet2Header{
+                source: [1;6],
+                destination: [2;6],
+                ether_type: ether_type::IPV6,
+            }.to_bytes().to_vec();
+            packet.extend_from_slice(&ipv6(ip_number::IPV6_FRAG, &payload));
+            let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            assert_eq!(
+                Payload::IpFragment{ ip_number: ip_number::TCP, fragment_offset: 5, more_fragments: false, payload: &[1,2] },
+                decoded.payload
+            );
+            let decoded = PacketHeaders::from_ip_slice(&packet[Ethernet2Header::SERIALIZED_SIZE..]).unwrap();
+            assert!(decoded.transport.is_none());
+            assert_eq!(
+                Payload::IpFragment{ ip_number: ip_number::TCP, fragment_offset: 5, more_fragments: false, payload: &[1,2] },
+                decoded.payload
+            );
+        }
+    }
+}
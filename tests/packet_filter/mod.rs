@@ -334,8 +334,14 @@ impl PacketFilterTest {
                     header.write(&mut vlan_data).unwrap();
                     Some(VlanSlice::DoubleVlan(DoubleVlanHeaderSlice::from_slice(&vlan_data[..]).unwrap()))
                 },
+                Some(header @ VlanHeader::Multi(_)) => {
+                    header.write(&mut vlan_data).unwrap();
+                    Some(VlanSlice::MultiVlan(MultiVlanHeaderSlice::from_slice(&vlan_data[..]).unwrap()))
+                },
                 None => None
             },
+            llc: None,
+            arp: None,
             ip: match &self.ip {
                 Some(IpHeader::Version4(header, _)) => {
                     header.write(&mut ip_data).unwrap();
@@ -367,9 +373,17 @@ impl PacketFilterTest {
                     header.write(&mut transport_data).unwrap();
                     Some(TransportSlice::Tcp(TcpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
                 },
+                Some(TransportHeader::Igmp(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::Igmp(IgmpSlice::from_slice(&transport_data[..]).unwrap()))
+                },
                 None => None
             },
-            payload: &payload[..]
+            tunnel: None,
+            payload: &payload[..],
+            trailer: &[],
+            padding: &[],
+            inner: None,
         };
 
         assert_eq!(expected_result, self.filter.applies_to_slice(&slice));
@@ -53,6 +53,117 @@ mod internet_slice {
             );
         }
     }
+
+    #[test]
+    fn source_destination_addr() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+        // ipv4
+        {
+            let header = Ipv4Header::new(0, 1, IpNumber::Udp, [1, 2, 3, 4], [5, 6, 7, 8]);
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            let ip = InternetSlice::Ipv4(
+                Ipv4HeaderSlice::from_slice(&buffer).unwrap(),
+                Default::default()
+            );
+            assert_eq!(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), ip.source_addr());
+            assert_eq!(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8)), ip.destination_addr());
+        }
+        // ipv6
+        {
+            let header = Ipv6Header {
+                next_header: ip_number::UDP,
+                source: [1;16],
+                destination: [2;16],
+                ..Default::default()
+            };
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            let ip = InternetSlice::Ipv6(
+                Ipv6HeaderSlice::from_slice(&buffer).unwrap(),
+                Ipv6ExtensionsSlice::from_slice(ip_number::UDP, &[]).unwrap().0
+            );
+            assert_eq!(IpAddr::V6(Ipv6Addr::from([1;16])), ip.source_addr());
+            assert_eq!(IpAddr::V6(Ipv6Addr::from([2;16])), ip.destination_addr());
+        }
+    }
+
+    #[test]
+    fn fragment_info() {
+        // ipv4 not fragmented
+        {
+            let mut header : Ipv4Header = Default::default();
+            // experimental protocol number (no transport header gets decoded)
+            header.protocol = 253;
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            let sliced = SlicedPacket::from_ip(&buffer).unwrap();
+            assert_eq!(None, sliced.ip.unwrap().fragment_info());
+        }
+        // ipv4 fragment
+        {
+            let mut header : Ipv4Header = Default::default();
+            header.protocol = ip_number::UDP;
+            header.identification = 0x1234;
+            header.fragments_offset = 3;
+            header.more_fragments = true;
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            let sliced = SlicedPacket::from_ip(&buffer).unwrap();
+            assert_eq!(
+                Some(IpFragmentInfo{
+                    identification: 0x1234,
+                    byte_offset: 24,
+                    more_fragments: true,
+                    payload_ip_number: ip_number::UDP,
+                }),
+                sliced.ip.unwrap().fragment_info()
+            );
+        }
+        // ipv6
+        for (fragment_offset, more_fragments) in [(0u16, false), (0, true), (10, false)].iter() {
+            let header = Ipv6Header {
+                next_header: ip_number::IPV6_FRAG,
+                payload_length: 8,
+                ..Default::default()
+            };
+            let frag = Ipv6FragmentHeader {
+                next_header: 253,
+                fragment_offset: *fragment_offset,
+                more_fragments: *more_fragments,
+                identification: 0x12345678,
+            };
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            frag.write(&mut buffer).unwrap();
+            let sliced = SlicedPacket::from_ip(&buffer).unwrap();
+            let expected = if frag.is_fragmenting_payload() {
+                Some(IpFragmentInfo{
+                    identification: 0x12345678,
+                    byte_offset: *fragment_offset*8,
+                    more_fragments: *more_fragments,
+                    payload_ip_number: 253,
+                })
+            } else {
+                None
+            };
+            assert_eq!(expected, sliced.ip.unwrap().fragment_info());
+        }
+        // ipv6 without fragment header
+        {
+            let header = Ipv6Header {
+                next_header: ip_number::UDP,
+                ..Default::default()
+            };
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            let ip = InternetSlice::Ipv6(
+                Ipv6HeaderSlice::from_slice(&buffer).unwrap(),
+                Ipv6ExtensionsSlice::from_slice(ip_number::UDP, &[]).unwrap().0
+            );
+            assert_eq!(None, ip.fragment_info());
+        }
+    }
 }
 
 mod transport_slice {
@@ -112,9 +223,447 @@ mod transport_slice {
     }
 }
 
+mod transport_slice_fns {
+    use super::*;
+
+    #[test]
+    fn slice() {
+        // udp
+        {
+            let raw = UdpHeader::default().to_bytes();
+            let u = UdpHeaderSlice::from_slice(&raw).unwrap();
+            assert_eq!(&raw[..], TransportSlice::Udp(u).slice());
+        }
+        // tcp
+        {
+            let mut buffer = Vec::new();
+            TcpHeader::default().write(&mut buffer).unwrap();
+            let t = TcpHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..], TransportSlice::Tcp(t).slice());
+        }
+        // igmp
+        {
+            let raw = IgmpHeader::new(IgmpType::LeaveGroup{ group_address: [224,0,0,1] }).to_bytes().unwrap();
+            let i = IgmpSlice::from_slice(&raw).unwrap();
+            assert_eq!(&raw[..], TransportSlice::Igmp(i).slice());
+        }
+        // unknown
+        assert!(TransportSlice::Unknown(ip_number::IGMP).slice().is_empty());
+    }
+}
+
 mod sliced_packet {
     use super::*;
 
+    #[test]
+    fn header_bytes_payload_bytes() {
+        let payload = [1,2,3,4,5,6,7,8];
+
+        // ethernet, vlan, ipv6 with extension & tcp
+        {
+            let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .single_vlan(123)
+                .ip(IpHeader::Version6(
+                    Ipv6Header {
+                        traffic_class: 0,
+                        flow_label: 0,
+                        payload_length: 0,
+                        next_header: 0,
+                        hop_limit: 4,
+                        source: [0;16],
+                        destination: [1;16]
+                    },
+                    Ipv6Extensions {
+                        fragment: Some(Ipv6FragmentHeader::new(0, 0, false, 1234)),
+                        ..Default::default()
+                    }
+                ))
+                .tcp(1, 2, 3, 4);
+            let mut packet = Vec::with_capacity(builder.size(payload.len()));
+            builder.write(&mut packet, &payload).unwrap();
+
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            let headers = sliced.header_bytes();
+            assert_eq!(headers[0], &packet[..14]);
+            assert_eq!(headers[1], &packet[14..18]);
+            assert!(headers[2].is_empty());
+            assert_eq!(headers[3], &packet[18..58]);
+            assert_eq!(headers[4], &packet[58..66]);
+            assert_eq!(headers[5], &packet[66..86]);
+            assert_eq!(sliced.payload_bytes(), &payload[..]);
+        }
+
+        // no link layer & unknown transport
+        {
+            let header = Ipv4Header::new(
+                payload.len() as u16,
+                4,
+                IpNumber::Sctp,
+                [1,2,3,4],
+                [5,6,7,8]
+            );
+            let mut packet = Vec::new();
+            header.write(&mut packet).unwrap();
+            packet.extend_from_slice(&payload);
+
+            let sliced = SlicedPacket::from_ip(&packet).unwrap();
+            let headers = sliced.header_bytes();
+            assert!(headers[0].is_empty());
+            assert!(headers[1].is_empty());
+            assert!(headers[2].is_empty());
+            assert_eq!(headers[3], &packet[..20]);
+            assert!(headers[4].is_empty());
+            assert!(headers[5].is_empty());
+            assert_eq!(sliced.payload_bytes(), &payload[..]);
+        }
+    }
+
+    #[test]
+    fn headers_len_total_len() {
+        let payload = [1,2,3,4,5,6,7,8];
+
+        // ethernet, vlan, ipv6 with extension & tcp with options
+        {
+            let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .double_vlan(123, 234)
+                .ip(IpHeader::Version6(
+                    Ipv6Header {
+                        traffic_class: 0,
+                        flow_label: 0,
+                        payload_length: 0,
+                        next_header: 0,
+                        hop_limit: 4,
+                        source: [0;16],
+                        destination: [1;16]
+                    },
+                    Ipv6Extensions {
+                        fragment: Some(Ipv6FragmentHeader::new(0, 0, false, 1234)),
+                        ..Default::default()
+                    }
+                ))
+                .tcp(1, 2, 3, 4)
+                .options(&[TcpOptionElement::MaximumSegmentSize(1400)])
+                .unwrap();
+            let mut packet = Vec::with_capacity(builder.size(payload.len()));
+            builder.write(&mut packet, &payload).unwrap();
+
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(packet.len() - payload.len(), sliced.headers_len());
+            assert_eq!(packet.len(), sliced.total_len(payload.len()));
+            assert_eq!(sliced.headers_len() + 100, sliced.total_len(100));
+        }
+
+        // no link layer & unknown transport
+        {
+            let header = Ipv4Header::new(
+                payload.len() as u16,
+                4,
+                IpNumber::Sctp,
+                [1,2,3,4],
+                [5,6,7,8]
+            );
+            let mut packet = Vec::new();
+            header.write(&mut packet).unwrap();
+            packet.extend_from_slice(&payload);
+
+            let sliced = SlicedPacket::from_ip(&packet).unwrap();
+            assert_eq!(20, sliced.headers_len());
+            assert_eq!(packet.len(), sliced.total_len(payload.len()));
+        }
+    }
+
+    #[test]
+    fn ip_number_chain() {
+        use ip_number::*;
+
+        let chain = |packet: &[u8], limits: ParseLimits| -> Vec<(u8, std::ops::Range<usize>)> {
+            SlicedPacket::from_ip_with_limits(packet, limits).unwrap()
+                .ip_number_chain()
+                .into_iter()
+                .map(|entry| (entry.ip_number, entry.range))
+                .collect()
+        };
+
+        // ipv6 with extension headers & tcp
+        {
+            let builder = PacketBuilder::ip(IpHeader::Version6(
+                    Ipv6Header{
+                        hop_limit: 4,
+                        ..Default::default()
+                    },
+                    Ipv6Extensions{
+                        hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[0;6]).unwrap()),
+                        routing: Some(Ipv6RoutingExtensions{
+                            routing: Ipv6RawExtensionHeader::new_raw(0, &[0;14]).unwrap(),
+                            final_destination_options: None,
+                        }),
+                        fragment: Some(Ipv6FragmentHeader::new(0, 0, false, 1234)),
+                        ..Default::default()
+                    }
+                ))
+                .tcp(1, 2, 3, 4);
+            let mut packet = Vec::with_capacity(builder.size(0));
+            builder.write(&mut packet, &[]).unwrap();
+            assert_eq!(
+                vec![
+                    (IPV6_HOP_BY_HOP, 40..48),
+                    (IPV6_ROUTE, 48..64),
+                    (IPV6_FRAG, 64..72),
+                    (TCP, 72..92),
+                ],
+                chain(&packet, Default::default())
+            );
+
+            // chain of the extension headers only
+            let sliced = SlicedPacket::from_ip(&packet).unwrap();
+            match sliced.ip.unwrap() {
+                InternetSlice::Ipv6(_, exts) => assert_eq!(
+                    vec![
+                        IpNumberChainEntry{ ip_number: IPV6_HOP_BY_HOP, range: 0..8 },
+                        IpNumberChainEntry{ ip_number: IPV6_ROUTE, range: 8..24 },
+                        IpNumberChainEntry{ ip_number: IPV6_FRAG, range: 24..32 },
+                    ],
+                    exts.ip_number_chain().collect::<Vec<_>>()
+                ),
+                _ => unreachable!(),
+            }
+        }
+
+        // ipv4 with authentication header & udp
+        {
+            let builder = PacketBuilder::ip(IpHeader::Version4(
+                    Ipv4Header::new(0, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]),
+                    Ipv4Extensions{
+                        auth: Some(IpAuthenticationHeader::new(UDP, 1, 2, &[3;4]).unwrap()),
+                    }
+                ))
+                .udp(1, 2);
+            let mut packet = Vec::with_capacity(builder.size(4));
+            builder.write(&mut packet, &[1,2,3,4]).unwrap();
+            assert_eq!(
+                vec![(AUTH, 20..36), (UDP, 36..44)],
+                chain(&packet, Default::default())
+            );
+        }
+
+        // esp header followed by udp
+        {
+            let mut payload = EspHeader{ spi: 1, sequence_number: 2 }.to_bytes().to_vec();
+            UdpHeader{ source_port: 1, destination_port: 2, length: 8, checksum: 0 }.write(&mut payload).unwrap();
+            // padding, trailer & icv
+            payload.extend_from_slice(&[1, 2, 2, UDP]);
+            payload.extend_from_slice(&[0;12]);
+            let mut packet = Vec::new();
+            Ipv4Header::new(payload.len() as u16, 20, IpNumber::EncapsulatingSecurityPayload, [1,2,3,4], [5,6,7,8])
+                .write(&mut packet)
+                .unwrap();
+            packet.extend_from_slice(&payload);
+            let limits = ParseLimits{
+                esp_null_icv_len: Some(12),
+                ..Default::default()
+            };
+            assert_eq!(
+                vec![(ENCAP_SEC, 20..28), (UDP, 28..36)],
+                chain(&packet, limits)
+            );
+        }
+
+        // unknown protocol
+        {
+            let mut packet = Vec::new();
+            Ipv4Header::new(0, 20, IpNumber::ExperimentalAndTesting0, [1,2,3,4], [5,6,7,8])
+                .write(&mut packet)
+                .unwrap();
+            assert_eq!(vec![(EXP0, 20..20)], chain(&packet, Default::default()));
+        }
+
+        // no ip header
+        {
+            let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .udp(21, 1234);
+            let mut packet = Vec::with_capacity(builder.size(0));
+            builder.write(&mut packet, &[]).unwrap();
+            let mut sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            sliced.ip = None;
+            assert!(sliced.ip_number_chain().is_empty());
+        }
+    }
+
+    #[test]
+    fn io_slices() {
+        use std::io::Write;
+
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .double_vlan(1, 2)
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let payload = [1,2,3,4];
+        let mut packet = Vec::with_capacity(builder.size(payload.len()));
+        builder.write(&mut packet, &payload).unwrap();
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(
+            packet.len(),
+            out.write_vectored(&sliced.io_slices()).unwrap()
+        );
+        assert_eq!(out, packet);
+    }
+
+    #[test]
+    fn padding() {
+        use std::io::Write;
+
+        // ipv4 with ethernet padding
+        {
+            let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .pad_to_min_frame_len()
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .udp(21, 1234);
+            let payload = [1,2,3,4];
+            let mut packet = Vec::with_capacity(builder.size(payload.len()));
+            builder.write(&mut packet, &payload).unwrap();
+
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(&payload, sliced.payload);
+            assert_eq!(&packet[46..], sliced.padding);
+            assert_eq!(14, sliced.padding.len());
+
+            // the padding is part of the io slices
+            let mut out = Vec::new();
+            assert_eq!(
+                packet.len(),
+                out.write_vectored(&sliced.io_slices()).unwrap()
+            );
+            assert_eq!(out, packet);
+
+            // from_ip
+            let sliced = SlicedPacket::from_ip(&packet[14..]).unwrap();
+            assert_eq!(&payload, sliced.payload);
+            assert_eq!(&packet[46..], sliced.padding);
+
+            // trimming disabled (padding is part of the payload)
+            let limits = ParseLimits{
+                trim_ip_payload: false,
+                ..Default::default()
+            };
+            let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits).unwrap();
+            assert_eq!(&packet[42..], sliced.payload);
+            assert!(sliced.padding.is_empty());
+            let sliced = SlicedPacket::from_ip_with_limits(&packet[14..], limits).unwrap();
+            assert_eq!(&packet[42..], sliced.payload);
+            assert!(sliced.padding.is_empty());
+        }
+
+        // ipv6 with trailing data (e.g. a FCS)
+        {
+            let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+                .tcp(21, 1234, 1, 1024);
+            let payload = [1,2,3,4];
+            let mut packet = Vec::with_capacity(builder.size(payload.len()) + 4);
+            builder.write(&mut packet, &payload).unwrap();
+            packet.extend_from_slice(&[5,6,7,8]);
+
+            let sliced = SlicedPacket::from_ip(&packet).unwrap();
+            assert_eq!(&payload, sliced.payload);
+            assert_eq!(&[5,6,7,8], sliced.padding);
+
+            let limits = ParseLimits{
+                trim_ip_payload: false,
+                ..Default::default()
+            };
+            let sliced = SlicedPacket::from_ip_with_limits(&packet, limits).unwrap();
+            assert_eq!(&[1,2,3,4,5,6,7,8], sliced.payload);
+            assert!(sliced.padding.is_empty());
+
+            // no padding if the data is shorter then the ip payload length
+            let sliced = SlicedPacket::from_ip(&packet[..packet.len() - 6]).unwrap();
+            assert_eq!(&payload[..2], sliced.payload);
+            assert!(sliced.padding.is_empty());
+
+            // payload length zero with a hop by hop header (jumbogram)
+            let mut jumbo = packet[..40].to_vec();
+            jumbo[4] = 0;
+            jumbo[5] = 0;
+            jumbo[6] = ip_number::IPV6_HOP_BY_HOP;
+            jumbo.extend_from_slice(&[59, 0, 1, 4, 0, 0, 0, 0]);
+            jumbo.extend_from_slice(&[9;4]);
+            let sliced = SlicedPacket::from_ip(&jumbo).unwrap();
+            assert_eq!(&[9;4], sliced.payload);
+            assert!(sliced.padding.is_empty());
+        }
+
+        // no padding without an ip header
+        {
+            let mut packet = [0u8;60];
+            packet[12] = 0x12; // unknown ether type
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(&packet[14..], sliced.payload);
+            assert!(sliced.padding.is_empty());
+        }
+    }
+
+    #[test]
+    fn from_icmp_error_payload() {
+        // ipv4 & tcp (only the first 8 bytes of the tcp header are quoted)
+        {
+            let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 1)
+                .tcp(21, 1234, 1, 4000);
+            let mut packet = Vec::with_capacity(builder.size(4));
+            builder.write(&mut packet, &[1,2,3,4]).unwrap();
+            let quoted = &packet[..20 + 8];
+
+            // not accepted by the normal slicing
+            assert_matches!(
+                SlicedPacket::from_ip(quoted),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+
+            let sliced = SlicedPacket::from_icmp_error_payload(quoted).unwrap();
+            assert_eq!(
+                Ipv4HeaderSlice::from_slice(&packet).unwrap(),
+                sliced.ip.as_ref().map(|ip| match ip {
+                    InternetSlice::Ipv4(header, _) => header.clone(),
+                    _ => panic!("unexpected ip slice"),
+                }).unwrap()
+            );
+            assert_eq!(None, sliced.transport);
+            assert_eq!(&packet[20..28], sliced.payload);
+            assert!(sliced.padding.is_empty());
+        }
+        // ipv6 & udp (complete udp header)
+        {
+            let builder = PacketBuilder::ipv6([1;16], [2;16], 1)
+                .udp(21, 1234);
+            let mut packet = Vec::with_capacity(builder.size(4));
+            builder.write(&mut packet, &[1,2,3,4]).unwrap();
+            let quoted = &packet[..40 + 8 + 2];
+
+            let sliced = SlicedPacket::from_icmp_error_payload(quoted).unwrap();
+            assert!(sliced.ip.is_some());
+            assert_eq!(
+                Some(1234),
+                sliced.transport.as_ref().map(|t| match t {
+                    TransportSlice::Udp(udp) => udp.destination_port(),
+                    _ => panic!("unexpected transport slice"),
+                })
+            );
+            assert_eq!(&[1,2], sliced.payload);
+        }
+        // truncated ip headers are still an error
+        {
+            let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 1)
+                .udp(21, 1234);
+            let mut packet = Vec::with_capacity(builder.size(0));
+            builder.write(&mut packet, &[]).unwrap();
+            assert_matches!(
+                SlicedPacket::from_icmp_error_payload(&packet[..19]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+        }
+    }
+
     #[test]
     fn from_ip_errors() {
         use crate::ReadError::*;
@@ -143,19 +692,31 @@ mod sliced_packet {
         let header = SlicedPacket{
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             transport: None,
-            payload: &[]
+            tunnel: None,
+            payload: &[],
+            trailer: &[],
+            padding: &[],
+            inner: None,
         };
         assert_eq!(
             format!("{:?}", header),
             format!(
-                "SlicedPacket {{ link: {:?}, vlan: {:?}, ip: {:?}, transport: {:?}, payload: {:?} }}",
+                "SlicedPacket {{ link: {:?}, vlan: {:?}, llc: {:?}, arp: {:?}, ip: {:?}, transport: {:?}, tunnel: {:?}, payload: {:?}, trailer: {:?}, padding: {:?}, inner: {:?} }}",
                 header.link,
                 header.vlan,
+                header.llc,
+                header.arp,
                 header.ip,
                 header.transport,
-                header.payload
+                header.tunnel,
+                header.payload,
+                header.trailer,
+                header.padding,
+                header.inner
             )
         );
     }
@@ -165,9 +726,15 @@ mod sliced_packet {
         let header = SlicedPacket{
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             transport: None,
-            payload: &[]
+            tunnel: None,
+            payload: &[],
+            trailer: &[],
+            padding: &[],
+            inner: None,
         };
         assert_eq!(header.clone(), header);
     }
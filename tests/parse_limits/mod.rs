@@ -0,0 +1,337 @@
+use super::*;
+
+fn limits(f: impl FnOnce(&mut ParseLimits)) -> ParseLimits {
+    let mut result = ParseLimits::default();
+    f(&mut result);
+    result
+}
+
+fn assert_limit_exceeded<T: std::fmt::Debug>(expected: ParseLimit, result: Result<T, ReadError>) {
+    match result {
+        Err(ReadError::ParseLimitExceeded(limit)) => assert_eq!(expected, limit),
+        other => panic!("expected {:?} to be exceeded, got {:?}", expected, other),
+    }
+}
+
+#[test]
+fn default() {
+    let limits = ParseLimits::default();
+    assert_eq!(ParseLimits::DEFAULT, limits);
+    assert_eq!(8, limits.max_vlan_depth);
+    assert_eq!(&VlanHeader::VLAN_ETHER_TYPES[..], limits.vlan_tpids);
+    assert_eq!(usize::MAX, limits.max_ipv6_extension_headers);
+    assert_eq!(8, limits.max_tunnel_depth);
+    assert_eq!(usize::MAX, limits.max_option_bytes);
+    assert!(!limits.decode_udp_tunnels);
+    assert!(!limits.decode_ip_in_ip);
+    assert_eq!(None, limits.esp_null_icv_len);
+    assert!(limits.trim_ip_payload);
+}
+
+#[test]
+fn vlan_depth() {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .double_vlan(1, 2)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::new();
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+
+    for max in 0..2 {
+        let limits = limits(|l| l.max_vlan_depth = max);
+        assert_limit_exceeded(ParseLimit::VlanDepth, SlicedPacket::from_ethernet_with_limits(&packet, limits));
+        assert_limit_exceeded(ParseLimit::VlanDepth, PacketHeaders::from_ethernet_slice_with_limits(&packet, limits));
+        assert_limit_exceeded(ParseLimit::VlanDepth, SlicedPacket::from_ether_type_with_limits(ether_type::VLAN_TAGGED_FRAME, &packet[14..], limits));
+        assert_limit_exceeded(ParseLimit::VlanDepth, PacketHeaders::from_ether_type_with_limits(ether_type::VLAN_TAGGED_FRAME, &packet[14..], limits));
+    }
+    let limits_ok = limits(|l| l.max_vlan_depth = 2);
+    assert_eq!(
+        SlicedPacket::from_ethernet(&packet).unwrap(),
+        SlicedPacket::from_ethernet_with_limits(&packet, limits_ok).unwrap()
+    );
+    assert_eq!(
+        PacketHeaders::from_ethernet_slice(&packet).unwrap(),
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits_ok).unwrap()
+    );
+
+    // a single vlan header only requires a depth of 1
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(1)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::new();
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    assert!(SlicedPacket::from_ethernet_with_limits(&packet, limits(|l| l.max_vlan_depth = 1)).is_ok());
+    assert!(PacketHeaders::from_ethernet_slice_with_limits(&packet, limits(|l| l.max_vlan_depth = 1)).is_ok());
+    assert_limit_exceeded(ParseLimit::VlanDepth, SlicedPacket::from_ethernet_with_limits(&packet, limits(|l| l.max_vlan_depth = 0)));
+    assert_limit_exceeded(ParseLimit::VlanDepth, PacketHeaders::from_ethernet_slice_with_limits(&packet, limits(|l| l.max_vlan_depth = 0)));
+
+    // stacks of more then two vlan headers
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .vlan(VlanHeader::Multi(vec![Default::default();4]))
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::new();
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    for max in 0..4 {
+        let limits = limits(|l| l.max_vlan_depth = max);
+        assert_limit_exceeded(ParseLimit::VlanDepth, SlicedPacket::from_ethernet_with_limits(&packet, limits));
+        assert_limit_exceeded(ParseLimit::VlanDepth, PacketHeaders::from_ethernet_slice_with_limits(&packet, limits));
+    }
+    assert!(SlicedPacket::from_ethernet_with_limits(&packet, limits(|l| l.max_vlan_depth = 4)).is_ok());
+    assert!(PacketHeaders::from_ethernet_slice_with_limits(&packet, limits(|l| l.max_vlan_depth = 4)).is_ok());
+}
+
+#[test]
+fn ipv4_option_bytes() {
+    let mut ip = Ipv4Header::new(8, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2]);
+    ip.set_options(&[1,1,1,1,1,1,1,0]).unwrap();
+    let udp = UdpHeader{ source_port: 1, destination_port: 2, length: 8, checksum: 0 };
+    let mut packet = Vec::new();
+    ip.write(&mut packet).unwrap();
+    udp.write(&mut packet).unwrap();
+
+    let limits_ok = limits(|l| l.max_option_bytes = 8);
+    assert!(SlicedPacket::from_ip_with_limits(&packet, limits_ok).is_ok());
+    assert!(PacketHeaders::from_ip_slice_with_limits(&packet, limits_ok).is_ok());
+
+    let limits_exceeded = limits(|l| l.max_option_bytes = 7);
+    assert_limit_exceeded(ParseLimit::OptionBytes, SlicedPacket::from_ip_with_limits(&packet, limits_exceeded));
+    assert_limit_exceeded(ParseLimit::OptionBytes, PacketHeaders::from_ip_slice_with_limits(&packet, limits_exceeded));
+    assert_limit_exceeded(ParseLimit::OptionBytes, SlicedPacket::from_ether_type_with_limits(ether_type::IPV4, &packet, limits_exceeded));
+    assert_limit_exceeded(ParseLimit::OptionBytes, PacketHeaders::from_ether_type_with_limits(ether_type::IPV4, &packet, limits_exceeded));
+}
+
+#[test]
+fn tcp_option_bytes() {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(21, 1234, 1, 1024)
+        .options(&[TcpOptionElement::MaximumSegmentSize(1400)])
+        .unwrap();
+    let mut packet = Vec::new();
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+
+    let limits_ok = limits(|l| l.max_option_bytes = 4);
+    assert!(SlicedPacket::from_ip_with_limits(&packet, limits_ok).is_ok());
+    assert!(PacketHeaders::from_ip_slice_with_limits(&packet, limits_ok).is_ok());
+
+    let limits_exceeded = limits(|l| l.max_option_bytes = 3);
+    assert_limit_exceeded(ParseLimit::OptionBytes, SlicedPacket::from_ip_with_limits(&packet, limits_exceeded));
+    assert_limit_exceeded(ParseLimit::OptionBytes, PacketHeaders::from_ip_slice_with_limits(&packet, limits_exceeded));
+}
+
+/// IPv6 packet with a destination options & routing header followed by an udp header.
+fn ipv6_packet() -> Vec<u8> {
+    let ip = Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 8 + 8 + 8,
+        next_header: ip_number::IPV6_DEST_OPTIONS,
+        hop_limit: 64,
+        source: [1;16],
+        destination: [2;16],
+    };
+    let mut packet = Vec::new();
+    ip.write(&mut packet).unwrap();
+    // destination options with 6 bytes of padding
+    packet.extend_from_slice(&[ip_number::IPV6_ROUTE, 0, 1, 4, 0, 0, 0, 0]);
+    // routing header
+    packet.extend_from_slice(&[ip_number::UDP, 0, 0, 0, 0, 0, 0, 0]);
+    UdpHeader{ source_port: 1, destination_port: 2, length: 8, checksum: 0 }.write(&mut packet).unwrap();
+    packet
+}
+
+#[test]
+fn ipv6_extension_headers() {
+    let packet = ipv6_packet();
+
+    let limits_ok = limits(|l| l.max_ipv6_extension_headers = 2);
+    assert!(SlicedPacket::from_ip_with_limits(&packet, limits_ok).unwrap().transport.is_some());
+    assert!(PacketHeaders::from_ip_slice_with_limits(&packet, limits_ok).unwrap().transport.is_some());
+
+    let limits_exceeded = limits(|l| l.max_ipv6_extension_headers = 1);
+    assert_limit_exceeded(ParseLimit::Ipv6ExtensionHeaders, SlicedPacket::from_ip_with_limits(&packet, limits_exceeded));
+    assert_limit_exceeded(ParseLimit::Ipv6ExtensionHeaders, PacketHeaders::from_ip_slice_with_limits(&packet, limits_exceeded));
+    assert_limit_exceeded(
+        ParseLimit::Ipv6ExtensionHeaders,
+        Ipv6ExtensionsSlice::from_slice_with_limits(ip_number::IPV6_DEST_OPTIONS, &packet[Ipv6Header::SERIALIZED_SIZE..], limits_exceeded)
+    );
+}
+
+#[test]
+fn ipv6_extension_headers_chain() {
+    // a long chain of destination options headers is only bounded by the
+    // limit when slicing
+    let mut data = Vec::new();
+    for _ in 0..100 {
+        data.extend_from_slice(&[ip_number::IPV6_DEST_OPTIONS, 0, 1, 4, 0, 0, 0, 0]);
+    }
+    data.extend_from_slice(&[ip_number::UDP, 0, 1, 4, 0, 0, 0, 0]);
+
+    let (_, next_header, rest) = Ipv6ExtensionsSlice::from_slice(ip_number::IPV6_DEST_OPTIONS, &data).unwrap();
+    assert_eq!(ip_number::UDP, next_header);
+    assert!(rest.is_empty());
+
+    let limits = limits(|l| l.max_ipv6_extension_headers = IPV6_MAX_NUM_HEADER_EXTENSIONS);
+    assert_limit_exceeded(
+        ParseLimit::Ipv6ExtensionHeaders,
+        Ipv6ExtensionsSlice::from_slice_with_limits(ip_number::IPV6_DEST_OPTIONS, &data, limits)
+    );
+}
+
+#[test]
+fn ipv6_option_bytes() {
+    let packet = ipv6_packet();
+
+    let limits_ok = limits(|l| l.max_option_bytes = 6);
+    assert!(SlicedPacket::from_ip_with_limits(&packet, limits_ok).is_ok());
+    assert!(PacketHeaders::from_ip_slice_with_limits(&packet, limits_ok).is_ok());
+
+    let limits_exceeded = limits(|l| l.max_option_bytes = 5);
+    assert_limit_exceeded(ParseLimit::OptionBytes, SlicedPacket::from_ip_with_limits(&packet, limits_exceeded));
+    assert_limit_exceeded(ParseLimit::OptionBytes, PacketHeaders::from_ip_slice_with_limits(&packet, limits_exceeded));
+}
+
+/// IPv4 packet with the given protocol & payload.
+fn ipv4_packet(protocol: IpNumber, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ipv4Header::new(payload.len() as u16, 20, protocol, [1,2,3,4], [5,6,7,8])
+        .write(&mut packet)
+        .unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// IPv6 packet with the given next header & payload.
+fn ipv6_outer_packet(next_header: u8, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: payload.len() as u16,
+        next_header,
+        hop_limit: 64,
+        source: [1;16],
+        destination: [2;16],
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+#[test]
+fn ip_in_ip() {
+    let inner_v6 = ipv6_packet();
+    let inner_v4 = {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([9,9,9,9], [8,8,8,8], 20)
+            .udp(1, 2)
+            .write(&mut packet, &[1,2,3])
+            .unwrap();
+        packet
+    };
+    let enabled = limits(|l| l.decode_ip_in_ip = true);
+
+    // 4in4, 6in4, 4in6 & 6in6
+    for (packet, inner, inner_is_v4) in &[
+        (ipv4_packet(IpNumber::IPv4, &inner_v4), &inner_v4, true),
+        (ipv4_packet(IpNumber::Ipv6, &inner_v6), &inner_v6, false),
+        (ipv6_outer_packet(ip_number::IPV4, &inner_v4), &inner_v4, true),
+        (ipv6_outer_packet(ip_number::IPV6, &inner_v6), &inner_v6, false),
+    ] {
+        // not decoded by default
+        {
+            let sliced = SlicedPacket::from_ip(packet).unwrap();
+            assert_eq!(None, sliced.inner);
+            let headers = PacketHeaders::from_ip_slice(packet).unwrap();
+            assert_eq!(None, headers.inner);
+        }
+
+        // slicing
+        {
+            let sliced = SlicedPacket::from_ip_with_limits(packet, enabled).unwrap();
+            assert!(sliced.ip.is_some());
+            assert_eq!(None, sliced.tunnel);
+            assert_eq!(&inner[..], sliced.payload);
+            let inner_sliced = sliced.inner.unwrap();
+            if *inner_is_v4 {
+                assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+            } else {
+                assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv6(_, _)));
+            }
+            assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+        }
+
+        // decoding
+        {
+            let headers = PacketHeaders::from_ip_slice_with_limits(packet, enabled).unwrap();
+            assert!(headers.ip.is_some());
+            assert_eq!(None, headers.tunnel);
+            assert_eq!(&inner[..], headers.payload.slice());
+            let inner_headers = headers.inner.unwrap();
+            assert_eq!(*inner_is_v4, matches!(inner_headers.ip, Some(IpHeader::Version4(_, _))));
+            assert_matches!(inner_headers.transport, Some(TransportHeader::Udp(_)));
+        }
+    }
+}
+
+#[test]
+fn tunnel_depth_default() {
+    // 1000 nested GRE in IPv4 packets
+    let mut packet = ipv4_packet(IpNumber::Udp, &[0,1,0,2,0,8,0,0]);
+    for _ in 0..1000 {
+        let mut gre = GreHeader{
+            protocol_type: ether_type::IPV4,
+            ..Default::default()
+        }.to_bytes();
+        gre.extend_from_slice(&packet);
+        packet = ipv4_packet(IpNumber::Gre, &gre);
+    }
+
+    // the default limits stop the decoding after 8 levels
+    assert_limit_exceeded(ParseLimit::TunnelDepth, SlicedPacket::from_ip(&packet));
+    assert_limit_exceeded(ParseLimit::TunnelDepth, PacketHeaders::from_ip_slice(&packet));
+}
+
+#[test]
+fn ip_in_ip_depth() {
+    // 4in6in4
+    let packet = ipv4_packet(
+        IpNumber::Ipv6,
+        &ipv6_outer_packet(ip_number::IPV4, &ipv4_packet(IpNumber::Udp, &[0,1,0,2,0,8,0,0]))
+    );
+
+    for max in 0..2 {
+        let limits = limits(|l| {
+            l.decode_ip_in_ip = true;
+            l.max_tunnel_depth = max;
+        });
+        assert_limit_exceeded(ParseLimit::TunnelDepth, SlicedPacket::from_ip_with_limits(&packet, limits));
+        assert_limit_exceeded(ParseLimit::TunnelDepth, PacketHeaders::from_ip_slice_with_limits(&packet, limits));
+    }
+
+    let limits_ok = limits(|l| {
+        l.decode_ip_in_ip = true;
+        l.max_tunnel_depth = 2;
+    });
+    let sliced = SlicedPacket::from_ip_with_limits(&packet, limits_ok).unwrap();
+    let innermost = sliced.inner.unwrap().inner.unwrap();
+    assert_matches!(innermost.transport, Some(TransportSlice::Udp(_)));
+
+    let headers = PacketHeaders::from_ip_slice_with_limits(&packet, limits_ok).unwrap();
+    let innermost = headers.inner.unwrap().inner.unwrap();
+    assert_matches!(innermost.transport, Some(TransportHeader::Udp(_)));
+
+    // truncated inner packet (outer headers & payload are kept)
+    let packet = ipv4_packet(IpNumber::IPv4, &[0x45, 0]);
+    let enabled = limits(|l| l.decode_ip_in_ip = true);
+    let sliced = SlicedPacket::from_ip_with_limits(&packet, enabled).unwrap();
+    assert!(sliced.inner.is_none());
+    assert_eq!(sliced.payload, &[0x45, 0]);
+    let headers = PacketHeaders::from_ip_slice_with_limits(&packet, enabled).unwrap();
+    assert!(headers.inner.is_none());
+    assert_eq!(Payload::Unknown(ip_number::IPV4, &[0x45, 0]), headers.payload);
+}
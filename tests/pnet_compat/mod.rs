@@ -0,0 +1,106 @@
+use super::*;
+use pnet_packet::Packet;
+use pnet_packet::ethernet::{EthernetPacket, EtherTypes};
+use pnet_packet::ipv4::Ipv4Packet;
+use pnet_packet::ip::IpNextHeaderProtocols;
+use pnet_packet::tcp::TcpPacket;
+use std::convert::TryFrom;
+
+fn tcp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(1000, 80, 100, 1024)
+        .syn();
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+#[test]
+fn ethernet() {
+    let packet = tcp_packet();
+    let expected = Ethernet2Header::from_slice(&packet).unwrap().0;
+
+    // pnet -> etherparse
+    let pnet = EthernetPacket::new(&packet).unwrap();
+    assert_eq!(expected, Ethernet2Header::try_from(&pnet).unwrap());
+    let slice = Ethernet2HeaderSlice::try_from(&pnet).unwrap();
+    assert_eq!(expected, slice.to_header());
+    assert_eq!(EtherTypes::Ipv4, pnet.get_ethertype());
+
+    // etherparse -> pnet
+    let from_slice = EthernetPacket::from(&slice);
+    assert_eq!(&packet[..Ethernet2Header::SERIALIZED_SIZE], from_slice.packet());
+    let from_header = EthernetPacket::from(&expected);
+    assert_eq!(&packet[..Ethernet2Header::SERIALIZED_SIZE], from_header.packet());
+    assert_eq!(expected.source, from_header.get_source().octets());
+}
+
+#[test]
+fn ipv4() {
+    let packet = tcp_packet();
+    let ip_data = &packet[Ethernet2Header::SERIALIZED_SIZE..];
+    let expected = Ipv4Header::from_slice(ip_data).unwrap().0;
+
+    // pnet -> etherparse
+    let pnet = Ipv4Packet::new(ip_data).unwrap();
+    assert_eq!(expected, Ipv4Header::try_from(&pnet).unwrap());
+    let slice = Ipv4HeaderSlice::try_from(&pnet).unwrap();
+    assert_eq!(expected, slice.to_header());
+    assert_eq!(IpNextHeaderProtocols::Tcp, pnet.get_next_level_protocol());
+
+    // etherparse -> pnet
+    let from_slice = Ipv4Packet::from(&slice);
+    assert_eq!(&ip_data[..expected.header_len()], from_slice.packet());
+    let from_header = Ipv4Packet::try_from(&expected).unwrap();
+    assert_eq!(&ip_data[..expected.header_len()], from_header.packet());
+    assert_eq!(expected.time_to_live, from_header.get_ttl());
+
+    // invalid header in pnet packet
+    {
+        let mut data = ip_data.to_vec();
+        data[0] = 0x65; // ip version 6
+        let pnet = Ipv4Packet::new(&data).unwrap();
+        assert_matches!(Ipv4Header::try_from(&pnet), Err(ReadError::Ipv4UnexpectedVersion(6)));
+        assert_matches!(Ipv4HeaderSlice::try_from(&pnet), Err(ReadError::Ipv4UnexpectedVersion(6)));
+    }
+
+    // header with values that can not be serialized
+    {
+        let mut header = expected.clone();
+        header.fragments_offset = 0xffff;
+        assert_matches!(Ipv4Packet::try_from(&header), Err(WriteError::ValueError(_)));
+    }
+}
+
+#[test]
+fn tcp() {
+    let packet = tcp_packet();
+    let tcp_data = &packet[Ethernet2Header::SERIALIZED_SIZE + Ipv4Header::SERIALIZED_SIZE..];
+    let expected = TcpHeader::from_slice(tcp_data).unwrap().0;
+
+    // pnet -> etherparse
+    let pnet = TcpPacket::new(tcp_data).unwrap();
+    assert_eq!(expected, TcpHeader::try_from(&pnet).unwrap());
+    let slice = TcpHeaderSlice::try_from(&pnet).unwrap();
+    assert_eq!(expected, slice.to_header());
+    assert_eq!(expected.sequence_number, pnet.get_sequence());
+
+    // etherparse -> pnet
+    let len = usize::from(expected.header_len());
+    let from_slice = TcpPacket::from(&slice);
+    assert_eq!(&tcp_data[..len], from_slice.packet());
+    let from_header = TcpPacket::from(&expected);
+    assert_eq!(&tcp_data[..len], from_header.packet());
+    assert_eq!(expected.destination_port, from_header.get_destination());
+
+    // invalid data offset
+    {
+        let mut data = tcp_data.to_vec();
+        data[12] = 0x10; // data offset 1
+        let pnet = TcpPacket::new(&data).unwrap();
+        assert_matches!(TcpHeader::try_from(&pnet), Err(ReadError::TcpDataOffsetTooSmall(1)));
+        assert_matches!(TcpHeaderSlice::try_from(&pnet), Err(ReadError::TcpDataOffsetTooSmall(1)));
+    }
+}
@@ -6,12 +6,27 @@ pub fn error_field_any() -> impl Strategy<Value = ErrorField> {
     use ErrorField::*;
     prop_oneof![
         Just(Ipv4PayloadLength),
-        Just(Ipv4Dscp),
-        Just(Ipv4Ecn),
         Just(Ipv4FragmentsOffset),
         Just(Ipv6FlowLabel),
         Just(VlanTagPriorityCodePoint),
-        Just(VlanTagVlanId)
+        Just(VlanTagVlanId),
+        Just(MldQuerierRobustnessVariable),
+        Just(IgmpQuerierRobustnessVariable),
+        Just(VxlanVni),
+        Just(GeneveVersion),
+        Just(GeneveVni),
+        Just(GtpuPduType),
+        Just(GtpuQosFlowIdentifier),
+        Just(PppoeVersion),
+        Just(PppoeType),
+        Just(PbbItagPriorityCodePoint),
+        Just(PbbItagIsid),
+        Just(NvgreVsid),
+        Just(VxlanGpeVni),
+        Just(Ipv4TimestampOverflow),
+        Just(Ipv4TimestampFlag),
+        Just(Dscp),
+        Just(Ecn)
     ]
 }
 
@@ -53,14 +68,18 @@ pub static ETHERNET_KNOWN_ETHER_TYPES: &'static [u16] = &[
     ether_type::IPV6,
     ether_type::VLAN_TAGGED_FRAME,
     ether_type::PROVIDER_BRIDGING,
-    ether_type::VLAN_DOUBLE_TAGGED_FRAME
+    ether_type::VLAN_DOUBLE_TAGGED_FRAME,
+    ether_type::ARP,
+    ether_type::PPPOE_DISCOVERY,
+    ether_type::PPPOE_SESSION,
+    ether_type::PROVIDER_BACKBONE_BRIDGING
 ];
 
 prop_compose! {
     pub(crate) fn ethernet_2_unknown()(
         source in prop::array::uniform6(any::<u8>()),
         dest in prop::array::uniform6(any::<u8>()),
-        ether_type in any::<u16>().prop_filter("ether_type must be unknown",
+        ether_type in (LlcHeader::ETHER_TYPE_MIN..=u16::MAX).prop_filter("ether_type must be unknown",
             |v| !ETHERNET_KNOWN_ETHER_TYPES.iter().any(|&x| v == &x)))
         -> Ethernet2Header
     {
@@ -77,7 +96,7 @@ prop_compose! {
         priority_code_point in prop::bits::u8::between(0,3),
         drop_eligible_indicator in any::<bool>(),
         vlan_identifier in prop::bits::u16::between(0,12),
-        ether_type in any::<u16>().prop_filter("ether_type must be unknown",
+        ether_type in (LlcHeader::ETHER_TYPE_MIN..=u16::MAX).prop_filter("ether_type must be unknown",
             |v| !ETHERNET_KNOWN_ETHER_TYPES.iter().any(|&x| v == &x)))
         -> SingleVlanHeader
     {
@@ -85,7 +104,8 @@ prop_compose! {
             priority_code_point: priority_code_point,
             drop_eligible_indicator: drop_eligible_indicator,
             vlan_identifier: vlan_identifier,
-            ether_type: ether_type
+            ether_type: ether_type,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
         }
     }
 }
@@ -102,7 +122,8 @@ prop_compose! {
             priority_code_point: priority_code_point,
             drop_eligible_indicator: drop_eligible_indicator,
             vlan_identifier: vlan_identifier,
-            ether_type: ether_type
+            ether_type: ether_type,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
         }
     }
 }
@@ -136,8 +157,11 @@ prop_compose! {
         inner in vlan_single_with(inner_ethertype)
     ) -> DoubleVlanHeader {
         DoubleVlanHeader {
+            inner: SingleVlanHeader {
+                tpid: outer.ether_type,
+                ..inner
+            },
             outer,
-            inner
         }
     }
 }
@@ -183,8 +207,8 @@ prop_compose! {
             result.set_options(&options[..len]).unwrap();
         }
         
-        result.differentiated_services_code_point = dscp;
-        result.explicit_congestion_notification = ecn;
+        result.differentiated_services_code_point = Dscp::try_new(dscp).unwrap();
+        result.explicit_congestion_notification = Ecn::try_new(ecn).unwrap();
         result.payload_len = payload_len;
         result.identification = identification;
         result.dont_fragment = dont_fragment;
@@ -212,6 +236,8 @@ prop_compose! {
 static IPV4_KNOWN_PROTOCOLS: &'static [u8] = &[
     ip_number::UDP,
     ip_number::TCP,
+    ip_number::IGMP,
+    ip_number::GRE,
     ip_number::AUTH,
 ];
 
@@ -309,6 +335,8 @@ prop_compose! {
 static IPV6_KNOWN_NEXT_HEADERS: &'static [u8] = &[
     ip_number::UDP,
     ip_number::TCP,
+    ip_number::IGMP,
+    ip_number::GRE,
     ip_number::IPV6_HOP_BY_HOP,
     ip_number::IPV6_ROUTE,
     ip_number::IPV6_FRAG,
@@ -393,6 +421,12 @@ prop_compose! {
         final_destination_options in ipv6_raw_extension_any()
     ) -> Ipv6Extensions
     {
+        // destination options after the fragment or authentication header
+        // in packets without a routing header (only distinguishable from
+        // the first destination options header if a header precedes it)
+        let has_unrouted_final_destination_options = has_final_destination_options &&
+            !has_routing &&
+            (has_destination_options || has_fragment || has_auth);
         let mut result = Ipv6Extensions {
             hop_by_hop_options: if has_hop_by_hop_options {
                 Some(hop_by_hop_options)
@@ -409,7 +443,7 @@ prop_compose! {
                     Ipv6RoutingExtensions{
                         routing,
                         final_destination_options: if has_final_destination_options {
-                            Some(final_destination_options)
+                            Some(final_destination_options.clone())
                         } else {
                             None
                         }
@@ -428,6 +462,11 @@ prop_compose! {
             } else {
                 None
             },
+            final_destination_options: if has_unrouted_final_destination_options {
+                Some(final_destination_options)
+            } else {
+                None
+            },
         };
         result.set_next_headers(next_header);
         result
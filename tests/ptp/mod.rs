@@ -0,0 +1,171 @@
+use super::*;
+use etherparse::ptp::*;
+
+/// PTP message with the given message type, flags, correction field,
+/// sequence id & body.
+fn ptp_message(message_type: u8, flags: u16, correction: i64, sequence_id: u16, body: &[u8]) -> Vec<u8> {
+    let mut result = vec![0x10 | message_type, 0x12];
+    result.extend_from_slice(&((34 + body.len()) as u16).to_be_bytes());
+    result.extend_from_slice(&[24, 0]);
+    result.extend_from_slice(&flags.to_be_bytes());
+    result.extend_from_slice(&correction.to_be_bytes());
+    result.extend_from_slice(&[0, 0, 0, 0]);
+    result.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 0, 3]);
+    result.extend_from_slice(&sequence_id.to_be_bytes());
+    result.extend_from_slice(&[5, 0xfd]);
+    result.extend_from_slice(body);
+    result
+}
+
+/// Serialized timestamp.
+fn timestamp(seconds: u64, nanoseconds: u32) -> Vec<u8> {
+    let mut result = seconds.to_be_bytes()[2..].to_vec();
+    result.extend_from_slice(&nanoseconds.to_be_bytes());
+    result
+}
+
+#[test]
+fn header_fields() {
+    let mut bytes = ptp_message(
+        ptp_message_type::SYNC,
+        ptp_flags::TWO_STEP | ptp_flags::UNICAST | ptp_flags::PTP_TIMESCALE,
+        (1500 << 16) | 0x8000,
+        0x1234,
+        &timestamp(0x0102_0304_0506, 999_999_999)
+    );
+    // ethernet padding
+    bytes.extend_from_slice(&[0;2]);
+
+    let message = PtpMessageSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..44], message.slice());
+    assert_eq!(1, message.transport_specific());
+    assert_eq!(ptp_message_type::SYNC, message.message_type());
+    assert!(message.is_event());
+    assert_eq!(1, message.minor_version());
+    assert_eq!(2, message.version());
+    assert_eq!(44, message.message_length());
+    assert_eq!(24, message.domain_number());
+    assert_eq!(0, message.minor_sdo_id());
+    assert_eq!(ptp_flags::TWO_STEP | ptp_flags::UNICAST | ptp_flags::PTP_TIMESCALE, message.flags());
+    assert!(message.two_step());
+    assert!(message.unicast());
+    assert_eq!((1500 << 16) | 0x8000, message.correction_field());
+    assert_eq!(1500, message.correction_ns());
+    assert_eq!(&[0, 0, 0, 0], message.message_type_specific());
+    assert_eq!([1, 2, 3, 4, 5, 6, 7, 8], message.clock_identity());
+    assert_eq!(3, message.port_number());
+    assert_eq!(0x1234, message.sequence_id());
+    assert_eq!(5, message.control_field());
+    assert_eq!(-3, message.log_message_interval());
+    assert_eq!(&bytes[34..44], message.body());
+    let expected = PtpTimestamp{ seconds: 0x0102_0304_0506, nanoseconds: 999_999_999 };
+    assert_eq!(Some(expected), message.timestamp());
+    assert_eq!(Some(expected), message.origin_timestamp());
+    assert_eq!(message.clone(), message);
+
+    // negative correction
+    let bytes = ptp_message(ptp_message_type::SIGNALING, 0, -(3 << 16) - 1, 0, &[]);
+    assert_eq!(-4, PtpMessageSlice::from_slice(&bytes).unwrap().correction_ns());
+}
+
+#[test]
+fn timestamps() {
+    use ptp_message_type::*;
+
+    let ts = timestamp(10, 20);
+    let expected = Some(PtpTimestamp{ seconds: 10, nanoseconds: 20 });
+    for &(message_type, has_timestamp, is_origin) in &[
+        (SYNC, true, true),
+        (DELAY_REQ, true, true),
+        (PDELAY_REQ, true, true),
+        (PDELAY_RESP, true, false),
+        (FOLLOW_UP, true, true),
+        (DELAY_RESP, true, false),
+        (PDELAY_RESP_FOLLOW_UP, true, false),
+        (ANNOUNCE, true, true),
+        (SIGNALING, false, false),
+        (MANAGEMENT, false, false),
+    ] {
+        let bytes = ptp_message(message_type, 0, 0, 0, &ts);
+        let message = PtpMessageSlice::from_slice(&bytes).unwrap();
+        assert_eq!(message_type < FOLLOW_UP, message.is_event());
+        assert_eq!(if has_timestamp { expected } else { None }, message.timestamp());
+        assert_eq!(if is_origin { expected } else { None }, message.origin_timestamp());
+    }
+
+    assert_eq!(
+        std::time::Duration::new(10, 20),
+        PtpTimestamp{ seconds: 10, nanoseconds: 20 }.to_duration()
+    );
+}
+
+#[test]
+fn from_slice_errors() {
+    let bytes = ptp_message(ptp_message_type::SYNC, 0, 0, 0, &timestamp(1, 2));
+    for len in 0..bytes.len() {
+        assert_matches!(
+            PtpMessageSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+    assert_matches!(
+        PtpMessageSlice::from_slice(&bytes[..33]),
+        Err(ReadError::UnexpectedEndOfSlice(PtpMessageSlice::HEADER_LEN))
+    );
+    assert_matches!(
+        PtpMessageSlice::from_slice(&bytes[..43]),
+        Err(ReadError::UnexpectedEndOfSlice(44))
+    );
+
+    // message length too small for the timestamp
+    let mut short = bytes.clone();
+    short[3] = 34;
+    assert_matches!(
+        PtpMessageSlice::from_slice(&short),
+        Err(ReadError::UnexpectedEndOfSlice(44))
+    );
+
+    // message length larger than the slice
+    let mut long = bytes.clone();
+    long[3] = 50;
+    assert_matches!(
+        PtpMessageSlice::from_slice(&long),
+        Err(ReadError::UnexpectedEndOfSlice(50))
+    );
+
+    // signaling messages only require the header
+    let signaling = ptp_message(ptp_message_type::SIGNALING, 0, 0, 0, &[]);
+    assert!(PtpMessageSlice::from_slice(&signaling).is_ok());
+}
+
+#[test]
+fn from_ethernet() {
+    let mut packet = Ethernet2Header{
+        source: [1;6],
+        destination: [0x01, 0x1b, 0x19, 0, 0, 0],
+        ether_type: ether_type::PTP,
+    }.to_bytes().to_vec();
+    packet.extend_from_slice(&ptp_message(ptp_message_type::SYNC, 0, 0, 7, &timestamp(1, 2)));
+    // ethernet padding
+    packet.extend_from_slice(&[0;2]);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let message = PtpMessageSlice::from_slice(sliced.payload).unwrap();
+    assert_eq!(7, message.sequence_id());
+}
+
+#[test]
+fn from_udp() {
+    let payload = ptp_message(ptp_message_type::FOLLOW_UP, 0, 0, 8, &timestamp(3, 4));
+    let builder = PacketBuilder::
+        ethernet2([1;6], [2;6])
+        .ipv4([192, 168, 1, 1], [224, 0, 1, 129], 1)
+        .udp(PtpMessageSlice::GENERAL_PORT, PtpMessageSlice::GENERAL_PORT);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let message = PtpMessageSlice::from_slice(sliced.payload).unwrap();
+    assert_eq!(8, message.sequence_id());
+    assert_eq!(Some(PtpTimestamp{ seconds: 3, nanoseconds: 4 }), message.origin_timestamp());
+}
@@ -0,0 +1,40 @@
+use super::*;
+use etherparse::raw_socket::RawSocket;
+
+#[test]
+fn open_unknown_interface() {
+    assert!(RawSocket::open("etherparse-none").is_err());
+    assert_eq!(
+        std::io::ErrorKind::InvalidInput,
+        RawSocket::open("lo\0").unwrap_err().kind()
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn send_recv_loopback() {
+    let mut socket = match RawSocket::open("lo") {
+        Ok(value) => value,
+        // opening raw sockets requires elevated privileges
+        Err(_) => return,
+    };
+
+    let builder = PacketBuilder::
+        ethernet2([0;6], [0;6])
+        .ipv4([127,0,0,1], [127,0,0,1], 20)
+        .udp(47123, 47124);
+    socket.send_udp(builder, &[1,2,3,4]).unwrap();
+
+    // the loopback interface delivers the sent frame (other traffic
+    // on the interface is skipped)
+    for _ in 0..1000 {
+        let packet = socket.recv_sliced();
+        if let Ok(SlicedPacket{ transport: Some(TransportSlice::Udp(udp)), payload, .. }) = packet {
+            if 47123 == udp.source_port() && 47124 == udp.destination_port() {
+                assert_eq!(&[1,2,3,4], payload);
+                return;
+            }
+        }
+    }
+    panic!("sent frame was not received");
+}
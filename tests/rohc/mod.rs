@@ -0,0 +1,313 @@
+use super::*;
+use etherparse::rohc::*;
+
+/// Rtp packet with the given sequence number, timestamp & marker.
+fn rtp_ipv4(sn: u16, ts: u32, marker: bool, ttl: u8) -> Vec<u8> {
+    let rtp = RtpHeader{
+        marker,
+        payload_type: 8,
+        sequence_number: sn,
+        timestamp: ts,
+        ssrc: 0x1234_5678,
+        ..Default::default()
+    };
+    let mut payload = rtp.to_bytes().to_vec();
+    payload.extend_from_slice(&[0xaa;20]);
+
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], ttl)
+        .udp(5004, 5006);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+    packet
+}
+
+fn rtp_ipv6(sn: u16, ts: u32) -> Vec<u8> {
+    let rtp = RtpHeader{
+        sequence_number: sn,
+        timestamp: ts,
+        ssrc: 1,
+        ..Default::default()
+    };
+    let mut payload = rtp.to_bytes().to_vec();
+    payload.extend_from_slice(&[1,2,3,4]);
+
+    let builder = PacketBuilder::
+        ipv6([1;16], [2;16], 64)
+        .udp(5004, 5006);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+    packet
+}
+
+/// Compresses & decompresses the packet, checks the result is identical
+/// & returns the compressed packet.
+fn roundtrip(compressor: &mut Compressor, decompressor: &mut Decompressor, packet: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    compressor.compress(packet, &mut compressed);
+    let mut decompressed = Vec::new();
+    decompressor.decompress(&compressed, &mut decompressed).unwrap();
+    assert_eq!(packet, &decompressed[..]);
+    compressed
+}
+
+#[test]
+fn rtp_header() {
+    let header = RtpHeader{
+        padding: true,
+        extension: false,
+        csrc_count: 2,
+        marker: true,
+        payload_type: 0x7f,
+        sequence_number: 0x1234,
+        timestamp: 0x5678_9abc,
+        ssrc: 0xdef0_1234,
+    };
+    let bytes = header.to_bytes();
+    assert_eq!(
+        [0b1010_0010, 0xff, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x12, 0x34],
+        bytes
+    );
+    assert_eq!(Some(header), RtpHeader::from_slice(&bytes));
+    // too short
+    assert_eq!(None, RtpHeader::from_slice(&bytes[..11]));
+    // wrong version
+    let mut bytes = bytes;
+    bytes[0] = 0x40;
+    assert_eq!(None, RtpHeader::from_slice(&bytes));
+}
+
+#[test]
+fn rtp_ipv4_states() {
+    let mut compressor = Compressor::new(MAX_CID);
+    let mut decompressor = Decompressor::new();
+    assert_eq!(None, compressor.context_state(0));
+    assert_eq!(None, decompressor.context_profile(0));
+
+    // ir packets
+    for i in 0..3u16 {
+        let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(i, u32::from(i)*160, false, 64));
+        assert_eq!(&[0xfd, PROFILE_RTP], &compressed[..2]);
+    }
+    assert_eq!(Some((PROFILE_RTP, CompressorState::SecondOrder)), compressor.context_state(0));
+    assert_eq!(Some(PROFILE_RTP), decompressor.context_profile(0));
+
+    // uo-0 (with the random ip id & udp checksum appended)
+    let original = rtp_ipv4(3, 480, false, 64);
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &original);
+    assert_eq!(0, compressed[0] & 0x80);
+    assert_eq!(1 + 2 + 2 + 20, compressed.len());
+
+    // uo-1 (marker set)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(4, 640, true, 64));
+    assert_eq!(0x80, compressed[0] & 0xc0);
+
+    // uo-1 (timestamp jump)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(5, 1600, false, 64));
+    assert_eq!(0x80, compressed[0] & 0xc0);
+
+    // uor-2 (sequence number jump)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(25, 1600 + 20*160, false, 64));
+    assert_eq!(0xc0, compressed[0] & 0xe0);
+
+    // ir-dyn (ttl changed)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(26, 1600 + 21*160, false, 63));
+    assert_eq!(&[0xf8, PROFILE_RTP], &compressed[..2]);
+    assert_eq!(Some((PROFILE_RTP, CompressorState::FirstOrder)), compressor.context_state(0));
+    for i in 27..29u16 {
+        let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(i, 1600 + u32::from(i - 5)*160, false, 63));
+        assert_eq!(0xf8, compressed[0]);
+    }
+    assert_eq!(Some((PROFILE_RTP, CompressorState::SecondOrder)), compressor.context_state(0));
+
+    // ir-dyn (timestamp not a multiple of the stride)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(29, 1600 + 24*160 + 1, false, 63));
+    assert_eq!(0xf8, compressed[0]);
+
+    // sequence number wrap around
+    let mut compressor = Compressor::new(MAX_CID);
+    let mut decompressor = Decompressor::new();
+    for i in 0..40u16 {
+        let sn = i.wrapping_add(0xfff0);
+        roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(sn, u32::from(i)*160, false, 64));
+    }
+}
+
+#[test]
+fn rtp_ipv6_flow() {
+    let mut compressor = Compressor::new(MAX_CID);
+    let mut decompressor = Decompressor::new();
+    for i in 0..10u16 {
+        let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv6(i, u32::from(i)*80));
+        if i >= 3 {
+            // uo-0 + udp checksum
+            assert_eq!(1 + 2 + 4, compressed.len());
+        }
+    }
+}
+
+#[test]
+fn refresh() {
+    let mut compressor = Compressor::new(MAX_CID);
+    let mut decompressor = Decompressor::new();
+    let mut ir_count = 0;
+    for i in 0..600u16 {
+        let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(i, u32::from(i)*160, false, 64));
+        if 0xfd == compressed[0] {
+            ir_count += 1;
+        }
+    }
+    assert_eq!(9, ir_count);
+}
+
+#[test]
+fn uncompressed_profile() {
+    let mut compressor = Compressor::new(MAX_CID);
+    let mut decompressor = Decompressor::new();
+
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(21, 1234, 1, 2000);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+
+    for _ in 0..3 {
+        let compressed = roundtrip(&mut compressor, &mut decompressor, &packet);
+        assert_eq!(&[0xfc, PROFILE_UNCOMPRESSED], &compressed[..2]);
+        assert_eq!(3 + packet.len(), compressed.len());
+    }
+    assert_eq!(Some((PROFILE_UNCOMPRESSED, CompressorState::SecondOrder)), compressor.context_state(0));
+    assert_eq!(Some(PROFILE_UNCOMPRESSED), decompressor.context_profile(0));
+    // normal packets
+    assert_eq!(packet, roundtrip(&mut compressor, &mut decompressor, &packet));
+    // packets that could be confused with ROHC packet types are sent as IR packets
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &[0xf8, 1, 2]);
+    assert_eq!(&[0xfc, PROFILE_UNCOMPRESSED], &compressed[..2]);
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &[]);
+    assert_eq!(3, compressed.len());
+}
+
+#[test]
+fn multiple_contexts() {
+    let mut compressor = Compressor::new(1);
+    let mut decompressor = Decompressor::new();
+
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(21, 1234, 1, 2000);
+    let mut tcp = Vec::with_capacity(builder.size(4));
+    builder.write(&mut tcp, &[1,2,3,4]).unwrap();
+
+    // first flow uses cid 0 (no add-cid octet)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(0, 0, false, 64));
+    assert_eq!(0xfd, compressed[0]);
+    // second flow uses cid 1
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &tcp);
+    assert_eq!(&[0xe1, 0xfc], &compressed[..2]);
+    assert_eq!(Some((PROFILE_UNCOMPRESSED, CompressorState::InitializationAndRefresh)), compressor.context_state(1));
+    // both flows continue independently
+    for i in 1..5u16 {
+        roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(i, u32::from(i)*160, false, 64));
+        roundtrip(&mut compressor, &mut decompressor, &tcp);
+    }
+    // third flow replaces the least recently used context (cid 0)
+    let compressed = roundtrip(&mut compressor, &mut decompressor, &rtp_ipv6(0, 0));
+    assert_eq!(0xfd, compressed[0]);
+    assert_eq!(Some((PROFILE_RTP, CompressorState::InitializationAndRefresh)), compressor.context_state(0));
+    assert_eq!(Some((PROFILE_UNCOMPRESSED, CompressorState::SecondOrder)), compressor.context_state(1));
+
+    // max cid is limited to MAX_CID
+    let mut compressor = Compressor::new(200);
+    for i in 0..20u32 {
+        let rtp = RtpHeader{ ssrc: i, ..Default::default() };
+        let builder = PacketBuilder::
+            ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(5004, 5004);
+        let mut packet = Vec::with_capacity(builder.size(12));
+        builder.write(&mut packet, &rtp.to_bytes()).unwrap();
+        let mut compressed = Vec::new();
+        compressor.compress(&packet, &mut compressed);
+    }
+    assert!(compressor.context_state(15).is_some());
+    assert!(compressor.context_state(16).is_none());
+}
+
+#[test]
+fn decompress_errors() {
+    let mut compressor = Compressor::new(MAX_CID);
+    let mut decompressor = Decompressor::new();
+    let mut ir = Vec::new();
+    compressor.compress(&rtp_ipv4(0, 0, false, 64), &mut ir);
+
+    // unknown context
+    assert_eq!(Err(RohcError::UnknownContext(0)), decompressor.decompress(&[0x08], &mut Vec::new()));
+    assert_eq!(Err(RohcError::UnknownContext(3)), decompressor.decompress(&[0xe3, 0xf8, 1, 0], &mut Vec::new()));
+
+    // unexpected end
+    assert_eq!(Err(RohcError::UnexpectedEnd), decompressor.decompress(&[], &mut Vec::new()));
+    assert_eq!(Err(RohcError::UnexpectedEnd), decompressor.decompress(&[0xe0, 0xe1], &mut Vec::new()));
+    for len in 1..(3 + 10 + 8 + 5 + 2 + 8) {
+        assert_eq!(Err(RohcError::UnexpectedEnd), decompressor.decompress(&ir[..len], &mut Vec::new()));
+    }
+
+    // unsupported profile & packet types
+    assert_eq!(Err(RohcError::UnsupportedProfile(2)), decompressor.decompress(&[0xfd, 2, 0], &mut Vec::new()));
+    assert_eq!(Err(RohcError::UnsupportedPacketType(0xfe)), decompressor.decompress(&[0xfe, 1], &mut Vec::new()));
+    assert_eq!(Err(RohcError::UnsupportedPacketType(0xfc)), decompressor.decompress(&[0xfc, 1, 0], &mut Vec::new()));
+
+    // unsupported ip version
+    {
+        let mut packet = ir.clone();
+        packet[3] = 5 << 4;
+        assert_eq!(Err(RohcError::UnsupportedIpVersion(5)), decompressor.decompress(&packet, &mut Vec::new()));
+    }
+
+    // crc mismatch in ir packet
+    {
+        let mut packet = ir.clone();
+        packet[2] ^= 1;
+        assert_eq!(Err(RohcError::CrcMismatch), decompressor.decompress(&packet, &mut Vec::new()));
+        assert_eq!(None, decompressor.context_profile(0));
+    }
+
+    // padding is skipped
+    {
+        let mut packet = vec![0xe0, 0xe0];
+        packet.extend_from_slice(&ir);
+        let mut output = Vec::new();
+        decompressor.decompress(&packet, &mut output).unwrap();
+        assert_eq!(rtp_ipv4(0, 0, false, 64), output);
+    }
+
+    // crc mismatch in uo packet
+    {
+        for i in 1..3u16 {
+            roundtrip(&mut compressor, &mut decompressor, &rtp_ipv4(i, u32::from(i)*160, false, 64));
+        }
+        let mut uo = Vec::new();
+        compressor.compress(&rtp_ipv4(3, 480, false, 64), &mut uo);
+        let mut corrupted = uo.clone();
+        corrupted[0] ^= 0b111;
+        let mut output = vec![1];
+        assert_eq!(Err(RohcError::CrcMismatch), decompressor.decompress(&corrupted, &mut output));
+        assert_eq!(vec![1], output);
+        // context unchanged
+        let mut output = Vec::new();
+        decompressor.decompress(&uo, &mut output).unwrap();
+        assert_eq!(rtp_ipv4(3, 480, false, 64), output);
+        // missing appended fields
+        assert_eq!(Err(RohcError::UnexpectedEnd), decompressor.decompress(&uo[..2], &mut Vec::new()));
+    }
+}
+
+#[test]
+fn error_display() {
+    use RohcError::*;
+    assert_eq!("RohcError: Packet ended before all expected fields could be read.", &format!("{}", UnexpectedEnd));
+    assert_eq!("RohcError: Packet type 0xfe is not supported.", &format!("{}", UnsupportedPacketType(0xfe)));
+    assert_eq!("RohcError: Profile 0x02 is not supported.", &format!("{}", UnsupportedProfile(2)));
+    assert_eq!("RohcError: No context established for the CID 3.", &format!("{}", UnknownContext(3)));
+    assert_eq!("RohcError: IP version 5 in the static chain is not supported.", &format!("{}", UnsupportedIpVersion(5)));
+    assert_eq!("RohcError: CRC does not match the decompressed headers.", &format!("{}", CrcMismatch));
+    assert_eq!("RohcError: Payload length 70000 is too large for the decompressed headers.", &format!("{}", PayloadTooLarge(70000)));
+}
@@ -0,0 +1,379 @@
+use super::*;
+use etherparse::sixlowpan::*;
+use std::time::Duration;
+
+const SRC_LINK: LinkAddress = LinkAddress::Extended([0x00, 0x12, 0x4b, 0x00, 0x01, 0x02, 0x03, 0x04]);
+const DST_LINK: LinkAddress = LinkAddress::Short([0x12, 0x34]);
+
+fn ipv6(source: [u8;16], destination: [u8;16]) -> Ipv6Header {
+    Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 0,
+        next_header: ip_number::UDP,
+        hop_limit: 64,
+        source,
+        destination,
+    }
+}
+
+/// Compresses, decompresses & checks that the original headers are restored.
+fn round_trip(iphc: &Iphc, mut ip: Ipv6Header, udp_ports: Option<(u16, u16)>, payload: &[u8]) -> Vec<u8> {
+    let udp = udp_ports.map(|(src, dst)| {
+        ip.next_header = ip_number::UDP;
+        ip.payload_length = (UdpHeader::SERIALIZED_SIZE + payload.len()) as u16;
+        UdpHeader::with_ipv6_checksum(src, dst, &ip, payload).unwrap()
+    });
+    if udp.is_none() {
+        ip.payload_length = payload.len() as u16;
+    }
+    let mut frame = Vec::new();
+    iphc.compress(&ip, udp.as_ref(), SRC_LINK, DST_LINK, &mut frame).unwrap();
+    frame.extend_from_slice(payload);
+
+    let (d_ip, d_udp, d_payload) = iphc.decompress(&frame, SRC_LINK, DST_LINK).unwrap();
+    assert_eq!(ip, d_ip);
+    assert_eq!(udp, d_udp);
+    assert_eq!(payload, d_payload);
+    frame
+}
+
+#[test]
+fn link_address_interface_identifier() {
+    assert_eq!([0x02, 0x12, 0x4b, 0, 1, 2, 3, 4], SRC_LINK.interface_identifier());
+    assert_eq!([0, 0, 0, 0xff, 0xfe, 0, 0x12, 0x34], DST_LINK.interface_identifier());
+}
+
+#[test]
+fn compress_link_local_fully_elided() {
+    let ip = ipv6(
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0x12, 0x34],
+    );
+    let frame = round_trip(&Iphc::default(), ip, Some((0xf0b1, 0xf0b2)), &[1, 2, 3, 4]);
+    // TF=11, NH=1, HLIM=10, SAM=11, DAM=11
+    assert_eq!(&[0x7e, 0x33, 0xf3, 0x12], &frame[..4]);
+    assert_eq!(2 + 1 + 1 + 2 + 4, frame.len());
+}
+
+#[test]
+fn compress_address_modes() {
+    let iphc = Iphc::default();
+    // 16 bit & 64 bit inline interface identifiers
+    let frame = round_trip(
+        &iphc,
+        ipv6(
+            [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0xab, 0xcd],
+            [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+        ),
+        None,
+        &[]
+    );
+    assert_eq!(0x21, frame[1]);
+    // unspecified source & global destination inline
+    let frame = round_trip(
+        &iphc,
+        ipv6(
+            [0;16],
+            [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        ),
+        Some((1000, 2000)),
+        &[9; 10]
+    );
+    assert_eq!(0x40, frame[1]);
+    // multicast modes
+    for (destination, dam) in [
+        ([0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x1a], 0b11),
+        ([0xff, 0x05, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x02, 0x03], 0b10),
+        ([0xff, 0x05, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x02, 0x03, 0x04, 0x05], 0b01),
+        ([0xff, 0x05, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06], 0b00),
+    ] {
+        let frame = round_trip(
+            &iphc,
+            ipv6([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4], destination),
+            Some((5683, 5683)),
+            &[1]
+        );
+        assert_eq!(0x38 | dam, frame[1]);
+    }
+}
+
+#[test]
+fn compress_context() {
+    let mut iphc = Iphc::default();
+    iphc.contexts[3] = Some([0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 1]);
+    let frame = round_trip(
+        &iphc,
+        ipv6(
+            [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 1, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+            [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 1, 0, 0, 0, 0xff, 0xfe, 0, 0x56, 0x78],
+        ),
+        Some((0xf012, 80)),
+        &[1, 2, 3]
+    );
+    // CID, SAC & DAC set
+    assert_eq!(0xf6, frame[1]);
+    assert_eq!(0x33, frame[2]);
+
+    // unknown context
+    let (_, _, _) = iphc.decompress(&frame, SRC_LINK, DST_LINK).unwrap();
+    assert_eq!(
+        Err(SixlowpanError::UnknownContext(3)),
+        Iphc::default().decompress(&frame, SRC_LINK, DST_LINK)
+    );
+}
+
+#[test]
+fn compress_traffic_class_flow_label_hop_limit() {
+    let iphc = Iphc::default();
+    for (traffic_class, flow_label, hop_limit, tf) in [
+        (0, 0, 1, 0b11),
+        (0b1011_1001, 0, 255, 0b10),
+        (0b0000_0010, 0x1_2345, 64, 0b01),
+        (0b1011_1011, 0xf_ffff, 33, 0b00),
+    ] {
+        let mut ip = ipv6(
+            [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+            [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0x12, 0x34],
+        );
+        ip.traffic_class = traffic_class;
+        ip.flow_label = flow_label;
+        ip.hop_limit = hop_limit;
+        let frame = round_trip(&iphc, ip, Some((1, 2)), &[]);
+        assert_eq!(tf, (frame[0] >> 3) & 0b11);
+    }
+}
+
+#[test]
+fn compress_inline_next_header() {
+    let mut ip = ipv6(
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0x12, 0x34],
+    );
+    ip.next_header = ip_number::IPV6_ICMP;
+    let frame = round_trip(&Iphc::default(), ip, None, &[128, 0, 0, 0]);
+    assert_eq!(0, frame[0] & 0b100);
+    assert_eq!(ip_number::IPV6_ICMP, frame[2]);
+}
+
+#[test]
+fn decompress_uncompressed_dispatch() {
+    let ip = ipv6([1;16], [2;16]);
+    let mut frame = vec![DISPATCH_IPV6];
+    ip.write(&mut frame).unwrap();
+    let (d_ip, d_udp, rest) = Iphc::default().decompress(&frame, SRC_LINK, DST_LINK).unwrap();
+    assert_eq!(ip, d_ip);
+    assert_eq!(None, d_udp);
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn decompress_errors() {
+    use SixlowpanError::*;
+    let iphc = Iphc::default();
+    assert_eq!(Err(UnexpectedEnd), iphc.decompress(&[], SRC_LINK, DST_LINK));
+    assert_eq!(Err(UnexpectedEnd), iphc.decompress(&[0x60], SRC_LINK, DST_LINK));
+    assert_eq!(Err(UnsupportedDispatch(0x80)), iphc.decompress(&[0x80, 0], SRC_LINK, DST_LINK));
+    // multicast with DAC set
+    assert_eq!(Err(ReservedAddressMode), iphc.decompress(&[0x7f, 0x3c], SRC_LINK, DST_LINK));
+    // unicast destination with DAC set & DAM 00
+    assert_eq!(Err(ReservedAddressMode), iphc.decompress(&[0x7f, 0x34], SRC_LINK, DST_LINK));
+    // unsupported nhc (extension header)
+    assert_eq!(Err(UnsupportedNextHeader(0xe0)), iphc.decompress(&[0x7e, 0x33, 0xe0], SRC_LINK, DST_LINK));
+    // missing inline fields
+    assert_eq!(Err(UnexpectedEnd), iphc.decompress(&[0x78, 0x00, 17], SRC_LINK, DST_LINK));
+    assert_eq!(
+        "SixlowpanError: Dispatch value 0x80 is not supported.",
+        format!("{}", UnsupportedDispatch(0x80))
+    );
+}
+
+#[test]
+fn fragment_header() {
+    let first = SixlowpanFragmentHeader::First{ datagram_size: 1280, datagram_tag: 0x1234 };
+    let subsequent = SixlowpanFragmentHeader::Subsequent{ datagram_size: 1280, datagram_tag: 0x1234, datagram_offset: 12 };
+    for (header, expected) in [
+        (first, &[0xc5, 0x00, 0x12, 0x34][..]),
+        (subsequent, &[0xe5, 0x00, 0x12, 0x34, 12][..]),
+    ] {
+        let mut bytes = Vec::new();
+        header.write(&mut bytes);
+        assert_eq!(expected, &bytes[..]);
+        assert_eq!(header.header_len(), bytes.len());
+        bytes.push(0xaa);
+        assert_eq!(Some((header, &[0xaa][..])), SixlowpanFragmentHeader::from_slice(&bytes).unwrap());
+        assert_eq!(Err(SixlowpanError::UnexpectedEnd), SixlowpanFragmentHeader::from_slice(&bytes[..header.header_len() - 1]));
+    }
+    assert_eq!(None, SixlowpanFragmentHeader::from_slice(&[0x7e, 0x33]).unwrap());
+}
+
+#[test]
+fn fragment_and_reassemble() {
+    let iphc = Iphc::default();
+    let mut ip = ipv6(
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0x12, 0x34],
+    );
+    let payload: Vec<u8> = (0..300u16).map(|v| v as u8).collect();
+    ip.payload_length = (UdpHeader::SERIALIZED_SIZE + payload.len()) as u16;
+    let udp = UdpHeader::with_ipv6_checksum(1234, 5678, &ip, &payload).unwrap();
+
+    let mut expected = Vec::new();
+    ip.write(&mut expected).unwrap();
+    udp.write(&mut expected).unwrap();
+    expected.extend_from_slice(&payload);
+
+    let frames = iphc.compress_fragmented(&ip, &expected[40..], SRC_LINK, DST_LINK, 0x42, 102).unwrap();
+    assert!(frames.len() > 1);
+    for frame in &frames {
+        assert!(frame.len() <= 102);
+    }
+
+    // reassemble in reverse order
+    let mut reassembler = Reassembler::new(Default::default());
+    for frame in frames[1..].iter().rev() {
+        assert_eq!(None, reassembler.add(&iphc, frame, SRC_LINK, DST_LINK, NOW).unwrap());
+    }
+    assert_eq!(1, reassembler.len());
+    assert_eq!(Some(expected.clone()), reassembler.add(&iphc, &frames[0], SRC_LINK, DST_LINK, NOW).unwrap());
+    assert!(reassembler.is_empty());
+
+    // unfragmented
+    let frames = iphc.compress_fragmented(&ip, &expected[40..], SRC_LINK, DST_LINK, 0x42, 1000).unwrap();
+    assert_eq!(1, frames.len());
+    assert_eq!(Some(expected.clone()), reassembler.add(&iphc, &frames[0], SRC_LINK, DST_LINK, NOW).unwrap());
+
+    // errors
+    assert_eq!(
+        Err(SixlowpanError::FrameTooSmall(10)),
+        iphc.compress_fragmented(&ip, &expected[40..], SRC_LINK, DST_LINK, 0x42, 10)
+    );
+    assert_eq!(
+        Err(SixlowpanError::DatagramTooLarge(40 + 3000)),
+        iphc.compress_fragmented(&ip, &[0;3000], SRC_LINK, DST_LINK, 0x42, 100)
+    );
+    assert_eq!(
+        Err(SixlowpanError::InvalidFragment),
+        reassembler.add(&iphc, &[0xe0, 0x10, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], SRC_LINK, DST_LINK, NOW)
+    );
+}
+
+const NOW: Duration = Duration::from_secs(0);
+
+/// Ip header & payload of an udp packet & the frames containing the
+/// fragments of it (with a maximum frame length of 60).
+fn fragmented_udp(payload_len: u16, datagram_tag: u16) -> (Vec<u8>, Vec<Vec<u8>>) {
+    let mut ip = ipv6(
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0x02, 0x12, 0x4b, 0, 1, 2, 3, 4],
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xfe, 0, 0x12, 0x34],
+    );
+    let payload: Vec<u8> = (0..payload_len).map(|v| v as u8).collect();
+    ip.payload_length = (UdpHeader::SERIALIZED_SIZE + payload.len()) as u16;
+    let udp = UdpHeader::with_ipv6_checksum(1234, 5678, &ip, &payload).unwrap();
+
+    let mut expected = Vec::new();
+    ip.write(&mut expected).unwrap();
+    udp.write(&mut expected).unwrap();
+    expected.extend_from_slice(&payload);
+    let frames = Iphc::default().compress_fragmented(&ip, &expected[40..], SRC_LINK, DST_LINK, datagram_tag, 60).unwrap();
+    (expected, frames)
+}
+
+#[test]
+fn reassembler_config() {
+    let config = ReassemblerConfig::default();
+    assert_eq!(Duration::from_secs(60), config.timeout);
+    assert_eq!(64, config.max_datagrams);
+    assert_eq!(&config, Reassembler::default().config());
+}
+
+#[test]
+fn reassembler_timeout() {
+    let iphc = Iphc::default();
+    let (expected, frames) = fragmented_udp(100, 0x42);
+    assert_eq!(3, frames.len());
+
+    let mut reassembler = Reassembler::new(Default::default());
+    assert_eq!(None, reassembler.add(&iphc, &frames[0], SRC_LINK, DST_LINK, NOW).unwrap());
+    assert_eq!(None, reassembler.add(&iphc, &frames[1], SRC_LINK, DST_LINK, Duration::from_secs(59)).unwrap());
+    // the first fragments are discarded after 60 seconds
+    assert_eq!(None, reassembler.add(&iphc, &frames[2], SRC_LINK, DST_LINK, Duration::from_secs(60)).unwrap());
+    assert_eq!(1, reassembler.len());
+    reassembler.remove_expired(Duration::from_secs(120));
+    assert!(reassembler.is_empty());
+
+    // within the timeout
+    for (index, frame) in frames.iter().enumerate() {
+        let result = reassembler.add(&iphc, frame, SRC_LINK, DST_LINK, Duration::from_secs(200 + index as u64 * 29)).unwrap();
+        assert_eq!(if 2 == index { Some(expected.clone()) } else { None }, result);
+    }
+}
+
+#[test]
+fn reassembler_max_datagrams() {
+    let iphc = Iphc::default();
+    let mut reassembler = Reassembler::new(ReassemblerConfig{
+        max_datagrams: 2,
+        ..Default::default()
+    });
+    let datagrams: Vec<_> = (0..3).map(|tag| fragmented_udp(100, tag)).collect();
+    for (index, (_, frames)) in datagrams.iter().enumerate() {
+        assert_eq!(None, reassembler.add(&iphc, &frames[0], SRC_LINK, DST_LINK, Duration::from_secs(index as u64)).unwrap());
+    }
+    assert_eq!(2, reassembler.len());
+
+    // the oldest datagram was discarded
+    for frame in &datagrams[0].1[1..] {
+        assert_eq!(None, reassembler.add(&iphc, frame, SRC_LINK, DST_LINK, Duration::from_secs(3)).unwrap());
+    }
+    // the newer ones can still be completed
+    let (expected, frames) = &datagrams[2];
+    assert_eq!(None, reassembler.add(&iphc, &frames[1], SRC_LINK, DST_LINK, Duration::from_secs(3)).unwrap());
+    assert_eq!(Some(expected.clone()), reassembler.add(&iphc, &frames[2], SRC_LINK, DST_LINK, Duration::from_secs(3)).unwrap());
+}
+
+#[test]
+fn reassembler_overlap() {
+    let iphc = Iphc::default();
+    let (expected, frames) = fragmented_udp(100, 0x42);
+    assert_eq!(3, frames.len());
+
+    // duplicates are ignored
+    {
+        let mut reassembler = Reassembler::new(Default::default());
+        for frame in &[&frames[0], &frames[1], &frames[1]] {
+            assert_eq!(None, reassembler.add(&iphc, frame, SRC_LINK, DST_LINK, NOW).unwrap());
+        }
+        assert_eq!(Some(expected.clone()), reassembler.add(&iphc, &frames[2], SRC_LINK, DST_LINK, NOW).unwrap());
+    }
+
+    // a fragment overlapping the second fragment (starting 8 bytes later
+    // with a modified payload) restarts the reassembly
+    let (header, rest) = SixlowpanFragmentHeader::from_slice(&frames[1]).unwrap().unwrap();
+    let offset = match header {
+        SixlowpanFragmentHeader::Subsequent{ datagram_offset, .. } => datagram_offset,
+        _ => panic!("expected subsequent fragment"),
+    };
+    let mut overlapping = Vec::new();
+    SixlowpanFragmentHeader::Subsequent{
+        datagram_size: header.datagram_size(),
+        datagram_tag: header.datagram_tag(),
+        datagram_offset: offset + 1,
+    }.write(&mut overlapping);
+    overlapping.extend(rest[8..].iter().map(|v| !v));
+    {
+        let mut reassembler = Reassembler::new(Default::default());
+        assert_eq!(None, reassembler.add(&iphc, &frames[0], SRC_LINK, DST_LINK, NOW).unwrap());
+        assert_eq!(None, reassembler.add(&iphc, &frames[1], SRC_LINK, DST_LINK, NOW).unwrap());
+        assert_eq!(None, reassembler.add(&iphc, &overlapping, SRC_LINK, DST_LINK, NOW).unwrap());
+        // the previously received fragments were discarded
+        assert_eq!(None, reassembler.add(&iphc, &frames[2], SRC_LINK, DST_LINK, NOW).unwrap());
+        assert_eq!(1, reassembler.len());
+
+        // the datagram can be reassembled once all fragments were received again
+        // (the second fragment overlaps the modified fragment & restarts the reassembly)
+        assert_eq!(None, reassembler.add(&iphc, &frames[1], SRC_LINK, DST_LINK, NOW).unwrap());
+        assert_eq!(None, reassembler.add(&iphc, &frames[0], SRC_LINK, DST_LINK, NOW).unwrap());
+        assert_eq!(Some(expected.clone()), reassembler.add(&iphc, &frames[2], SRC_LINK, DST_LINK, NOW).unwrap());
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+use smoltcp::wire::*;
+use std::convert::TryFrom;
+
+#[test]
+fn ipv4() {
+    let mut header = Ipv4Header::new(123, 20, IpNumber::Tcp, [1,2,3,4], [5,6,7,8]);
+    header.protocol = 0xfe;
+
+    let repr = Ipv4Repr::from(&header);
+    assert_eq!(
+        Ipv4Repr{
+            src_addr: Ipv4Address([1,2,3,4]),
+            dst_addr: Ipv4Address([5,6,7,8]),
+            next_header: IpProtocol::from(0xfe),
+            payload_len: 123,
+            hop_limit: 20,
+        },
+        repr
+    );
+    assert_eq!(header, Ipv4Header::try_from(&repr).unwrap());
+
+    // payload too large
+    let repr = Ipv4Repr{ payload_len: 0x10000, ..repr };
+    assert_matches!(Ipv4Header::try_from(&repr), Err(ValueError::Ipv4PayloadLengthTooLarge(0x10000)));
+}
+
+#[test]
+fn ipv6() {
+    let header = Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 123,
+        next_header: ip_number::UDP,
+        hop_limit: 20,
+        source: [1;16],
+        destination: [2;16],
+    };
+
+    let repr = Ipv6Repr::from(&header);
+    assert_eq!(
+        Ipv6Repr{
+            src_addr: Ipv6Address([1;16]),
+            dst_addr: Ipv6Address([2;16]),
+            next_header: IpProtocol::Udp,
+            payload_len: 123,
+            hop_limit: 20,
+        },
+        repr
+    );
+    assert_eq!(header, Ipv6Header::try_from(&repr).unwrap());
+
+    // payload too large
+    let repr = Ipv6Repr{ payload_len: 0x10000, ..repr };
+    assert_matches!(Ipv6Header::try_from(&repr), Err(ValueError::Ipv6PayloadLengthTooLarge(0x10000)));
+}
+
+#[test]
+fn udp() {
+    let header = UdpHeader{
+        source_port: 1234,
+        destination_port: 53,
+        length: UdpHeader::SERIALIZED_SIZE as u16,
+        checksum: 0,
+    };
+    let repr = UdpRepr::from(&header);
+    assert_eq!(UdpRepr{ src_port: 1234, dst_port: 53 }, repr);
+    assert_eq!(header, UdpHeader::from(&repr));
+}
+
+#[test]
+fn tcp() {
+    // control flags
+    for (syn, fin, rst, psh, expected) in [
+        (false, false, false, false, Some(TcpControl::None)),
+        (false, false, false, true, Some(TcpControl::Psh)),
+        (true, false, false, true, Some(TcpControl::Syn)),
+        (false, true, false, false, Some(TcpControl::Fin)),
+        (false, false, true, true, Some(TcpControl::Rst)),
+        (true, true, false, false, None),
+        (false, true, true, false, None),
+    ].iter().cloned() {
+        let mut header = TcpHeader::new(1234, 80, 100, 1024);
+        header.syn = syn;
+        header.fin = fin;
+        header.rst = rst;
+        header.psh = psh;
+        assert_eq!(expected, header.to_smoltcp_repr(&[]).map(|r| r.control));
+    }
+
+    // all fields & options
+    {
+        let mut header = TcpHeader::new(1234, 80, 0xffff_fff0, 1024);
+        header.syn = true;
+        header.ack = true;
+        header.acknowledgment_number = 5678;
+        header.set_options(&[
+            TcpOptionElement::MaximumSegmentSize(1400),
+            TcpOptionElement::WindowScale(7),
+            TcpOptionElement::SelectiveAcknowledgementPermitted,
+            TcpOptionElement::SelectiveAcknowledgement((1,2), [Some((3,4)), None, None]),
+        ]).unwrap();
+
+        let payload = [1,2,3,4];
+        let repr = header.to_smoltcp_repr(&payload).unwrap();
+        assert_eq!(
+            TcpRepr{
+                src_port: 1234,
+                dst_port: 80,
+                control: TcpControl::Syn,
+                seq_number: TcpSeqNumber(0xffff_fff0u32 as i32),
+                ack_number: Some(TcpSeqNumber(5678)),
+                window_len: 1024,
+                window_scale: Some(7),
+                max_seg_size: Some(1400),
+                sack_permitted: true,
+                sack_ranges: [Some((1,2)), Some((3,4)), None],
+                payload: &payload,
+            },
+            repr
+        );
+        assert_eq!(header, TcpHeader::from_smoltcp_repr(&repr).unwrap());
+    }
+
+    // no options & no ack
+    {
+        let mut header = TcpHeader::new(1234, 80, 100, 1024);
+        header.fin = true;
+        let repr = header.to_smoltcp_repr(&[]).unwrap();
+        assert_eq!(None, repr.ack_number);
+        assert_eq!(header, TcpHeader::from_smoltcp_repr(&repr).unwrap());
+    }
+
+    // invalid options
+    {
+        let mut header = TcpHeader::new(1234, 80, 100, 1024);
+        header.set_options_raw(&[0xff, 0, 0, 0]).unwrap();
+        assert_eq!(None, header.to_smoltcp_repr(&[]));
+    }
+}
+
+#[test]
+fn tcp_serialized_roundtrip() {
+    // a header generated from a smoltcp repr should be parsable by smoltcp
+    let repr = TcpRepr{
+        src_port: 1234,
+        dst_port: 80,
+        control: TcpControl::Psh,
+        seq_number: TcpSeqNumber(100),
+        ack_number: Some(TcpSeqNumber(200)),
+        window_len: 1024,
+        window_scale: None,
+        max_seg_size: None,
+        sack_permitted: false,
+        sack_ranges: [None;3],
+        payload: &[1,2,3,4],
+    };
+    let mut header = TcpHeader::from_smoltcp_repr(&repr).unwrap();
+    header.checksum = header.calc_checksum_ipv4_raw([1,2,3,4], [5,6,7,8], repr.payload).unwrap();
+
+    let mut buffer = Vec::new();
+    header.write(&mut buffer).unwrap();
+    buffer.extend_from_slice(repr.payload);
+
+    let packet = TcpPacket::new_checked(&buffer[..]).unwrap();
+    let parsed = TcpRepr::parse(
+        &packet,
+        &IpAddress::Ipv4(Ipv4Address([1,2,3,4])),
+        &IpAddress::Ipv4(Ipv4Address([5,6,7,8])),
+        &smoltcp::phy::ChecksumCapabilities::default()
+    ).unwrap();
+    assert_eq!(repr, parsed);
+}
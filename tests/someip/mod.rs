@@ -0,0 +1,300 @@
+use super::*;
+use etherparse::someip::*;
+
+fn header(message_id: u32, message_type: u8, payload_len: usize) -> SomeIpHeader {
+    SomeIpHeader{
+        message_id,
+        length: (SomeIpHeader::LENGTH_OFFSET + payload_len) as u32,
+        request_id: 0x0012_0034,
+        protocol_version: SomeIpHeader::PROTOCOL_VERSION,
+        interface_version: 3,
+        message_type,
+        return_code: someip_return_code::E_OK,
+    }
+}
+
+/// SOME/IP message with the given header fields & payload.
+fn someip_message(message_id: u32, message_type: u8, payload: &[u8]) -> Vec<u8> {
+    let mut result = header(message_id, message_type, payload.len()).to_bytes().to_vec();
+    result.extend_from_slice(payload);
+    result
+}
+
+/// SOME/IP-SD payload with the given flags, entries & options.
+fn sd_payload(flags: u8, entries: &[[u8;16]], options: &[u8]) -> Vec<u8> {
+    let mut result = vec![flags, 0, 0, 0];
+    result.extend_from_slice(&((entries.len()*16) as u32).to_be_bytes());
+    for entry in entries {
+        result.extend_from_slice(entry);
+    }
+    result.extend_from_slice(&(options.len() as u32).to_be_bytes());
+    result.extend_from_slice(options);
+    result
+}
+
+#[test]
+fn header_read_write() {
+    let header = SomeIpHeader{
+        message_id: 0x1234_0421,
+        length: 8,
+        request_id: 0xabcd_0102,
+        protocol_version: 1,
+        interface_version: 2,
+        message_type: someip_message_type::TP_RESPONSE,
+        return_code: someip_return_code::E_NOT_READY,
+    };
+    let bytes = header.to_bytes();
+    assert_eq!(
+        [
+            0x12, 0x34, 0x04, 0x21,
+            0, 0, 0, 8,
+            0xab, 0xcd, 0x01, 0x02,
+            1, 2, 0xa0, 0x04
+        ],
+        bytes
+    );
+    let mut written = Vec::new();
+    header.write(&mut written).unwrap();
+    assert_eq!(&bytes[..], &written[..]);
+
+    let (decoded, rest) = SomeIpHeader::from_slice(&bytes).unwrap();
+    assert_eq!(header, decoded);
+    assert!(rest.is_empty());
+
+    assert_eq!(0x1234, decoded.service_id());
+    assert_eq!(0x0421, decoded.method_id());
+    assert!(!decoded.is_event());
+    assert_eq!(0xabcd, decoded.client_id());
+    assert_eq!(0x0102, decoded.session_id());
+    assert!(decoded.is_tp());
+    assert!(!decoded.is_sd());
+    assert_eq!(0, decoded.payload_len());
+
+    // length field smaller than the header bytes it covers
+    assert_eq!(0, SomeIpHeader{ length: 3, ..header }.payload_len());
+
+    for len in 0..SomeIpHeader::LEN {
+        assert_matches!(
+            SomeIpHeader::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(SomeIpHeader::LEN))
+        );
+    }
+}
+
+#[test]
+fn message_slice() {
+    let mut bytes = someip_message(0x0100_8005, someip_message_type::NOTIFICATION, &[1, 2, 3]);
+    bytes.push(0xff);
+
+    let message = SomeIpMessageSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..bytes.len() - 1], message.slice());
+    assert_eq!(header(0x0100_8005, someip_message_type::NOTIFICATION, 3), message.header());
+    assert!(message.header().is_event());
+    assert_eq!(&[1, 2, 3], message.payload());
+    assert!(message.sd().is_none());
+    assert_eq!(message.clone(), message);
+
+    // payload missing
+    assert_matches!(
+        SomeIpMessageSlice::from_slice(&bytes[..18]),
+        Err(ReadError::UnexpectedEndOfSlice(19))
+    );
+    // length field smaller than 8
+    let mut bytes = bytes.clone();
+    bytes[7] = 7;
+    assert_matches!(
+        SomeIpMessageSlice::from_slice(&bytes),
+        Err(ReadError::UnexpectedEndOfSlice(SomeIpHeader::LEN))
+    );
+}
+
+#[test]
+fn messages_iterator() {
+    let mut payload = someip_message(0x0001_0001, someip_message_type::REQUEST, &[1]);
+    payload.extend_from_slice(&someip_message(0x0001_0002, someip_message_type::REQUEST_NO_RETURN, &[]));
+    let complete_len = payload.len();
+    // message continuing in the next segment
+    payload.extend_from_slice(&someip_message(0x0001_0003, someip_message_type::REQUEST, &[1, 2, 3])[..17]);
+
+    let mut iter = SomeIpMessagesIterator::from_slice(&payload);
+    assert_eq!(Some(0x0001_0001), iter.next().map(|m| m.unwrap().header().message_id));
+    assert_eq!(Some(0x0001_0002), iter.next().map(|m| m.unwrap().header().message_id));
+    assert_eq!(&payload[complete_len..], iter.rest());
+    assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(19))));
+    assert!(iter.next().is_none());
+    assert!(iter.rest().is_empty());
+
+    assert!(SomeIpMessagesIterator::from_slice(&[]).next().is_none());
+}
+
+#[test]
+fn sd_entries_and_options() {
+    use someip_sd_entry_type::*;
+    use someip_sd_option_type::*;
+
+    let entries = [
+        [
+            OFFER_SERVICE, 0, 1, 0x21,
+            0x12, 0x34, 0x00, 0x01,
+            0x02, 0x00, 0x0e, 0x10,
+            0x00, 0x00, 0x00, 0x05,
+        ],
+        [
+            SUBSCRIBE_EVENTGROUP, 2, 0, 0x10,
+            0x12, 0x34, 0x00, 0x01,
+            0x02, 0x00, 0x00, 0x00,
+            0x00, 0x03, 0x00, 0x42,
+        ],
+        [
+            0x7f, 0, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 0, 0,
+            1, 2, 3, 4,
+        ],
+    ];
+    let mut options = vec![
+        0, 9, IPV4_ENDPOINT, 0,
+        10, 0, 0, 1, 0, ip_number::TCP, 0x75, 0x30,
+        0, 21, IPV6_MULTICAST, 0,
+    ];
+    options.extend_from_slice(&[0xff, 0x14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    options.extend_from_slice(&[0, ip_number::UDP, 0x75, 0x31]);
+    options.extend_from_slice(&[0, 5, LOAD_BALANCING, 0, 0, 1, 0, 2]);
+    options.extend_from_slice(&[0, 5, CONFIGURATION, 0, 3, b'a', b'=', b'b']);
+    options.extend_from_slice(&[0, 2, 0x42, 0, 9]);
+    // ipv4 endpoint with unexpected length
+    options.extend_from_slice(&[0, 1, IPV4_SD_ENDPOINT, 0]);
+
+    let mut payload = sd_payload(someip_sd_flags::UNICAST, &entries, &options);
+    payload.push(0);
+    let message = someip_message(SomeIpHeader::SD_MESSAGE_ID, someip_message_type::NOTIFICATION, &payload);
+    let message = SomeIpMessageSlice::from_slice(&message).unwrap();
+    assert!(message.header().is_sd());
+
+    let sd = message.sd().unwrap().unwrap();
+    assert_eq!(&payload[..payload.len() - 1], sd.slice());
+    assert_eq!(someip_sd_flags::UNICAST, sd.flags());
+    assert!(!sd.reboot());
+    assert!(sd.unicast());
+    assert_eq!(&payload[8..8 + 48], sd.entries_slice());
+    assert_eq!(&options[..], sd.options_slice());
+
+    assert_eq!(
+        vec![
+            SomeIpSdEntry{
+                entry_type: OFFER_SERVICE,
+                index_first_options: 0,
+                index_second_options: 1,
+                number_of_first_options: 2,
+                number_of_second_options: 1,
+                service_id: 0x1234,
+                instance_id: 1,
+                major_version: 2,
+                ttl: 3600,
+                data: SomeIpSdEntryData::Service{ minor_version: 5 },
+            },
+            SomeIpSdEntry{
+                entry_type: SUBSCRIBE_EVENTGROUP,
+                index_first_options: 2,
+                index_second_options: 0,
+                number_of_first_options: 1,
+                number_of_second_options: 0,
+                service_id: 0x1234,
+                instance_id: 1,
+                major_version: 2,
+                ttl: 0,
+                data: SomeIpSdEntryData::Eventgroup{ counter: 3, eventgroup_id: 0x42 },
+            },
+            SomeIpSdEntry{
+                entry_type: 0x7f,
+                index_first_options: 0,
+                index_second_options: 0,
+                number_of_first_options: 0,
+                number_of_second_options: 0,
+                service_id: 0,
+                instance_id: 0,
+                major_version: 0,
+                ttl: 0,
+                data: SomeIpSdEntryData::Unknown([1, 2, 3, 4]),
+            },
+        ],
+        sd.entries().collect::<Vec<_>>()
+    );
+
+    let mut ipv6 = [0u8;16];
+    ipv6[0] = 0xff;
+    ipv6[1] = 0x14;
+    ipv6[15] = 1;
+    let expected = vec![
+        SomeIpSdOption::Ipv4Endpoint{ option_type: IPV4_ENDPOINT, address: [10, 0, 0, 1], ip_number: ip_number::TCP, port: 30000 },
+        SomeIpSdOption::Ipv6Endpoint{ option_type: IPV6_MULTICAST, address: ipv6, ip_number: ip_number::UDP, port: 30001 },
+        SomeIpSdOption::LoadBalancing{ priority: 1, weight: 2 },
+        SomeIpSdOption::Configuration(&[3, b'a', b'=', b'b']),
+        SomeIpSdOption::Other{ option_type: 0x42, data: &[9] },
+        SomeIpSdOption::Other{ option_type: IPV4_SD_ENDPOINT, data: &[] },
+    ];
+    assert_eq!(expected, sd.options().collect::<Vec<_>>());
+    assert_eq!(
+        vec![IPV4_ENDPOINT, IPV6_MULTICAST, LOAD_BALANCING, CONFIGURATION, 0x42, IPV4_SD_ENDPOINT],
+        expected.iter().map(|o| o.option_type()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn sd_from_slice_errors() {
+    let payload = sd_payload(0, &[[0;16]], &[0, 1, 1, 0]);
+    for len in 0..SomeIpSdSlice::MIN_LEN {
+        assert_matches!(
+            SomeIpSdSlice::from_slice(&payload[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(SomeIpSdSlice::MIN_LEN))
+        );
+    }
+    // entries array incomplete
+    assert_matches!(
+        SomeIpSdSlice::from_slice(&payload[..27]),
+        Err(ReadError::UnexpectedEndOfSlice(28))
+    );
+    // options array incomplete
+    assert_matches!(
+        SomeIpSdSlice::from_slice(&payload[..payload.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(32))
+    );
+    // option exceeding the options array
+    {
+        let payload = sd_payload(0, &[], &[0, 2, 1, 0]);
+        assert_matches!(
+            SomeIpSdSlice::from_slice(&payload),
+            Err(ReadError::UnexpectedEndOfSlice(17))
+        );
+    }
+    // incomplete option header
+    {
+        let payload = sd_payload(0, &[], &[0, 0]);
+        assert_matches!(
+            SomeIpSdSlice::from_slice(&payload),
+            Err(ReadError::UnexpectedEndOfSlice(15))
+        );
+    }
+}
+
+#[test]
+fn from_udp() {
+    let payload = someip_message(
+        SomeIpHeader::SD_MESSAGE_ID,
+        someip_message_type::NOTIFICATION,
+        &sd_payload(someip_sd_flags::REBOOT, &[], &[])
+    );
+    let builder = PacketBuilder::
+        ethernet2([1;6], [2;6])
+        .ipv4([192, 168, 1, 2], [224, 224, 224, 245], 1)
+        .udp(SomeIpHeader::SD_PORT, SomeIpHeader::SD_PORT);
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let messages = SomeIpMessagesIterator::from_slice(sliced.payload)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(1, messages.len());
+    assert!(messages[0].sd().unwrap().unwrap().reboot());
+}
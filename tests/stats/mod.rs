@@ -0,0 +1,197 @@
+use super::*;
+use etherparse::stats::*;
+
+/// Serializes a list of packets generated via the packet builder.
+fn packets() -> Vec<Vec<u8>> {
+    let mut result = Vec::new();
+
+    // ethernet + ipv4 + udp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(53, 1234);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        result.push(packet);
+    }
+    // ethernet + single vlan + ipv6 + tcp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(12)
+            .ipv6([0;16], [1;16], 20)
+            .tcp(80, 80, 1, 1024);
+        let mut packet = Vec::with_capacity(builder.size(10));
+        builder.write(&mut packet, &[0;10]).unwrap();
+        result.push(packet);
+    }
+    // ethernet + double vlan + ipv4 + tcp
+    {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .double_vlan(12, 13)
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(80, 4321, 1, 1024);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        result.push(packet);
+    }
+    result
+}
+
+fn counter(packets: u64, bytes: usize) -> Counter {
+    Counter{ packets, bytes: bytes as u64 }
+}
+
+#[test]
+fn counter_add_merge() {
+    let mut a: Counter = Default::default();
+    a.add(10);
+    a.add(5);
+    assert_eq!(counter(2, 15), a);
+
+    let mut b = counter(1, 1);
+    b.merge(&a);
+    assert_eq!(counter(3, 16), b);
+}
+
+#[test]
+fn add_ethernet_slice() {
+    let packets = packets();
+    let mut collector = StatsCollector::new();
+    for p in &packets {
+        collector.add_ethernet_slice(p);
+    }
+    collector.add_ethernet_slice(&[1,2,3]);
+
+    let lens: Vec<usize> = packets.iter().map(|p| p.len()).collect();
+    let actual = collector.snapshot();
+    assert_eq!(counter(4, lens.iter().sum::<usize>() + 3), actual.total);
+    assert_eq!(counter(1, 3), actual.errors);
+
+    assert_eq!(2, actual.ether_types.len());
+    assert_eq!(counter(2, lens[0] + lens[2]), actual.ether_types[&ether_type::IPV4]);
+    assert_eq!(counter(1, lens[1]), actual.ether_types[&ether_type::IPV6]);
+
+    assert_eq!(2, actual.vlan_ids.len());
+    assert_eq!(counter(2, lens[1] + lens[2]), actual.vlan_ids[&12]);
+    assert_eq!(counter(1, lens[2]), actual.vlan_ids[&13]);
+
+    assert_eq!(2, actual.ip_protocols.len());
+    assert_eq!(counter(1, lens[0]), actual.ip_protocols[&ip_number::UDP]);
+    assert_eq!(counter(2, lens[1] + lens[2]), actual.ip_protocols[&ip_number::TCP]);
+
+    assert_eq!(2, actual.udp_ports.len());
+    assert_eq!(counter(1, lens[0]), actual.udp_ports[&53]);
+    assert_eq!(counter(1, lens[0]), actual.udp_ports[&1234]);
+
+    assert_eq!(2, actual.tcp_ports.len());
+    assert_eq!(counter(2, lens[1] + lens[2]), actual.tcp_ports[&80]);
+    assert_eq!(counter(1, lens[2]), actual.tcp_ports[&4321]);
+}
+
+#[test]
+fn add_ip_slice() {
+    let packets = packets();
+    let mut collector = StatsCollector::new();
+    collector.add_ip_slice(&packets[0][Ethernet2Header::SERIALIZED_SIZE..]);
+    collector.add_ip_slice(&[]);
+
+    let len = packets[0].len() - Ethernet2Header::SERIALIZED_SIZE;
+    let actual = collector.snapshot();
+    assert_eq!(counter(2, len), actual.total);
+    assert_eq!(counter(1, 0), actual.errors);
+    assert!(actual.ether_types.is_empty());
+    assert!(actual.vlan_ids.is_empty());
+    assert_eq!(counter(1, len), actual.ip_protocols[&ip_number::UDP]);
+}
+
+#[test]
+fn add_sliced_fragmented() {
+    // the transport layer of fragmented packets is not decoded, the
+    // ip protocol should still be counted
+    let ipv4 = {
+        let mut header = Ipv4Header::new(0, 20, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+        header.more_fragments = true;
+        let mut result = Vec::new();
+        header.write(&mut result).unwrap();
+        result
+    };
+    let ipv6 = {
+        let frag = Ipv6FragmentHeader::new(ip_number::TCP, 0, true, 1234);
+        let header = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: frag.header_len() as u16,
+            next_header: ip_number::IPV6_FRAG,
+            hop_limit: 1,
+            source: [0;16],
+            destination: [0;16],
+        };
+        let mut result = Vec::new();
+        header.write(&mut result).unwrap();
+        frag.write(&mut result).unwrap();
+        result
+    };
+
+    let mut collector = StatsCollector::new();
+    collector.add_ip_slice(&ipv4);
+    collector.add_ip_slice(&ipv6);
+    let actual = collector.snapshot();
+    assert_eq!(counter(1, ipv4.len()), actual.ip_protocols[&ip_number::UDP]);
+    assert_eq!(counter(1, ipv6.len()), actual.ip_protocols[&ip_number::TCP]);
+    assert!(actual.udp_ports.is_empty());
+    assert!(actual.tcp_ports.is_empty());
+}
+
+#[test]
+fn add_packet_headers() {
+    // decoded headers should result in the same statistics as the sliced packets
+    let packets = packets();
+    let mut sliced = StatsCollector::new();
+    let mut decoded = StatsCollector::new();
+    for p in &packets {
+        sliced.add_sliced_packet(&SlicedPacket::from_ethernet(p).unwrap());
+        decoded.add_packet_headers(&PacketHeaders::from_ethernet_slice(p).unwrap());
+    }
+    assert_eq!(sliced.snapshot(), decoded.snapshot());
+}
+
+#[test]
+fn merge_take_snapshot() {
+    let packets = packets();
+
+    let mut all = StatsCollector::new();
+    let mut first = StatsCollector::new();
+    let mut second = StatsCollector::new();
+    for (i, p) in packets.iter().enumerate() {
+        all.add_ethernet_slice(p);
+        if 0 == i % 2 {
+            first.add_ethernet_slice(p);
+        } else {
+            second.add_ethernet_slice(p);
+        }
+    }
+    all.add_error(10);
+    second.add_error(10);
+
+    // merge via snapshot
+    {
+        let mut merged = first.snapshot();
+        merged.merge(&second.snapshot());
+        assert_eq!(all.snapshot(), merged);
+    }
+    // merge via collector
+    {
+        let mut merged = first.clone();
+        merged.merge(&second.snapshot());
+        assert_eq!(all, merged);
+    }
+    // take snapshot resets the collector
+    {
+        let expected = all.snapshot();
+        assert_eq!(expected, all.take_snapshot());
+        assert_eq!(StatsSnapshot::default(), all.snapshot());
+    }
+}
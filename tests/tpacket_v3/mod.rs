@@ -0,0 +1,155 @@
+use super::*;
+use etherparse::tpacket_v3::*;
+use std::time::Duration;
+
+fn udp_frame(source_port: u16) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(source_port, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+/// Appends a `tpacket3_hdr` followed by the frame (with an offset of 64 to the mac header).
+fn push_frame(block: &mut Vec<u8>, last: bool, status: u32, vlan: (u32, u16), frame: &[u8]) {
+    let start = block.len();
+    let next_offset = if last { 0 } else { 64 + frame.len() as u32 };
+    block.extend_from_slice(&next_offset.to_ne_bytes());
+    block.extend_from_slice(&10u32.to_ne_bytes()); // sec
+    block.extend_from_slice(&20u32.to_ne_bytes()); // nsec
+    block.extend_from_slice(&(frame.len() as u32).to_ne_bytes()); // snaplen
+    block.extend_from_slice(&(frame.len() as u32 + 4).to_ne_bytes()); // len
+    block.extend_from_slice(&status.to_ne_bytes());
+    block.extend_from_slice(&64u16.to_ne_bytes()); // mac
+    block.extend_from_slice(&78u16.to_ne_bytes()); // net
+    block.extend_from_slice(&0x1234u32.to_ne_bytes()); // rxhash
+    block.extend_from_slice(&vlan.0.to_ne_bytes()); // vlan tci
+    block.extend_from_slice(&vlan.1.to_ne_bytes()); // vlan tpid
+    block.resize(start + 64, 0);
+    block.extend_from_slice(frame);
+}
+
+fn block(frames: &[(u32, (u32, u16), Vec<u8>)]) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&TPACKET_V3.to_ne_bytes());
+    result.extend_from_slice(&0u32.to_ne_bytes()); // offset_to_priv
+    result.extend_from_slice(&(TP_STATUS_USER | TP_STATUS_BLK_TMO).to_ne_bytes());
+    result.extend_from_slice(&(frames.len() as u32).to_ne_bytes());
+    result.extend_from_slice(&48u32.to_ne_bytes()); // offset_to_first_pkt
+    result.extend_from_slice(&0u32.to_ne_bytes()); // blk_len (set below)
+    result.extend_from_slice(&7u64.to_ne_bytes()); // seq_num
+    result.extend_from_slice(&1u32.to_ne_bytes());
+    result.extend_from_slice(&2u32.to_ne_bytes());
+    result.extend_from_slice(&3u32.to_ne_bytes());
+    result.extend_from_slice(&4u32.to_ne_bytes());
+    for (i, (status, vlan, frame)) in frames.iter().enumerate() {
+        push_frame(&mut result, i + 1 == frames.len(), *status, *vlan, frame);
+    }
+    let len = result.len() as u32;
+    result[20..24].copy_from_slice(&len.to_ne_bytes());
+    // unused space at the end of the block
+    result.resize(result.len() + 100, 0xff);
+    result
+}
+
+#[test]
+fn block_and_frames() {
+    let mut data = block(&[
+        (TP_STATUS_USER, (0, 0), udp_frame(1)),
+        (TP_STATUS_USER | TP_STATUS_VLAN_VALID | TP_STATUS_VLAN_TPID_VALID, (0x3000 | 123, 0x88a8), udp_frame(2)),
+        (TP_STATUS_USER | TP_STATUS_VLAN_VALID, (0x2000 | 42, 0x88a8), udp_frame(3)),
+    ]);
+    {
+        let block = TpacketV3Block::from_slice(&data).unwrap();
+        assert_eq!(data.len() - 100, block.slice().len());
+        assert_eq!(TPACKET_V3, block.version());
+        assert_eq!(TP_STATUS_USER | TP_STATUS_BLK_TMO, block.block_status());
+        assert_eq!(3, block.num_packets());
+        assert_eq!(48, block.offset_to_first_packet());
+        assert_eq!(block.slice().len() as u32, block.block_len());
+        assert_eq!(7, block.seq_num());
+        assert_eq!(Duration::new(1, 2), block.first_timestamp());
+        assert_eq!(Duration::new(3, 4), block.last_timestamp());
+
+        let frames: Vec<_> = block.frames().map(|f| f.unwrap()).collect();
+        assert_eq!(3, frames.len());
+
+        // without vlan
+        assert_eq!(Duration::new(10, 20), frames[0].timestamp());
+        assert_eq!(udp_frame(1), frames[0].data());
+        assert_eq!(frames[0].data().len() as u32, frames[0].snap_len());
+        assert_eq!(frames[0].data().len() as u32 + 4, frames[0].len());
+        assert!(!frames[0].is_empty());
+        assert_eq!(0x1234, frames[0].rx_hash());
+        assert_eq!(48, frames[0].header_slice().len());
+        assert_eq!(None, frames[0].vlan_tci());
+        assert_eq!(None, frames[0].vlan_tpid());
+        assert_eq!(None, frames[0].vlan());
+        {
+            let sliced = frames[0].slice_ethernet().unwrap();
+            assert_matches!(sliced.transport, Some(TransportSlice::Udp(ref udp)) if 1 == udp.source_port());
+        }
+
+        // with vlan & tpid
+        assert_eq!(Some(0x3000 | 123), frames[1].vlan_tci());
+        assert_eq!(Some(0x88a8), frames[1].vlan_tpid());
+        assert_eq!(
+            Some(SingleVlanHeader{
+                priority_code_point: 1,
+                drop_eligible_indicator: true,
+                vlan_identifier: 123,
+                ether_type: ether_type::IPV4,
+                tpid: ether_type::PROVIDER_BRIDGING,
+            }),
+            frames[1].vlan()
+        );
+
+        // with vlan without tpid
+        assert_eq!(None, frames[2].vlan_tpid());
+        assert_eq!(42, frames[2].vlan().unwrap().vlan_identifier);
+        assert_eq!(1, frames[2].vlan().unwrap().priority_code_point);
+        assert!(!frames[2].vlan().unwrap().drop_eligible_indicator);
+    }
+
+    // release
+    TpacketV3Block::release(&mut data);
+    assert_eq!(0, TpacketV3Block::from_slice(&data).unwrap().block_status());
+}
+
+#[test]
+fn errors() {
+    let data = block(&[(TP_STATUS_USER, (0, 0), udp_frame(1))]);
+
+    // block too short
+    assert_matches!(
+        TpacketV3Block::from_slice(&data[..47]),
+        Err(ReadError::UnexpectedEndOfSlice(48))
+    );
+    let block_len = data.len() - 100;
+    assert_matches!(
+        TpacketV3Block::from_slice(&data[..block_len - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+
+    // frame too short
+    assert_matches!(
+        TpacketV3Frame::from_slice(&data[48..48 + 47]),
+        Err(ReadError::UnexpectedEndOfSlice(48))
+    );
+    assert_matches!(
+        TpacketV3Frame::from_slice(&data[48..block_len - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+
+    // more packets announced then present (error ends the iteration)
+    let mut broken = data.clone();
+    broken[12..16].copy_from_slice(&2u32.to_ne_bytes());
+    broken[48..52].copy_from_slice(&1000u32.to_ne_bytes());
+    let block = TpacketV3Block::from_slice(&broken).unwrap();
+    let mut frames = block.frames();
+    assert!(frames.next().unwrap().is_ok());
+    assert!(frames.next().unwrap().is_err());
+    assert!(frames.next().is_none());
+}
@@ -0,0 +1,245 @@
+use etherparse::*;
+use etherparse::multicast::{group_record_type, membership_events, MembershipEvent};
+
+use super::super::*;
+
+fn headers() -> Vec<IgmpHeader> {
+    use IgmpType::*;
+    vec![
+        IgmpHeader::new(MembershipQuery{ max_response_time: 0, group_address: [0;4] }),
+        IgmpHeader::new(MembershipQuery{ max_response_time: 100, group_address: [239,1,2,3] }),
+        IgmpHeader::new(MembershipQueryV3(IgmpV3Query{
+            max_response_code: 0x81,
+            group_address: [239,1,2,3],
+            suppress_router_side_processing: true,
+            querier_robustness_variable: 2,
+            querier_query_interval_code: 125,
+            sources: vec![[192,168,1,1], [192,168,1,2]],
+        })),
+        IgmpHeader::new(MembershipQueryV3(Default::default())),
+        IgmpHeader::new(MembershipReportV1{ group_address: [239,1,2,3] }),
+        IgmpHeader::new(MembershipReportV2{ group_address: [239,1,2,3] }),
+        IgmpHeader::new(LeaveGroup{ group_address: [239,1,2,3] }),
+        IgmpHeader::new(MembershipReportV3(vec![
+            IgmpGroupRecord{
+                record_type: group_record_type::MODE_IS_INCLUDE,
+                group_address: [239,1,2,3],
+                sources: vec![[192,168,1,1]],
+                aux_data: vec![1,2,3,4],
+            },
+            IgmpGroupRecord{
+                record_type: group_record_type::CHANGE_TO_EXCLUDE_MODE,
+                group_address: [239,1,2,4],
+                sources: Vec::new(),
+                aux_data: Vec::new(),
+            },
+        ])),
+        IgmpHeader::new(MembershipReportV3(Vec::new())),
+        IgmpHeader::new(Unknown{ type_u8: 0x13, code_u8: 1, bytes5to8: [1,2,3,4] }),
+    ]
+}
+
+#[test]
+fn to_bytes_from_slice() {
+    for header in headers() {
+        let header = IgmpHeader::with_checksum(header.igmp_type).unwrap();
+        let bytes = header.to_bytes().unwrap();
+        assert_eq!(header.header_len(), bytes.len());
+        assert_eq!(header.igmp_type.type_u8(), bytes[0]);
+
+        // the checksum over the complete message is 0
+        assert_eq!(
+            0,
+            etherparse::checksum::Sum16BitWords::new().add_slice(&bytes).ones_complement()
+        );
+
+        // from_slice
+        let (decoded, rest) = IgmpHeader::from_slice(&bytes).unwrap();
+        assert_eq!(header, decoded);
+        assert!(rest.is_empty());
+
+        // slice
+        let slice = IgmpSlice::from_slice(&bytes).unwrap();
+        assert_eq!(&bytes[..], slice.slice());
+        assert_eq!(bytes[0], slice.type_u8());
+        assert_eq!(bytes[1], slice.code_u8());
+        assert_eq!(header.checksum, slice.checksum());
+        assert_eq!(header, slice.to_header());
+        assert_eq!(slice.clone(), slice);
+
+        // write
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        assert_eq!(bytes, buffer);
+    }
+}
+
+#[test]
+fn from_slice_rest() {
+    // data after reports is not part of the message
+    let bytes = [0x16, 0, 0, 0, 239, 1, 2, 3, 9, 9];
+    let (header, rest) = IgmpHeader::from_slice(&bytes).unwrap();
+    assert_eq!(IgmpType::MembershipReportV2{ group_address: [239,1,2,3] }, header.igmp_type);
+    assert_eq!(&[9, 9], rest);
+
+    // a query with additional data is an IGMPv3 query
+    let bytes = [0x11, 100, 0, 0, 239, 1, 2, 3, 0, 0, 0, 0, 9, 9];
+    let (header, rest) = IgmpHeader::from_slice(&bytes).unwrap();
+    assert_matches!(header.igmp_type, IgmpType::MembershipQueryV3(_));
+    assert_eq!(&[9, 9], rest);
+}
+
+#[test]
+fn from_slice_too_short() {
+    for header in headers() {
+        let bytes = header.to_bytes().unwrap();
+        for len in 0..bytes.len() {
+            match &header.igmp_type {
+                // shorter IGMPv3 queries are IGMPv2 queries
+                IgmpType::MembershipQueryV3(_) if len >= IgmpV3Query::MIN_LEN => {},
+                IgmpType::MembershipQueryV3(_) if len >= IgmpHeader::MIN_LEN => {
+                    assert_matches!(
+                        IgmpHeader::from_slice(&bytes[..len]),
+                        Ok((IgmpHeader{ igmp_type: IgmpType::MembershipQuery{ .. }, .. }, _))
+                    );
+                },
+                _ => {
+                    assert_matches!(IgmpSlice::from_slice(&bytes[..len]), Err(ReadError::UnexpectedEndOfSlice(_)));
+                    assert_matches!(IgmpHeader::from_slice(&bytes[..len]), Err(ReadError::UnexpectedEndOfSlice(_)));
+                }
+            }
+        }
+    }
+    // record count exceeding the slice
+    assert_matches!(
+        IgmpSlice::from_slice(&[0x22, 0, 0, 0, 0, 0, 0xff, 0xff]),
+        Err(ReadError::UnexpectedEndOfSlice(16))
+    );
+}
+
+#[test]
+fn query_v3_fields() {
+    let query = IgmpV3Query{
+        max_response_code: 0x81,
+        suppress_router_side_processing: true,
+        querier_robustness_variable: 7,
+        querier_query_interval_code: 0x90,
+        ..Default::default()
+    };
+    let bytes = IgmpHeader::new(IgmpType::MembershipQueryV3(query.clone())).to_bytes().unwrap();
+    assert_eq!(0x81, bytes[1]);
+    assert_eq!(0b1111, bytes[8]);
+    assert_eq!(0x90, bytes[9]);
+    assert_eq!(0x11 << 3, query.max_response_time());
+    assert_eq!(0x10 << 4, query.query_interval());
+    assert_eq!(100, IgmpV3Query{ max_response_code: 100, ..Default::default() }.max_response_time());
+    assert_eq!(125, IgmpV3Query{ querier_query_interval_code: 125, ..Default::default() }.query_interval());
+}
+
+#[test]
+fn to_bytes_errors() {
+    use ValueError::*;
+
+    assert_eq!(
+        Err(U8TooLarge{ value: 8, max: 7, field: ErrorField::IgmpQuerierRobustnessVariable }),
+        IgmpHeader::new(IgmpType::MembershipQueryV3(IgmpV3Query{
+            querier_robustness_variable: 8,
+            ..Default::default()
+        })).to_bytes()
+    );
+    assert_eq!(
+        Err(MulticastRecordLengthBad),
+        IgmpHeader::new(IgmpType::MembershipQueryV3(IgmpV3Query{
+            sources: vec![[0;4]; 0x10000],
+            ..Default::default()
+        })).to_bytes()
+    );
+    assert_eq!(
+        Err(MulticastRecordLengthBad),
+        IgmpHeader::new(IgmpType::MembershipReportV3(vec![IgmpGroupRecord::default(); 0x10000])).to_bytes()
+    );
+    for aux_len in &[3, 0xff*4 + 4] {
+        let header = IgmpHeader::new(IgmpType::MembershipReportV3(vec![IgmpGroupRecord{
+            aux_data: vec![0; *aux_len],
+            ..Default::default()
+        }]));
+        assert_eq!(Err(MulticastRecordLengthBad), header.to_bytes());
+        assert_eq!(Err(MulticastRecordLengthBad), header.calc_checksum(&[]));
+        assert_matches!(
+            header.write(&mut Vec::new()),
+            Err(WriteError::ValueError(MulticastRecordLengthBad))
+        );
+    }
+}
+
+/// IGMPv2 query in an ethernet frame padded to the minimum frame size.
+fn padded_query_frame() -> (IgmpHeader, Vec<u8>) {
+    let igmp = IgmpHeader::with_checksum(IgmpType::MembershipQuery{
+        max_response_time: 100,
+        group_address: [0;4],
+    }).unwrap();
+    let mut frame = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [1,0,0x5e,0,0,1],
+        ether_type: ether_type::IPV4,
+    }.write(&mut frame).unwrap();
+    Ipv4Header::new(
+        igmp.header_len() as u16,
+        1,
+        IpNumber::Igmp,
+        [192,168,1,1],
+        [224,0,0,1]
+    ).write(&mut frame).unwrap();
+    igmp.write(&mut frame).unwrap();
+    frame.resize(60, 0);
+    (igmp, frame)
+}
+
+#[test]
+fn sliced_packet() {
+    let (igmp, frame) = padded_query_frame();
+
+    let sliced = SlicedPacket::from_ethernet(&frame).unwrap();
+    match sliced.transport.as_ref().unwrap() {
+        TransportSlice::Igmp(value) => {
+            // the padding is not interpreted as IGMPv3 query fields
+            assert_eq!(igmp, value.to_header());
+            assert_eq!(&frame[34..42], value.slice());
+        },
+        value => panic!("unexpected transport {:?}", value),
+    }
+    // the ethernet padding is separated from the payload
+    assert!(sliced.payload.is_empty());
+    assert_eq!(&frame[42..], sliced.padding);
+
+    // truncated
+    assert_matches!(
+        SlicedPacket::from_ethernet(&frame[..41]),
+        Err(ReadError::UnexpectedEndOfSlice(42))
+    );
+
+    // membership events are still extracted
+    assert_matches!(
+        &membership_events(&sliced).unwrap()[..],
+        [MembershipEvent::Query{ group: None, .. }]
+    );
+}
+
+#[test]
+fn packet_headers() {
+    let (igmp, frame) = padded_query_frame();
+
+    let headers = PacketHeaders::from_ethernet_slice(&frame).unwrap();
+    assert_eq!(Some(TransportHeader::Igmp(igmp.clone())), headers.transport);
+    assert_eq!(Payload::Igmp(&frame[42..]), headers.payload);
+
+    let headers = PacketHeaders::from_ip_slice(&frame[14..]).unwrap();
+    assert_eq!(Some(TransportHeader::Igmp(igmp)), headers.transport);
+    assert_eq!(Payload::Igmp(&frame[42..]), headers.payload);
+
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(&frame[..41]),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+}
@@ -1,5 +1,6 @@
 pub mod udp;
 pub mod tcp;
+pub mod igmp;
 
 mod transport_header {
     use super::super::*;
@@ -66,6 +67,41 @@ mod transport_header {
         assert_eq!(Some(&mut tcp.clone()), TransportHeader::Tcp(tcp).mut_tcp());
         assert_eq!(None, TransportHeader::Udp(Default::default()).mut_tcp());
     }
+    #[test]
+    fn igmp() {
+        let igmp = IgmpHeader::new(IgmpType::LeaveGroup{ group_address: [224,0,0,1] });
+        assert_eq!(Some(igmp.clone()), TransportHeader::Igmp(igmp).igmp());
+        assert_eq!(None, TransportHeader::Udp(Default::default()).igmp());
+    }
+    #[test]
+    fn mut_igmp() {
+        let igmp = IgmpHeader::new(IgmpType::LeaveGroup{ group_address: [224,0,0,1] });
+        assert_eq!(Some(&mut igmp.clone()), TransportHeader::Igmp(igmp).mut_igmp());
+        assert_eq!(None, TransportHeader::Tcp(Default::default()).mut_igmp());
+        assert_eq!(None, TransportHeader::Igmp(IgmpHeader::new(IgmpType::LeaveGroup{ group_address: [0;4] })).mut_udp());
+        assert_eq!(None, TransportHeader::Igmp(IgmpHeader::new(IgmpType::LeaveGroup{ group_address: [0;4] })).tcp());
+    }
+    #[test]
+    fn igmp_header_len_checksum_write() {
+        let igmp = IgmpHeader::new(IgmpType::MembershipReportV3(vec![IgmpGroupRecord{
+            record_type: 4,
+            group_address: [239,1,2,3],
+            ..Default::default()
+        }]));
+        let mut transport = TransportHeader::Igmp(igmp.clone());
+        assert_eq!(16, transport.header_len());
+
+        let expected = igmp.calc_checksum(&[]).unwrap();
+        transport.update_checksum_ipv4(&Ipv4Header::default(), &[]).unwrap();
+        assert_eq!(expected, transport.clone().igmp().unwrap().checksum);
+        let mut transport6 = TransportHeader::Igmp(igmp);
+        transport6.update_checksum_ipv6(&Ipv6Header::default(), &[]).unwrap();
+        assert_eq!(expected, transport6.igmp().unwrap().checksum);
+
+        let mut buffer = Vec::new();
+        transport.write(&mut buffer).unwrap();
+        assert_eq!(transport.igmp().unwrap().to_bytes().unwrap(), buffer);
+    }
     proptest! {
         #[test]
         fn header_size_tcp(ref input in tcp_any()) {
@@ -207,6 +243,100 @@ mod transport_header {
             }
         }
     }
+    #[test]
+    fn update_checksum_final_destination() {
+        let payload = [1,2,3,4,5,6,7,8];
+        let udp = UdpHeader{
+            source_port: 1,
+            destination_port: 2,
+            length: (UdpHeader::SERIALIZED_SIZE + payload.len()) as u16,
+            checksum: 0,
+        };
+        let tcp = TcpHeader::new(1, 2, 3, 4);
+
+        // ipv4 with a loose source route option
+        {
+            let mut ip_header = Ipv4Header::new(0, 1, IpNumber::Udp, [1,2,3,4], [5,6,7,8]);
+            let mut builder = Ipv4OptionsBuilder::new();
+            builder.add(&Ipv4OptionElement::LooseSourceRoute(Ipv4RouteOption{ pointer: 4, route_data: &[9;4] })).unwrap();
+            builder.apply(&mut ip_header);
+
+            let mut transport = TransportHeader::Udp(udp.clone());
+            transport.update_checksum_ipv4_final_destination(&ip_header, &payload).unwrap();
+            assert_eq!(
+                udp.calc_checksum_ipv4_raw([1,2,3,4], [9;4], &payload).unwrap(),
+                transport.clone().udp().unwrap().checksum
+            );
+            assert_ne!(
+                udp.calc_checksum_ipv4(&ip_header, &payload).unwrap(),
+                transport.clone().udp().unwrap().checksum
+            );
+
+            let mut transport = TransportHeader::Tcp(tcp.clone());
+            transport.update_checksum_ipv4_final_destination(&ip_header, &payload).unwrap();
+            assert_eq!(
+                tcp.calc_checksum_ipv4_raw([1,2,3,4], [9;4], &payload).unwrap(),
+                transport.clone().tcp().unwrap().checksum
+            );
+
+            // igmp does not use a pseudo header
+            let igmp = IgmpHeader::new(IgmpType::LeaveGroup{ group_address: [0;4] });
+            let mut transport = TransportHeader::Igmp(igmp.clone());
+            transport.update_checksum_ipv4_final_destination(&ip_header, &[]).unwrap();
+            assert_eq!(igmp.calc_checksum(&[]).unwrap(), transport.igmp().unwrap().checksum);
+        }
+
+        // ipv6 with a routing header
+        {
+            let ip_header = Ipv6Header{
+                source: [1;16],
+                destination: [2;16],
+                ..Default::default()
+            };
+            let mut routing_payload = vec![Ipv6RoutingHeader::TYPE_0, 1, 0, 0, 0, 0];
+            routing_payload.extend_from_slice(&[3;16]);
+            let extensions = Ipv6Extensions{
+                routing: Some(Ipv6RoutingExtensions{
+                    routing: Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &routing_payload).unwrap(),
+                    final_destination_options: None,
+                }),
+                ..Default::default()
+            };
+
+            let mut transport = TransportHeader::Udp(udp.clone());
+            transport.update_checksum_ipv6_final_destination(&ip_header, &extensions, &payload).unwrap();
+            assert_eq!(
+                udp.calc_checksum_ipv6_raw([1;16], [3;16], &payload).unwrap(),
+                transport.clone().udp().unwrap().checksum
+            );
+            assert_ne!(
+                udp.calc_checksum_ipv6(&ip_header, &payload).unwrap(),
+                transport.clone().udp().unwrap().checksum
+            );
+
+            let mut transport = TransportHeader::Tcp(tcp.clone());
+            transport.update_checksum_ipv6_final_destination(&ip_header, &extensions, &payload).unwrap();
+            assert_eq!(
+                tcp.calc_checksum_ipv6_raw([1;16], [3;16], &payload).unwrap(),
+                transport.clone().tcp().unwrap().checksum
+            );
+
+            // without a routing header the destination of the header is used
+            let mut transport = TransportHeader::Udp(udp.clone());
+            transport.update_checksum_ipv6_final_destination(&ip_header, &Default::default(), &payload).unwrap();
+            assert_eq!(
+                udp.calc_checksum_ipv6(&ip_header, &payload).unwrap(),
+                transport.clone().udp().unwrap().checksum
+            );
+
+            // icmpv6
+            assert_eq!(
+                etherparse::icmpv6::checksum([1;16], [3;16], &payload),
+                etherparse::icmpv6::checksum_final_destination(&ip_header, &extensions, &payload)
+            );
+        }
+    }
+
     proptest! {
         #[test]
         fn write_udp(ref input in udp_any()) {
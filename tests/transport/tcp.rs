@@ -1183,6 +1183,37 @@ fn calc_header_checksum_ipv6_error() {
     assert_eq!(Err(ValueError::TcpLengthTooLarge(std::u32::MAX as usize + 1)), tcp_slice.calc_checksum_ipv6_raw(ip_slice.source(), ip_slice.destination(), &tcp_payload));
 }
 
+#[test]
+fn slice_is_checksum_valid() {
+    let payload = [1,2,3,4,5];
+    let source4 = [192,168,1,42];
+    let destination4 = [192,168,1,1];
+    let source6 = [1;16];
+    let destination6 = [2;16];
+
+    let mut tcp = TcpHeader::new(1234, 80, 1, 1024);
+    tcp.syn = true;
+    for ipv4 in [true, false] {
+        tcp.checksum = if ipv4 {
+            tcp.calc_checksum_ipv4_raw(source4, destination4, &payload).unwrap()
+        } else {
+            tcp.calc_checksum_ipv6_raw(source6, destination6, &payload).unwrap()
+        };
+        let mut buffer = Vec::new();
+        tcp.write(&mut buffer).unwrap();
+        let slice = TcpHeaderSlice::from_slice(&buffer).unwrap();
+
+        assert_eq!(ipv4, slice.is_checksum_valid_ipv4(source4, destination4, &payload));
+        assert_eq!(!ipv4, slice.is_checksum_valid_ipv6(source6, destination6, &payload));
+
+        // modified payload or addresses
+        assert!(!slice.is_checksum_valid_ipv4(source4, destination4, &payload[..4]));
+        assert!(!slice.is_checksum_valid_ipv4(destination4, [192,168,1,2], &payload));
+        assert!(!slice.is_checksum_valid_ipv6(source6, destination6, &[1,2,3,4,6]));
+        assert!(!slice.is_checksum_valid_ipv6(source6, [3;16], &payload));
+    }
+}
+
 #[test]
 fn options_iterator_method() {
     let options = [
@@ -818,6 +818,48 @@ mod udp_header_slice {
         }
     }
 
+    #[test]
+    fn is_checksum_valid() {
+        let payload = [1,2,3,4,5];
+        let ipv4_header = Ipv4Header::new(13, 64, IpNumber::Udp, [192,168,1,1], [192,168,1,2]);
+        let ipv6_header = Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 13,
+            next_header: ip_number::UDP,
+            hop_limit: 64,
+            source: [1;16],
+            destination: [2;16],
+        };
+
+        // ipv4
+        {
+            let header = UdpHeader::with_ipv4_checksum(1, 2, &ipv4_header, &payload).unwrap();
+            let bytes = header.to_bytes();
+            let slice = UdpHeaderSlice::from_slice(&bytes).unwrap();
+            assert!(slice.is_checksum_valid_ipv4(ipv4_header.source, ipv4_header.destination, &payload));
+            assert!(!slice.is_checksum_valid_ipv4(ipv4_header.source, ipv4_header.destination, &[1,2,3,4,6]));
+            assert!(!slice.is_checksum_valid_ipv4(ipv4_header.destination, [192,168,1,3], &payload));
+        }
+        // ipv6
+        {
+            let header = UdpHeader::with_ipv6_checksum(1, 2, &ipv6_header, &payload).unwrap();
+            let bytes = header.to_bytes();
+            let slice = UdpHeaderSlice::from_slice(&bytes).unwrap();
+            assert!(slice.is_checksum_valid_ipv6(ipv6_header.source, ipv6_header.destination, &payload));
+            assert!(!slice.is_checksum_valid_ipv6(ipv6_header.source, ipv6_header.destination, &[1,2,3,4,6]));
+            assert!(!slice.is_checksum_valid_ipv6(ipv6_header.source, [3;16], &payload));
+        }
+        // no checksum (only valid in ipv4)
+        {
+            let header = UdpHeader::without_ipv4_checksum(1, 2, payload.len()).unwrap();
+            let bytes = header.to_bytes();
+            let slice = UdpHeaderSlice::from_slice(&bytes).unwrap();
+            assert!(slice.is_checksum_valid_ipv4(ipv4_header.source, ipv4_header.destination, &payload));
+            assert!(!slice.is_checksum_valid_ipv6(ipv6_header.source, ipv6_header.destination, &payload));
+        }
+    }
+
     proptest! {
         #[test]
         fn dbg(input in udp_any()) {
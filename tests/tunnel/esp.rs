@@ -0,0 +1,310 @@
+use etherparse::*;
+
+use super::super::*;
+
+use std::io::Cursor;
+
+/// Limits enabling the decoding of NULL encrypted ESP packets with a 12 byte ICV.
+fn limits() -> ParseLimits {
+    ParseLimits{
+        esp_null_icv_len: Some(12),
+        ..Default::default()
+    }
+}
+
+/// NULL encrypted ESP packet (header, payload, padding, trailer & a 12 byte ICV).
+fn esp(next_header: u8, payload: &[u8]) -> Vec<u8> {
+    let mut result = EspHeader{ spi: 0x1234_5678, sequence_number: 9 }.to_bytes().to_vec();
+    result.extend_from_slice(payload);
+    // padding to 4 bytes
+    let pad_length = (4 - (payload.len() + 2) % 4) % 4;
+    result.extend((1..=pad_length as u8).collect::<Vec<u8>>());
+    result.push(pad_length as u8);
+    result.push(next_header);
+    result.extend_from_slice(&[0xaa;12]);
+    result
+}
+
+/// Serialized IPv4 packet with the given protocol & payload.
+fn ipv4(protocol: IpNumber, payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    Ipv4Header::new(payload.len() as u16, 20, protocol, [1,2,3,4], [5,6,7,8])
+        .write(&mut result)
+        .unwrap();
+    result.extend_from_slice(payload);
+    result
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        spi in any::<u32>(),
+        sequence_number in any::<u32>(),
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = EspHeader{ spi, sequence_number };
+        let bytes = {
+            let mut bytes = header.to_bytes().to_vec();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+
+        // header
+        {
+            let (decoded, decoded_rest) = EspHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+            assert_eq!(8, header.header_len());
+        }
+
+        // slice
+        {
+            let slice = EspHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..8], slice.slice());
+            assert_eq!(spi, slice.spi());
+            assert_eq!(sequence_number, slice.sequence_number());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..8], &buffer[..]);
+            assert_eq!(header, EspHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for len in 0..8 {
+            assert_matches!(
+                EspHeader::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+            assert_matches!(
+                EspHeader::read(&mut Cursor::new(&bytes[..len])),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn null_slice() {
+    // payload, 2 bytes padding, pad length, next header & 4 bytes icv
+    let data = [1,2,3,4,5,6, 1,2, 2, ip_number::UDP, 9,9,9,9];
+    let null = EspNullSlice::from_slice(&data, 4).unwrap();
+    assert_eq!(&[1,2,3,4,5,6], null.payload());
+    assert_eq!(&[1,2], null.padding());
+    assert_eq!(2, null.pad_length());
+    assert_eq!(ip_number::UDP, null.next_header());
+    assert_eq!(&[9,9,9,9], null.icv());
+    assert_eq!(null.clone(), null);
+
+    // without icv & padding
+    let null = EspNullSlice::from_slice(&data[..10], 0).unwrap();
+    assert_eq!(&[1,2,3,4,5,6], null.payload());
+    assert!(null.icv().is_empty());
+
+    // too short for the trailer & icv
+    assert_matches!(
+        EspNullSlice::from_slice(&data[..5], 4),
+        Err(ReadError::UnexpectedEndOfSlice(6))
+    );
+    assert_matches!(
+        EspNullSlice::from_slice(&data, usize::MAX),
+        Err(ReadError::UnexpectedEndOfSlice(usize::MAX))
+    );
+
+    // pad length bigger then the preceding data
+    assert_matches!(
+        EspNullSlice::from_slice(&[1, 2, ip_number::UDP], 0),
+        Err(ReadError::EspPadLengthTooLarge(2))
+    );
+}
+
+#[test]
+fn slice_transport_mode() {
+    let udp = {
+        let mut result = Vec::new();
+        UdpHeader{ source_port: 1, destination_port: 2, length: 11, checksum: 0 }.write(&mut result).unwrap();
+        result.extend_from_slice(&[7,8,9]);
+        result
+    };
+    let packet = ipv4(IpNumber::EncapsulatingSecurityPayload, &esp(ip_number::UDP, &udp));
+
+    // not decoded by default
+    {
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(Some(TransportSlice::Unknown(ip_number::ENCAP_SEC)), sliced.transport);
+        let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert_eq!(None, headers.tunnel);
+    }
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip_with_limits(&packet, limits()).unwrap();
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Esp(h)) if 9 == h.sequence_number());
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[7,8,9], sliced.payload);
+        assert_eq!(None, sliced.inner);
+
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(
+            Some(TunnelHeader::Esp(EspHeader{ spi: 0x1234_5678, sequence_number: 9 })),
+            owned.tunnel
+        );
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ip_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(
+            Some(TunnelHeader::Esp(EspHeader{ spi: 0x1234_5678, sequence_number: 9 })),
+            headers.tunnel
+        );
+        assert_matches!(headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Payload::Udp(&[7,8,9]), headers.payload);
+    }
+}
+
+#[test]
+fn transport_mode_round_trip() {
+    let udp = {
+        let mut result = Vec::new();
+        UdpHeader{ source_port: 1, destination_port: 2, length: 11, checksum: 0 }.write(&mut result).unwrap();
+        result.extend_from_slice(&[7,8,9]);
+        result
+    };
+    let esp = esp(ip_number::UDP, &udp);
+    let mut packet = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [2,3,4,5,6,7],
+        ether_type: ether_type::IPV4,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&ipv4(IpNumber::EncapsulatingSecurityPayload, &esp));
+    // ethernet padding
+    packet.extend_from_slice(&[0;4]);
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    // the esp header precedes the udp header
+    let headers = sliced.header_bytes();
+    assert_eq!(&esp[..8], headers[5]);
+    assert_eq!(&udp[..8], headers[6]);
+    assert_eq!(&[7,8,9], sliced.payload);
+    // padding, pad length, next header & icv
+    assert_eq!(&esp[8 + udp.len()..], sliced.trailer);
+    assert_eq!(&[0;4], sliced.padding);
+    assert_eq!(14 + 20 + 8 + 8, sliced.headers_len());
+
+    // header bytes
+    {
+        let mut copy = headers.concat();
+        copy.extend_from_slice(sliced.payload);
+        copy.extend_from_slice(sliced.trailer);
+        copy.extend_from_slice(sliced.padding);
+        assert_eq!(packet, copy);
+    }
+
+    // io slices
+    {
+        use std::io::Write;
+        let mut out = Vec::new();
+        for slice in sliced.io_slices().iter() {
+            out.write_all(slice).unwrap();
+        }
+        assert_eq!(packet, out);
+    }
+
+    // bpf programs see the original frame (returning the length & the udp
+    // destination port)
+    for program in ["2\n128 0 0 0\n22 0 0 0\n", "2\n40 0 0 44\n22 0 0 0\n"].iter() {
+        let program = etherparse::bpf::BpfProgram::from_tcpdump_ddd(program).unwrap();
+        assert_eq!(program.run(&packet), program.run_sliced(&sliced));
+    }
+
+    // icmp error responses quote the complete ip packet
+    {
+        use etherparse::icmpv4::*;
+        let value = Icmp4Type::TimeExceeded(TimeExceededCode::TtlExceededInTransit);
+        let response = etherparse::icmpv4::error_response([9,9,9,9], 64, value, &sliced).unwrap();
+        let (_, message) = Ipv4Header::from_slice(&response).unwrap();
+        let (_, quoted) = Icmp4Type::from_bytes(message).unwrap();
+        assert_eq!(&packet[14..packet.len() - 4], quoted);
+    }
+}
+
+#[test]
+fn slice_tunnel_mode() {
+    let inner = {
+        let builder = PacketBuilder::
+            ipv6([1;16], [2;16], 20)
+            .udp(1, 2);
+        let mut result = Vec::with_capacity(builder.size(2));
+        builder.write(&mut result, &[3,4]).unwrap();
+        result
+    };
+    let mut packet = {
+        let mut result = Vec::new();
+        Ethernet2Header{
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::IPV4,
+        }.write(&mut result).unwrap();
+        result.extend_from_slice(&ipv4(IpNumber::EncapsulatingSecurityPayload, &esp(ip_number::IPV6, &inner)));
+        result
+    };
+    // ethernet padding is not interpreted as part of the esp trailer
+    packet.extend_from_slice(&[0;4]);
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Esp(_)));
+    assert_eq!(None, sliced.transport);
+    assert_eq!(&inner[..], sliced.payload);
+    // pad length, next header & icv
+    assert_eq!(2 + 12, sliced.trailer.len());
+    assert_eq!(&[0;4], sliced.padding);
+    let inner_sliced = sliced.inner.unwrap();
+    assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_eq!(&[3,4], inner_sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert_matches!(headers.tunnel, Some(TunnelHeader::Esp(_)));
+    assert_eq!(None, headers.transport);
+    assert_eq!(
+        Payload::Ether{ ether_type: ether_type::IPV6, payload: &inner },
+        headers.payload
+    );
+    assert_eq!(Payload::Udp(&[3,4]), headers.inner.unwrap().payload);
+}
+
+#[test]
+fn slice_errors() {
+    // icv longer then the payload
+    let packet = ipv4(IpNumber::EncapsulatingSecurityPayload, &esp(ip_number::UDP, &[]));
+    let limits = ParseLimits{
+        esp_null_icv_len: Some(100),
+        ..Default::default()
+    };
+    assert_matches!(
+        SlicedPacket::from_ip_with_limits(&packet, limits),
+        Err(ReadError::UnexpectedEndOfSlice(130))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_limits(&packet, limits),
+        Err(ReadError::UnexpectedEndOfSlice(102))
+    );
+
+    // truncated esp header
+    let packet = ipv4(IpNumber::EncapsulatingSecurityPayload, &[0;4]);
+    assert_matches!(
+        SlicedPacket::from_ip_with_limits(&packet, self::limits()),
+        Err(ReadError::UnexpectedEndOfSlice(28))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_limits(&packet, self::limits()),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+}
@@ -0,0 +1,377 @@
+use etherparse::*;
+
+use super::super::*;
+use super::{limits, inner_frame, outer_ipv6_frame};
+
+use std::io::Cursor;
+
+/// Geneve header with two options.
+fn header(protocol_type: u16) -> GeneveHeader {
+    GeneveHeader{
+        version: 0,
+        control_packet: false,
+        critical_options_present: true,
+        protocol_type,
+        vni: 0x123456,
+        options: vec![
+            GeneveOption{ class: 0x0102, option_type: 0x83, data: vec![1,2,3,4] },
+            GeneveOption{ class: 0xffff, option_type: 1, data: Vec::new() },
+        ],
+    }
+}
+
+fn geneve_option_any() -> impl Strategy<Value = GeneveOption> {
+    (
+        any::<u16>(),
+        any::<u8>(),
+        // limited to 30 words so two options never exceed GeneveHeader::MAX_OPTIONS_LEN
+        (0..=30usize).prop_flat_map(|len| proptest::collection::vec(any::<u8>(), len*4)),
+    ).prop_map(|(class, option_type, data)| GeneveOption{ class, option_type, data })
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        version in 0..=GeneveHeader::MAX_VERSION,
+        control_packet in any::<bool>(),
+        critical_options_present in any::<bool>(),
+        protocol_type in any::<u16>(),
+        vni in 0..=GeneveHeader::MAX_VNI,
+        options in proptest::collection::vec(geneve_option_any(), 0..3),
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = GeneveHeader{
+            version,
+            control_packet,
+            critical_options_present,
+            protocol_type,
+            vni,
+            options,
+        };
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+        let len = header.header_len();
+
+        // header
+        {
+            let (decoded, decoded_rest) = GeneveHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+        }
+
+        // slice
+        {
+            let slice = GeneveHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..len], slice.slice());
+            assert_eq!(version, slice.version());
+            assert_eq!(control_packet, slice.control_packet());
+            assert_eq!(critical_options_present, slice.critical_options_present());
+            assert_eq!(protocol_type, slice.protocol_type());
+            assert_eq!(vni, slice.vni());
+            assert_eq!(&bytes[GeneveHeader::MIN_LEN..len], slice.options_slice());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+
+            // options
+            let mut iter = slice.options();
+            for option in &header.options {
+                let option_slice = iter.next().unwrap();
+                assert_eq!(option.class, option_slice.class());
+                assert_eq!(option.option_type, option_slice.option_type());
+                assert_eq!(option.is_critical(), option_slice.is_critical());
+                assert_eq!(option.data.len() / 4, usize::from(option_slice.length()));
+                assert_eq!(&option.data[..], option_slice.data());
+                assert_eq!(option.option_len(), option_slice.slice().len());
+                assert_eq!(option, &option_slice.to_option());
+            }
+            assert_eq!(None, iter.next());
+            assert!(iter.rest().is_empty());
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..len], &buffer[..]);
+            assert_eq!(header, GeneveHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for cut in 0..len {
+            assert_matches!(
+                GeneveHeader::from_slice(&bytes[..cut]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+            assert_matches!(
+                GeneveHeader::read(&mut Cursor::new(&bytes[..cut])),
+                Err(ReadError::IoError(_))
+            );
+        }
+    }
+}
+
+#[test]
+fn to_bytes() {
+    assert_eq!(
+        Ok(vec![
+            3, 0b0100_0000, 0x65, 0x58, 0x12, 0x34, 0x56, 0,
+            1, 2, 0x83, 1, 1, 2, 3, 4,
+            0xff, 0xff, 1, 0,
+        ]),
+        header(ether_type::TRANSPARENT_ETHERNET_BRIDGING).to_bytes()
+    );
+    assert_eq!(20, header(0).header_len());
+    assert_eq!(12, header(0).options_len());
+}
+
+#[test]
+fn to_bytes_value_errors() {
+    // version
+    {
+        let mut header = header(0);
+        header.version = GeneveHeader::MAX_VERSION + 1;
+        assert_eq!(
+            Err(ValueError::U8TooLarge{
+                value: GeneveHeader::MAX_VERSION + 1,
+                max: GeneveHeader::MAX_VERSION,
+                field: ErrorField::GeneveVersion,
+            }),
+            header.to_bytes()
+        );
+    }
+    // vni
+    {
+        let mut header = header(0);
+        header.vni = GeneveHeader::MAX_VNI + 1;
+        assert_eq!(
+            Err(ValueError::U32TooLarge{
+                value: GeneveHeader::MAX_VNI + 1,
+                max: GeneveHeader::MAX_VNI,
+                field: ErrorField::GeneveVni,
+            }),
+            header.to_bytes()
+        );
+    }
+    // option data length
+    for len in [1, 2, 3, 5, GeneveOption::MAX_DATA_LEN + 4] {
+        let mut header = header(0);
+        header.options[1].data = vec![0;len];
+        assert_eq!(
+            Err(ValueError::GeneveOptionDataLengthBad(len)),
+            header.to_bytes()
+        );
+        assert_matches!(
+            header.write(&mut Vec::new()),
+            Err(WriteError::ValueError(ValueError::GeneveOptionDataLengthBad(_)))
+        );
+    }
+    // options length
+    {
+        let mut header = header(0);
+        header.options = vec![
+            GeneveOption{
+                class: 0,
+                option_type: 0,
+                data: vec![0;GeneveOption::MAX_DATA_LEN],
+            };
+            2
+        ];
+        header.options.push(GeneveOption{ class: 0, option_type: 0, data: vec![0;4] });
+        assert_eq!(
+            Err(ValueError::GeneveOptionsLengthTooLarge(2*128 + 8)),
+            header.to_bytes()
+        );
+        header.options.pop();
+        assert_eq!(GeneveHeader::MAX_OPTIONS_LEN + 4, header.options_len());
+        header.options[1].data.truncate(GeneveOption::MAX_DATA_LEN - 4);
+        assert_eq!(GeneveHeader::MAX_LEN, header.to_bytes().unwrap().len());
+    }
+}
+
+#[test]
+fn write_io_error() {
+    let mut writer = TestWriter::with_max_size(19);
+    assert_matches!(
+        header(0).write(&mut writer),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn from_slice_option_length_bad() {
+    // options area of 4 bytes containing an option with 4 bytes of data
+    let bytes = [1, 0, 0x65, 0x58, 0, 0, 1, 0, 0, 1, 2, 1, 1, 2, 3, 4];
+    assert_matches!(
+        GeneveHeaderSlice::from_slice(&bytes),
+        Err(ReadError::GeneveOptionLengthBad(1))
+    );
+    assert_matches!(
+        GeneveHeader::read(&mut Cursor::new(&bytes)),
+        Err(ReadError::GeneveOptionLengthBad(1))
+    );
+}
+
+#[test]
+fn slice_geneve() {
+    let inner = inner_frame(&[1,2,3,4]);
+    let geneve_and_inner = {
+        let mut result = header(ether_type::TRANSPARENT_ETHERNET_BRIDGING).to_bytes().unwrap();
+        result.extend_from_slice(&inner);
+        result
+    };
+    let packet = outer_ipv6_frame(GeneveHeader::PORT, &geneve_and_inner);
+
+    // not decoded by default
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&geneve_and_inner[..], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(None, headers.tunnel);
+        assert_eq!(Payload::Udp(&geneve_and_inner), headers.payload);
+    }
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Geneve(geneve)) if 0x123456 == geneve.vni());
+        assert_eq!(&inner[..], sliced.payload);
+        assert_eq!(14 + 40 + 8 + 20, sliced.headers_len());
+
+        let inner_sliced = sliced.inner.as_ref().unwrap();
+        assert_matches!(inner_sliced.link, Some(LinkSlice::Ethernet2(_)));
+        assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+        let mut copy = sliced.header_bytes().concat();
+        copy.extend_from_slice(sliced.payload);
+        assert_eq!(packet, copy);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(
+            Some(TunnelHeader::Geneve(header(ether_type::TRANSPARENT_ETHERNET_BRIDGING))),
+            headers.tunnel
+        );
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::TRANSPARENT_ETHERNET_BRIDGING, payload: &inner },
+            headers.payload
+        );
+        assert_eq!(14 + 40 + 8 + 20, headers.header_len());
+
+        let inner_headers = headers.inner.as_ref().unwrap();
+        assert_matches!(inner_headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Payload::Udp(&[1,2,3,4]), inner_headers.payload);
+    }
+
+    // owned packet
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(
+            Some(TunnelHeader::Geneve(header(ether_type::TRANSPARENT_ETHERNET_BRIDGING))),
+            owned.tunnel
+        );
+    }
+}
+
+#[test]
+fn slice_geneve_ip_payload() {
+    // ipv4 packet directly following the geneve header
+    let ip_packet = {
+        let builder = PacketBuilder::
+            ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let mut result = Vec::with_capacity(builder.size(2));
+        builder.write(&mut result, &[5,6]).unwrap();
+        result
+    };
+    let packet = outer_ipv6_frame(GeneveHeader::PORT, &{
+        let mut result = header(ether_type::IPV4).to_bytes().unwrap();
+        result.extend_from_slice(&ip_packet);
+        result
+    });
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    let inner = sliced.inner.unwrap();
+    assert_eq!(None, inner.link);
+    assert_matches!(inner.ip, Some(InternetSlice::Ipv4(_, _)));
+    assert_eq!(&[5,6], inner.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert_eq!(
+        Payload::Ether{ ether_type: ether_type::IPV4, payload: &ip_packet },
+        headers.payload
+    );
+    assert_eq!(Payload::Udp(&[5,6]), headers.inner.unwrap().payload);
+
+    // unknown protocol types are not decoded
+    let packet = outer_ipv6_frame(GeneveHeader::PORT, &{
+        let mut result = header(0x1234).to_bytes().unwrap();
+        result.extend_from_slice(&[1,2,3]);
+        result
+    });
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Geneve(_)));
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&[1,2,3], sliced.payload);
+}
+
+#[test]
+fn slice_errors() {
+    // truncated options
+    let packet = outer_ipv6_frame(GeneveHeader::PORT, &header(0).to_bytes().unwrap()[..12]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(82))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(20))
+    );
+
+    // bad option length
+    let packet = outer_ipv6_frame(GeneveHeader::PORT, &[1, 0, 0x65, 0x58, 0, 0, 1, 0, 0, 1, 2, 1]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::GeneveOptionLengthBad(1))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::GeneveOptionLengthBad(1))
+    );
+}
+
+#[test]
+fn compose() {
+    let stack = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: 0,
+        } /
+        Ipv4Header::new(0, 64, IpNumber::Udp, [10,0,0,1], [10,0,0,2]) /
+        UdpHeader{ source_port: 1234, destination_port: GeneveHeader::PORT, length: 0, checksum: 0 } /
+        header(ether_type::TRANSPARENT_ETHERNET_BRIDGING) /
+        Ethernet2Header{
+            source: [3;6],
+            destination: [4;6],
+            ether_type: 0,
+        } /
+        Ipv4Header::new(0, 64, IpNumber::Udp, [10,0,0,3], [10,0,0,4]) /
+        UdpHeader{ source_port: 1, destination_port: 2, length: 0, checksum: 0 } /
+        [1,2,3];
+    let bytes = stack.to_bytes().unwrap();
+    assert_eq!(stack.len(), bytes.len());
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&bytes, limits()).unwrap();
+    assert_matches!(&sliced.tunnel, Some(TunnelSlice::Geneve(geneve)) if 2 == geneve.options().count());
+    assert_eq!(&[1,2,3], sliced.inner.unwrap().payload);
+}
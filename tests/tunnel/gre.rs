@@ -0,0 +1,481 @@
+use etherparse::*;
+
+use super::super::*;
+
+use std::io::Cursor;
+
+/// GRE headers with all combinations of the optional fields.
+fn headers() -> Vec<GreHeader> {
+    let mut result = Vec::new();
+    for checksum in &[None, Some(0x1234)] {
+        for key in &[None, Some(0x5678_9abc)] {
+            for sequence_number in &[None, Some(0xdef0_1234)] {
+                result.push(GreHeader{
+                    protocol_type: ether_type::IPV4,
+                    checksum: *checksum,
+                    key: *key,
+                    sequence_number: *sequence_number,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Serialized IPv4 packet with the given protocol & payload.
+fn ipv4(protocol: IpNumber, payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    Ipv4Header::new(payload.len() as u16, 20, protocol, [1,2,3,4], [5,6,7,8])
+        .write(&mut result)
+        .unwrap();
+    result.extend_from_slice(payload);
+    result
+}
+
+/// Serialized IPv4 UDP packet.
+fn ipv4_udp(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, payload).unwrap();
+    result
+}
+
+/// Serialized GRE header followed by the given payload.
+fn gre(protocol_type: u16, payload: &[u8]) -> Vec<u8> {
+    let mut result = GreHeader{
+        protocol_type,
+        checksum: None,
+        key: Some(1),
+        sequence_number: None,
+    }.to_bytes();
+    result.extend_from_slice(payload);
+    result
+}
+
+#[test]
+fn header_len() {
+    let expected = [4, 8, 8, 12, 8, 12, 12, 16];
+    for (header, expected) in headers().iter().zip(expected.iter()) {
+        assert_eq!(*expected, header.header_len());
+        assert_eq!(*expected, header.to_bytes().len());
+    }
+}
+
+#[test]
+fn to_bytes() {
+    assert_eq!(
+        vec![0, 0, 0x65, 0x58],
+        GreHeader{
+            protocol_type: ether_type::TRANSPARENT_ETHERNET_BRIDGING,
+            ..Default::default()
+        }.to_bytes()
+    );
+    assert_eq!(
+        vec![
+            0b1011_0000, 0, 0x08, 0x00,
+            0x12, 0x34, 0, 0,
+            0x56, 0x78, 0x9a, 0xbc,
+            0xde, 0xf0, 0x12, 0x34,
+        ],
+        headers()[7].to_bytes()
+    );
+}
+
+#[test]
+fn from_slice() {
+    for header in headers() {
+        let mut bytes = header.to_bytes();
+        bytes.extend_from_slice(&[1,2,3]);
+
+        // header
+        let (decoded, rest) = GreHeader::from_slice(&bytes).unwrap();
+        assert_eq!(header, decoded);
+        assert_eq!(&[1,2,3], rest);
+
+        // slice
+        let slice = GreHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(&bytes[..header.header_len()], slice.slice());
+        assert_eq!(header.checksum.is_some(), slice.checksum_present());
+        assert_eq!(header.key.is_some(), slice.key_present());
+        assert_eq!(header.sequence_number.is_some(), slice.sequence_number_present());
+        assert_eq!(0, slice.version());
+        assert_eq!(header.protocol_type, slice.protocol_type());
+        assert_eq!(header.checksum, slice.checksum());
+        assert_eq!(header.key, slice.key());
+        assert_eq!(header.sequence_number, slice.sequence_number());
+        assert_eq!(header, slice.to_header());
+
+        // unexpected end of slice
+        for len in 0..header.header_len() {
+            assert_matches!(
+                GreHeader::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+        }
+        assert_matches!(
+            GreHeaderSlice::from_slice(&bytes[..2]),
+            Err(ReadError::UnexpectedEndOfSlice(4))
+        );
+        assert_matches!(
+            GreHeaderSlice::from_slice(&bytes[..header.header_len() - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(len)) if len == header.header_len()
+        );
+    }
+}
+
+#[test]
+fn from_slice_reserved_bits() {
+    // routing & strict source route bits as well as the reserved0 bits are ignored
+    let bytes = [0b0100_1111, 0b1111_1000, 0x86, 0xdd];
+    assert_eq!(
+        GreHeader{
+            protocol_type: ether_type::IPV6,
+            ..Default::default()
+        },
+        GreHeader::from_slice(&bytes).unwrap().0
+    );
+}
+
+#[test]
+fn from_slice_unsupported_version() {
+    for version in 1..=7 {
+        let bytes = [0, version, 0x88, 0x0b];
+        assert_matches!(
+            GreHeader::from_slice(&bytes),
+            Err(ReadError::GreUnsupportedVersion(v)) if v == version
+        );
+        assert_matches!(
+            GreHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GreUnsupportedVersion(v)) if v == version
+        );
+    }
+}
+
+#[test]
+fn read_write() {
+    for header in headers() {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        assert_eq!(header.to_bytes(), buffer);
+
+        let mut cursor = Cursor::new(&buffer);
+        assert_eq!(header, GreHeader::read(&mut cursor).unwrap());
+        assert_eq!(header.header_len() as u64, cursor.position());
+
+        // unexpected end
+        for len in 0..header.header_len() {
+            assert_matches!(
+                GreHeader::read(&mut Cursor::new(&buffer[..len])),
+                Err(ReadError::IoError(_))
+            );
+        }
+
+        // write error
+        let mut writer = TestWriter::with_max_size(header.header_len() - 1);
+        assert_matches!(header.write(&mut writer), Err(WriteError::IoError(_)));
+    }
+}
+
+#[test]
+fn calc_checksum() {
+    let payload = [1,2,3,4,5];
+    let mut header = GreHeader{
+        protocol_type: ether_type::IPV4,
+        checksum: Some(0xffff),
+        key: Some(0x1234_5678),
+        sequence_number: Some(9),
+    };
+    header.checksum = Some(header.calc_checksum(&payload));
+
+    // the checksum over the header & payload results in 0
+    let mut bytes = header.to_bytes();
+    bytes.extend_from_slice(&payload);
+    assert_eq!(
+        0,
+        etherparse::checksum::Sum16BitWords::new()
+            .add_slice(&bytes)
+            .ones_complement()
+    );
+}
+
+#[test]
+fn slice_ipv4_in_gre() {
+    let inner = ipv4_udp(&[1,2,3,4]);
+    let packet = {
+        let mut result = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: ether_type::IPV4,
+        }.to_bytes().to_vec();
+        result.extend_from_slice(&ipv4(IpNumber::Gre, &gre(ether_type::IPV4, &inner)));
+        result
+    };
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.transport);
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Gre(gre)) if gre.key() == Some(1));
+        assert_eq!(&inner[..], sliced.payload);
+
+        let inner_sliced = sliced.inner.as_ref().unwrap();
+        assert_eq!(None, inner_sliced.link);
+        assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+        // the headers & payload of the outer packet result in the original packet
+        let mut copy = sliced.header_bytes().concat();
+        copy.extend_from_slice(sliced.payload);
+        assert_eq!(packet, copy);
+        assert_eq!(14 + 20 + 8, sliced.headers_len());
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(None, headers.transport);
+        assert_eq!(
+            Some(TunnelHeader::Gre(GreHeader{
+                protocol_type: ether_type::IPV4,
+                checksum: None,
+                key: Some(1),
+                sequence_number: None,
+            })),
+            headers.tunnel
+        );
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::IPV4, payload: &inner },
+            headers.payload
+        );
+        assert_eq!(14 + 20 + 8, headers.header_len());
+
+        let inner_headers = headers.inner.as_ref().unwrap();
+        assert_matches!(inner_headers.ip, Some(IpHeader::Version4(_, _)));
+        assert_matches!(inner_headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Payload::Udp(&[1,2,3,4]), inner_headers.payload);
+    }
+}
+
+#[test]
+fn slice_ethernet_in_gre() {
+    let inner = {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([1;16], [2;16], 20)
+            .tcp(1, 2, 3, 4);
+        let mut result = Vec::with_capacity(builder.size(2));
+        builder.write(&mut result, &[5,6]).unwrap();
+        result
+    };
+    let packet = {
+        let mut result = Vec::new();
+        Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: (8 + inner.len()) as u16,
+            next_header: ip_number::GRE,
+            hop_limit: 1,
+            source: [1;16],
+            destination: [2;16],
+        }.write(&mut result).unwrap();
+        result.extend_from_slice(&gre(ether_type::TRANSPARENT_ETHERNET_BRIDGING, &inner));
+        result
+    };
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Gre(_)));
+    let inner_sliced = sliced.inner.unwrap();
+    assert_matches!(inner_sliced.link, Some(LinkSlice::Ethernet2(_)));
+    assert_matches!(inner_sliced.transport, Some(TransportSlice::Tcp(_)));
+    assert_eq!(&[5,6], inner_sliced.payload);
+
+    let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_matches!(headers.tunnel, Some(TunnelHeader::Gre(_)));
+    let inner_headers = headers.inner.unwrap();
+    assert!(inner_headers.link.is_some());
+    assert_matches!(inner_headers.transport, Some(TransportHeader::Tcp(_)));
+    assert_eq!(Payload::Tcp(&[5,6]), inner_headers.payload);
+}
+
+#[test]
+fn slice_unknown_protocol_type() {
+    let packet = ipv4(IpNumber::Gre, &gre(0x880b, &[1,2,3]));
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Gre(_)));
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&[1,2,3], sliced.payload);
+
+    let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_matches!(headers.tunnel, Some(TunnelHeader::Gre(_)));
+    assert_eq!(None, headers.inner);
+    assert_eq!(Payload::Ether{ ether_type: 0x880b, payload: &[1,2,3] }, headers.payload);
+}
+
+#[test]
+fn slice_unsupported_version() {
+    // enhanced GRE header (version 1) as used by PPTP
+    let payload = [0x30, 0x81, 0x88, 0x0b, 0, 0, 0, 1];
+    let packet = ipv4(IpNumber::Gre, &payload);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_eq!(None, sliced.tunnel);
+    assert_eq!(Some(TransportSlice::Unknown(ip_number::GRE)), sliced.transport);
+    assert_eq!(&payload, sliced.payload);
+
+    let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(None, headers.tunnel);
+    assert_eq!(None, headers.transport);
+    assert_eq!(Payload::Unknown(ip_number::GRE, &payload), headers.payload);
+}
+
+#[test]
+fn slice_errors() {
+    // truncated gre header
+    let packet = ipv4(IpNumber::Gre, &[0x80, 0, 0x08, 0x00, 0]);
+    assert_matches!(
+        SlicedPacket::from_ip(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(28))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+
+    // truncated inner packet (outer headers & payload are kept)
+    let packet = ipv4(IpNumber::Gre, &gre(ether_type::IPV4, &[0x45, 0]));
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Gre(_)));
+    assert!(sliced.inner.is_none());
+    assert_eq!(sliced.payload, &[0x45, 0]);
+    let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_matches!(headers.tunnel, Some(TunnelHeader::Gre(_)));
+    assert!(headers.inner.is_none());
+    assert_eq!(
+        Payload::Ether{ ether_type: ether_type::IPV4, payload: &[0x45, 0] },
+        headers.payload
+    );
+}
+
+#[test]
+fn tunnel_depth_limit() {
+    // gre in gre
+    let packet = ipv4(
+        IpNumber::Gre,
+        &gre(ether_type::IPV4, &ipv4(IpNumber::Gre, &gre(ether_type::IPV4, &ipv4_udp(&[])))),
+    );
+
+    for depth in 0..2 {
+        let limits = ParseLimits{
+            max_tunnel_depth: depth,
+            ..Default::default()
+        };
+        assert_matches!(
+            SlicedPacket::from_ip_with_limits(&packet, limits),
+            Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+        );
+        assert_matches!(
+            PacketHeaders::from_ip_slice_with_limits(&packet, limits),
+            Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+        );
+    }
+
+    let limits = ParseLimits{
+        max_tunnel_depth: 2,
+        ..Default::default()
+    };
+    let sliced = SlicedPacket::from_ip_with_limits(&packet, limits).unwrap();
+    let innermost = sliced.inner.unwrap().inner.unwrap();
+    assert_matches!(innermost.transport, Some(TransportSlice::Udp(_)));
+
+    let headers = PacketHeaders::from_ip_slice_with_limits(&packet, limits).unwrap();
+    let innermost = headers.inner.unwrap().inner.unwrap();
+    assert_matches!(innermost.transport, Some(TransportHeader::Udp(_)));
+}
+
+#[test]
+fn nvgre() {
+    let header = GreHeader::nvgre(0x12_3456, 0x78).unwrap();
+    assert_eq!(
+        GreHeader{
+            protocol_type: ether_type::TRANSPARENT_ETHERNET_BRIDGING,
+            checksum: None,
+            key: Some(0x1234_5678),
+            sequence_number: None,
+        },
+        header
+    );
+    assert!(header.is_nvgre());
+    assert_eq!(Some(0x12_3456), header.nvgre_vsid());
+    assert_eq!(Some(0x78), header.nvgre_flow_id());
+
+    let bytes = header.to_bytes();
+    assert_eq!(&[0x20, 0, 0x65, 0x58, 0x12, 0x34, 0x56, 0x78], &bytes[..]);
+    let slice = GreHeaderSlice::from_slice(&bytes).unwrap();
+    assert!(slice.is_nvgre());
+    assert_eq!(Some(0x12_3456), slice.nvgre_vsid());
+    assert_eq!(Some(0x78), slice.nvgre_flow_id());
+
+    // vsid too large
+    assert_eq!(
+        Err(ValueError::U32TooLarge{
+            value: GreHeader::MAX_NVGRE_VSID + 1,
+            max: GreHeader::MAX_NVGRE_VSID,
+            field: ErrorField::NvgreVsid,
+        }),
+        GreHeader::nvgre(GreHeader::MAX_NVGRE_VSID + 1, 0)
+    );
+
+    // headers not matching the NVGRE format
+    for header in headers().into_iter().chain(std::iter::once(
+        GreHeader{
+            protocol_type: ether_type::TRANSPARENT_ETHERNET_BRIDGING,
+            checksum: None,
+            key: None,
+            sequence_number: None,
+        }
+    )) {
+        let bytes = header.to_bytes();
+        let slice = GreHeaderSlice::from_slice(&bytes).unwrap();
+        assert!(!header.is_nvgre());
+        assert!(!slice.is_nvgre());
+        assert_eq!(None, header.nvgre_vsid());
+        assert_eq!(None, slice.nvgre_vsid());
+        assert_eq!(None, header.nvgre_flow_id());
+        assert_eq!(None, slice.nvgre_flow_id());
+    }
+}
+
+#[test]
+fn slice_ethernet_in_nvgre() {
+    let inner = {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([10,0,0,1], [10,0,0,2], 20)
+            .udp(1, 2);
+        let mut result = Vec::with_capacity(builder.size(2));
+        builder.write(&mut result, &[5,6]).unwrap();
+        result
+    };
+    let mut payload = GreHeader::nvgre(0xab_cdef, 1).unwrap().to_bytes();
+    payload.extend_from_slice(&inner);
+    let packet = ipv4(IpNumber::Gre, &payload);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    match sliced.tunnel {
+        Some(TunnelSlice::Gre(ref gre)) => assert_eq!(Some(0xab_cdef), gre.nvgre_vsid()),
+        ref other => panic!("unexpected tunnel {:?}", other),
+    }
+    let inner_sliced = sliced.inner.unwrap();
+    assert_matches!(inner_sliced.link, Some(LinkSlice::Ethernet2(_)));
+    assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+    assert_eq!(&[5,6], inner_sliced.payload);
+
+    let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(Some(0xab_cdef), headers.tunnel.clone().and_then(|t| t.gre()).and_then(|g| g.nvgre_vsid()));
+    assert_eq!(Some(1), headers.tunnel.and_then(|t| t.gre()).and_then(|g| g.nvgre_flow_id()));
+    assert!(headers.inner.unwrap().link.is_some());
+}
@@ -0,0 +1,411 @@
+use etherparse::*;
+
+use super::super::*;
+use super::{limits, inner_ipv4, outer_ipv6_frame};
+
+use std::io::Cursor;
+
+/// G-PDU header with a PDU session container extension header.
+fn header() -> GtpuHeader {
+    GtpuHeader{
+        message_type: gtpu_message_type::G_PDU,
+        length: 0,
+        teid: 0x1234_5678,
+        sequence_number: None,
+        n_pdu_number: None,
+        extension_headers: vec![
+            GtpuPduSessionContainer{
+                pdu_type: GtpuPduSessionContainer::UPLINK,
+                qos_flow_identifier: 9,
+            }.to_extension_header().unwrap()
+        ],
+    }
+}
+
+fn extension_header_any() -> impl Strategy<Value = GtpuExtensionHeader> {
+    (
+        any::<u8>().prop_filter("type must not be 0", |v| 0 != *v),
+        (1..4usize).prop_flat_map(|len| proptest::collection::vec(any::<u8>(), len*4 - 2)),
+    ).prop_map(|(extension_type, content)| GtpuExtensionHeader{ extension_type, content })
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        message_type in any::<u8>(),
+        length in any::<u16>(),
+        teid in any::<u32>(),
+        sequence_number in proptest::option::of(any::<u16>()),
+        n_pdu_number in proptest::option::of(any::<u8>()),
+        extension_headers in proptest::collection::vec(extension_header_any(), 0..3),
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = GtpuHeader{
+            message_type,
+            length,
+            teid,
+            sequence_number,
+            n_pdu_number,
+            extension_headers,
+        };
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+        let len = header.header_len();
+
+        // header
+        {
+            let (decoded, decoded_rest) = GtpuHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+        }
+
+        // slice
+        {
+            let slice = GtpuHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..len], slice.slice());
+            assert_eq!(1, slice.version());
+            assert!(slice.protocol_type());
+            assert_eq!(!header.extension_headers.is_empty(), slice.extension_header_present());
+            assert_eq!(sequence_number.is_some(), slice.sequence_number_present());
+            assert_eq!(n_pdu_number.is_some(), slice.n_pdu_number_present());
+            assert_eq!(message_type, slice.message_type());
+            assert_eq!(length, slice.length());
+            assert_eq!(teid, slice.teid());
+            assert_eq!(sequence_number, slice.sequence_number());
+            assert_eq!(n_pdu_number, slice.n_pdu_number());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+
+            // extension headers
+            let mut iter = slice.extension_headers();
+            for extension in &header.extension_headers {
+                let extension_slice = iter.next().unwrap();
+                assert_eq!(extension.extension_type, extension_slice.extension_type());
+                assert_eq!(&extension.content[..], extension_slice.content());
+                assert_eq!(extension.header_len(), extension_slice.slice().len());
+                assert_eq!(extension, &extension_slice.to_header());
+            }
+            assert_eq!(None, iter.next());
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..len], &buffer[..]);
+            assert_eq!(header, GtpuHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for cut in 0..len {
+            assert_matches!(
+                GtpuHeader::from_slice(&bytes[..cut]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+            assert_matches!(
+                GtpuHeader::read(&mut Cursor::new(&bytes[..cut])),
+                Err(ReadError::IoError(_))
+            );
+        }
+    }
+}
+
+#[test]
+fn to_bytes() {
+    assert_eq!(
+        Ok(vec![
+            0b0011_0100, 0xff, 0, 0, 0x12, 0x34, 0x56, 0x78,
+            0, 0, 0, 0x85,
+            1, 0x10, 9, 0,
+        ]),
+        header().to_bytes()
+    );
+    assert_eq!(16, header().header_len());
+
+    // without optional fields
+    let header = GtpuHeader{
+        message_type: gtpu_message_type::ECHO_REQUEST,
+        length: 0,
+        teid: 1,
+        ..Default::default()
+    };
+    assert_eq!(Ok(vec![0b0011_0000, 1, 0, 0, 0, 0, 0, 1]), header.to_bytes());
+    assert_eq!(8, header.header_len());
+}
+
+#[test]
+fn set_payload_len() {
+    let mut header = header();
+    header.set_payload_len(20).unwrap();
+    assert_eq!(28, header.length);
+
+    let max = usize::from(u16::MAX) - 8;
+    header.set_payload_len(max).unwrap();
+    assert_eq!(u16::MAX, header.length);
+    assert_eq!(
+        Err(ValueError::GtpuPayloadLengthTooLarge(usize::from(u16::MAX) + 1)),
+        header.set_payload_len(max + 1)
+    );
+}
+
+#[test]
+fn to_bytes_extension_length_bad() {
+    for len in [0, 1, 3, 4, GtpuExtensionHeader::MAX_CONTENT_LEN + 4] {
+        let mut header = header();
+        header.extension_headers[0].content = vec![0;len];
+        assert_eq!(
+            Err(ValueError::GtpuExtensionHeaderLengthBad(len)),
+            header.to_bytes()
+        );
+        assert_matches!(
+            header.write(&mut Vec::new()),
+            Err(WriteError::ValueError(ValueError::GtpuExtensionHeaderLengthBad(_)))
+        );
+    }
+}
+
+#[test]
+fn write_io_error() {
+    let mut writer = TestWriter::with_max_size(15);
+    assert_matches!(
+        header().write(&mut writer),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn pdu_session_container() {
+    let container = GtpuPduSessionContainer{
+        pdu_type: GtpuPduSessionContainer::DOWNLINK,
+        qos_flow_identifier: GtpuPduSessionContainer::MAX_QOS_FLOW_IDENTIFIER,
+    };
+    let extension = container.to_extension_header().unwrap();
+    assert_eq!(Some(container.clone()), extension.pdu_session_container());
+
+    // slice
+    let bytes = GtpuHeader{
+        extension_headers: vec![extension],
+        ..header()
+    }.to_bytes().unwrap();
+    let slice = GtpuHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(Some(container), slice.extension_headers().next().unwrap().pdu_session_container());
+
+    // other extension type
+    let other = GtpuExtensionHeader{
+        extension_type: gtpu_extension_header_type::UDP_PORT,
+        content: vec![1,2],
+    };
+    assert_eq!(None, other.pdu_session_container());
+    let bytes = GtpuHeader{
+        extension_headers: vec![other],
+        ..header()
+    }.to_bytes().unwrap();
+    let slice = GtpuHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(None, slice.extension_headers().next().unwrap().pdu_session_container());
+
+    // content too short
+    assert_eq!(None, GtpuPduSessionContainer::from_content(&[1]));
+
+    // value errors
+    assert_eq!(
+        Err(ValueError::U8TooLarge{
+            value: GtpuPduSessionContainer::MAX_PDU_TYPE + 1,
+            max: GtpuPduSessionContainer::MAX_PDU_TYPE,
+            field: ErrorField::GtpuPduType,
+        }),
+        GtpuPduSessionContainer{
+            pdu_type: GtpuPduSessionContainer::MAX_PDU_TYPE + 1,
+            qos_flow_identifier: 0,
+        }.to_extension_header()
+    );
+    assert_eq!(
+        Err(ValueError::U8TooLarge{
+            value: GtpuPduSessionContainer::MAX_QOS_FLOW_IDENTIFIER + 1,
+            max: GtpuPduSessionContainer::MAX_QOS_FLOW_IDENTIFIER,
+            field: ErrorField::GtpuQosFlowIdentifier,
+        }),
+        GtpuPduSessionContainer{
+            pdu_type: 0,
+            qos_flow_identifier: GtpuPduSessionContainer::MAX_QOS_FLOW_IDENTIFIER + 1,
+        }.to_extension_header()
+    );
+}
+
+#[test]
+fn from_slice_errors() {
+    // unsupported version
+    for version in [0u8, 2, 7] {
+        let mut bytes = header().to_bytes().unwrap();
+        bytes[0] = (bytes[0] & 0b1_1111) | (version << 5);
+        assert_matches!(
+            GtpuHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GtpuUnsupportedVersion(v)) if v == version
+        );
+        assert_matches!(
+            GtpuHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GtpuUnsupportedVersion(v)) if v == version
+        );
+    }
+
+    // extension header with a length of 0
+    let mut bytes = header().to_bytes().unwrap();
+    bytes[12] = 0;
+    assert_matches!(
+        GtpuHeaderSlice::from_slice(&bytes),
+        Err(ReadError::GtpuExtensionHeaderLengthZero)
+    );
+    assert_matches!(
+        GtpuHeader::read(&mut Cursor::new(&bytes)),
+        Err(ReadError::GtpuExtensionHeaderLengthZero)
+    );
+}
+
+#[test]
+fn slice_gtpu() {
+    let inner = inner_ipv4(&[1,2,3,4]);
+    let gtpu_and_inner = {
+        let mut header = header();
+        header.set_payload_len(inner.len()).unwrap();
+        let mut result = header.to_bytes().unwrap();
+        result.extend_from_slice(&inner);
+        result
+    };
+    let packet = outer_ipv6_frame(GtpuHeader::PORT, &gtpu_and_inner);
+
+    // not decoded by default
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(&gtpu_and_inner[..], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(None, headers.tunnel);
+        assert_eq!(Payload::Udp(&gtpu_and_inner), headers.payload);
+    }
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Gtpu(gtpu)) if 0x1234_5678 == gtpu.teid());
+        assert_eq!(&inner[..], sliced.payload);
+        assert_eq!(14 + 40 + 8 + 16, sliced.headers_len());
+
+        let inner_sliced = sliced.inner.as_ref().unwrap();
+        assert_eq!(None, inner_sliced.link);
+        assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+        let mut copy = sliced.header_bytes().concat();
+        copy.extend_from_slice(sliced.payload);
+        assert_eq!(packet, copy);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        let gtpu = headers.tunnel.clone().unwrap().gtpu().unwrap();
+        assert_eq!((16 - 8 + inner.len()) as u16, gtpu.length);
+        assert_eq!(
+            Some(GtpuPduSessionContainer{ pdu_type: 1, qos_flow_identifier: 9 }),
+            gtpu.extension_headers[0].pdu_session_container()
+        );
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::IPV4, payload: &inner },
+            headers.payload
+        );
+        assert_eq!(14 + 40 + 8 + 16, headers.header_len());
+        assert_eq!(Payload::Udp(&[1,2,3,4]), headers.inner.as_ref().unwrap().payload);
+    }
+
+    // owned packet
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_matches!(owned.tunnel, Some(TunnelHeader::Gtpu(_)));
+    }
+}
+
+#[test]
+fn slice_gtpu_non_ip_payload() {
+    // echo request (no payload decoding)
+    let echo = GtpuHeader{
+        message_type: gtpu_message_type::ECHO_REQUEST,
+        sequence_number: Some(1),
+        ..Default::default()
+    };
+    let mut gtpu_and_payload = echo.to_bytes().unwrap();
+    gtpu_and_payload.extend_from_slice(&[0x45, 0, 0]);
+    let packet = outer_ipv6_frame(GtpuHeader::PORT, &gtpu_and_payload);
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Gtpu(_)));
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&[0x45, 0, 0], sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert_eq!(Some(TunnelHeader::Gtpu(echo)), headers.tunnel);
+    assert_eq!(None, headers.inner);
+    assert_eq!(Payload::Udp(&[0x45, 0, 0]), headers.payload);
+
+    // g-pdu without an ip packet
+    let mut gtpu_and_payload = header().to_bytes().unwrap();
+    gtpu_and_payload.extend_from_slice(&[0x12, 0x34]);
+    let packet = outer_ipv6_frame(GtpuHeader::PORT, &gtpu_and_payload);
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&[0x12, 0x34], sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert_eq!(Payload::Udp(&[0x12, 0x34]), headers.payload);
+}
+
+#[test]
+fn slice_errors() {
+    // truncated extension header
+    let packet = outer_ipv6_frame(GtpuHeader::PORT, &header().to_bytes().unwrap()[..14]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(78))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(16))
+    );
+
+    // unsupported version
+    let packet = outer_ipv6_frame(GtpuHeader::PORT, &[0b0101_0000, 0xff, 0, 0, 0, 0, 0, 1]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::GtpuUnsupportedVersion(2))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::GtpuUnsupportedVersion(2))
+    );
+}
+
+#[test]
+fn compose() {
+    let stack = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: 0,
+        } /
+        Ipv4Header::new(0, 64, IpNumber::Udp, [10,0,0,1], [10,0,0,2]) /
+        UdpHeader{ source_port: GtpuHeader::PORT, destination_port: GtpuHeader::PORT, length: 0, checksum: 0 } /
+        header() /
+        Ipv4Header::new(0, 64, IpNumber::Udp, [10,0,0,3], [10,0,0,4]) /
+        UdpHeader{ source_port: 1, destination_port: 2, length: 0, checksum: 0 } /
+        [1,2,3];
+    let bytes = stack.to_bytes().unwrap();
+    assert_eq!(stack.len(), bytes.len());
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&bytes, limits()).unwrap();
+    assert_matches!(&sliced.tunnel, Some(TunnelSlice::Gtpu(gtpu)) if 8 + 20 + 8 + 3 == gtpu.length());
+    assert_eq!(&[1,2,3], sliced.inner.unwrap().payload);
+}
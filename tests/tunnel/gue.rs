@@ -0,0 +1,267 @@
+use etherparse::*;
+
+use super::super::*;
+use super::{limits, inner_ipv4, outer_ipv4_frame};
+
+use std::io::Cursor;
+
+/// GUE header followed by the given payload.
+fn gue_payload(header: &GueHeader, payload: &[u8]) -> Vec<u8> {
+    let mut result = header.to_bytes().unwrap();
+    result.extend_from_slice(payload);
+    result
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        control in any::<bool>(),
+        proto_ctype in any::<u8>(),
+        flags in any::<u16>(),
+        hlen in 0..=0b1_1111usize,
+        extension_byte in any::<u8>(),
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = GueHeader{
+            control,
+            proto_ctype,
+            flags,
+            extension_fields: vec![extension_byte;hlen*4],
+        };
+        let bytes = gue_payload(&header, &rest);
+        let len = header.header_len();
+        assert_eq!(4 + hlen*4, len);
+
+        // header
+        {
+            let (decoded, decoded_rest) = GueHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+            assert_eq!(Some(0), GueHeader::variant(&bytes));
+        }
+
+        // slice
+        {
+            let slice = GueHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..len], slice.slice());
+            assert_eq!(control, slice.control());
+            assert_eq!(hlen as u8, slice.hlen());
+            assert_eq!(proto_ctype, slice.proto_ctype());
+            assert_eq!(flags, slice.flags());
+            assert_eq!(&header.extension_fields[..], slice.extension_fields());
+            assert_eq!(header.payload_ether_type(), slice.payload_ether_type());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..len], &buffer[..]);
+            assert_eq!(header, GueHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for end in 0..len {
+            assert_matches!(
+                GueHeader::from_slice(&bytes[..end]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+            assert_matches!(
+                GueHeader::read(&mut Cursor::new(&bytes[..end])),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn from_slice_unsupported_variant() {
+    for variant in 1..4u8 {
+        let bytes = [variant << 6, 0, 0, 0];
+        assert_eq!(Some(variant), GueHeader::variant(&bytes));
+        assert_matches!(
+            GueHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GueUnsupportedVariant(v)) if v == variant
+        );
+        assert_matches!(
+            GueHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GueUnsupportedVariant(v)) if v == variant
+        );
+    }
+    assert_eq!(None, GueHeader::variant(&[]));
+}
+
+#[test]
+fn to_bytes() {
+    let header = GueHeader{
+        control: true,
+        proto_ctype: 3,
+        flags: 0x1234,
+        extension_fields: vec![5,6,7,8],
+    };
+    assert_eq!(Ok(vec![0b0010_0001, 3, 0x12, 0x34, 5, 6, 7, 8]), header.to_bytes());
+
+    // extension fields length not a multiple of 4
+    {
+        let header = GueHeader{ extension_fields: vec![0;3], ..Default::default() };
+        assert_eq!(Err(ValueError::GueExtensionFieldsLengthBad(3)), header.to_bytes());
+        assert_matches!(
+            header.write(&mut Vec::new()),
+            Err(WriteError::ValueError(ValueError::GueExtensionFieldsLengthBad(3)))
+        );
+    }
+    // extension fields too long
+    {
+        let len = GueHeader::MAX_EXTENSION_FIELDS_LEN + 4;
+        let header = GueHeader{ extension_fields: vec![0;len], ..Default::default() };
+        assert_eq!(Err(ValueError::GueExtensionFieldsLengthBad(len)), header.to_bytes());
+    }
+}
+
+#[test]
+fn payload_ether_type() {
+    for (proto, expected) in &[
+        (ip_number::IPV4, Some(ether_type::IPV4)),
+        (ip_number::IPV6, Some(ether_type::IPV6)),
+        (ip_number::UDP, None),
+    ] {
+        let header = GueHeader{ proto_ctype: *proto, ..Default::default() };
+        assert_eq!(*expected, header.payload_ether_type());
+
+        // control messages contain no ip packet
+        let header = GueHeader{ control: true, proto_ctype: *proto, ..Default::default() };
+        assert_eq!(None, header.payload_ether_type());
+    }
+}
+
+#[test]
+fn slice_ipv4_in_gue() {
+    let header = GueHeader{
+        proto_ctype: ip_number::IPV4,
+        extension_fields: vec![1,2,3,4],
+        ..Default::default()
+    };
+    let inner = inner_ipv4(&[1,2,3,4]);
+    let packet = outer_ipv4_frame(49152, GueHeader::PORT, &gue_payload(&header, &inner));
+
+    // not decoded by default
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(None, sliced.inner);
+    }
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Gue(h)) if 1 == h.hlen());
+        assert_eq!(&inner[..], sliced.payload);
+        assert_eq!(14 + 20 + 8 + 8, sliced.headers_len());
+
+        let inner_sliced = sliced.inner.as_ref().unwrap();
+        assert_eq!(None, inner_sliced.link);
+        assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(Some(TunnelHeader::Gue(header.clone())), owned.tunnel);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(Some(TunnelHeader::Gue(header.clone())), headers.tunnel);
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::IPV4, payload: &inner },
+            headers.payload
+        );
+        let inner_headers = headers.inner.as_ref().unwrap();
+        assert_matches!(inner_headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Payload::Udp(&[1,2,3,4]), inner_headers.payload);
+    }
+}
+
+#[test]
+fn slice_direct_ip() {
+    // variant 1 (ip packet directly following the udp header)
+    let inner = inner_ipv4(&[5,6]);
+    let packet = outer_ipv4_frame(49152, GueHeader::PORT, &inner);
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert_eq!(None, sliced.tunnel);
+    assert_eq!(&inner[..], sliced.payload);
+    let inner_sliced = sliced.inner.as_ref().unwrap();
+    assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+    assert_eq!(&[5,6], inner_sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert_eq!(None, headers.tunnel);
+    assert_eq!(
+        Payload::Ether{ ether_type: ether_type::IPV4, payload: &inner },
+        headers.payload
+    );
+    assert_eq!(Payload::Udp(&[5,6]), headers.inner.unwrap().payload);
+}
+
+#[test]
+fn slice_not_decoded() {
+    // control message
+    {
+        let header = GueHeader{ control: true, proto_ctype: ip_number::IPV4, ..Default::default() };
+        let packet = outer_ipv4_frame(49152, GueHeader::PORT, &gue_payload(&header, &[1,2,3]));
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::Gue(_)));
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&[1,2,3], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(Some(TunnelHeader::Gue(header)), headers.tunnel);
+        assert_eq!(None, headers.inner);
+        assert_eq!(Payload::Udp(&[1,2,3]), headers.payload);
+    }
+    // variant 1 payload that is neither IPv4 nor IPv6 & variant 2 & 3 payloads
+    for payload in &[[0x50, 1], [0x80, 2], [0xc0, 3]] {
+        let packet = outer_ipv4_frame(49152, GueHeader::PORT, payload);
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&payload[..], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, headers.tunnel);
+        assert_eq!(None, headers.inner);
+        assert_eq!(Payload::Udp(&payload[..]), headers.payload);
+    }
+    // empty udp payload
+    {
+        let packet = outer_ipv4_frame(49152, GueHeader::PORT, &[]);
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, headers.tunnel);
+    }
+}
+
+#[test]
+fn slice_errors() {
+    // truncated extension fields
+    let packet = outer_ipv4_frame(49152, GueHeader::PORT, &[0b0000_0001, ip_number::IPV4, 0, 0, 1, 2]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(50))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+
+    // truncated inner ip header
+    let header = GueHeader{ proto_ctype: ip_number::IPV4, ..Default::default() };
+    let packet = outer_ipv4_frame(49152, GueHeader::PORT, &gue_payload(&header, &[0x45, 0]));
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert!(sliced.inner.is_none());
+    assert_eq!(sliced.payload, &[0x45, 0]);
+}
@@ -0,0 +1,363 @@
+pub mod gre;
+pub mod vxlan;
+pub mod vxlan_gpe;
+pub mod geneve;
+pub mod gtpu;
+pub mod gue;
+pub mod teredo;
+pub mod esp;
+pub mod pppoe;
+pub mod pbb;
+
+use etherparse::*;
+
+/// Limits enabling the decoding of udp based tunnels.
+fn limits() -> ParseLimits {
+    ParseLimits{
+        decode_udp_tunnels: true,
+        ..Default::default()
+    }
+}
+
+/// Serialized IPv4 UDP packet with the given payload.
+fn inner_ipv4(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, payload).unwrap();
+    result
+}
+
+/// Serialized ethernet frame containing an IPv4 UDP packet with the given
+/// payload.
+fn inner_frame(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, payload).unwrap();
+    result
+}
+
+/// Serialized ethernet frame containing an IPv6 UDP packet with the given
+/// destination port & udp payload.
+fn outer_ipv6_frame(destination_port: u16, udp_payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([11,12,13,14,15,16], [17,18,19,20,21,22])
+        .ipv6([1;16], [2;16], 20)
+        .udp(49152, destination_port);
+    let mut result = Vec::with_capacity(builder.size(udp_payload.len()));
+    builder.write(&mut result, udp_payload).unwrap();
+    result
+}
+
+/// Serialized ethernet frame containing an IPv4 UDP packet with the given
+/// ports & udp payload.
+fn outer_ipv4_frame(source_port: u16, destination_port: u16, udp_payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ethernet2([11,12,13,14,15,16], [17,18,19,20,21,22])
+        .ipv4([10,0,0,1], [10,0,0,2], 20)
+        .udp(source_port, destination_port);
+    let mut result = Vec::with_capacity(builder.size(udp_payload.len()));
+    builder.write(&mut result, udp_payload).unwrap();
+    result
+}
+
+mod tunnel_header {
+    use super::super::*;
+
+    fn gre() -> GreHeader {
+        GreHeader{
+            protocol_type: ether_type::IPV6,
+            checksum: Some(1),
+            key: None,
+            sequence_number: Some(2),
+        }
+    }
+
+    fn geneve() -> GeneveHeader {
+        GeneveHeader{
+            protocol_type: ether_type::IPV4,
+            vni: 1,
+            options: vec![GeneveOption{ class: 1, option_type: 2, data: vec![3;4] }],
+            ..Default::default()
+        }
+    }
+
+    fn gtpu() -> GtpuHeader {
+        GtpuHeader{
+            message_type: gtpu_message_type::G_PDU,
+            length: 4,
+            teid: 1,
+            sequence_number: Some(2),
+            ..Default::default()
+        }
+    }
+
+    fn gue() -> GueHeader {
+        GueHeader{
+            proto_ctype: ip_number::IPV4,
+            flags: 0x8000,
+            extension_fields: vec![1,2,3,4],
+            ..Default::default()
+        }
+    }
+
+    fn teredo() -> TeredoHeader {
+        TeredoHeader{
+            authentication: None,
+            origin_indication: Some(TeredoOriginIndication{ port: 1, address: [2,3,4,5] }),
+        }
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(
+            format!("Gre({:?})", gre()),
+            format!("{:?}", TunnelHeader::Gre(gre())),
+        );
+        assert_eq!(
+            format!("Vxlan({:?})", VxlanHeader::new(1)),
+            format!("{:?}", TunnelHeader::Vxlan(VxlanHeader::new(1))),
+        );
+        assert_eq!(
+            format!("Geneve({:?})", geneve()),
+            format!("{:?}", TunnelHeader::Geneve(geneve())),
+        );
+        assert_eq!(
+            format!("Gtpu({:?})", gtpu()),
+            format!("{:?}", TunnelHeader::Gtpu(gtpu())),
+        );
+        assert_eq!(
+            format!("Pppoe({:?})", PppoeHeader::new_session(1, 2)),
+            format!("{:?}", TunnelHeader::Pppoe(PppoeHeader::new_session(1, 2))),
+        );
+    }
+
+    #[test]
+    fn gre_accessors() {
+        assert_eq!(Some(gre()), TunnelHeader::Gre(gre()).gre());
+
+        let mut header = TunnelHeader::Gre(gre());
+        header.mut_gre().unwrap().key = Some(3);
+        assert_eq!(Some(3), header.gre().unwrap().key);
+
+        assert_eq!(None, TunnelHeader::Vxlan(VxlanHeader::new(1)).gre());
+        assert_eq!(None, TunnelHeader::Vxlan(VxlanHeader::new(1)).mut_gre());
+    }
+
+    #[test]
+    fn vxlan_accessors() {
+        assert_eq!(Some(VxlanHeader::new(1)), TunnelHeader::Vxlan(VxlanHeader::new(1)).vxlan());
+
+        let mut header = TunnelHeader::Vxlan(VxlanHeader::new(1));
+        header.mut_vxlan().unwrap().vni = 2;
+        assert_eq!(Some(VxlanHeader::new(2)), header.vxlan());
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).vxlan());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_vxlan());
+    }
+
+    #[test]
+    fn geneve_accessors() {
+        assert_eq!(Some(geneve()), TunnelHeader::Geneve(geneve()).geneve());
+
+        let mut header = TunnelHeader::Geneve(geneve());
+        header.mut_geneve().unwrap().vni = 2;
+        assert_eq!(2, header.geneve().unwrap().vni);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).geneve());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_geneve());
+    }
+
+    #[test]
+    fn gtpu_accessors() {
+        assert_eq!(Some(gtpu()), TunnelHeader::Gtpu(gtpu()).gtpu());
+
+        let mut header = TunnelHeader::Gtpu(gtpu());
+        header.mut_gtpu().unwrap().teid = 2;
+        assert_eq!(2, header.gtpu().unwrap().teid);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).gtpu());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_gtpu());
+    }
+
+    #[test]
+    fn gue_accessors() {
+        assert_eq!(Some(gue()), TunnelHeader::Gue(gue()).gue());
+
+        let mut header = TunnelHeader::Gue(gue());
+        header.mut_gue().unwrap().flags = 2;
+        assert_eq!(2, header.gue().unwrap().flags);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).gue());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_gue());
+    }
+
+    #[test]
+    fn teredo_accessors() {
+        assert_eq!(Some(teredo()), TunnelHeader::Teredo(teredo()).teredo());
+
+        let mut header = TunnelHeader::Teredo(teredo());
+        header.mut_teredo().unwrap().origin_indication = None;
+        assert_eq!(Some(TeredoHeader::default()), header.teredo());
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).teredo());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_teredo());
+    }
+
+    #[test]
+    fn esp_accessors() {
+        let esp = EspHeader{ spi: 1, sequence_number: 2 };
+        assert_eq!(Some(esp.clone()), TunnelHeader::Esp(esp.clone()).esp());
+
+        let mut header = TunnelHeader::Esp(esp);
+        header.mut_esp().unwrap().sequence_number = 3;
+        assert_eq!(3, header.esp().unwrap().sequence_number);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).esp());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_esp());
+    }
+
+    #[test]
+    fn pppoe_accessors() {
+        let pppoe = PppoeHeader::new_session(1, 2);
+        assert_eq!(Some(pppoe.clone()), TunnelHeader::Pppoe(pppoe.clone()).pppoe());
+
+        let mut header = TunnelHeader::Pppoe(pppoe);
+        header.mut_pppoe().unwrap().session_id = 3;
+        assert_eq!(3, header.pppoe().unwrap().session_id);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).pppoe());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_pppoe());
+    }
+
+    #[test]
+    fn vxlan_gpe_accessors() {
+        let vxlan_gpe = VxlanGpeHeader::new(1, vxlan_gpe_next_protocol::IPV4);
+        assert_eq!(Some(vxlan_gpe.clone()), TunnelHeader::VxlanGpe(vxlan_gpe.clone()).vxlan_gpe());
+
+        let mut header = TunnelHeader::VxlanGpe(vxlan_gpe);
+        header.mut_vxlan_gpe().unwrap().vni = 3;
+        assert_eq!(3, header.vxlan_gpe().unwrap().vni);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).vxlan_gpe());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_vxlan_gpe());
+    }
+
+    #[test]
+    fn pbb_accessors() {
+        let pbb = PbbItagHeader{ i_sid: 1, ..Default::default() };
+        assert_eq!(Some(pbb.clone()), TunnelHeader::Pbb(pbb.clone()).pbb());
+
+        let mut header = TunnelHeader::Pbb(pbb);
+        header.mut_pbb().unwrap().i_sid = 3;
+        assert_eq!(3, header.pbb().unwrap().i_sid);
+
+        assert_eq!(None, TunnelHeader::Gre(gre()).pbb());
+        assert_eq!(None, TunnelHeader::Gre(gre()).mut_pbb());
+    }
+
+    #[test]
+    fn header_len() {
+        assert_eq!(12, TunnelHeader::Gre(gre()).header_len());
+        assert_eq!(8, TunnelHeader::Vxlan(VxlanHeader::new(1)).header_len());
+        assert_eq!(8, TunnelHeader::VxlanGpe(VxlanGpeHeader::new(1, 2)).header_len());
+        assert_eq!(16, TunnelHeader::Geneve(geneve()).header_len());
+        assert_eq!(12, TunnelHeader::Gtpu(gtpu()).header_len());
+        assert_eq!(8, TunnelHeader::Gue(gue()).header_len());
+        assert_eq!(8, TunnelHeader::Teredo(teredo()).header_len());
+        assert_eq!(8, TunnelHeader::Esp(Default::default()).header_len());
+        assert_eq!(8, TunnelHeader::Pppoe(PppoeHeader::new_session(1, 2)).header_len());
+        assert_eq!(18, TunnelHeader::Pbb(Default::default()).header_len());
+    }
+
+    #[test]
+    fn write() {
+        let mut buffer = Vec::new();
+        TunnelHeader::Gre(gre()).write(&mut buffer).unwrap();
+        assert_eq!(gre().to_bytes(), buffer);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Vxlan(VxlanHeader::new(1)).write(&mut buffer).unwrap();
+        assert_eq!(&VxlanHeader::new(1).to_bytes().unwrap(), &buffer[..]);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::VxlanGpe(VxlanGpeHeader::new(1, 2)).write(&mut buffer).unwrap();
+        assert_eq!(&VxlanGpeHeader::new(1, 2).to_bytes().unwrap(), &buffer[..]);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Geneve(geneve()).write(&mut buffer).unwrap();
+        assert_eq!(geneve().to_bytes().unwrap(), buffer);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Gtpu(gtpu()).write(&mut buffer).unwrap();
+        assert_eq!(gtpu().to_bytes().unwrap(), buffer);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Gue(gue()).write(&mut buffer).unwrap();
+        assert_eq!(gue().to_bytes().unwrap(), buffer);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Teredo(teredo()).write(&mut buffer).unwrap();
+        assert_eq!(teredo().to_bytes().unwrap(), buffer);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Esp(EspHeader{ spi: 1, sequence_number: 2 }).write(&mut buffer).unwrap();
+        assert_eq!(&[0,0,0,1,0,0,0,2], &buffer[..]);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Pppoe(PppoeHeader::new_session(1, 2)).write(&mut buffer).unwrap();
+        assert_eq!(PppoeHeader::new_session(1, 2).to_bytes().unwrap(), buffer);
+
+        let mut buffer = Vec::new();
+        TunnelHeader::Pbb(Default::default()).write(&mut buffer).unwrap();
+        assert_eq!(&PbbItagHeader::default().to_bytes().unwrap(), &buffer[..]);
+    }
+
+    #[test]
+    fn slice() {
+        let bytes = gre().to_bytes();
+        let slice = TunnelSlice::Gre(GreHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+        assert_eq!(slice.clone(), slice);
+
+        let bytes = VxlanHeader::new(1).to_bytes().unwrap();
+        let slice = TunnelSlice::Vxlan(VxlanHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = VxlanGpeHeader::new(1, 2).to_bytes().unwrap();
+        let slice = TunnelSlice::VxlanGpe(VxlanGpeHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = geneve().to_bytes().unwrap();
+        let slice = TunnelSlice::Geneve(GeneveHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = gtpu().to_bytes().unwrap();
+        let slice = TunnelSlice::Gtpu(GtpuHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = gue().to_bytes().unwrap();
+        let slice = TunnelSlice::Gue(GueHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = teredo().to_bytes().unwrap();
+        let slice = TunnelSlice::Teredo(TeredoHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = EspHeader::default().to_bytes();
+        let slice = TunnelSlice::Esp(EspHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = PppoeHeader::new_session(1, 2).to_bytes().unwrap();
+        let slice = TunnelSlice::Pppoe(PppoeHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+
+        let bytes = PbbItagHeader::default().to_bytes().unwrap();
+        let slice = TunnelSlice::Pbb(PbbItagHeaderSlice::from_slice(&bytes).unwrap());
+        assert_eq!(&bytes[..], slice.slice());
+    }
+}
@@ -0,0 +1,289 @@
+use etherparse::*;
+
+use super::super::*;
+
+use std::io::Cursor;
+
+/// Serialized IPv4 UDP packet.
+fn ip_packet(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, payload).unwrap();
+    result
+}
+
+fn itag(ether_type: u16) -> PbbItagHeader {
+    PbbItagHeader{
+        priority_code_point: 5,
+        drop_eligible_indicator: true,
+        use_customer_address: false,
+        i_sid: 0x12_3456,
+        customer_destination: [1, 2, 3, 4, 5, 6],
+        customer_source: [7, 8, 9, 10, 11, 12],
+        ether_type,
+    }
+}
+
+/// Backbone ethernet frame containing an I-TAG & the given customer payload.
+fn backbone_frame(ether_type: u16, payload: &[u8]) -> Vec<u8> {
+    let mut result = Ethernet2Header{
+        source: [0xa;6],
+        destination: [0xb;6],
+        ether_type: ether_type::PROVIDER_BACKBONE_BRIDGING,
+    }.to_bytes().to_vec();
+    result.extend_from_slice(&itag(ether_type).to_bytes().unwrap());
+    result.extend_from_slice(payload);
+    result
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        priority_code_point in 0..=7u8,
+        drop_eligible_indicator in any::<bool>(),
+        use_customer_address in any::<bool>(),
+        i_sid in 0..=PbbItagHeader::MAX_I_SID,
+        customer_destination in any::<[u8;6]>(),
+        customer_source in any::<[u8;6]>(),
+        ether_type in any::<u16>(),
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = PbbItagHeader{
+            priority_code_point,
+            drop_eligible_indicator,
+            use_customer_address,
+            i_sid,
+            customer_destination,
+            customer_source,
+            ether_type,
+        };
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap().to_vec();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+
+        // header
+        {
+            let (decoded, decoded_rest) = PbbItagHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+            assert_eq!(PbbItagHeader::SERIALIZED_SIZE, header.header_len());
+        }
+
+        // slice
+        {
+            let slice = PbbItagHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..18], slice.slice());
+            assert_eq!(priority_code_point, slice.priority_code_point());
+            assert_eq!(drop_eligible_indicator, slice.drop_eligible_indicator());
+            assert_eq!(use_customer_address, slice.use_customer_address());
+            assert_eq!(i_sid, slice.i_sid());
+            assert_eq!(customer_destination, slice.customer_destination());
+            assert_eq!(customer_source, slice.customer_source());
+            assert_eq!(ether_type, slice.ether_type());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..18], &buffer[..]);
+            assert_eq!(header, PbbItagHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for cut in 0..18 {
+            assert_matches!(
+                PbbItagHeader::from_slice(&bytes[..cut]),
+                Err(ReadError::UnexpectedEndOfSlice(18))
+            );
+            assert_matches!(
+                PbbItagHeader::read(&mut Cursor::new(&bytes[..cut])),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn to_bytes() {
+    assert_eq!(
+        Ok([
+            0xb0, 0x12, 0x34, 0x56,
+            1, 2, 3, 4, 5, 6,
+            7, 8, 9, 10, 11, 12,
+            0x08, 0x00,
+        ]),
+        itag(ether_type::IPV4).to_bytes()
+    );
+    let header = PbbItagHeader{
+        use_customer_address: true,
+        ..Default::default()
+    };
+    assert_eq!(0x08, header.to_bytes().unwrap()[0]);
+}
+
+#[test]
+fn to_bytes_value_errors() {
+    let mut header = itag(ether_type::IPV4);
+    header.priority_code_point = 8;
+    assert_eq!(
+        Err(ValueError::U8TooLarge{
+            value: 8,
+            max: 7,
+            field: ErrorField::PbbItagPriorityCodePoint,
+        }),
+        header.to_bytes()
+    );
+    assert_matches!(
+        header.write(&mut Vec::new()),
+        Err(WriteError::ValueError(_))
+    );
+
+    let mut header = itag(ether_type::IPV4);
+    header.i_sid = PbbItagHeader::MAX_I_SID + 1;
+    assert_eq!(
+        Err(ValueError::U32TooLarge{
+            value: PbbItagHeader::MAX_I_SID + 1,
+            max: PbbItagHeader::MAX_I_SID,
+            field: ErrorField::PbbItagIsid,
+        }),
+        header.to_bytes()
+    );
+}
+
+#[test]
+fn write_io_error() {
+    let mut writer = TestWriter::with_max_size(17);
+    assert_matches!(
+        itag(ether_type::IPV4).write(&mut writer),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn slice_customer_frame() {
+    let ip = ip_packet(&[1,2,3,4]);
+    let packet = backbone_frame(ether_type::IPV4, &ip);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.ip);
+        match &sliced.tunnel {
+            Some(TunnelSlice::Pbb(header)) => {
+                assert_eq!(0x12_3456, header.i_sid());
+                assert_eq!([1, 2, 3, 4, 5, 6], header.customer_destination());
+                assert_eq!([7, 8, 9, 10, 11, 12], header.customer_source());
+            },
+            value => panic!("unexpected tunnel {:?}", value),
+        }
+        assert_eq!(&ip[..], sliced.payload);
+        assert_eq!(14 + 18, sliced.headers_len());
+
+        let inner = sliced.inner.as_ref().unwrap();
+        assert_matches!(inner.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner.payload);
+
+        let mut copy = sliced.header_bytes().concat();
+        copy.extend_from_slice(sliced.payload);
+        assert_eq!(packet, copy);
+
+        // from ether type
+        let sliced = SlicedPacket::from_ether_type(ether_type::PROVIDER_BACKBONE_BRIDGING, &packet[14..]).unwrap();
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::Pbb(_)));
+        assert!(sliced.inner.is_some());
+
+        // owned packet
+        let owned = OwnedPacket::from_sliced(&SlicedPacket::from_ethernet(&packet).unwrap()).unwrap();
+        assert_eq!(Some(TunnelHeader::Pbb(itag(ether_type::IPV4))), owned.tunnel);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(itag(ether_type::IPV4)), headers.tunnel.clone().unwrap().pbb());
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::IPV4, payload: &ip },
+            headers.payload
+        );
+        assert_eq!(14 + 18, headers.header_len());
+        assert_eq!(Payload::Udp(&[1,2,3,4]), headers.inner.as_ref().unwrap().payload);
+    }
+
+    // backbone vlan tag (B-TAG)
+    {
+        let mut payload = SingleVlanHeader{
+            priority_code_point: 0,
+            drop_eligible_indicator: false,
+            vlan_identifier: 1,
+            ether_type: ether_type::PROVIDER_BACKBONE_BRIDGING,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        }.to_bytes().unwrap().to_vec();
+        payload.extend_from_slice(&packet[14..]);
+        let mut packet = packet[..12].to_vec();
+        packet.extend_from_slice(&ether_type::PROVIDER_BRIDGING.to_be_bytes());
+        packet.extend_from_slice(&payload);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(sliced.vlan.is_some());
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::Pbb(_)));
+        assert_eq!(&[1,2,3,4], sliced.inner.unwrap().payload);
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert!(headers.vlan.is_some());
+        assert_eq!(Payload::Udp(&[1,2,3,4]), headers.inner.unwrap().payload);
+    }
+}
+
+#[test]
+fn slice_unknown_customer_ether_type() {
+    let packet = backbone_frame(0x1234, &[1, 2, 3]);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Pbb(_)));
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&[1, 2, 3], sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(None, headers.inner);
+    assert_eq!(
+        Payload::Ether{ ether_type: 0x1234, payload: &[1, 2, 3] },
+        headers.payload
+    );
+}
+
+#[test]
+fn slice_errors() {
+    // truncated header
+    let packet = backbone_frame(ether_type::IPV4, &[]);
+    assert_matches!(
+        SlicedPacket::from_ethernet(&packet[..packet.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(32))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(&packet[..packet.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(18))
+    );
+
+    // tunnel depth
+    let packet = backbone_frame(ether_type::IPV4, &ip_packet(&[]));
+    let limits = ParseLimits{
+        max_tunnel_depth: 0,
+        ..Default::default()
+    };
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+    );
+}
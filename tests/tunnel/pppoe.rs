@@ -0,0 +1,429 @@
+use etherparse::*;
+
+use super::super::*;
+
+use std::io::Cursor;
+
+/// Serialized IPv4 UDP packet.
+fn ip_packet(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, payload).unwrap();
+    result
+}
+
+/// Serialized ethernet frame with the given ether type & payload.
+fn ethernet_frame(ether_type: u16, payload: &[u8]) -> Vec<u8> {
+    let mut result = Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type,
+    }.to_bytes().to_vec();
+    result.extend_from_slice(payload);
+    result
+}
+
+/// PADI discovery packet payload with a service name & host uniq tag.
+const PADI_TAGS: [u8;14] = [
+    0x01, 0x01, 0, 0,
+    0x01, 0x03, 0, 6, 1, 2, 3, 4, 5, 6,
+];
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        version in 0..=PppoeHeader::MAX_VERSION,
+        pppoe_type in 0..=PppoeHeader::MAX_VERSION,
+        code in any::<u8>(),
+        session_id in any::<u16>(),
+        length in any::<u16>(),
+        protocol in any::<u16>(),
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = PppoeHeader{
+            version,
+            pppoe_type,
+            code,
+            session_id,
+            length,
+            ppp_protocol: if pppoe_code::SESSION_DATA == code {
+                Some(protocol)
+            } else {
+                None
+            },
+        };
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+        let len = header.header_len();
+
+        // header
+        {
+            let (decoded, decoded_rest) = PppoeHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+        }
+
+        // slice
+        {
+            let slice = PppoeHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..len], slice.slice());
+            assert_eq!(version, slice.version());
+            assert_eq!(pppoe_type, slice.pppoe_type());
+            assert_eq!(code, slice.code());
+            assert_eq!(session_id, slice.session_id());
+            assert_eq!(length, slice.length());
+            assert_eq!(header.ppp_protocol, slice.ppp_protocol());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..len], &buffer[..]);
+            assert_eq!(header, PppoeHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for cut in 0..len {
+            assert_matches!(
+                PppoeHeader::from_slice(&bytes[..cut]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+            assert_matches!(
+                PppoeHeader::read(&mut Cursor::new(&bytes[..cut])),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn constructors() {
+    let session = PppoeHeader::new_session(0x1234, ppp_protocol::IPV6);
+    assert_eq!(Ok(vec![0x11, 0, 0x12, 0x34, 0, 2, 0, 0x57]), session.to_bytes());
+    assert_eq!(8, session.header_len());
+
+    let discovery = PppoeHeader::new_discovery(pppoe_code::PADT, 0x1234);
+    assert_eq!(Ok(vec![0x11, 0xa7, 0x12, 0x34, 0, 0]), discovery.to_bytes());
+    assert_eq!(6, discovery.header_len());
+}
+
+#[test]
+fn set_payload_len() {
+    let mut session = PppoeHeader::new_session(1, ppp_protocol::IPV4);
+    session.set_payload_len(20).unwrap();
+    assert_eq!(22, session.length);
+    assert_eq!(
+        Err(ValueError::PppoePayloadLengthTooLarge(usize::from(u16::MAX) + 1)),
+        session.set_payload_len(usize::from(u16::MAX) - 1)
+    );
+
+    let mut discovery = PppoeHeader::new_discovery(pppoe_code::PADI, 0);
+    discovery.set_payload_len(usize::from(u16::MAX)).unwrap();
+    assert_eq!(u16::MAX, discovery.length);
+}
+
+#[test]
+fn to_bytes_value_errors() {
+    let mut header = PppoeHeader::new_session(1, 2);
+    header.version = PppoeHeader::MAX_VERSION + 1;
+    assert_eq!(
+        Err(ValueError::U8TooLarge{
+            value: PppoeHeader::MAX_VERSION + 1,
+            max: PppoeHeader::MAX_VERSION,
+            field: ErrorField::PppoeVersion,
+        }),
+        header.to_bytes()
+    );
+    assert_matches!(
+        header.write(&mut Vec::new()),
+        Err(WriteError::ValueError(_))
+    );
+
+    let mut header = PppoeHeader::new_session(1, 2);
+    header.pppoe_type = PppoeHeader::MAX_VERSION + 1;
+    assert_eq!(
+        Err(ValueError::U8TooLarge{
+            value: PppoeHeader::MAX_VERSION + 1,
+            max: PppoeHeader::MAX_VERSION,
+            field: ErrorField::PppoeType,
+        }),
+        header.to_bytes()
+    );
+}
+
+#[test]
+fn write_io_error() {
+    let mut writer = TestWriter::with_max_size(7);
+    assert_matches!(
+        PppoeHeader::new_session(1, 2).write(&mut writer),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn tags_iterator() {
+    let mut iter = PppoeTagsIterator::from_slice(&PADI_TAGS);
+    let service_name = iter.next().unwrap().unwrap();
+    assert_eq!(pppoe_tag_type::SERVICE_NAME, service_name.tag_type());
+    assert_eq!(0, service_name.tag_length());
+    assert!(service_name.value().is_empty());
+    assert_eq!(&PADI_TAGS[..4], service_name.slice());
+
+    let host_uniq = iter.next().unwrap().unwrap();
+    assert_eq!(pppoe_tag_type::HOST_UNIQ, host_uniq.tag_type());
+    assert_eq!(6, host_uniq.tag_length());
+    assert_eq!(&[1,2,3,4,5,6], host_uniq.value());
+    assert!(iter.rest().is_empty());
+    assert!(iter.next().is_none());
+
+    // end of list tag
+    {
+        let tags = [0x01, 0x01, 0, 0, 0, 0, 0, 0, 0x01, 0x02, 0, 0];
+        let mut iter = PppoeTagsIterator::from_slice(&tags);
+        assert!(iter.next().unwrap().is_ok());
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+    }
+
+    // unexpected end
+    {
+        let mut iter = PppoeTagsIterator::from_slice(&PADI_TAGS[..13]);
+        assert!(iter.next().unwrap().is_ok());
+        assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(14))));
+        assert!(iter.next().is_none());
+
+        let mut iter = PppoeTagsIterator::from_slice(&PADI_TAGS[..6]);
+        assert!(iter.next().unwrap().is_ok());
+        assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(8))));
+        assert!(iter.next().is_none());
+    }
+}
+
+#[test]
+fn slice_session() {
+    let ip = ip_packet(&[1,2,3,4]);
+    let pppoe_and_ip = {
+        let mut header = PppoeHeader::new_session(0x1234, ppp_protocol::IPV4);
+        header.set_payload_len(ip.len()).unwrap();
+        let mut result = header.to_bytes().unwrap();
+        result.extend_from_slice(&ip);
+        result
+    };
+    let packet = ethernet_frame(ether_type::PPPOE_SESSION, &pppoe_and_ip);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.ip);
+        assert_matches!(
+            &sliced.tunnel,
+            Some(TunnelSlice::Pppoe(pppoe)) if Some(ppp_protocol::IPV4) == pppoe.ppp_protocol()
+        );
+        assert_eq!(&ip[..], sliced.payload);
+        assert_eq!(14 + 8, sliced.headers_len());
+
+        let inner = sliced.inner.as_ref().unwrap();
+        assert_matches!(inner.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner.payload);
+
+        let mut copy = sliced.header_bytes().concat();
+        copy.extend_from_slice(sliced.payload);
+        assert_eq!(packet, copy);
+
+        // from ether type
+        let sliced = SlicedPacket::from_ether_type(ether_type::PPPOE_SESSION, &pppoe_and_ip).unwrap();
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::Pppoe(_)));
+        assert!(sliced.inner.is_some());
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        let pppoe = headers.tunnel.clone().unwrap().pppoe().unwrap();
+        assert_eq!(0x1234, pppoe.session_id);
+        assert_eq!((ip.len() + 2) as u16, pppoe.length);
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::IPV4, payload: &ip },
+            headers.payload
+        );
+        assert_eq!(14 + 8, headers.header_len());
+        assert_eq!(Payload::Udp(&[1,2,3,4]), headers.inner.as_ref().unwrap().payload);
+    }
+
+    // owned packet
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_matches!(owned.tunnel, Some(TunnelHeader::Pppoe(_)));
+    }
+
+    // vlan tagged
+    {
+        let mut payload = SingleVlanHeader{
+            priority_code_point: 0,
+            drop_eligible_indicator: false,
+            vlan_identifier: 1,
+            ether_type: ether_type::PPPOE_SESSION,
+            tpid: ether_type::VLAN_TAGGED_FRAME,
+        }.to_bytes().unwrap().to_vec();
+        payload.extend_from_slice(&pppoe_and_ip);
+        let packet = ethernet_frame(ether_type::VLAN_TAGGED_FRAME, &payload);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(sliced.vlan.is_some());
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::Pppoe(_)));
+        assert_eq!(&[1,2,3,4], sliced.inner.unwrap().payload);
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert!(headers.vlan.is_some());
+        assert_eq!(Payload::Udp(&[1,2,3,4]), headers.inner.unwrap().payload);
+    }
+}
+
+#[test]
+fn slice_session_other_protocol() {
+    // lcp packet (not decoded further)
+    let mut payload = PppoeHeader::new_session(1, ppp_protocol::LCP).to_bytes().unwrap();
+    payload.extend_from_slice(&[1, 2, 0, 4]);
+    let packet = ethernet_frame(ether_type::PPPOE_SESSION, &payload);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_matches!(sliced.tunnel, Some(TunnelSlice::Pppoe(_)));
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&[1, 2, 0, 4], sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(None, headers.inner);
+    assert_eq!(
+        Payload::Ether{ ether_type: ether_type::PPPOE_SESSION, payload: &[1, 2, 0, 4] },
+        headers.payload
+    );
+}
+
+#[test]
+fn slice_discovery() {
+    let mut payload = {
+        let mut header = PppoeHeader::new_discovery(pppoe_code::PADI, 0);
+        header.set_payload_len(PADI_TAGS.len()).unwrap();
+        header.to_bytes().unwrap()
+    };
+    payload.extend_from_slice(&PADI_TAGS);
+    // ethernet padding
+    payload.extend_from_slice(&[0;4]);
+    let packet = ethernet_frame(ether_type::PPPOE_DISCOVERY, &payload);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let length = match &sliced.tunnel {
+        Some(TunnelSlice::Pppoe(pppoe)) => {
+            assert_eq!(pppoe_code::PADI, pppoe.code());
+            assert_eq!(None, pppoe.ppp_protocol());
+            usize::from(pppoe.length())
+        },
+        _ => panic!("expected pppoe header"),
+    };
+    assert_eq!(None, sliced.inner);
+    let tags: Vec<_> = PppoeTagsIterator::from_slice(&sliced.payload[..length])
+        .map(|tag| tag.unwrap().tag_type())
+        .collect();
+    assert_eq!(vec![pppoe_tag_type::SERVICE_NAME, pppoe_tag_type::HOST_UNIQ], tags);
+
+    let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(
+        Some(TunnelHeader::Pppoe(PppoeHeader{
+            length: PADI_TAGS.len() as u16,
+            ..PppoeHeader::new_discovery(pppoe_code::PADI, 0)
+        })),
+        headers.tunnel
+    );
+    assert_eq!(
+        Payload::Ether{ ether_type: ether_type::PPPOE_DISCOVERY, payload: &payload[6..] },
+        headers.payload
+    );
+}
+
+#[test]
+fn slice_errors() {
+    // truncated header
+    let packet = ethernet_frame(ether_type::PPPOE_SESSION, &[0x11, 0, 0, 1, 0, 2, 0]);
+    assert_matches!(
+        SlicedPacket::from_ethernet(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(22))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+
+    // tunnel depth
+    let mut payload = PppoeHeader::new_session(1, ppp_protocol::IPV4).to_bytes().unwrap();
+    payload.extend_from_slice(&ip_packet(&[]));
+    let packet = ethernet_frame(ether_type::PPPOE_SESSION, &payload);
+    let limits = ParseLimits{
+        max_tunnel_depth: 0,
+        ..Default::default()
+    };
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+    );
+}
+
+#[test]
+fn from_link_type_ppp() {
+    // the ppp link type shares the protocol mapping with pppoe
+    let mut packet = vec![0xff, 0x03, 0x00, 0x57];
+    packet.extend_from_slice(&{
+        let builder = PacketBuilder::
+            ipv6([1;16], [2;16], 20)
+            .udp(1, 2);
+        let mut result = Vec::with_capacity(builder.size(1));
+        builder.write(&mut result, &[9]).unwrap();
+        result
+    });
+    let sliced = SlicedPacket::from_link_type(LinkType::Ppp, &packet).unwrap();
+    assert_matches!(sliced.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_eq!(&[9], sliced.payload);
+}
+
+#[test]
+fn compose() {
+    let stack = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: 0,
+        } /
+        PppoeHeader::new_session(7, 0) /
+        Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 0,
+            next_header: 0,
+            hop_limit: 64,
+            source: [1;16],
+            destination: [2;16],
+        } /
+        UdpHeader{ source_port: 1, destination_port: 2, length: 0, checksum: 0 } /
+        [1,2,3];
+    let bytes = stack.to_bytes().unwrap();
+    assert_eq!(stack.len(), bytes.len());
+
+    let sliced = SlicedPacket::from_ethernet(&bytes).unwrap();
+    assert_matches!(
+        &sliced.tunnel,
+        Some(TunnelSlice::Pppoe(pppoe)) if Some(ppp_protocol::IPV6) == pppoe.ppp_protocol() && 2 + 40 + 8 + 3 == pppoe.length()
+    );
+    assert_eq!(&[1,2,3], sliced.inner.unwrap().payload);
+}
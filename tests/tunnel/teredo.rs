@@ -0,0 +1,239 @@
+use etherparse::*;
+
+use super::super::*;
+use super::{limits, outer_ipv4_frame};
+
+/// Serialized IPv6 UDP packet with the given payload.
+fn inner_ipv6(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::
+        ipv6([0x20,0x01,0,0,1,2,3,4,5,6,7,8,9,10,11,12], [3;16], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, payload).unwrap();
+    result
+}
+
+fn authentication() -> TeredoAuthentication {
+    TeredoAuthentication{
+        client_id: vec![1,2,3],
+        authentication_value: vec![4,5],
+        nonce: [6,7,8,9,10,11,12,13],
+        confirmation: 14,
+    }
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        client_id in proptest::collection::vec(any::<u8>(), 0..20),
+        authentication_value in proptest::collection::vec(any::<u8>(), 0..20),
+        nonce in any::<[u8;8]>(),
+        confirmation in any::<u8>(),
+        port in any::<u16>(),
+        address in any::<[u8;4]>(),
+        with_authentication in any::<bool>(),
+        with_origin_indication in any::<bool>(),
+    ) {
+        let header = TeredoHeader{
+            authentication: if with_authentication {
+                Some(TeredoAuthentication{
+                    client_id: client_id.clone(),
+                    authentication_value: authentication_value.clone(),
+                    nonce,
+                    confirmation,
+                })
+            } else {
+                None
+            },
+            origin_indication: if with_origin_indication {
+                Some(TeredoOriginIndication{ port, address })
+            } else {
+                None
+            },
+        };
+        let len = header.header_len();
+        let inner = inner_ipv6(&[]);
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap();
+            assert_eq!(len, bytes.len());
+            bytes.extend_from_slice(&inner);
+            bytes
+        };
+
+        // header
+        {
+            let (decoded, rest) = TeredoHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&inner[..], rest);
+        }
+
+        // slice
+        {
+            let slice = TeredoHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..len], slice.slice());
+            assert_eq!(header.origin_indication, slice.origin_indication());
+            if let Some(authentication) = slice.authentication() {
+                assert_eq!(&client_id[..], authentication.client_id());
+                assert_eq!(&authentication_value[..], authentication.authentication_value());
+                assert_eq!(nonce, authentication.nonce());
+                assert_eq!(confirmation, authentication.confirmation());
+                assert_eq!(header.authentication.clone(), Some(authentication.to_header()));
+            } else {
+                assert_eq!(None, header.authentication);
+            }
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..len], &buffer[..]);
+        }
+
+        // unexpected end of slice
+        for end in 0..len {
+            assert_matches!(
+                TeredoHeader::from_slice(&bytes[..end]),
+                Err(ReadError::UnexpectedEndOfSlice(_)) | Ok(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn origin_indication_obfuscation() {
+    let origin = TeredoOriginIndication{ port: 0x1234, address: [192,0,2,1] };
+    let bytes = origin.to_bytes();
+    assert_eq!([0, 0, 0xed, 0xcb, 63, 255, 253, 254], bytes);
+    assert_eq!(origin, TeredoOriginIndication::from_bytes(bytes));
+}
+
+#[test]
+fn authentication_to_bytes() {
+    assert_eq!(
+        Ok(vec![0, 1, 3, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+        authentication().to_bytes()
+    );
+
+    let header = TeredoAuthentication{
+        client_id: vec![0;256],
+        ..Default::default()
+    };
+    assert_eq!(Err(ValueError::TeredoAuthenticationLengthTooLarge(256)), header.to_bytes());
+
+    let header = TeredoHeader{
+        authentication: Some(TeredoAuthentication{
+            authentication_value: vec![0;300],
+            ..Default::default()
+        }),
+        origin_indication: None,
+    };
+    assert_matches!(
+        header.write(&mut Vec::new()),
+        Err(WriteError::ValueError(ValueError::TeredoAuthenticationLengthTooLarge(300)))
+    );
+}
+
+#[test]
+fn slice_ipv6_in_teredo() {
+    let header = TeredoHeader{
+        authentication: Some(authentication()),
+        origin_indication: Some(TeredoOriginIndication{ port: 40000, address: [192,0,2,1] }),
+    };
+    let inner = inner_ipv6(&[1,2,3,4]);
+    let udp_payload = {
+        let mut result = header.to_bytes().unwrap();
+        result.extend_from_slice(&inner);
+        result
+    };
+
+    // the teredo port can be used as source or destination port
+    for (source_port, destination_port) in &[(49152, TeredoHeader::PORT), (TeredoHeader::PORT, 49152)] {
+        let packet = outer_ipv4_frame(*source_port, *destination_port, &udp_payload);
+
+        // not decoded by default
+        {
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(None, sliced.tunnel);
+            assert_eq!(None, sliced.inner);
+        }
+
+        // slicing
+        {
+            let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+            assert_matches!(&sliced.tunnel, Some(TunnelSlice::Teredo(_)));
+            assert_eq!(&inner[..], sliced.payload);
+            assert_eq!(14 + 20 + 8 + 18 + 8, sliced.headers_len());
+
+            let inner_sliced = sliced.inner.as_ref().unwrap();
+            assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv6(_, _)));
+            assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+            assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+            let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+            assert_eq!(Some(TunnelHeader::Teredo(header.clone())), owned.tunnel);
+        }
+
+        // decoding
+        {
+            let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+            assert_eq!(Some(TunnelHeader::Teredo(header.clone())), headers.tunnel);
+            assert_eq!(
+                Payload::Ether{ ether_type: ether_type::IPV6, payload: &inner },
+                headers.payload
+            );
+            assert_eq!(Payload::Udp(&[1,2,3,4]), headers.inner.unwrap().payload);
+        }
+    }
+
+    // without the optional headers
+    {
+        let packet = outer_ipv4_frame(49152, TeredoHeader::PORT, &inner);
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Teredo(h)) if h.slice().is_empty());
+        assert!(sliced.inner.is_some());
+
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(Some(TunnelHeader::Teredo(Default::default())), headers.tunnel);
+        assert!(headers.inner.is_some());
+    }
+}
+
+#[test]
+fn slice_not_teredo() {
+    // payloads not containing an ipv6 packet are not decoded as teredo
+    for payload in &[vec![], vec![0x45, 0], vec![0, 2, 3]] {
+        let packet = outer_ipv4_frame(49152, TeredoHeader::PORT, payload);
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&payload[..], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, headers.tunnel);
+        assert_eq!(Payload::Udp(&payload[..]), headers.payload);
+    }
+}
+
+#[test]
+fn slice_errors() {
+    // truncated authentication header
+    let packet = outer_ipv4_frame(49152, TeredoHeader::PORT, &[0, 1, 2, 0, 1]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(55))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(13))
+    );
+
+    // truncated origin indication
+    let packet = outer_ipv4_frame(49152, TeredoHeader::PORT, &[0, 0, 1, 2]);
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+}
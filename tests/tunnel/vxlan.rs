@@ -0,0 +1,254 @@
+use etherparse::*;
+
+use super::super::*;
+use super::{limits, inner_frame, outer_ipv6_frame};
+
+use std::io::Cursor;
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        flags in any::<u8>(),
+        vni in 0..=VxlanHeader::MAX_VNI,
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = VxlanHeader{ flags, vni };
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap().to_vec();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+
+        // header
+        {
+            let (decoded, decoded_rest) = VxlanHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+        }
+
+        // slice
+        {
+            let slice = VxlanHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..8], slice.slice());
+            assert_eq!(flags, slice.flags());
+            assert_eq!(0 != flags & 0b1000, slice.vni_valid());
+            assert_eq!(vni, slice.vni());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..8], &buffer[..]);
+            assert_eq!(header, VxlanHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for len in 0..8 {
+            assert_matches!(
+                VxlanHeader::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+            assert_matches!(
+                VxlanHeader::read(&mut Cursor::new(&bytes[..len])),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn new() {
+    let header = VxlanHeader::new(0x123456);
+    assert_eq!(0b1000, header.flags);
+    assert_eq!(0x123456, header.vni);
+    assert!(header.vni_valid());
+    assert!(!VxlanHeader::default().vni_valid());
+    assert_eq!(8, header.header_len());
+    assert_eq!(
+        Ok([0b1000, 0, 0, 0, 0x12, 0x34, 0x56, 0]),
+        header.to_bytes()
+    );
+}
+
+#[test]
+fn to_bytes_vni_too_large() {
+    let header = VxlanHeader::new(VxlanHeader::MAX_VNI + 1);
+    assert_eq!(
+        Err(ValueError::U32TooLarge{
+            value: VxlanHeader::MAX_VNI + 1,
+            max: VxlanHeader::MAX_VNI,
+            field: ErrorField::VxlanVni,
+        }),
+        header.to_bytes()
+    );
+    assert_matches!(
+        header.write(&mut Vec::new()),
+        Err(WriteError::ValueError(ValueError::U32TooLarge{ .. }))
+    );
+}
+
+#[test]
+fn write_io_error() {
+    let mut writer = TestWriter::with_max_size(7);
+    assert_matches!(
+        VxlanHeader::new(1).write(&mut writer),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn slice_vxlan() {
+    let inner = inner_frame(&[1,2,3,4]);
+    let vxlan_and_inner = {
+        let mut result = VxlanHeader::new(42).to_bytes().unwrap().to_vec();
+        result.extend_from_slice(&inner);
+        result
+    };
+    let packet = outer_ipv6_frame(VxlanHeader::PORT, &vxlan_and_inner);
+
+    // not decoded by default
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&vxlan_and_inner[..], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(None, headers.tunnel);
+        assert_eq!(None, headers.inner);
+        assert_eq!(Payload::Udp(&vxlan_and_inner), headers.payload);
+    }
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::Vxlan(vxlan)) if 42 == vxlan.vni());
+        assert_eq!(&inner[..], sliced.payload);
+        assert_eq!(14 + 40 + 8 + 8, sliced.headers_len());
+
+        let inner_sliced = sliced.inner.as_ref().unwrap();
+        assert_matches!(inner_sliced.link, Some(LinkSlice::Ethernet2(_)));
+        assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+        let mut copy = sliced.header_bytes().concat();
+        copy.extend_from_slice(sliced.payload);
+        assert_eq!(packet, copy);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_matches!(headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Some(TunnelHeader::Vxlan(VxlanHeader::new(42))), headers.tunnel);
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::TRANSPARENT_ETHERNET_BRIDGING, payload: &inner },
+            headers.payload
+        );
+        assert_eq!(14 + 40 + 8 + 8, headers.header_len());
+
+        let inner_headers = headers.inner.as_ref().unwrap();
+        assert!(inner_headers.link.is_some());
+        assert_matches!(inner_headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Payload::Udp(&[1,2,3,4]), inner_headers.payload);
+    }
+
+    // owned packet
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(Some(TunnelHeader::Vxlan(VxlanHeader::new(42))), owned.tunnel);
+    }
+}
+
+#[test]
+fn slice_other_port() {
+    let packet = outer_ipv6_frame(VxlanHeader::PORT - 1, &VxlanHeader::new(1).to_bytes().unwrap());
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert_eq!(None, sliced.tunnel);
+    assert_eq!(8, sliced.payload.len());
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert_eq!(None, headers.tunnel);
+}
+
+#[test]
+fn slice_errors() {
+    // truncated vxlan header
+    let packet = outer_ipv6_frame(VxlanHeader::PORT, &[0b1000, 0, 0]);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(70))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+
+    // truncated inner ethernet header
+    let packet = outer_ipv6_frame(VxlanHeader::PORT, &[0b1000, 0, 0, 0, 0, 0, 1, 0, 1, 2]);
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert!(sliced.inner.is_none());
+    assert_eq!(sliced.payload, &[1, 2]);
+
+    // tunnel depth
+    let packet = outer_ipv6_frame(VxlanHeader::PORT, &{
+        let mut result = VxlanHeader::new(1).to_bytes().unwrap().to_vec();
+        result.extend_from_slice(&inner_frame(&[]));
+        result
+    });
+    let limits = ParseLimits{
+        max_tunnel_depth: 0,
+        ..limits()
+    };
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::TunnelDepth))
+    );
+}
+
+#[test]
+fn compose() {
+    let stack = Ethernet2Header{
+            source: [1;6],
+            destination: [2;6],
+            ether_type: 0,
+        } /
+        Ipv4Header::new(0, 64, IpNumber::Udp, [10,0,0,1], [10,0,0,2]) /
+        UdpHeader{ source_port: 1234, destination_port: VxlanHeader::PORT, length: 0, checksum: 0 } /
+        VxlanHeader::new(7) /
+        Ethernet2Header{
+            source: [3;6],
+            destination: [4;6],
+            ether_type: 0,
+        } /
+        Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 0,
+            next_header: 0,
+            hop_limit: 64,
+            source: [1;16],
+            destination: [2;16],
+        } /
+        UdpHeader{ source_port: 1, destination_port: 2, length: 0, checksum: 0 } /
+        [1,2,3];
+    let bytes = stack.to_bytes().unwrap();
+    assert_eq!(stack.len(), bytes.len());
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&bytes, limits()).unwrap();
+    assert_matches!(&sliced.tunnel, Some(TunnelSlice::Vxlan(vxlan)) if 7 == vxlan.vni());
+    let inner = sliced.inner.unwrap();
+    assert_matches!(inner.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_eq!(&[1,2,3], inner.payload);
+}
@@ -0,0 +1,258 @@
+use etherparse::*;
+
+use super::super::*;
+use super::{limits, inner_ipv4, outer_ipv6_frame};
+
+use std::io::Cursor;
+
+/// Serialized ethernet frame containing an IPv6 UDP packet to the
+/// VXLAN-GPE port with the given VXLAN-GPE header & payload.
+fn outer_frame(header: &VxlanGpeHeader, payload: &[u8]) -> Vec<u8> {
+    let mut udp_payload = header.to_bytes().unwrap().to_vec();
+    udp_payload.extend_from_slice(payload);
+    outer_ipv6_frame(VxlanGpeHeader::PORT, &udp_payload)
+}
+
+proptest! {
+    #[test]
+    fn from_slice_to_bytes(
+        flags in any::<u8>(),
+        next_protocol in any::<u8>(),
+        vni in 0..=VxlanGpeHeader::MAX_VNI,
+        rest in proptest::collection::vec(any::<u8>(), 0..10),
+    ) {
+        let header = VxlanGpeHeader{ flags, next_protocol, vni };
+        let bytes = {
+            let mut bytes = header.to_bytes().unwrap().to_vec();
+            bytes.extend_from_slice(&rest);
+            bytes
+        };
+
+        // header
+        {
+            let (decoded, decoded_rest) = VxlanGpeHeader::from_slice(&bytes).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&rest[..], decoded_rest);
+        }
+
+        // slice
+        {
+            let slice = VxlanGpeHeaderSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..8], slice.slice());
+            assert_eq!(flags, slice.flags());
+            assert_eq!((flags >> 4) & 0b11, slice.version());
+            assert_eq!(header.version(), slice.version());
+            assert_eq!(0 != flags & 0b1000, slice.vni_valid());
+            assert_eq!(header.vni_valid(), slice.vni_valid());
+            assert_eq!(0 != flags & 0b100, slice.next_protocol_present());
+            assert_eq!(header.next_protocol_present(), slice.next_protocol_present());
+            assert_eq!(next_protocol, slice.next_protocol());
+            assert_eq!(vni, slice.vni());
+            assert_eq!(header.payload_ether_type(), slice.payload_ether_type());
+            assert_eq!(header, slice.to_header());
+            assert_eq!(slice.clone(), slice);
+        }
+
+        // read & write
+        {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..8], &buffer[..]);
+            assert_eq!(header, VxlanGpeHeader::read(&mut Cursor::new(&buffer)).unwrap());
+        }
+
+        // unexpected end of slice
+        for len in 0..8 {
+            assert_matches!(
+                VxlanGpeHeader::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+            assert_matches!(
+                VxlanGpeHeader::read(&mut Cursor::new(&bytes[..len])),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn new() {
+    let header = VxlanGpeHeader::new(0x123456, vxlan_gpe_next_protocol::NSH);
+    assert_eq!(0b1100, header.flags);
+    assert_eq!(vxlan_gpe_next_protocol::NSH, header.next_protocol);
+    assert_eq!(0x123456, header.vni);
+    assert_eq!(0, header.version());
+    assert!(header.vni_valid());
+    assert!(header.next_protocol_present());
+    assert!(!VxlanGpeHeader::default().vni_valid());
+    assert!(!VxlanGpeHeader::default().next_protocol_present());
+    assert_eq!(8, header.header_len());
+    assert_eq!(
+        Ok([0b1100, 0, 0, 4, 0x12, 0x34, 0x56, 0]),
+        header.to_bytes()
+    );
+}
+
+#[test]
+fn payload_ether_type() {
+    use vxlan_gpe_next_protocol::*;
+
+    for (next_protocol, expected) in &[
+        (IPV4, Some(ether_type::IPV4)),
+        (IPV6, Some(ether_type::IPV6)),
+        (ETHERNET, Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING)),
+        (NSH, Some(ether_type::NSH)),
+        (MPLS, Some(ether_type::MPLS_UNICAST)),
+        (0x7f, None),
+    ] {
+        assert_eq!(*expected, VxlanGpeHeader::new(1, *next_protocol).payload_ether_type());
+    }
+
+    // without the "P" flag an ethernet frame follows (like in VXLAN)
+    let header = VxlanGpeHeader{
+        flags: VxlanGpeHeader::FLAG_VNI_VALID,
+        next_protocol: IPV4,
+        vni: 1,
+    };
+    assert_eq!(Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING), header.payload_ether_type());
+}
+
+#[test]
+fn to_bytes_vni_too_large() {
+    let header = VxlanGpeHeader::new(VxlanGpeHeader::MAX_VNI + 1, 1);
+    assert_eq!(
+        Err(ValueError::U32TooLarge{
+            value: VxlanGpeHeader::MAX_VNI + 1,
+            max: VxlanGpeHeader::MAX_VNI,
+            field: ErrorField::VxlanGpeVni,
+        }),
+        header.to_bytes()
+    );
+    assert_matches!(
+        header.write(&mut Vec::new()),
+        Err(WriteError::ValueError(ValueError::U32TooLarge{ .. }))
+    );
+}
+
+#[test]
+fn slice_ipv4_in_vxlan_gpe() {
+    let header = VxlanGpeHeader::new(42, vxlan_gpe_next_protocol::IPV4);
+    let inner = inner_ipv4(&[1,2,3,4]);
+    let packet = outer_frame(&header, &inner);
+
+    // not decoded by default
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(None, sliced.tunnel);
+        assert_eq!(None, sliced.inner);
+    }
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(&sliced.tunnel, Some(TunnelSlice::VxlanGpe(h)) if 42 == h.vni());
+        assert_eq!(&inner[..], sliced.payload);
+        assert_eq!(14 + 40 + 8 + 8, sliced.headers_len());
+
+        let inner_sliced = sliced.inner.as_ref().unwrap();
+        assert_eq!(None, inner_sliced.link);
+        assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(inner_sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1,2,3,4], inner_sliced.payload);
+
+        let owned = OwnedPacket::from_sliced(&sliced).unwrap();
+        assert_eq!(Some(TunnelHeader::VxlanGpe(header.clone())), owned.tunnel);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(Some(TunnelHeader::VxlanGpe(header.clone())), headers.tunnel);
+        assert_eq!(
+            Payload::Ether{ ether_type: ether_type::IPV4, payload: &inner },
+            headers.payload
+        );
+        let inner_headers = headers.inner.as_ref().unwrap();
+        assert_eq!(None, inner_headers.link);
+        assert_matches!(inner_headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Payload::Udp(&[1,2,3,4]), inner_headers.payload);
+    }
+}
+
+#[test]
+fn slice_ethernet_in_vxlan_gpe() {
+    let inner = {
+        let builder = PacketBuilder::
+            ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([3;16], [4;16], 20)
+            .udp(1, 2);
+        let mut result = Vec::with_capacity(builder.size(1));
+        builder.write(&mut result, &[9]).unwrap();
+        result
+    };
+    let packet = outer_frame(&VxlanGpeHeader::new(7, vxlan_gpe_next_protocol::ETHERNET), &inner);
+
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    let inner_sliced = sliced.inner.unwrap();
+    assert_matches!(inner_sliced.link, Some(LinkSlice::Ethernet2(_)));
+    assert_matches!(inner_sliced.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_eq!(&[9], inner_sliced.payload);
+
+    let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+    assert!(headers.inner.unwrap().link.is_some());
+}
+
+#[test]
+fn slice_not_decodable_next_protocol() {
+    // nsh (known ether type, but not decoded)
+    {
+        let packet = outer_frame(&VxlanGpeHeader::new(1, vxlan_gpe_next_protocol::NSH), &[1,2,3]);
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::VxlanGpe(_)));
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&[1,2,3], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_eq!(None, headers.inner);
+        assert_eq!(Payload::Ether{ ether_type: ether_type::NSH, payload: &[1,2,3] }, headers.payload);
+    }
+    // unknown next protocol
+    {
+        let packet = outer_frame(&VxlanGpeHeader::new(1, 0x7f), &[1,2,3]);
+        let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+        assert_matches!(sliced.tunnel, Some(TunnelSlice::VxlanGpe(_)));
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&[1,2,3], sliced.payload);
+
+        let headers = PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()).unwrap();
+        assert_matches!(headers.tunnel, Some(TunnelHeader::VxlanGpe(_)));
+        assert_eq!(None, headers.inner);
+        assert_eq!(Payload::Udp(&[1,2,3]), headers.payload);
+    }
+}
+
+#[test]
+fn slice_errors() {
+    // truncated vxlan-gpe header
+    let mut packet = outer_frame(&VxlanGpeHeader::new(1, 1), &[]);
+    packet.truncate(packet.len() - 5);
+    let len = packet.len();
+    // fix the udp & ip lengths of the truncated packet
+    packet[14 + 4..14 + 6].copy_from_slice(&11u16.to_be_bytes());
+    packet[14 + 40 + 4..14 + 40 + 6].copy_from_slice(&11u16.to_be_bytes());
+    assert_eq!(14 + 40 + 8 + 3, len);
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(70))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_limits(&packet, limits()),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+
+    // truncated inner ip header
+    let packet = outer_frame(&VxlanGpeHeader::new(1, vxlan_gpe_next_protocol::IPV4), &[0x45, 0]);
+    let sliced = SlicedPacket::from_ethernet_with_limits(&packet, limits()).unwrap();
+    assert!(sliced.inner.is_none());
+    assert_eq!(sliced.payload, &[0x45, 0]);
+}
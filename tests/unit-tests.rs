@@ -8,15 +8,51 @@ extern crate proptest;
 
 use std::io;
 
+#[cfg(feature = "rayon")]
+mod batch;
+mod anonymize;
+mod bpf;
 mod checksum;
+mod compose;
+#[cfg(feature = "conntrack")]
+mod conntrack;
+mod dedup;
+mod dhcp;
+#[cfg(feature = "differential")]
+mod differential;
+mod ecn;
+mod filter_expression;
+mod generator;
+mod hexdump;
+mod icmp_extension;
+mod icmpv4;
+mod icmpv6;
+mod json;
+mod multicast;
+mod owned_packet;
 mod errors;
 mod link;
 mod internet;
 mod transport;
+mod tunnel;
 mod packet_builder;
 mod packet_decoder;
 mod packet_filter;
 mod packet_slicing;
+mod parse_limits;
+#[cfg(feature = "pnet_packet")]
+mod pnet_compat;
+mod ptp;
+#[cfg(all(feature = "raw_socket", any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "freebsd", target_os = "dragonfly")))]
+mod raw_socket;
+mod rohc;
+mod sixlowpan;
+#[cfg(feature = "someip")]
+mod someip;
+#[cfg(feature = "smoltcp")]
+mod smoltcp_compat;
+mod stats;
+mod tpacket_v3;
 mod proptest_generators;
 pub use crate::proptest_generators::*;
 use proptest::prelude::*;
@@ -29,7 +65,7 @@ use test_reader::*;
 #[test]
 fn test_eq() {
     assert_eq!(ErrorField::Ipv4PayloadLength, ErrorField::Ipv4PayloadLength);
-    assert_ne!(ErrorField::Ipv4PayloadLength, ErrorField::Ipv4Dscp);
+    assert_ne!(ErrorField::Ipv4PayloadLength, ErrorField::Dscp);
 }
 
 #[test]
@@ -85,9 +121,9 @@ fn test_debug_write() {
             Ipv4PayloadLengthTooLarge(0),
             Ipv6PayloadLengthTooLarge(0),
             UdpPayloadLengthTooLarge(0),
-            U8TooLarge{value: 0, max: 0, field: ErrorField::Ipv4Ecn},
-            U16TooLarge{value: 0, max: 0, field: ErrorField::Ipv4Ecn},
-            U32TooLarge{value: 0, max: 0, field: ErrorField::Ipv4Ecn}
+            U8TooLarge{value: 0, max: 0, field: ErrorField::Ecn},
+            U16TooLarge{value: 0, max: 0, field: ErrorField::Ecn},
+            U32TooLarge{value: 0, max: 0, field: ErrorField::Ecn}
         ].iter() {
             println!("{:?}", value);
         }
@@ -97,12 +133,27 @@ fn test_debug_write() {
         use crate::ErrorField::*;
         for value in [
             Ipv4PayloadLength,
-            Ipv4Dscp,
-            Ipv4Ecn,
             Ipv4FragmentsOffset,
             Ipv6FlowLabel,
             VlanTagPriorityCodePoint,
-            VlanTagVlanId
+            VlanTagVlanId,
+            MldQuerierRobustnessVariable,
+            IgmpQuerierRobustnessVariable,
+            VxlanVni,
+            GeneveVersion,
+            GeneveVni,
+            GtpuPduType,
+            GtpuQosFlowIdentifier,
+            PppoeVersion,
+            PppoeType,
+            PbbItagPriorityCodePoint,
+            PbbItagIsid,
+            NvgreVsid,
+            VxlanGpeVni,
+            Ipv4TimestampOverflow,
+            Ipv4TimestampFlag,
+            Dscp,
+            Ecn
         ].iter() {
             println!("{:?}", value);
         }
@@ -113,6 +164,8 @@ fn test_debug_write() {
         let value = PacketHeaders{
             link: None,
             vlan: None,
+            llc: None,
+            arp: None,
             ip: None,
             /*ip_extensions: [
                 None, None, None, None, None,
@@ -120,7 +173,9 @@ fn test_debug_write() {
                 None, None
             ],*/
             transport: None,
-            payload: &dummy[..]
+            tunnel: None,
+            payload: Payload::Udp(&dummy[..]),
+            inner: None,
         };
         println!("{:?}", value);
     }
@@ -180,6 +235,70 @@ mod read_error {
             .unexpected_end_of_slice_min_expected_size().unwrap()
         );
     }
+
+    #[test]
+    fn error_code() {
+        use crate::ReadError::*;
+        let values = [
+            IoError(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "oh no!")),
+            UnexpectedEndOfSlice(0),
+            DoubleVlanOuterNonVlanEtherType(0),
+            IpUnsupportedVersion(0),
+            Ipv4UnexpectedVersion(0),
+            Ipv4HeaderLengthBad(0),
+            Ipv4TotalLengthTooSmall(0),
+            Ipv6UnexpectedVersion(0),
+            Ipv6TooManyHeaderExtensions,
+            Ipv6HopByHopHeaderNotAtStart,
+            IpAuthenticationHeaderTooSmallPayloadLength(0),
+            TcpDataOffsetTooSmall(0),
+            Ieee802154UnsupportedFrameType(0),
+            Ieee802154ReservedAddressingMode(0),
+            ParseLimitExceeded(etherparse::ParseLimit::TunnelDepth),
+            GreUnsupportedVersion(0),
+            GeneveOptionLengthBad(0),
+            GtpuUnsupportedVersion(0),
+            GtpuExtensionHeaderLengthZero,
+            Ieee80211UnsupportedFrameType(0),
+            StpUnknownBpduType(0),
+            LacpUnexpectedSubtype(0),
+            GueUnsupportedVariant(0),
+            EspPadLengthTooLarge(0),
+            Ipv6RoutingHeaderLengthBad(0),
+            Icmpv6RouterRenumberingOpLengthBad(0),
+        ];
+        // codes are stable & follow the declaration order
+        for (index, value) in values.iter().enumerate() {
+            assert_eq!(100 + index as u16, value.error_code());
+        }
+    }
+
+    #[test]
+    fn into_io_error() {
+        use super::*;
+        use std::io::ErrorKind;
+
+        // io errors are unwrapped
+        let err: std::io::Error = ReadError::IoError(
+            std::io::Error::new(ErrorKind::BrokenPipe, "oh no!")
+        ).into();
+        assert_eq!(ErrorKind::BrokenPipe, err.kind());
+        assert_eq!("oh no!", err.to_string());
+
+        let err: std::io::Error = ReadError::UnexpectedEndOfSlice(2).into();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(ReadError::UnexpectedEndOfSlice(2).to_string(), err.to_string());
+
+        let err: std::io::Error = ReadError::TcpDataOffsetTooSmall(1).into();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(ReadError::TcpDataOffsetTooSmall(1).to_string(), err.to_string());
+
+        // ? operator in io centric code
+        fn read_ip(data: &[u8]) -> std::io::Result<IpHeader> {
+            Ok(IpHeader::from_slice(data)?.0)
+        }
+        assert_eq!(ErrorKind::UnexpectedEof, read_ip(&[0x45]).unwrap_err().kind());
+    }
 }
 
 mod write_error {
@@ -210,4 +329,82 @@ mod write_error {
             .value_error()
         );
     }
+
+    #[test]
+    fn error_code() {
+        use super::*;
+        assert_eq!(
+            200,
+            WriteError::IoError(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "oh no!")).error_code()
+        );
+        assert_eq!(201, WriteError::SliceTooSmall(0).error_code());
+        assert_eq!(
+            ValueError::TcpLengthTooLarge(0).error_code(),
+            WriteError::ValueError(ValueError::TcpLengthTooLarge(0)).error_code()
+        );
+    }
+
+    #[test]
+    fn into_io_error() {
+        use super::*;
+        use std::io::ErrorKind;
+
+        let err: std::io::Error = WriteError::IoError(
+            std::io::Error::new(ErrorKind::BrokenPipe, "oh no!")
+        ).into();
+        assert_eq!(ErrorKind::BrokenPipe, err.kind());
+
+        let err: std::io::Error = WriteError::ValueError(ValueError::TcpLengthTooLarge(0)).into();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let err: std::io::Error = WriteError::SliceTooSmall(4).into();
+        assert_eq!(ErrorKind::WriteZero, err.kind());
+        assert_eq!(WriteError::SliceTooSmall(4).to_string(), err.to_string());
+    }
+}
+
+mod value_error {
+    #[test]
+    fn error_code() {
+        use super::*;
+        use crate::ValueError::*;
+        let values = [
+            Ipv4OptionsLengthBad(0),
+            Ipv4PayloadLengthTooLarge(0),
+            Ipv6PayloadLengthTooLarge(0),
+            Ipv6ExtensionPayloadTooSmall(0),
+            Ipv6ExtensionPayloadTooLarge(0),
+            Ipv6ExtensionPayloadLengthUnaligned(0),
+            IpAuthenticationHeaderBadIcvLength(0),
+            Ipv4ExtensionNotReferenced(IpNumber::Udp),
+            Ipv6ExtensionHopByHopNotAtStart,
+            Ipv6ExtensionNotReferenced(IpNumber::Udp),
+            Ipv6ExtensionNotDefinedReference(IpNumber::Udp),
+            UdpPayloadLengthTooLarge(0),
+            TcpLengthTooLarge(0),
+            U8TooLarge{value: 0, max: 0, field: ErrorField::Ecn},
+            U16TooLarge{value: 0, max: 0, field: ErrorField::Ecn},
+            U32TooLarge{value: 0, max: 0, field: ErrorField::Ecn},
+            ArpAddressLengthBad,
+            MulticastRecordLengthBad,
+            GeneveOptionDataLengthBad(0),
+            GeneveOptionsLengthTooLarge(0),
+            GtpuExtensionHeaderLengthBad(0),
+            GtpuPayloadLengthTooLarge(0),
+            PppoePayloadLengthTooLarge(0),
+            GueExtensionFieldsLengthBad(0),
+            TeredoAuthenticationLengthTooLarge(0),
+            Ipv4FragmentMtuTooSmall(0),
+            Ipv4DontFragmentSet(0),
+            Ipv6FragmentMtuTooSmall(0),
+            Ipv6SegmentRoutingNoSegments,
+            Ipv6SegmentRoutingTlvTooLarge(0),
+            Ipv6ExtensionOutOfOrder{ header: IpNumber::Udp, previous: IpNumber::Udp },
+            Icmpv6RouterRenumberingTooManyUsePrefixes(0),
+        ];
+        // codes are stable & follow the declaration order
+        for (index, value) in values.iter().enumerate() {
+            assert_eq!(300 + index as u16, value.error_code());
+        }
+    }
 }